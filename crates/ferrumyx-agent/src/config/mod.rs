@@ -1,6 +1,10 @@
 //! Configuration loading for Ferrumyx.
 //! Reads ferrumyx.toml from the current directory or path in FERRUMYX_CONFIG env var.
 
+use ferrumyx_common::confidence::{EvidenceDecay, DEFAULT_EVIDENCE_HALF_LIFE_YEARS};
+use ferrumyx_common::CancerType;
+use ferrumyx_ingestion::scheduler::IngestionSchedule;
+use ferrumyx_ranker::weights::WeightVector;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
@@ -14,7 +18,11 @@ pub struct Config {
     pub scoring: ScoringConfig,
     pub structural: StructuralConfig,
     pub security: SecurityConfig,
+    #[serde(default)]
+    pub tools: ToolsConfig,
     pub workspace: WorkspaceConfig,
+    #[serde(default)]
+    pub ranker: RankerConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,6 +52,9 @@ pub struct LlmConfig {
     /// Backend for CONFIDENTIAL/INTERNAL data (must be local)
     #[serde(default = "default_local_backend")]
     pub local_backend: String,
+    /// Let INTERNAL data go to remote backends; CONFIDENTIAL never does.
+    #[serde(default)]
+    pub allow_internal_remote: bool,
     pub ollama: Option<OllamaBackendConfig>,
     pub openai: Option<ApiBackendConfig>,
     pub anthropic: Option<ApiBackendConfig>,
@@ -53,6 +64,8 @@ pub struct LlmConfig {
     pub limits: LlmLimits,
     #[serde(default)]
     pub rate_limits: LlmRateLimits,
+    #[serde(default)]
+    pub cache: LlmCacheConfig,
 }
 
 fn default_llm_mode() -> String {
@@ -151,6 +164,9 @@ pub struct LlmRateLimits {
     pub compat_rpm: u32,
     #[serde(default = "default_ollama_rpm")]
     pub ollama_rpm: u32,
+    /// Longest a call may wait for its slot before failing as rate limited.
+    #[serde(default = "default_max_queue_delay_secs")]
+    pub max_queue_delay_secs: u64,
 }
 
 fn default_openai_rpm() -> u32 {
@@ -168,6 +184,28 @@ fn default_compat_rpm() -> u32 {
 fn default_ollama_rpm() -> u32 {
     120
 }
+fn default_max_queue_delay_secs() -> u64 {
+    30
+}
+
+/// Response cache for the answer router, stored under
+/// `<workspace>/llm_cache`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct LlmCacheConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_cache_ttl_secs")]
+    pub ttl_secs: u64,
+    #[serde(default = "default_cache_max_entries")]
+    pub max_entries: usize,
+}
+
+fn default_cache_ttl_secs() -> u64 {
+    7 * 24 * 3600
+}
+fn default_cache_max_entries() -> usize {
+    10_000
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IngestionConfig {
@@ -176,6 +214,9 @@ pub struct IngestionConfig {
     pub pubmed: Option<SourceConfig>,
     pub europepmc: Option<SourceConfig>,
     pub semanticscholar: Option<SourceConfig>,
+    /// Recurring jobs from `[[ingestion.schedules]]`.
+    #[serde(default)]
+    pub schedules: Vec<IngestionSchedule>,
 }
 
 fn default_sources() -> Vec<String> {
@@ -212,6 +253,20 @@ pub struct EmbeddingConfig {
     /// Local BiomedBERT service URL
     #[serde(default = "default_biomedbert_url")]
     pub biomedbert_url: String,
+    /// Load local model files from the cache only; prefetch them with
+    /// `ferrumyx models download`
+    #[serde(default)]
+    pub offline: bool,
+    /// Device for local models: "cpu" | "cuda:<n>" | "metal" | "auto"
+    #[serde(default)]
+    pub device: Option<String>,
+    /// Weight precision for local models: "f32" | "f16" | "bf16" | "auto"
+    #[serde(default)]
+    pub dtype: Option<String>,
+    /// Projection after pooling: "none" | "truncate:<dim>" | "pca:<dim>:<path>";
+    /// the output width must equal `embedding_dim`
+    #[serde(default)]
+    pub post_process: Option<String>,
 }
 
 fn default_embed_backend() -> String {
@@ -232,7 +287,8 @@ fn default_biomedbert_url() -> String {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NerConfig {
-    /// Primary NER backend: "trie" (Rust Aho-Corasick) or "scispacy" (Docker)
+    /// Primary NER backend: "trie" (Rust Aho-Corasick), "hybrid" (trie merged
+    /// with a registered span tagger) or "scispacy" (Docker)
     #[serde(default = "default_ner_primary")]
     pub primary: String,
     #[serde(default = "default_bern2_threshold")]
@@ -246,6 +302,25 @@ pub struct NerConfig {
     /// NER service URL (only needed if primary = "scispacy")
     #[serde(default = "default_ner_url")]
     pub service_url: String,
+    /// Entities below this confidence are dropped before the KG sees them.
+    #[serde(default)]
+    pub min_score: f32,
+    /// Entity labels to keep (e.g. ["GENE", "DISEASE"]); all when unset.
+    #[serde(default)]
+    pub allowed_types: Option<Vec<String>>,
+    /// Build the trie from cached dictionaries only, never downloading them.
+    #[serde(default)]
+    pub offline: bool,
+    /// Tokens between the starts of consecutive windows over a long text;
+    /// 0 matches each text in one pass.
+    #[serde(default = "default_ner_window_stride")]
+    pub window_stride: usize,
+    /// Tokens each window shares with the next.
+    #[serde(default = "default_ner_window_overlap")]
+    pub window_overlap: usize,
+    /// Chunk texts each parallel NER task matches in turn.
+    #[serde(default = "default_ner_batch_size")]
+    pub batch_size: usize,
 }
 
 fn default_ner_primary() -> String {
@@ -263,6 +338,15 @@ fn default_bern2_image() -> String {
 fn default_ner_url() -> String {
     "http://localhost:8001".to_string()
 }
+fn default_ner_window_stride() -> usize {
+    ferrumyx_kg::ner::NerWindow::default().stride
+}
+fn default_ner_window_overlap() -> usize {
+    ferrumyx_kg::ner::NerWindow::default().overlap
+}
+fn default_ner_batch_size() -> usize {
+    ferrumyx_kg::ner::TrieNer::DEFAULT_BATCH_SIZE
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScoringConfig {
@@ -274,6 +358,39 @@ pub struct ScoringConfig {
     pub primary_shortlist_threshold: f64,
     #[serde(default = "default_secondary_threshold")]
     pub secondary_shortlist_threshold: f64,
+    /// Half-life, in years, of literature evidence in KG confidence and the
+    /// literature components; 0 disables decay.
+    #[serde(default = "default_evidence_half_life_years")]
+    pub evidence_half_life_years: f64,
+    /// `[scoring.weights]`; all nine components must be given.
+    #[serde(default)]
+    pub weights: WeightVector,
+}
+
+impl ScoringConfig {
+    /// Resolve `focus_cancer` to its OncoTree code, so "pancreatic cancer"
+    /// or "TCGA-PAAD" configure the same run as "PAAD", and validate the
+    /// weights and evidence half-life.
+    pub fn validated(mut self) -> anyhow::Result<Self> {
+        let cancer: CancerType = self
+            .focus_cancer
+            .parse()
+            .map_err(|e| anyhow::anyhow!("scoring.focus_cancer: {e}"))?;
+        self.focus_cancer = cancer.code().to_string();
+        self.weights = self.weights.validated()?;
+        if !self.evidence_half_life_years.is_finite() || self.evidence_half_life_years < 0.0 {
+            anyhow::bail!(
+                "scoring.evidence_half_life_years must be 0 (no decay) or positive, got {}",
+                self.evidence_half_life_years
+            );
+        }
+        Ok(self)
+    }
+
+    /// The evidence decay these settings configure.
+    pub fn evidence_decay(&self) -> EvidenceDecay {
+        EvidenceDecay::with_half_life(self.evidence_half_life_years)
+    }
 }
 
 fn default_focus_cancer() -> String {
@@ -288,6 +405,9 @@ fn default_primary_threshold() -> f64 {
 fn default_secondary_threshold() -> f64 {
     0.45
 }
+fn default_evidence_half_life_years() -> f64 {
+    DEFAULT_EVIDENCE_HALF_LIFE_YEARS
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StructuralConfig {
@@ -297,8 +417,16 @@ pub struct StructuralConfig {
     pub alphafold_requests_per_second: u32,
     #[serde(default = "default_fpocket_image")]
     pub fpocket_docker_image: String,
+    /// Seconds one fpocket container may run before it is stopped.
+    #[serde(default = "default_fpocket_timeout_secs")]
+    pub fpocket_timeout_secs: u64,
     #[serde(default = "default_vina_image")]
     pub vina_docker_image: String,
+    /// Ligands dock_ligand prepares and docks at once.
+    #[serde(default = "default_docking_parallelism")]
+    pub docking_parallelism: usize,
+    /// Provides `rdkit-prep`, which prepares receptor and ligand PDBQT for
+    /// dock_ligand.
     #[serde(default = "default_rdkit_image")]
     pub rdkit_docker_image: String,
     #[serde(default = "default_admet_image")]
@@ -308,9 +436,15 @@ pub struct StructuralConfig {
 fn default_fpocket_image() -> String {
     "ferrumyx/fpocket:latest".to_string()
 }
+fn default_fpocket_timeout_secs() -> u64 {
+    300
+}
 fn default_vina_image() -> String {
     "ferrumyx/autodock-vina:latest".to_string()
 }
+fn default_docking_parallelism() -> usize {
+    ferrumyx_molecules::docking::DEFAULT_DOCKING_PARALLELISM
+}
 fn default_rdkit_image() -> String {
     "ferrumyx/rdkit-service:latest".to_string()
 }
@@ -326,6 +460,9 @@ pub struct SecurityConfig {
     pub enforce_data_classification: bool,
     #[serde(default = "default_retention")]
     pub audit_log_retention_days: u32,
+    /// Reject tool parameters the tool schema does not declare.
+    #[serde(default)]
+    pub strict_tool_params: bool,
 }
 
 fn bool_true() -> bool {
@@ -338,15 +475,78 @@ fn default_retention() -> u32 {
     90
 }
 
+/// Limits the tool registry applies to every tool invocation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolsConfig {
+    /// Timeout for tools that do not declare their own.
+    #[serde(default = "default_tool_timeout_secs")]
+    pub default_timeout_secs: u64,
+    /// Invocations running at once across all tools.
+    #[serde(default = "default_tool_max_concurrent")]
+    pub max_concurrent: usize,
+    /// Invocations of one tool running at once, for tools without their own limit.
+    #[serde(default = "default_tool_max_concurrent_per_tool")]
+    pub max_concurrent_per_tool: usize,
+}
+
+impl Default for ToolsConfig {
+    fn default() -> Self {
+        Self {
+            default_timeout_secs: default_tool_timeout_secs(),
+            max_concurrent: default_tool_max_concurrent(),
+            max_concurrent_per_tool: default_tool_max_concurrent_per_tool(),
+        }
+    }
+}
+
+fn default_tool_timeout_secs() -> u64 {
+    60
+}
+fn default_tool_max_concurrent() -> usize {
+    8
+}
+fn default_tool_max_concurrent_per_tool() -> usize {
+    2
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkspaceConfig {
     #[serde(default = "default_workspace_path")]
     pub path: String,
+    /// Most bytes one job may write into the workspace through its tools;
+    /// 0 for no limit.
+    #[serde(default = "default_max_job_bytes")]
+    pub max_job_bytes: u64,
 }
 
 fn default_workspace_path() -> String {
     "./workspace".to_string()
 }
+fn default_max_job_bytes() -> u64 {
+    10 * 1024 * 1024 * 1024
+}
+
+/// The parts of `[ranker]` the agent reads at start-up; the ranker itself
+/// picks up the remaining provider settings from the environment.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RankerConfig {
+    #[serde(default)]
+    pub providers: RankerProvidersConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RankerProvidersConfig {
+    #[serde(default)]
+    pub cosmic: CosmicProviderConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CosmicProviderConfig {
+    /// Local COSMIC Cancer Mutation Census export (TSV or CSV). COSMIC
+    /// downloads need a licence, so the file is supplied by the user.
+    #[serde(default)]
+    pub census_path: Option<String>,
+}
 
 mod tests;
 
@@ -365,7 +565,21 @@ impl Config {
         }
 
         let content = std::fs::read_to_string(&path)?;
-        let config: Config = toml::from_str(&content)?;
+        let mut root: toml::Value = toml::from_str(&content)?;
+        let failed = ferrumyx_common::secrets::open_sealed_values(&mut root);
+        if !failed.is_empty() {
+            tracing::warn!(
+                "Saved secrets do not open with this machine's key and read as unset: {}",
+                failed.join(", ")
+            );
+        }
+        Self::from_toml(root)
+    }
+
+    /// Configuration from an already parsed (and opened) `ferrumyx.toml`.
+    pub fn from_toml(root: toml::Value) -> anyhow::Result<Self> {
+        let mut config: Config = root.try_into()?;
+        config.scoring = config.scoring.validated()?;
         Ok(config)
     }
 }
@@ -9,9 +9,75 @@ mod tests {
             focus_mutation: default_focus_mutation(),
             primary_shortlist_threshold: default_primary_threshold(),
             secondary_shortlist_threshold: default_secondary_threshold(),
+            evidence_half_life_years: default_evidence_half_life_years(),
+            weights: WeightVector::default(),
         };
         assert_eq!(scoring.focus_cancer, "PAAD");
         assert!(scoring.primary_shortlist_threshold > scoring.secondary_shortlist_threshold);
+        assert_eq!(scoring.evidence_decay().half_life_years(), Some(10.0));
+    }
+
+    #[test]
+    fn test_evidence_half_life_validation() {
+        let parse = |toml: &str| toml::from_str::<ScoringConfig>(toml).unwrap().validated();
+        let off = parse("evidence_half_life_years = 0").unwrap();
+        assert!(!off.evidence_decay().is_enabled());
+        assert!(parse("evidence_half_life_years = -5.0").is_err());
+    }
+
+    #[test]
+    fn test_scoring_weights_table_parses() {
+        let scoring: ScoringConfig = toml::from_str(
+            r#"
+            focus_cancer = "LUAD"
+            [weights]
+            mutation_freq = 0.4
+            crispr_dependency = 0.4
+            survival_correlation = 0.2
+            expression_specificity = 0.0
+            structural_tractability = 0.0
+            pocket_detectability = 0.0
+            novelty_score = 0.0
+            pathway_independence = 0.0
+            literature_novelty = 0.0
+            "#,
+        )
+        .unwrap();
+        assert!((scoring.weights.mutation_freq - 0.4).abs() < 1e-9);
+        assert!(scoring.weights.validate());
+
+        let defaulted: ScoringConfig = toml::from_str("focus_cancer = \"PAAD\"").unwrap();
+        assert!(defaulted.weights.validate());
+    }
+
+    #[test]
+    fn test_ingestion_schedules_parse() {
+        let ingestion: IngestionConfig = toml::from_str(
+            r#"
+            sources = ["pubmed"]
+            [[schedules]]
+            id = "kras-pdac"
+            gene = "KRAS"
+            mutation = "G12D"
+            cancer = "pancreatic cancer"
+            interval_secs = 86400
+            [[schedules]]
+            id = "egfr-nsclc"
+            gene = "EGFR"
+            cancer_type = "non-small cell lung cancer"
+            sources = ["pubmed", "europepmc", "biorxiv"]
+            cron = "0 6 * * MON"
+            max_results = 25
+            "#,
+        )
+        .unwrap();
+        assert_eq!(ingestion.schedules.len(), 2);
+        assert_eq!(ingestion.schedules[0].cancer_type, "pancreatic cancer");
+        assert_eq!(ingestion.schedules[1].sources.len(), 3);
+        assert!(ingestion.schedules.iter().all(|s| s.validate().is_ok()));
+
+        let unscheduled: IngestionConfig = toml::from_str("sources = [\"pubmed\"]").unwrap();
+        assert!(unscheduled.schedules.is_empty());
     }
 
     #[test]
@@ -58,9 +124,71 @@ mod tests {
             embedding_dim: default_embed_dim(),
             batch_size: default_batch_size(),
             biomedbert_url: default_biomedbert_url(),
+            offline: false,
+            device: None,
+            dtype: None,
+            post_process: None,
         };
         assert_eq!(emb.backend, "openai");
         assert_eq!(emb.embedding_model, "text-embedding-3-small");
         assert_eq!(emb.embedding_dim, 1536);
     }
+
+    #[test]
+    fn test_focus_cancer_resolves_to_oncotree_code() {
+        let parse = |focus: &str| {
+            toml::from_str::<ScoringConfig>(&format!("focus_cancer = \"{focus}\""))
+                .unwrap()
+                .validated()
+        };
+        assert_eq!(parse("PAAD").unwrap().focus_cancer, "PAAD");
+        assert_eq!(parse("pancreatic cancer").unwrap().focus_cancer, "PAAD");
+        assert_eq!(parse("TCGA-LIHC").unwrap().focus_cancer, "HCC");
+
+        let err = parse("PADD").unwrap_err().to_string();
+        assert!(
+            err.starts_with("scoring.focus_cancer: unknown cancer type 'PADD'"),
+            "{err}"
+        );
+        assert!(err.contains("PAAD (Pancreatic Adenocarcinoma)"), "{err}");
+    }
+
+    #[test]
+    fn test_cosmic_census_path_reads_beside_other_provider_keys() {
+        let ranker: RankerConfig = toml::from_str(
+            "[providers.cosmic]\n\
+             base_url = \"https://cancer.sanger.ac.uk/cosmic/api\"\n\
+             census_path = \"data/providers/Cosmic_MutantCensus.tsv\"\n\
+             [phase4.background_refresh]\nenabled = true\n",
+        )
+        .unwrap();
+        assert_eq!(
+            ranker.providers.cosmic.census_path.as_deref(),
+            Some("data/providers/Cosmic_MutantCensus.tsv")
+        );
+        let unset: RankerConfig = toml::from_str("").unwrap();
+        assert!(unset.providers.cosmic.census_path.is_none());
+    }
+
+    #[test]
+    fn test_tools_limits_default_per_field() {
+        let tools: ToolsConfig = toml::from_str("default_timeout_secs = 120").unwrap();
+        assert_eq!(tools.default_timeout_secs, 120);
+        assert_eq!(tools.max_concurrent, 8);
+        assert_eq!(tools.max_concurrent_per_tool, 2);
+
+        let defaulted: ToolsConfig = toml::from_str("").unwrap();
+        assert_eq!(defaulted.default_timeout_secs, 60);
+    }
+
+    #[test]
+    fn test_offline_flags_default_off() {
+        let ner: NerConfig = toml::from_str("primary = \"trie\"").unwrap();
+        assert!(!ner.offline);
+        assert_eq!((ner.window_stride, ner.window_overlap), (384, 128));
+        assert_eq!(ner.batch_size, 8);
+        let emb: EmbeddingConfig = toml::from_str("offline = true").unwrap();
+        assert!(emb.offline);
+        assert_eq!(emb.backend, "openai");
+    }
 }
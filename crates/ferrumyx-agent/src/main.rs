@@ -8,6 +8,8 @@ use std::sync::Arc;
 use std::time::Duration;
 
 mod config;
+mod models;
+mod repro;
 mod tools;
 use ferrumyx_runtime::llm::{CooldownConfig, FailoverProvider};
 use rig::client::CompletionClient;
@@ -18,19 +20,24 @@ use rig::providers::openai::CompletionsClient as OpenAiCompletionsClient;
 use serde::Deserialize;
 use tokio::process::Command;
 
-/// Returns a Boxed CompletionModel to inject into the Agent.
-/// It natively maps the Ferrumyx config directly to `rig-core` LLM clients.
-async fn build_completion_model(
-    config: &config::Config,
-) -> anyhow::Result<Arc<dyn ferrumyx_runtime::llm::LlmProvider>> {
+/// A configured LLM backend, by normalized backend name.
+struct LlmBackend {
+    name: String,
+    provider: Arc<dyn ferrumyx_runtime::llm::LlmProvider>,
+    /// Prompts stay on this machine.
+    local: bool,
+}
+
+/// Builds every reachable backend in failover order. In `local_only` mode
+/// remote backends are skipped.
+async fn build_llm_backends(config: &config::Config) -> anyhow::Result<Vec<LlmBackend>> {
     let mode = config.llm.mode.to_lowercase();
     let local_only = mode == "local_only";
     let default_backend = normalize_backend_name(&config.llm.default_backend);
     let local_backend = normalize_backend_name(&config.llm.local_backend);
     let failover_order = resolve_failover_backend_order(&default_backend, &local_backend, &mode);
 
-    let mut providers: Vec<Arc<dyn ferrumyx_runtime::llm::LlmProvider>> = Vec::new();
-    let mut provider_names: Vec<String> = Vec::new();
+    let mut backends = Vec::new();
     let mut seen = HashSet::new();
 
     for backend in failover_order {
@@ -55,17 +62,61 @@ async fn build_completion_model(
         };
 
         if let Some(provider) = maybe_provider {
-            provider_names.push(backend);
-            providers.push(provider);
+            let local = match backend.as_str() {
+                "ollama" => config
+                    .llm
+                    .ollama
+                    .as_ref()
+                    .is_some_and(|o| is_local_base_url(&o.base_url)),
+                "openai_compatible" => config
+                    .llm
+                    .openai_compatible
+                    .as_ref()
+                    .is_some_and(|c| is_local_base_url(&c.base_url)),
+                _ => false,
+            };
+            backends.push(LlmBackend {
+                name: backend,
+                provider,
+                local,
+            });
         }
     }
 
-    if providers.is_empty() {
+    if backends.is_empty() {
         anyhow::bail!("No LLM providers were successfully configured in ferrumyx.toml");
     }
+    Ok(backends)
+}
+
+/// Returns a Boxed CompletionModel to inject into the Agent.
+/// It natively maps the Ferrumyx config directly to `rig-core` LLM clients.
+///
+/// Every backend refuses requests carrying `INTERNAL` / `CONFIDENTIAL` tool
+/// results unless it is local (`INTERNAL` is let through with `[llm]
+/// allow_internal_remote`); refused requests are retried on the first local
+/// backend, and fail when there is none.
+fn build_completion_model(
+    backends: &[LlmBackend],
+    allow_internal_remote: bool,
+) -> anyhow::Result<Arc<dyn ferrumyx_runtime::llm::LlmProvider>> {
+    use ferrumyx_runtime::llm::{DataClassGuard, LocalRetryProvider};
+
+    let guarded = |b: &LlmBackend| -> Arc<dyn ferrumyx_runtime::llm::LlmProvider> {
+        Arc::new(
+            DataClassGuard::new(b.provider.clone(), b.name.clone(), b.local)
+                .with_allow_internal_remote(allow_internal_remote),
+        )
+    };
+    let local = backends.iter().find(|b| b.local).map(guarded);
+    let mut providers: Vec<Arc<dyn ferrumyx_runtime::llm::LlmProvider>> =
+        backends.iter().map(guarded).collect();
+    let provider_names: Vec<String> = backends.iter().map(|b| b.name.clone()).collect();
+
     if providers.len() == 1 {
         tracing::info!("Using single LLM backend: {}", provider_names[0]);
-        return Ok(providers.remove(0));
+        let only = providers.remove(0);
+        return Ok(Arc::new(LocalRetryProvider::new(only, local)));
     }
 
     let cooldown_secs = env_u64("FERRUMYX_LLM_FAILOVER_COOLDOWN_SECS", 120).clamp(15, 3600);
@@ -85,7 +136,7 @@ async fn build_completion_model(
         cooldown_secs,
         failure_threshold
     );
-    Ok(Arc::new(failover))
+    Ok(Arc::new(LocalRetryProvider::new(Arc::new(failover), local)))
 }
 
 fn normalize_backend_name(raw: &str) -> String {
@@ -308,6 +359,305 @@ async fn try_build_ollama(
     Ok(None)
 }
 
+/// Routes `/api/query/answer` through the configured backends in failover
+/// order; INTERNAL and CONFIDENTIAL questions only use the local ones.
+fn build_answer_router(
+    config: &config::Config,
+    backends: &[LlmBackend],
+) -> ferrumyx_web::llm::LlmRouter {
+    let mut router = ferrumyx_web::llm::LlmRouter::default()
+        .with_local_only(config.llm.mode.eq_ignore_ascii_case("local_only"))
+        .with_enforce_internal(
+            config.security.enforce_data_classification && !config.llm.allow_internal_remote,
+        )
+        .with_audit_calls(config.security.audit_llm_calls)
+        .with_health_config(ferrumyx_web::llm::HealthConfig::from_env())
+        .with_rate_limiter(Arc::new(ferrumyx_web::llm_rate_limit::RateLimiter::new(
+            rate_limits(&config.llm.rate_limits),
+        )));
+    if config.llm.cache.enabled {
+        use ferrumyx_web::llm_cache::{ResponseCache, ResponseCacheConfig};
+
+        let dir = std::path::Path::new(&config.workspace.path).join("llm_cache");
+        let cache_config = ResponseCacheConfig::new(&dir)
+            .with_ttl(Duration::from_secs(config.llm.cache.ttl_secs))
+            .with_max_entries(config.llm.cache.max_entries);
+        match ResponseCache::open(cache_config) {
+            Ok(cache) => router = router.with_response_cache(Arc::new(cache)),
+            Err(e) => tracing::warn!(
+                "LLM response cache disabled, cannot open {}: {}",
+                dir.display(),
+                e
+            ),
+        }
+    }
+    for model in answer_models(config, backends) {
+        router = router.with_backend(model);
+    }
+    if !backends.iter().any(|b| b.local) {
+        tracing::warn!(
+            "No local LLM backend available; INTERNAL and CONFIDENTIAL answers are disabled."
+        );
+    }
+    router
+}
+
+/// Answer models over `backends`, in the same order.
+fn answer_models(
+    config: &config::Config,
+    backends: &[LlmBackend],
+) -> Vec<Arc<dyn ferrumyx_web::llm::AnswerModel>> {
+    backends
+        .iter()
+        .map(|backend| -> Arc<dyn ferrumyx_web::llm::AnswerModel> {
+            match streaming_answer_model(config, backend) {
+                Some(streaming) => Arc::new(streaming),
+                None => Arc::new(RuntimeAnswerModel {
+                    provider: backend.provider.clone(),
+                    backend: backend.name.clone(),
+                    local: backend.local,
+                }),
+            }
+        })
+        .collect()
+}
+
+/// Rebuilds the answer backends from saved settings the way start-up does.
+/// Routing policy (mode flags, cache, rate limits) and the agent's own chat
+/// model stay as started.
+struct AgentAnswerBackends;
+
+#[async_trait::async_trait]
+impl ferrumyx_web::llm::AnswerBackendSource for AgentAnswerBackends {
+    async fn answer_backends(
+        &self,
+        root: &toml::Value,
+    ) -> anyhow::Result<Vec<Arc<dyn ferrumyx_web::llm::AnswerModel>>> {
+        let config = config::Config::from_toml(root.clone())?;
+        let backends = build_llm_backends(&config).await?;
+        Ok(answer_models(&config, &backends))
+    }
+
+    fn fixed_components(&self) -> Vec<String> {
+        vec!["agent chat model".to_string()]
+    }
+}
+
+/// Streaming HTTP client for `backend` so answers arrive token by token;
+/// `None` leaves it on the runtime provider, which returns whole
+/// completions.
+fn streaming_answer_model(
+    config: &config::Config,
+    backend: &LlmBackend,
+) -> Option<ferrumyx_web::llm_stream::StreamingAnswerModel> {
+    use ferrumyx_web::llm_stream::{StreamingAnswerModel, WireFormat};
+
+    let key = |configured: &str, env: &str| {
+        if configured.is_empty() {
+            std::env::var(env).unwrap_or_default()
+        } else {
+            configured.to_string()
+        }
+    };
+    let (format, base_url, api_key) = match backend.name.as_str() {
+        "openai" => {
+            let openai = config.llm.openai.as_ref()?;
+            let api_key = key(&openai.api_key, "FERRUMYX_OPENAI_API_KEY");
+            (WireFormat::OpenAi, String::new(), api_key)
+        }
+        "anthropic" => {
+            let anthropic = config.llm.anthropic.as_ref()?;
+            let api_key = key(&anthropic.api_key, "FERRUMYX_ANTHROPIC_API_KEY");
+            (WireFormat::Anthropic, String::new(), api_key)
+        }
+        "gemini" => {
+            let gemini = config.llm.gemini.as_ref()?;
+            let api_key = key(&gemini.api_key, "FERRUMYX_GEMINI_API_KEY");
+            (WireFormat::Gemini, String::new(), api_key)
+        }
+        "openai_compatible" => {
+            let compat = config.llm.openai_compatible.as_ref()?;
+            let api_key = key(&compat.api_key, "FERRUMYX_COMPAT_API_KEY");
+            (WireFormat::OpenAi, compat.base_url.clone(), api_key)
+        }
+        "ollama" => {
+            let ollama = config.llm.ollama.as_ref()?;
+            (
+                WireFormat::OllamaChat,
+                ollama.base_url.clone(),
+                String::new(),
+            )
+        }
+        _ => return None,
+    };
+    let (prompt_usd, completion_usd) = provider_cost_per_token(backend.provider.as_ref());
+    Some(
+        StreamingAnswerModel::new(
+            &backend.name,
+            format,
+            &base_url,
+            &api_key,
+            backend.provider.model_name(),
+        )
+        .with_local(backend.local)
+        .with_cost_per_token(prompt_usd, completion_usd),
+    )
+}
+
+/// USD per prompt and completion token from the provider's pricing.
+fn provider_cost_per_token(provider: &dyn ferrumyx_runtime::llm::LlmProvider) -> (f64, f64) {
+    use rust_decimal::prelude::ToPrimitive;
+
+    let (input, output) = provider.cost_per_token();
+    (
+        input.to_f64().unwrap_or(0.0),
+        output.to_f64().unwrap_or(0.0),
+    )
+}
+
+/// `[llm.limits]` as answer router budgets; zero means uncapped.
+fn budget_limits(limits: &config::LlmLimits) -> ferrumyx_web::llm_usage::BudgetLimits {
+    let mut budget = ferrumyx_web::llm_usage::BudgetLimits::default();
+    for (backend, tokens) in [
+        ("openai", limits.max_tokens_per_day_openai),
+        ("anthropic", limits.max_tokens_per_day_anthropic),
+        ("gemini", limits.max_tokens_per_day_gemini),
+    ] {
+        if tokens > 0 {
+            budget = budget.with_token_limit(backend, tokens);
+        }
+    }
+    if limits.max_cost_per_day_usd > 0.0 {
+        budget = budget.with_cost_limit(limits.max_cost_per_day_usd);
+    }
+    if limits.alert_cost_threshold_usd > 0.0 {
+        budget = budget.with_cost_alert(limits.alert_cost_threshold_usd);
+    }
+    budget
+}
+
+/// `[llm.rate_limits]` per answer router backend; zero means unlimited.
+fn rate_limits(limits: &config::LlmRateLimits) -> ferrumyx_web::llm_rate_limit::RateLimits {
+    ferrumyx_web::llm_rate_limit::RateLimits::default()
+        .with_rpm("openai", limits.openai_rpm)
+        .with_rpm("anthropic", limits.anthropic_rpm)
+        .with_rpm("gemini", limits.gemini_rpm)
+        .with_rpm("openai_compatible", limits.compat_rpm)
+        .with_rpm("ollama", limits.ollama_rpm)
+        .with_max_queue_delay(Duration::from_secs(limits.max_queue_delay_secs))
+}
+
+/// Serves the `/api/tools` endpoints from the agent's tool registry.
+struct WebAgentTools(Arc<ferrumyx_runtime::tools::ToolRegistry>);
+
+impl ferrumyx_web::handlers::tools::AgentTools for WebAgentTools {
+    fn validate_params(
+        &self,
+        tool: &str,
+        params: serde_json::Value,
+    ) -> Option<Result<serde_json::Value, Vec<String>>> {
+        use ferrumyx_runtime::tools::ToolError;
+
+        self.0.validate_params(tool, params).map(|checked| {
+            checked.map_err(|e| match e {
+                ToolError::InvalidParams { violations, .. } => violations,
+                other => vec![other.to_string()],
+            })
+        })
+    }
+
+    fn stats(&self) -> Vec<ferrumyx_common::tool_stats::ToolStats> {
+        self.0.stats()
+    }
+}
+
+/// Answer backend over a runtime provider, which returns whole completions;
+/// for backends without a [`streaming_answer_model`].
+struct RuntimeAnswerModel {
+    provider: Arc<dyn ferrumyx_runtime::llm::LlmProvider>,
+    backend: String,
+    local: bool,
+}
+
+#[async_trait::async_trait]
+impl ferrumyx_web::llm::AnswerModel for RuntimeAnswerModel {
+    fn model_name(&self) -> &str {
+        self.provider.model_name()
+    }
+
+    fn backend(&self) -> &str {
+        &self.backend
+    }
+
+    fn is_local(&self) -> bool {
+        self.local
+    }
+
+    fn cost_per_token(&self) -> (f64, f64) {
+        provider_cost_per_token(self.provider.as_ref())
+    }
+
+    async fn generate(
+        &self,
+        system: &str,
+        prompt: &str,
+        max_tokens: u32,
+        tokens: &tokio::sync::mpsc::UnboundedSender<String>,
+    ) -> anyhow::Result<ferrumyx_web::llm::Generation> {
+        use ferrumyx_runtime::llm::{ChatMessage, CompletionRequest};
+
+        let request =
+            CompletionRequest::new(vec![ChatMessage::system(system), ChatMessage::user(prompt)])
+                .with_max_tokens(max_tokens)
+                .with_temperature(0.1);
+        let response = self
+            .provider
+            .complete(request)
+            .await
+            .map_err(|e| anyhow::anyhow!("{e}"))?;
+        let _ = tokens.send(response.content.clone());
+        Ok(ferrumyx_web::llm::Generation {
+            text: response.content,
+            prompt_tokens: Some(response.input_tokens),
+            completion_tokens: Some(response.output_tokens),
+        })
+    }
+
+    /// OpenAI-compatible APIs get `response_format: json_object`; the rest
+    /// rely on the prompt, as Anthropic and Gemini reject the field.
+    async fn generate_json(
+        &self,
+        system: &str,
+        prompt: &str,
+        max_tokens: u32,
+    ) -> anyhow::Result<ferrumyx_web::llm::Generation> {
+        use ferrumyx_runtime::llm::{
+            ChatMessage, CompletionRequest, JSON_OBJECT_FORMAT, RESPONSE_FORMAT_KEY,
+        };
+
+        let mut request =
+            CompletionRequest::new(vec![ChatMessage::system(system), ChatMessage::user(prompt)])
+                .with_max_tokens(max_tokens)
+                .with_temperature(0.0);
+        if matches!(self.backend.as_str(), "openai" | "openai_compatible") {
+            request.metadata.insert(
+                RESPONSE_FORMAT_KEY.to_string(),
+                JSON_OBJECT_FORMAT.to_string(),
+            );
+        }
+        let response = self
+            .provider
+            .complete(request)
+            .await
+            .map_err(|e| anyhow::anyhow!("{e}"))?;
+        Ok(ferrumyx_web::llm::Generation {
+            text: response.content,
+            prompt_tokens: Some(response.input_tokens),
+            completion_tokens: Some(response.output_tokens),
+        })
+    }
+}
+
 fn is_local_base_url(base_url: &str) -> bool {
     let b = base_url.to_lowercase();
     b.contains("localhost") || b.contains("127.0.0.1")
@@ -414,6 +764,72 @@ fn pick_ollama_model_for_hardware() -> String {
 
 fn sync_runtime_env_from_config(config: &config::Config) {
     std::env::set_var("LLM_BACKEND", config.llm.default_backend.clone());
+    if config.ner.min_score > 0.0 {
+        std::env::set_var("FERRUMYX_NER_MIN_SCORE", config.ner.min_score.to_string());
+    }
+    if let Some(ref types) = config.ner.allowed_types {
+        std::env::set_var("FERRUMYX_NER_ALLOWED_TYPES", types.join(","));
+    }
+    if config.ner.offline {
+        std::env::set_var(ferrumyx_kg::ner::dictionaries::OFFLINE_ENV, "1");
+    }
+    std::env::set_var(
+        ferrumyx_kg::ner::NerWindow::STRIDE_ENV,
+        config.ner.window_stride.to_string(),
+    );
+    std::env::set_var(
+        ferrumyx_kg::ner::NerWindow::OVERLAP_ENV,
+        config.ner.window_overlap.to_string(),
+    );
+    std::env::set_var(
+        ferrumyx_kg::ner::TrieNer::BATCH_SIZE_ENV,
+        config.ner.batch_size.to_string(),
+    );
+    let ner_mode = ferrumyx_kg::ner::NerMode::parse(&config.ner.primary).unwrap_or_default();
+    std::env::set_var(ferrumyx_kg::ner::NerMode::ENV, ner_mode.as_str());
+    if config.embedding.offline {
+        std::env::set_var("FERRUMYX_EMBED_OFFLINE", "1");
+    }
+    if let Some(ref device) = config.embedding.device {
+        std::env::set_var(ferrumyx_ingestion::embed::DeviceSpec::ENV_VAR, device);
+    }
+    if let Some(ref dtype) = config.embedding.dtype {
+        std::env::set_var(ferrumyx_ingestion::embed::Precision::ENV_VAR, dtype);
+    }
+    if let Some(ref post_process) = config.embedding.post_process {
+        std::env::set_var(
+            ferrumyx_ingestion::embed::PostProcess::ENV_VAR,
+            post_process,
+        );
+    }
+
+    if let Some(path) = config
+        .ranker
+        .providers
+        .cosmic
+        .census_path
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+    {
+        std::env::set_var(
+            ferrumyx_ingestion::sources::cosmic_census::CENSUS_PATH_ENV,
+            path,
+        );
+    }
+
+    for (source, source_config) in [
+        ("pubmed", &config.ingestion.pubmed),
+        ("europepmc", &config.ingestion.europepmc),
+        ("semanticscholar", &config.ingestion.semanticscholar),
+    ] {
+        if let Some(source_config) = source_config {
+            std::env::set_var(
+                ferrumyx_ingestion::rate_limit::requests_per_second_env(source),
+                source_config.requests_per_second.to_string(),
+            );
+        }
+    }
 
     if let Some(ref ollama) = config.llm.ollama {
         std::env::set_var("OLLAMA_BASE_URL", ollama.base_url.clone());
@@ -626,6 +1042,28 @@ async fn discover_background_refresh_genes(
     out
 }
 
+/// Load reference datasets into the KG once at start-up. Currently the
+/// COSMIC Cancer Mutation Census, when a census export is configured.
+fn spawn_reference_data_bootstrap(db: Arc<ferrumyx_db::Database>) {
+    if ferrumyx_ingestion::sources::CosmicCensus::configured_path().is_none() {
+        tracing::debug!("No COSMIC census configured; skipping hotspot bootstrap.");
+        return;
+    }
+    tokio::spawn(async move {
+        match ferrumyx_ranker::bootstrap_configured_cosmic_hotspots(db).await {
+            Ok(Some(report)) => tracing::info!(
+                "COSMIC hotspot bootstrap: {} facts for {} genes ({} known drivers, {} replaced)",
+                report.inserted,
+                report.genes,
+                report.known_drivers,
+                report.deleted
+            ),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("COSMIC hotspot bootstrap failed: {e:#}"),
+        }
+    });
+}
+
 fn spawn_background_provider_refresh_scheduler(db: Arc<ferrumyx_db::Database>) {
     let bootstrap_cfg = BackgroundProviderRefreshConfig::from_env();
     if !bootstrap_cfg.enabled {
@@ -753,6 +1191,150 @@ use ferrumyx_runtime::agent::SessionManager;
 use tracing::info;
 use tracing_subscriber::EnvFilter;
 
+/// Open the configured database; a new chunks table is sized for the
+/// configured embedding backend.
+async fn open_database(config: &config::Config) -> anyhow::Result<ferrumyx_db::Database> {
+    let dim =
+        tools::ingestion_tool::configured_embedding_dim().unwrap_or(config.embedding.embedding_dim);
+    Ok(ferrumyx_db::Database::open(&config.database.url)
+        .await?
+        .with_embedding_dim(dim))
+}
+
+async fn run_db_command(config: &config::Config, args: &[String]) -> anyhow::Result<()> {
+    match args.first().map(String::as_str) {
+        Some("migrate") => {
+            let dry_run = args.iter().any(|a| a == "--dry-run");
+            let db = open_database(config).await?;
+            let plan = ferrumyx_db::schema_evolution::plan_migrations(&db).await?;
+            println!("{}", serde_json::to_string_pretty(&plan)?);
+            if let Some((table, change)) = plan.first_incompatible() {
+                anyhow::bail!("table {table} needs a manual migration: {change:?}");
+            }
+            if dry_run {
+                if plan.is_empty() {
+                    println!("Schema is up to date (version {}).", plan.target_version);
+                } else {
+                    println!("Dry run: no changes applied.");
+                }
+                return Ok(());
+            }
+            db.initialize().await?;
+            println!(
+                "Applied schema migrations for {} table(s); schema version {}.",
+                plan.tables.len(),
+                plan.target_version
+            );
+            Ok(())
+        }
+        Some("migrate-embedding-dim") => {
+            let clear_only = args.iter().any(|a| a == "--clear");
+            let db = open_database(config).await?;
+            db.initialize().await?;
+            let db = Arc::new(db);
+            let runtime = tools::embedding_backfill_tool::resolve_tool_embedding_runtime(1);
+            let dim = match args.iter().position(|a| a == "--dim") {
+                Some(i) => args
+                    .get(i + 1)
+                    .and_then(|v| v.parse::<usize>().ok())
+                    .ok_or_else(|| anyhow::anyhow!("--dim expects a positive integer"))?,
+                None => runtime
+                    .cfg
+                    .as_ref()
+                    .map(|cfg| cfg.dim)
+                    .ok_or_else(|| anyhow::anyhow!("no embedding backend configured; pass --dim"))?,
+            };
+            println!(
+                "Chunks table stores {}-dim embeddings; target is {dim}.",
+                db.embedding_dim()
+            );
+            if clear_only {
+                let cleared = db.migrate_embedding_dim(dim).await?;
+                println!("Re-created chunks table at {dim} dims; cleared {cleared} embedding(s).");
+                return Ok(());
+            }
+            let Some(cfg) = runtime.cfg else {
+                anyhow::bail!("no embedding backend configured; pass --clear to only clear vectors");
+            };
+            if cfg.dim != dim {
+                anyhow::bail!(
+                    "configured embedding backend produces {}-dim vectors; set embedding.embedding_dim = {dim} or pass --clear",
+                    cfg.dim
+                );
+            }
+            let repo = ferrumyx_ingestion::repository::IngestionRepository::new(db.clone());
+            let batch_size = cfg.batch_size;
+            let report = ferrumyx_ingestion::embedding::migrate_embedding_dim(
+                &repo,
+                cfg,
+                batch_size,
+                |p| println!("re-embedded {}/{} chunk(s)", p.embedded, p.pending),
+            )
+            .await?;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            if let Some(err) = report.error {
+                anyhow::bail!("re-embedding stopped: {err}; rerun the command to resume");
+            }
+            Ok(())
+        }
+        other => anyhow::bail!(
+            "unknown db command {:?}; usage: ferrumyx db migrate [--dry-run] | ferrumyx db migrate-embedding-dim [--dim N] [--clear]",
+            other.unwrap_or("")
+        ),
+    }
+}
+
+async fn run_verify_repro(args: &[String]) -> anyhow::Result<()> {
+    let flag_value = |name: &str| {
+        args.iter()
+            .position(|a| a == name)
+            .and_then(|i| args.get(i + 1))
+            .cloned()
+    };
+    let seed = if args.iter().any(|a| a == "--no-repro") {
+        None
+    } else {
+        match flag_value("--seed") {
+            Some(v) => Some(
+                v.parse::<u64>()
+                    .map_err(|e| anyhow::anyhow!("invalid --seed {v:?}: {e}"))?,
+            ),
+            None => Some(repro::DEFAULT_SEED),
+        }
+    };
+    let out_dir = flag_value("--out").map(std::path::PathBuf::from);
+
+    let report = repro::verify_repro(seed, out_dir.as_deref()).await?;
+    for diff in report.differences.iter().take(50) {
+        println!("{}", serde_json::to_string(diff)?);
+    }
+    match report.seed {
+        Some(seed) if report.is_reproducible() => {
+            println!(
+                "Reproducible: {} rows identical across two runs (seed {seed}).",
+                report.rows
+            );
+            Ok(())
+        }
+        Some(seed) => anyhow::bail!(
+            "{} difference(s) between two runs with seed {seed}",
+            report.differences.len()
+        ),
+        None => {
+            let volatile = report
+                .differences
+                .iter()
+                .filter(|d| d.is_volatile())
+                .count();
+            println!(
+                "Repro mode off: {} difference(s), {volatile} in id/timestamp columns.",
+                report.differences.len()
+            );
+            Ok(())
+        }
+    }
+}
+
 fn main() -> anyhow::Result<()> {
     // Slightly larger per-thread stacks reduce risk of overflow under
     // deep parser/expression workloads in dependent crates.
@@ -790,42 +1372,129 @@ async fn async_main() -> anyhow::Result<()> {
             return Ok(());
         }
     };
+    let evidence_decay = config.scoring.evidence_decay();
+    match evidence_decay.half_life_years() {
+        Some(years) => info!("Evidence decay: half-life {years} years"),
+        None => info!("Evidence decay: off"),
+    }
+    ferrumyx_common::confidence::set_evidence_decay(evidence_decay);
 
     // Bridge Ferrumyx settings into runtime core env-style configuration.
     sync_runtime_env_from_config(&config);
 
+    // `ferrumyx db migrate [--dry-run]` runs schema evolution and exits.
+    let cli_args: Vec<String> = std::env::args().skip(1).collect();
+    if cli_args.first().map(String::as_str) == Some("db") {
+        return run_db_command(&config, &cli_args[1..]).await;
+    }
+    // `ferrumyx verify-repro [--seed N | --no-repro] [--out DIR]` runs the
+    // fixture twice and fails on any difference between the exports.
+    if cli_args.first().map(String::as_str) == Some("verify-repro") {
+        return run_verify_repro(&cli_args[1..]).await;
+    }
+    // `ferrumyx models download|status` fetches or checks the local model
+    // files and NER dictionaries, for machines that run offline.
+    if cli_args.first().map(String::as_str) == Some("models") {
+        return models::run_models_command(&config, &cli_args[1..]).await;
+    }
+
     // Connect to LanceDB
     info!("Connecting to LanceDB...");
-    let db = ferrumyx_db::Database::open(&config.database.url).await?;
+    let db = open_database(&config).await?;
     db.initialize().await?;
     let db = std::sync::Arc::new(db);
     info!("✅ LanceDB connected and initialized.");
 
     // Start Phase 3: Knowledge Graph Event Queue
-    let _kg_event_tx = ferrumyx_kg::update::start_scoring_event_queue(db.clone());
+    let kg_graph = std::sync::Arc::new(ferrumyx_kg::KgGraphIndex::new(db.clone()));
+    let kg_events = ferrumyx_kg::update::start_event_queue_with_graph(db.clone(), kg_graph.clone());
     info!("✅ KG event-driven scoring queue initialized.");
+    spawn_reference_data_bootstrap(db.clone());
     spawn_background_provider_refresh_scheduler(db.clone());
+    let ingestion_scheduler = Arc::new(
+        ferrumyx_ingestion::scheduler::IngestionScheduler::for_pipeline(
+            config.ingestion.schedules.clone(),
+            tools::ingestion_tool::scheduled_job_template(),
+            db.clone(),
+        ),
+    );
+    ingestion_scheduler.clone().start().await;
+    info!(
+        "✅ Ingestion scheduler started ({} schedules).",
+        ingestion_scheduler.schedules().len()
+    );
 
     // Build LLM client
-    let runtime_llm = build_completion_model(&config).await?;
+    let llm_backends = build_llm_backends(&config).await?;
+    let runtime_llm = build_completion_model(&llm_backends, config.llm.allow_internal_remote)?;
     let runtime_core_llm = ferrumyx_runtime::llm::to_core_provider(runtime_llm.clone());
+    let llm_usage = Arc::new(
+        ferrumyx_web::llm_usage::UsageTracker::new(budget_limits(&config.llm.limits))
+            .with_repository(ferrumyx_db::LlmUsageRepository::new(db.clone())),
+    );
+    if let Err(e) = llm_usage.restore().await {
+        tracing::warn!("Failed to restore today's LLM usage: {}", e);
+    }
+    let llm_audit = ferrumyx_web::llm_audit::DbAuditSink::new(db.clone());
+    llm_audit.spawn_retention_purge(config.security.audit_log_retention_days);
+    let answer_llm = Arc::new(
+        build_answer_router(&config, &llm_backends)
+            .with_usage_tracker(llm_usage)
+            .with_audit_sink(Arc::new(llm_audit)),
+    );
+    answer_llm.spawn_health_probe();
 
     // Build Tool Registry
-    let runtime_tool_registry = Arc::new(ferrumyx_runtime::tools::ToolRegistry::new());
+    let mut sandbox = ferrumyx_common::sandbox::Sandbox::new(&config.workspace.path)?;
+    if config.workspace.max_job_bytes > 0 {
+        sandbox = sandbox.with_max_job_bytes(config.workspace.max_job_bytes);
+    }
+    let runtime_tool_registry = Arc::new(
+        ferrumyx_runtime::tools::ToolRegistry::new()
+            .with_strict_params(config.security.strict_tool_params)
+            .with_limits(ferrumyx_runtime::tools::ToolLimits {
+                default_timeout: Duration::from_secs(config.tools.default_timeout_secs),
+                max_concurrent: config.tools.max_concurrent,
+                max_concurrent_per_tool: config.tools.max_concurrent_per_tool,
+            })
+            .with_sandbox(Arc::new(sandbox)),
+    );
     runtime_tool_registry.register_sync(Arc::new(tools::ingestion_tool::IngestionTool::new(
         db.clone(),
     )));
     runtime_tool_registry.register_sync(Arc::new(
         tools::embedding_backfill_tool::BackfillEmbeddingsTool::new(db.clone()),
     ));
+    runtime_tool_registry.register_sync(Arc::new(
+        tools::embedding_backfill_tool::EmbedBackfillTool::new(db.clone()),
+    ));
+    runtime_tool_registry.register_sync(Arc::new(
+        tools::enrich_papers_tool::EnrichPapersTool::new(db.clone()),
+    ));
     runtime_tool_registry.register_sync(Arc::new(tools::query_tool::TargetQueryTool::new(
         db.clone(),
     )));
     runtime_tool_registry.register_sync(Arc::new(
         tools::workflow_status_tool::WorkflowStatusTool::new(db.clone()),
     ));
+    runtime_tool_registry.register_sync(Arc::new(tools::snapshot_tool::ExportSnapshotTool::new(
+        db.clone(),
+    )));
+    runtime_tool_registry.register_sync(Arc::new(tools::snapshot_tool::ImportSnapshotTool::new(
+        db.clone(),
+    )));
+    let ranker_weights = Arc::new(std::sync::RwLock::new(config.scoring.weights.clone()));
+    runtime_tool_registry.register_sync(Arc::new(
+        tools::scoring_tool::RecomputeTargetScoresTool::new(db.clone())
+            .with_weights(ranker_weights.clone()),
+    ));
+    runtime_tool_registry.register_sync(Arc::new(
+        tools::target_report_tool::TargetReportTool::new(db.clone())
+            .with_weights(ranker_weights.clone()),
+    ));
     runtime_tool_registry.register_sync(Arc::new(
-        tools::scoring_tool::RecomputeTargetScoresTool::new(db.clone()),
+        tools::dossier_tool::TargetDossierTool::new(db.clone())
+            .with_weights(ranker_weights.clone()),
     ));
     runtime_tool_registry.register_sync(Arc::new(
         tools::provider_refresh_tool::RefreshProviderSignalsTool::new(db.clone()),
@@ -833,6 +1502,46 @@ async fn async_main() -> anyhow::Result<()> {
     runtime_tool_registry.register_sync(Arc::new(
         tools::molecule_tool::RunMoleculePipelineTool::new(),
     ));
+    runtime_tool_registry.register_sync(Arc::new(
+        tools::molecule_tool::GenerateCandidatesTool::new().with_store(db.clone()),
+    ));
+    let structures_dir = std::path::Path::new(&config.workspace.path).join("structures");
+    runtime_tool_registry.register_sync(Arc::new(tools::structure_tool::FetchStructureTool::new(
+        &structures_dir,
+    )));
+    runtime_tool_registry.register_sync(Arc::new(
+        tools::structure_tool::DetectPocketsTool::new(
+            &structures_dir,
+            &config.structural.fpocket_docker_image,
+        )
+        .with_fpocket_timeout(std::time::Duration::from_secs(
+            config.structural.fpocket_timeout_secs,
+        )),
+    ));
+    runtime_tool_registry.register_sync(Arc::new(
+        tools::structure_tool::DockLigandTool::new(
+            &structures_dir,
+            &config.structural.vina_docker_image,
+        )
+        .with_rdkit_image(&config.structural.rdkit_docker_image)
+        .with_parallelism(config.structural.docking_parallelism)
+        .with_results(db.clone()),
+    ));
+    let (molecule_progress, _) = tokio::sync::broadcast::channel(256);
+    runtime_tool_registry.register_sync(Arc::new(
+        tools::design_tool::DesignMoleculesTool::new(
+            &config.workspace.path,
+            &config.structural.fpocket_docker_image,
+            &config.structural.vina_docker_image,
+        )
+        .with_fpocket_timeout(std::time::Duration::from_secs(
+            config.structural.fpocket_timeout_secs,
+        ))
+        .with_rdkit_image(&config.structural.rdkit_docker_image)
+        .with_parallelism(config.structural.docking_parallelism)
+        .with_store(db.clone())
+        .with_progress(molecule_progress.clone()),
+    ));
     runtime_tool_registry.register_sync(Arc::new(
         tools::autonomous_cycle_tool::AutonomousCycleTool::new(db.clone()),
     ));
@@ -946,7 +1655,17 @@ async fn async_main() -> anyhow::Result<()> {
     });
 
     // Build app state and router
-    let state = ferrumyx_web::state::AppState::new(db);
+    let state = ferrumyx_web::state::AppState::new(db)
+        .with_ranker_weights(ranker_weights)
+        .with_kg_graph(kg_graph)
+        .with_kg_events(kg_events.sender())
+        .with_ingestion_scheduler(ingestion_scheduler)
+        .with_llm_router(answer_llm)
+        .with_answer_backends(Arc::new(AgentAnswerBackends))
+        .with_agent_tools(Arc::new(WebAgentTools(runtime_tool_registry)));
+    state.forward_score_updates(kg_events.subscribe());
+    state.forward_molecule_progress(molecule_progress.subscribe());
+    state.spawn_metrics_collector();
     let router = ferrumyx_web::router::build_router(state);
 
     // Start web server
@@ -0,0 +1,265 @@
+//! Model files the local backends load, and `ferrumyx models` to fetch them.
+//!
+//! The Candle embedder, the cross-encoder reranker and the trie NER all
+//! download on first use. `ferrumyx models download` fetches everything the
+//! config needs ahead of time, so a machine without network access can run
+//! with `offline = true` under `[embedding]` and `[ner]`.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use ferrumyx_db::ChunkRepository;
+use ferrumyx_ingestion::embed::artifacts::{BERT_FILES, CROSS_ENCODER_FILES};
+use ferrumyx_ingestion::embed::{fit_pca, ModelStore};
+use ferrumyx_ingestion::rerank::{RerankConfig, RerankerKind};
+
+use crate::config::{EmbeddingConfig, NerConfig};
+
+/// A Hugging Face model and the files it loads.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequiredModel {
+    pub model_id: String,
+    pub files: &'static [&'static [&'static str]],
+}
+
+#[derive(Debug, Default)]
+pub struct PrefetchReport {
+    /// Local path of every file, by model id.
+    pub models: Vec<(String, Vec<PathBuf>)>,
+    /// Cached NER dictionaries.
+    pub dictionaries: Vec<PathBuf>,
+}
+
+/// Models `embed` and the reranker in `rerank` load through Candle.
+pub fn required_models(embed: &EmbeddingConfig, rerank: &RerankConfig) -> Vec<RequiredModel> {
+    let mut models = Vec::new();
+    // Unrecognised backends fall back to the Candle embedder.
+    let remote = matches!(
+        embed.backend.trim().to_lowercase().as_str(),
+        "openai" | "gemini" | "openai_compatible" | "ollama" | "biomedbert" | "fastembed"
+    );
+    if !remote {
+        models.push(RequiredModel {
+            model_id: embed.embedding_model.clone(),
+            files: BERT_FILES,
+        });
+    }
+    if rerank.kind == RerankerKind::CrossEncoder {
+        models.push(RequiredModel {
+            model_id: rerank.cross_encoder_model.clone(),
+            files: CROSS_ENCODER_FILES,
+        });
+    }
+    models
+}
+
+/// Downloads every model file and NER dictionary the configs need into
+/// their caches, whether or not they are set offline.
+pub async fn prefetch_models(
+    configs: &[NerConfig],
+    embed: &EmbeddingConfig,
+) -> anyhow::Result<PrefetchReport> {
+    let mut report = PrefetchReport::default();
+    let cache_dir = ferrumyx_ingestion::embedding::resolve_embed_cache_dir();
+    for model in required_models(embed, &RerankConfig::from_env()) {
+        let cache_dir = cache_dir.clone();
+        let model_id = model.model_id.clone();
+        let files = tokio::task::spawn_blocking(move || {
+            ModelStore::new(cache_dir.as_deref(), false).resolve(&model_id, model.files)
+        })
+        .await??;
+        report.models.push((model.model_id, files));
+    }
+    if !configs.is_empty() {
+        report.dictionaries = ferrumyx_kg::ner::dictionaries::prefetch().await?;
+    }
+    Ok(report)
+}
+
+/// Files missing from `store`, as "model: file" lines.
+pub fn missing_files(store: &ModelStore, models: &[RequiredModel]) -> Vec<String> {
+    models
+        .iter()
+        .flat_map(|m| {
+            store
+                .missing(&m.model_id, m.files)
+                .into_iter()
+                .map(move |file| format!("{}: {file}", m.model_id))
+        })
+        .collect()
+}
+
+/// Up to `limit` stored chunk embeddings of the table's width.
+async fn sample_embeddings(
+    db: Arc<ferrumyx_db::Database>,
+    limit: usize,
+) -> anyhow::Result<Vec<Vec<f32>>> {
+    const PAGE: usize = 1000;
+    let width = db.embedding_dim();
+    let chunks = ChunkRepository::new(db);
+    let mut sample = Vec::with_capacity(limit);
+    let mut offset = 0;
+    while sample.len() < limit {
+        let page = chunks.list(offset, PAGE).await?;
+        if page.is_empty() {
+            break;
+        }
+        offset += page.len();
+        sample.extend(
+            page.into_iter()
+                .filter_map(|c| c.embedding)
+                .filter(|e| e.len() == width)
+                .take(limit - sample.len()),
+        );
+    }
+    Ok(sample)
+}
+
+/// `ferrumyx models download` fetches what the config needs; `ferrumyx
+/// models status` lists what is still missing; `ferrumyx models fit-pca
+/// --dim N [--sample M] [--out PATH]` fits a PCA projection on stored chunk
+/// embeddings for `post_process = "pca:N:PATH"`.
+pub async fn run_models_command(
+    config: &crate::config::Config,
+    args: &[String],
+) -> anyhow::Result<()> {
+    match args.first().map(String::as_str) {
+        Some("download") => {
+            let report =
+                prefetch_models(std::slice::from_ref(&config.ner), &config.embedding).await?;
+            for (model, files) in &report.models {
+                println!("{model}");
+                for file in files {
+                    println!("  {}", file.display());
+                }
+            }
+            for path in &report.dictionaries {
+                println!("NER dictionary {}", path.display());
+            }
+            println!(
+                "Fetched {} model(s) and {} NER dictionaries.",
+                report.models.len(),
+                report.dictionaries.len()
+            );
+            Ok(())
+        }
+        Some("status") => {
+            let cache_dir = ferrumyx_ingestion::embedding::resolve_embed_cache_dir();
+            let store = ModelStore::new(cache_dir.as_deref(), true);
+            let models = required_models(&config.embedding, &RerankConfig::from_env());
+            let mut missing = missing_files(&store, &models);
+            missing.extend(
+                ferrumyx_kg::ner::dictionaries::missing()
+                    .into_iter()
+                    .map(|p| format!("NER dictionary: {}", p.display())),
+            );
+            if missing.is_empty() {
+                println!("All model files are cached.");
+                return Ok(());
+            }
+            for line in &missing {
+                println!("missing {line}");
+            }
+            anyhow::bail!(
+                "{} file(s) not cached; run `ferrumyx models download`",
+                missing.len()
+            )
+        }
+        Some("fit-pca") => {
+            let flag = |name: &str| {
+                args.iter()
+                    .position(|a| a == name)
+                    .and_then(|i| args.get(i + 1))
+            };
+            let dim = flag("--dim")
+                .and_then(|v| v.parse::<usize>().ok())
+                .ok_or_else(|| anyhow::anyhow!("--dim expects a positive integer"))?;
+            let sample_size = flag("--sample")
+                .map(|v| v.parse::<usize>())
+                .transpose()?
+                .unwrap_or(5000);
+            let out = PathBuf::from(
+                flag("--out")
+                    .cloned()
+                    .unwrap_or_else(|| format!("data/models/pca-{dim}.json")),
+            );
+
+            let db = Arc::new(crate::open_database(config).await?);
+            let sample = sample_embeddings(db, sample_size).await?;
+            let n = sample.len();
+            let pca = tokio::task::spawn_blocking(move || fit_pca(&sample, dim)).await??;
+            pca.save(&out)?;
+            println!(
+                "Fitted {dim} components from {n} chunk embeddings to {}.",
+                out.display()
+            );
+            println!(
+                "Set [embedding] post_process = \"pca:{dim}:{}\" and embedding_dim = {dim}, \
+                 then run `ferrumyx db migrate-embedding-dim --dim {dim}`.",
+                out.display()
+            );
+            Ok(())
+        }
+        other => anyhow::bail!(
+            "unknown models command {:?}; expected `download`, `status` or `fit-pca`",
+            other.unwrap_or("")
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn embedding(backend: &str) -> EmbeddingConfig {
+        toml::from_str(&format!(
+            "backend = \"{backend}\"\nembedding_model = \"org/tiny-bert\""
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn only_local_backends_need_model_files() {
+        let rerank = RerankConfig::default();
+        assert_eq!(
+            required_models(&embedding("rust_native"), &rerank),
+            vec![RequiredModel {
+                model_id: "org/tiny-bert".to_string(),
+                files: BERT_FILES,
+            }]
+        );
+        assert!(required_models(&embedding("openai"), &rerank).is_empty());
+
+        let rerank = RerankConfig {
+            kind: RerankerKind::CrossEncoder,
+            ..RerankConfig::default()
+        };
+        let models = required_models(&embedding("ollama"), &rerank);
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].files, CROSS_ENCODER_FILES);
+    }
+
+    #[test]
+    fn status_lists_files_missing_from_a_partial_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("models--org--tiny-bert");
+        let snapshot = repo.join("snapshots").join("0123abcd");
+        std::fs::create_dir_all(repo.join("refs")).unwrap();
+        std::fs::write(repo.join("refs").join("main"), "0123abcd").unwrap();
+        std::fs::create_dir_all(&snapshot).unwrap();
+        for file in ["config.json", "tokenizer.json"] {
+            std::fs::write(snapshot.join(file), b"{}").unwrap();
+        }
+
+        let store = ModelStore::new(dir.path().to_str(), true);
+        let models = required_models(&embedding("rust_native"), &RerankConfig::default());
+        assert_eq!(
+            missing_files(&store, &models),
+            ["org/tiny-bert: model.safetensors or pytorch_model.bin"]
+        );
+
+        std::fs::write(snapshot.join("pytorch_model.bin"), b"").unwrap();
+        assert!(missing_files(&store, &models).is_empty());
+        assert_eq!(store.resolve("org/tiny-bert", BERT_FILES).unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,217 @@
+//! `ferrumyx verify-repro`: run a small offline fixture through storage,
+//! fact building and target scoring twice, export both databases as JSONL,
+//! and diff them.
+//!
+//! The fixture exercises the same nondeterminism the live pipeline has:
+//! papers are written from concurrent tasks, facts come from
+//! `build_facts_batch`, and scores from `compute_target_scores`.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use ferrumyx_common::repro;
+use ferrumyx_db::repro_export::{self, ExportDifference};
+use ferrumyx_db::schema::{Chunk, Entity, EntityType, KgFact, Paper};
+use ferrumyx_db::{ChunkRepository, Database, KgFactRepository, PaperRepository};
+use ferrumyx_kg::extraction::build_facts_batch;
+
+/// Seed used when `--seed` is not given.
+pub const DEFAULT_SEED: u64 = 42;
+
+struct FixturePaper {
+    doi: &'static str,
+    pmid: &'static str,
+    title: &'static str,
+    genes: &'static [&'static str],
+    sections: &'static [&'static str],
+}
+
+const FIXTURE: &[FixturePaper] = &[
+    FixturePaper {
+        doi: "10.1000/fixture.kras.1",
+        pmid: "90000001",
+        title: "KRAS G12D drives pancreatic ductal adenocarcinoma progression",
+        genes: &["KRAS", "TP53"],
+        sections: &[
+            "KRAS G12D mutation is a driver of pancreatic cancer and was observed in most tumours.",
+            "Loss of TP53 cooperates with KRAS G12D in pancreatic cancer models.",
+        ],
+    },
+    FixturePaper {
+        doi: "10.1000/fixture.kras.2",
+        pmid: "90000002",
+        title: "Sotorasib sensitivity of KRAS G12C lung adenocarcinoma",
+        genes: &["KRAS", "STK11"],
+        sections: &[
+            "KRAS G12C lung cancer cell lines were sensitive to sotorasib.",
+            "STK11 loss conferred resistance in lung cancer.",
+        ],
+    },
+    FixturePaper {
+        doi: "10.1000/fixture.braf.1",
+        pmid: "90000003",
+        title: "BRAF V600E in colorectal cancer",
+        genes: &["BRAF", "KRAS"],
+        sections: &[
+            "BRAF V600E mutation is associated with poor survival in colorectal cancer.",
+            "KRAS and BRAF mutations were mutually exclusive in colorectal cancer.",
+        ],
+    },
+];
+
+/// Outcome of a double run.
+#[derive(Debug)]
+pub struct ReproReport {
+    pub seed: Option<u64>,
+    pub rows: usize,
+    pub differences: Vec<ExportDifference>,
+}
+
+impl ReproReport {
+    pub fn is_reproducible(&self) -> bool {
+        self.differences.is_empty()
+    }
+}
+
+/// Run the fixture twice with `seed` (or with repro mode off for `None`)
+/// and diff the exports. When `out_dir` is set, each run's JSONL export is
+/// written to `out_dir/run-{1,2}`.
+pub async fn verify_repro(
+    seed: Option<u64>,
+    out_dir: Option<&Path>,
+) -> anyhow::Result<ReproReport> {
+    let previous = repro::seed();
+    repro::set_seed(seed);
+    let result = run_twice(seed, out_dir).await;
+    repro::set_seed(previous);
+    result
+}
+
+async fn run_twice(seed: Option<u64>, out_dir: Option<&Path>) -> anyhow::Result<ReproReport> {
+    let mut exports = Vec::with_capacity(2);
+    for run in 1..=2 {
+        let dir = scratch_dir(run);
+        let db = Arc::new(Database::open(&dir).await?);
+        db.initialize().await?;
+        run_fixture(db.clone()).await?;
+        let export = repro_export::export_tables(db).await?;
+        if let Some(out) = out_dir {
+            repro_export::write_jsonl(&export, &out.join(format!("run-{run}")))?;
+        }
+        exports.push(export);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    let rows = exports[0].values().map(Vec::len).sum();
+    let differences = repro_export::diff_exports(&exports[0], &exports[1]);
+    Ok(ReproReport {
+        seed,
+        rows,
+        differences,
+    })
+}
+
+fn scratch_dir(run: u32) -> PathBuf {
+    // Always random: the scratch location must not collide across runs.
+    std::env::temp_dir().join(format!(
+        "ferrumyx-verify-repro-{}-{run}",
+        uuid::Uuid::new_v4().simple()
+    ))
+}
+
+/// Write the fixture from one task per paper, then score the resulting facts.
+async fn run_fixture(db: Arc<Database>) -> anyhow::Result<()> {
+    let mut tasks = tokio::task::JoinSet::new();
+    for item in FIXTURE {
+        let db = db.clone();
+        tasks.spawn(async move { ingest_fixture_paper(db, item).await });
+    }
+    while let Some(joined) = tasks.join_next().await {
+        joined??;
+    }
+    ferrumyx_kg::scoring::compute_target_scores(db).await?;
+    Ok(())
+}
+
+async fn ingest_fixture_paper(db: Arc<Database>, item: &FixturePaper) -> anyhow::Result<()> {
+    let mut paper = Paper::new(item.title.to_string(), "fixture".to_string());
+    paper.doi = Some(item.doi.to_string());
+    paper.pmid = Some(item.pmid.to_string());
+    paper.abstract_text = Some(item.sections.join(" "));
+    paper.parse_status = "parsed".to_string();
+    PaperRepository::new(db.clone()).insert(&paper).await?;
+
+    let chunks: Vec<Chunk> = item
+        .sections
+        .iter()
+        .enumerate()
+        .map(|(i, text)| Chunk::new(paper.id, i as i64, text.to_string()))
+        .collect();
+    ChunkRepository::new(db.clone())
+        .insert_batch(&chunks)
+        .await?;
+
+    let genes: Vec<String> = item.genes.iter().map(|g| g.to_string()).collect();
+    let mut facts = Vec::new();
+    for chunk in &chunks {
+        for extracted in build_facts_batch(&genes, &chunk.content) {
+            let subject = Entity::new(
+                EntityType::Gene,
+                extracted.subject.clone(),
+                extracted.subject.clone(),
+                "fixture".to_string(),
+            );
+            let object = Entity::new(
+                EntityType::CancerType,
+                extracted.object.clone(),
+                extracted.object.clone(),
+                "fixture".to_string(),
+            );
+            let mut fact = KgFact::new(
+                paper.id,
+                subject.id,
+                extracted.subject,
+                extracted.fact_type,
+                object.id,
+                extracted.object,
+            );
+            fact.confidence = 0.8;
+            fact.evidence = Some(chunk.content.clone());
+            fact.evidence_type = "typed_relation".to_string();
+            facts.push(fact);
+        }
+    }
+    if !facts.is_empty() {
+        KgFactRepository::new(db).insert_batch(&facts).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // One test: both halves flip the process-wide repro setting.
+    #[tokio::test]
+    async fn double_run_diff_is_empty_only_in_repro_mode() {
+        let seeded = verify_repro(Some(DEFAULT_SEED), None).await.unwrap();
+        assert!(seeded.rows > 0);
+        assert!(
+            seeded.is_reproducible(),
+            "unexpected differences: {:?}",
+            seeded.differences
+        );
+
+        let unseeded = verify_repro(None, None).await.unwrap();
+        assert!(!unseeded.is_reproducible());
+        let non_volatile: Vec<_> = unseeded
+            .differences
+            .iter()
+            .filter(|d| !d.is_volatile())
+            .collect();
+        assert!(
+            non_volatile.is_empty(),
+            "differences outside id/timestamp columns: {non_volatile:?}"
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 use ferrumyx_runtime::context::JobContext;
-use ferrumyx_runtime::tools::{Tool, ToolError, ToolOutput};
+use ferrumyx_runtime::tools::{CancellationToken, Tool, ToolError, ToolOutput};
 use serde_json::json;
 use std::fs;
 use std::path::PathBuf;
@@ -148,7 +148,8 @@ fn resolve_pubmed_api_key() -> Option<String> {
         }
     }
     let content = fs::read_to_string(config_path()).ok()?;
-    let root = toml::from_str::<toml::Value>(&content).ok()?;
+    let mut root = toml::from_str::<toml::Value>(&content).ok()?;
+    ferrumyx_common::secrets::open_sealed_values(&mut root);
     root.get("ingestion")
         .and_then(|v| v.get("pubmed"))
         .and_then(|v| v.get("api_key").or_else(|| v.get("api_key_secret")))
@@ -172,7 +173,8 @@ fn resolve_semantic_scholar_api_key() -> Option<String> {
         }
     }
     let content = fs::read_to_string(config_path()).ok()?;
-    let root = toml::from_str::<toml::Value>(&content).ok()?;
+    let mut root = toml::from_str::<toml::Value>(&content).ok()?;
+    ferrumyx_common::secrets::open_sealed_values(&mut root);
     root.get("ingestion")
         .and_then(|v| v.get("semanticscholar"))
         .and_then(|v| v.get("api_key").or_else(|| v.get("api_key_secret")))
@@ -237,7 +239,8 @@ fn toml_bool(root: &toml::Value, path: &[&str], default: bool) -> bool {
 fn resolve_default_embedding_cfg() -> Option<IngestionEmbeddingConfig> {
     let path = config_path();
     let content = fs::read_to_string(path).ok()?;
-    let root = toml::from_str::<toml::Value>(&content).ok()?;
+    let mut root = toml::from_str::<toml::Value>(&content).ok()?;
+    ferrumyx_common::secrets::open_sealed_values(&mut root);
     let enabled = toml_bool(
         &root,
         &["ingestion", "enable_embeddings"],
@@ -394,15 +397,16 @@ impl Tool for AutonomousCycleTool {
         })
     }
 
-    fn execution_timeout(&self) -> Duration {
+    fn timeout(&self) -> Option<Duration> {
         // Autonomous loop may run ingestion + scoring + provider refresh for multiple cycles.
-        Duration::from_secs(4 * 60 * 60)
+        Some(Duration::from_secs(4 * 60 * 60))
     }
 
     async fn execute(
         &self,
         params: serde_json::Value,
         _ctx: &JobContext,
+        _cancel: &CancellationToken,
     ) -> Result<ToolOutput, ToolError> {
         let gene = require_str(&params, "gene")?.to_string();
         let cancer_type = require_str(&params, "cancer_type")?.to_string();
@@ -558,6 +562,10 @@ impl Tool for AutonomousCycleTool {
                         full_text_prefetch_workers: None,
                         source_cache_enabled: cycle_source_cache_enabled,
                         source_cache_ttl_secs: cycle_source_cache_ttl_secs,
+                        memory_budget: Default::default(),
+                        repro_seed: None,
+                        since: None,
+                        incremental: false,
                     },
                     repo.clone(),
                     None,
@@ -0,0 +1,382 @@
+//! The design_molecules tool: the whole Phase 5 pipeline for one target,
+//! from its structure to a ranked list of docked ligands. Stage outputs are
+//! checkpointed under `<workspace>/molecules/`, so calling it again for the
+//! same gene and cancer type resumes an interrupted run. fpocket and Vina
+//! run in Docker and a run can take hours, so every call needs approval.
+//! Everything a run downloads or docks counts against the job's sandbox
+//! quota.
+
+use async_trait::async_trait;
+use ferrumyx_common::sandbox::Sandbox;
+use ferrumyx_db::{Database, DockingResultRepository, MoleculeCandidateRepository};
+use ferrumyx_ingestion::sources::ChemblClient;
+use ferrumyx_molecules::analogs::CandidateLigand;
+use ferrumyx_molecules::docking::{
+    DockingRun, LigandInput, ReceptorInput, DEFAULT_DOCKING_PARALLELISM,
+};
+use ferrumyx_molecules::ligand::LigandGenerator;
+use ferrumyx_molecules::pipeline::{
+    run_molecule_pipeline, PipelineConfig, PipelineError, PipelineProgress, PipelineStages,
+    DEFAULT_MAX_LIGANDS, DEFAULT_MAX_POCKETS,
+};
+use ferrumyx_molecules::pocket::{FPocketRunner, Pocket, DEFAULT_FPOCKET_TIMEOUT};
+use ferrumyx_molecules::tractability::{FetchedStructure, StructuralProvider};
+use ferrumyx_runtime::context::JobContext;
+use ferrumyx_runtime::tools::{
+    ApprovalRequirement, CancellationToken, Tool, ToolError, ToolOutput,
+};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, OnceCell};
+
+use super::structure_tool::{docking_batch, pocket_cache, uniprot_for_gene, usage};
+
+const MAX_POCKETS: usize = 5;
+const MAX_LIGANDS: usize = 200;
+
+/// Runs [`run_molecule_pipeline`] for a gene with the Docker-backed stages.
+pub struct DesignMoleculesTool {
+    structures_dir: PathBuf,
+    runs_dir: PathBuf,
+    fpocket_image: String,
+    fpocket_timeout: Duration,
+    vina_image: String,
+    rdkit_image: Option<String>,
+    parallelism: usize,
+    db: Option<Arc<Database>>,
+    progress: Option<broadcast::Sender<PipelineProgress>>,
+    hgnc: OnceCell<ferrumyx_kg::ner::HgncNormaliser>,
+}
+
+impl DesignMoleculesTool {
+    pub fn new(
+        workspace: impl AsRef<Path>,
+        fpocket_image: impl Into<String>,
+        vina_image: impl Into<String>,
+    ) -> Self {
+        let workspace = workspace.as_ref();
+        Self {
+            structures_dir: workspace.join("structures"),
+            runs_dir: workspace.join("molecules"),
+            fpocket_image: fpocket_image.into(),
+            fpocket_timeout: DEFAULT_FPOCKET_TIMEOUT,
+            vina_image: vina_image.into(),
+            rdkit_image: None,
+            parallelism: DEFAULT_DOCKING_PARALLELISM,
+            db: None,
+            progress: None,
+            hgnc: OnceCell::new(),
+        }
+    }
+
+    /// Give up on an fpocket container after `timeout`.
+    pub fn with_fpocket_timeout(mut self, timeout: Duration) -> Self {
+        self.fpocket_timeout = timeout;
+        self
+    }
+
+    /// Prepare PDBQT with the rdkit-service image's `rdkit-prep`.
+    pub fn with_rdkit_image(mut self, image: impl Into<String>) -> Self {
+        self.rdkit_image = Some(image.into());
+        self
+    }
+
+    /// Most ligands docked at once.
+    pub fn with_parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = parallelism;
+        self
+    }
+
+    /// Store the ranking in molecule_candidates and every docking in
+    /// docking_results.
+    pub fn with_store(mut self, db: Arc<Database>) -> Self {
+        self.db = Some(db);
+        self
+    }
+
+    /// Publish each stage's progress, for the web UI's event stream.
+    pub fn with_progress(mut self, progress: broadcast::Sender<PipelineProgress>) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+}
+
+/// The Docker-backed stages for one run.
+struct WorkspaceStages<'a> {
+    tool: &'a DesignMoleculesTool,
+    gene: &'a str,
+    uniprot_id: String,
+    exhaustiveness: u32,
+    sandbox: &'a Sandbox,
+    job_id: String,
+}
+
+impl WorkspaceStages<'_> {
+    /// Charge the files added to the structures directory since it held
+    /// `before` bytes.
+    fn charge_structures(&self, before: u64) -> anyhow::Result<()> {
+        let added = usage(&self.tool.structures_dir).saturating_sub(before);
+        Ok(self.sandbox.charge(&self.job_id, added)?)
+    }
+}
+
+#[async_trait]
+impl PipelineStages for WorkspaceStages<'_> {
+    async fn fetch_structure(&self, _gene: &str) -> anyhow::Result<FetchedStructure> {
+        let before = usage(&self.tool.structures_dir);
+        let structure = StructuralProvider::new(&self.tool.structures_dir)
+            .fetch_best_structure(&self.uniprot_id)
+            .await?;
+        self.charge_structures(before)?;
+        Ok(structure)
+    }
+
+    async fn detect_pockets(&self, structure: &FetchedStructure) -> anyhow::Result<Vec<Pocket>> {
+        let before = usage(&self.tool.structures_dir);
+        let pockets = FPocketRunner::new("fpocket")
+            .in_docker(&self.tool.fpocket_image)
+            .with_timeout(self.tool.fpocket_timeout)
+            .with_cache_dir(pocket_cache(&self.tool.structures_dir))
+            .detect(&structure.path)
+            .await?;
+        self.charge_structures(before)?;
+        Ok(pockets)
+    }
+
+    async fn generate_ligands(
+        &self,
+        gene: &str,
+        max_n: usize,
+    ) -> anyhow::Result<Vec<CandidateLigand>> {
+        let target = ChemblClient::new()
+            .resolve_target(gene, Some(&self.uniprot_id))
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("no ChEMBL target for {gene}"))?;
+        LigandGenerator::new()
+            .generate_candidates(&target, max_n)
+            .await
+    }
+
+    async fn dock(
+        &self,
+        structure: &FetchedStructure,
+        pocket: &Pocket,
+        ligands: &[CandidateLigand],
+        work_dir: &Path,
+    ) -> anyhow::Result<DockingRun> {
+        let inputs: Vec<LigandInput> = ligands
+            .iter()
+            .map(|l| LigandInput::new(&l.smiles, &l.smiles))
+            .collect();
+        let run = docking_batch(&self.tool.vina_image, self.tool.rdkit_image.as_deref())
+            .with_exhaustiveness(self.exhaustiveness)
+            .with_parallelism(self.tool.parallelism)
+            .dock(
+                &ReceptorInput::from_path(&structure.path),
+                pocket,
+                &inputs,
+                work_dir,
+            )
+            .await?;
+        if let Some(db) = &self.tool.db {
+            if let Err(e) = DockingResultRepository::new(db.clone())
+                .upsert_many(&run.records(self.gene))
+                .await
+            {
+                tracing::warn!("Storing docking results for {} failed: {e}", self.gene);
+            }
+        }
+        Ok(run)
+    }
+}
+
+#[async_trait]
+impl Tool for DesignMoleculesTool {
+    fn name(&self) -> &str {
+        "design_molecules"
+    }
+
+    fn description(&self) -> &str {
+        "Runs the full molecule design pipeline for a target gene: fetches its structure, detects pockets, generates ligands from ChEMBL actives and analogs, docks them into the top pockets with Vina, scores ADMET and returns the ranked candidates. Reruns resume from the last completed stage."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "gene": {
+                    "type": "string",
+                    "description": "HGNC gene symbol (for example: KRAS)"
+                },
+                "cancer_type": {
+                    "type": "string",
+                    "description": "Cancer type the run is for (for example: PAAD)"
+                },
+                "max_pockets": {
+                    "type": "integer",
+                    "description": "Top-ranked pockets to dock into",
+                    "minimum": 1,
+                    "maximum": MAX_POCKETS,
+                    "default": DEFAULT_MAX_POCKETS
+                },
+                "max_ligands": {
+                    "type": "integer",
+                    "description": "Ligands to generate and dock",
+                    "minimum": 1,
+                    "maximum": MAX_LIGANDS,
+                    "default": DEFAULT_MAX_LIGANDS
+                },
+                "exhaustiveness": {
+                    "type": "integer",
+                    "description": "Vina search exhaustiveness",
+                    "minimum": 1,
+                    "maximum": 32,
+                    "default": 8
+                },
+                "restart": {
+                    "type": "boolean",
+                    "description": "Discard the checkpoints of an earlier run instead of resuming it",
+                    "default": false
+                }
+            },
+            "required": ["gene", "cancer_type"]
+        })
+    }
+
+    fn requires_approval(&self, _params: &serde_json::Value) -> ApprovalRequirement {
+        ApprovalRequirement::Always
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        Some(Duration::from_secs(12 * 60 * 60))
+    }
+
+    /// Runs of the same target would share checkpoints, and runs of
+    /// different ones would compete for the same containers.
+    fn max_concurrency(&self) -> Option<usize> {
+        Some(1)
+    }
+
+    async fn execute(
+        &self,
+        params: serde_json::Value,
+        ctx: &JobContext,
+        _cancel: &CancellationToken,
+    ) -> Result<ToolOutput, ToolError> {
+        let started = Instant::now();
+        let sandbox = ferrumyx_runtime::tools::sandbox(ctx)?;
+        let job_id = ctx.job_id.to_string();
+        sandbox.confine(&self.structures_dir)?;
+        let gene = required_str(&params, "gene")?.to_uppercase();
+        let cancer_type = required_str(&params, "cancer_type")?.to_uppercase();
+        let limit = |name: &str, default: usize, max: usize| {
+            params
+                .get(name)
+                .and_then(|v| v.as_u64())
+                .map_or(default, |n| n as usize)
+                .clamp(1, max)
+        };
+        let config = PipelineConfig::new(&self.runs_dir)
+            .with_max_pockets(limit("max_pockets", DEFAULT_MAX_POCKETS, MAX_POCKETS))
+            .with_max_ligands(limit("max_ligands", DEFAULT_MAX_LIGANDS, MAX_LIGANDS))
+            .with_sandbox(Arc::clone(sandbox), job_id.clone());
+        let exhaustiveness = limit("exhaustiveness", 8, 32) as u32;
+
+        let run_dir = sandbox.confine(&config.run_dir(&gene, &cancer_type))?;
+        let restart = params
+            .get("restart")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if restart && run_dir.exists() {
+            tokio::fs::remove_dir_all(&run_dir).await.map_err(|e| {
+                ToolError::ExecutionFailed(format!("cannot clear {}: {e}", run_dir.display()))
+            })?;
+        }
+
+        let stages = WorkspaceStages {
+            tool: self,
+            gene: &gene,
+            uniprot_id: uniprot_for_gene(&self.hgnc, &gene).await?,
+            exhaustiveness,
+            sandbox,
+            job_id,
+        };
+        let progress = |p: PipelineProgress| {
+            if let Some(tx) = &self.progress {
+                let _ = tx.send(p);
+            }
+        };
+        let ranked = run_molecule_pipeline(&gene, &cancer_type, &config, &stages, &progress)
+            .await
+            .map_err(|e| match e {
+                PipelineError::Checkpoint { .. } => ToolError::ExecutionFailed(e.to_string()),
+                PipelineError::Sandbox(e) => e.into(),
+                PipelineError::Stage { .. } => {
+                    ToolError::ExecutionFailed(format!("{e}; call again to resume"))
+                }
+            })?;
+
+        let stored = match &self.db {
+            Some(db) => {
+                let records: Vec<_> = ranked
+                    .iter()
+                    .map(|c| c.record(&gene, &cancer_type))
+                    .collect();
+                match MoleculeCandidateRepository::new(db.clone())
+                    .replace_ranking(&gene, &cancer_type, &records)
+                    .await
+                {
+                    Ok(()) => true,
+                    Err(e) => {
+                        tracing::warn!("Storing ranked candidates for {gene} failed: {e}");
+                        false
+                    }
+                }
+            }
+            None => false,
+        };
+        let candidates: Vec<_> = ranked
+            .iter()
+            .map(|c| {
+                json!({
+                    "rank": c.rank,
+                    "smiles": c.ligand.smiles,
+                    "parent_chembl_id": c.ligand.parent_chembl_id,
+                    "novel": c.ligand.novelty,
+                    "pocket_rank": c.pocket_rank,
+                    "best_affinity_kcal_mol": c.best_affinity_kcal,
+                    "admet_score": c.admet.score,
+                    "sa_score": c.admet.sa_score,
+                    "pains_alerts": c.admet.pains_alerts,
+                    "composite_score": c.composite_score
+                })
+            })
+            .collect();
+
+        Ok(ToolOutput::success(
+            json!({
+                "status": "ok",
+                "gene": gene,
+                "cancer_type": cancer_type,
+                "uniprot_id": stages.uniprot_id,
+                "run_dir": run_dir,
+                "stored": stored,
+                "candidates": candidates
+            }),
+            started.elapsed(),
+        ))
+    }
+}
+
+fn required_str<'a>(params: &'a serde_json::Value, name: &str) -> Result<&'a str, ToolError> {
+    params
+        .get(name)
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| {
+            ToolError::InvalidParameters(format!("missing required string parameter: {name}"))
+        })
+}
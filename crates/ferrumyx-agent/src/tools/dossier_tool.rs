@@ -0,0 +1,206 @@
+//! The target_dossier tool: one evidence dossier per gene, or one for every
+//! primary-tier target of a cancer type, so the agent loop can hand
+//! reviewers a document per shortlisted target.
+
+use async_trait::async_trait;
+use ferrumyx_runtime::context::JobContext;
+use ferrumyx_runtime::tools::{CancellationToken, Tool, ToolError, ToolOutput};
+use serde_json::json;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::OnceCell;
+
+use ferrumyx_db::Database;
+use ferrumyx_ranker::dossier::{build_target_dossier, render_markdown};
+use ferrumyx_ranker::providers::depmap::DepMapClient;
+use ferrumyx_ranker::scorer::ShortlistTier;
+use ferrumyx_ranker::weights::WeightVector;
+
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 50;
+
+/// Builds [`ferrumyx_ranker::dossier::TargetDossier`]s from the persisted
+/// scores and the live providers.
+pub struct TargetDossierTool {
+    db: Arc<Database>,
+    weights: Arc<RwLock<WeightVector>>,
+    depmap: OnceCell<Option<DepMapClient>>,
+}
+
+impl TargetDossierTool {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self {
+            db,
+            weights: Arc::default(),
+            depmap: OnceCell::new(),
+        }
+    }
+
+    /// Explain scores with the shared, runtime-configurable weight vector.
+    pub fn with_weights(mut self, weights: Arc<RwLock<WeightVector>>) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    /// The cached DepMap release, loaded on first use. Without one the
+    /// dossiers fall back to the persisted dependency signal.
+    async fn depmap(&self) -> Option<&DepMapClient> {
+        self.depmap
+            .get_or_init(|| async {
+                match DepMapClient::load_cached().await {
+                    Ok(client) => client,
+                    Err(e) => {
+                        tracing::warn!("Loading the cached DepMap release failed: {e:#}");
+                        None
+                    }
+                }
+            })
+            .await
+            .as_ref()
+    }
+}
+
+#[async_trait]
+impl Tool for TargetDossierTool {
+    fn name(&self) -> &str {
+        "target_dossier"
+    }
+
+    fn description(&self) -> &str {
+        "Builds an evidence dossier for a target in a cancer type: score and explanation, DepMap \
+         dependency and co-dependencies, TCGA mutations and hotspots, expression, ChEMBL \
+         chemistry, structure, clinical trials, top KG facts with citations (reviews marked), \
+         conflicts and gene sets enriched in the shortlist. \
+         Without a gene, builds one for every primary-tier target. Missing sources are listed."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "cancer_type": {
+                    "type": "string",
+                    "description": "OncoTree code (e.g. PAAD)"
+                },
+                "gene": {
+                    "type": "string",
+                    "description": "HGNC symbol; omit to cover every primary-tier target"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Most primary-tier targets to cover, best first",
+                    "minimum": 1,
+                    "maximum": MAX_LIMIT,
+                    "default": DEFAULT_LIMIT
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Also save each dossier as <path>/<GENE>_<CANCER>.md, relative to the workspace"
+                }
+            },
+            "required": ["cancer_type"]
+        })
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        Some(Duration::from_secs(30 * 60))
+    }
+
+    async fn execute(
+        &self,
+        params: serde_json::Value,
+        ctx: &JobContext,
+        _cancel: &CancellationToken,
+    ) -> Result<ToolOutput, ToolError> {
+        let started = Instant::now();
+        let str_param = |name: &str| {
+            params
+                .get(name)
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+        };
+        let cancer_type = str_param("cancer_type")
+            .ok_or_else(|| ToolError::InvalidParameters("cancer_type is required".to_string()))?
+            .to_uppercase();
+        let limit = params
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .map_or(DEFAULT_LIMIT, |n| n as usize)
+            .clamp(1, MAX_LIMIT);
+        let weights = self
+            .weights
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+
+        let genes = match str_param("gene") {
+            Some(gene) => vec![gene.to_uppercase()],
+            None => {
+                let rows =
+                    ferrumyx_ranker::report::load_export_rows(self.db.clone(), Some(&cancer_type))
+                        .await
+                        .map_err(|e| {
+                            ToolError::ExecutionFailed(format!("loading target scores failed: {e}"))
+                        })?;
+                rows.into_iter()
+                    .filter(|r| r.tier == ShortlistTier::Primary.as_str())
+                    .take(limit)
+                    .map(|r| r.gene)
+                    .collect()
+            }
+        };
+
+        let dir = match str_param("path") {
+            Some(path) => {
+                let sandbox = ferrumyx_runtime::tools::sandbox(ctx)?;
+                Some((sandbox, sandbox.resolve(path)?))
+            }
+            None => None,
+        };
+        let depmap = self.depmap().await;
+        let mut dossiers = Vec::with_capacity(genes.len());
+        for gene in &genes {
+            let dossier =
+                build_target_dossier(self.db.clone(), gene, &cancer_type, &weights, depmap, true)
+                    .await;
+            let markdown = render_markdown(&dossier);
+            let path = match &dir {
+                Some((sandbox, dir)) => {
+                    let name = format!("{}_{}.md", dossier.gene, dossier.cancer_type);
+                    let path = dir.join(ferrumyx_common::sandbox::file_name(&name)?);
+                    sandbox.charge(&ctx.job_id.to_string(), markdown.len() as u64)?;
+                    tokio::fs::create_dir_all(dir).await.ok();
+                    tokio::fs::write(&path, &markdown).await.map_err(|e| {
+                        ToolError::ExecutionFailed(format!(
+                            "failed to write dossier to {}: {e}",
+                            path.display()
+                        ))
+                    })?;
+                    Some(path)
+                }
+                None => None,
+            };
+            let mut entry = json!({
+                "gene": dossier.gene,
+                "missing_sections": dossier.missing_sections(),
+                "path": path,
+                "markdown": markdown,
+            });
+            if genes.len() == 1 {
+                entry["dossier"] = json!(dossier);
+            }
+            dossiers.push(entry);
+        }
+
+        Ok(ToolOutput::success(
+            json!({
+                "status": "ok",
+                "cancer_type": cancer_type,
+                "count": dossiers.len(),
+                "dossiers": dossiers,
+            }),
+            started.elapsed(),
+        ))
+    }
+}
@@ -1,11 +1,12 @@
 use async_trait::async_trait;
 use ferrumyx_db::Database;
 use ferrumyx_ingestion::embedding::{
-    embed_pending_chunks_for_papers, EmbeddingClient, EmbeddingConfig as IngestionEmbeddingConfig,
+    embed_missing_chunks, embed_pending_chunks_for_papers, EmbeddingClient,
+    EmbeddingConfig as IngestionEmbeddingConfig,
 };
 use ferrumyx_ingestion::repository::IngestionRepository;
 use ferrumyx_runtime::context::JobContext;
-use ferrumyx_runtime::tools::{Tool, ToolError, ToolOutput};
+use ferrumyx_runtime::tools::{CancellationToken, Tool, ToolError, ToolOutput};
 use serde_json::json;
 use std::collections::HashSet;
 use std::sync::Arc;
@@ -120,9 +121,9 @@ impl Tool for BackfillEmbeddingsTool {
         &self,
         params: serde_json::Value,
         _ctx: &JobContext,
+        _cancel: &CancellationToken,
     ) -> Result<ToolOutput, ToolError> {
         let defaults = load_runtime_defaults();
-        let profile = RuntimeProfile::detect_and_prepare();
         let input = parse_input(&params)?;
         let effective_scan_limit = input
             .scan_limit
@@ -133,25 +134,13 @@ impl Tool for BackfillEmbeddingsTool {
             .len()
             .max(effective_scan_limit.unwrap_or(defaults.max_results))
             .max(1);
-        let perf_mode = match defaults.perf_mode.as_str() {
-            "throughput" => "throughput",
-            "balanced" => "balanced",
-            "safe" => "safe",
-            _ => "auto",
-        };
-        let resolved = resolve_embedding_runtime(
-            &defaults,
-            &profile,
-            perf_mode,
-            requested_max_results,
-        );
         let ResolvedEmbeddingRuntime {
             cfg: embedding_cfg,
             speed_mode,
             async_backfill_enabled,
             throughput_chunk_cap,
             ..
-        } = resolved;
+        } = resolve_tool_embedding_runtime(requested_max_results);
         let Some(embedding_cfg) = embedding_cfg else {
             return Err(ToolError::ExecutionFailed(
                 "embedding is not configured for this runtime".to_string(),
@@ -187,6 +176,101 @@ impl Tool for BackfillEmbeddingsTool {
     }
 }
 
+/// Embeds every stored chunk left without a vector, across all papers.
+pub struct EmbedBackfillTool {
+    db: Arc<Database>,
+}
+
+impl EmbedBackfillTool {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl Tool for EmbedBackfillTool {
+    fn name(&self) -> &str {
+        "embed_backfill"
+    }
+
+    fn description(&self) -> &str {
+        "Embeds all stored chunks that have no embedding yet (e.g. after an embedding quota or memory failure), in batches with retry. Safe to run during an ingestion."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "batch_size": {
+                    "type": "integer",
+                    "description": "Chunks per embedding request (default: the configured embedding batch size)"
+                }
+            }
+        })
+    }
+
+    async fn execute(
+        &self,
+        params: serde_json::Value,
+        _ctx: &JobContext,
+        _cancel: &CancellationToken,
+    ) -> Result<ToolOutput, ToolError> {
+        let resolved = resolve_tool_embedding_runtime(load_runtime_defaults().max_results);
+        let Some(embedding_cfg) = resolved.cfg else {
+            return Err(ToolError::ExecutionFailed(
+                "embedding is not configured for this runtime".to_string(),
+            ));
+        };
+        let batch_size = params
+            .get("batch_size")
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize)
+            .filter(|n| *n > 0)
+            .unwrap_or(resolved.batch_size_effective.max(1));
+
+        let started = std::time::Instant::now();
+        let repo = IngestionRepository::new(self.db.clone());
+        let report = embed_missing_chunks(&repo, embedding_cfg, batch_size, |p| {
+            tracing::info!(
+                pending = p.pending,
+                embedded = p.embedded,
+                batches = p.batches,
+                "Embedding backfill progress"
+            );
+        })
+        .await
+        .map_err(|e| ToolError::ExecutionFailed(format!("embed backfill failed: {e}")))?;
+
+        Ok(ToolOutput::success(
+            json!({
+                "status": if report.error.is_some() { "partial" } else { "ok" },
+                "embedding_speed_mode": resolved.speed_mode,
+                "batch_size": batch_size,
+                "chunks_pending": report.pending,
+                "chunks_embedded": report.embedded,
+                "chunks_skipped": report.skipped,
+                "batches": report.batches,
+                "error": report.error,
+            }),
+            started.elapsed(),
+        ))
+    }
+}
+
+pub(crate) fn resolve_tool_embedding_runtime(
+    requested_max_results: usize,
+) -> ResolvedEmbeddingRuntime {
+    let defaults = load_runtime_defaults();
+    let profile = RuntimeProfile::detect_and_prepare();
+    let perf_mode = match defaults.perf_mode.as_str() {
+        "throughput" => "throughput",
+        "balanced" => "balanced",
+        "safe" => "safe",
+        _ => "auto",
+    };
+    resolve_embedding_runtime(&defaults, &profile, perf_mode, requested_max_results)
+}
+
 async fn resolve_backfill_targets(
     repo: &IngestionRepository,
     paper_ids: Vec<Uuid>,
@@ -0,0 +1,104 @@
+use async_trait::async_trait;
+use ferrumyx_db::Database;
+use ferrumyx_ingestion::enrichment::{enrich_missing, enrich_paper_ids, enrichment_client};
+use ferrumyx_runtime::context::JobContext;
+use ferrumyx_runtime::tools::{CancellationToken, Tool, ToolError, ToolOutput};
+use serde_json::json;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::ingestion_tool::load_runtime_defaults;
+
+const DEFAULT_SCAN_LIMIT: usize = 200;
+
+pub struct EnrichPapersTool {
+    db: Arc<Database>,
+}
+
+impl EnrichPapersTool {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl Tool for EnrichPapersTool {
+    fn name(&self) -> &str {
+        "enrich_papers"
+    }
+
+    fn description(&self) -> &str {
+        "Fills missing DOIs and citation counts from CrossRef, by paper_id list or by scanning papers that lack them."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "paper_ids": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Optional list of paper UUIDs to enrich"
+                },
+                "scan_limit": {
+                    "type": "integer",
+                    "description": "Papers missing a DOI or citation count to scan when no paper_ids are given (default 200)"
+                }
+            }
+        })
+    }
+
+    async fn execute(
+        &self,
+        params: serde_json::Value,
+        _ctx: &JobContext,
+        _cancel: &CancellationToken,
+    ) -> Result<ToolOutput, ToolError> {
+        let defaults = load_runtime_defaults();
+        let paper_ids = parse_paper_ids(&params)?;
+        let scan_limit = params
+            .get("scan_limit")
+            .and_then(|v| v.as_u64())
+            .map(|n| (n as usize).clamp(1, 5_000))
+            .unwrap_or(DEFAULT_SCAN_LIMIT);
+
+        let started = std::time::Instant::now();
+        let client = enrichment_client(
+            defaults.crossref_mailto.clone(),
+            defaults.crossref_requests_per_second,
+        );
+        let report = if paper_ids.is_empty() {
+            enrich_missing(self.db.clone(), &client, scan_limit).await
+        } else {
+            enrich_paper_ids(self.db.clone(), &client, &paper_ids).await
+        }
+        .map_err(|e| ToolError::ExecutionFailed(format!("enrichment failed: {e}")))?;
+
+        Ok(ToolOutput::success(
+            json!({
+                "status": "ok",
+                "papers_scanned": report.scanned,
+                "dois_filled": report.dois_filled,
+                "citation_counts_updated": report.citation_counts_updated,
+                "errors": report.errors,
+            }),
+            started.elapsed(),
+        ))
+    }
+}
+
+fn parse_paper_ids(params: &serde_json::Value) -> Result<Vec<Uuid>, ToolError> {
+    let mut paper_ids = Vec::new();
+    if let Some(raw_ids) = params.get("paper_ids").and_then(|v| v.as_array()) {
+        for value in raw_ids {
+            let raw = value.as_str().ok_or_else(|| {
+                ToolError::InvalidParameters("paper_ids must be strings".to_string())
+            })?;
+            let paper_id = Uuid::parse_str(raw).map_err(|e| {
+                ToolError::InvalidParameters(format!("invalid paper_id '{raw}': {e}"))
+            })?;
+            paper_ids.push(paper_id);
+        }
+    }
+    Ok(paper_ids)
+}
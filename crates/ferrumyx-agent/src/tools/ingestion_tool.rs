@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 use ferrumyx_runtime::context::JobContext;
-use ferrumyx_runtime::tools::{Tool, ToolError, ToolOutput};
+use ferrumyx_runtime::tools::{CancellationToken, Tool, ToolError, ToolOutput};
 use serde_json::json;
 use std::fs;
 use std::path::PathBuf;
@@ -12,6 +12,7 @@ use tokio::time::{timeout, Instant};
 use super::runtime_profile::RuntimeProfile;
 use super::embedding_backfill_tool::backfill_embeddings_for_papers;
 use ferrumyx_db::Database;
+use ferrumyx_ingestion::backpressure::PipelineMemoryBudget;
 use ferrumyx_ingestion::embedding::{
     fastembed_enabled, EmbeddingBackend as IngestionEmbeddingBackend,
     EmbeddingConfig as IngestionEmbeddingConfig,
@@ -76,9 +77,13 @@ pub(crate) struct IngestionRuntimeDefaults {
     pubmed_api_key: Option<String>,
     semantic_scholar_api_key: Option<String>,
     unpaywall_email: Option<String>,
+    pub(crate) crossref_enrich_enabled: bool,
+    pub(crate) crossref_mailto: Option<String>,
+    pub(crate) crossref_requests_per_second: u32,
+    citation_harvest_enabled: bool,
+    scihub_enabled: bool,
     scihub_domain_parallelism: usize,
     scihub_domain_cooldown_secs: u64,
-    scihub_defer_ms: u64,
     scihub_adaptive_enabled: bool,
     scihub_adaptive_fail_streak: u64,
     scihub_adaptive_backoff_secs: u64,
@@ -91,6 +96,7 @@ pub(crate) struct IngestionRuntimeDefaults {
     embedding_global_batch: bool,
     embedding_throughput_chunk_cap: Option<usize>,
     embedding_cfg: Option<IngestionEmbeddingConfig>,
+    memory_budget_mb: Option<usize>,
 }
 
 impl Default for IngestionRuntimeDefaults {
@@ -287,6 +293,20 @@ impl Default for IngestionRuntimeDefaults {
             unpaywall_email: std::env::var("FERRUMYX_UNPAYWALL_EMAIL")
                 .ok()
                 .filter(|v| !v.trim().is_empty()),
+            crossref_enrich_enabled: std::env::var("FERRUMYX_CROSSREF_ENRICH_ENABLED")
+                .ok()
+                .is_none_or(|v| !(v == "0" || v.eq_ignore_ascii_case("false"))),
+            citation_harvest_enabled: std::env::var("FERRUMYX_CITATION_HARVEST_ENABLED")
+                .ok()
+                .is_none_or(|v| !(v == "0" || v.eq_ignore_ascii_case("false"))),
+            crossref_mailto: std::env::var("FERRUMYX_CROSSREF_MAILTO")
+                .ok()
+                .filter(|v| !v.trim().is_empty()),
+            crossref_requests_per_second: std::env::var("FERRUMYX_CROSSREF_REQUESTS_PER_SECOND")
+                .ok()
+                .and_then(|v| v.parse::<u32>().ok())
+                .unwrap_or(5)
+                .clamp(1, 50),
             scihub_domain_parallelism: std::env::var("FERRUMYX_SCIHUB_DOMAIN_PARALLELISM")
                 .ok()
                 .and_then(|v| v.parse::<usize>().ok())
@@ -297,11 +317,9 @@ impl Default for IngestionRuntimeDefaults {
                 .and_then(|v| v.parse::<u64>().ok())
                 .unwrap_or(300)
                 .clamp(15, 3600),
-            scihub_defer_ms: std::env::var("FERRUMYX_SCIHUB_DEFER_MS")
+            scihub_enabled: std::env::var("FERRUMYX_SCIHUB_ENABLED")
                 .ok()
-                .and_then(|v| v.parse::<u64>().ok())
-                .unwrap_or(700)
-                .clamp(0, 10_000),
+                .is_some_and(|v| v == "1" || v.eq_ignore_ascii_case("true")),
             scihub_adaptive_enabled: std::env::var("FERRUMYX_SCIHUB_ADAPTIVE_ENABLED")
                 .ok()
                 .is_none_or(|v| v == "1" || v.eq_ignore_ascii_case("true")),
@@ -352,6 +370,10 @@ impl Default for IngestionRuntimeDefaults {
             .and_then(|v| v.parse::<usize>().ok())
             .map(|v| v.clamp(1, 4096)),
             embedding_cfg: None,
+            memory_budget_mb: std::env::var("FERRUMYX_INGESTION_MEMORY_BUDGET_MB")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .map(|v| v.clamp(16, 64 * 1024)),
         }
     }
 }
@@ -424,9 +446,10 @@ pub(crate) fn load_runtime_defaults() -> IngestionRuntimeDefaults {
     let Ok(content) = fs::read_to_string(path) else {
         return defaults;
     };
-    let Ok(root) = toml::from_str::<toml::Value>(&content) else {
+    let Ok(mut root) = toml::from_str::<toml::Value>(&content) else {
         return defaults;
     };
+    ferrumyx_common::secrets::open_sealed_values(&mut root);
 
     defaults.max_results = toml_u64(
         &root,
@@ -518,6 +541,15 @@ pub(crate) fn load_runtime_defaults() -> IngestionRuntimeDefaults {
         &["ingestion", "performance", "strict_fuzzy_dedup"],
         false,
     );
+    defaults.memory_budget_mb = Some(
+        toml_u64(
+            &root,
+            &["ingestion", "performance", "memory_budget_mb"],
+            defaults.memory_budget_mb.unwrap_or(0) as u64,
+        )
+        .clamp(0, 64 * 1024) as usize,
+    )
+    .filter(|v| *v > 0);
     defaults.source_max_inflight = toml_u64(
         &root,
         &["ingestion", "performance", "source_max_inflight"],
@@ -713,6 +745,26 @@ pub(crate) fn load_runtime_defaults() -> IngestionRuntimeDefaults {
         defaults.unpaywall_email =
             first_nonempty_toml_string(&root, &[&["ingestion", "unpaywall", "email"]]);
     }
+    defaults.crossref_enrich_enabled = toml_bool(
+        &root,
+        &["ingestion", "crossref", "enrich_enabled"],
+        defaults.crossref_enrich_enabled,
+    );
+    defaults.citation_harvest_enabled = toml_bool(
+        &root,
+        &["ingestion", "crossref", "citations_enabled"],
+        defaults.citation_harvest_enabled,
+    );
+    if defaults.crossref_mailto.is_none() {
+        defaults.crossref_mailto =
+            first_nonempty_toml_string(&root, &[&["ingestion", "crossref", "mailto"]]);
+    }
+    defaults.crossref_requests_per_second = toml_u64(
+        &root,
+        &["ingestion", "crossref", "requests_per_second"],
+        defaults.crossref_requests_per_second as u64,
+    )
+    .clamp(1, 50) as u32;
     defaults.scihub_domain_parallelism = toml_u64(
         &root,
         &["ingestion", "scihub", "domain_parallelism"],
@@ -725,12 +777,11 @@ pub(crate) fn load_runtime_defaults() -> IngestionRuntimeDefaults {
         defaults.scihub_domain_cooldown_secs,
     )
     .clamp(15, 3600);
-    defaults.scihub_defer_ms = toml_u64(
+    defaults.scihub_enabled = toml_bool(
         &root,
-        &["ingestion", "scihub", "defer_ms"],
-        defaults.scihub_defer_ms,
-    )
-    .clamp(0, 10_000);
+        &["ingestion", "scihub", "enabled"],
+        defaults.scihub_enabled,
+    );
     defaults.scihub_adaptive_enabled = toml_bool(
         &root,
         &["ingestion", "scihub", "adaptive_enabled"],
@@ -841,6 +892,29 @@ pub(crate) fn load_runtime_defaults() -> IngestionRuntimeDefaults {
     defaults
 }
 
+/// Job settings shared by every `[[ingestion.schedules]]` run: API keys,
+/// timeouts, caching and embedding from `[ingestion]`. Each schedule fills
+/// in its own query, sources and result cap.
+pub(crate) fn scheduled_job_template() -> IngestionJob {
+    let defaults = load_runtime_defaults();
+    IngestionJob {
+        pubmed_api_key: defaults.pubmed_api_key,
+        semantic_scholar_api_key: defaults.semantic_scholar_api_key,
+        unpaywall_email: defaults.unpaywall_email,
+        embedding_cfg: defaults.embedding_cfg,
+        source_timeout_secs: defaults.source_timeout_secs.or(Some(45)),
+        full_text_step_timeout_secs: defaults.full_text_step_timeout_secs,
+        full_text_prefetch_workers: defaults.full_text_prefetch_workers,
+        source_cache_enabled: defaults.source_cache_enabled,
+        source_cache_ttl_secs: Some(defaults.source_cache_ttl_secs),
+        memory_budget: defaults
+            .memory_budget_mb
+            .map(|mb| PipelineMemoryBudget::with_budget_mb(mb.clamp(16, 64 * 1024)))
+            .unwrap_or_default(),
+        ..IngestionJob::default()
+    }
+}
+
 fn build_source_list(profile: &str, include_semantic: bool) -> Vec<IngestionSourceSpec> {
     let mut sources = if profile == "full" {
         vec![
@@ -938,6 +1012,16 @@ fn resolve_auto_fastembed_enabled() -> bool {
         .is_none_or(|v| v == "1" || v.eq_ignore_ascii_case("true"))
 }
 
+/// Vector width the configured embedding backend produces, used to size a
+/// new chunks table. Local BiomedBERT backends always emit 768 dims.
+pub(crate) fn configured_embedding_dim() -> Option<usize> {
+    let cfg = load_runtime_defaults().embedding_cfg?;
+    Some(match cfg.backend {
+        IngestionEmbeddingBackend::RustNative | IngestionEmbeddingBackend::BiomedBert => 768,
+        _ => cfg.dim,
+    })
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct ResolvedEmbeddingRuntime {
     pub cfg: Option<IngestionEmbeddingConfig>,
@@ -1079,22 +1163,35 @@ impl Tool for IngestionTool {
                 "max_runtime_secs": {
                     "type": "integer",
                     "description": "Soft safety cap for total ingestion runtime in seconds (default: 14400)"
+                },
+                "memory_budget_mb": {
+                    "type": "integer",
+                    "description": "Chunk buffer memory budget in MB; papers beyond it spill to DB-backed embedding (default: 512)"
+                },
+                "repro_seed": {
+                    "type": "integer",
+                    "description": "Optional reproducibility seed; reruns with the same seed and inputs persist identical records"
+                },
+                "incremental": {
+                    "type": "boolean",
+                    "description": "Only search PubMed/Europe PMC for papers added since the last run of the same query (default: false)"
                 }
             },
             "required": ["gene", "cancer_type"]
         })
     }
 
-    fn execution_timeout(&self) -> Duration {
+    fn timeout(&self) -> Option<Duration> {
         // Keep framework-level timeout very high; activity-based watchdog below
         // performs stall detection and controlled termination.
-        Duration::from_secs(6 * 60 * 60)
+        Some(Duration::from_secs(6 * 60 * 60))
     }
 
     async fn execute(
         &self,
         params: serde_json::Value,
         _ctx: &JobContext,
+        cancel: &CancellationToken,
     ) -> Result<ToolOutput, ToolError> {
         let defaults = load_runtime_defaults();
         let profile = RuntimeProfile::detect_and_prepare();
@@ -1242,6 +1339,29 @@ impl Tool for IngestionTool {
             "FERRUMYX_INGESTION_MIN_NER_CHARS",
             defaults.min_ner_chars.to_string(),
         );
+        std::env::set_var(
+            "FERRUMYX_CROSSREF_ENRICH_ENABLED",
+            if defaults.crossref_enrich_enabled {
+                "1"
+            } else {
+                "0"
+            },
+        );
+        std::env::set_var(
+            "FERRUMYX_CITATION_HARVEST_ENABLED",
+            if defaults.citation_harvest_enabled {
+                "1"
+            } else {
+                "0"
+            },
+        );
+        std::env::set_var(
+            "FERRUMYX_CROSSREF_REQUESTS_PER_SECOND",
+            defaults.crossref_requests_per_second.to_string(),
+        );
+        if let Some(mailto) = &defaults.crossref_mailto {
+            std::env::set_var("FERRUMYX_CROSSREF_MAILTO", mailto);
+        }
         if let Some(v) = defaults.full_text_total_timeout_secs {
             std::env::set_var(
                 "FERRUMYX_INGESTION_FULLTEXT_TOTAL_TIMEOUT_SECS",
@@ -1276,10 +1396,6 @@ impl Tool for IngestionTool {
             "FERRUMYX_SCIHUB_DOMAIN_COOLDOWN_SECS",
             defaults.scihub_domain_cooldown_secs.to_string(),
         );
-        std::env::set_var(
-            "FERRUMYX_SCIHUB_DEFER_MS",
-            defaults.scihub_defer_ms.to_string(),
-        );
         std::env::set_var(
             "FERRUMYX_SCIHUB_ADAPTIVE_ENABLED",
             if defaults.scihub_adaptive_enabled {
@@ -1405,13 +1521,26 @@ impl Tool for IngestionTool {
             semantic_scholar_api_key: defaults.semantic_scholar_api_key,
             unpaywall_email: defaults.unpaywall_email,
             embedding_cfg,
-            enable_scihub_fallback: false,
+            enable_scihub_fallback: defaults.scihub_enabled,
             full_text_enabled: profile.use_full_text_default(),
             source_timeout_secs,
             full_text_step_timeout_secs: defaults.full_text_step_timeout_secs,
             full_text_prefetch_workers,
             source_cache_enabled: defaults.source_cache_enabled,
             source_cache_ttl_secs: Some(defaults.source_cache_ttl_secs),
+            memory_budget: params
+                .get("memory_budget_mb")
+                .and_then(|v| v.as_u64())
+                .map(|mb| mb as usize)
+                .or(defaults.memory_budget_mb)
+                .map(|mb| PipelineMemoryBudget::with_budget_mb(mb.clamp(16, 64 * 1024)))
+                .unwrap_or_default(),
+            repro_seed: params.get("repro_seed").and_then(|v| v.as_u64()),
+            since: None,
+            incremental: params
+                .get("incremental")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
         };
 
         let repo = Arc::new(IngestionRepository::new(self.db.clone()));
@@ -1419,6 +1548,14 @@ impl Tool for IngestionTool {
         let ingest_repo = repo.clone();
         let ingest_task =
             tokio::spawn(async move { run_ingestion(job, ingest_repo, Some(progress_tx)).await });
+        // The registry cancels the token when it gives up on this call; the
+        // spawned run would otherwise outlive the dropped handle.
+        let abort = ingest_task.abort_handle();
+        let cancel = cancel.clone();
+        tokio::spawn(async move {
+            cancel.cancelled().await;
+            abort.abort();
+        });
 
         let started_at = Instant::now();
         let hard_deadline = started_at + max_runtime;
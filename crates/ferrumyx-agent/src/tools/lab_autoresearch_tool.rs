@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 use ferrumyx_runtime::context::JobContext;
-use ferrumyx_runtime::tools::{Tool, ToolError, ToolOutput};
+use ferrumyx_runtime::tools::{CancellationToken, Tool, ToolError, ToolOutput};
 use serde_json::{json, Value};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -71,14 +71,15 @@ impl Tool for LabAutoresearchTool {
         })
     }
 
-    fn execution_timeout(&self) -> Duration {
-        Duration::from_secs(4 * 60 * 60)
+    fn timeout(&self) -> Option<Duration> {
+        Some(Duration::from_secs(4 * 60 * 60))
     }
 
     async fn execute(
         &self,
         params: serde_json::Value,
         ctx: &JobContext,
+        cancel: &CancellationToken,
     ) -> Result<ToolOutput, ToolError> {
         let started = Instant::now();
         let objective = require_nonempty_str(&params, "objective")?.to_string();
@@ -131,6 +132,7 @@ impl Tool for LabAutoresearchTool {
                     "max_cycles_hint": max_cycles_safety
                 }),
                 ctx,
+                cancel,
             )
             .await?;
         let planner_payload = planner_output.result;
@@ -172,7 +174,7 @@ impl Tool for LabAutoresearchTool {
                 retriever_params["mutation"] = json!(mutn);
             }
 
-            let retriever_output = retriever.execute(retriever_params, ctx).await?;
+            let retriever_output = retriever.execute(retriever_params, ctx, cancel).await?;
             let retriever_payload = retriever_output.result;
             let parsed_metrics = retriever_payload
                 .get("parsed_metrics")
@@ -196,6 +198,7 @@ impl Tool for LabAutoresearchTool {
                         "max_results": validation_top_n
                     }),
                     ctx,
+                    cancel,
                 )
                 .await?;
             let validator_payload = validator_output.result;
@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 use ferrumyx_runtime::context::JobContext;
-use ferrumyx_runtime::tools::{Tool, ToolError, ToolOutput};
+use ferrumyx_runtime::tools::{CancellationToken, Tool, ToolError, ToolOutput};
 use serde_json::json;
 
 use super::lab_state;
@@ -42,6 +42,7 @@ impl Tool for LabPlannerTool {
         &self,
         params: serde_json::Value,
         _ctx: &JobContext,
+        _cancel: &CancellationToken,
     ) -> Result<ToolOutput, ToolError> {
         let started = std::time::Instant::now();
         let objective = require_nonempty_str(&params, "objective")?.to_string();
@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 use ferrumyx_runtime::context::JobContext;
-use ferrumyx_runtime::tools::{Tool, ToolError, ToolOutput};
+use ferrumyx_runtime::tools::{CancellationToken, Tool, ToolError, ToolOutput};
 use serde_json::json;
 use std::sync::Arc;
 
@@ -48,6 +48,7 @@ impl Tool for LabRetrieverTool {
         &self,
         params: serde_json::Value,
         ctx: &JobContext,
+        cancel: &CancellationToken,
     ) -> Result<ToolOutput, ToolError> {
         let started = std::time::Instant::now();
         let run_id = require_nonempty_str(&params, "run_id")?;
@@ -104,7 +105,9 @@ impl Tool for LabRetrieverTool {
             ingestion_params["mutation"] = json!(mutation);
         }
 
-        let ingestion_output = ingestion_tool.execute(ingestion_params, ctx).await?;
+        let ingestion_output = ingestion_tool
+            .execute(ingestion_params, ctx, cancel)
+            .await?;
         let ingestion_summary = ingestion_output
             .result
             .as_str()
@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 use ferrumyx_runtime::context::JobContext;
-use ferrumyx_runtime::tools::{Tool, ToolError, ToolOutput};
+use ferrumyx_runtime::tools::{CancellationToken, Tool, ToolError, ToolOutput};
 use serde_json::json;
 use std::sync::Arc;
 
@@ -47,6 +47,7 @@ impl Tool for LabValidatorTool {
         &self,
         params: serde_json::Value,
         ctx: &JobContext,
+        cancel: &CancellationToken,
     ) -> Result<ToolOutput, ToolError> {
         let started = std::time::Instant::now();
         let run_id = require_nonempty_str(&params, "run_id")?;
@@ -69,11 +70,11 @@ impl Tool for LabValidatorTool {
 
         // Keep rankings fresh before validation.
         let scoring_tool = RecomputeTargetScoresTool::new(self.db.clone());
-        let scoring_result = scoring_tool.execute(json!({}), ctx).await.ok();
+        let scoring_result = scoring_tool.execute(json!({}), ctx, cancel).await.ok();
 
         let status_tool = WorkflowStatusTool::new(self.db.clone());
         let status_result = status_tool
-            .execute(json!({ "top_n": max_results }), ctx)
+            .execute(json!({ "top_n": max_results }), ctx, cancel)
             .await?;
 
         let query_tool = TargetQueryTool::new(self.db.clone());
@@ -86,6 +87,7 @@ impl Tool for LabValidatorTool {
                     "max_results": max_results
                 }),
                 ctx,
+                cancel,
             )
             .await?;
 
@@ -1,5 +1,8 @@
 pub mod autonomous_cycle_tool;
+pub mod design_tool;
+pub mod dossier_tool;
 pub mod embedding_backfill_tool;
+pub mod enrich_papers_tool;
 pub mod ingestion_tool;
 pub mod lab_autoresearch_tool;
 pub mod lab_planner_tool;
@@ -12,5 +15,8 @@ pub mod provider_refresh_tool;
 pub mod query_tool;
 pub mod runtime_profile;
 pub mod scoring_tool;
+pub mod snapshot_tool;
+pub mod structure_tool;
 pub mod system_command_tool;
+pub mod target_report_tool;
 pub mod workflow_status_tool;
@@ -1,7 +1,14 @@
 use async_trait::async_trait;
+use ferrumyx_db::{CandidateLigandRepository, Database};
+use ferrumyx_molecules::ligand::LigandGenerator;
 use ferrumyx_runtime::context::JobContext;
-use ferrumyx_runtime::tools::{Tool, ToolError, ToolOutput};
+use ferrumyx_runtime::tools::{CancellationToken, Tool, ToolError, ToolOutput};
 use serde_json::json;
+use std::sync::Arc;
+
+use super::structure_tool::usage;
+
+const MAX_CANDIDATES: usize = 200;
 
 /// Tool to run the molecular pipeline for a target protein identifier.
 pub struct RunMoleculePipelineTool;
@@ -42,7 +49,8 @@ impl Tool for RunMoleculePipelineTool {
     async fn execute(
         &self,
         params: serde_json::Value,
-        _ctx: &JobContext,
+        ctx: &JobContext,
+        _cancel: &CancellationToken,
     ) -> Result<ToolOutput, ToolError> {
         let uniprot_id = require_str(&params, "uniprot_id")?.to_string();
         let max_results = params
@@ -53,11 +61,18 @@ impl Tool for RunMoleculePipelineTool {
             .clamp(1, 50);
 
         let started = std::time::Instant::now();
-        let pipeline = ferrumyx_molecules::pipeline::MoleculesPipeline::new("data/molecules");
+        let sandbox = ferrumyx_runtime::tools::sandbox(ctx)?;
+        let structures_dir = sandbox.resolve("structures")?;
+        let before = usage(&structures_dir);
+        let pipeline = ferrumyx_molecules::pipeline::MoleculesPipeline::new(&structures_dir);
         let ranked = pipeline
             .run(&uniprot_id)
             .await
             .map_err(|e| ToolError::ExecutionFailed(format!("molecule pipeline failed: {e}")))?;
+        sandbox.charge(
+            &ctx.job_id.to_string(),
+            usage(&structures_dir).saturating_sub(before),
+        )?;
 
         let top: Vec<_> = ranked
             .into_iter()
@@ -84,6 +99,106 @@ impl Tool for RunMoleculePipelineTool {
     }
 }
 
+/// Proposes docking candidates for a ChEMBL target from its known actives
+/// and their R-group analogs.
+pub struct GenerateCandidatesTool {
+    db: Option<Arc<Database>>,
+}
+
+impl GenerateCandidatesTool {
+    pub fn new() -> Self {
+        Self { db: None }
+    }
+
+    /// Store the candidates in candidate_ligands for the docking stage.
+    pub fn with_store(mut self, db: Arc<Database>) -> Self {
+        self.db = Some(db);
+        self
+    }
+}
+
+#[async_trait]
+impl Tool for GenerateCandidatesTool {
+    fn name(&self) -> &str {
+        "generate_candidates"
+    }
+
+    fn description(&self) -> &str {
+        "Proposes ligands for a ChEMBL target: diverse known actives per Murcko scaffold, then novel R-group analogs of them. Returns canonical SMILES to pass to dock_ligand."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "target_chembl_id": {
+                    "type": "string",
+                    "description": "ChEMBL target ID (for example: CHEMBL203)"
+                },
+                "max_candidates": {
+                    "type": "integer",
+                    "description": "Maximum candidates, known actives included",
+                    "minimum": 1,
+                    "maximum": MAX_CANDIDATES,
+                    "default": 50
+                }
+            },
+            "required": ["target_chembl_id"]
+        })
+    }
+
+    async fn execute(
+        &self,
+        params: serde_json::Value,
+        _ctx: &JobContext,
+        _cancel: &CancellationToken,
+    ) -> Result<ToolOutput, ToolError> {
+        let target = require_str(&params, "target_chembl_id")?.trim().to_string();
+        let max_candidates = params
+            .get("max_candidates")
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize)
+            .unwrap_or(50)
+            .clamp(1, MAX_CANDIDATES);
+
+        let started = std::time::Instant::now();
+        let candidates = LigandGenerator::new()
+            .generate_candidates(&target, max_candidates)
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(format!("ChEMBL lookup failed: {e:#}")))?;
+
+        let stored = match &self.db {
+            Some(db) => {
+                let records: Vec<_> = candidates.iter().map(|c| c.record(&target)).collect();
+                match CandidateLigandRepository::new(db.clone())
+                    .upsert_many(&records)
+                    .await
+                {
+                    Ok(()) => true,
+                    Err(e) => {
+                        tracing::warn!("Storing candidate ligands for {target} failed: {e}");
+                        false
+                    }
+                }
+            }
+            None => false,
+        };
+        let novel = candidates.iter().filter(|c| c.novelty).count();
+
+        Ok(ToolOutput::success(
+            json!({
+                "status": "ok",
+                "target_chembl_id": target,
+                "known_actives": candidates.len() - novel,
+                "novel": novel,
+                "stored": stored,
+                "candidates": candidates
+            }),
+            started.elapsed(),
+        ))
+    }
+}
+
 fn require_str<'a>(params: &'a serde_json::Value, name: &str) -> Result<&'a str, ToolError> {
     params
         .get(name)
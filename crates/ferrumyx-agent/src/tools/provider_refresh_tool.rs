@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 use ferrumyx_runtime::context::JobContext;
-use ferrumyx_runtime::tools::{Tool, ToolError, ToolOutput};
+use ferrumyx_runtime::tools::{CancellationToken, Tool, ToolError, ToolOutput};
 use serde_json::json;
 use std::sync::Arc;
 
@@ -65,6 +65,7 @@ impl Tool for RefreshProviderSignalsTool {
         &self,
         params: serde_json::Value,
         _ctx: &JobContext,
+        _cancel: &CancellationToken,
     ) -> Result<ToolOutput, ToolError> {
         let mut genes: Vec<String> = params
             .get("genes")
@@ -10,7 +10,7 @@ use ferrumyx_ingestion::embedding::{
 use ferrumyx_ingestion::repository::IngestionRepository;
 use ferrumyx_ranker::TargetQueryEngine;
 use ferrumyx_runtime::context::JobContext;
-use ferrumyx_runtime::tools::{Tool, ToolError, ToolOutput};
+use ferrumyx_runtime::tools::{CancellationToken, Tool, ToolError, ToolOutput};
 use serde_json::json;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::sync::Arc;
@@ -252,6 +252,7 @@ impl Tool for TargetQueryTool {
         &self,
         params: serde_json::Value,
         _ctx: &JobContext,
+        _cancel: &CancellationToken,
     ) -> Result<ToolOutput, ToolError> {
         let req = QueryRequest {
             query_text: require_str(&params, "query_text")?.to_string(),
@@ -1,20 +1,34 @@
 use async_trait::async_trait;
 use ferrumyx_runtime::context::JobContext;
-use ferrumyx_runtime::tools::{Tool, ToolError, ToolOutput};
+use ferrumyx_runtime::tools::{CancellationToken, Tool, ToolError, ToolOutput};
 use serde_json::json;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
 use ferrumyx_db::Database;
+use ferrumyx_ranker::weights::WeightVector;
 
 /// Tool to force a target-score recomputation from current KG facts.
+///
+/// With a `cancer_type` it instead runs the weighted composite ranker over
+/// every KG gene for that cancer type.
 pub struct RecomputeTargetScoresTool {
     db: Arc<Database>,
+    weights: Arc<RwLock<WeightVector>>,
 }
 
 impl RecomputeTargetScoresTool {
     pub fn new(db: Arc<Database>) -> Self {
-        Self { db }
+        Self {
+            db,
+            weights: Arc::default(),
+        }
+    }
+
+    /// Rank with the shared, runtime-configurable weight vector.
+    pub fn with_weights(mut self, weights: Arc<RwLock<WeightVector>>) -> Self {
+        self.weights = weights;
+        self
     }
 }
 
@@ -25,27 +39,63 @@ impl Tool for RecomputeTargetScoresTool {
     }
 
     fn description(&self) -> &str {
-        "Recomputes persisted target scores using current knowledge graph evidence."
+        "Recomputes persisted target scores using current knowledge graph evidence. \
+         Pass cancer_type to rank every KG gene for that cancer type with the configured weights."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
         json!({
             "type": "object",
-            "properties": {}
+            "properties": {
+                "cancer_type": {
+                    "type": "string",
+                    "description": "OncoTree code (e.g. PAAD) to rank all genes for"
+                }
+            }
         })
     }
 
-    fn execution_timeout(&self) -> Duration {
+    fn timeout(&self) -> Option<Duration> {
         // Score recomputation can exceed default chat/tool limits on larger KG states.
-        Duration::from_secs(20 * 60)
+        Some(Duration::from_secs(20 * 60))
     }
 
     async fn execute(
         &self,
-        _params: serde_json::Value,
+        params: serde_json::Value,
         _ctx: &JobContext,
+        _cancel: &CancellationToken,
     ) -> Result<ToolOutput, ToolError> {
         let started = std::time::Instant::now();
+        if let Some(cancer_type) = params
+            .get("cancer_type")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+        {
+            let weights = self
+                .weights
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone();
+            let summary = ferrumyx_ranker::scorer::rank_all_targets(
+                self.db.clone(),
+                cancer_type,
+                &weights,
+                |_| {},
+            )
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(format!("target ranking failed: {e}")))?;
+            return Ok(ToolOutput::success(
+                json!({
+                    "status": "ok",
+                    "ranking": summary,
+                    "weights": weights
+                }),
+                started.elapsed(),
+            ));
+        }
+
         let upserted = ferrumyx_kg::compute_target_scores(self.db.clone())
             .await
             .map_err(|e| {
@@ -0,0 +1,231 @@
+//! Knowledge-base snapshot tools over `ferrumyx_db::snapshot`: export the
+//! papers, chunks, entities, mentions and facts to a `.tar.zst` archive and
+//! load one back. Importing rewrites those tables, so it needs approval,
+//! and a `replace` import always does. Archive paths are relative to the
+//! workspace sandbox, and exports count against the job's quota.
+
+use async_trait::async_trait;
+use ferrumyx_db::{Database, SnapshotImportMode, SnapshotProgress};
+use ferrumyx_runtime::context::JobContext;
+use ferrumyx_runtime::tools::{
+    ApprovalRequirement, CancellationToken, Tool, ToolError, ToolOutput,
+};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const DEFAULT_SNAPSHOT_DIR: &str = "snapshots";
+
+pub struct ExportSnapshotTool {
+    db: Arc<Database>,
+}
+
+impl ExportSnapshotTool {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl Tool for ExportSnapshotTool {
+    fn name(&self) -> &str {
+        "export_snapshot"
+    }
+
+    fn description(&self) -> &str {
+        "Exports papers, chunks (with embeddings), entities, entity mentions and KG facts to a versioned .tar.zst snapshot archive."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Archive to write, relative to the workspace (default snapshots/ferrumyx-<timestamp>.tar.zst)"
+                }
+            }
+        })
+    }
+
+    fn requires_approval(&self, _params: &serde_json::Value) -> ApprovalRequirement {
+        ApprovalRequirement::UnlessAutoApproved
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        Some(Duration::from_secs(60 * 60))
+    }
+
+    async fn execute(
+        &self,
+        params: serde_json::Value,
+        ctx: &JobContext,
+        _cancel: &CancellationToken,
+    ) -> Result<ToolOutput, ToolError> {
+        let started = Instant::now();
+        let sandbox = ferrumyx_runtime::tools::sandbox(ctx)?;
+        let path = params
+            .get("path")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| {
+                format!(
+                    "{DEFAULT_SNAPSHOT_DIR}/ferrumyx-{}.tar.zst",
+                    chrono::Utc::now().format("%Y%m%dT%H%M%SZ")
+                )
+            });
+        let path = sandbox.resolve(&path)?;
+
+        let manifest =
+            ferrumyx_db::export_snapshot_with_progress(self.db.clone(), &path, &log_progress)
+                .await
+                .map_err(|e| ToolError::ExecutionFailed(format!("snapshot export failed: {e}")))?;
+        // The archive size is only known once it is written; one that does
+        // not fit the quota is removed again.
+        let size = ferrumyx_common::sandbox::disk_usage(&path).unwrap_or(0);
+        if let Err(e) = sandbox.charge(&ctx.job_id.to_string(), size) {
+            tokio::fs::remove_file(&path).await.ok();
+            return Err(e.into());
+        }
+
+        Ok(ToolOutput::success(
+            json!({
+                "status": "ok",
+                "path": path,
+                "schema_version": manifest.schema_version,
+                "embedding_dim": manifest.embedding_dim,
+                "tables": manifest
+                    .tables
+                    .iter()
+                    .map(|t| json!({ "table": t.name, "rows": t.rows }))
+                    .collect::<Vec<_>>(),
+            }),
+            started.elapsed(),
+        ))
+    }
+}
+
+pub struct ImportSnapshotTool {
+    db: Arc<Database>,
+}
+
+impl ImportSnapshotTool {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl Tool for ImportSnapshotTool {
+    fn name(&self) -> &str {
+        "import_snapshot"
+    }
+
+    fn description(&self) -> &str {
+        "Imports a .tar.zst snapshot written by export_snapshot. mode=merge (default) keeps local rows and deduplicates papers by DOI/PMID and facts by triple; mode=replace deletes the local knowledge base first."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Snapshot archive to import, relative to the workspace"
+                },
+                "mode": {
+                    "type": "string",
+                    "enum": ["merge", "replace"],
+                    "default": "merge"
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    fn requires_approval(&self, params: &serde_json::Value) -> ApprovalRequirement {
+        match parse_mode(params) {
+            Ok(SnapshotImportMode::Merge) => ApprovalRequirement::UnlessAutoApproved,
+            _ => ApprovalRequirement::Always,
+        }
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        Some(Duration::from_secs(60 * 60))
+    }
+
+    async fn execute(
+        &self,
+        params: serde_json::Value,
+        ctx: &JobContext,
+        _cancel: &CancellationToken,
+    ) -> Result<ToolOutput, ToolError> {
+        let started = Instant::now();
+        let path = params
+            .get("path")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .ok_or_else(|| ToolError::InvalidParameters("path is required".to_string()))?;
+        let path = ferrumyx_runtime::tools::sandbox(ctx)?.resolve(path)?;
+        let mode = parse_mode(&params)?;
+
+        let report =
+            ferrumyx_db::import_snapshot_with_progress(self.db.clone(), &path, mode, &log_progress)
+                .await
+                .map_err(|e| ToolError::ExecutionFailed(format!("snapshot import failed: {e}")))?;
+
+        Ok(ToolOutput::success(
+            json!({
+                "status": "ok",
+                "path": path,
+                "mode": report.mode,
+                "snapshot_schema_version": report.manifest.schema_version,
+                "tables": report.tables,
+            }),
+            started.elapsed(),
+        ))
+    }
+}
+
+fn parse_mode(params: &serde_json::Value) -> Result<SnapshotImportMode, ToolError> {
+    match params.get("mode").and_then(|v| v.as_str()) {
+        None => Ok(SnapshotImportMode::default()),
+        Some(raw) => serde_json::from_value(json!(raw.trim().to_ascii_lowercase())).map_err(|_| {
+            ToolError::InvalidParameters(format!("mode must be merge or replace, got '{raw}'"))
+        }),
+    }
+}
+
+fn log_progress(progress: SnapshotProgress) {
+    tracing::debug!(
+        operation = progress.operation,
+        table = %progress.table,
+        rows = progress.rows,
+        total = progress.total,
+        "Snapshot progress"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mode_defaults_to_merge_and_rejects_unknown_values() {
+        assert_eq!(
+            parse_mode(&json!({"path": "kb.tar.zst"})).unwrap(),
+            SnapshotImportMode::Merge
+        );
+        assert_eq!(
+            parse_mode(&json!({"mode": " Replace"})).unwrap(),
+            SnapshotImportMode::Replace
+        );
+        assert!(matches!(
+            parse_mode(&json!({"mode": "wipe"})),
+            Err(ToolError::InvalidParameters(_))
+        ));
+    }
+}
@@ -0,0 +1,647 @@
+//! Structure-based tools over ferrumyx-molecules: fetch a target structure
+//! into the workspace, find its pockets with fpocket and dock ligands into
+//! one of them with AutoDock Vina. fpocket and Vina run in Docker, so those
+//! two tools need approval. Docking results are also stored in the
+//! docking_results table for the molecule viewer. Files stay inside the
+//! workspace sandbox, and what the downloads and containers add there is
+//! charged to the job once they finish.
+
+use async_trait::async_trait;
+use ferrumyx_common::sandbox::{disk_usage, Sandbox};
+use ferrumyx_db::{Database, DockingResultRepository};
+use ferrumyx_molecules::docking::{
+    DockingBatch, LigandInput, OpenBabel, RdkitService, ReceptorInput, VinaRunner,
+    DEFAULT_DOCKING_PARALLELISM,
+};
+use ferrumyx_molecules::pocket::{FPocketRunner, FpocketError, DEFAULT_FPOCKET_TIMEOUT};
+use ferrumyx_molecules::tractability::StructuralProvider;
+use ferrumyx_runtime::context::JobContext;
+use ferrumyx_runtime::tools::{
+    ApprovalRequirement, CancellationToken, Tool, ToolError, ToolOutput,
+};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::OnceCell;
+
+const MAX_LIGANDS: usize = 10;
+
+/// Downloads the PDB or AlphaFold structure of a target into the workspace.
+pub struct FetchStructureTool {
+    structures_dir: PathBuf,
+    hgnc: OnceCell<ferrumyx_kg::ner::HgncNormaliser>,
+}
+
+impl FetchStructureTool {
+    pub fn new(structures_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            structures_dir: structures_dir.into(),
+            hgnc: OnceCell::new(),
+        }
+    }
+}
+
+/// The UniProt accession HGNC lists for `gene`, downloading the HGNC table
+/// into `hgnc` on first use.
+pub(crate) async fn uniprot_for_gene(
+    hgnc: &OnceCell<ferrumyx_kg::ner::HgncNormaliser>,
+    gene: &str,
+) -> Result<String, ToolError> {
+    let hgnc = hgnc
+        .get_or_try_init(ferrumyx_kg::ner::HgncNormaliser::from_download)
+        .await
+        .map_err(|e| ToolError::ExternalService(format!("HGNC unavailable: {e}")))?;
+    hgnc.to_uniprot_id(gene).ok_or_else(|| {
+        ToolError::InvalidParameters(format!("no UniProt accession known for gene {gene}"))
+    })
+}
+
+#[async_trait]
+impl Tool for FetchStructureTool {
+    fn name(&self) -> &str {
+        "fetch_structure"
+    }
+
+    fn description(&self) -> &str {
+        "Downloads the best experimental (PDB) structure of a target, or its AlphaFold model, into the workspace and returns its path."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "gene": {
+                    "type": "string",
+                    "description": "HGNC gene symbol (for example: KRAS)"
+                },
+                "uniprot_id": {
+                    "type": "string",
+                    "description": "UniProt accession (for example: P01116)"
+                },
+                "pdb_id": {
+                    "type": "string",
+                    "description": "Fetch this RCSB entry instead of the best one"
+                }
+            }
+        })
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        Some(Duration::from_secs(2 * 60))
+    }
+
+    async fn execute(
+        &self,
+        params: serde_json::Value,
+        ctx: &JobContext,
+        _cancel: &CancellationToken,
+    ) -> Result<ToolOutput, ToolError> {
+        let started = Instant::now();
+        let sandbox = ferrumyx_runtime::tools::sandbox(ctx)?;
+        let structures_dir = sandbox.confine(&self.structures_dir)?;
+        let before = usage(&structures_dir);
+        let provider = StructuralProvider::new(&structures_dir);
+        let (uniprot_id, fetched) = if let Some(pdb_id) = optional_str(&params, "pdb_id") {
+            (None, provider.fetch_pdb_entry(pdb_id).await)
+        } else {
+            let uniprot_id = match optional_str(&params, "uniprot_id") {
+                Some(id) => id.to_uppercase(),
+                None => match optional_str(&params, "gene") {
+                    Some(gene) => uniprot_for_gene(&self.hgnc, gene).await?,
+                    None => {
+                        return Err(ToolError::InvalidParameters(
+                            "one of gene, uniprot_id or pdb_id is required".to_string(),
+                        ))
+                    }
+                },
+            };
+            let fetched = provider.fetch_best_structure(&uniprot_id).await;
+            (Some(uniprot_id), fetched)
+        };
+        let structure = fetched
+            .map_err(|e| ToolError::ExternalService(format!("structure fetch failed: {e}")))?;
+        sandbox.charge(
+            &ctx.job_id.to_string(),
+            usage(&structures_dir).saturating_sub(before),
+        )?;
+
+        let text = tokio::fs::read_to_string(&structure.path)
+            .await
+            .unwrap_or_default();
+        let atoms = text.lines().filter(|l| l.starts_with("ATOM")).count();
+        let mut chains: Vec<&str> = text
+            .lines()
+            .filter(|l| l.starts_with("ATOM"))
+            .filter_map(|l| l.get(21..22).map(str::trim))
+            .filter(|c| !c.is_empty())
+            .collect();
+        chains.dedup();
+
+        Ok(ToolOutput::success(
+            json!({
+                "status": "ok",
+                "uniprot_id": uniprot_id,
+                "path": structure.path,
+                "source": structure.source,
+                "pdb_id": structure.pdb_id,
+                "resolution_angstrom": structure.resolution,
+                "mean_plddt": structure.plddt,
+                "atom_count": atoms,
+                "chains": chains
+            }),
+            started.elapsed(),
+        ))
+    }
+}
+
+/// Runs fpocket in Docker on a fetched structure and returns its pockets.
+pub struct DetectPocketsTool {
+    structures_dir: PathBuf,
+    image: String,
+    fpocket_timeout: Duration,
+}
+
+impl DetectPocketsTool {
+    pub fn new(structures_dir: impl Into<PathBuf>, image: impl Into<String>) -> Self {
+        Self {
+            structures_dir: structures_dir.into(),
+            image: image.into(),
+            fpocket_timeout: DEFAULT_FPOCKET_TIMEOUT,
+        }
+    }
+
+    /// Give up on an fpocket container after `timeout`.
+    pub fn with_fpocket_timeout(mut self, timeout: Duration) -> Self {
+        self.fpocket_timeout = timeout;
+        self
+    }
+}
+
+/// fpocket results for the structures under `structures_dir`, keyed by
+/// the hash of each structure file.
+pub(crate) fn pocket_cache(structures_dir: &Path) -> PathBuf {
+    structures_dir.join("fpocket")
+}
+
+#[async_trait]
+impl Tool for DetectPocketsTool {
+    fn name(&self) -> &str {
+        "detect_pockets"
+    }
+
+    fn description(&self) -> &str {
+        "Runs fpocket on a structure from fetch_structure and returns its pockets ranked by score, with druggability, volume, hydrophobicity, polarity, lining residues and docking box. Results are cached per structure file."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "structure_path": {
+                    "type": "string",
+                    "description": "Path returned by fetch_structure"
+                },
+                "max_pockets": {
+                    "type": "integer",
+                    "description": "Maximum pockets to return",
+                    "minimum": 1,
+                    "maximum": 50,
+                    "default": 10
+                }
+            },
+            "required": ["structure_path"]
+        })
+    }
+
+    fn requires_approval(&self, _params: &serde_json::Value) -> ApprovalRequirement {
+        ApprovalRequirement::UnlessAutoApproved
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        Some(Duration::from_secs(10 * 60))
+    }
+
+    async fn execute(
+        &self,
+        params: serde_json::Value,
+        ctx: &JobContext,
+        _cancel: &CancellationToken,
+    ) -> Result<ToolOutput, ToolError> {
+        let started = Instant::now();
+        let sandbox = ferrumyx_runtime::tools::sandbox(ctx)?;
+        let structure = workspace_structure(sandbox, &self.structures_dir, &params)?;
+        let before = usage(&self.structures_dir);
+        let max_pockets = params
+            .get("max_pockets")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(10)
+            .clamp(1, 50) as usize;
+
+        let pockets = FPocketRunner::new("fpocket")
+            .in_docker(&self.image)
+            .with_timeout(self.fpocket_timeout)
+            .with_cache_dir(pocket_cache(&self.structures_dir))
+            .detect(&structure)
+            .await
+            .map_err(|e| match e {
+                FpocketError::ImageMissing(_) | FpocketError::Launch { .. } => {
+                    ToolError::ExternalService(e.to_string())
+                }
+                _ => ToolError::ExecutionFailed(e.to_string()),
+            })?;
+        sandbox.charge(
+            &ctx.job_id.to_string(),
+            usage(&self.structures_dir).saturating_sub(before),
+        )?;
+
+        Ok(ToolOutput::success(
+            json!({
+                "status": "ok",
+                "structure_path": structure,
+                "pocket_count": pockets.len(),
+                "pockets": pockets.into_iter().take(max_pockets).collect::<Vec<_>>()
+            }),
+            started.elapsed(),
+        ))
+    }
+}
+
+/// Docks ligands into a pocket found by [`DetectPocketsTool`] with Vina in
+/// Docker. Ligands, and receptors not already in PDBQT, are prepared with
+/// Open Babel from the Vina image unless an rdkit-service image is set.
+pub struct DockLigandTool {
+    structures_dir: PathBuf,
+    image: String,
+    rdkit_image: Option<String>,
+    parallelism: usize,
+    db: Option<Arc<Database>>,
+}
+
+impl DockLigandTool {
+    pub fn new(structures_dir: impl Into<PathBuf>, image: impl Into<String>) -> Self {
+        Self {
+            structures_dir: structures_dir.into(),
+            image: image.into(),
+            rdkit_image: None,
+            parallelism: DEFAULT_DOCKING_PARALLELISM,
+            db: None,
+        }
+    }
+
+    /// Prepare PDBQT with the rdkit-service image's `rdkit-prep`.
+    pub fn with_rdkit_image(mut self, image: impl Into<String>) -> Self {
+        self.rdkit_image = Some(image.into());
+        self
+    }
+
+    /// Most ligands docked at once.
+    pub fn with_parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = parallelism;
+        self
+    }
+
+    /// Store every docked ligand, failures included, in docking_results.
+    pub fn with_results(mut self, db: Arc<Database>) -> Self {
+        self.db = Some(db);
+        self
+    }
+
+    fn batch(&self, exhaustiveness: u32) -> DockingBatch {
+        docking_batch(&self.image, self.rdkit_image.as_deref())
+            .with_exhaustiveness(exhaustiveness)
+            .with_parallelism(self.parallelism)
+    }
+}
+
+/// Vina from `image`, preparing PDBQT with rdkit-service when its image is
+/// given and with the Open Babel in the Vina image otherwise.
+pub(crate) fn docking_batch(image: &str, rdkit_image: Option<&str>) -> DockingBatch {
+    let vina = VinaRunner::new("vina").in_docker(image);
+    match rdkit_image {
+        Some(rdkit) => DockingBatch::new(vina, RdkitService::new("rdkit-prep").in_docker(rdkit)),
+        None => DockingBatch::new(vina, OpenBabel::new("obabel").in_docker(image)),
+    }
+}
+
+#[async_trait]
+impl Tool for DockLigandTool {
+    fn name(&self) -> &str {
+        "dock_ligand"
+    }
+
+    fn description(&self) -> &str {
+        "Docks SMILES ligands into a pocket from detect_pockets with AutoDock Vina and returns their poses with binding affinities (kcal/mol, lower is better)."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "structure_path": {
+                    "type": "string",
+                    "description": "Path returned by fetch_structure, after detect_pockets ran on it"
+                },
+                "pocket_id": {
+                    "type": "integer",
+                    "description": "Pocket rank from detect_pockets",
+                    "minimum": 1
+                },
+                "target": {
+                    "type": "string",
+                    "description": "Gene symbol to file the results under; defaults to the structure's name"
+                },
+                "smiles": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "minItems": 1,
+                    "maxItems": MAX_LIGANDS,
+                    "description": "Ligands to dock"
+                },
+                "exhaustiveness": {
+                    "type": "integer",
+                    "description": "Vina search exhaustiveness",
+                    "minimum": 1,
+                    "maximum": 32,
+                    "default": 8
+                }
+            },
+            "required": ["structure_path", "pocket_id", "smiles"]
+        })
+    }
+
+    fn requires_approval(&self, _params: &serde_json::Value) -> ApprovalRequirement {
+        ApprovalRequirement::UnlessAutoApproved
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        Some(Duration::from_secs(30 * 60))
+    }
+
+    async fn execute(
+        &self,
+        params: serde_json::Value,
+        ctx: &JobContext,
+        _cancel: &CancellationToken,
+    ) -> Result<ToolOutput, ToolError> {
+        let started = Instant::now();
+        let sandbox = ferrumyx_runtime::tools::sandbox(ctx)?;
+        let structure = workspace_structure(sandbox, &self.structures_dir, &params)?;
+        let pocket_id = params
+            .get("pocket_id")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| ToolError::InvalidParameters("missing pocket_id".to_string()))?;
+        let smiles: Vec<&str> = params
+            .get("smiles")
+            .and_then(|v| v.as_array())
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+        if smiles.is_empty() || smiles.len() > MAX_LIGANDS {
+            return Err(ToolError::InvalidParameters(format!(
+                "smiles must list 1 to {MAX_LIGANDS} ligands"
+            )));
+        }
+        let exhaustiveness = params
+            .get("exhaustiveness")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(8)
+            .clamp(1, 32) as u32;
+
+        let stem = structure
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let pockets = FPocketRunner::new("fpocket")
+            .with_cache_dir(pocket_cache(&self.structures_dir))
+            .cached(&structure)
+            .map_err(|e| ToolError::ExecutionFailed(format!("unreadable structure: {e}")))?
+            .ok_or_else(|| {
+                ToolError::InvalidParameters(format!(
+                    "no fpocket output for {}; run detect_pockets on it first",
+                    structure.display()
+                ))
+            })?;
+        let pocket = pockets
+            .into_iter()
+            .find(|p| u64::from(p.rank) == pocket_id)
+            .ok_or_else(|| {
+                ToolError::InvalidParameters(format!("no pocket {pocket_id} in {}", stem))
+            })?;
+
+        let target = optional_str(&params, "target").map_or_else(|| stem.clone(), str::to_string);
+        let ligands: Vec<LigandInput> = smiles
+            .iter()
+            .map(|smiles| LigandInput::new(*smiles, *smiles))
+            .collect();
+        let docking_dir = structure.with_file_name(format!("{stem}_docking"));
+        let before = usage(&docking_dir);
+        // One ligand failing (e.g. an unparsable SMILES) does not stop the
+        // others; only an unusable receptor fails the call.
+        let run = self
+            .batch(exhaustiveness)
+            .dock(
+                &ReceptorInput::from_path(&structure),
+                &pocket,
+                &ligands,
+                &docking_dir,
+            )
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(format!("{e:#}")))?;
+        sandbox.charge(
+            &ctx.job_id.to_string(),
+            usage(&docking_dir).saturating_sub(before),
+        )?;
+
+        let stored = match &self.db {
+            Some(db) => {
+                let repo = DockingResultRepository::new(db.clone());
+                match repo.upsert_many(&run.records(&target)).await {
+                    Ok(()) => true,
+                    Err(e) => {
+                        tracing::warn!("Storing docking results for {target} failed: {e}");
+                        false
+                    }
+                }
+            }
+            None => false,
+        };
+        let results: Vec<_> = run
+            .results
+            .iter()
+            .map(|result| match &result.error {
+                None => json!({
+                    "smiles": result.smiles,
+                    "best_affinity_kcal_mol": result.best_affinity_kcal(),
+                    "poses": result.poses.iter().map(|p| json!({
+                        "mode": p.mode,
+                        "affinity_kcal": p.affinity_kcal,
+                        "rmsd_lb": p.rmsd_lb,
+                        "rmsd_ub": p.rmsd_ub
+                    })).collect::<Vec<_>>(),
+                    "pose_file": result.pose_file
+                }),
+                Some(error) => json!({ "smiles": result.smiles, "error": error }),
+            })
+            .collect();
+
+        Ok(ToolOutput::success(
+            json!({
+                "status": "ok",
+                "structure_path": structure,
+                "target": target,
+                "pocket": pocket,
+                "results": results,
+                "stored": stored
+            }),
+            started.elapsed(),
+        ))
+    }
+}
+
+/// `structure_path` resolved and checked to lie inside `structures_dir`
+/// in the sandbox, since its directory is mounted into the container.
+fn workspace_structure(
+    sandbox: &Sandbox,
+    structures_dir: &Path,
+    params: &serde_json::Value,
+) -> Result<PathBuf, ToolError> {
+    let path = optional_str(params, "structure_path").ok_or_else(|| {
+        ToolError::InvalidParameters("missing required string parameter: structure_path".into())
+    })?;
+    let path = sandbox.confine(Path::new(path))?;
+    let root = sandbox.confine(structures_dir)?;
+    if !root.is_dir() {
+        return Err(ToolError::InvalidParameters(
+            "no structures fetched yet; run fetch_structure".to_string(),
+        ));
+    }
+    if !path.starts_with(&root) || !path.is_file() {
+        return Err(ToolError::InvalidParameters(format!(
+            "structure_path must be a file returned by fetch_structure under {}",
+            root.display()
+        )));
+    }
+    Ok(path)
+}
+
+/// Bytes under `dir`, so files written by downloads and containers can be
+/// charged to the job once they are done.
+pub(crate) fn usage(dir: &Path) -> u64 {
+    disk_usage(dir).unwrap_or_else(|e| {
+        tracing::warn!("Cannot measure {}: {e}", dir.display());
+        0
+    })
+}
+
+fn optional_str<'a>(params: &'a serde_json::Value, name: &str) -> Option<&'a str> {
+    params
+        .get(name)
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn structure_paths_must_stay_in_the_workspace() {
+        let workspace = tempfile::tempdir().unwrap();
+        let sandbox = Sandbox::new(workspace.path().join("sandbox")).unwrap();
+        let structures = sandbox.root().join("structures");
+        std::fs::create_dir_all(&structures).unwrap();
+        std::fs::write(structures.join("1crn.pdb"), "ATOM\n").unwrap();
+        std::fs::write(sandbox.root().join("secret.pdb"), "ATOM\n").unwrap();
+        std::fs::write(workspace.path().join("outside.pdb"), "ATOM\n").unwrap();
+
+        let inside = json!({ "structure_path": structures.join("1crn.pdb") });
+        assert!(workspace_structure(&sandbox, &structures, &inside).is_ok());
+        for outside in [
+            sandbox.root().join("secret.pdb"),
+            structures.join("..").join("secret.pdb"),
+            structures.join("../../outside.pdb"),
+            structures.clone(),
+        ] {
+            let params = json!({ "structure_path": outside });
+            assert!(
+                workspace_structure(&sandbox, &structures, &params).is_err(),
+                "{outside:?}"
+            );
+        }
+        // A structures directory configured outside the sandbox is refused.
+        let params = json!({ "structure_path": workspace.path().join("outside.pdb") });
+        assert!(matches!(
+            workspace_structure(&sandbox, workspace.path(), &params),
+            Err(ToolError::Sandbox(_))
+        ));
+    }
+
+    #[test]
+    fn docker_tools_need_approval() {
+        let params = json!({});
+        assert_eq!(
+            DetectPocketsTool::new("s", "img").requires_approval(&params),
+            ApprovalRequirement::UnlessAutoApproved
+        );
+        assert_eq!(
+            DockLigandTool::new("s", "img").requires_approval(&params),
+            ApprovalRequirement::UnlessAutoApproved
+        );
+        assert_eq!(
+            FetchStructureTool::new("s").requires_approval(&params),
+            ApprovalRequirement::Never
+        );
+    }
+
+    /// Fetches crambin, finds its pockets and docks ethanol into the best
+    /// one. Needs network access, Docker and the configured images.
+    #[cfg(feature = "docker-tests")]
+    #[tokio::test]
+    async fn fetch_detect_and_dock_in_docker() {
+        if !ferrumyx_molecules::container::docker_available().await {
+            eprintln!("Docker unavailable, skipping");
+            return;
+        }
+        let workspace = tempfile::tempdir().unwrap();
+        let structures = workspace.path().join("structures");
+        let mut ctx = JobContext::default();
+        ctx.extensions
+            .insert(Arc::new(Sandbox::new(workspace.path()).unwrap()));
+        let cancel = CancellationToken::new();
+
+        let fetched = FetchStructureTool::new(&structures)
+            .execute(json!({ "pdb_id": "1CRN" }), &ctx, &cancel)
+            .await
+            .unwrap()
+            .result;
+        assert_eq!(fetched["source"], "pdb");
+        let path = fetched["path"].clone();
+
+        let pockets = DetectPocketsTool::new(&structures, "ferrumyx/fpocket:latest")
+            .execute(json!({ "structure_path": path }), &ctx, &cancel)
+            .await
+            .unwrap()
+            .result;
+        let best = pockets["pockets"][0]["rank"].as_u64().expect("a pocket");
+
+        let docked = DockLigandTool::new(&structures, "ferrumyx/autodock-vina:latest")
+            .execute(
+                json!({ "structure_path": path, "pocket_id": best, "smiles": ["CCO"], "exhaustiveness": 1 }),
+                &ctx,
+                &cancel,
+            )
+            .await
+            .unwrap()
+            .result;
+        let result = &docked["results"][0];
+        assert!(result["best_affinity_kcal_mol"].is_number(), "{result}");
+    }
+}
@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 use ferrumyx_runtime::context::JobContext;
-use ferrumyx_runtime::tools::{Tool, ToolError, ToolOutput};
+use ferrumyx_runtime::tools::{CancellationToken, Tool, ToolError, ToolOutput};
 use serde_json::json;
 use std::process::Stdio;
 use std::time::Duration;
@@ -81,14 +81,15 @@ impl Tool for SystemCommandTool {
         })
     }
 
-    fn execution_timeout(&self) -> Duration {
-        Duration::from_secs(5 * 60)
+    fn timeout(&self) -> Option<Duration> {
+        Some(Duration::from_secs(5 * 60))
     }
 
     async fn execute(
         &self,
         params: serde_json::Value,
         _ctx: &JobContext,
+        _cancel: &CancellationToken,
     ) -> Result<ToolOutput, ToolError> {
         let command = params
             .get("command")
@@ -116,6 +117,7 @@ impl Tool for SystemCommandTool {
             .arg(&command)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
+            .kill_on_drop(true)
             .spawn()
             .map_err(|e| ToolError::ExecutionFailed(format!("spawn failed: {e}")))?;
 
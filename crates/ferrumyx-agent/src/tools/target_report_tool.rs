@@ -0,0 +1,122 @@
+use async_trait::async_trait;
+use ferrumyx_runtime::context::JobContext;
+use ferrumyx_runtime::tools::{CancellationToken, Tool, ToolError, ToolOutput};
+use serde_json::json;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+
+use ferrumyx_db::Database;
+use ferrumyx_ranker::weights::WeightVector;
+
+/// Tool that summarises the persisted target scores for one cancer type as
+/// Markdown: the top targets, the weight vector and the data releases.
+pub struct TargetReportTool {
+    db: Arc<Database>,
+    weights: Arc<RwLock<WeightVector>>,
+}
+
+impl TargetReportTool {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self {
+            db,
+            weights: Arc::default(),
+        }
+    }
+
+    /// Report the shared, runtime-configurable weight vector.
+    pub fn with_weights(mut self, weights: Arc<RwLock<WeightVector>>) -> Self {
+        self.weights = weights;
+        self
+    }
+}
+
+#[async_trait]
+impl Tool for TargetReportTool {
+    fn name(&self) -> &str {
+        "target_ranking_report"
+    }
+
+    fn description(&self) -> &str {
+        "Writes a Markdown report of the persisted target scores for a cancer type: top 20 targets, \
+         the weight vector and the data release versions. Attach it to outputs that cite rankings."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "cancer_type": {
+                    "type": "string",
+                    "description": "OncoTree code (e.g. PAAD) to report on"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Also save the report to this file, relative to the workspace"
+                }
+            },
+            "required": ["cancer_type"]
+        })
+    }
+
+    async fn execute(
+        &self,
+        params: serde_json::Value,
+        ctx: &JobContext,
+        _cancel: &CancellationToken,
+    ) -> Result<ToolOutput, ToolError> {
+        let started = Instant::now();
+        let cancer_type = params
+            .get("cancer_type")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| ToolError::InvalidParameters("cancer_type is required".to_string()))?;
+        let weights = self
+            .weights
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+
+        let report = ferrumyx_ranker::report::generate_markdown_report(
+            self.db.clone(),
+            cancer_type,
+            &weights,
+        )
+        .await
+        .map_err(|e| ToolError::ExecutionFailed(format!("target report failed: {e}")))?;
+
+        let path = match params
+            .get("path")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+        {
+            Some(path) => {
+                let sandbox = ferrumyx_runtime::tools::sandbox(ctx)?;
+                let path = sandbox.resolve(path)?;
+                sandbox.charge(&ctx.job_id.to_string(), report.len() as u64)?;
+                if let Some(parent) = path.parent() {
+                    tokio::fs::create_dir_all(parent).await.ok();
+                }
+                tokio::fs::write(&path, &report).await.map_err(|e| {
+                    ToolError::ExecutionFailed(format!(
+                        "failed to write report to {}: {e}",
+                        path.display()
+                    ))
+                })?;
+                Some(path)
+            }
+            None => None,
+        };
+
+        Ok(ToolOutput::success(
+            json!({
+                "status": "ok",
+                "cancer_type": cancer_type,
+                "path": path,
+                "report": report,
+            }),
+            started.elapsed(),
+        ))
+    }
+}
@@ -0,0 +1,589 @@
+//! OncoTree cancer types.
+//!
+//! Cancer types arrive as OncoTree codes ("PAAD"), TCGA cohorts
+//! ("TCGA-PAAD"), MeSH IDs ("D021441") or free text ("pancreatic cancer").
+//! [`CancerType::parse`] maps all of them onto one node of the embedded
+//! OncoTree taxonomy, which carries the node's tissue, its parent, and the
+//! TCGA cohort and MeSH descriptor where one corresponds.
+//!
+//! The table is the part of OncoTree that the providers can serve: every
+//! tissue, every TCGA cohort and the common DepMap cell line codes, with
+//! intermediate levels collapsed where OncoTree has several.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// One OncoTree node.
+struct Node {
+    code: &'static str,
+    name: &'static str,
+    /// Empty for tissues, the top level under the OncoTree root.
+    parent: &'static str,
+    tcga: Option<&'static str>,
+    mesh: Option<&'static str>,
+    /// Normalised alternative names (see [`normalise`]).
+    synonyms: &'static [&'static str],
+}
+
+const fn node(
+    code: &'static str,
+    name: &'static str,
+    parent: &'static str,
+    tcga: Option<&'static str>,
+    mesh: Option<&'static str>,
+    synonyms: &'static [&'static str],
+) -> Node {
+    Node {
+        code,
+        name,
+        parent,
+        tcga,
+        mesh,
+        synonyms,
+    }
+}
+
+/// Parents precede their children; where several nodes share a TCGA
+/// cohort the first is the one the cohort parses to.
+#[rustfmt::skip]
+static ONCOTREE: &[Node] = &[
+    // Tissues
+    node("ADRENAL_GLAND", "Adrenal Gland", "", None, Some("D000310"), &["adrenal cancer"]),
+    node("AMPULLA_OF_VATER", "Ampulla of Vater", "", None, None, &[]),
+    node("BILIARY_TRACT", "Biliary Tract", "", None, Some("D001661"), &["biliary tract cancer"]),
+    node("BLADDER", "Bladder/Urinary Tract", "", None, Some("D001749"), &["urinary tract"]),
+    node("BONE", "Bone", "", None, Some("D001859"), &["bone cancer"]),
+    node("BOWEL", "Bowel", "", None, Some("D007414"), &["intestine"]),
+    node("BRAIN", "CNS/Brain", "", None, Some("D016543"), &["brain", "cns", "brain cancer", "brain tumor"]),
+    node("BREAST", "Breast", "", None, Some("D001943"), &[]),
+    node("CERVIX", "Cervix", "", None, Some("D002583"), &[]),
+    node("EYE", "Eye", "", None, Some("D005134"), &["eye cancer"]),
+    node("HEAD_NECK", "Head and Neck", "", None, Some("D006258"), &[]),
+    node("KIDNEY", "Kidney", "", None, Some("D007680"), &[]),
+    node("LIVER", "Liver", "", None, Some("D008113"), &[]),
+    node("LUNG", "Lung", "", None, Some("D008175"), &["lung cancer"]),
+    node("LYMPH", "Lymphoid", "", None, None, &[]),
+    node("MYELOID", "Myeloid", "", None, None, &[]),
+    node("OVARY", "Ovary/Fallopian Tube", "", None, Some("D010051"), &["ovary", "fallopian tube"]),
+    node("PANCREAS", "Pancreas", "", None, Some("D010190"), &[]),
+    node("PERITONEUM", "Peritoneum", "", None, Some("D010534"), &[]),
+    node("PLEURA", "Pleura", "", None, Some("D010997"), &[]),
+    node("PNS", "Peripheral Nervous System", "", None, Some("D010524"), &[]),
+    node("PROSTATE", "Prostate", "", None, Some("D011471"), &[]),
+    node("SKIN", "Skin", "", None, Some("D012878"), &["skin cancer"]),
+    node("SOFT_TISSUE", "Soft Tissue", "", None, Some("D012983"), &[]),
+    node("STOMACH", "Esophagus/Stomach", "", None, None, &["esophagus", "stomach"]),
+    node("TESTIS", "Testis", "", None, Some("D013736"), &["testicular cancer"]),
+    node("THYMUS", "Thymus", "", None, Some("D013953"), &[]),
+    node("THYROID", "Thyroid", "", None, Some("D013964"), &["thyroid cancer"]),
+    node("UTERUS", "Uterus", "", None, Some("D014594"), &["uterine cancer"]),
+    // Adrenal gland
+    node("ACC", "Adrenocortical Carcinoma", "ADRENAL_GLAND", Some("ACC"), Some("D018268"), &[]),
+    node("PHC", "Pheochromocytoma", "ADRENAL_GLAND", Some("PCPG"), Some("D010673"), &["pheochromocytoma and paraganglioma"]),
+    // Ampulla of Vater
+    node("AMPCA", "Ampullary Carcinoma", "AMPULLA_OF_VATER", None, None, &["ampullary cancer"]),
+    // Biliary tract
+    node("CHOL", "Cholangiocarcinoma", "BILIARY_TRACT", Some("CHOL"), Some("D018281"), &["bile duct cancer"]),
+    node("GBC", "Gallbladder Cancer", "BILIARY_TRACT", None, Some("D005706"), &["gallbladder carcinoma"]),
+    // Bladder/urinary tract
+    node("BLCA", "Bladder Urothelial Carcinoma", "BLADDER", Some("BLCA"), Some("D002295"), &["bladder cancer", "urothelial carcinoma"]),
+    node("UTUC", "Upper Tract Urothelial Carcinoma", "BLADDER", None, None, &[]),
+    // Bone
+    node("OS", "Osteosarcoma", "BONE", None, Some("D012516"), &[]),
+    node("ES", "Ewing Sarcoma", "BONE", None, Some("D012512"), &["ewings sarcoma"]),
+    node("CHS", "Chondrosarcoma", "BONE", None, Some("D002813"), &[]),
+    // Bowel
+    node("COADREAD", "Colorectal Adenocarcinoma", "BOWEL", None, Some("D015179"), &["colorectal cancer", "colorectal carcinoma", "crc"]),
+    node("COAD", "Colon Adenocarcinoma", "COADREAD", Some("COAD"), Some("D003110"), &["colon cancer"]),
+    node("READ", "Rectal Adenocarcinoma", "COADREAD", Some("READ"), Some("D012004"), &["rectal cancer"]),
+    node("APAD", "Appendiceal Adenocarcinoma", "BOWEL", None, None, &[]),
+    // CNS/brain
+    node("DIFG", "Diffuse Glioma", "BRAIN", None, Some("D005910"), &["glioma"]),
+    node("GBM", "Glioblastoma Multiforme", "DIFG", Some("GBM"), Some("D005909"), &["glioblastoma"]),
+    node("LGGNOS", "Low-Grade Glioma, NOS", "DIFG", Some("LGG"), None, &["low grade glioma", "brain lower grade glioma"]),
+    node("ASTR", "Astrocytoma", "DIFG", Some("LGG"), Some("D001254"), &[]),
+    node("ODG", "Oligodendroglioma", "DIFG", Some("LGG"), Some("D009837"), &[]),
+    node("MBL", "Medulloblastoma", "BRAIN", None, Some("D008527"), &[]),
+    node("EPM", "Ependymoma", "BRAIN", None, Some("D004806"), &[]),
+    node("MNG", "Meningioma", "BRAIN", None, Some("D008579"), &[]),
+    // Breast
+    node("BRCA", "Invasive Breast Carcinoma", "BREAST", Some("BRCA"), None, &["breast cancer", "breast carcinoma", "breast invasive carcinoma"]),
+    node("IDC", "Breast Invasive Ductal Carcinoma", "BRCA", Some("BRCA"), Some("D018270"), &["invasive ductal carcinoma"]),
+    node("ILC", "Breast Invasive Lobular Carcinoma", "BRCA", Some("BRCA"), Some("D018275"), &["invasive lobular carcinoma"]),
+    // Cervix
+    node("CESC", "Cervical Squamous Cell Carcinoma", "CERVIX", Some("CESC"), None, &["cervical cancer"]),
+    node("CEAD", "Cervical Adenocarcinoma", "CERVIX", Some("CESC"), None, &[]),
+    // Eye
+    node("UM", "Uveal Melanoma", "EYE", Some("UVM"), None, &[]),
+    node("RBL", "Retinoblastoma", "EYE", None, Some("D012175"), &[]),
+    // Head and neck
+    node("HNSC", "Head and Neck Squamous Cell Carcinoma", "HEAD_NECK", Some("HNSC"), Some("D000077195"), &["head and neck cancer", "hnscc"]),
+    node("NPC", "Nasopharyngeal Carcinoma", "HEAD_NECK", None, Some("D000077274"), &[]),
+    // Kidney
+    node("RCC", "Renal Cell Carcinoma", "KIDNEY", None, Some("D002292"), &["kidney cancer", "renal cancer"]),
+    node("CCRCC", "Renal Clear Cell Carcinoma", "RCC", Some("KIRC"), None, &["clear cell renal cell carcinoma", "kidney renal clear cell carcinoma"]),
+    node("PRCC", "Papillary Renal Cell Carcinoma", "RCC", Some("KIRP"), None, &["kidney renal papillary cell carcinoma"]),
+    node("CHRCC", "Chromophobe Renal Cell Carcinoma", "RCC", Some("KICH"), None, &["kidney chromophobe"]),
+    node("WT", "Wilms' Tumor", "KIDNEY", None, Some("D009396"), &["nephroblastoma"]),
+    // Liver
+    node("HCC", "Hepatocellular Carcinoma", "LIVER", Some("LIHC"), Some("D006528"), &["liver cancer", "liver hepatocellular carcinoma"]),
+    node("HB", "Hepatoblastoma", "LIVER", None, Some("D018197"), &[]),
+    // Lung
+    node("NSCLC", "Non-Small Cell Lung Cancer", "LUNG", None, Some("D002289"), &["non small cell lung carcinoma"]),
+    node("LUAD", "Lung Adenocarcinoma", "NSCLC", Some("LUAD"), Some("D000077192"), &["adenocarcinoma of lung"]),
+    node("LUSC", "Lung Squamous Cell Carcinoma", "NSCLC", Some("LUSC"), None, &["squamous cell lung cancer"]),
+    node("LCLC", "Large Cell Lung Carcinoma", "NSCLC", None, Some("D018287"), &[]),
+    node("SCLC", "Small Cell Lung Cancer", "LUNG", None, Some("D055752"), &["small cell lung carcinoma"]),
+    // Lymphoid
+    node("DLBCLNOS", "Diffuse Large B-Cell Lymphoma, NOS", "LYMPH", Some("DLBC"), Some("D016403"), &["diffuse large b cell lymphoma", "dlbcl"]),
+    node("CLLSLL", "Chronic Lymphocytic Leukemia/Small Lymphocytic Lymphoma", "LYMPH", None, Some("D015451"), &["chronic lymphocytic leukemia", "cll"]),
+    node("BLL", "B-Lymphoblastic Leukemia/Lymphoma", "LYMPH", None, Some("D054198"), &["b cell acute lymphoblastic leukemia"]),
+    node("PCM", "Plasma Cell Myeloma", "LYMPH", None, Some("D009101"), &["multiple myeloma", "myeloma"]),
+    node("CHL", "Classical Hodgkin Lymphoma", "LYMPH", None, Some("D006689"), &["hodgkin lymphoma"]),
+    node("BL", "Burkitt Lymphoma", "LYMPH", None, Some("D002051"), &[]),
+    node("MCL", "Mantle Cell Lymphoma", "LYMPH", None, Some("D020522"), &[]),
+    node("FL", "Follicular Lymphoma", "LYMPH", None, Some("D008224"), &[]),
+    // Myeloid
+    node("AML", "Acute Myeloid Leukemia", "MYELOID", Some("LAML"), Some("D015470"), &["acute myelogenous leukemia"]),
+    node("CML", "Chronic Myelogenous Leukemia", "MYELOID", None, Some("D015464"), &["chronic myeloid leukemia"]),
+    node("MDS", "Myelodysplastic Syndromes", "MYELOID", None, Some("D009190"), &[]),
+    // Ovary/fallopian tube
+    node("OVT", "Ovarian Epithelial Tumor", "OVARY", None, Some("D000077216"), &["ovarian cancer", "epithelial ovarian cancer"]),
+    node("SOC", "Serous Ovarian Cancer", "OVT", None, None, &[]),
+    node("HGSOC", "High-Grade Serous Ovarian Cancer", "SOC", Some("OV"), None, &["ovarian serous cystadenocarcinoma"]),
+    node("LGSOC", "Low-Grade Serous Ovarian Cancer", "SOC", None, None, &[]),
+    node("CCOV", "Clear Cell Ovarian Cancer", "OVT", None, None, &[]),
+    // Pancreas
+    node("PAAD", "Pancreatic Adenocarcinoma", "PANCREAS", Some("PAAD"), Some("D021441"), &["pancreatic cancer", "pancreatic carcinoma", "pancreatic ductal adenocarcinoma", "pdac"]),
+    node("PANET", "Pancreatic Neuroendocrine Tumor", "PANCREAS", None, None, &["pnet"]),
+    // Peritoneum
+    node("PEMESO", "Peritoneal Mesothelioma", "PERITONEUM", None, None, &[]),
+    // Pleura
+    node("PLMESO", "Pleural Mesothelioma", "PLEURA", Some("MESO"), Some("D008654"), &["mesothelioma"]),
+    // Peripheral nervous system
+    node("NBL", "Neuroblastoma", "PNS", None, Some("D009447"), &[]),
+    node("MPNST", "Malignant Peripheral Nerve Sheath Tumor", "PNS", None, None, &[]),
+    // Prostate
+    node("PRAD", "Prostate Adenocarcinoma", "PROSTATE", Some("PRAD"), None, &["prostate cancer"]),
+    // Skin
+    node("MEL", "Melanoma", "SKIN", None, Some("D008545"), &[]),
+    node("SKCM", "Cutaneous Melanoma", "MEL", Some("SKCM"), None, &["skin cutaneous melanoma"]),
+    node("ACRM", "Acral Melanoma", "MEL", None, None, &[]),
+    node("CSCC", "Cutaneous Squamous Cell Carcinoma", "SKIN", None, None, &[]),
+    node("BCC", "Basal Cell Carcinoma", "SKIN", None, Some("D002280"), &[]),
+    node("MCC", "Merkel Cell Carcinoma", "SKIN", None, Some("D015266"), &[]),
+    // Soft tissue
+    node("SARCNOS", "Sarcoma, NOS", "SOFT_TISSUE", Some("SARC"), Some("D012509"), &["sarcoma", "soft tissue sarcoma"]),
+    node("LMS", "Leiomyosarcoma", "SOFT_TISSUE", Some("SARC"), Some("D007890"), &[]),
+    node("LIPO", "Liposarcoma", "SOFT_TISSUE", None, Some("D008080"), &[]),
+    node("DDLS", "Dedifferentiated Liposarcoma", "LIPO", Some("SARC"), None, &[]),
+    node("SYNS", "Synovial Sarcoma", "SOFT_TISSUE", Some("SARC"), Some("D013584"), &[]),
+    node("MFS", "Myxofibrosarcoma", "SOFT_TISSUE", Some("SARC"), None, &[]),
+    node("RMS", "Rhabdomyosarcoma", "SOFT_TISSUE", None, Some("D012208"), &[]),
+    node("GIST", "Gastrointestinal Stromal Tumor", "SOFT_TISSUE", None, Some("D046152"), &[]),
+    // Esophagus/stomach
+    node("EGC", "Esophagogastric Adenocarcinoma", "STOMACH", None, None, &[]),
+    node("ESCA", "Esophageal Adenocarcinoma", "EGC", Some("ESCA"), None, &["esophageal cancer", "esophageal carcinoma"]),
+    node("STAD", "Stomach Adenocarcinoma", "EGC", Some("STAD"), Some("D013274"), &["gastric cancer", "stomach cancer", "gastric adenocarcinoma"]),
+    node("ESCC", "Esophageal Squamous Cell Carcinoma", "STOMACH", Some("ESCA"), Some("D000077277"), &[]),
+    // Testis
+    node("NSGCT", "Non-Seminomatous Germ Cell Tumor", "TESTIS", Some("TGCT"), None, &[]),
+    node("SEM", "Seminoma", "TESTIS", Some("TGCT"), Some("D018239"), &[]),
+    // Thymus
+    node("TM", "Thymoma", "THYMUS", Some("THYM"), Some("D013945"), &[]),
+    node("TC", "Thymic Carcinoma", "THYMUS", None, None, &[]),
+    // Thyroid
+    node("THPA", "Papillary Thyroid Cancer", "THYROID", Some("THCA"), Some("D000077273"), &["papillary thyroid carcinoma"]),
+    node("THFO", "Follicular Thyroid Cancer", "THYROID", None, None, &[]),
+    node("THAP", "Anaplastic Thyroid Cancer", "THYROID", None, Some("D065646"), &[]),
+    node("THME", "Medullary Thyroid Cancer", "THYROID", None, None, &[]),
+    // Uterus
+    node("UEC", "Endometrial Carcinoma", "UTERUS", None, Some("D016889"), &["endometrial cancer"]),
+    node("UCEC", "Uterine Endometrioid Carcinoma", "UEC", Some("UCEC"), None, &["uterine corpus endometrial carcinoma"]),
+    node("USC", "Uterine Serous Carcinoma/Uterine Papillary Serous Carcinoma", "UEC", Some("UCEC"), None, &["uterine serous carcinoma"]),
+    node("UCS", "Uterine Carcinosarcoma/Uterine Malignant Mixed Mullerian Tumor", "UTERUS", Some("UCS"), None, &["uterine carcinosarcoma"]),
+    node("ULMS", "Uterine Leiomyosarcoma", "UTERUS", None, None, &[]),
+];
+
+/// A node of the OncoTree taxonomy.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CancerType(u16);
+
+impl CancerType {
+    /// Resolve an OncoTree code, TCGA cohort ("TCGA-LUAD" or "LUAD"), MeSH
+    /// ID, name or common synonym, case-insensitively. Names and synonyms
+    /// also match with a typo or two when only one node is that close.
+    pub fn parse(input: &str) -> Option<Self> {
+        let query = normalise(input);
+        if query.is_empty() {
+            return None;
+        }
+        let find = |f: &dyn Fn(&Node) -> bool| ONCOTREE.iter().position(f).map(Self::at);
+
+        find(&|n| normalise(n.code) == query)
+            .or_else(|| {
+                let cohort = query.strip_prefix("tcga ").unwrap_or(&query);
+                find(&|n| n.tcga.is_some_and(|t| t.eq_ignore_ascii_case(cohort)))
+            })
+            .or_else(|| {
+                let mesh = query.strip_prefix("mesh ").unwrap_or(&query);
+                find(&|n| n.mesh.is_some_and(|m| m.eq_ignore_ascii_case(mesh)))
+            })
+            .or_else(|| find(&|n| spellings(n).any(|s| s == query)))
+            .or_else(|| Self::closest_spelling(&query))
+    }
+
+    /// The node with OncoTree `code`, case-insensitively.
+    pub fn from_code(code: &str) -> Option<Self> {
+        ONCOTREE
+            .iter()
+            .position(|n| n.code.eq_ignore_ascii_case(code.trim()))
+            .map(Self::at)
+    }
+
+    /// Every node, parents before children.
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..ONCOTREE.len()).map(Self::at)
+    }
+
+    /// Up to `limit` nodes whose code, name or synonyms look most like
+    /// `input`, best first; for "did you mean" messages.
+    pub fn suggestions(input: &str, limit: usize) -> Vec<Self> {
+        let query = normalise(input);
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let mut scored: Vec<(f64, Self)> = Self::all()
+            .filter_map(|c| {
+                let n = c.node();
+                let code = normalise(n.code);
+                let score = spellings(n)
+                    .chain(std::iter::once(code))
+                    .map(|s| {
+                        let similarity = strsim::normalized_damerau_levenshtein(&query, &s);
+                        if query.len() >= 3 && s.contains(query.as_str()) {
+                            similarity.max(0.8)
+                        } else {
+                            similarity
+                        }
+                    })
+                    .fold(0.0, f64::max);
+                (score >= 0.5).then_some((score, c))
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+        scored.into_iter().take(limit).map(|(_, c)| c).collect()
+    }
+
+    /// OncoTree code, e.g. "PAAD".
+    pub fn code(self) -> &'static str {
+        self.node().code
+    }
+
+    /// OncoTree name, e.g. "Pancreatic Adenocarcinoma".
+    pub fn name(self) -> &'static str {
+        self.node().name
+    }
+
+    /// The tissue the node belongs to, e.g. "Pancreas"; a tissue's own name
+    /// for tissue nodes. DepMap calls this the lineage.
+    pub fn tissue(self) -> &'static str {
+        self.ancestors().last().copied().unwrap_or(self).name()
+    }
+
+    /// Whether this is a tissue, the top level of the taxonomy.
+    pub fn is_tissue(self) -> bool {
+        self.node().parent.is_empty()
+    }
+
+    pub fn parent(self) -> Option<Self> {
+        Self::from_code(self.node().parent)
+    }
+
+    /// TCGA cohort, e.g. "PAAD" for project TCGA-PAAD.
+    pub fn tcga_cohort(self) -> Option<&'static str> {
+        self.node().tcga
+    }
+
+    /// MeSH descriptor ID, e.g. "D021441".
+    pub fn mesh_id(self) -> Option<&'static str> {
+        self.node().mesh
+    }
+
+    /// Parent, grandparent and so on up to the tissue.
+    pub fn ancestors(self) -> Vec<Self> {
+        std::iter::successors(self.parent(), |c| c.parent()).collect()
+    }
+
+    /// Every node below this one, depth first.
+    pub fn descendants(self) -> Vec<Self> {
+        Self::all()
+            .filter(|c| c.node().parent == self.code())
+            .flat_map(|child| std::iter::once(child).chain(child.descendants()))
+            .collect()
+    }
+
+    /// Whether `self` is `other` or lies below it.
+    pub fn is_within(self, other: Self) -> bool {
+        self == other || self.ancestors().contains(&other)
+    }
+
+    fn at(index: usize) -> Self {
+        Self(index as u16)
+    }
+
+    fn node(self) -> &'static Node {
+        &ONCOTREE[self.0 as usize]
+    }
+
+    /// The only node with a name or synonym within a small edit distance
+    /// of `query`: one edit from five characters, two from twelve.
+    fn closest_spelling(query: &str) -> Option<Self> {
+        let allowed = match query.len() {
+            0..=4 => return None,
+            5..=11 => 1,
+            _ => 2,
+        };
+        let mut best: Option<(usize, Self)> = None;
+        let mut tied = false;
+        for c in Self::all() {
+            let Some(distance) = spellings(c.node())
+                .map(|s| strsim::damerau_levenshtein(query, &s))
+                .min()
+            else {
+                continue;
+            };
+            if distance > allowed {
+                continue;
+            }
+            match best {
+                Some((d, _)) if distance > d => {}
+                Some((d, _)) if distance == d => tied = true,
+                _ => {
+                    best = Some((distance, c));
+                    tied = false;
+                }
+            }
+        }
+        best.filter(|_| !tied).map(|(_, c)| c)
+    }
+}
+
+/// A node's normalised name and synonyms.
+fn spellings(node: &Node) -> impl Iterator<Item = String> + '_ {
+    std::iter::once(normalise(node.name)).chain(node.synonyms.iter().map(|s| normalise(s)))
+}
+
+/// Lower case, apostrophes dropped, other punctuation as single spaces, and
+/// British spellings made American.
+fn normalise(s: &str) -> String {
+    let cleaned: String = s
+        .chars()
+        .filter(|c| !matches!(c, '\'' | '\u{2019}'))
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                ' '
+            }
+        })
+        .collect();
+    cleaned
+        .split_whitespace()
+        .map(|word| match word {
+            "tumour" => "tumor",
+            "tumours" => "tumors",
+            "oesophageal" => "esophageal",
+            "oesophagus" => "esophagus",
+            "leukaemia" => "leukemia",
+            "haematological" => "hematological",
+            other => other,
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Input that [`CancerType::parse`] does not resolve, with the closest
+/// matches for the error message.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("unknown cancer type '{input}'{}", did_you_mean(.suggestions))]
+pub struct UnknownCancerType {
+    pub input: String,
+    pub suggestions: Vec<CancerType>,
+}
+
+fn did_you_mean(suggestions: &[CancerType]) -> String {
+    if suggestions.is_empty() {
+        return String::new();
+    }
+    let listed: Vec<String> = suggestions
+        .iter()
+        .map(|c| format!("{} ({})", c.code(), c.name()))
+        .collect();
+    format!("; did you mean {}?", listed.join(", "))
+}
+
+impl std::str::FromStr for CancerType {
+    type Err = UnknownCancerType;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| UnknownCancerType {
+            input: s.trim().to_string(),
+            suggestions: Self::suggestions(s, 3),
+        })
+    }
+}
+
+impl std::fmt::Display for CancerType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl std::fmt::Debug for CancerType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CancerType({})", self.code())
+    }
+}
+
+impl Serialize for CancerType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for CancerType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        raw.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_is_consistent() {
+        for (i, n) in ONCOTREE.iter().enumerate() {
+            assert_eq!(
+                ONCOTREE.iter().position(|m| m.code == n.code),
+                Some(i),
+                "duplicate code {}",
+                n.code
+            );
+            if !n.parent.is_empty() {
+                let parent = ONCOTREE.iter().position(|m| m.code == n.parent);
+                assert!(
+                    parent.is_some_and(|p| p < i),
+                    "{} before its parent",
+                    n.code
+                );
+            }
+            for s in n.synonyms {
+                assert_eq!(normalise(s), *s, "synonym of {} is not normalised", n.code);
+            }
+        }
+    }
+
+    #[test]
+    fn parses_codes_cohorts_names_and_misspellings() {
+        let cases = [
+            ("PAAD", Some("PAAD")),
+            ("paad", Some("PAAD")),
+            (" Pancreatic Adenocarcinoma ", Some("PAAD")),
+            ("pancreatic cancer", Some("PAAD")),
+            ("PDAC", Some("PAAD")),
+            ("TCGA-PAAD", Some("PAAD")),
+            ("D021441", Some("PAAD")),
+            ("MeSH:D002289", Some("NSCLC")),
+            ("TCGA-LIHC", Some("HCC")),
+            ("KIRC", Some("CCRCC")),
+            ("LAML", Some("AML")),
+            ("OV", Some("HGSOC")),
+            ("LGG", Some("LGGNOS")),
+            ("SARC", Some("SARCNOS")),
+            ("Breast Cancer", Some("BRCA")),
+            ("colorectal cancer", Some("COADREAD")),
+            ("Wilms tumour", Some("WT")),
+            ("oesophageal cancer", Some("ESCA")),
+            ("head_neck", Some("HEAD_NECK")),
+            ("CNS/Brain", Some("BRAIN")),
+            ("Non-small cell lung cancer", Some("NSCLC")),
+            ("glioblastma", Some("GBM")),
+            ("pancreatic adenocarinoma", Some("PAAD")),
+            ("melanomma", Some("MEL")),
+            ("hepatocelular carcinoma", Some("HCC")),
+            ("lung adenocarcnoma", Some("LUAD")),
+            ("cholangiocarcionma", Some("CHOL")),
+            // "rental cancer" is one edit from both renal and rectal.
+            ("rental cancer", None),
+            ("PADD", None),
+            ("XYZ", None),
+            ("", None),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                CancerType::parse(input).map(CancerType::code),
+                expected,
+                "parse({input:?})"
+            );
+        }
+    }
+
+    #[test]
+    fn hierarchy_and_mappings() {
+        let luad = CancerType::parse("LUAD").unwrap();
+        let lung = CancerType::parse("LUNG").unwrap();
+        assert_eq!(
+            luad.ancestors()
+                .iter()
+                .map(|c| c.code())
+                .collect::<Vec<_>>(),
+            ["NSCLC", "LUNG"]
+        );
+        assert_eq!(luad.tissue(), "Lung");
+        assert_eq!(lung.tissue(), "Lung");
+        assert!(lung.is_tissue() && !luad.is_tissue());
+        assert!(luad.is_within(lung) && !lung.is_within(luad));
+        assert_eq!(
+            lung.descendants()
+                .iter()
+                .map(|c| c.code())
+                .collect::<Vec<_>>(),
+            ["NSCLC", "LUAD", "LUSC", "LCLC", "SCLC"]
+        );
+        assert_eq!(luad.tcga_cohort(), Some("LUAD"));
+        assert_eq!(luad.mesh_id(), Some("D000077192"));
+        assert_eq!(lung.tcga_cohort(), None);
+        assert!(luad.descendants().is_empty());
+
+        let json = serde_json::to_string(&luad).unwrap();
+        assert_eq!(json, "\"LUAD\"");
+        let back: CancerType = serde_json::from_str("\"lung adenocarcinoma\"").unwrap();
+        assert_eq!(back, luad);
+        assert!(serde_json::from_str::<CancerType>("\"nope\"").is_err());
+    }
+
+    #[test]
+    fn suggestions_rank_near_misses_first() {
+        let codes = |input| {
+            CancerType::suggestions(input, 3)
+                .into_iter()
+                .map(CancerType::code)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(codes("PADD").first(), Some(&"PAAD"));
+        assert!(codes("pancreatic").contains(&"PAAD"));
+        assert!(codes("qqqqqqqq").is_empty());
+
+        let err = "PADD".parse::<CancerType>().unwrap_err();
+        assert_eq!(err.input, "PADD");
+        assert!(err.to_string().starts_with(
+            "unknown cancer type 'PADD'; did you mean PAAD (Pancreatic Adenocarcinoma)"
+        ));
+        assert_eq!(
+            "qqqqqqqq".parse::<CancerType>().unwrap_err().to_string(),
+            "unknown cancer type 'qqqqqqqq'"
+        );
+    }
+}
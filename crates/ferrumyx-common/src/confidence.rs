@@ -66,6 +66,187 @@ pub fn aggregate_confidence(confidences: &[f64]) -> f64 {
     1.0 - product
 }
 
+/// `KgFact::study_type` value marking facts extracted from review articles.
+pub const REVIEW_STUDY_TYPE: &str = "review";
+
+/// Default fraction of its confidence a review-sourced fact contributes.
+pub const DEFAULT_REVIEW_EVIDENCE_WEIGHT: f64 = 0.3;
+
+/// Whether a fact's study type marks it as review-sourced.
+pub fn is_review_study_type(study_type: Option<&str>) -> bool {
+    study_type.is_some_and(|s| s.trim().eq_ignore_ascii_case(REVIEW_STUDY_TYPE))
+}
+
+/// Weight applied to review-sourced evidence in confidence aggregation.
+/// Reads `FERRUMYX_REVIEW_EVIDENCE_WEIGHT`, clamped to [0.0, 1.0].
+pub fn review_evidence_weight() -> f64 {
+    std::env::var("FERRUMYX_REVIEW_EVIDENCE_WEIGHT")
+        .ok()
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .filter(|v| v.is_finite())
+        .unwrap_or(DEFAULT_REVIEW_EVIDENCE_WEIGHT)
+        .clamp(0.0, 1.0)
+}
+
+/// Environment variable overriding the evidence half-life, in years.
+pub const EVIDENCE_HALF_LIFE_ENV: &str = "FERRUMYX_EVIDENCE_HALF_LIFE_YEARS";
+
+/// Default half-life of literature evidence, in years.
+pub const DEFAULT_EVIDENCE_HALF_LIFE_YEARS: f64 = 10.0;
+
+const DAYS_PER_YEAR: f64 = 365.25;
+
+/// Exponential time decay of literature evidence.
+///
+/// A paper `age` years old weighs `0.5^(age / half_life)`. Papers with an
+/// unknown or future publication date keep the neutral weight of 1.0, so
+/// missing metadata never erases evidence.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EvidenceDecay {
+    half_life_years: Option<f64>,
+}
+
+impl EvidenceDecay {
+    /// Every paper weighs 1.0 regardless of its age.
+    pub const NONE: Self = Self {
+        half_life_years: None,
+    };
+
+    /// Decay with the given half-life; zero, negative or non-finite values
+    /// disable decay.
+    pub fn with_half_life(years: f64) -> Self {
+        Self {
+            half_life_years: (years.is_finite() && years > 0.0).then_some(years),
+        }
+    }
+
+    pub fn half_life_years(&self) -> Option<f64> {
+        self.half_life_years
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.half_life_years.is_some()
+    }
+
+    /// Weight in (0.0, 1.0] of evidence published at `published_at`, as seen
+    /// at `now`.
+    pub fn weight_at(
+        &self,
+        published_at: Option<chrono::DateTime<chrono::Utc>>,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> f64 {
+        let (Some(half_life), Some(published_at)) = (self.half_life_years, published_at) else {
+            return 1.0;
+        };
+        let age_years = (now - published_at).num_days() as f64 / DAYS_PER_YEAR;
+        if age_years <= 0.0 {
+            return 1.0;
+        }
+        0.5_f64.powf(age_years / half_life)
+    }
+
+    /// [`Self::weight_at`] against [`crate::repro::now`]; pinned runs see
+    /// every paper as current and therefore apply no decay.
+    pub fn weight(&self, published_at: Option<chrono::DateTime<chrono::Utc>>) -> f64 {
+        self.weight_at(published_at, crate::repro::now())
+    }
+}
+
+impl Default for EvidenceDecay {
+    fn default() -> Self {
+        Self::with_half_life(DEFAULT_EVIDENCE_HALF_LIFE_YEARS)
+    }
+}
+
+static EVIDENCE_DECAY: std::sync::RwLock<Option<EvidenceDecay>> = std::sync::RwLock::new(None);
+
+/// Process-wide evidence decay: the value passed to [`set_evidence_decay`],
+/// else `FERRUMYX_EVIDENCE_HALF_LIFE_YEARS` (0 disables decay), else the
+/// default half-life.
+pub fn evidence_decay() -> EvidenceDecay {
+    if let Some(decay) = *EVIDENCE_DECAY.read().unwrap_or_else(|e| e.into_inner()) {
+        return decay;
+    }
+    std::env::var(EVIDENCE_HALF_LIFE_ENV)
+        .ok()
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .map_or_else(EvidenceDecay::default, EvidenceDecay::with_half_life)
+}
+
+/// Set the process-wide evidence decay, typically from
+/// `[scoring] evidence_half_life_years`. Overrides the environment.
+pub fn set_evidence_decay(decay: EvidenceDecay) {
+    *EVIDENCE_DECAY.write().unwrap_or_else(|e| e.into_inner()) = Some(decay);
+}
+
+/// One supporting fact for an aggregated edge.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SupportingEvidence {
+    /// Stable key of the source paper (id, DOI, ...).
+    pub paper_key: String,
+    pub confidence: f64,
+    pub is_review: bool,
+}
+
+/// Aggregate support for one (subject, predicate, object) triple.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct AggregatedSupport {
+    /// Noisy-OR confidence with review evidence down-weighted.
+    pub confidence: f64,
+    /// Independent primary papers.
+    pub paper_count: usize,
+    /// Review articles, shown separately and never counted as independent.
+    pub review_count: usize,
+}
+
+impl AggregatedSupport {
+    /// Display label such as `"1 (+3 reviews)"`.
+    pub fn label(&self) -> String {
+        match self.review_count {
+            0 => self.paper_count.to_string(),
+            1 => format!("{} (+1 review)", self.paper_count),
+            n => format!("{} (+{} reviews)", self.paper_count, n),
+        }
+    }
+}
+
+/// Aggregate supporting evidence for a triple.
+///
+/// Review confidences are scaled by `review_weight` before the noisy-OR,
+/// and papers are counted once each, primary and review separately.
+pub fn aggregate_support(evidence: &[SupportingEvidence], review_weight: f64) -> AggregatedSupport {
+    let weight = review_weight.clamp(0.0, 1.0);
+    let confidences: Vec<f64> = evidence
+        .iter()
+        .map(|e| {
+            let c = e.confidence.clamp(0.0, 1.0);
+            if e.is_review {
+                c * weight
+            } else {
+                c
+            }
+        })
+        .collect();
+
+    let mut primary = std::collections::HashSet::new();
+    let mut reviews = std::collections::HashSet::new();
+    for e in evidence {
+        if e.is_review {
+            reviews.insert(e.paper_key.as_str());
+        } else {
+            primary.insert(e.paper_key.as_str());
+        }
+    }
+    // A paper flagged both ways counts as primary.
+    reviews.retain(|k| !primary.contains(k));
+
+    AggregatedSupport {
+        confidence: aggregate_confidence(&confidences),
+        paper_count: primary.len(),
+        review_count: reviews.len(),
+    }
+}
+
 /// Handle contradictory evidence: compute net confidence
 /// and apply contradiction penalty (×0.70).
 /// signed_confidences: positive = supporting, negative = contradicting
@@ -78,6 +259,7 @@ pub fn contradictory_confidence(signed_confidences: &[f64]) -> f64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::{DateTime, Utc};
 
     #[test]
     fn test_retracted_is_zero() {
@@ -106,6 +288,87 @@ mod tests {
         assert!((agg - 0.91).abs() < 1e-6);
     }
 
+    fn evidence(paper: &str, confidence: f64, is_review: bool) -> SupportingEvidence {
+        SupportingEvidence {
+            paper_key: paper.to_string(),
+            confidence,
+            is_review,
+        }
+    }
+
+    #[test]
+    fn test_reviews_counted_separately_and_down_weighted() {
+        let mixed = [
+            evidence("p1", 0.7, false),
+            evidence("r1", 0.7, true),
+            evidence("r2", 0.7, true),
+            evidence("r3", 0.7, true),
+        ];
+        let agg = aggregate_support(&mixed, DEFAULT_REVIEW_EVIDENCE_WEIGHT);
+        assert_eq!(agg.paper_count, 1);
+        assert_eq!(agg.review_count, 3);
+        assert_eq!(agg.label(), "1 (+3 reviews)");
+
+        // 1 - 0.3 * (1 - 0.21)^3
+        assert!((agg.confidence - (1.0 - 0.3 * 0.79_f64.powi(3))).abs() < 1e-9);
+
+        let primary_only = aggregate_support(&mixed[..1], DEFAULT_REVIEW_EVIDENCE_WEIGHT);
+        let four_primary = aggregate_support(
+            &[
+                evidence("p1", 0.7, false),
+                evidence("p2", 0.7, false),
+                evidence("p3", 0.7, false),
+                evidence("p4", 0.7, false),
+            ],
+            DEFAULT_REVIEW_EVIDENCE_WEIGHT,
+        );
+        assert!(agg.confidence > primary_only.confidence);
+        assert!(agg.confidence < four_primary.confidence);
+        assert_eq!(four_primary.paper_count, 4);
+    }
+
+    #[test]
+    fn test_review_study_type() {
+        assert!(is_review_study_type(Some("Review")));
+        assert!(!is_review_study_type(Some("rct")));
+        assert!(!is_review_study_type(None));
+    }
+
+    fn years_ago(now: DateTime<Utc>, years: i64) -> Option<DateTime<Utc>> {
+        Some(now - chrono::Duration::days((years as f64 * DAYS_PER_YEAR) as i64))
+    }
+
+    #[test]
+    fn test_evidence_decay_halves_per_half_life() {
+        let now = Utc::now();
+        let decay = EvidenceDecay::with_half_life(10.0);
+        let weights: Vec<f64> = [0, 5, 10, 30]
+            .into_iter()
+            .map(|years| decay.weight_at(years_ago(now, years), now))
+            .collect();
+        let expected = [1.0, 0.5_f64.sqrt(), 0.5, 0.125];
+        for (weight, expected) in weights.iter().zip(expected) {
+            assert!((weight - expected).abs() < 1e-3, "{weights:?}");
+        }
+    }
+
+    #[test]
+    fn test_evidence_decay_neutral_cases() {
+        let now = Utc::now();
+        let decay = EvidenceDecay::with_half_life(10.0);
+        assert_eq!(decay.weight_at(None, now), 1.0);
+        assert_eq!(
+            decay.weight_at(Some(now + chrono::Duration::days(30)), now),
+            1.0
+        );
+
+        for off in [EvidenceDecay::NONE, EvidenceDecay::with_half_life(0.0)] {
+            assert!(!off.is_enabled());
+            assert_eq!(off.weight_at(years_ago(now, 30), now), 1.0);
+        }
+        assert!(!EvidenceDecay::with_half_life(f64::NAN).is_enabled());
+    }
+
     #[test]
     fn test_capped_at_one() {
         let mods = ConfidenceModifiers {
@@ -0,0 +1,119 @@
+//! Data classification shared by tools, LLM routing and the audit log.
+//!
+//! Classes are ordered by sensitivity, so the class of content that mixes
+//! several sources is the `max` of theirs. Tool results carry their class
+//! under [`DATA_CLASS_KEY`], and LLM requests carry it in their metadata
+//! under the same key.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// JSON field on tool results and metadata key on LLM requests.
+pub const DATA_CLASS_KEY: &str = "data_class";
+
+/// Sensitivity of the data a tool returned or an LLM call would see.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize,
+)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum DataClass {
+    /// Published literature and public databases.
+    #[default]
+    Public,
+    /// Unpublished but shareable with a vetted remote provider when
+    /// `[llm] allow_internal_remote` is set.
+    Internal,
+    /// Never leaves a local backend.
+    Confidential,
+}
+
+impl DataClass {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Public => "PUBLIC",
+            Self::Internal => "INTERNAL",
+            Self::Confidential => "CONFIDENTIAL",
+        }
+    }
+
+    /// Whether content of this class may only go to a local backend.
+    pub fn requires_local(self, allow_internal_remote: bool) -> bool {
+        match self {
+            Self::Public => false,
+            Self::Internal => !allow_internal_remote,
+            Self::Confidential => true,
+        }
+    }
+
+    /// Highest class tagged anywhere in `text` as `"data_class": "..."`,
+    /// e.g. tool results embedded in a prompt.
+    pub fn tagged_in(text: &str) -> Option<Self> {
+        let needle = format!("\"{DATA_CLASS_KEY}\"");
+        let mut highest = None;
+        let mut rest = text;
+        while let Some(at) = rest.find(&needle) {
+            rest = &rest[at + needle.len()..];
+            let Some(value) = rest.trim_start().strip_prefix(':') else {
+                continue;
+            };
+            let Some(value) = value.trim_start().strip_prefix('"') else {
+                continue;
+            };
+            let Some(class) = value
+                .split_once('"')
+                .and_then(|(class, _)| class.parse::<Self>().ok())
+            else {
+                continue;
+            };
+            highest = highest.max(Some(class));
+        }
+        highest
+    }
+}
+
+impl fmt::Display for DataClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DataClass {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_uppercase().as_str() {
+            "PUBLIC" => Ok(Self::Public),
+            "INTERNAL" => Ok(Self::Internal),
+            "CONFIDENTIAL" => Ok(Self::Confidential),
+            other => Err(format!(
+                "unknown data class {other:?}, expected PUBLIC, INTERNAL or CONFIDENTIAL"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classes_parse_order_and_are_found_in_text() {
+        assert_eq!("confidential".parse(), Ok(DataClass::Confidential));
+        assert!("secret".parse::<DataClass>().is_err());
+        assert!(DataClass::Public < DataClass::Internal);
+        assert!(DataClass::Internal.requires_local(false));
+        assert!(!DataClass::Internal.requires_local(true));
+        assert!(DataClass::Confidential.requires_local(true));
+
+        let prompt = "<tool_output>\n{\n  \"data_class\": \"CONFIDENTIAL\",\n  \"rows\": 3\n}\
+                      </tool_output> and {\"data_class\":\"INTERNAL\"}";
+        assert_eq!(DataClass::tagged_in(prompt), Some(DataClass::Confidential));
+        assert_eq!(
+            DataClass::tagged_in("{\"data_class\":\"PUBLIC\"}"),
+            Some(DataClass::Public)
+        );
+        assert_eq!(DataClass::tagged_in("data_class: CONFIDENTIAL"), None);
+    }
+}
@@ -45,6 +45,16 @@ pub enum ApiError {
     NotFound(String),
     #[error("Bad request: {0}")]
     BadRequest(String),
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+    #[error("Conflict: {0}")]
+    Conflict(String),
+    #[error("Too many requests: {0}")]
+    TooManyRequests(String),
+    #[error("Payload too large: {0}")]
+    PayloadTooLarge(String),
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
     #[error("Internal error: {0}")]
     Internal(String),
 }
@@ -54,6 +64,11 @@ impl axum::response::IntoResponse for ApiError {
         let (status, message) = match self {
             ApiError::NotFound(msg) => (axum::http::StatusCode::NOT_FOUND, msg),
             ApiError::BadRequest(msg) => (axum::http::StatusCode::BAD_REQUEST, msg),
+            ApiError::Forbidden(msg) => (axum::http::StatusCode::FORBIDDEN, msg),
+            ApiError::Conflict(msg) => (axum::http::StatusCode::CONFLICT, msg),
+            ApiError::TooManyRequests(msg) => (axum::http::StatusCode::TOO_MANY_REQUESTS, msg),
+            ApiError::PayloadTooLarge(msg) => (axum::http::StatusCode::PAYLOAD_TOO_LARGE, msg),
+            ApiError::ServiceUnavailable(msg) => (axum::http::StatusCode::SERVICE_UNAVAILABLE, msg),
             ApiError::Internal(msg) => (axum::http::StatusCode::INTERNAL_SERVER_ERROR, msg),
         };
         let body = axum::Json(serde_json::json!({ "error": message }));
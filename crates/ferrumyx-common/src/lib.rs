@@ -1,11 +1,19 @@
 //! ferrumyx-common — Shared types, errors, and traits used across all Ferrumyx crates.
 
+pub mod cancer_type;
 pub mod confidence;
+pub mod data_class;
 pub mod entities;
 pub mod error;
 pub mod federation;
 pub mod query;
+pub mod repro;
+pub mod sandbox;
+pub mod secrets;
 pub mod target_config;
+pub mod tool_stats;
 
 // Re-export commonly used types
+pub use cancer_type::{CancerType, UnknownCancerType};
+pub use data_class::DataClass;
 pub use target_config::{Constraints, ScoringConfig, TargetConfig, TargetSpec};
@@ -37,6 +37,10 @@ pub struct TargetMetrics {
     pub chembl_inhibitor_count: u32,
     pub reactome_escape_pathway_count: u32,
     pub literature_novelty_velocity: f64,
+    /// Summed DepMap co-dependency of the gene's strongest partners; high
+    /// values mean other genes can stand in for it.
+    #[serde(default)]
+    pub pathway_redundancy: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
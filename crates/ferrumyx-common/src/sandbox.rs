@@ -0,0 +1,367 @@
+//! Confinement of tool and pipeline files to the configured workspace.
+//!
+//! Paths built from tool parameters go through [`Sandbox::resolve`] before
+//! anything is created, so neither `../../etc/cron.d` nor a symlink planted
+//! in the workspace can lead a write outside it. Each job also has a byte
+//! quota that writers charge before they write.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+use thiserror::Error;
+
+/// Directory under the root holding [`Sandbox::create_scoped_dir`]s.
+pub const JOBS_DIR: &str = "jobs";
+
+#[derive(Debug, Error)]
+pub enum SandboxError {
+    #[error("{} is absolute; paths must be relative to the workspace", .0.display())]
+    AbsolutePath(PathBuf),
+    #[error("{} leads outside the workspace", .0.display())]
+    Escape(PathBuf),
+    #[error("{0:?} is not a plain file or directory name")]
+    InvalidName(String),
+    #[error("job {job_id} has used {used} of its {limit} bytes and cannot write {requested} more")]
+    QuotaExceeded {
+        job_id: String,
+        used: u64,
+        requested: u64,
+        limit: u64,
+    },
+    #[error("{}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, SandboxError>;
+
+/// The workspace root every tool file has to stay under, and the bytes each
+/// job has written there.
+#[derive(Debug)]
+pub struct Sandbox {
+    root: PathBuf,
+    max_job_bytes: Option<u64>,
+    usage: Mutex<HashMap<String, u64>>,
+}
+
+impl Sandbox {
+    /// Sandbox over `root`, which is created if missing. Jobs may write
+    /// without limit until [`Self::with_max_job_bytes`] sets a quota.
+    pub fn new(root: impl AsRef<Path>) -> Result<Self> {
+        let root = root.as_ref();
+        std::fs::create_dir_all(root).map_err(|e| io_error(root, e))?;
+        let root = root.canonicalize().map_err(|e| io_error(root, e))?;
+        Ok(Self {
+            root,
+            max_job_bytes: None,
+            usage: Mutex::default(),
+        })
+    }
+
+    /// Refuse writes that would take a job past `bytes` in total.
+    pub fn with_max_job_bytes(mut self, bytes: u64) -> Self {
+        self.max_job_bytes = Some(bytes);
+        self
+    }
+
+    /// The canonical workspace root.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn max_job_bytes(&self) -> Option<u64> {
+        self.max_job_bytes
+    }
+
+    /// `relative` under the root. Absolute paths, `..` climbing above the
+    /// root and symlinks leading out of it are rejected; the part of the
+    /// path that does not exist yet is kept as given.
+    pub fn resolve(&self, relative: &str) -> Result<PathBuf> {
+        let path = Path::new(relative);
+        let mut normal = PathBuf::new();
+        for component in path.components() {
+            match component {
+                Component::Normal(part) => normal.push(part),
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    if !normal.pop() {
+                        return Err(SandboxError::Escape(path.to_path_buf()));
+                    }
+                }
+                Component::RootDir | Component::Prefix(_) => {
+                    return Err(SandboxError::AbsolutePath(path.to_path_buf()))
+                }
+            }
+        }
+        self.confine(&self.root.join(normal))
+    }
+
+    /// `path`, absolute or relative to the working directory, with its
+    /// existing part canonicalised, if that lies under the root. For
+    /// directories the operator configured rather than names from tool
+    /// parameters, which go through [`Self::resolve`].
+    pub fn confine(&self, path: &Path) -> Result<PathBuf> {
+        let mut existing = path;
+        let mut missing = Vec::new();
+        let canonical = loop {
+            match existing.canonicalize() {
+                Ok(canonical) => break canonical,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    // A dangling symlink would be followed by the write.
+                    if existing.symlink_metadata().is_ok() {
+                        return Err(SandboxError::Escape(path.to_path_buf()));
+                    }
+                    let (Some(parent), Some(name)) = (existing.parent(), existing.file_name())
+                    else {
+                        return Err(SandboxError::Escape(path.to_path_buf()));
+                    };
+                    missing.push(name);
+                    existing = if parent.as_os_str().is_empty() {
+                        Path::new(".")
+                    } else {
+                        parent
+                    };
+                }
+                Err(e) => return Err(io_error(existing, e)),
+            }
+        };
+        let resolved = missing
+            .iter()
+            .rev()
+            .fold(canonical, |dir, name| dir.join(name));
+        if resolved.starts_with(&self.root) {
+            Ok(resolved)
+        } else {
+            Err(SandboxError::Escape(path.to_path_buf()))
+        }
+    }
+
+    /// `<root>/jobs/<job_id>`, created if missing, for files that belong to
+    /// one job only.
+    pub fn create_scoped_dir(&self, job_id: &str) -> Result<PathBuf> {
+        let dir = self.root.join(JOBS_DIR).join(file_name(job_id)?);
+        let dir = self.confine(&dir)?;
+        std::fs::create_dir_all(&dir).map_err(|e| io_error(&dir, e))?;
+        self.confine(&dir)
+    }
+
+    /// Count `bytes` against the quota of `job_id` ahead of writing them.
+    /// Nothing is counted when they do not fit.
+    pub fn charge(&self, job_id: &str, bytes: u64) -> Result<()> {
+        let mut usage = self.usage.lock().unwrap_or_else(|e| e.into_inner());
+        let used = usage.get(job_id).copied().unwrap_or(0);
+        if let Some(limit) = self.max_job_bytes {
+            if used.saturating_add(bytes) > limit {
+                return Err(SandboxError::QuotaExceeded {
+                    job_id: job_id.to_string(),
+                    used,
+                    requested: bytes,
+                    limit,
+                });
+            }
+        }
+        usage.insert(job_id.to_string(), used.saturating_add(bytes));
+        Ok(())
+    }
+
+    /// Bytes charged to `job_id` so far.
+    pub fn job_usage(&self, job_id: &str) -> u64 {
+        self.usage
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(job_id)
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+/// `name` if it can name one entry of a sandboxed directory: no separators,
+/// and neither `.` nor `..`.
+pub fn file_name(name: &str) -> Result<&str> {
+    let mut components = Path::new(name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) if !name.contains(['/', '\\']) => Ok(name),
+        _ => Err(SandboxError::InvalidName(name.to_string())),
+    }
+}
+
+/// Bytes of the regular files under `path`, for output written by
+/// containers or child processes that cannot be charged ahead of time.
+/// Symlinks are not followed; a missing path counts as empty.
+pub fn disk_usage(path: &Path) -> io::Result<u64> {
+    let metadata = match path.symlink_metadata() {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    };
+    if metadata.is_file() {
+        return Ok(metadata.len());
+    }
+    if !metadata.is_dir() {
+        return Ok(0);
+    }
+    let mut total = 0;
+    for entry in std::fs::read_dir(path)? {
+        total += disk_usage(&entry?.path())?;
+    }
+    Ok(total)
+}
+
+fn io_error(path: &Path, source: io::Error) -> SandboxError {
+    SandboxError::Io {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sandbox() -> (tempfile::TempDir, Sandbox) {
+        let dir = tempfile::tempdir().unwrap();
+        let sandbox = Sandbox::new(dir.path().join("workspace")).unwrap();
+        (dir, sandbox)
+    }
+
+    #[test]
+    fn resolves_relative_paths_under_the_root() {
+        let (_dir, sandbox) = sandbox();
+        std::fs::create_dir_all(sandbox.root().join("structures")).unwrap();
+
+        assert_eq!(
+            sandbox.resolve("structures/1crn.pdb").unwrap(),
+            sandbox.root().join("structures/1crn.pdb")
+        );
+        assert_eq!(
+            sandbox.resolve("./exports/../reports/KRAS.md").unwrap(),
+            sandbox.root().join("reports/KRAS.md")
+        );
+        assert_eq!(sandbox.resolve("").unwrap(), sandbox.root());
+    }
+
+    #[test]
+    fn rejects_absolute_paths_and_traversal() {
+        let (_dir, sandbox) = sandbox();
+
+        assert!(matches!(
+            sandbox.resolve("/etc/cron.d/job"),
+            Err(SandboxError::AbsolutePath(_))
+        ));
+        for escape in [
+            "..",
+            "../outside.txt",
+            "../../etc/cron.d",
+            "reports/../../outside.txt",
+            "a/b/../../../outside.txt",
+        ] {
+            assert!(
+                matches!(sandbox.resolve(escape), Err(SandboxError::Escape(_))),
+                "{escape}"
+            );
+        }
+        let outside = sandbox.root().parent().unwrap().join("outside.txt");
+        assert!(matches!(
+            sandbox.confine(&outside),
+            Err(SandboxError::Escape(_))
+        ));
+        assert!(sandbox.confine(&sandbox.root().join("structures")).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_symlinks_leading_out_of_the_root() {
+        let (dir, sandbox) = sandbox();
+        let outside = dir.path().join("outside");
+        std::fs::create_dir_all(&outside).unwrap();
+        std::os::unix::fs::symlink(&outside, sandbox.root().join("escape")).unwrap();
+        std::os::unix::fs::symlink(
+            outside.join("missing.txt"),
+            sandbox.root().join("dangling.txt"),
+        )
+        .unwrap();
+        std::fs::create_dir_all(sandbox.root().join("inside")).unwrap();
+        std::os::unix::fs::symlink(sandbox.root().join("inside"), sandbox.root().join("alias"))
+            .unwrap();
+
+        for escape in ["escape", "escape/cron.d", "escape/new/dir", "dangling.txt"] {
+            assert!(
+                matches!(sandbox.resolve(escape), Err(SandboxError::Escape(_))),
+                "{escape}"
+            );
+        }
+        assert_eq!(
+            sandbox.resolve("alias/file.txt").unwrap(),
+            sandbox.root().join("inside/file.txt")
+        );
+    }
+
+    #[test]
+    fn scoped_dirs_are_created_per_job() {
+        let (_dir, sandbox) = sandbox();
+
+        let dir = sandbox.create_scoped_dir("job-1").unwrap();
+        assert!(dir.is_dir());
+        assert_eq!(dir, sandbox.root().join(JOBS_DIR).join("job-1"));
+        for name in ["", ".", "..", "../job", "a/b", "a\\b"] {
+            assert!(
+                matches!(
+                    sandbox.create_scoped_dir(name),
+                    Err(SandboxError::InvalidName(_))
+                ),
+                "{name:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn quota_is_charged_per_job_until_exhausted() {
+        let (_dir, sandbox) = sandbox();
+        let sandbox = sandbox.with_max_job_bytes(100);
+
+        sandbox.charge("a", 60).unwrap();
+        sandbox.charge("a", 40).unwrap();
+        let err = sandbox.charge("a", 1).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                SandboxError::QuotaExceeded {
+                    used: 100,
+                    requested: 1,
+                    limit: 100,
+                    ..
+                }
+            ),
+            "{err}"
+        );
+        assert_eq!(sandbox.job_usage("a"), 100);
+        // Other jobs keep their own allowance.
+        sandbox.charge("b", 100).unwrap();
+        assert!(sandbox.charge("b", 1).is_err());
+    }
+
+    #[test]
+    fn unlimited_without_a_quota() {
+        let (_dir, sandbox) = sandbox();
+        sandbox.charge("a", u64::MAX).unwrap();
+        sandbox.charge("a", 1).unwrap();
+        assert_eq!(sandbox.job_usage("a"), u64::MAX);
+    }
+
+    #[test]
+    fn disk_usage_counts_regular_files() {
+        let (_dir, sandbox) = sandbox();
+        let dir = sandbox.create_scoped_dir("job").unwrap();
+        std::fs::create_dir_all(dir.join("poses")).unwrap();
+        std::fs::write(dir.join("receptor.pdbqt"), [0u8; 10]).unwrap();
+        std::fs::write(dir.join("poses/ligand_1.pdbqt"), [0u8; 5]).unwrap();
+
+        assert_eq!(disk_usage(&dir).unwrap(), 15);
+        assert_eq!(disk_usage(&dir.join("missing")).unwrap(), 0);
+    }
+}
@@ -0,0 +1,239 @@
+//! Encryption at rest for API keys and tokens saved to `ferrumyx.toml`.
+//!
+//! A sealed value is `enc:v1:` followed by base64 of `salt || nonce ||
+//! ciphertext`: AES-256-GCM under a key derived with HKDF-SHA256 from the
+//! machine key and the per-value salt. The machine key is a SHA-256 of
+//! `FERRUMYX_SECRET_KEY` when set, otherwise of the OS machine id (or host
+//! and user name where there is none), so a copied config file does not
+//! carry usable keys to another machine.
+//!
+//! Values without the prefix pass through [`open`] unchanged, so
+//! hand-written plain-text keys keep working.
+
+use aes_gcm::aead::{rand_core::RngCore, Aead, AeadCore, OsRng};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use base64::Engine;
+use hkdf::Hkdf;
+use sha2::{Digest, Sha256};
+
+use crate::error::{FerrumyxError, Result};
+
+/// Prefix of every sealed value.
+pub const SEALED_PREFIX: &str = "enc:v1:";
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HKDF_INFO: &[u8] = b"ferrumyx-settings-secret-v1";
+
+/// Key material secrets are sealed under.
+#[derive(Clone)]
+pub struct SecretKey([u8; 32]);
+
+impl SecretKey {
+    /// Key derived from `material`; the same material gives the same key.
+    pub fn from_material(material: &[u8]) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(HKDF_INFO);
+        hasher.update(material);
+        Self(hasher.finalize().into())
+    }
+
+    /// This machine's key: `FERRUMYX_SECRET_KEY` if set, else the OS
+    /// machine id, else host and user name.
+    pub fn machine() -> Self {
+        if let Some(key) = std::env::var("FERRUMYX_SECRET_KEY")
+            .ok()
+            .filter(|k| !k.trim().is_empty())
+        {
+            return Self::from_material(key.trim().as_bytes());
+        }
+        let machine_id = ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+            .iter()
+            .find_map(|path| {
+                std::fs::read_to_string(path)
+                    .ok()
+                    .map(|id| id.trim().to_string())
+                    .filter(|id| !id.is_empty())
+            });
+        let material = machine_id.unwrap_or_else(|| {
+            let env = |names: &[&str]| {
+                names
+                    .iter()
+                    .find_map(|n| std::env::var(n).ok())
+                    .unwrap_or_default()
+            };
+            format!(
+                "{}/{}",
+                env(&["HOSTNAME", "COMPUTERNAME"]),
+                env(&["USER", "USERNAME"])
+            )
+        });
+        Self::from_material(material.as_bytes())
+    }
+
+    fn cipher(&self, salt: &[u8]) -> Result<Aes256Gcm> {
+        let mut key = [0u8; 32];
+        Hkdf::<Sha256>::new(Some(salt), &self.0)
+            .expand(HKDF_INFO, &mut key)
+            .map_err(|_| FerrumyxError::SecurityError("secret key derivation failed".into()))?;
+        Aes256Gcm::new_from_slice(&key).map_err(|e| FerrumyxError::SecurityError(e.to_string()))
+    }
+}
+
+impl std::fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SecretKey([REDACTED])")
+    }
+}
+
+/// Whether `value` was written by [`seal`].
+pub fn is_sealed(value: &str) -> bool {
+    value.starts_with(SEALED_PREFIX)
+}
+
+/// Seal `plaintext` under this machine's key.
+pub fn seal(plaintext: &str) -> Result<String> {
+    seal_with(&SecretKey::machine(), plaintext)
+}
+
+/// Open a sealed `value` with this machine's key; anything else is
+/// returned as is.
+pub fn open(value: &str) -> Result<String> {
+    open_with(&SecretKey::machine(), value)
+}
+
+pub fn seal_with(key: &SecretKey, plaintext: &str) -> Result<String> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = key
+        .cipher(&salt)?
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|e| FerrumyxError::SecurityError(format!("secret encryption failed: {e}")))?;
+
+    let mut sealed = Vec::with_capacity(SALT_LEN + NONCE_LEN + ciphertext.len());
+    sealed.extend_from_slice(&salt);
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&ciphertext);
+    Ok(format!(
+        "{SEALED_PREFIX}{}",
+        base64::engine::general_purpose::STANDARD.encode(sealed)
+    ))
+}
+
+pub fn open_with(key: &SecretKey, value: &str) -> Result<String> {
+    let Some(encoded) = value.strip_prefix(SEALED_PREFIX) else {
+        return Ok(value.to_string());
+    };
+    let sealed = base64::engine::general_purpose::STANDARD
+        .decode(encoded.trim())
+        .map_err(|e| FerrumyxError::SecurityError(format!("sealed secret is not base64: {e}")))?;
+    if sealed.len() < SALT_LEN + NONCE_LEN {
+        return Err(FerrumyxError::SecurityError(
+            "sealed secret is truncated".into(),
+        ));
+    }
+    let (salt, rest) = sealed.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    let plaintext = key
+        .cipher(salt)?
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| {
+            FerrumyxError::SecurityError(
+                "sealed secret does not open with this machine's key".into(),
+            )
+        })?;
+    String::from_utf8(plaintext).map_err(|e| FerrumyxError::SecurityError(e.to_string()))
+}
+
+/// Open every sealed string in `root` in place with this machine's key.
+/// Values that do not open are blanked, so they read as unset, and their
+/// dotted paths returned.
+pub fn open_sealed_values(root: &mut toml::Value) -> Vec<String> {
+    let key = SecretKey::machine();
+    let mut failed = Vec::new();
+    open_sealed_in(&key, root, &mut String::new(), &mut failed);
+    failed
+}
+
+fn open_sealed_in(
+    key: &SecretKey,
+    value: &mut toml::Value,
+    path: &mut String,
+    failed: &mut Vec<String>,
+) {
+    match value {
+        toml::Value::String(s) if is_sealed(s) => match open_with(key, s) {
+            Ok(plain) => *s = plain,
+            Err(_) => {
+                s.clear();
+                failed.push(path.clone());
+            }
+        },
+        toml::Value::Table(table) => {
+            for (name, child) in table.iter_mut() {
+                let len = path.len();
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(name);
+                open_sealed_in(key, child, path, failed);
+                path.truncate(len);
+            }
+        }
+        toml::Value::Array(items) => {
+            for item in items {
+                open_sealed_in(key, item, path, failed);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sealed_secrets_round_trip() {
+        let key = SecretKey::from_material(b"machine-a");
+        let sealed = seal_with(&key, "sk-test-123").unwrap();
+        assert!(is_sealed(&sealed));
+        assert!(!sealed.contains("sk-test-123"));
+        assert_eq!(open_with(&key, &sealed).unwrap(), "sk-test-123");
+
+        // A fresh salt and nonce each time.
+        assert_ne!(seal_with(&key, "sk-test-123").unwrap(), sealed);
+        assert_eq!(open_with(&key, "plain-key").unwrap(), "plain-key");
+    }
+
+    #[test]
+    fn sealed_secrets_do_not_open_elsewhere() {
+        let sealed = seal_with(&SecretKey::from_material(b"machine-a"), "sk-test").unwrap();
+        let other = SecretKey::from_material(b"machine-b");
+        assert!(open_with(&other, &sealed).is_err());
+
+        let engine = base64::engine::general_purpose::STANDARD;
+        let mut bytes = engine.decode(&sealed[SEALED_PREFIX.len()..]).unwrap();
+        *bytes.last_mut().unwrap() ^= 1;
+        let tampered = format!("{SEALED_PREFIX}{}", engine.encode(bytes));
+        assert!(open_with(&SecretKey::from_material(b"machine-a"), &tampered).is_err());
+        assert!(open_with(&other, "enc:v1:AAAA").is_err());
+    }
+
+    #[test]
+    fn sealed_values_open_in_place() {
+        let key = SecretKey::machine();
+        let mut root: toml::Value = toml::from_str(&format!(
+            "[llm.openai]\napi_key = \"{}\"\nmodel = \"gpt-4o\"\n\n[llm.gemini]\napi_key = \"enc:v1:garbage\"\n",
+            seal_with(&key, "sk-openai").unwrap()
+        ))
+        .unwrap();
+
+        let failed = open_sealed_values(&mut root);
+        assert_eq!(failed, ["llm.gemini.api_key"]);
+        assert_eq!(root["llm"]["openai"]["api_key"].as_str(), Some("sk-openai"));
+        assert_eq!(root["llm"]["openai"]["model"].as_str(), Some("gpt-4o"));
+        assert_eq!(root["llm"]["gemini"]["api_key"].as_str(), Some(""));
+    }
+}
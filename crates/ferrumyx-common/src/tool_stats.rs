@@ -0,0 +1,118 @@
+//! Per-tool invocation counters, shared by the tool registry that records
+//! them and the web API that serves them.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Upper bounds of the latency histogram buckets, in milliseconds. A last,
+/// unbounded bucket counts anything slower.
+pub const LATENCY_BUCKETS_MS: [u64; 8] = [10, 100, 500, 1_000, 5_000, 30_000, 120_000, 600_000];
+
+/// How one invocation ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolOutcome {
+    Success,
+    Error,
+    /// Cancelled by the registry after the tool's timeout; also an error.
+    TimedOut,
+}
+
+/// Invocations of one tool since start-up.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolStats {
+    pub tool: String,
+    pub invocations: u64,
+    pub successes: u64,
+    /// Failed invocations, timeouts included.
+    pub errors: u64,
+    pub timeouts: u64,
+    pub total_latency_ms: u64,
+    pub max_latency_ms: u64,
+    /// Invocations per [`LATENCY_BUCKETS_MS`] bucket, plus the unbounded one.
+    pub latency_buckets: Vec<u64>,
+}
+
+impl ToolStats {
+    pub fn new(tool: impl Into<String>) -> Self {
+        Self {
+            tool: tool.into(),
+            invocations: 0,
+            successes: 0,
+            errors: 0,
+            timeouts: 0,
+            total_latency_ms: 0,
+            max_latency_ms: 0,
+            latency_buckets: vec![0; LATENCY_BUCKETS_MS.len() + 1],
+        }
+    }
+
+    pub fn record(&mut self, latency: Duration, outcome: ToolOutcome) {
+        let ms = u64::try_from(latency.as_millis()).unwrap_or(u64::MAX);
+        self.invocations += 1;
+        match outcome {
+            ToolOutcome::Success => self.successes += 1,
+            ToolOutcome::Error => self.errors += 1,
+            ToolOutcome::TimedOut => {
+                self.errors += 1;
+                self.timeouts += 1;
+            }
+        }
+        self.total_latency_ms = self.total_latency_ms.saturating_add(ms);
+        self.max_latency_ms = self.max_latency_ms.max(ms);
+        let bucket = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|&bound| ms <= bound)
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+        self.latency_buckets[bucket] += 1;
+    }
+
+    pub fn mean_latency_ms(&self) -> Option<u64> {
+        (self.invocations > 0).then(|| self.total_latency_ms / self.invocations)
+    }
+
+    /// Upper bound of the bucket holding quantile `q` (0.0–1.0) of the
+    /// latencies; the maximum latency when it falls in the unbounded bucket.
+    pub fn latency_quantile_ms(&self, q: f64) -> Option<u64> {
+        if self.invocations == 0 {
+            return None;
+        }
+        let rank = ((q.clamp(0.0, 1.0) * self.invocations as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (i, count) in self.latency_buckets.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return Some(
+                    LATENCY_BUCKETS_MS
+                        .get(i)
+                        .map_or(self.max_latency_ms, |&bound| bound.min(self.max_latency_ms)),
+                );
+            }
+        }
+        Some(self.max_latency_ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_outcomes_and_latency_buckets() {
+        let mut stats = ToolStats::new("ingest_literature");
+        assert_eq!(stats.latency_quantile_ms(0.95), None);
+        for ms in [5, 80, 90, 400] {
+            stats.record(Duration::from_millis(ms), ToolOutcome::Success);
+        }
+        stats.record(Duration::from_millis(3_000), ToolOutcome::Error);
+        stats.record(Duration::from_secs(700), ToolOutcome::TimedOut);
+
+        assert_eq!(stats.invocations, 6);
+        assert_eq!((stats.successes, stats.errors, stats.timeouts), (4, 2, 1));
+        assert_eq!(stats.latency_buckets, [1, 2, 1, 0, 1, 0, 0, 0, 1]);
+        assert_eq!(stats.max_latency_ms, 700_000);
+        assert_eq!(stats.latency_quantile_ms(0.5), Some(100));
+        assert_eq!(stats.latency_quantile_ms(1.0), Some(700_000));
+        assert_eq!(stats.mean_latency_ms(), Some(703_575 / 6));
+    }
+}
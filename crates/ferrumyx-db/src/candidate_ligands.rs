@@ -0,0 +1,193 @@
+//! Candidate ligand repository.
+//!
+//! The ligands queued for docking against a target, keyed by
+//! (target_chembl_id, smiles) with the SMILES in canonical form, so
+//! regenerating a target's candidates replaces rather than duplicates them.
+
+use crate::database::{candidate_ligands_table_schema, Database};
+use crate::error::{DbError, Result};
+use crate::schema::{CandidateLigandRecord, TABLE_CANDIDATE_LIGANDS};
+use crate::schema_evolution::conform_row;
+use std::sync::Arc;
+
+use arrow_array::{Array, BooleanArray, RecordBatch, StringArray};
+use futures::StreamExt;
+use lancedb::query::{ExecutableQuery, QueryBase};
+
+/// Repository for candidate ligands.
+#[derive(Clone)]
+pub struct CandidateLigandRepository {
+    db: Arc<Database>,
+}
+
+impl CandidateLigandRepository {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Insert or replace candidates by (target_chembl_id, smiles).
+    pub async fn upsert_many(&self, candidates: &[CandidateLigandRecord]) -> Result<()> {
+        if candidates.is_empty() {
+            return Ok(());
+        }
+        let table = self.open().await?;
+        let batch = candidates_to_batch(candidates)?;
+        let schema = batch.schema();
+        let iter = arrow_array::RecordBatchIterator::new(vec![Ok(batch)], schema);
+        let mut builder = table.merge_insert(&["target_chembl_id", "smiles"]);
+        builder.when_matched_update_all(None);
+        builder.when_not_matched_insert_all();
+        builder.execute(Box::new(iter)).await?;
+        Ok(())
+    }
+
+    /// Candidates for a target: known actives first, then analogs, each
+    /// grouped by scaffold.
+    pub async fn list_by_target(
+        &self,
+        target_chembl_id: &str,
+    ) -> Result<Vec<CandidateLigandRecord>> {
+        let filter = format!(
+            "target_chembl_id = '{}'",
+            target_chembl_id.replace('\'', "''")
+        );
+        let mut rows = self.query(Some(filter)).await?;
+        rows.sort_by(|a, b| {
+            (a.novelty, &a.scaffold_id, &a.smiles).cmp(&(b.novelty, &b.scaffold_id, &b.smiles))
+        });
+        Ok(rows)
+    }
+
+    pub async fn count(&self) -> Result<u64> {
+        Ok(self.open().await?.count_rows(None).await? as u64)
+    }
+
+    async fn open(&self) -> Result<lancedb::Table> {
+        Ok(self
+            .db
+            .connection()
+            .open_table(TABLE_CANDIDATE_LIGANDS)
+            .execute()
+            .await?)
+    }
+
+    async fn query(&self, filter: Option<String>) -> Result<Vec<CandidateLigandRecord>> {
+        let table = self.open().await?;
+        let mut query = table.query();
+        if let Some(filter) = filter {
+            query = query.only_if(filter);
+        }
+        let mut stream = query.execute().await?;
+        let mut rows = Vec::new();
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            for row in 0..batch.num_rows() {
+                rows.push(batch_to_candidate(&batch, row)?);
+            }
+        }
+        Ok(rows)
+    }
+}
+
+fn candidates_to_batch(candidates: &[CandidateLigandRecord]) -> Result<RecordBatch> {
+    let strings = |f: &dyn Fn(&CandidateLigandRecord) -> String| -> Arc<dyn Array> {
+        Arc::new(StringArray::from(
+            candidates.iter().map(f).collect::<Vec<_>>(),
+        ))
+    };
+    let cols: Vec<Arc<dyn Array>> = vec![
+        strings(&|c| c.target_chembl_id.clone()),
+        strings(&|c| c.smiles.clone()),
+        strings(&|c| c.parent_chembl_id.clone()),
+        strings(&|c| c.scaffold_id.clone()),
+        Arc::new(BooleanArray::from(
+            candidates.iter().map(|c| c.novelty).collect::<Vec<_>>(),
+        )),
+        strings(&|c| c.generated_at.to_rfc3339()),
+    ];
+    Ok(RecordBatch::try_new(
+        candidate_ligands_table_schema(),
+        cols,
+    )?)
+}
+
+fn batch_to_candidate(batch: &RecordBatch, row: usize) -> Result<CandidateLigandRecord> {
+    let (batch, row) = conform_row(batch, row, &candidate_ligands_table_schema())?;
+    let batch: &RecordBatch = &batch;
+    let get_s = |col: &str| -> Result<String> {
+        let arr = batch
+            .column_by_name(col)
+            .and_then(|a| a.as_any().downcast_ref::<StringArray>())
+            .ok_or_else(|| DbError::Arrow(format!("{col} is not StringArray")))?;
+        Ok(if arr.is_null(row) {
+            String::new()
+        } else {
+            arr.value(row).to_string()
+        })
+    };
+    let novelty = batch
+        .column_by_name("novelty")
+        .and_then(|a| a.as_any().downcast_ref::<BooleanArray>())
+        .ok_or_else(|| DbError::Arrow("novelty is not BooleanArray".to_string()))?
+        .value(row);
+    let generated_at = chrono::DateTime::parse_from_rfc3339(&get_s("generated_at")?)
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+        .map_err(|e| DbError::InvalidQuery(e.to_string()))?;
+
+    Ok(CandidateLigandRecord {
+        target_chembl_id: get_s("target_chembl_id")?,
+        smiles: get_s("smiles")?,
+        parent_chembl_id: get_s("parent_chembl_id")?,
+        scaffold_id: get_s("scaffold_id")?,
+        novelty,
+        generated_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn candidate(smiles: &str, parent: &str, novelty: bool) -> CandidateLigandRecord {
+        CandidateLigandRecord {
+            target_chembl_id: "CHEMBL203".to_string(),
+            smiles: smiles.to_string(),
+            parent_chembl_id: parent.to_string(),
+            scaffold_id: "c1ccccc1".to_string(),
+            novelty,
+            generated_at: chrono::Utc.with_ymd_and_hms(2026, 6, 1, 12, 0, 0).unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn candidates_round_trip_and_replace_by_target_and_smiles() {
+        let dir =
+            std::env::temp_dir().join(format!("ferrumyx-candidates-{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        let repo = CandidateLigandRepository::new(db);
+
+        let known = candidate("CC(=O)Oc1ccccc1C(=O)O", "CHEMBL25", false);
+        let analog = candidate("CC(=O)Oc1ccc(F)cc1C(=O)O", "CHEMBL25", true);
+        repo.upsert_many(&[analog.clone(), known.clone()])
+            .await
+            .unwrap();
+
+        let reparented = candidate("CC(=O)Oc1ccc(F)cc1C(=O)O", "CHEMBL1", true);
+        let elsewhere = CandidateLigandRecord {
+            target_chembl_id: "CHEMBL2835".to_string(),
+            ..known.clone()
+        };
+        repo.upsert_many(&[reparented.clone(), elsewhere])
+            .await
+            .unwrap();
+        assert_eq!(repo.count().await.unwrap(), 3);
+        assert_eq!(
+            repo.list_by_target("CHEMBL203").await.unwrap(),
+            vec![known, reparented]
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -3,10 +3,12 @@
 //! Provides CRUD operations for text chunks with vector search.
 
 use crate::database::Database;
-use crate::error::Result;
+use crate::error::{DbError, Result};
 use crate::schema::Chunk;
 use crate::schema_arrow::{chunk_to_record, record_to_chunk};
+use arrow_array::{Array, Float32Array};
 use futures::StreamExt;
+use lancedb::index::scalar::FullTextSearchQuery;
 use lancedb::query::{ExecutableQuery, QueryBase};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -22,6 +24,31 @@ impl ChunkRepository {
         Self { db }
     }
 
+    /// Width of the stored chunk embeddings.
+    pub fn embedding_dim(&self) -> usize {
+        self.db.embedding_dim()
+    }
+
+    /// Vector search over `embedding`, refusing queries of another width.
+    async fn vector_query(&self, query_vector: &[f32]) -> Result<lancedb::query::VectorQuery> {
+        let expected = self.db.embedding_dim();
+        if query_vector.len() != expected {
+            return Err(DbError::DimensionMismatch {
+                expected,
+                actual: query_vector.len(),
+            });
+        }
+        let table = self
+            .db
+            .connection()
+            .open_table(crate::schema::TABLE_CHUNKS)
+            .execute()
+            .await?;
+        Ok(table
+            .vector_search(query_vector.to_vec())?
+            .column("embedding"))
+    }
+
     /// Insert a new chunk.
     pub async fn insert(&self, chunk: &Chunk) -> Result<()> {
         let table = self
@@ -31,7 +58,7 @@ impl ChunkRepository {
             .execute()
             .await?;
 
-        let record = chunk_to_record(chunk)?;
+        let record = chunk_to_record(chunk, self.db.embedding_dim())?;
         let schema = record.schema();
         let iter = arrow_array::RecordBatchIterator::new(vec![Ok(record)], schema);
 
@@ -52,8 +79,11 @@ impl ChunkRepository {
             .execute()
             .await?;
 
-        let records: Vec<arrow_array::RecordBatch> =
-            chunks.iter().map(chunk_to_record).collect::<Result<_>>()?;
+        let dim = self.db.embedding_dim();
+        let records: Vec<arrow_array::RecordBatch> = chunks
+            .iter()
+            .map(|chunk| chunk_to_record(chunk, dim))
+            .collect::<Result<_>>()?;
 
         let schema = records[0].schema();
         let iter = arrow_array::RecordBatchIterator::new(records.into_iter().map(Ok), schema);
@@ -161,6 +191,27 @@ impl ChunkRepository {
         Ok(())
     }
 
+    /// Delete the chunks of every paper in `paper_ids`, or with `dry_run`
+    /// only count them. Returns how many match.
+    pub async fn delete_by_paper_ids(
+        &self,
+        paper_ids: &[uuid::Uuid],
+        dry_run: bool,
+    ) -> Result<u64> {
+        let table = self
+            .db
+            .connection()
+            .open_table(crate::schema::TABLE_CHUNKS)
+            .execute()
+            .await?;
+        let ids: Vec<String> = paper_ids.iter().map(ToString::to_string).collect();
+        if dry_run {
+            crate::maintenance::count_in(&table, "paper_id", &ids).await
+        } else {
+            crate::maintenance::delete_in(&table, "paper_id", &ids).await
+        }
+    }
+
     /// Delete a chunk by ID.
     pub async fn delete(&self, id: uuid::Uuid) -> Result<()> {
         let table = self
@@ -222,17 +273,12 @@ impl ChunkRepository {
 
     /// Search for similar chunks using vector similarity.
     ///
-    /// Returns the top-k most similar chunks to the given query vector.
+    /// Returns the top-k most similar chunks to the given query vector, which
+    /// must have [`embedding_dim`](Self::embedding_dim) elements.
     pub async fn search_similar(&self, query_vector: &[f32], k: usize) -> Result<Vec<Chunk>> {
-        let table = self
-            .db
-            .connection()
-            .open_table(crate::schema::TABLE_CHUNKS)
-            .execute()
-            .await?;
-
-        let mut stream = table
-            .vector_search(query_vector.to_vec())?
+        let mut stream = self
+            .vector_query(query_vector)
+            .await?
             .limit(k)
             .execute()
             .await?;
@@ -248,24 +294,68 @@ impl ChunkRepository {
         Ok(chunks)
     }
 
-    /// Search for similar chunks with a filter.
-    ///
-    /// Returns the top-k most similar chunks that match the filter.
-    pub async fn search_similar_filtered(
+    /// Like [`search_similar`](Self::search_similar), also returning each
+    /// chunk's distance to the query (squared L2; smaller is closer).
+    pub async fn search_similar_scored(
         &self,
         query_vector: &[f32],
         k: usize,
-        filter: &str,
-    ) -> Result<Vec<Chunk>> {
+    ) -> Result<Vec<(Chunk, f32)>> {
+        let mut stream = self
+            .vector_query(query_vector)
+            .await?
+            .limit(k)
+            .execute()
+            .await?;
+
+        let mut rows = Vec::new();
+        while let Some(batch) = stream.next().await {
+            scored_rows(&batch?, "_distance", &mut rows)?;
+        }
+        Ok(rows)
+    }
+
+    /// Keyword search over chunk content using the LanceDB full-text index
+    /// (built on first use). Returns the top-k chunks with their BM25 score,
+    /// best first; exact tokens such as `G12D` or `NCT04330664` match as-is.
+    pub async fn search_keyword(&self, query: &str, k: usize) -> Result<Vec<(Chunk, f32)>> {
+        self.db.create_fts_index().await?;
         let table = self
             .db
             .connection()
             .open_table(crate::schema::TABLE_CHUNKS)
             .execute()
             .await?;
+        let fts = FullTextSearchQuery::new(query.to_string())
+            .with_column("content".to_string())
+            .map_err(|e| DbError::InvalidQuery(e.to_string()))?;
 
         let mut stream = table
-            .vector_search(query_vector.to_vec())?
+            .query()
+            .full_text_search(fts)
+            .limit(k)
+            .execute()
+            .await?;
+
+        let mut rows = Vec::new();
+        while let Some(batch) = stream.next().await {
+            scored_rows(&batch?, "_score", &mut rows)?;
+        }
+        Ok(rows)
+    }
+
+    /// Search for similar chunks with a filter.
+    ///
+    /// Returns the top-k most similar chunks that match the filter.
+    pub async fn search_similar_filtered(
+        &self,
+        query_vector: &[f32],
+        k: usize,
+        filter: &str,
+    ) -> Result<Vec<Chunk>> {
+        let mut stream = self
+            .vector_query(query_vector)
+            .await?
             .only_if(filter)
             .limit(k)
             .execute()
@@ -286,6 +376,15 @@ impl ChunkRepository {
     ///
     /// This is done by deleting the old chunk and inserting a new one with the embedding.
     pub async fn update_embedding(&self, chunk_id: uuid::Uuid, embedding: Vec<f32>) -> Result<()> {
+        // Checked before the delete so a mismatched vector cannot drop the chunk.
+        let expected = self.db.embedding_dim();
+        if embedding.len() != expected {
+            return Err(DbError::DimensionMismatch {
+                expected,
+                actual: embedding.len(),
+            });
+        }
+
         // First, get the existing chunk
         let existing = self.find_by_id(chunk_id).await?.ok_or_else(|| {
             crate::error::DbError::NotFound(format!("Chunk {} not found", chunk_id))
@@ -306,6 +405,8 @@ impl ChunkRepository {
             section: existing.section,
             page: existing.page,
             created_at: existing.created_at,
+            char_start: existing.char_start,
+            char_end: existing.char_end,
         };
 
         self.insert(&updated).await?;
@@ -346,6 +447,8 @@ impl ChunkRepository {
                     section: chunk.section.clone(),
                     page: chunk.page,
                     created_at: chunk.created_at,
+                    char_start: chunk.char_start,
+                    char_end: chunk.char_end,
                 };
                 rows.push(updated);
             }
@@ -362,8 +465,11 @@ impl ChunkRepository {
             .execute()
             .await?;
 
-        let records: Vec<arrow_array::RecordBatch> =
-            rows.iter().map(chunk_to_record).collect::<Result<_>>()?;
+        let dim = self.db.embedding_dim();
+        let records: Vec<arrow_array::RecordBatch> = rows
+            .iter()
+            .map(|chunk| chunk_to_record(chunk, dim))
+            .collect::<Result<_>>()?;
         let schema = records[0].schema();
         let iter = arrow_array::RecordBatchIterator::new(records.into_iter().map(Ok), schema);
 
@@ -374,3 +480,166 @@ impl ChunkRepository {
         Ok(rows.len())
     }
 }
+
+/// Pair each chunk in a search batch with its `score_column` value.
+fn scored_rows(
+    batch: &arrow_array::RecordBatch,
+    score_column: &str,
+    out: &mut Vec<(Chunk, f32)>,
+) -> Result<()> {
+    let scores = batch
+        .column_by_name(score_column)
+        .and_then(|c| c.as_any().downcast_ref::<Float32Array>().cloned())
+        .ok_or_else(|| DbError::Arrow(format!("search results lack {score_column}")))?;
+    for i in 0..batch.num_rows() {
+        out.push((record_to_chunk(batch, i)?, scores.value(i)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn open_db(dir: &std::path::Path, dim: usize) -> Arc<Database> {
+        let db = Database::open(dir).await.unwrap().with_embedding_dim(dim);
+        db.initialize().await.unwrap();
+        Arc::new(db)
+    }
+
+    fn embedded_chunk(paper_id: uuid::Uuid, index: i64, dim: usize, hot: usize) -> Chunk {
+        let mut chunk = Chunk::new(paper_id, index, format!("KRAS chunk {index}"));
+        let mut embedding = vec![0.0f32; dim];
+        embedding[hot] = 1.0;
+        chunk.embedding = Some(embedding);
+        chunk
+    }
+
+    #[tokio::test]
+    async fn chunks_table_uses_configured_embedding_dimension() {
+        for dim in [384, 768, 1536] {
+            let dir = std::env::temp_dir()
+                .join(format!("ferrumyx-chunks-{dim}-{}", uuid::Uuid::new_v4()));
+            let db = open_db(&dir, dim).await;
+            assert_eq!(db.recorded_embedding_dim().await.unwrap(), Some(dim));
+            let repo = ChunkRepository::new(db);
+            assert_eq!(repo.embedding_dim(), dim);
+
+            let paper_id = uuid::Uuid::new_v4();
+            repo.insert(&embedded_chunk(paper_id, 0, dim, 0))
+                .await
+                .unwrap();
+            repo.insert_batch(&[embedded_chunk(paper_id, 1, dim, 1)])
+                .await
+                .unwrap();
+
+            let mut query = vec![0.0f32; dim];
+            query[1] = 1.0;
+            let hits = repo.search_similar(&query, 1).await.unwrap();
+            assert_eq!(hits.len(), 1);
+            assert_eq!(hits[0].chunk_index, 1);
+            assert_eq!(hits[0].embedding.as_ref().map(Vec::len), Some(dim));
+
+            let wrong = dim / 2;
+            match repo.insert(&embedded_chunk(paper_id, 2, wrong, 0)).await {
+                Err(DbError::DimensionMismatch { expected, actual }) => {
+                    assert_eq!((expected, actual), (dim, wrong));
+                }
+                other => panic!("expected DimensionMismatch, got {other:?}"),
+            }
+            assert!(matches!(
+                repo.search_similar(&vec![0.0; wrong], 1).await,
+                Err(DbError::DimensionMismatch { .. })
+            ));
+            assert_eq!(repo.count().await.unwrap(), 2);
+
+            // Reopening with another configured width keeps the stored one.
+            let reopened = open_db(&dir, 1024).await;
+            assert_eq!(reopened.embedding_dim(), dim);
+
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+
+    #[tokio::test]
+    async fn keyword_search_matches_exact_identifiers() {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-chunks-{}", uuid::Uuid::new_v4()));
+        let repo = ChunkRepository::new(open_db(&dir, 768).await);
+        let paper_id = uuid::Uuid::new_v4();
+        let texts = [
+            "KRAS G12D drives pancreatic ductal adenocarcinoma.",
+            "KRAS G12C tumours respond to sotorasib.",
+            "Trial NCT04330664 enrolled adagrasib patients.",
+        ];
+        let chunks: Vec<Chunk> = texts
+            .iter()
+            .enumerate()
+            .map(|(i, text)| Chunk::new(paper_id, i as i64, text.to_string()))
+            .collect();
+        repo.insert_batch(&chunks).await.unwrap();
+
+        let hits = repo.search_keyword("NCT04330664", 5).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0.id, chunks[2].id);
+        assert!(hits[0].1 > 0.0);
+
+        let hits = repo.search_keyword("KRAS G12D", 5).await.unwrap();
+        assert_eq!(hits[0].0.id, chunks[0].id);
+        assert_eq!(hits.len(), 2);
+
+        // Chunks written after the index was built are still found.
+        let late = Chunk::new(paper_id, 3, "MRTX1133 targets G12D.".to_string());
+        repo.insert(&late).await.unwrap();
+        let hits = repo.search_keyword("MRTX1133", 5).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0.id, late.id);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn migrate_embedding_dim_clears_vectors_for_reembedding() {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-chunks-{}", uuid::Uuid::new_v4()));
+        let db = open_db(&dir, 768).await;
+        let repo = ChunkRepository::new(db.clone());
+        let mut chunk = embedded_chunk(uuid::Uuid::new_v4(), 0, 768, 0);
+        chunk.section = Some("Results".to_string());
+        chunk.char_start = Some(10);
+        repo.insert(&chunk).await.unwrap();
+
+        assert_eq!(db.migrate_embedding_dim(1536).await.unwrap(), 1);
+        assert_eq!(repo.embedding_dim(), 1536);
+        assert_eq!(db.recorded_embedding_dim().await.unwrap(), Some(1536));
+
+        let stored = repo.find_by_id(chunk.id).await.unwrap().unwrap();
+        assert_eq!(stored.embedding, None);
+        assert_eq!(stored.content, chunk.content);
+        assert_eq!(stored.section, chunk.section);
+        assert_eq!(stored.char_start, Some(10));
+
+        // A stale 768-dim vector is refused without dropping the chunk.
+        assert!(matches!(
+            repo.update_embedding(chunk.id, vec![0.5; 768]).await,
+            Err(DbError::DimensionMismatch {
+                expected: 1536,
+                actual: 768
+            })
+        ));
+        assert!(repo.find_by_id(chunk.id).await.unwrap().is_some());
+
+        let mut vector = vec![0.0f32; 1536];
+        vector[3] = 1.0;
+        repo.update_embeddings_batch(&[(chunk.id, vector.clone())])
+            .await
+            .unwrap();
+        let hits = repo.search_similar(&vector, 1).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].id, chunk.id);
+
+        // The schema check on reopen accepts the migrated width.
+        let reopened = open_db(&dir, 768).await;
+        assert_eq!(reopened.embedding_dim(), 1536);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
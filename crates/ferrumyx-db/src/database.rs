@@ -2,24 +2,36 @@
 //!
 //! Provides a unified interface for LanceDB operations.
 
-use crate::error::Result;
+use crate::error::{DbError, Result};
+use crate::maintenance::IndexStatus;
 use crate::schema;
-use arrow_array::RecordBatchIterator;
+use crate::schema_arrow::chunk_embedding_dim;
+use arrow_array::{
+    new_null_array, Array, ArrayRef, Int64Array, RecordBatch, RecordBatchIterator, StringArray,
+};
 use arrow_schema::{DataType, Field, Fields, Schema};
+use futures::StreamExt;
 use lancedb::connection::Connection;
+use lancedb::database::CreateTableMode;
+use lancedb::query::{ExecutableQuery, QueryBase};
 use std::collections::HashSet;
 use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use tracing::{info, warn};
 
 /// Main database handle.
 #[derive(Clone)]
 pub struct Database {
     conn: Connection,
     path: String,
+    /// Width of `chunks.embedding`, shared by clones so a dimension
+    /// migration is seen by every handle.
+    embedding_dim: Arc<AtomicUsize>,
 }
 
 impl Database {
-    async fn table_names_set(&self) -> Result<HashSet<String>> {
+    pub(crate) async fn table_names_set(&self) -> Result<HashSet<String>> {
         Ok(self
             .conn
             .table_names()
@@ -43,9 +55,25 @@ impl Database {
         Ok(Self {
             conn,
             path: path_str,
+            embedding_dim: Arc::new(AtomicUsize::new(schema::EMBEDDING_DIM)),
         })
     }
 
+    /// Set the embedding dimension used when [`initialize`](Self::initialize)
+    /// creates the chunks table, normally `EmbeddingConfig.embedding_dim`.
+    /// An existing chunks table keeps the dimension it was created with; use
+    /// [`migrate_embedding_dim`](Self::migrate_embedding_dim) to change it.
+    pub fn with_embedding_dim(self, dim: usize) -> Self {
+        self.embedding_dim.store(dim, Ordering::Relaxed);
+        self
+    }
+
+    /// Width of the vectors in `chunks.embedding`. Reflects the stored table
+    /// once [`initialize`](Self::initialize) has run.
+    pub fn embedding_dim(&self) -> usize {
+        self.embedding_dim.load(Ordering::Relaxed)
+    }
+
     /// Get the underlying connection.
     pub fn connection(&self) -> &Connection {
         &self.conn
@@ -58,7 +86,10 @@ impl Database {
 
     /// Initialize all tables with schemas.
     ///
-    /// This creates the tables if they don't exist.
+    /// This creates the tables if they don't exist, then applies pending
+    /// schema evolution to existing ones (see [`crate::schema_evolution`]).
+    /// Fails with `DbError::MigrationRequired` if an on-disk table cannot be
+    /// migrated automatically.
     /// LanceDB requires initial data to create a table with a schema.
     pub async fn initialize(&self) -> Result<()> {
         let mut existing_tables = self.table_names_set().await?;
@@ -79,6 +110,36 @@ impl Database {
         create_if_missing!(schema::TABLE_KG_CONFLICTS, create_kg_conflicts_table);
         create_if_missing!(schema::TABLE_TARGET_SCORES, create_target_scores_table);
         create_if_missing!(schema::TABLE_INGESTION_AUDIT, create_ingestion_audit_table);
+        create_if_missing!(
+            schema::TABLE_INGESTION_WATERMARKS,
+            create_ingestion_watermarks_table
+        );
+        create_if_missing!(
+            schema::TABLE_INGESTION_JOB_RUNS,
+            create_ingestion_job_runs_table
+        );
+        create_if_missing!(schema::TABLE_INGESTION_JOBS, create_ingestion_jobs_table);
+        create_if_missing!(schema::TABLE_PAPER_CITATIONS, create_paper_citations_table);
+        create_if_missing!(schema::TABLE_LLM_AUDIT, create_llm_audit_table);
+        create_if_missing!(schema::TABLE_LLM_USAGE, create_llm_usage_table);
+        create_if_missing!(
+            schema::TABLE_PAPER_TOMBSTONES,
+            create_paper_tombstones_table
+        );
+        create_if_missing!(schema::TABLE_METRIC_ROLLUPS, create_metric_rollups_table);
+        create_if_missing!(schema::TABLE_FAILED_ITEMS, create_failed_items_table);
+        create_if_missing!(schema::TABLE_TRIALS, create_trials_table);
+        create_if_missing!(schema::TABLE_DOCKING_RESULTS, create_docking_results_table);
+        create_if_missing!(
+            schema::TABLE_CANDIDATE_LIGANDS,
+            create_candidate_ligands_table
+        );
+        create_if_missing!(
+            schema::TABLE_MOLECULE_CANDIDATES,
+            create_molecule_candidates_table
+        );
+        create_if_missing!(schema::TABLE_SCHEMA_META, create_schema_meta_table);
+        create_if_missing!(schema::TABLE_EMBEDDING_META, create_embedding_meta_table);
 
         create_if_missing!(schema::TABLE_ENT_GENES, create_ent_genes_table);
         create_if_missing!(schema::TABLE_ENT_MUTATIONS, create_ent_mutations_table);
@@ -125,285 +186,379 @@ impl Database {
             schema::TABLE_ENT_REACTOME_GENES,
             create_ent_reactome_genes_table
         );
+        create_if_missing!(
+            schema::TABLE_ENT_STRUCTURAL_ASSESSMENTS,
+            create_ent_structural_assessments_table
+        );
         create_if_missing!(
             schema::TABLE_ENT_PROVIDER_REFRESH_RUNS,
             create_ent_provider_refresh_runs_table
         );
 
+        self.sync_embedding_dim().await?;
+        crate::schema_evolution::apply_migrations(self).await?;
+
         Ok(())
     }
 
-    /// Check if a table exists.
-    pub async fn table_exists(&self, name: &str) -> Result<bool> {
-        Ok(self.table_names_set().await?.contains(name))
+    /// Adopt the dimension of the on-disk chunks table and record it in
+    /// `embedding_meta`.
+    async fn sync_embedding_dim(&self) -> Result<()> {
+        let configured = self.embedding_dim();
+        let stored = self.on_disk_embedding_dim().await?.unwrap_or(configured);
+        if stored != configured {
+            warn!(
+                configured,
+                stored,
+                "Chunks table keeps its stored embedding dimension; run `ferrumyx db migrate-embedding-dim` to change it"
+            );
+        }
+        self.embedding_dim.store(stored, Ordering::Relaxed);
+        if self.recorded_embedding_dim().await? != Some(stored) {
+            self.record_embedding_dim(stored).await?;
+        }
+        Ok(())
     }
 
-    /// Create the papers table with an empty schema.
-    async fn create_papers_table(&self) -> Result<()> {
-        let fields: Fields = vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("doi", DataType::Utf8, true),
-            Field::new("pmid", DataType::Utf8, true),
-            Field::new("title", DataType::Utf8, false),
-            Field::new("abstract_text", DataType::Utf8, true),
-            Field::new("full_text", DataType::Utf8, true),
-            Field::new("raw_json", DataType::Utf8, true),
-            Field::new("source", DataType::Utf8, false),
-            Field::new("source_id", DataType::Utf8, true),
-            Field::new("published_at", DataType::Utf8, true),
-            Field::new("authors", DataType::Utf8, true),
-            Field::new("journal", DataType::Utf8, true),
-            Field::new("volume", DataType::Utf8, true),
-            Field::new("issue", DataType::Utf8, true),
-            Field::new("pages", DataType::Utf8, true),
-            Field::new("parse_status", DataType::Utf8, false),
-            Field::new("open_access", DataType::Boolean, false),
-            Field::new("retrieval_tier", DataType::Int32, true),
-            Field::new("ingested_at", DataType::Utf8, false),
-            Field::new("abstract_simhash", DataType::Int64, true),
-            Field::new("published_version_doi", DataType::Utf8, true),
-        ]
-        .into();
-
-        let schema = Arc::new(Schema::new(fields));
-
-        // Create empty iterator with schema
-        let empty_iter = RecordBatchIterator::new(vec![], schema.clone());
+    /// Embedding width of the chunks table schema, if the table exists.
+    pub(crate) async fn on_disk_embedding_dim(&self) -> Result<Option<usize>> {
+        if !self.table_exists(schema::TABLE_CHUNKS).await? {
+            return Ok(None);
+        }
+        let table = self.conn.open_table(schema::TABLE_CHUNKS).execute().await?;
+        Ok(chunk_embedding_dim(&table.schema().await?))
+    }
 
-        self.conn
-            .create_table(schema::TABLE_PAPERS, empty_iter)
+    /// Dimension recorded for `chunks.embedding` in `embedding_meta`.
+    pub async fn recorded_embedding_dim(&self) -> Result<Option<usize>> {
+        if !self.table_exists(schema::TABLE_EMBEDDING_META).await? {
+            return Ok(None);
+        }
+        let table = self
+            .conn
+            .open_table(schema::TABLE_EMBEDDING_META)
             .execute()
             .await?;
+        let mut stream = table
+            .query()
+            .only_if(&format!(
+                "table_name = '{}' AND column_name = 'embedding'",
+                schema::TABLE_CHUNKS
+            ))
+            .execute()
+            .await?;
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            let Some(dims) = batch
+                .column_by_name("dim")
+                .and_then(|c| c.as_any().downcast_ref::<Int64Array>().cloned())
+            else {
+                continue;
+            };
+            if let Some(dim) = dims.iter().flatten().next() {
+                return Ok(usize::try_from(dim).ok());
+            }
+        }
+        Ok(None)
+    }
 
+    async fn record_embedding_dim(&self, dim: usize) -> Result<()> {
+        let table = self
+            .conn
+            .open_table(schema::TABLE_EMBEDDING_META)
+            .execute()
+            .await?;
+        let schema = table.schema().await?;
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec![schema::TABLE_CHUNKS])) as ArrayRef,
+                Arc::new(StringArray::from(vec!["embedding"])),
+                Arc::new(Int64Array::from(vec![dim as i64])),
+                Arc::new(StringArray::from(vec![chrono::Utc::now().to_rfc3339()])),
+            ],
+        )?;
+        table
+            .delete(&format!(
+                "table_name = '{}' AND column_name = 'embedding'",
+                schema::TABLE_CHUNKS
+            ))
+            .await?;
+        table
+            .add(RecordBatchIterator::new(vec![Ok(batch)], schema))
+            .execute()
+            .await?;
         Ok(())
     }
 
-    /// Create the chunks table with embedding column.
-    async fn create_chunks_table(&self) -> Result<()> {
-        let embedding_field = Field::new(
-            "embedding",
-            DataType::FixedSizeList(
-                Arc::new(Field::new("item", DataType::Float32, false)),
-                schema::EMBEDDING_DIM as i32,
-            ),
-            true,
-        );
-
-        let fields: Fields = vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("paper_id", DataType::Utf8, false),
-            Field::new("chunk_index", DataType::Int64, false),
-            Field::new("token_count", DataType::Int32, false),
-            Field::new("content", DataType::Utf8, false),
-            Field::new("section", DataType::Utf8, true),
-            Field::new("page", DataType::Int64, true),
-            Field::new("created_at", DataType::Utf8, false),
-            embedding_field,
-            Field::new(
-                "embedding_large",
-                DataType::FixedSizeList(
-                    Arc::new(Field::new("item", DataType::Float32, false)),
-                    1024 as i32,
-                ),
-                true,
-            ),
-        ]
-        .into();
+    /// Re-create the chunks table with `dim`-wide embedding vectors.
+    ///
+    /// Every chunk is kept with its `embedding` cleared, ready to be
+    /// re-embedded by a model of the new width (see
+    /// `ferrumyx_ingestion::embedding::migrate_embedding_dim`). Reads the
+    /// whole table into memory and drops its vector index; returns the number
+    /// of chunks carried over.
+    pub async fn migrate_embedding_dim(&self, dim: usize) -> Result<u64> {
+        if dim == 0 {
+            return Err(DbError::InvalidQuery(
+                "embedding dimension must be positive".to_string(),
+            ));
+        }
+        let table = self.conn.open_table(schema::TABLE_CHUNKS).execute().await?;
+        let on_disk = table.schema().await?;
 
-        let schema = Arc::new(Schema::new(fields));
-        let empty_iter = RecordBatchIterator::new(vec![], schema);
+        // Keep on-disk physical types so existing columns copy over as-is.
+        let fields: Vec<Field> = chunks_table_schema(dim)
+            .fields()
+            .iter()
+            .map(|f| match on_disk.field_with_name(f.name()) {
+                Ok(d) if f.name() != "embedding" => f
+                    .as_ref()
+                    .clone()
+                    .with_data_type(d.data_type().clone())
+                    .with_nullable(f.is_nullable() || d.is_nullable()),
+                _ => f.as_ref().clone().with_nullable(true),
+            })
+            .collect();
+        let target = Arc::new(Schema::new(fields));
+
+        let mut batches = Vec::new();
+        let mut stream = table.query().execute().await?;
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            let columns = target
+                .fields()
+                .iter()
+                .map(|f| match batch.column_by_name(f.name()) {
+                    Some(column) if f.name() != "embedding" => column.clone(),
+                    _ => new_null_array(f.data_type(), batch.num_rows()),
+                })
+                .collect();
+            batches.push(RecordBatch::try_new(target.clone(), columns)?);
+        }
+        let rows: usize = batches.iter().map(|b| b.num_rows()).sum();
 
+        let reader = RecordBatchIterator::new(
+            batches.into_iter().map(Ok::<_, arrow_schema::ArrowError>),
+            target,
+        );
         self.conn
-            .create_table(schema::TABLE_CHUNKS, empty_iter)
+            .create_table(schema::TABLE_CHUNKS, reader)
+            .mode(CreateTableMode::Overwrite)
             .execute()
             .await?;
+        self.embedding_dim.store(dim, Ordering::Relaxed);
+        self.record_embedding_dim(dim).await?;
+        info!(
+            rows,
+            dim, "Re-created chunks table for new embedding dimension"
+        );
+        Ok(rows as u64)
+    }
 
-        Ok(())
+    /// Check if a table exists.
+    pub async fn table_exists(&self, name: &str) -> Result<bool> {
+        Ok(self.table_names_set().await?.contains(name))
     }
 
-    /// Create the entities table.
-    async fn create_entities_table(&self) -> Result<()> {
-        let fields: Fields = vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("external_id", DataType::Utf8, false),
-            Field::new("name", DataType::Utf8, false),
-            Field::new("canonical_name", DataType::Utf8, true),
-            Field::new("entity_type", DataType::Utf8, false),
-            Field::new("synonyms", DataType::Utf8, true),
-            Field::new("description", DataType::Utf8, true),
-            Field::new("source_db", DataType::Utf8, false),
-            Field::new("metadata", DataType::Utf8, true),
-            Field::new("created_at", DataType::Utf8, false),
-            Field::new("updated_at", DataType::Utf8, false),
-        ]
-        .into();
-
-        let schema = Arc::new(Schema::new(fields));
+    /// Create an empty table with the given Arrow schema.
+    async fn create_empty_table(&self, table: &str, schema: Arc<Schema>) -> Result<()> {
         let empty_iter = RecordBatchIterator::new(vec![], schema);
+        self.conn.create_table(table, empty_iter).execute().await?;
+        Ok(())
+    }
 
-        self.conn
-            .create_table(schema::TABLE_ENTITIES, empty_iter)
-            .execute()
-            .await?;
+    /// Create the papers table with an empty schema.
+    async fn create_papers_table(&self) -> Result<()> {
+        self.create_empty_table(schema::TABLE_PAPERS, papers_table_schema())
+            .await
+    }
 
-        Ok(())
+    /// Create the chunks table with embedding column.
+    async fn create_chunks_table(&self) -> Result<()> {
+        self.create_empty_table(
+            schema::TABLE_CHUNKS,
+            chunks_table_schema(self.embedding_dim()),
+        )
+        .await
+    }
+
+    /// Create the entities table.
+    async fn create_entities_table(&self) -> Result<()> {
+        self.create_empty_table(schema::TABLE_ENTITIES, entities_table_schema())
+            .await
     }
 
     /// Create the entity_mentions table.
     async fn create_entity_mentions_table(&self) -> Result<()> {
-        let fields: Fields = vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("entity_id", DataType::Utf8, false),
-            Field::new("chunk_id", DataType::Utf8, false),
-            Field::new("paper_id", DataType::Utf8, false),
-            Field::new("start_offset", DataType::Int64, false),
-            Field::new("end_offset", DataType::Int64, false),
-            Field::new("text", DataType::Utf8, false),
-            Field::new("confidence", DataType::Float32, true),
-            Field::new("context", DataType::Utf8, true),
-            Field::new("created_at", DataType::Utf8, false),
-        ]
-        .into();
-
-        let schema = Arc::new(Schema::new(fields));
-        let empty_iter = RecordBatchIterator::new(vec![], schema);
-
-        self.conn
-            .create_table(schema::TABLE_ENTITY_MENTIONS, empty_iter)
-            .execute()
-            .await?;
-
-        Ok(())
+        self.create_empty_table(
+            schema::TABLE_ENTITY_MENTIONS,
+            entity_mentions_table_schema(),
+        )
+        .await
     }
 
     /// Create the kg_facts table.
     async fn create_kg_facts_table(&self) -> Result<()> {
-        let fields: Fields = vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("paper_id", DataType::Utf8, false),
-            Field::new("subject_id", DataType::Utf8, false),
-            Field::new("subject_name", DataType::Utf8, false),
-            Field::new("predicate", DataType::Utf8, false),
-            Field::new("object_id", DataType::Utf8, false),
-            Field::new("object_name", DataType::Utf8, false),
-            Field::new("confidence", DataType::Float32, false),
-            Field::new("evidence", DataType::Utf8, true),
-            Field::new("evidence_type", DataType::Utf8, false),
-            Field::new("study_type", DataType::Utf8, true),
-            Field::new("sample_size", DataType::Int32, true),
-            Field::new("valid_from", DataType::Utf8, false),
-            Field::new("valid_until", DataType::Utf8, true),
-            Field::new("created_at", DataType::Utf8, false),
-        ]
-        .into();
-
-        let schema = Arc::new(Schema::new(fields));
-        let empty_iter = RecordBatchIterator::new(vec![], schema);
-
-        self.conn
-            .create_table(schema::TABLE_KG_FACTS, empty_iter)
-            .execute()
-            .await?;
-
-        Ok(())
+        self.create_empty_table(schema::TABLE_KG_FACTS, kg_facts_table_schema())
+            .await
     }
 
     /// Create the kg_conflicts table.
     async fn create_kg_conflicts_table(&self) -> Result<()> {
-        let fields: Fields = vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("fact_a_id", DataType::Utf8, false),
-            Field::new("fact_b_id", DataType::Utf8, false),
-            Field::new("conflict_type", DataType::Utf8, false),
-            Field::new("net_confidence", DataType::Float32, false),
-            Field::new("resolution", DataType::Utf8, false),
-            Field::new("detected_at", DataType::Utf8, false),
-        ]
-        .into();
-
-        let schema = Arc::new(Schema::new(fields));
-        let empty_iter = RecordBatchIterator::new(vec![], schema);
-
-        self.conn
-            .create_table(schema::TABLE_KG_CONFLICTS, empty_iter)
-            .execute()
-            .await?;
-
-        Ok(())
+        self.create_empty_table(schema::TABLE_KG_CONFLICTS, kg_conflicts_table_schema())
+            .await
     }
 
     /// Create the target_scores table.
     async fn create_target_scores_table(&self) -> Result<()> {
-        let fields: Fields = vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("gene_id", DataType::Utf8, false),
-            Field::new("cancer_id", DataType::Utf8, false),
-            Field::new("score_version", DataType::Int64, false),
-            Field::new("is_current", DataType::Boolean, false),
-            Field::new("composite_score", DataType::Float64, false),
-            Field::new("confidence_adjusted_score", DataType::Float64, false),
-            Field::new("penalty_score", DataType::Float64, false),
-            Field::new("shortlist_tier", DataType::Utf8, false),
-            Field::new("components_raw", DataType::Utf8, false),
-            Field::new("components_normed", DataType::Utf8, false),
-            Field::new("created_at", DataType::Utf8, false),
-        ]
-        .into();
-
-        let schema = Arc::new(Schema::new(fields));
-        let empty_iter = RecordBatchIterator::new(vec![], schema);
-
-        self.conn
-            .create_table(schema::TABLE_TARGET_SCORES, empty_iter)
-            .execute()
-            .await?;
-
-        Ok(())
+        self.create_empty_table(schema::TABLE_TARGET_SCORES, target_scores_table_schema())
+            .await
     }
 
     /// Create the ingestion_audit table.
     async fn create_ingestion_audit_table(&self) -> Result<()> {
-        let fields: Fields = vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("job_id", DataType::Utf8, true),
-            Field::new("paper_id", DataType::Utf8, true),
-            Field::new("action", DataType::Utf8, false),
-            Field::new("detail", DataType::Utf8, false),
-            Field::new("created_at", DataType::Utf8, false),
-        ]
-        .into();
-
-        let schema = Arc::new(Schema::new(fields));
-        let empty_iter = RecordBatchIterator::new(vec![], schema);
+        self.create_empty_table(
+            schema::TABLE_INGESTION_AUDIT,
+            ingestion_audit_table_schema(),
+        )
+        .await
+    }
 
-        self.conn
-            .create_table(schema::TABLE_INGESTION_AUDIT, empty_iter)
-            .execute()
-            .await?;
+    /// Create the ingestion_watermarks table.
+    async fn create_ingestion_watermarks_table(&self) -> Result<()> {
+        self.create_empty_table(
+            schema::TABLE_INGESTION_WATERMARKS,
+            ingestion_watermarks_table_schema(),
+        )
+        .await
+    }
 
-        Ok(())
+    /// Create the ingestion_job_runs table.
+    async fn create_ingestion_job_runs_table(&self) -> Result<()> {
+        self.create_empty_table(
+            schema::TABLE_INGESTION_JOB_RUNS,
+            ingestion_job_runs_table_schema(),
+        )
+        .await
+    }
+
+    /// Create the ingestion_jobs table.
+    async fn create_ingestion_jobs_table(&self) -> Result<()> {
+        self.create_empty_table(schema::TABLE_INGESTION_JOBS, ingestion_jobs_table_schema())
+            .await
+    }
+
+    /// Create the paper_citations table.
+    async fn create_paper_citations_table(&self) -> Result<()> {
+        self.create_empty_table(
+            schema::TABLE_PAPER_CITATIONS,
+            paper_citations_table_schema(),
+        )
+        .await
+    }
+
+    /// Create the llm_audit table.
+    async fn create_llm_audit_table(&self) -> Result<()> {
+        self.create_empty_table(schema::TABLE_LLM_AUDIT, llm_audit_table_schema())
+            .await
+    }
+
+    /// Create the llm_usage table.
+    async fn create_llm_usage_table(&self) -> Result<()> {
+        self.create_empty_table(schema::TABLE_LLM_USAGE, llm_usage_table_schema())
+            .await
+    }
+
+    /// Create the paper_tombstones table.
+    async fn create_paper_tombstones_table(&self) -> Result<()> {
+        self.create_empty_table(
+            schema::TABLE_PAPER_TOMBSTONES,
+            paper_tombstones_table_schema(),
+        )
+        .await
     }
 
-    /// Create a vector index on the chunks table for embedding search.
-    pub async fn create_vector_index(&self) -> Result<()> {
+    /// Create the metric_rollups table.
+    async fn create_metric_rollups_table(&self) -> Result<()> {
+        self.create_empty_table(schema::TABLE_METRIC_ROLLUPS, metric_rollups_table_schema())
+            .await
+    }
+
+    /// Create the failed_items table.
+    async fn create_failed_items_table(&self) -> Result<()> {
+        self.create_empty_table(schema::TABLE_FAILED_ITEMS, failed_items_table_schema())
+            .await
+    }
+
+    /// Create the trials table.
+    async fn create_trials_table(&self) -> Result<()> {
+        self.create_empty_table(schema::TABLE_TRIALS, trials_table_schema())
+            .await
+    }
+
+    /// Create the docking_results table.
+    async fn create_docking_results_table(&self) -> Result<()> {
+        self.create_empty_table(
+            schema::TABLE_DOCKING_RESULTS,
+            docking_results_table_schema(),
+        )
+        .await
+    }
+
+    /// Create the candidate_ligands table.
+    async fn create_candidate_ligands_table(&self) -> Result<()> {
+        self.create_empty_table(
+            schema::TABLE_CANDIDATE_LIGANDS,
+            candidate_ligands_table_schema(),
+        )
+        .await
+    }
+
+    /// Create the molecule_candidates table.
+    async fn create_molecule_candidates_table(&self) -> Result<()> {
+        self.create_empty_table(
+            schema::TABLE_MOLECULE_CANDIDATES,
+            molecule_candidates_table_schema(),
+        )
+        .await
+    }
+
+    /// Create the schema_meta table (applied schema version per table).
+    async fn create_schema_meta_table(&self) -> Result<()> {
+        self.create_empty_table(schema::TABLE_SCHEMA_META, schema_meta_table_schema())
+            .await
+    }
+
+    /// Create the embedding_meta table (stored vector width per column).
+    async fn create_embedding_meta_table(&self) -> Result<()> {
+        self.create_empty_table(schema::TABLE_EMBEDDING_META, embedding_meta_table_schema())
+            .await
+    }
+
+    /// Create the full-text (BM25) index on chunk content for keyword search.
+    ///
+    /// Chunks written after the index was built are still searched (unindexed
+    /// rows are scanned) until [`optimize`](Self::optimize) folds them in.
+    pub async fn create_fts_index(&self) -> Result<()> {
         let table = self.conn.open_table(schema::TABLE_CHUNKS).execute().await?;
 
         if table
             .list_indices()
             .await?
             .iter()
-            .any(|index| index.columns.len() == 1 && index.columns[0] == "embedding")
+            .any(|index| index.columns.len() == 1 && index.columns[0] == "content")
         {
             return Ok(());
         }
 
         match table
-            .create_index(&["embedding"], lancedb::index::Index::Auto)
+            .create_index(&["content"], lancedb::index::Index::FTS(Default::default()))
             .execute()
             .await
         {
             Ok(()) => {}
-            Err(err) if is_existing_vector_index_error(&err) => {}
+            Err(err) if is_existing_index_error(&err) => {}
             Err(err) => return Err(err.into()),
         }
 
@@ -449,6 +604,14 @@ impl Database {
             .count_rows_if_present(&existing_tables, schema::TABLE_INGESTION_AUDIT)
             .await?;
 
+        let (mut fragments, mut small_fragments) = (0, 0);
+        for name in &existing_tables {
+            let table = self.conn.open_table(name).execute().await?;
+            let stats = table.stats().await?.fragment_stats;
+            fragments += stats.num_fragments as u64;
+            small_fragments += stats.num_small_fragments as u64;
+        }
+
         Ok(DatabaseStats {
             papers: papers_count,
             chunks: chunks_count,
@@ -457,6 +620,9 @@ impl Database {
             kg_facts: facts_count,
             target_scores: target_scores_count,
             ingestion_audit: ingestion_audit_count,
+            fragments,
+            small_fragments,
+            indices: self.index_status().await?,
         })
     }
 }
@@ -476,7 +642,7 @@ impl Database {
     }
 }
 
-fn is_existing_vector_index_error(err: &lancedb::Error) -> bool {
+pub(crate) fn is_existing_index_error(err: &lancedb::Error) -> bool {
     let message = err.to_string().to_ascii_lowercase();
     message.contains("already exists")
         || message.contains("already indexed")
@@ -493,6 +659,11 @@ pub struct DatabaseStats {
     pub kg_facts: u64,
     pub target_scores: u64,
     pub ingestion_audit: u64,
+    /// Data fragments across all tables.
+    pub fragments: u64,
+    /// Fragments small enough for [`compact_all`](Database::compact_all) to merge.
+    pub small_fragments: u64,
+    pub indices: Vec<IndexStatus>,
 }
 
 // =============================================================================
@@ -500,359 +671,951 @@ pub struct DatabaseStats {
 // =============================================================================
 impl Database {
     pub async fn create_ent_genes_table(&self) -> Result<()> {
-        let fields: Fields = vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("hgnc_id", DataType::Utf8, true),
-            Field::new("symbol", DataType::Utf8, false),
-            Field::new("name", DataType::Utf8, true),
-            Field::new("uniprot_id", DataType::Utf8, true),
-            Field::new("ensembl_id", DataType::Utf8, true),
-            Field::new("entrez_id", DataType::Utf8, true),
-            Field::new("gene_biotype", DataType::Utf8, true),
-            Field::new("chromosome", DataType::Utf8, true),
-            Field::new("strand", DataType::Int16, true),
-            Field::new("aliases", DataType::Utf8, true),
-            Field::new("oncogene_flag", DataType::Boolean, false),
-            Field::new("tsg_flag", DataType::Boolean, false),
-            Field::new("created_at", DataType::Utf8, false),
-        ]
-        .into();
-        let schema = Arc::new(Schema::new(fields));
-        let empty_iter = RecordBatchIterator::new(vec![], schema);
-        self.conn
-            .create_table(schema::TABLE_ENT_GENES, empty_iter)
-            .execute()
-            .await?;
-        Ok(())
+        self.create_empty_table(schema::TABLE_ENT_GENES, ent_genes_table_schema())
+            .await
     }
 
     pub async fn create_ent_mutations_table(&self) -> Result<()> {
-        let fields: Fields = vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("gene_id", DataType::Utf8, false),
-            Field::new("hgvs_p", DataType::Utf8, true),
-            Field::new("hgvs_c", DataType::Utf8, true),
-            Field::new("rs_id", DataType::Utf8, true),
-            Field::new("aa_ref", DataType::Utf8, true),
-            Field::new("aa_alt", DataType::Utf8, true),
-            Field::new("aa_position", DataType::Int32, true),
-            Field::new("oncogenicity", DataType::Utf8, true),
-            Field::new("hotspot_flag", DataType::Boolean, false),
-            Field::new("vaf_context", DataType::Utf8, true),
-            Field::new("created_at", DataType::Utf8, false),
-        ]
-        .into();
-        let schema = Arc::new(Schema::new(fields));
-        let empty_iter = RecordBatchIterator::new(vec![], schema);
-        self.conn
-            .create_table(schema::TABLE_ENT_MUTATIONS, empty_iter)
-            .execute()
-            .await?;
-        Ok(())
+        self.create_empty_table(schema::TABLE_ENT_MUTATIONS, ent_mutations_table_schema())
+            .await
     }
 
     pub async fn create_ent_cancer_types_table(&self) -> Result<()> {
-        let fields: Fields = vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("oncotree_code", DataType::Utf8, true),
-            Field::new("oncotree_name", DataType::Utf8, true),
-            Field::new("icd_o3_code", DataType::Utf8, true),
-            Field::new("tissue", DataType::Utf8, true),
-            Field::new("parent_code", DataType::Utf8, true),
-            Field::new("level", DataType::Int32, true),
-            Field::new("created_at", DataType::Utf8, false),
-        ]
-        .into();
-        let schema = Arc::new(Schema::new(fields));
-        let empty_iter = RecordBatchIterator::new(vec![], schema);
-        self.conn
-            .create_table(schema::TABLE_ENT_CANCER_TYPES, empty_iter)
-            .execute()
-            .await?;
-        Ok(())
+        self.create_empty_table(
+            schema::TABLE_ENT_CANCER_TYPES,
+            ent_cancer_types_table_schema(),
+        )
+        .await
     }
 
     pub async fn create_ent_pathways_table(&self) -> Result<()> {
-        let fields: Fields = vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("kegg_id", DataType::Utf8, true),
-            Field::new("reactome_id", DataType::Utf8, true),
-            Field::new("go_term", DataType::Utf8, true),
-            Field::new("name", DataType::Utf8, false),
-            Field::new("gene_members", DataType::Utf8, true),
-            Field::new("source", DataType::Utf8, true),
-            Field::new("created_at", DataType::Utf8, false),
-        ]
-        .into();
-        let schema = Arc::new(Schema::new(fields));
-        let empty_iter = RecordBatchIterator::new(vec![], schema);
-        self.conn
-            .create_table(schema::TABLE_ENT_PATHWAYS, empty_iter)
-            .execute()
-            .await?;
-        Ok(())
+        self.create_empty_table(schema::TABLE_ENT_PATHWAYS, ent_pathways_table_schema())
+            .await
     }
 
     pub async fn create_ent_clinical_evidence_table(&self) -> Result<()> {
-        let fields: Fields = vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("nct_id", DataType::Utf8, true),
-            Field::new("pmid", DataType::Utf8, true),
-            Field::new("doi", DataType::Utf8, true),
-            Field::new("phase", DataType::Utf8, true),
-            Field::new("intervention", DataType::Utf8, true),
-            Field::new("target_gene_id", DataType::Utf8, false),
-            Field::new("cancer_id", DataType::Utf8, false),
-            Field::new("primary_endpoint", DataType::Utf8, true),
-            Field::new("outcome", DataType::Utf8, true),
-            Field::new("evidence_grade", DataType::Utf8, true),
-            Field::new("created_at", DataType::Utf8, false),
-        ]
-        .into();
-        let schema = Arc::new(Schema::new(fields));
-        let empty_iter = RecordBatchIterator::new(vec![], schema);
-        self.conn
-            .create_table(schema::TABLE_ENT_CLINICAL_EVIDENCE, empty_iter)
-            .execute()
-            .await?;
-        Ok(())
+        self.create_empty_table(
+            schema::TABLE_ENT_CLINICAL_EVIDENCE,
+            ent_clinical_evidence_table_schema(),
+        )
+        .await
     }
 
     pub async fn create_ent_compounds_table(&self) -> Result<()> {
-        let fields: Fields = vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("chembl_id", DataType::Utf8, true),
-            Field::new("name", DataType::Utf8, true),
-            Field::new("smiles", DataType::Utf8, true),
-            Field::new("inchi_key", DataType::Utf8, true),
-            Field::new("moa", DataType::Utf8, true),
-            Field::new("patent_status", DataType::Utf8, true),
-            Field::new("max_phase", DataType::Int32, true),
-            Field::new("target_gene_ids", DataType::Utf8, true),
-            Field::new("created_at", DataType::Utf8, false),
-        ]
-        .into();
-        let schema = Arc::new(Schema::new(fields));
-        let empty_iter = RecordBatchIterator::new(vec![], schema);
-        self.conn
-            .create_table(schema::TABLE_ENT_COMPOUNDS, empty_iter)
-            .execute()
-            .await?;
-        Ok(())
+        self.create_empty_table(schema::TABLE_ENT_COMPOUNDS, ent_compounds_table_schema())
+            .await
     }
 
     pub async fn create_ent_structures_table(&self) -> Result<()> {
-        let fields: Fields = vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("gene_id", DataType::Utf8, false),
-            Field::new("pdb_ids", DataType::Utf8, true),
-            Field::new("best_resolution", DataType::Float32, true),
-            Field::new("exp_method", DataType::Utf8, true),
-            Field::new("af_accession", DataType::Utf8, true),
-            Field::new("af_plddt_mean", DataType::Float32, true),
-            Field::new("af_plddt_active", DataType::Float32, true),
-            Field::new("has_pdb", DataType::Boolean, false),
-            Field::new("has_alphafold", DataType::Boolean, false),
-            Field::new("updated_at", DataType::Utf8, false),
-        ]
-        .into();
-        let schema = Arc::new(Schema::new(fields));
-        let empty_iter = RecordBatchIterator::new(vec![], schema);
-        self.conn
-            .create_table(schema::TABLE_ENT_STRUCTURES, empty_iter)
-            .execute()
-            .await?;
-        Ok(())
+        self.create_empty_table(schema::TABLE_ENT_STRUCTURES, ent_structures_table_schema())
+            .await
     }
 
     pub async fn create_ent_druggability_table(&self) -> Result<()> {
-        let fields: Fields = vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("structure_id", DataType::Utf8, false),
-            Field::new("fpocket_score", DataType::Float32, true),
-            Field::new("fpocket_volume", DataType::Float32, true),
-            Field::new("fpocket_pocket_count", DataType::Int32, true),
-            Field::new("dogsitescorer", DataType::Float32, true),
-            Field::new("overall_assessment", DataType::Utf8, true),
-            Field::new("assessed_at", DataType::Utf8, false),
-        ]
-        .into();
-        let schema = Arc::new(Schema::new(fields));
-        let empty_iter = RecordBatchIterator::new(vec![], schema);
-        self.conn
-            .create_table(schema::TABLE_ENT_DRUGGABILITY, empty_iter)
-            .execute()
-            .await?;
-        Ok(())
+        self.create_empty_table(
+            schema::TABLE_ENT_DRUGGABILITY,
+            ent_druggability_table_schema(),
+        )
+        .await
     }
 
     pub async fn create_ent_synthetic_lethality_table(&self) -> Result<()> {
-        let fields: Fields = vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("gene1_id", DataType::Utf8, false),
-            Field::new("gene2_id", DataType::Utf8, false),
-            Field::new("cancer_id", DataType::Utf8, false),
-            Field::new("evidence_type", DataType::Utf8, true),
-            Field::new("source_db", DataType::Utf8, true),
-            Field::new("screen_id", DataType::Utf8, true),
-            Field::new("effect_size", DataType::Float32, true),
-            Field::new("confidence", DataType::Float32, true),
-            Field::new("pmid", DataType::Utf8, true),
-            Field::new("created_at", DataType::Utf8, false),
-        ]
-        .into();
-        let schema = Arc::new(Schema::new(fields));
-        let empty_iter = RecordBatchIterator::new(vec![], schema);
-        self.conn
-            .create_table(schema::TABLE_ENT_SYNTHETIC_LETHALITY, empty_iter)
-            .execute()
-            .await?;
-        Ok(())
+        self.create_empty_table(
+            schema::TABLE_ENT_SYNTHETIC_LETHALITY,
+            ent_synthetic_lethality_table_schema(),
+        )
+        .await
     }
 
     pub async fn create_ent_tcga_survival_table(&self) -> Result<()> {
-        let fields: Fields = vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("gene_symbol", DataType::Utf8, false),
-            Field::new("cancer_code", DataType::Utf8, false),
-            Field::new("tcga_project_id", DataType::Utf8, false),
-            Field::new("survival_score", DataType::Float64, false),
-            Field::new("source", DataType::Utf8, false),
-            Field::new("fetched_at", DataType::Utf8, false),
-        ]
-        .into();
-        let schema = Arc::new(Schema::new(fields));
-        let empty_iter = RecordBatchIterator::new(vec![], schema);
-        self.conn
-            .create_table(schema::TABLE_ENT_TCGA_SURVIVAL, empty_iter)
-            .execute()
-            .await?;
-        Ok(())
+        self.create_empty_table(
+            schema::TABLE_ENT_TCGA_SURVIVAL,
+            ent_tcga_survival_table_schema(),
+        )
+        .await
     }
 
     pub async fn create_ent_cbio_mutation_frequency_table(&self) -> Result<()> {
-        let fields: Fields = vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("gene_symbol", DataType::Utf8, false),
-            Field::new("cancer_code", DataType::Utf8, false),
-            Field::new("study_id", DataType::Utf8, false),
-            Field::new("molecular_profile_id", DataType::Utf8, false),
-            Field::new("sample_list_id", DataType::Utf8, false),
-            Field::new("mutated_sample_count", DataType::Int64, false),
-            Field::new("profiled_sample_count", DataType::Int64, false),
-            Field::new("mutation_frequency", DataType::Float64, false),
-            Field::new("source", DataType::Utf8, false),
-            Field::new("fetched_at", DataType::Utf8, false),
-        ]
-        .into();
-        let schema = Arc::new(Schema::new(fields));
-        let empty_iter = RecordBatchIterator::new(vec![], schema);
-        self.conn
-            .create_table(schema::TABLE_ENT_CBIO_MUTATION_FREQUENCY, empty_iter)
-            .execute()
-            .await?;
-        Ok(())
+        self.create_empty_table(
+            schema::TABLE_ENT_CBIO_MUTATION_FREQUENCY,
+            ent_cbio_mutation_frequency_table_schema(),
+        )
+        .await
     }
 
     pub async fn create_ent_cosmic_mutation_frequency_table(&self) -> Result<()> {
-        let fields: Fields = vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("gene_symbol", DataType::Utf8, false),
-            Field::new("cancer_code", DataType::Utf8, false),
-            Field::new("mutated_sample_count", DataType::Int64, false),
-            Field::new("profiled_sample_count", DataType::Int64, false),
-            Field::new("mutation_frequency", DataType::Float64, false),
-            Field::new("source", DataType::Utf8, false),
-            Field::new("fetched_at", DataType::Utf8, false),
-        ]
-        .into();
-        let schema = Arc::new(Schema::new(fields));
-        let empty_iter = RecordBatchIterator::new(vec![], schema);
-        self.conn
-            .create_table(schema::TABLE_ENT_COSMIC_MUTATION_FREQUENCY, empty_iter)
-            .execute()
-            .await?;
-        Ok(())
+        self.create_empty_table(
+            schema::TABLE_ENT_COSMIC_MUTATION_FREQUENCY,
+            ent_cosmic_mutation_frequency_table_schema(),
+        )
+        .await
     }
 
     pub async fn create_ent_gtex_expression_table(&self) -> Result<()> {
-        let fields: Fields = vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("gene_symbol", DataType::Utf8, false),
-            Field::new("expression_score", DataType::Float64, false),
-            Field::new("source", DataType::Utf8, false),
-            Field::new("fetched_at", DataType::Utf8, false),
-        ]
-        .into();
-        let schema = Arc::new(Schema::new(fields));
-        let empty_iter = RecordBatchIterator::new(vec![], schema);
-        self.conn
-            .create_table(schema::TABLE_ENT_GTEX_EXPRESSION, empty_iter)
-            .execute()
-            .await?;
-        Ok(())
+        self.create_empty_table(
+            schema::TABLE_ENT_GTEX_EXPRESSION,
+            ent_gtex_expression_table_schema(),
+        )
+        .await
     }
 
     pub async fn create_ent_chembl_targets_table(&self) -> Result<()> {
-        let fields: Fields = vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("gene_symbol", DataType::Utf8, false),
-            Field::new("inhibitor_count", DataType::Int64, false),
-            Field::new("source", DataType::Utf8, false),
-            Field::new("fetched_at", DataType::Utf8, false),
-        ]
-        .into();
-        let schema = Arc::new(Schema::new(fields));
-        let empty_iter = RecordBatchIterator::new(vec![], schema);
-        self.conn
-            .create_table(schema::TABLE_ENT_CHEMBL_TARGETS, empty_iter)
-            .execute()
-            .await?;
-        Ok(())
+        self.create_empty_table(
+            schema::TABLE_ENT_CHEMBL_TARGETS,
+            ent_chembl_targets_table_schema(),
+        )
+        .await
     }
 
     pub async fn create_ent_reactome_genes_table(&self) -> Result<()> {
-        let fields: Fields = vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("gene_symbol", DataType::Utf8, false),
-            Field::new("pathway_count", DataType::Int64, false),
-            Field::new("source", DataType::Utf8, false),
-            Field::new("fetched_at", DataType::Utf8, false),
-        ]
-        .into();
-        let schema = Arc::new(Schema::new(fields));
-        let empty_iter = RecordBatchIterator::new(vec![], schema);
-        self.conn
-            .create_table(schema::TABLE_ENT_REACTOME_GENES, empty_iter)
-            .execute()
-            .await?;
-        Ok(())
+        self.create_empty_table(
+            schema::TABLE_ENT_REACTOME_GENES,
+            ent_reactome_genes_table_schema(),
+        )
+        .await
+    }
+
+    pub async fn create_ent_structural_assessments_table(&self) -> Result<()> {
+        self.create_empty_table(
+            schema::TABLE_ENT_STRUCTURAL_ASSESSMENTS,
+            ent_structural_assessments_table_schema(),
+        )
+        .await
     }
 
     pub async fn create_ent_provider_refresh_runs_table(&self) -> Result<()> {
-        let fields: Fields = vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("provider", DataType::Utf8, false),
-            Field::new("started_at", DataType::Utf8, false),
-            Field::new("finished_at", DataType::Utf8, false),
-            Field::new("genes_requested", DataType::Int64, false),
-            Field::new("genes_processed", DataType::Int64, false),
-            Field::new("attempted", DataType::Int64, false),
-            Field::new("success", DataType::Int64, false),
-            Field::new("failed", DataType::Int64, false),
-            Field::new("skipped", DataType::Int64, false),
-            Field::new("duration_ms", DataType::Int64, false),
-            Field::new("error_rate", DataType::Float64, false),
-            Field::new("cadence_interval_secs", DataType::Int64, false),
-            Field::new("trigger_reason", DataType::Utf8, false),
-        ]
-        .into();
-        let schema = Arc::new(Schema::new(fields));
-        let empty_iter = RecordBatchIterator::new(vec![], schema);
-        self.conn
-            .create_table(schema::TABLE_ENT_PROVIDER_REFRESH_RUNS, empty_iter)
-            .execute()
-            .await?;
-        Ok(())
+        self.create_empty_table(
+            schema::TABLE_ENT_PROVIDER_REFRESH_RUNS,
+            ent_provider_refresh_runs_table_schema(),
+        )
+        .await
     }
 }
+
+// =============================================================================
+// Expected Table Schemas
+// =============================================================================
+
+/// Every table managed by [`Database::initialize`] with the Arrow schema the
+/// current code expects, for a chunks table storing `embedding_dim`-wide
+/// vectors. Schema evolution diffs on-disk tables against this.
+pub fn expected_table_schemas(embedding_dim: usize) -> Vec<(&'static str, Arc<Schema>)> {
+    vec![
+        (schema::TABLE_PAPERS, papers_table_schema()),
+        (schema::TABLE_CHUNKS, chunks_table_schema(embedding_dim)),
+        (schema::TABLE_ENTITIES, entities_table_schema()),
+        (
+            schema::TABLE_ENTITY_MENTIONS,
+            entity_mentions_table_schema(),
+        ),
+        (schema::TABLE_KG_FACTS, kg_facts_table_schema()),
+        (schema::TABLE_KG_CONFLICTS, kg_conflicts_table_schema()),
+        (schema::TABLE_TARGET_SCORES, target_scores_table_schema()),
+        (
+            schema::TABLE_INGESTION_AUDIT,
+            ingestion_audit_table_schema(),
+        ),
+        (
+            schema::TABLE_INGESTION_WATERMARKS,
+            ingestion_watermarks_table_schema(),
+        ),
+        (
+            schema::TABLE_INGESTION_JOB_RUNS,
+            ingestion_job_runs_table_schema(),
+        ),
+        (schema::TABLE_INGESTION_JOBS, ingestion_jobs_table_schema()),
+        (
+            schema::TABLE_PAPER_CITATIONS,
+            paper_citations_table_schema(),
+        ),
+        (schema::TABLE_LLM_AUDIT, llm_audit_table_schema()),
+        (schema::TABLE_LLM_USAGE, llm_usage_table_schema()),
+        (
+            schema::TABLE_PAPER_TOMBSTONES,
+            paper_tombstones_table_schema(),
+        ),
+        (schema::TABLE_METRIC_ROLLUPS, metric_rollups_table_schema()),
+        (schema::TABLE_FAILED_ITEMS, failed_items_table_schema()),
+        (schema::TABLE_TRIALS, trials_table_schema()),
+        (
+            schema::TABLE_DOCKING_RESULTS,
+            docking_results_table_schema(),
+        ),
+        (
+            schema::TABLE_CANDIDATE_LIGANDS,
+            candidate_ligands_table_schema(),
+        ),
+        (
+            schema::TABLE_MOLECULE_CANDIDATES,
+            molecule_candidates_table_schema(),
+        ),
+        (schema::TABLE_SCHEMA_META, schema_meta_table_schema()),
+        (schema::TABLE_EMBEDDING_META, embedding_meta_table_schema()),
+        (schema::TABLE_ENT_GENES, ent_genes_table_schema()),
+        (schema::TABLE_ENT_MUTATIONS, ent_mutations_table_schema()),
+        (
+            schema::TABLE_ENT_CANCER_TYPES,
+            ent_cancer_types_table_schema(),
+        ),
+        (schema::TABLE_ENT_PATHWAYS, ent_pathways_table_schema()),
+        (
+            schema::TABLE_ENT_CLINICAL_EVIDENCE,
+            ent_clinical_evidence_table_schema(),
+        ),
+        (schema::TABLE_ENT_COMPOUNDS, ent_compounds_table_schema()),
+        (schema::TABLE_ENT_STRUCTURES, ent_structures_table_schema()),
+        (
+            schema::TABLE_ENT_DRUGGABILITY,
+            ent_druggability_table_schema(),
+        ),
+        (
+            schema::TABLE_ENT_SYNTHETIC_LETHALITY,
+            ent_synthetic_lethality_table_schema(),
+        ),
+        (
+            schema::TABLE_ENT_TCGA_SURVIVAL,
+            ent_tcga_survival_table_schema(),
+        ),
+        (
+            schema::TABLE_ENT_CBIO_MUTATION_FREQUENCY,
+            ent_cbio_mutation_frequency_table_schema(),
+        ),
+        (
+            schema::TABLE_ENT_COSMIC_MUTATION_FREQUENCY,
+            ent_cosmic_mutation_frequency_table_schema(),
+        ),
+        (
+            schema::TABLE_ENT_GTEX_EXPRESSION,
+            ent_gtex_expression_table_schema(),
+        ),
+        (
+            schema::TABLE_ENT_CHEMBL_TARGETS,
+            ent_chembl_targets_table_schema(),
+        ),
+        (
+            schema::TABLE_ENT_REACTOME_GENES,
+            ent_reactome_genes_table_schema(),
+        ),
+        (
+            schema::TABLE_ENT_STRUCTURAL_ASSESSMENTS,
+            ent_structural_assessments_table_schema(),
+        ),
+        (
+            schema::TABLE_ENT_PROVIDER_REFRESH_RUNS,
+            ent_provider_refresh_runs_table_schema(),
+        ),
+    ]
+}
+
+fn papers_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("doi", DataType::Utf8, true),
+        Field::new("pmid", DataType::Utf8, true),
+        Field::new("title", DataType::Utf8, false),
+        Field::new("abstract_text", DataType::Utf8, true),
+        Field::new("full_text", DataType::Utf8, true),
+        Field::new("raw_json", DataType::Utf8, true),
+        Field::new("source", DataType::Utf8, false),
+        Field::new("source_id", DataType::Utf8, true),
+        Field::new("published_at", DataType::Utf8, true),
+        Field::new("authors", DataType::Utf8, true),
+        Field::new("journal", DataType::Utf8, true),
+        Field::new("volume", DataType::Utf8, true),
+        Field::new("issue", DataType::Utf8, true),
+        Field::new("pages", DataType::Utf8, true),
+        Field::new("parse_status", DataType::Utf8, false),
+        Field::new("open_access", DataType::Boolean, false),
+        Field::new("retrieval_tier", DataType::Int32, true),
+        Field::new("ingested_at", DataType::Utf8, false),
+        Field::new("abstract_simhash", DataType::Int64, true),
+        Field::new("published_version_doi", DataType::Utf8, true),
+        Field::new("is_review", DataType::Boolean, true),
+        Field::new("citation_count", DataType::Int64, true),
+        Field::new("minhash_signature", DataType::Binary, true),
+        Field::new("duplicate_of", DataType::Utf8, true),
+        Field::new("text_quality", DataType::Float64, true),
+        Field::new("job_id", DataType::Utf8, true),
+        Field::new("query_tag", DataType::Utf8, true),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+fn chunks_table_schema(embedding_dim: usize) -> Arc<Schema> {
+    let embedding_field = Field::new(
+        "embedding",
+        DataType::FixedSizeList(
+            Arc::new(Field::new("item", DataType::Float32, false)),
+            embedding_dim as i32,
+        ),
+        true,
+    );
+
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("paper_id", DataType::Utf8, false),
+        Field::new("chunk_index", DataType::Int64, false),
+        Field::new("token_count", DataType::Int32, false),
+        Field::new("content", DataType::Utf8, false),
+        Field::new("section", DataType::Utf8, true),
+        Field::new("page", DataType::Int64, true),
+        Field::new("created_at", DataType::Utf8, false),
+        embedding_field,
+        Field::new(
+            "embedding_large",
+            DataType::FixedSizeList(
+                Arc::new(Field::new("item", DataType::Float32, false)),
+                1024 as i32,
+            ),
+            true,
+        ),
+        Field::new("char_start", DataType::Int64, true),
+        Field::new("char_end", DataType::Int64, true),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+fn entities_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("external_id", DataType::Utf8, false),
+        Field::new("name", DataType::Utf8, false),
+        Field::new("canonical_name", DataType::Utf8, true),
+        Field::new("entity_type", DataType::Utf8, false),
+        Field::new("synonyms", DataType::Utf8, true),
+        Field::new("description", DataType::Utf8, true),
+        Field::new("source_db", DataType::Utf8, false),
+        Field::new("metadata", DataType::Utf8, true),
+        Field::new("created_at", DataType::Utf8, false),
+        Field::new("updated_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+fn entity_mentions_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("entity_id", DataType::Utf8, false),
+        Field::new("chunk_id", DataType::Utf8, false),
+        Field::new("paper_id", DataType::Utf8, false),
+        Field::new("start_offset", DataType::Int64, false),
+        Field::new("end_offset", DataType::Int64, false),
+        Field::new("text", DataType::Utf8, false),
+        Field::new("confidence", DataType::Float32, true),
+        Field::new("context", DataType::Utf8, true),
+        Field::new("created_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+fn kg_facts_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("paper_id", DataType::Utf8, false),
+        Field::new("subject_id", DataType::Utf8, false),
+        Field::new("subject_name", DataType::Utf8, false),
+        Field::new("predicate", DataType::Utf8, false),
+        Field::new("object_id", DataType::Utf8, false),
+        Field::new("object_name", DataType::Utf8, false),
+        Field::new("confidence", DataType::Float32, false),
+        Field::new("evidence", DataType::Utf8, true),
+        Field::new("evidence_type", DataType::Utf8, false),
+        Field::new("study_type", DataType::Utf8, true),
+        Field::new("sample_size", DataType::Int32, true),
+        Field::new("valid_from", DataType::Utf8, false),
+        Field::new("valid_until", DataType::Utf8, true),
+        Field::new("created_at", DataType::Utf8, false),
+        Field::new("chunk_id", DataType::Utf8, true),
+        Field::new("evidence_start", DataType::Int64, true),
+        Field::new("evidence_end", DataType::Int64, true),
+        Field::new("supporting_evidence", DataType::Utf8, true),
+        Field::new("support_count", DataType::Int64, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+fn kg_conflicts_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("fact_a_id", DataType::Utf8, false),
+        Field::new("fact_b_id", DataType::Utf8, false),
+        Field::new("conflict_type", DataType::Utf8, false),
+        Field::new("net_confidence", DataType::Float32, false),
+        Field::new("resolution", DataType::Utf8, false),
+        Field::new("detected_at", DataType::Utf8, false),
+        Field::new("severity", DataType::Utf8, true),
+        Field::new("paper_ids", DataType::Utf8, true),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+fn target_scores_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("gene_id", DataType::Utf8, false),
+        Field::new("cancer_id", DataType::Utf8, false),
+        Field::new("score_version", DataType::Int64, false),
+        Field::new("is_current", DataType::Boolean, false),
+        Field::new("composite_score", DataType::Float64, false),
+        Field::new("confidence_adjusted_score", DataType::Float64, false),
+        Field::new("penalty_score", DataType::Float64, false),
+        Field::new("shortlist_tier", DataType::Utf8, false),
+        Field::new("components_raw", DataType::Utf8, false),
+        Field::new("components_normed", DataType::Utf8, false),
+        Field::new("created_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+fn ingestion_audit_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("job_id", DataType::Utf8, true),
+        Field::new("paper_id", DataType::Utf8, true),
+        Field::new("action", DataType::Utf8, false),
+        Field::new("detail", DataType::Utf8, false),
+        Field::new("created_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+pub(crate) fn ingestion_watermarks_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("query", DataType::Utf8, false),
+        Field::new("source", DataType::Utf8, false),
+        Field::new("last_run_at", DataType::Utf8, false),
+        Field::new("last_seen_publication_date", DataType::Utf8, true),
+        Field::new("paper_count", DataType::Int64, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+pub(crate) fn ingestion_job_runs_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("schedule_id", DataType::Utf8, false),
+        Field::new("trigger", DataType::Utf8, false),
+        Field::new("status", DataType::Utf8, false),
+        Field::new("started_at", DataType::Utf8, false),
+        Field::new("finished_at", DataType::Utf8, true),
+        Field::new("duration_ms", DataType::Int64, false),
+        Field::new("papers_found", DataType::Int64, false),
+        Field::new("papers_inserted", DataType::Int64, false),
+        Field::new("papers_duplicate", DataType::Int64, false),
+        Field::new("chunks_inserted", DataType::Int64, false),
+        Field::new("error", DataType::Utf8, true),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+pub(crate) fn ingestion_jobs_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("gene", DataType::Utf8, false),
+        Field::new("mutation", DataType::Utf8, true),
+        Field::new("cancer_type", DataType::Utf8, false),
+        Field::new("sources", DataType::Utf8, false),
+        Field::new("max_results", DataType::Int64, false),
+        Field::new("status", DataType::Utf8, false),
+        Field::new("stage", DataType::Utf8, false),
+        Field::new("papers_found", DataType::Int64, false),
+        Field::new("papers_inserted", DataType::Int64, false),
+        Field::new("papers_duplicate", DataType::Int64, false),
+        Field::new("chunks_inserted", DataType::Int64, false),
+        Field::new("started_at", DataType::Utf8, false),
+        Field::new("finished_at", DataType::Utf8, true),
+        Field::new("error", DataType::Utf8, true),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+pub(crate) fn paper_citations_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("citing_paper_id", DataType::Utf8, false),
+        Field::new("cited_doi", DataType::Utf8, false),
+        Field::new("cited_paper_id", DataType::Utf8, true),
+        Field::new("context", DataType::Utf8, true),
+        Field::new("source", DataType::Utf8, false),
+        Field::new("created_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+pub(crate) fn llm_audit_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("session_id", DataType::Utf8, true),
+        Field::new("model", DataType::Utf8, false),
+        Field::new("backend", DataType::Utf8, false),
+        Field::new("prompt_tokens", DataType::Int64, true),
+        Field::new("completion_tokens", DataType::Int64, true),
+        Field::new("data_class", DataType::Utf8, false),
+        Field::new("output_hash", DataType::Utf8, false),
+        Field::new("latency_ms", DataType::Int64, true),
+        Field::new("chunk_ids", DataType::Utf8, false),
+        Field::new("called_at", DataType::Utf8, false),
+        Field::new("cached", DataType::Boolean, true),
+        Field::new("caller", DataType::Utf8, true),
+        Field::new("error", DataType::Utf8, true),
+        Field::new("prompt_hash", DataType::Utf8, true),
+        Field::new("prompt_chars", DataType::Int64, true),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+pub(crate) fn llm_usage_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("day", DataType::Utf8, false),
+        Field::new("backend", DataType::Utf8, false),
+        Field::new("model", DataType::Utf8, false),
+        Field::new("prompt_tokens", DataType::Int64, false),
+        Field::new("completion_tokens", DataType::Int64, false),
+        Field::new("cost_usd", DataType::Float64, false),
+        Field::new("recorded_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+pub(crate) fn paper_tombstones_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("paper_id", DataType::Utf8, false),
+        Field::new("doi", DataType::Utf8, true),
+        Field::new("pmid", DataType::Utf8, true),
+        Field::new("title", DataType::Utf8, false),
+        Field::new("source", DataType::Utf8, false),
+        Field::new("reason", DataType::Utf8, false),
+        Field::new("deleted_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+pub(crate) fn metric_rollups_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("metric", DataType::Utf8, false),
+        Field::new("day", DataType::Utf8, false),
+        Field::new("samples", DataType::Int64, false),
+        Field::new("min", DataType::Float64, false),
+        Field::new("max", DataType::Float64, false),
+        Field::new("sum", DataType::Float64, false),
+        Field::new("last", DataType::Float64, false),
+        Field::new("updated_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+pub(crate) fn failed_items_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("paper_id", DataType::Utf8, false),
+        Field::new("job_id", DataType::Utf8, true),
+        Field::new("title", DataType::Utf8, false),
+        Field::new("doi", DataType::Utf8, true),
+        Field::new("pmid", DataType::Utf8, true),
+        Field::new("source", DataType::Utf8, false),
+        Field::new("metadata", DataType::Utf8, false),
+        Field::new("stage", DataType::Utf8, false),
+        Field::new("error", DataType::Utf8, false),
+        Field::new("retry_count", DataType::Int64, false),
+        Field::new("status", DataType::Utf8, false),
+        Field::new("first_failed_at", DataType::Utf8, false),
+        Field::new("last_failed_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+/// List-valued columns hold JSON arrays; `interventions` holds
+/// [`TrialIntervention`](crate::schema::TrialIntervention) objects.
+pub(crate) fn trials_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("nct_id", DataType::Utf8, false),
+        Field::new("title", DataType::Utf8, false),
+        Field::new("conditions", DataType::Utf8, false),
+        Field::new("interventions", DataType::Utf8, false),
+        Field::new("phase", DataType::Utf8, false),
+        Field::new("status", DataType::Utf8, false),
+        Field::new("study_type", DataType::Utf8, false),
+        Field::new("enrollment", DataType::Int64, true),
+        Field::new("start_date", DataType::Utf8, true),
+        Field::new("completion_date", DataType::Utf8, true),
+        Field::new("sponsors", DataType::Utf8, false),
+        Field::new("drugs", DataType::Utf8, false),
+        Field::new("target_genes", DataType::Utf8, false),
+        Field::new("cancer_codes", DataType::Utf8, false),
+        Field::new("fetched_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+/// `poses` holds a JSON array of
+/// [`DockedPose`](crate::schema::DockedPose) objects.
+pub(crate) fn docking_results_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("target", DataType::Utf8, false),
+        Field::new("pocket_rank", DataType::Int64, false),
+        Field::new("ligand_id", DataType::Utf8, false),
+        Field::new("smiles", DataType::Utf8, false),
+        Field::new("structure", DataType::Utf8, false),
+        Field::new("best_affinity_kcal", DataType::Float64, true),
+        Field::new("poses", DataType::Utf8, false),
+        Field::new("error", DataType::Utf8, true),
+        Field::new("docked_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+pub(crate) fn candidate_ligands_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("target_chembl_id", DataType::Utf8, false),
+        Field::new("smiles", DataType::Utf8, false),
+        Field::new("parent_chembl_id", DataType::Utf8, false),
+        Field::new("scaffold_id", DataType::Utf8, false),
+        Field::new("novelty", DataType::Boolean, false),
+        Field::new("generated_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+/// `pains_alerts` holds a JSON array of alert names.
+pub(crate) fn molecule_candidates_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("gene", DataType::Utf8, false),
+        Field::new("cancer_type", DataType::Utf8, false),
+        Field::new("rank", DataType::Int64, false),
+        Field::new("smiles", DataType::Utf8, false),
+        Field::new("parent_chembl_id", DataType::Utf8, false),
+        Field::new("scaffold_id", DataType::Utf8, false),
+        Field::new("novelty", DataType::Boolean, false),
+        Field::new("pocket_rank", DataType::Int64, false),
+        Field::new("best_affinity_kcal", DataType::Float64, false),
+        Field::new("admet_score", DataType::Float64, false),
+        Field::new("sa_score", DataType::Float64, false),
+        Field::new("pains_alerts", DataType::Utf8, false),
+        Field::new("composite_score", DataType::Float64, false),
+        Field::new("ranked_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+fn schema_meta_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("table_name", DataType::Utf8, false),
+        Field::new("schema_version", DataType::Int64, false),
+        Field::new("column_count", DataType::Int64, false),
+        Field::new("applied_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+fn embedding_meta_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("table_name", DataType::Utf8, false),
+        Field::new("column_name", DataType::Utf8, false),
+        Field::new("dim", DataType::Int64, false),
+        Field::new("updated_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+fn ent_genes_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("hgnc_id", DataType::Utf8, true),
+        Field::new("symbol", DataType::Utf8, false),
+        Field::new("name", DataType::Utf8, true),
+        Field::new("uniprot_id", DataType::Utf8, true),
+        Field::new("ensembl_id", DataType::Utf8, true),
+        Field::new("entrez_id", DataType::Utf8, true),
+        Field::new("gene_biotype", DataType::Utf8, true),
+        Field::new("chromosome", DataType::Utf8, true),
+        Field::new("strand", DataType::Int16, true),
+        Field::new("aliases", DataType::Utf8, true),
+        Field::new("oncogene_flag", DataType::Boolean, false),
+        Field::new("tsg_flag", DataType::Boolean, false),
+        Field::new("created_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+fn ent_mutations_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("gene_id", DataType::Utf8, false),
+        Field::new("hgvs_p", DataType::Utf8, true),
+        Field::new("hgvs_c", DataType::Utf8, true),
+        Field::new("rs_id", DataType::Utf8, true),
+        Field::new("aa_ref", DataType::Utf8, true),
+        Field::new("aa_alt", DataType::Utf8, true),
+        Field::new("aa_position", DataType::Int32, true),
+        Field::new("oncogenicity", DataType::Utf8, true),
+        Field::new("hotspot_flag", DataType::Boolean, false),
+        Field::new("vaf_context", DataType::Utf8, true),
+        Field::new("created_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+fn ent_cancer_types_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("oncotree_code", DataType::Utf8, true),
+        Field::new("oncotree_name", DataType::Utf8, true),
+        Field::new("icd_o3_code", DataType::Utf8, true),
+        Field::new("tissue", DataType::Utf8, true),
+        Field::new("parent_code", DataType::Utf8, true),
+        Field::new("level", DataType::Int32, true),
+        Field::new("created_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+fn ent_pathways_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("kegg_id", DataType::Utf8, true),
+        Field::new("reactome_id", DataType::Utf8, true),
+        Field::new("go_term", DataType::Utf8, true),
+        Field::new("name", DataType::Utf8, false),
+        Field::new("gene_members", DataType::Utf8, true),
+        Field::new("source", DataType::Utf8, true),
+        Field::new("created_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+fn ent_clinical_evidence_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("nct_id", DataType::Utf8, true),
+        Field::new("pmid", DataType::Utf8, true),
+        Field::new("doi", DataType::Utf8, true),
+        Field::new("phase", DataType::Utf8, true),
+        Field::new("intervention", DataType::Utf8, true),
+        Field::new("target_gene_id", DataType::Utf8, false),
+        Field::new("cancer_id", DataType::Utf8, false),
+        Field::new("primary_endpoint", DataType::Utf8, true),
+        Field::new("outcome", DataType::Utf8, true),
+        Field::new("evidence_grade", DataType::Utf8, true),
+        Field::new("created_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+fn ent_compounds_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("chembl_id", DataType::Utf8, true),
+        Field::new("name", DataType::Utf8, true),
+        Field::new("smiles", DataType::Utf8, true),
+        Field::new("inchi_key", DataType::Utf8, true),
+        Field::new("moa", DataType::Utf8, true),
+        Field::new("patent_status", DataType::Utf8, true),
+        Field::new("max_phase", DataType::Int32, true),
+        Field::new("target_gene_ids", DataType::Utf8, true),
+        Field::new("created_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+fn ent_structures_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("gene_id", DataType::Utf8, false),
+        Field::new("pdb_ids", DataType::Utf8, true),
+        Field::new("best_resolution", DataType::Float32, true),
+        Field::new("exp_method", DataType::Utf8, true),
+        Field::new("af_accession", DataType::Utf8, true),
+        Field::new("af_plddt_mean", DataType::Float32, true),
+        Field::new("af_plddt_active", DataType::Float32, true),
+        Field::new("has_pdb", DataType::Boolean, false),
+        Field::new("has_alphafold", DataType::Boolean, false),
+        Field::new("updated_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+fn ent_druggability_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("structure_id", DataType::Utf8, false),
+        Field::new("fpocket_score", DataType::Float32, true),
+        Field::new("fpocket_volume", DataType::Float32, true),
+        Field::new("fpocket_pocket_count", DataType::Int32, true),
+        Field::new("dogsitescorer", DataType::Float32, true),
+        Field::new("overall_assessment", DataType::Utf8, true),
+        Field::new("assessed_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+fn ent_synthetic_lethality_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("gene1_id", DataType::Utf8, false),
+        Field::new("gene2_id", DataType::Utf8, false),
+        Field::new("cancer_id", DataType::Utf8, false),
+        Field::new("evidence_type", DataType::Utf8, true),
+        Field::new("source_db", DataType::Utf8, true),
+        Field::new("screen_id", DataType::Utf8, true),
+        Field::new("effect_size", DataType::Float32, true),
+        Field::new("confidence", DataType::Float32, true),
+        Field::new("pmid", DataType::Utf8, true),
+        Field::new("created_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+pub(crate) fn ent_tcga_survival_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("gene_symbol", DataType::Utf8, false),
+        Field::new("cancer_code", DataType::Utf8, false),
+        Field::new("tcga_project_id", DataType::Utf8, false),
+        Field::new("survival_score", DataType::Float64, false),
+        Field::new("source", DataType::Utf8, false),
+        Field::new("fetched_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+pub(crate) fn ent_cbio_mutation_frequency_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("gene_symbol", DataType::Utf8, false),
+        Field::new("cancer_code", DataType::Utf8, false),
+        Field::new("study_id", DataType::Utf8, false),
+        Field::new("molecular_profile_id", DataType::Utf8, false),
+        Field::new("sample_list_id", DataType::Utf8, false),
+        Field::new("mutated_sample_count", DataType::Int64, false),
+        Field::new("profiled_sample_count", DataType::Int64, false),
+        Field::new("mutation_frequency", DataType::Float64, false),
+        Field::new("source", DataType::Utf8, false),
+        Field::new("fetched_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+pub(crate) fn ent_cosmic_mutation_frequency_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("gene_symbol", DataType::Utf8, false),
+        Field::new("cancer_code", DataType::Utf8, false),
+        Field::new("mutated_sample_count", DataType::Int64, false),
+        Field::new("profiled_sample_count", DataType::Int64, false),
+        Field::new("mutation_frequency", DataType::Float64, false),
+        Field::new("source", DataType::Utf8, false),
+        Field::new("fetched_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+pub(crate) fn ent_gtex_expression_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("gene_symbol", DataType::Utf8, false),
+        Field::new("expression_score", DataType::Float64, false),
+        Field::new("source", DataType::Utf8, false),
+        Field::new("fetched_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+pub(crate) fn ent_chembl_targets_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("gene_symbol", DataType::Utf8, false),
+        Field::new("inhibitor_count", DataType::Int64, false),
+        Field::new("source", DataType::Utf8, false),
+        Field::new("fetched_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+pub(crate) fn ent_structural_assessments_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("uniprot_id", DataType::Utf8, false),
+        Field::new("has_pdb", DataType::Boolean, false),
+        Field::new("pdb_count", DataType::Int64, false),
+        Field::new("best_pdb_id", DataType::Utf8, true),
+        Field::new("best_resolution", DataType::Float64, true),
+        Field::new("alphafold_plddt", DataType::Float64, true),
+        Field::new("best_pocket_score", DataType::Float64, true),
+        Field::new("pocket_count", DataType::Int64, true),
+        Field::new("source", DataType::Utf8, false),
+        Field::new("fetched_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+pub(crate) fn ent_reactome_genes_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("gene_symbol", DataType::Utf8, false),
+        Field::new("pathway_count", DataType::Int64, false),
+        Field::new("source", DataType::Utf8, false),
+        Field::new("fetched_at", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
+
+pub(crate) fn ent_provider_refresh_runs_table_schema() -> Arc<Schema> {
+    let fields: Fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("provider", DataType::Utf8, false),
+        Field::new("started_at", DataType::Utf8, false),
+        Field::new("finished_at", DataType::Utf8, false),
+        Field::new("genes_requested", DataType::Int64, false),
+        Field::new("genes_processed", DataType::Int64, false),
+        Field::new("attempted", DataType::Int64, false),
+        Field::new("success", DataType::Int64, false),
+        Field::new("failed", DataType::Int64, false),
+        Field::new("skipped", DataType::Int64, false),
+        Field::new("duration_ms", DataType::Int64, false),
+        Field::new("error_rate", DataType::Float64, false),
+        Field::new("cadence_interval_secs", DataType::Int64, false),
+        Field::new("trigger_reason", DataType::Utf8, false),
+    ]
+    .into();
+    Arc::new(Schema::new(fields))
+}
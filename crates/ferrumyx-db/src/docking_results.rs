@@ -0,0 +1,251 @@
+//! Docking result repository.
+//!
+//! One row per ligand docked into a target pocket, keyed by
+//! (target, pocket_rank, ligand_id). Re-docking a ligand replaces its row;
+//! failed dockings are stored too, with their error and no poses, so the
+//! molecule viewer can show what did not dock as well as what did.
+
+use crate::database::{docking_results_table_schema, Database};
+use crate::error::{DbError, Result};
+use crate::schema::{DockingResultRecord, TABLE_DOCKING_RESULTS};
+use crate::schema_evolution::conform_row;
+use std::sync::Arc;
+
+use arrow_array::{Array, Float64Array, Int64Array, RecordBatch, StringArray};
+use futures::StreamExt;
+use lancedb::query::{ExecutableQuery, QueryBase};
+
+/// Repository for docking results.
+#[derive(Clone)]
+pub struct DockingResultRepository {
+    db: Arc<Database>,
+}
+
+impl DockingResultRepository {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Insert or replace results by (target, pocket_rank, ligand_id).
+    pub async fn upsert_many(&self, results: &[DockingResultRecord]) -> Result<()> {
+        if results.is_empty() {
+            return Ok(());
+        }
+        let table = self.open().await?;
+        let batch = results_to_batch(results)?;
+        let schema = batch.schema();
+        let iter = arrow_array::RecordBatchIterator::new(vec![Ok(batch)], schema);
+        let mut builder = table.merge_insert(&["target", "pocket_rank", "ligand_id"]);
+        builder.when_matched_update_all(None);
+        builder.when_not_matched_insert_all();
+        builder.execute(Box::new(iter)).await?;
+        Ok(())
+    }
+
+    pub async fn find(
+        &self,
+        target: &str,
+        pocket_rank: u32,
+        ligand_id: &str,
+    ) -> Result<Option<DockingResultRecord>> {
+        let filter = format!(
+            "target = '{}' AND pocket_rank = {pocket_rank} AND ligand_id = '{}'",
+            target.replace('\'', "''"),
+            ligand_id.replace('\'', "''")
+        );
+        Ok(self.query(Some(filter)).await?.into_iter().next())
+    }
+
+    /// Results for `target`, best affinity first; failures last.
+    pub async fn list_by_target(&self, target: &str) -> Result<Vec<DockingResultRecord>> {
+        let filter = format!("target = '{}'", target.replace('\'', "''"));
+        let mut rows = self.query(Some(filter)).await?;
+        sort_by_affinity(&mut rows);
+        Ok(rows)
+    }
+
+    /// Every stored result, best affinity first; failures last.
+    pub async fn list(&self) -> Result<Vec<DockingResultRecord>> {
+        let mut rows = self.query(None).await?;
+        sort_by_affinity(&mut rows);
+        Ok(rows)
+    }
+
+    pub async fn count(&self) -> Result<u64> {
+        Ok(self.open().await?.count_rows(None).await? as u64)
+    }
+
+    async fn open(&self) -> Result<lancedb::Table> {
+        Ok(self
+            .db
+            .connection()
+            .open_table(TABLE_DOCKING_RESULTS)
+            .execute()
+            .await?)
+    }
+
+    async fn query(&self, filter: Option<String>) -> Result<Vec<DockingResultRecord>> {
+        let table = self.open().await?;
+        let mut query = table.query();
+        if let Some(filter) = filter {
+            query = query.only_if(filter);
+        }
+        let mut stream = query.execute().await?;
+        let mut rows = Vec::new();
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            for row in 0..batch.num_rows() {
+                rows.push(batch_to_result(&batch, row)?);
+            }
+        }
+        Ok(rows)
+    }
+}
+
+/// More negative affinities bind tighter, so they sort first.
+fn sort_by_affinity(rows: &mut [DockingResultRecord]) {
+    rows.sort_by(|a, b| match (a.best_affinity_kcal, b.best_affinity_kcal) {
+        (Some(x), Some(y)) => x.total_cmp(&y),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => a.ligand_id.cmp(&b.ligand_id),
+    });
+}
+
+fn results_to_batch(results: &[DockingResultRecord]) -> Result<RecordBatch> {
+    let strings = |f: &dyn Fn(&DockingResultRecord) -> Option<String>| -> Arc<dyn Array> {
+        Arc::new(StringArray::from(results.iter().map(f).collect::<Vec<_>>()))
+    };
+    let poses = results
+        .iter()
+        .map(|r| serde_json::to_string(&r.poses))
+        .collect::<serde_json::Result<Vec<_>>>()?;
+    let cols: Vec<Arc<dyn Array>> = vec![
+        strings(&|r| Some(r.target.clone())),
+        Arc::new(Int64Array::from(
+            results
+                .iter()
+                .map(|r| i64::from(r.pocket_rank))
+                .collect::<Vec<_>>(),
+        )),
+        strings(&|r| Some(r.ligand_id.clone())),
+        strings(&|r| Some(r.smiles.clone())),
+        strings(&|r| Some(r.structure.clone())),
+        Arc::new(Float64Array::from(
+            results
+                .iter()
+                .map(|r| r.best_affinity_kcal)
+                .collect::<Vec<_>>(),
+        )),
+        Arc::new(StringArray::from(poses)),
+        strings(&|r| r.error.clone()),
+        strings(&|r| Some(r.docked_at.to_rfc3339())),
+    ];
+    Ok(RecordBatch::try_new(docking_results_table_schema(), cols)?)
+}
+
+fn batch_to_result(batch: &RecordBatch, row: usize) -> Result<DockingResultRecord> {
+    let (batch, row) = conform_row(batch, row, &docking_results_table_schema())?;
+    let batch: &RecordBatch = &batch;
+    let get_opt = |col: &str| -> Result<Option<String>> {
+        let arr = batch
+            .column_by_name(col)
+            .and_then(|a| a.as_any().downcast_ref::<StringArray>())
+            .ok_or_else(|| DbError::Arrow(format!("{col} is not StringArray")))?;
+        Ok((!arr.is_null(row)).then(|| arr.value(row).to_string()))
+    };
+    let get_s = |col: &str| -> Result<String> { Ok(get_opt(col)?.unwrap_or_default()) };
+    let pocket_rank = batch
+        .column_by_name("pocket_rank")
+        .and_then(|a| a.as_any().downcast_ref::<Int64Array>())
+        .ok_or_else(|| DbError::Arrow("pocket_rank is not Int64Array".to_string()))?
+        .value(row);
+    let affinity = batch
+        .column_by_name("best_affinity_kcal")
+        .and_then(|a| a.as_any().downcast_ref::<Float64Array>())
+        .ok_or_else(|| DbError::Arrow("best_affinity_kcal is not Float64Array".to_string()))?;
+    let best_affinity_kcal = (!affinity.is_null(row)).then(|| affinity.value(row));
+    let docked_at = chrono::DateTime::parse_from_rfc3339(&get_s("docked_at")?)
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+        .map_err(|e| DbError::InvalidQuery(e.to_string()))?;
+
+    Ok(DockingResultRecord {
+        target: get_s("target")?,
+        pocket_rank: u32::try_from(pocket_rank).unwrap_or_default(),
+        ligand_id: get_s("ligand_id")?,
+        smiles: get_s("smiles")?,
+        structure: get_s("structure")?,
+        best_affinity_kcal,
+        poses: get_opt("poses")?
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default(),
+        error: get_opt("error")?,
+        docked_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::DockedPose;
+    use chrono::TimeZone;
+
+    fn docked(ligand_id: &str, affinity: Option<f64>) -> DockingResultRecord {
+        DockingResultRecord {
+            target: "KRAS".to_string(),
+            pocket_rank: 1,
+            ligand_id: ligand_id.to_string(),
+            smiles: "CCO".to_string(),
+            structure: "AF-P01116-F1.pdbqt".to_string(),
+            best_affinity_kcal: affinity,
+            poses: affinity
+                .map(|a| DockedPose {
+                    mode: 1,
+                    affinity_kcal: a,
+                    rmsd_lb: 0.0,
+                    rmsd_ub: 0.0,
+                    pose_pdbqt: "MODEL 1\nENDMDL\n".to_string(),
+                })
+                .into_iter()
+                .collect(),
+            error: affinity
+                .is_none()
+                .then(|| "ligand preparation failed".to_string()),
+            docked_at: chrono::Utc.with_ymd_and_hms(2026, 6, 1, 12, 0, 0).unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn results_round_trip_and_replace_by_target_pocket_and_ligand() {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-docking-{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        let repo = DockingResultRepository::new(db);
+
+        let ethanol = docked("ethanol", Some(-2.3));
+        let failed = docked("bad", None);
+        repo.upsert_many(&[ethanol.clone(), failed.clone()])
+            .await
+            .unwrap();
+        assert_eq!(
+            repo.find("KRAS", 1, "ethanol").await.unwrap(),
+            Some(ethanol.clone())
+        );
+        assert_eq!(repo.find("KRAS", 2, "ethanol").await.unwrap(), None);
+
+        let redocked = docked("ethanol", Some(-3.1));
+        let other = DockingResultRecord {
+            target: "EGFR".to_string(),
+            ..docked("ethanol", Some(-4.0))
+        };
+        repo.upsert_many(&[redocked.clone(), other]).await.unwrap();
+        assert_eq!(repo.count().await.unwrap(), 3);
+        assert_eq!(
+            repo.list_by_target("KRAS").await.unwrap(),
+            vec![redocked, failed]
+        );
+        assert_eq!(repo.list().await.unwrap()[0].target, "EGFR");
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -164,6 +164,7 @@ impl EntityRepository {
             EntityType::CancerType => "cancer_type",
             EntityType::Pathway => "pathway",
             EntityType::Protein => "protein",
+            EntityType::ClinicalTrial => "clinical_trial",
         };
 
         let mut stream = table
@@ -341,6 +342,7 @@ impl EntityRepository {
             EntityType::CancerType => "cancer_type",
             EntityType::Pathway => "pathway",
             EntityType::Protein => "protein",
+            EntityType::ClinicalTrial => "clinical_trial",
         };
 
         let count = table
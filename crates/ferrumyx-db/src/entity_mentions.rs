@@ -191,6 +191,27 @@ impl EntityMentionRepository {
         Ok(())
     }
 
+    /// Delete the mentions of every paper in `paper_ids`, or with `dry_run`
+    /// only count them. Returns how many match.
+    pub async fn delete_by_paper_ids(
+        &self,
+        paper_ids: &[uuid::Uuid],
+        dry_run: bool,
+    ) -> Result<u64> {
+        let table = self
+            .db
+            .connection()
+            .open_table(crate::schema::TABLE_ENTITY_MENTIONS)
+            .execute()
+            .await?;
+        let ids: Vec<String> = paper_ids.iter().map(ToString::to_string).collect();
+        if dry_run {
+            crate::maintenance::count_in(&table, "paper_id", &ids).await
+        } else {
+            crate::maintenance::delete_in(&table, "paper_id", &ids).await
+        }
+    }
+
     /// Delete a mention by ID.
     pub async fn delete(&self, id: uuid::Uuid) -> Result<()> {
         let table = self
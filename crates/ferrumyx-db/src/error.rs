@@ -24,8 +24,10 @@ pub enum DbError {
     #[error("Duplicate entry: {0}")]
     Duplicate(String),
 
-    #[error("Invalid embedding dimension: expected {expected}, got {actual}")]
-    InvalidEmbeddingDimension { expected: usize, actual: usize },
+    #[error(
+        "Embedding dimension mismatch: chunks table stores {expected}-dim vectors, got {actual}"
+    )]
+    DimensionMismatch { expected: usize, actual: usize },
 
     #[error("Database not initialized")]
     NotInitialized,
@@ -35,6 +37,9 @@ pub enum DbError {
 
     #[error("Invalid query: {0}")]
     InvalidQuery(String),
+
+    #[error("Migration required for table {table}: {detail}")]
+    MigrationRequired { table: String, detail: String },
 }
 
 impl From<lancedb::Error> for DbError {
@@ -0,0 +1,281 @@
+//! Failed ingestion item repository.
+//!
+//! A dead-letter table: a paper that panics or errors partway through
+//! ingestion gets one row here, keyed by paper id, and the run carries on
+//! with the rest of its batch. Rows are removed once a retry succeeds.
+
+use crate::database::{failed_items_table_schema, Database};
+use crate::error::{DbError, Result};
+use crate::schema::{FailedItem, TABLE_FAILED_ITEMS};
+use crate::schema_evolution::conform_row;
+use std::sync::Arc;
+
+use arrow_array::{Array, Int64Array, RecordBatch, StringArray};
+use futures::StreamExt;
+use lancedb::query::{ExecutableQuery, QueryBase};
+
+/// Repository for failed ingestion items.
+#[derive(Clone)]
+pub struct FailedItemRepository {
+    db: Arc<Database>,
+}
+
+impl FailedItemRepository {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Store `item` as failed. A paper that already has a row keeps its
+    /// first failure time and retry count; everything else is replaced.
+    pub async fn record(&self, item: &FailedItem) -> Result<()> {
+        let mut item = item.clone();
+        if let Some(existing) = self.find(item.paper_id).await? {
+            item.first_failed_at = existing.first_failed_at;
+            item.retry_count = existing.retry_count;
+        }
+        item.status = "failed".to_string();
+        self.upsert(&[item]).await
+    }
+
+    pub async fn find(&self, paper_id: uuid::Uuid) -> Result<Option<FailedItem>> {
+        let table = self.open().await?;
+        let mut stream = table
+            .query()
+            .only_if(format!("paper_id = '{}'", paper_id))
+            .limit(1)
+            .execute()
+            .await?;
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            if batch.num_rows() > 0 {
+                return Ok(Some(batch_to_item(&batch, 0)?));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Most recent failures first.
+    pub async fn list(&self, limit: usize) -> Result<Vec<FailedItem>> {
+        let mut rows = self.all().await?;
+        rows.sort_by(|a, b| b.last_failed_at.cmp(&a.last_failed_at));
+        rows.truncate(limit);
+        Ok(rows)
+    }
+
+    /// Rows in the table, whether waiting or being retried.
+    pub async fn count(&self) -> Result<u64> {
+        Ok(self.open().await?.count_rows(None).await? as u64)
+    }
+
+    /// Mark up to `limit` items, oldest failure first, as "requeued" with
+    /// one more retry counted, and return them. Items left "requeued" by a
+    /// retry that never finished are taken again.
+    pub async fn requeue(&self, limit: usize) -> Result<Vec<FailedItem>> {
+        let mut rows = self.all().await?;
+        rows.sort_by(|a, b| a.first_failed_at.cmp(&b.first_failed_at));
+        rows.truncate(limit);
+        for row in &mut rows {
+            row.status = "requeued".to_string();
+            row.retry_count += 1;
+        }
+        self.upsert(&rows).await?;
+        Ok(rows)
+    }
+
+    /// Drop the row for `paper_id`, after a retry stored the paper.
+    pub async fn resolve(&self, paper_id: uuid::Uuid) -> Result<()> {
+        self.open()
+            .await?
+            .delete(&format!("paper_id = '{}'", paper_id))
+            .await?;
+        Ok(())
+    }
+
+    async fn open(&self) -> Result<lancedb::Table> {
+        Ok(self
+            .db
+            .connection()
+            .open_table(TABLE_FAILED_ITEMS)
+            .execute()
+            .await?)
+    }
+
+    async fn all(&self) -> Result<Vec<FailedItem>> {
+        let mut stream = self.open().await?.query().execute().await?;
+        let mut rows = Vec::new();
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            for row in 0..batch.num_rows() {
+                rows.push(batch_to_item(&batch, row)?);
+            }
+        }
+        Ok(rows)
+    }
+
+    async fn upsert(&self, items: &[FailedItem]) -> Result<()> {
+        if items.is_empty() {
+            return Ok(());
+        }
+        let table = self.open().await?;
+        let batch = items_to_batch(items)?;
+        let schema = batch.schema();
+        let iter = arrow_array::RecordBatchIterator::new(vec![Ok(batch)], schema);
+        let mut builder = table.merge_insert(&["paper_id"]);
+        builder.when_matched_update_all(None);
+        builder.when_not_matched_insert_all();
+        builder.execute(Box::new(iter)).await?;
+        Ok(())
+    }
+}
+
+fn items_to_batch(items: &[FailedItem]) -> Result<RecordBatch> {
+    let strings = |f: fn(&FailedItem) -> Option<String>| -> Arc<dyn Array> {
+        Arc::new(StringArray::from(items.iter().map(f).collect::<Vec<_>>()))
+    };
+    let cols: Vec<Arc<dyn Array>> = vec![
+        strings(|i| Some(i.paper_id.to_string())),
+        strings(|i| i.job_id.map(|id| id.to_string())),
+        strings(|i| Some(i.title.clone())),
+        strings(|i| i.doi.clone()),
+        strings(|i| i.pmid.clone()),
+        strings(|i| Some(i.source.clone())),
+        strings(|i| Some(i.metadata.clone())),
+        strings(|i| Some(i.stage.clone())),
+        strings(|i| Some(i.error.clone())),
+        Arc::new(Int64Array::from(
+            items.iter().map(|i| i.retry_count).collect::<Vec<_>>(),
+        )),
+        strings(|i| Some(i.status.clone())),
+        strings(|i| Some(i.first_failed_at.to_rfc3339())),
+        strings(|i| Some(i.last_failed_at.to_rfc3339())),
+    ];
+    Ok(RecordBatch::try_new(failed_items_table_schema(), cols)?)
+}
+
+fn batch_to_item(batch: &RecordBatch, row: usize) -> Result<FailedItem> {
+    let (batch, row) = conform_row(batch, row, &failed_items_table_schema())?;
+    let batch: &RecordBatch = &batch;
+    let get_opt = |col: &str| -> Result<Option<String>> {
+        let arr = batch
+            .column_by_name(col)
+            .and_then(|a| a.as_any().downcast_ref::<StringArray>())
+            .ok_or_else(|| DbError::Arrow(format!("{col} is not StringArray")))?;
+        Ok((!arr.is_null(row)).then(|| arr.value(row).to_string()))
+    };
+    let get_s = |col: &str| -> Result<String> { Ok(get_opt(col)?.unwrap_or_default()) };
+    let parse_id =
+        |s: &str| uuid::Uuid::parse_str(s).map_err(|e| DbError::InvalidQuery(e.to_string()));
+    let parse_dt = |s: &str| {
+        chrono::DateTime::parse_from_rfc3339(s)
+            .map(|dt| dt.with_timezone(&chrono::Utc))
+            .map_err(|e| DbError::InvalidQuery(e.to_string()))
+    };
+    let retry_count = batch
+        .column_by_name("retry_count")
+        .and_then(|a| a.as_any().downcast_ref::<Int64Array>())
+        .ok_or_else(|| DbError::Arrow("retry_count is not Int64Array".to_string()))?
+        .value(row);
+
+    Ok(FailedItem {
+        paper_id: parse_id(&get_s("paper_id")?)?,
+        job_id: match get_opt("job_id")? {
+            Some(s) => Some(parse_id(&s)?),
+            None => None,
+        },
+        title: get_s("title")?,
+        doi: get_opt("doi")?,
+        pmid: get_opt("pmid")?,
+        source: get_s("source")?,
+        metadata: get_s("metadata")?,
+        stage: get_s("stage")?,
+        error: get_s("error")?,
+        retry_count,
+        status: get_s("status")?,
+        first_failed_at: parse_dt(&get_s("first_failed_at")?)?,
+        last_failed_at: parse_dt(&get_s("last_failed_at")?)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[tokio::test]
+    async fn failures_keep_their_history_until_resolved() {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-failed-{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        let repo = FailedItemRepository::new(db);
+
+        let at = |hour| {
+            chrono::Utc
+                .with_ymd_and_hms(2026, 5, 4, hour, 0, 0)
+                .unwrap()
+        };
+        let first = FailedItem {
+            paper_id: uuid::Uuid::new_v4(),
+            job_id: Some(uuid::Uuid::new_v4()),
+            title: "KRAS G12D organoid screen".to_string(),
+            doi: Some("10.1000/kras".to_string()),
+            pmid: Some("101".to_string()),
+            source: "pubmed".to_string(),
+            metadata: "{}".to_string(),
+            stage: "chunking".to_string(),
+            error: "panicked: index out of bounds".to_string(),
+            retry_count: 0,
+            status: "failed".to_string(),
+            first_failed_at: at(8),
+            last_failed_at: at(8),
+        };
+        let second = FailedItem {
+            paper_id: uuid::Uuid::new_v4(),
+            job_id: None,
+            pmid: Some("102".to_string()),
+            stage: "full_text".to_string(),
+            first_failed_at: at(9),
+            last_failed_at: at(9),
+            ..first.clone()
+        };
+        repo.record(&first).await.unwrap();
+        repo.record(&second).await.unwrap();
+        assert_eq!(repo.count().await.unwrap(), 2);
+
+        let requeued = repo.requeue(1).await.unwrap();
+        assert_eq!(requeued.len(), 1);
+        assert_eq!(requeued[0].paper_id, first.paper_id);
+        assert_eq!(requeued[0].retry_count, 1);
+        assert_eq!(requeued[0].status, "requeued");
+
+        // The retry failed again, later and at a different stage.
+        repo.record(&FailedItem {
+            stage: "chunk_insert".to_string(),
+            first_failed_at: at(11),
+            last_failed_at: at(11),
+            ..first.clone()
+        })
+        .await
+        .unwrap();
+        let stored = repo.find(first.paper_id).await.unwrap().unwrap();
+        assert_eq!(stored.stage, "chunk_insert");
+        assert_eq!(stored.retry_count, 1);
+        assert_eq!(stored.status, "failed");
+        assert_eq!(stored.first_failed_at, at(8));
+        assert_eq!(stored.last_failed_at, at(11));
+        assert_eq!(
+            repo.list(10)
+                .await
+                .unwrap()
+                .iter()
+                .map(|i| i.paper_id)
+                .collect::<Vec<_>>(),
+            vec![first.paper_id, second.paper_id]
+        );
+
+        repo.resolve(first.paper_id).await.unwrap();
+        assert_eq!(repo.count().await.unwrap(), 1);
+        assert_eq!(repo.find(first.paper_id).await.unwrap(), None);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
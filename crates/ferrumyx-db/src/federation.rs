@@ -1381,6 +1381,7 @@ async fn collect_table_counts(db: &Database) -> Result<BTreeMap<String, u64>> {
         crate::schema::TABLE_ENT_GTEX_EXPRESSION,
         crate::schema::TABLE_ENT_CHEMBL_TARGETS,
         crate::schema::TABLE_ENT_REACTOME_GENES,
+        crate::schema::TABLE_ENT_STRUCTURAL_ASSESSMENTS,
         crate::schema::TABLE_ENT_PROVIDER_REFRESH_RUNS,
     ];
 
@@ -0,0 +1,234 @@
+//! Ingestion job run repository.
+//!
+//! One row per run of a scheduled ingestion job, written when the run starts
+//! and rewritten when it finishes, so the last run of each schedule survives
+//! restarts.
+
+use crate::database::{ingestion_job_runs_table_schema, Database};
+use crate::error::{DbError, Result};
+use crate::schema::{IngestionJobRun, TABLE_INGESTION_JOB_RUNS};
+use crate::schema_evolution::conform_row;
+use std::sync::Arc;
+
+use arrow_array::{Array, Int64Array, RecordBatch, StringArray};
+use futures::StreamExt;
+use lancedb::query::{ExecutableQuery, QueryBase};
+
+/// Repository for ingestion job run operations.
+#[derive(Clone)]
+pub struct IngestionJobRunRepository {
+    db: Arc<Database>,
+}
+
+impl IngestionJobRunRepository {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Insert `run`, or replace the stored run with the same id.
+    pub async fn upsert(&self, run: &IngestionJobRun) -> Result<()> {
+        let table = self
+            .db
+            .connection()
+            .open_table(TABLE_INGESTION_JOB_RUNS)
+            .execute()
+            .await?;
+        let record = run_to_record(run)?;
+        let schema = record.schema();
+        let iter = arrow_array::RecordBatchIterator::new(vec![Ok(record)], schema);
+        let mut builder = table.merge_insert(&["id"]);
+        builder.when_matched_update_all(None);
+        builder.when_not_matched_insert_all();
+        builder.execute(Box::new(iter)).await?;
+        Ok(())
+    }
+
+    /// Runs of `schedule_id`, or of every schedule, newest first.
+    pub async fn list(
+        &self,
+        schedule_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<IngestionJobRun>> {
+        let table = self
+            .db
+            .connection()
+            .open_table(TABLE_INGESTION_JOB_RUNS)
+            .execute()
+            .await?;
+        let mut query = table.query();
+        if let Some(schedule_id) = schedule_id {
+            query = query.only_if(format!("schedule_id = '{}'", escape_sql(schedule_id)));
+        }
+        let mut stream = query.execute().await?;
+        let mut rows = Vec::new();
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            for row in 0..batch.num_rows() {
+                rows.push(record_to_run(&batch, row)?);
+            }
+        }
+        rows.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        rows.truncate(limit);
+        Ok(rows)
+    }
+
+    /// Most recent run of `schedule_id`.
+    pub async fn latest(&self, schedule_id: &str) -> Result<Option<IngestionJobRun>> {
+        Ok(self.list(Some(schedule_id), 1).await?.into_iter().next())
+    }
+
+    /// Mark runs still recorded as "running" as "interrupted". Called on
+    /// startup, since no run survives the process that started it.
+    pub async fn mark_interrupted(&self) -> Result<usize> {
+        let stale: Vec<IngestionJobRun> = self
+            .list(None, usize::MAX)
+            .await?
+            .into_iter()
+            .filter(|run| run.status == "running")
+            .collect();
+        for run in &stale {
+            let mut run = run.clone();
+            run.status = "interrupted".to_string();
+            self.upsert(&run).await?;
+        }
+        Ok(stale.len())
+    }
+}
+
+fn run_to_record(run: &IngestionJobRun) -> Result<RecordBatch> {
+    let cols: Vec<Arc<dyn Array>> = vec![
+        Arc::new(StringArray::from(vec![run.id.to_string()])),
+        Arc::new(StringArray::from(vec![run.schedule_id.clone()])),
+        Arc::new(StringArray::from(vec![run.trigger.clone()])),
+        Arc::new(StringArray::from(vec![run.status.clone()])),
+        Arc::new(StringArray::from(vec![run.started_at.to_rfc3339()])),
+        Arc::new(StringArray::from(vec![run
+            .finished_at
+            .map(|dt| dt.to_rfc3339())])),
+        Arc::new(Int64Array::from(vec![run.duration_ms])),
+        Arc::new(Int64Array::from(vec![run.papers_found])),
+        Arc::new(Int64Array::from(vec![run.papers_inserted])),
+        Arc::new(Int64Array::from(vec![run.papers_duplicate])),
+        Arc::new(Int64Array::from(vec![run.chunks_inserted])),
+        Arc::new(StringArray::from(vec![run.error.clone()])),
+    ];
+    Ok(RecordBatch::try_new(
+        ingestion_job_runs_table_schema(),
+        cols,
+    )?)
+}
+
+fn record_to_run(batch: &RecordBatch, row: usize) -> Result<IngestionJobRun> {
+    let (batch, row) = conform_row(batch, row, &ingestion_job_runs_table_schema())?;
+    let batch: &RecordBatch = &batch;
+    let get_s = |col: &str| -> Result<Option<String>> {
+        let idx = batch
+            .schema()
+            .index_of(col)
+            .map_err(|e| DbError::Arrow(e.to_string()))?;
+        let arr = batch
+            .column(idx)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .ok_or_else(|| DbError::Arrow(format!("{col} is not StringArray")))?;
+        Ok((!arr.is_null(row)).then(|| arr.value(row).to_string()))
+    };
+    let get_i = |col: &str| -> Result<i64> {
+        let idx = batch
+            .schema()
+            .index_of(col)
+            .map_err(|e| DbError::Arrow(e.to_string()))?;
+        Ok(batch
+            .column(idx)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .ok_or_else(|| DbError::Arrow(format!("{col} is not Int64Array")))?
+            .value(row))
+    };
+    let parse_dt = |s: &str| {
+        chrono::DateTime::parse_from_rfc3339(s)
+            .map(|dt| dt.with_timezone(&chrono::Utc))
+            .map_err(|e| DbError::InvalidQuery(e.to_string()))
+    };
+
+    let id = uuid::Uuid::parse_str(&get_s("id")?.unwrap_or_default())
+        .map_err(|e| DbError::InvalidQuery(e.to_string()))?;
+    let started_at = parse_dt(&get_s("started_at")?.unwrap_or_default())?;
+    let finished_at = match get_s("finished_at")? {
+        Some(s) => Some(parse_dt(&s)?),
+        None => None,
+    };
+
+    Ok(IngestionJobRun {
+        id,
+        schedule_id: get_s("schedule_id")?.unwrap_or_default(),
+        trigger: get_s("trigger")?.unwrap_or_default(),
+        status: get_s("status")?.unwrap_or_default(),
+        started_at,
+        finished_at,
+        duration_ms: get_i("duration_ms")?,
+        papers_found: get_i("papers_found")?,
+        papers_inserted: get_i("papers_inserted")?,
+        papers_duplicate: get_i("papers_duplicate")?,
+        chunks_inserted: get_i("chunks_inserted")?,
+        error: get_s("error")?,
+    })
+}
+
+fn escape_sql(input: &str) -> String {
+    input.replace('\'', "''")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[tokio::test]
+    async fn finished_run_replaces_its_running_row() {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-job-runs-{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        let repo = IngestionJobRunRepository::new(db);
+
+        let started_at = chrono::Utc.with_ymd_and_hms(2026, 3, 1, 6, 0, 0).unwrap();
+        let mut run =
+            IngestionJobRun::started("kras-pdac".to_string(), "schedule".to_string(), started_at);
+        repo.upsert(&run).await.unwrap();
+        let older = IngestionJobRun::started(
+            "kras-pdac".to_string(),
+            "manual".to_string(),
+            started_at - chrono::Duration::days(1),
+        );
+        repo.upsert(&older).await.unwrap();
+        let other =
+            IngestionJobRun::started("egfr-nsclc".to_string(), "schedule".to_string(), started_at);
+        repo.upsert(&other).await.unwrap();
+
+        run.status = "succeeded".to_string();
+        run.finished_at = Some(started_at + chrono::Duration::seconds(90));
+        run.duration_ms = 90_000;
+        run.papers_found = 40;
+        run.papers_inserted = 7;
+        run.papers_duplicate = 33;
+        repo.upsert(&run).await.unwrap();
+
+        assert_eq!(repo.latest("kras-pdac").await.unwrap(), Some(run.clone()));
+        assert_eq!(
+            repo.list(Some("kras-pdac"), 10).await.unwrap(),
+            vec![run, older.clone()]
+        );
+        assert_eq!(repo.list(None, 10).await.unwrap().len(), 3);
+
+        assert_eq!(repo.mark_interrupted().await.unwrap(), 2);
+        let older = repo
+            .list(Some("kras-pdac"), 10)
+            .await
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!(older.status, "interrupted");
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,222 @@
+//! Ingestion job repository.
+//!
+//! Finished web/API ingestion jobs, so the job list survives restarts.
+
+use crate::database::{ingestion_jobs_table_schema, Database};
+use crate::error::{DbError, Result};
+use crate::schema::{IngestionJobRecord, TABLE_INGESTION_JOBS};
+use crate::schema_evolution::conform_row;
+use std::sync::Arc;
+
+use arrow_array::{Array, Int64Array, RecordBatch, StringArray};
+use futures::StreamExt;
+use lancedb::query::{ExecutableQuery, QueryBase};
+
+/// Repository for ingestion job operations.
+#[derive(Clone)]
+pub struct IngestionJobRecordRepository {
+    db: Arc<Database>,
+}
+
+impl IngestionJobRecordRepository {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Insert `job`, or replace the stored job with the same id.
+    pub async fn upsert(&self, job: &IngestionJobRecord) -> Result<()> {
+        let table = self
+            .db
+            .connection()
+            .open_table(TABLE_INGESTION_JOBS)
+            .execute()
+            .await?;
+        let record = job_to_record(job)?;
+        let schema = record.schema();
+        let iter = arrow_array::RecordBatchIterator::new(vec![Ok(record)], schema);
+        let mut builder = table.merge_insert(&["id"]);
+        builder.when_matched_update_all(None);
+        builder.when_not_matched_insert_all();
+        builder.execute(Box::new(iter)).await?;
+        Ok(())
+    }
+
+    pub async fn find_by_id(&self, id: uuid::Uuid) -> Result<Option<IngestionJobRecord>> {
+        let table = self
+            .db
+            .connection()
+            .open_table(TABLE_INGESTION_JOBS)
+            .execute()
+            .await?;
+        let mut stream = table
+            .query()
+            .only_if(format!("id = '{}'", id))
+            .limit(1)
+            .execute()
+            .await?;
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            if batch.num_rows() > 0 {
+                return Ok(Some(record_to_job(&batch, 0)?));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Most recently started jobs first.
+    pub async fn list(&self, limit: usize) -> Result<Vec<IngestionJobRecord>> {
+        let table = self
+            .db
+            .connection()
+            .open_table(TABLE_INGESTION_JOBS)
+            .execute()
+            .await?;
+        let mut stream = table.query().execute().await?;
+        let mut rows = Vec::new();
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            for row in 0..batch.num_rows() {
+                rows.push(record_to_job(&batch, row)?);
+            }
+        }
+        rows.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        rows.truncate(limit);
+        Ok(rows)
+    }
+}
+
+fn job_to_record(job: &IngestionJobRecord) -> Result<RecordBatch> {
+    let cols: Vec<Arc<dyn Array>> = vec![
+        Arc::new(StringArray::from(vec![job.id.to_string()])),
+        Arc::new(StringArray::from(vec![job.gene.clone()])),
+        Arc::new(StringArray::from(vec![job.mutation.clone()])),
+        Arc::new(StringArray::from(vec![job.cancer_type.clone()])),
+        Arc::new(StringArray::from(vec![job.sources.clone()])),
+        Arc::new(Int64Array::from(vec![job.max_results])),
+        Arc::new(StringArray::from(vec![job.status.clone()])),
+        Arc::new(StringArray::from(vec![job.stage.clone()])),
+        Arc::new(Int64Array::from(vec![job.papers_found])),
+        Arc::new(Int64Array::from(vec![job.papers_inserted])),
+        Arc::new(Int64Array::from(vec![job.papers_duplicate])),
+        Arc::new(Int64Array::from(vec![job.chunks_inserted])),
+        Arc::new(StringArray::from(vec![job.started_at.to_rfc3339()])),
+        Arc::new(StringArray::from(vec![job
+            .finished_at
+            .map(|dt| dt.to_rfc3339())])),
+        Arc::new(StringArray::from(vec![job.error.clone()])),
+    ];
+    Ok(RecordBatch::try_new(ingestion_jobs_table_schema(), cols)?)
+}
+
+fn record_to_job(batch: &RecordBatch, row: usize) -> Result<IngestionJobRecord> {
+    let (batch, row) = conform_row(batch, row, &ingestion_jobs_table_schema())?;
+    let batch: &RecordBatch = &batch;
+    let get_s = |col: &str| -> Result<Option<String>> {
+        let idx = batch
+            .schema()
+            .index_of(col)
+            .map_err(|e| DbError::Arrow(e.to_string()))?;
+        let arr = batch
+            .column(idx)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .ok_or_else(|| DbError::Arrow(format!("{col} is not StringArray")))?;
+        Ok((!arr.is_null(row)).then(|| arr.value(row).to_string()))
+    };
+    let get_i = |col: &str| -> Result<i64> {
+        let idx = batch
+            .schema()
+            .index_of(col)
+            .map_err(|e| DbError::Arrow(e.to_string()))?;
+        Ok(batch
+            .column(idx)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .ok_or_else(|| DbError::Arrow(format!("{col} is not Int64Array")))?
+            .value(row))
+    };
+    let parse_dt = |s: &str| {
+        chrono::DateTime::parse_from_rfc3339(s)
+            .map(|dt| dt.with_timezone(&chrono::Utc))
+            .map_err(|e| DbError::InvalidQuery(e.to_string()))
+    };
+
+    let id = uuid::Uuid::parse_str(&get_s("id")?.unwrap_or_default())
+        .map_err(|e| DbError::InvalidQuery(e.to_string()))?;
+    let started_at = parse_dt(&get_s("started_at")?.unwrap_or_default())?;
+    let finished_at = match get_s("finished_at")? {
+        Some(s) => Some(parse_dt(&s)?),
+        None => None,
+    };
+
+    Ok(IngestionJobRecord {
+        id,
+        gene: get_s("gene")?.unwrap_or_default(),
+        mutation: get_s("mutation")?,
+        cancer_type: get_s("cancer_type")?.unwrap_or_default(),
+        sources: get_s("sources")?.unwrap_or_default(),
+        max_results: get_i("max_results")?,
+        status: get_s("status")?.unwrap_or_default(),
+        stage: get_s("stage")?.unwrap_or_default(),
+        papers_found: get_i("papers_found")?,
+        papers_inserted: get_i("papers_inserted")?,
+        papers_duplicate: get_i("papers_duplicate")?,
+        chunks_inserted: get_i("chunks_inserted")?,
+        started_at,
+        finished_at,
+        error: get_s("error")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[tokio::test]
+    async fn finished_jobs_round_trip_newest_first() {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-jobs-{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        let repo = IngestionJobRecordRepository::new(db);
+
+        let started_at = chrono::Utc.with_ymd_and_hms(2026, 4, 2, 8, 0, 0).unwrap();
+        let older = IngestionJobRecord {
+            id: uuid::Uuid::new_v4(),
+            gene: "KRAS".to_string(),
+            mutation: Some("G12D".to_string()),
+            cancer_type: "pancreatic cancer".to_string(),
+            sources: "pubmed,europepmc".to_string(),
+            max_results: 100,
+            status: "succeeded".to_string(),
+            stage: "complete".to_string(),
+            papers_found: 80,
+            papers_inserted: 12,
+            papers_duplicate: 68,
+            chunks_inserted: 140,
+            started_at,
+            finished_at: Some(started_at + chrono::Duration::minutes(4)),
+            error: None,
+        };
+        let newer = IngestionJobRecord {
+            id: uuid::Uuid::new_v4(),
+            mutation: None,
+            status: "cancelled".to_string(),
+            stage: "cancelled".to_string(),
+            started_at: started_at + chrono::Duration::hours(1),
+            finished_at: None,
+            ..older.clone()
+        };
+        repo.upsert(&older).await.unwrap();
+        repo.upsert(&newer).await.unwrap();
+
+        assert_eq!(
+            repo.find_by_id(older.id).await.unwrap(),
+            Some(older.clone())
+        );
+        assert_eq!(repo.list(10).await.unwrap(), vec![newer, older]);
+        assert_eq!(repo.find_by_id(uuid::Uuid::new_v4()).await.unwrap(), None);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
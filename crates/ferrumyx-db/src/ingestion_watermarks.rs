@@ -0,0 +1,198 @@
+//! Ingestion watermark repository.
+//!
+//! One row per (query, source) recording when the last ingestion run for it
+//! finished, so incremental runs can bound their searches by date.
+
+use crate::database::{ingestion_watermarks_table_schema, Database};
+use crate::error::{DbError, Result};
+use crate::schema::{IngestionWatermark, TABLE_INGESTION_WATERMARKS};
+use crate::schema_evolution::conform_row;
+use std::sync::Arc;
+
+use arrow_array::{Array, Int64Array, RecordBatch, StringArray};
+use futures::StreamExt;
+use lancedb::query::{ExecutableQuery, QueryBase};
+
+/// Repository for ingestion watermark operations.
+#[derive(Clone)]
+pub struct IngestionWatermarkRepository {
+    db: Arc<Database>,
+}
+
+impl IngestionWatermarkRepository {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Watermark of `query` on `source`, if a run has recorded one.
+    pub async fn find(&self, query: &str, source: &str) -> Result<Option<IngestionWatermark>> {
+        let table = self
+            .db
+            .connection()
+            .open_table(TABLE_INGESTION_WATERMARKS)
+            .execute()
+            .await?;
+        let filter = format!(
+            "query = '{}' AND source = '{}'",
+            escape_sql(query),
+            escape_sql(source)
+        );
+        let mut stream = table.query().only_if(&filter).limit(1).execute().await?;
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            if batch.num_rows() > 0 {
+                return Ok(Some(record_to_watermark(&batch, 0)?));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Insert or replace the watermark of `watermark.query` on
+    /// `watermark.source`.
+    pub async fn upsert(&self, watermark: &IngestionWatermark) -> Result<()> {
+        let table = self
+            .db
+            .connection()
+            .open_table(TABLE_INGESTION_WATERMARKS)
+            .execute()
+            .await?;
+        let record = watermark_to_record(watermark)?;
+        let schema = record.schema();
+        let iter = arrow_array::RecordBatchIterator::new(vec![Ok(record)], schema);
+        let mut builder = table.merge_insert(&["query", "source"]);
+        builder.when_matched_update_all(None);
+        builder.when_not_matched_insert_all();
+        builder.execute(Box::new(iter)).await?;
+        Ok(())
+    }
+
+    /// Every stored watermark, most recent run first.
+    pub async fn list(&self) -> Result<Vec<IngestionWatermark>> {
+        let table = self
+            .db
+            .connection()
+            .open_table(TABLE_INGESTION_WATERMARKS)
+            .execute()
+            .await?;
+        let mut stream = table.query().execute().await?;
+        let mut rows = Vec::new();
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            for row in 0..batch.num_rows() {
+                rows.push(record_to_watermark(&batch, row)?);
+            }
+        }
+        rows.sort_by(|a, b| b.last_run_at.cmp(&a.last_run_at));
+        Ok(rows)
+    }
+}
+
+fn watermark_to_record(watermark: &IngestionWatermark) -> Result<RecordBatch> {
+    let cols: Vec<Arc<dyn Array>> = vec![
+        Arc::new(StringArray::from(vec![watermark.id.to_string()])),
+        Arc::new(StringArray::from(vec![watermark.query.clone()])),
+        Arc::new(StringArray::from(vec![watermark.source.clone()])),
+        Arc::new(StringArray::from(vec![watermark.last_run_at.to_rfc3339()])),
+        Arc::new(StringArray::from(vec![watermark
+            .last_seen_publication_date
+            .map(|d| d.to_string())])),
+        Arc::new(Int64Array::from(vec![watermark.paper_count])),
+    ];
+    Ok(RecordBatch::try_new(
+        ingestion_watermarks_table_schema(),
+        cols,
+    )?)
+}
+
+fn record_to_watermark(batch: &RecordBatch, row: usize) -> Result<IngestionWatermark> {
+    let (batch, row) = conform_row(batch, row, &ingestion_watermarks_table_schema())?;
+    let batch: &RecordBatch = &batch;
+    let get_s = |col: &str| -> Result<Option<String>> {
+        let idx = batch
+            .schema()
+            .index_of(col)
+            .map_err(|e| DbError::Arrow(e.to_string()))?;
+        let arr = batch
+            .column(idx)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .ok_or_else(|| DbError::Arrow(format!("{col} is not StringArray")))?;
+        Ok((!arr.is_null(row)).then(|| arr.value(row).to_string()))
+    };
+    let idx = batch
+        .schema()
+        .index_of("paper_count")
+        .map_err(|e| DbError::Arrow(e.to_string()))?;
+    let paper_count = batch
+        .column(idx)
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .ok_or_else(|| DbError::Arrow("paper_count is not Int64Array".to_string()))?
+        .value(row);
+
+    let id = uuid::Uuid::parse_str(&get_s("id")?.unwrap_or_default())
+        .map_err(|e| DbError::InvalidQuery(e.to_string()))?;
+    let last_run_at =
+        chrono::DateTime::parse_from_rfc3339(&get_s("last_run_at")?.unwrap_or_default())
+            .map(|dt| dt.with_timezone(&chrono::Utc))
+            .map_err(|e| DbError::InvalidQuery(e.to_string()))?;
+    let last_seen_publication_date = get_s("last_seen_publication_date")?
+        .and_then(|d| chrono::NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok());
+
+    Ok(IngestionWatermark {
+        id,
+        query: get_s("query")?.unwrap_or_default(),
+        source: get_s("source")?.unwrap_or_default(),
+        last_run_at,
+        last_seen_publication_date,
+        paper_count,
+    })
+}
+
+fn escape_sql(input: &str) -> String {
+    input.replace('\'', "''")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[tokio::test]
+    async fn upsert_replaces_the_watermark_of_a_query_and_source() {
+        let dir =
+            std::env::temp_dir().join(format!("ferrumyx-watermarks-{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        let repo = IngestionWatermarkRepository::new(db);
+
+        let query = "KRAS AND 'pancreatic cancer'";
+        assert_eq!(repo.find(query, "pubmed").await.unwrap(), None);
+
+        let mut first = IngestionWatermark::new(
+            query.to_string(),
+            "pubmed".to_string(),
+            chrono::Utc.with_ymd_and_hms(2026, 1, 5, 9, 0, 0).unwrap(),
+        );
+        first.paper_count = 12;
+        repo.upsert(&first).await.unwrap();
+        let other = IngestionWatermark::new(
+            query.to_string(),
+            "europepmc".to_string(),
+            first.last_run_at,
+        );
+        repo.upsert(&other).await.unwrap();
+
+        let mut second = first.clone();
+        second.last_run_at = chrono::Utc.with_ymd_and_hms(2026, 2, 1, 9, 0, 0).unwrap();
+        second.last_seen_publication_date = chrono::NaiveDate::from_ymd_opt(2026, 1, 30);
+        second.paper_count = 3;
+        repo.upsert(&second).await.unwrap();
+
+        assert_eq!(repo.find(query, "pubmed").await.unwrap(), Some(second));
+        assert_eq!(repo.find(query, "europepmc").await.unwrap(), Some(other));
+        assert_eq!(repo.list().await.unwrap().len(), 2);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
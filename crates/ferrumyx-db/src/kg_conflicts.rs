@@ -5,7 +5,7 @@
 use crate::database::Database;
 use crate::error::Result;
 use crate::schema::KgConflict;
-use crate::schema_arrow::record_to_kg_conflict;
+use crate::schema_arrow::{kg_conflict_to_record, record_to_kg_conflict};
 use futures::StreamExt;
 use lancedb::query::{ExecutableQuery, QueryBase};
 use std::collections::HashSet;
@@ -22,6 +22,77 @@ impl KgConflictRepository {
         Self { db }
     }
 
+    /// Insert conflicts, replacing rows with the same id. Ids derive from
+    /// the fact pair, so re-detecting a conflict refreshes it in place.
+    pub async fn upsert_batch(&self, conflicts: &[KgConflict]) -> Result<usize> {
+        if conflicts.is_empty() {
+            return Ok(0);
+        }
+
+        let table = self
+            .db
+            .connection()
+            .open_table(crate::schema::TABLE_KG_CONFLICTS)
+            .execute()
+            .await?;
+
+        let records: Vec<arrow_array::RecordBatch> = conflicts
+            .iter()
+            .map(kg_conflict_to_record)
+            .collect::<Result<_>>()?;
+        let schema = records[0].schema();
+        let iter = arrow_array::RecordBatchIterator::new(records.into_iter().map(Ok), schema);
+
+        let mut builder = table.merge_insert(&["id"]);
+        builder.when_matched_update_all(None);
+        builder.when_not_matched_insert_all();
+        builder.execute(Box::new(iter)).await?;
+
+        Ok(conflicts.len())
+    }
+
+    /// Count all conflicts.
+    pub async fn count(&self) -> Result<u64> {
+        let table = self
+            .db
+            .connection()
+            .open_table(crate::schema::TABLE_KG_CONFLICTS)
+            .execute()
+            .await?;
+        Ok(table.count_rows(None).await? as u64)
+    }
+
+    /// List conflicts with pagination, optionally of one severity.
+    pub async fn list(
+        &self,
+        severity: Option<&str>,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<KgConflict>> {
+        let table = self
+            .db
+            .connection()
+            .open_table(crate::schema::TABLE_KG_CONFLICTS)
+            .execute()
+            .await?;
+
+        let mut query = table.query().limit(limit).offset(offset);
+        if let Some(severity) = severity {
+            query = query.only_if(format!("severity = '{}'", severity.replace('\'', "''")));
+        }
+        let mut stream = query.execute().await?;
+
+        let mut conflicts = Vec::new();
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            for i in 0..batch.num_rows() {
+                conflicts.push(record_to_kg_conflict(&batch, i)?);
+            }
+        }
+
+        Ok(conflicts)
+    }
+
     /// Find conflict by fact_a_id or fact_b_id.
     pub async fn find_by_fact_id(&self, fact_id: uuid::Uuid) -> Result<Vec<KgConflict>> {
         let table = self
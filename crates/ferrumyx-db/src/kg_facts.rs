@@ -1,17 +1,150 @@
 //! Knowledge graph facts repository.
 //!
 //! Provides CRUD operations for KG facts (subject-predicate-object triples).
+//!
+//! [`KgFactRepository::upsert_batch`] keeps one row per
+//! (subject_id, predicate, object_id) triple: the same fact from another
+//! paper is added to the row's supporting evidence instead of becoming a
+//! row of its own. Each paper's confidence is scaled by its
+//! [`evidence_decay`] weight, so a triple backed only by old papers counts
+//! for less than one backed by recent ones.
 
 use crate::database::Database;
 use crate::error::Result;
-use crate::schema::KgFact;
+use crate::papers::PaperRepository;
+use crate::schema::{FactSupport, KgFact};
 use crate::schema_arrow::{kg_fact_to_record, record_to_kg_fact};
 use arrow_array::Array;
+use ferrumyx_common::confidence::{evidence_decay, EvidenceDecay};
 use futures::StreamExt;
 use lancedb::query::{ExecutableQuery, QueryBase};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+/// Cap on a merged triple's confidence; agreeing papers never make a fact
+/// certain.
+pub const MAX_AGGREGATE_CONFIDENCE: f32 = 0.99;
+
+type TripleKey = (uuid::Uuid, String, uuid::Uuid);
+
+fn triple_key(fact: &KgFact) -> TripleKey {
+    (fact.subject_id, fact.predicate.clone(), fact.object_id)
+}
+
+/// Distinct triples about one subject and the papers behind them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FactCounts {
+    pub triples: u32,
+    pub support: u32,
+}
+
+/// Every paper backing `fact`; a row that was never merged is backed by its
+/// own paper.
+pub fn fact_supports(fact: &KgFact) -> Vec<FactSupport> {
+    if !fact.supporting_evidence.is_empty() {
+        return fact.supporting_evidence.clone();
+    }
+    vec![FactSupport {
+        paper_id: fact.paper_id,
+        confidence: fact.confidence,
+        evidence: fact.evidence.clone(),
+        chunk_id: fact.chunk_id,
+        published_at: None,
+    }]
+}
+
+/// `1 - Π(1 - cᵢ)`, capped at [`MAX_AGGREGATE_CONFIDENCE`].
+pub fn aggregate_confidence(confidences: impl IntoIterator<Item = f32>) -> f32 {
+    let doubt: f32 = confidences
+        .into_iter()
+        .map(|c| 1.0 - c.clamp(0.0, 1.0))
+        .product();
+    (1.0 - doubt).min(MAX_AGGREGATE_CONFIDENCE)
+}
+
+/// Fold rows of one triple into the first: supports are collected once per
+/// paper, and the confidence becomes their [`aggregate_confidence`] with
+/// each paper weighted by the process-wide [`evidence_decay`].
+pub fn merge_facts(facts: impl IntoIterator<Item = KgFact>) -> Option<KgFact> {
+    merge_facts_with(facts, evidence_decay())
+}
+
+/// [`merge_facts`] under an explicit `decay`.
+pub fn merge_facts_with(
+    facts: impl IntoIterator<Item = KgFact>,
+    decay: EvidenceDecay,
+) -> Option<KgFact> {
+    let mut facts = facts.into_iter();
+    let mut merged = facts.next()?;
+    let mut supports = fact_supports(&merged);
+    for fact in facts {
+        for support in fact_supports(&fact) {
+            if supports.iter().all(|s| s.paper_id != support.paper_id) {
+                supports.push(support);
+            }
+        }
+    }
+    reweigh(&mut merged, supports, decay);
+    Some(merged)
+}
+
+/// Set `fact`'s support and confidence from `supports`.
+///
+/// A lone support of the row's own paper that decay leaves whole folds back
+/// into the row. Otherwise the supports are kept with their raw confidences,
+/// so merging the row again never decays a paper twice.
+fn reweigh(fact: &mut KgFact, supports: Vec<FactSupport>, decay: EvidenceDecay) {
+    let weighted: Vec<f32> = supports
+        .iter()
+        .map(|s| s.confidence * decay.weight(s.published_at) as f32)
+        .collect();
+    fact.support_count = supports.len() as i64;
+    match (supports.as_slice(), weighted.as_slice()) {
+        ([only], &[confidence])
+            if confidence == only.confidence && only.paper_id == fact.paper_id =>
+        {
+            fact.confidence = confidence;
+            fact.supporting_evidence = Vec::new();
+            return;
+        }
+        (_, &[confidence]) => fact.confidence = confidence,
+        _ => fact.confidence = aggregate_confidence(weighted),
+    }
+    fact.supporting_evidence = supports;
+}
+
+/// `fact` without the support of the `removed` papers, re-weighted over the
+/// papers left; `None` once no paper backs it.
+pub fn without_papers(fact: &KgFact, removed: &HashSet<uuid::Uuid>) -> Option<KgFact> {
+    let kept: Vec<FactSupport> = fact_supports(fact)
+        .into_iter()
+        .filter(|s| !removed.contains(&s.paper_id))
+        .collect();
+    let first = kept.first()?.clone();
+    let mut fact = fact.clone();
+    if removed.contains(&fact.paper_id) {
+        fact.paper_id = first.paper_id;
+        fact.evidence = first.evidence.clone();
+        fact.chunk_id = first.chunk_id;
+        fact.evidence_start = None;
+        fact.evidence_end = None;
+    }
+    reweigh(&mut fact, kept, evidence_decay());
+    Some(fact)
+}
+
+/// Facts [`KgFactRepository::retract_papers`] changed.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct FactRetraction {
+    /// Facts no remaining paper backs.
+    pub deleted: u64,
+    /// Facts re-weighted over their remaining papers.
+    pub updated: u64,
+    /// Subjects of the deleted and updated facts, i.e. the entities whose
+    /// scores they fed.
+    pub subject_ids: Vec<uuid::Uuid>,
+}
+
 /// Repository for knowledge graph fact operations.
 #[derive(Clone)]
 pub struct KgFactRepository {
@@ -65,6 +198,136 @@ impl KgFactRepository {
         Ok(())
     }
 
+    /// Insert one fact, merging it into the stored row of its triple.
+    pub async fn upsert(&self, fact: &KgFact) -> Result<()> {
+        self.upsert_batch(std::slice::from_ref(fact)).await?;
+        Ok(())
+    }
+
+    /// Insert `facts`, merging each into the stored row of the same
+    /// (subject_id, predicate, object_id) triple with [`merge_facts`].
+    /// Duplicate rows left by earlier inserts are folded in and removed.
+    /// Returns the number of triples written.
+    pub async fn upsert_batch(&self, facts: &[KgFact]) -> Result<usize> {
+        if facts.is_empty() {
+            return Ok(0);
+        }
+
+        let mut index: HashMap<TripleKey, usize> = HashMap::new();
+        let mut groups: Vec<(Vec<KgFact>, Vec<KgFact>)> = Vec::new();
+        for fact in facts {
+            let i = *index.entry(triple_key(fact)).or_insert_with(|| {
+                groups.push((Vec::new(), Vec::new()));
+                groups.len() - 1
+            });
+            groups[i].1.push(fact.clone());
+        }
+        let subject_ids: Vec<uuid::Uuid> = facts.iter().map(|f| f.subject_id).collect();
+        for stored in self.find_by_subject_ids(&subject_ids, 32).await? {
+            if let Some(&i) = index.get(&triple_key(&stored)) {
+                groups[i].0.push(stored);
+            }
+        }
+        let mut rows: Vec<&mut KgFact> = groups
+            .iter_mut()
+            .flat_map(|(stored, incoming)| stored.iter_mut().chain(incoming.iter_mut()))
+            .collect();
+        self.date_supports(&mut rows).await?;
+
+        let mut merged = Vec::with_capacity(groups.len());
+        let mut stale: HashSet<uuid::Uuid> = HashSet::new();
+        for (stored, incoming) in groups {
+            stale.extend(stored.iter().map(|f| f.id));
+            if let Some(fact) = merge_facts(stored.into_iter().chain(incoming)) {
+                merged.push(fact);
+            }
+        }
+        for fact in &merged {
+            stale.remove(&fact.id);
+        }
+
+        let table = self
+            .db
+            .connection()
+            .open_table(crate::schema::TABLE_KG_FACTS)
+            .execute()
+            .await?;
+        let records: Vec<arrow_array::RecordBatch> = merged
+            .iter()
+            .map(kg_fact_to_record)
+            .collect::<Result<_>>()?;
+        let schema = records[0].schema();
+        let iter = arrow_array::RecordBatchIterator::new(records.into_iter().map(Ok), schema);
+        let mut builder = table.merge_insert(&["id"]);
+        builder.when_matched_update_all(None);
+        builder.when_not_matched_insert_all();
+        builder.execute(Box::new(iter)).await?;
+
+        if !stale.is_empty() {
+            let ids = stale
+                .iter()
+                .map(|id| format!("'{}'", id))
+                .collect::<Vec<_>>()
+                .join(",");
+            table.delete(&format!("id IN ({})", ids)).await?;
+        }
+        Ok(merged.len())
+    }
+
+    /// The triple with every paper backing it, folding rows that were
+    /// inserted without [`Self::upsert_batch`].
+    pub async fn get_fact_with_evidence(
+        &self,
+        subject_id: uuid::Uuid,
+        predicate: &str,
+        object_id: uuid::Uuid,
+    ) -> Result<Option<(KgFact, Vec<FactSupport>)>> {
+        let mut rows: Vec<KgFact> = self
+            .find_by_subject_and_predicate(subject_id, predicate)
+            .await?
+            .into_iter()
+            .filter(|f| f.object_id == object_id)
+            .collect();
+        self.date_supports(&mut rows.iter_mut().collect::<Vec<_>>())
+            .await?;
+        Ok(merge_facts(rows).map(|fact| {
+            let supports = fact_supports(&fact);
+            (fact, supports)
+        }))
+    }
+
+    /// Copy each paper's publication date into the supports of `facts` that
+    /// lack one, so merges can decay them. Does nothing while decay is off.
+    async fn date_supports(&self, facts: &mut [&mut KgFact]) -> Result<()> {
+        if !evidence_decay().is_enabled() {
+            return Ok(());
+        }
+        let undated: Vec<uuid::Uuid> = facts
+            .iter()
+            .flat_map(|f| fact_supports(f))
+            .filter(|s| s.published_at.is_none())
+            .map(|s| s.paper_id)
+            .collect();
+        if undated.is_empty() {
+            return Ok(());
+        }
+        let published = PaperRepository::new(self.db.clone())
+            .find_published_at_by_ids(&undated)
+            .await?;
+        for fact in facts.iter_mut() {
+            let mut supports = fact_supports(fact);
+            let mut dated = false;
+            for support in supports.iter_mut().filter(|s| s.published_at.is_none()) {
+                support.published_at = published.get(&support.paper_id).copied();
+                dated |= support.published_at.is_some();
+            }
+            if dated {
+                fact.supporting_evidence = supports;
+            }
+        }
+        Ok(())
+    }
+
     /// Find a fact by ID.
     pub async fn find_by_id(&self, id: uuid::Uuid) -> Result<Option<KgFact>> {
         let table = self
@@ -315,6 +578,89 @@ impl KgFactRepository {
         Ok(())
     }
 
+    /// Withdraw the support of `paper_ids` from every fact: facts no other
+    /// paper backs are deleted, the rest re-weighted with
+    /// [`without_papers`]. With `dry_run` nothing is written.
+    pub async fn retract_papers(
+        &self,
+        paper_ids: &[uuid::Uuid],
+        dry_run: bool,
+    ) -> Result<FactRetraction> {
+        let mut retraction = FactRetraction::default();
+        if paper_ids.is_empty() {
+            return Ok(retraction);
+        }
+        let removed: HashSet<uuid::Uuid> = paper_ids.iter().copied().collect();
+        let mut uniq: Vec<uuid::Uuid> = removed.iter().copied().collect();
+        uniq.sort_unstable();
+
+        let table = self
+            .db
+            .connection()
+            .open_table(crate::schema::TABLE_KG_FACTS)
+            .execute()
+            .await?;
+
+        // Merged rows name their papers only inside supporting_evidence.
+        let mut affected: HashMap<uuid::Uuid, KgFact> = HashMap::new();
+        for group in uniq.chunks(64) {
+            let filter = group
+                .iter()
+                .map(|id| format!("paper_id = '{id}' OR supporting_evidence LIKE '%{id}%'"))
+                .collect::<Vec<_>>()
+                .join(" OR ");
+            let mut stream = table.query().only_if(&filter).execute().await?;
+            while let Some(batch) = stream.next().await {
+                let batch = batch?;
+                for i in 0..batch.num_rows() {
+                    let fact = record_to_kg_fact(&batch, i)?;
+                    affected.insert(fact.id, fact);
+                }
+            }
+        }
+
+        let mut affected: Vec<KgFact> = affected.into_values().collect();
+        self.date_supports(&mut affected.iter_mut().collect::<Vec<_>>())
+            .await?;
+
+        let mut stale = Vec::new();
+        let mut updated = Vec::new();
+        let mut subjects = std::collections::BTreeSet::new();
+        for fact in affected {
+            if !fact_supports(&fact)
+                .iter()
+                .any(|s| removed.contains(&s.paper_id))
+            {
+                continue;
+            }
+            subjects.insert(fact.subject_id);
+            match without_papers(&fact, &removed) {
+                Some(fact) => updated.push(fact),
+                None => stale.push(fact.id.to_string()),
+            }
+        }
+        retraction.deleted = stale.len() as u64;
+        retraction.updated = updated.len() as u64;
+        retraction.subject_ids = subjects.into_iter().collect();
+        if dry_run {
+            return Ok(retraction);
+        }
+
+        crate::maintenance::delete_in(&table, "id", &stale).await?;
+        if !updated.is_empty() {
+            let records: Vec<arrow_array::RecordBatch> = updated
+                .iter()
+                .map(kg_fact_to_record)
+                .collect::<Result<_>>()?;
+            let schema = records[0].schema();
+            let iter = arrow_array::RecordBatchIterator::new(records.into_iter().map(Ok), schema);
+            let mut builder = table.merge_insert(&["id"]);
+            builder.when_matched_update_all(None);
+            builder.execute(Box::new(iter)).await?;
+        }
+        Ok(retraction)
+    }
+
     /// Delete all facts for a paper.
     pub async fn delete_by_paper_id(&self, paper_id: uuid::Uuid) -> Result<()> {
         let table = self
@@ -515,4 +861,147 @@ impl KgFactRepository {
         }
         Ok(out)
     }
+
+    /// Distinct triples and supporting papers per subject_id for a bounded
+    /// set of subjects.
+    pub async fn fact_counts_by_subject_ids(
+        &self,
+        subject_ids: &[uuid::Uuid],
+        chunk_size: usize,
+    ) -> Result<HashMap<uuid::Uuid, FactCounts>> {
+        let mut triples: HashMap<TripleKey, u32> = HashMap::new();
+        for fact in self.find_by_subject_ids(subject_ids, chunk_size).await? {
+            let support = triples.entry(triple_key(&fact)).or_insert(0);
+            *support = (*support).saturating_add(fact.support_count.max(1) as u32);
+        }
+        let mut out: HashMap<uuid::Uuid, FactCounts> = HashMap::new();
+        for ((subject_id, _, _), support) in triples {
+            let counts = out.entry(subject_id).or_default();
+            counts.triples += 1;
+            counts.support += support;
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fact(paper_id: uuid::Uuid, confidence: f32) -> KgFact {
+        let mut fact = KgFact::new(
+            paper_id,
+            uuid::Uuid::from_u128(1),
+            "KRAS".to_string(),
+            "associated_with".to_string(),
+            uuid::Uuid::from_u128(2),
+            "PAAD".to_string(),
+        );
+        fact.confidence = confidence;
+        fact
+    }
+
+    #[test]
+    fn merging_accumulates_papers_and_confidence() {
+        let papers: Vec<uuid::Uuid> = (10..13).map(uuid::Uuid::from_u128).collect();
+        let first = fact(papers[0], 0.5);
+        let merged = merge_facts([
+            first.clone(),
+            fact(papers[1], 0.5),
+            fact(papers[2], 0.8),
+            // Re-ingesting a paper adds nothing.
+            fact(papers[1], 0.9),
+        ])
+        .unwrap();
+
+        assert_eq!(merged.id, first.id);
+        assert_eq!(merged.support_count, 3);
+        let supporting: Vec<uuid::Uuid> = merged
+            .supporting_evidence
+            .iter()
+            .map(|s| s.paper_id)
+            .collect();
+        assert_eq!(supporting, papers);
+        assert!((merged.confidence - 0.95).abs() < 1e-6);
+
+        let again = merge_facts([merged.clone(), fact(uuid::Uuid::from_u128(13), 0.9)]).unwrap();
+        assert_eq!(again.support_count, 4);
+        assert_eq!(again.confidence, MAX_AGGREGATE_CONFIDENCE);
+    }
+
+    #[test]
+    fn retracting_a_paper_reweights_or_drops_its_facts() {
+        let papers: Vec<uuid::Uuid> = (10..13).map(uuid::Uuid::from_u128).collect();
+        let merged = merge_facts(papers.iter().map(|p| fact(*p, 0.5))).unwrap();
+        assert_eq!(merged.paper_id, papers[0]);
+
+        let removed: HashSet<uuid::Uuid> = [papers[0]].into();
+        let kept = without_papers(&merged, &removed).unwrap();
+        assert_eq!(kept.id, merged.id);
+        assert_eq!(kept.paper_id, papers[1]);
+        assert_eq!(kept.support_count, 2);
+        assert!((kept.confidence - 0.75).abs() < 1e-6);
+
+        let removed: HashSet<uuid::Uuid> = [papers[0], papers[1]].into();
+        let single = without_papers(&merged, &removed).unwrap();
+        assert_eq!(single.paper_id, papers[2]);
+        assert_eq!(single.support_count, 1);
+        assert_eq!(single.confidence, 0.5);
+        assert!(single.supporting_evidence.is_empty());
+
+        let removed: HashSet<uuid::Uuid> = papers.iter().copied().collect();
+        assert!(without_papers(&merged, &removed).is_none());
+        assert!(without_papers(&fact(papers[0], 0.9), &[papers[0]].into()).is_none());
+    }
+
+    #[test]
+    fn single_fact_keeps_its_own_support() {
+        let paper = uuid::Uuid::from_u128(10);
+        let merged = merge_facts([fact(paper, 0.7), fact(paper, 0.6)]).unwrap();
+        assert_eq!(merged.support_count, 1);
+        assert_eq!(merged.confidence, 0.7);
+        assert!(merged.supporting_evidence.is_empty());
+        assert_eq!(fact_supports(&merged)[0].paper_id, paper);
+    }
+
+    fn published(paper: u128, years_ago: i64) -> KgFact {
+        let mut fact = fact(uuid::Uuid::from_u128(paper), 0.8);
+        fact.supporting_evidence = fact_supports(&fact);
+        fact.supporting_evidence[0].published_at =
+            Some(chrono::Utc::now() - chrono::Duration::days((years_ago as f64 * 365.25) as i64));
+        fact
+    }
+
+    #[test]
+    fn old_papers_weigh_less_in_merged_confidence() {
+        let decay = EvidenceDecay::with_half_life(10.0);
+
+        // 0.8 at 0 and 5 years: 1 - 0.2 * (1 - 0.8 * √½).
+        let recent = merge_facts_with([published(10, 0), published(11, 5)], decay).unwrap();
+        assert!((recent.confidence - (1.0 - 0.2 * (1.0 - 0.8 * 0.5_f32.sqrt()))).abs() < 1e-3);
+        // 0.8 at 10 and 30 years: 1 - (1 - 0.4) * (1 - 0.1).
+        let old = merge_facts_with([published(12, 10), published(13, 30)], decay).unwrap();
+        assert!((old.confidence - 0.46).abs() < 1e-3);
+        assert!(old.supporting_evidence.iter().all(|s| s.confidence == 0.8));
+
+        // Supports keep their raw confidence, so merging again never decays twice.
+        let again = merge_facts_with([old.clone()], decay).unwrap();
+        assert_eq!(again.confidence, old.confidence);
+        let lone = merge_facts_with([published(14, 30)], decay).unwrap();
+        assert!((lone.confidence - 0.1).abs() < 1e-3);
+        assert_eq!(lone.supporting_evidence.len(), 1);
+        let lone_again = merge_facts_with([lone.clone()], decay).unwrap();
+        assert_eq!(lone_again.confidence, lone.confidence);
+
+        // Undated papers stay neutral, and without decay age does not matter.
+        let p = |i| uuid::Uuid::from_u128(i);
+        let undated = merge_facts_with([fact(p(15), 0.8), fact(p(16), 0.8)], decay).unwrap();
+        assert!((undated.confidence - 0.96).abs() < 1e-6);
+        let off =
+            merge_facts_with([published(12, 10), published(13, 30)], EvidenceDecay::NONE).unwrap();
+        assert!((off.confidence - 0.96).abs() < 1e-6);
+        let fresh = merge_facts_with([published(14, 30)], EvidenceDecay::NONE).unwrap();
+        assert_eq!(fresh.confidence, 0.8);
+        assert!(fresh.supporting_evidence.is_empty());
+    }
 }
@@ -28,27 +28,47 @@
 //! }
 //! ```
 
+pub mod candidate_ligands;
 pub mod chunks;
 pub mod database;
+pub mod docking_results;
 pub mod ent_stage;
 pub mod entities;
 pub mod entity_mentions;
 pub mod error;
+pub mod failed_items;
 pub mod federation;
+pub mod ingestion_job_runs;
+pub mod ingestion_jobs;
+pub mod ingestion_watermarks;
 pub mod kg_conflicts;
 pub mod kg_facts;
+pub mod llm_audit;
+pub mod llm_usage;
+pub mod maintenance;
+pub mod metric_rollups;
+pub mod molecule_candidates;
+pub mod paper_citations;
+pub mod paper_tombstones;
 pub mod papers;
 pub mod phase4_signals;
+pub mod repro_export;
 pub mod schema;
 pub mod schema_arrow;
+pub mod schema_evolution;
+pub mod snapshot;
 pub mod target_scores;
+pub mod trials;
 
+pub use candidate_ligands::CandidateLigandRepository;
 pub use chunks::ChunkRepository;
 pub use database::{Database, DatabaseStats};
+pub use docking_results::DockingResultRepository;
 pub use ent_stage::{EntEnrichment, EntStageRepository};
 pub use entities::EntityRepository;
 pub use entity_mentions::EntityMentionRepository;
 pub use error::{DbError, Result};
+pub use failed_items::FailedItemRepository;
 pub use federation::{
     build_contribution_manifest_draft, export_contribution_package, validate_contribution_manifest,
     validate_contribution_package, sign_contribution_package, submit_package_for_merge,
@@ -60,13 +80,34 @@ pub use federation::{
     list_trusted_signing_keys, upsert_trusted_signing_key, revoke_trusted_signing_key,
     TrustKeyRecord, TrustKeyRevokeRequest, TrustKeyUpsertRequest,
 };
+pub use ingestion_job_runs::IngestionJobRunRepository;
+pub use ingestion_jobs::IngestionJobRecordRepository;
+pub use ingestion_watermarks::IngestionWatermarkRepository;
 pub use kg_conflicts::KgConflictRepository;
 pub use kg_facts::KgFactRepository;
-pub use papers::PaperRepository;
+pub use llm_audit::{LlmAuditFilter, LlmAuditRepository};
+pub use llm_usage::LlmUsageRepository;
+pub use maintenance::{
+    CompactionReport, IndexStatus, OrphanCleanup, VectorIndexKind, VectorIndexParams,
+};
+pub use metric_rollups::MetricRollupRepository;
+pub use molecule_candidates::MoleculeCandidateRepository;
+pub use paper_citations::PaperCitationRepository;
+pub use paper_tombstones::{PaperTombstoneRepository, TombstonedIds};
+pub use papers::{DeletedPaper, PaperDeletion, PaperFilter, PaperRepository};
 pub use phase4_signals::Phase4SignalRepository;
 pub use schema::EntProviderRefreshRun;
+pub use schema::FailedItem;
+pub use schema::IngestionJobRecord;
+pub use schema::IngestionJobRun;
+pub use schema::IngestionWatermark;
+pub use schema::LlmAuditLog;
+pub use schema::LlmUsageRecord;
+pub use schema::MetricRollup;
+pub use schema::PaperCitation;
+pub use schema::PaperTombstone;
 pub use schema::{
-    Chunk, Entity, EntityMention, EntityType, KgConflict, KgFact, Paper, TargetScore,
+    Chunk, Entity, EntityMention, EntityType, FactSupport, KgConflict, KgFact, Paper, TargetScore,
     EMBEDDING_DIM, TABLE_CHUNKS, TABLE_ENTITIES, TABLE_ENTITY_MENTIONS, TABLE_KG_CONFLICTS,
     TABLE_KG_FACTS, TABLE_PAPERS, TABLE_TARGET_SCORES,
 };
@@ -74,4 +115,11 @@ pub use schema::{
     EntCbioMutationFrequency, EntChemblTarget, EntCosmicMutationFrequency, EntGtexExpression,
     EntReactomeGene, EntTcgaSurvival,
 };
+pub use schema_evolution::{MigrationPlan, SchemaChange, TableMigration, SCHEMA_VERSION};
+pub use snapshot::{
+    export_snapshot, export_snapshot_with_progress, import_snapshot, import_snapshot_with_progress,
+    SnapshotImport, SnapshotImportMode, SnapshotManifest, SnapshotProgress, SnapshotTable,
+    TableImport,
+};
 pub use target_scores::TargetScoreRepository;
+pub use trials::TrialRepository;
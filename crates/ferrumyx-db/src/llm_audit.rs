@@ -0,0 +1,348 @@
+//! LLM audit log repository.
+//!
+//! One row per LLM call, recording the backend, data class and the chunks
+//! the model was shown, so every generated answer can be traced back to
+//! its evidence.
+
+use crate::database::{llm_audit_table_schema, Database};
+use crate::error::{DbError, Result};
+use crate::schema::{LlmAuditLog, TABLE_LLM_AUDIT};
+use crate::schema_evolution::conform_row;
+use ferrumyx_common::DataClass;
+use std::sync::Arc;
+
+use arrow_array::{Array, BooleanArray, Int64Array, RecordBatch, StringArray};
+use futures::StreamExt;
+use lancedb::query::{ExecutableQuery, QueryBase};
+
+/// Which calls [`LlmAuditRepository::search`] returns; unset fields match
+/// everything.
+#[derive(Debug, Clone, Default)]
+pub struct LlmAuditFilter {
+    /// Calls made at or after this time.
+    pub since: Option<chrono::DateTime<chrono::Utc>>,
+    pub backend: Option<String>,
+    pub data_class: Option<DataClass>,
+}
+
+/// Repository for LLM audit log operations.
+#[derive(Clone)]
+pub struct LlmAuditRepository {
+    db: Arc<Database>,
+}
+
+impl LlmAuditRepository {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Append `entry` to the log.
+    pub async fn insert(&self, entry: &LlmAuditLog) -> Result<()> {
+        let table = self
+            .db
+            .connection()
+            .open_table(TABLE_LLM_AUDIT)
+            .execute()
+            .await?;
+        let record = entry_to_record(entry)?;
+        let schema = record.schema();
+        let iter = arrow_array::RecordBatchIterator::new(vec![Ok(record)], schema);
+        table.add(iter).execute().await?;
+        Ok(())
+    }
+
+    /// Calls logged under `session_id`, oldest first.
+    pub async fn for_session(&self, session_id: &str) -> Result<Vec<LlmAuditLog>> {
+        let mut rows = self
+            .query(Some(&format!(
+                "session_id = '{}'",
+                session_id.replace('\'', "''")
+            )))
+            .await?;
+        rows.reverse();
+        Ok(rows)
+    }
+
+    /// The `limit` most recent calls, newest first.
+    pub async fn recent(&self, limit: usize) -> Result<Vec<LlmAuditLog>> {
+        let mut rows = self.query(None).await?;
+        rows.truncate(limit);
+        Ok(rows)
+    }
+
+    /// Calls matching `filter`, newest first, skipping `offset` and
+    /// returning at most `limit`, with the number that matched.
+    pub async fn search(
+        &self,
+        filter: &LlmAuditFilter,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(usize, Vec<LlmAuditLog>)> {
+        let mut clauses = Vec::new();
+        if let Some(backend) = &filter.backend {
+            clauses.push(format!("backend = '{}'", backend.replace('\'', "''")));
+        }
+        if let Some(class) = filter.data_class {
+            clauses.push(format!("data_class = '{}'", class.as_str()));
+        }
+        let where_clause = clauses.join(" AND ");
+        let mut rows = self
+            .query((!where_clause.is_empty()).then_some(where_clause.as_str()))
+            .await?;
+        if let Some(since) = filter.since {
+            rows.retain(|row| row.called_at >= since);
+        }
+        let total = rows.len();
+        Ok((total, rows.into_iter().skip(offset).take(limit).collect()))
+    }
+
+    /// Delete calls made before `cutoff`, returning how many were removed.
+    pub async fn purge_before(&self, cutoff: chrono::DateTime<chrono::Utc>) -> Result<usize> {
+        let stale: Vec<String> = self
+            .query(None)
+            .await?
+            .into_iter()
+            .filter(|row| row.called_at < cutoff)
+            .map(|row| format!("'{}'", row.id))
+            .collect();
+        if stale.is_empty() {
+            return Ok(0);
+        }
+        let table = self
+            .db
+            .connection()
+            .open_table(TABLE_LLM_AUDIT)
+            .execute()
+            .await?;
+        table
+            .delete(&format!("id IN ({})", stale.join(",")))
+            .await?;
+        Ok(stale.len())
+    }
+
+    async fn query(&self, filter: Option<&str>) -> Result<Vec<LlmAuditLog>> {
+        let table = self
+            .db
+            .connection()
+            .open_table(TABLE_LLM_AUDIT)
+            .execute()
+            .await?;
+        let mut query = table.query();
+        if let Some(filter) = filter {
+            query = query.only_if(filter);
+        }
+        let mut stream = query.execute().await?;
+        let mut rows = Vec::new();
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            for row in 0..batch.num_rows() {
+                rows.push(record_to_entry(&batch, row)?);
+            }
+        }
+        rows.sort_by(|a, b| b.called_at.cmp(&a.called_at));
+        Ok(rows)
+    }
+}
+
+fn entry_to_record(entry: &LlmAuditLog) -> Result<RecordBatch> {
+    let chunk_ids = entry
+        .chunk_ids
+        .iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    let cols: Vec<Arc<dyn Array>> = vec![
+        Arc::new(StringArray::from(vec![entry.id.to_string()])),
+        Arc::new(StringArray::from(vec![entry.session_id.clone()])),
+        Arc::new(StringArray::from(vec![entry.model.clone()])),
+        Arc::new(StringArray::from(vec![entry.backend.clone()])),
+        Arc::new(Int64Array::from(vec![entry.prompt_tokens])),
+        Arc::new(Int64Array::from(vec![entry.completion_tokens])),
+        Arc::new(StringArray::from(vec![entry.data_class.as_str()])),
+        Arc::new(StringArray::from(vec![entry.output_hash.clone()])),
+        Arc::new(Int64Array::from(vec![entry.latency_ms])),
+        Arc::new(StringArray::from(vec![chunk_ids])),
+        Arc::new(StringArray::from(vec![entry.called_at.to_rfc3339()])),
+        Arc::new(BooleanArray::from(vec![entry.cached])),
+        Arc::new(StringArray::from(vec![entry.caller.clone()])),
+        Arc::new(StringArray::from(vec![entry.error.clone()])),
+        Arc::new(StringArray::from(vec![entry.prompt_hash.clone()])),
+        Arc::new(Int64Array::from(vec![entry.prompt_chars])),
+    ];
+    Ok(RecordBatch::try_new(llm_audit_table_schema(), cols)?)
+}
+
+fn record_to_entry(batch: &RecordBatch, row: usize) -> Result<LlmAuditLog> {
+    let (batch, row) = conform_row(batch, row, &llm_audit_table_schema())?;
+    let batch: &RecordBatch = &batch;
+    let get_s = |col: &str| -> Result<Option<String>> {
+        let idx = batch
+            .schema()
+            .index_of(col)
+            .map_err(|e| DbError::Arrow(e.to_string()))?;
+        let arr = batch
+            .column(idx)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .ok_or_else(|| DbError::Arrow(format!("{col} is not StringArray")))?;
+        Ok((!arr.is_null(row)).then(|| arr.value(row).to_string()))
+    };
+    let get_i = |col: &str| -> Result<Option<i64>> {
+        let idx = batch
+            .schema()
+            .index_of(col)
+            .map_err(|e| DbError::Arrow(e.to_string()))?;
+        let arr = batch
+            .column(idx)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .ok_or_else(|| DbError::Arrow(format!("{col} is not Int64Array")))?;
+        Ok((!arr.is_null(row)).then(|| arr.value(row)))
+    };
+    let cached = {
+        let idx = batch
+            .schema()
+            .index_of("cached")
+            .map_err(|e| DbError::Arrow(e.to_string()))?;
+        let arr = batch
+            .column(idx)
+            .as_any()
+            .downcast_ref::<BooleanArray>()
+            .ok_or_else(|| DbError::Arrow("cached is not BooleanArray".to_string()))?;
+        !arr.is_null(row) && arr.value(row)
+    };
+    let parse_id =
+        |s: &str| uuid::Uuid::parse_str(s).map_err(|e| DbError::InvalidQuery(e.to_string()));
+
+    let chunk_ids = get_s("chunk_ids")?
+        .unwrap_or_default()
+        .split(',')
+        .filter(|s| !s.is_empty())
+        .map(parse_id)
+        .collect::<Result<Vec<_>>>()?;
+    let called_at = chrono::DateTime::parse_from_rfc3339(&get_s("called_at")?.unwrap_or_default())
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+        .map_err(|e| DbError::InvalidQuery(e.to_string()))?;
+
+    Ok(LlmAuditLog {
+        id: parse_id(&get_s("id")?.unwrap_or_default())?,
+        session_id: get_s("session_id")?,
+        model: get_s("model")?.unwrap_or_default(),
+        backend: get_s("backend")?.unwrap_or_default(),
+        prompt_tokens: get_i("prompt_tokens")?,
+        completion_tokens: get_i("completion_tokens")?,
+        data_class: get_s("data_class")?
+            .unwrap_or_default()
+            .parse::<DataClass>()
+            .map_err(DbError::InvalidQuery)?,
+        output_hash: get_s("output_hash")?.unwrap_or_default(),
+        latency_ms: get_i("latency_ms")?,
+        chunk_ids,
+        called_at,
+        cached,
+        caller: get_s("caller")?,
+        error: get_s("error")?,
+        prompt_hash: get_s("prompt_hash")?,
+        prompt_chars: get_i("prompt_chars")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn audit_entries_round_trip_with_their_chunk_ids() {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-llm-audit-{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        let repo = LlmAuditRepository::new(db);
+
+        let mut entry = LlmAuditLog::new(
+            "llama3.2".to_string(),
+            "ollama".to_string(),
+            DataClass::Confidential,
+            "KRAS G12C is targeted by sotorasib [1].",
+        );
+        entry.session_id = Some("answer-1".to_string());
+        entry.prompt_tokens = Some(812);
+        entry.latency_ms = Some(240);
+        entry.chunk_ids = vec![uuid::Uuid::new_v4(), uuid::Uuid::new_v4()];
+        repo.insert(&entry).await.unwrap();
+        let mut other = LlmAuditLog::new(
+            "gpt-4o".to_string(),
+            "openai".to_string(),
+            DataClass::Public,
+            "",
+        );
+        other.called_at = entry.called_at + chrono::Duration::seconds(1);
+        other.cached = true;
+        repo.insert(&other).await.unwrap();
+
+        assert_eq!(entry.output_hash.len(), 64);
+        assert_ne!(entry.output_hash, other.output_hash);
+        assert_eq!(
+            repo.for_session("answer-1").await.unwrap(),
+            vec![entry.clone()]
+        );
+        assert_eq!(repo.recent(10).await.unwrap(), vec![other.clone(), entry]);
+        assert_eq!(repo.recent(1).await.unwrap(), vec![other]);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn search_filters_and_pages_and_purge_drops_old_calls() {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-llm-audit-{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        let repo = LlmAuditRepository::new(db);
+
+        let now = chrono::Utc::now();
+        let mut entries = Vec::new();
+        for (days_ago, backend, class) in [
+            (40, "openai", DataClass::Public),
+            (2, "ollama", DataClass::Confidential),
+            (1, "openai", DataClass::Public),
+            (0, "ollama", DataClass::Confidential),
+        ] {
+            let mut entry = LlmAuditLog::new("m".to_string(), backend.to_string(), class, "");
+            entry.called_at = now - chrono::Duration::days(days_ago);
+            entry.caller = Some("answer".to_string());
+            entry.prompt_hash = Some("ab".repeat(32));
+            entry.prompt_chars = Some(1200);
+            if days_ago == 2 {
+                entry.error = Some("ollama: connection refused".to_string());
+            }
+            repo.insert(&entry).await.unwrap();
+            entries.push(entry);
+        }
+
+        let ollama = LlmAuditFilter {
+            backend: Some("ollama".to_string()),
+            ..LlmAuditFilter::default()
+        };
+        let (total, page) = repo.search(&ollama, 1, 1).await.unwrap();
+        assert_eq!((total, page), (2, vec![entries[1].clone()]));
+        let recent_public = LlmAuditFilter {
+            since: Some(now - chrono::Duration::days(7)),
+            data_class: Some(DataClass::Public),
+            ..LlmAuditFilter::default()
+        };
+        let (total, page) = repo.search(&recent_public, 0, 10).await.unwrap();
+        assert_eq!((total, page), (1, vec![entries[2].clone()]));
+
+        let purged = repo
+            .purge_before(now - chrono::Duration::days(30))
+            .await
+            .unwrap();
+        assert_eq!(purged, 1);
+        let (total, _) = repo
+            .search(&LlmAuditFilter::default(), 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(total, 3);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,159 @@
+//! LLM usage repository.
+//!
+//! One row per LLM call with its tokens and estimated cost, so the daily
+//! `[llm.limits]` budgets survive restarts.
+
+use crate::database::{llm_usage_table_schema, Database};
+use crate::error::{DbError, Result};
+use crate::schema::{LlmUsageRecord, TABLE_LLM_USAGE};
+use crate::schema_evolution::conform_row;
+use std::sync::Arc;
+
+use arrow_array::{Array, Float64Array, Int64Array, RecordBatch, StringArray};
+use futures::StreamExt;
+use lancedb::query::{ExecutableQuery, QueryBase};
+
+/// Repository for LLM usage operations.
+#[derive(Clone)]
+pub struct LlmUsageRepository {
+    db: Arc<Database>,
+}
+
+impl LlmUsageRepository {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Append `record` to the usage log.
+    pub async fn insert(&self, record: &LlmUsageRecord) -> Result<()> {
+        let table = self
+            .db
+            .connection()
+            .open_table(TABLE_LLM_USAGE)
+            .execute()
+            .await?;
+        let batch = record_to_batch(record)?;
+        let schema = batch.schema();
+        let iter = arrow_array::RecordBatchIterator::new(vec![Ok(batch)], schema);
+        table.add(iter).execute().await?;
+        Ok(())
+    }
+
+    /// Calls counted against `day`, oldest first.
+    pub async fn for_day(&self, day: chrono::NaiveDate) -> Result<Vec<LlmUsageRecord>> {
+        let table = self
+            .db
+            .connection()
+            .open_table(TABLE_LLM_USAGE)
+            .execute()
+            .await?;
+        let mut stream = table
+            .query()
+            .only_if(format!("day = '{}'", day.format("%Y-%m-%d")))
+            .execute()
+            .await?;
+        let mut rows = Vec::new();
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            for row in 0..batch.num_rows() {
+                rows.push(batch_to_record(&batch, row)?);
+            }
+        }
+        rows.sort_by(|a, b| a.recorded_at.cmp(&b.recorded_at));
+        Ok(rows)
+    }
+}
+
+fn record_to_batch(record: &LlmUsageRecord) -> Result<RecordBatch> {
+    let cols: Vec<Arc<dyn Array>> = vec![
+        Arc::new(StringArray::from(vec![record.id.to_string()])),
+        Arc::new(StringArray::from(vec![record
+            .day
+            .format("%Y-%m-%d")
+            .to_string()])),
+        Arc::new(StringArray::from(vec![record.backend.clone()])),
+        Arc::new(StringArray::from(vec![record.model.clone()])),
+        Arc::new(Int64Array::from(vec![record.prompt_tokens])),
+        Arc::new(Int64Array::from(vec![record.completion_tokens])),
+        Arc::new(Float64Array::from(vec![record.cost_usd])),
+        Arc::new(StringArray::from(vec![record.recorded_at.to_rfc3339()])),
+    ];
+    Ok(RecordBatch::try_new(llm_usage_table_schema(), cols)?)
+}
+
+fn batch_to_record(batch: &RecordBatch, row: usize) -> Result<LlmUsageRecord> {
+    let (batch, row) = conform_row(batch, row, &llm_usage_table_schema())?;
+    let batch: &RecordBatch = &batch;
+    let index = |col: &str| -> Result<usize> {
+        batch
+            .schema()
+            .index_of(col)
+            .map_err(|e| DbError::Arrow(e.to_string()))
+    };
+    let get_s = |col: &str| -> Result<String> {
+        let arr = batch
+            .column(index(col)?)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .ok_or_else(|| DbError::Arrow(format!("{col} is not StringArray")))?;
+        Ok(arr.value(row).to_string())
+    };
+    let get_i = |col: &str| -> Result<i64> {
+        let arr = batch
+            .column(index(col)?)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .ok_or_else(|| DbError::Arrow(format!("{col} is not Int64Array")))?;
+        Ok(arr.value(row))
+    };
+    let cost_usd = batch
+        .column(index("cost_usd")?)
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .ok_or_else(|| DbError::Arrow("cost_usd is not Float64Array".to_string()))?
+        .value(row);
+
+    let day = chrono::NaiveDate::parse_from_str(&get_s("day")?, "%Y-%m-%d")
+        .map_err(|e| DbError::InvalidQuery(e.to_string()))?;
+    let recorded_at = chrono::DateTime::parse_from_rfc3339(&get_s("recorded_at")?)
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+        .map_err(|e| DbError::InvalidQuery(e.to_string()))?;
+
+    Ok(LlmUsageRecord {
+        id: uuid::Uuid::parse_str(&get_s("id")?)
+            .map_err(|e| DbError::InvalidQuery(e.to_string()))?,
+        day,
+        backend: get_s("backend")?,
+        model: get_s("model")?,
+        prompt_tokens: get_i("prompt_tokens")?,
+        completion_tokens: get_i("completion_tokens")?,
+        cost_usd,
+        recorded_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn usage_rows_are_read_back_by_day() {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-llm-usage-{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        let repo = LlmUsageRepository::new(db);
+
+        let today =
+            LlmUsageRecord::new("openai".to_string(), "gpt-4o".to_string(), 900, 120, 0.0035);
+        repo.insert(&today).await.unwrap();
+        let mut yesterday = today.clone();
+        yesterday.id = uuid::Uuid::new_v4();
+        yesterday.day = today.day.pred_opt().unwrap();
+        repo.insert(&yesterday).await.unwrap();
+
+        assert_eq!(repo.for_day(today.day).await.unwrap(), vec![today.clone()]);
+        assert_eq!(repo.for_day(yesterday.day).await.unwrap(), vec![yesterday]);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
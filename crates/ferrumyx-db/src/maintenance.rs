@@ -0,0 +1,499 @@
+//! Table maintenance: compaction, vector indexing and orphan cleanup.
+//!
+//! The insert-heavy ingestion workload leaves many small fragments behind;
+//! [`Database::compact_all`] folds them together. [`Database::cleanup_orphans`]
+//! removes rows that point at a paper which no longer exists.
+
+use std::collections::HashSet;
+
+use arrow_array::{Array, StringArray};
+use futures::StreamExt;
+use lancedb::index::vector::{IvfHnswPqIndexBuilder, IvfHnswSqIndexBuilder, IvfPqIndexBuilder};
+use lancedb::index::Index;
+use lancedb::query::{ExecutableQuery, QueryBase, Select};
+use lancedb::table::OptimizeAction;
+use lancedb::Table;
+use serde::{Deserialize, Serialize};
+
+use crate::database::{is_existing_index_error, Database};
+use crate::error::{DbError, Result};
+use crate::schema::{self, FactSupport};
+
+/// Rows per `IN (...)` delete predicate.
+const DELETE_BATCH: usize = 512;
+
+/// Fragment counts of one table around a compaction.
+#[derive(Debug, Clone, Serialize)]
+pub struct CompactionReport {
+    pub table: String,
+    pub fragments_before: usize,
+    pub fragments_after: usize,
+    pub small_fragments_before: usize,
+    pub small_fragments_after: usize,
+    /// Bytes freed by pruning old versions.
+    pub bytes_removed: u64,
+}
+
+/// Vector index algorithm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VectorIndexKind {
+    /// Let LanceDB choose from the column type.
+    Auto,
+    IvfPq,
+    /// HNSW graphs per IVF partition over 8-bit scalar-quantised vectors.
+    #[default]
+    IvfHnswSq,
+    IvfHnswPq,
+}
+
+/// Parameters of [`Database::create_vector_index`]. Distances are L2, as
+/// [`ChunkRepository::search_similar`](crate::ChunkRepository::search_similar)
+/// expects.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VectorIndexParams {
+    pub column: String,
+    pub kind: VectorIndexKind,
+    /// IVF partitions; LanceDB uses about the square root of the row count
+    /// when unset.
+    pub num_partitions: Option<u32>,
+    /// PQ sub-vectors; LanceDB picks a divisor of the dimension when unset.
+    pub num_sub_vectors: Option<u32>,
+    /// HNSW neighbours per node.
+    pub num_edges: u32,
+    /// HNSW candidate list size while building.
+    pub ef_construction: u32,
+    /// Rebuild an existing index on the column.
+    pub replace: bool,
+}
+
+impl Default for VectorIndexParams {
+    /// IVF-HNSW-SQ on `chunks.embedding`.
+    fn default() -> Self {
+        Self {
+            column: "embedding".to_string(),
+            kind: VectorIndexKind::default(),
+            num_partitions: None,
+            num_sub_vectors: None,
+            num_edges: 20,
+            ef_construction: 300,
+            replace: false,
+        }
+    }
+}
+
+impl VectorIndexParams {
+    fn index(&self) -> Index {
+        match self.kind {
+            VectorIndexKind::Auto => Index::Auto,
+            VectorIndexKind::IvfPq => {
+                let mut builder = IvfPqIndexBuilder::default();
+                if let Some(n) = self.num_partitions {
+                    builder = builder.num_partitions(n);
+                }
+                if let Some(n) = self.num_sub_vectors {
+                    builder = builder.num_sub_vectors(n);
+                }
+                Index::IvfPq(builder)
+            }
+            VectorIndexKind::IvfHnswSq => {
+                let mut builder = IvfHnswSqIndexBuilder::default()
+                    .num_edges(self.num_edges)
+                    .ef_construction(self.ef_construction);
+                if let Some(n) = self.num_partitions {
+                    builder = builder.num_partitions(n);
+                }
+                Index::IvfHnswSq(builder)
+            }
+            VectorIndexKind::IvfHnswPq => {
+                let mut builder = IvfHnswPqIndexBuilder::default()
+                    .num_edges(self.num_edges)
+                    .ef_construction(self.ef_construction);
+                if let Some(n) = self.num_partitions {
+                    builder = builder.num_partitions(n);
+                }
+                if let Some(n) = self.num_sub_vectors {
+                    builder = builder.num_sub_vectors(n);
+                }
+                Index::IvfHnswPq(builder)
+            }
+        }
+    }
+}
+
+/// One index and how much of its table it covers.
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexStatus {
+    pub table: String,
+    pub name: String,
+    pub index_type: String,
+    pub columns: Vec<String>,
+    pub indexed_rows: usize,
+    /// Rows written since the index was built; searched by a scan until
+    /// the next optimize.
+    pub unindexed_rows: usize,
+}
+
+/// Rows removed by [`Database::cleanup_orphans`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct OrphanCleanup {
+    pub chunks: u64,
+    pub entity_mentions: u64,
+    pub kg_facts: u64,
+}
+
+impl Database {
+    /// Compacts every table and prunes old versions, reporting fragment
+    /// counts before and after.
+    pub async fn compact_all(&self) -> Result<Vec<CompactionReport>> {
+        let mut names: Vec<String> = self.table_names_set().await?.into_iter().collect();
+        names.sort();
+
+        let mut reports = Vec::with_capacity(names.len());
+        for name in names {
+            let table = self.open_existing(&name).await?;
+            let before = table.stats().await?.fragment_stats;
+            let optimized = table.optimize(OptimizeAction::All).await?;
+            let after = table.stats().await?.fragment_stats;
+            reports.push(CompactionReport {
+                table: name,
+                fragments_before: before.num_fragments,
+                fragments_after: after.num_fragments,
+                small_fragments_before: before.num_small_fragments,
+                small_fragments_after: after.num_small_fragments,
+                bytes_removed: optimized.prune.map_or(0, |p| p.bytes_removed),
+            });
+        }
+        Ok(reports)
+    }
+
+    /// Builds a vector index on `table`. An existing index on the column is
+    /// kept unless `params.replace` is set.
+    pub async fn create_vector_index(&self, table: &str, params: &VectorIndexParams) -> Result<()> {
+        let handle = self.open_existing(table).await?;
+
+        if !params.replace
+            && handle
+                .list_indices()
+                .await?
+                .iter()
+                .any(|index| index.columns.len() == 1 && index.columns[0] == params.column)
+        {
+            return Ok(());
+        }
+
+        match handle
+            .create_index(&[params.column.as_str()], params.index())
+            .replace(params.replace)
+            .execute()
+            .await
+        {
+            Ok(()) => {}
+            Err(err) if !params.replace && is_existing_index_error(&err) => {}
+            Err(err) => return Err(err.into()),
+        }
+
+        Ok(())
+    }
+
+    /// Every index of every table with its coverage.
+    pub async fn index_status(&self) -> Result<Vec<IndexStatus>> {
+        let mut names: Vec<String> = self.table_names_set().await?.into_iter().collect();
+        names.sort();
+
+        let mut statuses = Vec::new();
+        for name in names {
+            let table = self.open_existing(&name).await?;
+            for index in table.list_indices().await? {
+                let stats = table.index_stats(&index.name).await?;
+                statuses.push(IndexStatus {
+                    table: name.clone(),
+                    index_type: index.index_type.to_string(),
+                    columns: index.columns,
+                    indexed_rows: stats.as_ref().map_or(0, |s| s.num_indexed_rows),
+                    unindexed_rows: stats.as_ref().map_or(0, |s| s.num_unindexed_rows),
+                    name: index.name,
+                });
+            }
+        }
+        Ok(statuses)
+    }
+
+    /// Deletes chunks, entity mentions and KG facts whose paper no longer
+    /// exists. Provider facts (nil paper id) are kept, as are facts that a
+    /// surviving paper still supports.
+    pub async fn cleanup_orphans(&self) -> Result<OrphanCleanup> {
+        let tables = self.table_names_set().await?;
+        let mut report = OrphanCleanup::default();
+        if !tables.contains(schema::TABLE_PAPERS) {
+            return Ok(report);
+        }
+
+        let papers = self.open_existing(schema::TABLE_PAPERS).await?;
+        let mut live: HashSet<String> = string_columns(&papers, &["id"])
+            .await?
+            .into_iter()
+            .map(|mut row| row.remove(0).unwrap_or_default())
+            .collect();
+        live.insert(uuid::Uuid::nil().to_string());
+
+        for (name, removed) in [
+            (schema::TABLE_CHUNKS, &mut report.chunks),
+            (schema::TABLE_ENTITY_MENTIONS, &mut report.entity_mentions),
+        ] {
+            if !tables.contains(name) {
+                continue;
+            }
+            let table = self.open_existing(name).await?;
+            let orphans: HashSet<String> = string_columns(&table, &["paper_id"])
+                .await?
+                .into_iter()
+                .filter_map(|mut row| row.remove(0))
+                .filter(|paper_id| !live.contains(paper_id))
+                .collect();
+            let orphans: Vec<String> = orphans.into_iter().collect();
+            *removed = delete_in(&table, "paper_id", &orphans).await?;
+        }
+
+        if tables.contains(schema::TABLE_KG_FACTS) {
+            let table = self.open_existing(schema::TABLE_KG_FACTS).await?;
+            let orphans: Vec<String> =
+                string_columns(&table, &["id", "paper_id", "supporting_evidence"])
+                    .await?
+                    .into_iter()
+                    .filter_map(|row| {
+                        let [id, paper_id, support] = <[Option<String>; 3]>::try_from(row).ok()?;
+                        if live.contains(paper_id.as_deref()?) {
+                            return None;
+                        }
+                        let supported = support
+                            .and_then(|s| serde_json::from_str::<Vec<FactSupport>>(&s).ok())
+                            .is_some_and(|s| {
+                                s.iter().any(|s| live.contains(&s.paper_id.to_string()))
+                            });
+                        (!supported).then_some(id).flatten()
+                    })
+                    .collect();
+            report.kg_facts = delete_in(&table, "id", &orphans).await?;
+        }
+
+        Ok(report)
+    }
+
+    async fn open_existing(&self, table: &str) -> Result<Table> {
+        Ok(self.connection().open_table(table).execute().await?)
+    }
+}
+
+/// The Utf8 `columns` of every row.
+pub(crate) async fn string_columns(
+    table: &Table,
+    columns: &[&str],
+) -> Result<Vec<Vec<Option<String>>>> {
+    let mut stream = table
+        .query()
+        .select(Select::columns(columns))
+        .execute()
+        .await?;
+
+    let mut rows = Vec::new();
+    while let Some(batch) = stream.next().await {
+        let batch = batch?;
+        let arrays = columns
+            .iter()
+            .map(|column| {
+                batch
+                    .column_by_name(column)
+                    .and_then(|a| a.as_any().downcast_ref::<StringArray>())
+                    .ok_or_else(|| DbError::Arrow(format!("{column} column was not Utf8")))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        for row in 0..batch.num_rows() {
+            rows.push(
+                arrays
+                    .iter()
+                    .map(|a| (!a.is_null(row)).then(|| a.value(row).to_string()))
+                    .collect(),
+            );
+        }
+    }
+    Ok(rows)
+}
+
+/// `column IN (...)` predicates over `values`, [`DELETE_BATCH`] at a time.
+pub(crate) fn in_filters(column: &str, values: &[String]) -> Vec<String> {
+    values
+        .chunks(DELETE_BATCH)
+        .map(|batch| {
+            format!(
+                "{column} IN ({})",
+                batch
+                    .iter()
+                    .map(|v| format!("'{}'", v.replace('\'', "''")))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })
+        .collect()
+}
+
+/// Rows whose `column` is one of `values`.
+pub(crate) async fn count_in(table: &Table, column: &str, values: &[String]) -> Result<u64> {
+    let mut count = 0;
+    for filter in in_filters(column, values) {
+        count += table.count_rows(Some(filter)).await? as u64;
+    }
+    Ok(count)
+}
+
+/// Deletes rows whose `column` is one of `values`; returns how many.
+pub(crate) async fn delete_in(table: &Table, column: &str, values: &[String]) -> Result<u64> {
+    let mut removed = 0;
+    for filter in in_filters(column, values) {
+        removed += table.count_rows(Some(filter.clone())).await? as u64;
+        table.delete(&filter).await?;
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{Chunk, EntityMention, KgFact, Paper};
+    use crate::{ChunkRepository, EntityMentionRepository, KgFactRepository, PaperRepository};
+    use std::sync::Arc;
+
+    fn fact(paper_id: uuid::Uuid, object_id: u128, object: &str) -> KgFact {
+        KgFact::new(
+            paper_id,
+            uuid::Uuid::from_u128(1),
+            "KRAS".to_string(),
+            "associated_with".to_string(),
+            uuid::Uuid::from_u128(object_id),
+            object.to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn cleanup_removes_rows_of_deleted_papers() {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-orphans-{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+
+        let kept = Paper::new("Kept".to_string(), "pubmed".to_string());
+        let deleted = Paper::new("Deleted".to_string(), "pubmed".to_string());
+        let papers = PaperRepository::new(db.clone());
+        papers
+            .insert_batch(&[kept.clone(), deleted.clone()])
+            .await
+            .unwrap();
+
+        let chunks = ChunkRepository::new(db.clone());
+        let kept_chunk = Chunk::new(kept.id, 0, "KRAS G12D drives PDAC.".to_string());
+        let orphan_chunks: Vec<Chunk> = (0..2)
+            .map(|i| Chunk::new(deleted.id, i, format!("orphan chunk {i}")))
+            .collect();
+        chunks.insert(&kept_chunk).await.unwrap();
+        chunks.insert_batch(&orphan_chunks).await.unwrap();
+
+        let mentions = EntityMentionRepository::new(db.clone());
+        let entity_id = uuid::Uuid::from_u128(1);
+        mentions
+            .insert_batch(&[
+                EntityMention::new(entity_id, kept_chunk.id, kept.id, "KRAS".to_string(), 0, 4),
+                EntityMention::new(
+                    entity_id,
+                    orphan_chunks[0].id,
+                    deleted.id,
+                    "KRAS".to_string(),
+                    0,
+                    4,
+                ),
+            ])
+            .await
+            .unwrap();
+
+        // A fact first seen in the deleted paper but also supported by the
+        // kept one survives, as does a provider fact with no paper.
+        let mut shared = fact(deleted.id, 2, "PAAD");
+        shared.supporting_evidence = vec![
+            FactSupport {
+                paper_id: deleted.id,
+                confidence: 0.5,
+                evidence: None,
+                chunk_id: None,
+                published_at: None,
+            },
+            FactSupport {
+                paper_id: kept.id,
+                confidence: 0.5,
+                evidence: None,
+                chunk_id: None,
+                published_at: None,
+            },
+        ];
+        let facts = KgFactRepository::new(db.clone());
+        facts
+            .insert_batch(&[
+                fact(kept.id, 3, "LUAD"),
+                fact(deleted.id, 4, "COAD"),
+                shared.clone(),
+                fact(uuid::Uuid::nil(), 5, "BRCA"),
+            ])
+            .await
+            .unwrap();
+
+        papers.delete(deleted.id).await.unwrap();
+        let report = db.cleanup_orphans().await.unwrap();
+        assert_eq!(
+            report,
+            OrphanCleanup {
+                chunks: 2,
+                entity_mentions: 1,
+                kg_facts: 1,
+            }
+        );
+        assert_eq!(chunks.count().await.unwrap(), 1);
+        assert_eq!(mentions.count().await.unwrap(), 1);
+        let mut objects: Vec<String> = facts
+            .list(0, 10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|f| f.object_name)
+            .collect();
+        objects.sort();
+        assert_eq!(objects, ["BRCA", "LUAD", "PAAD"]);
+
+        assert_eq!(
+            db.cleanup_orphans().await.unwrap(),
+            OrphanCleanup::default()
+        );
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[tokio::test]
+    async fn compaction_merges_fragments() {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-compact-{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        let papers = PaperRepository::new(db.clone());
+        for i in 0..4 {
+            papers
+                .insert(&Paper::new(format!("Paper {i}"), "pubmed".to_string()))
+                .await
+                .unwrap();
+        }
+
+        let reports = db.compact_all().await.unwrap();
+        let papers_report = reports
+            .iter()
+            .find(|r| r.table == schema::TABLE_PAPERS)
+            .unwrap();
+        assert_eq!(papers_report.fragments_before, 4);
+        assert_eq!(papers_report.fragments_after, 1);
+        assert_eq!(papers.count().await.unwrap(), 4);
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
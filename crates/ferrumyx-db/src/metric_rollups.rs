@@ -0,0 +1,192 @@
+//! Metric rollup repository.
+//!
+//! One row per metric per UTC day with the count, extremes, sum and last
+//! value of that day's samples, so dashboard time series reach further
+//! back than the in-memory sample buffer and survive restarts.
+
+use crate::database::{metric_rollups_table_schema, Database};
+use crate::error::{DbError, Result};
+use crate::schema::{MetricRollup, TABLE_METRIC_ROLLUPS};
+use crate::schema_evolution::conform_row;
+use std::sync::Arc;
+
+use arrow_array::{Array, Float64Array, Int64Array, RecordBatch, StringArray};
+use futures::StreamExt;
+use lancedb::query::{ExecutableQuery, QueryBase};
+
+/// Repository for metric rollup operations.
+#[derive(Clone)]
+pub struct MetricRollupRepository {
+    db: Arc<Database>,
+}
+
+impl MetricRollupRepository {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Insert `rollups`, replacing stored rows for the same metric and day.
+    pub async fn upsert_batch(&self, rollups: &[MetricRollup]) -> Result<()> {
+        if rollups.is_empty() {
+            return Ok(());
+        }
+        let table = self
+            .db
+            .connection()
+            .open_table(TABLE_METRIC_ROLLUPS)
+            .execute()
+            .await?;
+        let batch = rollups_to_batch(rollups)?;
+        let schema = batch.schema();
+        let iter = arrow_array::RecordBatchIterator::new(vec![Ok(batch)], schema);
+        let mut builder = table.merge_insert(&["metric", "day"]);
+        builder.when_matched_update_all(None);
+        builder.when_not_matched_insert_all();
+        builder.execute(Box::new(iter)).await?;
+        Ok(())
+    }
+
+    /// Rollups of `metric` from `since` on, oldest first.
+    pub async fn list_since(
+        &self,
+        metric: &str,
+        since: chrono::NaiveDate,
+    ) -> Result<Vec<MetricRollup>> {
+        let table = self
+            .db
+            .connection()
+            .open_table(TABLE_METRIC_ROLLUPS)
+            .execute()
+            .await?;
+        let mut stream = table
+            .query()
+            .only_if(format!(
+                "metric = '{}' AND day >= '{}'",
+                metric.replace('\'', "''"),
+                since.format("%Y-%m-%d")
+            ))
+            .execute()
+            .await?;
+        let mut rows = Vec::new();
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            for row in 0..batch.num_rows() {
+                rows.push(batch_to_rollup(&batch, row)?);
+            }
+        }
+        rows.sort_by(|a, b| a.day.cmp(&b.day));
+        Ok(rows)
+    }
+}
+
+fn rollups_to_batch(rollups: &[MetricRollup]) -> Result<RecordBatch> {
+    let floats = |f: fn(&MetricRollup) -> f64| -> Arc<dyn Array> {
+        Arc::new(Float64Array::from(
+            rollups.iter().map(f).collect::<Vec<_>>(),
+        ))
+    };
+    let cols: Vec<Arc<dyn Array>> = vec![
+        Arc::new(StringArray::from(
+            rollups.iter().map(|r| r.metric.clone()).collect::<Vec<_>>(),
+        )),
+        Arc::new(StringArray::from(
+            rollups
+                .iter()
+                .map(|r| r.day.format("%Y-%m-%d").to_string())
+                .collect::<Vec<_>>(),
+        )),
+        Arc::new(Int64Array::from(
+            rollups.iter().map(|r| r.samples).collect::<Vec<_>>(),
+        )),
+        floats(|r| r.min),
+        floats(|r| r.max),
+        floats(|r| r.sum),
+        floats(|r| r.last),
+        Arc::new(StringArray::from(
+            rollups
+                .iter()
+                .map(|r| r.updated_at.to_rfc3339())
+                .collect::<Vec<_>>(),
+        )),
+    ];
+    Ok(RecordBatch::try_new(metric_rollups_table_schema(), cols)?)
+}
+
+fn batch_to_rollup(batch: &RecordBatch, row: usize) -> Result<MetricRollup> {
+    let (batch, row) = conform_row(batch, row, &metric_rollups_table_schema())?;
+    let batch: &RecordBatch = &batch;
+    let get_s = |col: &str| -> Result<String> {
+        let arr = batch
+            .column_by_name(col)
+            .and_then(|a| a.as_any().downcast_ref::<StringArray>())
+            .ok_or_else(|| DbError::Arrow(format!("{col} is not StringArray")))?;
+        Ok(arr.value(row).to_string())
+    };
+    let get_f = |col: &str| -> Result<f64> {
+        let arr = batch
+            .column_by_name(col)
+            .and_then(|a| a.as_any().downcast_ref::<Float64Array>())
+            .ok_or_else(|| DbError::Arrow(format!("{col} is not Float64Array")))?;
+        Ok(arr.value(row))
+    };
+    let samples = batch
+        .column_by_name("samples")
+        .and_then(|a| a.as_any().downcast_ref::<Int64Array>())
+        .ok_or_else(|| DbError::Arrow("samples is not Int64Array".to_string()))?
+        .value(row);
+
+    let day = chrono::NaiveDate::parse_from_str(&get_s("day")?, "%Y-%m-%d")
+        .map_err(|e| DbError::InvalidQuery(e.to_string()))?;
+    let updated_at = chrono::DateTime::parse_from_rfc3339(&get_s("updated_at")?)
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+        .map_err(|e| DbError::InvalidQuery(e.to_string()))?;
+
+    Ok(MetricRollup {
+        metric: get_s("metric")?,
+        day,
+        samples,
+        min: get_f("min")?,
+        max: get_f("max")?,
+        sum: get_f("sum")?,
+        last: get_f("last")?,
+        updated_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[tokio::test]
+    async fn rollups_upsert_by_metric_and_day() {
+        let dir =
+            std::env::temp_dir().join(format!("ferrumyx-metric-rollups-{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        let repo = MetricRollupRepository::new(db);
+
+        let at = chrono::Utc.with_ymd_and_hms(2026, 3, 9, 12, 0, 0).unwrap();
+        let mut papers = MetricRollup::new("papers", 10.0, at);
+        let earlier = MetricRollup::new("papers", 4.0, at - chrono::Duration::days(2));
+        let chunks = MetricRollup::new("chunks", 80.0, at);
+        repo.upsert_batch(&[papers.clone(), earlier.clone(), chunks])
+            .await
+            .unwrap();
+        papers.absorb(12.0, at + chrono::Duration::minutes(1));
+        repo.upsert_batch(std::slice::from_ref(&papers))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            repo.list_since("papers", earlier.day).await.unwrap(),
+            vec![earlier, papers.clone()]
+        );
+        assert_eq!(
+            repo.list_since("papers", papers.day).await.unwrap(),
+            vec![papers]
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,268 @@
+//! Ranked molecule candidate repository.
+//!
+//! The final list of a molecule pipeline run, one row per ligand keyed by
+//! (gene, cancer_type, smiles). Storing a run's ranking replaces the
+//! previous one for that gene and cancer type, so ligands that dropped out
+//! do not linger with stale ranks.
+
+use crate::database::{molecule_candidates_table_schema, Database};
+use crate::error::{DbError, Result};
+use crate::schema::{MoleculeCandidateRecord, TABLE_MOLECULE_CANDIDATES};
+use crate::schema_evolution::conform_row;
+use std::sync::Arc;
+
+use arrow_array::{Array, BooleanArray, Float64Array, Int64Array, RecordBatch, StringArray};
+use futures::StreamExt;
+use lancedb::query::{ExecutableQuery, QueryBase};
+
+/// Repository for ranked molecule candidates.
+#[derive(Clone)]
+pub struct MoleculeCandidateRepository {
+    db: Arc<Database>,
+}
+
+impl MoleculeCandidateRepository {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Replace the ranking stored for (`gene`, `cancer_type`) with
+    /// `candidates`.
+    pub async fn replace_ranking(
+        &self,
+        gene: &str,
+        cancer_type: &str,
+        candidates: &[MoleculeCandidateRecord],
+    ) -> Result<()> {
+        let table = self.open().await?;
+        table.delete(&run_filter(gene, cancer_type)).await?;
+        if candidates.is_empty() {
+            return Ok(());
+        }
+        let batch = candidates_to_batch(candidates)?;
+        let schema = batch.schema();
+        let iter = arrow_array::RecordBatchIterator::new(vec![Ok(batch)], schema);
+        table.add(iter).execute().await?;
+        Ok(())
+    }
+
+    /// The ranking for (`gene`, `cancer_type`), best first.
+    pub async fn list_for(
+        &self,
+        gene: &str,
+        cancer_type: &str,
+    ) -> Result<Vec<MoleculeCandidateRecord>> {
+        let mut rows = self.query(Some(run_filter(gene, cancer_type))).await?;
+        rows.sort_by_key(|r| r.rank);
+        Ok(rows)
+    }
+
+    /// Every stored ranking, by gene and cancer type and then rank.
+    pub async fn list(&self) -> Result<Vec<MoleculeCandidateRecord>> {
+        let mut rows = self.query(None).await?;
+        rows.sort_by(|a, b| {
+            (&a.gene, &a.cancer_type, a.rank).cmp(&(&b.gene, &b.cancer_type, b.rank))
+        });
+        Ok(rows)
+    }
+
+    pub async fn count(&self) -> Result<u64> {
+        Ok(self.open().await?.count_rows(None).await? as u64)
+    }
+
+    async fn open(&self) -> Result<lancedb::Table> {
+        Ok(self
+            .db
+            .connection()
+            .open_table(TABLE_MOLECULE_CANDIDATES)
+            .execute()
+            .await?)
+    }
+
+    async fn query(&self, filter: Option<String>) -> Result<Vec<MoleculeCandidateRecord>> {
+        let table = self.open().await?;
+        let mut query = table.query();
+        if let Some(filter) = filter {
+            query = query.only_if(filter);
+        }
+        let mut stream = query.execute().await?;
+        let mut rows = Vec::new();
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            for row in 0..batch.num_rows() {
+                rows.push(batch_to_candidate(&batch, row)?);
+            }
+        }
+        Ok(rows)
+    }
+}
+
+fn run_filter(gene: &str, cancer_type: &str) -> String {
+    format!(
+        "gene = '{}' AND cancer_type = '{}'",
+        gene.replace('\'', "''"),
+        cancer_type.replace('\'', "''")
+    )
+}
+
+fn candidates_to_batch(candidates: &[MoleculeCandidateRecord]) -> Result<RecordBatch> {
+    let strings = |f: &dyn Fn(&MoleculeCandidateRecord) -> String| -> Arc<dyn Array> {
+        Arc::new(StringArray::from(
+            candidates.iter().map(f).collect::<Vec<_>>(),
+        ))
+    };
+    let ints = |f: &dyn Fn(&MoleculeCandidateRecord) -> u32| -> Arc<dyn Array> {
+        Arc::new(Int64Array::from(
+            candidates
+                .iter()
+                .map(|c| i64::from(f(c)))
+                .collect::<Vec<_>>(),
+        ))
+    };
+    let floats = |f: &dyn Fn(&MoleculeCandidateRecord) -> f64| -> Arc<dyn Array> {
+        Arc::new(Float64Array::from(
+            candidates.iter().map(f).collect::<Vec<_>>(),
+        ))
+    };
+    let alerts = candidates
+        .iter()
+        .map(|c| serde_json::to_string(&c.pains_alerts))
+        .collect::<serde_json::Result<Vec<_>>>()?;
+    let cols: Vec<Arc<dyn Array>> = vec![
+        strings(&|c| c.gene.clone()),
+        strings(&|c| c.cancer_type.clone()),
+        ints(&|c| c.rank),
+        strings(&|c| c.smiles.clone()),
+        strings(&|c| c.parent_chembl_id.clone()),
+        strings(&|c| c.scaffold_id.clone()),
+        Arc::new(BooleanArray::from(
+            candidates.iter().map(|c| c.novelty).collect::<Vec<_>>(),
+        )),
+        ints(&|c| c.pocket_rank),
+        floats(&|c| c.best_affinity_kcal),
+        floats(&|c| c.admet_score),
+        floats(&|c| c.sa_score),
+        Arc::new(StringArray::from(alerts)),
+        floats(&|c| c.composite_score),
+        strings(&|c| c.ranked_at.to_rfc3339()),
+    ];
+    Ok(RecordBatch::try_new(
+        molecule_candidates_table_schema(),
+        cols,
+    )?)
+}
+
+fn batch_to_candidate(batch: &RecordBatch, row: usize) -> Result<MoleculeCandidateRecord> {
+    let (batch, row) = conform_row(batch, row, &molecule_candidates_table_schema())?;
+    let batch: &RecordBatch = &batch;
+    let get_s = |col: &str| -> Result<String> {
+        let arr = batch
+            .column_by_name(col)
+            .and_then(|a| a.as_any().downcast_ref::<StringArray>())
+            .ok_or_else(|| DbError::Arrow(format!("{col} is not StringArray")))?;
+        Ok(if arr.is_null(row) {
+            String::new()
+        } else {
+            arr.value(row).to_string()
+        })
+    };
+    let get_u32 = |col: &str| -> Result<u32> {
+        let value = batch
+            .column_by_name(col)
+            .and_then(|a| a.as_any().downcast_ref::<Int64Array>())
+            .ok_or_else(|| DbError::Arrow(format!("{col} is not Int64Array")))?
+            .value(row);
+        Ok(u32::try_from(value).unwrap_or_default())
+    };
+    let get_f = |col: &str| -> Result<f64> {
+        Ok(batch
+            .column_by_name(col)
+            .and_then(|a| a.as_any().downcast_ref::<Float64Array>())
+            .ok_or_else(|| DbError::Arrow(format!("{col} is not Float64Array")))?
+            .value(row))
+    };
+    let novelty = batch
+        .column_by_name("novelty")
+        .and_then(|a| a.as_any().downcast_ref::<BooleanArray>())
+        .ok_or_else(|| DbError::Arrow("novelty is not BooleanArray".to_string()))?
+        .value(row);
+    let ranked_at = chrono::DateTime::parse_from_rfc3339(&get_s("ranked_at")?)
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+        .map_err(|e| DbError::InvalidQuery(e.to_string()))?;
+
+    Ok(MoleculeCandidateRecord {
+        gene: get_s("gene")?,
+        cancer_type: get_s("cancer_type")?,
+        rank: get_u32("rank")?,
+        smiles: get_s("smiles")?,
+        parent_chembl_id: get_s("parent_chembl_id")?,
+        scaffold_id: get_s("scaffold_id")?,
+        novelty,
+        pocket_rank: get_u32("pocket_rank")?,
+        best_affinity_kcal: get_f("best_affinity_kcal")?,
+        admet_score: get_f("admet_score")?,
+        sa_score: get_f("sa_score")?,
+        pains_alerts: serde_json::from_str(&get_s("pains_alerts")?).unwrap_or_default(),
+        composite_score: get_f("composite_score")?,
+        ranked_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn candidate(gene: &str, rank: u32, smiles: &str) -> MoleculeCandidateRecord {
+        MoleculeCandidateRecord {
+            gene: gene.to_string(),
+            cancer_type: "PAAD".to_string(),
+            rank,
+            smiles: smiles.to_string(),
+            parent_chembl_id: "CHEMBL25".to_string(),
+            scaffold_id: "c1ccccc1".to_string(),
+            novelty: rank > 1,
+            pocket_rank: 1,
+            best_affinity_kcal: -9.5 + f64::from(rank),
+            admet_score: 0.9,
+            sa_score: 2.1,
+            pains_alerts: vec!["catechol_A".to_string()],
+            composite_score: 0.8 - 0.1 * f64::from(rank),
+            ranked_at: chrono::Utc.with_ymd_and_hms(2026, 6, 1, 12, 0, 0).unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn a_new_ranking_replaces_the_runs_previous_one() {
+        let dir = std::env::temp_dir().join(format!(
+            "ferrumyx-molecule-candidates-{}",
+            uuid::Uuid::new_v4()
+        ));
+        let db = Arc::new(Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        let repo = MoleculeCandidateRepository::new(db);
+
+        let other_gene = candidate("EGFR", 1, "CCO");
+        repo.replace_ranking("EGFR", "PAAD", &[other_gene.clone()])
+            .await
+            .unwrap();
+        repo.replace_ranking(
+            "KRAS",
+            "PAAD",
+            &[candidate("KRAS", 1, "CCN"), candidate("KRAS", 2, "CCC")],
+        )
+        .await
+        .unwrap();
+
+        let rerun = vec![candidate("KRAS", 2, "CCO"), candidate("KRAS", 1, "CCC")];
+        repo.replace_ranking("KRAS", "PAAD", &rerun).await.unwrap();
+        assert_eq!(repo.count().await.unwrap(), 3);
+        assert_eq!(
+            repo.list_for("KRAS", "PAAD").await.unwrap(),
+            vec![rerun[1].clone(), rerun[0].clone()]
+        );
+        assert_eq!(repo.list().await.unwrap()[0], other_gene);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
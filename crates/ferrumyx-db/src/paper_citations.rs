@@ -0,0 +1,214 @@
+//! Paper citation repository.
+//!
+//! References from stored papers to DOIs, linked to the cited paper when
+//! it is stored too.
+
+use crate::database::{paper_citations_table_schema, Database};
+use crate::error::{DbError, Result};
+use crate::schema::{normalise_doi, PaperCitation, TABLE_PAPER_CITATIONS};
+use crate::schema_evolution::conform_row;
+use std::sync::Arc;
+
+use arrow_array::{Array, RecordBatch, StringArray};
+use futures::StreamExt;
+use lancedb::query::{ExecutableQuery, QueryBase};
+
+/// Repository for paper citation operations.
+#[derive(Clone)]
+pub struct PaperCitationRepository {
+    db: Arc<Database>,
+}
+
+impl PaperCitationRepository {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Insert `citations`, replacing stored rows with the same id.
+    pub async fn upsert_batch(&self, citations: &[PaperCitation]) -> Result<()> {
+        if citations.is_empty() {
+            return Ok(());
+        }
+        let table = self
+            .db
+            .connection()
+            .open_table(TABLE_PAPER_CITATIONS)
+            .execute()
+            .await?;
+        let record = citations_to_record(citations)?;
+        let schema = record.schema();
+        let iter = arrow_array::RecordBatchIterator::new(vec![Ok(record)], schema);
+        let mut builder = table.merge_insert(&["id"]);
+        builder.when_matched_update_all(None);
+        builder.when_not_matched_insert_all();
+        builder.execute(Box::new(iter)).await?;
+        Ok(())
+    }
+
+    /// References made by `paper_id`.
+    pub async fn citations_of(&self, paper_id: uuid::Uuid) -> Result<Vec<PaperCitation>> {
+        self.query(&format!("citing_paper_id = '{}'", paper_id))
+            .await
+    }
+
+    /// Citations of `paper_id` by other stored papers.
+    pub async fn cited_by(&self, paper_id: uuid::Uuid) -> Result<Vec<PaperCitation>> {
+        self.query(&format!("cited_paper_id = '{}'", paper_id))
+            .await
+    }
+
+    /// Citations between papers in `paper_ids`, for drawing edges among
+    /// them.
+    pub async fn edges_among(&self, paper_ids: &[uuid::Uuid]) -> Result<Vec<PaperCitation>> {
+        if paper_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let in_clause = paper_ids
+            .iter()
+            .map(|id| format!("'{}'", id))
+            .collect::<Vec<_>>()
+            .join(",");
+        self.query(&format!(
+            "citing_paper_id IN ({in_clause}) AND cited_paper_id IN ({in_clause})"
+        ))
+        .await
+    }
+
+    /// Point stored citations of `doi` at `paper_id`, e.g. once the cited
+    /// work has been ingested.
+    pub async fn link_cited_paper(&self, doi: &str, paper_id: uuid::Uuid) -> Result<()> {
+        let table = self
+            .db
+            .connection()
+            .open_table(TABLE_PAPER_CITATIONS)
+            .execute()
+            .await?;
+        let doi = normalise_doi(doi).replace('\'', "''");
+        table
+            .update()
+            .only_if(format!("cited_doi = '{doi}' AND cited_paper_id IS NULL"))
+            .column("cited_paper_id", format!("'{}'", paper_id))
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    async fn query(&self, filter: &str) -> Result<Vec<PaperCitation>> {
+        let table = self
+            .db
+            .connection()
+            .open_table(TABLE_PAPER_CITATIONS)
+            .execute()
+            .await?;
+        let mut stream = table.query().only_if(filter).execute().await?;
+        let mut rows = Vec::new();
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            for row in 0..batch.num_rows() {
+                rows.push(record_to_citation(&batch, row)?);
+            }
+        }
+        rows.sort_by(|a, b| a.cited_doi.cmp(&b.cited_doi));
+        Ok(rows)
+    }
+}
+
+fn citations_to_record(citations: &[PaperCitation]) -> Result<RecordBatch> {
+    let strings = |f: &dyn Fn(&PaperCitation) -> Option<String>| -> Arc<dyn Array> {
+        Arc::new(StringArray::from(
+            citations.iter().map(f).collect::<Vec<Option<String>>>(),
+        ))
+    };
+    let cols: Vec<Arc<dyn Array>> = vec![
+        strings(&|c| Some(c.id.to_string())),
+        strings(&|c| Some(c.citing_paper_id.to_string())),
+        strings(&|c| Some(c.cited_doi.clone())),
+        strings(&|c| c.cited_paper_id.map(|id| id.to_string())),
+        strings(&|c| c.context.clone()),
+        strings(&|c| Some(c.source.clone())),
+        strings(&|c| Some(c.created_at.to_rfc3339())),
+    ];
+    Ok(RecordBatch::try_new(paper_citations_table_schema(), cols)?)
+}
+
+fn record_to_citation(batch: &RecordBatch, row: usize) -> Result<PaperCitation> {
+    let (batch, row) = conform_row(batch, row, &paper_citations_table_schema())?;
+    let batch: &RecordBatch = &batch;
+    let get_s = |col: &str| -> Result<Option<String>> {
+        let idx = batch
+            .schema()
+            .index_of(col)
+            .map_err(|e| DbError::Arrow(e.to_string()))?;
+        let arr = batch
+            .column(idx)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .ok_or_else(|| DbError::Arrow(format!("{col} is not StringArray")))?;
+        Ok((!arr.is_null(row)).then(|| arr.value(row).to_string()))
+    };
+    let parse_id =
+        |s: &str| uuid::Uuid::parse_str(s).map_err(|e| DbError::InvalidQuery(e.to_string()));
+
+    let cited_paper_id = match get_s("cited_paper_id")? {
+        Some(s) => Some(parse_id(&s)?),
+        None => None,
+    };
+    let created_at =
+        chrono::DateTime::parse_from_rfc3339(&get_s("created_at")?.unwrap_or_default())
+            .map(|dt| dt.with_timezone(&chrono::Utc))
+            .map_err(|e| DbError::InvalidQuery(e.to_string()))?;
+
+    Ok(PaperCitation {
+        id: parse_id(&get_s("id")?.unwrap_or_default())?,
+        citing_paper_id: parse_id(&get_s("citing_paper_id")?.unwrap_or_default())?,
+        cited_doi: get_s("cited_doi")?.unwrap_or_default(),
+        cited_paper_id,
+        context: get_s("context")?,
+        source: get_s("source")?.unwrap_or_default(),
+        created_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn citations_link_to_stored_papers() {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-citations-{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        let repo = PaperCitationRepository::new(db);
+
+        let citing = uuid::Uuid::new_v4();
+        let cited = uuid::Uuid::new_v4();
+        let mut internal =
+            PaperCitation::new(citing, "https://doi.org/10.1000/KRAS.1", "europepmc");
+        internal.context = Some("KRAS G12D drives PDAC [1].".to_string());
+        let external = PaperCitation::new(citing, "10.1000/other", "crossref");
+        assert_eq!(internal.cited_doi, "10.1000/kras.1");
+        repo.upsert_batch(&[internal.clone(), external.clone()])
+            .await
+            .unwrap();
+        // Re-harvesting replaces rather than duplicates.
+        repo.upsert_batch(&[internal.clone()]).await.unwrap();
+
+        assert_eq!(
+            repo.citations_of(citing).await.unwrap(),
+            vec![internal.clone(), external.clone()]
+        );
+        assert!(repo.cited_by(cited).await.unwrap().is_empty());
+
+        repo.link_cited_paper("10.1000/kras.1", cited)
+            .await
+            .unwrap();
+        let cited_by = repo.cited_by(cited).await.unwrap();
+        assert_eq!(cited_by.len(), 1);
+        assert_eq!(cited_by[0].citing_paper_id, citing);
+        assert_eq!(cited_by[0].context, internal.context);
+        assert!(repo.edges_among(&[citing]).await.unwrap().is_empty());
+        assert_eq!(repo.edges_among(&[citing, cited]).await.unwrap().len(), 1);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,153 @@
+//! Paper tombstone repository.
+//!
+//! [`crate::papers::PaperRepository::delete_papers`] leaves one row per
+//! deleted paper, so a later ingestion run can skip papers already judged
+//! bad instead of storing them again.
+
+use crate::database::{paper_tombstones_table_schema, Database};
+use crate::error::{DbError, Result};
+use crate::schema::{PaperTombstone, TABLE_PAPER_TOMBSTONES};
+use crate::schema_evolution::conform_row;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use arrow_array::{Array, RecordBatch, StringArray};
+use futures::StreamExt;
+use lancedb::query::{ExecutableQuery, QueryBase, Select};
+
+/// Repository for paper tombstone operations.
+#[derive(Clone)]
+pub struct PaperTombstoneRepository {
+    db: Arc<Database>,
+}
+
+/// Identifiers of every tombstoned paper, lower-cased.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TombstonedIds {
+    pub dois: HashSet<String>,
+    pub pmids: HashSet<String>,
+}
+
+impl PaperTombstoneRepository {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Append `tombstones`.
+    pub async fn insert_batch(&self, tombstones: &[PaperTombstone]) -> Result<()> {
+        if tombstones.is_empty() {
+            return Ok(());
+        }
+        let table = self
+            .db
+            .connection()
+            .open_table(TABLE_PAPER_TOMBSTONES)
+            .execute()
+            .await?;
+        let batch = tombstones_to_batch(tombstones)?;
+        let schema = batch.schema();
+        let iter = arrow_array::RecordBatchIterator::new(vec![Ok(batch)], schema);
+        table.add(iter).execute().await?;
+        Ok(())
+    }
+
+    /// Every tombstone, newest first.
+    pub async fn list(&self) -> Result<Vec<PaperTombstone>> {
+        let table = self
+            .db
+            .connection()
+            .open_table(TABLE_PAPER_TOMBSTONES)
+            .execute()
+            .await?;
+        let mut stream = table.query().execute().await?;
+        let mut rows = Vec::new();
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            for row in 0..batch.num_rows() {
+                rows.push(batch_to_tombstone(&batch, row)?);
+            }
+        }
+        rows.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+        Ok(rows)
+    }
+
+    /// DOIs and PMIDs of every tombstoned paper.
+    pub async fn tombstoned_ids(&self) -> Result<TombstonedIds> {
+        let table = self
+            .db
+            .connection()
+            .open_table(TABLE_PAPER_TOMBSTONES)
+            .execute()
+            .await?;
+        let mut stream = table
+            .query()
+            .select(Select::columns(&["doi", "pmid"]))
+            .execute()
+            .await?;
+        let mut ids = TombstonedIds::default();
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            for (column, set) in [("doi", &mut ids.dois), ("pmid", &mut ids.pmids)] {
+                let Some(values) = batch
+                    .column_by_name(column)
+                    .and_then(|a| a.as_any().downcast_ref::<StringArray>())
+                else {
+                    continue;
+                };
+                set.extend(
+                    (0..values.len())
+                        .filter(|&row| !values.is_null(row))
+                        .map(|row| values.value(row).trim().to_ascii_lowercase())
+                        .filter(|v| !v.is_empty()),
+                );
+            }
+        }
+        Ok(ids)
+    }
+}
+
+fn tombstones_to_batch(tombstones: &[PaperTombstone]) -> Result<RecordBatch> {
+    let strings = |f: fn(&PaperTombstone) -> Option<String>| -> Arc<dyn Array> {
+        Arc::new(StringArray::from(
+            tombstones.iter().map(f).collect::<Vec<_>>(),
+        ))
+    };
+    let cols: Vec<Arc<dyn Array>> = vec![
+        strings(|t| Some(t.paper_id.to_string())),
+        strings(|t| t.doi.clone()),
+        strings(|t| t.pmid.clone()),
+        strings(|t| Some(t.title.clone())),
+        strings(|t| Some(t.source.clone())),
+        strings(|t| Some(t.reason.clone())),
+        strings(|t| Some(t.deleted_at.to_rfc3339())),
+    ];
+    Ok(RecordBatch::try_new(paper_tombstones_table_schema(), cols)?)
+}
+
+fn batch_to_tombstone(batch: &RecordBatch, row: usize) -> Result<PaperTombstone> {
+    let (batch, row) = conform_row(batch, row, &paper_tombstones_table_schema())?;
+    let batch: &RecordBatch = &batch;
+    let get_opt = |col: &str| -> Result<Option<String>> {
+        let arr = batch
+            .column_by_name(col)
+            .and_then(|a| a.as_any().downcast_ref::<StringArray>())
+            .ok_or_else(|| DbError::Arrow(format!("{col} is not StringArray")))?;
+        Ok((!arr.is_null(row)).then(|| arr.value(row).to_string()))
+    };
+    let get_s = |col: &str| -> Result<String> { Ok(get_opt(col)?.unwrap_or_default()) };
+
+    let deleted_at = chrono::DateTime::parse_from_rfc3339(&get_s("deleted_at")?)
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+        .map_err(|e| DbError::InvalidQuery(e.to_string()))?;
+
+    Ok(PaperTombstone {
+        paper_id: uuid::Uuid::parse_str(&get_s("paper_id")?)
+            .map_err(|e| DbError::InvalidQuery(e.to_string()))?,
+        doi: get_opt("doi")?,
+        pmid: get_opt("pmid")?,
+        title: get_s("title")?,
+        source: get_s("source")?,
+        reason: get_s("reason")?,
+        deleted_at,
+    })
+}
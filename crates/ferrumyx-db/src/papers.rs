@@ -2,11 +2,15 @@
 //!
 //! Provides CRUD operations for paper metadata.
 
+use crate::chunks::ChunkRepository;
 use crate::database::Database;
-use crate::error::Result;
-use crate::schema::Paper;
+use crate::entity_mentions::EntityMentionRepository;
+use crate::error::{DbError, Result};
+use crate::kg_facts::{FactRetraction, KgFactRepository};
+use crate::paper_tombstones::PaperTombstoneRepository;
+use crate::schema::{Paper, PaperTombstone};
 use crate::schema_arrow::{paper_to_record, record_to_paper};
-use arrow_array::{Array, StringArray};
+use arrow_array::{Array, BinaryArray, BooleanArray, Int64Array, StringArray};
 use futures::StreamExt;
 use lancedb::query::{ExecutableQuery, QueryBase};
 use std::collections::HashMap;
@@ -32,6 +36,85 @@ pub struct PaperReference {
     pub source: Option<String>,
     pub source_id: Option<String>,
     pub published_version_doi: Option<String>,
+    pub is_review: bool,
+    pub journal: Option<String>,
+    pub published_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Stored MinHash signature of a paper, with its abstract for rows stored
+/// before signatures were recorded.
+#[derive(Debug, Clone, Default)]
+pub struct PaperSignature {
+    pub id: uuid::Uuid,
+    pub minhash_signature: Option<Vec<u32>>,
+    pub abstract_text: Option<String>,
+}
+
+/// Papers selected by [`PaperRepository::delete_papers`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaperFilter {
+    /// Papers `source` returned for the search query `query`.
+    SourceQuery { source: String, query: String },
+    /// Papers with any of these DOIs, compared case-insensitively.
+    Dois(Vec<String>),
+    /// Papers stored by one ingestion job.
+    JobId(uuid::Uuid),
+}
+
+impl PaperFilter {
+    /// Filter predicates; a paper matching any of them is selected.
+    fn predicates(&self) -> Result<Vec<String>> {
+        let quote = |v: &str| v.replace('\'', "''");
+        match self {
+            Self::SourceQuery { source, query } => {
+                let (source, query) = (source.trim(), query.trim());
+                if source.is_empty() || query.is_empty() {
+                    return Err(DbError::InvalidQuery(
+                        "source and query must both be set".to_string(),
+                    ));
+                }
+                Ok(vec![format!(
+                    "source = '{}' AND query_tag = '{}'",
+                    quote(source),
+                    quote(query)
+                )])
+            }
+            Self::Dois(dois) => {
+                let mut dois: Vec<String> = dois
+                    .iter()
+                    .map(|d| d.trim().to_ascii_lowercase())
+                    .filter(|d| !d.is_empty())
+                    .collect();
+                dois.sort_unstable();
+                dois.dedup();
+                Ok(crate::maintenance::in_filters("lower(doi)", &dois))
+            }
+            Self::JobId(job_id) => Ok(vec![format!("job_id = '{job_id}'")]),
+        }
+    }
+}
+
+/// A paper removed by [`PaperRepository::delete_papers`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct DeletedPaper {
+    pub id: uuid::Uuid,
+    pub doi: Option<String>,
+    pub pmid: Option<String>,
+    pub title: String,
+    pub source: String,
+}
+
+/// What [`PaperRepository::delete_papers`] removed, or would remove.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct PaperDeletion {
+    pub dry_run: bool,
+    pub papers: Vec<DeletedPaper>,
+    pub chunks: u64,
+    pub entity_mentions: u64,
+    pub kg_facts: FactRetraction,
+    /// Tombstones written; none on a dry run.
+    pub tombstones: usize,
 }
 
 impl PaperRepository {
@@ -213,6 +296,33 @@ impl PaperRepository {
         Ok(papers)
     }
 
+    /// Papers still missing a DOI or a citation count, up to `limit`.
+    pub async fn find_missing_citation_metadata(&self, limit: usize) -> Result<Vec<Paper>> {
+        let table = self
+            .db
+            .connection()
+            .open_table(crate::schema::TABLE_PAPERS)
+            .execute()
+            .await?;
+
+        let mut stream = table
+            .query()
+            .only_if("doi IS NULL OR citation_count IS NULL")
+            .limit(limit)
+            .execute()
+            .await?;
+
+        let mut papers = Vec::new();
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            for i in 0..batch.num_rows() {
+                papers.push(record_to_paper(&batch, i)?);
+            }
+        }
+
+        Ok(papers)
+    }
+
     /// Update a paper.
     pub async fn update(&self, paper: &Paper) -> Result<()> {
         // LanceDB doesn't have direct update, so we use merge_insert
@@ -255,6 +365,53 @@ impl PaperRepository {
         Ok(())
     }
 
+    /// Record the text-quality score of a paper's parsed full text together
+    /// with its parse status.
+    pub async fn update_text_quality(
+        &self,
+        id: uuid::Uuid,
+        status: &str,
+        text_quality: f64,
+    ) -> Result<()> {
+        let table = self
+            .db
+            .connection()
+            .open_table(crate::schema::TABLE_PAPERS)
+            .execute()
+            .await?;
+
+        let escaped = status.replace('\'', "''");
+        table
+            .update()
+            .only_if(&format!("id = '{}'", id))
+            .column("parse_status", format!("'{}'", escaped))
+            .column("text_quality", format!("CAST({} AS DOUBLE)", text_quality))
+            .execute()
+            .await?;
+
+        Ok(())
+    }
+
+    /// Replace the `raw_json` metadata of a paper.
+    pub async fn update_raw_json(&self, id: uuid::Uuid, raw_json: &str) -> Result<()> {
+        let table = self
+            .db
+            .connection()
+            .open_table(crate::schema::TABLE_PAPERS)
+            .execute()
+            .await?;
+
+        let escaped = raw_json.replace('\'', "''");
+        table
+            .update()
+            .only_if(&format!("id = '{}'", id))
+            .column("raw_json", format!("'{}'", escaped))
+            .execute()
+            .await?;
+
+        Ok(())
+    }
+
     /// Delete a paper by ID.
     pub async fn delete(&self, id: uuid::Uuid) -> Result<()> {
         let table = self
@@ -267,6 +424,107 @@ impl PaperRepository {
         Ok(())
     }
 
+    /// Delete the papers `filter` selects, cascading to their chunks and
+    /// entity mentions and withdrawing their support from KG facts, and
+    /// leave a [`PaperTombstone`] with `reason` for each. With `dry_run`
+    /// nothing is written and the report lists what would be removed.
+    ///
+    /// The subjects in `kg_facts.subject_ids` have stale target scores.
+    pub async fn delete_papers(
+        &self,
+        filter: &PaperFilter,
+        reason: &str,
+        dry_run: bool,
+    ) -> Result<PaperDeletion> {
+        let mut report = PaperDeletion {
+            dry_run,
+            ..PaperDeletion::default()
+        };
+        let table = self
+            .db
+            .connection()
+            .open_table(crate::schema::TABLE_PAPERS)
+            .execute()
+            .await?;
+
+        let mut seen = std::collections::HashSet::new();
+        for predicate in filter.predicates()? {
+            let mut stream = table
+                .query()
+                .only_if(&predicate)
+                .select(lancedb::query::Select::columns(&[
+                    "id", "doi", "pmid", "title", "source",
+                ]))
+                .execute()
+                .await?;
+            while let Some(batch) = stream.next().await {
+                let batch = batch?;
+                let column = |name: &str| {
+                    batch
+                        .column_by_name(name)
+                        .and_then(|a| a.as_any().downcast_ref::<StringArray>())
+                        .ok_or_else(|| DbError::Arrow(format!("{name} column was not Utf8")))
+                };
+                let (ids, dois, pmids) = (column("id")?, column("doi")?, column("pmid")?);
+                let (titles, sources) = (column("title")?, column("source")?);
+                for row in 0..batch.num_rows() {
+                    let Ok(id) = uuid::Uuid::parse_str(ids.value(row)) else {
+                        continue;
+                    };
+                    if seen.insert(id) {
+                        report.papers.push(DeletedPaper {
+                            id,
+                            doi: opt_string_at(dois, row),
+                            pmid: opt_string_at(pmids, row),
+                            title: titles.value(row).to_string(),
+                            source: sources.value(row).to_string(),
+                        });
+                    }
+                }
+            }
+        }
+        if report.papers.is_empty() {
+            return Ok(report);
+        }
+
+        let ids: Vec<uuid::Uuid> = report.papers.iter().map(|p| p.id).collect();
+        report.chunks = ChunkRepository::new(self.db.clone())
+            .delete_by_paper_ids(&ids, dry_run)
+            .await?;
+        report.entity_mentions = EntityMentionRepository::new(self.db.clone())
+            .delete_by_paper_ids(&ids, dry_run)
+            .await?;
+        report.kg_facts = KgFactRepository::new(self.db.clone())
+            .retract_papers(&ids, dry_run)
+            .await?;
+        if dry_run {
+            return Ok(report);
+        }
+
+        let deleted_at = ferrumyx_common::repro::now();
+        let tombstones: Vec<PaperTombstone> = report
+            .papers
+            .iter()
+            .map(|p| PaperTombstone {
+                paper_id: p.id,
+                doi: p.doi.clone(),
+                pmid: p.pmid.clone(),
+                title: p.title.clone(),
+                source: p.source.clone(),
+                reason: reason.to_string(),
+                deleted_at,
+            })
+            .collect();
+        PaperTombstoneRepository::new(self.db.clone())
+            .insert_batch(&tombstones)
+            .await?;
+        report.tombstones = tombstones.len();
+
+        let keys: Vec<String> = ids.iter().map(ToString::to_string).collect();
+        crate::maintenance::delete_in(&table, "id", &keys).await?;
+        Ok(report)
+    }
+
     /// Count total papers.
     pub async fn count(&self) -> Result<u64> {
         let table = self
@@ -352,7 +610,8 @@ impl PaperRepository {
         Ok(out)
     }
 
-    /// Resolve paper title + identifier metadata for a list of IDs in one query.
+    /// Resolve paper title, identifier and citation metadata for a list of IDs
+    /// in one query.
     pub async fn find_references_by_ids(
         &self,
         ids: &[uuid::Uuid],
@@ -389,6 +648,9 @@ impl PaperRepository {
                 "source",
                 "source_id",
                 "published_version_doi",
+                "is_review",
+                "journal",
+                "published_at",
             ]))
             .execute()
             .await?;
@@ -474,6 +736,18 @@ impl PaperRepository {
                 Some(a) => a,
                 None => continue,
             };
+            let is_review_arr = schema
+                .index_of("is_review")
+                .ok()
+                .and_then(|i| batch.column(i).as_any().downcast_ref::<BooleanArray>());
+            let journal_arr = schema
+                .index_of("journal")
+                .ok()
+                .and_then(|i| batch.column(i).as_any().downcast_ref::<StringArray>());
+            let published_arr = schema
+                .index_of("published_at")
+                .ok()
+                .and_then(|i| batch.column(i).as_any().downcast_ref::<StringArray>());
 
             for row in 0..batch.num_rows() {
                 if ids_arr.is_null(row) || titles_arr.is_null(row) {
@@ -491,6 +765,12 @@ impl PaperRepository {
                         source: opt_string_at(source_arr, row),
                         source_id: opt_string_at(source_id_arr, row),
                         published_version_doi: opt_string_at(published_version_doi_arr, row),
+                        is_review: is_review_arr.is_some_and(|a| !a.is_null(row) && a.value(row)),
+                        journal: journal_arr.and_then(|a| opt_string_at(a, row)),
+                        published_at: published_arr
+                            .and_then(|a| opt_string_at(a, row))
+                            .and_then(|v| chrono::DateTime::parse_from_rfc3339(&v).ok())
+                            .map(|dt| dt.with_timezone(&chrono::Utc)),
                     },
                 );
             }
@@ -719,6 +999,7 @@ impl PaperRepository {
                 "id",
                 "published_at",
                 "raw_json",
+                "citation_count",
             ]))
             .execute()
             .await?;
@@ -756,6 +1037,10 @@ impl PaperRepository {
                 Some(a) => a,
                 None => continue,
             };
+            let citation_arr = schema
+                .index_of("citation_count")
+                .ok()
+                .and_then(|i| batch.column(i).as_any().downcast_ref::<Int64Array>());
 
             for row in 0..batch.num_rows() {
                 if ids_arr.is_null(row) {
@@ -773,11 +1058,17 @@ impl PaperRepository {
                     None
                 };
 
-                let citation_count = if !raw_arr.is_null(row) {
-                    extract_citation_count(raw_arr.value(row))
-                } else {
-                    None
-                };
+                // The enriched column wins; raw source payloads are the fallback.
+                let citation_count = citation_arr
+                    .filter(|a| !a.is_null(row))
+                    .map(|a| a.value(row).clamp(0, u32::MAX as i64) as u32)
+                    .or_else(|| {
+                        if !raw_arr.is_null(row) {
+                            extract_citation_count(raw_arr.value(row))
+                        } else {
+                            None
+                        }
+                    });
 
                 out.insert(
                     id,
@@ -843,6 +1134,73 @@ impl PaperRepository {
 
         Ok(papers)
     }
+
+    /// MinHash signatures of all stored papers, for building the
+    /// near-duplicate index.
+    pub async fn list_signatures(&self) -> Result<Vec<PaperSignature>> {
+        let table = self
+            .db
+            .connection()
+            .open_table(crate::schema::TABLE_PAPERS)
+            .execute()
+            .await?;
+
+        let mut stream = table
+            .query()
+            .select(lancedb::query::Select::columns(&[
+                "id",
+                "minhash_signature",
+                "abstract_text",
+            ]))
+            .execute()
+            .await?;
+
+        let mut out = Vec::new();
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            let schema = batch.schema();
+            let Some(ids_arr) = schema
+                .index_of("id")
+                .ok()
+                .and_then(|i| batch.column(i).as_any().downcast_ref::<StringArray>())
+            else {
+                continue;
+            };
+            let signature_arr = schema
+                .index_of("minhash_signature")
+                .ok()
+                .and_then(|i| batch.column(i).as_any().downcast_ref::<BinaryArray>());
+            let abstract_arr = schema
+                .index_of("abstract_text")
+                .ok()
+                .and_then(|i| batch.column(i).as_any().downcast_ref::<StringArray>());
+
+            for row in 0..batch.num_rows() {
+                if ids_arr.is_null(row) {
+                    continue;
+                }
+                let Ok(id) = uuid::Uuid::parse_str(ids_arr.value(row)) else {
+                    continue;
+                };
+                let minhash_signature = signature_arr.filter(|a| !a.is_null(row)).map(|a| {
+                    a.value(row)
+                        .chunks_exact(4)
+                        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                        .collect()
+                });
+                let abstract_text = abstract_arr
+                    .filter(|a| !a.is_null(row))
+                    .map(|a| a.value(row).to_string());
+                out.push(PaperSignature {
+                    id,
+                    minhash_signature,
+                    abstract_text,
+                });
+            }
+        }
+
+        Ok(out)
+    }
 }
 
 fn extract_citation_count(raw_json: &str) -> Option<u32> {
@@ -881,7 +1239,7 @@ fn opt_string_at(array: &StringArray, idx: usize) -> Option<String> {
 
 #[cfg(test)]
 mod tests {
-    use super::extract_citation_count;
+    use super::*;
 
     #[test]
     fn citation_extract_supports_common_keys() {
@@ -901,4 +1259,188 @@ mod tests {
         assert_eq!(extract_citation_count("{}"), None);
         assert_eq!(extract_citation_count("{not-json"), None);
     }
+
+    #[tokio::test]
+    async fn enriched_citation_count_overrides_raw_payload() {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-papers-{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        let repo = PaperRepository::new(db);
+
+        let mut paper = Paper::new("KRAS G12C inhibitors".to_string(), "pubmed".to_string());
+        paper.raw_json = Some(r#"{"citationCount": 3}"#.to_string());
+        repo.insert(&paper).await.unwrap();
+
+        let missing = repo.find_missing_citation_metadata(10).await.unwrap();
+        assert_eq!(missing.len(), 1);
+        let signals = repo.find_novelty_signals_by_ids(&[paper.id]).await.unwrap();
+        assert_eq!(signals[&paper.id].citation_count, Some(3));
+
+        paper.doi = Some("10.1000/kras.1".to_string());
+        paper.citation_count = Some(41);
+        repo.update(&paper).await.unwrap();
+
+        let stored = repo.find_by_id(paper.id).await.unwrap().unwrap();
+        assert_eq!(stored.citation_count, Some(41));
+        let missing = repo.find_missing_citation_metadata(10).await.unwrap();
+        assert!(missing.is_empty());
+        let signals = repo.find_novelty_signals_by_ids(&[paper.id]).await.unwrap();
+        assert_eq!(signals[&paper.id].citation_count, Some(41));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn signatures_list_with_abstract_fallback() {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-papers-{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        let repo = PaperRepository::new(db);
+
+        let mut signed = Paper::new("KRAS G12D".to_string(), "pubmed".to_string());
+        signed.minhash_signature = Some(vec![1, 2, 3]);
+        let mut legacy = Paper::new("KRAS G12V".to_string(), "pubmed".to_string());
+        legacy.abstract_text = Some("KRAS G12V drives PDAC.".to_string());
+        repo.insert(&signed).await.unwrap();
+        repo.insert(&legacy).await.unwrap();
+
+        let mut rows = repo.list_signatures().await.unwrap();
+        rows.sort_by_key(|r| r.minhash_signature.is_none());
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].id, signed.id);
+        assert_eq!(rows[0].minhash_signature, Some(vec![1, 2, 3]));
+        assert_eq!(rows[1].id, legacy.id);
+        assert_eq!(rows[1].abstract_text, legacy.abstract_text);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn text_quality_is_stored_with_parse_status() {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-papers-{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        let repo = PaperRepository::new(db);
+
+        let paper = Paper::new("Garbled PDF".to_string(), "pubmed".to_string());
+        repo.insert(&paper).await.unwrap();
+        repo.update_text_quality(paper.id, "low_quality", 0.25)
+            .await
+            .unwrap();
+
+        let stored = repo.find_by_id(paper.id).await.unwrap().unwrap();
+        assert_eq!(stored.parse_status, "low_quality");
+        assert_eq!(stored.text_quality, Some(0.25));
+        assert_eq!(repo.count_by_parse_status("low_quality").await.unwrap(), 1);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn deleting_a_job_cascades_and_leaves_tombstones() {
+        use crate::schema::{Chunk, EntityMention, FactSupport, KgFact};
+
+        let dir = std::env::temp_dir().join(format!("ferrumyx-papers-{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        let repo = PaperRepository::new(db.clone());
+
+        let bad_job = uuid::Uuid::from_u128(7);
+        let mut bad = Paper::new("Off-topic".to_string(), "pubmed".to_string());
+        bad.doi = Some("10.1000/Bad.1".to_string());
+        bad.job_id = Some(bad_job);
+        bad.query_tag = Some("kras".to_string());
+        let mut kept = Paper::new("KRAS G12D".to_string(), "pubmed".to_string());
+        kept.doi = Some("10.1000/kept.1".to_string());
+        repo.insert_batch(&[bad.clone(), kept.clone()])
+            .await
+            .unwrap();
+
+        let chunks = ChunkRepository::new(db.clone());
+        let bad_chunk = Chunk::new(bad.id, 0, "off-topic text".to_string());
+        chunks
+            .insert_batch(&[
+                bad_chunk.clone(),
+                Chunk::new(kept.id, 0, "KRAS G12D drives PDAC.".to_string()),
+            ])
+            .await
+            .unwrap();
+        let mentions = EntityMentionRepository::new(db.clone());
+        mentions
+            .insert(&EntityMention::new(
+                uuid::Uuid::from_u128(1),
+                bad_chunk.id,
+                bad.id,
+                "KRAS".to_string(),
+                0,
+                4,
+            ))
+            .await
+            .unwrap();
+
+        let fact = |paper_id, object_id: u128| {
+            KgFact::new(
+                paper_id,
+                uuid::Uuid::from_u128(1),
+                "KRAS".to_string(),
+                "associated_with".to_string(),
+                uuid::Uuid::from_u128(object_id),
+                "PAAD".to_string(),
+            )
+        };
+        let support = |paper_id| FactSupport {
+            paper_id,
+            confidence: 0.5,
+            evidence: None,
+            chunk_id: None,
+            published_at: None,
+        };
+        let mut shared = fact(bad.id, 2);
+        shared.supporting_evidence = vec![support(bad.id), support(kept.id)];
+        shared.support_count = 2;
+        let facts = KgFactRepository::new(db.clone());
+        facts
+            .insert_batch(&[fact(bad.id, 3), shared.clone(), fact(kept.id, 4)])
+            .await
+            .unwrap();
+
+        let preview = repo
+            .delete_papers(&PaperFilter::JobId(bad_job), "off-topic", true)
+            .await
+            .unwrap();
+        assert_eq!(preview.papers.len(), 1);
+        assert_eq!(preview.papers[0].id, bad.id);
+        assert_eq!(
+            (preview.chunks, preview.entity_mentions, preview.tombstones),
+            (1, 1, 0)
+        );
+        assert_eq!((preview.kg_facts.deleted, preview.kg_facts.updated), (1, 1));
+        assert_eq!(preview.kg_facts.subject_ids, vec![uuid::Uuid::from_u128(1)]);
+        assert_eq!(repo.count().await.unwrap(), 2);
+        assert_eq!(facts.count().await.unwrap(), 3);
+
+        let by_doi = PaperFilter::Dois(vec!["10.1000/bad.1".to_string()]);
+        let report = repo
+            .delete_papers(&by_doi, "off-topic", false)
+            .await
+            .unwrap();
+        assert_eq!(report.papers, preview.papers);
+        assert_eq!(report.tombstones, 1);
+        assert!(repo.find_by_id(bad.id).await.unwrap().is_none());
+        assert_eq!(chunks.count().await.unwrap(), 1);
+        assert_eq!(mentions.count().await.unwrap(), 0);
+        assert_eq!(facts.count().await.unwrap(), 2);
+        let reweighted = facts.find_by_id(shared.id).await.unwrap().unwrap();
+        assert_eq!(reweighted.paper_id, kept.id);
+        assert_eq!(reweighted.support_count, 1);
+
+        let tombstones = PaperTombstoneRepository::new(db.clone());
+        let listed = tombstones.list().await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].reason, "off-topic");
+        let known = tombstones.tombstoned_ids().await.unwrap();
+        assert!(known.dois.contains("10.1000/bad.1"));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
 }
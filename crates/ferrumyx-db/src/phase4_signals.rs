@@ -2,17 +2,25 @@
 //!
 //! Stores bounded external enrichments so ranker can avoid repeated API calls.
 
-use crate::database::Database;
+use crate::database::{
+    ent_cbio_mutation_frequency_table_schema, ent_chembl_targets_table_schema,
+    ent_cosmic_mutation_frequency_table_schema, ent_gtex_expression_table_schema,
+    ent_provider_refresh_runs_table_schema, ent_reactome_genes_table_schema,
+    ent_structural_assessments_table_schema, ent_tcga_survival_table_schema, Database,
+};
 use crate::error::{DbError, Result};
 use crate::schema::{
     EntCbioMutationFrequency, EntChemblTarget, EntCosmicMutationFrequency, EntGtexExpression,
-    EntProviderRefreshRun, EntReactomeGene, EntTcgaSurvival, TABLE_ENT_CBIO_MUTATION_FREQUENCY,
-    TABLE_ENT_CHEMBL_TARGETS, TABLE_ENT_COSMIC_MUTATION_FREQUENCY, TABLE_ENT_GTEX_EXPRESSION,
-    TABLE_ENT_PROVIDER_REFRESH_RUNS, TABLE_ENT_REACTOME_GENES, TABLE_ENT_TCGA_SURVIVAL,
+    EntProviderRefreshRun, EntReactomeGene, EntStructuralAssessment, EntTcgaSurvival,
+    TABLE_ENT_CBIO_MUTATION_FREQUENCY, TABLE_ENT_CHEMBL_TARGETS,
+    TABLE_ENT_COSMIC_MUTATION_FREQUENCY, TABLE_ENT_GTEX_EXPRESSION,
+    TABLE_ENT_PROVIDER_REFRESH_RUNS, TABLE_ENT_REACTOME_GENES, TABLE_ENT_STRUCTURAL_ASSESSMENTS,
+    TABLE_ENT_TCGA_SURVIVAL,
 };
+use crate::schema_evolution::conform_row;
 use std::sync::Arc;
 
-use arrow_array::{Array, Float64Array, Int64Array, RecordBatch, StringArray};
+use arrow_array::{Array, BooleanArray, Float64Array, Int64Array, RecordBatch, StringArray};
 use arrow_schema::{DataType, Field, Schema};
 use futures::StreamExt;
 use lancedb::query::{ExecutableQuery, QueryBase};
@@ -350,6 +358,59 @@ impl Phase4SignalRepository {
         Ok(())
     }
 
+    pub async fn find_structural_assessment(
+        &self,
+        uniprot_id: &str,
+    ) -> Result<Option<EntStructuralAssessment>> {
+        let accession = normalize_symbol(uniprot_id);
+        if accession.is_empty() {
+            return Ok(None);
+        }
+        let table = self
+            .db
+            .connection()
+            .open_table(TABLE_ENT_STRUCTURAL_ASSESSMENTS)
+            .execute()
+            .await?;
+        let filter = format!("uniprot_id = '{}'", escape_sql(&accession));
+        let mut stream = table.query().only_if(&filter).limit(1).execute().await?;
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            if batch.num_rows() > 0 {
+                return Ok(Some(record_to_structural_assessment(&batch, 0)?));
+            }
+        }
+        Ok(None)
+    }
+
+    pub async fn find_structural_assessment_fresh(
+        &self,
+        uniprot_id: &str,
+        max_age_days: i64,
+    ) -> Result<Option<EntStructuralAssessment>> {
+        let found = self.find_structural_assessment(uniprot_id).await?;
+        Ok(found.filter(|v| is_fresh(v.fetched_at, max_age_days)))
+    }
+
+    pub async fn upsert_structural_assessment(
+        &self,
+        signal: &EntStructuralAssessment,
+    ) -> Result<()> {
+        let table = self
+            .db
+            .connection()
+            .open_table(TABLE_ENT_STRUCTURAL_ASSESSMENTS)
+            .execute()
+            .await?;
+        let record = structural_assessment_to_record(signal)?;
+        let schema = record.schema();
+        let iter = arrow_array::RecordBatchIterator::new(vec![Ok(record)], schema);
+        let mut builder = table.merge_insert(&["uniprot_id"]);
+        builder.when_matched_update_all(None);
+        builder.execute(Box::new(iter)).await?;
+        Ok(())
+    }
+
     pub async fn find_reactome_gene(&self, gene_symbol: &str) -> Result<Option<EntReactomeGene>> {
         let gene = normalize_symbol(gene_symbol);
         if gene.is_empty() {
@@ -613,6 +674,26 @@ fn chembl_target_to_record(signal: &EntChemblTarget) -> Result<RecordBatch> {
     Ok(RecordBatch::try_new(schema, cols)?)
 }
 
+fn structural_assessment_to_record(signal: &EntStructuralAssessment) -> Result<RecordBatch> {
+    let schema = ent_structural_assessments_table_schema();
+    let cols: Vec<Arc<dyn Array>> = vec![
+        Arc::new(StringArray::from(vec![signal.id.to_string()])),
+        Arc::new(StringArray::from(vec![normalize_symbol(
+            &signal.uniprot_id,
+        )])),
+        Arc::new(BooleanArray::from(vec![signal.has_pdb])),
+        Arc::new(Int64Array::from(vec![signal.pdb_count])),
+        Arc::new(StringArray::from(vec![signal.best_pdb_id.clone()])),
+        Arc::new(Float64Array::from(vec![signal.best_resolution])),
+        Arc::new(Float64Array::from(vec![signal.alphafold_plddt])),
+        Arc::new(Float64Array::from(vec![signal.best_pocket_score])),
+        Arc::new(Int64Array::from(vec![signal.pocket_count])),
+        Arc::new(StringArray::from(vec![signal.source.clone()])),
+        Arc::new(StringArray::from(vec![signal.fetched_at.to_rfc3339()])),
+    ];
+    Ok(RecordBatch::try_new(schema, cols)?)
+}
+
 fn reactome_gene_to_record(signal: &EntReactomeGene) -> Result<RecordBatch> {
     let schema = Arc::new(Schema::new(vec![
         Field::new("id", DataType::Utf8, false),
@@ -670,6 +751,8 @@ fn provider_refresh_run_to_record(signal: &EntProviderRefreshRun) -> Result<Reco
 }
 
 fn record_to_tcga_survival(batch: &RecordBatch, row: usize) -> Result<EntTcgaSurvival> {
+    let (batch, row) = conform_row(batch, row, &ent_tcga_survival_table_schema())?;
+    let batch: &RecordBatch = &batch;
     let get_s = |col: &str| -> Result<String> {
         let idx = batch
             .schema()
@@ -724,6 +807,8 @@ fn record_to_cbio_mutation_frequency(
     batch: &RecordBatch,
     row: usize,
 ) -> Result<EntCbioMutationFrequency> {
+    let (batch, row) = conform_row(batch, row, &ent_cbio_mutation_frequency_table_schema())?;
+    let batch: &RecordBatch = &batch;
     let get_s = |col: &str| -> Result<String> {
         let idx = batch
             .schema()
@@ -798,6 +883,8 @@ fn record_to_cosmic_mutation_frequency(
     batch: &RecordBatch,
     row: usize,
 ) -> Result<EntCosmicMutationFrequency> {
+    let (batch, row) = conform_row(batch, row, &ent_cosmic_mutation_frequency_table_schema())?;
+    let batch: &RecordBatch = &batch;
     let get_s = |col: &str| -> Result<String> {
         let idx = batch
             .schema()
@@ -866,6 +953,8 @@ fn record_to_cosmic_mutation_frequency(
 }
 
 fn record_to_gtex_expression(batch: &RecordBatch, row: usize) -> Result<EntGtexExpression> {
+    let (batch, row) = conform_row(batch, row, &ent_gtex_expression_table_schema())?;
+    let batch: &RecordBatch = &batch;
     let get_s = |col: &str| -> Result<String> {
         let idx = batch
             .schema()
@@ -915,6 +1004,8 @@ fn record_to_gtex_expression(batch: &RecordBatch, row: usize) -> Result<EntGtexE
 }
 
 fn record_to_chembl_target(batch: &RecordBatch, row: usize) -> Result<EntChemblTarget> {
+    let (batch, row) = conform_row(batch, row, &ent_chembl_targets_table_schema())?;
+    let batch: &RecordBatch = &batch;
     let get_s = |col: &str| -> Result<String> {
         let idx = batch
             .schema()
@@ -963,7 +1054,70 @@ fn record_to_chembl_target(batch: &RecordBatch, row: usize) -> Result<EntChemblT
     })
 }
 
+fn record_to_structural_assessment(
+    batch: &RecordBatch,
+    row: usize,
+) -> Result<EntStructuralAssessment> {
+    let (batch, row) = conform_row(batch, row, &ent_structural_assessments_table_schema())?;
+    let batch: &RecordBatch = &batch;
+    let column = |col: &str| -> Result<&Arc<dyn Array>> {
+        let idx = batch
+            .schema()
+            .index_of(col)
+            .map_err(|e| DbError::Arrow(e.to_string()))?;
+        Ok(batch.column(idx))
+    };
+    let get_opt_s = |col: &str| -> Result<Option<String>> {
+        let arr = column(col)?
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .ok_or_else(|| DbError::Arrow(format!("{col} is not StringArray")))?;
+        Ok((!arr.is_null(row)).then(|| arr.value(row).to_string()))
+    };
+    let get_opt_f = |col: &str| -> Result<Option<f64>> {
+        let arr = column(col)?
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .ok_or_else(|| DbError::Arrow(format!("{col} is not Float64Array")))?;
+        Ok((!arr.is_null(row)).then(|| arr.value(row)))
+    };
+    let get_opt_i = |col: &str| -> Result<Option<i64>> {
+        let arr = column(col)?
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .ok_or_else(|| DbError::Arrow(format!("{col} is not Int64Array")))?;
+        Ok((!arr.is_null(row)).then(|| arr.value(row)))
+    };
+    let has_pdb = column("has_pdb")?
+        .as_any()
+        .downcast_ref::<BooleanArray>()
+        .ok_or_else(|| DbError::Arrow("has_pdb is not BooleanArray".to_string()))?;
+
+    let id = uuid::Uuid::parse_str(&get_opt_s("id")?.unwrap_or_default())
+        .map_err(|e| DbError::InvalidQuery(e.to_string()))?;
+    let fetched_at =
+        chrono::DateTime::parse_from_rfc3339(&get_opt_s("fetched_at")?.unwrap_or_default())
+            .map(|dt| dt.with_timezone(&chrono::Utc))
+            .unwrap_or_else(|_| chrono::Utc::now());
+
+    Ok(EntStructuralAssessment {
+        id,
+        uniprot_id: get_opt_s("uniprot_id")?.unwrap_or_default(),
+        has_pdb: !has_pdb.is_null(row) && has_pdb.value(row),
+        pdb_count: get_opt_i("pdb_count")?.unwrap_or(0),
+        best_pdb_id: get_opt_s("best_pdb_id")?,
+        best_resolution: get_opt_f("best_resolution")?,
+        alphafold_plddt: get_opt_f("alphafold_plddt")?,
+        best_pocket_score: get_opt_f("best_pocket_score")?,
+        pocket_count: get_opt_i("pocket_count")?,
+        source: get_opt_s("source")?.unwrap_or_default(),
+        fetched_at,
+    })
+}
+
 fn record_to_reactome_gene(batch: &RecordBatch, row: usize) -> Result<EntReactomeGene> {
+    let (batch, row) = conform_row(batch, row, &ent_reactome_genes_table_schema())?;
+    let batch: &RecordBatch = &batch;
     let get_s = |col: &str| -> Result<String> {
         let idx = batch
             .schema()
@@ -1016,6 +1170,8 @@ fn record_to_provider_refresh_run(
     batch: &RecordBatch,
     row: usize,
 ) -> Result<EntProviderRefreshRun> {
+    let (batch, row) = conform_row(batch, row, &ent_provider_refresh_runs_table_schema())?;
+    let batch: &RecordBatch = &batch;
     let get_s = |col: &str| -> Result<String> {
         let idx = batch
             .schema()
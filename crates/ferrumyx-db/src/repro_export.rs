@@ -0,0 +1,288 @@
+//! Deterministic JSONL export of pipeline outputs, and a field-level diff
+//! between two exports.
+//!
+//! Used by `ferrumyx verify-repro` to check that two runs over the same
+//! inputs persisted the same papers, chunks, facts and scores. Rows are
+//! matched across runs by a content key rather than by id, so a run with
+//! reproducibility mode off still lines up and its differences can be
+//! attributed to the volatile id and timestamp columns.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::chunks::ChunkRepository;
+use crate::database::Database;
+use crate::error::Result;
+use crate::kg_facts::KgFactRepository;
+use crate::papers::PaperRepository;
+use crate::schema::{TABLE_CHUNKS, TABLE_KG_FACTS, TABLE_PAPERS, TABLE_TARGET_SCORES};
+use crate::target_scores::TargetScoreRepository;
+
+/// Tables covered by the export, in output order.
+pub const EXPORT_TABLES: [&str; 4] = [
+    TABLE_PAPERS,
+    TABLE_CHUNKS,
+    TABLE_KG_FACTS,
+    TABLE_TARGET_SCORES,
+];
+
+/// Columns that legitimately differ between runs outside reproducibility
+/// mode: generated identifiers, references to them, and wall-clock stamps.
+pub const VOLATILE_FIELDS: [&str; 12] = [
+    "id",
+    "paper_id",
+    "subject_id",
+    "object_id",
+    "gene_id",
+    "cancer_id",
+    "created_at",
+    "updated_at",
+    "ingested_at",
+    "valid_from",
+    "valid_until",
+    "detected_at",
+];
+
+/// Vector columns are deterministic given their content and too large to
+/// diff usefully, so they are left out of the export.
+const SKIPPED_FIELDS: [&str; 2] = ["embedding", "embedding_large"];
+
+/// Exported rows per table, each sorted by content key.
+pub type Export = BTreeMap<String, Vec<Value>>;
+
+/// One field that differs between two exports.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExportDifference {
+    pub table: String,
+    /// Content key of the row (see [`row_key`]).
+    pub row: String,
+    /// Differing column, or `"<missing>"` when the row exists on one side only.
+    pub field: String,
+}
+
+impl ExportDifference {
+    /// Whether the difference is confined to an id or timestamp column.
+    pub fn is_volatile(&self) -> bool {
+        VOLATILE_FIELDS.contains(&self.field.as_str())
+    }
+}
+
+/// Export papers, chunks, facts and scores from `db`.
+pub async fn export_tables(db: Arc<Database>) -> Result<Export> {
+    let mut out = Export::new();
+
+    let papers = PaperRepository::new(db.clone());
+    let total = papers.count().await? as usize;
+    let rows = papers.list(0, total.max(1)).await?;
+    out.insert(TABLE_PAPERS.to_string(), to_rows(TABLE_PAPERS, &rows)?);
+
+    let chunks = ChunkRepository::new(db.clone());
+    let total = chunks.count().await? as usize;
+    let rows = chunks.list(0, total.max(1)).await?;
+    out.insert(TABLE_CHUNKS.to_string(), to_rows(TABLE_CHUNKS, &rows)?);
+
+    let facts = KgFactRepository::new(db.clone());
+    let total = facts.count().await? as usize;
+    let rows = facts.list(0, total.max(1)).await?;
+    out.insert(TABLE_KG_FACTS.to_string(), to_rows(TABLE_KG_FACTS, &rows)?);
+
+    let scores = TargetScoreRepository::new(db);
+    let total = scores.count().await? as usize;
+    let rows = scores.list(0, total.max(1)).await?;
+    out.insert(
+        TABLE_TARGET_SCORES.to_string(),
+        to_rows(TABLE_TARGET_SCORES, &rows)?,
+    );
+
+    Ok(out)
+}
+
+/// Write one `<table>.jsonl` file per exported table under `dir`.
+pub fn write_jsonl(export: &Export, dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir)?;
+    for (table, rows) in export {
+        let mut body = String::new();
+        for row in rows {
+            body.push_str(&serde_json::to_string(row)?);
+            body.push('\n');
+        }
+        std::fs::write(dir.join(format!("{table}.jsonl")), body)?;
+    }
+    Ok(())
+}
+
+/// Content key used to line rows up across runs.
+///
+/// Built only from non-volatile columns so it is stable even when ids and
+/// timestamps are not.
+pub fn row_key(table: &str, row: &Value) -> String {
+    let fields: &[&str] = match table {
+        TABLE_PAPERS => &["source", "doi", "pmid", "title"],
+        TABLE_CHUNKS => &["chunk_index", "section", "content"],
+        TABLE_KG_FACTS => &[
+            "subject_name",
+            "predicate",
+            "object_name",
+            "evidence_type",
+            "evidence",
+        ],
+        TABLE_TARGET_SCORES => &["components_raw", "score_version"],
+        _ => &[],
+    };
+    fields
+        .iter()
+        .map(|f| match row.get(*f) {
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Null) | None => String::new(),
+            Some(other) => other.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+/// Field-level differences between two exports.
+pub fn diff_exports(left: &Export, right: &Export) -> Vec<ExportDifference> {
+    let mut diffs = Vec::new();
+    for table in EXPORT_TABLES {
+        let empty = Vec::new();
+        let l = keyed(table, left.get(table).unwrap_or(&empty));
+        let r = keyed(table, right.get(table).unwrap_or(&empty));
+
+        for (key, l_rows) in &l {
+            let Some(r_rows) = r.get(key) else {
+                diffs.push(missing(table, key));
+                continue;
+            };
+            if l_rows.len() != r_rows.len() {
+                diffs.push(missing(table, key));
+            }
+            for (a, b) in l_rows.iter().zip(r_rows) {
+                diff_row(table, key, a, b, &mut diffs);
+            }
+        }
+        for key in r.keys().filter(|k| !l.contains_key(*k)) {
+            diffs.push(missing(table, key));
+        }
+    }
+    diffs
+}
+
+fn to_rows<T: Serialize>(table: &str, rows: &[T]) -> Result<Vec<Value>> {
+    let mut out = Vec::with_capacity(rows.len());
+    for row in rows {
+        let mut value = serde_json::to_value(row)?;
+        if let Value::Object(map) = &mut value {
+            for field in SKIPPED_FIELDS {
+                map.remove(field);
+            }
+        }
+        out.push(value);
+    }
+    out.sort_by_cached_key(|v| (row_key(table, v), v.to_string()));
+    Ok(out)
+}
+
+fn keyed<'a>(table: &str, rows: &'a [Value]) -> BTreeMap<String, Vec<&'a Value>> {
+    let mut out: BTreeMap<String, Vec<&Value>> = BTreeMap::new();
+    for row in rows {
+        out.entry(row_key(table, row)).or_default().push(row);
+    }
+    out
+}
+
+fn diff_row(table: &str, key: &str, a: &Value, b: &Value, out: &mut Vec<ExportDifference>) {
+    let (Value::Object(a), Value::Object(b)) = (a, b) else {
+        if a != b {
+            out.push(missing(table, key));
+        }
+        return;
+    };
+    let mut fields: Vec<&String> = a.keys().chain(b.keys()).collect();
+    fields.sort();
+    fields.dedup();
+    for field in fields {
+        if a.get(field) != b.get(field) {
+            out.push(ExportDifference {
+                table: table.to_string(),
+                row: key.to_string(),
+                field: field.clone(),
+            });
+        }
+    }
+}
+
+fn missing(table: &str, key: &str) -> ExportDifference {
+    ExportDifference {
+        table: table.to_string(),
+        row: key.to_string(),
+        field: "<missing>".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn export_with(table: &str, rows: Vec<Value>) -> Export {
+        let mut out = Export::new();
+        out.insert(table.to_string(), rows);
+        out
+    }
+
+    #[test]
+    fn identical_exports_have_no_differences() {
+        let rows = vec![json!({"id": "a", "title": "KRAS", "source": "pubmed"})];
+        let left = export_with(TABLE_PAPERS, rows.clone());
+        let right = export_with(TABLE_PAPERS, rows);
+        assert!(diff_exports(&left, &right).is_empty());
+    }
+
+    #[test]
+    fn rows_align_by_content_and_report_volatile_fields() {
+        let left = export_with(
+            TABLE_KG_FACTS,
+            vec![json!({
+                "id": "1", "subject_name": "KRAS", "predicate": "has_mutation",
+                "object_name": "G12D", "confidence": 0.9, "created_at": "t1"
+            })],
+        );
+        let right = export_with(
+            TABLE_KG_FACTS,
+            vec![json!({
+                "id": "2", "subject_name": "KRAS", "predicate": "has_mutation",
+                "object_name": "G12D", "confidence": 0.9, "created_at": "t2"
+            })],
+        );
+        let diffs = diff_exports(&left, &right);
+        let fields: Vec<&str> = diffs.iter().map(|d| d.field.as_str()).collect();
+        assert_eq!(fields, vec!["created_at", "id"]);
+        assert!(diffs.iter().all(ExportDifference::is_volatile));
+    }
+
+    #[test]
+    fn content_changes_and_missing_rows_are_not_volatile() {
+        let left = export_with(
+            TABLE_KG_FACTS,
+            vec![
+                json!({"subject_name": "KRAS", "predicate": "p", "object_name": "A", "confidence": 0.9}),
+                json!({"subject_name": "TP53", "predicate": "p", "object_name": "B", "confidence": 0.5}),
+            ],
+        );
+        let right = export_with(
+            TABLE_KG_FACTS,
+            vec![
+                json!({"subject_name": "KRAS", "predicate": "p", "object_name": "A", "confidence": 0.8}),
+            ],
+        );
+        let diffs = diff_exports(&left, &right);
+        assert_eq!(diffs.len(), 2);
+        assert!(diffs.iter().any(|d| d.field == "confidence"));
+        assert!(diffs.iter().any(|d| d.field == "<missing>"));
+        assert!(!diffs.iter().any(ExportDifference::is_volatile));
+    }
+}
@@ -3,7 +3,10 @@
 //! LanceDB uses Apache Arrow for storage, so we define schemas
 //! using Arrow types with vector support for embeddings.
 
-/// Embedding dimension (BiomedBERT-base outputs 768-dim vectors)
+use ferrumyx_common::{repro, DataClass};
+
+/// Default embedding dimension for new databases (BiomedBERT-base outputs
+/// 768-dim vectors). See [`crate::Database::with_embedding_dim`].
 pub const EMBEDDING_DIM: usize = 768;
 
 // =============================================================================
@@ -34,12 +37,27 @@ pub struct Paper {
     pub ingested_at: chrono::DateTime<chrono::Utc>,
     pub abstract_simhash: Option<i64>,
     pub published_version_doi: Option<String>,
+    /// Review article; its facts are down-weighted rather than excluded.
+    pub is_review: bool,
+    /// CrossRef `is-referenced-by-count`, filled by post-ingestion enrichment.
+    pub citation_count: Option<i64>,
+    /// MinHash signature of the abstract, indexed for near-duplicate lookup.
+    pub minhash_signature: Option<Vec<u32>>,
+    /// Stored paper this one is a near-duplicate of under another venue,
+    /// e.g. the journal version of a preprint. Both rows are kept.
+    pub duplicate_of: Option<uuid::Uuid>,
+    /// Text-quality score of the parsed PDF full text, 0..=1.
+    pub text_quality: Option<f64>,
+    /// Ingestion job that stored the paper.
+    pub job_id: Option<uuid::Uuid>,
+    /// Search query the paper was found by.
+    pub query_tag: Option<String>,
 }
 
 impl Paper {
     pub fn new(title: String, source: String) -> Self {
         Self {
-            id: uuid::Uuid::new_v4(),
+            id: repro::new_id("paper", &format!("{source}|{title}")),
             doi: None,
             pmid: None,
             title,
@@ -57,9 +75,16 @@ impl Paper {
             parse_status: "pending".to_string(),
             open_access: false,
             retrieval_tier: None,
-            ingested_at: chrono::Utc::now(),
+            ingested_at: repro::now(),
             abstract_simhash: None,
             published_version_doi: None,
+            is_review: false,
+            citation_count: None,
+            minhash_signature: None,
+            duplicate_of: None,
+            text_quality: None,
+            job_id: None,
+            query_tag: None,
         }
     }
 }
@@ -81,21 +106,27 @@ pub struct Chunk {
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub embedding: Option<Vec<f32>>,
     pub embedding_large: Option<Vec<f32>>,
+    /// Char offsets of `content` in the paper text; None for chunks stored
+    /// before offsets were recorded.
+    pub char_start: Option<i64>,
+    pub char_end: Option<i64>,
 }
 
 impl Chunk {
     pub fn new(paper_id: uuid::Uuid, chunk_index: i64, content: String) -> Self {
         Self {
-            id: uuid::Uuid::new_v4(),
+            id: repro::new_id("chunk", &format!("{paper_id}|{chunk_index}")),
             paper_id,
             chunk_index,
             token_count: 0,
             content,
             section: None,
             page: None,
-            created_at: chrono::Utc::now(),
+            created_at: repro::now(),
             embedding: None,
             embedding_large: None,
+            char_start: None,
+            char_end: None,
         }
     }
 }
@@ -114,6 +145,7 @@ pub enum EntityType {
     CancerType,
     Pathway,
     Protein,
+    ClinicalTrial,
 }
 
 impl std::fmt::Display for EntityType {
@@ -126,6 +158,7 @@ impl std::fmt::Display for EntityType {
             EntityType::CancerType => write!(f, "cancer_type"),
             EntityType::Pathway => write!(f, "pathway"),
             EntityType::Protein => write!(f, "protein"),
+            EntityType::ClinicalTrial => write!(f, "clinical_trial"),
         }
     }
 }
@@ -142,6 +175,7 @@ impl std::str::FromStr for EntityType {
             "cancer_type" | "cancertype" => Ok(EntityType::CancerType),
             "pathway" => Ok(EntityType::Pathway),
             "protein" => Ok(EntityType::Protein),
+            "clinical_trial" => Ok(EntityType::ClinicalTrial),
             _ => Err(format!("Unknown entity type: {}", s)),
         }
     }
@@ -170,9 +204,12 @@ impl Entity {
         external_id: String,
         source_db: String,
     ) -> Self {
-        let now = chrono::Utc::now();
+        let now = repro::now();
         Self {
-            id: uuid::Uuid::new_v4(),
+            id: repro::new_id(
+                "entity",
+                &format!("{entity_type}|{source_db}|{external_id}|{name}"),
+            ),
             external_id,
             name,
             canonical_name: None,
@@ -377,6 +414,24 @@ pub struct EntChemblTarget {
     pub fetched_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// Cached structural tractability of one protein, keyed by UniProt
+/// accession (fpocket runs are too slow to repeat per ranking).
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct EntStructuralAssessment {
+    pub id: uuid::Uuid,
+    pub uniprot_id: String,
+    pub has_pdb: bool,
+    pub pdb_count: i64,
+    pub best_pdb_id: Option<String>,
+    /// Best experimental resolution in Å.
+    pub best_resolution: Option<f64>,
+    pub alphafold_plddt: Option<f64>,
+    pub best_pocket_score: Option<f64>,
+    pub pocket_count: Option<i64>,
+    pub source: String,
+    pub fetched_at: chrono::DateTime<chrono::Utc>,
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct EntReactomeGene {
     pub id: uuid::Uuid,
@@ -426,6 +481,31 @@ pub struct KgFact {
     pub valid_from: chrono::DateTime<chrono::Utc>,
     pub valid_until: Option<chrono::DateTime<chrono::Utc>>,
     pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Chunk the fact was read from.
+    pub chunk_id: Option<uuid::Uuid>,
+    /// Byte range of the evidence sentence in the chunk content.
+    pub evidence_start: Option<i64>,
+    pub evidence_end: Option<i64>,
+    /// Papers backing the triple once the same fact was ingested from more
+    /// than one; empty while the row's own paper is the only one.
+    pub supporting_evidence: Vec<FactSupport>,
+    /// Number of papers backing the triple.
+    pub support_count: i64,
+}
+
+/// One paper backing a [`KgFact`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FactSupport {
+    pub paper_id: uuid::Uuid,
+    pub confidence: f32,
+    #[serde(default)]
+    pub evidence: Option<String>,
+    #[serde(default)]
+    pub chunk_id: Option<uuid::Uuid>,
+    /// Publication date of the paper, copied from `papers` so merges can
+    /// decay old evidence without a join.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 impl KgFact {
@@ -438,7 +518,10 @@ impl KgFact {
         object_name: String,
     ) -> Self {
         Self {
-            id: uuid::Uuid::new_v4(),
+            id: repro::new_id(
+                "kg_fact",
+                &format!("{paper_id}|{subject_id}|{predicate}|{object_id}"),
+            ),
             paper_id,
             subject_id,
             subject_name,
@@ -450,9 +533,14 @@ impl KgFact {
             evidence_type: "unknown".to_string(),
             study_type: None,
             sample_size: None,
-            valid_from: chrono::Utc::now(),
+            valid_from: repro::now(),
             valid_until: None,
-            created_at: chrono::Utc::now(),
+            created_at: repro::now(),
+            chunk_id: None,
+            evidence_start: None,
+            evidence_end: None,
+            supporting_evidence: Vec::new(),
+            support_count: 1,
         }
     }
 }
@@ -486,7 +574,10 @@ impl EntityMention {
         end_offset: i64,
     ) -> Self {
         Self {
-            id: uuid::Uuid::new_v4(),
+            id: repro::new_id(
+                "entity_mention",
+                &format!("{entity_id}|{chunk_id}|{start_offset}|{end_offset}"),
+            ),
             entity_id,
             chunk_id,
             paper_id,
@@ -495,7 +586,7 @@ impl EntityMention {
             text,
             confidence: None,
             context: None,
-            created_at: chrono::Utc::now(),
+            created_at: repro::now(),
         }
     }
 }
@@ -514,6 +605,11 @@ pub struct KgConflict {
     pub net_confidence: f32,
     pub resolution: String,
     pub detected_at: chrono::DateTime<chrono::Utc>,
+    /// `low`, `medium` or `high`; None for conflicts recorded before
+    /// severities were assigned.
+    pub severity: Option<String>,
+    /// Papers behind either fact.
+    pub paper_ids: Vec<uuid::Uuid>,
 }
 
 impl KgConflict {
@@ -525,13 +621,18 @@ impl KgConflict {
         resolution: String,
     ) -> Self {
         Self {
-            id: uuid::Uuid::new_v4(),
+            id: repro::new_id(
+                "kg_conflict",
+                &format!("{fact_a_id}|{fact_b_id}|{conflict_type}"),
+            ),
             fact_a_id,
             fact_b_id,
             conflict_type,
             net_confidence,
             resolution,
-            detected_at: chrono::Utc::now(),
+            detected_at: repro::now(),
+            severity: None,
+            paper_ids: Vec::new(),
         }
     }
 }
@@ -567,7 +668,7 @@ impl TargetScore {
         shortlist_tier: String,
     ) -> Self {
         Self {
-            id: uuid::Uuid::new_v4(),
+            id: repro::new_id("target_score", &format!("{gene_id}|{cancer_id}|1")),
             gene_id,
             cancer_id,
             score_version: 1,
@@ -578,7 +679,7 @@ impl TargetScore {
             shortlist_tier,
             components_raw: "{}".to_string(),
             components_normed: "{}".to_string(),
-            created_at: chrono::Utc::now(),
+            created_at: repro::now(),
         }
     }
 }
@@ -601,16 +702,500 @@ pub struct IngestionAudit {
 impl IngestionAudit {
     pub fn new(action: String, detail: String) -> Self {
         Self {
-            id: uuid::Uuid::new_v4(),
+            id: repro::new_id("ingestion_audit", &format!("{action}|{detail}")),
             job_id: None,
             paper_id: None,
             action,
             detail,
-            created_at: chrono::Utc::now(),
+            created_at: repro::now(),
+        }
+    }
+}
+
+// =============================================================================
+// Ingestion Watermark Schema
+// =============================================================================
+
+/// Where the last ingestion run for one (query, source) left off, so the
+/// next incremental run only asks the source for newer papers.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct IngestionWatermark {
+    pub id: uuid::Uuid,
+    pub query: String,
+    pub source: String,
+    pub last_run_at: chrono::DateTime<chrono::Utc>,
+    /// Newest publication date among the papers the source has returned.
+    pub last_seen_publication_date: Option<chrono::NaiveDate>,
+    /// Papers the source returned on the last run.
+    pub paper_count: i64,
+}
+
+impl IngestionWatermark {
+    pub fn new(query: String, source: String, last_run_at: chrono::DateTime<chrono::Utc>) -> Self {
+        Self {
+            id: repro::new_id("ingestion_watermark", &format!("{query}|{source}")),
+            query,
+            source,
+            last_run_at,
+            last_seen_publication_date: None,
+            paper_count: 0,
+        }
+    }
+}
+
+// =============================================================================
+// Ingestion Job Run Schema
+// =============================================================================
+
+/// One run of a scheduled ingestion job.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct IngestionJobRun {
+    pub id: uuid::Uuid,
+    pub schedule_id: String,
+    /// What started the run: "schedule" or "manual".
+    pub trigger: String,
+    /// "running", "succeeded", "partial", "failed" or "interrupted".
+    pub status: String,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    pub finished_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub duration_ms: i64,
+    pub papers_found: i64,
+    pub papers_inserted: i64,
+    pub papers_duplicate: i64,
+    pub chunks_inserted: i64,
+    pub error: Option<String>,
+}
+
+impl IngestionJobRun {
+    /// A run of `schedule_id` that has just started.
+    pub fn started(
+        schedule_id: String,
+        trigger: String,
+        started_at: chrono::DateTime<chrono::Utc>,
+    ) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4(),
+            schedule_id,
+            trigger,
+            status: "running".to_string(),
+            started_at,
+            finished_at: None,
+            duration_ms: 0,
+            papers_found: 0,
+            papers_inserted: 0,
+            papers_duplicate: 0,
+            chunks_inserted: 0,
+            error: None,
+        }
+    }
+}
+
+// =============================================================================
+// Ingestion Job Schema
+// =============================================================================
+
+/// An ingestion job started from the web UI or API: its parameters and how
+/// far it got.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct IngestionJobRecord {
+    pub id: uuid::Uuid,
+    pub gene: String,
+    pub mutation: Option<String>,
+    pub cancer_type: String,
+    /// Source names, comma-separated.
+    pub sources: String,
+    pub max_results: i64,
+    /// "running", "succeeded", "partial", "failed" or "cancelled".
+    pub status: String,
+    /// Last pipeline stage the job reported.
+    pub stage: String,
+    pub papers_found: i64,
+    pub papers_inserted: i64,
+    pub papers_duplicate: i64,
+    pub chunks_inserted: i64,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    pub finished_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub error: Option<String>,
+}
+
+// =============================================================================
+// Paper Citation Schema
+// =============================================================================
+
+/// One reference from a stored paper to the work with `cited_doi`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PaperCitation {
+    /// Derived from the citing paper and DOI, so re-harvesting a paper
+    /// replaces its rows instead of duplicating them.
+    pub id: uuid::Uuid,
+    pub citing_paper_id: uuid::Uuid,
+    /// Lowercased, without a `https://doi.org/` prefix.
+    pub cited_doi: String,
+    /// The stored paper with `cited_doi`, if we have it.
+    pub cited_paper_id: Option<uuid::Uuid>,
+    /// Sentence of the citing paper's body that cites the reference.
+    pub context: Option<String>,
+    /// "europepmc" or "crossref".
+    pub source: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl PaperCitation {
+    pub fn new(citing_paper_id: uuid::Uuid, cited_doi: &str, source: &str) -> Self {
+        let cited_doi = normalise_doi(cited_doi);
+        Self {
+            id: uuid::Uuid::new_v5(
+                &uuid::Uuid::NAMESPACE_URL,
+                format!("{citing_paper_id}|{cited_doi}").as_bytes(),
+            ),
+            citing_paper_id,
+            cited_doi,
+            cited_paper_id: None,
+            context: None,
+            source: source.to_string(),
+            created_at: repro::now(),
         }
     }
 }
 
+const DOI_PREFIXES: &[&str] = &[
+    "https://doi.org/",
+    "http://doi.org/",
+    "https://dx.doi.org/",
+    "doi:",
+];
+
+/// `doi` lowercased, trimmed and stripped of a resolver or `doi:` prefix.
+pub fn normalise_doi(doi: &str) -> String {
+    let lower = doi.trim().to_lowercase();
+    DOI_PREFIXES
+        .iter()
+        .find_map(|prefix| lower.strip_prefix(prefix))
+        .unwrap_or(&lower)
+        .trim()
+        .to_string()
+}
+
+// =============================================================================
+// LLM Audit Schema
+// =============================================================================
+
+/// One LLM call: which backend answered, under which data class, and which
+/// chunks it was shown. The output itself is kept only as a hash.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LlmAuditLog {
+    pub id: uuid::Uuid,
+    /// Caller-side id tying the call to a request, e.g. an answer id.
+    pub session_id: Option<String>,
+    pub model: String,
+    /// "ollama", "openai", "anthropic", "gemini" or "openai_compatible".
+    pub backend: String,
+    pub prompt_tokens: Option<i64>,
+    pub completion_tokens: Option<i64>,
+    pub data_class: DataClass,
+    /// Hex SHA-256 of the model output.
+    pub output_hash: String,
+    pub latency_ms: Option<i64>,
+    /// Chunks included in the prompt.
+    pub chunk_ids: Vec<uuid::Uuid>,
+    pub called_at: chrono::DateTime<chrono::Utc>,
+    /// Served from the response cache rather than the backend.
+    #[serde(default)]
+    pub cached: bool,
+    /// What made the call, e.g. "answer".
+    #[serde(default)]
+    pub caller: Option<String>,
+    /// Why no backend served the call; `None` on success.
+    #[serde(default)]
+    pub error: Option<String>,
+    /// Hex SHA-256 of the system and user prompts. Prompts themselves are
+    /// never stored.
+    #[serde(default)]
+    pub prompt_hash: Option<String>,
+    /// Characters in the system and user prompts.
+    #[serde(default)]
+    pub prompt_chars: Option<i64>,
+}
+
+impl LlmAuditLog {
+    pub fn new(model: String, backend: String, data_class: DataClass, output: &str) -> Self {
+        use sha2::{Digest, Sha256};
+
+        let output_hash = Sha256::digest(output.as_bytes())
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        Self {
+            id: uuid::Uuid::new_v4(),
+            session_id: None,
+            model,
+            backend,
+            prompt_tokens: None,
+            completion_tokens: None,
+            data_class,
+            output_hash,
+            latency_ms: None,
+            chunk_ids: Vec::new(),
+            called_at: repro::now(),
+            cached: false,
+            caller: None,
+            error: None,
+            prompt_hash: None,
+            prompt_chars: None,
+        }
+    }
+}
+
+/// Tokens and estimated cost of one LLM call, for the daily `[llm.limits]`
+/// budgets.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LlmUsageRecord {
+    pub id: uuid::Uuid,
+    /// UTC day the call counts against.
+    pub day: chrono::NaiveDate,
+    pub backend: String,
+    pub model: String,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub cost_usd: f64,
+    pub recorded_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl LlmUsageRecord {
+    pub fn new(
+        backend: String,
+        model: String,
+        prompt_tokens: i64,
+        completion_tokens: i64,
+        cost_usd: f64,
+    ) -> Self {
+        let recorded_at = repro::now();
+        Self {
+            id: uuid::Uuid::new_v4(),
+            day: recorded_at.date_naive(),
+            backend,
+            model,
+            prompt_tokens,
+            completion_tokens,
+            cost_usd,
+            recorded_at,
+        }
+    }
+}
+
+/// Record of a deleted paper, so re-ingestion can skip it.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PaperTombstone {
+    /// Id the paper was stored under.
+    pub paper_id: uuid::Uuid,
+    pub doi: Option<String>,
+    pub pmid: Option<String>,
+    pub title: String,
+    pub source: String,
+    pub reason: String,
+    pub deleted_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// One metric's samples over one UTC day.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct MetricRollup {
+    pub metric: String,
+    pub day: chrono::NaiveDate,
+    pub samples: i64,
+    pub min: f64,
+    pub max: f64,
+    pub sum: f64,
+    /// Most recent sample.
+    pub last: f64,
+    /// Time of the most recent sample.
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl MetricRollup {
+    /// A rollup holding the single sample `value` taken at `at`.
+    pub fn new(metric: impl Into<String>, value: f64, at: chrono::DateTime<chrono::Utc>) -> Self {
+        Self {
+            metric: metric.into(),
+            day: at.date_naive(),
+            samples: 1,
+            min: value,
+            max: value,
+            sum: value,
+            last: value,
+            updated_at: at,
+        }
+    }
+
+    /// Fold in `value` sampled at `at`, a time on [`Self::day`].
+    pub fn absorb(&mut self, value: f64, at: chrono::DateTime<chrono::Utc>) {
+        self.samples += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.sum += value;
+        if at >= self.updated_at {
+            self.last = value;
+            self.updated_at = at;
+        }
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.samples > 0 {
+            self.sum / self.samples as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+/// A paper whose ingestion failed, kept so it can be retried instead of
+/// aborting the run it was part of.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FailedItem {
+    /// Id the paper is stored under.
+    pub paper_id: uuid::Uuid,
+    /// Ingestion run the paper last failed in.
+    pub job_id: Option<uuid::Uuid>,
+    pub title: String,
+    pub doi: Option<String>,
+    pub pmid: Option<String>,
+    pub source: String,
+    /// The paper's metadata as fetched, JSON-encoded, so a retry does not
+    /// need to search again.
+    pub metadata: String,
+    /// Pipeline stage the paper failed in, e.g. "full_text" or "chunking".
+    pub stage: String,
+    pub error: String,
+    /// Retries attempted so far.
+    pub retry_count: i64,
+    /// "failed", or "requeued" while a retry is in flight.
+    pub status: String,
+    pub first_failed_at: chrono::DateTime<chrono::Utc>,
+    pub last_failed_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A ClinicalTrials.gov study, keyed by NCT id.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TrialRecord {
+    /// e.g. "NCT04956640".
+    pub nct_id: String,
+    pub title: String,
+    /// Conditions as the sponsor listed them.
+    pub conditions: Vec<String>,
+    pub interventions: Vec<TrialIntervention>,
+    /// Highest listed phase, e.g. "PHASE3", or "NA".
+    pub phase: String,
+    /// Overall status, e.g. "RECRUITING".
+    pub status: String,
+    /// e.g. "INTERVENTIONAL".
+    pub study_type: String,
+    /// Actual enrollment once reported, the estimate before.
+    pub enrollment: Option<i64>,
+    /// Dates listed with month precision fall on the first of the month.
+    pub start_date: Option<chrono::NaiveDate>,
+    pub completion_date: Option<chrono::NaiveDate>,
+    /// Lead sponsor first, then collaborators.
+    pub sponsors: Vec<String>,
+    /// Generic names of the known drugs among the interventions.
+    pub drugs: Vec<String>,
+    /// HGNC symbols those drugs are known to inhibit.
+    pub target_genes: Vec<String>,
+    /// OncoTree codes the conditions resolve to.
+    pub cancer_codes: Vec<String>,
+    pub fetched_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// One intervention of a [`TrialRecord`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TrialIntervention {
+    pub name: String,
+    /// e.g. "DRUG", "BIOLOGICAL", "PROCEDURE".
+    pub kind: String,
+    /// Code names and brand names, e.g. "AMG 510" for sotorasib.
+    #[serde(default)]
+    pub other_names: Vec<String>,
+    /// Labels of the arms that receive it.
+    #[serde(default)]
+    pub arm_groups: Vec<String>,
+}
+
+/// Docking of one ligand into one pocket of a target structure, keyed by
+/// (target, pocket_rank, ligand_id).
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DockingResultRecord {
+    /// Gene symbol or structure id the receptor was prepared from.
+    pub target: String,
+    /// fpocket rank of the pocket the search box was built around.
+    pub pocket_rank: u32,
+    pub ligand_id: String,
+    pub smiles: String,
+    /// Receptor file the ligand was docked against.
+    pub structure: String,
+    /// Affinity of the top pose; `None` when docking failed.
+    pub best_affinity_kcal: Option<f64>,
+    /// Binding modes, best first.
+    pub poses: Vec<DockedPose>,
+    /// Why preparation or docking failed, if it did.
+    pub error: Option<String>,
+    pub docked_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// One binding mode of a [`DockingResultRecord`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DockedPose {
+    pub mode: u32,
+    pub affinity_kcal: f64,
+    pub rmsd_lb: f64,
+    pub rmsd_ub: f64,
+    /// The pose's MODEL block from Vina's output PDBQT.
+    pub pose_pdbqt: String,
+}
+
+/// A ligand proposed for docking against a target, keyed by
+/// (target_chembl_id, smiles): a known active or an analog enumerated from
+/// one.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CandidateLigandRecord {
+    pub target_chembl_id: String,
+    /// Canonical SMILES.
+    pub smiles: String,
+    /// The known active this candidate is, or was enumerated from.
+    pub parent_chembl_id: String,
+    /// Canonical SMILES of the Murcko scaffold; empty for acyclic ligands.
+    pub scaffold_id: String,
+    /// Not a known active of the target.
+    pub novelty: bool,
+    pub generated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// One ligand of the ranked list the molecule pipeline produced for a
+/// (gene, cancer_type) run, keyed by (gene, cancer_type, smiles). A new run
+/// replaces the whole list.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct MoleculeCandidateRecord {
+    pub gene: String,
+    pub cancer_type: String,
+    /// 1 is the best composite score.
+    pub rank: u32,
+    /// Canonical SMILES.
+    pub smiles: String,
+    /// The known active this is, or was enumerated from.
+    pub parent_chembl_id: String,
+    pub scaffold_id: String,
+    /// Not a known active of the target.
+    pub novelty: bool,
+    /// fpocket rank of the pocket the best pose is in.
+    pub pocket_rank: u32,
+    pub best_affinity_kcal: f64,
+    /// Rule-based ADMET score, 0 to 1.
+    pub admet_score: f64,
+    /// Synthetic accessibility, 1 (easy) to 10 (hard).
+    pub sa_score: f64,
+    pub pains_alerts: Vec<String>,
+    /// 0 to 1, higher is better.
+    pub composite_score: f64,
+    pub ranked_at: chrono::DateTime<chrono::Utc>,
+}
+
 // =============================================================================
 // Table Names
 // =============================================================================
@@ -623,6 +1208,21 @@ pub const TABLE_ENTITY_MENTIONS: &str = "entity_mentions";
 pub const TABLE_KG_CONFLICTS: &str = "kg_conflicts";
 pub const TABLE_TARGET_SCORES: &str = "target_scores";
 pub const TABLE_INGESTION_AUDIT: &str = "ingestion_audit";
+pub const TABLE_INGESTION_WATERMARKS: &str = "ingestion_watermarks";
+pub const TABLE_INGESTION_JOB_RUNS: &str = "ingestion_job_runs";
+pub const TABLE_INGESTION_JOBS: &str = "ingestion_jobs";
+pub const TABLE_PAPER_CITATIONS: &str = "paper_citations";
+pub const TABLE_LLM_AUDIT: &str = "llm_audit";
+pub const TABLE_LLM_USAGE: &str = "llm_usage";
+pub const TABLE_PAPER_TOMBSTONES: &str = "paper_tombstones";
+pub const TABLE_METRIC_ROLLUPS: &str = "metric_rollups";
+pub const TABLE_FAILED_ITEMS: &str = "failed_items";
+pub const TABLE_TRIALS: &str = "trials";
+pub const TABLE_DOCKING_RESULTS: &str = "docking_results";
+pub const TABLE_CANDIDATE_LIGANDS: &str = "candidate_ligands";
+pub const TABLE_MOLECULE_CANDIDATES: &str = "molecule_candidates";
+pub const TABLE_SCHEMA_META: &str = "schema_meta";
+pub const TABLE_EMBEDDING_META: &str = "embedding_meta";
 
 // Entropy specific tables
 pub const TABLE_ENT_GENES: &str = "ent_genes";
@@ -640,4 +1240,5 @@ pub const TABLE_ENT_COSMIC_MUTATION_FREQUENCY: &str = "ent_cosmic_mutation_frequ
 pub const TABLE_ENT_GTEX_EXPRESSION: &str = "ent_gtex_expression";
 pub const TABLE_ENT_CHEMBL_TARGETS: &str = "ent_chembl_targets";
 pub const TABLE_ENT_REACTOME_GENES: &str = "ent_reactome_genes";
+pub const TABLE_ENT_STRUCTURAL_ASSESSMENTS: &str = "ent_structural_assessments";
 pub const TABLE_ENT_PROVIDER_REFRESH_RUNS: &str = "ent_provider_refresh_runs";
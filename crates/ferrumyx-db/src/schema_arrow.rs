@@ -5,11 +5,15 @@
 
 use crate::error::{DbError, Result};
 use crate::schema::*;
-use arrow_array::{Array, FixedSizeListArray, Float32Array, Int64Array, RecordBatch, StringArray};
+use crate::schema_evolution::conform_row;
+use arrow_array::{
+    Array, BinaryArray, FixedSizeListArray, Float32Array, Int64Array, RecordBatch, StringArray,
+};
 use arrow_schema::{DataType, Field, Schema};
 use std::sync::Arc;
 
-/// Embedding dimension (BiomedBERT-base outputs 768-dim vectors)
+/// Default embedding dimension (BiomedBERT-base outputs 768-dim vectors);
+/// a database may store another width, see [`crate::Database::embedding_dim`].
 pub const EMBEDDING_DIM: usize = 768;
 /// Large Embedding dimension for high-precision mode
 pub const EMBEDDING_LARGE_DIM: usize = 1024;
@@ -41,6 +45,13 @@ pub fn paper_schema() -> Arc<Schema> {
         Field::new("ingested_at", DataType::Utf8, false),
         Field::new("abstract_simhash", DataType::Int64, true),
         Field::new("published_version_doi", DataType::Utf8, true),
+        Field::new("is_review", DataType::Boolean, true),
+        Field::new("citation_count", DataType::Int64, true),
+        Field::new("minhash_signature", DataType::Binary, true),
+        Field::new("duplicate_of", DataType::Utf8, true),
+        Field::new("text_quality", DataType::Float64, true),
+        Field::new("job_id", DataType::Utf8, true),
+        Field::new("query_tag", DataType::Utf8, true),
     ]))
 }
 
@@ -68,6 +79,18 @@ pub fn paper_to_record(paper: &Paper) -> Result<RecordBatch> {
     let ingested_at = StringArray::from(vec![paper.ingested_at.to_rfc3339()]);
     let abstract_simhash = Int64Array::from(vec![paper.abstract_simhash]);
     let published_version_doi = StringArray::from(vec![paper.published_version_doi.as_deref()]);
+    let is_review = arrow_array::BooleanArray::from(vec![Some(paper.is_review)]);
+    let citation_count = Int64Array::from(vec![paper.citation_count]);
+    let minhash_bytes = paper.minhash_signature.as_ref().map(|sig| {
+        sig.iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect::<Vec<u8>>()
+    });
+    let minhash_signature = BinaryArray::from(vec![minhash_bytes.as_deref()]);
+    let duplicate_of = StringArray::from(vec![paper.duplicate_of.map(|id| id.to_string())]);
+    let text_quality = arrow_array::Float64Array::from(vec![paper.text_quality]);
+    let job_id = StringArray::from(vec![paper.job_id.map(|id| id.to_string())]);
+    let query_tag = StringArray::from(vec![paper.query_tag.as_deref()]);
 
     RecordBatch::try_new(
         schema,
@@ -93,12 +116,21 @@ pub fn paper_to_record(paper: &Paper) -> Result<RecordBatch> {
             Arc::new(ingested_at),
             Arc::new(abstract_simhash),
             Arc::new(published_version_doi),
+            Arc::new(is_review),
+            Arc::new(citation_count),
+            Arc::new(minhash_signature),
+            Arc::new(duplicate_of),
+            Arc::new(text_quality),
+            Arc::new(job_id),
+            Arc::new(query_tag),
         ],
     )
     .map_err(|e| DbError::Arrow(e.to_string()))
 }
 
 pub fn record_to_paper(batch: &RecordBatch, row: usize) -> Result<Paper> {
+    let (batch, row) = conform_row(batch, row, &paper_schema())?;
+    let batch: &RecordBatch = &batch;
     let get_string = |col: usize| -> String {
         let arr = batch
             .column(col)
@@ -146,6 +178,32 @@ pub fn record_to_paper(batch: &RecordBatch, row: usize) -> Result<Paper> {
         }
     };
 
+    let get_opt_u32s = |col: usize| -> Option<Vec<u32>> {
+        let arr = batch.column(col).as_any().downcast_ref::<BinaryArray>()?;
+        if arr.is_null(row) {
+            None
+        } else {
+            Some(
+                arr.value(row)
+                    .chunks_exact(4)
+                    .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                    .collect(),
+            )
+        }
+    };
+
+    let get_opt_f64 = |col: usize| -> Option<f64> {
+        let arr = batch
+            .column(col)
+            .as_any()
+            .downcast_ref::<arrow_array::Float64Array>()?;
+        if arr.is_null(row) {
+            None
+        } else {
+            Some(arr.value(row))
+        }
+    };
+
     let get_bool = |col: usize| -> bool {
         let arr = batch
             .column(col)
@@ -186,6 +244,13 @@ pub fn record_to_paper(batch: &RecordBatch, row: usize) -> Result<Paper> {
             .unwrap_or_else(|_| chrono::Utc::now()),
         abstract_simhash: get_opt_i64(19),
         published_version_doi: get_opt_string(20),
+        is_review: get_bool(21),
+        citation_count: get_opt_i64(22),
+        minhash_signature: get_opt_u32s(23),
+        duplicate_of: get_opt_string(24).and_then(|s| uuid::Uuid::parse_str(&s).ok()),
+        text_quality: get_opt_f64(25),
+        job_id: get_opt_string(26).and_then(|s| uuid::Uuid::parse_str(&s).ok()),
+        query_tag: get_opt_string(27),
     })
 }
 
@@ -193,7 +258,8 @@ pub fn record_to_paper(batch: &RecordBatch, row: usize) -> Result<Paper> {
 // Chunk Arrow Conversion
 // =============================================================================
 
-pub fn chunk_schema() -> Arc<Schema> {
+/// Chunk schema with `dim`-wide `embedding` vectors.
+pub fn chunk_schema(dim: usize) -> Arc<Schema> {
     Arc::new(Schema::new(vec![
         Field::new("id", DataType::Utf8, false),
         Field::new("paper_id", DataType::Utf8, false),
@@ -207,7 +273,7 @@ pub fn chunk_schema() -> Arc<Schema> {
             "embedding",
             DataType::FixedSizeList(
                 Arc::new(Field::new("item", DataType::Float32, false)),
-                EMBEDDING_DIM as i32,
+                dim as i32,
             ),
             true,
         ),
@@ -219,11 +285,23 @@ pub fn chunk_schema() -> Arc<Schema> {
             ),
             true,
         ),
+        Field::new("char_start", DataType::Int64, true),
+        Field::new("char_end", DataType::Int64, true),
     ]))
 }
 
-pub fn chunk_to_record(chunk: &Chunk) -> Result<RecordBatch> {
-    let schema = chunk_schema();
+/// Width of the `embedding` vectors in a chunk batch or table schema.
+pub fn chunk_embedding_dim(schema: &Schema) -> Option<usize> {
+    match schema.field_with_name("embedding").ok()?.data_type() {
+        DataType::FixedSizeList(_, size) => usize::try_from(*size).ok(),
+        _ => None,
+    }
+}
+
+/// Convert a chunk for a table storing `dim`-wide embeddings. Fails with
+/// [`DbError::DimensionMismatch`] when the chunk's embedding has another length.
+pub fn chunk_to_record(chunk: &Chunk, dim: usize) -> Result<RecordBatch> {
+    let schema = chunk_schema(dim);
 
     let id = StringArray::from(vec![chunk.id.to_string()]);
     let paper_id = StringArray::from(vec![chunk.paper_id.to_string()]);
@@ -233,19 +311,27 @@ pub fn chunk_to_record(chunk: &Chunk) -> Result<RecordBatch> {
     let section = StringArray::from(vec![chunk.section.as_deref()]);
     let page = Int64Array::from(vec![chunk.page]);
     let created_at = StringArray::from(vec![chunk.created_at.to_rfc3339()]);
+    let char_start = Int64Array::from(vec![chunk.char_start]);
+    let char_end = Int64Array::from(vec![chunk.char_end]);
 
     // Handle embedding
     let embedding: Arc<dyn Array> = if let Some(ref emb) = chunk.embedding {
+        if emb.len() != dim {
+            return Err(DbError::DimensionMismatch {
+                expected: dim,
+                actual: emb.len(),
+            });
+        }
         let values = Float32Array::from(emb.clone());
         let field = Arc::new(Field::new("item", DataType::Float32, false));
         Arc::new(
-            FixedSizeListArray::try_new(field, EMBEDDING_DIM as i32, Arc::new(values), None)
+            FixedSizeListArray::try_new(field, dim as i32, Arc::new(values), None)
                 .map_err(|e| DbError::Arrow(e.to_string()))?,
         )
     } else {
         Arc::new(FixedSizeListArray::new_null(
             Arc::new(Field::new("item", DataType::Float32, false)),
-            EMBEDDING_DIM as i32,
+            dim as i32,
             1,
         ))
     };
@@ -278,12 +364,17 @@ pub fn chunk_to_record(chunk: &Chunk) -> Result<RecordBatch> {
             Arc::new(created_at),
             embedding,
             embedding_large,
+            Arc::new(char_start),
+            Arc::new(char_end),
         ],
     )
     .map_err(|e| DbError::Arrow(e.to_string()))
 }
 
 pub fn record_to_chunk(batch: &RecordBatch, row: usize) -> Result<Chunk> {
+    let dim = chunk_embedding_dim(&batch.schema()).unwrap_or(EMBEDDING_DIM);
+    let (batch, row) = conform_row(batch, row, &chunk_schema(dim))?;
+    let batch: &RecordBatch = &batch;
     let get_string = |col: usize| -> String {
         batch
             .column(col)
@@ -366,6 +457,8 @@ pub fn record_to_chunk(batch: &RecordBatch, row: usize) -> Result<Chunk> {
             .unwrap_or_else(|_| chrono::Utc::now()),
         embedding: get_embedding(8),
         embedding_large: get_embedding(9),
+        char_start: get_opt_i64(10),
+        char_end: get_opt_i64(11),
     })
 }
 
@@ -424,6 +517,8 @@ pub fn entity_to_record(entity: &Entity) -> Result<RecordBatch> {
 }
 
 pub fn record_to_entity(batch: &RecordBatch, row: usize) -> Result<Entity> {
+    let (batch, row) = conform_row(batch, row, &entity_schema())?;
+    let batch: &RecordBatch = &batch;
     let get_string = |col: usize| -> String {
         batch
             .column(col)
@@ -488,6 +583,11 @@ pub fn kg_fact_schema() -> Arc<Schema> {
         Field::new("valid_from", DataType::Utf8, false),
         Field::new("valid_until", DataType::Utf8, true),
         Field::new("created_at", DataType::Utf8, false),
+        Field::new("chunk_id", DataType::Utf8, true),
+        Field::new("evidence_start", DataType::Int64, true),
+        Field::new("evidence_end", DataType::Int64, true),
+        Field::new("supporting_evidence", DataType::Utf8, true),
+        Field::new("support_count", DataType::Int64, false),
     ]))
 }
 
@@ -509,6 +609,18 @@ pub fn kg_fact_to_record(fact: &KgFact) -> Result<RecordBatch> {
     let valid_from = StringArray::from(vec![fact.valid_from.to_rfc3339()]);
     let valid_until = StringArray::from(vec![fact.valid_until.map(|dt| dt.to_rfc3339())]);
     let created_at = StringArray::from(vec![fact.created_at.to_rfc3339()]);
+    let chunk_id = StringArray::from(vec![fact.chunk_id.map(|id| id.to_string())]);
+    let evidence_start = Int64Array::from(vec![fact.evidence_start]);
+    let evidence_end = Int64Array::from(vec![fact.evidence_end]);
+    let supporting_evidence = StringArray::from(vec![if fact.supporting_evidence.is_empty() {
+        None
+    } else {
+        Some(
+            serde_json::to_string(&fact.supporting_evidence)
+                .map_err(|e| DbError::Arrow(e.to_string()))?,
+        )
+    }]);
+    let support_count = Int64Array::from(vec![fact.support_count]);
 
     RecordBatch::try_new(
         schema,
@@ -528,12 +640,19 @@ pub fn kg_fact_to_record(fact: &KgFact) -> Result<RecordBatch> {
             Arc::new(valid_from),
             Arc::new(valid_until),
             Arc::new(created_at),
+            Arc::new(chunk_id),
+            Arc::new(evidence_start),
+            Arc::new(evidence_end),
+            Arc::new(supporting_evidence),
+            Arc::new(support_count),
         ],
     )
     .map_err(|e| DbError::Arrow(e.to_string()))
 }
 
 pub fn record_to_kg_fact(batch: &RecordBatch, row: usize) -> Result<KgFact> {
+    let (batch, row) = conform_row(batch, row, &kg_fact_schema())?;
+    let batch: &RecordBatch = &batch;
     let get_string = |col: usize| -> String {
         batch
             .column(col)
@@ -579,6 +698,19 @@ pub fn record_to_kg_fact(batch: &RecordBatch, row: usize) -> Result<KgFact> {
         }
     };
 
+    let get_opt_i64 = |col: usize| -> Option<i64> {
+        let arr = batch
+            .column(col)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        if arr.is_null(row) {
+            None
+        } else {
+            Some(arr.value(row))
+        }
+    };
+
     Ok(KgFact {
         id: uuid::Uuid::parse_str(&get_string(0))
             .map_err(|e| DbError::InvalidQuery(e.to_string()))?,
@@ -605,6 +737,14 @@ pub fn record_to_kg_fact(batch: &RecordBatch, row: usize) -> Result<KgFact> {
         created_at: chrono::DateTime::parse_from_rfc3339(&get_string(14))
             .map(|dt| dt.with_timezone(&chrono::Utc))
             .unwrap_or_else(|_| chrono::Utc::now()),
+        chunk_id: get_opt_string(15).and_then(|s| uuid::Uuid::parse_str(&s).ok()),
+        evidence_start: get_opt_i64(16),
+        evidence_end: get_opt_i64(17),
+        supporting_evidence: get_opt_string(18)
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default(),
+        // Null only in batches read before the column was backfilled.
+        support_count: get_opt_i64(19).unwrap_or(1),
     })
 }
 
@@ -660,6 +800,8 @@ pub fn entity_mention_to_record(mention: &EntityMention) -> Result<RecordBatch>
 }
 
 pub fn record_to_entity_mention(batch: &RecordBatch, row: usize) -> Result<EntityMention> {
+    let (batch, row) = conform_row(batch, row, &entity_mention_schema())?;
+    let batch: &RecordBatch = &batch;
     let get_string = |col: usize| -> String {
         batch
             .column(col)
@@ -738,6 +880,8 @@ pub fn kg_conflict_schema() -> Arc<Schema> {
         Field::new("net_confidence", DataType::Float32, false),
         Field::new("resolution", DataType::Utf8, false),
         Field::new("detected_at", DataType::Utf8, false),
+        Field::new("severity", DataType::Utf8, true),
+        Field::new("paper_ids", DataType::Utf8, true),
     ]))
 }
 
@@ -751,6 +895,15 @@ pub fn kg_conflict_to_record(conflict: &KgConflict) -> Result<RecordBatch> {
     let net_confidence = Float32Array::from(vec![conflict.net_confidence]);
     let resolution = StringArray::from(vec![conflict.resolution.as_str()]);
     let detected_at = StringArray::from(vec![conflict.detected_at.to_rfc3339()]);
+    let severity = StringArray::from(vec![conflict.severity.as_deref()]);
+    let paper_ids = StringArray::from(vec![if conflict.paper_ids.is_empty() {
+        None
+    } else {
+        Some(
+            serde_json::to_string(&conflict.paper_ids)
+                .map_err(|e| DbError::Arrow(e.to_string()))?,
+        )
+    }]);
 
     RecordBatch::try_new(
         schema,
@@ -762,12 +915,16 @@ pub fn kg_conflict_to_record(conflict: &KgConflict) -> Result<RecordBatch> {
             Arc::new(net_confidence),
             Arc::new(resolution),
             Arc::new(detected_at),
+            Arc::new(severity),
+            Arc::new(paper_ids),
         ],
     )
     .map_err(|e| DbError::Arrow(e.to_string()))
 }
 
 pub fn record_to_kg_conflict(batch: &RecordBatch, row: usize) -> Result<KgConflict> {
+    let (batch, row) = conform_row(batch, row, &kg_conflict_schema())?;
+    let batch: &RecordBatch = &batch;
     let get_string = |col: usize| -> String {
         batch
             .column(col)
@@ -787,6 +944,19 @@ pub fn record_to_kg_conflict(batch: &RecordBatch, row: usize) -> Result<KgConfli
             .value(row)
     };
 
+    let get_opt_string = |col: usize| -> Option<String> {
+        let arr = batch
+            .column(col)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        if arr.is_null(row) {
+            None
+        } else {
+            Some(arr.value(row).to_string())
+        }
+    };
+
     Ok(KgConflict {
         id: uuid::Uuid::parse_str(&get_string(0))
             .map_err(|e| DbError::InvalidQuery(e.to_string()))?,
@@ -800,6 +970,10 @@ pub fn record_to_kg_conflict(batch: &RecordBatch, row: usize) -> Result<KgConfli
         detected_at: chrono::DateTime::parse_from_rfc3339(&get_string(6))
             .map(|dt| dt.with_timezone(&chrono::Utc))
             .unwrap_or_else(|_| chrono::Utc::now()),
+        severity: get_opt_string(7),
+        paper_ids: get_opt_string(8)
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default(),
     })
 }
 // =============================================================================
@@ -867,6 +1041,8 @@ pub fn ent_gene_to_record(item: &EntGene) -> Result<RecordBatch> {
 }
 
 pub fn record_to_ent_gene(batch: &RecordBatch, row: usize) -> Result<EntGene> {
+    let (batch, row) = conform_row(batch, row, &ent_gene_schema())?;
+    let batch: &RecordBatch = &batch;
     let get_string = |col: usize| -> String {
         batch
             .column(col)
@@ -1009,6 +1185,8 @@ pub fn ent_mutation_to_record(item: &EntMutation) -> Result<RecordBatch> {
 }
 
 pub fn record_to_ent_mutation(batch: &RecordBatch, row: usize) -> Result<EntMutation> {
+    let (batch, row) = conform_row(batch, row, &ent_mutation_schema())?;
+    let batch: &RecordBatch = &batch;
     let get_string = |col: usize| -> String {
         batch
             .column(col)
@@ -1138,6 +1316,8 @@ pub fn ent_cancer_type_to_record(item: &EntCancerType) -> Result<RecordBatch> {
 }
 
 pub fn record_to_ent_cancer_type(batch: &RecordBatch, row: usize) -> Result<EntCancerType> {
+    let (batch, row) = conform_row(batch, row, &ent_cancer_type_schema())?;
+    let batch: &RecordBatch = &batch;
     let get_string = |col: usize| -> String {
         batch
             .column(col)
@@ -1265,6 +1445,8 @@ pub fn ent_pathway_to_record(item: &EntPathway) -> Result<RecordBatch> {
 }
 
 pub fn record_to_ent_pathway(batch: &RecordBatch, row: usize) -> Result<EntPathway> {
+    let (batch, row) = conform_row(batch, row, &ent_pathway_schema())?;
+    let batch: &RecordBatch = &batch;
     let get_string = |col: usize| -> String {
         batch
             .column(col)
@@ -1404,6 +1586,8 @@ pub fn record_to_ent_clinical_evidence(
     batch: &RecordBatch,
     row: usize,
 ) -> Result<EntClinicalEvidence> {
+    let (batch, row) = conform_row(batch, row, &ent_clinical_evidence_schema())?;
+    let batch: &RecordBatch = &batch;
     let get_string = |col: usize| -> String {
         batch
             .column(col)
@@ -1543,6 +1727,8 @@ pub fn ent_compound_to_record(item: &EntCompound) -> Result<RecordBatch> {
 }
 
 pub fn record_to_ent_compound(batch: &RecordBatch, row: usize) -> Result<EntCompound> {
+    let (batch, row) = conform_row(batch, row, &ent_compound_schema())?;
+    let batch: &RecordBatch = &batch;
     let get_string = |col: usize| -> String {
         batch
             .column(col)
@@ -1681,6 +1867,8 @@ pub fn ent_structure_to_record(item: &EntStructure) -> Result<RecordBatch> {
 }
 
 pub fn record_to_ent_structure(batch: &RecordBatch, row: usize) -> Result<EntStructure> {
+    let (batch, row) = conform_row(batch, row, &ent_structure_schema())?;
+    let batch: &RecordBatch = &batch;
     let get_string = |col: usize| -> String {
         batch
             .column(col)
@@ -1809,6 +1997,8 @@ pub fn ent_druggability_to_record(item: &EntDruggability) -> Result<RecordBatch>
 }
 
 pub fn record_to_ent_druggability(batch: &RecordBatch, row: usize) -> Result<EntDruggability> {
+    let (batch, row) = conform_row(batch, row, &ent_druggability_schema())?;
+    let batch: &RecordBatch = &batch;
     let get_string = |col: usize| -> String {
         batch
             .column(col)
@@ -1946,6 +2136,8 @@ pub fn record_to_ent_synthetic_lethality(
     batch: &RecordBatch,
     row: usize,
 ) -> Result<EntSyntheticLethality> {
+    let (batch, row) = conform_row(batch, row, &ent_synthetic_lethality_schema())?;
+    let batch: &RecordBatch = &batch;
     let get_string = |col: usize| -> String {
         batch
             .column(col)
@@ -0,0 +1,831 @@
+//! Schema evolution for LanceDB tables.
+//!
+//! New features add columns to existing tables. On [`Database::initialize`]
+//! every on-disk table schema is diffed against
+//! [`expected_table_schemas`](crate::database::expected_table_schemas):
+//!
+//! - missing nullable columns are added in place (all-null), falling back to
+//!   a data-preserving rewrite when the alter path is unsupported;
+//! - missing non-nullable columns are only added when a backfill default is
+//!   registered in [`BACKFILL_DEFAULTS`];
+//! - type changes and non-nullable additions without a default are refused
+//!   with [`DbError::MigrationRequired`].
+//!
+//! The applied schema version is recorded per table in `schema_meta`.
+//! Readers call [`conform_row`] so batches that predate a column read it as null.
+
+use crate::database::{expected_table_schemas, Database};
+use crate::error::{DbError, Result};
+use crate::schema::TABLE_SCHEMA_META;
+use arrow_array::RecordBatchIterator;
+use arrow_array::{new_null_array, Array, ArrayRef, Int64Array, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema};
+use futures::StreamExt;
+use lancedb::database::CreateTableMode;
+use lancedb::query::ExecutableQuery;
+use lancedb::table::{ColumnAlteration, NewColumnTransform};
+use serde::Serialize;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Version of the expected schema set. Bump whenever a table gains a column.
+pub const SCHEMA_VERSION: i64 = 12;
+
+/// SQL backfill expressions for non-nullable columns added after release.
+/// `(table, column, expression)`.
+pub const BACKFILL_DEFAULTS: &[(&str, &str, &str)] = &[
+    ("target_scores", "score_version", "CAST(1 AS BIGINT)"),
+    ("target_scores", "is_current", "true"),
+    ("kg_facts", "support_count", "CAST(1 AS BIGINT)"),
+];
+
+/// One difference between an on-disk table and the expected schema.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SchemaChange {
+    /// Nullable column missing on disk; added as all-null.
+    AddNullableColumn { column: String, data_type: String },
+    /// Non-nullable column missing on disk; added from a registered default.
+    BackfillColumn {
+        column: String,
+        data_type: String,
+        default_sql: String,
+    },
+    /// Column is non-nullable on disk but nullable in the current schema.
+    RelaxNullability { column: String },
+    /// Change that cannot be applied automatically.
+    Incompatible { column: String, reason: String },
+}
+
+impl SchemaChange {
+    pub fn is_incompatible(&self) -> bool {
+        matches!(self, SchemaChange::Incompatible { .. })
+    }
+}
+
+/// Pending changes for a single table.
+#[derive(Debug, Clone, Serialize)]
+pub struct TableMigration {
+    pub table: String,
+    pub changes: Vec<SchemaChange>,
+}
+
+/// Pending changes for the whole database.
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationPlan {
+    pub target_version: i64,
+    /// Schema version recorded in `schema_meta` per table (absent = never recorded).
+    pub recorded_versions: BTreeMap<String, i64>,
+    pub tables: Vec<TableMigration>,
+}
+
+impl MigrationPlan {
+    /// True when no table needs a schema change.
+    pub fn is_empty(&self) -> bool {
+        self.tables.iter().all(|t| t.changes.is_empty())
+    }
+
+    /// First change that blocks automatic migration, if any.
+    pub fn first_incompatible(&self) -> Option<(&str, &SchemaChange)> {
+        self.tables.iter().find_map(|t| {
+            t.changes
+                .iter()
+                .find(|c| c.is_incompatible())
+                .map(|c| (t.table.as_str(), c))
+        })
+    }
+}
+
+/// Expected schemas for `db`, sized to the embedding width already on disk.
+/// Dimension changes are explicit ([`Database::migrate_embedding_dim`]), so a
+/// chunks table of another width is never reported as incompatible.
+async fn expected_schemas_for(db: &Database) -> Result<Vec<(&'static str, Arc<Schema>)>> {
+    let embedding_dim = db
+        .on_disk_embedding_dim()
+        .await?
+        .unwrap_or_else(|| db.embedding_dim());
+    Ok(expected_table_schemas(embedding_dim))
+}
+
+fn backfill_default(table: &str, column: &str) -> Option<&'static str> {
+    BACKFILL_DEFAULTS
+        .iter()
+        .find(|(t, c, _)| *t == table && *c == column)
+        .map(|(_, _, sql)| *sql)
+}
+
+/// Type equality that ignores nested field names and nullability (list item
+/// nullability has varied between writers and does not affect readers).
+fn same_type(a: &DataType, b: &DataType) -> bool {
+    match (a, b) {
+        (DataType::FixedSizeList(a, a_size), DataType::FixedSizeList(b, b_size)) => {
+            a_size == b_size && same_type(a.data_type(), b.data_type())
+        }
+        (DataType::List(a), DataType::List(b))
+        | (DataType::LargeList(a), DataType::LargeList(b)) => {
+            same_type(a.data_type(), b.data_type())
+        }
+        _ => a.equals_datatype(b),
+    }
+}
+
+/// Diff an on-disk schema against the expected one. Columns present on disk
+/// but unknown to the current code are left alone.
+pub fn diff_schema(table: &str, on_disk: &Schema, expected: &Schema) -> Vec<SchemaChange> {
+    let mut changes = Vec::new();
+    for field in expected.fields() {
+        let column = field.name().to_string();
+        match on_disk.field_with_name(field.name()) {
+            Err(_) if field.is_nullable() => changes.push(SchemaChange::AddNullableColumn {
+                column,
+                data_type: field.data_type().to_string(),
+            }),
+            Err(_) => match backfill_default(table, field.name()) {
+                Some(sql) => changes.push(SchemaChange::BackfillColumn {
+                    column,
+                    data_type: field.data_type().to_string(),
+                    default_sql: sql.to_string(),
+                }),
+                None => changes.push(SchemaChange::Incompatible {
+                    column,
+                    reason: "non-nullable column added without a backfill default".to_string(),
+                }),
+            },
+            Ok(existing) if !same_type(existing.data_type(), field.data_type()) => {
+                changes.push(SchemaChange::Incompatible {
+                    column,
+                    reason: format!(
+                        "type change {} -> {}",
+                        existing.data_type(),
+                        field.data_type()
+                    ),
+                })
+            }
+            Ok(existing) if !existing.is_nullable() && field.is_nullable() => {
+                changes.push(SchemaChange::RelaxNullability { column })
+            }
+            Ok(_) => {}
+        }
+    }
+    changes
+}
+
+/// Compute pending schema changes without touching the database.
+/// Tables that do not exist yet are skipped; `initialize` creates them.
+pub async fn plan_migrations(db: &Database) -> Result<MigrationPlan> {
+    let existing: HashSet<String> = db
+        .connection()
+        .table_names()
+        .execute()
+        .await?
+        .into_iter()
+        .collect();
+
+    let mut tables = Vec::new();
+    for (name, expected) in expected_schemas_for(db).await? {
+        if !existing.contains(name) {
+            continue;
+        }
+        let table = db.connection().open_table(name).execute().await?;
+        let on_disk = table.schema().await?;
+        let changes = diff_schema(name, &on_disk, &expected);
+        if !changes.is_empty() {
+            tables.push(TableMigration {
+                table: name.to_string(),
+                changes,
+            });
+        }
+    }
+
+    let recorded_versions = if existing.contains(TABLE_SCHEMA_META) {
+        read_recorded_versions(db).await?
+    } else {
+        BTreeMap::new()
+    };
+
+    Ok(MigrationPlan {
+        target_version: SCHEMA_VERSION,
+        recorded_versions,
+        tables,
+    })
+}
+
+/// Apply all pending schema changes and record the schema version.
+/// Refuses (without modifying anything) if any change is incompatible.
+pub async fn apply_migrations(db: &Database) -> Result<MigrationPlan> {
+    let plan = plan_migrations(db).await?;
+    if let Some((table, change)) = plan.first_incompatible() {
+        let detail = match change {
+            SchemaChange::Incompatible { column, reason } => format!("{column}: {reason}"),
+            other => format!("{other:?}"),
+        };
+        return Err(DbError::MigrationRequired {
+            table: table.to_string(),
+            detail,
+        });
+    }
+
+    let expected: BTreeMap<&str, Arc<Schema>> =
+        expected_schemas_for(db).await?.into_iter().collect();
+    for migration in &plan.tables {
+        let Some(schema) = expected.get(migration.table.as_str()) else {
+            continue;
+        };
+        apply_table_migration(db, migration, schema).await?;
+        info!(
+            table = %migration.table,
+            changes = migration.changes.len(),
+            "Applied schema migration"
+        );
+    }
+
+    let up_to_date = expected.keys().all(|t| {
+        plan.recorded_versions
+            .get(*t)
+            .is_some_and(|v| *v == SCHEMA_VERSION)
+    });
+    if !plan.is_empty() || !up_to_date {
+        record_schema_versions(db).await?;
+    }
+    Ok(plan)
+}
+
+async fn apply_table_migration(
+    db: &Database,
+    migration: &TableMigration,
+    expected: &Arc<Schema>,
+) -> Result<()> {
+    let table = db
+        .connection()
+        .open_table(migration.table.as_str())
+        .execute()
+        .await?;
+
+    let backfills: Vec<(String, String)> = migration
+        .changes
+        .iter()
+        .filter_map(|c| match c {
+            SchemaChange::BackfillColumn {
+                column,
+                default_sql,
+                ..
+            } => Some((column.clone(), default_sql.clone())),
+            _ => None,
+        })
+        .collect();
+    if !backfills.is_empty() {
+        table
+            .add_columns(NewColumnTransform::SqlExpressions(backfills), None)
+            .await?;
+    }
+
+    let null_fields: Vec<Field> = migration
+        .changes
+        .iter()
+        .filter_map(|c| match c {
+            SchemaChange::AddNullableColumn { column, .. } => {
+                expected.field_with_name(column).ok().cloned()
+            }
+            _ => None,
+        })
+        .collect();
+    let relax: Vec<ColumnAlteration> = migration
+        .changes
+        .iter()
+        .filter_map(|c| match c {
+            SchemaChange::RelaxNullability { column } => {
+                Some(ColumnAlteration::new(column.clone()).set_nullable(true))
+            }
+            _ => None,
+        })
+        .collect();
+
+    if !null_fields.is_empty() {
+        let nulls = Arc::new(Schema::new(null_fields));
+        if let Err(err) = table
+            .add_columns(NewColumnTransform::AllNulls(nulls), None)
+            .await
+        {
+            warn!(
+                table = %migration.table,
+                "add_columns unsupported ({err}); rewriting table"
+            );
+            return rewrite_table(db, &migration.table, expected).await;
+        }
+    }
+    if !relax.is_empty() {
+        if let Err(err) = table.alter_columns(&relax).await {
+            warn!(
+                table = %migration.table,
+                "alter_columns unsupported ({err}); rewriting table"
+            );
+            return rewrite_table(db, &migration.table, expected).await;
+        }
+    }
+    Ok(())
+}
+
+/// Rewrite a table under the expected schema, keeping every row and any
+/// columns the current code does not know about. Reads the whole table into
+/// memory, so it is only used when in-place alteration fails.
+async fn rewrite_table(db: &Database, name: &str, expected: &Schema) -> Result<()> {
+    let table = db.connection().open_table(name).execute().await?;
+    let on_disk = table.schema().await?;
+
+    let mut fields: Vec<Field> = expected
+        .fields()
+        .iter()
+        .map(|f| match on_disk.field_with_name(f.name()) {
+            // Keep the on-disk physical type so conformed batches match exactly.
+            Ok(d) => f
+                .as_ref()
+                .clone()
+                .with_data_type(d.data_type().clone())
+                .with_nullable(f.is_nullable() || d.is_nullable()),
+            Err(_) => f.as_ref().clone().with_nullable(true),
+        })
+        .collect();
+    for field in on_disk.fields() {
+        if expected.field_with_name(field.name()).is_err() {
+            fields.push(field.as_ref().clone());
+        }
+    }
+    let target = Arc::new(Schema::new(fields));
+
+    let mut batches = Vec::new();
+    let mut stream = table.query().execute().await?;
+    while let Some(batch) = stream.next().await {
+        batches.push(conform_batch(&batch?, &target)?);
+    }
+    let rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+
+    let reader = RecordBatchIterator::new(
+        batches.into_iter().map(Ok::<_, arrow_schema::ArrowError>),
+        target.clone(),
+    );
+    db.connection()
+        .create_table(name, reader)
+        .mode(CreateTableMode::Overwrite)
+        .execute()
+        .await?;
+    info!(table = name, rows, "Rewrote table for schema migration");
+    Ok(())
+}
+
+async fn read_recorded_versions(db: &Database) -> Result<BTreeMap<String, i64>> {
+    let table = db
+        .connection()
+        .open_table(TABLE_SCHEMA_META)
+        .execute()
+        .await?;
+    let mut out = BTreeMap::new();
+    let mut stream = table.query().execute().await?;
+    while let Some(batch) = stream.next().await {
+        let batch = batch?;
+        let (Some(names), Some(versions)) = (
+            batch
+                .column_by_name("table_name")
+                .and_then(|c| c.as_any().downcast_ref::<StringArray>().cloned()),
+            batch
+                .column_by_name("schema_version")
+                .and_then(|c| c.as_any().downcast_ref::<Int64Array>().cloned()),
+        ) else {
+            continue;
+        };
+        for row in 0..batch.num_rows() {
+            if names.is_null(row) || versions.is_null(row) {
+                continue;
+            }
+            out.insert(names.value(row).to_string(), versions.value(row));
+        }
+    }
+    Ok(out)
+}
+
+async fn record_schema_versions(db: &Database) -> Result<()> {
+    let table = db
+        .connection()
+        .open_table(TABLE_SCHEMA_META)
+        .execute()
+        .await?;
+    let schemas = expected_schemas_for(db).await?;
+    let applied_at = chrono::Utc::now().to_rfc3339();
+
+    let schema = table.schema().await?;
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(StringArray::from(
+                schemas.iter().map(|(t, _)| *t).collect::<Vec<_>>(),
+            )) as ArrayRef,
+            Arc::new(Int64Array::from(vec![SCHEMA_VERSION; schemas.len()])),
+            Arc::new(Int64Array::from(
+                schemas
+                    .iter()
+                    .map(|(_, s)| s.fields().len() as i64)
+                    .collect::<Vec<_>>(),
+            )),
+            Arc::new(StringArray::from(vec![applied_at.as_str(); schemas.len()])),
+        ],
+    )?;
+
+    // One merge commit replaces every row, so a failure part-way through
+    // leaves the previous versions rather than none.
+    let iter = RecordBatchIterator::new(vec![Ok(batch)], schema);
+    let mut builder = table.merge_insert(&["table_name"]);
+    builder
+        .when_matched_update_all(None)
+        .when_not_matched_insert_all()
+        .when_not_matched_by_source_delete(None);
+    builder.execute(Box::new(iter)).await?;
+    Ok(())
+}
+
+// =============================================================================
+// Reader tolerance
+// =============================================================================
+
+fn matches_prefix(actual: &Schema, expected: &Schema) -> bool {
+    actual.fields().len() >= expected.fields().len()
+        && expected
+            .fields()
+            .iter()
+            .zip(actual.fields().iter())
+            .all(|(e, a)| e.name() == a.name() && same_type(e.data_type(), a.data_type()))
+}
+
+/// Project `batch` onto `expected` by column name. Columns missing from the
+/// batch become all-null (their field is marked nullable); extra columns are
+/// dropped. A column with a different type is an error.
+pub fn conform_batch(batch: &RecordBatch, expected: &Schema) -> Result<RecordBatch> {
+    let rows = batch.num_rows();
+    let source = batch.schema();
+    let mut fields = Vec::with_capacity(expected.fields().len());
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(expected.fields().len());
+    for field in expected.fields() {
+        match source.index_of(field.name()) {
+            Ok(idx) => {
+                let column = batch.column(idx);
+                if !same_type(column.data_type(), field.data_type()) {
+                    return Err(DbError::Arrow(format!(
+                        "column {} has type {} but {} is expected",
+                        field.name(),
+                        column.data_type(),
+                        field.data_type()
+                    )));
+                }
+                let nullable = field.is_nullable() || column.null_count() > 0;
+                fields.push(
+                    field
+                        .as_ref()
+                        .clone()
+                        .with_data_type(column.data_type().clone())
+                        .with_nullable(nullable),
+                );
+                columns.push(column.clone());
+            }
+            Err(_) => {
+                fields.push(field.as_ref().clone().with_nullable(true));
+                columns.push(new_null_array(field.data_type(), rows));
+            }
+        }
+    }
+    Ok(RecordBatch::try_new(
+        Arc::new(Schema::new(fields)),
+        columns,
+    )?)
+}
+
+/// Row view for positional readers: borrows `batch` when its leading columns
+/// already match `expected`, otherwise conforms just that row.
+pub(crate) fn conform_row<'a>(
+    batch: &'a RecordBatch,
+    row: usize,
+    expected: &Schema,
+) -> Result<(Cow<'a, RecordBatch>, usize)> {
+    if matches_prefix(&batch.schema(), expected) {
+        return Ok((Cow::Borrowed(batch), row));
+    }
+    let single = batch.slice(row, 1);
+    Ok((Cow::Owned(conform_batch(&single, expected)?), 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kg_facts::KgFactRepository;
+    use crate::papers::PaperRepository;
+    use crate::schema::{KgFact, Paper, TABLE_KG_FACTS, TABLE_PAPERS};
+    use crate::schema_arrow::{
+        kg_fact_schema, kg_fact_to_record, paper_schema, record_to_kg_fact, record_to_paper,
+    };
+
+    fn legacy_paper_schema() -> Arc<Schema> {
+        // Paper schema before abstract_simhash / published_version_doi / is_review /
+        // citation_count / minhash_signature / duplicate_of / text_quality /
+        // job_id / query_tag shipped.
+        let fields: Vec<Field> = paper_schema()
+            .fields()
+            .iter()
+            .filter(|f| {
+                !matches!(
+                    f.name().as_str(),
+                    "abstract_simhash"
+                        | "published_version_doi"
+                        | "is_review"
+                        | "citation_count"
+                        | "minhash_signature"
+                        | "duplicate_of"
+                        | "text_quality"
+                        | "job_id"
+                        | "query_tag"
+                )
+            })
+            .map(|f| f.as_ref().clone())
+            .collect();
+        Arc::new(Schema::new(fields))
+    }
+
+    fn legacy_paper_batch(id: uuid::Uuid) -> RecordBatch {
+        let schema = legacy_paper_schema();
+        let mut paper = Paper::new("Legacy KRAS paper".to_string(), "pubmed".to_string());
+        paper.id = id;
+        let batch = crate::schema_arrow::paper_to_record(&paper).unwrap();
+        conform_batch(&batch, &schema).unwrap()
+    }
+
+    fn legacy_kg_fact_batch(fact: &KgFact) -> RecordBatch {
+        // Fact schema before evidence offsets and support merging shipped.
+        let fields: Vec<Field> = kg_fact_schema()
+            .fields()
+            .iter()
+            .filter(|f| {
+                !matches!(
+                    f.name().as_str(),
+                    "chunk_id"
+                        | "evidence_start"
+                        | "evidence_end"
+                        | "supporting_evidence"
+                        | "support_count"
+                )
+            })
+            .map(|f| f.as_ref().clone())
+            .collect();
+        let batch = kg_fact_to_record(fact).unwrap();
+        conform_batch(&batch, &Schema::new(fields)).unwrap()
+    }
+
+    fn kras_fact() -> KgFact {
+        let mut fact = KgFact::new(
+            uuid::Uuid::new_v4(),
+            uuid::Uuid::new_v4(),
+            "KRAS".to_string(),
+            "has_mutation".to_string(),
+            uuid::Uuid::new_v4(),
+            "G12D".to_string(),
+        );
+        fact.evidence = Some("KRAS G12D is common in PDAC.".to_string());
+        fact
+    }
+
+    async fn fixture_db(name: &str) -> (Database, std::path::PathBuf) {
+        let dir =
+            std::env::temp_dir().join(format!("ferrumyx-schema-{name}-{}", uuid::Uuid::new_v4()));
+        let db = Database::open(&dir).await.unwrap();
+        (db, dir)
+    }
+
+    #[test]
+    fn diff_reports_nullable_additions_and_type_changes() {
+        let on_disk = Schema::new(vec![
+            Field::new("id", DataType::Utf8, false),
+            Field::new("score", DataType::Int32, true),
+        ]);
+        let expected = Schema::new(vec![
+            Field::new("id", DataType::Utf8, true),
+            Field::new("score", DataType::Float64, true),
+            Field::new("license", DataType::Utf8, true),
+            Field::new("section_index", DataType::Int64, false),
+        ]);
+        let changes = diff_schema("papers", &on_disk, &expected);
+        assert_eq!(
+            changes[0],
+            SchemaChange::RelaxNullability {
+                column: "id".to_string()
+            }
+        );
+        assert!(changes[1].is_incompatible());
+        assert!(matches!(
+            &changes[2],
+            SchemaChange::AddNullableColumn { column, .. } if column == "license"
+        ));
+        assert!(changes[3].is_incompatible());
+    }
+
+    #[test]
+    fn conform_fills_missing_columns_with_nulls() {
+        let id = uuid::Uuid::new_v4();
+        let batch = legacy_paper_batch(id);
+        let (conformed, row) = conform_row(&batch, 0, &paper_schema()).unwrap();
+        let paper = record_to_paper(conformed.as_ref(), row).unwrap();
+        assert_eq!(paper.id, id);
+        assert_eq!(paper.abstract_simhash, None);
+        assert_eq!(paper.published_version_doi, None);
+        assert!(!paper.is_review);
+    }
+
+    #[test]
+    fn legacy_kg_facts_read_without_evidence_offsets() {
+        let fact = kras_fact();
+        let batch = legacy_kg_fact_batch(&fact);
+        assert_eq!(batch.num_columns(), kg_fact_schema().fields().len() - 5);
+
+        let read = record_to_kg_fact(&batch, 0).unwrap();
+        assert_eq!(read.id, fact.id);
+        assert_eq!(read.evidence, fact.evidence);
+        assert_eq!(read.chunk_id, None);
+        assert_eq!(read.evidence_start, None);
+        assert_eq!(read.support_count, 1);
+        assert_eq!(read.evidence_end, None);
+        assert!(read.supporting_evidence.is_empty());
+        assert_eq!(read.support_count, 1);
+    }
+
+    #[tokio::test]
+    async fn initialize_migrates_legacy_kg_fact_table() {
+        let (db, dir) = fixture_db("legacy-facts").await;
+        let legacy = kras_fact();
+        let batch = legacy_kg_fact_batch(&legacy);
+        let schema = batch.schema();
+        db.connection()
+            .create_table(
+                TABLE_KG_FACTS,
+                RecordBatchIterator::new(vec![Ok(batch)], schema),
+            )
+            .execute()
+            .await
+            .unwrap();
+
+        db.initialize().await.unwrap();
+        assert!(plan_migrations(&db).await.unwrap().is_empty());
+
+        let repo = KgFactRepository::new(Arc::new(db));
+        let read = repo.find_by_id(legacy.id).await.unwrap().unwrap();
+        assert_eq!(read.chunk_id, None);
+        assert_eq!(read.evidence_start, None);
+        assert_eq!(read.support_count, 1);
+
+        let mut fresh = kras_fact();
+        fresh.chunk_id = Some(uuid::Uuid::new_v4());
+        fresh.evidence_start = Some(0);
+        fresh.evidence_end = Some(28);
+        repo.insert(&fresh).await.unwrap();
+        let read = repo.find_by_id(fresh.id).await.unwrap().unwrap();
+        assert_eq!(read.chunk_id, fresh.chunk_id);
+        assert_eq!(
+            (read.evidence_start, read.evidence_end),
+            (Some(0), Some(28))
+        );
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn initialize_migrates_legacy_paper_table() {
+        let (db, dir) = fixture_db("legacy").await;
+        let legacy_id = uuid::Uuid::new_v4();
+        let batch = legacy_paper_batch(legacy_id);
+        let schema = batch.schema();
+        db.connection()
+            .create_table(
+                TABLE_PAPERS,
+                RecordBatchIterator::new(vec![Ok(batch)], schema),
+            )
+            .execute()
+            .await
+            .unwrap();
+
+        let plan = plan_migrations(&db).await.unwrap();
+        assert_eq!(plan.tables.len(), 1);
+        assert_eq!(plan.tables[0].changes.len(), 9);
+
+        db.initialize().await.unwrap();
+        let table = db
+            .connection()
+            .open_table(TABLE_PAPERS)
+            .execute()
+            .await
+            .unwrap();
+        let on_disk = table.schema().await.unwrap();
+        assert!(on_disk.field_with_name("abstract_simhash").is_ok());
+        assert!(on_disk.field_with_name("published_version_doi").is_ok());
+        assert!(on_disk.field_with_name("is_review").is_ok());
+        assert!(on_disk.field_with_name("citation_count").is_ok());
+        assert!(on_disk.field_with_name("minhash_signature").is_ok());
+        assert!(on_disk.field_with_name("duplicate_of").is_ok());
+        assert!(on_disk.field_with_name("text_quality").is_ok());
+        assert!(on_disk.field_with_name("job_id").is_ok());
+        assert!(on_disk.field_with_name("query_tag").is_ok());
+        assert!(plan_migrations(&db).await.unwrap().is_empty());
+
+        let db = Arc::new(db);
+        let repo = PaperRepository::new(db.clone());
+        let legacy = repo.find_by_id(legacy_id).await.unwrap().unwrap();
+        assert_eq!(legacy.abstract_simhash, None);
+        assert_eq!(legacy.minhash_signature, None);
+
+        let mut fresh = Paper::new("New paper".to_string(), "pubmed".to_string());
+        fresh.abstract_simhash = Some(42);
+        fresh.minhash_signature = Some(vec![7, u32::MAX, 0]);
+        fresh.duplicate_of = Some(legacy_id);
+        repo.insert(&fresh).await.unwrap();
+        let read_back = repo.find_by_id(fresh.id).await.unwrap().unwrap();
+        assert_eq!(read_back.abstract_simhash, Some(42));
+        assert_eq!(read_back.minhash_signature, fresh.minhash_signature);
+        assert_eq!(read_back.duplicate_of, Some(legacy_id));
+
+        let recorded = read_recorded_versions(&db).await.unwrap();
+        assert_eq!(recorded.get(TABLE_PAPERS), Some(&SCHEMA_VERSION));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn recording_versions_replaces_stale_rows_in_place() {
+        let (db, dir) = fixture_db("record").await;
+        db.initialize().await.unwrap();
+        let table = db
+            .connection()
+            .open_table(TABLE_SCHEMA_META)
+            .execute()
+            .await
+            .unwrap();
+        let schema = table.schema().await.unwrap();
+        let retired = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec!["retired_table"])) as ArrayRef,
+                Arc::new(Int64Array::from(vec![SCHEMA_VERSION - 1])),
+                Arc::new(Int64Array::from(vec![3])),
+                Arc::new(StringArray::from(vec!["2020-01-01T00:00:00Z"])),
+            ],
+        )
+        .unwrap();
+        table
+            .add(RecordBatchIterator::new(vec![Ok(retired)], schema))
+            .execute()
+            .await
+            .unwrap();
+
+        record_schema_versions(&db).await.unwrap();
+        record_schema_versions(&db).await.unwrap();
+
+        let expected = expected_schemas_for(&db).await.unwrap();
+        let recorded = read_recorded_versions(&db).await.unwrap();
+        let rows = db
+            .connection()
+            .open_table(TABLE_SCHEMA_META)
+            .execute()
+            .await
+            .unwrap()
+            .count_rows(None)
+            .await
+            .unwrap();
+        assert_eq!(rows, expected.len());
+        assert!(!recorded.contains_key("retired_table"));
+        assert_eq!(recorded.len(), expected.len());
+        assert!(recorded.values().all(|v| *v == SCHEMA_VERSION));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn initialize_refuses_incompatible_type_change() {
+        let (db, dir) = fixture_db("incompatible").await;
+        let fields: Vec<Field> = paper_schema()
+            .fields()
+            .iter()
+            .map(|f| {
+                if f.name() == "retrieval_tier" {
+                    Field::new("retrieval_tier", DataType::Utf8, true)
+                } else {
+                    f.as_ref().clone()
+                }
+            })
+            .collect();
+        let schema = Arc::new(Schema::new(fields));
+        db.connection()
+            .create_table(TABLE_PAPERS, RecordBatchIterator::new(vec![], schema))
+            .execute()
+            .await
+            .unwrap();
+
+        match db.initialize().await {
+            Err(DbError::MigrationRequired { table, detail }) => {
+                assert_eq!(table, TABLE_PAPERS);
+                assert!(detail.contains("retrieval_tier"), "{detail}");
+            }
+            other => panic!("expected MigrationRequired, got {other:?}"),
+        }
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
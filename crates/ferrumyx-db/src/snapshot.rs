@@ -0,0 +1,1070 @@
+//! Portable knowledge-base snapshots.
+//!
+//! [`export_snapshot`] writes papers, entities, chunks, entity mentions and
+//! KG facts to a `.tar.zst` archive: a `manifest.json` recording the schema
+//! version, embedding width and per-table row counts and digests, then one
+//! JSONL file per table holding the serde form of the schema structs. Chunk
+//! embeddings are stored as base64 little-endian `f32` so vectors come back
+//! bit-identical.
+//!
+//! [`import_snapshot`] checks the manifest and every file digest before it
+//! writes anything. [`SnapshotImportMode::Replace`] empties the tables and
+//! loads the snapshot as-is; [`SnapshotImportMode::Merge`] keeps the local
+//! rows, matches snapshot papers to stored ones by id, DOI or PMID and
+//! entities by id or `(entity_type, external_id)`, and folds facts into the
+//! stored row of their triple.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use arrow_array::RecordBatch;
+use base64::Engine;
+use futures::StreamExt;
+use lancedb::query::ExecutableQuery;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::chunks::ChunkRepository;
+use crate::database::Database;
+use crate::entities::EntityRepository;
+use crate::entity_mentions::EntityMentionRepository;
+use crate::error::{DbError, Result};
+use crate::kg_facts::KgFactRepository;
+use crate::maintenance::string_columns;
+use crate::papers::PaperRepository;
+use crate::schema::{
+    Chunk, Entity, EntityMention, KgFact, Paper, TABLE_CHUNKS, TABLE_ENTITIES,
+    TABLE_ENTITY_MENTIONS, TABLE_KG_FACTS, TABLE_PAPERS,
+};
+use crate::schema_arrow::{
+    record_to_chunk, record_to_entity, record_to_entity_mention, record_to_kg_fact, record_to_paper,
+};
+use crate::schema_evolution::SCHEMA_VERSION;
+
+/// `format` of every snapshot manifest.
+pub const SNAPSHOT_FORMAT: &str = "ferrumyx-snapshot";
+
+/// Archive layout version; bumped when the file set or row encoding changes.
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// Tables in a snapshot, in import order: rows refer only to tables before
+/// them.
+pub const SNAPSHOT_TABLES: [&str; 5] = [
+    TABLE_PAPERS,
+    TABLE_ENTITIES,
+    TABLE_CHUNKS,
+    TABLE_ENTITY_MENTIONS,
+    TABLE_KG_FACTS,
+];
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Rows per insert while importing.
+const IMPORT_BATCH: usize = 500;
+
+/// Contents of `manifest.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub format: String,
+    pub format_version: u32,
+    /// [`SCHEMA_VERSION`] of the exporting build.
+    pub schema_version: i64,
+    pub ferrumyx_version: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Width of `chunks.embedding`.
+    pub embedding_dim: usize,
+    pub tables: Vec<SnapshotTable>,
+}
+
+/// One table file of a snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotTable {
+    pub name: String,
+    pub file: String,
+    pub rows: u64,
+    /// Hex SHA-256 of the file.
+    pub sha256: String,
+}
+
+impl SnapshotManifest {
+    pub fn table(&self, name: &str) -> Option<&SnapshotTable> {
+        self.tables.iter().find(|t| t.name == name)
+    }
+}
+
+/// How [`import_snapshot`] treats rows already in the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotImportMode {
+    /// Delete every row of the snapshot tables first.
+    Replace,
+    /// Keep local rows and add the snapshot's, deduplicated.
+    #[default]
+    Merge,
+}
+
+/// Progress of an export or import, reported after each batch of rows.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SnapshotProgress {
+    /// `export` or `import`.
+    pub operation: &'static str,
+    /// Table being copied, or `archive` while the archive is packed or
+    /// unpacked.
+    pub table: String,
+    pub rows: u64,
+    pub total: u64,
+}
+
+/// Rows of one table read by [`import_snapshot`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TableImport {
+    pub table: String,
+    pub rows: u64,
+    pub written: u64,
+    /// Rows folded into a record that was already stored or already read
+    /// from the snapshot.
+    pub merged: u64,
+}
+
+/// Outcome of [`import_snapshot`].
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotImport {
+    pub mode: SnapshotImportMode,
+    pub manifest: SnapshotManifest,
+    pub tables: Vec<TableImport>,
+}
+
+/// Export the knowledge base to a `.tar.zst` archive at `path`.
+pub async fn export_snapshot(
+    db: Arc<Database>,
+    path: impl AsRef<Path>,
+) -> Result<SnapshotManifest> {
+    export_snapshot_with_progress(db, path, &|_| {}).await
+}
+
+/// [`export_snapshot`], calling `progress` as rows are written.
+pub async fn export_snapshot_with_progress(
+    db: Arc<Database>,
+    path: impl AsRef<Path>,
+    progress: &(dyn Fn(SnapshotProgress) + Send + Sync),
+) -> Result<SnapshotManifest> {
+    let path = path.as_ref();
+    let staging = staging_dir(path, "export");
+    std::fs::create_dir_all(&staging)?;
+    let result = write_snapshot(&db, path, &staging, progress).await;
+    std::fs::remove_dir_all(&staging).ok();
+    result
+}
+
+/// Import the archive at `path` written by [`export_snapshot`].
+pub async fn import_snapshot(
+    db: Arc<Database>,
+    path: impl AsRef<Path>,
+    mode: SnapshotImportMode,
+) -> Result<SnapshotImport> {
+    import_snapshot_with_progress(db, path, mode, &|_| {}).await
+}
+
+/// [`import_snapshot`], calling `progress` as rows are written.
+pub async fn import_snapshot_with_progress(
+    db: Arc<Database>,
+    path: impl AsRef<Path>,
+    mode: SnapshotImportMode,
+    progress: &(dyn Fn(SnapshotProgress) + Send + Sync),
+) -> Result<SnapshotImport> {
+    let path = path.as_ref();
+    let staging = staging_dir(path, "import");
+    std::fs::create_dir_all(&staging)?;
+    let result = read_snapshot(&db, path, &staging, mode, progress).await;
+    std::fs::remove_dir_all(&staging).ok();
+    result
+}
+
+async fn write_snapshot(
+    db: &Database,
+    path: &Path,
+    staging: &Path,
+    progress: &(dyn Fn(SnapshotProgress) + Send + Sync),
+) -> Result<SnapshotManifest> {
+    let mut tables = Vec::with_capacity(SNAPSHOT_TABLES.len());
+    for name in SNAPSHOT_TABLES {
+        let table = db.connection().open_table(name).execute().await?;
+        let total = table.count_rows(None).await? as u64;
+        let file = format!("{name}.jsonl");
+        let mut out = BufWriter::new(File::create(staging.join(&file))?);
+        let mut rows = 0;
+        let mut stream = table.query().execute().await?;
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            for row in 0..batch.num_rows() {
+                serde_json::to_writer(&mut out, &row_to_json(name, &batch, row)?)?;
+                out.write_all(b"\n")?;
+            }
+            rows += batch.num_rows() as u64;
+            progress(SnapshotProgress {
+                operation: "export",
+                table: name.to_string(),
+                rows,
+                total,
+            });
+        }
+        out.flush()?;
+        drop(out);
+        let (sha256, _) = file_digest(&staging.join(&file))?;
+        tables.push(SnapshotTable {
+            name: name.to_string(),
+            file,
+            rows,
+            sha256,
+        });
+    }
+
+    let manifest = SnapshotManifest {
+        format: SNAPSHOT_FORMAT.to_string(),
+        format_version: SNAPSHOT_FORMAT_VERSION,
+        schema_version: SCHEMA_VERSION,
+        ferrumyx_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: chrono::Utc::now(),
+        embedding_dim: db.embedding_dim(),
+        tables,
+    };
+    std::fs::write(
+        staging.join(MANIFEST_FILE),
+        serde_json::to_vec_pretty(&manifest)?,
+    )?;
+
+    let files: Vec<String> = std::iter::once(MANIFEST_FILE.to_string())
+        .chain(manifest.tables.iter().map(|t| t.file.clone()))
+        .collect();
+    let (archive, dir) = (path.to_path_buf(), staging.to_path_buf());
+    tokio::task::spawn_blocking(move || pack(&archive, &dir, &files))
+        .await
+        .map_err(|e| DbError::Io(std::io::Error::other(e)))??;
+    let total = manifest.tables.iter().map(|t| t.rows).sum();
+    progress(SnapshotProgress {
+        operation: "export",
+        table: "archive".to_string(),
+        rows: total,
+        total,
+    });
+    Ok(manifest)
+}
+
+async fn read_snapshot(
+    db: &Arc<Database>,
+    path: &Path,
+    staging: &Path,
+    mode: SnapshotImportMode,
+    progress: &(dyn Fn(SnapshotProgress) + Send + Sync),
+) -> Result<SnapshotImport> {
+    let (archive, dir) = (path.to_path_buf(), staging.to_path_buf());
+    tokio::task::spawn_blocking(move || unpack(&archive, &dir))
+        .await
+        .map_err(|e| DbError::Io(std::io::Error::other(e)))??;
+
+    let manifest_path = staging.join(MANIFEST_FILE);
+    if !manifest_path.exists() {
+        return Err(DbError::InvalidQuery(format!(
+            "{} has no {MANIFEST_FILE}",
+            path.display()
+        )));
+    }
+    let manifest: SnapshotManifest = serde_json::from_slice(&std::fs::read(manifest_path)?)?;
+    validate_manifest(&manifest, db.embedding_dim())?;
+    for table in &manifest.tables {
+        let (sha256, lines) = file_digest(&staging.join(&table.file))?;
+        if sha256 != table.sha256 || lines != table.rows {
+            return Err(DbError::InvalidQuery(format!(
+                "{} does not match the manifest: {lines} rows with digest {sha256}, expected {} rows with digest {}",
+                table.file, table.rows, table.sha256
+            )));
+        }
+    }
+    progress(SnapshotProgress {
+        operation: "import",
+        table: "archive".to_string(),
+        rows: 0,
+        total: manifest.tables.iter().map(|t| t.rows).sum(),
+    });
+
+    let mut index = match mode {
+        SnapshotImportMode::Replace => {
+            for name in SNAPSHOT_TABLES {
+                let table = db.connection().open_table(name).execute().await?;
+                table.delete("id IS NOT NULL").await?;
+            }
+            None
+        }
+        SnapshotImportMode::Merge => Some(MergeIndex::load(db).await?),
+    };
+
+    let mut tables = Vec::with_capacity(SNAPSHOT_TABLES.len());
+    for name in SNAPSHOT_TABLES {
+        let entry = manifest
+            .table(name)
+            .expect("validate_manifest checked every table is listed");
+        let mut report = TableImport {
+            table: name.to_string(),
+            ..TableImport::default()
+        };
+        let mut reader = JsonlReader::open(&staging.join(&entry.file))?;
+        while let Some(values) = reader.next_batch(IMPORT_BATCH)? {
+            report.rows += values.len() as u64;
+            let written = load_batch(db, name, values, index.as_mut(), mode).await?;
+            report.written += written;
+            progress(SnapshotProgress {
+                operation: "import",
+                table: name.to_string(),
+                rows: report.rows,
+                total: entry.rows,
+            });
+        }
+        report.merged = report.rows - report.written;
+        tables.push(report);
+    }
+
+    tracing::info!(
+        path = %path.display(),
+        ?mode,
+        rows = tables.iter().map(|t| t.written).sum::<u64>(),
+        "Imported knowledge-base snapshot"
+    );
+    Ok(SnapshotImport {
+        mode,
+        manifest,
+        tables,
+    })
+}
+
+/// Checks that this build can load `manifest` into a database whose chunks
+/// hold `embedding_dim`-wide vectors.
+pub fn validate_manifest(manifest: &SnapshotManifest, embedding_dim: usize) -> Result<()> {
+    if manifest.format != SNAPSHOT_FORMAT {
+        return Err(DbError::InvalidQuery(format!(
+            "not a Ferrumyx snapshot (format {:?})",
+            manifest.format
+        )));
+    }
+    if manifest.format_version > SNAPSHOT_FORMAT_VERSION {
+        return Err(DbError::InvalidQuery(format!(
+            "snapshot format version {} is newer than the supported {SNAPSHOT_FORMAT_VERSION}",
+            manifest.format_version
+        )));
+    }
+    if manifest.schema_version > SCHEMA_VERSION {
+        return Err(DbError::InvalidQuery(format!(
+            "snapshot schema version {} is newer than this build's {SCHEMA_VERSION}; upgrade before importing",
+            manifest.schema_version
+        )));
+    }
+    for name in SNAPSHOT_TABLES {
+        let table = manifest
+            .table(name)
+            .ok_or_else(|| DbError::InvalidQuery(format!("snapshot has no {name} table")))?;
+        if !is_plain_file_name(Path::new(&table.file)) {
+            return Err(DbError::InvalidQuery(format!(
+                "snapshot file name {:?} is not a plain file name",
+                table.file
+            )));
+        }
+    }
+    let has_chunks = manifest.table(TABLE_CHUNKS).is_some_and(|t| t.rows > 0);
+    if has_chunks && manifest.embedding_dim != embedding_dim {
+        return Err(DbError::DimensionMismatch {
+            expected: embedding_dim,
+            actual: manifest.embedding_dim,
+        });
+    }
+    Ok(())
+}
+
+/// Identifiers of the stored rows a merge deduplicates against, and the
+/// local ids that snapshot papers and entities were folded into.
+#[derive(Debug, Default)]
+struct MergeIndex {
+    paper_ids: HashSet<uuid::Uuid>,
+    dois: HashMap<String, uuid::Uuid>,
+    pmids: HashMap<String, uuid::Uuid>,
+    entity_ids: HashSet<uuid::Uuid>,
+    entity_keys: HashMap<(String, String), uuid::Uuid>,
+    /// Snapshot paper id to the stored paper it duplicates.
+    papers: HashMap<uuid::Uuid, uuid::Uuid>,
+    /// Snapshot entity id to the stored entity it duplicates.
+    entities: HashMap<uuid::Uuid, uuid::Uuid>,
+}
+
+impl MergeIndex {
+    async fn load(db: &Database) -> Result<Self> {
+        let mut index = Self::default();
+        let papers = db.connection().open_table(TABLE_PAPERS).execute().await?;
+        for row in string_columns(&papers, &["id", "doi", "pmid"]).await? {
+            let Some(id) = row[0]
+                .as_deref()
+                .and_then(|id| uuid::Uuid::parse_str(id).ok())
+            else {
+                continue;
+            };
+            index.add_paper(id, row[1].as_deref(), row[2].as_deref());
+        }
+        let entities = db.connection().open_table(TABLE_ENTITIES).execute().await?;
+        for row in string_columns(&entities, &["id", "entity_type", "external_id"]).await? {
+            let Some(id) = row[0]
+                .as_deref()
+                .and_then(|id| uuid::Uuid::parse_str(id).ok())
+            else {
+                continue;
+            };
+            index.add_entity(id, row[1].as_deref(), row[2].as_deref());
+        }
+        Ok(index)
+    }
+
+    fn add_paper(&mut self, id: uuid::Uuid, doi: Option<&str>, pmid: Option<&str>) {
+        self.paper_ids.insert(id);
+        if let Some(doi) = normalized(doi) {
+            self.dois.entry(doi).or_insert(id);
+        }
+        if let Some(pmid) = normalized(pmid) {
+            self.pmids.entry(pmid).or_insert(id);
+        }
+    }
+
+    fn add_entity(&mut self, id: uuid::Uuid, entity_type: Option<&str>, external_id: Option<&str>) {
+        self.entity_ids.insert(id);
+        if let Some(key) = entity_key(entity_type, external_id) {
+            self.entity_keys.entry(key).or_insert(id);
+        }
+    }
+
+    /// The stored paper `paper` duplicates; otherwise records it so later
+    /// snapshot rows can match it.
+    fn merge_paper(&mut self, paper: &Paper) -> Option<uuid::Uuid> {
+        let existing = if self.paper_ids.contains(&paper.id) {
+            Some(paper.id)
+        } else {
+            normalized(paper.doi.as_deref())
+                .and_then(|doi| self.dois.get(&doi).copied())
+                .or_else(|| {
+                    normalized(paper.pmid.as_deref()).and_then(|p| self.pmids.get(&p).copied())
+                })
+        };
+        match existing {
+            Some(id) => {
+                self.papers.insert(paper.id, id);
+                Some(id)
+            }
+            None => {
+                self.add_paper(paper.id, paper.doi.as_deref(), paper.pmid.as_deref());
+                None
+            }
+        }
+    }
+
+    fn merge_entity(&mut self, entity: &Entity) -> Option<uuid::Uuid> {
+        let existing = if self.entity_ids.contains(&entity.id) {
+            Some(entity.id)
+        } else {
+            entity_key(Some(&entity.entity_type), Some(&entity.external_id))
+                .and_then(|key| self.entity_keys.get(&key).copied())
+        };
+        match existing {
+            Some(id) => {
+                self.entities.insert(entity.id, id);
+                Some(id)
+            }
+            None => {
+                self.add_entity(
+                    entity.id,
+                    Some(&entity.entity_type),
+                    Some(&entity.external_id),
+                );
+                None
+            }
+        }
+    }
+
+    fn paper(&self, id: uuid::Uuid) -> uuid::Uuid {
+        self.papers.get(&id).copied().unwrap_or(id)
+    }
+
+    fn entity(&self, id: uuid::Uuid) -> uuid::Uuid {
+        self.entities.get(&id).copied().unwrap_or(id)
+    }
+}
+
+fn normalized(value: Option<&str>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_ascii_lowercase())
+        .filter(|v| !v.is_empty())
+}
+
+fn entity_key(entity_type: Option<&str>, external_id: Option<&str>) -> Option<(String, String)> {
+    Some((normalized(entity_type)?, normalized(external_id)?))
+}
+
+/// Writes one batch of `table` rows; returns how many were written.
+async fn load_batch(
+    db: &Arc<Database>,
+    table: &str,
+    values: Vec<Value>,
+    mut index: Option<&mut MergeIndex>,
+    mode: SnapshotImportMode,
+) -> Result<u64> {
+    match table {
+        TABLE_PAPERS => {
+            let mut papers: Vec<Paper> = decode_all(values)?;
+            if let Some(index) = index.as_deref_mut() {
+                papers.retain(|p| index.merge_paper(p).is_none());
+            }
+            PaperRepository::new(db.clone())
+                .insert_batch(&papers)
+                .await?;
+            Ok(papers.len() as u64)
+        }
+        TABLE_ENTITIES => {
+            let mut entities: Vec<Entity> = decode_all(values)?;
+            if let Some(index) = index.as_deref_mut() {
+                entities.retain(|e| index.merge_entity(e).is_none());
+            }
+            EntityRepository::new(db.clone())
+                .insert_batch(&entities)
+                .await?;
+            Ok(entities.len() as u64)
+        }
+        TABLE_CHUNKS => {
+            let mut chunks = values
+                .into_iter()
+                .map(chunk_from_json)
+                .collect::<Result<Vec<_>>>()?;
+            // A merged paper keeps the chunks it already has.
+            if let Some(index) = index.as_deref() {
+                chunks.retain(|c| !index.papers.contains_key(&c.paper_id));
+            }
+            ChunkRepository::new(db.clone())
+                .insert_batch(&chunks)
+                .await?;
+            Ok(chunks.len() as u64)
+        }
+        TABLE_ENTITY_MENTIONS => {
+            let mut mentions: Vec<EntityMention> = decode_all(values)?;
+            if let Some(index) = index.as_deref() {
+                mentions.retain(|m| !index.papers.contains_key(&m.paper_id));
+                for mention in &mut mentions {
+                    mention.entity_id = index.entity(mention.entity_id);
+                }
+            }
+            EntityMentionRepository::new(db.clone())
+                .insert_batch(&mentions)
+                .await?;
+            Ok(mentions.len() as u64)
+        }
+        TABLE_KG_FACTS => {
+            let mut facts: Vec<KgFact> = decode_all(values)?;
+            let repo = KgFactRepository::new(db.clone());
+            match (mode, index.as_deref()) {
+                (SnapshotImportMode::Merge, Some(index)) => {
+                    for fact in &mut facts {
+                        remap_fact(fact, index);
+                    }
+                    Ok(repo.upsert_batch(&facts).await? as u64)
+                }
+                _ => {
+                    repo.insert_batch(&facts).await?;
+                    Ok(facts.len() as u64)
+                }
+            }
+        }
+        other => Err(DbError::InvalidQuery(format!(
+            "{other} is not a snapshot table"
+        ))),
+    }
+}
+
+/// Points `fact` at the stored papers and entities its snapshot rows were
+/// merged into. Chunk references of merged papers are dropped, as those
+/// chunks were not imported.
+fn remap_fact(fact: &mut KgFact, index: &MergeIndex) {
+    let is_merged = |paper_id: uuid::Uuid| index.papers.contains_key(&paper_id);
+    if is_merged(fact.paper_id) {
+        fact.chunk_id = None;
+    }
+    fact.paper_id = index.paper(fact.paper_id);
+    fact.subject_id = index.entity(fact.subject_id);
+    fact.object_id = index.entity(fact.object_id);
+    for support in &mut fact.supporting_evidence {
+        if is_merged(support.paper_id) {
+            support.chunk_id = None;
+        }
+        support.paper_id = index.paper(support.paper_id);
+    }
+}
+
+fn decode_all<T: serde::de::DeserializeOwned>(values: Vec<Value>) -> Result<Vec<T>> {
+    Ok(values
+        .into_iter()
+        .map(serde_json::from_value)
+        .collect::<serde_json::Result<_>>()?)
+}
+
+fn row_to_json(table: &str, batch: &RecordBatch, row: usize) -> Result<Value> {
+    Ok(match table {
+        TABLE_PAPERS => serde_json::to_value(record_to_paper(batch, row)?)?,
+        TABLE_ENTITIES => serde_json::to_value(record_to_entity(batch, row)?)?,
+        TABLE_CHUNKS => chunk_to_json(&record_to_chunk(batch, row)?)?,
+        TABLE_ENTITY_MENTIONS => serde_json::to_value(record_to_entity_mention(batch, row)?)?,
+        TABLE_KG_FACTS => serde_json::to_value(record_to_kg_fact(batch, row)?)?,
+        other => {
+            return Err(DbError::InvalidQuery(format!(
+                "{other} is not a snapshot table"
+            )))
+        }
+    })
+}
+
+const EMBEDDING_FIELDS: [&str; 2] = ["embedding", "embedding_large"];
+
+/// `chunk` as JSON with its vectors as base64 little-endian `f32`.
+fn chunk_to_json(chunk: &Chunk) -> Result<Value> {
+    let mut value = serde_json::to_value(chunk)?;
+    for (field, vector) in EMBEDDING_FIELDS
+        .iter()
+        .zip([&chunk.embedding, &chunk.embedding_large])
+    {
+        if let Some(vector) = vector {
+            let bytes: Vec<u8> = vector.iter().flat_map(|x| x.to_le_bytes()).collect();
+            value[*field] = Value::String(base64::engine::general_purpose::STANDARD.encode(bytes));
+        }
+    }
+    Ok(value)
+}
+
+fn chunk_from_json(mut value: Value) -> Result<Chunk> {
+    let mut vectors = [None, None];
+    for (field, vector) in EMBEDDING_FIELDS.iter().zip(vectors.iter_mut()) {
+        let Some(Value::String(encoded)) = value.get_mut(*field).map(Value::take) else {
+            continue;
+        };
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|e| DbError::InvalidQuery(format!("chunk {field}: {e}")))?;
+        if bytes.len() % 4 != 0 {
+            return Err(DbError::InvalidQuery(format!(
+                "chunk {field} is not a whole number of f32 values"
+            )));
+        }
+        *vector = Some(
+            bytes
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect::<Vec<f32>>(),
+        );
+    }
+    let mut chunk: Chunk = serde_json::from_value(value)?;
+    let [embedding, embedding_large] = vectors;
+    if embedding.is_some() {
+        chunk.embedding = embedding;
+    }
+    if embedding_large.is_some() {
+        chunk.embedding_large = embedding_large;
+    }
+    Ok(chunk)
+}
+
+/// Reads a JSONL file a batch of rows at a time.
+struct JsonlReader {
+    name: String,
+    lines: std::io::Lines<BufReader<File>>,
+    line: usize,
+}
+
+impl JsonlReader {
+    fn open(path: &Path) -> Result<Self> {
+        Ok(Self {
+            name: path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            lines: BufReader::new(File::open(path)?).lines(),
+            line: 0,
+        })
+    }
+
+    fn next_batch(&mut self, size: usize) -> Result<Option<Vec<Value>>> {
+        let mut batch = Vec::new();
+        while batch.len() < size {
+            let Some(line) = self.lines.next() else {
+                break;
+            };
+            let line = line?;
+            self.line += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            batch.push(serde_json::from_str(&line).map_err(|e| {
+                DbError::InvalidQuery(format!("{} line {}: {e}", self.name, self.line))
+            })?);
+        }
+        Ok((!batch.is_empty()).then_some(batch))
+    }
+}
+
+/// Hex SHA-256 and line count of the file at `path`.
+fn file_digest(path: &Path) -> Result<(String, u64)> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut lines = 0;
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        lines += buf[..n].iter().filter(|&&b| b == b'\n').count() as u64;
+    }
+    Ok((hex::encode(hasher.finalize()), lines))
+}
+
+/// Scratch directory next to `archive`.
+fn staging_dir(archive: &Path, operation: &str) -> PathBuf {
+    let name = archive
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "snapshot".to_string());
+    archive.with_file_name(format!(".{name}.{operation}-{}", uuid::Uuid::new_v4()))
+}
+
+fn is_plain_file_name(path: &Path) -> bool {
+    let mut components = path.components();
+    matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(_)), None)
+    )
+}
+
+/// Packs `files` of `dir` into a zstd-compressed tar at `archive`, writing
+/// to a temporary name first so a failed export leaves no partial archive.
+fn pack(archive: &Path, dir: &Path, files: &[String]) -> Result<()> {
+    if let Some(parent) = archive.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let partial = staging_dir(archive, "partial");
+    let write = || -> Result<()> {
+        let encoder = zstd::Encoder::new(File::create(&partial)?, 3)?;
+        let mut builder = tar::Builder::new(encoder);
+        for file in files {
+            builder.append_path_with_name(dir.join(file), file)?;
+        }
+        builder.into_inner()?.finish()?.sync_all()?;
+        Ok(())
+    };
+    match write() {
+        Ok(()) => Ok(std::fs::rename(&partial, archive)?),
+        Err(e) => {
+            std::fs::remove_file(&partial).ok();
+            Err(e)
+        }
+    }
+}
+
+/// Unpacks the regular files of `archive` into `dir`, refusing entries
+/// that would land outside it.
+fn unpack(archive: &Path, dir: &Path) -> Result<()> {
+    let decoder = zstd::Decoder::new(File::open(archive)?)?;
+    let mut tar = tar::Archive::new(decoder);
+    for entry in tar.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry.path()?.into_owned();
+        if !is_plain_file_name(&path) {
+            return Err(DbError::InvalidQuery(format!(
+                "snapshot entry {} is not a plain file name",
+                path.display()
+            )));
+        }
+        entry.unpack(dir.join(path))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{EntityType, FactSupport};
+
+    async fn open(label: &str) -> (Arc<Database>, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-{label}-{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        (db, dir)
+    }
+
+    fn embedding(seed: usize, dim: usize) -> Vec<f32> {
+        (0..dim)
+            .map(|i| ((seed * 31 + i) as f32).sin() / 3.0 + f32::EPSILON * i as f32)
+            .collect()
+    }
+
+    async fn counts(db: &Arc<Database>) -> [u64; 5] {
+        [
+            PaperRepository::new(db.clone()).count().await.unwrap(),
+            EntityRepository::new(db.clone()).count().await.unwrap(),
+            ChunkRepository::new(db.clone()).count().await.unwrap(),
+            EntityMentionRepository::new(db.clone())
+                .count()
+                .await
+                .unwrap(),
+            KgFactRepository::new(db.clone()).count().await.unwrap(),
+        ]
+    }
+
+    #[tokio::test]
+    async fn export_then_import_round_trips_rows_and_vectors() {
+        let (source, source_dir) = open("snapshot-src").await;
+        let dim = source.embedding_dim();
+
+        let mut cited = Paper::new("KRAS G12D in PDAC".to_string(), "pubmed".to_string());
+        cited.doi = Some("10.1000/KRAS".to_string());
+        cited.pmid = Some("123".to_string());
+        cited.minhash_signature = Some(vec![1, 2, 3]);
+        let other = Paper::new("TP53 loss".to_string(), "europepmc".to_string());
+        PaperRepository::new(source.clone())
+            .insert_batch(&[cited.clone(), other.clone()])
+            .await
+            .unwrap();
+
+        let kras = Entity::new(
+            EntityType::Gene,
+            "KRAS".to_string(),
+            "HGNC:6407".to_string(),
+            "hgnc".to_string(),
+        );
+        let pdac = Entity::new(
+            EntityType::CancerType,
+            "PDAC".to_string(),
+            "C3850".to_string(),
+            "oncotree".to_string(),
+        );
+        EntityRepository::new(source.clone())
+            .insert_batch(&[kras.clone(), pdac.clone()])
+            .await
+            .unwrap();
+
+        let chunks: Vec<Chunk> = [&cited, &other]
+            .iter()
+            .enumerate()
+            .map(|(i, paper)| {
+                let mut chunk = Chunk::new(paper.id, 0, format!("chunk {i}"));
+                chunk.embedding = Some(embedding(i, dim));
+                chunk
+            })
+            .collect();
+        ChunkRepository::new(source.clone())
+            .insert_batch(&chunks)
+            .await
+            .unwrap();
+        EntityMentionRepository::new(source.clone())
+            .insert(&EntityMention::new(
+                kras.id,
+                chunks[0].id,
+                cited.id,
+                "KRAS".to_string(),
+                0,
+                4,
+            ))
+            .await
+            .unwrap();
+        let mut fact = KgFact::new(
+            cited.id,
+            kras.id,
+            "KRAS".to_string(),
+            "associated_with".to_string(),
+            pdac.id,
+            "PDAC".to_string(),
+        );
+        fact.supporting_evidence = vec![
+            FactSupport {
+                paper_id: cited.id,
+                confidence: 0.6,
+                evidence: Some("KRAS drives PDAC".to_string()),
+                chunk_id: Some(chunks[0].id),
+                published_at: None,
+            },
+            FactSupport {
+                paper_id: other.id,
+                confidence: 0.4,
+                evidence: None,
+                chunk_id: None,
+                published_at: None,
+            },
+        ];
+        fact.support_count = 2;
+        KgFactRepository::new(source.clone())
+            .insert(&fact)
+            .await
+            .unwrap();
+
+        let archive = source_dir.join("exports").join("kb.tar.zst");
+        let seen = std::sync::Mutex::new(Vec::new());
+        let manifest = export_snapshot_with_progress(source.clone(), &archive, &|p| {
+            seen.lock().unwrap().push(p)
+        })
+        .await
+        .unwrap();
+        assert_eq!(
+            manifest.tables.iter().map(|t| t.rows).collect::<Vec<_>>(),
+            counts(&source).await
+        );
+        assert_eq!(manifest.schema_version, SCHEMA_VERSION);
+        assert_eq!(seen.lock().unwrap().last().unwrap().table, "archive");
+
+        let (target, target_dir) = open("snapshot-dst").await;
+        let report = import_snapshot(target.clone(), &archive, SnapshotImportMode::Replace)
+            .await
+            .unwrap();
+        assert_eq!(counts(&target).await, counts(&source).await);
+        assert!(report.tables.iter().all(|t| t.merged == 0));
+
+        let chunk = ChunkRepository::new(target.clone())
+            .find_by_id(chunks[1].id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(chunk.embedding, chunks[1].embedding);
+        let paper = PaperRepository::new(target.clone())
+            .find_by_id(cited.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(paper.doi, cited.doi);
+        assert_eq!(paper.minhash_signature, cited.minhash_signature);
+        let stored = KgFactRepository::new(target.clone())
+            .find_by_id(fact.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.supporting_evidence, fact.supporting_evidence);
+        assert_eq!(stored.object_name, "PDAC");
+
+        // Merging the snapshot back into its source adds nothing.
+        let report = import_snapshot(source.clone(), &archive, SnapshotImportMode::Merge)
+            .await
+            .unwrap();
+        assert_eq!(counts(&source).await, counts(&target).await);
+        assert_eq!(report.tables[0].merged, 2);
+
+        std::fs::remove_dir_all(source_dir).ok();
+        std::fs::remove_dir_all(target_dir).ok();
+    }
+
+    #[tokio::test]
+    async fn merge_matches_papers_by_doi_and_facts_by_triple() {
+        let (source, source_dir) = open("snapshot-merge-src").await;
+        let mut paper = Paper::new("KRAS review".to_string(), "pubmed".to_string());
+        paper.doi = Some("10.1000/shared".to_string());
+        PaperRepository::new(source.clone())
+            .insert(&paper)
+            .await
+            .unwrap();
+        let subject = uuid::Uuid::from_u128(1);
+        let object = uuid::Uuid::from_u128(2);
+        let fact = KgFact::new(
+            paper.id,
+            subject,
+            "KRAS".to_string(),
+            "associated_with".to_string(),
+            object,
+            "PDAC".to_string(),
+        );
+        KgFactRepository::new(source.clone())
+            .insert(&fact)
+            .await
+            .unwrap();
+        let archive = source_dir.join("kb.tar.zst");
+        export_snapshot(source.clone(), &archive).await.unwrap();
+
+        // The target stored the same DOI under another id from another source.
+        let (target, target_dir) = open("snapshot-merge-dst").await;
+        let mut local = Paper::new("KRAS review".to_string(), "europepmc".to_string());
+        local.doi = Some("10.1000/SHARED ".to_string());
+        PaperRepository::new(target.clone())
+            .insert(&local)
+            .await
+            .unwrap();
+        let mut local_fact = fact.clone();
+        local_fact.id = uuid::Uuid::new_v4();
+        local_fact.paper_id = local.id;
+        KgFactRepository::new(target.clone())
+            .insert(&local_fact)
+            .await
+            .unwrap();
+
+        let report = import_snapshot(target.clone(), &archive, SnapshotImportMode::Merge)
+            .await
+            .unwrap();
+        assert_eq!(report.tables[0].merged, 1);
+        assert_eq!(
+            PaperRepository::new(target.clone()).count().await.unwrap(),
+            1
+        );
+        let facts = KgFactRepository::new(target.clone())
+            .list(0, 10)
+            .await
+            .unwrap();
+        assert_eq!(facts.len(), 1);
+        assert_eq!(facts[0].paper_id, local.id);
+        assert_eq!(facts[0].support_count, 1);
+
+        std::fs::remove_dir_all(source_dir).ok();
+        std::fs::remove_dir_all(target_dir).ok();
+    }
+
+    #[test]
+    fn manifest_validation_rejects_foreign_or_newer_snapshots() {
+        let manifest = SnapshotManifest {
+            format: SNAPSHOT_FORMAT.to_string(),
+            format_version: SNAPSHOT_FORMAT_VERSION,
+            schema_version: SCHEMA_VERSION,
+            ferrumyx_version: "0.0.0".to_string(),
+            created_at: chrono::Utc::now(),
+            embedding_dim: 768,
+            tables: SNAPSHOT_TABLES
+                .iter()
+                .map(|name| SnapshotTable {
+                    name: name.to_string(),
+                    file: format!("{name}.jsonl"),
+                    rows: 1,
+                    sha256: String::new(),
+                })
+                .collect(),
+        };
+        assert!(validate_manifest(&manifest, 768).is_ok());
+        assert!(matches!(
+            validate_manifest(&manifest, 1024),
+            Err(DbError::DimensionMismatch {
+                expected: 1024,
+                actual: 768
+            })
+        ));
+
+        let newer = SnapshotManifest {
+            schema_version: SCHEMA_VERSION + 1,
+            ..manifest.clone()
+        };
+        assert!(validate_manifest(&newer, 768).is_err());
+
+        let mut escaping = manifest.clone();
+        escaping.tables[0].file = "../papers.jsonl".to_string();
+        assert!(validate_manifest(&escaping, 768).is_err());
+
+        let mut missing = manifest;
+        missing.tables.retain(|t| t.name != TABLE_KG_FACTS);
+        assert!(validate_manifest(&missing, 768).is_err());
+    }
+}
@@ -5,6 +5,7 @@
 use crate::database::Database;
 use crate::error::{DbError, Result};
 use crate::schema::TargetScore;
+use ferrumyx_common::repro;
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -156,6 +157,15 @@ impl TargetScoreRepository {
                 let mut updated = score.clone();
                 updated.score_version = base + 1;
                 updated.is_current = true;
+                if repro::is_enabled() {
+                    updated.id = repro::new_id(
+                        "target_score",
+                        &format!(
+                            "{}|{}|{}",
+                            updated.gene_id, updated.cancer_id, updated.score_version
+                        ),
+                    );
+                }
                 next_versions.insert(key, updated.score_version);
                 versioned_rows.push(updated);
             }
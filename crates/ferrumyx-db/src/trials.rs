@@ -0,0 +1,237 @@
+//! Clinical trial repository.
+//!
+//! One row per ClinicalTrials.gov study, keyed by NCT id. Re-fetching a
+//! study replaces its row, so status and enrollment stay current.
+
+use crate::database::{trials_table_schema, Database};
+use crate::error::{DbError, Result};
+use crate::schema::{TrialRecord, TABLE_TRIALS};
+use crate::schema_evolution::conform_row;
+use std::sync::Arc;
+
+use arrow_array::{Array, Int64Array, RecordBatch, StringArray};
+use futures::StreamExt;
+use lancedb::query::{ExecutableQuery, QueryBase};
+
+/// Repository for clinical trials.
+#[derive(Clone)]
+pub struct TrialRepository {
+    db: Arc<Database>,
+}
+
+impl TrialRepository {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Insert or replace trials by NCT id.
+    pub async fn upsert_many(&self, trials: &[TrialRecord]) -> Result<()> {
+        if trials.is_empty() {
+            return Ok(());
+        }
+        let table = self.open().await?;
+        let batch = trials_to_batch(trials)?;
+        let schema = batch.schema();
+        let iter = arrow_array::RecordBatchIterator::new(vec![Ok(batch)], schema);
+        let mut builder = table.merge_insert(&["nct_id"]);
+        builder.when_matched_update_all(None);
+        builder.when_not_matched_insert_all();
+        builder.execute(Box::new(iter)).await?;
+        Ok(())
+    }
+
+    pub async fn find(&self, nct_id: &str) -> Result<Option<TrialRecord>> {
+        let table = self.open().await?;
+        let mut stream = table
+            .query()
+            .only_if(format!("nct_id = '{}'", nct_id.replace('\'', "''")))
+            .limit(1)
+            .execute()
+            .await?;
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            if batch.num_rows() > 0 {
+                return Ok(Some(batch_to_trial(&batch, 0)?));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Trials whose drugs are known to target `gene` (case-insensitive).
+    pub async fn find_by_gene(&self, gene: &str) -> Result<Vec<TrialRecord>> {
+        let mut rows = self.all().await?;
+        rows.retain(|t| t.target_genes.iter().any(|g| g.eq_ignore_ascii_case(gene)));
+        Ok(rows)
+    }
+
+    /// Every stored trial.
+    pub async fn list(&self) -> Result<Vec<TrialRecord>> {
+        self.all().await
+    }
+
+    pub async fn count(&self) -> Result<u64> {
+        Ok(self.open().await?.count_rows(None).await? as u64)
+    }
+
+    async fn open(&self) -> Result<lancedb::Table> {
+        Ok(self
+            .db
+            .connection()
+            .open_table(TABLE_TRIALS)
+            .execute()
+            .await?)
+    }
+
+    async fn all(&self) -> Result<Vec<TrialRecord>> {
+        let mut stream = self.open().await?.query().execute().await?;
+        let mut rows = Vec::new();
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            for row in 0..batch.num_rows() {
+                rows.push(batch_to_trial(&batch, row)?);
+            }
+        }
+        Ok(rows)
+    }
+}
+
+fn trials_to_batch(trials: &[TrialRecord]) -> Result<RecordBatch> {
+    let strings = |f: &dyn Fn(&TrialRecord) -> Option<String>| -> Arc<dyn Array> {
+        Arc::new(StringArray::from(trials.iter().map(f).collect::<Vec<_>>()))
+    };
+    let json = |f: &dyn Fn(&TrialRecord) -> serde_json::Result<String>| -> Result<Arc<dyn Array>> {
+        let values = trials
+            .iter()
+            .map(f)
+            .collect::<serde_json::Result<Vec<_>>>()?;
+        Ok(Arc::new(StringArray::from(values)))
+    };
+    let cols: Vec<Arc<dyn Array>> = vec![
+        strings(&|t| Some(t.nct_id.clone())),
+        strings(&|t| Some(t.title.clone())),
+        json(&|t| serde_json::to_string(&t.conditions))?,
+        json(&|t| serde_json::to_string(&t.interventions))?,
+        strings(&|t| Some(t.phase.clone())),
+        strings(&|t| Some(t.status.clone())),
+        strings(&|t| Some(t.study_type.clone())),
+        Arc::new(Int64Array::from(
+            trials.iter().map(|t| t.enrollment).collect::<Vec<_>>(),
+        )),
+        strings(&|t| t.start_date.map(|d| d.to_string())),
+        strings(&|t| t.completion_date.map(|d| d.to_string())),
+        json(&|t| serde_json::to_string(&t.sponsors))?,
+        json(&|t| serde_json::to_string(&t.drugs))?,
+        json(&|t| serde_json::to_string(&t.target_genes))?,
+        json(&|t| serde_json::to_string(&t.cancer_codes))?,
+        strings(&|t| Some(t.fetched_at.to_rfc3339())),
+    ];
+    Ok(RecordBatch::try_new(trials_table_schema(), cols)?)
+}
+
+fn batch_to_trial(batch: &RecordBatch, row: usize) -> Result<TrialRecord> {
+    let (batch, row) = conform_row(batch, row, &trials_table_schema())?;
+    let batch: &RecordBatch = &batch;
+    let get_opt = |col: &str| -> Result<Option<String>> {
+        let arr = batch
+            .column_by_name(col)
+            .and_then(|a| a.as_any().downcast_ref::<StringArray>())
+            .ok_or_else(|| DbError::Arrow(format!("{col} is not StringArray")))?;
+        Ok((!arr.is_null(row)).then(|| arr.value(row).to_string()))
+    };
+    let get_s = |col: &str| -> Result<String> { Ok(get_opt(col)?.unwrap_or_default()) };
+    let get_list = |col: &str| -> Vec<String> {
+        get_opt(col)
+            .ok()
+            .flatten()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    };
+    let get_date = |col: &str| -> Result<Option<chrono::NaiveDate>> {
+        Ok(get_opt(col)?.and_then(|s| s.parse().ok()))
+    };
+    let enrollment = batch
+        .column_by_name("enrollment")
+        .and_then(|a| a.as_any().downcast_ref::<Int64Array>())
+        .ok_or_else(|| DbError::Arrow("enrollment is not Int64Array".to_string()))?;
+    let enrollment = (!enrollment.is_null(row)).then(|| enrollment.value(row));
+    let fetched_at = chrono::DateTime::parse_from_rfc3339(&get_s("fetched_at")?)
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+        .map_err(|e| DbError::InvalidQuery(e.to_string()))?;
+
+    Ok(TrialRecord {
+        nct_id: get_s("nct_id")?,
+        title: get_s("title")?,
+        conditions: get_list("conditions"),
+        interventions: get_opt("interventions")?
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default(),
+        phase: get_s("phase")?,
+        status: get_s("status")?,
+        study_type: get_s("study_type")?,
+        enrollment,
+        start_date: get_date("start_date")?,
+        completion_date: get_date("completion_date")?,
+        sponsors: get_list("sponsors"),
+        drugs: get_list("drugs"),
+        target_genes: get_list("target_genes"),
+        cancer_codes: get_list("cancer_codes"),
+        fetched_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::TrialIntervention;
+    use chrono::TimeZone;
+
+    #[tokio::test]
+    async fn trials_round_trip_and_replace_by_nct_id() {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-trials-{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        let repo = TrialRepository::new(db);
+
+        let trial = TrialRecord {
+            nct_id: "NCT04303780".to_string(),
+            title: "Sotorasib versus docetaxel in KRAS G12C NSCLC".to_string(),
+            conditions: vec!["Non-Small Cell Lung Cancer".to_string()],
+            interventions: vec![TrialIntervention {
+                name: "Sotorasib".to_string(),
+                kind: "DRUG".to_string(),
+                other_names: vec!["AMG 510".to_string()],
+                arm_groups: vec!["Sotorasib".to_string()],
+            }],
+            phase: "PHASE3".to_string(),
+            status: "ACTIVE_NOT_RECRUITING".to_string(),
+            study_type: "INTERVENTIONAL".to_string(),
+            enrollment: Some(345),
+            start_date: chrono::NaiveDate::from_ymd_opt(2020, 6, 1),
+            completion_date: None,
+            sponsors: vec!["Amgen".to_string()],
+            drugs: vec!["sotorasib".to_string()],
+            target_genes: vec!["KRAS".to_string()],
+            cancer_codes: vec!["NSCLC".to_string()],
+            fetched_at: chrono::Utc.with_ymd_and_hms(2026, 5, 4, 8, 0, 0).unwrap(),
+        };
+        repo.upsert_many(std::slice::from_ref(&trial))
+            .await
+            .unwrap();
+        assert_eq!(repo.find("NCT04303780").await.unwrap(), Some(trial.clone()));
+
+        let updated = TrialRecord {
+            status: "COMPLETED".to_string(),
+            enrollment: None,
+            ..trial.clone()
+        };
+        repo.upsert_many(std::slice::from_ref(&updated))
+            .await
+            .unwrap();
+        assert_eq!(repo.count().await.unwrap(), 1);
+        assert_eq!(repo.find_by_gene("kras").await.unwrap(), vec![updated]);
+        assert!(repo.find_by_gene("EGFR").await.unwrap().is_empty());
+        assert_eq!(repo.find("NCT00000000").await.unwrap(), None);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,399 @@
+//! Memory budget and backpressure primitives for the ingestion pipeline.
+//!
+//! A large job (thousands of papers) must not let discovery race ahead of
+//! chunking/embedding. The pipeline enforces three limits:
+//!
+//!   1. Every stage holds at most `channel_capacity` papers: the channel
+//!      between prefetch and processing, the prefetch and paper worker pools,
+//!      and the queue of papers awaiting heavy enrichment.
+//!   2. A global in-flight paper window: a paper holds a [`PaperWindowPermit`]
+//!      from the moment its full text is fetched until it is fully processed
+//!      and persisted, so no more than `max_inflight_papers` full texts are
+//!      ever resident at once.
+//!   3. A byte budget for buffered chunk content. Papers whose chunks would
+//!      push the buffer past `max_chunk_buffer_bytes` *spill*: their chunks
+//!      are already persisted without embeddings, so the in-memory copy is
+//!      dropped and the DB-backed embedding pass reads them back in batches.
+//!
+//! The default budget is 512 MB of chunk content, 64 in-flight papers and
+//! 32-item stage channels. Each value can be overridden per job through
+//! [`IngestionJob::memory_budget`](crate::pipeline::IngestionJob) or globally
+//! through `FERRUMYX_INGESTION_MEMORY_BUDGET_MB`,
+//! `FERRUMYX_INGESTION_MAX_INFLIGHT_PAPERS` and
+//! `FERRUMYX_INGESTION_STAGE_CHANNEL_CAPACITY`.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Default chunk buffer budget (512 MB).
+pub const DEFAULT_MEMORY_BUDGET_BYTES: usize = 512 * 1024 * 1024;
+/// Default number of papers allowed between full-text fetch and persistence.
+pub const DEFAULT_MAX_INFLIGHT_PAPERS: usize = 64;
+/// Default capacity of each inter-stage channel.
+pub const DEFAULT_STAGE_CHANNEL_CAPACITY: usize = 32;
+
+/// Fixed per-chunk overhead (ids, section metadata, Vec headers) added to the
+/// content length when estimating the resident size of a buffered chunk.
+const CHUNK_OVERHEAD_BYTES: usize = 256;
+
+static GAUGE_BUFFERED_BYTES: AtomicUsize = AtomicUsize::new(0);
+static GAUGE_INFLIGHT_PAPERS: AtomicUsize = AtomicUsize::new(0);
+static GAUGE_SPILLED_PAPERS: AtomicUsize = AtomicUsize::new(0);
+static GAUGE_PEAK_BUFFERED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// Per-job memory budget for the ingestion pipeline.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PipelineMemoryBudget {
+    /// Maximum papers between full-text fetch and completed persistence.
+    #[serde(default = "default_max_inflight_papers")]
+    pub max_inflight_papers: usize,
+    /// Papers each pipeline stage (channel, worker pool, enrichment queue)
+    /// may hold at once.
+    #[serde(default = "default_stage_channel_capacity")]
+    pub channel_capacity: usize,
+    /// Byte budget for chunk content buffered in memory awaiting enrichment
+    /// or embedding. Exceeding it spills the paper to the DB-backed path.
+    #[serde(default = "default_memory_budget_bytes")]
+    pub max_chunk_buffer_bytes: usize,
+}
+
+fn default_max_inflight_papers() -> usize {
+    max_inflight_papers_from(&env_var)
+}
+
+fn default_stage_channel_capacity() -> usize {
+    stage_channel_capacity_from(&env_var)
+}
+
+fn default_memory_budget_bytes() -> usize {
+    memory_budget_bytes_from(&env_var)
+}
+
+fn env_var(key: &str) -> Option<String> {
+    std::env::var(key).ok()
+}
+
+fn parse_var(var: &dyn Fn(&str) -> Option<String>, key: &str) -> Option<usize> {
+    var(key).and_then(|v| v.trim().parse::<usize>().ok())
+}
+
+fn max_inflight_papers_from(var: &dyn Fn(&str) -> Option<String>) -> usize {
+    parse_var(var, "FERRUMYX_INGESTION_MAX_INFLIGHT_PAPERS")
+        .unwrap_or(DEFAULT_MAX_INFLIGHT_PAPERS)
+        .clamp(1, 4096)
+}
+
+fn stage_channel_capacity_from(var: &dyn Fn(&str) -> Option<String>) -> usize {
+    parse_var(var, "FERRUMYX_INGESTION_STAGE_CHANNEL_CAPACITY")
+        .unwrap_or(DEFAULT_STAGE_CHANNEL_CAPACITY)
+        .clamp(1, 4096)
+}
+
+fn memory_budget_bytes_from(var: &dyn Fn(&str) -> Option<String>) -> usize {
+    parse_var(var, "FERRUMYX_INGESTION_MEMORY_BUDGET_MB")
+        .map(|mb| mb.clamp(16, 64 * 1024) * 1024 * 1024)
+        .unwrap_or(DEFAULT_MEMORY_BUDGET_BYTES)
+}
+
+impl Default for PipelineMemoryBudget {
+    fn default() -> Self {
+        Self::from_vars(env_var)
+    }
+}
+
+impl PipelineMemoryBudget {
+    /// Budget from the `FERRUMYX_INGESTION_*` settings as `var` reports
+    /// them; unset or unparsable values take the defaults.
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        Self {
+            max_inflight_papers: max_inflight_papers_from(&var),
+            channel_capacity: stage_channel_capacity_from(&var),
+            max_chunk_buffer_bytes: memory_budget_bytes_from(&var),
+        }
+    }
+
+    /// Budget with a chunk buffer of `mb` megabytes and default limits otherwise.
+    pub fn with_budget_mb(mb: usize) -> Self {
+        Self {
+            max_chunk_buffer_bytes: mb.max(1) * 1024 * 1024,
+            ..Self::default()
+        }
+    }
+
+    /// Clamp every limit to a usable (non-zero) value.
+    pub fn sanitized(&self) -> Self {
+        Self {
+            max_inflight_papers: self.max_inflight_papers.max(1),
+            channel_capacity: self.channel_capacity.max(1),
+            max_chunk_buffer_bytes: self.max_chunk_buffer_bytes.max(1),
+        }
+    }
+}
+
+/// Estimated resident size of a set of chunk contents.
+pub fn estimate_chunk_bytes<'a, I>(contents: I) -> usize
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    contents
+        .into_iter()
+        .map(|c| c.len() + CHUNK_OVERHEAD_BYTES)
+        .sum()
+}
+
+// ── In-flight paper window ────────────────────────────────────────────────────
+
+/// Global window of papers allowed between full-text fetch and persistence.
+#[derive(Clone)]
+pub struct PaperWindow {
+    semaphore: Arc<Semaphore>,
+    capacity: usize,
+    inflight: Arc<AtomicUsize>,
+    peak: Arc<AtomicUsize>,
+}
+
+/// Held by a paper until it has been fully processed; releases its window slot on drop.
+pub struct PaperWindowPermit {
+    _permit: OwnedSemaphorePermit,
+    inflight: Arc<AtomicUsize>,
+}
+
+impl PaperWindow {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            semaphore: Arc::new(Semaphore::new(capacity)),
+            capacity,
+            inflight: Arc::new(AtomicUsize::new(0)),
+            peak: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Wait until a slot is free in the window.
+    pub async fn acquire(&self) -> PaperWindowPermit {
+        let permit = self
+            .semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("paper window semaphore closed");
+        let now = self.inflight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.peak.fetch_max(now, Ordering::SeqCst);
+        GAUGE_INFLIGHT_PAPERS.fetch_add(1, Ordering::Relaxed);
+        PaperWindowPermit {
+            _permit: permit,
+            inflight: self.inflight.clone(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn inflight(&self) -> usize {
+        self.inflight.load(Ordering::SeqCst)
+    }
+
+    /// Highest number of papers simultaneously in flight so far.
+    pub fn peak_inflight(&self) -> usize {
+        self.peak.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for PaperWindowPermit {
+    fn drop(&mut self) {
+        self.inflight.fetch_sub(1, Ordering::SeqCst);
+        GAUGE_INFLIGHT_PAPERS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+// ── Chunk buffer budget ───────────────────────────────────────────────────────
+
+/// Byte accounting for chunk content held in memory by the pipeline.
+#[derive(Clone)]
+pub struct ChunkBufferBudget {
+    limit: usize,
+    used: Arc<AtomicUsize>,
+    peak: Arc<AtomicUsize>,
+    spilled: Arc<AtomicUsize>,
+}
+
+/// Reservation of buffered chunk bytes; released on drop.
+pub struct ChunkBufferReservation {
+    bytes: usize,
+    used: Arc<AtomicUsize>,
+}
+
+impl ChunkBufferBudget {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit: limit.max(1),
+            used: Arc::new(AtomicUsize::new(0)),
+            peak: Arc::new(AtomicUsize::new(0)),
+            spilled: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Reserve `bytes` of buffer space, or return `None` when the reservation
+    /// would exceed the budget (the caller should spill to the DB path).
+    ///
+    /// A single paper larger than the whole budget always spills.
+    pub fn try_reserve(&self, bytes: usize) -> Option<ChunkBufferReservation> {
+        let mut current = self.used.load(Ordering::SeqCst);
+        loop {
+            let next = current.saturating_add(bytes);
+            if next > self.limit {
+                self.spilled.fetch_add(1, Ordering::SeqCst);
+                GAUGE_SPILLED_PAPERS.fetch_add(1, Ordering::Relaxed);
+                return None;
+            }
+            match self
+                .used
+                .compare_exchange(current, next, Ordering::SeqCst, Ordering::SeqCst)
+            {
+                Ok(_) => break,
+                Err(actual) => current = actual,
+            }
+        }
+        self.peak
+            .fetch_max(self.used.load(Ordering::SeqCst), Ordering::SeqCst);
+        let gauge_now = GAUGE_BUFFERED_BYTES.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        GAUGE_PEAK_BUFFERED_BYTES.fetch_max(gauge_now, Ordering::Relaxed);
+        Some(ChunkBufferReservation {
+            bytes,
+            used: self.used.clone(),
+        })
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    pub fn used(&self) -> usize {
+        self.used.load(Ordering::SeqCst)
+    }
+
+    pub fn peak(&self) -> usize {
+        self.peak.load(Ordering::SeqCst)
+    }
+
+    /// Number of reservations refused (papers routed to the spill path).
+    pub fn spilled(&self) -> usize {
+        self.spilled.load(Ordering::SeqCst)
+    }
+}
+
+impl ChunkBufferReservation {
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+}
+
+impl Drop for ChunkBufferReservation {
+    fn drop(&mut self) {
+        self.used.fetch_sub(self.bytes, Ordering::SeqCst);
+        GAUGE_BUFFERED_BYTES.fetch_sub(self.bytes, Ordering::Relaxed);
+    }
+}
+
+// ── Process-wide gauge ────────────────────────────────────────────────────────
+
+/// Snapshot of the process-wide `pipeline_memory` gauge.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct PipelineMemoryGauge {
+    pub buffered_chunk_bytes: usize,
+    pub peak_buffered_chunk_bytes: usize,
+    pub inflight_papers: usize,
+    pub spilled_papers: usize,
+}
+
+/// Current `pipeline_memory` gauge values across all running jobs.
+pub fn pipeline_memory_gauge() -> PipelineMemoryGauge {
+    PipelineMemoryGauge {
+        buffered_chunk_bytes: GAUGE_BUFFERED_BYTES.load(Ordering::Relaxed),
+        peak_buffered_chunk_bytes: GAUGE_PEAK_BUFFERED_BYTES.load(Ordering::Relaxed),
+        inflight_papers: GAUGE_INFLIGHT_PAPERS.load(Ordering::Relaxed),
+        spilled_papers: GAUGE_SPILLED_PAPERS.load(Ordering::Relaxed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_default_budget_is_512mb() {
+        let budget = PipelineMemoryBudget::from_vars(|_| None);
+        assert_eq!(budget.max_chunk_buffer_bytes, DEFAULT_MEMORY_BUDGET_BYTES);
+        assert_eq!(budget.max_inflight_papers, DEFAULT_MAX_INFLIGHT_PAPERS);
+        assert_eq!(budget.channel_capacity, DEFAULT_STAGE_CHANNEL_CAPACITY);
+        assert_eq!(
+            PipelineMemoryBudget::with_budget_mb(64).max_chunk_buffer_bytes,
+            64 * 1024 * 1024
+        );
+    }
+
+    #[test]
+    fn test_budget_reads_overrides() {
+        let budget = PipelineMemoryBudget::from_vars(|key| {
+            match key {
+                "FERRUMYX_INGESTION_MEMORY_BUDGET_MB" => Some("64"),
+                "FERRUMYX_INGESTION_MAX_INFLIGHT_PAPERS" => Some(" 8 "),
+                "FERRUMYX_INGESTION_STAGE_CHANNEL_CAPACITY" => Some("lots"),
+                _ => None,
+            }
+            .map(str::to_string)
+        });
+        assert_eq!(budget.max_chunk_buffer_bytes, 64 * 1024 * 1024);
+        assert_eq!(budget.max_inflight_papers, 8);
+        assert_eq!(budget.channel_capacity, DEFAULT_STAGE_CHANNEL_CAPACITY);
+    }
+
+    #[tokio::test]
+    async fn test_paper_window_never_exceeds_capacity() {
+        let window = PaperWindow::new(3);
+        let mut tasks = tokio::task::JoinSet::new();
+        for _ in 0..20 {
+            let window = window.clone();
+            tasks.spawn(async move {
+                let _permit = window.acquire().await;
+                assert!(window.inflight() <= window.capacity());
+                // Slow fake embedder: keep the paper in flight for a while.
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            });
+        }
+        while let Some(joined) = tasks.join_next().await {
+            joined.unwrap();
+        }
+        assert!(window.peak_inflight() <= 3);
+        assert_eq!(window.peak_inflight(), 3);
+        assert_eq!(window.inflight(), 0);
+    }
+
+    #[test]
+    fn test_chunk_budget_spills_past_limit() {
+        let budget = ChunkBufferBudget::new(1_000);
+        let a = budget.try_reserve(600).expect("first reservation fits");
+        assert!(budget.try_reserve(600).is_none(), "second must spill");
+        assert_eq!(budget.spilled(), 1);
+        drop(a);
+        assert_eq!(budget.used(), 0);
+        assert!(budget.try_reserve(600).is_some());
+        assert!(
+            budget.try_reserve(5_000).is_none(),
+            "oversized paper spills"
+        );
+        assert_eq!(budget.spilled(), 2);
+    }
+
+    #[test]
+    fn test_estimate_chunk_bytes_includes_overhead() {
+        assert_eq!(
+            estimate_chunk_bytes(["abc", "de"]),
+            5 + 2 * CHUNK_OVERHEAD_BYTES
+        );
+    }
+}
@@ -0,0 +1,98 @@
+//! Embedding throughput per device and dtype.
+//!
+//! ```text
+//! cargo run --release -p ferrumyx-ingestion --features metal --bin embed_benchmark -- \
+//!     --devices cpu,metal --dtypes f32,f16 --texts 256
+//! ```
+//!
+//! Prints tokens/sec for every combination, with the device and dtype the
+//! embedder actually ran on after any fallback.
+
+use std::time::Instant;
+
+use ferrumyx_ingestion::embed::{DeviceSpec, EmbeddingConfig, Precision};
+use ferrumyx_ingestion::BiomedBertEmbedder;
+
+const SAMPLE: &str = "KRAS G12D mutations drive pancreatic ductal adenocarcinoma through \
+    constitutive MAPK signalling, and covalent inhibitors such as MRTX1133 bind the \
+    switch-II pocket to suppress tumour growth in preclinical models.";
+
+fn arg(args: &[String], name: &str) -> Option<String> {
+    args.iter()
+        .position(|a| a == name)
+        .and_then(|i| args.get(i + 1))
+        .cloned()
+}
+
+fn list<T>(raw: &str, parse: impl Fn(&str) -> Option<T>, what: &str) -> anyhow::Result<Vec<T>> {
+    raw.split(',')
+        .map(|v| parse(v).ok_or_else(|| anyhow::anyhow!("unknown {what} {v:?}")))
+        .collect()
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let devices = list(
+        &arg(&args, "--devices").unwrap_or_else(|| "cpu,auto".to_string()),
+        DeviceSpec::parse,
+        "device",
+    )?;
+    let dtypes = list(
+        &arg(&args, "--dtypes").unwrap_or_else(|| "f32,f16".to_string()),
+        Precision::parse,
+        "dtype",
+    )?;
+    let n_texts: usize = arg(&args, "--texts")
+        .map(|v| v.parse())
+        .transpose()?
+        .unwrap_or(128);
+    let mut base = EmbeddingConfig {
+        cache_size: 0,
+        cache_dir: ferrumyx_ingestion::embedding::resolve_embed_cache_dir(),
+        ..EmbeddingConfig::default()
+    };
+    if let Some(model) = arg(&args, "--model") {
+        base = base.with_model(model);
+    }
+    let texts: Vec<String> = (0..n_texts).map(|i| format!("[{i}] {SAMPLE}")).collect();
+
+    println!(
+        "{:<10} {:<6} {:<12} {:<6} {:>12}",
+        "device", "dtype", "ran on", "as", "tokens/sec"
+    );
+    for &device in &devices {
+        for &dtype in &dtypes {
+            let config = EmbeddingConfig {
+                device,
+                dtype,
+                use_gpu: true,
+                ..base.clone()
+            };
+            let device_name = device.to_string();
+            let embedder = match BiomedBertEmbedder::new(config).await {
+                Ok(embedder) => embedder,
+                Err(e) => {
+                    println!(
+                        "{device_name:<10} {:<6} failed to load: {e}",
+                        dtype.as_str()
+                    );
+                    continue;
+                }
+            };
+            embedder.embed(&texts[..texts.len().min(8)]).await?;
+            let tokens = embedder.token_count(&texts)?;
+            let start = Instant::now();
+            embedder.embed(&texts).await?;
+            let secs = start.elapsed().as_secs_f64().max(1e-9);
+            let ran_on = format!("{:?}", embedder.device().location());
+            let ran_as = format!("{:?}", embedder.dtype());
+            println!(
+                "{device_name:<10} {:<6} {ran_on:<12} {ran_as:<6} {:>12.0}",
+                dtype.as_str(),
+                tokens as f64 / secs
+            );
+        }
+    }
+    Ok(())
+}
@@ -1,29 +1,99 @@
 //! Section-aware document chunker.
 //! See ARCHITECTURE.md §2.7
+//!
+//! Sections are split into sentences, and sentences are packed into chunks
+//! up to a token budget measured with the embedding tokenizer. Consecutive
+//! chunks share `overlap_sentences` sentences. A sentence is only cut, at
+//! word boundaries, when it alone exceeds the budget.
+
+use std::fmt;
+use std::ops::Range;
+use std::sync::Arc;
 
 use crate::models::{DocumentChunk, SectionType};
+use ferrumyx_common::repro;
 use uuid::Uuid;
 
-use uuid; // Ensure uuid is available for Uuid::new_v4() calls
+/// Separator between sections in the paper text chunk offsets refer to.
+pub const SECTION_SEPARATOR: &str = "\n\n";
+
+/// Abbreviations after which a period never ends a sentence.
+const NON_TERMINAL_ABBREVIATIONS: &[&str] = &[
+    "approx", "ca", "cf", "dr", "e.g", "i.e", "mr", "mrs", "ms", "prof", "resp", "sp", "spp", "st",
+    "viz", "vs",
+];
+
+/// Abbreviations of references to figures, tables and papers: the period
+/// ends a sentence only when followed by a word that is not a label like
+/// `2`, `S3` or `(2019)`.
+const REFERENCE_ABBREVIATIONS: &[&str] = &[
+    "al", "eq", "eqs", "ext", "fig", "figs", "no", "p", "pp", "ref", "refs", "suppl", "tab", "vol",
+];
+
+/// Counts the tokens of a text as the embedding model sees them.
+pub trait TokenCounter: Send + Sync {
+    fn count_tokens(&self, text: &str) -> usize;
+}
+
+/// Word-count approximation for when the embedding tokenizer is not
+/// available. See [`estimate_tokens`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WordEstimate;
+
+impl TokenCounter for WordEstimate {
+    fn count_tokens(&self, text: &str) -> usize {
+        estimate_tokens(text)
+    }
+}
+
+impl TokenCounter for tokenizers::Tokenizer {
+    /// Tokens without special tokens, which `max_tokens` already leaves
+    /// room for.
+    fn count_tokens(&self, text: &str) -> usize {
+        self.encode(text, false)
+            .map(|encoding| encoding.len())
+            .unwrap_or_else(|_| estimate_tokens(text))
+    }
+}
 
 /// Configuration for the chunker.
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct ChunkerConfig {
     /// Maximum tokens per chunk (BiomedBERT limit: 512 including special tokens).
     pub max_tokens: usize,
-    /// Token overlap between consecutive chunks.
-    pub overlap_tokens: usize,
+    /// Sentences repeated at the start of the next chunk.
+    pub overlap_sentences: usize,
+    /// Measures chunks against `max_tokens`.
+    pub tokenizer: Arc<dyn TokenCounter>,
 }
 
 impl Default for ChunkerConfig {
     fn default() -> Self {
         Self {
             max_tokens: 510, // 512 - 2 for [CLS] and [SEP]
-            overlap_tokens: 64,
+            overlap_sentences: 1,
+            tokenizer: Arc::new(WordEstimate),
         }
     }
 }
 
+impl fmt::Debug for ChunkerConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChunkerConfig")
+            .field("max_tokens", &self.max_tokens)
+            .field("overlap_sentences", &self.overlap_sentences)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ChunkerConfig {
+    /// Measure the budget with `tokenizer` instead of the word estimate.
+    pub fn with_tokenizer(mut self, tokenizer: Arc<dyn TokenCounter>) -> Self {
+        self.tokenizer = tokenizer;
+        self
+    }
+}
+
 /// A section of a parsed document.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct DocumentSection {
@@ -33,6 +103,16 @@ pub struct DocumentSection {
     pub page_number: Option<u32>,
 }
 
+/// The paper text chunk offsets index into: section texts in order, joined
+/// by [`SECTION_SEPARATOR`].
+pub fn document_text(sections: &[DocumentSection]) -> String {
+    sections
+        .iter()
+        .map(|s| s.text.as_str())
+        .collect::<Vec<_>>()
+        .join(SECTION_SEPARATOR)
+}
+
 /// Chunk a document into retrieval-optimised units.
 /// See ARCHITECTURE.md §2.7 for chunking rules.
 pub fn chunk_document(
@@ -42,80 +122,272 @@ pub fn chunk_document(
 ) -> Vec<DocumentChunk> {
     let mut chunks = Vec::new();
     let mut chunk_index = 0;
+    let mut section_start = 0;
 
     for section in sections {
-        let section_chunks = chunk_section(paper_id, &section, &mut chunk_index, config);
+        let section_chunks =
+            chunk_section(paper_id, &section, section_start, &mut chunk_index, config);
         chunks.extend(section_chunks);
+        section_start += section.text.chars().count() + SECTION_SEPARATOR.chars().count();
     }
 
     chunks
 }
 
+/// A sentence, or a piece of one over budget, with surrounding whitespace
+/// trimmed.
+struct Unit {
+    range: Range<usize>,
+    tokens: usize,
+}
+
 fn chunk_section(
     paper_id: Uuid,
     section: &DocumentSection,
+    section_start: usize,
     chunk_index: &mut usize,
     config: &ChunkerConfig,
 ) -> Vec<DocumentChunk> {
+    let text = section.text.as_str();
+    let max_tokens = config.max_tokens.max(1);
+    let tokenizer = config.tokenizer.as_ref();
     let mut chunks = Vec::new();
-
-    // Abstract and Figure Captions: always a single chunk
-    if matches!(
-        section.section_type,
-        SectionType::Abstract | SectionType::FigureCaption
-    ) {
+    let mut push = |range: Range<usize>| {
+        let content = text[range.clone()].to_string();
+        let char_start = section_start + text[..range.start].chars().count();
         chunks.push(DocumentChunk {
             paper_id,
-            chunk_id: Uuid::new_v4(),
+            chunk_id: repro::new_id("chunk", &format!("{paper_id}|{chunk_index}")),
             chunk_index: *chunk_index,
             section_type: section.section_type.clone(),
             section_heading: section.heading.clone(),
-            content: section.text.clone(),
+            token_count: tokenizer.count_tokens(&content),
+            char_start,
+            char_end: char_start + content.chars().count(),
+            content,
             page_number: section.page_number,
-            token_count: estimate_tokens(&section.text),
         });
         *chunk_index += 1;
+    };
+
+    let Some(whole) = trimmed(text, 0..text.len()) else {
         return chunks;
-    }
+    };
 
-    // All other sections: sliding window with overlap
-    let words: Vec<&str> = section.text.split_whitespace().collect();
-    if words.is_empty() {
+    // Abstract and Figure Captions: a single chunk when they fit
+    if matches!(
+        section.section_type,
+        SectionType::Abstract | SectionType::FigureCaption
+    ) && tokenizer.count_tokens(&text[whole.clone()]) <= max_tokens
+    {
+        push(whole);
         return chunks;
     }
 
-    // Approximate: 1 token ≈ 0.75 words (WordPiece tokenization)
-    let words_per_chunk = (config.max_tokens as f32 * 0.75) as usize;
-    let overlap_words = (config.overlap_tokens as f32 * 0.75) as usize;
+    let mut units = Vec::new();
+    for sentence in split_sentences(text) {
+        let Some(range) = trimmed(text, sentence) else {
+            continue;
+        };
+        let tokens = tokenizer.count_tokens(&text[range.clone()]);
+        if tokens <= max_tokens {
+            units.push(Unit { range, tokens });
+        } else {
+            units.extend(split_long_sentence(text, range, max_tokens, tokenizer));
+        }
+    }
 
     let mut start = 0;
-    while start < words.len() {
-        let end = (start + words_per_chunk).min(words.len());
-        let content = words[start..end].join(" ");
-        let token_count = estimate_tokens(&content);
-
-        chunks.push(DocumentChunk {
-            paper_id,
-            chunk_id: Uuid::new_v4(),
-            chunk_index: *chunk_index,
-            section_type: section.section_type.clone(),
-            section_heading: section.heading.clone(),
-            content,
-            page_number: section.page_number,
-            token_count,
-        });
-        *chunk_index += 1;
+    while start < units.len() {
+        let mut end = start;
+        let mut tokens = 0;
+        while end < units.len() && (end == start || tokens + units[end].tokens <= max_tokens) {
+            tokens += units[end].tokens;
+            end += 1;
+        }
+        push(units[start].range.start..units[end - 1].range.end);
 
-        if end == words.len() {
+        if end == units.len() {
             break;
         }
-        // Advance by chunk size minus overlap
-        start += words_per_chunk.saturating_sub(overlap_words);
+        // Repeat the last sentences, as many as fit alongside the next one.
+        let mut next = end.saturating_sub(config.overlap_sentences).max(start + 1);
+        while next < end && units[next..=end].iter().map(|u| u.tokens).sum::<usize>() > max_tokens {
+            next += 1;
+        }
+        start = next;
     }
 
     chunks
 }
 
+/// `range` of `text` without leading and trailing whitespace; None when
+/// only whitespace is left.
+fn trimmed(text: &str, range: Range<usize>) -> Option<Range<usize>> {
+    let slice = &text[range.clone()];
+    let start = range.start + (slice.len() - slice.trim_start().len());
+    let end = range.end - (slice.len() - slice.trim_end().len());
+    (start < end).then_some(start..end)
+}
+
+/// Cut an over-budget sentence into runs of whole words that fit. A single
+/// word over budget becomes a piece of its own.
+fn split_long_sentence(
+    text: &str,
+    range: Range<usize>,
+    max_tokens: usize,
+    tokenizer: &dyn TokenCounter,
+) -> Vec<Unit> {
+    let mut pieces: Vec<Unit> = Vec::new();
+    let mut current: Option<Unit> = None;
+    for word in word_ranges(text, range) {
+        let tokens = tokenizer.count_tokens(&text[word.clone()]);
+        match current.as_mut() {
+            Some(unit) if unit.tokens + tokens <= max_tokens => {
+                unit.range.end = word.end;
+                unit.tokens += tokens;
+            }
+            _ => {
+                pieces.extend(current.take());
+                current = Some(Unit {
+                    range: word,
+                    tokens,
+                });
+            }
+        }
+    }
+    pieces.extend(current);
+    pieces
+}
+
+fn word_ranges(text: &str, range: Range<usize>) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    let mut word_start = None;
+    for (i, c) in text[range.clone()].char_indices() {
+        let i = range.start + i;
+        match (c.is_whitespace(), word_start) {
+            (true, Some(start)) => {
+                words.push(start..i);
+                word_start = None;
+            }
+            (false, None) => word_start = Some(i),
+            _ => {}
+        }
+    }
+    if let Some(start) = word_start {
+        words.push(start..range.end);
+    }
+    words
+}
+
+/// Byte ranges of the sentences of `text`, in order. Each range runs up to
+/// the start of the next sentence, so together they cover `text`.
+///
+/// A sentence ends at `.`, `!` or `?` (and any closing quotes or brackets)
+/// followed by whitespace and a word that does not start in lowercase, and
+/// at blank lines. Periods inside numbers (`3.5`), after initials and after
+/// abbreviations such as `e.g.`, `Fig. 2` and `et al. (2019)` do not end a
+/// sentence.
+pub fn split_sentences(text: &str) -> Vec<Range<usize>> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        let (_, c) = chars[i];
+        let boundary = if matches!(c, '.' | '!' | '?') {
+            let mut j = i + 1;
+            while j < chars.len() && is_closer(chars[j].1) {
+                j += 1;
+            }
+            let ws = whitespace_run(&chars, j);
+            (ws > j && ends_sentence(text, &chars, i, ws)).then_some(ws)
+        } else if c.is_whitespace() {
+            let ws = whitespace_run(&chars, i);
+            let newlines = chars[i..ws].iter().filter(|(_, c)| *c == '\n').count();
+            (newlines >= 2 && ws < chars.len()).then_some(ws)
+        } else {
+            None
+        };
+
+        match boundary {
+            Some(next) if next < chars.len() => {
+                let end = chars[next].0;
+                if end > start {
+                    sentences.push(start..end);
+                }
+                start = end;
+                i = next;
+            }
+            Some(_) => break,
+            None => i += 1,
+        }
+    }
+    if start < text.len() {
+        sentences.push(start..text.len());
+    }
+    sentences
+}
+
+fn is_closer(c: char) -> bool {
+    matches!(
+        c,
+        ')' | ']' | '}' | '"' | '\'' | '\u{201d}' | '\u{2019}' | '.' | '!' | '?'
+    )
+}
+
+/// Index of the first non-whitespace char at or after `from`.
+fn whitespace_run(chars: &[(usize, char)], from: usize) -> usize {
+    let mut j = from;
+    while j < chars.len() && chars[j].1.is_whitespace() {
+        j += 1;
+    }
+    j
+}
+
+/// Whether the terminator at `chars[at]` ends a sentence, given the next
+/// word starts at `chars[next]`.
+fn ends_sentence(text: &str, chars: &[(usize, char)], at: usize, next: usize) -> bool {
+    let Some(&(next_byte, next_char)) = chars.get(next) else {
+        return true;
+    };
+    if next_char.is_lowercase() {
+        return false;
+    }
+    if chars[at].1 != '.' {
+        return true;
+    }
+
+    // The word the period belongs to, e.g. `al`, `e.g` or `J`.
+    let mut word_start = at;
+    while word_start > 0
+        && (chars[word_start - 1].1.is_alphabetic() || chars[word_start - 1].1 == '.')
+    {
+        word_start -= 1;
+    }
+    let word = text[chars[word_start].0..chars[at].0].to_lowercase();
+    let word = word.trim_start_matches('.');
+    if word.is_empty() {
+        return true;
+    }
+    if word.chars().count() == 1 && chars[word_start].1.is_uppercase() {
+        return false; // an initial, as in `J. Smith`
+    }
+    if NON_TERMINAL_ABBREVIATIONS.contains(&word) {
+        return false;
+    }
+    if REFERENCE_ABBREVIATIONS.contains(&word) {
+        let next_word = text[next_byte..]
+            .split_whitespace()
+            .next()
+            .unwrap_or_default();
+        let is_label = !next_word.starts_with(char::is_alphabetic)
+            || next_word.chars().any(|c| c.is_ascii_digit());
+        return !is_label;
+    }
+    true
+}
+
 /// Rough token estimation: words / 0.75 (WordPiece averages ~1.3 tokens/word).
 pub fn estimate_tokens(text: &str) -> usize {
     let words = text.split_whitespace().count();
@@ -125,6 +397,39 @@ pub fn estimate_tokens(text: &str) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    /// Stand-in for a subword tokenizer: one token per three chars of a word.
+    struct CharPieces;
+
+    impl TokenCounter for CharPieces {
+        fn count_tokens(&self, text: &str) -> usize {
+            text.split_whitespace()
+                .map(|w| w.chars().count().div_ceil(3))
+                .sum()
+        }
+    }
+
+    fn section(section_type: SectionType, text: &str) -> DocumentSection {
+        DocumentSection {
+            section_type,
+            heading: None,
+            text: text.to_string(),
+            page_number: None,
+        }
+    }
+
+    fn sentences(text: &str) -> Vec<&str> {
+        split_sentences(text)
+            .into_iter()
+            .map(|r| text[r].trim())
+            .collect()
+    }
+
+    fn chars_of(text: &str, range: Range<usize>) -> String {
+        text.chars().skip(range.start).take(range.len()).collect()
+    }
 
     #[test]
     fn test_abstract_is_single_chunk() {
@@ -153,7 +458,8 @@ mod tests {
         }];
         let config = ChunkerConfig {
             max_tokens: 100,
-            overlap_tokens: 10,
+            overlap_sentences: 1,
+            ..ChunkerConfig::default()
         };
         let chunks = chunk_document(paper_id, sections, &config);
         assert!(
@@ -161,4 +467,251 @@ mod tests {
             "Long section should produce multiple chunks"
         );
     }
+
+    #[test]
+    fn splits_sentences_around_abbreviations_and_numbers() {
+        let text = "KRAS G12D drives PDAC (Fig. 2A). As shown by Hingorani et al. (2003), \
+                    mice develop PanIN. Tumours grew 3.5-fold, e.g. in Figs. S2 and 3? \
+                    Yes! Smith et al. Then J. Doe replicated it.\n\nMethods follow";
+        assert_eq!(
+            sentences(text),
+            vec![
+                "KRAS G12D drives PDAC (Fig. 2A).",
+                "As shown by Hingorani et al. (2003), mice develop PanIN.",
+                "Tumours grew 3.5-fold, e.g. in Figs. S2 and 3?",
+                "Yes!",
+                "Smith et al.",
+                "Then J. Doe replicated it.",
+                "Methods follow",
+            ]
+        );
+    }
+
+    #[test]
+    fn sentences_are_never_split_and_overlap_by_whole_sentences() {
+        let text = "KRAS is mutated in most PDAC. It sits upstream of MAPK. \
+                    MEK inhibitors were tested. Responses were short.";
+        let config = ChunkerConfig {
+            max_tokens: 16,
+            overlap_sentences: 1,
+            ..ChunkerConfig::default()
+        };
+        let chunks = chunk_document(
+            Uuid::nil(),
+            vec![section(SectionType::Results, text)],
+            &config,
+        );
+        let contents: Vec<&str> = chunks.iter().map(|c| c.content.as_str()).collect();
+        assert_eq!(
+            contents,
+            vec![
+                "KRAS is mutated in most PDAC. It sits upstream of MAPK.",
+                "It sits upstream of MAPK. MEK inhibitors were tested.",
+                "MEK inhibitors were tested. Responses were short.",
+            ]
+        );
+    }
+
+    #[test]
+    fn chunks_carry_offsets_into_the_paper_text() {
+        let sections = vec![
+            section(SectionType::Abstract, "Short abstract."),
+            section(SectionType::Results, "  Ünïcode KRAS result. Second one.  "),
+        ];
+        let doc = document_text(&sections);
+        let chunks = chunk_document(Uuid::nil(), sections, &ChunkerConfig::default());
+        assert_eq!(chunks.len(), 2);
+        for chunk in &chunks {
+            assert_eq!(
+                chars_of(&doc, chunk.char_start..chunk.char_end),
+                chunk.content
+            );
+        }
+        assert_eq!(
+            chunks[1].char_start,
+            "Short abstract.\n\n  ".chars().count()
+        );
+    }
+
+    #[test]
+    fn over_budget_abstract_and_sentences_are_split() {
+        let text = format!("{} end.", "KRAS ".repeat(30));
+        let config = ChunkerConfig {
+            max_tokens: 10,
+            overlap_sentences: 2,
+            ..ChunkerConfig::default()
+        };
+        let chunks = chunk_document(
+            Uuid::nil(),
+            vec![section(SectionType::Abstract, &text)],
+            &config,
+        );
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.token_count <= 10));
+    }
+
+    #[test]
+    fn tokenizer_measures_the_budget() {
+        use tokenizers::models::wordpiece::WordPiece;
+        use tokenizers::pre_tokenizers::bert::BertPreTokenizer;
+
+        let vocab = [
+            ("[UNK]", 0),
+            ("kras", 1),
+            ("g12", 2),
+            ("##d", 3),
+            ("drives", 4),
+            ("pdac", 5),
+            (".", 6),
+        ]
+        .map(|(token, id)| (token.to_string(), id));
+        let wordpiece = WordPiece::builder()
+            .vocab(vocab)
+            .unk_token("[UNK]".to_string())
+            .build()
+            .unwrap();
+        let mut tokenizer = tokenizers::Tokenizer::new(wordpiece);
+        tokenizer.with_pre_tokenizer(Some(BertPreTokenizer));
+
+        assert_eq!(tokenizer.count_tokens("kras g12d drives pdac."), 6);
+        let config = ChunkerConfig {
+            max_tokens: 6,
+            overlap_sentences: 0,
+            ..ChunkerConfig::default()
+        }
+        .with_tokenizer(Arc::new(tokenizer));
+        let text = "kras g12d drives pdac. kras drives pdac.";
+        let chunks = chunk_document(
+            Uuid::nil(),
+            vec![section(SectionType::Results, text)],
+            &config,
+        );
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].token_count, 6);
+    }
+
+    /// Random prose made of biomedical words, abbreviations, numbers and
+    /// the occasional run-on sentence.
+    fn random_text(rng: &mut StdRng) -> String {
+        const WORDS: &[&str] = &[
+            "kras",
+            "G12D",
+            "mutant",
+            "cells",
+            "showed",
+            "increased",
+            "MAPK",
+            "signalling",
+            "(Fig.",
+            "2B)",
+            "et",
+            "al.",
+            "3.5-fold",
+            "e.g.",
+            "p",
+            "<",
+            "0.05",
+            "TP53",
+            "pancreatic",
+            "adenocarcinoma",
+            "inhibitor",
+            "Ünïcode",
+            "resistance",
+            "[12]",
+        ];
+        let mut text = String::new();
+        for _ in 0..rng.gen_range(0..40) {
+            let words = if rng.gen_bool(0.05) {
+                rng.gen_range(80..200)
+            } else {
+                rng.gen_range(1..25)
+            };
+            let mut sentence = (0..words)
+                .map(|_| WORDS[rng.gen_range(0..WORDS.len())])
+                .collect::<Vec<_>>()
+                .join(if rng.gen_bool(0.1) { "  " } else { " " });
+            if let Some(first) = sentence.get(..1) {
+                sentence = first.to_uppercase() + &sentence[1..];
+            }
+            text.push_str(&sentence);
+            text.push_str([". ", "? ", "! ", ".\n\n", " ", ".) "][rng.gen_range(0..6)]);
+        }
+        text
+    }
+
+    fn normalise_ws(text: &str) -> String {
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    #[test]
+    fn property_chunks_fit_the_budget_and_reconstruct_the_source() {
+        for seed in 0..300u64 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let sections: Vec<DocumentSection> = (0..rng.gen_range(1..4))
+                .map(|i| {
+                    let kind = if i == 0 {
+                        SectionType::Abstract
+                    } else {
+                        SectionType::Results
+                    };
+                    section(kind, &random_text(&mut rng))
+                })
+                .collect();
+            let tokenizer: Arc<dyn TokenCounter> = if seed % 2 == 0 {
+                Arc::new(WordEstimate)
+            } else {
+                Arc::new(CharPieces)
+            };
+            let config = ChunkerConfig {
+                max_tokens: rng.gen_range(12..160),
+                overlap_sentences: rng.gen_range(0..4),
+                tokenizer: tokenizer.clone(),
+            };
+            let doc = document_text(&sections);
+            let chunks = chunk_document(Uuid::nil(), sections.clone(), &config);
+
+            for chunk in &chunks {
+                assert!(
+                    chunk.token_count <= config.max_tokens,
+                    "seed {seed}: {} tokens over {}",
+                    chunk.token_count,
+                    config.max_tokens
+                );
+                assert_eq!(chunk.token_count, tokenizer.count_tokens(&chunk.content));
+                assert_eq!(
+                    chars_of(&doc, chunk.char_start..chunk.char_end),
+                    chunk.content,
+                    "seed {seed}"
+                );
+            }
+
+            // Each chunk minus the sentences it repeats from the previous
+            // one, concatenated, is the source.
+            for (i, s) in sections.iter().enumerate() {
+                let section_chunks: Vec<&DocumentChunk> = chunks
+                    .iter()
+                    .filter(|c| {
+                        let start = sections[..i]
+                            .iter()
+                            .map(|s| s.text.chars().count() + 2)
+                            .sum::<usize>();
+                        c.char_start >= start && c.char_end <= start + s.text.chars().count()
+                    })
+                    .collect();
+                let mut rebuilt = Vec::new();
+                let mut cursor = 0;
+                for chunk in section_chunks {
+                    assert!(chunk.char_end > cursor, "seed {seed}: chunk adds nothing");
+                    let from = chunk.char_start.max(cursor);
+                    rebuilt.push(chars_of(&doc, from..chunk.char_end));
+                    cursor = chunk.char_end;
+                }
+                assert_eq!(
+                    normalise_ws(&rebuilt.join(" ")),
+                    normalise_ws(&s.text),
+                    "seed {seed}"
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,175 @@
+//! Citation graph harvesting.
+//!
+//! Reads the reference list of each stored paper from two places: the
+//! `<ref-list>` of its Europe PMC full-text XML, which also gives the
+//! sentence citing each reference, and the reference metadata CrossRef
+//! holds for its DOI. References are stored by DOI in `paper_citations`
+//! and linked to the cited paper row when that paper is stored too.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use ferrumyx_db::schema::{normalise_doi, Paper, PaperCitation};
+use ferrumyx_db::{papers::PaperRepository, Database, PaperCitationRepository};
+use serde::Serialize;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::jats;
+use crate::sources::crossref::CrossRefClient;
+use crate::sources::europepmc::EuropePmcClient;
+use crate::sources::LiteratureSource;
+
+const SOURCE_EUROPEPMC: &str = "europepmc";
+const SOURCE_CROSSREF: &str = "crossref";
+const DOI_LOOKUP_CHUNK: usize = 200;
+
+/// Counts from one harvesting pass.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CitationReport {
+    pub scanned: usize,
+    pub citations_stored: usize,
+    /// Stored citations whose cited DOI belongs to a stored paper.
+    pub internal_links: usize,
+    pub errors: Vec<String>,
+}
+
+/// Harvest the references of the papers with these ids.
+pub async fn harvest_citations(
+    db: Arc<Database>,
+    crossref: &CrossRefClient,
+    europepmc: &EuropePmcClient,
+    ids: &[Uuid],
+) -> anyhow::Result<CitationReport> {
+    let papers = PaperRepository::new(db.clone());
+    let citations = PaperCitationRepository::new(db);
+    let mut report = CitationReport::default();
+
+    for id in ids {
+        let Some(paper) = papers.find_by_id(*id).await? else {
+            continue;
+        };
+        report.scanned += 1;
+
+        let mut found = Vec::new();
+        if let Some(pmcid) = paper.source_id.as_deref() {
+            match europepmc.fetch_full_text(pmcid).await {
+                Ok(Some(xml)) => {
+                    for c in jats::parse_jats(&xml)
+                        .map(|p| p.citations)
+                        .unwrap_or_default()
+                    {
+                        let mut row = PaperCitation::new(paper.id, &c.doi, SOURCE_EUROPEPMC);
+                        row.context = c.context;
+                        found.push(row);
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => report.errors.push(format!("{}: {e}", paper.id)),
+            }
+        }
+        if let Some(doi) = paper.doi.as_deref() {
+            match crossref.get_references(doi).await {
+                Ok(dois) => found.extend(
+                    dois.iter()
+                        .map(|d| PaperCitation::new(paper.id, d, SOURCE_CROSSREF)),
+                ),
+                Err(e) => report.errors.push(format!("{}: {e}", paper.id)),
+            }
+        }
+
+        let mut rows = merge_citations(&paper, found);
+        if let Err(e) = link_stored(&papers, &mut rows).await {
+            report.errors.push(format!("{}: {e}", paper.id));
+        }
+        match citations.upsert_batch(&rows).await {
+            Ok(()) => {
+                report.citations_stored += rows.len();
+                report.internal_links += rows.iter().filter(|r| r.cited_paper_id.is_some()).count();
+            }
+            Err(e) => report.errors.push(format!("{}: {e}", paper.id)),
+        }
+
+        // Papers harvested earlier may cite this one.
+        if let Some(doi) = paper.doi.as_deref() {
+            if let Err(e) = citations.link_cited_paper(doi, paper.id).await {
+                report.errors.push(format!("{}: {e}", paper.id));
+            }
+        }
+        debug!(paper_id = %paper.id, citations = rows.len(), "Harvested citations");
+    }
+
+    if !report.errors.is_empty() {
+        warn!(
+            errors = report.errors.len(),
+            "Citation harvesting skipped some papers"
+        );
+    }
+    Ok(report)
+}
+
+/// One row per cited DOI, preferring the entry that carries a citing
+/// sentence. Self-citations by DOI are dropped.
+fn merge_citations(paper: &Paper, found: Vec<PaperCitation>) -> Vec<PaperCitation> {
+    let own_doi = paper.doi.as_deref().map(normalise_doi);
+    let mut by_doi: HashMap<String, PaperCitation> = HashMap::new();
+    for row in found {
+        if row.cited_doi.is_empty() || own_doi.as_deref() == Some(row.cited_doi.as_str()) {
+            continue;
+        }
+        match by_doi.get(&row.cited_doi) {
+            Some(kept) if kept.context.is_some() || row.context.is_none() => {}
+            _ => {
+                by_doi.insert(row.cited_doi.clone(), row);
+            }
+        }
+    }
+    let mut rows = by_doi.into_values().collect::<Vec<_>>();
+    rows.sort_by(|a, b| a.cited_doi.cmp(&b.cited_doi));
+    rows
+}
+
+/// Set `cited_paper_id` on rows whose DOI belongs to a stored paper.
+/// Stored DOIs keep the source's casing, so both cases are looked up.
+async fn link_stored(papers: &PaperRepository, rows: &mut [PaperCitation]) -> anyhow::Result<()> {
+    let candidates = rows
+        .iter()
+        .flat_map(|r| [r.cited_doi.clone(), r.cited_doi.to_uppercase()])
+        .collect::<Vec<_>>();
+    let stored = papers
+        .find_ids_by_dois(&candidates, DOI_LOOKUP_CHUNK)
+        .await?
+        .into_iter()
+        .map(|(doi, id)| (normalise_doi(&doi), id))
+        .collect::<HashMap<_, _>>();
+    for row in rows {
+        row.cited_paper_id = stored.get(&row.cited_doi).copied();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_prefers_entries_with_context_and_drops_self_citations() {
+        let mut paper = Paper::new("KRAS G12D".to_string(), "europepmc".to_string());
+        paper.doi = Some("10.1000/Self".to_string());
+        let mut with_context = PaperCitation::new(paper.id, "10.1000/a", SOURCE_EUROPEPMC);
+        with_context.context = Some("KRAS drives PDAC [1].".to_string());
+        let found = vec![
+            PaperCitation::new(paper.id, "10.1000/A", SOURCE_CROSSREF),
+            with_context.clone(),
+            PaperCitation::new(paper.id, "doi:10.1000/a", SOURCE_CROSSREF),
+            PaperCitation::new(paper.id, "10.1000/self", SOURCE_CROSSREF),
+            PaperCitation::new(paper.id, "10.1000/b", SOURCE_CROSSREF),
+        ];
+
+        let rows = merge_citations(&paper, found);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0], with_context);
+        assert_eq!(rows[1].cited_doi, "10.1000/b");
+        assert_eq!(rows[1].source, SOURCE_CROSSREF);
+    }
+}
@@ -1,6 +1,10 @@
 //! Deduplication logic for ingested papers.
 //! See ARCHITECTURE.md §2.10
 
+use std::collections::{HashMap, HashSet};
+
+use uuid::Uuid;
+
 use crate::models::PaperMetadata;
 
 /// Result of a deduplication check.
@@ -145,6 +149,157 @@ pub fn hamming_distance(a: i64, b: i64) -> u32 {
     ((a as u64) ^ (b as u64)).count_ones()
 }
 
+/// Number of MinHash permutations per signature.
+pub const MINHASH_PERMUTATIONS: usize = 128;
+
+/// LSH bands; two signatures become candidates when any band matches.
+/// With 32 bands of 4 rows a pair at Jaccard 0.5 is found ~87% of the time,
+/// at 0.7 almost always, and at 0.2 ~5% of the time.
+const LSH_BANDS: usize = 32;
+const LSH_ROWS: usize = MINHASH_PERMUTATIONS / LSH_BANDS;
+
+/// Words per shingle.
+const SHINGLE_WORDS: usize = 3;
+
+/// Exact shingle Jaccard at or above which a candidate is a near-duplicate.
+pub const NEAR_DUPLICATE_JACCARD: f64 = 0.6;
+
+/// Hashed word 3-grams of lowercased alphanumeric tokens. Texts shorter
+/// than a shingle hash as a single shingle.
+fn shingles(text: &str) -> HashSet<u64> {
+    let normalised = text.to_lowercase();
+    let words: Vec<&str> = normalised
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    if words.is_empty() {
+        return HashSet::new();
+    }
+    words
+        .windows(SHINGLE_WORDS.min(words.len()))
+        .map(|window| fnv64(window.join(" ").as_bytes()))
+        .collect()
+}
+
+/// SplitMix64 finaliser, used to derive one hash function per permutation.
+fn mix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+/// MinHash signature of a text's shingle set, or None for empty text.
+pub fn minhash_signature(text: &str) -> Option<Vec<u32>> {
+    let shingles = shingles(text);
+    if shingles.is_empty() {
+        return None;
+    }
+    let seeds: Vec<u64> = (0..MINHASH_PERMUTATIONS as u64).map(mix64).collect();
+    let signature = seeds
+        .iter()
+        .map(|seed| {
+            shingles
+                .iter()
+                .map(|s| mix64(s ^ seed) as u32)
+                .min()
+                .unwrap_or(u32::MAX)
+        })
+        .collect();
+    Some(signature)
+}
+
+/// Jaccard similarity estimated from two signatures.
+pub fn estimated_jaccard(a: &[u32], b: &[u32]) -> f64 {
+    if a.is_empty() || a.len() != b.len() {
+        return 0.0;
+    }
+    let equal = a.iter().zip(b).filter(|(x, y)| x == y).count();
+    equal as f64 / a.len() as f64
+}
+
+/// Exact Jaccard similarity of two texts' shingle sets, used to verify LSH
+/// candidates.
+pub fn jaccard(a: &str, b: &str) -> f64 {
+    let (a, b) = (shingles(a), shingles(b));
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f64 / union as f64
+}
+
+/// LSH banding index over MinHash signatures.
+///
+/// Lookup hashes the query's bands into per-band buckets, so its cost
+/// depends on bucket sizes rather than on the number of indexed papers.
+#[derive(Debug)]
+pub struct MinHashIndex {
+    signatures: HashMap<Uuid, Vec<u32>>,
+    bands: Vec<HashMap<u64, Vec<Uuid>>>,
+}
+
+impl MinHashIndex {
+    pub fn new() -> Self {
+        Self {
+            signatures: HashMap::new(),
+            bands: vec![HashMap::new(); LSH_BANDS],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.signatures.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.signatures.is_empty()
+    }
+
+    /// Index `signature` under `id`. Signatures of the wrong length and ids
+    /// already indexed are ignored.
+    pub fn insert(&mut self, id: Uuid, signature: Vec<u32>) {
+        if signature.len() != MINHASH_PERMUTATIONS || self.signatures.contains_key(&id) {
+            return;
+        }
+        for (band, key) in band_keys(&signature).enumerate() {
+            self.bands[band].entry(key).or_default().push(id);
+        }
+        self.signatures.insert(id, signature);
+    }
+
+    /// Indexed papers sharing at least one band with `signature`, with their
+    /// estimated Jaccard similarity, most similar first.
+    pub fn candidates(&self, signature: &[u32]) -> Vec<(Uuid, f64)> {
+        if signature.len() != MINHASH_PERMUTATIONS {
+            return Vec::new();
+        }
+        let mut seen = HashSet::new();
+        let mut out = Vec::new();
+        for (band, key) in band_keys(signature).enumerate() {
+            for id in self.bands[band].get(&key).into_iter().flatten() {
+                if seen.insert(*id) {
+                    out.push((*id, estimated_jaccard(signature, &self.signatures[id])));
+                }
+            }
+        }
+        out.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        out
+    }
+}
+
+impl Default for MinHashIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn band_keys(signature: &[u32]) -> impl Iterator<Item = u64> + '_ {
+    signature.chunks_exact(LSH_ROWS).map(|rows| {
+        let bytes: Vec<u8> = rows.iter().flat_map(|v| v.to_le_bytes()).collect();
+        fnv64(&bytes)
+    })
+}
+
 /// Stop words to exclude from SimHash computation.
 const STOP_WORDS: &[&str] = &[
     "the", "a", "an", "and", "or", "in", "of", "to", "is", "was", "for", "on", "with", "this",
@@ -154,6 +309,9 @@ const STOP_WORDS: &[&str] = &[
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
 
     #[test]
     fn test_identical_texts_same_simhash() {
@@ -196,6 +354,8 @@ mod tests {
             source: crate::models::IngestionSource::PubMed,
             open_access: false,
             full_text_url: None,
+            is_review: false,
+            preprint: None,
         };
         let p2 = PaperMetadata {
             doi: None,
@@ -213,6 +373,8 @@ mod tests {
             source: crate::models::IngestionSource::PubMed,
             open_access: false,
             full_text_url: None,
+            is_review: false,
+            preprint: None,
         };
 
         let res = check_fuzzy_duplicate(&p1, vec![&p2]);
@@ -252,4 +414,107 @@ mod tests {
         assert_eq!(clamp_u64_to_i64(i64::MAX as u64 + 1), i64::MAX);
         assert_eq!(clamp_u64_to_i64(u64::MAX), i64::MAX);
     }
+
+    #[test]
+    fn minhash_estimate_tracks_exact_jaccard() {
+        let t1 = "KRAS G12D mutation drives pancreatic ductal adenocarcinoma through MAPK signalling in mouse models";
+        let t2 = "KRAS G12D mutation drives pancreatic ductal adenocarcinoma through MAPK signalling in human organoids";
+        let (a, b) = (
+            minhash_signature(t1).unwrap(),
+            minhash_signature(t2).unwrap(),
+        );
+        assert_eq!(a.len(), MINHASH_PERMUTATIONS);
+        assert_eq!(a, minhash_signature(&t1.to_uppercase()).unwrap());
+        assert!((estimated_jaccard(&a, &b) - jaccard(t1, t2)).abs() < 0.15);
+        assert_eq!(minhash_signature("  "), None);
+        assert_eq!(jaccard("", ""), 0.0);
+    }
+
+    fn synthetic_abstract(rng: &mut StdRng, vocab: &[String]) -> Vec<String> {
+        let len = rng.gen_range(90..180);
+        (0..len)
+            .map(|_| vocab[rng.gen_range(0..vocab.len())].clone())
+            .collect()
+    }
+
+    /// Copy of `words` with ~3% of words replaced and the odd word inserted
+    /// or dropped, like a revised preprint abstract.
+    fn near_duplicate(rng: &mut StdRng, vocab: &[String], words: &[String]) -> Vec<String> {
+        let mut out = Vec::with_capacity(words.len() + 4);
+        for word in words {
+            match rng.gen_range(0..200) {
+                0..=5 => out.push(vocab[rng.gen_range(0..vocab.len())].clone()),
+                6 => {}
+                7 => {
+                    out.push(word.clone());
+                    out.push(vocab[rng.gen_range(0..vocab.len())].clone());
+                }
+                _ => out.push(word.clone()),
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn lsh_candidates_find_known_near_duplicates() {
+        let mut rng = StdRng::seed_from_u64(296);
+        let vocab: Vec<String> = (0..3_000)
+            .map(|i| format!("{}{i}", ["kras", "tp53", "egfr", "pdac", "mapk"][i % 5]))
+            .collect();
+
+        let bases: Vec<Vec<String>> = (0..150)
+            .map(|_| synthetic_abstract(&mut rng, &vocab))
+            .collect();
+        let mut docs: Vec<(Uuid, String)> = bases
+            .iter()
+            .map(|words| (Uuid::new_v4(), words.join(" ")))
+            .collect();
+        let mut truth = HashSet::new();
+        for base in 0..50 {
+            let words = near_duplicate(&mut rng, &vocab, &bases[base * 3]);
+            let id = Uuid::new_v4();
+            truth.insert(pair(id, docs[base * 3].0));
+            docs.push((id, words.join(" ")));
+        }
+        docs.shuffle(&mut rng);
+        assert_eq!(docs.len(), 200);
+
+        let texts: HashMap<Uuid, &str> = docs.iter().map(|(id, t)| (*id, t.as_str())).collect();
+        let mut index = MinHashIndex::new();
+        let mut candidates = HashSet::new();
+        let mut lookup = std::time::Duration::ZERO;
+        for (id, text) in &docs {
+            let signature = minhash_signature(text).unwrap();
+            let started = std::time::Instant::now();
+            let found = index.candidates(&signature);
+            lookup += started.elapsed();
+            candidates.extend(found.into_iter().map(|(other, _)| pair(*id, other)));
+            index.insert(*id, signature);
+        }
+        assert_eq!(index.len(), 200);
+
+        let hits = candidates.intersection(&truth).count();
+        let recall = hits as f64 / truth.len() as f64;
+        let precision = hits as f64 / candidates.len().max(1) as f64;
+        assert!(recall >= 0.95, "recall {recall}");
+        assert!(precision >= 0.9, "precision {precision}");
+        let mean_lookup = lookup / docs.len() as u32;
+        assert!(
+            mean_lookup < std::time::Duration::from_millis(1),
+            "mean lookup {mean_lookup:?}"
+        );
+
+        // Exact verification keeps the true pairs and rejects the rest.
+        let verified: HashSet<_> = candidates
+            .iter()
+            .filter(|(a, b)| jaccard(texts[a], texts[b]) >= NEAR_DUPLICATE_JACCARD)
+            .copied()
+            .collect();
+        assert!(verified.is_subset(&truth));
+        assert!(verified.len() as f64 >= truth.len() as f64 * 0.95);
+    }
+
+    fn pair(a: Uuid, b: Uuid) -> (Uuid, Uuid) {
+        (a.min(b), a.max(b))
+    }
 }
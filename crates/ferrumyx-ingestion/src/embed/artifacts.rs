@@ -0,0 +1,178 @@
+//! Model files in the Hugging Face cache.
+//!
+//! Every Candle model loads its files through a [`ModelStore`], which serves
+//! them from the cache and downloads what is missing. An offline store
+//! never touches the network, so an air-gapped machine fails on startup with
+//! the list of files to prefetch instead of deep inside a pipeline.
+
+use std::path::PathBuf;
+
+use hf_hub::api::sync::ApiBuilder;
+use hf_hub::Cache;
+
+use crate::embed::{EmbedError, Result};
+
+/// Files a BERT encoder loads. Each entry lists interchangeable files, the
+/// preferred one first.
+pub const BERT_FILES: &[&[&str]] = &[
+    &["config.json"],
+    &["tokenizer.json", "vocab.txt"],
+    &["model.safetensors", "pytorch_model.bin"],
+];
+
+/// Files a cross-encoder loads; it needs a full tokenizer.
+pub const CROSS_ENCODER_FILES: &[&[&str]] = &[
+    &["config.json"],
+    &["tokenizer.json"],
+    &["model.safetensors", "pytorch_model.bin"],
+];
+
+/// Resolves model files from a Hugging Face cache directory.
+pub struct ModelStore {
+    cache: Cache,
+    offline: bool,
+}
+
+impl ModelStore {
+    /// A store over `cache_dir`, or the default Hugging Face cache when
+    /// unset. An `offline` store only serves files already cached.
+    pub fn new(cache_dir: Option<&str>, offline: bool) -> Self {
+        let cache = match cache_dir.map(str::trim).filter(|d| !d.is_empty()) {
+            Some(dir) => Cache::new(PathBuf::from(dir)),
+            None => Cache::default(),
+        };
+        Self { cache, offline }
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// One local path per entry of `files`, downloading missing files
+    /// unless offline. Blocking; call from `spawn_blocking` in async code.
+    pub fn resolve(&self, model_id: &str, files: &[&[&str]]) -> Result<Vec<PathBuf>> {
+        let cached = self.cache.model(model_id.to_string());
+        let lookup = |alternatives: &[&str]| alternatives.iter().find_map(|f| cached.get(f));
+
+        if self.offline {
+            let missing = self.missing(model_id, files);
+            if !missing.is_empty() {
+                return Err(EmbedError::NotCached {
+                    model: model_id.to_string(),
+                    missing,
+                });
+            }
+            return Ok(files.iter().filter_map(|alts| lookup(alts)).collect());
+        }
+
+        let dir = self.cache.path();
+        let _ = std::fs::create_dir_all(dir);
+        let api = ApiBuilder::from_cache(self.cache.clone())
+            .build()
+            .map_err(|e| EmbedError::Download(format!("API init: {}", e)))?;
+        let repo = api.model(model_id.to_string());
+
+        let mut paths = Vec::with_capacity(files.len());
+        for alternatives in files {
+            if let Some(path) = lookup(alternatives) {
+                paths.push(path);
+                continue;
+            }
+            let mut last_err = None;
+            let path = alternatives.iter().find_map(|f| match repo.get(f) {
+                Ok(path) => Some(path),
+                Err(e) => {
+                    last_err = Some(e);
+                    None
+                }
+            });
+            match (path, last_err) {
+                (Some(path), _) => paths.push(path),
+                (None, Some(e)) => return Err(e.into()),
+                (None, None) => {}
+            }
+        }
+        Ok(paths)
+    }
+
+    /// Entries of `files` with no cached file, alternatives joined by "or".
+    pub fn missing(&self, model_id: &str, files: &[&[&str]]) -> Vec<String> {
+        let cached = self.cache.model(model_id.to_string());
+        files
+            .iter()
+            .filter(|alts| alts.iter().all(|f| cached.get(f).is_none()))
+            .map(|alts| alts.join(" or "))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lays out `files` for `model_id` the way hf-hub caches a download.
+    fn populate(root: &std::path::Path, model_id: &str, files: &[&str]) {
+        let repo = Cache::new(root.to_path_buf()).model(model_id.to_string());
+        repo.create_ref("0123abcd").unwrap();
+        let snapshot = root
+            .join(format!("models--{}", model_id.replace('/', "--")))
+            .join("snapshots")
+            .join("0123abcd");
+        std::fs::create_dir_all(&snapshot).unwrap();
+        for file in files {
+            std::fs::write(snapshot.join(file), b"{}").unwrap();
+        }
+    }
+
+    #[test]
+    fn offline_store_serves_cached_files_and_their_alternatives() {
+        let dir = tempfile::tempdir().unwrap();
+        populate(
+            dir.path(),
+            "org/tiny-bert",
+            &["config.json", "vocab.txt", "model.safetensors"],
+        );
+        let store = ModelStore::new(dir.path().to_str(), true);
+
+        let paths = store.resolve("org/tiny-bert", BERT_FILES).unwrap();
+        let names: Vec<_> = paths
+            .iter()
+            .map(|p| p.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(names, ["config.json", "vocab.txt", "model.safetensors"]);
+        assert!(store.missing("org/tiny-bert", BERT_FILES).is_empty());
+    }
+
+    #[test]
+    fn offline_store_lists_missing_files_without_downloading() {
+        let dir = tempfile::tempdir().unwrap();
+        populate(dir.path(), "org/tiny-bert", &["config.json"]);
+        let store = ModelStore::new(dir.path().to_str(), true);
+
+        let err = store.resolve("org/tiny-bert", BERT_FILES).unwrap_err();
+        match &err {
+            EmbedError::NotCached { model, missing } => {
+                assert_eq!(model, "org/tiny-bert");
+                assert_eq!(
+                    missing,
+                    &[
+                        "tokenizer.json or vocab.txt",
+                        "model.safetensors or pytorch_model.bin"
+                    ]
+                );
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+        let message = err.to_string();
+        assert!(
+            message.contains("org/tiny-bert not found locally"),
+            "{message}"
+        );
+        assert!(message.contains("ferrumyx models download"), "{message}");
+
+        let err = store
+            .resolve("org/never-fetched", CROSS_ENCODER_FILES)
+            .unwrap_err();
+        assert!(matches!(err, EmbedError::NotCached { ref missing, .. } if missing.len() == 3));
+    }
+}
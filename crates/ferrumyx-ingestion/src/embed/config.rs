@@ -1,6 +1,6 @@
 //! Configuration for the embedding service.
 
-use super::PoolingStrategy;
+use super::{DeviceSpec, PoolingStrategy, PostProcess, Precision};
 use serde::{Deserialize, Serialize};
 
 /// Configuration for BiomedBERT embedder.
@@ -21,14 +21,31 @@ pub struct EmbeddingConfig {
     /// Pooling strategy (default: mean)
     pub pooling: PoolingStrategy,
 
+    /// Dimension reduction after pooling, before normalisation (default: none)
+    #[serde(default)]
+    pub post_process: PostProcess,
+
     /// Use GPU if available (default: true)
     pub use_gpu: bool,
 
+    /// Inference device (default: auto); an explicit device wins over
+    /// `use_gpu`
+    #[serde(default)]
+    pub device: DeviceSpec,
+
+    /// Weight precision (default: f32)
+    #[serde(default)]
+    pub dtype: Precision,
+
     /// Cache directory for downloaded models
     pub cache_dir: Option<String>,
 
     /// Maximum cache size for embeddings (number of entries)
     pub cache_size: usize,
+
+    /// Load model files from `cache_dir` only, never the network
+    #[serde(default)]
+    pub offline: bool,
 }
 
 impl Default for EmbeddingConfig {
@@ -39,9 +56,13 @@ impl Default for EmbeddingConfig {
             batch_size: 32,
             normalize: true,
             pooling: PoolingStrategy::Mean,
+            post_process: PostProcess::None,
             use_gpu: true,
+            device: DeviceSpec::Auto,
+            dtype: Precision::F32,
             cache_dir: None,
             cache_size: 10_000,
+            offline: false,
         }
     }
 }
@@ -65,6 +86,15 @@ impl EmbeddingConfig {
         self.model_id = model_id.into();
         self
     }
+
+    /// Device to open: `device` when explicit, otherwise the CPU unless
+    /// `use_gpu`.
+    pub fn device_spec(&self) -> DeviceSpec {
+        match self.device {
+            DeviceSpec::Auto if !self.use_gpu => DeviceSpec::Cpu,
+            spec => spec,
+        }
+    }
 }
 
 /// Runtime speed/quality trade-off for the Rust-native embedder.
@@ -0,0 +1,132 @@
+//! MiniLM cross-encoder using Candle.
+//!
+//! Scores (query, passage) pairs jointly through a BERT encoder with a
+//! sequence-classification head, as used for MS MARCO re-ranking.
+
+use std::time::Instant;
+
+use candle_core::{DType, Device, Tensor};
+use candle_nn::{Linear, Module, VarBuilder};
+use candle_transformers::models::bert::BertModel;
+use tokenizers::{Tokenizer, TruncationParams, TruncationStrategy};
+use tracing::info;
+
+use crate::embed::artifacts::{ModelStore, CROSS_ENCODER_FILES};
+use crate::embed::{BiomedBertEmbedder, EmbedError, Result};
+
+pub const DEFAULT_CROSS_ENCODER_MODEL: &str = "cross-encoder/ms-marco-MiniLM-L-6-v2";
+
+pub struct CrossEncoder {
+    model: BertModel,
+    pooler: Linear,
+    classifier: Linear,
+    tokenizer: Tokenizer,
+    device: Device,
+}
+
+impl CrossEncoder {
+    /// Download (or reuse from `cache_dir`) and load `model_id` on the CPU.
+    /// When `offline`, the files must already be in `cache_dir`.
+    pub async fn new(
+        model_id: &str,
+        cache_dir: Option<String>,
+        offline: bool,
+        max_length: usize,
+    ) -> Result<Self> {
+        let start = Instant::now();
+        info!("Loading cross-encoder model: {}", model_id);
+        let device = Device::Cpu;
+
+        let model_id = model_id.to_string();
+        let store = ModelStore::new(cache_dir.as_deref(), offline);
+        let (bert_config, mut tokenizer, weights_path) = tokio::task::spawn_blocking(move || {
+            let [config_path, tokenizer_path, weights_path] = store
+                .resolve(&model_id, CROSS_ENCODER_FILES)?
+                .try_into()
+                .map_err(|_| EmbedError::ModelLoad(format!("incomplete files for {model_id}")))?;
+            let bert_config = BiomedBertEmbedder::load_config(&config_path)?;
+            let tokenizer = Tokenizer::from_file(tokenizer_path)
+                .map_err(|e| EmbedError::Tokenizer(e.to_string()))?;
+            Ok::<_, EmbedError>((bert_config, tokenizer, weights_path))
+        })
+        .await
+        .map_err(|e| EmbedError::Download(e.to_string()))??;
+
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: max_length.min(512),
+                strategy: TruncationStrategy::LongestFirst,
+                ..TruncationParams::default()
+            }))
+            .map_err(|e| EmbedError::Tokenizer(e.to_string()))?;
+
+        let vb = if weights_path
+            .extension()
+            .map(|e| e == "safetensors")
+            .unwrap_or(false)
+        {
+            unsafe { VarBuilder::from_mmaped_safetensors(&[&weights_path], DType::F32, &device)? }
+        } else {
+            VarBuilder::from_pth(&weights_path, DType::F32, &device)?
+        };
+
+        // BertForSequenceClassification layout: `bert.*` encoder, a tanh
+        // pooler over [CLS], then a single-logit `classifier`.
+        let hidden = bert_config.hidden_size;
+        let model = BertModel::load(vb.pp("bert"), &bert_config)?;
+        let pooler = candle_nn::linear(hidden, hidden, vb.pp("bert.pooler.dense"))?;
+        let classifier = candle_nn::linear(hidden, 1, vb.pp("classifier"))?;
+        info!(
+            "Cross-encoder loaded in {:.2}s",
+            start.elapsed().as_secs_f32()
+        );
+
+        Ok(Self {
+            model,
+            pooler,
+            classifier,
+            tokenizer,
+            device,
+        })
+    }
+
+    /// Relevance logit for each `(query, passage)` pair; higher is better.
+    pub fn score(&self, query: &str, passages: &[String]) -> Result<Vec<f32>> {
+        if passages.is_empty() {
+            return Ok(Vec::new());
+        }
+        let pairs: Vec<(&str, &str)> = passages.iter().map(|p| (query, p.as_str())).collect();
+        let encodings = self
+            .tokenizer
+            .encode_batch(pairs, true)
+            .map_err(|e| EmbedError::Tokenizer(e.to_string()))?;
+
+        let max_len = encodings.iter().map(|e| e.len()).max().unwrap_or(0);
+        let mut input_ids = Vec::with_capacity(encodings.len() * max_len);
+        let mut attention_mask = Vec::with_capacity(encodings.len() * max_len);
+        let mut token_type_ids = Vec::with_capacity(encodings.len() * max_len);
+        for encoding in &encodings {
+            let pad_len = max_len - encoding.len();
+            input_ids.extend(encoding.get_ids().iter().copied());
+            input_ids.extend(std::iter::repeat_n(0, pad_len));
+            attention_mask.extend(encoding.get_attention_mask().iter().copied());
+            attention_mask.extend(std::iter::repeat_n(0, pad_len));
+            token_type_ids.extend(encoding.get_type_ids().iter().copied());
+            token_type_ids.extend(std::iter::repeat_n(0, pad_len));
+        }
+
+        let shape = (encodings.len(), max_len);
+        let input_ids = Tensor::from_vec(input_ids, shape, &self.device)?;
+        let attention_mask =
+            Tensor::from_vec(attention_mask, shape, &self.device)?.to_dtype(DType::F32)?;
+        let token_type_ids = Tensor::from_vec(token_type_ids, shape, &self.device)?;
+
+        let hidden = self
+            .model
+            .forward(&input_ids, &token_type_ids, Some(&attention_mask))?;
+        let cls = hidden.narrow(1, 0, 1)?.squeeze(1)?;
+        let pooled = self.pooler.forward(&cls)?.tanh()?;
+        let logits = self.classifier.forward(&pooled)?.squeeze(1)?;
+        Ok(logits.to_vec1::<f32>()?)
+    }
+}
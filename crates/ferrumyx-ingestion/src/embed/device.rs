@@ -0,0 +1,238 @@
+//! Device and precision selection for the Candle models.
+//!
+//! A [`DeviceSpec`] names where to run ("cpu", "cuda:<n>", "metal" or
+//! "auto"); [`select_device`] tries it and falls back to the CPU when the
+//! device is unavailable or not compiled in. [`Precision`] picks the weight
+//! dtype for the chosen device, keeping F32 on the CPU.
+
+use std::fmt;
+
+use candle_core::{DType, Device};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+/// Where to run inference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum DeviceSpec {
+    /// First available of CUDA device 0, Metal, then the CPU.
+    #[default]
+    Auto,
+    Cpu,
+    Cuda(usize),
+    Metal,
+}
+
+impl DeviceSpec {
+    pub const ENV_VAR: &'static str = "FERRUMYX_EMBED_DEVICE";
+
+    /// Parses "auto", "cpu", "cuda", "cuda:<n>", "gpu" (CUDA 0) or "metal".
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_ascii_lowercase();
+        match value.as_str() {
+            "auto" | "" => Some(Self::Auto),
+            "cpu" => Some(Self::Cpu),
+            "cuda" | "gpu" => Some(Self::Cuda(0)),
+            "metal" | "mps" => Some(Self::Metal),
+            _ => value
+                .strip_prefix("cuda:")
+                .and_then(|n| n.parse().ok())
+                .map(Self::Cuda),
+        }
+    }
+}
+
+impl fmt::Display for DeviceSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Auto => f.write_str("auto"),
+            Self::Cpu => f.write_str("cpu"),
+            Self::Cuda(n) => write!(f, "cuda:{n}"),
+            Self::Metal => f.write_str("metal"),
+        }
+    }
+}
+
+impl TryFrom<String> for DeviceSpec {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value).ok_or_else(|| {
+            format!("unknown device {value:?}; expected cpu, cuda:<n>, metal or auto")
+        })
+    }
+}
+
+impl From<DeviceSpec> for String {
+    fn from(spec: DeviceSpec) -> Self {
+        spec.to_string()
+    }
+}
+
+/// Weight precision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Precision {
+    #[default]
+    F32,
+    F16,
+    Bf16,
+    /// F16 on a GPU, F32 on the CPU.
+    Auto,
+}
+
+impl Precision {
+    pub const ENV_VAR: &'static str = "FERRUMYX_EMBED_DTYPE";
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "f32" | "fp32" | "float32" => Some(Self::F32),
+            "f16" | "fp16" | "float16" | "half" => Some(Self::F16),
+            "bf16" | "bfloat16" => Some(Self::Bf16),
+            "auto" => Some(Self::Auto),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::F32 => "f32",
+            Self::F16 => "f16",
+            Self::Bf16 => "bf16",
+            Self::Auto => "auto",
+        }
+    }
+
+    /// Dtype to load weights in on `device`. Half precision is only used
+    /// on a GPU; BF16 falls back to F16 where the device lacks it.
+    pub fn dtype_for(self, device: &Device) -> DType {
+        self.dtype_with(!device.is_cpu(), device.supports_bf16())
+    }
+
+    fn dtype_with(self, gpu: bool, supports_bf16: bool) -> DType {
+        match self {
+            _ if !gpu => DType::F32,
+            Self::F32 => DType::F32,
+            Self::F16 | Self::Auto => DType::F16,
+            Self::Bf16 if supports_bf16 => DType::BF16,
+            Self::Bf16 => DType::F16,
+        }
+    }
+}
+
+/// Opens the device `spec` names, falling back to the CPU.
+pub fn select_device(spec: DeviceSpec) -> Device {
+    select_device_with(spec, Device::new_cuda, Device::new_metal)
+}
+
+fn select_device_with(
+    spec: DeviceSpec,
+    cuda: impl Fn(usize) -> candle_core::Result<Device>,
+    metal: impl Fn(usize) -> candle_core::Result<Device>,
+) -> Device {
+    let attempts: &[DeviceSpec] = match spec {
+        DeviceSpec::Cpu => &[],
+        DeviceSpec::Cuda(_) | DeviceSpec::Metal => std::slice::from_ref(&spec),
+        DeviceSpec::Auto => &[DeviceSpec::Cuda(0), DeviceSpec::Metal],
+    };
+    for attempt in attempts {
+        let opened = match *attempt {
+            DeviceSpec::Cuda(n) => cuda(n),
+            _ => metal(0),
+        };
+        match opened {
+            Ok(device) => {
+                info!(requested = %spec, device = %attempt, "Selected inference device");
+                return device;
+            }
+            Err(e) if spec == DeviceSpec::Auto => {
+                tracing::debug!(device = %attempt, "Device unavailable: {e}");
+            }
+            Err(e) => {
+                warn!(requested = %spec, "Device unavailable, falling back to CPU: {e}");
+            }
+        }
+    }
+    info!(requested = %spec, device = "cpu", "Selected inference device");
+    Device::Cpu
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn device_strings_parse_and_round_trip() {
+        for (raw, spec) in [
+            ("auto", DeviceSpec::Auto),
+            (" CPU ", DeviceSpec::Cpu),
+            ("cuda", DeviceSpec::Cuda(0)),
+            ("cuda:2", DeviceSpec::Cuda(2)),
+            ("metal", DeviceSpec::Metal),
+        ] {
+            assert_eq!(DeviceSpec::parse(raw), Some(spec), "{raw}");
+            assert_eq!(DeviceSpec::parse(&spec.to_string()), Some(spec));
+        }
+        for raw in ["cuda:", "cuda:x", "tpu", "metal:1"] {
+            assert_eq!(DeviceSpec::parse(raw), None, "{raw}");
+        }
+
+        let spec: DeviceSpec = serde_json::from_str("\"cuda:1\"").unwrap();
+        assert_eq!(spec, DeviceSpec::Cuda(1));
+        assert!(serde_json::from_str::<DeviceSpec>("\"opencl\"").is_err());
+        assert_eq!(Precision::parse("BF16"), Some(Precision::Bf16));
+        assert_eq!(Precision::parse("int8"), None);
+        for precision in [
+            Precision::F32,
+            Precision::F16,
+            Precision::Bf16,
+            Precision::Auto,
+        ] {
+            assert_eq!(Precision::parse(precision.as_str()), Some(precision));
+        }
+    }
+
+    #[test]
+    fn unavailable_devices_fall_back_in_order() {
+        let tried = RefCell::new(Vec::new());
+        let unavailable = |name: &'static str| {
+            let tried = &tried;
+            move |_: usize| -> candle_core::Result<Device> {
+                tried.borrow_mut().push(name);
+                Err(candle_core::Error::Msg(format!("no {name}")))
+            }
+        };
+
+        let device =
+            select_device_with(DeviceSpec::Auto, unavailable("cuda"), unavailable("metal"));
+        assert!(device.is_cpu());
+        assert_eq!(*tried.borrow(), ["cuda", "metal"]);
+
+        tried.borrow_mut().clear();
+        let device =
+            select_device_with(DeviceSpec::Metal, unavailable("cuda"), unavailable("metal"));
+        assert!(device.is_cpu());
+        assert_eq!(*tried.borrow(), ["metal"]);
+
+        tried.borrow_mut().clear();
+        select_device_with(DeviceSpec::Cpu, unavailable("cuda"), unavailable("metal"));
+        assert!(tried.borrow().is_empty());
+
+        // Without the cuda feature candle reports CUDA as not compiled in.
+        #[cfg(not(feature = "cuda"))]
+        assert!(select_device(DeviceSpec::Cuda(0)).is_cpu());
+    }
+
+    #[test]
+    fn half_precision_only_on_gpus() {
+        for precision in [Precision::F16, Precision::Bf16, Precision::Auto] {
+            assert_eq!(precision.dtype_with(false, false), DType::F32);
+            assert_eq!(precision.dtype_for(&Device::Cpu), DType::F32);
+        }
+        assert_eq!(Precision::Auto.dtype_with(true, true), DType::F16);
+        assert_eq!(Precision::F32.dtype_with(true, true), DType::F32);
+        assert_eq!(Precision::Bf16.dtype_with(true, true), DType::BF16);
+        assert_eq!(Precision::Bf16.dtype_with(true, false), DType::F16);
+    }
+}
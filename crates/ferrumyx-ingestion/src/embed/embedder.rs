@@ -6,20 +6,25 @@ use std::time::Instant;
 use candle_core::{DType, Device, Tensor};
 use candle_nn::VarBuilder;
 use candle_transformers::models::bert::{BertModel, Config, HiddenAct, PositionEmbeddingType};
-use hf_hub::api::sync::ApiBuilder;
 use lru::LruCache;
 use std::num::NonZeroUsize;
-use std::path::PathBuf;
+use std::path::Path;
 use tokenizers::Tokenizer;
-use tracing::info;
+use tracing::{info, warn};
 
+use crate::embed::artifacts::{ModelStore, BERT_FILES};
+use crate::embed::device::select_device;
 use crate::embed::pooling::l2_normalize;
+use crate::embed::postprocess::Projection;
 use crate::embed::{EmbedError, EmbeddingConfig, Result};
 
 pub struct BiomedBertEmbedder {
     model: BertModel,
     tokenizer: Tokenizer,
     device: Device,
+    dtype: DType,
+    projection: Projection,
+    output_dim: usize,
     config: EmbeddingConfig,
     cache: Option<Arc<std::sync::Mutex<LruCache<String, Vec<f32>>>>>,
 }
@@ -28,40 +33,23 @@ impl BiomedBertEmbedder {
     pub async fn new(config: EmbeddingConfig) -> Result<Self> {
         let start = Instant::now();
         info!("Loading BiomedBERT model: {}", config.model_id);
-        let device = Self::select_device(&config)?;
+        let device = select_device(config.device_spec());
 
         let model_id = config.model_id.clone();
-        let cache_dir = config.cache_dir.clone();
+        let store = ModelStore::new(config.cache_dir.as_deref(), config.offline);
         let (bert_config, tokenizer, weights_path) = tokio::task::spawn_blocking(move || {
-            let mut builder = ApiBuilder::new();
-            if let Some(dir) = cache_dir
-                .as_ref()
-                .map(|v| v.trim().to_string())
-                .filter(|v| !v.is_empty())
-            {
-                let path = PathBuf::from(dir);
-                if let Some(parent) = path.parent() {
-                    let _ = std::fs::create_dir_all(parent);
-                }
-                let _ = std::fs::create_dir_all(&path);
-                builder = builder.with_cache_dir(path);
-            }
-            let api = builder
-                .build()
-                .map_err(|e| EmbedError::Download(format!("API init: {}", e)))?;
-            let repo = hf_hub::Repo::new(model_id.clone(), hf_hub::RepoType::Model);
-            let api_repo = api.repo(repo);
-
-            let config_path = api_repo.get("config.json")?;
+            let [config_path, tokenizer_path, weights_path] = store
+                .resolve(&model_id, BERT_FILES)?
+                .try_into()
+                .map_err(|_| EmbedError::ModelLoad(format!("incomplete files for {model_id}")))?;
             let bert_config = Self::load_config(&config_path)?;
 
-            let tokenizer = if let Ok(tokenizer_path) = api_repo.get("tokenizer.json") {
+            let tokenizer = if tokenizer_path.ends_with("tokenizer.json") {
                 Tokenizer::from_file(&tokenizer_path)
                     .map_err(|e| EmbedError::Tokenizer(e.to_string()))?
             } else {
-                let vocab_path = api_repo.get("vocab.txt")?;
                 let wordpiece = tokenizers::models::wordpiece::WordPiece::from_file(
-                    vocab_path.to_str().unwrap(),
+                    tokenizer_path.to_str().unwrap(),
                 )
                 .unk_token("[UNK]".to_string())
                 .build()
@@ -69,26 +57,20 @@ impl BiomedBertEmbedder {
                 Tokenizer::new(wordpiece)
             };
 
-            let weights_path = api_repo
-                .get("model.safetensors")
-                .or_else(|_| api_repo.get("pytorch_model.bin"))?;
             Ok::<_, EmbedError>((bert_config, tokenizer, weights_path))
         })
         .await
         .map_err(|e| EmbedError::Download(e.to_string()))??;
 
-        let vb = if weights_path
-            .extension()
-            .map(|e| e == "safetensors")
-            .unwrap_or(false)
-        {
-            unsafe { VarBuilder::from_mmaped_safetensors(&[&weights_path], DType::F32, &device)? }
-        } else {
-            VarBuilder::from_pth(&weights_path, DType::F32, &device)?
-        };
-
-        let model = BertModel::load(vb, &bert_config)?;
-        info!("Model loaded in {:.2}s", start.elapsed().as_secs_f32());
+        let projection = Projection::load(&config.post_process, bert_config.hidden_size, &device)?;
+        let output_dim = config.post_process.output_dim(bert_config.hidden_size);
+        let dtype = config.dtype.dtype_for(&device);
+        let model = Self::load_model(&weights_path, &bert_config, dtype, &device)?;
+        info!(
+            "Model loaded in {:.2}s ({:?})",
+            start.elapsed().as_secs_f32(),
+            dtype
+        );
 
         let cache = if config.cache_size > 0 {
             Some(Arc::new(std::sync::Mutex::new(LruCache::new(
@@ -98,52 +80,80 @@ impl BiomedBertEmbedder {
             None
         };
 
-        Ok(Self {
+        let mut embedder = Self {
             model,
             tokenizer,
             device,
+            dtype,
+            projection,
+            output_dim,
             config,
             cache,
-        })
-    }
-
-    fn select_device(config: &EmbeddingConfig) -> Result<Device> {
-        if !config.use_gpu {
-            info!("GPU usage disabled in config, using CPU.");
-            return Ok(Device::Cpu);
-        }
-
-        #[cfg(feature = "cuda")]
-        {
-            match Device::new_cuda(0) {
-                Ok(d) => {
-                    info!("Successfully initialized CUDA device.");
-                    return Ok(d);
-                }
-                Err(e) => {
-                    tracing::warn!("CUDA feature is enabled but device initialization failed: {}. Falling back to CPU.", e);
-                }
+        };
+        if dtype != DType::F32 {
+            // Not every op has a half-precision kernel on every backend, and
+            // F16 can overflow; probe once and fall back to F32.
+            let probe = embedder.embed_batch(&["warmup".to_string()]).await;
+            let usable = matches!(&probe, Ok(v) if v.iter().flatten().all(|x| x.is_finite()));
+            if !usable {
+                warn!(
+                    ?dtype,
+                    error = ?probe.err(),
+                    "Half-precision inference failed; reloading in F32"
+                );
+                embedder.model =
+                    Self::load_model(&weights_path, &bert_config, DType::F32, &embedder.device)?;
+                embedder.dtype = DType::F32;
             }
         }
+        Ok(embedder)
+    }
 
-        #[cfg(feature = "metal")]
+    fn load_model(
+        weights_path: &Path,
+        bert_config: &Config,
+        dtype: DType,
+        device: &Device,
+    ) -> Result<BertModel> {
+        let vb = if weights_path
+            .extension()
+            .map(|e| e == "safetensors")
+            .unwrap_or(false)
         {
-            match Device::new_metal(0) {
-                Ok(d) => {
-                    info!("Successfully initialized Metal device.");
-                    return Ok(d);
-                }
-                Err(e) => {
-                    tracing::warn!("Metal feature is enabled but device initialization failed: {}. Falling back to CPU.", e);
-                }
-            }
-        }
+            unsafe { VarBuilder::from_mmaped_safetensors(&[weights_path], dtype, device)? }
+        } else {
+            VarBuilder::from_pth(weights_path, dtype, device)?
+        };
+        Ok(BertModel::load(vb, bert_config)?)
+    }
+
+    /// Width of the vectors [`Self::embed`] returns.
+    pub fn output_dim(&self) -> usize {
+        self.output_dim
+    }
+
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
 
-        info!("No GPU features enabled or GPU initialization failed. Using CPU.");
-        Ok(Device::Cpu)
+    /// Dtype the weights run in, after any fallback to F32.
+    pub fn dtype(&self) -> DType {
+        self.dtype
     }
 
-    fn load_config(path: &std::path::PathBuf) -> Result<Config> {
+    /// Tokens `texts` take up after truncation, for throughput figures.
+    pub fn token_count(&self, texts: &[String]) -> Result<usize> {
+        let max_len = self.config.max_length.min(512);
+        let refs: Vec<&str> = texts.iter().map(String::as_str).collect();
+        Ok(self
+            .tokenizer
+            .encode_batch(refs, true)?
+            .iter()
+            .map(|e| e.get_ids().len().min(max_len))
+            .sum())
+    }
+
+    pub(crate) fn load_config(path: &std::path::PathBuf) -> Result<Config> {
         let content = std::fs::read_to_string(path)?;
         let json: serde_json::Value = serde_json::from_str(&content)?;
         let hidden_act = match json.get("hidden_act").and_then(|v| v.as_str()) {
@@ -289,8 +299,10 @@ impl BiomedBertEmbedder {
 
         let embeddings = self
             .model
-            .forward(&input_ids, &token_type_ids, Some(&attention_mask))?;
+            .forward(&input_ids, &token_type_ids, Some(&attention_mask))?
+            .to_dtype(DType::F32)?;
         let pooled = self.config.pooling.apply(&embeddings, &attention_mask)?;
+        let pooled = self.projection.apply(&pooled)?;
         let normalized = if self.config.normalize {
             l2_normalize(&pooled)?
         } else {
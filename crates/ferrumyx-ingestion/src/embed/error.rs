@@ -21,6 +21,12 @@ pub enum EmbedError {
     #[error("Model download failed: {0}")]
     Download(String),
 
+    #[error(
+        "model {model} not found locally (missing {}); run `ferrumyx models download` to fetch it",
+        .missing.join(", ")
+    )]
+    NotCached { model: String, missing: Vec<String> },
+
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 
@@ -1,10 +1,18 @@
+pub mod artifacts;
 pub mod batch;
 pub mod config;
+pub mod cross_encoder;
+pub mod device;
 pub mod embedder;
 pub mod error;
 pub mod pooling;
+pub mod postprocess;
 
+pub use artifacts::ModelStore;
 pub use config::EmbeddingConfig;
+pub use cross_encoder::CrossEncoder;
+pub use device::{DeviceSpec, Precision};
 pub use embedder::BiomedBertEmbedder;
 pub use error::{EmbedError, Result};
 pub use pooling::PoolingStrategy;
+pub use postprocess::{fit_pca, PcaProjection, PostProcess};
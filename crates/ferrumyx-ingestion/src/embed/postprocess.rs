@@ -0,0 +1,468 @@
+//! Dimension reduction between pooling and L2 normalisation.
+//!
+//! Storing fewer dimensions shrinks the chunks table and its vector index.
+//! [`PostProcess::Truncate`] keeps the leading dimensions, which works for
+//! Matryoshka-trained models and roughly for others once renormalised;
+//! [`PostProcess::Pca`] applies a projection fitted on the corpus with
+//! [`fit_pca`].
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use candle_core::{Device, Tensor};
+use serde::{Deserialize, Serialize};
+
+use crate::embed::{EmbedError, Result};
+
+/// Reduction applied to pooled embeddings.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum PostProcess {
+    #[default]
+    None,
+    /// Keep the first `dim` dimensions.
+    Truncate(usize),
+    /// Project onto the `dim` principal components saved at `path`.
+    Pca { dim: usize, path: PathBuf },
+}
+
+impl PostProcess {
+    pub const ENV_VAR: &'static str = "FERRUMYX_EMBED_POST_PROCESS";
+
+    /// Parses "none", "truncate:<dim>" or "pca:<dim>:<path>".
+    pub fn parse(value: &str) -> std::result::Result<Self, String> {
+        let value = value.trim();
+        let mut parts = value.splitn(3, ':');
+        let kind = parts.next().unwrap_or("").to_ascii_lowercase();
+        let dim = parts
+            .next()
+            .map(|d| d.trim().parse::<usize>().ok().filter(|&d| d > 0));
+        match (kind.as_str(), dim, parts.next()) {
+            ("" | "none", None, None) => Ok(Self::None),
+            ("truncate", Some(Some(dim)), None) => Ok(Self::Truncate(dim)),
+            ("pca", Some(Some(dim)), Some(path)) if !path.trim().is_empty() => Ok(Self::Pca {
+                dim,
+                path: PathBuf::from(path.trim()),
+            }),
+            _ => Err(format!(
+                "invalid post-process {value:?}; expected none, truncate:<dim> or pca:<dim>:<path>"
+            )),
+        }
+    }
+
+    /// Width of the vectors produced from `input_dim`-wide embeddings.
+    pub fn output_dim(&self, input_dim: usize) -> usize {
+        match self {
+            Self::None => input_dim,
+            Self::Truncate(dim) | Self::Pca { dim, .. } => *dim,
+        }
+    }
+}
+
+impl fmt::Display for PostProcess {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None => f.write_str("none"),
+            Self::Truncate(dim) => write!(f, "truncate:{dim}"),
+            Self::Pca { dim, path } => write!(f, "pca:{dim}:{}", path.display()),
+        }
+    }
+}
+
+impl TryFrom<String> for PostProcess {
+    type Error = String;
+
+    fn try_from(value: String) -> std::result::Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl From<PostProcess> for String {
+    fn from(post: PostProcess) -> Self {
+        post.to_string()
+    }
+}
+
+/// Principal components of a sample of embeddings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PcaProjection {
+    /// Sample mean, subtracted before projecting.
+    pub mean: Vec<f32>,
+    /// Unit-length components, by decreasing variance.
+    pub components: Vec<Vec<f32>>,
+}
+
+impl PcaProjection {
+    pub fn input_dim(&self) -> usize {
+        self.mean.len()
+    }
+
+    pub fn dim(&self) -> usize {
+        self.components.len()
+    }
+
+    pub fn project(&self, v: &[f32]) -> Vec<f32> {
+        self.components
+            .iter()
+            .map(|c| {
+                c.iter()
+                    .zip(v.iter().zip(&self.mean))
+                    .map(|(c, (x, m))| c * (x - m))
+                    .sum()
+            })
+            .collect()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let pca: Self = serde_json::from_slice(&std::fs::read(path)?)?;
+        if pca.components.is_empty() || pca.components.iter().any(|c| c.len() != pca.mean.len()) {
+            return Err(EmbedError::InvalidInput(format!(
+                "{} is not a PCA projection",
+                path.display()
+            )));
+        }
+        Ok(pca)
+    }
+}
+
+/// Fits the `dim` leading principal components of `sample`.
+pub fn fit_pca(sample: &[Vec<f32>], dim: usize) -> Result<PcaProjection> {
+    let input_dim = sample.first().map(Vec::len).unwrap_or(0);
+    if sample.len() < 2 || sample.iter().any(|v| v.len() != input_dim) {
+        return Err(EmbedError::InvalidInput(
+            "PCA needs at least two embeddings of one width".to_string(),
+        ));
+    }
+    if dim == 0 || dim > input_dim {
+        return Err(EmbedError::InvalidInput(format!(
+            "cannot reduce {input_dim}-dim embeddings to {dim}"
+        )));
+    }
+
+    let n = sample.len() as f64;
+    let mut mean = vec![0f64; input_dim];
+    for v in sample {
+        for (m, x) in mean.iter_mut().zip(v) {
+            *m += *x as f64 / n;
+        }
+    }
+    let mut cov = vec![0f64; input_dim * input_dim];
+    let mut centred = vec![0f64; input_dim];
+    for v in sample {
+        for ((c, x), m) in centred.iter_mut().zip(v).zip(&mean) {
+            *c = *x as f64 - m;
+        }
+        for (i, &ci) in centred.iter().enumerate() {
+            let row = &mut cov[i * input_dim..(i + 1) * input_dim];
+            for (r, cj) in row[i..].iter_mut().zip(&centred[i..]) {
+                *r += ci * cj;
+            }
+        }
+    }
+    for i in 0..input_dim {
+        for j in i..input_dim {
+            let c = cov[i * input_dim + j] / (n - 1.0);
+            cov[i * input_dim + j] = c;
+            cov[j * input_dim + i] = c;
+        }
+    }
+
+    let components = leading_eigenvectors(&cov, input_dim, dim);
+    Ok(PcaProjection {
+        mean: mean.into_iter().map(|m| m as f32).collect(),
+        components: components
+            .into_iter()
+            .map(|c| c.into_iter().map(|x| x as f32).collect())
+            .collect(),
+    })
+}
+
+/// Leading eigenvectors of the symmetric `n`×`n` matrix `m`, by subspace
+/// iteration, sorted by eigenvalue.
+fn leading_eigenvectors(m: &[f64], n: usize, k: usize) -> Vec<Vec<f64>> {
+    const MAX_ITERATIONS: usize = 200;
+    const TOLERANCE: f64 = 1e-9;
+
+    let mul = |v: &[f64]| -> Vec<f64> {
+        (0..n)
+            .map(|i| {
+                m[i * n..(i + 1) * n]
+                    .iter()
+                    .zip(v)
+                    .map(|(a, b)| a * b)
+                    .sum()
+            })
+            .collect()
+    };
+    // Deterministic start, so a refit on the same sample gives the same file.
+    let mut seed = 0x9e37_79b9_7f4a_7c15u64;
+    let mut basis: Vec<Vec<f64>> = (0..k)
+        .map(|_| {
+            (0..n)
+                .map(|_| {
+                    seed ^= seed << 13;
+                    seed ^= seed >> 7;
+                    seed ^= seed << 17;
+                    (seed >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+                })
+                .collect()
+        })
+        .collect();
+    orthonormalise(&mut basis);
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut next: Vec<Vec<f64>> = basis.iter().map(|v| mul(v)).collect();
+        orthonormalise(&mut next);
+        // Share of each new vector outside the old subspace; rotations
+        // within the subspace don't count.
+        let change = next
+            .iter()
+            .map(|v| 1.0 - basis.iter().map(|b| dot(v, b).powi(2)).sum::<f64>())
+            .fold(0.0, f64::max);
+        basis = next;
+        if change < TOLERANCE {
+            break;
+        }
+    }
+
+    let mut ranked: Vec<(f64, Vec<f64>)> = basis
+        .into_iter()
+        .map(|mut v| {
+            // Fix the sign so the largest entry is positive.
+            let peak = v
+                .iter()
+                .copied()
+                .fold(0.0, |p: f64, x| if x.abs() > p.abs() { x } else { p });
+            if peak < 0.0 {
+                v.iter_mut().for_each(|x| *x = -*x);
+            }
+            (dot(&v, &mul(&v)), v)
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+    ranked.into_iter().map(|(_, v)| v).collect()
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Modified Gram–Schmidt; a vector that vanishes is replaced by a unit axis.
+fn orthonormalise(vectors: &mut [Vec<f64>]) {
+    for i in 0..vectors.len() {
+        let (done, rest) = vectors.split_at_mut(i);
+        let v = &mut rest[0];
+        for u in done.iter() {
+            let p = dot(v, u);
+            v.iter_mut().zip(u).for_each(|(x, y)| *x -= p * y);
+        }
+        let norm = dot(v, v).sqrt();
+        if norm > 1e-12 {
+            v.iter_mut().for_each(|x| *x /= norm);
+        } else {
+            v.iter_mut()
+                .enumerate()
+                .for_each(|(j, x)| *x = if j == i { 1.0 } else { 0.0 });
+        }
+    }
+}
+
+/// A [`PostProcess`] loaded onto the inference device.
+pub(crate) enum Projection {
+    None,
+    Truncate(usize),
+    Linear { mean: Tensor, weights: Tensor },
+}
+
+impl Projection {
+    pub(crate) fn load(post: &PostProcess, input_dim: usize, device: &Device) -> Result<Self> {
+        match post {
+            PostProcess::None => Ok(Self::None),
+            PostProcess::Truncate(dim) if *dim <= input_dim => Ok(Self::Truncate(*dim)),
+            PostProcess::Truncate(dim) => Err(EmbedError::InvalidInput(format!(
+                "cannot truncate {input_dim}-dim embeddings to {dim}"
+            ))),
+            PostProcess::Pca { dim, path } => {
+                let pca = PcaProjection::load(path)?;
+                if pca.input_dim() != input_dim || pca.dim() != *dim {
+                    return Err(EmbedError::InvalidInput(format!(
+                        "{} projects {}-dim embeddings to {}, not {input_dim} to {dim}",
+                        path.display(),
+                        pca.input_dim(),
+                        pca.dim()
+                    )));
+                }
+                let weights: Vec<f32> = pca.components.concat();
+                Ok(Self::Linear {
+                    mean: Tensor::from_vec(pca.mean, (1, input_dim), device)?,
+                    weights: Tensor::from_vec(weights, (*dim, input_dim), device)?
+                        .t()?
+                        .contiguous()?,
+                })
+            }
+        }
+    }
+
+    /// Reduces pooled `(batch, input_dim)` embeddings.
+    pub(crate) fn apply(&self, pooled: &Tensor) -> candle_core::Result<Tensor> {
+        match self {
+            Self::None => Ok(pooled.clone()),
+            Self::Truncate(dim) => pooled.narrow(1, 0, *dim),
+            Self::Linear { mean, weights } => pooled.broadcast_sub(mean)?.matmul(weights),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn cosine(a: &[f32], b: &[f32]) -> f32 {
+        let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+        let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+        dot / (norm(a) * norm(b))
+    }
+
+    /// Mean share of each query's `k` nearest neighbours under `full` that
+    /// stay among its `k` nearest under `reduced`.
+    fn neighbour_overlap(full: &[Vec<f32>], reduced: &[Vec<f32>], k: usize) -> f32 {
+        let nearest = |vs: &[Vec<f32>], q: usize| -> Vec<usize> {
+            let mut ids: Vec<usize> = (0..vs.len()).filter(|&i| i != q).collect();
+            ids.sort_by(|&a, &b| cosine(&vs[q], &vs[b]).total_cmp(&cosine(&vs[q], &vs[a])));
+            ids.truncate(k);
+            ids
+        };
+        let queries = 20;
+        let kept: usize = (0..queries)
+            .map(|q| {
+                let reduced = nearest(reduced, q);
+                nearest(full, q)
+                    .iter()
+                    .filter(|i| reduced.contains(i))
+                    .count()
+            })
+            .sum();
+        kept as f32 / (queries * k) as f32
+    }
+
+    /// Vectors whose variance decays along the dimensions, as in a
+    /// Matryoshka-trained model.
+    fn decaying(n: usize, d: usize, seed: u64) -> Vec<Vec<f32>> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..n)
+            .map(|_| {
+                (0..d)
+                    .map(|i| rng.gen_range(-1.0f32..1.0) * (-(i as f32) / 12.0).exp())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn post_process_strings_parse_and_round_trip() {
+        for (raw, post) in [
+            ("none", PostProcess::None),
+            ("truncate:256", PostProcess::Truncate(256)),
+            (
+                "pca:128:data/models/pca.json",
+                PostProcess::Pca {
+                    dim: 128,
+                    path: PathBuf::from("data/models/pca.json"),
+                },
+            ),
+        ] {
+            assert_eq!(PostProcess::parse(raw), Ok(post.clone()), "{raw}");
+            assert_eq!(PostProcess::parse(&post.to_string()), Ok(post));
+        }
+        for raw in [
+            "truncate",
+            "truncate:0",
+            "truncate:x",
+            "pca:64",
+            "none:3",
+            "zip",
+        ] {
+            assert!(PostProcess::parse(raw).is_err(), "{raw}");
+        }
+        assert_eq!(PostProcess::Truncate(256).output_dim(768), 256);
+        assert_eq!(PostProcess::None.output_dim(768), 768);
+    }
+
+    #[test]
+    fn truncation_roughly_preserves_cosine_neighbours() {
+        let full = decaying(300, 96, 7);
+        let truncated: Vec<Vec<f32>> = full.iter().map(|v| v[..24].to_vec()).collect();
+        let overlap = neighbour_overlap(&full, &truncated, 10);
+        assert!(overlap > 0.8, "top-10 overlap {overlap}");
+
+        // Keeping the tail instead loses most of the ordering.
+        let tail: Vec<Vec<f32>> = full.iter().map(|v| v[72..].to_vec()).collect();
+        assert!(neighbour_overlap(&full, &tail, 10) < overlap / 2.0);
+    }
+
+    #[test]
+    fn pca_recovers_a_low_rank_subspace() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let basis: Vec<Vec<f32>> = (0..6)
+            .map(|_| (0..48).map(|_| rng.gen_range(-1.0f32..1.0)).collect())
+            .collect();
+        let sample: Vec<Vec<f32>> = (0..400)
+            .map(|_| {
+                let weights: Vec<f32> = (0..6).map(|_| rng.gen_range(-1.0f32..1.0)).collect();
+                (0..48)
+                    .map(|j| {
+                        let signal: f32 = weights.iter().zip(&basis).map(|(w, b)| w * b[j]).sum();
+                        1.5 + signal + rng.gen_range(-0.01f32..0.01)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let pca = fit_pca(&sample, 6).unwrap();
+        assert_eq!((pca.input_dim(), pca.dim()), (48, 6));
+        for (i, a) in pca.components.iter().enumerate() {
+            for (j, b) in pca.components.iter().enumerate() {
+                let expected = if i == j { 1.0 } else { 0.0 };
+                let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+                assert!((dot - expected).abs() < 1e-3, "components {i},{j}: {dot}");
+            }
+        }
+
+        // All the variance lies in the fitted subspace, so the centred
+        // vectors keep their neighbours after projection.
+        let centred: Vec<Vec<f32>> = sample
+            .iter()
+            .map(|v| v.iter().zip(&pca.mean).map(|(x, m)| x - m).collect())
+            .collect();
+        let projected: Vec<Vec<f32>> = sample.iter().map(|v| pca.project(v)).collect();
+        assert!(neighbour_overlap(&centred, &projected, 10) > 0.9);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pca.json");
+        pca.save(&path).unwrap();
+        let loaded = PcaProjection::load(&path).unwrap();
+        assert_eq!(loaded.project(&sample[0]), pca.project(&sample[0]));
+
+        let device = Device::Cpu;
+        let post = PostProcess::Pca { dim: 6, path };
+        let projection = Projection::load(&post, 48, &device).unwrap();
+        let batch = Tensor::new(sample[..2].to_vec(), &device).unwrap();
+        let out = projection.apply(&batch).unwrap().to_vec2::<f32>().unwrap();
+        for (row, v) in out.iter().zip(&sample) {
+            for (a, b) in row.iter().zip(pca.project(v)) {
+                assert!((a - b).abs() < 1e-4);
+            }
+        }
+        assert!(Projection::load(&PostProcess::Truncate(64), 48, &device).is_err());
+        assert!(fit_pca(&sample, 49).is_err());
+    }
+}
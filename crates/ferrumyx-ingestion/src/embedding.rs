@@ -14,7 +14,7 @@
 
 use anyhow::{Context, Result};
 use reqwest::Client;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::process::Command;
 use std::path::PathBuf;
 use std::sync::OnceLock;
@@ -25,8 +25,10 @@ use uuid::Uuid;
 // Internal embedder module
 use crate::embed::config::EmbeddingSpeedMode;
 use crate::embed::EmbeddingConfig as RustEmbedConfig;
+use crate::embed::{DeviceSpec, PostProcess, Precision};
 
 use crate::repository::IngestionRepository;
+use ferrumyx_db::schema::Chunk;
 
 // ── Backend config ────────────────────────────────────────────────────────────
 
@@ -338,9 +340,13 @@ impl EmbeddingClient {
             // backend (remote and local) follows one consistent write path.
             normalize: false,
             pooling: crate::embed::PoolingStrategy::Mean,
+            post_process: resolve_embed_post_process()?,
             use_gpu,
+            device: resolve_embed_device(),
+            dtype: resolve_embed_dtype(),
             cache_size: 1000,
             cache_dir: resolve_embed_cache_dir(),
+            offline: resolve_embed_offline(),
         };
 
         if EMBEDDER.get().is_none() {
@@ -391,6 +397,17 @@ impl EmbeddingClient {
                         )?
                     }
                 };
+                // The chunks table is sized from `embedding_dim`; a reduced
+                // width has to match it rather than fail on every insert.
+                if embedder.output_dim() != self.cfg.dim {
+                    return Err(anyhow::anyhow!(
+                        "Rust embedder produces {}-dim vectors (post-process {}) but embedding_dim is {}; set [embedding].embedding_dim = {}",
+                        embedder.output_dim(),
+                        config.post_process,
+                        self.cfg.dim,
+                        embedder.output_dim()
+                    ));
+                }
                 let _ = EMBEDDER.set(Arc::new(Mutex::new(embedder)));
             }
         }
@@ -431,6 +448,9 @@ fn resolve_embed_use_gpu() -> bool {
 }
 
 fn resolve_embed_use_gpu_uncached() -> bool {
+    if cfg!(feature = "metal") && candle_core::utils::metal_is_available() {
+        return true;
+    }
     let has_nvidia = detect_nvidia_gpu();
     if has_nvidia && !command_success("nvcc", &["--version"]) {
         let _ = try_install_cuda_toolkit_once();
@@ -438,6 +458,41 @@ fn resolve_embed_use_gpu_uncached() -> bool {
     has_nvidia || command_success("nvcc", &["--version"])
 }
 
+fn resolve_embed_device() -> DeviceSpec {
+    match std::env::var(DeviceSpec::ENV_VAR) {
+        Ok(raw) if !raw.trim().is_empty() => DeviceSpec::parse(&raw).unwrap_or_else(|| {
+            warn!(
+                env_var = DeviceSpec::ENV_VAR,
+                value = %raw,
+                "Invalid embedding device; falling back to automatic selection"
+            );
+            DeviceSpec::Auto
+        }),
+        _ => DeviceSpec::Auto,
+    }
+}
+
+fn resolve_embed_post_process() -> Result<PostProcess> {
+    match std::env::var(PostProcess::ENV_VAR) {
+        Ok(raw) => PostProcess::parse(&raw).map_err(|e| anyhow::anyhow!(e)),
+        Err(_) => Ok(PostProcess::None),
+    }
+}
+
+fn resolve_embed_dtype() -> Precision {
+    match std::env::var(Precision::ENV_VAR) {
+        Ok(raw) if !raw.trim().is_empty() => Precision::parse(&raw).unwrap_or_else(|| {
+            warn!(
+                env_var = Precision::ENV_VAR,
+                value = %raw,
+                "Invalid embedding dtype; falling back to f32"
+            );
+            Precision::F32
+        }),
+        _ => Precision::F32,
+    }
+}
+
 fn resolve_embed_speed_mode() -> EmbeddingSpeedMode {
     static RESOLVED_SPEED_MODE: OnceLock<EmbeddingSpeedMode> = OnceLock::new();
 
@@ -465,7 +520,36 @@ fn resolve_embed_speed_mode_auto() -> EmbeddingSpeedMode {
     }
 }
 
-fn resolve_embed_cache_dir() -> Option<String> {
+/// Tokenizer of `model` from the local model cache, without downloading.
+/// Looks in the embedder's cache first, then the default Hugging Face cache.
+pub fn cached_tokenizer(model: &str) -> Option<tokenizers::Tokenizer> {
+    let model = model.trim();
+    if model.is_empty() {
+        return None;
+    }
+    let path = resolve_embed_cache_dir()
+        .map(|dir| hf_hub::Cache::new(PathBuf::from(dir)))
+        .into_iter()
+        .chain([hf_hub::Cache::from_env()])
+        .find_map(|cache| cache.model(model.to_string()).get("tokenizer.json"))?;
+    match tokenizers::Tokenizer::from_file(&path) {
+        Ok(tokenizer) => Some(tokenizer),
+        Err(e) => {
+            warn!(path = %path.display(), "Failed to load cached tokenizer: {e}");
+            None
+        }
+    }
+}
+
+/// Whether `FERRUMYX_EMBED_OFFLINE` forbids model downloads.
+pub(crate) fn resolve_embed_offline() -> bool {
+    std::env::var("FERRUMYX_EMBED_OFFLINE")
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+/// Hugging Face cache the Candle embedder and cross-encoder load from.
+pub fn resolve_embed_cache_dir() -> Option<String> {
     if let Ok(raw) = std::env::var("FERRUMYX_EMBED_CACHE_DIR") {
         let trimmed = raw.trim();
         if !trimmed.is_empty() {
@@ -677,6 +761,20 @@ pub async fn embed_pending_chunks_for_papers(
     client: &EmbeddingClient,
     repo: &IngestionRepository,
     paper_ids: &[Uuid],
+) -> Result<usize> {
+    embed_pending_chunks_for_papers_bounded(client, repo, paper_ids, usize::MAX).await
+}
+
+/// Same as [`embed_pending_chunks_for_papers`], but never holds more than
+/// `max_buffer_bytes` of pending chunk content in memory: chunks are read back
+/// from the DB paper by paper and flushed through the embedder whenever the
+/// buffered content reaches the budget.
+#[instrument(skip(client, repo, paper_ids), fields(n_papers = paper_ids.len()))]
+pub async fn embed_pending_chunks_for_papers_bounded(
+    client: &EmbeddingClient,
+    repo: &IngestionRepository,
+    paper_ids: &[Uuid],
+    max_buffer_bytes: usize,
 ) -> Result<usize> {
     if paper_ids.is_empty() {
         return Ok(0);
@@ -685,6 +783,9 @@ pub async fn embed_pending_chunks_for_papers(
     let mut seen = HashSet::with_capacity(paper_ids.len());
     let cap = resolve_throughput_embedding_chunk_cap();
     let mut pending_chunks: Vec<(Uuid, String)> = Vec::new();
+    let mut pending_bytes = 0usize;
+    let mut total_embedded = 0usize;
+    let mut total_pending = 0usize;
 
     for paper_id in paper_ids {
         if !seen.insert(*paper_id) {
@@ -705,21 +806,36 @@ pub async fn embed_pending_chunks_for_papers(
                 chunks.truncate(per_paper_cap);
             }
         }
+        total_pending += chunks.len();
+        pending_bytes += chunks.iter().map(|(_, text)| text.len()).sum::<usize>();
         pending_chunks.extend(chunks);
+
+        if pending_bytes >= max_buffer_bytes {
+            debug!(
+                n_chunks = pending_chunks.len(),
+                pending_bytes, max_buffer_bytes, "Flushing embedding buffer at memory budget"
+            );
+            total_embedded +=
+                embed_chunk_updates(client, repo, std::mem::take(&mut pending_chunks)).await?;
+            pending_bytes = 0;
+        }
     }
 
-    if pending_chunks.is_empty() {
+    if total_pending == 0 {
         debug!("No pending chunks across requested papers");
         return Ok(0);
     }
 
     info!(
         n_papers = seen.len(),
-        n_chunks = pending_chunks.len(),
+        n_chunks = total_pending,
         batch_size = client.cfg.batch_size,
         "Embedding chunks across papers"
     );
-    embed_chunk_updates(client, repo, pending_chunks).await
+    if !pending_chunks.is_empty() {
+        total_embedded += embed_chunk_updates(client, repo, pending_chunks).await?;
+    }
+    Ok(total_embedded)
 }
 
 async fn embed_chunk_updates(
@@ -749,15 +865,7 @@ async fn embed_chunk_updates(
             );
         }
 
-        let updates: Vec<(Uuid, Vec<f32>)> = batch_ids
-            .iter()
-            .zip(vecs.into_iter())
-            .map(|(chunk_id, embedding)| {
-                let norm = l2_norm(&embedding);
-                let normalized = embedding.into_iter().map(|x| x / norm).collect();
-                (*chunk_id, normalized)
-            })
-            .collect();
+        let updates = normalized_updates(&batch_ids, vecs);
 
         match repo.bulk_update_embeddings(&updates).await {
             Ok(n) => {
@@ -772,6 +880,163 @@ async fn embed_chunk_updates(
     Ok(total_embedded)
 }
 
+fn normalized_updates(chunk_ids: &[Uuid], vecs: Vec<Vec<f32>>) -> Vec<(Uuid, Vec<f32>)> {
+    chunk_ids
+        .iter()
+        .zip(vecs)
+        .map(|(chunk_id, embedding)| {
+            let norm = l2_norm(&embedding);
+            let normalized = embedding.into_iter().map(|x| x / norm).collect();
+            (*chunk_id, normalized)
+        })
+        .collect()
+}
+
+// ── Backfill: chunks left without embeddings ──────────────────────────────────
+
+/// Chunks younger than this are left to the ingestion run that stored them.
+const BACKFILL_MIN_CHUNK_AGE: Duration = Duration::from_secs(60);
+const BACKFILL_MAX_ATTEMPTS: u32 = 4;
+const BACKFILL_RETRY_BASE: Duration = Duration::from_millis(500);
+
+/// Progress of [`embed_missing_chunks`], reported after every batch.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct EmbedBackfillProgress {
+    /// Chunks without an embedding when the run started, not counting ones
+    /// too recent to touch.
+    pub pending: u64,
+    pub embedded: usize,
+    pub batches: usize,
+    /// Chunks the embedder returned no vector for.
+    pub skipped: usize,
+    /// Set when a batch still failed after retries; the run stops there and
+    /// a later run resumes from the remaining chunks.
+    pub error: Option<String>,
+}
+
+/// Embed every stored chunk whose embedding is NULL, `batch_size` chunks at
+/// a time, updating the rows in place.
+///
+/// Failing batches are retried with exponential backoff. Chunks stored less
+/// than a minute ago are skipped so the pass can run alongside an ingestion
+/// that is still embedding its own chunks.
+#[instrument(skip(repo, cfg, on_progress), fields(backend = ?cfg.backend))]
+pub async fn embed_missing_chunks(
+    repo: &IngestionRepository,
+    cfg: EmbeddingConfig,
+    batch_size: usize,
+    mut on_progress: impl FnMut(&EmbedBackfillProgress),
+) -> Result<EmbedBackfillProgress> {
+    let batch_size = batch_size.clamp(1, 1_024);
+    let client = EmbeddingClient::new(EmbeddingConfig { batch_size, ..cfg });
+    let cutoff =
+        chrono::Utc::now() - chrono::Duration::from_std(BACKFILL_MIN_CHUNK_AGE).unwrap_or_default();
+    let mut progress = EmbedBackfillProgress {
+        pending: repo
+            .count_chunks_missing_embeddings(Some(cutoff))
+            .await
+            .context("count pending chunks failed")?,
+        ..Default::default()
+    };
+    on_progress(&progress);
+
+    // Skipped chunks stay NULL, so each page is widened by their count to
+    // keep them from crowding out chunks not yet tried.
+    let mut attempted = HashSet::new();
+    loop {
+        let batch: Vec<(Uuid, String)> = repo
+            .find_chunks_missing_embeddings(cutoff, batch_size + progress.skipped)
+            .await
+            .context("fetch pending chunks failed")?
+            .into_iter()
+            .filter(|(id, _)| attempted.insert(*id))
+            .take(batch_size)
+            .collect();
+        if batch.is_empty() {
+            break;
+        }
+
+        let batch_ids: Vec<Uuid> = batch.iter().map(|(id, _)| *id).collect();
+        let batch_texts: Vec<String> = batch.into_iter().map(|(_, t)| t).collect();
+        let vecs = match embed_batch_with_retry(&client, &batch_texts).await {
+            Ok(v) => v,
+            Err(e) => {
+                warn!(
+                    embedded = progress.embedded,
+                    "Embedding backfill stopped: {e}"
+                );
+                progress.error = Some(e.to_string());
+                on_progress(&progress);
+                return Ok(progress);
+            }
+        };
+        let updates = normalized_updates(&batch_ids, vecs);
+        let updated = repo
+            .bulk_update_embeddings(&updates)
+            .await
+            .context("embedding update failed")?;
+        progress.embedded += updated;
+        progress.skipped += batch_ids.len().saturating_sub(updated);
+        progress.batches += 1;
+        on_progress(&progress);
+    }
+
+    info!(
+        embedded = progress.embedded,
+        skipped = progress.skipped,
+        batches = progress.batches,
+        "Embedding backfill complete"
+    );
+    Ok(progress)
+}
+
+/// Switch the chunks table to `cfg.dim`-wide vectors and re-embed it with
+/// `cfg`, for moving to an embedding model of another width.
+///
+/// Existing vectors are cleared by
+/// [`Database::migrate_embedding_dim`](ferrumyx_db::Database::migrate_embedding_dim)
+/// and then refilled by [`embed_missing_chunks`]. Calling it again after a
+/// failed pass resumes the re-embedding without clearing anything.
+pub async fn migrate_embedding_dim(
+    repo: &IngestionRepository,
+    cfg: EmbeddingConfig,
+    batch_size: usize,
+    on_progress: impl FnMut(&EmbedBackfillProgress),
+) -> Result<EmbedBackfillProgress> {
+    let db = repo.db();
+    if db.embedding_dim() != cfg.dim {
+        let cleared = db
+            .migrate_embedding_dim(cfg.dim)
+            .await
+            .context("re-creating chunks table failed")?;
+        info!(
+            cleared,
+            dim = cfg.dim,
+            "Cleared chunk embeddings for re-embedding"
+        );
+    }
+    embed_missing_chunks(repo, cfg, batch_size, on_progress).await
+}
+
+async fn embed_batch_with_retry(
+    client: &EmbeddingClient,
+    texts: &[String],
+) -> Result<Vec<Vec<f32>>> {
+    let mut attempt = 0;
+    loop {
+        match client.embed_batch(texts).await {
+            Ok(vecs) => return Ok(vecs),
+            Err(e) if attempt + 1 < BACKFILL_MAX_ATTEMPTS => {
+                let delay = BACKFILL_RETRY_BASE * 2u32.pow(attempt);
+                warn!(attempt, ?delay, "Embedding batch failed, retrying: {e}");
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
 fn resolve_throughput_embedding_chunk_cap() -> Option<usize> {
     std::env::var("FERRUMYX_EMBED_THROUGHPUT_MAX_CHUNKS_PER_PAPER")
         .ok()
@@ -784,16 +1049,23 @@ fn l2_norm(v: &[f32]) -> f32 {
     s.sqrt().max(1e-10)
 }
 
-// ── Hybrid search (FTS + vector RRF) — Ferrumyx Runtime Core-aligned ──────────────────────
+// ── Hybrid search (BM25 keyword + vector, RRF or alpha blend) ─────────────────────────────
 
 #[derive(Debug, Clone)]
 pub struct SearchResult {
     pub chunk_id: Uuid,
     pub paper_id: Uuid,
     pub content: String,
+    /// Fused score, normalised so the best result is 1.0.
     pub score: f32,
     pub fts_rank: Option<u32>,
     pub vector_rank: Option<u32>,
+    /// BM25 score from the keyword index.
+    pub fts_score: Option<f32>,
+    /// Cosine similarity to the query embedding.
+    pub vector_score: Option<f32>,
+    /// Relevance assigned by the re-ranking stage, when it ran.
+    pub rerank_score: Option<f32>,
 }
 
 impl SearchResult {
@@ -808,6 +1080,9 @@ pub struct HybridSearchConfig {
     pub pre_fusion_limit: usize,
     pub use_fts: bool,
     pub use_vector: bool,
+    /// Fuse by `alpha * vector + (1 - alpha) * keyword` over min-max
+    /// normalised scores instead of reciprocal-rank fusion.
+    pub alpha: Option<f32>,
 }
 
 impl Default for HybridSearchConfig {
@@ -818,11 +1093,16 @@ impl Default for HybridSearchConfig {
             pre_fusion_limit: 100,
             use_fts: true,
             use_vector: true,
+            alpha: None,
         }
     }
 }
 
-/// Hybrid search over paper_chunks: FTS + vector → RRF fusion.
+/// Hybrid search over chunks: BM25 keyword + vector retrieval, then fusion.
+///
+/// Keyword retrieval catches exact identifiers (`KRAS G12D`, `NCT04330664`)
+/// that embeddings blur. If it fails while the vector leg is usable, the
+/// vector results are returned alone.
 pub async fn hybrid_search(
     repo: &IngestionRepository,
     query_text: &str,
@@ -830,102 +1110,132 @@ pub async fn hybrid_search(
     cfg: &HybridSearchConfig,
 ) -> Result<Vec<SearchResult>> {
     use ferrumyx_db::chunks::ChunkRepository;
-    use std::collections::HashMap;
 
-    let mut fts_rows: Vec<(Uuid, Uuid, String, i64)> = vec![];
-    let mut vector_rows: Vec<(Uuid, Uuid, String, i64)> = vec![];
+    let chunk_repo = ChunkRepository::new(repo.db());
+    let query_vec = query_vec.filter(|_| cfg.use_vector);
 
-    // 1. Full-text search via LanceDB
+    // 1. Keyword search via the LanceDB full-text index
+    let mut fts_rows = Vec::new();
     if cfg.use_fts {
-        let chunk_repo = ChunkRepository::new(repo.db());
-        let all_chunks = chunk_repo
-            .list(0, cfg.pre_fusion_limit)
+        match chunk_repo
+            .search_keyword(query_text, cfg.pre_fusion_limit)
             .await
-            .context("FTS query failed")?;
+        {
+            Ok(rows) => fts_rows = rows,
+            Err(e) if query_vec.is_some() => {
+                warn!("Keyword search failed, using vector results only: {e}");
+            }
+            Err(e) => return Err(e).context("FTS query failed"),
+        }
+    }
 
-        let query_lower = query_text.to_lowercase();
-        let mut matches: Vec<_> = all_chunks
+    // 2. Vector search via LanceDB
+    let mut vector_rows = Vec::new();
+    if let Some(qv) = query_vec {
+        let norm = l2_norm(&qv);
+        let normalised: Vec<f32> = qv.iter().map(|x| x / norm).collect();
+        vector_rows = chunk_repo
+            .search_similar_scored(&normalised, cfg.pre_fusion_limit)
+            .await
+            .context("Vector search query failed")?
             .into_iter()
-            .filter(|c| c.content.to_lowercase().contains(&query_lower))
-            .enumerate()
-            .map(|(i, c)| (c.id, c.paper_id, c.content, i as i64 + 1))
-            .take(cfg.pre_fusion_limit)
+            // Stored embeddings are unit length, so squared L2 is 2 - 2cos.
+            .map(|(chunk, distance)| (chunk, 1.0 - distance / 2.0))
             .collect();
-
-        fts_rows.append(&mut matches);
     }
 
-    // 2. Vector search via LanceDB
-    if cfg.use_vector {
-        if let Some(ref qv) = query_vec {
-            let norm = l2_norm(qv);
-            let normalised: Vec<f32> = qv.iter().map(|x| x / norm).collect();
-
-            let chunk_repo = ChunkRepository::new(repo.db());
-            let similar_chunks = chunk_repo
-                .search_similar(&normalised, cfg.pre_fusion_limit)
-                .await
-                .context("Vector search query failed")?;
+    Ok(fuse_search_results(fts_rows, vector_rows, cfg))
+}
 
-            vector_rows = similar_chunks
-                .into_iter()
-                .enumerate()
-                .map(|(i, c)| (c.id, c.paper_id, c.content, i as i64 + 1))
-                .collect();
-        }
+/// Merge keyword `(chunk, bm25)` and vector `(chunk, cosine)` hits, each
+/// ordered best first, then normalise → sort → truncate.
+fn fuse_search_results(
+    fts_rows: Vec<(Chunk, f32)>,
+    vector_rows: Vec<(Chunk, f32)>,
+    cfg: &HybridSearchConfig,
+) -> Vec<SearchResult> {
+    let mut by_id: HashMap<Uuid, SearchResult> = HashMap::new();
+    for (rank, (chunk, score)) in fts_rows.into_iter().enumerate() {
+        let hit = fused_entry(&mut by_id, chunk);
+        hit.fts_rank = Some(rank as u32 + 1);
+        hit.fts_score = Some(score);
+    }
+    for (rank, (chunk, score)) in vector_rows.into_iter().enumerate() {
+        let hit = fused_entry(&mut by_id, chunk);
+        hit.vector_rank = Some(rank as u32 + 1);
+        hit.vector_score = Some(score);
     }
 
-    // 3. RRF fusion
+    let fts_range = score_range(by_id.values().filter_map(|r| r.fts_score));
+    let vector_range = score_range(by_id.values().filter_map(|r| r.vector_score));
     let k = cfg.rrf_k as f32;
-    let mut scores: HashMap<Uuid, (Uuid, String, f32, Option<u32>, Option<u32>)> = HashMap::new();
-
-    for (chunk_id, paper_id, content, rank) in &fts_rows {
-        let rrf = 1.0 / (k + *rank as f32);
-        let entry =
-            scores
-                .entry(*chunk_id)
-                .or_insert((*paper_id, content.clone(), 0.0, None, None));
-        entry.2 += rrf;
-        entry.3 = Some(*rank as u32);
-    }
-    for (chunk_id, paper_id, content, rank) in &vector_rows {
-        let rrf = 1.0 / (k + *rank as f32);
-        let entry =
-            scores
-                .entry(*chunk_id)
-                .or_insert((*paper_id, content.clone(), 0.0, None, None));
-        entry.2 += rrf;
-        entry.4 = Some(*rank as u32);
-    }
-
-    // 4. Normalise → sort → truncate
-    let max_score = scores.values().map(|v| v.2).fold(0.0f32, f32::max);
-    let mut results: Vec<SearchResult> = scores
-        .into_iter()
-        .map(
-            |(chunk_id, (paper_id, content, score, fts_rank, vector_rank))| SearchResult {
-                chunk_id,
-                paper_id,
-                content,
-                score: if max_score > 0.0 {
-                    score / max_score
-                } else {
-                    0.0
-                },
-                fts_rank,
-                vector_rank,
-            },
-        )
+    for hit in by_id.values_mut() {
+        hit.score = match cfg.alpha {
+            Some(alpha) => {
+                let alpha = alpha.clamp(0.0, 1.0);
+                alpha * min_max(hit.vector_score, vector_range)
+                    + (1.0 - alpha) * min_max(hit.fts_score, fts_range)
+            }
+            None => [hit.fts_rank, hit.vector_rank]
+                .into_iter()
+                .flatten()
+                .map(|rank| 1.0 / (k + rank as f32))
+                .sum(),
+        };
+    }
+
+    let max_score = by_id.values().map(|r| r.score).fold(0.0f32, f32::max);
+    let mut results: Vec<SearchResult> = by_id
+        .into_values()
+        .map(|mut r| {
+            r.score = if max_score > 0.0 {
+                r.score / max_score
+            } else {
+                0.0
+            };
+            r
+        })
         .collect();
 
     results.sort_by(|a, b| {
         b.score
             .partial_cmp(&a.score)
             .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.chunk_id.cmp(&b.chunk_id))
     });
     results.truncate(cfg.limit);
+    results
+}
 
-    Ok(results)
+fn fused_entry(by_id: &mut HashMap<Uuid, SearchResult>, chunk: Chunk) -> &mut SearchResult {
+    by_id.entry(chunk.id).or_insert_with(|| SearchResult {
+        chunk_id: chunk.id,
+        paper_id: chunk.paper_id,
+        content: chunk.content,
+        score: 0.0,
+        fts_rank: None,
+        vector_rank: None,
+        fts_score: None,
+        vector_score: None,
+        rerank_score: None,
+    })
+}
+
+fn score_range(scores: impl Iterator<Item = f32>) -> Option<(f32, f32)> {
+    scores.fold(None, |acc, s| match acc {
+        None => Some((s, s)),
+        Some((lo, hi)) => Some((lo.min(s), hi.max(s))),
+    })
+}
+
+/// Min-max normalise `score` into [0, 1]; a leg with one distinct score
+/// gives its hits 1.0, and a chunk the leg did not return gets 0.0.
+fn min_max(score: Option<f32>, range: Option<(f32, f32)>) -> f32 {
+    match (score, range) {
+        (Some(s), Some((lo, hi))) if hi > lo => (s - lo) / (hi - lo),
+        (Some(_), _) => 1.0,
+        (None, _) => 0.0,
+    }
 }
 
 #[cfg(test)]
@@ -949,5 +1259,240 @@ mod tests {
         let cfg = EmbeddingConfig::default();
         assert_eq!(cfg.backend, EmbeddingBackend::RustNative);
     }
+
+    /// Answer one connection per canned response; returns the number of
+    /// requests served.
+    async fn serve(
+        responses: Vec<(&'static str, String)>,
+    ) -> (String, tokio::task::JoinHandle<usize>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let mut served = 0;
+            for (status, body) in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                let body_len = loop {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                    if let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                        let head = String::from_utf8_lossy(&request[..end]).to_lowercase();
+                        let len = head
+                            .lines()
+                            .find_map(|l| l.strip_prefix("content-length:"))
+                            .and_then(|v| v.trim().parse::<usize>().ok())
+                            .unwrap_or(0);
+                        break end + 4 + len;
+                    }
+                };
+                while request.len() < body_len {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let head = format!(
+                    "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                socket.write_all(head.as_bytes()).await.unwrap();
+                socket.write_all(body.as_bytes()).await.unwrap();
+                socket.shutdown().await.unwrap();
+                served += 1;
+            }
+            served
+        });
+        (url, handle)
+    }
+
+    #[tokio::test]
+    async fn backfill_embeds_settled_chunks_and_retries_failed_batches() {
+        use ferrumyx_db::chunks::ChunkRepository;
+        use ferrumyx_db::schema::{Chunk, EMBEDDING_DIM};
+
+        let dir = std::env::temp_dir().join(format!("ferrumyx-embed-backfill-{}", Uuid::new_v4()));
+        let db = std::sync::Arc::new(ferrumyx_db::Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        let repo = IngestionRepository::new(db.clone());
+
+        let paper_id = Uuid::new_v4();
+        let mut chunks: Vec<Chunk> = (0..3)
+            .map(|i| {
+                let mut chunk = Chunk::new(paper_id, i, format!("KRAS G12D chunk {i}"));
+                chunk.created_at = chrono::Utc::now() - chrono::Duration::minutes(5);
+                chunk
+            })
+            .collect();
+        // Written by an ingestion that may still be embedding it.
+        chunks.push(Chunk::new(paper_id, 3, "MRTX1133 chunk".to_string()));
+        ChunkRepository::new(db)
+            .insert_batch(&chunks)
+            .await
+            .unwrap();
+
+        let vectors = |n: usize| {
+            serde_json::json!({
+                "data": vec![serde_json::json!({ "embedding": vec![1.0f32; EMBEDDING_DIM] }); n]
+            })
+            .to_string()
+        };
+        let (url, server) = serve(vec![
+            ("503 Service Unavailable", "quota exceeded".to_string()),
+            ("200 OK", vectors(2)),
+            ("200 OK", vectors(1)),
+        ])
+        .await;
+        let cfg = EmbeddingConfig {
+            backend: EmbeddingBackend::OpenAiCompatible,
+            base_url: Some(url),
+            dim: EMBEDDING_DIM,
+            ..Default::default()
+        };
+
+        let mut updates = Vec::new();
+        let report = embed_missing_chunks(&repo, cfg, 2, |p| updates.push(p.clone()))
+            .await
+            .unwrap();
+        assert_eq!(report.pending, 3);
+        assert_eq!(report.embedded, 3);
+        assert_eq!(report.batches, 2);
+        assert_eq!(report.skipped, 0);
+        assert!(report.error.is_none());
+        assert_eq!(server.await.unwrap(), 3);
+        assert_eq!(
+            updates.iter().map(|p| p.embedded).collect::<Vec<_>>(),
+            vec![0, 2, 3]
+        );
+
+        let pending = repo.find_chunks_without_embeddings(paper_id).await.unwrap();
+        assert_eq!(pending, vec![(chunks[3].id, chunks[3].content.clone())]);
+        assert_eq!(repo.count_chunks_missing_embeddings(None).await.unwrap(), 1);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn migrate_embedding_dim_reembeds_chunks_at_new_width() {
+        use ferrumyx_db::chunks::ChunkRepository;
+        use ferrumyx_db::schema::{Chunk, EMBEDDING_DIM};
+
+        let dir = std::env::temp_dir().join(format!("ferrumyx-embed-dim-{}", Uuid::new_v4()));
+        let db = std::sync::Arc::new(ferrumyx_db::Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        let repo = IngestionRepository::new(db.clone());
+
+        let paper_id = Uuid::new_v4();
+        let chunks: Vec<Chunk> = (0..2)
+            .map(|i| {
+                let mut chunk = Chunk::new(paper_id, i, format!("KRAS G12D chunk {i}"));
+                chunk.created_at = chrono::Utc::now() - chrono::Duration::minutes(5);
+                chunk.embedding = Some(vec![1.0; EMBEDDING_DIM]);
+                chunk
+            })
+            .collect();
+        let chunk_repo = ChunkRepository::new(db.clone());
+        chunk_repo.insert_batch(&chunks).await.unwrap();
+
+        let body = serde_json::json!({
+            "data": vec![serde_json::json!({ "embedding": vec![1.0f32; 384] }); 2]
+        })
+        .to_string();
+        let (url, server) = serve(vec![("200 OK", body)]).await;
+        let cfg = EmbeddingConfig {
+            backend: EmbeddingBackend::OpenAiCompatible,
+            base_url: Some(url),
+            dim: 384,
+            ..Default::default()
+        };
+
+        let report = migrate_embedding_dim(&repo, cfg, 8, |_| {}).await.unwrap();
+        assert_eq!(report.pending, 2);
+        assert_eq!(report.embedded, 2);
+        assert!(report.error.is_none());
+        assert_eq!(server.await.unwrap(), 1);
+        assert_eq!(db.embedding_dim(), 384);
+        for chunk in chunk_repo.find_by_paper_id(paper_id).await.unwrap() {
+            assert_eq!(chunk.embedding.map(|v| v.len()), Some(384));
+        }
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn hybrid_search_ranks_exact_mutation_above_vector_neighbour() {
+        use ferrumyx_db::chunks::ChunkRepository;
+        use ferrumyx_db::schema::EMBEDDING_DIM;
+
+        let dir = std::env::temp_dir().join(format!("ferrumyx-hybrid-{}", Uuid::new_v4()));
+        let db = std::sync::Arc::new(ferrumyx_db::Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        let repo = IngestionRepository::new(db.clone());
+
+        let unit = |axes: &[usize]| {
+            let mut v = vec![0.0f32; EMBEDDING_DIM];
+            for &i in axes {
+                v[i] = 1.0;
+            }
+            let norm = l2_norm(&v);
+            v.into_iter().map(|x| x / norm).collect::<Vec<f32>>()
+        };
+        // The G12C chunk sits closest to the query embedding; only the
+        // keyword leg can tell the mutations apart.
+        let paper_id = Uuid::new_v4();
+        let mut g12c = Chunk::new(paper_id, 0, "Sotorasib binds the G12C pocket.".to_string());
+        g12c.embedding = Some(unit(&[0]));
+        let mut g12d = Chunk::new(paper_id, 1, "KRAS G12D drives PDAC.".to_string());
+        g12d.embedding = Some(unit(&[0, 1]));
+        let mut tp53 = Chunk::new(paper_id, 2, "TP53 loss follows.".to_string());
+        tp53.embedding = Some(unit(&[2]));
+        ChunkRepository::new(db)
+            .insert_batch(&[g12c.clone(), g12d.clone(), tp53])
+            .await
+            .unwrap();
+
+        let query = "KRAS G12D";
+        let vector_only = HybridSearchConfig {
+            limit: 3,
+            use_fts: false,
+            ..HybridSearchConfig::default()
+        };
+        let rows = hybrid_search(&repo, query, Some(unit(&[0])), &vector_only)
+            .await
+            .unwrap();
+        assert_eq!(rows[0].chunk_id, g12c.id);
+        assert!(rows.iter().all(|r| r.fts_score.is_none()));
+
+        let rrf = HybridSearchConfig {
+            limit: 3,
+            ..HybridSearchConfig::default()
+        };
+        let rows = hybrid_search(&repo, query, Some(unit(&[0])), &rrf)
+            .await
+            .unwrap();
+        assert_eq!(rows[0].chunk_id, g12d.id);
+        assert!(rows[0].is_hybrid());
+        assert_eq!(rows[0].score, 1.0);
+        assert!(rows[0].fts_score.is_some_and(|s| s > 0.0));
+        let cosine = rows[0].vector_score.unwrap();
+        assert!(
+            (cosine - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-3,
+            "{cosine}"
+        );
+        assert_eq!(rows[1].chunk_id, g12c.id);
+        assert_eq!(rows[1].fts_rank, None);
+
+        let blend = HybridSearchConfig {
+            limit: 3,
+            alpha: Some(0.5),
+            ..HybridSearchConfig::default()
+        };
+        let rows = hybrid_search(&repo, query, Some(unit(&[0])), &blend)
+            .await
+            .unwrap();
+        assert_eq!(rows[0].chunk_id, g12d.id);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
 }
 
@@ -0,0 +1,797 @@
+//! Full-text retrieval routes for a paper, tried one at a time.
+//!
+//! [`FullTextResolver`] walks the routes in [`FullTextRoute::ORDER`] and
+//! stops at the first document that reads as usable text:
+//!
+//! 1. the PDF link the search source returned with the paper;
+//! 2. Unpaywall's best open-access location (needs a contact email);
+//! 3. the publisher PDF link CrossRef holds for the DOI;
+//! 4. Europe PMC's open-access XML, then the PDF it renders (needs a PMCID);
+//! 5. Sci-Hub, only on resolvers built with [`FullTextResolver::with_scihub`].
+//!
+//! The resolver finds documents; the caller reads them (downloading and
+//! parsing PDFs, or parsing JATS XML). A route whose document does not read
+//! passes the paper on to the next route. The outcome names the route that
+//! produced the text, which the pipeline stores as the paper's
+//! `full_text_source`.
+//!
+//! Sci-Hub has no default: a resolver only reaches it when its caller
+//! passes a [`SciHubClient`], and that is logged as a warning. The pipeline
+//! does so only for jobs with `enable_scihub_fallback`, which defaults to
+//! off.
+
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant as StdInstant};
+
+use anyhow::{anyhow, bail};
+use serde::{Deserialize, Serialize};
+use tokio::time::{timeout, Instant};
+use tracing::{debug, info, warn};
+
+use crate::chunker::DocumentSection;
+use crate::models::PaperMetadata;
+use crate::sources::crossref::CrossRefClient;
+use crate::sources::europepmc::EuropePmcClient;
+use crate::sources::scihub::SciHubClient;
+use crate::sources::unpaywall::UnpaywallClient;
+use crate::sources::LiteratureSource;
+
+static SCIHUB_ADAPTIVE_STATE: OnceLock<Mutex<ScihubAdaptiveState>> = OnceLock::new();
+
+/// Outcomes of recent Sci-Hub attempts across all mirrors, used to back
+/// the whole fallback off while Sci-Hub is unreachable.
+#[derive(Debug, Default)]
+struct ScihubAdaptiveState {
+    attempts: u64,
+    successes: u64,
+    consecutive_failures: u64,
+    cooldown_until: Option<StdInstant>,
+    cooldown_skips: u64,
+}
+
+/// Where a paper's full text came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FullTextRoute {
+    #[serde(rename = "source_url")]
+    SourceUrl,
+    #[serde(rename = "unpaywall")]
+    Unpaywall,
+    #[serde(rename = "crossref")]
+    CrossRef,
+    #[serde(rename = "europepmc")]
+    EuropePmc,
+    #[serde(rename = "scihub")]
+    SciHub,
+}
+
+impl FullTextRoute {
+    /// The order routes are tried in.
+    pub const ORDER: [FullTextRoute; 5] = [
+        FullTextRoute::SourceUrl,
+        FullTextRoute::Unpaywall,
+        FullTextRoute::CrossRef,
+        FullTextRoute::EuropePmc,
+        FullTextRoute::SciHub,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FullTextRoute::SourceUrl => "source_url",
+            FullTextRoute::Unpaywall => "unpaywall",
+            FullTextRoute::CrossRef => "crossref",
+            FullTextRoute::EuropePmc => "europepmc",
+            FullTextRoute::SciHub => "scihub",
+        }
+    }
+}
+
+impl std::fmt::Display for FullTextRoute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A document a route found, for the caller to read.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FullTextDocument {
+    /// A PDF still to be downloaded.
+    PdfUrl(String),
+    /// A PDF already downloaded.
+    Pdf(Vec<u8>),
+    /// Europe PMC full-text XML.
+    JatsXml(String),
+}
+
+/// Text one route produced.
+#[derive(Debug)]
+pub struct ResolvedFullText {
+    pub route: FullTextRoute,
+    pub sections: Vec<DocumentSection>,
+}
+
+/// Result of [`FullTextResolver::resolve`].
+#[derive(Debug, Default)]
+pub struct FullTextOutcome {
+    /// `None` when no route produced usable text.
+    pub resolved: Option<ResolvedFullText>,
+    /// Routes tried without success, in order, with why. Routes that do
+    /// not apply to the paper (no DOI, no PMCID, Sci-Hub off) are absent.
+    pub failures: Vec<(FullTextRoute, anyhow::Error)>,
+}
+
+/// Tries a paper's full-text routes in order.
+pub struct FullTextResolver {
+    unpaywall: Option<UnpaywallClient>,
+    crossref: CrossRefClient,
+    europepmc: EuropePmcClient,
+    scihub: Option<SciHubClient>,
+    /// Budget for one lookup or one document read.
+    step_timeout: Duration,
+    /// Budget for all routes of one paper.
+    total_timeout: Duration,
+}
+
+impl FullTextResolver {
+    /// Resolver over the public CrossRef and Europe PMC APIs, without
+    /// Unpaywall or Sci-Hub.
+    pub fn new(step_timeout: Duration, total_timeout: Duration) -> Self {
+        Self {
+            unpaywall: None,
+            crossref: CrossRefClient::new(),
+            europepmc: EuropePmcClient::new(),
+            scihub: None,
+            step_timeout,
+            total_timeout: total_timeout.max(step_timeout),
+        }
+    }
+
+    pub fn with_unpaywall(mut self, client: UnpaywallClient) -> Self {
+        self.unpaywall = Some(client);
+        self
+    }
+
+    pub fn with_crossref(mut self, client: CrossRefClient) -> Self {
+        self.crossref = client;
+        self
+    }
+
+    pub fn with_europepmc(mut self, client: EuropePmcClient) -> Self {
+        self.europepmc = client;
+        self
+    }
+
+    /// Fall back to Sci-Hub once every open-access route has failed.
+    pub fn with_scihub(mut self, client: SciHubClient) -> Self {
+        warn!("Sci-Hub full-text fallback is enabled");
+        self.scihub = Some(client);
+        self
+    }
+
+    pub fn scihub_enabled(&self) -> bool {
+        self.scihub.is_some()
+    }
+
+    /// Try each route for `paper` until `read` turns one's document into
+    /// non-empty sections. `read` returning an error or no sections moves
+    /// on to the next route.
+    pub async fn resolve<F, Fut>(&self, paper: &PaperMetadata, read: F) -> FullTextOutcome
+    where
+        F: Fn(FullTextDocument) -> Fut,
+        Fut: Future<Output = anyhow::Result<Vec<DocumentSection>>>,
+    {
+        let deadline = Instant::now() + self.total_timeout;
+        let mut outcome = FullTextOutcome::default();
+        let mut tried_urls = HashSet::new();
+        for route in FullTextRoute::ORDER {
+            if Instant::now() >= deadline {
+                debug!(title = %paper.title, "Full-text budget spent before {route}");
+                break;
+            }
+            match self
+                .try_route(route, paper, &read, deadline, &mut tried_urls)
+                .await
+            {
+                Ok(Some(sections)) => {
+                    outcome.resolved = Some(ResolvedFullText { route, sections });
+                    break;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    debug!(title = %paper.title, "Full-text route {route} failed: {e:#}");
+                    outcome.failures.push((route, e));
+                }
+            }
+        }
+        outcome
+    }
+
+    /// Sections from `route`, or `Ok(None)` when it does not apply.
+    async fn try_route<F, Fut>(
+        &self,
+        route: FullTextRoute,
+        paper: &PaperMetadata,
+        read: &F,
+        deadline: Instant,
+        tried_urls: &mut HashSet<String>,
+    ) -> anyhow::Result<Option<Vec<DocumentSection>>>
+    where
+        F: Fn(FullTextDocument) -> Fut,
+        Fut: Future<Output = anyhow::Result<Vec<DocumentSection>>>,
+    {
+        let doi = paper
+            .doi
+            .as_deref()
+            .map(str::trim)
+            .filter(|d| !d.is_empty());
+        let url = match route {
+            FullTextRoute::SourceUrl => match paper.full_text_url.as_deref() {
+                Some(url) if !url.trim().is_empty() => url.trim().to_string(),
+                _ => return Ok(None),
+            },
+            FullTextRoute::Unpaywall => {
+                let (Some(unpaywall), Some(doi)) = (&self.unpaywall, doi) else {
+                    return Ok(None);
+                };
+                self.step(deadline, unpaywall.resolve_pdf_url(doi))
+                    .await?
+                    .ok_or_else(|| anyhow!("no open-access PDF location"))?
+            }
+            FullTextRoute::CrossRef => {
+                let Some(doi) = doi else {
+                    return Ok(None);
+                };
+                self.step(deadline, self.crossref.publisher_pdf_url(doi))
+                    .await?
+                    .ok_or_else(|| anyhow!("no publisher PDF link"))?
+            }
+            FullTextRoute::EuropePmc => {
+                let Some(pmcid) = paper.pmcid.as_deref() else {
+                    return Ok(None);
+                };
+                return self
+                    .try_europepmc(pmcid, read, deadline, tried_urls)
+                    .await
+                    .map(Some);
+            }
+            FullTextRoute::SciHub => {
+                let Some(scihub) = &self.scihub else {
+                    return Ok(None);
+                };
+                let Some(identifier) = doi.or(paper.pmid.as_deref()) else {
+                    return Ok(None);
+                };
+                return self
+                    .try_scihub(scihub, identifier, read, deadline)
+                    .await
+                    .map(Some);
+            }
+        };
+        self.read_pdf_url(url, read, deadline, tried_urls)
+            .await
+            .map(Some)
+    }
+
+    async fn try_europepmc<F, Fut>(
+        &self,
+        pmcid: &str,
+        read: &F,
+        deadline: Instant,
+        tried_urls: &mut HashSet<String>,
+    ) -> anyhow::Result<Vec<DocumentSection>>
+    where
+        F: Fn(FullTextDocument) -> Fut,
+        Fut: Future<Output = anyhow::Result<Vec<DocumentSection>>>,
+    {
+        let xml = self
+            .step(deadline, self.europepmc.fetch_full_text(pmcid))
+            .await;
+        if let Ok(Some(xml)) = xml {
+            match self
+                .read_document(FullTextDocument::JatsXml(xml), read, deadline)
+                .await
+            {
+                Ok(sections) => return Ok(sections),
+                Err(e) => debug!(pmcid, "Europe PMC XML did not read: {e:#}"),
+            }
+        }
+        let url = self
+            .europepmc
+            .pdf_render_url(pmcid)
+            .ok_or_else(|| anyhow!("{pmcid} is not a PMCID"))?;
+        self.read_pdf_url(url, read, deadline, tried_urls).await
+    }
+
+    async fn try_scihub<F, Fut>(
+        &self,
+        scihub: &SciHubClient,
+        identifier: &str,
+        read: &F,
+        deadline: Instant,
+    ) -> anyhow::Result<Vec<DocumentSection>>
+    where
+        F: Fn(FullTextDocument) -> Fut,
+        Fut: Future<Output = anyhow::Result<Vec<DocumentSection>>>,
+    {
+        if !scihub_adaptive_should_attempt() {
+            bail!("skipped while Sci-Hub is backing off");
+        }
+        let budget = scihub_adaptive_step_timeout(self.step_timeout);
+        let deadline = deadline.min(Instant::now() + budget);
+        let pdf = match self.step(deadline, scihub.download_pdf(identifier)).await {
+            Ok(Some(pdf)) => pdf,
+            Ok(None) => {
+                scihub_adaptive_record_attempt(false);
+                bail!("no mirror had the PDF");
+            }
+            Err(e) => {
+                scihub_adaptive_record_attempt(false);
+                return Err(e);
+            }
+        };
+        scihub_adaptive_record_attempt(true);
+        info!(
+            identifier,
+            bytes = pdf.len(),
+            "Full text downloaded through the Sci-Hub fallback"
+        );
+        self.read_document(FullTextDocument::Pdf(pdf), read, deadline)
+            .await
+    }
+
+    async fn read_pdf_url<F, Fut>(
+        &self,
+        url: String,
+        read: &F,
+        deadline: Instant,
+        tried_urls: &mut HashSet<String>,
+    ) -> anyhow::Result<Vec<DocumentSection>>
+    where
+        F: Fn(FullTextDocument) -> Fut,
+        Fut: Future<Output = anyhow::Result<Vec<DocumentSection>>>,
+    {
+        if !tried_urls.insert(url.clone()) {
+            bail!("{url} was already tried");
+        }
+        self.read_document(FullTextDocument::PdfUrl(url), read, deadline)
+            .await
+    }
+
+    async fn read_document<F, Fut>(
+        &self,
+        document: FullTextDocument,
+        read: &F,
+        deadline: Instant,
+    ) -> anyhow::Result<Vec<DocumentSection>>
+    where
+        F: Fn(FullTextDocument) -> Fut,
+        Fut: Future<Output = anyhow::Result<Vec<DocumentSection>>>,
+    {
+        let sections = self.step(deadline, read(document)).await?;
+        if sections.is_empty() {
+            bail!("document has no usable text");
+        }
+        Ok(sections)
+    }
+
+    /// `work` bounded by the step timeout and what is left of `deadline`.
+    async fn step<T>(
+        &self,
+        deadline: Instant,
+        work: impl Future<Output = anyhow::Result<T>>,
+    ) -> anyhow::Result<T> {
+        let budget = self
+            .step_timeout
+            .min(deadline.saturating_duration_since(Instant::now()));
+        timeout(budget, work)
+            .await
+            .map_err(|_| anyhow!("timed out after {}ms", budget.as_millis()))?
+    }
+}
+
+fn resolve_scihub_adaptive_enabled() -> bool {
+    std::env::var("FERRUMYX_SCIHUB_ADAPTIVE_ENABLED")
+        .ok()
+        .is_none_or(|v| !(v == "0" || v.eq_ignore_ascii_case("false")))
+}
+
+fn resolve_scihub_adaptive_fail_streak() -> u64 {
+    std::env::var("FERRUMYX_SCIHUB_ADAPTIVE_FAIL_STREAK")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(8)
+        .clamp(3, 200)
+}
+
+fn resolve_scihub_adaptive_backoff_secs() -> u64 {
+    std::env::var("FERRUMYX_SCIHUB_ADAPTIVE_BACKOFF_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(300)
+        .clamp(15, 3600)
+}
+
+fn resolve_scihub_adaptive_probe_every() -> u64 {
+    std::env::var("FERRUMYX_SCIHUB_ADAPTIVE_PROBE_EVERY")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(10)
+        .clamp(1, 256)
+}
+
+fn resolve_scihub_adaptive_min_step_timeout_secs() -> u64 {
+    std::env::var("FERRUMYX_SCIHUB_ADAPTIVE_MIN_STEP_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(3)
+        .clamp(2, 60)
+}
+
+fn scihub_adaptive_should_attempt() -> bool {
+    if !resolve_scihub_adaptive_enabled() {
+        return true;
+    }
+    let now = StdInstant::now();
+    let state = SCIHUB_ADAPTIVE_STATE.get_or_init(|| Mutex::new(ScihubAdaptiveState::default()));
+    let Ok(mut guard) = state.lock() else {
+        return true;
+    };
+    if let Some(until) = guard.cooldown_until {
+        if now < until {
+            let probe_every = resolve_scihub_adaptive_probe_every();
+            guard.cooldown_skips = guard.cooldown_skips.saturating_add(1);
+            return guard.cooldown_skips % probe_every == 0;
+        }
+        guard.cooldown_until = None;
+        guard.cooldown_skips = 0;
+    }
+    true
+}
+
+fn scihub_adaptive_step_timeout(base: Duration) -> Duration {
+    if !resolve_scihub_adaptive_enabled() {
+        return base;
+    }
+    let min_secs = resolve_scihub_adaptive_min_step_timeout_secs();
+    let state = SCIHUB_ADAPTIVE_STATE.get_or_init(|| Mutex::new(ScihubAdaptiveState::default()));
+    let Ok(guard) = state.lock() else {
+        return base;
+    };
+    if guard.attempts < 3 {
+        return base;
+    }
+    let mut secs = base.as_secs().max(1);
+    let success_rate = if guard.attempts == 0 {
+        1.0
+    } else {
+        guard.successes as f64 / guard.attempts as f64
+    };
+    let fail_streak = resolve_scihub_adaptive_fail_streak();
+    if guard.consecutive_failures >= 3 {
+        secs = secs.min(min_secs.saturating_add(1));
+    }
+    if guard.consecutive_failures >= fail_streak / 2 {
+        secs = secs.min(min_secs.saturating_add(2));
+    }
+    if guard.attempts >= 20 {
+        if success_rate < 0.05 {
+            secs = secs.min(min_secs);
+        } else if success_rate < 0.15 {
+            secs = secs.min(min_secs.saturating_add(1));
+        }
+    }
+    Duration::from_secs(secs.max(1))
+}
+
+fn scihub_adaptive_record_attempt(success: bool) {
+    if !resolve_scihub_adaptive_enabled() {
+        return;
+    }
+    let state = SCIHUB_ADAPTIVE_STATE.get_or_init(|| Mutex::new(ScihubAdaptiveState::default()));
+    let Ok(mut guard) = state.lock() else {
+        return;
+    };
+    guard.attempts = guard.attempts.saturating_add(1);
+    if success {
+        guard.successes = guard.successes.saturating_add(1);
+        guard.consecutive_failures = 0;
+        guard.cooldown_until = None;
+        guard.cooldown_skips = 0;
+        return;
+    }
+
+    guard.consecutive_failures = guard.consecutive_failures.saturating_add(1);
+    if guard.consecutive_failures >= resolve_scihub_adaptive_fail_streak() {
+        guard.cooldown_until =
+            Some(StdInstant::now() + Duration::from_secs(resolve_scihub_adaptive_backoff_secs()));
+        guard.cooldown_skips = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{IngestionSource, SectionType};
+    use crate::sources::scihub::MirrorHealth;
+    use std::sync::Arc;
+
+    type Response = (&'static str, &'static str, String);
+
+    /// Local HTTP server answering requests in arrival order with the
+    /// responses `script` builds from its base URL, then 404s. Returns the
+    /// base URL and the request paths received so far.
+    async fn mock_http(
+        script: impl FnOnce(&str) -> Vec<Response>,
+    ) -> (String, Arc<Mutex<Vec<String>>>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let mut responses = script(&url).into_iter();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let request = String::from_utf8_lossy(&request).to_string();
+                let path = request.split_whitespace().nth(1).unwrap_or_default();
+                log.lock().unwrap().push(path.to_string());
+                let (status, content_type, body) =
+                    responses
+                        .next()
+                        .unwrap_or(("404 Not Found", "text/plain", String::new()));
+                let head = format!(
+                    "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                let _ = socket.write_all(head.as_bytes()).await;
+                let _ = socket.write_all(body.as_bytes()).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+        (url, seen)
+    }
+
+    fn paths(seen: &Mutex<Vec<String>>) -> Vec<String> {
+        seen.lock().unwrap().clone()
+    }
+
+    fn paper() -> PaperMetadata {
+        PaperMetadata {
+            doi: Some("10.1000/kras".to_string()),
+            pmid: Some("31234567".to_string()),
+            pmcid: Some("PMC123".to_string()),
+            title: "KRAS G12D drives pancreatic cancer".to_string(),
+            abstract_text: None,
+            authors: Vec::new(),
+            journal: None,
+            pub_date: None,
+            source: IngestionSource::PubMed,
+            open_access: false,
+            full_text_url: None,
+            is_review: false,
+            preprint: None,
+        }
+    }
+
+    fn resolver(url: &str) -> FullTextResolver {
+        FullTextResolver::new(Duration::from_secs(5), Duration::from_secs(30))
+            .with_unpaywall(
+                UnpaywallClient::new("curator@example.org")
+                    .with_base_url(format!("{url}/unpaywall")),
+            )
+            .with_crossref(CrossRefClient::new().with_base_url(format!("{url}/crossref")))
+            .with_europepmc(
+                EuropePmcClient::new()
+                    .with_base_urls(format!("{url}/epmc"), format!("{url}/render")),
+            )
+    }
+
+    /// Reads a "PDF" as the text after its `%PDF` header, and JATS XML as
+    /// the text inside `<article>`.
+    async fn read(document: FullTextDocument) -> anyhow::Result<Vec<DocumentSection>> {
+        let body = match document {
+            FullTextDocument::PdfUrl(url) => {
+                reqwest::get(&url).await?.error_for_status()?.text().await?
+            }
+            FullTextDocument::Pdf(bytes) => String::from_utf8(bytes)?,
+            FullTextDocument::JatsXml(xml) => xml,
+        };
+        let text = body
+            .strip_prefix("%PDF")
+            .or_else(|| body.strip_prefix("<article>"));
+        Ok(text
+            .map(|text| DocumentSection {
+                section_type: SectionType::Other,
+                heading: None,
+                text: text.trim().to_string(),
+                page_number: None,
+            })
+            .into_iter()
+            .collect())
+    }
+
+    fn failed_routes(outcome: &FullTextOutcome) -> Vec<FullTextRoute> {
+        outcome.failures.iter().map(|(route, _)| *route).collect()
+    }
+
+    #[tokio::test]
+    async fn each_failed_route_hands_over_to_the_next() {
+        let (url, seen) = mock_http(|url| {
+            vec![
+                ("404 Not Found", "text/plain", String::new()),
+                (
+                    "200 OK",
+                    "application/json",
+                    r#"{"best_oa_location": null}"#.to_string(),
+                ),
+                (
+                    "200 OK",
+                    "application/json",
+                    serde_json::json!({ "message": { "link": [
+                        { "URL": format!("{url}/publisher.pdf"), "content-type": "application/pdf" }
+                    ] } })
+                    .to_string(),
+                ),
+                ("403 Forbidden", "text/html", String::new()),
+                ("404 Not Found", "text/plain", String::new()),
+                (
+                    "200 OK",
+                    "text/html",
+                    "<html>Not available</html>".to_string(),
+                ),
+                (
+                    "200 OK",
+                    "text/html",
+                    r#"<embed type="application/pdf" src="/scihub/files/kras.pdf">"#.to_string(),
+                ),
+                ("200 OK", "application/pdf", "%PDF mirror copy".to_string()),
+            ]
+        })
+        .await;
+        let scihub = SciHubClient::new()
+            .with_retry_domains(vec![format!("{url}/scihub")])
+            .with_mirror_health(Arc::new(MirrorHealth::new(Duration::from_secs(60))))
+            .with_domain_parallelism(1);
+        let resolver = resolver(&url).with_scihub(scihub);
+        let paper = PaperMetadata {
+            full_text_url: Some(format!("{url}/source.pdf")),
+            ..paper()
+        };
+
+        let outcome = resolver.resolve(&paper, read).await;
+        let resolved = outcome.resolved.as_ref().unwrap();
+        assert_eq!(resolved.route, FullTextRoute::SciHub);
+        assert_eq!(resolved.sections[0].text, "mirror copy");
+        assert_eq!(
+            failed_routes(&outcome),
+            [
+                FullTextRoute::SourceUrl,
+                FullTextRoute::Unpaywall,
+                FullTextRoute::CrossRef,
+                FullTextRoute::EuropePmc,
+            ]
+        );
+        assert_eq!(
+            paths(&seen),
+            [
+                "/source.pdf",
+                "/unpaywall/10.1000/kras?email=curator%40example.org",
+                "/crossref/works/10.1000/kras",
+                "/publisher.pdf",
+                "/epmc/PMC123/fullTextXML",
+                "/render?accid=PMC123&blobtype=pdf",
+                "/scihub/10.1000/kras",
+                "/scihub/files/kras.pdf",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn the_first_route_with_text_wins() {
+        let (url, seen) = mock_http(|url| {
+            vec![
+                (
+                    "200 OK",
+                    "application/json",
+                    serde_json::json!({ "best_oa_location": { "url_for_pdf": format!("{url}/oa.pdf") } })
+                        .to_string(),
+                ),
+                ("200 OK", "application/pdf", "%PDF open access".to_string()),
+            ]
+        })
+        .await;
+
+        let outcome = resolver(&url).resolve(&paper(), read).await;
+        let resolved = outcome.resolved.unwrap();
+        assert_eq!(resolved.route, FullTextRoute::Unpaywall);
+        assert_eq!(resolved.sections[0].text, "open access");
+        assert!(outcome.failures.is_empty());
+        assert_eq!(paths(&seen).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn europepmc_xml_is_read_before_its_pdf() {
+        let (url, seen) = mock_http(|_| {
+            vec![(
+                "200 OK",
+                "application/xml",
+                "<article>KRAS G12D results".to_string(),
+            )]
+        })
+        .await;
+        let paper = PaperMetadata {
+            doi: None,
+            pmid: None,
+            ..paper()
+        };
+
+        let outcome = resolver(&url).resolve(&paper, read).await;
+        let resolved = outcome.resolved.unwrap();
+        assert_eq!(resolved.route, FullTextRoute::EuropePmc);
+        assert_eq!(resolved.sections[0].text, "KRAS G12D results");
+        assert_eq!(paths(&seen), ["/epmc/PMC123/fullTextXML"]);
+    }
+
+    #[tokio::test]
+    async fn scihub_is_not_tried_unless_enabled() {
+        let (url, seen) = mock_http(|_| {
+            vec![(
+                "200 OK",
+                "application/json",
+                r#"{"best_oa_location": null}"#.to_string(),
+            )]
+        })
+        .await;
+        let (mirror, mirror_seen) = mock_http(|_| Vec::new()).await;
+        let paper = PaperMetadata {
+            pmcid: None,
+            ..paper()
+        };
+
+        let disabled = resolver(&url);
+        assert!(!disabled.scihub_enabled());
+        let outcome = disabled.resolve(&paper, read).await;
+        assert!(outcome.resolved.is_none());
+        assert_eq!(
+            failed_routes(&outcome),
+            [FullTextRoute::Unpaywall, FullTextRoute::CrossRef]
+        );
+        assert_eq!(paths(&seen).len(), 2);
+
+        let enabled = FullTextResolver::new(Duration::from_secs(5), Duration::from_secs(30))
+            .with_crossref(CrossRefClient::new().with_base_url(format!("{url}/crossref")))
+            .with_scihub(
+                SciHubClient::new()
+                    .with_retry_domains(vec![mirror])
+                    .with_mirror_health(Arc::new(MirrorHealth::new(Duration::from_secs(60)))),
+            );
+        let outcome = enabled.resolve(&paper, read).await;
+        assert!(outcome.resolved.is_none());
+        assert_eq!(
+            failed_routes(&outcome),
+            [FullTextRoute::CrossRef, FullTextRoute::SciHub]
+        );
+        assert_eq!(paths(&mirror_seen), ["/10.1000/kras"]);
+    }
+
+    #[test]
+    fn routes_serialise_as_their_provenance_names() {
+        for route in FullTextRoute::ORDER {
+            assert_eq!(
+                serde_json::to_value(route).unwrap(),
+                serde_json::json!(route.as_str())
+            );
+        }
+    }
+}
@@ -0,0 +1,739 @@
+//! JATS full-text XML parsing.
+//! See ARCHITECTURE.md §2.5-2.7
+//!
+//! Europe PMC serves open-access articles as JATS from its `fullTextXML`
+//! endpoint. [`parse_jats`] turns one into the [`DocumentSection`]s the
+//! chunker consumes: the abstract, then every `<sec>` of the body, nested
+//! ones included, with its own title. Figure captions, tables and the
+//! reference list are collected separately so none of them end up in
+//! section text. References with a DOI are also returned as
+//! [`JatsCitation`]s, each with the body sentence that first cites it.
+
+use std::collections::HashMap;
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use crate::chunker::DocumentSection;
+use crate::models::SectionType;
+
+/// A JATS article split into prose sections and the material kept out of
+/// them.
+#[derive(Debug, Clone, Default)]
+pub struct ParsedJats {
+    pub title: Option<String>,
+    /// Abstract first, then body sections in document order.
+    pub sections: Vec<DocumentSection>,
+    /// One per `<fig>`, label first, e.g. `Figure 1 KRAS dependency. ...`.
+    pub figure_captions: Vec<String>,
+    /// One per `<table-wrap>`: label, caption, then one line per row with
+    /// cells separated by ` | `.
+    pub tables: Vec<String>,
+    /// One per `<ref>` in the reference list.
+    pub references: Vec<String>,
+    /// One per `<ref>` with a DOI, in reference-list order.
+    pub citations: Vec<JatsCitation>,
+}
+
+/// A reference-list entry with a DOI.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JatsCitation {
+    pub doi: String,
+    /// First body sentence with an `<xref ref-type="bibr">` to the entry.
+    pub context: Option<String>,
+}
+
+impl ParsedJats {
+    /// Sections to chunk: [`Self::sections`] followed by one
+    /// `FigureCaption` section per caption. Tables and references are left
+    /// out.
+    pub fn chunker_sections(&self) -> Vec<DocumentSection> {
+        let captions = self.figure_captions.iter().map(|caption| DocumentSection {
+            section_type: SectionType::FigureCaption,
+            heading: None,
+            text: caption.clone(),
+            page_number: None,
+        });
+        self.sections.iter().cloned().chain(captions).collect()
+    }
+}
+
+/// Parse a JATS article. Returns `None` when `xml` has no `<article>`
+/// element, e.g. an error page served in its place.
+pub fn parse_jats(xml: &str) -> Option<ParsedJats> {
+    let article = parse_tree(xml)?;
+    let mut parsed = ParsedJats::default();
+
+    let front = article.child("front");
+    parsed.title = front
+        .and_then(|f| f.child("article-meta"))
+        .and_then(|m| m.child("title-group"))
+        .and_then(|t| t.child("article-title"))
+        .map(|t| tidy(&prose(t)))
+        .filter(|t| !t.is_empty());
+
+    if let Some(text) = front.and_then(abstract_text) {
+        parsed.sections.push(DocumentSection {
+            section_type: SectionType::Abstract,
+            heading: Some("Abstract".to_string()),
+            text,
+            page_number: None,
+        });
+    }
+
+    if let Some(body) = article.child("body") {
+        // Paragraphs directly under <body>, as in short articles without
+        // sections.
+        let loose = tidy(&own_text(body));
+        if !loose.is_empty() {
+            parsed.sections.push(DocumentSection {
+                section_type: SectionType::Other,
+                heading: None,
+                text: loose,
+                page_number: None,
+            });
+        }
+        for sec in body.elements().filter(|e| e.name == "sec") {
+            push_section(sec, SectionType::Other, &mut parsed.sections);
+        }
+    }
+
+    collect_floats(&article, &mut parsed);
+    Some(parsed)
+}
+
+/// Push `sec` and then its subsections. A section whose `sec-type` and
+/// title give no type takes `inherited`, the type of its parent.
+fn push_section(sec: &Element, inherited: SectionType, out: &mut Vec<DocumentSection>) {
+    let heading = sec
+        .child("title")
+        .map(|t| tidy(&prose(t)))
+        .filter(|t| !t.is_empty());
+    let section_type = sec
+        .attr("sec-type")
+        .and_then(section_type_from_attr)
+        .or_else(|| {
+            heading
+                .as_deref()
+                .map(SectionType::from_heading)
+                .filter(|t| *t != SectionType::Other)
+        })
+        .unwrap_or(inherited);
+
+    let text = tidy(&own_text(sec));
+    if !text.is_empty() {
+        out.push(DocumentSection {
+            section_type: section_type.clone(),
+            heading,
+            text,
+            page_number: None,
+        });
+    }
+    for child in sec.elements().filter(|e| e.name == "sec") {
+        push_section(child, section_type.clone(), out);
+    }
+}
+
+/// `sec-type` values from the JATS tag library; combined values such as
+/// `materials|methods` take their first known part.
+fn section_type_from_attr(value: &str) -> Option<SectionType> {
+    value.split('|').find_map(|part| match part.trim() {
+        "intro" | "introduction" | "background" => Some(SectionType::Introduction),
+        "materials" | "methods" => Some(SectionType::Methods),
+        "results" => Some(SectionType::Results),
+        "discussion" => Some(SectionType::Discussion),
+        "conclusions" | "conclusion" => Some(SectionType::Conclusion),
+        "supplementary-material" => Some(SectionType::SupplementaryMethods),
+        _ => None,
+    })
+}
+
+/// Paragraphs of the article abstract, preferring one without an
+/// `abstract-type` over graphical abstracts and author summaries.
+fn abstract_text(front: &Element) -> Option<String> {
+    let meta = front.child("article-meta")?;
+    let abstracts: Vec<&Element> = meta.elements().filter(|e| e.name == "abstract").collect();
+    let chosen = abstracts
+        .iter()
+        .find(|a| a.attr("abstract-type").is_none())
+        .or_else(|| abstracts.first())?;
+    let mut paragraphs = Vec::new();
+    chosen.descendants("p", &mut paragraphs);
+    let text = tidy(
+        &paragraphs
+            .iter()
+            .map(|p| prose(p))
+            .collect::<Vec<_>>()
+            .join(" "),
+    );
+    (!text.is_empty()).then_some(text)
+}
+
+/// Text of `sec` outside its title and subsections.
+fn own_text(sec: &Element) -> String {
+    let mut out = String::new();
+    for child in &sec.children {
+        match child {
+            Node::Text(text) => out.push_str(text),
+            Node::Element(e)
+                if is_skipped(e) || matches!(e.name.as_str(), "title" | "label" | "sec") => {}
+            Node::Element(e) => {
+                out.push(' ');
+                prose_into(e, &mut out);
+                out.push(' ');
+            }
+        }
+    }
+    out
+}
+
+/// Figures, tables and references anywhere in the article.
+fn collect_floats(article: &Element, parsed: &mut ParsedJats) {
+    let mut figures = Vec::new();
+    article.descendants("fig", &mut figures);
+    for fig in figures {
+        let caption = labelled(fig, fig.child("caption").map(prose).unwrap_or_default());
+        if !caption.is_empty() {
+            parsed.figure_captions.push(caption);
+        }
+    }
+
+    let mut tables = Vec::new();
+    article.descendants("table-wrap", &mut tables);
+    for table in tables {
+        let mut lines = vec![labelled(
+            table,
+            table.child("caption").map(prose).unwrap_or_default(),
+        )];
+        let mut rows = Vec::new();
+        table.descendants("tr", &mut rows);
+        lines.extend(rows.iter().map(|row| {
+            row.elements()
+                .filter(|c| matches!(c.name.as_str(), "td" | "th"))
+                .map(|c| tidy(&prose(c)))
+                .collect::<Vec<_>>()
+                .join(" | ")
+        }));
+        if let Some(foot) = table.child("table-wrap-foot") {
+            lines.push(tidy(&prose(foot)));
+        }
+        lines.retain(|l| !l.is_empty());
+        if !lines.is_empty() {
+            parsed.tables.push(lines.join("\n"));
+        }
+    }
+
+    let contexts = article
+        .child("body")
+        .map(citing_sentences)
+        .unwrap_or_default();
+    let mut refs = Vec::new();
+    article.descendants("ref", &mut refs);
+    for r in refs {
+        let mut text = String::new();
+        for child in r.elements().filter(|c| c.name != "label") {
+            spaced_into(child, &mut text);
+        }
+        let text = tidy(&text);
+        if !text.is_empty() {
+            parsed.references.push(text);
+        }
+
+        let mut ids = Vec::new();
+        r.descendants("pub-id", &mut ids);
+        let doi = ids
+            .iter()
+            .find(|id| id.attr("pub-id-type") == Some("doi"))
+            .map(|id| tidy(&prose(id)))
+            .filter(|doi| !doi.is_empty());
+        if let Some(doi) = doi {
+            parsed.citations.push(JatsCitation {
+                doi,
+                context: r.attr("id").and_then(|id| contexts.get(id)).cloned(),
+            });
+        }
+    }
+}
+
+/// First sentence citing each reference id, from the body's paragraphs.
+fn citing_sentences(body: &Element) -> HashMap<String, String> {
+    let mut paragraphs = Vec::new();
+    collect_paragraphs(body, &mut paragraphs);
+
+    let mut out = HashMap::new();
+    for p in paragraphs {
+        let mut text = String::new();
+        let mut marks = Vec::new();
+        marked_prose_into(p, &mut text, &mut marks);
+        let spans = sentence_spans(&text);
+        for (offset, rids) in marks {
+            let Some(&(start, end)) = spans.iter().find(|(_, end)| offset <= *end) else {
+                continue;
+            };
+            let sentence = tidy(&text[start..end]);
+            if sentence.is_empty() {
+                continue;
+            }
+            for rid in rids.split_whitespace() {
+                out.entry(rid.to_string())
+                    .or_insert_with(|| sentence.clone());
+            }
+        }
+    }
+    out
+}
+
+/// `<p>` elements below `element`, outside floats and the reference list.
+fn collect_paragraphs<'a>(element: &'a Element, out: &mut Vec<&'a Element>) {
+    for e in element.elements().filter(|e| !is_skipped(e)) {
+        if e.name == "p" {
+            out.push(e);
+        } else {
+            collect_paragraphs(e, out);
+        }
+    }
+}
+
+/// Like [`prose_into`], also recording the offset in `out` of each
+/// citation xref with its `rid`.
+fn marked_prose_into(element: &Element, out: &mut String, marks: &mut Vec<(usize, String)>) {
+    for child in &element.children {
+        match child {
+            Node::Text(text) => out.push_str(text),
+            Node::Element(e) if e.name == "xref" && e.attr("ref-type") == Some("bibr") => {
+                if let Some(rid) = e.attr("rid") {
+                    marks.push((out.len(), rid.to_string()));
+                }
+            }
+            Node::Element(e) if is_skipped(e) => {}
+            Node::Element(e) => {
+                let block = BLOCKS.contains(&e.name.as_str());
+                if block {
+                    out.push(' ');
+                }
+                marked_prose_into(e, out, marks);
+                if block {
+                    out.push(' ');
+                }
+            }
+        }
+    }
+}
+
+/// Byte ranges of the sentences in `text`. A sentence ends at `.`, `!` or
+/// `?` followed by whitespace; the last one runs to the end.
+fn sentence_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let at_break = chars.peek().is_some_and(|(_, next)| next.is_whitespace());
+        if matches!(c, '.' | '!' | '?') && at_break {
+            spans.push((start, i + 1));
+            start = i + 1;
+        }
+    }
+    if start < text.len() {
+        spans.push((start, text.len()));
+    }
+    spans
+}
+
+/// `text` prefixed with the element's `<label>`, if any.
+fn labelled(element: &Element, text: String) -> String {
+    let label = element.child("label").map(prose).unwrap_or_default();
+    tidy(&format!("{} {}", label, text))
+}
+
+/// Elements whose content never belongs to the prose around them.
+const SKIPPED: &[&str] = &[
+    "fig",
+    "fig-group",
+    "table-wrap",
+    "table-wrap-group",
+    "disp-formula",
+    "tex-math",
+    "supplementary-material",
+    "ref-list",
+    "fn-group",
+];
+
+/// Elements that read as separate blocks, so their text gets spaces
+/// around it; inline markup like `<italic>` joins its neighbours as is.
+const BLOCKS: &[&str] = &[
+    "p",
+    "title",
+    "label",
+    "caption",
+    "list-item",
+    "def-item",
+    "disp-quote",
+    "tr",
+    "td",
+    "th",
+];
+
+fn prose(element: &Element) -> String {
+    let mut out = String::new();
+    prose_into(element, &mut out);
+    out
+}
+
+fn prose_into(element: &Element, out: &mut String) {
+    for child in &element.children {
+        match child {
+            Node::Text(text) => out.push_str(text),
+            Node::Element(e) if is_skipped(e) => {}
+            Node::Element(e) => {
+                let block = BLOCKS.contains(&e.name.as_str());
+                if block {
+                    out.push(' ');
+                }
+                prose_into(e, out);
+                if block {
+                    out.push(' ');
+                }
+            }
+        }
+    }
+}
+
+/// Citation xrefs are dropped along with the floats; figure and section
+/// xrefs read as part of the sentence.
+fn is_skipped(element: &Element) -> bool {
+    SKIPPED.contains(&element.name.as_str())
+        || (element.name == "xref" && element.attr("ref-type") == Some("bibr"))
+}
+
+/// Text with a space around every element, for structured citations where
+/// `<surname>` and `<given-names>` sit next to each other.
+fn spaced_into(element: &Element, out: &mut String) {
+    for child in &element.children {
+        match child {
+            Node::Text(text) => out.push_str(text),
+            Node::Element(e) => {
+                out.push(' ');
+                spaced_into(e, out);
+                out.push(' ');
+            }
+        }
+    }
+}
+
+/// Collapse whitespace, drop brackets left empty by removed citations such
+/// as `[, ]`, and close up the space before trailing punctuation.
+fn tidy(text: &str) -> String {
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut out = String::with_capacity(collapsed.len());
+    let mut rest = collapsed.as_str();
+    while let Some(c) = rest.chars().next() {
+        if let Some(len) = empty_group_len(rest) {
+            out.truncate(out.trim_end().len());
+            rest = &rest[len..];
+            continue;
+        }
+        let rest_after = &rest[c.len_utf8()..];
+        if matches!(c, ',' | '.' | ';' | ':' | ')')
+            && out.ends_with(' ')
+            && rest_after.chars().next().is_none_or(char::is_whitespace)
+        {
+            out.pop();
+        }
+        out.push(c);
+        rest = rest_after;
+    }
+    out.trim().to_string()
+}
+
+/// Byte length of a leading `[…]` or `(…)` that holds only separators.
+fn empty_group_len(text: &str) -> Option<usize> {
+    let close = match text.chars().next()? {
+        '[' => ']',
+        '(' => ')',
+        _ => return None,
+    };
+    let end = text.find(close)?;
+    text[1..end]
+        .chars()
+        .all(|c| c.is_whitespace() || matches!(c, ',' | ';' | '-' | '–'))
+        .then_some(end + 1)
+}
+
+#[derive(Debug)]
+struct Element {
+    /// Local name, without a namespace prefix such as `mml:`.
+    name: String,
+    attrs: Vec<(String, String)>,
+    children: Vec<Node>,
+}
+
+#[derive(Debug)]
+enum Node {
+    Element(Element),
+    Text(String),
+}
+
+impl Element {
+    fn from_start(start: &BytesStart) -> Self {
+        Self {
+            name: String::from_utf8_lossy(start.local_name().as_ref()).into_owned(),
+            attrs: start
+                .attributes()
+                .flatten()
+                .map(|a| {
+                    (
+                        String::from_utf8_lossy(a.key.local_name().as_ref()).into_owned(),
+                        a.unescape_value()
+                            .map(|v| v.into_owned())
+                            .unwrap_or_default(),
+                    )
+                })
+                .collect(),
+            children: Vec::new(),
+        }
+    }
+
+    fn attr(&self, key: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|c| match c {
+            Node::Element(e) => Some(e),
+            Node::Text(_) => None,
+        })
+    }
+
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.elements().find(|e| e.name == name)
+    }
+
+    /// Elements named `name` below this one, outermost first, without
+    /// descending into a match.
+    fn descendants<'a>(&'a self, name: &str, out: &mut Vec<&'a Element>) {
+        for e in self.elements() {
+            if e.name == name {
+                out.push(e);
+            } else {
+                e.descendants(name, out);
+            }
+        }
+    }
+}
+
+/// Build the element tree and return its `<article>`. Malformed input keeps
+/// whatever was read before the error.
+fn parse_tree(xml: &str) -> Option<Element> {
+    let mut reader = Reader::from_str(xml);
+    let mut stack = vec![Element {
+        name: String::new(),
+        attrs: Vec::new(),
+        children: Vec::new(),
+    }];
+
+    loop {
+        let node = match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                stack.push(Element::from_start(&e));
+                continue;
+            }
+            Ok(Event::End(_)) if stack.len() > 1 => {
+                Node::Element(stack.pop().expect("stack holds the root"))
+            }
+            Ok(Event::Empty(e)) => Node::Element(Element::from_start(&e)),
+            // Publishers use HTML entities the XML unescaper rejects; keep
+            // the raw text rather than dropping it.
+            Ok(Event::Text(t)) => Node::Text(
+                t.unescape()
+                    .map(|v| v.into_owned())
+                    .unwrap_or_else(|_| String::from_utf8_lossy(&t).into_owned()),
+            ),
+            Ok(Event::CData(c)) => Node::Text(String::from_utf8_lossy(&c).into_owned()),
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(_) => continue,
+        };
+        stack.last_mut()?.children.push(node);
+    }
+
+    while stack.len() > 1 {
+        let open = stack.pop()?;
+        stack.last_mut()?.children.push(Node::Element(open));
+    }
+    into_article(stack.pop()?)
+}
+
+/// The first `<article>` at or below `element`; Europe PMC wraps some
+/// responses, e.g. in `<articles>`.
+fn into_article(element: Element) -> Option<Element> {
+    if element.name == "article" {
+        return Some(element);
+    }
+    element.children.into_iter().find_map(|c| match c {
+        Node::Element(e) => into_article(e),
+        Node::Text(_) => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = include_str!("../tests/fixtures/europepmc_jats_sample.xml");
+
+    fn headings(parsed: &ParsedJats) -> Vec<(SectionType, Option<&str>)> {
+        parsed
+            .sections
+            .iter()
+            .map(|s| (s.section_type.clone(), s.heading.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn splits_body_into_titled_sections() {
+        let parsed = parse_jats(SAMPLE).unwrap();
+        assert_eq!(
+            parsed.title.as_deref(),
+            Some("KRAS G12D dependency in pancreatic ductal adenocarcinoma organoids")
+        );
+        assert_eq!(
+            headings(&parsed),
+            vec![
+                (SectionType::Abstract, Some("Abstract")),
+                (SectionType::Introduction, Some("Introduction")),
+                (SectionType::Methods, Some("Organoid culture")),
+                (SectionType::Methods, Some("Drug screening")),
+                (SectionType::Results, Some("Results")),
+                (
+                    SectionType::Results,
+                    Some("G12D organoids depend on KRAS signalling")
+                ),
+                (SectionType::Discussion, Some("Discussion")),
+            ]
+        );
+
+        let text = |i: usize| parsed.sections[i].text.as_str();
+        assert_eq!(
+            text(0),
+            "Pancreatic ductal adenocarcinoma (PDAC) is driven by KRAS mutations. \
+             Organoids carrying KRAS G12D were sensitive to MRTX1133."
+        );
+        assert_eq!(
+            text(1),
+            "Activating KRAS mutations occur in over 90% of PDAC cases. \
+             Direct inhibitors of the G12D allele have only recently become available."
+        );
+        assert_eq!(
+            text(2),
+            "Patient-derived organoids were grown in Matrigel as described in Drug screening."
+        );
+        assert_eq!(text(3), "Viability was read out after 72 h of treatment.");
+        // The subsection's text stays out of its parent.
+        assert_eq!(text(4), "MRTX1133 reduced viability in all G12D lines.");
+        assert_eq!(
+            text(5),
+            "Knockdown of KRAS reduced ERK phosphorylation (Figure 1)."
+        );
+    }
+
+    #[test]
+    fn floats_and_references_stay_out_of_chunk_text() {
+        let parsed = parse_jats(SAMPLE).unwrap();
+        assert_eq!(
+            parsed.figure_captions,
+            vec![
+                "Figure 1 KRAS dependency. FIGCAPTION viability of organoids after knockdown.",
+                "Figure 2 FIGCAPTION dose response in floats group.",
+            ]
+        );
+        assert_eq!(
+            parsed.tables,
+            vec![
+                "Table 1 Organoid lines used in this study.\nLine | Genotype\nPDO-7 | KRAS G12D\n\
+                 TABLEFOOT organoid provenance is listed in the supplement."
+            ]
+        );
+        assert_eq!(
+            parsed.references,
+            vec![
+                "Roe R REFTEXT KRAS in pancreatic cancer Test Oncol 2020 1 1",
+                "Poe P. REFTEXT G12D inhibitors. Test Oncol. 2023; 4: 10.",
+            ]
+        );
+
+        let config = crate::chunker::ChunkerConfig {
+            max_tokens: 16,
+            overlap_sentences: 1,
+            ..Default::default()
+        };
+        let chunks =
+            crate::chunker::chunk_document(uuid::Uuid::nil(), parsed.chunker_sections(), &config);
+        let body: Vec<&str> = chunks
+            .iter()
+            .filter(|c| c.section_type != SectionType::FigureCaption)
+            .map(|c| c.content.as_str())
+            .collect();
+        for marker in [
+            "REFTEXT",
+            "TABLEFOOT",
+            "PDO-7",
+            "FIGCAPTION",
+            "V=100",
+            "thank",
+        ] {
+            assert!(
+                body.iter().all(|c| !c.contains(marker)),
+                "{marker} leaked into {body:?}"
+            );
+        }
+        let captions = chunks
+            .iter()
+            .filter(|c| c.section_type == SectionType::FigureCaption)
+            .count();
+        assert_eq!(captions, 2);
+    }
+
+    #[test]
+    fn rejects_non_article_responses() {
+        assert!(parse_jats("<html><body>Not found</body></html>").is_none());
+        assert!(parse_jats("").is_none());
+    }
+
+    #[test]
+    fn references_with_dois_carry_their_citing_sentence() {
+        let xml = r#"<article><body><sec><title>Introduction</title>
+            <p>KRAS is mutated in most PDAC. Inhibitors now exist
+               [<xref ref-type="bibr" rid="R1 R2">1,2</xref>]. They help.</p>
+            <p>Resistance emerges quickly.<xref ref-type="bibr" rid="R2">2</xref></p>
+            <fig><caption><p>Not a citation <xref ref-type="bibr" rid="R3">3</xref>.</p></caption></fig>
+          </sec></body>
+          <back><ref-list>
+            <ref id="R1"><element-citation><pub-id pub-id-type="doi">10.1000/ONE</pub-id></element-citation></ref>
+            <ref id="R2"><element-citation><pub-id pub-id-type="pmid">123</pub-id>
+              <pub-id pub-id-type="doi"> 10.1000/two </pub-id></element-citation></ref>
+            <ref id="R3"><element-citation><pub-id pub-id-type="doi">10.1000/three</pub-id></element-citation></ref>
+            <ref id="R4"><mixed-citation>No identifier.</mixed-citation></ref>
+          </ref-list></back></article>"#;
+        let parsed = parse_jats(xml).unwrap();
+        assert_eq!(
+            parsed.citations,
+            vec![
+                JatsCitation {
+                    doi: "10.1000/ONE".to_string(),
+                    context: Some("Inhibitors now exist.".to_string()),
+                },
+                JatsCitation {
+                    doi: "10.1000/two".to_string(),
+                    context: Some("Inhibitors now exist.".to_string()),
+                },
+                JatsCitation {
+                    doi: "10.1000/three".to_string(),
+                    context: None,
+                },
+            ]
+        );
+        assert_eq!(parsed.references.len(), 4);
+    }
+}
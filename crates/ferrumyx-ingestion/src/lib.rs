@@ -1,16 +1,27 @@
 //! ferrumyx-ingestion — Literature ingestion pipeline.
 //! Covers Phase 2 of ARCHITECTURE.md.
 
+pub mod backpressure;
 pub mod chunker;
+pub mod citations;
 pub mod dedup;
 pub mod embed;
 pub mod embedding;
+pub mod enrichment;
+pub mod full_text;
+pub mod jats;
+pub mod linker;
 pub mod models;
 pub mod normalise;
+pub mod paper_search;
 pub mod pdf_parser;
 pub mod pipeline;
+pub mod rate_limit;
 pub mod repository;
+pub mod rerank;
+pub mod scheduler;
 pub mod sources;
+pub mod trials;
 
 pub use embed::embedder::BiomedBertEmbedder;
 
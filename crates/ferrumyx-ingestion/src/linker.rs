@@ -0,0 +1,895 @@
+//! Entity linking: connects NER mentions to rows of the entities table.
+//!
+//! A mention's surface form is looked up in a [`SenseDictionary`] built from
+//! the dictionary id the NER attached, rows already in the entities table and
+//! a short list of known homographs. A single sense links directly. When a
+//! form has several senses — MET the receptor or methionine, ALL the
+//! leukaemia or the word, KIT the receptor or a reagent kit — the mention's
+//! sentence is embedded and compared with a short description of each sense,
+//! and the closest sense wins if it leads the runner-up by
+//! [`LinkerConfig::min_margin`]. Mentions with no sense, or no clear winner,
+//! link to a provisional entity whose metadata flags it `needs_review`.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{ensure, Result};
+use async_trait::async_trait;
+use ferrumyx_db::entities::EntityRepository;
+use ferrumyx_db::entity_mentions::EntityMentionRepository;
+use ferrumyx_db::schema::{Entity, EntityMention, EntityType as DbEntityType};
+use ferrumyx_kg::ner::ExtractedEntity;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::embed::embedder::BiomedBertEmbedder;
+use crate::embedding::EmbeddingClient;
+use crate::pipeline::{canonical_key, map_ner_type};
+
+/// Metadata key set on provisional entities.
+pub const NEEDS_REVIEW_KEY: &str = "needs_review";
+
+/// Senses of symbols that are also common words or abbreviations. The
+/// descriptions leave out the symbol itself, which every sense shares.
+const HOMOGRAPHS: &[(&str, Option<DbEntityType>, &str, Option<&str>, &str)] = &[
+    (
+        "MET",
+        Some(DbEntityType::Gene),
+        "MET",
+        Some("HGNC:7029"),
+        "receptor tyrosine kinase for hepatocyte growth factor; amplification or exon 14 \
+         skipping in lung cancer, targeted by inhibitors such as capmatinib",
+    ),
+    (
+        "MET",
+        Some(DbEntityType::Chemical),
+        "methionine",
+        None,
+        "methionine, the sulfur-containing amino acid; residues in a protein or peptide \
+         sequence, oxidation and metabolism",
+    ),
+    (
+        "MET",
+        None,
+        "metabolic equivalent",
+        None,
+        "metabolic equivalent of task, a unit of physical activity and exercise intensity",
+    ),
+    (
+        "ALL",
+        Some(DbEntityType::CancerType),
+        "acute lymphoblastic leukemia",
+        None,
+        "acute lymphoblastic leukemia, a blood cancer of lymphoid precursor cells, mostly \
+         in children; B-cell or T-cell disease treated with induction chemotherapy",
+    ),
+    (
+        "ALL",
+        None,
+        "all",
+        None,
+        "the word meaning every one or the whole of a group, as in every sample, patient \
+         or cell",
+    ),
+    (
+        "KIT",
+        Some(DbEntityType::Gene),
+        "KIT",
+        Some("HGNC:6342"),
+        "receptor tyrosine kinase for stem cell factor; mutations drive gastrointestinal \
+         stromal tumours and respond to imatinib",
+    ),
+    (
+        "KIT",
+        None,
+        "kit",
+        None,
+        "a commercial reagent or assay set used according to the manufacturer's protocol \
+         for extraction, staining or measurement",
+    ),
+];
+
+/// Embeds text for context scoring.
+#[async_trait]
+pub trait TextEmbedder: Send + Sync {
+    /// One vector per text, in input order.
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>>;
+}
+
+#[async_trait]
+impl TextEmbedder for EmbeddingClient {
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        self.embed_batch(texts).await
+    }
+}
+
+#[async_trait]
+impl TextEmbedder for BiomedBertEmbedder {
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        Ok(BiomedBertEmbedder::embed(self, texts).await?)
+    }
+}
+
+/// One meaning a surface form can have.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sense {
+    /// `None` for a common-word reading that is not an entity.
+    pub entity_type: Option<DbEntityType>,
+    pub name: String,
+    /// HGNC id, OncoTree code or the external id of an existing row.
+    pub external_id: Option<String>,
+    /// Short description embedded for context scoring.
+    pub description: String,
+}
+
+impl Sense {
+    pub fn entity(
+        entity_type: DbEntityType,
+        name: impl Into<String>,
+        external_id: Option<&str>,
+        description: impl Into<String>,
+    ) -> Self {
+        Self {
+            entity_type: Some(entity_type),
+            name: name.into(),
+            external_id: external_id.map(str::to_string),
+            description: description.into(),
+        }
+    }
+
+    pub fn word(name: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            entity_type: None,
+            name: name.into(),
+            external_id: None,
+            description: description.into(),
+        }
+    }
+
+    pub fn is_entity(&self) -> bool {
+        self.entity_type.is_some()
+    }
+
+    /// Same type, and the same dictionary id or name.
+    fn same_as(&self, other: &Sense) -> bool {
+        self.entity_type == other.entity_type
+            && (matches!((&self.external_id, &other.external_id), (Some(a), Some(b)) if a == b)
+                || self.name.eq_ignore_ascii_case(&other.name))
+    }
+}
+
+/// Senses by upper-cased surface form.
+#[derive(Debug, Clone, Default)]
+pub struct SenseDictionary {
+    senses: HashMap<String, Vec<Sense>>,
+}
+
+impl SenseDictionary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A dictionary holding the built-in homograph senses.
+    pub fn with_homographs() -> Self {
+        let mut dictionary = Self::new();
+        for &(form, entity_type, name, external_id, description) in HOMOGRAPHS {
+            let sense = match entity_type {
+                Some(t) => Sense::entity(t, name, external_id, description),
+                None => Sense::word(name, description),
+            };
+            dictionary.add(form, sense);
+        }
+        dictionary
+    }
+
+    /// Adds `sense` under `form`, merging it into an equal sense.
+    pub fn add(&mut self, form: &str, sense: Sense) {
+        merge_sense(self.senses.entry(form_key(form)).or_default(), sense);
+    }
+
+    /// Adds an entities-table row under its name, canonical name and
+    /// synonyms. Rows of an unknown type are skipped.
+    pub fn add_entity(&mut self, entity: &Entity) {
+        let Ok(entity_type) = entity.entity_type.parse::<DbEntityType>() else {
+            return;
+        };
+        let name = entity.canonical_name.as_deref().unwrap_or(&entity.name);
+        let description = entity
+            .description
+            .clone()
+            .unwrap_or_else(|| format!("{entity_type} {name}"));
+        let sense = Sense::entity(entity_type, name, Some(&entity.external_id), description);
+        let mut forms = vec![entity.name.clone(), name.to_string()];
+        forms.extend(parse_synonyms(entity.synonyms.as_deref()));
+        for form in forms {
+            self.add(&form, sense.clone());
+        }
+    }
+
+    pub fn senses(&self, form: &str) -> &[Sense] {
+        self.senses.get(&form_key(form)).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn contains(&self, form: &str) -> bool {
+        !self.senses(form).is_empty()
+    }
+}
+
+fn form_key(form: &str) -> String {
+    form.trim().to_uppercase()
+}
+
+fn merge_sense(senses: &mut Vec<Sense>, sense: Sense) {
+    match senses.iter_mut().find(|s| s.same_as(&sense)) {
+        Some(existing) if existing.external_id.is_none() => {
+            existing.external_id = sense.external_id;
+        }
+        Some(_) => {}
+        None => senses.push(sense),
+    }
+}
+
+/// Synonyms are stored as a JSON array or a single value.
+fn parse_synonyms(raw: Option<&str>) -> Vec<String> {
+    let Some(raw) = raw.map(str::trim).filter(|s| !s.is_empty()) else {
+        return Vec::new();
+    };
+    serde_json::from_str::<Vec<String>>(raw).unwrap_or_else(|_| vec![raw.to_string()])
+}
+
+/// The sentence of `text` around the byte range `start..end`.
+pub fn sentence_around(text: &str, start: usize, end: usize) -> &str {
+    let start = start.min(text.len());
+    let end = end.clamp(start, text.len());
+    let boundary = |i: usize, c: char| {
+        c == '\n'
+            || (matches!(c, '.' | '!' | '?')
+                && text[i + c.len_utf8()..]
+                    .chars()
+                    .next()
+                    .is_none_or(char::is_whitespace))
+    };
+    let from = text[..start]
+        .char_indices()
+        .rev()
+        .find(|&(i, c)| boundary(i, c))
+        .map_or(0, |(i, c)| i + c.len_utf8());
+    let to = text[end..]
+        .char_indices()
+        .find(|&(i, c)| boundary(end + i, c))
+        .map_or(text.len(), |(i, c)| end + i + c.len_utf8());
+    text[from..to].trim()
+}
+
+/// A NER hit and the sentence it was found in.
+#[derive(Debug, Clone)]
+pub struct Mention {
+    pub entity: ExtractedEntity,
+    pub sentence: String,
+}
+
+impl Mention {
+    /// A hit found in `text`, with its sentence cut out around it.
+    pub fn new(entity: ExtractedEntity, text: &str) -> Self {
+        let sentence = sentence_around(text, entity.start, entity.end).to_string();
+        Self { entity, sentence }
+    }
+}
+
+/// How a mention was linked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkMethod {
+    /// The surface form has a single sense.
+    Dictionary,
+    /// Chosen among several senses by sentence similarity.
+    Context,
+    /// No sense fits; linked to a provisional entity.
+    Provisional,
+}
+
+/// The sense a mention resolved to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Link {
+    /// A common-word sense means the mention is not an entity.
+    pub sense: Sense,
+    pub method: LinkMethod,
+    pub confidence: f32,
+}
+
+impl Link {
+    pub fn needs_review(&self) -> bool {
+        self.method == LinkMethod::Provisional
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct LinkerConfig {
+    /// Cosine lead the best sense needs over the runner-up.
+    pub min_margin: f32,
+    /// Softmax temperature turning sense similarities into a confidence.
+    pub temperature: f32,
+    /// Multiplier on the NER confidence of provisional links.
+    pub provisional_weight: f32,
+}
+
+impl Default for LinkerConfig {
+    fn default() -> Self {
+        Self {
+            min_margin: 0.02,
+            temperature: 0.05,
+            provisional_weight: 0.5,
+        }
+    }
+}
+
+pub struct EntityLinker {
+    dictionary: SenseDictionary,
+    embedder: Arc<dyn TextEmbedder>,
+    config: LinkerConfig,
+    /// Description embeddings, which repeat across calls.
+    descriptions: Mutex<HashMap<String, Vec<f32>>>,
+}
+
+impl EntityLinker {
+    pub fn new(
+        dictionary: SenseDictionary,
+        embedder: Arc<dyn TextEmbedder>,
+        config: LinkerConfig,
+    ) -> Self {
+        Self {
+            dictionary,
+            embedder,
+            config,
+            descriptions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn dictionary_mut(&mut self) -> &mut SenseDictionary {
+        &mut self.dictionary
+    }
+
+    /// Adds entities-table rows matching the surface forms of `mentions`
+    /// that the dictionary does not know yet.
+    pub async fn load_entities(
+        &mut self,
+        repo: &EntityRepository,
+        mentions: &[Mention],
+    ) -> Result<()> {
+        let mut forms: Vec<&str> = mentions
+            .iter()
+            .map(|m| m.entity.text.trim())
+            .filter(|form| !self.dictionary.contains(form))
+            .collect();
+        forms.sort_unstable();
+        forms.dedup();
+        for form in forms {
+            let mut rows = repo.find_by_name(form).await?;
+            rows.extend(repo.find_by_synonym(form).await?);
+            for row in &rows {
+                self.dictionary.add_entity(row);
+            }
+        }
+        Ok(())
+    }
+
+    /// Links every mention, in input order.
+    pub async fn link(&self, mentions: &[Mention]) -> Result<Vec<Link>> {
+        let candidates: Vec<Vec<Sense>> = mentions
+            .iter()
+            .map(|m| self.candidates(&m.entity))
+            .collect();
+
+        // One embedding call for the sentences of ambiguous mentions and
+        // the descriptions not cached yet.
+        let mut texts: Vec<String> = Vec::new();
+        let mut sentence_index: HashMap<usize, usize> = HashMap::new();
+        for (i, senses) in candidates.iter().enumerate() {
+            if senses.len() > 1 {
+                sentence_index.insert(i, texts.len());
+                texts.push(mentions[i].sentence.clone());
+            }
+        }
+        let n_sentences = texts.len();
+        {
+            let cached = self
+                .descriptions
+                .lock()
+                .expect("description cache poisoned");
+            for sense in candidates.iter().filter(|s| s.len() > 1).flatten() {
+                if !cached.contains_key(&sense.description)
+                    && !texts[n_sentences..].contains(&sense.description)
+                {
+                    texts.push(sense.description.clone());
+                }
+            }
+        }
+        let vectors = if texts.is_empty() {
+            Vec::new()
+        } else {
+            self.embedder.embed(&texts).await?
+        };
+        ensure!(
+            vectors.len() == texts.len(),
+            "embedder returned {} vectors for {} texts",
+            vectors.len(),
+            texts.len()
+        );
+        let (sentences, new_descriptions) = vectors.split_at(n_sentences);
+
+        let mut cached = self
+            .descriptions
+            .lock()
+            .expect("description cache poisoned");
+        for (text, vector) in texts[n_sentences..].iter().zip(new_descriptions) {
+            cached.insert(text.clone(), vector.clone());
+        }
+        Ok(mentions
+            .iter()
+            .zip(candidates)
+            .enumerate()
+            .map(|(i, (mention, mut senses))| match senses.len() {
+                0 => self.provisional(&mention.entity),
+                1 => Link {
+                    sense: senses.remove(0),
+                    method: LinkMethod::Dictionary,
+                    confidence: mention.entity.confidence,
+                },
+                _ => self.by_context(
+                    &mention.entity,
+                    senses,
+                    &sentences[sentence_index[&i]],
+                    &cached,
+                ),
+            })
+            .collect())
+    }
+
+    /// Dictionary senses of the mention's form, plus the dictionary id the
+    /// NER attached.
+    fn candidates(&self, entity: &ExtractedEntity) -> Vec<Sense> {
+        let mut senses = self.dictionary.senses(&entity.text).to_vec();
+        if let Some(id) = &entity.canonical_id {
+            let entity_type = map_ner_type(entity.label);
+            let name = entity.canonical_name.as_deref().unwrap_or(&entity.text);
+            let description = format!("{entity_type} {name}");
+            merge_sense(
+                &mut senses,
+                Sense::entity(entity_type, name, Some(id), description),
+            );
+        }
+        senses
+    }
+
+    fn by_context(
+        &self,
+        entity: &ExtractedEntity,
+        mut senses: Vec<Sense>,
+        sentence: &[f32],
+        descriptions: &HashMap<String, Vec<f32>>,
+    ) -> Link {
+        let similarities: Vec<f32> = senses
+            .iter()
+            .map(|s| cosine(sentence, &descriptions[&s.description]))
+            .collect();
+        let mut order: Vec<usize> = (0..senses.len()).collect();
+        order.sort_by(|&a, &b| similarities[b].total_cmp(&similarities[a]));
+        let (best, runner_up) = (order[0], order[1]);
+        if similarities[best] - similarities[runner_up] < self.config.min_margin {
+            return self.provisional(entity);
+        }
+
+        let weights: Vec<f32> = similarities
+            .iter()
+            .map(|s| ((s - similarities[best]) / self.config.temperature).exp())
+            .collect();
+        let share = weights[best] / weights.iter().sum::<f32>();
+        Link {
+            sense: senses.swap_remove(best),
+            method: LinkMethod::Context,
+            confidence: entity.confidence * share,
+        }
+    }
+
+    fn provisional(&self, entity: &ExtractedEntity) -> Link {
+        Link {
+            sense: Sense::entity(map_ner_type(entity.label), entity.text.trim(), None, ""),
+            method: LinkMethod::Provisional,
+            confidence: entity.confidence * self.config.provisional_weight,
+        }
+    }
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let denom = norm(a) * norm(b);
+    if denom == 0.0 {
+        0.0
+    } else {
+        dot / denom
+    }
+}
+
+/// Whether `entity` is a provisional row awaiting review.
+pub fn needs_review(entity: &Entity) -> bool {
+    entity
+        .metadata
+        .as_deref()
+        .and_then(|m| serde_json::from_str::<serde_json::Value>(m).ok())
+        .and_then(|m| m.get(NEEDS_REVIEW_KEY)?.as_bool())
+        .unwrap_or(false)
+}
+
+/// Writes the links of one chunk's mentions: resolves or creates each
+/// entity and records the mention with its link confidence and sentence.
+/// Common-word links are skipped. Returns the mentions written.
+pub async fn persist_links(
+    entities: &EntityRepository,
+    mention_repo: &EntityMentionRepository,
+    chunk_id: Uuid,
+    paper_id: Uuid,
+    mentions: &[Mention],
+    links: &[Link],
+) -> Result<Vec<EntityMention>> {
+    let mut ids: HashMap<String, Uuid> = HashMap::new();
+    let mut rows = Vec::new();
+    for (mention, link) in mentions.iter().zip(links) {
+        let Some(entity_type) = link.sense.entity_type else {
+            continue;
+        };
+        let external_id = link_external_id(entity_type, link);
+        let entity_id = match ids.get(&external_id) {
+            Some(id) => *id,
+            None => {
+                let id = resolve_or_create(entities, entity_type, link, &external_id).await?;
+                ids.insert(external_id, id);
+                id
+            }
+        };
+        let mut row = EntityMention::new(
+            entity_id,
+            chunk_id,
+            paper_id,
+            mention.entity.text.clone(),
+            mention.entity.start as i64,
+            mention.entity.end as i64,
+        );
+        row.confidence = Some(link.confidence);
+        row.context = Some(mention.sentence.clone());
+        rows.push(row);
+    }
+    mention_repo.insert_batch(&rows).await?;
+    Ok(rows)
+}
+
+/// Dictionary id of the sense, else the name-keyed id the pipeline gives
+/// entities without one. Provisional rows get their own namespace.
+fn link_external_id(entity_type: DbEntityType, link: &Link) -> String {
+    let key = canonical_key(entity_type, &link.sense.name);
+    match &link.sense.external_id {
+        _ if link.needs_review() => format!("FERRUMYX:PROVISIONAL:{key}"),
+        Some(id) => id.clone(),
+        None => format!("FERRUMYX:{key}"),
+    }
+}
+
+async fn resolve_or_create(
+    repo: &EntityRepository,
+    entity_type: DbEntityType,
+    link: &Link,
+    external_id: &str,
+) -> Result<Uuid> {
+    if let Some(existing) = repo
+        .find_by_external_id(external_id)
+        .await?
+        .into_iter()
+        .next()
+    {
+        return Ok(existing.id);
+    }
+    let mut entity = Entity::new(
+        entity_type,
+        link.sense.name.clone(),
+        external_id.to_string(),
+        "ferrumyx".to_string(),
+    );
+    entity.canonical_name = Some(link.sense.name.clone());
+    if !link.sense.description.is_empty() {
+        entity.description = Some(link.sense.description.clone());
+    }
+    if link.needs_review() {
+        entity.metadata = Some(serde_json::json!({ NEEDS_REVIEW_KEY: true }).to_string());
+    }
+    repo.insert(&entity).await?;
+    Ok(entity.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ferrumyx_kg::ner::EntityType as NerEntityType;
+
+    const DIM: usize = 512;
+    const STOP_WORDS: &[&str] = &[
+        "the", "and", "with", "for", "was", "were", "are", "from", "that", "this", "into", "its",
+        "has", "had",
+    ];
+
+    /// Hashed bag of words: similarity is shared vocabulary.
+    struct BagOfWords;
+
+    #[async_trait]
+    impl TextEmbedder for BagOfWords {
+        async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            Ok(texts
+                .iter()
+                .map(|text| {
+                    let mut v = vec![0.0; DIM];
+                    for word in text.split(|c: char| !c.is_ascii_alphanumeric()) {
+                        let word = word.to_lowercase();
+                        if word.len() < 3 || STOP_WORDS.contains(&word.as_str()) {
+                            continue;
+                        }
+                        let word = match word.strip_suffix('s') {
+                            Some(stem) if word.len() > 3 => stem,
+                            _ => &word,
+                        };
+                        let hash = word.bytes().fold(0xcbf2_9ce4_8422_2325u64, |h, b| {
+                            (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+                        });
+                        v[(hash % DIM as u64) as usize] += 1.0;
+                    }
+                    v
+                })
+                .collect())
+        }
+    }
+
+    fn linker() -> EntityLinker {
+        EntityLinker::new(
+            SenseDictionary::with_homographs(),
+            Arc::new(BagOfWords),
+            LinkerConfig::default(),
+        )
+    }
+
+    fn mention(sentence: &str, form: &str, label: NerEntityType, id: Option<&str>) -> Mention {
+        let start = sentence.find(form).unwrap();
+        Mention::new(
+            ExtractedEntity {
+                text: form.to_string(),
+                label,
+                start,
+                end: start + form.len(),
+                confidence: 0.9,
+                canonical_id: id.map(str::to_string),
+                canonical_name: id.map(|_| form.to_uppercase()),
+            },
+            sentence,
+        )
+    }
+
+    #[tokio::test]
+    async fn resolves_hand_labelled_homographs() {
+        use NerEntityType::{CancerType, Gene};
+        let gene = Some(DbEntityType::Gene);
+        // (sentence, form, NER label, NER id, expected type and name;
+        // None for a provisional link).
+        let cases = [
+            (
+                "Capmatinib inhibits MET in lung cancers with exon 14 skipping.",
+                "MET",
+                Gene,
+                Some("HGNC:7029"),
+                Some((gene, "MET")),
+            ),
+            (
+                "MET amplification confers resistance to EGFR inhibitors.",
+                "MET",
+                Gene,
+                Some("HGNC:7029"),
+                Some((gene, "MET")),
+            ),
+            (
+                "Oxidation of Met residues in the peptide reduced binding.",
+                "Met",
+                Gene,
+                None,
+                Some((Some(DbEntityType::Chemical), "methionine")),
+            ),
+            (
+                "Patients walked at an intensity of 3 MET during exercise.",
+                "MET",
+                Gene,
+                Some("HGNC:7029"),
+                Some((None, "metabolic equivalent")),
+            ),
+            (
+                "Children with ALL received induction chemotherapy.",
+                "ALL",
+                CancerType,
+                None,
+                Some((
+                    Some(DbEntityType::CancerType),
+                    "acute lymphoblastic leukemia",
+                )),
+            ),
+            (
+                "Relapsed B-cell ALL responds to blinatumomab.",
+                "ALL",
+                CancerType,
+                None,
+                Some((
+                    Some(DbEntityType::CancerType),
+                    "acute lymphoblastic leukemia",
+                )),
+            ),
+            (
+                "ALL samples were sequenced on the same platform.",
+                "ALL",
+                Gene,
+                None,
+                Some((None, "all")),
+            ),
+            (
+                "Imatinib targets KIT mutations in gastrointestinal stromal tumours.",
+                "KIT",
+                Gene,
+                Some("HGNC:6342"),
+                Some((gene, "KIT")),
+            ),
+            (
+                "RNA was extracted with the RNeasy kit following the manufacturer's protocol.",
+                "kit",
+                Gene,
+                None,
+                Some((None, "kit")),
+            ),
+            (
+                "Staining used a commercial assay kit.",
+                "kit",
+                Gene,
+                None,
+                Some((None, "kit")),
+            ),
+            (
+                "KRAS G12D drives pancreatic cancer.",
+                "KRAS",
+                Gene,
+                Some("HGNC:6407"),
+                Some((gene, "KRAS")),
+            ),
+            ("MET was noted.", "MET", Gene, Some("HGNC:7029"), None),
+            ("FOOBAR1 was upregulated.", "FOOBAR1", Gene, None, None),
+        ];
+        let mentions: Vec<Mention> = cases
+            .iter()
+            .map(|(sentence, form, label, id, _)| mention(sentence, form, *label, *id))
+            .collect();
+        let links = linker().link(&mentions).await.unwrap();
+
+        let misses: Vec<String> = cases
+            .iter()
+            .zip(&links)
+            .filter(|((.., expected), link)| match expected {
+                Some((entity_type, name)) => {
+                    link.needs_review()
+                        || link.sense.entity_type != *entity_type
+                        || link.sense.name != *name
+                }
+                None => !link.needs_review(),
+            })
+            .map(|((sentence, ..), link)| format!("{sentence} -> {link:?}"))
+            .collect();
+        assert!(misses.is_empty(), "{misses:#?}");
+
+        assert_eq!(links[0].method, LinkMethod::Context);
+        assert_eq!(links[0].sense.external_id.as_deref(), Some("HGNC:7029"));
+        assert!(links[0].confidence > 0.8 && links[0].confidence <= 0.9);
+        assert_eq!(links[10].method, LinkMethod::Dictionary);
+        assert_eq!(links[10].confidence, 0.9);
+        assert_eq!(links[12].confidence, 0.45);
+    }
+
+    #[tokio::test]
+    async fn entity_rows_link_by_alias() {
+        let mut erbb2 = Entity::new(
+            DbEntityType::Gene,
+            "ERBB2".to_string(),
+            "HGNC:3430".to_string(),
+            "ferrumyx".to_string(),
+        );
+        erbb2.synonyms = Some(r#"["HER2","NEU"]"#.to_string());
+        let mut linker = linker();
+        linker.dictionary_mut().add_entity(&erbb2);
+        assert_eq!(linker.dictionary_mut().senses("neu").len(), 1);
+
+        let text = "Trastuzumab targets HER2-amplified breast cancer. HER2 loss follows.";
+        let links = linker
+            .link(&[mention(
+                text,
+                "HER2",
+                NerEntityType::Gene,
+                Some("HGNC:3430"),
+            )])
+            .await
+            .unwrap();
+        assert_eq!(links[0].method, LinkMethod::Dictionary);
+        assert_eq!(links[0].sense.name, "ERBB2");
+        assert_eq!(links[0].sense.external_id.as_deref(), Some("HGNC:3430"));
+    }
+
+    #[test]
+    fn sentences_split_at_terminal_punctuation() {
+        let text = "KRAS p.G12D is common. MET amplification follows! Done";
+        let start = text.find("MET").unwrap();
+        assert_eq!(
+            sentence_around(text, start, start + 3),
+            "MET amplification follows!"
+        );
+        assert_eq!(sentence_around(text, 0, 4), "KRAS p.G12D is common.");
+        assert_eq!(sentence_around(text, text.len() - 4, text.len()), "Done");
+    }
+
+    #[tokio::test]
+    async fn persists_mentions_and_flags_provisional_entities() {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-linker-{}", Uuid::new_v4()));
+        let db = Arc::new(ferrumyx_db::Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        let entities = EntityRepository::new(db.clone());
+        let mention_repo = EntityMentionRepository::new(db.clone());
+
+        let text = "FOOBAR1 was upregulated. ALL samples were sequenced on the same platform.";
+        let mentions = [
+            mention(text, "FOOBAR1", NerEntityType::Gene, None),
+            mention(text, "ALL", NerEntityType::Gene, None),
+        ];
+        let linker = linker();
+        let links = linker.link(&mentions).await.unwrap();
+        let (chunk_id, paper_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let rows = persist_links(
+            &entities,
+            &mention_repo,
+            chunk_id,
+            paper_id,
+            &mentions,
+            &links,
+        )
+        .await
+        .unwrap();
+        assert_eq!(rows.len(), 1, "the common-word ALL is not recorded");
+        assert_eq!(rows[0].confidence, Some(0.45));
+        assert_eq!(rows[0].context.as_deref(), Some("FOOBAR1 was upregulated."));
+
+        let provisional = entities
+            .find_by_external_id("FERRUMYX:PROVISIONAL:gene:FOOBAR1")
+            .await
+            .unwrap();
+        assert_eq!(provisional.len(), 1);
+        assert!(needs_review(&provisional[0]));
+        assert_eq!(rows[0].entity_id, provisional[0].id);
+
+        // A second pass reuses the provisional row.
+        persist_links(
+            &entities,
+            &mention_repo,
+            Uuid::new_v4(),
+            paper_id,
+            &mentions[..1],
+            &links[..1],
+        )
+        .await
+        .unwrap();
+        assert_eq!(entities.count().await.unwrap(), 1);
+        assert_eq!(
+            mention_repo
+                .find_by_entity_id(provisional[0].id)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
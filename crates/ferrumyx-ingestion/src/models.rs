@@ -18,6 +18,34 @@ pub struct PaperMetadata {
     pub source: IngestionSource,
     pub open_access: bool,
     pub full_text_url: Option<String>,
+    /// Review article per PubMed publication types / Europe PMC metadata.
+    #[serde(default)]
+    pub is_review: bool,
+    /// Version and journal linkage of a bioRxiv/medRxiv preprint.
+    #[serde(default)]
+    pub preprint: Option<PreprintInfo>,
+}
+
+impl PaperMetadata {
+    /// DOI of the journal article a preprint was published as.
+    pub fn published_doi(&self) -> Option<&str> {
+        self.preprint.as_ref()?.published_doi.as_deref()
+    }
+}
+
+/// Preprint server metadata: which version this record is, and the DOI of
+/// the journal article it became, if any.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PreprintInfo {
+    pub version: u32,
+    pub published_doi: Option<String>,
+}
+
+/// Whether a PubMed publication type or Europe PMC `pubType` denotes a
+/// review article ("Review", "Systematic Review", "review-article", ...).
+pub fn is_review_publication_type(pub_type: &str) -> bool {
+    let t = pub_type.trim().to_ascii_lowercase();
+    t == "review" || t == "review-article" || t.ends_with(" review")
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -122,4 +150,8 @@ pub struct DocumentChunk {
     pub content: String,
     pub page_number: Option<u32>,
     pub token_count: usize,
+    /// Char offsets of `content` in the paper text, see
+    /// [`crate::chunker::document_text`].
+    pub char_start: usize,
+    pub char_end: usize,
 }
@@ -0,0 +1,176 @@
+//! Drug name normalisation for targeted oncology drugs.
+//!
+//! Trial registries name a drug however the sponsor likes: "Sotorasib",
+//! "AMG 510", "Lumakras (sotorasib) 960 mg". [`normalise_drugs`] maps such
+//! text to the generic names in [`KNOWN_DRUGS`], whose targets let a trial
+//! of the drug count as a trial of the gene.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// A drug with its brand and development-code names and the HGNC symbols
+/// it is approved or developed against.
+#[derive(Debug, PartialEq, Eq)]
+pub struct KnownDrug {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    pub targets: &'static [&'static str],
+}
+
+const fn drug(
+    name: &'static str,
+    aliases: &'static [&'static str],
+    targets: &'static [&'static str],
+) -> KnownDrug {
+    KnownDrug {
+        name,
+        aliases,
+        targets,
+    }
+}
+
+/// Every drug [`known_inhibitors`](crate::sources::clinicaltrials::known_inhibitors)
+/// searches for, and a few more.
+pub const KNOWN_DRUGS: &[KnownDrug] = &[
+    drug("sotorasib", &["Lumakras", "Lumykras", "AMG 510"], &["KRAS"]),
+    drug("adagrasib", &["Krazati", "MRTX849"], &["KRAS"]),
+    drug("divarasib", &["GDC-6036"], &["KRAS"]),
+    drug("osimertinib", &["Tagrisso", "AZD9291"], &["EGFR"]),
+    drug("erlotinib", &["Tarceva"], &["EGFR"]),
+    drug("gefitinib", &["Iressa"], &["EGFR"]),
+    drug("afatinib", &["Gilotrif", "Giotrif"], &["EGFR", "ERBB2"]),
+    drug("vemurafenib", &["Zelboraf", "PLX4032"], &["BRAF"]),
+    drug("dabrafenib", &["Tafinlar"], &["BRAF"]),
+    drug("encorafenib", &["Braftovi", "LGX818"], &["BRAF"]),
+    drug("alectinib", &["Alecensa"], &["ALK"]),
+    drug("crizotinib", &["Xalkori"], &["ALK", "ROS1", "MET"]),
+    drug("lorlatinib", &["Lorbrena", "Lorviqua"], &["ALK", "ROS1"]),
+    drug("trastuzumab", &["Herceptin"], &["ERBB2"]),
+    drug("lapatinib", &["Tykerb", "Tyverb"], &["ERBB2", "EGFR"]),
+    drug("tucatinib", &["Tukysa"], &["ERBB2"]),
+    drug("capmatinib", &["Tabrecta", "INC280"], &["MET"]),
+    drug("tepotinib", &["Tepmetko"], &["MET"]),
+    drug("selpercatinib", &["Retevmo", "LOXO-292"], &["RET"]),
+    drug("pralsetinib", &["Gavreto", "BLU-667"], &["RET"]),
+    drug(
+        "entrectinib",
+        &["Rozlytrek"],
+        &["NTRK1", "NTRK2", "NTRK3", "ROS1", "ALK"],
+    ),
+    drug(
+        "larotrectinib",
+        &["Vitrakvi", "LOXO-101"],
+        &["NTRK1", "NTRK2", "NTRK3"],
+    ),
+    drug("alpelisib", &["Piqray", "BYL719"], &["PIK3CA"]),
+    drug("ivosidenib", &["Tibsovo", "AG-120"], &["IDH1"]),
+    drug("enasidenib", &["Idhifa", "AG-221"], &["IDH2"]),
+    drug("midostaurin", &["Rydapt", "PKC412"], &["FLT3", "KIT"]),
+    drug("gilteritinib", &["Xospata"], &["FLT3"]),
+    drug(
+        "imatinib",
+        &["Gleevec", "Glivec", "STI571"],
+        &["ABL1", "KIT"],
+    ),
+    drug("olaparib", &["Lynparza"], &["PARP1", "PARP2"]),
+    drug("niraparib", &["Zejula"], &["PARP1", "PARP2"]),
+    drug("palbociclib", &["Ibrance"], &["CDK4", "CDK6"]),
+    drug("ribociclib", &["Kisqali"], &["CDK4", "CDK6"]),
+    drug("abemaciclib", &["Verzenio"], &["CDK4", "CDK6"]),
+    drug("tazemetostat", &["Tazverik"], &["EZH2"]),
+    drug("pemigatinib", &["Pemazyre"], &["FGFR1", "FGFR2", "FGFR3"]),
+];
+
+/// Names longer than this many words are not in the table; bounds the
+/// windows [`normalise_drugs`] tries.
+const MAX_NAME_WORDS: usize = 3;
+
+/// Lower-case alphanumerics only, so "AMG 510", "AMG-510" and "amg510"
+/// agree.
+fn compact(s: &str) -> String {
+    s.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn drug_index() -> &'static HashMap<String, &'static KnownDrug> {
+    static INDEX: OnceLock<HashMap<String, &'static KnownDrug>> = OnceLock::new();
+    INDEX.get_or_init(|| {
+        KNOWN_DRUGS
+            .iter()
+            .flat_map(|d| {
+                std::iter::once(d.name)
+                    .chain(d.aliases.iter().copied())
+                    .map(move |n| (compact(n), d))
+            })
+            .collect()
+    })
+}
+
+/// The known drug `name` is, or one of its aliases.
+pub fn find_drug(name: &str) -> Option<&'static KnownDrug> {
+    drug_index().get(&compact(name)).copied()
+}
+
+/// Known drugs named anywhere in `text`, in order of first mention.
+/// Doses, formulations and combination partners around them are ignored.
+pub fn normalise_drugs(text: &str) -> Vec<&'static KnownDrug> {
+    let words: Vec<&str> = text
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    let mut found: Vec<&'static KnownDrug> = Vec::new();
+    for start in 0..words.len() {
+        for len in 1..=MAX_NAME_WORDS.min(words.len() - start) {
+            let Some(drug) = find_drug(&words[start..start + len].concat()) else {
+                continue;
+            };
+            if !found.contains(&drug) {
+                found.push(drug);
+            }
+        }
+    }
+    found
+}
+
+/// HGNC symbols `drug` (a generic, brand or code name) targets.
+pub fn drug_targets(drug: &str) -> &'static [&'static str] {
+    find_drug(drug).map_or(&[], |d| d.targets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::clinicaltrials::known_inhibitors;
+
+    fn names(text: &str) -> Vec<&'static str> {
+        normalise_drugs(text).iter().map(|d| d.name).collect()
+    }
+
+    #[test]
+    fn brand_and_code_names_resolve_to_the_generic() {
+        assert_eq!(names("Sotorasib"), ["sotorasib"]);
+        assert_eq!(names("AMG 510"), ["sotorasib"]);
+        assert_eq!(names("AMG-510 960 mg oral tablet"), ["sotorasib"]);
+        assert_eq!(names("Lumakras (sotorasib) + panitumumab"), ["sotorasib"]);
+        assert_eq!(names("MRTX849 and Cetuximab"), ["adagrasib"]);
+        assert_eq!(names("dabrafenib/trametinib"), ["dabrafenib"]);
+        assert!(names("Placebo").is_empty());
+        assert!(names("Gemcitabine and nab-paclitaxel").is_empty());
+        assert_eq!(drug_targets("Xalkori"), ["ALK", "ROS1", "MET"]);
+        assert!(drug_targets("docetaxel").is_empty());
+    }
+
+    #[test]
+    fn every_searched_inhibitor_maps_back_to_its_gene() {
+        for gene in KNOWN_DRUGS.iter().flat_map(|d| d.targets.iter()) {
+            for inhibitor in known_inhibitors(gene) {
+                assert!(
+                    drug_targets(inhibitor).contains(gene),
+                    "{inhibitor} should target {gene}"
+                );
+            }
+        }
+    }
+}
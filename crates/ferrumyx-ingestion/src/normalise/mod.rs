@@ -1,3 +1,5 @@
 //! Normalisation utilities for clinical and biological data.
 
 // HGNC and HGVS moved to ferrumyx-kg
+
+pub mod drug;
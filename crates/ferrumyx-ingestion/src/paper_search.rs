@@ -0,0 +1,377 @@
+//! Paper-level retrieval: groups chunk hits from [`hybrid_search`] by paper
+//! and attaches citation metadata, so one paper shows up once with its best
+//! snippet instead of as several fragments.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use chrono::Datelike;
+use ferrumyx_db::papers::{PaperReference, PaperRepository};
+use uuid::Uuid;
+
+use crate::embedding::{hybrid_search, HybridSearchConfig, SearchResult};
+use crate::repository::IngestionRepository;
+
+/// Weight of the second-best hit in a paper's score; each further hit
+/// counts half as much as the one before.
+const MULTI_HIT_BONUS: f32 = 0.15;
+/// Target snippet length in characters.
+const SNIPPET_CHARS: usize = 240;
+
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "by", "for", "from", "in", "is", "of", "on", "or", "the",
+    "to", "what", "which", "with",
+];
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PaperChunkHit {
+    pub chunk_id: Uuid,
+    pub content: String,
+    pub score: f32,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PaperSearchResult {
+    pub paper_id: Uuid,
+    pub title: Option<String>,
+    pub journal: Option<String>,
+    pub year: Option<i32>,
+    pub doi: Option<String>,
+    pub pmid: Option<String>,
+    /// DOI link, else PubMed.
+    pub url: Option<String>,
+    /// Best chunk score plus a diminishing bonus for further matching chunks.
+    pub score: f32,
+    /// HTML-escaped excerpt of the best chunk with query terms in `<mark>`.
+    pub snippet: String,
+    pub matching_chunks: usize,
+    /// Matching chunks, best first.
+    pub chunks: Vec<PaperChunkHit>,
+}
+
+/// Hybrid-search chunks, then return the top `k` papers they belong to.
+///
+/// `cfg.limit` bounds how many chunk hits are grouped; keep it a few times
+/// `k` so papers with several matching chunks are recognised.
+pub async fn search_papers(
+    repo: &IngestionRepository,
+    query_text: &str,
+    query_vec: Option<Vec<f32>>,
+    k: usize,
+    cfg: &HybridSearchConfig,
+) -> Result<Vec<PaperSearchResult>> {
+    let hits = hybrid_search(repo, query_text, query_vec, cfg).await?;
+    let paper_ids: Vec<Uuid> = hits.iter().map(|h| h.paper_id).collect();
+    let references = PaperRepository::new(repo.db())
+        .find_references_by_ids(&paper_ids)
+        .await
+        .context("Paper metadata lookup failed")?;
+    Ok(group_hits_by_paper(query_text, hits, &references, k))
+}
+
+/// Group chunk hits by paper, score each paper and keep the top `k`.
+pub fn group_hits_by_paper(
+    query_text: &str,
+    hits: Vec<SearchResult>,
+    references: &HashMap<Uuid, PaperReference>,
+    k: usize,
+) -> Vec<PaperSearchResult> {
+    let mut by_paper: HashMap<Uuid, Vec<PaperChunkHit>> = HashMap::new();
+    for hit in hits {
+        by_paper
+            .entry(hit.paper_id)
+            .or_default()
+            .push(PaperChunkHit {
+                chunk_id: hit.chunk_id,
+                content: hit.content,
+                score: hit.score,
+            });
+    }
+
+    let terms = query_terms(query_text);
+    let mut papers: Vec<PaperSearchResult> = by_paper
+        .into_iter()
+        .map(|(paper_id, mut chunks)| {
+            chunks.sort_by(|a, b| {
+                b.score
+                    .partial_cmp(&a.score)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then_with(|| a.chunk_id.cmp(&b.chunk_id))
+            });
+            let reference = references.get(&paper_id);
+            PaperSearchResult {
+                paper_id,
+                title: reference.map(|r| r.title.clone()),
+                journal: reference.and_then(|r| r.journal.clone()),
+                year: reference.and_then(|r| r.published_at).map(|d| d.year()),
+                doi: reference.and_then(|r| r.doi.clone()),
+                pmid: reference.and_then(|r| r.pmid.clone()),
+                url: reference.and_then(paper_url),
+                score: aggregate_score(chunks.iter().map(|c| c.score)),
+                snippet: highlight_snippet(&chunks[0].content, &terms),
+                matching_chunks: chunks.len(),
+                chunks,
+            }
+        })
+        .collect();
+
+    papers.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.paper_id.cmp(&b.paper_id))
+    });
+    papers.truncate(k);
+    papers
+}
+
+/// `scores` best first: the top score plus `MULTI_HIT_BONUS * s / 2^(i-1)`
+/// for the i-th further hit.
+fn aggregate_score(mut scores: impl Iterator<Item = f32>) -> f32 {
+    let Some(best) = scores.next() else {
+        return 0.0;
+    };
+    let mut weight = MULTI_HIT_BONUS;
+    let mut total = best;
+    for score in scores {
+        total += weight * score;
+        weight /= 2.0;
+    }
+    total
+}
+
+fn paper_url(reference: &PaperReference) -> Option<String> {
+    if let Some(doi) = reference
+        .doi
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+    {
+        return Some(format!("https://doi.org/{doi}"));
+    }
+    reference
+        .pmid
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|pmid| format!("https://pubmed.ncbi.nlm.nih.gov/{pmid}/"))
+}
+
+/// Lowercased query tokens worth highlighting.
+fn query_terms(query_text: &str) -> Vec<String> {
+    let mut terms: Vec<String> = query_text
+        .split(|c: char| !c.is_alphanumeric())
+        .map(|t| t.to_ascii_lowercase())
+        .filter(|t| t.chars().count() >= 2 && !STOPWORDS.contains(&t.as_str()))
+        .collect();
+    terms.sort();
+    terms.dedup();
+    terms
+}
+
+/// Byte ranges in `text` where a term starts at a word boundary, merged
+/// where they overlap.
+fn term_matches(text: &str, terms: &[String]) -> Vec<(usize, usize)> {
+    let lower = text.to_ascii_lowercase();
+    let mut ranges = Vec::new();
+    for term in terms {
+        for (start, _) in lower.match_indices(term.as_str()) {
+            let at_boundary = lower[..start]
+                .chars()
+                .next_back()
+                .is_none_or(|c| !c.is_alphanumeric());
+            if at_boundary {
+                ranges.push((start, start + term.len()));
+            }
+        }
+    }
+    ranges.sort_unstable();
+    let mut merged: Vec<(usize, usize)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// Window of about `SNIPPET_CHARS` around the first match in `content`,
+/// HTML-escaped with matches wrapped in `<mark>`.
+fn highlight_snippet(content: &str, terms: &[String]) -> String {
+    let content = content.trim();
+    let matches = term_matches(content, terms);
+    let anchor = matches.first().map(|m| m.0).unwrap_or(0);
+
+    // Start a quarter-window before the first match, on a char boundary.
+    let lead = content[..anchor]
+        .char_indices()
+        .rev()
+        .nth(SNIPPET_CHARS / 4)
+        .map(|(i, _)| i)
+        .unwrap_or(0);
+    let end = content[lead..]
+        .char_indices()
+        .nth(SNIPPET_CHARS)
+        .map(|(i, _)| lead + i)
+        .unwrap_or(content.len());
+
+    let mut out = String::new();
+    if lead > 0 {
+        out.push('…');
+    }
+    let mut cursor = lead;
+    for &(start, stop) in matches.iter().filter(|m| m.0 >= lead && m.1 <= end) {
+        out.push_str(&html_escape(&content[cursor..start]));
+        out.push_str("<mark>");
+        out.push_str(&html_escape(&content[start..stop]));
+        out.push_str("</mark>");
+        cursor = stop;
+    }
+    out.push_str(&html_escape(&content[cursor..end]));
+    if end < content.len() {
+        out.push('…');
+    }
+    out
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two KRAS chunks from one paper, one from another and an off-topic
+    /// chunk, scored as hybrid search would.
+    fn fixture() -> (Vec<SearchResult>, HashMap<Uuid, PaperReference>, [Uuid; 3]) {
+        let ids = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        let corpus = [
+            (
+                ids[0],
+                "Sotorasib inhibits KRAS G12C in lung adenocarcinoma.",
+                1.0,
+            ),
+            (ids[1], "KRAS G12C inhibitors show durable responses.", 0.9),
+            (
+                ids[1],
+                "Resistance to KRAS G12C blockade emerges via MAPK reactivation.",
+                0.85,
+            ),
+            (ids[2], "TP53 status did not change outcomes.", 0.2),
+        ];
+        let hits = corpus
+            .iter()
+            .map(|(paper_id, content, score)| SearchResult {
+                chunk_id: Uuid::new_v4(),
+                paper_id: *paper_id,
+                content: content.to_string(),
+                score: *score,
+                fts_rank: None,
+                vector_rank: None,
+                fts_score: None,
+                vector_score: None,
+                rerank_score: None,
+            })
+            .collect();
+        let mut references = HashMap::new();
+        references.insert(
+            ids[1],
+            PaperReference {
+                title: "KRAS G12C inhibition in NSCLC".to_string(),
+                doi: Some("10.1000/kras.2024".to_string()),
+                pmid: Some("38000001".to_string()),
+                journal: Some("Nature Medicine".to_string()),
+                published_at: chrono::DateTime::parse_from_rfc3339("2024-03-01T00:00:00Z")
+                    .ok()
+                    .map(|d| d.with_timezone(&chrono::Utc)),
+                ..PaperReference::default()
+            },
+        );
+        references.insert(
+            ids[0],
+            PaperReference {
+                title: "Sotorasib in lung cancer".to_string(),
+                pmid: Some("37000002".to_string()),
+                ..PaperReference::default()
+            },
+        );
+        (hits, references, ids)
+    }
+
+    #[test]
+    fn groups_chunks_by_paper_with_multi_hit_bonus() {
+        let (hits, references, ids) = fixture();
+
+        let papers = group_hits_by_paper("KRAS G12C", hits, &references, 10);
+
+        assert_eq!(papers.len(), 3);
+        // 0.9 + 0.15 * 0.85 beats a single 1.0 hit.
+        assert_eq!(papers[0].paper_id, ids[1]);
+        assert_eq!(papers[0].matching_chunks, 2);
+        assert!((papers[0].score - (0.9 + 0.15 * 0.85)).abs() < 1e-6);
+        assert!(papers[0].chunks[0].score >= papers[0].chunks[1].score);
+        assert_eq!(papers[0].journal.as_deref(), Some("Nature Medicine"));
+        assert_eq!(papers[0].year, Some(2024));
+        assert_eq!(
+            papers[0].url.as_deref(),
+            Some("https://doi.org/10.1000/kras.2024")
+        );
+
+        assert_eq!(papers[1].paper_id, ids[0]);
+        assert_eq!(papers[1].score, 1.0);
+        assert_eq!(
+            papers[1].url.as_deref(),
+            Some("https://pubmed.ncbi.nlm.nih.gov/37000002/")
+        );
+
+        // Unknown papers are kept without metadata.
+        assert_eq!(papers[2].paper_id, ids[2]);
+        assert_eq!(papers[2].title, None);
+        assert_eq!(papers[2].url, None);
+
+        let top = group_hits_by_paper("KRAS G12C", fixture().0, &references, 1);
+        assert_eq!(top.len(), 1);
+    }
+
+    #[test]
+    fn aggregate_bonus_diminishes_per_extra_hit() {
+        assert_eq!(aggregate_score([].into_iter()), 0.0);
+        assert_eq!(aggregate_score([0.8].into_iter()), 0.8);
+        let many = aggregate_score([0.5; 20].into_iter());
+        assert!(many < 0.5 + 2.0 * MULTI_HIT_BONUS * 0.5, "{many}");
+    }
+
+    #[test]
+    fn snippet_highlights_query_terms_case_insensitively() {
+        let (hits, references, _) = fixture();
+        let papers = group_hits_by_paper("kras g12c inhibitors", hits, &references, 10);
+
+        assert_eq!(
+            papers[0].snippet,
+            "<mark>KRAS</mark> <mark>G12C</mark> <mark>inhibitors</mark> show durable responses."
+        );
+        // No match: the snippet is the escaped chunk start without marks.
+        assert_eq!(papers[2].snippet, "TP53 status did not change outcomes.");
+    }
+
+    #[test]
+    fn snippet_windows_long_chunks_and_escapes_html() {
+        let terms = query_terms("the EGFR mutation");
+        assert_eq!(terms, vec!["egfr", "mutation"]);
+
+        let filler = "background text ".repeat(40);
+        let content = format!("{filler}EGFR <T790M> mutations persist. {filler}");
+        let snippet = highlight_snippet(&content, &terms);
+
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+        assert!(snippet.contains("<mark>EGFR</mark> &lt;T790M&gt; <mark>mutation</mark>s"));
+        assert!(snippet.chars().count() < SNIPPET_CHARS + 80);
+        // Only word-initial matches count.
+        assert!(!highlight_snippet("PEGFR signalling", &terms).contains("<mark>"));
+    }
+}
@@ -9,32 +9,141 @@ use uuid::Uuid;
 use crate::chunker::{chunk_document, ChunkerConfig, DocumentSection};
 use crate::models::SectionType;
 
+/// Text-quality score below which an extraction is treated as garbled.
+pub const DEFAULT_MIN_TEXT_QUALITY: f64 = 0.5;
+
+/// Text extraction strategy, in fallback order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PdfExtractor {
+    /// lopdf text extraction with a content-stream fallback for empty pages.
+    Ferrules,
+    /// pdf-extract, which decodes fonts and lays text out separately.
+    PdfExtract,
+    /// Layout-unaware dump of the literal strings in each content stream.
+    RawText,
+}
+
+impl PdfExtractor {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PdfExtractor::Ferrules => "ferrules",
+            PdfExtractor::PdfExtract => "pdf_extract",
+            PdfExtractor::RawText => "raw_text",
+        }
+    }
+}
+
+/// Signals of how much an extraction reads like prose.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextQuality {
+    /// Fraction of word tokens found in a list of common English and
+    /// biomedical words.
+    pub dictionary_word_ratio: f64,
+    /// Fraction of non-whitespace chars that are alphabetic.
+    pub alphabetic_ratio: f64,
+    /// Mean length in chars of non-empty lines.
+    pub mean_line_length: f64,
+    /// Distinct section headings (Introduction, Methods, ...) found.
+    pub section_headers: usize,
+    /// Weighted combination of the signals, in 0..=1.
+    pub score: f64,
+}
+
+/// Score how much `text` reads like prose rather than ligature soup or
+/// interleaved columns.
+pub fn score_text_quality(text: &str) -> TextQuality {
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+        .collect();
+    let known = words
+        .iter()
+        .filter(|w| DICTIONARY_WORDS.contains(w))
+        .count();
+    let dictionary_word_ratio = ratio(known, words.len());
+
+    let visible = text.chars().filter(|c| !c.is_whitespace()).count();
+    let alphabetic = text.chars().filter(|c| c.is_alphabetic()).count();
+    let alphabetic_ratio = ratio(alphabetic, visible);
+
+    let lines: Vec<usize> = text
+        .lines()
+        .map(|l| l.trim().chars().count())
+        .filter(|n| *n > 0)
+        .collect();
+    let mean_line_length = if lines.is_empty() {
+        0.0
+    } else {
+        lines.iter().sum::<usize>() as f64 / lines.len() as f64
+    };
+
+    let section_headers = SECTION_HEADERS.iter().filter(|h| words.contains(h)).count();
+
+    let score = 0.4 * (dictionary_word_ratio / 0.3).min(1.0)
+        + 0.3 * ((alphabetic_ratio - 0.5) / 0.3).clamp(0.0, 1.0)
+        + 0.15 * ((mean_line_length - 10.0) / 30.0).clamp(0.0, 1.0)
+        + 0.15 * (section_headers as f64 / 2.0).min(1.0);
+
+    TextQuality {
+        dictionary_word_ratio,
+        alphabetic_ratio,
+        mean_line_length,
+        section_headers,
+        score,
+    }
+}
+
+fn ratio(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 / whole as f64
+    }
+}
+
 /// Parse a PDF file and extract structured sections.
 pub fn parse_pdf_sections(pdf_path: &Path) -> Result<ParsedPdf> {
+    parse_pdf_sections_with_min_quality(pdf_path, DEFAULT_MIN_TEXT_QUALITY)
+}
+
+/// Parse a PDF, trying each [`PdfExtractor`] in turn until one scores at
+/// least `min_quality`. The best-scoring extraction is kept, and the result
+/// is marked `low_quality` when none reaches the threshold.
+pub fn parse_pdf_sections_with_min_quality(pdf_path: &Path, min_quality: f64) -> Result<ParsedPdf> {
     use lopdf::Document as PdfDoc;
 
     let pdf = PdfDoc::load(pdf_path)?;
 
-    // Extract text from all pages
-    let mut full_text = String::new();
-    let mut pages: Vec<(u32, String)> = Vec::new();
-
-    for (page_num, page_id) in pdf.get_pages() {
-        let mut page_text = pdf
-            .extract_text(&[page_num])
-            .ok()
-            .map(|txt| normalize_whitespace(&txt))
-            .unwrap_or_default();
-
-        if page_text.trim().is_empty() {
-            if let Ok(content) = pdf.get_page_content(page_id) {
-                page_text = extract_from_content_stream(&content);
-            }
+    let mut best = extract_with_ferrules(&pdf);
+    for extractor in [PdfExtractor::PdfExtract, PdfExtractor::RawText] {
+        if best.quality.score >= min_quality {
+            break;
         }
+        let candidate = match extractor {
+            PdfExtractor::PdfExtract => extract_with_pdf_extract(pdf_path),
+            _ => Some(extract_raw_text(&pdf)),
+        };
+        let Some(candidate) = candidate else {
+            continue;
+        };
+        tracing::debug!(
+            from = best.extractor.as_str(),
+            from_score = best.quality.score,
+            to = candidate.extractor.as_str(),
+            to_score = candidate.quality.score,
+            "Retrying low-quality PDF text extraction"
+        );
+        if candidate.quality.score > best.quality.score {
+            best = candidate;
+        }
+    }
 
-        pages.push((page_num, page_text.clone()));
+    let pages = best.pages;
+    let mut full_text = String::new();
+    for (_, page_text) in &pages {
         if !page_text.trim().is_empty() {
-            full_text.push_str(&page_text);
+            full_text.push_str(page_text);
             full_text.push('\n');
         }
     }
@@ -58,9 +167,76 @@ pub fn parse_pdf_sections(pdf_path: &Path) -> Result<ParsedPdf> {
         sections,
         full_text,
         page_count: pages.len(),
+        extractor: best.extractor,
+        quality: best.quality,
+        low_quality: best.quality.score < min_quality,
     })
 }
 
+/// Pages of normalised text from one extractor, scored on the text as
+/// extracted so line structure counts.
+struct Extraction {
+    extractor: PdfExtractor,
+    pages: Vec<(u32, String)>,
+    quality: TextQuality,
+}
+
+impl Extraction {
+    fn new(extractor: PdfExtractor, raw_pages: Vec<(u32, String)>) -> Self {
+        let raw = raw_pages
+            .iter()
+            .map(|(_, text)| text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        Self {
+            extractor,
+            quality: score_text_quality(&raw),
+            pages: raw_pages
+                .into_iter()
+                .map(|(num, text)| (num, normalize_whitespace(&text)))
+                .collect(),
+        }
+    }
+}
+
+fn extract_with_ferrules(pdf: &lopdf::Document) -> Extraction {
+    let mut pages = Vec::new();
+    for (page_num, page_id) in pdf.get_pages() {
+        let mut page_text = pdf.extract_text(&[page_num]).unwrap_or_default();
+        if page_text.trim().is_empty() {
+            if let Ok(content) = pdf.get_page_content(page_id) {
+                page_text = extract_from_content_stream(&content);
+            }
+        }
+        pages.push((page_num, page_text));
+    }
+    Extraction::new(PdfExtractor::Ferrules, pages)
+}
+
+fn extract_with_pdf_extract(pdf_path: &Path) -> Option<Extraction> {
+    // pdf-extract panics on some malformed fonts.
+    let pages = std::panic::catch_unwind(|| pdf_extract::extract_text_by_pages(pdf_path))
+        .ok()?
+        .ok()?;
+    let pages = (1u32..).zip(pages).collect();
+    Some(Extraction::new(PdfExtractor::PdfExtract, pages))
+}
+
+fn extract_raw_text(pdf: &lopdf::Document) -> Extraction {
+    let pages = pdf
+        .get_pages()
+        .into_iter()
+        .map(|(page_num, page_id)| {
+            let text = pdf
+                .get_page_content(page_id)
+                .map(|content| extract_from_content_stream(&content))
+                .unwrap_or_default();
+            (page_num, text)
+        })
+        .collect();
+    Extraction::new(PdfExtractor::RawText, pages)
+}
+
 fn extract_from_content_stream(content: &[u8]) -> String {
     let mut out = String::new();
     let mut in_literal = false;
@@ -204,8 +380,156 @@ pub struct ParsedPdf {
     pub sections: Vec<DocumentSection>,
     pub full_text: String,
     pub page_count: usize,
+    /// Extractor whose text was kept.
+    pub extractor: PdfExtractor,
+    pub quality: TextQuality,
+    /// No extractor reached the quality threshold.
+    pub low_quality: bool,
 }
 
+/// Common English function words and biomedical terms, for the
+/// dictionary-word signal of [`score_text_quality`].
+const DICTIONARY_WORDS: &[&str] = &[
+    "a",
+    "about",
+    "after",
+    "all",
+    "also",
+    "among",
+    "an",
+    "analysis",
+    "and",
+    "are",
+    "as",
+    "assay",
+    "associated",
+    "at",
+    "be",
+    "been",
+    "between",
+    "both",
+    "but",
+    "by",
+    "cancer",
+    "cell",
+    "cells",
+    "clinical",
+    "compared",
+    "control",
+    "could",
+    "data",
+    "did",
+    "disease",
+    "do",
+    "dose",
+    "drug",
+    "during",
+    "each",
+    "effect",
+    "effects",
+    "expression",
+    "factor",
+    "for",
+    "found",
+    "from",
+    "function",
+    "gene",
+    "genes",
+    "group",
+    "groups",
+    "had",
+    "has",
+    "have",
+    "high",
+    "however",
+    "human",
+    "if",
+    "in",
+    "increased",
+    "into",
+    "is",
+    "it",
+    "its",
+    "level",
+    "levels",
+    "low",
+    "may",
+    "more",
+    "most",
+    "mutation",
+    "mutations",
+    "no",
+    "not",
+    "of",
+    "on",
+    "one",
+    "or",
+    "other",
+    "our",
+    "overall",
+    "patients",
+    "protein",
+    "rate",
+    "reduced",
+    "response",
+    "results",
+    "risk",
+    "samples",
+    "showed",
+    "significant",
+    "significantly",
+    "such",
+    "study",
+    "than",
+    "that",
+    "the",
+    "their",
+    "them",
+    "then",
+    "there",
+    "these",
+    "they",
+    "this",
+    "those",
+    "through",
+    "time",
+    "to",
+    "treatment",
+    "tumor",
+    "tumors",
+    "tumour",
+    "tumours",
+    "two",
+    "type",
+    "under",
+    "using",
+    "was",
+    "we",
+    "were",
+    "when",
+    "where",
+    "which",
+    "while",
+    "who",
+    "with",
+    "within",
+    "without",
+    "would",
+];
+
+/// Headings counted by the section-header signal.
+const SECTION_HEADERS: &[&str] = &[
+    "abstract",
+    "introduction",
+    "background",
+    "methods",
+    "results",
+    "discussion",
+    "conclusion",
+    "conclusions",
+    "references",
+];
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -217,4 +541,125 @@ mod tests {
         let sections = detect_sections(text, &pages);
         assert!(!sections.is_empty());
     }
+
+    const GARBLED_PDF: &[u8] = include_bytes!("../tests/fixtures/garbled_text.pdf");
+
+    const CLEAN_LINES: &[&str] = &[
+        "KRAS G12D in pancreatic cancer",
+        "Introduction",
+        "KRAS mutations are found in most pancreatic ductal adenocarcinomas and drive",
+        "tumor growth through sustained MAPK signalling in the cells of these patients.",
+        "Methods",
+        "We compared the expression of target genes between the treatment and control",
+        "groups using RNA sequencing of tumor samples collected during the study.",
+        "Results",
+        "Inhibition of the mutant protein reduced tumor growth and the effect was",
+        "significant in both groups of patients with high levels of expression.",
+    ];
+
+    fn write_temp(bytes: &[u8]) -> tempfile::NamedTempFile {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut file, bytes).unwrap();
+        file
+    }
+
+    /// One-page PDF with each of `lines` on its own text line.
+    fn text_pdf(lines: &[&str]) -> tempfile::NamedTempFile {
+        use lopdf::content::{Content, Operation};
+        use lopdf::{dictionary, Document, Object, Stream};
+
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let font_id = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Helvetica",
+            "Encoding" => "WinAnsiEncoding",
+        });
+        let resources_id = doc.add_object(dictionary! {
+            "Font" => dictionary! { "F1" => font_id },
+        });
+        let mut operations = vec![
+            Operation::new("BT", vec![]),
+            Operation::new("Tf", vec!["F1".into(), 10.into()]),
+            Operation::new("TL", vec![12.into()]),
+            Operation::new("Td", vec![56.into(), 760.into()]),
+        ];
+        for line in lines {
+            operations.push(Operation::new("Tj", vec![Object::string_literal(*line)]));
+            operations.push(Operation::new("T*", vec![]));
+        }
+        operations.push(Operation::new("ET", vec![]));
+        let content = Content { operations }.encode().unwrap();
+        let content_id = doc.add_object(Stream::new(dictionary! {}, content));
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "Contents" => content_id,
+        });
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => vec![page_id.into()],
+                "Count" => 1,
+                "Resources" => resources_id,
+                "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes).unwrap();
+        write_temp(&bytes)
+    }
+
+    #[test]
+    fn quality_separates_prose_from_garbled_text() {
+        let prose = score_text_quality(&CLEAN_LINES.join("\n"));
+        assert!(prose.score >= DEFAULT_MIN_TEXT_QUALITY, "{prose:?}");
+        assert_eq!(prose.section_headers, 3);
+        assert!(prose.alphabetic_ratio > 0.8);
+
+        let columns = score_text_quality(
+            "muta lled dr ween pancr rch duc nd adenoca ober\nG1 ents muta re dr lled pancr ween",
+        );
+        assert!(columns.score < DEFAULT_MIN_TEXT_QUALITY, "{columns:?}");
+        let soup = score_text_quality("<#7 ' \"- <5@< 85{< }:$/4 >` `q ]7=\"> .32@3");
+        assert!(soup.score < DEFAULT_MIN_TEXT_QUALITY, "{soup:?}");
+        assert_eq!(score_text_quality("").score, 0.0);
+    }
+
+    #[test]
+    fn clean_pdf_keeps_ferrules_text() {
+        let file = text_pdf(CLEAN_LINES);
+        let parsed = parse_pdf_sections(file.path()).unwrap();
+        assert!(!parsed.low_quality, "{:?}", parsed.quality);
+        assert_eq!(parsed.extractor, PdfExtractor::Ferrules);
+        assert!(parsed
+            .full_text
+            .contains("pancreatic ductal adenocarcinomas"));
+        assert!(parsed
+            .sections
+            .iter()
+            .any(|s| s.section_type == SectionType::Methods));
+    }
+
+    #[test]
+    fn garbled_pdf_fails_every_extractor() {
+        let file = write_temp(GARBLED_PDF);
+        let parsed = parse_pdf_sections(file.path()).unwrap();
+        assert!(parsed.low_quality);
+        assert!(parsed.quality.score < DEFAULT_MIN_TEXT_QUALITY);
+        assert!(!parsed.sections.is_empty());
+
+        // A permissive threshold keeps the Ferrules text.
+        let lenient = parse_pdf_sections_with_min_quality(file.path(), 0.0).unwrap();
+        assert!(!lenient.low_quality);
+        assert_eq!(lenient.extractor, PdfExtractor::Ferrules);
+    }
 }
@@ -15,14 +15,16 @@
 //! The pipeline is designed to be called from both the Ferrumyx Runtime Core tool
 //! (`ferrumyx-agent/src/tools/ingestion_tool.rs`) and the web API.
 
+use chrono::{DateTime, Utc};
+use futures::FutureExt;
 use serde::{Deserialize, Serialize};
-use std::collections::{hash_map::DefaultHasher, BTreeMap, HashMap, HashSet, VecDeque};
+use std::collections::{hash_map::DefaultHasher, BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::hash::{Hash, Hasher};
+use std::panic::AssertUnwindSafe;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::sync::OnceLock;
-use std::time::Instant as StdInstant;
 use tempfile::NamedTempFile;
 use tokio::sync::broadcast;
 use tokio::sync::mpsc;
@@ -30,46 +32,82 @@ use tokio::sync::OnceCell;
 use tokio::sync::Semaphore;
 use tokio::time::sleep;
 use tokio::time::timeout;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, instrument, warn};
 use uuid::Uuid;
 
+use crate::backpressure::{
+    estimate_chunk_bytes, ChunkBufferBudget, PaperWindow, PaperWindowPermit, PipelineMemoryBudget,
+};
 use crate::chunker::{chunk_document, ChunkerConfig, DocumentSection};
+use crate::citations::harvest_citations;
 use crate::embedding::{
-    embed_pending_chunks, embed_pending_chunks_for_papers, EmbeddingClient, EmbeddingConfig,
+    cached_tokenizer, embed_pending_chunks, embed_pending_chunks_for_papers_bounded,
+    EmbeddingClient, EmbeddingConfig,
 };
+use crate::enrichment::{enrich_paper_ids, enrichment_client};
+use crate::full_text::{FullTextDocument, FullTextResolver, FullTextRoute};
 use crate::models::SectionType;
-use crate::pdf_parser::parse_pdf_sections;
+use crate::pdf_parser::{parse_pdf_sections_with_min_quality, DEFAULT_MIN_TEXT_QUALITY};
+use crate::rate_limit::source_bucket;
 use crate::repository::IngestionRepository;
 use crate::sources::arxiv::ArxivClient;
 use crate::sources::biorxiv::BioRxivClient;
-use crate::sources::clinicaltrials::ClinicalTrialsClient;
+use crate::sources::clinicaltrials::{
+    condition_for_cancer_code, target_search_term, ClinicalTrialsClient,
+};
 use crate::sources::crossref::CrossRefClient;
 use crate::sources::europepmc::EuropePmcClient;
 use crate::sources::pubmed::PubMedClient;
+use crate::sources::scihub::SciHubClient;
 use crate::sources::semanticscholar::SemanticScholarClient;
 use crate::sources::unpaywall::UnpaywallClient;
 use crate::sources::LiteratureSource;
+use crate::trials::ingest_trials;
+use ferrumyx_common::confidence::REVIEW_STUDY_TYPE;
+use ferrumyx_common::repro;
+use ferrumyx_db::chunks::ChunkRepository;
 use ferrumyx_db::entities::EntityRepository;
+use ferrumyx_db::failed_items::FailedItemRepository;
+use ferrumyx_db::ingestion_watermarks::IngestionWatermarkRepository;
+use ferrumyx_db::paper_tombstones::{PaperTombstoneRepository, TombstonedIds};
 use ferrumyx_db::papers::PaperRepository;
-use ferrumyx_db::schema::{Entity as DbEntity, EntityType as DbEntityType, KgFact};
+use ferrumyx_db::schema::{
+    Entity as DbEntity, EntityType as DbEntityType, FailedItem, IngestionWatermark, KgFact,
+};
 use ferrumyx_kg::extraction::build_facts_batch;
-use ferrumyx_kg::ner::{EntityType as NerEntityType, TrieNer};
+use ferrumyx_kg::ner::{
+    AbbreviationMap, EntityType as NerEntityType, ExtractedEntity, HybridNer, NerFilter, NerMode,
+    SpanTagger, TrieNer,
+};
+use ferrumyx_kg::KgRepository;
 use sha2::{Digest, Sha256};
 
 static SHARED_NER: OnceCell<Arc<TrieNer>> = OnceCell::const_new();
+static NER_TAGGER: OnceLock<Arc<dyn SpanTagger>> = OnceLock::new();
+static MISSING_NER_TAGGER_WARNED: std::sync::Once = std::sync::Once::new();
 static PDF_HTTP_CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
 static PDF_HOST_LIMITS: OnceLock<std::sync::Mutex<HashMap<String, Arc<Semaphore>>>> =
     OnceLock::new();
 static HEAVY_LANE_LIMITER: OnceLock<Arc<Semaphore>> = OnceLock::new();
-static SCIHUB_ADAPTIVE_STATE: OnceLock<Mutex<ScihubAdaptiveState>> = OnceLock::new();
 
+/// Every PDF found for a paper scored below the text-quality gate.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("PDF text quality {score:.2} is below the threshold")]
+struct LowQualityText {
+    score: f64,
+    /// Sections of the best-scoring extraction.
+    sections: Vec<DocumentSection>,
+}
+
+/// Full text fetched for a paper.
 #[derive(Debug, Default)]
-struct ScihubAdaptiveState {
-    attempts: u64,
-    successes: u64,
-    consecutive_failures: u64,
-    cooldown_until: Option<StdInstant>,
-    cooldown_skips: u64,
+struct FetchedFullText {
+    sections: Vec<DocumentSection>,
+    /// Route the sections came from.
+    route: Option<FullTextRoute>,
+    /// Text-quality score when `sections` come from a PDF below the gate.
+    low_quality: Option<f64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -86,6 +124,10 @@ struct ParsedPdfCacheEntry {
     char_count: usize,
     #[serde(default)]
     cached_at_epoch_secs: u64,
+    #[serde(default)]
+    text_quality: Option<f64>,
+    #[serde(default)]
+    low_quality: bool,
     sections: Vec<DocumentSection>,
 }
 
@@ -116,6 +158,44 @@ async fn get_or_init_ner() -> anyhow::Result<Arc<TrieNer>> {
         .cloned()
 }
 
+/// Register the tagger whose spans `FERRUMYX_NER_MODE=hybrid` merges with
+/// dictionary matches. Only the first registration takes effect; returns
+/// whether this one did.
+pub fn set_ner_tagger(tagger: Arc<dyn SpanTagger>) -> bool {
+    NER_TAGGER.set(tagger).is_ok()
+}
+
+/// Entities of each chunk text under `mode`. Hybrid mode falls back to
+/// dictionary matches when no tagger is registered or the tagger fails.
+fn extract_chunk_entities(
+    ner: &Arc<TrieNer>,
+    mode: NerMode,
+    tagger: Option<&dyn SpanTagger>,
+    texts: &[&str],
+) -> Vec<Vec<ExtractedEntity>> {
+    let tagger = match (mode, tagger) {
+        (NerMode::Trie, _) => return ner.extract_batch(texts),
+        (NerMode::Hybrid, None) => {
+            MISSING_NER_TAGGER_WARNED.call_once(|| {
+                warn!("hybrid NER mode has no registered tagger; using dictionary matches");
+            });
+            return ner.extract_batch(texts);
+        }
+        (NerMode::Hybrid, Some(tagger)) => tagger,
+    };
+    let hybrid = HybridNer::new(Arc::clone(ner));
+    texts
+        .iter()
+        .map(|text| match hybrid.extract(text, tagger) {
+            Ok(entities) => entities.into_iter().map(|e| e.entity).collect(),
+            Err(err) => {
+                warn!("NER tagger failed, using dictionary matches: {err:#}");
+                ner.extract(text)
+            }
+        })
+        .collect()
+}
+
 #[derive(Debug, Default)]
 struct PaperProcessingResult {
     chunks_inserted: usize,
@@ -127,8 +207,42 @@ struct PaperProcessingResult {
     unique_predicates: HashSet<String>,
     errors: Vec<String>,
     heavy_task: Option<tokio::task::JoinHandle<PaperProcessingResult>>,
+    /// Chunks were persisted but not buffered for in-process embedding because
+    /// the chunk buffer budget was exhausted; the DB-backed pass embeds them.
+    spilled: bool,
+    /// The paper could not be ingested and belongs in `failed_items`.
+    failure: Option<PaperFailure>,
+}
+
+/// Why one paper could not be ingested.
+#[derive(Debug, Clone, PartialEq)]
+struct PaperFailure {
+    /// Stage the paper had reached, e.g. "chunking" or "chunk_insert".
+    stage: &'static str,
+    error: String,
+}
+
+/// Stage a paper's processing has reached, read back when it panics.
+#[derive(Debug)]
+struct PaperStage(Mutex<&'static str>);
+
+impl PaperStage {
+    fn new() -> Self {
+        Self(Mutex::new("processing"))
+    }
+
+    fn enter(&self, stage: &'static str) {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = stage;
+    }
+
+    fn current(&self) -> &'static str {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
 }
 
+/// Failure messages kept in [`IngestionResult::failure_samples`].
+const FAILURE_SAMPLE_LIMIT: usize = 5;
+
 // ── Job config ────────────────────────────────────────────────────────────────
 
 /// Parameters for a single ingestion run.
@@ -165,6 +279,23 @@ pub struct IngestionJob {
     pub source_cache_enabled: bool,
     /// TTL for source search cache entries.
     pub source_cache_ttl_secs: Option<u64>,
+    /// Memory budget (in-flight paper window, channel sizes, chunk buffer bytes).
+    /// Defaults to 512 MB of buffered chunk content.
+    #[serde(default)]
+    pub memory_budget: PipelineMemoryBudget,
+    /// Reproducibility seed. When set, ids and timestamps are derived
+    /// deterministically so reruns over the same inputs persist identical rows.
+    #[serde(default)]
+    pub repro_seed: Option<u64>,
+    /// Only search for papers added on or after this instant. PubMed and
+    /// Europe PMC apply it as a date filter; other sources ignore it and
+    /// rely on deduplication against stored papers.
+    #[serde(default)]
+    pub since: Option<DateTime<Utc>>,
+    /// With `since` unset, bound each source by the time the previous run
+    /// of the same query against it started (its stored watermark).
+    #[serde(default)]
+    pub incremental: bool,
 }
 
 /// Which literature sources to search.
@@ -208,6 +339,10 @@ impl Default for IngestionJob {
             full_text_prefetch_workers: None,
             source_cache_enabled: true,
             source_cache_ttl_secs: Some(30 * 60),
+            memory_budget: PipelineMemoryBudget::default(),
+            repro_seed: None,
+            since: None,
+            incremental: false,
         }
     }
 }
@@ -247,6 +382,8 @@ pub struct IngestionSourceTelemetry {
     pub source: String,
     pub fetched: usize,
     pub error: Option<String>,
+    /// Lower date bound the source was searched with, if any.
+    pub since: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -255,7 +392,10 @@ pub struct IngestionResult {
     pub query: String,
     pub papers_found_raw: usize,
     pub papers_found: usize,
+    /// Papers not seen before, i.e. newly discovered.
     pub papers_inserted: usize,
+    /// Papers skipped because the database already had them, or had
+    /// deleted them with a tombstone.
     pub papers_duplicate: usize,
     pub inserted_paper_ids: Vec<Uuid>,
     pub chunks_inserted: usize,
@@ -263,7 +403,43 @@ pub struct IngestionResult {
     pub source_telemetry: Vec<IngestionSourceTelemetry>,
     pub perf_telemetry: IngestionPerfTelemetry,
     pub errors: Vec<String>,
+    /// New papers processed to the end.
+    pub papers_succeeded: usize,
+    /// New papers that failed and were recorded to `failed_items`.
+    pub papers_failed: usize,
+    /// The first few of those failures, as "<paper>: <stage>: <error>".
+    pub failure_samples: Vec<String>,
     pub duration_ms: u64,
+    /// The run stopped early because its cancellation token fired.
+    pub cancelled: bool,
+}
+
+impl IngestionResult {
+    /// "cancelled", "succeeded", "partial" (errors or failed papers, but
+    /// papers found) or "failed" (errors and nothing found).
+    pub fn status(&self) -> &'static str {
+        let clean = self.errors.is_empty() && self.papers_failed == 0;
+        match (self.cancelled, clean, self.papers_found) {
+            (true, _, _) => "cancelled",
+            (false, true, _) => "succeeded",
+            (false, false, 0) => "failed",
+            (false, false, _) => "partial",
+        }
+    }
+
+    /// Run errors followed by the sampled paper failures, joined with "; ",
+    /// or `None` for a clean run.
+    pub fn error_summary(&self) -> Option<String> {
+        let mut messages: Vec<String> = self.errors.clone();
+        messages.extend(self.failure_samples.iter().cloned());
+        let unsampled = self
+            .papers_failed
+            .saturating_sub(self.failure_samples.len());
+        if unsampled > 0 {
+            messages.push(format!("{unsampled} more papers failed"));
+        }
+        (!messages.is_empty()).then(|| messages.join("; "))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Default)]
@@ -283,6 +459,9 @@ pub struct IngestionPerfTelemetry {
     pub predicate_generic_share: f64,
     pub predicate_coverage_flagged: bool,
     pub predicate_histogram: BTreeMap<String, usize>,
+    pub peak_inflight_papers: usize,
+    pub peak_chunk_buffer_bytes: usize,
+    pub spilled_papers: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -318,6 +497,12 @@ pub struct IngestionPerfSnapshot {
     pub predicate_coverage_flagged: bool,
     #[serde(default)]
     pub predicate_histogram: BTreeMap<String, usize>,
+    #[serde(default)]
+    pub peak_inflight_papers: usize,
+    #[serde(default)]
+    pub peak_chunk_buffer_bytes: usize,
+    #[serde(default)]
+    pub spilled_papers: usize,
 }
 
 // ── Pipeline orchestrator ─────────────────────────────────────────────────────
@@ -326,18 +511,68 @@ pub struct IngestionPerfSnapshot {
 ///
 /// Progress events are sent via `progress_tx` if provided.
 /// The pipeline is non-destructive: on errors it logs and continues.
-#[instrument(skip(repo, progress_tx))]
 pub async fn run_ingestion(
     job: IngestionJob,
     repo: Arc<IngestionRepository>,
     progress_tx: Option<broadcast::Sender<IngestionProgress>>,
 ) -> IngestionResult {
-    let job_id = Uuid::new_v4();
+    run_ingestion_with_cancel(job, repo, progress_tx, CancellationToken::new()).await
+}
+
+/// [`run_ingestion`] that stops early once `cancel` fires.
+///
+/// The token is checked between stages and between papers: papers already
+/// being processed finish, nothing new is fetched or started, and the
+/// result comes back with `cancelled` set. Papers stored before the cancel
+/// stay stored.
+#[instrument(skip(repo, progress_tx, cancel))]
+pub async fn run_ingestion_with_cancel(
+    job: IngestionJob,
+    repo: Arc<IngestionRepository>,
+    progress_tx: Option<broadcast::Sender<IngestionProgress>>,
+    cancel: CancellationToken,
+) -> IngestionResult {
+    run_ingestion_with_overrides(job, repo, progress_tx, cancel, PipelineOverrides::default()).await
+}
+
+/// Stand-ins for the parts of a run that reach the network. Production runs
+/// use the defaults; tests supply papers, dictionaries and a chunker so the
+/// whole orchestration runs offline.
+#[derive(Clone, Default)]
+struct PipelineOverrides {
+    /// Papers to ingest instead of searching the job's sources.
+    papers: Option<Vec<crate::models::PaperMetadata>>,
+    /// NER to use instead of the shared HGNC/OncoTree dictionaries.
+    ner: Option<Arc<TrieNer>>,
+    /// Chunker to use instead of the one derived from the embedding model.
+    chunker: Option<ChunkerConfig>,
+    /// Skip CrossRef enrichment and citation harvesting.
+    skip_enrichment: bool,
+}
+
+async fn run_ingestion_with_overrides(
+    job: IngestionJob,
+    repo: Arc<IngestionRepository>,
+    progress_tx: Option<broadcast::Sender<IngestionProgress>>,
+    cancel: CancellationToken,
+    overrides: PipelineOverrides,
+) -> IngestionResult {
+    let _repro_scope = job.repro_seed.map(repro::override_seed);
+    let repro_seed = repro::seed();
+    let config_hash = job_config_hash(&job);
+    let job_id = repro::new_id("ingestion_job", &config_hash);
     let t0 = std::time::Instant::now();
+    let run_started_at = Utc::now();
 
     // Build search query
     let query = build_query(&job);
     info!(job_id = %job_id, query = %query, "Starting ingestion pipeline");
+    if let Err(e) = repo
+        .record_run_config(job_id, repro_seed, &config_hash)
+        .await
+    {
+        warn!(job_id = %job_id, "Failed to record run config: {e}");
+    }
 
     let emit = |stage: &str, msg: &str, mut prog: IngestionProgress| {
         prog.stage = stage.to_string();
@@ -366,7 +601,11 @@ pub async fn run_ingestion(
         "Loading biomedical NER databases",
         base_progress.clone(),
     );
-    let ner = match get_or_init_ner().await {
+    let ner = match overrides.ner.clone() {
+        Some(ner) => Ok(ner),
+        None => get_or_init_ner().await,
+    };
+    let ner = match ner {
         Ok(ner) => ner,
         Err(e) => {
             let msg = format!(
@@ -386,7 +625,11 @@ pub async fn run_ingestion(
                 source_telemetry: Vec::new(),
                 perf_telemetry: IngestionPerfTelemetry::default(),
                 errors: vec![msg],
+                papers_succeeded: 0,
+                papers_failed: 0,
+                failure_samples: Vec::new(),
                 duration_ms: (std::time::Instant::now() - t0).as_millis() as u64,
+                cancelled: false,
             };
         }
     };
@@ -414,10 +657,30 @@ pub async fn run_ingestion(
         source_telemetry: Vec::new(),
         perf_telemetry: IngestionPerfTelemetry::default(),
         errors: Vec::new(),
+        papers_succeeded: 0,
+        papers_failed: 0,
+        failure_samples: Vec::new(),
         duration_ms: 0,
+        cancelled: false,
     };
 
     let prog_base = IngestionProgress::new(job_id, "search", "");
+    let stop_cancelled = |mut result: IngestionResult, stage: &str| {
+        result.cancelled = true;
+        result.duration_ms = t0.elapsed().as_millis() as u64;
+        info!(job_id = %job_id, stage, "Ingestion cancelled");
+        emit("cancelled", &format!("Cancelled before {stage}"), {
+            let mut p = prog_base.clone();
+            p.papers_found = result.papers_found;
+            p.papers_inserted = result.papers_inserted;
+            p.chunks_inserted = result.chunks_inserted;
+            p
+        });
+        result
+    };
+    if cancel.is_cancelled() {
+        return stop_cancelled(result, "search");
+    }
     emit(
         "search",
         &format!("Searching with query: {query}"),
@@ -443,14 +706,49 @@ pub async fn run_ingestion(
     let abort_on_unique_target = resolve_search_abort_on_unique_target();
     let mut aborted_for_unique_target = false;
 
+    let watermark_repo = IngestionWatermarkRepository::new(repo.db());
+    let mut watermarks: HashMap<String, IngestionWatermark> = HashMap::new();
     let mut source_tasks = tokio::task::JoinSet::new();
-    for source in job.sources.clone() {
+    let source_timeout =
+        std::time::Duration::from_secs(job.source_timeout_secs.unwrap_or(45).clamp(5, 300));
+    let mut trial_task = None;
+    let sources = if overrides.papers.is_some() {
+        Vec::new()
+    } else {
+        job.sources.clone()
+    };
+    if let Some(papers) = overrides.papers.clone() {
+        papers_found_raw_total = papers.len();
+        all_papers = papers;
+    }
+    for source in sources {
+        if source == IngestionSourceSpec::ClinicalTrials {
+            trial_task = Some(spawn_trial_ingestion(
+                &job,
+                repo.db(),
+                per_source_max_results,
+                source_timeout,
+            ));
+            continue;
+        }
         let source_query = build_query_for_source(&job, &source);
+        let watermark = match watermark_repo
+            .find(&source_query, &format!("{:?}", source))
+            .await
+        {
+            Ok(found) => found,
+            Err(e) => {
+                warn!(source = ?source, "Failed to load ingestion watermark: {e}");
+                None
+            }
+        };
+        let since = resolve_since(&job, watermark.as_ref());
+        if let Some(watermark) = watermark {
+            watermarks.insert(format!("{:?}", source), watermark);
+        }
         let max_results = per_source_max_results;
         let pubmed_api_key = job.pubmed_api_key.clone();
         let semantic_scholar_api_key = job.semantic_scholar_api_key.clone();
-        let source_timeout =
-            std::time::Duration::from_secs(job.source_timeout_secs.unwrap_or(45).clamp(5, 300));
         let source_cache_enabled = job.source_cache_enabled;
         let source_cache_ttl_secs = job.source_cache_ttl_secs;
         let source_semaphore = source_semaphore.clone();
@@ -464,6 +762,7 @@ pub async fn run_ingestion(
                     source.clone(),
                     &source_query,
                     max_results,
+                    since.map(|s| s.date_naive()),
                     pubmed_api_key,
                     semantic_scholar_api_key,
                     source_cache_enabled,
@@ -478,13 +777,14 @@ pub async fn run_ingestion(
                     source_timeout.as_secs()
                 ))
             });
-            (source, source_result)
+            (source, source_query, since, source_result)
         });
     }
 
+    let mut searched_watermarks = Vec::new();
     while let Some(joined) = source_tasks.join_next().await {
         match joined {
-            Ok((source, Ok(papers))) => {
+            Ok((source, source_query, since, Ok(papers))) => {
                 papers_found_raw_total += papers.len();
                 let mut source_local_seen = HashSet::new();
                 let mut unique_added = 0usize;
@@ -509,7 +809,16 @@ pub async fn run_ingestion(
                     source: format!("{:?}", source),
                     fetched: papers.len(),
                     error: None,
+                    since,
                 });
+                let source_key = format!("{:?}", source);
+                searched_watermarks.push(advance_watermark(
+                    watermarks.remove(&source_key),
+                    &source_query,
+                    &source_key,
+                    run_started_at,
+                    &papers,
+                ));
                 if abort_on_unique_target
                     && !aborted_for_unique_target
                     && all_papers.len() >= unique_target
@@ -523,13 +832,14 @@ pub async fn run_ingestion(
                     );
                 }
             }
-            Ok((source, Err(e))) => {
+            Ok((source, _, since, Err(e))) => {
                 let msg = format!("Source {:?} error: {e}", source);
                 warn!("{}", &msg);
                 result.source_telemetry.push(IngestionSourceTelemetry {
                     source: format!("{:?}", source),
                     fetched: 0,
                     error: Some(e.to_string()),
+                    since,
                 });
                 result.errors.push(msg);
             }
@@ -543,6 +853,25 @@ pub async fn run_ingestion(
             }
         }
     }
+    if let Some(task) = trial_task {
+        let source = format!("{:?}", IngestionSourceSpec::ClinicalTrials);
+        let (fetched, error) = match task.await {
+            Ok(Ok(report)) => (report.fetched, None),
+            Ok(Err(e)) => (0, Some(e.to_string())),
+            Err(e) => (0, Some(format!("task join error: {e}"))),
+        };
+        if let Some(e) = &error {
+            let msg = format!("Source {source} error: {e}");
+            warn!("{}", &msg);
+            result.errors.push(msg);
+        }
+        result.source_telemetry.push(IngestionSourceTelemetry {
+            source,
+            fetched,
+            error,
+            since: None,
+        });
+    }
     result.perf_telemetry.search_ms = t_search.elapsed().as_millis() as u64;
 
     // Source-level dedupe before DB upsert to avoid repeatedly processing
@@ -554,6 +883,9 @@ pub async fn run_ingestion(
 
     result.papers_found = all_papers.len();
     result.perf_telemetry.dedup_ms = t_dedup.elapsed().as_millis() as u64;
+    if cancel.is_cancelled() {
+        return stop_cancelled(result, "upsert");
+    }
     emit(
         "upsert",
         &format!("{} unique papers found, deduplicating…", all_papers.len()),
@@ -565,93 +897,30 @@ pub async fn run_ingestion(
     );
 
     // ── 2. Upsert papers + chunk abstracts ───────────────────────────────────
-    let chunker_cfg = ChunkerConfig::default();
+    let chunker_cfg = overrides
+        .chunker
+        .clone()
+        .unwrap_or_else(|| resolve_chunker_config(job.embedding_cfg.as_ref()));
     let t_upsert = std::time::Instant::now();
-    let mut queued_new_papers: Vec<(crate::models::PaperMetadata, Uuid)> =
-        Vec::with_capacity(all_papers.len());
-    let paper_repo = PaperRepository::new(repo.db());
-    let mut candidate_dois: Vec<String> = all_papers
-        .iter()
-        .filter_map(|p| p.doi.as_deref().and_then(canonical_doi))
-        .collect();
-    candidate_dois.sort();
-    candidate_dois.dedup();
-    let mut candidate_pmids: Vec<String> = all_papers
-        .iter()
-        .filter_map(|p| p.pmid.as_deref().and_then(canonical_pmid))
-        .collect();
-    candidate_pmids.sort();
-    candidate_pmids.dedup();
-    let existing_by_doi_raw = paper_repo
-        .find_ids_by_dois(&candidate_dois, 200)
-        .await
-        .unwrap_or_default();
-    let existing_by_pmid_raw = paper_repo
-        .find_ids_by_pmids(&candidate_pmids, 200)
-        .await
-        .unwrap_or_default();
-    let mut existing_by_doi: HashMap<String, Uuid> = HashMap::new();
-    for (raw, id) in existing_by_doi_raw {
-        if let Some(norm) = canonical_doi(&raw) {
-            existing_by_doi.insert(norm, id);
-        }
-    }
-    let mut existing_by_pmid: HashMap<String, Uuid> = HashMap::new();
-    for (raw, id) in existing_by_pmid_raw {
-        if let Some(norm) = canonical_pmid(&raw) {
-            existing_by_pmid.insert(norm, id);
-        }
-    }
-    for paper in all_papers {
-        let doi_hit = paper
-            .doi
-            .as_ref()
-            .and_then(|d| canonical_doi(d))
-            .and_then(|d| existing_by_doi.get(&d).copied());
-        let pmid_hit = paper
-            .pmid
-            .as_ref()
-            .and_then(|p| canonical_pmid(p))
-            .and_then(|p| existing_by_pmid.get(&p).copied());
-        if doi_hit.is_some() || pmid_hit.is_some() {
-            result.papers_duplicate += 1;
-            continue;
-        }
+    let queued_new_papers = upsert_new_papers(&repo, all_papers, &mut result).await;
+    result.perf_telemetry.upsert_ms = t_upsert.elapsed().as_millis() as u64;
 
-        let upsert = match repo.upsert_paper(&paper).await {
-            Ok(u) => u,
-            Err(e) => {
-                let id = paper
-                    .pmid
-                    .as_ref()
-                    .or(paper.doi.as_ref())
-                    .map(|s| s.as_str())
-                    .unwrap_or("unknown");
-                let msg = format!("paper upsert failed for {}: {e}", id);
-                warn!("{}", &msg);
-                result.errors.push(msg);
-                continue;
-            }
-        };
-        if !upsert.was_new {
-            result.papers_duplicate += 1;
-            continue;
-        }
-        result.papers_inserted += 1;
-        result.inserted_paper_ids.push(upsert.paper_id);
-        if let Some(doi) = paper.doi.as_deref().and_then(canonical_doi) {
-            existing_by_doi.insert(doi, upsert.paper_id);
-        }
-        if let Some(pmid) = paper.pmid.as_deref().and_then(canonical_pmid) {
-            existing_by_pmid.insert(pmid, upsert.paper_id);
+    // Papers are stored; later runs of these queries can start from here.
+    for watermark in &searched_watermarks {
+        if let Err(e) = watermark_repo.upsert(watermark).await {
+            warn!(source = %watermark.source, "Failed to store ingestion watermark: {e}");
         }
-        queued_new_papers.push((paper, upsert.paper_id));
+    }
+    if cancel.is_cancelled() {
+        return stop_cancelled(result, "processing");
     }
 
-    result.perf_telemetry.upsert_ms = t_upsert.elapsed().as_millis() as u64;
-
-    let full_text_step_timeout =
-        std::time::Duration::from_secs(job.full_text_step_timeout_secs.unwrap_or(15).clamp(5, 120));
+    // Backpressure: every stage (prefetch workers, paper workers, queued
+    // heavy enrichment) holds at most `channel_capacity` papers; the paper
+    // window bounds how many full texts can be resident between fetch and
+    // persistence; the chunk budget bounds how much chunk content background
+    // enrichment may hold in memory.
+    let memory_budget = job.memory_budget.sanitized();
     let prefetch_worker_limit = job
         .full_text_prefetch_workers
         .unwrap_or_else(|| {
@@ -659,8 +928,9 @@ pub async fn run_ingestion(
                 .map(|n| n.get().clamp(2, 8))
                 .unwrap_or(4)
         })
-        .clamp(1, 32);
-    let paper_worker_limit = resolve_paper_process_workers();
+        .clamp(1, 32)
+        .min(memory_budget.channel_capacity);
+    let paper_worker_limit = resolve_paper_process_workers().min(memory_budget.channel_capacity);
     let total_new_papers = queued_new_papers.len();
     let t_prefetch = std::time::Instant::now();
 
@@ -678,57 +948,52 @@ pub async fn run_ingestion(
         },
     );
 
-    let (prefetch_tx, mut prefetch_rx) =
-        mpsc::channel::<(crate::models::PaperMetadata, Uuid, Vec<DocumentSection>)>(
-            total_new_papers.min(256).max(1),
-        );
-    let unpaywall_email = job.unpaywall_email.clone();
-    let enable_scihub = job.enable_scihub_fallback;
+    let paper_window = PaperWindow::new(memory_budget.max_inflight_papers);
+    let chunk_budget = ChunkBufferBudget::new(memory_budget.max_chunk_buffer_bytes);
+    let mut spilled_paper_ids: Vec<Uuid> = Vec::new();
+    let (prefetch_tx, mut prefetch_rx) = mpsc::channel::<PrefetchedPaper>(
+        total_new_papers.min(memory_budget.channel_capacity).max(1),
+    );
+    let resolver = Arc::new(full_text_resolver(&job));
     let full_text_enabled = job.full_text_enabled;
     let prefetch_input = queued_new_papers;
+    let prefetch_window = paper_window.clone();
+    let prefetch_cancel = cancel.clone();
     let prefetch_task = tokio::spawn(async move {
         let prefetch_started_at = std::time::Instant::now();
         if !full_text_enabled {
             for (paper, paper_id) in prefetch_input {
-                let _ = prefetch_tx.send((paper, paper_id, Vec::new())).await;
-            }
-            return prefetch_started_at.elapsed().as_millis() as u64;
-        }
-
-        let mut set = tokio::task::JoinSet::new();
-        let mut remaining = prefetch_input.into_iter();
-        let mut input_exhausted = false;
-        while !input_exhausted || !set.is_empty() {
-            while set.len() < prefetch_worker_limit {
-                let Some((paper, paper_id)) = remaining.next() else {
-                    input_exhausted = true;
+                let permit = prefetch_window.acquire().await;
+                if prefetch_cancel.is_cancelled() {
                     break;
-                };
-                let unpaywall_email = unpaywall_email.clone();
-                set.spawn(async move {
-                    let sections = fetch_full_text_sections_for_paper(
-                        &paper,
-                        unpaywall_email.as_deref(),
-                        enable_scihub,
-                        full_text_step_timeout,
-                    )
-                    .await
-                    .unwrap_or_default();
-                    (paper, paper_id, sections)
-                });
-            }
-
-            if let Some(joined) = set.join_next().await {
-                if let Ok(payload) = joined {
-                    let _ = prefetch_tx.send(payload).await;
                 }
+                let _ = prefetch_tx
+                    .send((paper, paper_id, FetchedFullText::default(), permit))
+                    .await;
             }
+            return prefetch_started_at.elapsed().as_millis() as u64;
         }
+
+        prefetch_full_texts(
+            prefetch_input,
+            prefetch_worker_limit,
+            &prefetch_window,
+            &prefetch_cancel,
+            &prefetch_tx,
+            |paper, paper_id| {
+                let paper = paper.clone();
+                let resolver = resolver.clone();
+                async move { fetch_full_text_isolated(&paper, paper_id, &resolver).await }
+            },
+        )
+        .await;
         prefetch_started_at.elapsed().as_millis() as u64
     });
 
+    let failed_items = FailedItemRepository::new(repo.db());
     let mut processing_set = tokio::task::JoinSet::new();
-    let mut heavy_tasks = Vec::new();
+    let mut heavy_tasks = VecDeque::new();
+    let drain_heavy_lane = resolve_heavy_lane_async_enabled() && resolve_heavy_lane_drain_enabled();
     let mut completed = 0usize;
     let mut adaptive_process_limit = paper_worker_limit.clamp(1, 16);
     let mut predicate_hist: HashMap<String, usize> = HashMap::new();
@@ -745,65 +1010,130 @@ pub async fn run_ingestion(
             Some(g)
         }
     };
+    let mut cancel_seen = false;
     while prefetch_rx.is_closed() == false || !processing_set.is_empty() {
-        while processing_set.len() < adaptive_process_limit {
-            let maybe_payload =
-                match timeout(processing_heartbeat_interval, prefetch_rx.recv()).await {
-                    Ok(payload) => payload,
-                    Err(_) => {
-                        emit(
-                            "progress",
-                            &format!(
-                                "Waiting on full-text prefetch ({}/{} papers complete, inflight={}, prefetch_backlog={})",
-                                completed,
-                                total_new_papers,
-                                processing_set.len(),
-                                prefetch_rx.len()
-                            ),
-                            {
-                                let mut p = prog_base.clone();
-                                p.papers_found = result.papers_found;
-                                p.papers_inserted = result.papers_inserted;
-                                p.chunks_inserted = result.chunks_inserted;
-                                p
-                            },
-                        );
-                        continue;
-                    }
-                };
-            let Some((paper, paper_id, full_text_sections)) = maybe_payload else {
+        if cancel.is_cancelled() && !cancel_seen {
+            // Stop the prefetcher and release the window slots of papers
+            // fetched but not started; in-flight papers still finish.
+            cancel_seen = true;
+            prefetch_rx.close();
+            while prefetch_rx.try_recv().is_ok() {}
+            emit(
+                "cancelling",
+                &format!(
+                    "Cancelling: waiting for {} in-flight papers ({}/{} complete)",
+                    processing_set.len(),
+                    completed,
+                    total_new_papers
+                ),
+                {
+                    let mut p = prog_base.clone();
+                    p.papers_found = result.papers_found;
+                    p.papers_inserted = result.papers_inserted;
+                    p.chunks_inserted = result.chunks_inserted;
+                    p
+                },
+            );
+        }
+        while !cancel_seen && processing_set.len() < adaptive_process_limit {
+            let received = tokio::select! {
+                _ = cancel.cancelled() => break,
+                received = timeout(processing_heartbeat_interval, prefetch_rx.recv()) => received,
+            };
+            let maybe_payload = match received {
+                Ok(payload) => payload,
+                Err(_) => {
+                    emit(
+                        "progress",
+                        &format!(
+                            "Waiting on full-text prefetch ({}/{} papers complete, inflight={}, prefetch_backlog={})",
+                            completed,
+                            total_new_papers,
+                            processing_set.len(),
+                            prefetch_rx.len()
+                        ),
+                        {
+                            let mut p = prog_base.clone();
+                            p.papers_found = result.papers_found;
+                            p.papers_inserted = result.papers_inserted;
+                            p.chunks_inserted = result.chunks_inserted;
+                            p
+                        },
+                    );
+                    continue;
+                }
+            };
+            let Some((paper, paper_id, full_text, window_permit)) = maybe_payload else {
                 break;
             };
+            let chunk_budget_clone = chunk_budget.clone();
             let repo_clone = repo.clone();
             let ner_clone = ner.clone();
             let chunker_cfg_clone = chunker_cfg.clone();
             let embed_client_clone = embed_client.clone();
             let query_gene_hint_clone = query_gene_hint.clone();
             let defer_embedding_to_global_batch_clone = defer_embedding_to_global_batch;
+            let failed_paper = paper.clone();
             processing_set.spawn(async move {
-                process_single_paper(
+                let outcome = process_paper_isolated(
                     paper,
                     paper_id,
-                    full_text_sections,
+                    full_text,
                     query_gene_hint_clone,
                     repo_clone,
                     ner_clone,
                     chunker_cfg_clone,
                     embed_client_clone,
                     defer_embedding_to_global_batch_clone,
+                    chunk_budget_clone,
                 )
-                .await
+                .await;
+                drop(window_permit);
+                (failed_paper, paper_id, outcome)
             });
         }
 
         match timeout(processing_heartbeat_interval, processing_set.join_next()).await {
             Ok(Some(joined)) => match joined {
-                Ok(outcome) => {
+                Ok((paper, paper_id, outcome)) => {
                     completed += 1;
+                    if outcome.spilled {
+                        spilled_paper_ids.push(paper_id);
+                    }
+                    note_paper_outcome(
+                        &failed_items,
+                        Some(job_id),
+                        &mut result,
+                        &paper,
+                        paper_id,
+                        outcome.failure.as_ref(),
+                    )
+                    .await;
                     if let Some(heavy_task) =
                         merge_paper_processing_outcome(&mut result, &mut predicate_hist, outcome)
                     {
-                        heavy_tasks.push(heavy_task);
+                        heavy_tasks.push_back(heavy_task);
+                    }
+                    // A draining run waits on the oldest enrichment rather
+                    // than queueing more than a stage's worth of papers.
+                    while drain_heavy_lane && heavy_tasks.len() > memory_budget.channel_capacity {
+                        let Some(oldest) = heavy_tasks.pop_front() else {
+                            break;
+                        };
+                        match oldest.await {
+                            Ok(heavy) => {
+                                let _ = merge_paper_processing_outcome(
+                                    &mut result,
+                                    &mut predicate_hist,
+                                    heavy,
+                                );
+                            }
+                            Err(e) => {
+                                let msg = format!("heavy enrichment task join error: {e}");
+                                warn!("{}", msg);
+                                result.errors.push(msg);
+                            }
+                        }
                     }
                     emit(
                         "progress",
@@ -892,10 +1222,12 @@ pub async fn run_ingestion(
             }
         }
     };
-    let heavy_lane_async = resolve_heavy_lane_async_enabled();
-    let drain_heavy_lane = heavy_lane_async && resolve_heavy_lane_drain_enabled();
     let heavy_lane_pending_for_telemetry = !drain_heavy_lane && !heavy_tasks.is_empty();
-    if drain_heavy_lane {
+    if cancel.is_cancelled() {
+        for task in &heavy_tasks {
+            task.abort();
+        }
+    } else if drain_heavy_lane {
         let heavy_total = heavy_tasks.len();
         for (heavy_idx, task) in heavy_tasks.into_iter().enumerate() {
             let mut task = task;
@@ -943,10 +1275,23 @@ pub async fn run_ingestion(
         }
     }
 
-    if defer_embedding_to_global_batch {
+    // Spilled papers were persisted without in-process embedding; the global
+    // pass reads their chunks back from the DB in bounded batches.
+    let embed_pass_paper_ids: Vec<Uuid> = if defer_embedding_to_global_batch {
+        result.inserted_paper_ids.clone()
+    } else {
+        spilled_paper_ids.clone()
+    };
+    // A cancelled run leaves its chunks for the embedding backfill.
+    if !embed_pass_paper_ids.is_empty() && !cancel.is_cancelled() {
         if let Some(ref ec) = embed_client {
-            match embed_pending_chunks_for_papers(ec.as_ref(), repo.as_ref(), &result.inserted_paper_ids)
-                .await
+            match embed_pending_chunks_for_papers_bounded(
+                ec.as_ref(),
+                repo.as_ref(),
+                &embed_pass_paper_ids,
+                memory_budget.max_chunk_buffer_bytes,
+            )
+            .await
             {
                 Ok(n) => {
                     result.chunks_embedded += n;
@@ -959,23 +1304,76 @@ pub async fn run_ingestion(
             }
         }
     }
-    result.perf_telemetry.process_ms = t_process.elapsed().as_millis() as u64;
-
-    let (pdf_hits, pdf_misses) = pdf_cache_counters();
-    result.perf_telemetry.pdf_cache_hits = pdf_hits;
-    result.perf_telemetry.pdf_cache_misses = pdf_misses;
-    result.perf_telemetry.unique_predicate_count = predicate_hist.len();
-    result.perf_telemetry.predicate_histogram = predicate_hist
-        .iter()
-        .map(|(k, v)| (k.clone(), *v))
-        .collect::<BTreeMap<_, _>>();
-    let total_relation_facts = result.perf_telemetry.relation_fact_count.max(1);
-    result.perf_telemetry.predicate_generic_share =
-        (result.perf_telemetry.generic_relation_fact_count as f64 / total_relation_facts as f64)
-            .clamp(0.0, 1.0);
-    let min_unique = resolve_predicate_coverage_min_unique();
-    let max_generic_share = resolve_predicate_coverage_max_generic_share();
-    result.perf_telemetry.predicate_coverage_flagged = result.perf_telemetry.unique_predicate_count
+    let mailto = resolve_crossref_mailto().or_else(|| job.unpaywall_email.clone());
+    let crossref = enrichment_client(mailto, resolve_crossref_requests_per_second());
+    // CrossRef fills the DOIs and citation counts the sources left out.
+    if !result.inserted_paper_ids.is_empty()
+        && !cancel.is_cancelled()
+        && !overrides.skip_enrichment
+        && resolve_crossref_enrich_enabled()
+    {
+        match enrich_paper_ids(repo.db(), &crossref, &result.inserted_paper_ids).await {
+            Ok(report) => {
+                info!(
+                    scanned = report.scanned,
+                    dois_filled = report.dois_filled,
+                    citation_counts = report.citation_counts_updated,
+                    "CrossRef enrichment complete"
+                );
+                result.errors.extend(report.errors);
+            }
+            Err(e) => {
+                let msg = format!("CrossRef enrichment failed: {e}");
+                warn!("{}", msg);
+                result.errors.push(msg);
+            }
+        }
+    }
+    // Reference lists, after enrichment so DOI-less papers have one.
+    if !result.inserted_paper_ids.is_empty()
+        && !cancel.is_cancelled()
+        && !overrides.skip_enrichment
+        && resolve_citation_harvest_enabled()
+    {
+        let europepmc = EuropePmcClient::new();
+        match harvest_citations(repo.db(), &crossref, &europepmc, &result.inserted_paper_ids).await
+        {
+            Ok(report) => {
+                info!(
+                    scanned = report.scanned,
+                    citations = report.citations_stored,
+                    internal_links = report.internal_links,
+                    "Citation harvesting complete"
+                );
+                result.errors.extend(report.errors);
+            }
+            Err(e) => {
+                let msg = format!("Citation harvesting failed: {e}");
+                warn!("{}", msg);
+                result.errors.push(msg);
+            }
+        }
+    }
+    result.perf_telemetry.process_ms = t_process.elapsed().as_millis() as u64;
+    result.perf_telemetry.peak_inflight_papers = paper_window.peak_inflight();
+    result.perf_telemetry.peak_chunk_buffer_bytes = chunk_budget.peak();
+    result.perf_telemetry.spilled_papers = spilled_paper_ids.len();
+
+    let (pdf_hits, pdf_misses) = pdf_cache_counters();
+    result.perf_telemetry.pdf_cache_hits = pdf_hits;
+    result.perf_telemetry.pdf_cache_misses = pdf_misses;
+    result.perf_telemetry.unique_predicate_count = predicate_hist.len();
+    result.perf_telemetry.predicate_histogram = predicate_hist
+        .iter()
+        .map(|(k, v)| (k.clone(), *v))
+        .collect::<BTreeMap<_, _>>();
+    let total_relation_facts = result.perf_telemetry.relation_fact_count.max(1);
+    result.perf_telemetry.predicate_generic_share =
+        (result.perf_telemetry.generic_relation_fact_count as f64 / total_relation_facts as f64)
+            .clamp(0.0, 1.0);
+    let min_unique = resolve_predicate_coverage_min_unique();
+    let max_generic_share = resolve_predicate_coverage_max_generic_share();
+    result.perf_telemetry.predicate_coverage_flagged = result.perf_telemetry.unique_predicate_count
         < min_unique
         || result.perf_telemetry.predicate_generic_share > max_generic_share;
     if heavy_lane_pending_for_telemetry && result.perf_telemetry.relation_fact_count == 0 {
@@ -1013,6 +1411,7 @@ pub async fn run_ingestion(
         predicate_coverage_flagged = result.perf_telemetry.predicate_coverage_flagged,
         duration_ms     = result.duration_ms,
         errors          = result.errors.len(),
+        papers_failed   = result.papers_failed,
         perf_search_ms  = result.perf_telemetry.search_ms,
         perf_upsert_ms  = result.perf_telemetry.upsert_ms,
         perf_process_ms = result.perf_telemetry.process_ms,
@@ -1034,14 +1433,22 @@ pub async fn run_ingestion(
 
     persist_perf_snapshot(&result);
 
+    result.cancelled = cancel.is_cancelled();
+    let (final_stage, done) = if result.cancelled {
+        ("cancelled", "Cancelled")
+    } else {
+        ("complete", "Done")
+    };
     emit(
-        "complete",
+        final_stage,
         &format!(
-            "Done. {} new papers, {} chunks ({} embedded), {} duplicates skipped.",
+            "{}. {} new papers, {} chunks ({} embedded), {} duplicates skipped, {} failed.",
+            done,
             result.papers_inserted,
             result.chunks_inserted,
             result.chunks_embedded,
-            result.papers_duplicate
+            result.papers_duplicate,
+            result.papers_failed
         ),
         {
             let mut p = prog_base.clone();
@@ -1055,6 +1462,332 @@ pub async fn run_ingestion(
     result
 }
 
+/// Retry up to `limit` papers from `failed_items`, oldest failure first.
+///
+/// Each paper is processed again from its stored metadata with `job`'s
+/// full-text and embedding settings, after dropping any chunks the failed
+/// attempt left behind. Papers that get through leave the table; papers
+/// that fail again stay, with one more retry counted.
+pub async fn retry_failed_items(
+    job: IngestionJob,
+    repo: Arc<IngestionRepository>,
+    limit: usize,
+) -> IngestionResult {
+    let t0 = std::time::Instant::now();
+    let mut result = IngestionResult {
+        job_id: Uuid::new_v4(),
+        query: "retry failed items".to_string(),
+        papers_found_raw: 0,
+        papers_found: 0,
+        papers_inserted: 0,
+        papers_duplicate: 0,
+        inserted_paper_ids: Vec::new(),
+        chunks_inserted: 0,
+        chunks_embedded: 0,
+        source_telemetry: Vec::new(),
+        perf_telemetry: IngestionPerfTelemetry::default(),
+        errors: Vec::new(),
+        papers_succeeded: 0,
+        papers_failed: 0,
+        failure_samples: Vec::new(),
+        duration_ms: 0,
+        cancelled: false,
+    };
+    let ner = match get_or_init_ner().await {
+        Ok(ner) => ner,
+        Err(e) => {
+            result.errors.push(format!("Failed to initialize NER: {e}"));
+            return result;
+        }
+    };
+    let failed_items = FailedItemRepository::new(repo.db());
+    let items = match failed_items.requeue(limit).await {
+        Ok(items) => items,
+        Err(e) => {
+            result
+                .errors
+                .push(format!("Failed to requeue failed items: {e}"));
+            return result;
+        }
+    };
+    result.papers_found_raw = items.len();
+    result.papers_found = items.len();
+    info!(items = items.len(), "Retrying failed ingestion items");
+
+    let chunker_cfg = resolve_chunker_config(job.embedding_cfg.as_ref());
+    let embed_client = job
+        .embedding_cfg
+        .as_ref()
+        .map(|cfg| Arc::new(EmbeddingClient::new(cfg.clone())));
+    let chunk_budget = ChunkBufferBudget::new(job.memory_budget.sanitized().max_chunk_buffer_bytes);
+    let resolver = full_text_resolver(&job);
+    let chunks = ChunkRepository::new(repo.db());
+    let mut predicate_hist = HashMap::new();
+    for item in items {
+        let paper: crate::models::PaperMetadata = match serde_json::from_str(&item.metadata) {
+            Ok(paper) => paper,
+            Err(e) => {
+                result.papers_failed += 1;
+                let failed = FailedItem {
+                    stage: "metadata".to_string(),
+                    error: format!("stored metadata is unreadable: {e}"),
+                    last_failed_at: Utc::now(),
+                    ..item
+                };
+                if let Err(e) = failed_items.record(&failed).await {
+                    warn!(paper_id = %failed.paper_id, "Failed to record failed ingestion item: {e}");
+                }
+                continue;
+            }
+        };
+        if let Err(e) = chunks.delete_by_paper_id(item.paper_id).await {
+            warn!(paper_id = %item.paper_id, "Failed to clear chunks before retry: {e}");
+        }
+        let full_text = if job.full_text_enabled {
+            fetch_full_text_isolated(&paper, item.paper_id, &resolver).await
+        } else {
+            FetchedFullText::default()
+        };
+        let mut outcome = process_paper_isolated(
+            paper.clone(),
+            item.paper_id,
+            full_text,
+            None,
+            repo.clone(),
+            ner.clone(),
+            chunker_cfg.clone(),
+            embed_client.clone(),
+            false,
+            chunk_budget.clone(),
+        )
+        .await;
+        let failure = outcome.failure.take();
+        if let Some(heavy_task) =
+            merge_paper_processing_outcome(&mut result, &mut predicate_hist, outcome)
+        {
+            match heavy_task.await {
+                Ok(heavy) => {
+                    let _ = merge_paper_processing_outcome(&mut result, &mut predicate_hist, heavy);
+                }
+                Err(e) => result
+                    .errors
+                    .push(format!("heavy enrichment task join error: {e}")),
+            }
+        }
+        note_paper_outcome(
+            &failed_items,
+            item.job_id,
+            &mut result,
+            &paper,
+            item.paper_id,
+            failure.as_ref(),
+        )
+        .await;
+        if failure.is_none() {
+            if let Err(e) = failed_items.resolve(item.paper_id).await {
+                warn!(paper_id = %item.paper_id, "Failed to clear retried ingestion item: {e}");
+            }
+        }
+    }
+    result.duration_ms = t0.elapsed().as_millis() as u64;
+    info!(
+        succeeded = result.papers_succeeded,
+        failed = result.papers_failed,
+        duration_ms = result.duration_ms,
+        "Failed item retry complete"
+    );
+    result
+}
+
+/// Hash of the job parameters that shape a run, with credentials removed so
+/// rotating a key does not change it.
+fn job_config_hash(job: &IngestionJob) -> String {
+    let mut redacted = job.clone();
+    redacted.pubmed_api_key = None;
+    redacted.semantic_scholar_api_key = None;
+    redacted.unpaywall_email = None;
+    if let Some(cfg) = redacted.embedding_cfg.as_mut() {
+        cfg.api_key = None;
+    }
+    repro::config_hash(&redacted)
+}
+
+/// Insert the papers the database does not have yet and count the rest as
+/// duplicates. Returns the inserted papers with their new ids.
+async fn upsert_new_papers(
+    repo: &IngestionRepository,
+    all_papers: Vec<crate::models::PaperMetadata>,
+    result: &mut IngestionResult,
+) -> Vec<(crate::models::PaperMetadata, Uuid)> {
+    let mut queued_new_papers: Vec<(crate::models::PaperMetadata, Uuid)> =
+        Vec::with_capacity(all_papers.len());
+    let paper_repo = PaperRepository::new(repo.db());
+    let mut candidate_dois: Vec<String> = all_papers
+        .iter()
+        .flat_map(|p| [p.doi.as_deref(), p.published_doi()])
+        .flatten()
+        .filter_map(canonical_doi)
+        .collect();
+    candidate_dois.sort();
+    candidate_dois.dedup();
+    let mut candidate_pmids: Vec<String> = all_papers
+        .iter()
+        .filter_map(|p| p.pmid.as_deref().and_then(canonical_pmid))
+        .collect();
+    candidate_pmids.sort();
+    candidate_pmids.dedup();
+    let existing_by_doi_raw = paper_repo
+        .find_ids_by_dois(&candidate_dois, 200)
+        .await
+        .unwrap_or_default();
+    let existing_by_pmid_raw = paper_repo
+        .find_ids_by_pmids(&candidate_pmids, 200)
+        .await
+        .unwrap_or_default();
+    let mut existing_by_doi: HashMap<String, Uuid> = HashMap::new();
+    for (raw, id) in existing_by_doi_raw {
+        if let Some(norm) = canonical_doi(&raw) {
+            existing_by_doi.insert(norm, id);
+        }
+    }
+    let mut existing_by_pmid: HashMap<String, Uuid> = HashMap::new();
+    for (raw, id) in existing_by_pmid_raw {
+        if let Some(norm) = canonical_pmid(&raw) {
+            existing_by_pmid.insert(norm, id);
+        }
+    }
+    let tombstoned = tombstoned_ids(repo).await;
+    for paper in all_papers {
+        if is_tombstoned(&paper, &tombstoned) {
+            debug!(doi = ?paper.doi, pmid = ?paper.pmid, "Skipping tombstoned paper");
+            result.papers_duplicate += 1;
+            continue;
+        }
+        // A preprint is a duplicate of its own DOI or of the journal
+        // article it was published as.
+        let doi_hit = [paper.doi.as_deref(), paper.published_doi()]
+            .into_iter()
+            .flatten()
+            .filter_map(canonical_doi)
+            .find_map(|d| existing_by_doi.get(&d).copied());
+        let pmid_hit = paper
+            .pmid
+            .as_ref()
+            .and_then(|p| canonical_pmid(p))
+            .and_then(|p| existing_by_pmid.get(&p).copied());
+        if doi_hit.is_some() || pmid_hit.is_some() {
+            result.papers_duplicate += 1;
+            continue;
+        }
+
+        let upsert = match repo
+            .upsert_paper(&paper, Some(result.job_id), Some(&result.query))
+            .await
+        {
+            Ok(u) => u,
+            Err(e) => {
+                let id = paper
+                    .pmid
+                    .as_ref()
+                    .or(paper.doi.as_ref())
+                    .map(|s| s.as_str())
+                    .unwrap_or("unknown");
+                let msg = format!("paper upsert failed for {}: {e}", id);
+                warn!("{}", &msg);
+                result.errors.push(msg);
+                continue;
+            }
+        };
+        if !upsert.was_new {
+            result.papers_duplicate += 1;
+            continue;
+        }
+        result.papers_inserted += 1;
+        result.inserted_paper_ids.push(upsert.paper_id);
+        if let Some(doi) = paper.doi.as_deref().and_then(canonical_doi) {
+            existing_by_doi.insert(doi, upsert.paper_id);
+        }
+        if let Some(pmid) = paper.pmid.as_deref().and_then(canonical_pmid) {
+            existing_by_pmid.insert(pmid, upsert.paper_id);
+        }
+        queued_new_papers.push((paper, upsert.paper_id));
+    }
+
+    queued_new_papers
+}
+
+/// Identifiers of deleted papers to keep out of this run, canonicalised;
+/// empty with `FERRUMYX_INGESTION_SKIP_TOMBSTONED=0`.
+async fn tombstoned_ids(repo: &IngestionRepository) -> TombstonedIds {
+    let skip = std::env::var("FERRUMYX_INGESTION_SKIP_TOMBSTONED")
+        .ok()
+        .is_none_or(|v| !(v == "0" || v.eq_ignore_ascii_case("false")));
+    if !skip {
+        return TombstonedIds::default();
+    }
+    match PaperTombstoneRepository::new(repo.db())
+        .tombstoned_ids()
+        .await
+    {
+        Ok(ids) => TombstonedIds {
+            dois: ids.dois.iter().filter_map(|d| canonical_doi(d)).collect(),
+            pmids: ids.pmids.iter().filter_map(|p| canonical_pmid(p)).collect(),
+        },
+        Err(e) => {
+            warn!("Failed to load paper tombstones: {e}");
+            TombstonedIds::default()
+        }
+    }
+}
+
+fn is_tombstoned(paper: &crate::models::PaperMetadata, tombstoned: &TombstonedIds) -> bool {
+    paper
+        .doi
+        .as_deref()
+        .and_then(canonical_doi)
+        .is_some_and(|d| tombstoned.dois.contains(&d))
+        || paper
+            .pmid
+            .as_deref()
+            .and_then(canonical_pmid)
+            .is_some_and(|p| tombstoned.pmids.contains(&p))
+}
+
+/// Lower date bound for one source: the job's own `since`, or with
+/// `incremental` set the start of the previous run of the same query.
+fn resolve_since(
+    job: &IngestionJob,
+    watermark: Option<&IngestionWatermark>,
+) -> Option<DateTime<Utc>> {
+    match (job.since, job.incremental) {
+        (Some(since), _) => Some(since),
+        (None, true) => watermark.map(|w| w.last_run_at),
+        (None, false) => None,
+    }
+}
+
+/// Watermark of a search that started at `run_started_at` and returned
+/// `papers`, carrying the newest publication date forward from `previous`.
+fn advance_watermark(
+    previous: Option<IngestionWatermark>,
+    query: &str,
+    source: &str,
+    run_started_at: DateTime<Utc>,
+    papers: &[crate::models::PaperMetadata],
+) -> IngestionWatermark {
+    let mut watermark = previous.unwrap_or_else(|| {
+        IngestionWatermark::new(query.to_string(), source.to_string(), run_started_at)
+    });
+    watermark.last_run_at = run_started_at;
+    watermark.last_seen_publication_date = papers
+        .iter()
+        .filter_map(|p| p.pub_date)
+        .chain(watermark.last_seen_publication_date)
+        .max();
+    watermark.paper_count = papers.len() as i64;
+    watermark
+}
+
 // ── Query builder ─────────────────────────────────────────────────────────────
 
 /// Build a PubMed/Europe PMC compatible search query.
@@ -1153,8 +1886,14 @@ fn canonical_title_key(raw: &str) -> Option<String> {
     }
 }
 
+/// A preprint that has been published keys on its journal DOI, so it and the
+/// journal record collapse into one.
 fn canonical_paper_identity_key(paper: &crate::models::PaperMetadata) -> String {
-    if let Some(doi) = paper.doi.as_deref().and_then(canonical_doi) {
+    if let Some(doi) = paper
+        .published_doi()
+        .or(paper.doi.as_deref())
+        .and_then(canonical_doi)
+    {
         return format!("doi:{doi}");
     }
     if let Some(pmid) = paper.pmid.as_deref().and_then(canonical_pmid) {
@@ -1167,6 +1906,70 @@ fn canonical_paper_identity_key(paper: &crate::models::PaperMetadata) -> String
     format!("title:{title}")
 }
 
+/// Days of bioRxiv/medRxiv listings harvested per search.
+fn resolve_preprint_window_days() -> i64 {
+    std::env::var("FERRUMYX_PREPRINT_WINDOW_DAYS")
+        .ok()
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .unwrap_or(90)
+        .clamp(1, 3650)
+}
+
+fn resolve_crossref_enrich_enabled() -> bool {
+    std::env::var("FERRUMYX_CROSSREF_ENRICH_ENABLED")
+        .ok()
+        .is_none_or(|v| !(v == "0" || v.eq_ignore_ascii_case("false")))
+}
+
+/// Chunk budget and overlap from `FERRUMYX_CHUNK_MAX_TOKENS` and
+/// `FERRUMYX_CHUNK_OVERLAP_SENTENCES`, measured with the embedding model's
+/// tokenizer when it is cached locally.
+fn resolve_chunker_config(embedding: Option<&EmbeddingConfig>) -> ChunkerConfig {
+    let defaults = ChunkerConfig::default();
+    let config = ChunkerConfig {
+        max_tokens: std::env::var("FERRUMYX_CHUNK_MAX_TOKENS")
+            .ok()
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .unwrap_or(defaults.max_tokens)
+            .clamp(32, 8_192),
+        overlap_sentences: std::env::var("FERRUMYX_CHUNK_OVERLAP_SENTENCES")
+            .ok()
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .unwrap_or(defaults.overlap_sentences)
+            .min(8),
+        ..defaults
+    };
+    match embedding.and_then(|cfg| cached_tokenizer(&cfg.model)) {
+        Some(tokenizer) => config.with_tokenizer(Arc::new(tokenizer)),
+        None => {
+            debug!("Embedding tokenizer not cached; chunk budget uses the word estimate");
+            config
+        }
+    }
+}
+
+fn resolve_citation_harvest_enabled() -> bool {
+    std::env::var("FERRUMYX_CITATION_HARVEST_ENABLED")
+        .ok()
+        .is_none_or(|v| !(v == "0" || v.eq_ignore_ascii_case("false")))
+}
+
+/// Contact address for CrossRef's polite pool.
+fn resolve_crossref_mailto() -> Option<String> {
+    std::env::var("FERRUMYX_CROSSREF_MAILTO")
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+fn resolve_crossref_requests_per_second() -> u32 {
+    std::env::var("FERRUMYX_CROSSREF_REQUESTS_PER_SECOND")
+        .ok()
+        .and_then(|v| v.trim().parse::<u32>().ok())
+        .unwrap_or(5)
+        .clamp(1, 50)
+}
+
 fn resolve_search_unique_target_multiplier() -> f64 {
     std::env::var("FERRUMYX_INGESTION_UNIQUE_TARGET_MULTIPLIER")
         .ok()
@@ -1307,6 +2110,22 @@ fn resolve_ingestion_validation_mode() -> IngestionValidationMode {
     }
 }
 
+fn resolve_pdf_min_text_quality() -> f64 {
+    std::env::var("FERRUMYX_PDF_MIN_TEXT_QUALITY")
+        .ok()
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .unwrap_or(DEFAULT_MIN_TEXT_QUALITY)
+        .clamp(0.0, 1.0)
+}
+
+/// Process papers whose full text failed the text-quality gate instead of
+/// excluding them from chunking, embedding and NER.
+fn resolve_pdf_low_quality_include() -> bool {
+    std::env::var("FERRUMYX_PDF_LOW_QUALITY_INCLUDE")
+        .ok()
+        .is_some_and(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+}
+
 fn resolve_pdf_parse_min_sections() -> usize {
     std::env::var("FERRUMYX_PDF_PARSE_MIN_SECTIONS")
         .ok()
@@ -1391,52 +2210,6 @@ fn resolve_max_relation_genes_per_chunk() -> usize {
         .clamp(1, 16)
 }
 
-fn resolve_scihub_defer_ms() -> u64 {
-    std::env::var("FERRUMYX_SCIHUB_DEFER_MS")
-        .ok()
-        .and_then(|v| v.trim().parse::<u64>().ok())
-        .unwrap_or(700)
-        .clamp(0, 10_000)
-}
-
-fn resolve_scihub_adaptive_enabled() -> bool {
-    std::env::var("FERRUMYX_SCIHUB_ADAPTIVE_ENABLED")
-        .ok()
-        .is_none_or(|v| !(v == "0" || v.eq_ignore_ascii_case("false")))
-}
-
-fn resolve_scihub_adaptive_fail_streak() -> u64 {
-    std::env::var("FERRUMYX_SCIHUB_ADAPTIVE_FAIL_STREAK")
-        .ok()
-        .and_then(|v| v.trim().parse::<u64>().ok())
-        .unwrap_or(8)
-        .clamp(3, 200)
-}
-
-fn resolve_scihub_adaptive_backoff_secs() -> u64 {
-    std::env::var("FERRUMYX_SCIHUB_ADAPTIVE_BACKOFF_SECS")
-        .ok()
-        .and_then(|v| v.trim().parse::<u64>().ok())
-        .unwrap_or(300)
-        .clamp(15, 3600)
-}
-
-fn resolve_scihub_adaptive_probe_every() -> u64 {
-    std::env::var("FERRUMYX_SCIHUB_ADAPTIVE_PROBE_EVERY")
-        .ok()
-        .and_then(|v| v.trim().parse::<u64>().ok())
-        .unwrap_or(10)
-        .clamp(1, 256)
-}
-
-fn resolve_scihub_adaptive_min_step_timeout_secs() -> u64 {
-    std::env::var("FERRUMYX_SCIHUB_ADAPTIVE_MIN_STEP_TIMEOUT_SECS")
-        .ok()
-        .and_then(|v| v.trim().parse::<u64>().ok())
-        .unwrap_or(3)
-        .clamp(2, 60)
-}
-
 fn paper_process_workers_from_config() -> Option<usize> {
     let path = std::env::var("FERRUMYX_CONFIG").unwrap_or_else(|_| "ferrumyx.toml".to_string());
     let content = std::fs::read_to_string(path).ok()?;
@@ -1482,6 +2255,12 @@ struct FullTextNegativeCacheEntry {
     reason: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FullTextSuccessCacheEntry {
+    route: FullTextRoute,
+    sections: Vec<DocumentSection>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct ChunkFingerprintCacheEntry {
     cached_at_epoch_secs: u64,
@@ -1579,7 +2358,7 @@ fn clear_full_text_negative(key: &str) {
     let _ = std::fs::remove_file(full_text_negative_cache_path(key));
 }
 
-fn load_full_text_success(key: &str) -> Option<Vec<DocumentSection>> {
+fn load_full_text_success(key: &str) -> Option<(FullTextRoute, Vec<DocumentSection>)> {
     if !resolve_full_text_success_cache_enabled() {
         return None;
     }
@@ -1593,21 +2372,25 @@ fn load_full_text_success(key: &str) -> Option<Vec<DocumentSection>> {
         return None;
     }
     let payload = std::fs::read_to_string(path).ok()?;
-    let sections: Vec<DocumentSection> = serde_json::from_str(&payload).ok()?;
-    if sections.is_empty() {
+    let entry: FullTextSuccessCacheEntry = serde_json::from_str(&payload).ok()?;
+    if entry.sections.is_empty() {
         None
     } else {
-        Some(sections)
+        Some((entry.route, entry.sections))
     }
 }
 
-fn save_full_text_success(key: &str, sections: &[DocumentSection]) {
+fn save_full_text_success(key: &str, route: FullTextRoute, sections: &[DocumentSection]) {
     if !resolve_full_text_success_cache_enabled() || sections.is_empty() {
         return;
     }
     let dir = full_text_success_cache_dir();
     let _ = std::fs::create_dir_all(&dir);
-    if let Ok(payload) = serde_json::to_string(sections) {
+    let entry = FullTextSuccessCacheEntry {
+        route,
+        sections: sections.to_vec(),
+    };
+    if let Ok(payload) = serde_json::to_string(&entry) {
         let _ = std::fs::write(full_text_success_cache_path(key), payload);
     }
 }
@@ -1689,19 +2472,26 @@ fn save_source_cache(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn search_source_with_cache(
     source: IngestionSourceSpec,
     source_query: &str,
     max_results: usize,
+    since: Option<chrono::NaiveDate>,
     pubmed_api_key: Option<String>,
     semantic_scholar_api_key: Option<String>,
     source_cache_enabled: bool,
     source_cache_ttl_secs: Option<u64>,
 ) -> anyhow::Result<Vec<crate::models::PaperMetadata>> {
+    // A date-bounded search answers a different question than an open one.
+    let cache_query = match since {
+        Some(since) => format!("{source_query} since:{since}"),
+        None => source_query.to_string(),
+    };
     if source_cache_enabled {
         if let Some(cached) = load_source_cache(
             &source,
-            source_query,
+            &cache_query,
             max_results,
             source_cache_ttl(source_cache_ttl_secs),
         ) {
@@ -1725,6 +2515,7 @@ async fn search_source_with_cache(
             &source,
             source_query,
             max_results,
+            since,
             pubmed_api_key.clone(),
             semantic_scholar_api_key.clone(),
         )
@@ -1753,7 +2544,7 @@ async fn search_source_with_cache(
     papers.retain(|paper| seen.insert(canonical_paper_identity_key(paper)));
 
     if source_cache_enabled && !papers.is_empty() {
-        save_source_cache(&source, source_query, max_results, &papers);
+        save_source_cache(&source, &cache_query, max_results, &papers);
     }
     Ok(papers)
 }
@@ -1790,6 +2581,9 @@ fn snapshot_from_result(result: &IngestionResult) -> IngestionPerfSnapshot {
         predicate_generic_share: result.perf_telemetry.predicate_generic_share,
         predicate_coverage_flagged: result.perf_telemetry.predicate_coverage_flagged,
         predicate_histogram: result.perf_telemetry.predicate_histogram.clone(),
+        peak_inflight_papers: result.perf_telemetry.peak_inflight_papers,
+        peak_chunk_buffer_bytes: result.perf_telemetry.peak_chunk_buffer_bytes,
+        spilled_papers: result.perf_telemetry.spilled_papers,
     }
 }
 
@@ -1841,39 +2635,82 @@ pub fn load_recent_perf_snapshots(limit: usize) -> Vec<IngestionPerfSnapshot> {
     snapshots.into_iter().rev().take(take).collect()
 }
 
+/// Fetch, link and store the job's clinical trials in the background.
+///
+/// Trials go to the `trials` table with their KG facts rather than
+/// through paper processing. The search is the gene and its known
+/// inhibitors in the job's condition; `since` does not apply.
+fn spawn_trial_ingestion(
+    job: &IngestionJob,
+    db: Arc<ferrumyx_db::Database>,
+    max_results: usize,
+    source_timeout: std::time::Duration,
+) -> tokio::task::JoinHandle<anyhow::Result<crate::trials::TrialIngestReport>> {
+    let term = target_search_term(job.gene.trim());
+    let condition = Some(condition_for_cancer_code(&job.cancer_type)).filter(|c| !c.is_empty());
+    tokio::spawn(async move {
+        let client = ClinicalTrialsClient::new();
+        timeout(
+            source_timeout,
+            ingest_trials(db, &client, &term, condition.as_deref(), max_results),
+        )
+        .await
+        .unwrap_or_else(|_| {
+            Err(anyhow::anyhow!(
+                "source request exceeded timeout ({}s)",
+                source_timeout.as_secs()
+            ))
+        })
+    })
+}
+
+/// `since` bounds PubMed, Europe PMC and the preprint servers' harvest
+/// window; the other sources have no date filter and return their usual
+/// matches.
+///
+/// The PubMed, Europe PMC, Semantic Scholar and ClinicalTrials.gov clients
+/// take a token from their source's shared bucket for every request; the
+/// other sources take one per search.
 async fn search_source_once(
     source: &IngestionSourceSpec,
     source_query: &str,
     max_results: usize,
+    since: Option<chrono::NaiveDate>,
     pubmed_api_key: Option<String>,
     semantic_scholar_api_key: Option<String>,
 ) -> anyhow::Result<Vec<crate::models::PaperMetadata>> {
     match source {
         IngestionSourceSpec::PubMed => {
-            let client = PubMedClient::new(pubmed_api_key);
+            let client = PubMedClient::new(pubmed_api_key).with_since(since);
             client.search(source_query, max_results).await
         }
         IngestionSourceSpec::EuropePmc => {
-            let client = EuropePmcClient::new();
+            let client = EuropePmcClient::new().with_since(since);
             client.search(source_query, max_results).await
         }
         IngestionSourceSpec::BioRxiv => {
-            let client = BioRxivClient::new_biorxiv();
+            source_bucket("biorxiv").acquire().await;
+            let client = BioRxivClient::new_biorxiv()
+                .with_window_days(resolve_preprint_window_days())
+                .with_since(since);
             client.search(source_query, max_results).await
         }
         IngestionSourceSpec::MedRxiv => {
-            let client = BioRxivClient::new_medrxiv();
+            source_bucket("medrxiv").acquire().await;
+            let client = BioRxivClient::new_medrxiv()
+                .with_window_days(resolve_preprint_window_days())
+                .with_since(since);
             client.search(source_query, max_results).await
         }
         IngestionSourceSpec::Arxiv => {
+            source_bucket("arxiv").acquire().await;
             let client = ArxivClient::new();
             client.search(source_query, max_results).await
         }
-        IngestionSourceSpec::ClinicalTrials => {
-            let client = ClinicalTrialsClient::new();
-            client.search(source_query, max_results).await
-        }
+        // Stored as trial records by `spawn_trial_ingestion` instead.
+        IngestionSourceSpec::ClinicalTrials => Ok(Vec::new()),
         IngestionSourceSpec::CrossRef => {
+            source_bucket("crossref").acquire().await;
             let client = CrossRefClient::new();
             client.search(source_query, max_results).await
         }
@@ -1914,7 +2751,7 @@ fn build_sections_from_abstract(paper: &crate::models::PaperMetadata) -> Vec<Doc
     sections
 }
 
-fn map_ner_type(label: NerEntityType) -> DbEntityType {
+pub(crate) fn map_ner_type(label: NerEntityType) -> DbEntityType {
     match label {
         NerEntityType::Gene => DbEntityType::Gene,
         NerEntityType::Disease => DbEntityType::Disease,
@@ -1978,7 +2815,38 @@ fn infer_object_type(predicate: &str, object: &str) -> DbEntityType {
     DbEntityType::Disease
 }
 
-fn canonical_key(entity_type: DbEntityType, name: &str) -> String {
+/// Entity type, display name and dictionary id (HGNC id, OncoTree code)
+/// of an entity row to resolve or create.
+type EntityCandidate = (DbEntityType, String, Option<String>);
+
+/// Name a NER hit is recorded under: the approved symbol for genes, the
+/// OncoTree code for cancer types (matching relation objects), otherwise
+/// the matched text.
+fn mention_subject(entity: &ExtractedEntity) -> String {
+    let canonical = match entity.label {
+        NerEntityType::CancerType => entity.canonical_id.as_ref(),
+        _ => entity.canonical_name.as_ref(),
+    };
+    canonical.unwrap_or(&entity.text).clone()
+}
+
+/// Queue `name` for entity resolution. A later candidate with the same key
+/// only contributes a dictionary id the first one lacked.
+fn add_entity_candidate(
+    candidates: &mut BTreeMap<String, EntityCandidate>,
+    entity_type: DbEntityType,
+    name: &str,
+    canonical_id: Option<&str>,
+) {
+    let candidate = candidates
+        .entry(canonical_key(entity_type, name))
+        .or_insert_with(|| (entity_type, name.to_string(), None));
+    if candidate.2.is_none() {
+        candidate.2 = canonical_id.map(str::to_string);
+    }
+}
+
+pub(crate) fn canonical_key(entity_type: DbEntityType, name: &str) -> String {
     let mut normalized = name.trim().to_uppercase();
     normalized = normalized
         .chars()
@@ -2033,6 +2901,8 @@ fn merge_paper_processing_outcome(
         unique_predicates,
         mut errors,
         heavy_task,
+        spilled: _,
+        failure: _,
     } = outcome;
 
     result.chunks_inserted += chunks_inserted;
@@ -2051,30 +2921,238 @@ fn merge_paper_processing_outcome(
     heavy_task
 }
 
+/// A fetched paper on its way to processing, holding its window slot.
+type PrefetchedPaper = (
+    crate::models::PaperMetadata,
+    Uuid,
+    FetchedFullText,
+    PaperWindowPermit,
+);
+
+/// Run `fetch` for up to `workers` papers at once and send each result to
+/// `tx` as it lands, so processing starts on the first paper while the rest
+/// are still downloading. Each paper takes a `window` slot before its fetch
+/// starts; the slot frees only once that paper is fully persisted, so
+/// fetching never runs further ahead of processing than the window allows.
+/// Per-source request rates are enforced inside `fetch`, by the source
+/// clients' shared buckets.
+async fn prefetch_full_texts<F, Fut>(
+    papers: Vec<(crate::models::PaperMetadata, Uuid)>,
+    workers: usize,
+    window: &PaperWindow,
+    cancel: &CancellationToken,
+    tx: &mpsc::Sender<PrefetchedPaper>,
+    fetch: F,
+) where
+    F: Fn(&crate::models::PaperMetadata, Uuid) -> Fut,
+    Fut: std::future::Future<Output = FetchedFullText> + Send + 'static,
+{
+    let mut set = tokio::task::JoinSet::new();
+    let mut remaining = papers.into_iter();
+    let mut input_exhausted = false;
+    while !input_exhausted || !set.is_empty() {
+        while set.len() < workers.max(1) {
+            let Some((paper, paper_id)) = remaining.next() else {
+                input_exhausted = true;
+                break;
+            };
+            let permit = window.acquire().await;
+            if cancel.is_cancelled() {
+                input_exhausted = true;
+                break;
+            }
+            let full_text = fetch(&paper, paper_id);
+            set.spawn(async move { (paper, paper_id, full_text.await, permit) });
+        }
+        if cancel.is_cancelled() {
+            set.abort_all();
+        }
+
+        if let Some(joined) = set.join_next().await {
+            if let Ok(payload) = joined {
+                let _ = tx.send(payload).await;
+            }
+        }
+    }
+}
+
+/// Fetch and parse a paper's full text, falling back to the abstract when
+/// no route finds it or the fetch panics.
+async fn fetch_full_text_isolated(
+    paper: &crate::models::PaperMetadata,
+    paper_id: Uuid,
+    resolver: &FullTextResolver,
+) -> FetchedFullText {
+    let fetched = AssertUnwindSafe(fetch_full_text_sections_for_paper(paper, resolver))
+        .catch_unwind()
+        .await;
+    match fetched {
+        Ok(full_text) => full_text,
+        Err(panic) => {
+            warn!(
+                paper_id = %paper_id,
+                "Full-text fetch panicked ({}); using abstract only",
+                panic_message(panic.as_ref())
+            );
+            FetchedFullText::default()
+        }
+    }
+}
+
+/// [`process_single_paper`] with panics caught. A paper that panics comes
+/// back with a [`PaperFailure`] naming the stage it reached and is marked
+/// "failed", so the rest of its batch carries on.
+#[allow(clippy::too_many_arguments)]
+async fn process_paper_isolated(
+    paper: crate::models::PaperMetadata,
+    paper_id: Uuid,
+    full_text: FetchedFullText,
+    query_gene_hint: Option<String>,
+    repo: Arc<IngestionRepository>,
+    ner: Arc<TrieNer>,
+    chunker_cfg: ChunkerConfig,
+    embed_client: Option<Arc<EmbeddingClient>>,
+    defer_embedding_to_global_batch: bool,
+    chunk_budget: ChunkBufferBudget,
+) -> PaperProcessingResult {
+    let stage = PaperStage::new();
+    let processed = AssertUnwindSafe(process_single_paper(
+        paper,
+        paper_id,
+        full_text,
+        query_gene_hint,
+        repo.clone(),
+        ner,
+        chunker_cfg,
+        embed_client,
+        defer_embedding_to_global_batch,
+        chunk_budget,
+        &stage,
+    ))
+    .catch_unwind()
+    .await;
+    match processed {
+        Ok(out) => out,
+        Err(panic) => {
+            let error = format!("panicked: {}", panic_message(panic.as_ref()));
+            warn!(paper_id = %paper_id, stage = stage.current(), "Paper processing {error}");
+            let _ = repo.set_parse_status(paper_id, "failed").await;
+            PaperProcessingResult {
+                failure: Some(PaperFailure {
+                    stage: stage.current(),
+                    error,
+                }),
+                ..Default::default()
+            }
+        }
+    }
+}
+
+fn panic_message(panic: &(dyn std::any::Any + Send)) -> String {
+    panic
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// Count a processed paper as succeeded or failed. Failures are recorded to
+/// `failed_items` with the paper's metadata, so they can be retried without
+/// searching again.
+async fn note_paper_outcome(
+    failed_items: &FailedItemRepository,
+    job_id: Option<Uuid>,
+    result: &mut IngestionResult,
+    paper: &crate::models::PaperMetadata,
+    paper_id: Uuid,
+    failure: Option<&PaperFailure>,
+) {
+    let Some(failure) = failure else {
+        result.papers_succeeded += 1;
+        return;
+    };
+    result.papers_failed += 1;
+    let label = paper
+        .pmid
+        .as_deref()
+        .or(paper.doi.as_deref())
+        .unwrap_or(paper.title.as_str());
+    if result.failure_samples.len() < FAILURE_SAMPLE_LIMIT {
+        result
+            .failure_samples
+            .push(format!("{label}: {}: {}", failure.stage, failure.error));
+    }
+    let now = Utc::now();
+    let item = FailedItem {
+        paper_id,
+        job_id,
+        title: paper.title.clone(),
+        doi: paper.doi.clone(),
+        pmid: paper.pmid.clone(),
+        source: paper.source.as_str().to_string(),
+        metadata: serde_json::to_string(paper).unwrap_or_default(),
+        stage: failure.stage.to_string(),
+        error: failure.error.clone(),
+        retry_count: 0,
+        status: "failed".to_string(),
+        first_failed_at: now,
+        last_failed_at: now,
+    };
+    if let Err(e) = failed_items.record(&item).await {
+        warn!(paper_id = %paper_id, "Failed to record failed ingestion item: {e}");
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn process_single_paper(
     paper: crate::models::PaperMetadata,
     paper_id: Uuid,
-    full_text_sections: Vec<DocumentSection>,
+    full_text: FetchedFullText,
     query_gene_hint: Option<String>,
     repo: Arc<IngestionRepository>,
     ner: Arc<TrieNer>,
     chunker_cfg: ChunkerConfig,
     embed_client: Option<Arc<EmbeddingClient>>,
     defer_embedding_to_global_batch: bool,
+    chunk_budget: ChunkBufferBudget,
+    stage: &PaperStage,
 ) -> PaperProcessingResult {
     let mut out = PaperProcessingResult::default();
     info!(paper_id = %paper_id, title = %paper.title, "Processing new paper");
     let _ = repo.set_parse_status(paper_id, "processing").await;
 
+    if let Some(score) = full_text.low_quality {
+        let _ = repo.set_text_quality(paper_id, "low_quality", score).await;
+        if !resolve_pdf_low_quality_include() {
+            info!(
+                paper_id = %paper_id,
+                text_quality = score,
+                "Full text failed the text-quality gate; excluding paper from embedding and NER"
+            );
+            out.quality_gate_skipped = true;
+            return out;
+        }
+    }
+
+    let full_text_sections = full_text.sections;
     let mut sections = build_sections_from_abstract(&paper);
     if !full_text_sections.is_empty() {
         info!(
             paper_id = %paper_id,
             n_sections = full_text_sections.len(),
+            route = ?full_text.route,
             "Full-text parsed successfully"
         );
         sections.extend(full_text_sections);
         let _ = repo.set_full_text_status(paper_id, true).await;
+        if let Some(route) = full_text.route {
+            if let Err(e) = repo
+                .set_paper_metadata(paper_id, "full_text_source", serde_json::json!(route))
+                .await
+            {
+                warn!(paper_id = %paper_id, "Failed to record full-text source: {e}");
+            }
+        }
     } else {
         debug!(
             paper_id = %paper_id,
@@ -2087,8 +3165,10 @@ async fn process_single_paper(
         return out;
     }
 
+    stage.enter("chunking");
     let chunks = chunk_document(paper_id, sections, &chunker_cfg);
     let n_chunks = chunks.len();
+    stage.enter("chunk_insert");
     match repo.bulk_insert_chunks(&chunks).await {
         Ok(inserted) => {
             out.chunks_inserted += inserted;
@@ -2106,14 +3186,17 @@ async fn process_single_paper(
                 .or(paper.doi.as_ref())
                 .map(|s| s.as_str())
                 .unwrap_or("unknown");
-            let msg = format!("chunk insert failed for {}: {e}", id);
-            warn!("{}", &msg);
-            out.errors.push(msg);
+            warn!("chunk insert failed for {}: {e}", id);
+            out.failure = Some(PaperFailure {
+                stage: "chunk_insert",
+                error: format!("chunk insert failed: {e}"),
+            });
             let _ = repo.set_parse_status(paper_id, "failed").await;
             return out;
         }
     }
 
+    stage.enter("enrichment");
     let min_ner_chars = std::env::var("FERRUMYX_INGESTION_MIN_NER_CHARS")
         .ok()
         .and_then(|v| v.trim().parse::<usize>().ok())
@@ -2137,7 +3220,29 @@ async fn process_single_paper(
         return out;
     }
 
-    if resolve_heavy_lane_async_enabled() {
+    // Background enrichment holds a copy of the chunks in memory until it
+    // completes. When the buffer budget is exhausted, enrich inline instead and
+    // leave embedding to the DB-backed pass (chunks are already persisted).
+    let chunk_reservation = if resolve_heavy_lane_async_enabled() {
+        let reservation = chunk_budget.try_reserve(estimate_chunk_bytes(
+            chunks.iter().map(|c| c.content.as_str()),
+        ));
+        if reservation.is_none() {
+            out.spilled = true;
+            debug!(
+                paper_id = %paper_id,
+                buffered_bytes = chunk_budget.used(),
+                budget_bytes = chunk_budget.limit(),
+                "Chunk buffer budget exhausted; spilling paper to DB-backed embedding"
+            );
+        }
+        reservation
+    } else {
+        None
+    };
+    let defer_embedding_to_global_batch = defer_embedding_to_global_batch || out.spilled;
+
+    if let Some(chunk_reservation) = chunk_reservation {
         let repo_bg = repo.clone();
         let ner_bg = ner.clone();
         let paper_bg = paper.clone();
@@ -2149,6 +3254,7 @@ async fn process_single_paper(
             .clone();
         out.heavy_task = Some(tokio::spawn(async move {
             let _permit = limiter.acquire_owned().await.ok();
+            let _chunk_reservation = chunk_reservation;
             run_heavy_enrichment_for_chunks(
                 paper_bg,
                 paper_id,
@@ -2199,6 +3305,29 @@ struct RelationFactSeed {
     predicate: String,
     object_name: String,
     confidence: f32,
+    evidence: Option<FactEvidence>,
+}
+
+/// Sentence a relation was read from, as a byte range of its chunk.
+#[derive(Debug, Clone)]
+struct FactEvidence {
+    chunk_id: Uuid,
+    start: usize,
+    end: usize,
+    sentence: String,
+}
+
+impl FactEvidence {
+    fn in_chunk(chunk: &crate::models::DocumentChunk, span: (usize, usize)) -> Option<Self> {
+        let (start, end) = span;
+        let sentence = chunk.content.get(start..end)?;
+        Some(Self {
+            chunk_id: chunk.chunk_id,
+            start,
+            end,
+            sentence: sentence.to_string(),
+        })
+    }
 }
 
 fn safe_prefix(text: &str, max_bytes: usize) -> &str {
@@ -2242,8 +3371,10 @@ async fn run_heavy_enrichment_for_chunks(
 
     let mut mention_seeds: Vec<MentionFactSeed> = Vec::new();
     let mut relation_seeds: Vec<RelationFactSeed> = Vec::new();
-    let mut unique_candidates: HashMap<String, (DbEntityType, String)> = HashMap::new();
+    // Ordered so entity creation does not depend on hash iteration order.
+    let mut unique_candidates: BTreeMap<String, EntityCandidate> = BTreeMap::new();
 
+    let mut kept_chunks = Vec::with_capacity(chunks.len());
     for chunk in &chunks {
         let fp_input = safe_prefix(&chunk.content, 512);
         if resolve_ingestion_validation_mode() != IngestionValidationMode::Strict {
@@ -2261,8 +3392,41 @@ async fn run_heavy_enrichment_for_chunks(
                 }
             }
         }
+        kept_chunks.push(chunk);
+    }
+    let texts: Vec<&str> = kept_chunks.iter().map(|c| c.content.as_str()).collect();
+    let ner_filter = NerFilter::from_env();
+    let mut extracted = extract_chunk_entities(
+        &ner,
+        NerMode::from_env(),
+        NER_TAGGER.get().map(|t| t.as_ref()),
+        &texts,
+    );
+    // A short form defined in one chunk applies to the whole paper.
+    let mut abbreviations = AbbreviationMap::default();
+    for (text, entities) in texts.iter().zip(&extracted) {
+        abbreviations.learn(text, entities);
+    }
+    for (text, entities) in texts.iter().zip(&mut extracted) {
+        abbreviations.apply(text, entities);
+        entities.retain(|e| ner_filter.allows(e));
+    }
+    if !abbreviations.is_empty() {
+        let value = serde_json::json!(abbreviations);
+        if let Err(err) = repo
+            .set_paper_metadata(paper_id, "abbreviations", value)
+            .await
+        {
+            let msg = format!(
+                "abbreviation metadata update failed for {:?}: {err}",
+                paper_id
+            );
+            warn!("{msg}");
+            out.errors.push(msg);
+        }
+    }
 
-        let entities = ner.extract(&chunk.content);
+    for (chunk, entities) in kept_chunks.into_iter().zip(extracted) {
         if !entities.is_empty() {
             info!(paper_id = %paper_id, count = entities.len(), "Entities extracted from chunk");
         }
@@ -2271,17 +3435,7 @@ async fn run_heavy_enrichment_for_chunks(
         let mut genes_for_relations: HashMap<String, f32> = HashMap::new();
 
         for entity in entities {
-            let mut canon_subject = entity.text.clone();
-            if entity.label == NerEntityType::Gene {
-                if let Some(sym) = ner.hgnc().normalise_symbol(&entity.text) {
-                    canon_subject = sym;
-                }
-            } else if entity.label == NerEntityType::CancerType {
-                if let Some(code) = cancer_normaliser.normalise(&entity.text) {
-                    canon_subject = code;
-                }
-            }
-
+            let canon_subject = mention_subject(&entity);
             if canon_subject.trim().is_empty() {
                 continue;
             }
@@ -2293,9 +3447,12 @@ async fn run_heavy_enrichment_for_chunks(
                     object_name: canon_subject.clone(),
                     confidence: entity.confidence,
                 });
-                unique_candidates
-                    .entry(canonical_key(entity_db_type, &canon_subject))
-                    .or_insert((entity_db_type, canon_subject.clone()));
+                add_entity_candidate(
+                    &mut unique_candidates,
+                    entity_db_type,
+                    &canon_subject,
+                    entity.canonical_id.as_deref(),
+                );
             }
 
             if entity.label == NerEntityType::Gene {
@@ -2336,7 +3493,7 @@ async fn run_heavy_enrichment_for_chunks(
             }
         }
         let mut genes_for_relations: Vec<(String, f32)> = genes_for_relations.into_iter().collect();
-        genes_for_relations.sort_by(|a, b| b.1.total_cmp(&a.1));
+        genes_for_relations.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
         genes_for_relations.truncate(max_relation_genes);
         let gene_confidence_map: HashMap<String, f32> = genes_for_relations
             .iter()
@@ -2355,14 +3512,15 @@ async fn run_heavy_enrichment_for_chunks(
                 }
             }
             let object_type = infer_object_type(&fact.fact_type, &fact.object);
-            unique_candidates
-                .entry(canonical_key(object_type, &fact.object))
-                .or_insert((object_type, fact.object.clone()));
+            add_entity_candidate(&mut unique_candidates, object_type, &fact.object, None);
             relation_seeds.push(RelationFactSeed {
                 gene_symbol: gene_symbol.clone(),
                 predicate: fact.fact_type.clone(),
                 object_name: fact.object.clone(),
                 confidence: gene_confidence,
+                evidence: fact
+                    .evidence_span
+                    .and_then(|span| FactEvidence::in_chunk(chunk, span)),
             });
         }
     }
@@ -2375,6 +3533,7 @@ async fn run_heavy_enrichment_for_chunks(
                 .unwrap_or_else(|| hinted.trim().to_uppercase());
             if !canonical_hint.trim().is_empty() {
                 let mut stitched = String::new();
+                let mut chunk_offsets = Vec::new();
                 for chunk in chunks.iter().take(6) {
                     if stitched.len() > 24_000 {
                         break;
@@ -2382,23 +3541,31 @@ async fn run_heavy_enrichment_for_chunks(
                     if !stitched.is_empty() {
                         stitched.push('\n');
                     }
+                    chunk_offsets.push((stitched.len(), chunk));
                     stitched.push_str(&chunk.content);
                 }
                 for mut fact in build_facts_batch(&[canonical_hint.clone()], &stitched) {
                     let object_type = infer_object_type(&fact.fact_type, &fact.object);
-                    unique_candidates
-                        .entry(canonical_key(object_type, &fact.object))
-                        .or_insert((object_type, fact.object.clone()));
+                    add_entity_candidate(&mut unique_candidates, object_type, &fact.object, None);
                     if fact.fact_type != "has_mutation" {
                         if let Some(code) = cancer_normaliser.normalise(&fact.object) {
                             fact.object = code;
                         }
                     }
+                    // Sentences never span the newline between two chunks.
+                    let evidence = fact.evidence_span.and_then(|(start, end)| {
+                        let (offset, chunk) = chunk_offsets
+                            .iter()
+                            .rev()
+                            .find(|(offset, _)| *offset <= start)?;
+                        FactEvidence::in_chunk(chunk, (start - offset, end - offset))
+                    });
                     relation_seeds.push(RelationFactSeed {
                         gene_symbol: canonical_hint.clone(),
                         predicate: fact.fact_type.clone(),
                         object_name: fact.object,
                         confidence: 0.62,
+                        evidence,
                     });
                 }
             }
@@ -2411,7 +3578,7 @@ async fn run_heavy_enrichment_for_chunks(
     );
 
     if !unique_candidates.is_empty() {
-        let candidates: Vec<(DbEntityType, String)> = unique_candidates.into_values().collect();
+        let candidates: Vec<EntityCandidate> = unique_candidates.into_values().collect();
         if let Err(err) = resolve_or_create_entities_bulk(
             &entity_repo,
             &mut entity_id_cache,
@@ -2487,9 +3654,30 @@ async fn run_heavy_enrichment_for_chunks(
         } else {
             "typed_relation".to_string()
         };
+        if let Some(evidence) = relation.evidence {
+            db_fact.chunk_id = Some(evidence.chunk_id);
+            db_fact.evidence_start = Some(evidence.start as i64);
+            db_fact.evidence_end = Some(evidence.end as i64);
+            db_fact.evidence = Some(evidence.sentence);
+        }
         paper_facts.push(db_fact);
     }
 
+    if paper.is_review {
+        // Reviews restate primary findings: keep the facts, flag their provenance.
+        for fact in &mut paper_facts {
+            fact.study_type = Some(REVIEW_STUDY_TYPE.to_string());
+        }
+    }
+
+    // Persist in content order so row order is independent of seed order.
+    paper_facts.sort_by(|a, b| {
+        a.predicate
+            .cmp(&b.predicate)
+            .then_with(|| a.subject_name.cmp(&b.subject_name))
+            .then_with(|| a.object_name.cmp(&b.object_name))
+    });
+
     if !paper_facts.is_empty() {
         let fact_batch_size = resolve_fact_insert_batch_size();
         for batch in paper_facts.chunks(fact_batch_size) {
@@ -2512,6 +3700,25 @@ async fn run_heavy_enrichment_for_chunks(
                 }
             }
         }
+
+        // New relations may contradict facts from earlier papers.
+        let subject_ids: Vec<Uuid> = paper_facts
+            .iter()
+            .filter(|f| f.evidence_type != "mention")
+            .map(|f| f.subject_id)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        if !subject_ids.is_empty() {
+            if let Err(e) = KgRepository::new(repo.db())
+                .refresh_conflicts(&subject_ids)
+                .await
+            {
+                let msg = format!("conflict detection failed for {:?}: {e}", paper_id);
+                warn!("{}", msg);
+                out.errors.push(msg);
+            }
+        }
     }
 
     let _ = repo.set_parse_status(paper_id, "parsed").await;
@@ -2539,7 +3746,7 @@ async fn run_heavy_enrichment_for_chunks(
 async fn resolve_or_create_entities_bulk(
     repo: &EntityRepository,
     cache: &mut HashMap<String, Uuid>,
-    candidates: &[(DbEntityType, String)],
+    candidates: &[EntityCandidate],
     insert_batch_size: usize,
 ) -> anyhow::Result<()> {
     if candidates.is_empty() {
@@ -2547,19 +3754,30 @@ async fn resolve_or_create_entities_bulk(
     }
 
     let mut missing: Vec<(String, DbEntity)> = Vec::new();
-    for (entity_type, display_name) in candidates {
+    for (entity_type, display_name, canonical_id) in candidates {
         let key = canonical_key(*entity_type, display_name);
         if cache.contains_key(&key) {
             continue;
         }
 
-        let external_id = format!("FERRUMYX:{}", key);
-        if let Some(existing) = repo
+        let legacy_external_id = format!("FERRUMYX:{}", key);
+        let external_id = canonical_id
+            .clone()
+            .unwrap_or_else(|| legacy_external_id.clone());
+        let mut existing = repo
             .find_by_external_id(&external_id)
             .await?
             .into_iter()
-            .next()
-        {
+            .next();
+        if existing.is_none() && external_id != legacy_external_id {
+            // Rows written before dictionary ids were used are keyed by name.
+            existing = repo
+                .find_by_external_id(&legacy_external_id)
+                .await?
+                .into_iter()
+                .next();
+        }
+        if let Some(existing) = existing {
             cache.insert(key, existing.id);
             continue;
         }
@@ -2607,330 +3825,97 @@ async fn resolve_or_create_entities_bulk(
     Ok(())
 }
 
-fn scihub_adaptive_should_attempt() -> bool {
-    if !resolve_scihub_adaptive_enabled() {
-        return true;
-    }
-    let now = StdInstant::now();
-    let state = SCIHUB_ADAPTIVE_STATE.get_or_init(|| Mutex::new(ScihubAdaptiveState::default()));
-    let Ok(mut guard) = state.lock() else {
-        return true;
-    };
-    if let Some(until) = guard.cooldown_until {
-        if now < until {
-            let probe_every = resolve_scihub_adaptive_probe_every();
-            guard.cooldown_skips = guard.cooldown_skips.saturating_add(1);
-            return guard.cooldown_skips % probe_every == 0;
-        }
-        guard.cooldown_until = None;
-        guard.cooldown_skips = 0;
-    }
-    true
-}
-
-fn scihub_adaptive_step_timeout(base: std::time::Duration) -> std::time::Duration {
-    if !resolve_scihub_adaptive_enabled() {
-        return base;
-    }
-    let min_secs = resolve_scihub_adaptive_min_step_timeout_secs();
-    let state = SCIHUB_ADAPTIVE_STATE.get_or_init(|| Mutex::new(ScihubAdaptiveState::default()));
-    let Ok(guard) = state.lock() else {
-        return base;
-    };
-    if guard.attempts < 3 {
-        return base;
-    }
-    let mut secs = base.as_secs().max(1);
-    let success_rate = if guard.attempts == 0 {
-        1.0
-    } else {
-        guard.successes as f64 / guard.attempts as f64
-    };
-    let fail_streak = resolve_scihub_adaptive_fail_streak();
-    if guard.consecutive_failures >= 3 {
-        secs = secs.min(min_secs.saturating_add(1));
-    }
-    if guard.consecutive_failures >= fail_streak / 2 {
-        secs = secs.min(min_secs.saturating_add(2));
-    }
-    if guard.attempts >= 20 {
-        if success_rate < 0.05 {
-            secs = secs.min(min_secs);
-        } else if success_rate < 0.15 {
-            secs = secs.min(min_secs.saturating_add(1));
-        }
-    }
-    std::time::Duration::from_secs(secs.max(1))
-}
-
-fn scihub_adaptive_record_attempt(success: bool) {
-    if !resolve_scihub_adaptive_enabled() {
-        return;
-    }
-    let state = SCIHUB_ADAPTIVE_STATE.get_or_init(|| Mutex::new(ScihubAdaptiveState::default()));
-    let Ok(mut guard) = state.lock() else {
-        return;
-    };
-    guard.attempts = guard.attempts.saturating_add(1);
-    if success {
-        guard.successes = guard.successes.saturating_add(1);
-        guard.consecutive_failures = 0;
-        guard.cooldown_until = None;
-        guard.cooldown_skips = 0;
-        return;
+/// Full-text routes for `job`'s papers. Sci-Hub is only among them when
+/// the job turns on `enable_scihub_fallback`.
+fn full_text_resolver(job: &IngestionJob) -> FullTextResolver {
+    let step_timeout =
+        std::time::Duration::from_secs(job.full_text_step_timeout_secs.unwrap_or(15).clamp(5, 120));
+    let total_timeout = std::time::Duration::from_secs(resolve_full_text_total_timeout_secs());
+    let mailto = resolve_crossref_mailto().or_else(|| job.unpaywall_email.clone());
+    let mut resolver = FullTextResolver::new(step_timeout, total_timeout).with_crossref(
+        enrichment_client(mailto, resolve_crossref_requests_per_second()),
+    );
+    if let Some(email) = job
+        .unpaywall_email
+        .as_deref()
+        .filter(|e| !e.trim().is_empty())
+    {
+        resolver = resolver.with_unpaywall(UnpaywallClient::new(email));
     }
-
-    guard.consecutive_failures = guard.consecutive_failures.saturating_add(1);
-    if guard.consecutive_failures >= resolve_scihub_adaptive_fail_streak() {
-        guard.cooldown_until = Some(
-            StdInstant::now()
-                + std::time::Duration::from_secs(resolve_scihub_adaptive_backoff_secs()),
-        );
-        guard.cooldown_skips = 0;
+    if job.enable_scihub_fallback {
+        resolver = resolver.with_scihub(SciHubClient::new());
     }
+    resolver
 }
 
+/// Full text from the first route whose document parses, cached per paper.
+/// A PDF below the text-quality gate does not end the search; the best one
+/// is kept and returned when no route does better.
 async fn fetch_full_text_sections_for_paper(
     paper: &crate::models::PaperMetadata,
-    unpaywall_email: Option<&str>,
-    enable_scihub_fallback: bool,
-    step_timeout: std::time::Duration,
-) -> anyhow::Result<Vec<DocumentSection>> {
-    let total_timeout = std::time::Duration::from_secs(
-        resolve_full_text_total_timeout_secs().max(step_timeout.as_secs()),
+    resolver: &FullTextResolver,
+) -> FetchedFullText {
+    let cache_key = format!(
+        "paper:{}|scihub={}",
+        canonical_paper_identity_key(paper),
+        resolver.scihub_enabled()
     );
-    let started_at = tokio::time::Instant::now();
-    let deadline = started_at + total_timeout;
-    let scihub_defer_ms = resolve_scihub_defer_ms();
-    let scihub_defer = std::time::Duration::from_millis(scihub_defer_ms);
-    let mut set = tokio::task::JoinSet::new();
-
-    if let Some(pdf_url) = paper.full_text_url.clone() {
-        set.spawn(async move { try_open_pdf_url(&pdf_url, step_timeout).await });
-    }
-    if let Some(pmcid) = paper.pmcid.clone() {
-        set.spawn(async move { try_pmc_paths(&pmcid, step_timeout).await });
-    }
-    if let (Some(doi), Some(email)) = (paper.doi.clone(), unpaywall_email.map(str::to_string)) {
-        if !email.trim().is_empty() {
-            set.spawn(async move { try_unpaywall_path(&doi, &email, step_timeout).await });
-        }
-    }
-    let mut scihub_identifier = if enable_scihub_fallback {
-        paper.doi.clone().or(paper.pmid.clone())
-    } else {
-        None
-    };
-    let mut scihub_spawned = false;
-
-    loop {
-        let now = tokio::time::Instant::now();
-        if now >= deadline {
-            set.abort_all();
-            return Ok(Vec::new());
-        }
-
-        // Prefer OA routes first, then launch Sci-Hub fallback after a short defer.
-        if !scihub_spawned {
-            let should_spawn_scihub = if set.is_empty() {
-                true
-            } else if scihub_defer_ms == 0 {
-                true
-            } else {
-                now.duration_since(started_at) >= scihub_defer
-            };
-            if should_spawn_scihub {
-                if let Some(id) = scihub_identifier.take() {
-                    scihub_spawned = true;
-                    set.spawn(async move { try_scihub_path(&id, step_timeout).await });
-                }
-            }
-        }
-
-        if set.is_empty() {
-            break;
-        }
-
-        let remaining = deadline.saturating_duration_since(now);
-        let mut wait_for = remaining;
-        if !scihub_spawned && !set.is_empty() && scihub_defer_ms > 0 {
-            let scihub_ready_at = started_at + scihub_defer;
-            if scihub_ready_at > now {
-                wait_for = wait_for.min(scihub_ready_at.saturating_duration_since(now));
-            }
-        }
-        if wait_for.is_zero() {
-            continue;
-        }
-
-        match timeout(wait_for, set.join_next()).await {
-            Ok(Some(Ok(Ok(sections)))) => {
-                if !sections.is_empty() {
-                    set.abort_all();
-                    return Ok(sections);
-                }
-            }
-            Ok(Some(Ok(Err(_)))) => {}
-            Ok(Some(Err(_))) => {}
-            Ok(None) => break,
-            Err(_) => {
-                // wait_for elapsed (e.g., Sci-Hub defer window reached); continue loop
-                continue;
-            }
-        }
-    }
-    Ok(Vec::new())
-}
-
-async fn try_open_pdf_url(
-    pdf_url: &str,
-    step_timeout: std::time::Duration,
-) -> anyhow::Result<Vec<DocumentSection>> {
-    let cache_key = format!("pdf_url:{}", pdf_url.trim().to_lowercase());
-    if let Some(sections) = load_full_text_success(&cache_key) {
-        return Ok(sections);
+    if let Some((route, sections)) = load_full_text_success(&cache_key) {
+        return FetchedFullText {
+            sections,
+            route: Some(route),
+            low_quality: None,
+        };
     }
     if full_text_negative_cached(&cache_key) {
-        return Ok(Vec::new());
-    }
-    match timeout(step_timeout, fetch_and_parse_pdf(pdf_url)).await {
-        Ok(Ok(sections)) if !sections.is_empty() => {
-            clear_full_text_negative(&cache_key);
-            save_full_text_success(&cache_key, &sections);
-            Ok(sections)
-        }
-        _ => {
-            save_full_text_negative(&cache_key, "pdf_url_fetch_or_parse_failed");
-            Ok(Vec::new())
-        }
+        return FetchedFullText::default();
     }
-}
 
-async fn try_pmc_paths(
-    pmcid_raw: &str,
-    step_timeout: std::time::Duration,
-) -> anyhow::Result<Vec<DocumentSection>> {
-    let cache_key = format!("pmcid:{}", pmcid_raw.trim().to_lowercase());
-    if let Some(sections) = load_full_text_success(&cache_key) {
-        return Ok(sections);
-    }
-    if full_text_negative_cached(&cache_key) {
-        return Ok(Vec::new());
+    let outcome = resolver.resolve(paper, read_full_text_document).await;
+    if let Some(resolved) = outcome.resolved {
+        clear_full_text_negative(&cache_key);
+        save_full_text_success(&cache_key, resolved.route, &resolved.sections);
+        return FetchedFullText {
+            sections: resolved.sections,
+            route: Some(resolved.route),
+            low_quality: None,
+        };
     }
-    let clean_pmcid = if pmcid_raw.starts_with("PMC") {
-        pmcid_raw.to_string()
-    } else {
-        format!("PMC{}", pmcid_raw)
-    };
-
-    let epmc = EuropePmcClient::new();
-    if let Ok(Ok(Some(xml))) = timeout(step_timeout, epmc.fetch_full_text(&clean_pmcid)).await {
-        let sections = parse_pmc_xml_sections(&xml);
-        if !sections.is_empty() {
-            clear_full_text_negative(&cache_key);
-            save_full_text_success(&cache_key, &sections);
-            return Ok(sections);
+    let low_quality = outcome
+        .failures
+        .into_iter()
+        .filter_map(|(route, e)| e.downcast::<LowQualityText>().ok().map(|lq| (route, lq)))
+        .max_by(|(_, a), (_, b)| a.score.total_cmp(&b.score));
+    match low_quality {
+        Some((route, lq)) => {
+            save_full_text_negative(&cache_key, "pdf_low_quality");
+            FetchedFullText {
+                sections: lq.sections,
+                route: Some(route),
+                low_quality: Some(lq.score),
+            }
         }
-    }
-
-    let epmc_url = format!(
-        "https://europepmc.org/backend/ptpmcrender.fcgi?accid={}&blobtype=pdf",
-        clean_pmcid
-    );
-    if let Ok(Ok(sections)) = timeout(step_timeout, fetch_and_parse_pdf(&epmc_url)).await {
-        if !sections.is_empty() {
-            clear_full_text_negative(&cache_key);
-            save_full_text_success(&cache_key, &sections);
-            return Ok(sections);
+        None => {
+            save_full_text_negative(&cache_key, "no_full_text_route");
+            FetchedFullText::default()
         }
     }
-    save_full_text_negative(&cache_key, "pmc_paths_failed");
-    Ok(Vec::new())
 }
 
-async fn try_unpaywall_path(
-    doi: &str,
-    email: &str,
-    step_timeout: std::time::Duration,
+/// Sections of a document a full-text route found.
+async fn read_full_text_document(
+    document: FullTextDocument,
 ) -> anyhow::Result<Vec<DocumentSection>> {
-    let cache_key = format!("doi:{}", doi.trim().to_lowercase());
-    if let Some(sections) = load_full_text_success(&cache_key) {
-        return Ok(sections);
+    match document {
+        FullTextDocument::PdfUrl(url) => fetch_and_parse_pdf(&url).await,
+        FullTextDocument::Pdf(bytes) => parse_pdf_bytes(&bytes).await,
+        FullTextDocument::JatsXml(xml) => Ok(parse_pmc_xml_sections(&xml)),
     }
-    if full_text_negative_cached(&cache_key) {
-        return Ok(Vec::new());
-    }
-    let unpaywall = UnpaywallClient::new(email);
-    if let Ok(Ok(Some(pdf_url))) = timeout(step_timeout, unpaywall.resolve_pdf_url(doi)).await {
-        if let Ok(Ok(sections)) = timeout(step_timeout, fetch_and_parse_pdf(&pdf_url)).await {
-            if !sections.is_empty() {
-                clear_full_text_negative(&cache_key);
-                save_full_text_success(&cache_key, &sections);
-                return Ok(sections);
-            }
-        }
-    }
-    save_full_text_negative(&cache_key, "unpaywall_path_failed");
-    Ok(Vec::new())
-}
-
-async fn try_scihub_path(
-    identifier: &str,
-    step_timeout: std::time::Duration,
-) -> anyhow::Result<Vec<DocumentSection>> {
-    if !scihub_adaptive_should_attempt() {
-        debug!(
-            identifier = identifier,
-            "Skipping Sci-Hub attempt due to adaptive global backoff"
-        );
-        return Ok(Vec::new());
-    }
-
-    let cache_key = format!("scihub:{}", identifier.trim().to_lowercase());
-    if let Some(sections) = load_full_text_success(&cache_key) {
-        return Ok(sections);
-    }
-    if full_text_negative_cached(&cache_key) {
-        return Ok(Vec::new());
-    }
-
-    let budget = scihub_adaptive_step_timeout(step_timeout);
-    let deadline = tokio::time::Instant::now() + budget;
-    let scihub = crate::sources::scihub::SciHubClient::new();
-
-    let download_remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
-    if download_remaining.is_zero() {
-        scihub_adaptive_record_attempt(false);
-        save_full_text_negative(&cache_key, "scihub_budget_exhausted_before_download");
-        return Ok(Vec::new());
-    }
-
-    if let Ok(Ok(Some(pdf_bytes))) =
-        timeout(download_remaining, scihub.download_pdf(identifier)).await
-    {
-        let parse_remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
-        if parse_remaining.is_zero() {
-            scihub_adaptive_record_attempt(false);
-            save_full_text_negative(&cache_key, "scihub_budget_exhausted_before_parse");
-            return Ok(Vec::new());
-        }
-        if let Ok(Ok(sections)) = timeout(parse_remaining, parse_pdf_bytes(&pdf_bytes)).await {
-            if !sections.is_empty() {
-                scihub_adaptive_record_attempt(true);
-                clear_full_text_negative(&cache_key);
-                save_full_text_success(&cache_key, &sections);
-                return Ok(sections);
-            }
-        }
-    }
-    scihub_adaptive_record_attempt(false);
-    save_full_text_negative(&cache_key, "scihub_path_failed");
-    Ok(Vec::new())
 }
 
 // ── Full-text PDF fetcher ─────────────────────────────────────────────────────
 
-/// Download a PDF from URL and parse it with Ferrules.
+/// Download a PDF from URL and parse it through the extractor fallback chain.
 /// Returns sections extracted from the PDF.
 async fn fetch_and_parse_pdf(pdf_url: &str) -> anyhow::Result<Vec<DocumentSection>> {
     let client = PDF_HTTP_CLIENT.get_or_init(|| {
@@ -2996,6 +3981,13 @@ async fn parse_pdf_bytes(pdf_bytes: &[u8]) -> anyhow::Result<Vec<DocumentSection
     let cache_key = hash_bytes(pdf_bytes);
     if let Some(cached) = load_pdf_parse_cache(&cache_key) {
         if should_use_cached_pdf_parse(&cached) {
+            if cached.low_quality {
+                return Err(LowQualityText {
+                    score: cached.text_quality.unwrap_or_default(),
+                    sections: cached.sections,
+                }
+                .into());
+            }
             if cached.parse_ok {
                 return Ok(cached.sections);
             }
@@ -3007,14 +3999,43 @@ async fn parse_pdf_bytes(pdf_bytes: &[u8]) -> anyhow::Result<Vec<DocumentSection
     std::io::Write::write_all(&mut temp_file, pdf_bytes)?;
     let temp_path = temp_file.path().to_path_buf();
 
-    let parsed = tokio::task::spawn_blocking(move || parse_pdf_sections(&temp_path)).await??;
+    let min_quality = resolve_pdf_min_text_quality();
+    let parsed = tokio::task::spawn_blocking(move || {
+        parse_pdf_sections_with_min_quality(&temp_path, min_quality)
+    })
+    .await??;
 
     info!(
         title = ?parsed.title,
         n_sections = parsed.sections.len(),
         page_count = parsed.page_count,
-        "PDF parsed with Ferrules"
+        extractor = parsed.extractor.as_str(),
+        text_quality = parsed.quality.score,
+        "PDF parsed"
     );
+    let parser_variant = parsed.extractor.as_str().to_string();
+    let text_quality = Some(parsed.quality.score);
+    if parsed.low_quality {
+        save_pdf_parse_cache(
+            &cache_key,
+            &ParsedPdfCacheEntry {
+                parse_ok: false,
+                quality_ok: false,
+                parser_variant,
+                section_count: parsed.sections.len(),
+                char_count: section_char_count(&parsed.sections),
+                cached_at_epoch_secs: now_epoch_secs(),
+                text_quality,
+                low_quality: true,
+                sections: parsed.sections.clone(),
+            },
+        );
+        return Err(LowQualityText {
+            score: parsed.quality.score,
+            sections: parsed.sections,
+        }
+        .into());
+    }
     let mut sections = parsed.sections;
     if !resolve_pdf_parse_fallback_enabled() {
         sections.retain(|s| !s.text.trim().is_empty());
@@ -3034,10 +4055,12 @@ async fn parse_pdf_bytes(pdf_bytes: &[u8]) -> anyhow::Result<Vec<DocumentSection
             &ParsedPdfCacheEntry {
                 parse_ok: false,
                 quality_ok: false,
-                parser_variant: "ferrules".to_string(),
+                parser_variant,
                 section_count: sections.len(),
                 char_count: section_char_count(&sections),
                 cached_at_epoch_secs: now_epoch_secs(),
+                text_quality,
+                low_quality: false,
                 sections: Vec::new(),
             },
         );
@@ -3048,10 +4071,12 @@ async fn parse_pdf_bytes(pdf_bytes: &[u8]) -> anyhow::Result<Vec<DocumentSection
         &ParsedPdfCacheEntry {
             parse_ok: !sections.is_empty(),
             quality_ok,
-            parser_variant: "ferrules".to_string(),
+            parser_variant,
             section_count: sections.len(),
             char_count: section_char_count(&sections),
             cached_at_epoch_secs: now_epoch_secs(),
+            text_quality,
+            low_quality: false,
             sections: sections.clone(),
         },
     );
@@ -3169,103 +4194,12 @@ fn pdf_cache_counters() -> (usize, usize) {
     )
 }
 
+/// Sections of a Europe PMC `fullTextXML` response, figure captions
+/// included; see [`crate::jats`].
 fn parse_pmc_xml_sections(xml: &str) -> Vec<DocumentSection> {
-    use quick_xml::events::Event;
-    use quick_xml::Reader;
-
-    let mut reader = Reader::from_str(xml);
-    reader.config_mut().trim_text(true);
-    let mut buf = Vec::new();
-
-    let mut sections = Vec::new();
-    let mut in_abstract = false;
-    let mut in_sec = false;
-    let mut in_title = false;
-    let mut in_p = false;
-    let mut sec_heading: Option<String> = None;
-    let mut sec_text = String::new();
-    let mut abstract_text = String::new();
-
-    loop {
-        match reader.read_event_into(&mut buf) {
-            Ok(Event::Start(e)) => match e.local_name().as_ref() {
-                b"abstract" => in_abstract = true,
-                b"sec" => {
-                    in_sec = true;
-                    sec_heading = None;
-                    sec_text.clear();
-                }
-                b"title" => in_title = true,
-                b"p" => in_p = true,
-                _ => {}
-            },
-            Ok(Event::Text(t)) => {
-                let text = t.unescape().map(|v| v.to_string()).unwrap_or_default();
-                let text = text.trim();
-                if text.is_empty() {
-                    buf.clear();
-                    continue;
-                }
-                if in_abstract && in_p {
-                    if !abstract_text.is_empty() {
-                        abstract_text.push(' ');
-                    }
-                    abstract_text.push_str(text);
-                } else if in_sec && in_title {
-                    if sec_heading.is_none() {
-                        sec_heading = Some(text.to_string());
-                    }
-                } else if in_sec && in_p {
-                    if !sec_text.is_empty() {
-                        sec_text.push(' ');
-                    }
-                    sec_text.push_str(text);
-                }
-            }
-            Ok(Event::End(e)) => match e.local_name().as_ref() {
-                b"title" => in_title = false,
-                b"p" => in_p = false,
-                b"abstract" => in_abstract = false,
-                b"sec" => {
-                    in_sec = false;
-                    if !sec_text.trim().is_empty() {
-                        let heading = sec_heading.clone();
-                        let section_type = heading
-                            .as_deref()
-                            .map(SectionType::from_heading)
-                            .unwrap_or(SectionType::Other);
-                        sections.push(DocumentSection {
-                            section_type,
-                            heading,
-                            text: sec_text.trim().to_string(),
-                            page_number: None,
-                        });
-                    }
-                    sec_heading = None;
-                    sec_text.clear();
-                }
-                _ => {}
-            },
-            Ok(Event::Eof) => break,
-            Err(_) => break,
-            _ => {}
-        }
-        buf.clear();
-    }
-
-    if !abstract_text.trim().is_empty() {
-        sections.insert(
-            0,
-            DocumentSection {
-                section_type: SectionType::Abstract,
-                heading: Some("Abstract".to_string()),
-                text: abstract_text.trim().to_string(),
-                page_number: None,
-            },
-        );
-    }
-
-    sections
+    crate::jats::parse_jats(xml)
+        .map(|article| article.chunker_sections())
+        .unwrap_or_default()
 }
 
 // ── Tests ─────────────────────────────────────────────────────────────────────
@@ -3273,6 +4207,8 @@ fn parse_pmc_xml_sections(xml: &str) -> Vec<DocumentSection> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::chunker::{TokenCounter, WordEstimate};
+    use crate::rate_limit::TokenBucket;
 
     #[test]
     fn test_build_query_with_mutation() {
@@ -3288,6 +4224,32 @@ mod tests {
         assert!(q.contains("pancreatic cancer"));
     }
 
+    #[test]
+    fn job_config_hash_ignores_credentials() {
+        let job = IngestionJob::default();
+        let mut with_keys = job.clone();
+        with_keys.pubmed_api_key = Some("secret".to_string());
+        with_keys.unpaywall_email = Some("me@example.org".to_string());
+        assert_eq!(job_config_hash(&job), job_config_hash(&with_keys));
+
+        let other_gene = IngestionJob {
+            gene: "BRAF".to_string(),
+            ..job.clone()
+        };
+        assert_ne!(job_config_hash(&job), job_config_hash(&other_gene));
+    }
+
+    #[test]
+    fn scihub_is_only_a_full_text_route_when_the_job_opts_in() {
+        let job = IngestionJob::default();
+        assert!(!full_text_resolver(&job).scihub_enabled());
+        let opted_in = IngestionJob {
+            enable_scihub_fallback: true,
+            ..job
+        };
+        assert!(full_text_resolver(&opted_in).scihub_enabled());
+    }
+
     #[test]
     fn test_build_query_without_mutation() {
         let job = IngestionJob {
@@ -3321,6 +4283,8 @@ mod tests {
             source: IngestionSource::PubMed,
             open_access: false,
             full_text_url: None,
+            is_review: false,
+            preprint: None,
         };
         let sections = build_sections_from_abstract(&paper);
         assert!(sections
@@ -3345,4 +4309,524 @@ mod tests {
             .iter()
             .any(|s| s.heading.as_deref() == Some("Results")));
     }
+
+    /// NER over a one-gene HGNC table (TP53, alias LFS1) and one cancer type.
+    fn tp53_ner() -> TrieNer {
+        use ferrumyx_kg::ner::{CancerNormaliser, HgncNormaliser};
+
+        let mut row = vec![""; 26];
+        row[0] = "HGNC:11998";
+        row[1] = "TP53";
+        row[2] = "tumor protein p53";
+        row[5] = "Approved";
+        row[8] = "p53|LFS1";
+        let hgnc = HgncNormaliser::from_tsv(&format!("header\n{}", row.join("\t"))).unwrap();
+        let cancers = CancerNormaliser::from_json(&serde_json::json!([
+            { "code": "PAAD", "name": "Pancreatic Adenocarcinoma" }
+        ]))
+        .unwrap();
+        TrieNer::from_normalisers(hgnc, cancers).unwrap()
+    }
+
+    /// Tags every occurrence of one phrase, standing in for a model.
+    struct PhraseTagger(&'static str);
+
+    impl SpanTagger for PhraseTagger {
+        fn max_len(&self) -> usize {
+            512
+        }
+
+        fn tag(&self, text: &str) -> anyhow::Result<Vec<ExtractedEntity>> {
+            Ok(text
+                .match_indices(self.0)
+                .map(|(start, phrase)| ExtractedEntity {
+                    text: phrase.to_string(),
+                    label: NerEntityType::CancerType,
+                    start,
+                    end: start + phrase.len(),
+                    confidence: 0.8,
+                    canonical_id: None,
+                    canonical_name: None,
+                })
+                .collect())
+        }
+    }
+
+    #[test]
+    fn ner_mode_selects_the_extractor() {
+        let ner = Arc::new(tp53_ner());
+        let texts = ["TP53 loss drives ductal carcinoma in PAAD"];
+        let tagger = PhraseTagger("ductal carcinoma");
+        let found = |mode, tagger: Option<&dyn SpanTagger>| -> Vec<String> {
+            extract_chunk_entities(&ner, mode, tagger, &texts)
+                .remove(0)
+                .into_iter()
+                .map(|e| e.text)
+                .collect()
+        };
+
+        assert_eq!(found(NerMode::Trie, Some(&tagger)), ["TP53", "PAAD"]);
+        assert_eq!(
+            found(NerMode::Hybrid, Some(&tagger)),
+            ["TP53", "ductal carcinoma", "PAAD"]
+        );
+        assert_eq!(found(NerMode::Hybrid, None), ["TP53", "PAAD"]);
+    }
+
+    #[test]
+    fn dictionary_aliases_collapse_to_one_entity_candidate() {
+        let ner = tp53_ner();
+
+        let mut candidates = BTreeMap::new();
+        for entity in ner.extract("TP53 (LFS1) is lost in PAAD and Pancreatic Adenocarcinoma") {
+            let subject = mention_subject(&entity);
+            add_entity_candidate(
+                &mut candidates,
+                map_ner_type(entity.label),
+                &subject,
+                entity.canonical_id.as_deref(),
+            );
+        }
+        // A relation object naming the same cancer type must not drop its id.
+        add_entity_candidate(&mut candidates, DbEntityType::CancerType, "PAAD", None);
+
+        let candidates: Vec<EntityCandidate> = candidates.into_values().collect();
+        assert_eq!(
+            candidates,
+            vec![
+                (
+                    DbEntityType::CancerType,
+                    "PAAD".to_string(),
+                    Some("PAAD".to_string())
+                ),
+                (
+                    DbEntityType::Gene,
+                    "TP53".to_string(),
+                    Some("HGNC:11998".to_string())
+                ),
+            ]
+        );
+    }
+
+    fn watermark_paper(pmid: &str, title: &str, day: u32) -> crate::models::PaperMetadata {
+        crate::models::PaperMetadata {
+            doi: None,
+            pmid: Some(pmid.to_string()),
+            pmcid: None,
+            title: title.to_string(),
+            abstract_text: None,
+            authors: Vec::new(),
+            journal: None,
+            pub_date: chrono::NaiveDate::from_ymd_opt(2026, 1, day),
+            source: crate::models::IngestionSource::PubMed,
+            open_access: false,
+            full_text_url: None,
+            is_review: false,
+            preprint: None,
+        }
+    }
+
+    #[test]
+    fn published_preprint_shares_identity_with_its_journal_record() {
+        let mut journal = watermark_paper("41000001", "KRAS G12D drives immune evasion", 5);
+        journal.doi = Some("https://doi.org/10.1038/S41586-026-01234-5".to_string());
+        let mut preprint = watermark_paper("", "KRAS G12D drives immune evasion", 5);
+        preprint.pmid = None;
+        preprint.doi = Some("10.1101/2026.01.12.598765".to_string());
+        preprint.source = crate::models::IngestionSource::BioRxiv;
+        preprint.preprint = Some(crate::models::PreprintInfo {
+            version: 2,
+            published_doi: Some("10.1038/s41586-026-01234-5".to_string()),
+        });
+        assert_eq!(
+            canonical_paper_identity_key(&preprint),
+            canonical_paper_identity_key(&journal)
+        );
+
+        preprint.preprint = Some(crate::models::PreprintInfo {
+            version: 1,
+            published_doi: None,
+        });
+        assert_eq!(
+            canonical_paper_identity_key(&preprint),
+            "doi:10.1101/2026.01.12.598765"
+        );
+    }
+
+    /// One run against a PubMed stand-in that filters on when it indexed
+    /// each paper: resolve `since`, search, store the papers and the
+    /// watermark.
+    async fn simulate_run(
+        repo: &IngestionRepository,
+        job: &IngestionJob,
+        indexed: &[(DateTime<Utc>, crate::models::PaperMetadata)],
+        started_at: DateTime<Utc>,
+    ) -> (Option<DateTime<Utc>>, IngestionResult) {
+        let watermarks = IngestionWatermarkRepository::new(repo.db());
+        let query = build_query_for_source(job, &IngestionSourceSpec::PubMed);
+        let previous = watermarks.find(&query, "PubMed").await.unwrap();
+        let since = resolve_since(job, previous.as_ref());
+        let found: Vec<_> = indexed
+            .iter()
+            .filter(|(at, _)| since.is_none_or(|since| *at >= since))
+            .map(|(_, paper)| paper.clone())
+            .collect();
+
+        let mut result = IngestionResult {
+            job_id: Uuid::new_v4(),
+            query: query.clone(),
+            papers_found_raw: found.len(),
+            papers_found: found.len(),
+            papers_inserted: 0,
+            papers_duplicate: 0,
+            inserted_paper_ids: Vec::new(),
+            chunks_inserted: 0,
+            chunks_embedded: 0,
+            source_telemetry: Vec::new(),
+            perf_telemetry: IngestionPerfTelemetry::default(),
+            errors: Vec::new(),
+            papers_succeeded: 0,
+            papers_failed: 0,
+            failure_samples: Vec::new(),
+            duration_ms: 0,
+            cancelled: false,
+        };
+        upsert_new_papers(repo, found.clone(), &mut result).await;
+        let watermark = advance_watermark(previous, &query, "PubMed", started_at, &found);
+        watermarks.upsert(&watermark).await.unwrap();
+        (since, result)
+    }
+
+    #[tokio::test]
+    async fn incremental_rerun_only_processes_the_delta() {
+        use chrono::TimeZone;
+
+        let dir = std::env::temp_dir().join(format!("ferrumyx-incremental-{}", Uuid::new_v4()));
+        let db = Arc::new(ferrumyx_db::Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        let repo = IngestionRepository::new(db);
+        let job = IngestionJob {
+            incremental: true,
+            ..Default::default()
+        };
+        let at = |month, day| Utc.with_ymd_and_hms(2026, month, day, 9, 0, 0).unwrap();
+
+        let mut indexed = vec![
+            (
+                at(1, 2),
+                watermark_paper("101", "KRAS G12D organoid screen", 2),
+            ),
+            (
+                at(1, 6),
+                watermark_paper("102", "MRTX1133 resistance in PDAC", 6),
+            ),
+        ];
+        let (since, first) = simulate_run(&repo, &job, &indexed, at(1, 10)).await;
+        assert_eq!(since, None);
+        assert_eq!((first.papers_inserted, first.papers_duplicate), (2, 0));
+
+        // A correction re-indexes 102; 103 is new.
+        indexed.push((
+            at(1, 15),
+            watermark_paper("102", "MRTX1133 resistance in PDAC", 6),
+        ));
+        indexed.push((
+            at(1, 20),
+            watermark_paper("103", "Pan-RAS inhibition in pancreatic cancer", 18),
+        ));
+        let (since, second) = simulate_run(&repo, &job, &indexed, at(2, 1)).await;
+        assert_eq!(since, Some(at(1, 10)));
+        assert_eq!(second.papers_found, 2);
+        assert_eq!((second.papers_inserted, second.papers_duplicate), (1, 1));
+        assert!(second.errors.is_empty(), "{:?}", second.errors);
+
+        let query = build_query_for_source(&job, &IngestionSourceSpec::PubMed);
+        let watermark = IngestionWatermarkRepository::new(repo.db())
+            .find(&query, "PubMed")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(watermark.last_run_at, at(2, 1));
+        assert_eq!(
+            watermark.last_seen_publication_date,
+            chrono::NaiveDate::from_ymd_opt(2026, 1, 18)
+        );
+        assert_eq!(watermark.paper_count, 2);
+        assert_eq!(
+            resolve_since(&IngestionJob::default(), Some(&watermark)),
+            None
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    /// Counts tokens like the default chunker, except that it panics on
+    /// text containing "MALFORMED".
+    struct PanicsOnMarker;
+
+    impl TokenCounter for PanicsOnMarker {
+        fn count_tokens(&self, text: &str) -> usize {
+            assert!(!text.contains("MALFORMED"), "tokenizer choked on {text:?}");
+            WordEstimate.count_tokens(text)
+        }
+    }
+
+    /// Prefetch 16 papers from a mocked source whose fetches take a token
+    /// from `bucket` and then spend a second on the wire.
+    async fn mocked_prefetch(workers: usize, bucket: Arc<TokenBucket>) -> std::time::Duration {
+        let papers = (0..16)
+            .map(|i| (watermark_paper(&i.to_string(), "KRAS", 1), Uuid::new_v4()))
+            .collect();
+        let (tx, mut rx) = mpsc::channel::<PrefetchedPaper>(4);
+        let received = tokio::spawn(async move {
+            let mut n = 0;
+            while rx.recv().await.is_some() {
+                n += 1;
+            }
+            n
+        });
+        let started = tokio::time::Instant::now();
+        prefetch_full_texts(
+            papers,
+            workers,
+            &PaperWindow::new(64),
+            &CancellationToken::new(),
+            &tx,
+            |_, _| {
+                let bucket = bucket.clone();
+                async move {
+                    bucket.acquire().await;
+                    sleep(std::time::Duration::from_secs(1)).await;
+                    FetchedFullText::default()
+                }
+            },
+        )
+        .await;
+        let elapsed = started.elapsed();
+        drop(tx);
+        assert_eq!(received.await.unwrap(), 16);
+        elapsed
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn prefetch_scales_with_workers_until_the_source_rate_binds() {
+        for (workers, secs) in [(1, 16), (2, 8), (4, 4), (8, 2), (16, 1), (32, 1)] {
+            let unthrottled = Arc::new(TokenBucket::new(50, 64));
+            assert_eq!(
+                mocked_prefetch(workers, unthrottled).await,
+                std::time::Duration::from_secs(secs),
+                "{workers} workers"
+            );
+        }
+
+        // At 4 requests a second the sixteenth fetch cannot start before
+        // 3.75 s, however many workers are free.
+        let paced = Arc::new(TokenBucket::new(4, 1));
+        assert_eq!(
+            mocked_prefetch(16, paced).await,
+            std::time::Duration::from_millis(4750)
+        );
+    }
+
+    #[tokio::test]
+    async fn a_paper_that_panics_in_chunking_does_not_stop_its_batch() {
+        let mut papers = vec![
+            watermark_paper("201", "TP53 loss in pancreatic cancer", 2),
+            watermark_paper("202", "MALFORMED record from a broken export", 3),
+            watermark_paper("203", "LFS1 carriers and PAAD risk", 4),
+        ];
+        for paper in &mut papers {
+            paper.abstract_text = Some(format!("{} was studied in a small cohort.", paper.title));
+        }
+        let chunker_cfg = ChunkerConfig {
+            tokenizer: Arc::new(PanicsOnMarker),
+            ..ChunkerConfig::default()
+        };
+        let (result, repo, dir) = run_offline(IngestionJob::default(), papers, chunker_cfg).await;
+        let failed_items = FailedItemRepository::new(repo.db());
+
+        assert_eq!(result.papers_inserted, 3);
+        assert_eq!((result.papers_succeeded, result.papers_failed), (2, 1));
+        assert!(result.chunks_inserted >= 2, "{}", result.chunks_inserted);
+        assert_eq!(result.status(), "partial");
+        assert_eq!(result.failure_samples.len(), 1);
+        assert!(
+            result.failure_samples[0].starts_with("202: chunking: panicked: tokenizer choked"),
+            "{:?}",
+            result.failure_samples
+        );
+        assert_eq!(repo.paper_count_by_status("failed").await.unwrap(), 1);
+
+        let failed = failed_items.list(10).await.unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].pmid.as_deref(), Some("202"));
+        assert_eq!(failed[0].stage, "chunking");
+        assert_eq!(failed[0].job_id, Some(result.job_id));
+        assert_eq!(failed[0].retry_count, 0);
+        let stored: crate::models::PaperMetadata =
+            serde_json::from_str(&failed[0].metadata).unwrap();
+        assert_eq!(stored.title, "MALFORMED record from a broken export");
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    /// OpenAI-compatible embedder that answers each request after `delay`
+    /// with one vector per input.
+    async fn serve_slow_embedder(delay: std::time::Duration) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 4096];
+                    let (body_start, body_len) = loop {
+                        let n = socket.read(&mut buf).await.unwrap();
+                        request.extend_from_slice(&buf[..n]);
+                        if let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                            let head = String::from_utf8_lossy(&request[..end]).to_lowercase();
+                            let len = head
+                                .lines()
+                                .find_map(|l| l.strip_prefix("content-length:"))
+                                .and_then(|v| v.trim().parse::<usize>().ok())
+                                .unwrap_or(0);
+                            break (end + 4, len);
+                        }
+                    };
+                    while request.len() < body_start + body_len {
+                        let n = socket.read(&mut buf).await.unwrap();
+                        request.extend_from_slice(&buf[..n]);
+                    }
+                    let body: serde_json::Value =
+                        serde_json::from_slice(&request[body_start..]).unwrap();
+                    let inputs = body["input"].as_array().map_or(0, Vec::len);
+                    tokio::time::sleep(delay).await;
+                    let data: Vec<serde_json::Value> = (0..inputs)
+                        .map(|i| {
+                            serde_json::json!({
+                                "index": i,
+                                "embedding": vec![0.5f32; ferrumyx_db::schema::EMBEDDING_DIM],
+                            })
+                        })
+                        .collect();
+                    let payload = serde_json::json!({ "data": data }).to_string();
+                    let head = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        payload.len()
+                    );
+                    socket.write_all(head.as_bytes()).await.unwrap();
+                    socket.write_all(payload.as_bytes()).await.unwrap();
+                    let _ = socket.shutdown().await;
+                });
+            }
+        });
+        url
+    }
+
+    /// Run `job` over `papers` against a fresh database, offline: no source
+    /// search, no dictionary download, no CrossRef.
+    async fn run_offline(
+        job: IngestionJob,
+        papers: Vec<crate::models::PaperMetadata>,
+        chunker: ChunkerConfig,
+    ) -> (IngestionResult, Arc<IngestionRepository>, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-offline-run-{}", Uuid::new_v4()));
+        let db = Arc::new(ferrumyx_db::Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        let repo = Arc::new(IngestionRepository::new(db));
+        let job = IngestionJob {
+            sources: Vec::new(),
+            full_text_enabled: false,
+            source_cache_enabled: false,
+            ..job
+        };
+        let overrides = PipelineOverrides {
+            papers: Some(papers),
+            ner: Some(Arc::new(tp53_ner())),
+            chunker: Some(chunker),
+            skip_enrichment: true,
+        };
+        let result = run_ingestion_with_overrides(
+            job,
+            repo.clone(),
+            None,
+            CancellationToken::new(),
+            overrides,
+        )
+        .await;
+        (result, repo, dir)
+    }
+
+    #[tokio::test]
+    async fn a_tight_memory_budget_spills_without_losing_chunks() {
+        let embedder = serve_slow_embedder(std::time::Duration::from_millis(25)).await;
+        // Odd papers alone outgrow the chunk buffer, so they always spill.
+        let papers: Vec<crate::models::PaperMetadata> = (0..12)
+            .map(|i| {
+                let mut paper = watermark_paper(
+                    &format!("30{i:02}"),
+                    &format!("TP53 loss in PAAD cohort {i}"),
+                    i + 1,
+                );
+                let sentence = "TP53 loss was seen with LFS1 variants across PAAD tumours. ";
+                let repeats = if i % 2 == 1 { 40 } else { 10 };
+                paper.abstract_text = Some(format!("Cohort {i}. {}", sentence.repeat(repeats)));
+                paper
+            })
+            .collect();
+        let job = |memory_budget| IngestionJob {
+            embedding_cfg: Some(EmbeddingConfig {
+                backend: crate::embedding::EmbeddingBackend::OpenAiCompatible,
+                base_url: Some(embedder.clone()),
+                batch_size: 4,
+                ..EmbeddingConfig::default()
+            }),
+            memory_budget,
+            ..IngestionJob::default()
+        };
+
+        let unconstrained = PipelineMemoryBudget {
+            max_inflight_papers: 4096,
+            channel_capacity: 4096,
+            max_chunk_buffer_bytes: usize::MAX / 2,
+        };
+        let (free, _, free_dir) =
+            run_offline(job(unconstrained), papers.clone(), ChunkerConfig::default()).await;
+        let tight = PipelineMemoryBudget {
+            max_inflight_papers: 2,
+            channel_capacity: 1,
+            max_chunk_buffer_bytes: 2048,
+        };
+        let (constrained, _, tight_dir) =
+            run_offline(job(tight), papers.clone(), ChunkerConfig::default()).await;
+
+        assert_eq!(free.papers_succeeded, papers.len(), "{:?}", free.errors);
+        assert_eq!(free.perf_telemetry.spilled_papers, 0);
+        assert!(free.chunks_inserted >= papers.len());
+        assert_eq!(free.chunks_embedded, free.chunks_inserted);
+
+        assert_eq!(
+            constrained.papers_succeeded,
+            papers.len(),
+            "{:?}",
+            constrained.errors
+        );
+        let peak = constrained.perf_telemetry.peak_inflight_papers;
+        assert!((1..=2).contains(&peak), "peak in-flight papers {peak}");
+        assert!(
+            constrained.perf_telemetry.spilled_papers >= papers.len() / 2,
+            "{:?}",
+            constrained.perf_telemetry
+        );
+        assert!(constrained.perf_telemetry.peak_chunk_buffer_bytes <= 2048);
+        assert_eq!(
+            (constrained.chunks_inserted, constrained.chunks_embedded),
+            (free.chunks_inserted, free.chunks_embedded)
+        );
+
+        let _ = std::fs::remove_dir_all(free_dir);
+        let _ = std::fs::remove_dir_all(tight_dir);
+    }
 }
@@ -0,0 +1,177 @@
+//! Request pacing shared by every client of a literature source.
+//!
+//! Source clients are cheap and the pipeline builds a new one for each
+//! search and each full-text attempt, so pacing kept inside a client does
+//! not stop eight prefetch workers from reaching Europe PMC at the same
+//! moment. Each source instead draws from one process-wide [`TokenBucket`],
+//! looked up by name with [`source_bucket`].
+//!
+//! A source's rate comes from `FERRUMYX_<SOURCE>_REQUESTS_PER_SECOND`
+//! (see [`requests_per_second_env`]), which the agent sets from
+//! `[ingestion.<source>] requests_per_second`, and otherwise from
+//! [`default_requests_per_second`].
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Token bucket refilled at a fixed rate.
+///
+/// Implemented by its theoretical arrival time: the instant at which the
+/// bucket would next be empty if every caller took a token on time. A
+/// caller waits until that instant is within `burst - 1` intervals of now.
+pub struct TokenBucket {
+    interval: Duration,
+    tolerance: Duration,
+    /// Held across the wait, so callers are served in arrival order.
+    arrival: tokio::sync::Mutex<Option<Instant>>,
+}
+
+impl TokenBucket {
+    /// `requests_per_second` tokens per second, up to `burst` held at once.
+    pub fn new(requests_per_second: u32, burst: u32) -> Self {
+        let interval = Duration::from_secs(1) / requests_per_second.max(1);
+        Self {
+            interval,
+            tolerance: interval * burst.max(1).saturating_sub(1),
+            arrival: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    /// Wait for a token and take it.
+    pub async fn acquire(&self) {
+        let mut arrival = self.arrival.lock().await;
+        let now = Instant::now();
+        let due = arrival.map_or(now, |at| at.max(now));
+        let ready_at = due.checked_sub(self.tolerance).unwrap_or(now);
+        if ready_at > now {
+            tokio::time::sleep_until(ready_at).await;
+        }
+        *arrival = Some(due + self.interval);
+    }
+
+    /// Spacing between tokens once any burst is spent.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+}
+
+/// Env var holding the request rate for `source`, e.g.
+/// `FERRUMYX_EUROPEPMC_REQUESTS_PER_SECOND`.
+pub fn requests_per_second_env(source: &str) -> String {
+    format!("FERRUMYX_{}_REQUESTS_PER_SECOND", source.to_uppercase())
+}
+
+/// Published or polite request rate for `source`. Sources without a
+/// published limit get a conservative 5.
+pub fn default_requests_per_second(source: &str) -> u32 {
+    match source {
+        // NCBI allows 3 a second, or 10 with an API key.
+        "pubmed" if pubmed_api_key_configured() => 10,
+        "pubmed" => 3,
+        // Unauthenticated Semantic Scholar traffic shares one pool.
+        "semanticscholar" => 1,
+        // arXiv asks for no more than one request every few seconds;
+        // ClinicalTrials.gov throttles at about 50 a minute.
+        "arxiv" | "clinicaltrials" => 1,
+        "unpaywall" => 10,
+        _ => 5,
+    }
+}
+
+fn pubmed_api_key_configured() -> bool {
+    std::env::var("FERRUMYX_PUBMED_API_KEY").is_ok_and(|k| !k.trim().is_empty())
+}
+
+/// Rate for `source`: its env var when set to a positive number, else the
+/// default. Capped at 50.
+pub fn requests_per_second(source: &str) -> u32 {
+    std::env::var(requests_per_second_env(source))
+        .ok()
+        .and_then(|v| v.trim().parse::<u32>().ok())
+        .filter(|&rps| rps > 0)
+        .unwrap_or_else(|| default_requests_per_second(source))
+        .min(50)
+}
+
+static SOURCE_BUCKETS: OnceLock<Mutex<HashMap<String, Arc<TokenBucket>>>> = OnceLock::new();
+
+/// The bucket every client of `source` shares, created at the source's
+/// [`requests_per_second`] on first use.
+///
+/// Shared buckets hold one token, so requests are evenly spaced: providers
+/// count requests per rolling second, and a refilled burst on top of a
+/// steady stream would briefly exceed the rate.
+pub fn source_bucket(source: &str) -> Arc<TokenBucket> {
+    let buckets = SOURCE_BUCKETS.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut buckets = buckets.lock().unwrap_or_else(|e| e.into_inner());
+    buckets
+        .entry(source.to_string())
+        .or_insert_with(|| Arc::new(TokenBucket::new(requests_per_second(source), 1)))
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn a_bucket_spends_its_burst_then_paces_at_its_rate() {
+        let bucket = TokenBucket::new(4, 2);
+        let started = Instant::now();
+        let mut taken_at = Vec::new();
+        for _ in 0..5 {
+            bucket.acquire().await;
+            taken_at.push(started.elapsed().as_millis());
+        }
+        assert_eq!(taken_at, [0, 0, 250, 500, 750]);
+
+        // A quiet spell refills the burst, but no more than the burst.
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        let idle_until = started.elapsed().as_millis();
+        for _ in 0..3 {
+            bucket.acquire().await;
+        }
+        assert_eq!(started.elapsed().as_millis() - idle_until, 250);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn concurrent_callers_share_one_rate() {
+        let bucket = Arc::new(TokenBucket::new(10, 1));
+        let started = Instant::now();
+        let callers: Vec<_> = (0..20)
+            .map(|_| {
+                let bucket = bucket.clone();
+                tokio::spawn(async move { bucket.acquire().await })
+            })
+            .collect();
+        for caller in callers {
+            caller.await.unwrap();
+        }
+        assert_eq!(started.elapsed(), Duration::from_millis(1900));
+    }
+
+    #[test]
+    fn rates_come_from_the_env_or_the_source_default() {
+        assert_eq!(
+            requests_per_second_env("europepmc"),
+            "FERRUMYX_EUROPEPMC_REQUESTS_PER_SECOND"
+        );
+        assert_eq!(requests_per_second("rate-test-unset"), 5);
+        std::env::set_var(requests_per_second_env("rate-test-a"), "12");
+        assert_eq!(requests_per_second("rate-test-a"), 12);
+        std::env::set_var(requests_per_second_env("rate-test-b"), "0");
+        assert_eq!(requests_per_second("rate-test-b"), 5);
+        std::env::set_var(requests_per_second_env("rate-test-c"), "500");
+        assert_eq!(requests_per_second("rate-test-c"), 50);
+        assert!(Arc::ptr_eq(
+            &source_bucket("rate-test-a"),
+            &source_bucket("rate-test-a")
+        ));
+        assert_eq!(
+            source_bucket("rate-test-a").interval(),
+            Duration::from_secs(1) / 12
+        );
+    }
+}
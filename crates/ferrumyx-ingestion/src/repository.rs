@@ -4,13 +4,17 @@
 //! - Paper INSERT with DOI/PMID deduplication
 //! - DocumentChunk INSERT with embedding placeholder
 //! - Ingestion audit logging
-//! - SimHash-based duplicate detection at the DB level
+//! - MinHash/LSH near-duplicate detection at the DB level
 
-use crate::dedup::{check_fuzzy_duplicate, hamming_distance, simhash, DedupResult};
+use crate::dedup::{
+    check_fuzzy_duplicate, jaccard, minhash_signature, simhash, DedupResult, MinHashIndex,
+    NEAR_DUPLICATE_JACCARD,
+};
 use crate::models::{Author, DocumentChunk, IngestionSource, PaperMetadata};
 use anyhow::Result;
 use arrow_array::{RecordBatch, RecordBatchIterator, StringArray};
 use arrow_schema::{DataType, Field, Schema};
+use ferrumyx_common::repro;
 use ferrumyx_db::{
     chunks::ChunkRepository,
     papers::PaperRepository,
@@ -23,13 +27,19 @@ use lancedb::query::{ExecutableQuery, QueryBase};
 use serde_json::{json, Value};
 use std::collections::HashSet;
 use std::sync::Arc;
+use tokio::sync::RwLock;
 use uuid::Uuid;
 
+/// Verified LSH candidates considered per incoming abstract.
+const MAX_VERIFIED_CANDIDATES: usize = 8;
+
 /// Result of a paper upsert.
 #[derive(Debug)]
 pub struct PaperUpsertResult {
     pub paper_id: Uuid,
     pub was_new: bool,
+    /// Stored paper this one duplicates; set on skipped duplicates and on
+    /// new rows linked to a near-duplicate from another venue.
     pub duplicate_of: Option<Uuid>,
 }
 
@@ -37,11 +47,16 @@ pub struct PaperUpsertResult {
 #[derive(Clone)]
 pub struct IngestionRepository {
     db: Arc<Database>,
+    /// Near-duplicate index over stored abstracts, built on first use.
+    minhash: Arc<RwLock<Option<MinHashIndex>>>,
 }
 
 impl IngestionRepository {
     pub fn new(db: Arc<Database>) -> Self {
-        Self { db }
+        Self {
+            db,
+            minhash: Arc::new(RwLock::new(None)),
+        }
     }
 
     /// Get underlying database reference.
@@ -52,8 +67,14 @@ impl IngestionRepository {
     // ── Paper operations ─────────────────────────────────────────────────────
 
     /// Insert a paper, skipping if DOI or PMID already exists.
-    /// Returns the paper UUID and whether it was newly inserted.
-    pub async fn upsert_paper(&self, meta: &PaperMetadata) -> Result<PaperUpsertResult> {
+    /// Returns the paper UUID and whether it was newly inserted. New rows
+    /// record the ingestion `job_id` and search `query` that found them.
+    pub async fn upsert_paper(
+        &self,
+        meta: &PaperMetadata,
+        job_id: Option<Uuid>,
+        query: Option<&str>,
+    ) -> Result<PaperUpsertResult> {
         let paper_repo = PaperRepository::new(self.db.clone());
 
         // Check if a paper with this DOI or PMID already exists
@@ -140,38 +161,49 @@ impl IngestionRepository {
             }
         }
 
-        // Optional Stage 2/3 lexical fuzzy dedup. Disabled by default because
-        // it can over-collapse distinct papers at large ingestion scale.
-        let strict_fuzzy_dedup = std::env::var("FERRUMYX_STRICT_FUZZY_DEDUP")
+        let incoming_abstract = meta
+            .abstract_text
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty());
+        let signature = incoming_abstract.and_then(minhash_signature);
+
+        // Stage 2: near-duplicate abstracts, found through the MinHash/LSH
+        // index and verified by exact shingle Jaccard. The same work from
+        // another venue (a preprint and its journal version) is stored and
+        // linked through `duplicate_of`; a copy from the same venue is skipped.
+        let minhash_dedup = std::env::var("FERRUMYX_MINHASH_DEDUP")
             .ok()
-            .is_some_and(|v| v == "1" || v.eq_ignore_ascii_case("true"));
-        if strict_fuzzy_dedup {
-            let recent = paper_repo.list(0, 500).await.unwrap_or_default();
-            if let Some(incoming_abstract) = meta
-                .abstract_text
-                .as_deref()
-                .map(str::trim)
-                .filter(|s| !s.is_empty())
+            .is_none_or(|v| !(v == "0" || v.eq_ignore_ascii_case("false")));
+        let mut near_duplicate_of = None;
+        if let (true, Some(signature), Some(text)) = (minhash_dedup, &signature, incoming_abstract)
+        {
+            if let Some((existing, similarity)) = self
+                .find_near_duplicate(&paper_repo, signature, text)
+                .await?
             {
-                let incoming_hash = simhash(incoming_abstract);
-                if let Some(existing) = recent.iter().find(|p| {
-                    p.abstract_simhash
-                        .map(|h| hamming_distance(incoming_hash, h) <= 6)
-                        .unwrap_or(false)
-                }) {
+                if !same_venue(meta, &existing) {
+                    tracing::debug!(
+                        paper_id = %existing.id,
+                        similarity = similarity,
+                        "Linking near-duplicate paper from another venue"
+                    );
+                    near_duplicate_of = Some(existing.id);
+                } else {
                     self.record_duplicate_audit(
                         existing.id,
-                        "strict_fuzzy",
+                        "minhash",
                         json!({
-                            "method": "strict_simhash",
+                            "method": "minhash",
                             "matched_paper_id": existing.id,
-                            "distance_max": 6,
+                            "jaccard": round_similarity(similarity),
                         }),
                     )
                     .await;
                     tracing::debug!(
                         paper_id = %existing.id,
-                        "Paper deduplicated by strict abstract SimHash distance <= 6"
+                        similarity = similarity,
+                        "Paper deduplicated by MinHash abstract similarity"
                     );
                     return Ok(PaperUpsertResult {
                         paper_id: existing.id,
@@ -180,7 +212,15 @@ impl IngestionRepository {
                     });
                 }
             }
+        }
 
+        // Optional Stage 3 lexical fuzzy dedup. Disabled by default because
+        // it can over-collapse distinct papers at large ingestion scale.
+        let strict_fuzzy_dedup = std::env::var("FERRUMYX_STRICT_FUZZY_DEDUP")
+            .ok()
+            .is_some_and(|v| v == "1" || v.eq_ignore_ascii_case("true"));
+        if strict_fuzzy_dedup {
+            let recent = paper_repo.list(0, 500).await.unwrap_or_default();
             let existing_meta: Vec<PaperMetadata> = recent.iter().map(paper_to_metadata).collect();
             match check_fuzzy_duplicate(meta, existing_meta.iter()) {
                 DedupResult::ProbableDuplicate { method, similarity } => {
@@ -221,15 +261,19 @@ impl IngestionRepository {
             }
         }
 
-        let simhash: Option<i64> = meta
-            .abstract_text
-            .as_deref()
-            .map(str::trim)
-            .filter(|s| !s.is_empty())
-            .map(simhash);
+        let simhash: Option<i64> = incoming_abstract.map(simhash);
 
         let paper = Paper {
-            id: Uuid::new_v4(),
+            id: repro::new_id(
+                "paper",
+                &format!(
+                    "{}|{}|{}|{}",
+                    meta.source.as_str(),
+                    meta.doi.as_deref().unwrap_or_default(),
+                    meta.pmid.as_deref().unwrap_or_default(),
+                    meta.title
+                ),
+            ),
             doi: meta.doi.clone(),
             pmid: meta.pmid.clone(),
             title: meta.title.clone(),
@@ -264,13 +308,23 @@ impl IngestionRepository {
             parse_status: "pending".to_string(),
             open_access: meta.open_access,
             retrieval_tier: None,
-            ingested_at: chrono::Utc::now(),
+            ingested_at: repro::now(),
             abstract_simhash: simhash,
             published_version_doi: None,
+            is_review: meta.is_review,
+            citation_count: None,
+            minhash_signature: signature.clone(),
+            duplicate_of: near_duplicate_of,
+            text_quality: None,
+            job_id,
+            query_tag: query.map(str::to_string),
         };
 
         let paper_id = paper.id;
         paper_repo.insert(&paper).await?;
+        if let (Some(index), Some(signature)) = (self.minhash.write().await.as_mut(), signature) {
+            index.insert(paper_id, signature);
+        }
 
         tracing::debug!(
             paper_id = %paper_id,
@@ -282,10 +336,47 @@ impl IngestionRepository {
         Ok(PaperUpsertResult {
             paper_id,
             was_new: true,
-            duplicate_of: None,
+            duplicate_of: near_duplicate_of,
         })
     }
 
+    /// Most similar stored paper whose abstract passes exact verification
+    /// against `text`, with its shingle Jaccard similarity.
+    async fn find_near_duplicate(
+        &self,
+        paper_repo: &PaperRepository,
+        signature: &[u32],
+        text: &str,
+    ) -> Result<Option<(Paper, f64)>> {
+        if self.minhash.read().await.is_none() {
+            let mut guard = self.minhash.write().await;
+            if guard.is_none() {
+                *guard = Some(build_minhash_index(paper_repo).await);
+            }
+        }
+        let candidates = self
+            .minhash
+            .read()
+            .await
+            .as_ref()
+            .map(|index| index.candidates(signature))
+            .unwrap_or_default();
+
+        for (id, _) in candidates.into_iter().take(MAX_VERIFIED_CANDIDATES) {
+            let Some(existing) = paper_repo.find_by_id(id).await? else {
+                continue;
+            };
+            let similarity = existing
+                .abstract_text
+                .as_deref()
+                .map_or(0.0, |stored| jaccard(text, stored));
+            if similarity >= NEAR_DUPLICATE_JACCARD {
+                return Ok(Some((existing, similarity)));
+            }
+        }
+        Ok(None)
+    }
+
     /// Fast existence check by DOI/PMID identity.
     pub async fn exists_paper_identity(&self, meta: &PaperMetadata) -> Result<bool> {
         let paper_repo = PaperRepository::new(self.db.clone());
@@ -309,6 +400,34 @@ impl IngestionRepository {
         Ok(())
     }
 
+    /// Mark a paper's parse status together with its PDF text-quality score.
+    pub async fn set_text_quality(&self, paper_id: Uuid, status: &str, score: f64) -> Result<()> {
+        let paper_repo = PaperRepository::new(self.db.clone());
+        paper_repo
+            .update_text_quality(paper_id, status, score)
+            .await?;
+        Ok(())
+    }
+
+    /// Store `value` under `key` in a paper's `raw_json` metadata, keeping
+    /// the other keys.
+    pub async fn set_paper_metadata(&self, paper_id: Uuid, key: &str, value: Value) -> Result<()> {
+        let paper_repo = PaperRepository::new(self.db.clone());
+        let Some(paper) = paper_repo.find_by_id(paper_id).await? else {
+            return Ok(());
+        };
+        let mut raw = paper
+            .raw_json
+            .as_deref()
+            .and_then(|raw| serde_json::from_str::<serde_json::Map<String, Value>>(raw).ok())
+            .unwrap_or_default();
+        raw.insert(key.to_string(), value);
+        paper_repo
+            .update_raw_json(paper_id, &Value::Object(raw).to_string())
+            .await?;
+        Ok(())
+    }
+
     /// Mark whether a paper has full-text available (PDF parsed successfully).
     pub async fn set_full_text_status(&self, paper_id: Uuid, has_full_text: bool) -> Result<()> {
         // In LanceDB, we store full_text directly, so this is just a status update
@@ -324,7 +443,10 @@ impl IngestionRepository {
         let chunk_repo = ChunkRepository::new(self.db.clone());
 
         let new_chunk = Chunk {
-            id: Uuid::new_v4(),
+            id: repro::new_id(
+                "chunk",
+                &format!("{}|{}", chunk.paper_id, chunk.chunk_index),
+            ),
             paper_id: chunk.paper_id,
             chunk_index: chunk.chunk_index as i64,
             token_count: chunk.token_count as i32,
@@ -333,7 +455,9 @@ impl IngestionRepository {
             embedding_large: None,
             section: chunk.section_heading.clone(),
             page: chunk.page_number.map(|p| p as i64),
-            created_at: chrono::Utc::now(),
+            created_at: repro::now(),
+            char_start: Some(chunk.char_start as i64),
+            char_end: Some(chunk.char_end as i64),
         };
 
         let id = new_chunk.id;
@@ -353,7 +477,10 @@ impl IngestionRepository {
         let new_chunks: Vec<Chunk> = chunks
             .iter()
             .map(|chunk| Chunk {
-                id: Uuid::new_v4(),
+                id: repro::new_id(
+                    "chunk",
+                    &format!("{}|{}", chunk.paper_id, chunk.chunk_index),
+                ),
                 paper_id: chunk.paper_id,
                 chunk_index: chunk.chunk_index as i64,
                 token_count: chunk.token_count as i32,
@@ -362,7 +489,9 @@ impl IngestionRepository {
                 embedding_large: None,
                 section: chunk.section_heading.clone(),
                 page: chunk.page_number.map(|p| p as i64),
-                created_at: chrono::Utc::now(),
+                created_at: repro::now(),
+                char_start: Some(chunk.char_start as i64),
+                char_end: Some(chunk.char_end as i64),
             })
             .collect();
 
@@ -417,19 +546,18 @@ impl IngestionRepository {
         );
         fact.confidence = confidence;
 
-        fact_repo.insert(&fact).await?;
+        fact_repo.upsert(&fact).await?;
         Ok(fact.id)
     }
 
-    /// Bulk insert facts.
+    /// Bulk insert facts, merging each into the stored row of its triple.
+    /// Returns the number of triples written.
     pub async fn bulk_insert_facts(&self, facts: &[ferrumyx_db::schema::KgFact]) -> Result<usize> {
         if facts.is_empty() {
             return Ok(0);
         }
         let fact_repo = ferrumyx_db::kg_facts::KgFactRepository::new(self.db.clone());
-        let count = facts.len();
-        fact_repo.insert_batch(facts).await?;
-        Ok(count)
+        Ok(fact_repo.upsert_batch(facts).await?)
     }
 
     // ── Embedding operations ───────────────────────────────────────────────────
@@ -476,6 +604,55 @@ impl IngestionRepository {
             .collect())
     }
 
+    /// Up to `limit` chunks across all papers that have no embedding and
+    /// were stored before `created_before`, as `(chunk_id, content)`.
+    pub async fn find_chunks_missing_embeddings(
+        &self,
+        created_before: chrono::DateTime<chrono::Utc>,
+        limit: usize,
+    ) -> Result<Vec<(Uuid, String)>> {
+        let table = self
+            .db
+            .connection()
+            .open_table(TABLE_CHUNKS)
+            .execute()
+            .await?;
+        let mut stream = table
+            .query()
+            .only_if(missing_embedding_filter(created_before))
+            .limit(limit)
+            .execute()
+            .await?;
+        let mut pending = Vec::new();
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            for i in 0..batch.num_rows() {
+                let chunk = record_to_chunk(&batch, i)?;
+                pending.push((chunk.id, chunk.content));
+            }
+        }
+        Ok(pending)
+    }
+
+    /// Number of chunks without an embedding, optionally only those stored
+    /// before `created_before`.
+    pub async fn count_chunks_missing_embeddings(
+        &self,
+        created_before: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<u64> {
+        let table = self
+            .db
+            .connection()
+            .open_table(TABLE_CHUNKS)
+            .execute()
+            .await?;
+        let filter = match created_before {
+            Some(cutoff) => missing_embedding_filter(cutoff),
+            None => "embedding IS NULL".to_string(),
+        };
+        Ok(table.count_rows(Some(filter)).await? as u64)
+    }
+
     /// Find paper IDs that still have chunks without embeddings, using a bounded
     /// paper scan as a fallback for manual backfill jobs.
     pub async fn pending_embedding_paper_ids(&self, scan_limit: usize) -> Result<Vec<Uuid>> {
@@ -565,7 +742,7 @@ impl IngestionRepository {
             "detail": detail,
         });
         if let Err(err) = self
-            .write_ingestion_audit(action, payload.to_string(), Some(paper_id))
+            .write_ingestion_audit(action, payload.to_string(), None, Some(paper_id))
             .await
         {
             tracing::warn!(
@@ -583,10 +760,26 @@ impl IngestionRepository {
         );
     }
 
+    /// Record the reproducibility seed and config hash a job ran with.
+    pub async fn record_run_config(
+        &self,
+        job_id: Uuid,
+        repro_seed: Option<u64>,
+        config_hash: &str,
+    ) -> Result<()> {
+        let payload = json!({
+            "repro_seed": repro_seed,
+            "config_hash": config_hash,
+        });
+        self.write_ingestion_audit("run_started", payload.to_string(), Some(job_id), None)
+            .await
+    }
+
     async fn write_ingestion_audit(
         &self,
         action: &str,
         detail: String,
+        job_id: Option<Uuid>,
         paper_id: Option<Uuid>,
     ) -> Result<()> {
         let table = self
@@ -605,12 +798,20 @@ impl IngestionRepository {
             Field::new("created_at", DataType::Utf8, false),
         ]));
 
-        let now = chrono::Utc::now().to_rfc3339();
+        let now = repro::now().to_rfc3339();
+        let id = repro::new_id(
+            "ingestion_audit",
+            &format!(
+                "{}|{}|{action}|{detail}",
+                job_id.map(|v| v.to_string()).unwrap_or_default(),
+                paper_id.map(|v| v.to_string()).unwrap_or_default()
+            ),
+        );
         let batch = RecordBatch::try_new(
             schema.clone(),
             vec![
-                Arc::new(StringArray::from(vec![Uuid::new_v4().to_string()])),
-                Arc::new(StringArray::from(vec![Option::<String>::None])),
+                Arc::new(StringArray::from(vec![id.to_string()])),
+                Arc::new(StringArray::from(vec![job_id.map(|v| v.to_string())])),
                 Arc::new(StringArray::from(vec![paper_id.map(|v| v.to_string())])),
                 Arc::new(StringArray::from(vec![action.to_string()])),
                 Arc::new(StringArray::from(vec![detail])),
@@ -653,6 +854,42 @@ fn paper_to_metadata(paper: &Paper) -> PaperMetadata {
         source: IngestionSource::PubMed,
         open_access: paper.open_access,
         full_text_url: None,
+        is_review: paper.is_review,
+        preprint: None,
+    }
+}
+
+/// Index the stored signatures, computing them from the abstract for rows
+/// stored before signatures were recorded.
+async fn build_minhash_index(paper_repo: &PaperRepository) -> MinHashIndex {
+    let mut index = MinHashIndex::new();
+    let rows = paper_repo.list_signatures().await.unwrap_or_default();
+    for row in rows {
+        let signature = row
+            .minhash_signature
+            .or_else(|| row.abstract_text.as_deref().and_then(minhash_signature));
+        if let Some(signature) = signature {
+            index.insert(row.id, signature);
+        }
+    }
+    tracing::debug!(papers = index.len(), "Built MinHash near-duplicate index");
+    index
+}
+
+fn is_preprint_source(source: &str) -> bool {
+    matches!(source, "biorxiv" | "medrxiv" | "arxiv")
+}
+
+/// Whether `meta` and `existing` come from the same venue: both preprints
+/// or both journal articles, and the same journal when both name one.
+fn same_venue(meta: &PaperMetadata, existing: &Paper) -> bool {
+    let incoming_preprint = meta.preprint.is_some() || is_preprint_source(meta.source.as_str());
+    if incoming_preprint != is_preprint_source(&existing.source) {
+        return false;
+    }
+    match (meta.journal.as_deref(), existing.journal.as_deref()) {
+        (Some(a), Some(b)) => a.trim().eq_ignore_ascii_case(b.trim()),
+        _ => true,
     }
 }
 
@@ -717,3 +954,11 @@ fn truncate_audit_detail(value: &str, max_chars: usize) -> String {
 fn round_similarity(value: f64) -> f64 {
     (value * 10_000.0).round() / 10_000.0
 }
+
+/// `created_at` is stored as RFC 3339 UTC text, so it orders as a string.
+fn missing_embedding_filter(created_before: chrono::DateTime<chrono::Utc>) -> String {
+    format!(
+        "embedding IS NULL AND created_at < '{}'",
+        created_before.to_rfc3339()
+    )
+}
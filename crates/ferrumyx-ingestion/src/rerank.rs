@@ -0,0 +1,469 @@
+//! Optional second-stage re-ranking of search hits.
+//!
+//! The top retrieval candidates are re-scored as (query, passage) pairs,
+//! either by a Candle MiniLM cross-encoder or by prompting the local Ollama
+//! model for a relevance grade, and reordered before truncating to top-k.
+//! Re-ranking is best effort: if the scorer fails or misses its deadline
+//! the retrieval order is returned unchanged.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use reqwest::Client;
+use serde::Serialize;
+use tokio::sync::OnceCell;
+use tracing::warn;
+
+use crate::embed::cross_encoder::{CrossEncoder, DEFAULT_CROSS_ENCODER_MODEL};
+use crate::embedding::{resolve_embed_cache_dir, resolve_embed_offline, SearchResult};
+
+/// Which scorer re-orders search candidates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RerankerKind {
+    #[default]
+    None,
+    CrossEncoder,
+    Llm,
+}
+
+impl RerankerKind {
+    pub const ENV_VAR: &'static str = "FERRUMYX_SEARCH_RERANKER";
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "" | "none" | "off" => Some(Self::None),
+            "cross_encoder" | "cross-encoder" => Some(Self::CrossEncoder),
+            "llm" => Some(Self::Llm),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::CrossEncoder => "cross_encoder",
+            Self::Llm => "llm",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RerankConfig {
+    pub kind: RerankerKind,
+    /// Leading hits handed to the scorer; hits past this keep their order.
+    pub candidates: usize,
+    /// Deadline for scoring, after which the retrieval order is kept.
+    pub timeout: Duration,
+    pub cross_encoder_model: String,
+    pub ollama_base_url: String,
+    pub ollama_model: String,
+}
+
+impl Default for RerankConfig {
+    fn default() -> Self {
+        Self {
+            kind: RerankerKind::None,
+            candidates: 50,
+            timeout: Duration::from_millis(2000),
+            cross_encoder_model: DEFAULT_CROSS_ENCODER_MODEL.to_string(),
+            ollama_base_url: "http://localhost:11434".to_string(),
+            ollama_model: "llama3.1:8b".to_string(),
+        }
+    }
+}
+
+impl RerankConfig {
+    /// Read `FERRUMYX_SEARCH_RERANKER`, `FERRUMYX_RERANK_*` and the
+    /// `OLLAMA_*` backend settings, keeping defaults for anything unset.
+    pub fn from_env() -> Self {
+        let mut cfg = Self::default();
+        if let Ok(raw) = std::env::var(RerankerKind::ENV_VAR) {
+            match RerankerKind::parse(&raw) {
+                Some(kind) => cfg.kind = kind,
+                None => warn!(
+                    env_var = RerankerKind::ENV_VAR,
+                    value = %raw,
+                    "Unknown reranker; expected none, cross_encoder or llm. Re-ranking disabled"
+                ),
+            }
+        }
+        if let Some(n) = env_parse::<usize>("FERRUMYX_RERANK_CANDIDATES") {
+            cfg.candidates = n.clamp(1, 200);
+        }
+        if let Some(ms) = env_parse::<u64>("FERRUMYX_RERANK_TIMEOUT_MS") {
+            cfg.timeout = Duration::from_millis(ms.max(1));
+        }
+        if let Some(model) = env_string("FERRUMYX_RERANK_MODEL") {
+            cfg.cross_encoder_model = model;
+        }
+        if let Some(url) = env_string("OLLAMA_BASE_URL") {
+            cfg.ollama_base_url = url;
+        }
+        if let Some(model) = env_string("OLLAMA_MODEL") {
+            cfg.ollama_model = model;
+        }
+        cfg
+    }
+}
+
+fn env_string(key: &str) -> Option<String> {
+    std::env::var(key)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+fn env_parse<T: std::str::FromStr>(key: &str) -> Option<T> {
+    env_string(key).and_then(|v| v.parse().ok())
+}
+
+/// Scores passages against a query; higher is more relevant.
+#[async_trait]
+pub trait RelevanceScorer: Send + Sync {
+    /// One score per passage, in input order.
+    async fn score(&self, query: &str, passages: &[String]) -> Result<Vec<f32>>;
+}
+
+/// What the re-ranking stage did, for response metadata.
+#[derive(Debug, Clone, Serialize)]
+pub struct RerankReport {
+    pub reranker: RerankerKind,
+    pub applied: bool,
+    pub candidates: usize,
+    pub latency_ms: u64,
+    /// Why the retrieval order was kept, when a configured scorer did not apply.
+    pub fallback: Option<String>,
+}
+
+impl RerankReport {
+    pub fn skipped(kind: RerankerKind) -> Self {
+        Self {
+            reranker: kind,
+            applied: false,
+            candidates: 0,
+            latency_ms: 0,
+            fallback: None,
+        }
+    }
+}
+
+/// Re-rank `results` with the scorer selected by `cfg`, returning at most
+/// `top_k` hits.
+pub async fn rerank_results(
+    query: &str,
+    results: Vec<SearchResult>,
+    top_k: usize,
+    cfg: &RerankConfig,
+) -> (Vec<SearchResult>, RerankReport) {
+    match cfg.kind {
+        RerankerKind::None => {
+            let mut results = results;
+            results.truncate(top_k);
+            (results, RerankReport::skipped(RerankerKind::None))
+        }
+        RerankerKind::CrossEncoder => {
+            let scorer = CrossEncoderScorer::shared(&cfg.cross_encoder_model);
+            rerank_with(query, results, top_k, scorer.as_ref(), cfg).await
+        }
+        RerankerKind::Llm => {
+            let scorer = OllamaRelevanceScorer::new(&cfg.ollama_base_url, &cfg.ollama_model);
+            rerank_with(query, results, top_k, &scorer, cfg).await
+        }
+    }
+}
+
+/// Re-rank the first `cfg.candidates` hits with `scorer` under `cfg.timeout`.
+pub async fn rerank_with(
+    query: &str,
+    mut results: Vec<SearchResult>,
+    top_k: usize,
+    scorer: &dyn RelevanceScorer,
+    cfg: &RerankConfig,
+) -> (Vec<SearchResult>, RerankReport) {
+    let start = Instant::now();
+    let n = cfg.candidates.min(results.len());
+    let mut report = RerankReport {
+        candidates: n,
+        ..RerankReport::skipped(cfg.kind)
+    };
+    if n == 0 {
+        results.truncate(top_k);
+        return (results, report);
+    }
+
+    let passages: Vec<String> = results[..n].iter().map(|r| r.content.clone()).collect();
+    let outcome = tokio::time::timeout(cfg.timeout, scorer.score(query, &passages)).await;
+    report.latency_ms = start.elapsed().as_millis() as u64;
+    let scores = match outcome {
+        Ok(Ok(scores)) if scores.len() == n => scores,
+        Ok(Ok(scores)) => {
+            report.fallback = Some(format!("scorer returned {} scores for {n}", scores.len()));
+            Vec::new()
+        }
+        Ok(Err(e)) => {
+            report.fallback = Some(format!("scorer failed: {e:#}"));
+            Vec::new()
+        }
+        Err(_) => {
+            report.fallback = Some(format!("timed out after {}ms", cfg.timeout.as_millis()));
+            Vec::new()
+        }
+    };
+    if let Some(reason) = &report.fallback {
+        warn!(
+            reranker = cfg.kind.as_str(),
+            "Keeping retrieval order: {reason}"
+        );
+        results.truncate(top_k);
+        return (results, report);
+    }
+
+    let rest = results.split_off(n);
+    let mut head: Vec<(usize, SearchResult)> = results
+        .into_iter()
+        .zip(scores)
+        .map(|(mut r, s)| {
+            r.rerank_score = Some(s);
+            r
+        })
+        .enumerate()
+        .collect();
+    // Ties keep retrieval order.
+    head.sort_by(|(ia, a), (ib, b)| {
+        b.rerank_score
+            .partial_cmp(&a.rerank_score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| ia.cmp(ib))
+    });
+
+    let mut results: Vec<SearchResult> = head.into_iter().map(|(_, r)| r).collect();
+    results.extend(rest);
+    results.truncate(top_k);
+    report.applied = true;
+    (results, report)
+}
+
+// ── Cross-encoder ─────────────────────────────────────────────────────────────
+
+/// Cross-encoder scorer; the model loads on first use and is reused.
+pub struct CrossEncoderScorer {
+    model_id: String,
+    model: OnceCell<Arc<CrossEncoder>>,
+}
+
+impl CrossEncoderScorer {
+    pub fn new(model_id: &str) -> Self {
+        Self {
+            model_id: model_id.to_string(),
+            model: OnceCell::new(),
+        }
+    }
+
+    /// Process-wide scorer, so the model is loaded once per model id.
+    fn shared(model_id: &str) -> Arc<Self> {
+        static SCORERS: std::sync::OnceLock<std::sync::Mutex<Vec<Arc<CrossEncoderScorer>>>> =
+            std::sync::OnceLock::new();
+        let mut scorers = SCORERS
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(scorer) = scorers.iter().find(|s| s.model_id == model_id) {
+            return scorer.clone();
+        }
+        let scorer = Arc::new(Self::new(model_id));
+        scorers.push(scorer.clone());
+        scorer
+    }
+}
+
+#[async_trait]
+impl RelevanceScorer for CrossEncoderScorer {
+    async fn score(&self, query: &str, passages: &[String]) -> Result<Vec<f32>> {
+        let model = self
+            .model
+            .get_or_try_init(|| async {
+                CrossEncoder::new(
+                    &self.model_id,
+                    resolve_embed_cache_dir(),
+                    resolve_embed_offline(),
+                    512,
+                )
+                .await
+                .map(Arc::new)
+            })
+            .await
+            .with_context(|| format!("Failed to load cross-encoder {}", self.model_id))?
+            .clone();
+        // Inference is CPU-bound; keep it off the runtime so the rerank
+        // deadline can still fire.
+        let (query, passages) = (query.to_string(), passages.to_vec());
+        tokio::task::spawn_blocking(move || model.score(&query, &passages))
+            .await?
+            .map_err(|e| anyhow!("Cross-encoder scoring failed: {e}"))
+    }
+}
+
+// ── LLM (Ollama) ──────────────────────────────────────────────────────────────
+
+const LLM_PASSAGE_CHARS: usize = 2000;
+const LLM_CONCURRENCY: usize = 4;
+
+/// Grades each passage 0–10 by prompting the local Ollama model.
+pub struct OllamaRelevanceScorer {
+    client: Client,
+    base_url: String,
+    model: String,
+}
+
+impl OllamaRelevanceScorer {
+    pub fn new(base_url: &str, model: &str) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+        }
+    }
+
+    async fn grade(&self, query: &str, passage: &str) -> Result<f32> {
+        let passage: String = passage.chars().take(LLM_PASSAGE_CHARS).collect();
+        let prompt = format!(
+            "Rate how well the passage answers the query on a scale from 0 \
+             (irrelevant) to 10 (directly answers it). Reply with the number only.\n\n\
+             Query: {query}\n\nPassage: {passage}\n\nScore:"
+        );
+        let body = serde_json::json!({
+            "model": &self.model,
+            "prompt": prompt,
+            "stream": false,
+            "options": {"temperature": 0},
+        });
+        let resp: serde_json::Value = self
+            .client
+            .post(format!("{}/api/generate", self.base_url))
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let reply = resp["response"].as_str().unwrap_or_default();
+        parse_relevance_grade(reply)
+            .ok_or_else(|| anyhow!("Ollama reply had no relevance score: {reply:?}"))
+    }
+}
+
+#[async_trait]
+impl RelevanceScorer for OllamaRelevanceScorer {
+    async fn score(&self, query: &str, passages: &[String]) -> Result<Vec<f32>> {
+        stream::iter(passages)
+            .map(|p| self.grade(query, p))
+            .buffered(LLM_CONCURRENCY)
+            .try_collect()
+            .await
+    }
+}
+
+/// First number in an LLM reply, clamped to the 0–10 grading scale.
+fn parse_relevance_grade(reply: &str) -> Option<f32> {
+    let start = reply.find(|c: char| c.is_ascii_digit())?;
+    let number: String = reply[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    number
+        .trim_end_matches('.')
+        .parse::<f32>()
+        .ok()
+        .map(|v| v.clamp(0.0, 10.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn hit(content: &str) -> SearchResult {
+        SearchResult {
+            chunk_id: Uuid::new_v4(),
+            paper_id: Uuid::new_v4(),
+            content: content.to_string(),
+            score: 1.0,
+            fts_rank: None,
+            vector_rank: None,
+            fts_score: None,
+            vector_score: None,
+            rerank_score: None,
+        }
+    }
+
+    struct FixedScorer(Vec<f32>);
+
+    #[async_trait]
+    impl RelevanceScorer for FixedScorer {
+        async fn score(&self, _query: &str, _passages: &[String]) -> Result<Vec<f32>> {
+            Ok(self.0.clone())
+        }
+    }
+
+    #[test]
+    fn parses_reranker_kinds() {
+        assert_eq!(RerankerKind::parse(""), Some(RerankerKind::None));
+        assert_eq!(
+            RerankerKind::parse("Cross-Encoder"),
+            Some(RerankerKind::CrossEncoder)
+        );
+        assert_eq!(RerankerKind::parse(" llm "), Some(RerankerKind::Llm));
+        assert_eq!(RerankerKind::parse("bm25"), None);
+    }
+
+    #[test]
+    fn parses_llm_relevance_grades() {
+        assert_eq!(parse_relevance_grade("8"), Some(8.0));
+        assert_eq!(parse_relevance_grade("Score: 7.5/10"), Some(7.5));
+        assert_eq!(parse_relevance_grade("10."), Some(10.0));
+        assert_eq!(parse_relevance_grade("42"), Some(10.0));
+        assert_eq!(parse_relevance_grade("not relevant"), None);
+    }
+
+    #[tokio::test]
+    async fn reorders_only_the_candidate_window() {
+        let results = vec![hit("a"), hit("b"), hit("c"), hit("d")];
+        let ids: Vec<Uuid> = results.iter().map(|r| r.chunk_id).collect();
+        let cfg = RerankConfig {
+            kind: RerankerKind::Llm,
+            candidates: 3,
+            ..RerankConfig::default()
+        };
+
+        let scorer = FixedScorer(vec![1.0, 5.0, 5.0]);
+        let (rows, report) = rerank_with("q", results, 3, &scorer, &cfg).await;
+
+        assert!(report.applied);
+        assert_eq!(report.candidates, 3);
+        let order: Vec<Uuid> = rows.iter().map(|r| r.chunk_id).collect();
+        assert_eq!(order, vec![ids[1], ids[2], ids[0]]);
+        assert_eq!(rows[0].rerank_score, Some(5.0));
+    }
+
+    #[tokio::test]
+    async fn keeps_retrieval_order_when_scores_do_not_line_up() {
+        let results = vec![hit("a"), hit("b")];
+        let ids: Vec<Uuid> = results.iter().map(|r| r.chunk_id).collect();
+        let cfg = RerankConfig {
+            kind: RerankerKind::Llm,
+            ..RerankConfig::default()
+        };
+
+        let scorer = FixedScorer(vec![9.0]);
+        let (rows, report) = rerank_with("q", results, 10, &scorer, &cfg).await;
+
+        assert!(!report.applied);
+        assert!(report.fallback.is_some());
+        assert_eq!(rows.iter().map(|r| r.chunk_id).collect::<Vec<_>>(), ids);
+        assert!(rows.iter().all(|r| r.rerank_score.is_none()));
+    }
+}
@@ -0,0 +1,614 @@
+//! Scheduled recurring ingestion jobs.
+//!
+//! Schedules come from `[[ingestion.schedules]]` in ferrumyx.toml; each one
+//! fires on a fixed interval or a cron expression. Every run is recorded in
+//! the `ingestion_job_runs` table, so "when did this last run" survives a
+//! restart, and a schedule never has two runs in flight at once.
+//! See ARCHITECTURE.md §2.2.
+
+use crate::pipeline::{run_ingestion, IngestionJob, IngestionResult, IngestionSourceSpec};
+use crate::repository::IngestionRepository;
+use anyhow::bail;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use ferrumyx_db::{Database, IngestionJobRun, IngestionJobRunRepository};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// How often the scheduler checks for due schedules.
+pub const SCHEDULER_TICK: Duration = Duration::from_secs(30);
+
+/// Shortest interval a schedule may use.
+pub const MIN_SCHEDULE_INTERVAL_SECS: u64 = 60;
+
+/// One `[[ingestion.schedules]]` entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IngestionSchedule {
+    /// Stable name; run history is keyed by it.
+    pub id: String,
+    pub gene: String,
+    #[serde(default)]
+    pub mutation: Option<String>,
+    #[serde(alias = "cancer")]
+    pub cancer_type: String,
+    #[serde(default = "default_schedule_sources")]
+    pub sources: Vec<IngestionSourceSpec>,
+    /// Run every this many seconds. Exactly one of `interval_secs` and
+    /// `cron` must be set.
+    #[serde(default)]
+    pub interval_secs: Option<u64>,
+    /// Cron expression in UTC, either five fields (minute first) or six
+    /// (second first).
+    #[serde(default)]
+    pub cron: Option<String>,
+    #[serde(default = "default_schedule_max_results")]
+    pub max_results: usize,
+    /// Only fetch papers newer than the previous run (see
+    /// [`IngestionJob::incremental`]).
+    #[serde(default = "default_true")]
+    pub incremental: bool,
+}
+
+fn default_schedule_sources() -> Vec<IngestionSourceSpec> {
+    vec![IngestionSourceSpec::PubMed, IngestionSourceSpec::EuropePmc]
+}
+fn default_schedule_max_results() -> usize {
+    100
+}
+fn default_true() -> bool {
+    true
+}
+
+impl IngestionSchedule {
+    /// Check that the schedule names exactly one valid timing rule.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.id.trim().is_empty() {
+            bail!("schedule id must not be empty");
+        }
+        if self.gene.trim().is_empty() {
+            bail!("schedule '{}' has no gene", self.id);
+        }
+        match (self.interval_secs, &self.cron) {
+            (Some(_), Some(_)) => bail!("schedule '{}' sets both interval_secs and cron", self.id),
+            (None, None) => bail!("schedule '{}' needs one of interval_secs or cron", self.id),
+            (Some(secs), None) if secs < MIN_SCHEDULE_INTERVAL_SECS => bail!(
+                "schedule '{}' interval_secs must be at least {}",
+                self.id,
+                MIN_SCHEDULE_INTERVAL_SECS
+            ),
+            (None, Some(expr)) => {
+                parse_cron(expr).map_err(|e| {
+                    anyhow::anyhow!("schedule '{}' has an invalid cron: {}", self.id, e)
+                })?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// When the schedule is next due, given when its last run started and
+    /// when the scheduler began watching it.
+    ///
+    /// A never-run interval schedule is due at `watching_since`; a never-run
+    /// cron schedule at its first fire time after it. A cron schedule that
+    /// missed fire times while the process was down is due at the first one
+    /// it missed, so it catches up once rather than once per missed slot.
+    pub fn next_run_after(
+        &self,
+        last_started: Option<DateTime<Utc>>,
+        watching_since: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        if let Some(secs) = self.interval_secs {
+            return Some(match last_started {
+                Some(last) => last + chrono::Duration::seconds(secs as i64),
+                None => watching_since,
+            });
+        }
+        let schedule = parse_cron(self.cron.as_deref()?).ok()?;
+        let after = last_started.unwrap_or(watching_since);
+        schedule.after(&after).next()
+    }
+
+    /// The pipeline job for one run, with settings the schedule does not
+    /// name (API keys, timeouts, caching) taken from `template`.
+    pub fn to_job(&self, template: &IngestionJob) -> IngestionJob {
+        IngestionJob {
+            gene: self.gene.clone(),
+            mutation: self.mutation.clone().filter(|m| !m.trim().is_empty()),
+            cancer_type: self.cancer_type.clone(),
+            max_results: self.max_results,
+            sources: self.sources.clone(),
+            since: None,
+            incremental: self.incremental,
+            ..template.clone()
+        }
+    }
+}
+
+/// Parse a cron expression, accepting the common five-field form by
+/// pinning the seconds field to zero.
+fn parse_cron(expr: &str) -> Result<cron::Schedule, cron::error::Error> {
+    let expr = expr.trim();
+    if expr.split_whitespace().count() == 5 {
+        cron::Schedule::from_str(&format!("0 {expr}"))
+    } else {
+        cron::Schedule::from_str(expr)
+    }
+}
+
+/// What started a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunTrigger {
+    /// The schedule came due.
+    Schedule,
+    /// Someone asked for it through `run-now`.
+    Manual,
+}
+
+impl RunTrigger {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Schedule => "schedule",
+            Self::Manual => "manual",
+        }
+    }
+}
+
+/// Outcome of asking a schedule to run.
+#[derive(Debug)]
+pub enum RunStart {
+    /// The run was recorded and spawned; `handle` completes once its
+    /// history row has been finalised.
+    Started {
+        run: IngestionJobRun,
+        handle: JoinHandle<()>,
+    },
+    /// A run of the schedule is still in flight.
+    AlreadyRunning,
+    UnknownSchedule,
+}
+
+/// Runs the pipeline job of a scheduled run.
+#[async_trait]
+pub trait ScheduledJobRunner: Send + Sync {
+    async fn run(&self, job: IngestionJob) -> IngestionResult;
+}
+
+/// [`ScheduledJobRunner`] over [`run_ingestion`].
+pub struct PipelineRunner {
+    repo: Arc<IngestionRepository>,
+}
+
+impl PipelineRunner {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self {
+            repo: Arc::new(IngestionRepository::new(db)),
+        }
+    }
+}
+
+#[async_trait]
+impl ScheduledJobRunner for PipelineRunner {
+    async fn run(&self, job: IngestionJob) -> IngestionResult {
+        run_ingestion(job, self.repo.clone(), None).await
+    }
+}
+
+/// A schedule with its last run and next due time.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduleStatus {
+    pub schedule: IngestionSchedule,
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub running: bool,
+    pub last_run: Option<IngestionJobRun>,
+}
+
+/// Fires configured ingestion schedules and records their runs.
+pub struct IngestionScheduler {
+    schedules: Vec<IngestionSchedule>,
+    template: IngestionJob,
+    runs: IngestionJobRunRepository,
+    runner: Arc<dyn ScheduledJobRunner>,
+    /// Ids of schedules with a run in flight.
+    running: Mutex<HashSet<String>>,
+    /// When this scheduler was built; never-run schedules count from here.
+    watching_since: DateTime<Utc>,
+}
+
+impl IngestionScheduler {
+    /// Build a scheduler over `schedules`, dropping (with a warning) any
+    /// that fail [`IngestionSchedule::validate`] or repeat an earlier id.
+    pub fn new(
+        schedules: Vec<IngestionSchedule>,
+        template: IngestionJob,
+        db: Arc<Database>,
+        runner: Arc<dyn ScheduledJobRunner>,
+    ) -> Self {
+        let mut seen = HashSet::new();
+        let schedules = schedules
+            .into_iter()
+            .filter(|s| match s.validate() {
+                Err(e) => {
+                    warn!("Ignoring ingestion schedule: {}", e);
+                    false
+                }
+                Ok(()) if !seen.insert(s.id.clone()) => {
+                    warn!("Ignoring duplicate ingestion schedule '{}'", s.id);
+                    false
+                }
+                Ok(()) => true,
+            })
+            .collect();
+        Self {
+            schedules,
+            template,
+            runs: IngestionJobRunRepository::new(db),
+            runner,
+            running: Mutex::default(),
+            watching_since: Utc::now(),
+        }
+    }
+
+    /// [`Self::new`] running jobs through the ingestion pipeline.
+    pub fn for_pipeline(
+        schedules: Vec<IngestionSchedule>,
+        template: IngestionJob,
+        db: Arc<Database>,
+    ) -> Self {
+        Self::new(
+            schedules,
+            template,
+            db.clone(),
+            Arc::new(PipelineRunner::new(db)),
+        )
+    }
+
+    pub fn schedules(&self) -> &[IngestionSchedule] {
+        &self.schedules
+    }
+
+    pub fn is_running(&self, id: &str) -> bool {
+        self.running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(id)
+    }
+
+    /// Every schedule with its last recorded run and next due time.
+    pub async fn statuses(&self) -> anyhow::Result<Vec<ScheduleStatus>> {
+        let mut out = Vec::with_capacity(self.schedules.len());
+        for schedule in &self.schedules {
+            let last_run = self.runs.latest(&schedule.id).await?;
+            out.push(ScheduleStatus {
+                next_run_at: schedule
+                    .next_run_after(last_run.as_ref().map(|r| r.started_at), self.watching_since),
+                running: self.is_running(&schedule.id),
+                schedule: schedule.clone(),
+                last_run,
+            });
+        }
+        Ok(out)
+    }
+
+    /// Start a run of schedule `id` unless one is already in flight.
+    pub async fn run_now(
+        self: &Arc<Self>,
+        id: &str,
+        trigger: RunTrigger,
+    ) -> anyhow::Result<RunStart> {
+        let Some(schedule) = self.schedules.iter().find(|s| s.id == id).cloned() else {
+            return Ok(RunStart::UnknownSchedule);
+        };
+        if !self
+            .running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(schedule.id.clone())
+        {
+            return Ok(RunStart::AlreadyRunning);
+        }
+        let guard = RunningGuard {
+            scheduler: self.clone(),
+            id: schedule.id.clone(),
+        };
+
+        let run = IngestionJobRun::started(
+            schedule.id.clone(),
+            trigger.as_str().to_string(),
+            Utc::now(),
+        );
+        self.runs.upsert(&run).await?;
+        info!(
+            schedule = %schedule.id,
+            trigger = trigger.as_str(),
+            "Starting scheduled ingestion run"
+        );
+
+        let job = schedule.to_job(&self.template);
+        let scheduler = self.clone();
+        let mut finished = run.clone();
+        let handle = tokio::spawn(async move {
+            let _guard = guard;
+            let result = scheduler.runner.run(job).await;
+            finish_run(&mut finished, &result, Utc::now());
+            if let Err(e) = scheduler.runs.upsert(&finished).await {
+                warn!(
+                    "Failed to record ingestion run {} of '{}': {}",
+                    finished.id, finished.schedule_id, e
+                );
+            }
+        });
+        Ok(RunStart::Started { run, handle })
+    }
+
+    /// Start every schedule due at `now` that is not already running.
+    async fn run_due(self: &Arc<Self>, now: DateTime<Utc>) -> Vec<JoinHandle<()>> {
+        let mut handles = Vec::new();
+        for schedule in &self.schedules {
+            if self.is_running(&schedule.id) {
+                continue;
+            }
+            let last_started = match self.runs.latest(&schedule.id).await {
+                Ok(run) => run.map(|r| r.started_at),
+                Err(e) => {
+                    warn!("Failed to load runs of schedule '{}': {}", schedule.id, e);
+                    continue;
+                }
+            };
+            if schedule
+                .next_run_after(last_started, self.watching_since)
+                .is_none_or(|due| due > now)
+            {
+                continue;
+            }
+            match self.run_now(&schedule.id, RunTrigger::Schedule).await {
+                Ok(RunStart::Started { handle, .. }) => handles.push(handle),
+                Ok(_) => {}
+                Err(e) => warn!("Failed to start schedule '{}': {}", schedule.id, e),
+            }
+        }
+        handles
+    }
+
+    /// Mark runs a previous process left unfinished as interrupted, then
+    /// check for due schedules every [`SCHEDULER_TICK`].
+    pub async fn start(self: Arc<Self>) -> JoinHandle<()> {
+        match self.runs.mark_interrupted().await {
+            Ok(0) => {}
+            Ok(n) => warn!("Marked {} unfinished ingestion runs as interrupted", n),
+            Err(e) => warn!("Failed to mark unfinished ingestion runs: {}", e),
+        }
+        info!(
+            schedules = self.schedules.len(),
+            "Started ingestion scheduler"
+        );
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(SCHEDULER_TICK);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                self.run_due(Utc::now()).await;
+            }
+        })
+    }
+}
+
+/// Clears a schedule's in-flight flag when its run ends, even by panic.
+struct RunningGuard {
+    scheduler: Arc<IngestionScheduler>,
+    id: String,
+}
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        self.scheduler
+            .running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.id);
+    }
+}
+
+/// Copy the outcome of `result` into `run`.
+fn finish_run(run: &mut IngestionJobRun, result: &IngestionResult, finished_at: DateTime<Utc>) {
+    run.status = result.status().to_string();
+    run.finished_at = Some(finished_at);
+    run.duration_ms = (finished_at - run.started_at).num_milliseconds().max(0);
+    run.papers_found = result.papers_found as i64;
+    run.papers_inserted = result.papers_inserted as i64;
+    run.papers_duplicate = result.papers_duplicate as i64;
+    run.chunks_inserted = result.chunks_inserted as i64;
+    run.error = result.error_summary();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use tokio::sync::Semaphore;
+
+    fn schedule(id: &str) -> IngestionSchedule {
+        IngestionSchedule {
+            id: id.to_string(),
+            gene: "KRAS".to_string(),
+            mutation: Some("G12D".to_string()),
+            cancer_type: "pancreatic cancer".to_string(),
+            sources: default_schedule_sources(),
+            interval_secs: Some(3600),
+            cron: None,
+            max_results: 50,
+            incremental: true,
+        }
+    }
+
+    /// Finds three papers, two of them new, once a permit is released.
+    struct GatedRunner {
+        gate: Semaphore,
+    }
+
+    #[async_trait]
+    impl ScheduledJobRunner for GatedRunner {
+        async fn run(&self, job: IngestionJob) -> IngestionResult {
+            self.gate.acquire().await.unwrap().forget();
+            IngestionResult {
+                job_id: uuid::Uuid::new_v4(),
+                query: job.gene,
+                papers_found_raw: 3,
+                papers_found: 3,
+                papers_inserted: 2,
+                papers_duplicate: 1,
+                inserted_paper_ids: Vec::new(),
+                chunks_inserted: 12,
+                chunks_embedded: 0,
+                source_telemetry: Vec::new(),
+                perf_telemetry: Default::default(),
+                errors: Vec::new(),
+                papers_succeeded: 2,
+                papers_failed: 0,
+                failure_samples: Vec::new(),
+                duration_ms: 0,
+                cancelled: false,
+            }
+        }
+    }
+
+    #[test]
+    fn validate_requires_exactly_one_valid_timing_rule() {
+        let both = IngestionSchedule {
+            cron: Some("0 6 * * *".to_string()),
+            ..schedule("both")
+        };
+        let neither = IngestionSchedule {
+            interval_secs: None,
+            ..schedule("neither")
+        };
+        let too_often = IngestionSchedule {
+            interval_secs: Some(5),
+            ..schedule("fast")
+        };
+        let bad_cron = IngestionSchedule {
+            interval_secs: None,
+            cron: Some("every morning".to_string()),
+            ..schedule("bad")
+        };
+        for s in [both, neither, too_often, bad_cron] {
+            assert!(s.validate().is_err(), "{} should be rejected", s.id);
+        }
+        assert!(schedule("ok").validate().is_ok());
+    }
+
+    #[test]
+    fn next_run_follows_interval_or_cron() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 4, 10, 30, 0).unwrap();
+        let hourly = schedule("hourly");
+        assert_eq!(hourly.next_run_after(None, now), Some(now));
+        let last = Utc.with_ymd_and_hms(2026, 3, 4, 10, 0, 0).unwrap();
+        assert_eq!(
+            hourly.next_run_after(Some(last), now),
+            Some(Utc.with_ymd_and_hms(2026, 3, 4, 11, 0, 0).unwrap())
+        );
+
+        let daily = IngestionSchedule {
+            interval_secs: None,
+            cron: Some("0 6 * * *".to_string()),
+            ..schedule("daily")
+        };
+        assert_eq!(
+            daily.next_run_after(None, now),
+            Some(Utc.with_ymd_and_hms(2026, 3, 5, 6, 0, 0).unwrap())
+        );
+        // Down for three days: due once, at the first missed slot.
+        let stale = Utc.with_ymd_and_hms(2026, 3, 1, 6, 0, 0).unwrap();
+        assert_eq!(
+            daily.next_run_after(Some(stale), now),
+            Some(Utc.with_ymd_and_hms(2026, 3, 2, 6, 0, 0).unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn overlapping_runs_are_refused_and_history_is_recorded() {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-scheduler-{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        let runner = Arc::new(GatedRunner {
+            gate: Semaphore::new(0),
+        });
+        let scheduler = Arc::new(IngestionScheduler::new(
+            vec![schedule("kras-pdac"), schedule("kras-pdac")],
+            IngestionJob::default(),
+            db.clone(),
+            runner.clone(),
+        ));
+        assert_eq!(scheduler.schedules().len(), 1);
+
+        let now = Utc::now();
+        let mut due = scheduler.run_due(now).await;
+        assert_eq!(due.len(), 1);
+        assert!(scheduler.is_running("kras-pdac"));
+        assert!(matches!(
+            scheduler
+                .run_now("kras-pdac", RunTrigger::Manual)
+                .await
+                .unwrap(),
+            RunStart::AlreadyRunning
+        ));
+        assert!(scheduler.run_due(now).await.is_empty());
+        assert!(matches!(
+            scheduler
+                .run_now("missing", RunTrigger::Manual)
+                .await
+                .unwrap(),
+            RunStart::UnknownSchedule
+        ));
+
+        let status = &scheduler.statuses().await.unwrap()[0];
+        assert!(status.running);
+        assert_eq!(status.last_run.as_ref().unwrap().status, "running");
+
+        runner.gate.add_permits(1);
+        due.pop().unwrap().await.unwrap();
+        assert!(!scheduler.is_running("kras-pdac"));
+
+        let runs = IngestionJobRunRepository::new(db);
+        let run = runs.latest("kras-pdac").await.unwrap().unwrap();
+        assert_eq!(run.status, "succeeded");
+        assert_eq!(run.trigger, "schedule");
+        assert_eq!(
+            (
+                run.papers_found,
+                run.papers_inserted,
+                run.papers_duplicate,
+                run.chunks_inserted
+            ),
+            (3, 2, 1, 12)
+        );
+        // Not due again until an interval after the run started.
+        assert!(scheduler.run_due(now).await.is_empty());
+        let status = &scheduler.statuses().await.unwrap()[0];
+        assert_eq!(
+            status.next_run_at,
+            Some(run.started_at + chrono::Duration::seconds(3600))
+        );
+
+        runner.gate.add_permits(1);
+        match scheduler
+            .run_now("kras-pdac", RunTrigger::Manual)
+            .await
+            .unwrap()
+        {
+            RunStart::Started { run, handle } => {
+                assert_eq!(run.trigger, "manual");
+                handle.await.unwrap();
+            }
+            other => panic!("expected a started run, got {other:?}"),
+        }
+        assert_eq!(runs.list(Some("kras-pdac"), 10).await.unwrap().len(), 2);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -171,6 +171,8 @@ fn parse_arxiv_atom(xml: &str) -> anyhow::Result<Vec<PaperMetadata>> {
                                 source: IngestionSource::Arxiv,
                                 open_access: true,
                                 full_text_url,
+                                is_review: false,
+                                preprint: None,
                             });
                         }
                     }
@@ -1,10 +1,14 @@
 //! bioRxiv / medRxiv preprint client.
 //!
 //! Uses the bioRxiv REST API:
-//!   https://api.biorxiv.org/details/biorxiv/{interval}/{cursor}
+//!   https://api.biorxiv.org/details/{server}/{start}/{end}/{cursor}/json
 //!
-//! For search-by-term we fall back to the bioRxiv search endpoint
-//! which returns article metadata matching a query string.
+//! The API has no free-text search: it lists every preprint posted in a
+//! date window, 100 records per page. Searches harvest the window, keep
+//! records in the configured subject categories and match the query terms
+//! against title and abstract locally.
+
+use std::collections::HashMap;
 
 use async_trait::async_trait;
 use chrono::NaiveDate;
@@ -12,16 +16,33 @@ use reqwest::Client;
 use tracing::{debug, instrument, warn};
 
 use super::LiteratureSource;
-use crate::models::{Author, IngestionSource, PaperMetadata};
+use crate::models::{Author, IngestionSource, PaperMetadata, PreprintInfo};
 
-const BIORXIV_SEARCH_URL: &str = "https://api.biorxiv.org/details/biorxiv";
-const MEDRXIV_SEARCH_URL: &str = "https://api.biorxiv.org/details/medrxiv";
+const BIORXIV_API_URL: &str = "https://api.biorxiv.org/details";
+/// Days back from today a search harvests by default.
+const DEFAULT_WINDOW_DAYS: i64 = 90;
+/// Pages harvested per search at most (100 records each).
+const MAX_PAGES: usize = 100;
 
 pub struct BioRxivClient {
     client: Client,
     /// "biorxiv" or "medrxiv"
     server: &'static str,
-    base: &'static str,
+    base_url: String,
+    /// Subject categories kept, compared case-insensitively with `_` read
+    /// as a space; empty keeps every category.
+    categories: Vec<String>,
+    window_days: i64,
+    /// Never harvest preprints posted before this date.
+    since: Option<NaiveDate>,
+}
+
+/// One page of the details endpoint.
+#[derive(Debug, Default)]
+struct DetailsPage {
+    records: Vec<serde_json::Value>,
+    /// Records in the whole window, when the server reports it.
+    total: Option<usize>,
 }
 
 impl BioRxivClient {
@@ -29,7 +50,10 @@ impl BioRxivClient {
         Self {
             client: Client::new(),
             server: "biorxiv",
-            base: BIORXIV_SEARCH_URL,
+            base_url: BIORXIV_API_URL.to_string(),
+            categories: vec!["cancer biology".to_string()],
+            window_days: DEFAULT_WINDOW_DAYS,
+            since: None,
         }
     }
 
@@ -37,123 +61,261 @@ impl BioRxivClient {
         Self {
             client: Client::new(),
             server: "medrxiv",
-            base: MEDRXIV_SEARCH_URL,
+            base_url: BIORXIV_API_URL.to_string(),
+            categories: vec!["oncology".to_string()],
+            window_days: DEFAULT_WINDOW_DAYS,
+            since: None,
         }
     }
 
-    /// Fetch recent preprints from a date range and filter by keyword.
-    /// bioRxiv API: /details/{server}/{interval}/{cursor}/{format}
+    /// Keep only preprints in `categories` (e.g. "cancer biology",
+    /// "genomics"); an empty list keeps all of them.
+    pub fn with_categories(mut self, categories: Vec<String>) -> Self {
+        self.categories = categories;
+        self
+    }
+
+    /// Harvest the last `days` days on each search.
+    pub fn with_window_days(mut self, days: i64) -> Self {
+        self.window_days = days.max(1);
+        self
+    }
+
+    /// Start the harvest window no earlier than `since`.
+    pub fn with_since(mut self, since: Option<NaiveDate>) -> Self {
+        self.since = since;
+        self
+    }
+
+    fn source(&self) -> IngestionSource {
+        if self.server == "biorxiv" {
+            IngestionSource::BioRxiv
+        } else {
+            IngestionSource::MedRxiv
+        }
+    }
+
+    /// Harvest `start..=end`, returning the latest version of each matching
+    /// preprint, oldest posting first.
     #[instrument(skip(self))]
-    async fn fetch_recent(
+    async fn harvest(
         &self,
-        interval: &str, // e.g. "2024-01-01/2025-01-01"
+        start: NaiveDate,
+        end: NaiveDate,
         query: &str,
         max_results: usize,
     ) -> anyhow::Result<Vec<PaperMetadata>> {
-        let url = format!("{}/{}/0/json", self.base, interval);
-        let resp = self
-            .client
-            .get(&url)
-            .send()
-            .await?
-            .json::<serde_json::Value>()
-            .await?;
-
-        let collection = resp["collection"].as_array().cloned().unwrap_or_default();
-
-        debug!(
-            server = self.server,
-            fetched = collection.len(),
-            "bioRxiv API response"
-        );
+        let terms = query_terms(query);
+        let mut papers: Vec<PaperMetadata> = Vec::new();
+        // Index into `papers` by preprint DOI; later versions replace
+        // earlier ones in place.
+        let mut by_doi: HashMap<String, usize> = HashMap::new();
+        let mut cursor = 0usize;
 
-        // Client-side keyword filter (API doesn't support free-text search natively)
-        let query_lower = query.to_lowercase();
-        let keywords: Vec<&str> = query_lower
-            .split(" AND ")
-            .map(|k| k.trim().trim_end_matches("[tiab]").trim())
-            .collect();
+        for _ in 0..MAX_PAGES {
+            let url = format!(
+                "{}/{}/{}/{}/{}/json",
+                self.base_url, self.server, start, end, cursor
+            );
+            let body: serde_json::Value = self
+                .client
+                .get(&url)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+            let page = parse_details_page(&body);
+            debug!(
+                server = self.server,
+                cursor,
+                fetched = page.records.len(),
+                total = ?page.total,
+                "bioRxiv details page"
+            );
+            if page.records.is_empty() {
+                break;
+            }
+            cursor += page.records.len();
 
-        let papers: Vec<PaperMetadata> = collection
-            .iter()
-            .filter(|item| {
-                let title = item["title"].as_str().unwrap_or("").to_lowercase();
-                let abstract_ = item["abstract"].as_str().unwrap_or("").to_lowercase();
-                keywords
-                    .iter()
-                    .any(|kw| title.contains(kw) || abstract_.contains(kw))
-            })
-            .map(|item| {
-                let source = if self.server == "biorxiv" {
-                    IngestionSource::BioRxiv
-                } else {
-                    IngestionSource::MedRxiv
+            for record in &page.records {
+                if !self.in_categories(record) || !matches_terms(record, &terms) {
+                    continue;
+                }
+                let Some(paper) = record_to_paper(record, self.server, self.source()) else {
+                    continue;
                 };
-
-                let authors: Vec<Author> = item["authors"]
-                    .as_str()
-                    .unwrap_or("")
-                    .split(';')
-                    .filter(|s| !s.trim().is_empty())
-                    .map(|name| Author {
-                        name: name.trim().to_string(),
-                        affiliation: None,
-                        orcid: None,
-                    })
-                    .collect();
-
-                let pub_date = item["date"]
-                    .as_str()
-                    .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
-
-                let doi = item["doi"].as_str().map(String::from);
-                let full_text_url = doi
-                    .as_ref()
-                    .map(|d| format!("https://www.biorxiv.org/content/{}.full.pdf", d));
-
-                PaperMetadata {
-                    doi,
-                    pmid: None,
-                    pmcid: None,
-                    title: item["title"].as_str().unwrap_or("").to_string(),
-                    abstract_text: item["abstract"].as_str().map(String::from),
-                    authors,
-                    journal: Some(format!("{} preprint", self.server)),
-                    pub_date,
-                    source,
-                    open_access: true, // all bioRxiv/medRxiv are OA
-                    full_text_url,
+                let key = paper.doi.clone().unwrap_or_default().to_ascii_lowercase();
+                match by_doi.get(&key) {
+                    Some(&idx) => {
+                        if preprint_version(&paper) >= preprint_version(&papers[idx]) {
+                            papers[idx] = paper;
+                        }
+                    }
+                    None => {
+                        by_doi.insert(key, papers.len());
+                        papers.push(paper);
+                    }
                 }
-            })
-            .take(max_results)
-            .collect();
+            }
+
+            if papers.len() >= max_results || page.total.is_some_and(|total| cursor >= total) {
+                break;
+            }
+        }
 
-        // If we got nothing from recent, try the previous year
         if papers.is_empty() {
             warn!(
                 server = self.server,
-                "No results for recent interval; try broadening date range"
+                %start,
+                %end,
+                "No matching preprints in window; try widening it"
             );
         }
-
+        papers.truncate(max_results);
         Ok(papers)
     }
+
+    fn in_categories(&self, record: &serde_json::Value) -> bool {
+        if self.categories.is_empty() {
+            return true;
+        }
+        let category = normalise_category(record["category"].as_str().unwrap_or(""));
+        self.categories
+            .iter()
+            .any(|c| normalise_category(c) == category)
+    }
+}
+
+/// The records and window total of a details response. The API reports
+/// counts as numbers or numeric strings depending on the endpoint version.
+fn parse_details_page(body: &serde_json::Value) -> DetailsPage {
+    let total = body["messages"]
+        .as_array()
+        .and_then(|m| m.first())
+        .and_then(|m| match &m["total"] {
+            serde_json::Value::Number(n) => n.as_u64().map(|n| n as usize),
+            serde_json::Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        });
+    DetailsPage {
+        records: body["collection"].as_array().cloned().unwrap_or_default(),
+        total,
+    }
+}
+
+fn normalise_category(raw: &str) -> String {
+    raw.trim().replace('_', " ").to_lowercase()
+}
+
+/// Lower-cased terms of a portable `A AND B AND C` query; each term is a
+/// list of words.
+fn query_terms(query: &str) -> Vec<Vec<String>> {
+    query
+        .to_lowercase()
+        .split(" and ")
+        .map(|term| {
+            term.trim()
+                .trim_end_matches("[tiab]")
+                .split_whitespace()
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .filter(|words| !words.is_empty())
+        .collect()
+}
+
+/// Every term must occur in the title or abstract; a multi-word term
+/// matches when all of its words occur, in any order.
+fn matches_terms(record: &serde_json::Value, terms: &[Vec<String>]) -> bool {
+    let text = format!(
+        "{} {}",
+        record["title"].as_str().unwrap_or(""),
+        record["abstract"].as_str().unwrap_or("")
+    )
+    .to_lowercase();
+    terms
+        .iter()
+        .all(|words| words.iter().all(|w| text.contains(w.as_str())))
+}
+
+fn preprint_version(paper: &PaperMetadata) -> u32 {
+    paper.preprint.as_ref().map_or(0, |p| p.version)
+}
+
+/// `PaperMetadata` for one details record; `None` without a DOI.
+fn record_to_paper(
+    record: &serde_json::Value,
+    server: &str,
+    source: IngestionSource,
+) -> Option<PaperMetadata> {
+    let doi = record["doi"]
+        .as_str()
+        .map(str::trim)
+        .filter(|d| !d.is_empty())?
+        .to_string();
+    let version = match &record["version"] {
+        serde_json::Value::Number(n) => n.as_u64().unwrap_or(1) as u32,
+        serde_json::Value::String(s) => s.trim().parse().unwrap_or(1),
+        _ => 1,
+    };
+    let published_doi = record["published"]
+        .as_str()
+        .map(str::trim)
+        .filter(|d| !d.is_empty() && !d.eq_ignore_ascii_case("NA"))
+        .map(String::from);
+
+    let authors: Vec<Author> = record["authors"]
+        .as_str()
+        .unwrap_or("")
+        .split(';')
+        .filter(|s| !s.trim().is_empty())
+        .map(|name| Author {
+            name: name.trim().to_string(),
+            affiliation: None,
+            orcid: None,
+        })
+        .collect();
+
+    let pub_date = record["date"]
+        .as_str()
+        .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+
+    Some(PaperMetadata {
+        full_text_url: Some(format!(
+            "https://www.{server}.org/content/{doi}v{version}.full.pdf"
+        )),
+        doi: Some(doi),
+        pmid: None,
+        pmcid: None,
+        title: record["title"].as_str().unwrap_or("").to_string(),
+        abstract_text: record["abstract"].as_str().map(String::from),
+        authors,
+        journal: Some(format!("{server} preprint")),
+        pub_date,
+        source,
+        open_access: true, // all bioRxiv/medRxiv are OA
+        is_review: false,
+        preprint: Some(PreprintInfo {
+            version,
+            published_doi,
+        }),
+    })
 }
 
 #[async_trait]
 impl LiteratureSource for BioRxivClient {
     async fn search(&self, query: &str, max_results: usize) -> anyhow::Result<Vec<PaperMetadata>> {
-        // Use a 2-year rolling window as default
-        let today = chrono::Utc::now().date_naive();
-        let two_years_ago = today - chrono::Duration::days(730);
-        let interval = format!("{}/{}", two_years_ago, today);
-
-        self.fetch_recent(&interval, query, max_results).await
+        let end = chrono::Utc::now().date_naive();
+        let mut start = end - chrono::Duration::days(self.window_days);
+        if let Some(since) = self.since {
+            start = start.max(since.min(end));
+        }
+        self.harvest(start, end, query, max_results).await
     }
 
     async fn fetch_full_text(&self, doi: &str) -> anyhow::Result<Option<String>> {
-        // bioRxiv full-text HTML
-        let url = format!("https://www.biorxiv.org/content/{}.full", doi);
+        let url = format!("https://www.{}.org/content/{}.full", self.server, doi);
         let resp = self.client.get(&url).send().await?;
         if !resp.status().is_success() {
             return Ok(None);
@@ -171,6 +333,45 @@ impl LiteratureSource for BioRxivClient {
 mod tests {
     use super::*;
 
+    const PAGE_1: &str = include_str!("../../tests/fixtures/biorxiv_details_page1.json");
+    const PAGE_2: &str = include_str!("../../tests/fixtures/biorxiv_details_page2.json");
+
+    /// Serve `bodies` as 200 responses in order, one connection each.
+    /// Returns the base URL and the request lines received.
+    async fn serve(bodies: Vec<&'static str>) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let mut seen = Vec::new();
+            for body in bodies {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let request = String::from_utf8_lossy(&request).to_string();
+                seen.push(request.lines().next().unwrap_or_default().to_string());
+                let head = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                socket.write_all(head.as_bytes()).await.unwrap();
+                socket.write_all(body.as_bytes()).await.unwrap();
+                socket.shutdown().await.unwrap();
+            }
+            seen
+        });
+        (url, handle)
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
     #[test]
     fn test_biorxiv_client_new() {
         let c = BioRxivClient::new_biorxiv();
@@ -181,6 +382,77 @@ mod tests {
     fn test_medrxiv_client_new() {
         let c = BioRxivClient::new_medrxiv();
         assert_eq!(c.server, "medrxiv");
+        assert_eq!(c.categories, ["oncology"]);
+    }
+
+    #[tokio::test]
+    async fn harvests_every_page_of_the_window() {
+        let (url, server) = serve(vec![PAGE_1, PAGE_2]).await;
+        let mut client = BioRxivClient::new_biorxiv();
+        client.base_url = url;
+
+        let papers = client
+            .harvest(
+                date(2026, 1, 1),
+                date(2026, 3, 31),
+                "KRAS AND pancreatic cancer AND G12D",
+                50,
+            )
+            .await
+            .unwrap();
+
+        // The neuroscience preprint is outside the category filter; the
+        // BRCA2 one does not match the query.
+        let dois: Vec<&str> = papers.iter().filter_map(|p| p.doi.as_deref()).collect();
+        assert_eq!(
+            dois,
+            ["10.1101/2026.01.12.598765", "10.1101/2026.03.15.605555"]
+        );
+        assert!(papers.iter().all(|p| p.open_access));
+        assert_eq!(papers[1].authors.len(), 2);
+        assert_eq!(papers[1].pub_date, Some(date(2026, 3, 15)));
+
+        let requests = server.await.unwrap();
+        assert_eq!(
+            requests,
+            [
+                "GET /biorxiv/2026-01-01/2026-03-31/0/json HTTP/1.1",
+                "GET /biorxiv/2026-01-01/2026-03-31/3/json HTTP/1.1",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn later_version_carries_the_published_doi() {
+        let (url, _server) = serve(vec![PAGE_1, PAGE_2]).await;
+        let mut client = BioRxivClient::new_biorxiv();
+        client.base_url = url;
+
+        let papers = client
+            .harvest(date(2026, 1, 1), date(2026, 3, 31), "CXCL1", 50)
+            .await
+            .unwrap();
+
+        assert_eq!(papers.len(), 1);
+        let paper = &papers[0];
+        assert_eq!(paper.doi.as_deref(), Some("10.1101/2026.01.12.598765"));
+        assert_eq!(
+            paper.preprint,
+            Some(PreprintInfo {
+                version: 2,
+                published_doi: Some("10.1038/s41586-026-01234-5".to_string()),
+            })
+        );
+        assert_eq!(paper.published_doi(), Some("10.1038/s41586-026-01234-5"));
+        assert_eq!(
+            paper.full_text_url.as_deref(),
+            Some("https://www.biorxiv.org/content/10.1101/2026.01.12.598765v2.full.pdf")
+        );
+        assert!(paper
+            .abstract_text
+            .as_deref()
+            .unwrap()
+            .contains("CXCR2 blockade"));
     }
 
     #[test]
@@ -189,4 +461,14 @@ mod tests {
         let c = BioRxivClient::new_biorxiv();
         assert_eq!(c.server, "biorxiv");
     }
+
+    #[test]
+    fn unpublished_preprints_have_no_journal_doi() {
+        let page: serde_json::Value = serde_json::from_str(PAGE_1).unwrap();
+        let page = parse_details_page(&page);
+        assert_eq!(page.total, Some(5));
+        let paper = record_to_paper(&page.records[0], "biorxiv", IngestionSource::BioRxiv).unwrap();
+        assert_eq!(paper.published_doi(), None);
+        assert_eq!(paper.preprint.unwrap().version, 1);
+    }
 }
@@ -14,6 +14,11 @@
 //!   - compound: ChEMBL compound ID, SMILES, properties
 //!   - target: Protein target with organism and type
 //!   - activity: IC50, Ki, etc. with assay details
+//!
+//! [`ChemblClient::count_bioactive_compounds`] summarises the potent
+//! chemistry against a gene's target for the ranker's novelty score.
+
+use std::collections::HashSet;
 
 use async_trait::async_trait;
 use reqwest::Client;
@@ -25,6 +30,18 @@ use crate::models::PaperMetadata;
 
 const CHEMBL_API_URL: &str = "https://www.ebi.ac.uk/chembl/api/data";
 
+/// Activities per page when counting potent compounds (the API maximum).
+const ACTIVITY_PAGE_SIZE: usize = 1000;
+
+/// Pages fetched per target; the count saturates at 10,000 activities.
+const MAX_ACTIVITY_PAGES: usize = 10;
+
+/// Compounds listed in [`ChemblTargetSummary::top_compounds`].
+const TOP_COMPOUNDS: usize = 5;
+
+/// Molecules requested per `molecule_chembl_id__in` lookup.
+const MOLECULE_BATCH_SIZE: usize = 50;
+
 /// Compound record from ChEMBL.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CompoundRecord {
@@ -62,6 +79,22 @@ pub struct ActivityRecord {
     pub pchembl_value: Option<f64>, // -log10(M) normalized value
 }
 
+/// Potent chemistry against one ChEMBL target.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChemblTargetSummary {
+    pub target_chembl_id: String,
+    /// UniProt accession the target was resolved from, if any.
+    pub uniprot_id: Option<String>,
+    /// IC50/Ki cut-off in nM.
+    pub activity_threshold_nm: f64,
+    /// Distinct compounds with an IC50 or Ki at or below the cut-off.
+    pub bioactive_compound_count: u32,
+    /// Whether an approved (phase 4) drug acts on the target.
+    pub has_approved_drug: bool,
+    /// Compound ChEMBL IDs, most potent first.
+    pub top_compounds: Vec<String>,
+}
+
 /// ChEMBL client for compound and target data.
 pub struct ChemblClient {
     client: Client,
@@ -89,23 +122,7 @@ impl ChemblClient {
 
         let json: serde_json::Value = resp.json().await?;
 
-        Ok(Some(CompoundRecord {
-            chembl_id: json["molecule_chembl_id"]
-                .as_str()
-                .unwrap_or("")
-                .to_string(),
-            name: json["pref_name"].as_str().map(String::from),
-            smiles: json["molecule_structures"]["canonical_smiles"]
-                .as_str()
-                .map(String::from),
-            inchi_key: json["molecule_structures"]["standard_inchi_key"]
-                .as_str()
-                .map(String::from),
-            molecular_weight: json["molecule_properties"]["mw_freebase"].as_f64(),
-            alogp: json["molecule_properties"]["alogp"].as_f64(),
-            max_phase: json["max_phase"].as_i64().map(|n| n as i32),
-            indication_class: None, // Would need separate query
-        }))
+        Ok(Some(compound_from_json(&json)))
     }
 
     /// Search compounds by SMILES structure similarity.
@@ -264,6 +281,194 @@ impl ChemblClient {
         Ok(activities)
     }
 
+    /// Human single-protein target for a gene: by UniProt accession when
+    /// one is known, otherwise the first matching target search hit.
+    #[instrument(skip(self))]
+    pub async fn resolve_target(
+        &self,
+        gene_symbol: &str,
+        uniprot_id: Option<&str>,
+    ) -> anyhow::Result<Option<String>> {
+        if let Some(accession) = uniprot_id.map(str::trim).filter(|a| !a.is_empty()) {
+            let json: serde_json::Value = self
+                .client
+                .get(format!("{}/target.json", CHEMBL_API_URL))
+                .query(&[
+                    ("target_components__accession", accession),
+                    ("target_type", "SINGLE PROTEIN"),
+                    ("organism", "Homo sapiens"),
+                ])
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+            if let Some(id) = json["targets"]
+                .as_array()
+                .and_then(|targets| targets.first())
+                .and_then(|t| t["target_chembl_id"].as_str())
+            {
+                return Ok(Some(id.to_string()));
+            }
+        }
+
+        Ok(self
+            .search_targets_by_gene(gene_symbol)
+            .await?
+            .into_iter()
+            .find(|t| {
+                t.target_type == "SINGLE PROTEIN" && t.organism.as_deref() == Some("Homo sapiens")
+            })
+            .map(|t| t.chembl_id))
+    }
+
+    /// Count distinct compounds with an IC50 or Ki at or below
+    /// `activity_threshold_nm` against the target of `gene_symbol`, and
+    /// whether an approved drug acts on it. `None` when the gene has no
+    /// human ChEMBL target.
+    #[instrument(skip(self))]
+    pub async fn count_bioactive_compounds(
+        &self,
+        gene_symbol: &str,
+        uniprot_id: Option<&str>,
+        activity_threshold_nm: f64,
+    ) -> anyhow::Result<Option<ChemblTargetSummary>> {
+        let Some(target) = self.resolve_target(gene_symbol, uniprot_id).await? else {
+            return Ok(None);
+        };
+
+        let activities = self
+            .potent_activities(&target, activity_threshold_nm, MAX_ACTIVITY_PAGES)
+            .await?;
+
+        let mechanisms: serde_json::Value = self
+            .client
+            .get(format!("{}/mechanism.json", CHEMBL_API_URL))
+            .query(&[("target_chembl_id", target.as_str()), ("limit", "1000")])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let has_approved_drug = mechanisms["mechanisms"]
+            .as_array()
+            .is_some_and(|m| m.iter().any(|m| json_f64(&m["max_phase"]) >= Some(4.0)));
+
+        let (count, top_compounds) = potent_compounds(&activities, activity_threshold_nm);
+        debug!(
+            target = target.as_str(),
+            count, has_approved_drug, "ChEMBL potent compounds counted"
+        );
+        Ok(Some(ChemblTargetSummary {
+            target_chembl_id: target,
+            uniprot_id: uniprot_id.map(String::from),
+            activity_threshold_nm,
+            bioactive_compound_count: count,
+            has_approved_drug,
+            top_compounds,
+        }))
+    }
+
+    /// Up to `max` distinct compounds with an IC50 or Ki at or below
+    /// `activity_threshold_nm` against `target_chembl_id`, most potent
+    /// first, with their structures. These are the known actives that
+    /// ligand generation starts from.
+    #[instrument(skip(self))]
+    pub async fn fetch_active_compounds(
+        &self,
+        target_chembl_id: &str,
+        activity_threshold_nm: f64,
+        max: usize,
+    ) -> anyhow::Result<Vec<CompoundRecord>> {
+        if max == 0 {
+            return Ok(Vec::new());
+        }
+        // Activities repeat compounds across assays; a few extra pages
+        // leave room for that.
+        let pages = (max / ACTIVITY_PAGE_SIZE + 2).min(MAX_ACTIVITY_PAGES);
+        let activities = self
+            .potent_activities(target_chembl_id, activity_threshold_nm, pages)
+            .await?;
+        let mut ids = ranked_compounds(&activities, activity_threshold_nm);
+        ids.truncate(max);
+
+        let url = format!("{}/molecule.json", CHEMBL_API_URL);
+        let limit = MOLECULE_BATCH_SIZE.to_string();
+        let mut compounds = Vec::with_capacity(ids.len());
+        for batch in ids.chunks(MOLECULE_BATCH_SIZE) {
+            let json: serde_json::Value = self
+                .client
+                .get(&url)
+                .query(&[
+                    ("molecule_chembl_id__in", batch.join(",").as_str()),
+                    ("limit", limit.as_str()),
+                ])
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+            let mut found: Vec<CompoundRecord> = json["molecules"]
+                .as_array()
+                .map(|m| m.iter().map(compound_from_json).collect())
+                .unwrap_or_default();
+            // The API does not keep the order of the `__in` list.
+            for id in batch {
+                if let Some(i) = found.iter().position(|c| &c.chembl_id == id) {
+                    compounds.push(found.swap_remove(i));
+                }
+            }
+        }
+        debug!(
+            target = target_chembl_id,
+            count = compounds.len(),
+            "ChEMBL active compounds fetched"
+        );
+        Ok(compounds)
+    }
+
+    /// IC50 and Ki activities at or below `activity_threshold_nm`, most
+    /// potent first, over at most `pages` pages.
+    async fn potent_activities(
+        &self,
+        target_chembl_id: &str,
+        activity_threshold_nm: f64,
+        pages: usize,
+    ) -> anyhow::Result<Vec<serde_json::Value>> {
+        let url = format!("{}/activity.json", CHEMBL_API_URL);
+        let threshold = activity_threshold_nm.to_string();
+        let limit = ACTIVITY_PAGE_SIZE.to_string();
+        let mut activities = Vec::new();
+        for page in 0..pages {
+            let offset = (page * ACTIVITY_PAGE_SIZE).to_string();
+            let json: serde_json::Value = self
+                .client
+                .get(&url)
+                .query(&[
+                    ("target_chembl_id", target_chembl_id),
+                    ("standard_type__in", "IC50,Ki"),
+                    ("standard_units", "nM"),
+                    ("standard_value__lte", threshold.as_str()),
+                    ("order_by", "standard_value"),
+                    ("only", "molecule_chembl_id,standard_value"),
+                    ("limit", limit.as_str()),
+                    ("offset", offset.as_str()),
+                ])
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+            let batch = json["activities"].as_array().cloned().unwrap_or_default();
+            let done = batch.len() < ACTIVITY_PAGE_SIZE;
+            activities.extend(batch);
+            if done {
+                break;
+            }
+        }
+        Ok(activities)
+    }
+
     /// Get approved drugs for a target.
     pub async fn get_approved_drugs(
         &self,
@@ -286,6 +491,64 @@ impl ChemblClient {
     }
 }
 
+/// Distinct compounds at or below `threshold_nm`, and the most potent few.
+fn potent_compounds(activities: &[serde_json::Value], threshold_nm: f64) -> (u32, Vec<String>) {
+    let mut ranked = ranked_compounds(activities, threshold_nm);
+    let count = ranked.len() as u32;
+    ranked.truncate(TOP_COMPOUNDS);
+    (count, ranked)
+}
+
+/// Distinct compounds at or below `threshold_nm`, each at its best
+/// potency, most potent first.
+fn ranked_compounds(activities: &[serde_json::Value], threshold_nm: f64) -> Vec<String> {
+    let mut potent: Vec<(f64, &str)> = activities
+        .iter()
+        .filter_map(|a| {
+            let value = json_f64(&a["standard_value"])?;
+            let id = a["molecule_chembl_id"].as_str()?;
+            (value <= threshold_nm).then_some((value, id))
+        })
+        .collect();
+    potent.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut seen = HashSet::new();
+    potent
+        .into_iter()
+        .filter(|(_, id)| seen.insert(*id))
+        .map(|(_, id)| id.to_string())
+        .collect()
+}
+
+/// A `molecule` resource, from `/molecule/{id}.json` or one entry of
+/// `/molecule.json`.
+fn compound_from_json(json: &serde_json::Value) -> CompoundRecord {
+    CompoundRecord {
+        chembl_id: json["molecule_chembl_id"]
+            .as_str()
+            .unwrap_or("")
+            .to_string(),
+        name: json["pref_name"].as_str().map(String::from),
+        smiles: json["molecule_structures"]["canonical_smiles"]
+            .as_str()
+            .map(String::from),
+        inchi_key: json["molecule_structures"]["standard_inchi_key"]
+            .as_str()
+            .map(String::from),
+        molecular_weight: json_f64(&json["molecule_properties"]["mw_freebase"]),
+        alogp: json_f64(&json["molecule_properties"]["alogp"]),
+        max_phase: json_f64(&json["max_phase"]).map(|n| n as i32),
+        indication_class: None, // Would need separate query
+    }
+}
+
+/// ChEMBL returns numeric fields as numbers or as strings ("4.0").
+fn json_f64(value: &serde_json::Value) -> Option<f64> {
+    value
+        .as_f64()
+        .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+}
+
 impl Default for ChemblClient {
     fn default() -> Self {
         Self::new()
@@ -365,4 +628,59 @@ mod tests {
         assert!(json.contains("IC50"));
         assert!(json.contains("8.52"));
     }
+
+    #[test]
+    fn test_potent_compounds_are_distinct_and_ordered_by_potency() {
+        let activities: Vec<serde_json::Value> = [
+            ("CHEMBL3", "250.0"),
+            ("CHEMBL1", "3.5"),
+            ("CHEMBL2", "12"),
+            ("CHEMBL1", "8.0"),
+            ("CHEMBL9", "5000"),
+            ("CHEMBL4", "40"),
+            ("CHEMBL5", "41"),
+            ("CHEMBL6", "900"),
+        ]
+        .iter()
+        .map(|(id, v)| serde_json::json!({"molecule_chembl_id": id, "standard_value": v}))
+        .chain(std::iter::once(
+            serde_json::json!({"molecule_chembl_id": "CHEMBL7", "standard_value": 1.0}),
+        ))
+        .collect();
+
+        let (count, top) = potent_compounds(&activities, 1000.0);
+        assert_eq!(count, 7);
+        assert_eq!(
+            top,
+            vec!["CHEMBL7", "CHEMBL1", "CHEMBL2", "CHEMBL4", "CHEMBL5"]
+        );
+        assert_eq!(potent_compounds(&activities, 2.0).0, 1);
+    }
+
+    #[test]
+    fn test_compound_from_json_reads_string_and_numeric_properties() {
+        let json = serde_json::json!({
+            "molecule_chembl_id": "CHEMBL553",
+            "pref_name": "ERLOTINIB",
+            "max_phase": "4.0",
+            "molecule_structures": {
+                "canonical_smiles": "C#Cc1cccc(Nc2ncnc3cc(OCCOC)c(OCCOC)cc23)c1",
+                "standard_inchi_key": "AAKJLRGGTJKAMG-UHFFFAOYSA-N"
+            },
+            "molecule_properties": {"mw_freebase": "393.44", "alogp": 3.41}
+        });
+        let compound = compound_from_json(&json);
+        assert_eq!(compound.chembl_id, "CHEMBL553");
+        assert_eq!(compound.name.as_deref(), Some("ERLOTINIB"));
+        assert_eq!(
+            compound.inchi_key.as_deref(),
+            Some("AAKJLRGGTJKAMG-UHFFFAOYSA-N")
+        );
+        assert_eq!(compound.molecular_weight, Some(393.44));
+        assert_eq!(compound.alogp, Some(3.41));
+        assert_eq!(compound.max_phase, Some(4));
+
+        let bare = compound_from_json(&serde_json::json!({"molecule_chembl_id": "CHEMBL1"}));
+        assert_eq!((bare.smiles, bare.max_phase), (None, None));
+    }
 }
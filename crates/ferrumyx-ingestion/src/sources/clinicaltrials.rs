@@ -9,35 +9,325 @@
 //!   - pmid        = None (NCT IDs stored in doi field)
 //!   - doi         = nct_id (e.g. NCT04956640)
 //!   - source      = ClinicalTrials
+//!
+//! [`ClinicalTrialsClient::fetch_trials`] pages through a search and
+//! returns each study as a [`TrialRecord`], keeping the interventions,
+//! arms, phase, status and enrollment that the paper mapping flattens into
+//! text; the pipeline stores these in the `trials` table.
+//!
+//! [`ClinicalTrialsClient::count_trials_for_target`] summarises the
+//! interventional trials for a gene (or its known inhibitors) in a
+//! condition, for the ranker's evidence panel and novelty penalty when no
+//! trials are stored.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
+use chrono::NaiveDate;
+use ferrumyx_db::schema::{TrialIntervention, TrialRecord};
 use reqwest::Client;
-use tracing::{debug, instrument};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument, warn};
 
 use super::LiteratureSource;
 use crate::models::{Author, IngestionSource, PaperMetadata};
+use crate::rate_limit::{source_bucket, TokenBucket};
 
 const CT_API_URL: &str = "https://clinicaltrials.gov/api/v2/studies";
 
+/// Studies per page when counting trials (the API maximum is 1000).
+const TRIAL_PAGE_SIZE: usize = 100;
+
+/// Pages fetched per summary; phase and status counts cover at most
+/// `TRIAL_PAGE_SIZE * MAX_TRIAL_PAGES` studies, `total` covers all.
+const MAX_TRIAL_PAGES: usize = 10;
+
+/// Fields requested by [`ClinicalTrialsClient::fetch_trials`].
+const TRIAL_RECORD_FIELDS: &str = "NCTId,BriefTitle,OverallStatus,Phase,StudyType,Condition,\
+     InterventionType,InterventionName,InterventionOtherName,InterventionArmGroupLabel,\
+     EnrollmentCount,StartDate,CompletionDate,LeadSponsorName,CollaboratorName";
+
+/// Trials listed in [`TrialSummary::top_trials`].
+const TOP_TRIALS: usize = 10;
+
+/// Trial summaries change slowly; a week keeps us well inside the rate limit.
+const DEFAULT_TRIAL_CACHE_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// One trial in a [`TrialSummary`] drill-down list.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrialRef {
+    pub nct_id: String,
+    pub title: String,
+    /// Highest listed phase, e.g. "PHASE3" or "NA".
+    pub phase: String,
+    pub status: String,
+}
+
+/// Interventional trials for a gene in one condition.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrialSummary {
+    pub gene: String,
+    /// Condition searched, e.g. "pancreatic cancer" for PAAD.
+    pub condition: String,
+    /// Matching trials as reported by the API.
+    pub total: u32,
+    /// Trials per highest listed phase; a phase 1/2 trial counts as phase 2.
+    pub by_phase: BTreeMap<String, u32>,
+    /// Trials per overall status, e.g. "RECRUITING".
+    pub by_status: BTreeMap<String, u32>,
+    /// First trials in API relevance order.
+    pub top_trials: Vec<TrialRef>,
+}
+
+impl TrialSummary {
+    /// Trials that reached phase 3 or 4.
+    pub fn phase3_or_later(&self) -> u32 {
+        ["PHASE3", "PHASE4"]
+            .iter()
+            .filter_map(|p| self.by_phase.get(*p))
+            .sum()
+    }
+
+    /// Summary of stored `trials`, counted the way the API count is.
+    pub fn from_records<'a>(
+        gene: &str,
+        condition: &str,
+        trials: impl IntoIterator<Item = &'a TrialRecord>,
+    ) -> Self {
+        let mut summary = TrialSummary {
+            gene: gene.to_string(),
+            condition: condition.to_string(),
+            ..TrialSummary::default()
+        };
+        for trial in trials {
+            summary.total += 1;
+            summary.add(TrialRef {
+                nct_id: trial.nct_id.clone(),
+                title: trial.title.clone(),
+                phase: trial.phase.clone(),
+                status: trial.status.clone(),
+            });
+        }
+        summary
+    }
+
+    fn add_study(&mut self, study: &serde_json::Value) {
+        let proto = &study["protocolSection"];
+        let id_mod = &proto["identificationModule"];
+        self.add(TrialRef {
+            nct_id: id_mod["nctId"].as_str().unwrap_or_default().to_string(),
+            title: id_mod["briefTitle"].as_str().unwrap_or("").to_string(),
+            phase: highest_phase(&proto["designModule"]["phases"]),
+            status: overall_status(proto),
+        });
+    }
+
+    fn add(&mut self, trial: TrialRef) {
+        *self.by_phase.entry(trial.phase.clone()).or_insert(0) += 1;
+        *self.by_status.entry(trial.status.clone()).or_insert(0) += 1;
+        if self.top_trials.len() < TOP_TRIALS && !trial.nct_id.is_empty() {
+            self.top_trials.push(trial);
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct TrialCacheEntry {
+    cached_at_epoch_secs: u64,
+    summary: TrialSummary,
+}
+
 pub struct ClinicalTrialsClient {
     client: Client,
+    base_url: String,
+    cache_dir: PathBuf,
+    cache_ttl: Duration,
+    limiter: Arc<TokenBucket>,
 }
 
 impl ClinicalTrialsClient {
     pub fn new() -> Self {
+        let cache_dir = std::env::var("FERRUMYX_CLINICAL_TRIALS_CACHE_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("data/cache/clinical_trials"));
+        let ttl_secs = std::env::var("FERRUMYX_CLINICAL_TRIALS_CACHE_TTL_SECS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .unwrap_or(DEFAULT_TRIAL_CACHE_TTL_SECS);
         Self {
             client: Client::new(),
+            base_url: CT_API_URL.to_string(),
+            cache_dir,
+            cache_ttl: Duration::from_secs(ttl_secs),
+            limiter: source_bucket("clinicaltrials"),
         }
     }
 
+    /// Keep trial summaries under `dir` for `ttl`.
+    pub fn with_cache(mut self, dir: impl Into<PathBuf>, ttl: Duration) -> Self {
+        self.cache_dir = dir.into();
+        self.cache_ttl = ttl;
+        self
+    }
+
+    /// Interventional trials mentioning `gene` or one of its known
+    /// inhibitors in `cancer_type` (a TCGA/OncoTree code or a condition
+    /// name). Served from the disk cache while it is fresh.
+    #[instrument(skip(self))]
+    pub async fn count_trials_for_target(
+        &self,
+        gene: &str,
+        cancer_type: &str,
+    ) -> anyhow::Result<TrialSummary> {
+        if let Some(summary) = self.cached_trial_summary(gene, cancer_type) {
+            return Ok(summary);
+        }
+
+        let condition = condition_for_cancer_code(cancer_type);
+        let term = target_search_term(gene);
+        let mut summary = TrialSummary {
+            gene: gene.to_string(),
+            condition: condition.clone(),
+            ..TrialSummary::default()
+        };
+        let page_size = TRIAL_PAGE_SIZE.to_string();
+        let mut page_token: Option<String> = None;
+        for _ in 0..MAX_TRIAL_PAGES {
+            let mut query = vec![
+                ("query.term", term.as_str()),
+                ("query.cond", condition.as_str()),
+                ("filter.advanced", "AREA[StudyType]INTERVENTIONAL"),
+                ("fields", "NCTId,BriefTitle,OverallStatus,Phase"),
+                ("countTotal", "true"),
+                ("pageSize", page_size.as_str()),
+                ("format", "json"),
+            ];
+            if let Some(token) = page_token.as_deref() {
+                query.push(("pageToken", token));
+            }
+            let resp = self.get_page(&query).await?;
+
+            if let Some(total) = resp["totalCount"].as_u64() {
+                summary.total = total as u32;
+            }
+            for study in resp["studies"].as_array().into_iter().flatten() {
+                summary.add_study(study);
+            }
+            page_token = resp["nextPageToken"].as_str().map(String::from);
+            if page_token.is_none() {
+                break;
+            }
+        }
+        debug!(
+            total = summary.total,
+            phase3 = summary.phase3_or_later(),
+            "ClinicalTrials.gov trials counted"
+        );
+
+        self.save_trial_summary(gene, cancer_type, &summary);
+        Ok(summary)
+    }
+
+    /// Up to `max_results` studies matching `term`, and `condition` if
+    /// given, in API relevance order. Link fields are left empty; see
+    /// [`crate::trials::link_trial`].
+    #[instrument(skip(self))]
+    pub async fn fetch_trials(
+        &self,
+        term: &str,
+        condition: Option<&str>,
+        max_results: usize,
+    ) -> anyhow::Result<Vec<TrialRecord>> {
+        let page_size = max_results.clamp(1, TRIAL_PAGE_SIZE).to_string();
+        let mut trials = Vec::new();
+        let mut page_token: Option<String> = None;
+        while trials.len() < max_results {
+            let mut query = vec![
+                ("query.term", term),
+                ("fields", TRIAL_RECORD_FIELDS),
+                ("pageSize", page_size.as_str()),
+                ("format", "json"),
+            ];
+            if let Some(condition) = condition {
+                query.push(("query.cond", condition));
+            }
+            if let Some(token) = page_token.as_deref() {
+                query.push(("pageToken", token));
+            }
+            let resp = self.get_page(&query).await?;
+            trials.extend(
+                resp["studies"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(parse_study),
+            );
+            page_token = resp["nextPageToken"].as_str().map(String::from);
+            if page_token.is_none() {
+                break;
+            }
+        }
+        trials.truncate(max_results);
+        debug!(
+            n = trials.len(),
+            "ClinicalTrials.gov trial records retrieved"
+        );
+        Ok(trials)
+    }
+
+    /// One page of `/studies`, after waiting for the source's rate limit.
+    async fn get_page(&self, query: &[(&str, &str)]) -> anyhow::Result<serde_json::Value> {
+        self.limiter.acquire().await;
+        Ok(self
+            .client
+            .get(&self.base_url)
+            .query(query)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<serde_json::Value>()
+            .await?)
+    }
+
+    /// Cached summary for `gene` in `cancer_type`, if one is younger than
+    /// the TTL. Never touches the network.
+    pub fn cached_trial_summary(&self, gene: &str, cancer_type: &str) -> Option<TrialSummary> {
+        let payload = std::fs::read_to_string(self.cache_path(gene, cancer_type)).ok()?;
+        let entry: TrialCacheEntry = serde_json::from_str(&payload).ok()?;
+        let age = now_epoch_secs().saturating_sub(entry.cached_at_epoch_secs);
+        (age <= self.cache_ttl.as_secs()).then_some(entry.summary)
+    }
+
+    fn save_trial_summary(&self, gene: &str, cancer_type: &str, summary: &TrialSummary) {
+        let entry = TrialCacheEntry {
+            cached_at_epoch_secs: now_epoch_secs(),
+            summary: summary.clone(),
+        };
+        let written = std::fs::create_dir_all(&self.cache_dir).and_then(|_| {
+            let payload = serde_json::to_string(&entry).map_err(std::io::Error::other)?;
+            std::fs::write(self.cache_path(gene, cancer_type), payload)
+        });
+        if let Err(e) = written {
+            warn!("Could not cache trial summary for {gene}: {e}");
+        }
+    }
+
+    fn cache_path(&self, gene: &str, cancer_type: &str) -> PathBuf {
+        cache_path(&self.cache_dir, gene, cancer_type)
+    }
+
     async fn search_studies(
         &self,
         query: &str,
         max_results: usize,
     ) -> anyhow::Result<Vec<serde_json::Value>> {
+        self.limiter.acquire().await;
         let resp = self
             .client
-            .get(CT_API_URL)
+            .get(&self.base_url)
             .query(&[
                 ("query.term", query),
                 ("pageSize", &max_results.to_string()),
@@ -58,6 +348,198 @@ impl ClinicalTrialsClient {
     }
 }
 
+/// `<dir>/<gene>__<cancer>.json`, lower-cased, other characters as `_`.
+fn cache_path(dir: &Path, gene: &str, cancer_type: &str) -> PathBuf {
+    let clean = |s: &str| -> String {
+        s.trim()
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' {
+                    c.to_ascii_lowercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    };
+    dir.join(format!("{}__{}.json", clean(gene), clean(cancer_type)))
+}
+
+fn now_epoch_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// The [`TrialRecord`] for one entry of a v2 `studies` array; `None`
+/// without an NCT id.
+pub fn parse_study(study: &serde_json::Value) -> Option<TrialRecord> {
+    let proto = &study["protocolSection"];
+    let nct_id = proto["identificationModule"]["nctId"]
+        .as_str()
+        .map(str::trim)
+        .filter(|id| !id.is_empty())?;
+    let status_mod = &proto["statusModule"];
+    let design_mod = &proto["designModule"];
+    let sponsor_mod = &proto["sponsorCollaboratorsModule"];
+    let strings = |v: &serde_json::Value| -> Vec<String> {
+        v.as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|s| s.as_str())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect()
+    };
+
+    let interventions = proto["armsInterventionsModule"]["interventions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|iv| {
+            Some(TrialIntervention {
+                name: iv["name"].as_str()?.trim().to_string(),
+                kind: iv["type"].as_str().unwrap_or("OTHER").to_string(),
+                other_names: strings(&iv["otherNames"]),
+                arm_groups: strings(&iv["armGroupLabels"]),
+            })
+        })
+        .collect();
+    let sponsors = std::iter::once(&sponsor_mod["leadSponsor"])
+        .chain(
+            sponsor_mod["collaborators"]
+                .as_array()
+                .into_iter()
+                .flatten(),
+        )
+        .filter_map(|s| s["name"].as_str())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    Some(TrialRecord {
+        nct_id: nct_id.to_string(),
+        title: proto["identificationModule"]["briefTitle"]
+            .as_str()
+            .unwrap_or("")
+            .to_string(),
+        conditions: strings(&proto["conditionsModule"]["conditions"]),
+        interventions,
+        phase: highest_phase(&design_mod["phases"]),
+        status: overall_status(proto),
+        study_type: design_mod["studyType"]
+            .as_str()
+            .unwrap_or("UNKNOWN")
+            .to_string(),
+        enrollment: design_mod["enrollmentInfo"]["count"].as_i64(),
+        start_date: study_date(&status_mod["startDateStruct"]["date"]),
+        completion_date: study_date(&status_mod["completionDateStruct"]["date"]),
+        sponsors,
+        drugs: Vec::new(),
+        target_genes: Vec::new(),
+        cancer_codes: Vec::new(),
+        fetched_at: chrono::Utc::now(),
+    })
+}
+
+fn overall_status(proto: &serde_json::Value) -> String {
+    proto["statusModule"]["overallStatus"]
+        .as_str()
+        .unwrap_or("UNKNOWN")
+        .to_string()
+}
+
+/// A study date, "2024-03-15" or month-precision "2024-03" (taken as the
+/// first of the month).
+fn study_date(value: &serde_json::Value) -> Option<NaiveDate> {
+    let raw = value.as_str()?.trim();
+    NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(&format!("{raw}-01"), "%Y-%m-%d"))
+        .ok()
+}
+
+/// Highest phase in a study's `phases` list; "NA" when none is listed.
+fn highest_phase(phases: &serde_json::Value) -> String {
+    phases
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|p| p.as_str())
+        .max_by_key(|p| match *p {
+            "EARLY_PHASE1" => 1,
+            "PHASE1" => 2,
+            "PHASE2" => 3,
+            "PHASE3" => 4,
+            "PHASE4" => 5,
+            _ => 0,
+        })
+        .unwrap_or("NA")
+        .to_string()
+}
+
+/// ClinicalTrials.gov condition for a TCGA/OncoTree code; anything else is
+/// taken to be a condition name already.
+pub fn condition_for_cancer_code(code: &str) -> String {
+    let name = match code.trim().to_ascii_uppercase().as_str() {
+        "PAAD" => "pancreatic cancer",
+        "LUAD" => "lung adenocarcinoma",
+        "LUSC" => "squamous cell lung carcinoma",
+        "NSCLC" => "non-small cell lung cancer",
+        "COAD" | "READ" | "COADREAD" => "colorectal cancer",
+        "BRCA" => "breast cancer",
+        "SKCM" => "melanoma",
+        "GBM" | "GB" => "glioblastoma",
+        "LAML" | "AML" => "acute myeloid leukemia",
+        "PRAD" => "prostate cancer",
+        "OV" => "ovarian cancer",
+        "STAD" => "gastric cancer",
+        "HNSC" => "head and neck squamous cell carcinoma",
+        "LIHC" => "hepatocellular carcinoma",
+        "KIRC" => "renal cell carcinoma",
+        "BLCA" => "bladder cancer",
+        "UCEC" => "endometrial cancer",
+        "THCA" => "thyroid cancer",
+        _ => return code.trim().to_string(),
+    };
+    name.to_string()
+}
+
+/// `query.term` for trials of `gene`: the symbol or any of its
+/// [`known_inhibitors`].
+pub fn target_search_term(gene: &str) -> String {
+    std::iter::once(gene)
+        .chain(known_inhibitors(gene).iter().copied())
+        .collect::<Vec<_>>()
+        .join(" OR ")
+}
+
+/// Approved or late-stage inhibitors searched alongside the gene symbol,
+/// since trials name the drug rather than its target.
+pub fn known_inhibitors(gene: &str) -> &'static [&'static str] {
+    match gene.trim().to_ascii_uppercase().as_str() {
+        "KRAS" => &["sotorasib", "adagrasib", "divarasib"],
+        "EGFR" => &["osimertinib", "erlotinib", "gefitinib", "afatinib"],
+        "BRAF" => &["vemurafenib", "dabrafenib", "encorafenib"],
+        "ALK" => &["alectinib", "crizotinib", "lorlatinib"],
+        "ERBB2" => &["trastuzumab", "lapatinib", "tucatinib"],
+        "MET" => &["capmatinib", "tepotinib"],
+        "RET" => &["selpercatinib", "pralsetinib"],
+        "ROS1" => &["crizotinib", "entrectinib"],
+        "NTRK1" => &["larotrectinib", "entrectinib"],
+        "PIK3CA" => &["alpelisib"],
+        "IDH1" => &["ivosidenib"],
+        "IDH2" => &["enasidenib"],
+        "FLT3" => &["midostaurin", "gilteritinib"],
+        "KIT" | "ABL1" => &["imatinib"],
+        "PARP1" => &["olaparib", "niraparib"],
+        "CDK4" | "CDK6" => &["palbociclib", "ribociclib", "abemaciclib"],
+        "EZH2" => &["tazemetostat"],
+        "FGFR2" => &["pemigatinib"],
+        _ => &[],
+    }
+}
+
 impl Default for ClinicalTrialsClient {
     fn default() -> Self {
         Self::new()
@@ -159,6 +641,8 @@ impl LiteratureSource for ClinicalTrialsClient {
                     source: IngestionSource::ClinicalTrials,
                     open_access: true,
                     full_text_url: Some(format!("https://clinicaltrials.gov/study/{}", nct_id)),
+                    is_review: false,
+                    preprint: None,
                 }
             })
             .collect();
@@ -176,6 +660,141 @@ impl LiteratureSource for ClinicalTrialsClient {
 mod tests {
     use super::*;
 
+    const PAGE_1: &str = include_str!("../../tests/fixtures/clinicaltrials_v2_page1.json");
+    const PAGE_2: &str = include_str!("../../tests/fixtures/clinicaltrials_v2_page2.json");
+
+    /// Serve `bodies` as 200 responses in order, one connection each.
+    /// Returns the base URL and the request lines received.
+    async fn serve(bodies: Vec<&'static str>) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/api/v2/studies", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let mut seen = Vec::new();
+            for body in bodies {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let request = String::from_utf8_lossy(&request).to_string();
+                seen.push(request.lines().next().unwrap_or_default().to_string());
+                let head = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                socket.write_all(head.as_bytes()).await.unwrap();
+                socket.write_all(body.as_bytes()).await.unwrap();
+                socket.shutdown().await.unwrap();
+            }
+            seen
+        });
+        (url, handle)
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(y, m, d)
+    }
+
+    #[tokio::test]
+    async fn fetch_trials_follows_page_tokens() {
+        let (url, server) = serve(vec![PAGE_1, PAGE_2]).await;
+        let mut client = ClinicalTrialsClient::new();
+        client.base_url = url;
+        client.limiter = Arc::new(TokenBucket::new(50, 16));
+
+        let trials = client
+            .fetch_trials("KRAS OR sotorasib", None, 10)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = trials.iter().map(|t| t.nct_id.as_str()).collect();
+        assert_eq!(
+            ids,
+            ["NCT04303780", "NCT04793958", "NCT04117087", "NCT05250843"]
+        );
+
+        let requests = server.await.unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].contains("query.term=KRAS+OR+sotorasib"));
+        assert!(!requests[0].contains("pageToken"));
+        assert!(requests[1].contains("pageToken=ZVNj7o2Elu8o3lpoWsSOYfg"));
+    }
+
+    #[tokio::test]
+    async fn fetch_trials_stops_at_max_results() {
+        let (url, server) = serve(vec![PAGE_1]).await;
+        let mut client = ClinicalTrialsClient::new();
+        client.base_url = url;
+        client.limiter = Arc::new(TokenBucket::new(50, 16));
+
+        let trials = client
+            .fetch_trials("KRAS", Some("colorectal cancer"), 2)
+            .await
+            .unwrap();
+        assert_eq!(trials.len(), 2);
+        let requests = server.await.unwrap();
+        assert!(requests[0].contains("pageSize=2"));
+        assert!(requests[0].contains("query.cond=colorectal+cancer"));
+    }
+
+    #[test]
+    fn parse_study_keeps_the_structured_fields() {
+        let page: serde_json::Value = serde_json::from_str(PAGE_1).unwrap();
+        let trial = parse_study(&page["studies"][1]).unwrap();
+        assert_eq!(trial.nct_id, "NCT04793958");
+        assert_eq!(trial.phase, "PHASE3");
+        assert_eq!(trial.status, "RECRUITING");
+        assert_eq!(trial.study_type, "INTERVENTIONAL");
+        assert_eq!(trial.enrollment, Some(420));
+        assert_eq!(trial.start_date, date(2021, 4, 13));
+        assert_eq!(
+            trial.sponsors,
+            ["Mirati Therapeutics Inc.", "Bristol-Myers Squibb"]
+        );
+        assert_eq!(trial.conditions, ["Metastatic Colorectal Cancer"]);
+        assert_eq!(trial.interventions.len(), 3);
+        assert_eq!(trial.interventions[0].other_names, ["MRTX849"]);
+        assert_eq!(
+            trial.interventions[1].arm_groups,
+            ["Adagrasib in combination with cetuximab"]
+        );
+        assert_eq!(trial.interventions[1].kind, "BIOLOGICAL");
+
+        // Month-precision dates and a study with no phase or enrollment.
+        let first = parse_study(&page["studies"][0]).unwrap();
+        assert_eq!(first.completion_date, date(2026, 12, 1));
+        let page: serde_json::Value = serde_json::from_str(PAGE_2).unwrap();
+        let observational = parse_study(&page["studies"][1]).unwrap();
+        assert_eq!(observational.phase, "NA");
+        assert_eq!(observational.enrollment, None);
+        assert_eq!(observational.start_date, None);
+        assert!(observational.interventions.is_empty());
+
+        assert_eq!(
+            parse_study(&serde_json::json!({"protocolSection": {}})),
+            None
+        );
+    }
+
+    #[test]
+    fn summary_from_records_counts_like_the_api() {
+        let page: serde_json::Value = serde_json::from_str(PAGE_1).unwrap();
+        let trials: Vec<TrialRecord> = page["studies"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(parse_study)
+            .collect();
+        let summary = TrialSummary::from_records("KRAS", "colorectal cancer", &trials);
+        assert_eq!(summary.total, 2);
+        assert_eq!(summary.phase3_or_later(), 2);
+        assert_eq!(summary.by_status["ACTIVE_NOT_RECRUITING"], 1);
+        assert_eq!(summary.top_trials[1].nct_id, "NCT04793958");
+    }
+
     #[test]
     fn test_clean_query_strips_tiab() {
         let raw = "KRAS[tiab] AND G12D[tiab] AND pancreatic cancer[tiab]";
@@ -192,4 +811,68 @@ mod tests {
     fn test_client_default() {
         let _c = ClinicalTrialsClient::default();
     }
+
+    fn study(nct_id: &str, phases: &[&str], status: &str) -> serde_json::Value {
+        serde_json::json!({
+            "protocolSection": {
+                "identificationModule": {"nctId": nct_id, "briefTitle": format!("Trial {nct_id}")},
+                "statusModule": {"overallStatus": status},
+                "designModule": {"phases": phases},
+            }
+        })
+    }
+
+    #[test]
+    fn test_summary_counts_highest_phase_and_status() {
+        let mut summary = TrialSummary::default();
+        summary.add_study(&study("NCT00000001", &["PHASE1", "PHASE2"], "RECRUITING"));
+        summary.add_study(&study("NCT00000002", &["PHASE3"], "COMPLETED"));
+        summary.add_study(&study("NCT00000003", &[], "RECRUITING"));
+        for i in 4..=14 {
+            summary.add_study(&study(&format!("NCT{i:08}"), &["PHASE4"], "COMPLETED"));
+        }
+
+        assert_eq!(summary.by_phase["PHASE2"], 1);
+        assert_eq!(summary.by_phase["NA"], 1);
+        assert!(!summary.by_phase.contains_key("PHASE1"));
+        assert_eq!(summary.by_status["RECRUITING"], 2);
+        assert_eq!(summary.phase3_or_later(), 12);
+        assert_eq!(summary.top_trials.len(), TOP_TRIALS);
+        assert_eq!(summary.top_trials[0].nct_id, "NCT00000001");
+        assert_eq!(summary.top_trials[0].phase, "PHASE2");
+    }
+
+    #[test]
+    fn test_trial_cache_respects_ttl() {
+        let dir = tempfile::tempdir().unwrap();
+        let summary = TrialSummary {
+            gene: "KRAS".to_string(),
+            condition: "pancreatic cancer".to_string(),
+            total: 3,
+            ..TrialSummary::default()
+        };
+        let fresh = ClinicalTrialsClient::new().with_cache(dir.path(), Duration::from_secs(3600));
+        fresh.save_trial_summary("KRAS", "PAAD", &summary);
+        assert_eq!(fresh.cached_trial_summary("KRAS", "PAAD"), Some(summary));
+        assert_eq!(fresh.cached_trial_summary("KRAS", "LUAD"), None);
+
+        let stale_entry = TrialCacheEntry {
+            cached_at_epoch_secs: now_epoch_secs() - 7200,
+            summary: TrialSummary::default(),
+        };
+        std::fs::write(
+            cache_path(dir.path(), "EGFR", "LUAD"),
+            serde_json::to_string(&stale_entry).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(fresh.cached_trial_summary("EGFR", "LUAD"), None);
+    }
+
+    #[test]
+    fn test_condition_and_inhibitor_lookup() {
+        assert_eq!(condition_for_cancer_code("paad"), "pancreatic cancer");
+        assert_eq!(condition_for_cancer_code(" glioma "), "glioma");
+        assert!(known_inhibitors("kras").contains(&"sotorasib"));
+        assert!(known_inhibitors("NOVEL1").is_empty());
+    }
 }
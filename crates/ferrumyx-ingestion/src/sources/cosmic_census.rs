@@ -0,0 +1,627 @@
+//! COSMIC Cancer Mutation Census (CMC) hotspot index.
+//!
+//! COSMIC downloads sit behind a licence and login, so the census is read
+//! from a local export the user supplies (`FERRUMYX_COSMIC_CENSUS_PATH`,
+//! set from `ranker.providers.cosmic.census_path`). Both the current CMC TSV
+//! and older comma-separated exports are accepted; the parser tolerates the
+//! formats seen across releases:
+//! - a UTF-8 byte order mark, `#` comment lines, a `#`-prefixed header, CRLF
+//!   line endings and stray non-UTF-8 bytes;
+//! - column names differing in case and punctuation (`Mutation AA`,
+//!   `AA Mutation`, `COSMIC_SAMPLE_MUTATED`, `Mutated samples`, ...);
+//! - transcript-suffixed gene names (`KRAS_ENST00000311936`);
+//! - one- or three-letter protein changes, with or without the `p.` prefix;
+//! - counts written as `1,204` or `861.0`, and `NS`/`NA` placeholders;
+//! - tiers written as `1`, `Tier 1` or `Non-CGC`;
+//! - disease lists such as `pancreas=6210;lung=1180` or
+//!   `breast(1,020); large_intestine(184)`.
+//!
+//! Rows repeated per transcript are folded into one hotspot per protein
+//! change.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+
+/// Environment variable holding the path of the local CMC export.
+pub const CENSUS_PATH_ENV: &str = "FERRUMYX_COSMIC_CENSUS_PATH";
+
+/// Mutated samples a hotspot in a Cancer Gene Census gene needs to count as
+/// a known driver when the export carries no significance tier.
+pub const RECURRENT_DRIVER_MIN_SAMPLES: u32 = 10;
+
+/// Lines searched for a header before the file is rejected.
+const MAX_PREAMBLE_LINES: usize = 64;
+
+const GENE_HEADERS: &[&str] = &["gene_name", "gene_symbol", "gene", "symbol", "hgnc_symbol"];
+const AA_HEADERS: &[&str] = &[
+    "mutation_aa",
+    "aa_mutation",
+    "aa_change",
+    "protein_change",
+    "hgvsp",
+    "mutation_protein",
+];
+const SAMPLE_HEADERS: &[&str] = &[
+    "cosmic_sample_mutated",
+    "mutated_samples",
+    "samples_mutated",
+    "mutated_sample_count",
+    "sample_count",
+    "count",
+];
+const DISEASE_HEADERS: &[&str] = &[
+    "disease",
+    "primary_site",
+    "primary_sites",
+    "cancer_types",
+    "cancer_type",
+    "tissue",
+];
+const CGC_TIER_HEADERS: &[&str] = &["cgc_tier", "census_tier", "tier"];
+const SIGNIFICANCE_HEADERS: &[&str] = &["mutation_significance_tier", "significance_tier"];
+
+/// One recurrent protein change in a gene, as recorded by the census.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HotspotRecord {
+    /// One-letter protein change without the `p.` prefix, e.g. `G12D`.
+    pub aa_change: String,
+    /// COSMIC samples carrying the change.
+    pub sample_count: u32,
+    /// Tissues or diseases the change was seen in, lower-cased and sorted.
+    pub cancer_types: Vec<String>,
+    /// Cancer Gene Census tier of the gene (1 or 2); `None` outside the CGC.
+    pub cgc_tier: Option<u8>,
+    /// CMC mutation significance tier (1 = highest); `None` when the export
+    /// has no such column or marks the change `Other`.
+    pub significance_tier: Option<u8>,
+}
+
+/// Census hotspots indexed by gene symbol and protein change.
+#[derive(Debug, Clone, Default)]
+pub struct CosmicCensus {
+    by_gene: HashMap<String, BTreeMap<String, HotspotRecord>>,
+    /// Whether the export carried a significance tier column.
+    significance_tiers: bool,
+}
+
+impl CosmicCensus {
+    /// Path of the configured census export, if one is set.
+    pub fn configured_path() -> Option<PathBuf> {
+        std::env::var(CENSUS_PATH_ENV)
+            .ok()
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .map(PathBuf::from)
+    }
+
+    /// Load the census at [`Self::configured_path`]; `Ok(None)` when no
+    /// path is configured.
+    pub fn load_configured() -> anyhow::Result<Option<Self>> {
+        Self::configured_path()
+            .map(|path| Self::load(&path))
+            .transpose()
+    }
+
+    /// Read a census export from disk.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Failed to open COSMIC census {:?}", path))?;
+        Self::from_reader(std::io::BufReader::new(file))
+            .with_context(|| format!("Failed to parse COSMIC census {:?}", path))
+    }
+
+    /// Parse a census export held in memory.
+    pub fn parse(content: &str) -> anyhow::Result<Self> {
+        Self::from_reader(content.as_bytes())
+    }
+
+    /// Parse a census export line by line.
+    pub fn from_reader(mut reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut buf = Vec::new();
+        let mut next_line = |reader: &mut dyn BufRead| -> anyhow::Result<Option<String>> {
+            buf.clear();
+            if reader.read_until(b'\n', &mut buf)? == 0 {
+                return Ok(None);
+            }
+            let line = String::from_utf8_lossy(&buf);
+            let line = line.trim_start_matches('\u{feff}');
+            Ok(Some(line.trim_end_matches(['\r', '\n']).to_string()))
+        };
+
+        let mut columns = None;
+        for _ in 0..MAX_PREAMBLE_LINES {
+            let Some(line) = next_line(&mut reader)? else {
+                break;
+            };
+            let header = line.trim().trim_start_matches('#').trim();
+            if header.is_empty() {
+                continue;
+            }
+            if let Some(found) = Columns::detect(header) {
+                columns = Some(found);
+                break;
+            }
+            if !line.trim_start().starts_with('#') {
+                bail!("COSMIC census header has no gene and protein change columns");
+            }
+        }
+        let Some(columns) = columns else {
+            bail!("COSMIC census has no header line");
+        };
+
+        let mut census = Self {
+            significance_tiers: columns.significance.is_some(),
+            ..Self::default()
+        };
+        while let Some(line) = next_line(&mut reader)? {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some((gene, record)) = columns.record(&split_fields(&line, columns.delimiter)) {
+                census.insert(gene, record);
+            }
+        }
+        Ok(census)
+    }
+
+    /// Hotspots recorded for `gene`, most frequently mutated first.
+    pub fn hotspots_for_gene(&self, gene: &str) -> Vec<HotspotRecord> {
+        let mut hotspots: Vec<HotspotRecord> = self
+            .by_gene
+            .get(&normalise_gene(gene))
+            .map(|by_change| by_change.values().cloned().collect())
+            .unwrap_or_default();
+        hotspots.sort_by(|a, b| {
+            b.sample_count
+                .cmp(&a.sample_count)
+                .then_with(|| a.aa_change.cmp(&b.aa_change))
+        });
+        hotspots
+    }
+
+    /// The census entry for one protein change (`G12D`, `p.G12D` or
+    /// `p.Gly12Asp`) in `gene`.
+    pub fn hotspot(&self, gene: &str, aa_change: &str) -> Option<&HotspotRecord> {
+        self.by_gene
+            .get(&normalise_gene(gene))?
+            .get(&normalise_aa_change(aa_change)?)
+    }
+
+    /// Whether `aa_change` in `gene` is an established driver.
+    pub fn is_known_driver(&self, gene: &str, aa_change: &str) -> bool {
+        self.hotspot(gene, aa_change)
+            .is_some_and(|record| self.is_driver_record(record))
+    }
+
+    /// Significance tier 1 or 2 when the export is tiered; otherwise a
+    /// recurrent change in a Cancer Gene Census gene.
+    pub fn is_driver_record(&self, record: &HotspotRecord) -> bool {
+        if self.significance_tiers {
+            record.significance_tier.is_some_and(|tier| tier <= 2)
+        } else {
+            record.cgc_tier.is_some() && record.sample_count >= RECURRENT_DRIVER_MIN_SAMPLES
+        }
+    }
+
+    /// Genes with at least one hotspot, sorted.
+    pub fn genes(&self) -> Vec<&str> {
+        let mut genes: Vec<&str> = self.by_gene.keys().map(String::as_str).collect();
+        genes.sort_unstable();
+        genes
+    }
+
+    /// Number of (gene, protein change) hotspots.
+    pub fn len(&self) -> usize {
+        self.by_gene.values().map(BTreeMap::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_gene.is_empty()
+    }
+
+    /// Fold `record` into the stored hotspot: the same change listed under
+    /// several transcripts keeps the largest count, every cancer type and
+    /// the strongest tiers.
+    fn insert(&mut self, gene: String, record: HotspotRecord) {
+        let by_change = self.by_gene.entry(gene).or_default();
+        let Some(stored) = by_change.get_mut(&record.aa_change) else {
+            by_change.insert(record.aa_change.clone(), record);
+            return;
+        };
+        stored.sample_count = stored.sample_count.max(record.sample_count);
+        let cancer_types: BTreeSet<String> = stored
+            .cancer_types
+            .drain(..)
+            .chain(record.cancer_types)
+            .collect();
+        stored.cancer_types = cancer_types.into_iter().collect();
+        stored.cgc_tier = min_tier(stored.cgc_tier, record.cgc_tier);
+        stored.significance_tier = min_tier(stored.significance_tier, record.significance_tier);
+    }
+}
+
+/// Column positions found in the header.
+#[derive(Debug, Clone, Copy)]
+struct Columns {
+    delimiter: char,
+    gene: usize,
+    aa_change: usize,
+    samples: Option<usize>,
+    disease: Option<usize>,
+    cgc_tier: Option<usize>,
+    significance: Option<usize>,
+}
+
+impl Columns {
+    fn detect(header: &str) -> Option<Self> {
+        let delimiter = if header.contains('\t') { '\t' } else { ',' };
+        let headers = split_fields(header, delimiter);
+        let find = |candidates: &[&str]| {
+            candidates.iter().find_map(|candidate| {
+                let key = header_key(candidate);
+                headers.iter().position(|h| header_key(h) == key)
+            })
+        };
+        Some(Self {
+            delimiter,
+            gene: find(GENE_HEADERS)?,
+            aa_change: find(AA_HEADERS)?,
+            samples: find(SAMPLE_HEADERS),
+            disease: find(DISEASE_HEADERS),
+            cgc_tier: find(CGC_TIER_HEADERS),
+            significance: find(SIGNIFICANCE_HEADERS),
+        })
+    }
+
+    fn record(&self, fields: &[String]) -> Option<(String, HotspotRecord)> {
+        let field = |i: Option<usize>| i.and_then(|i| fields.get(i)).map(String::as_str);
+        let gene = normalise_gene(field(Some(self.gene))?);
+        let aa_change = normalise_aa_change(field(Some(self.aa_change))?)?;
+        if gene.is_empty() {
+            return None;
+        }
+        Some((
+            gene,
+            HotspotRecord {
+                aa_change,
+                sample_count: field(self.samples).and_then(parse_count).unwrap_or(0),
+                cancer_types: field(self.disease)
+                    .map(parse_cancer_types)
+                    .unwrap_or_default(),
+                cgc_tier: field(self.cgc_tier).and_then(parse_tier),
+                significance_tier: field(self.significance).and_then(parse_tier),
+            },
+        ))
+    }
+}
+
+fn header_key(header: &str) -> String {
+    header
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+/// Split one row, honouring double quotes (with `""` escapes) so quoted
+/// commas stay inside their field.
+fn split_fields(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => {
+                fields.push(field.trim().to_string());
+                field.clear();
+            }
+            c => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+/// Upper-cased gene symbol without a transcript suffix.
+fn normalise_gene(gene: &str) -> String {
+    let gene = gene.trim();
+    let gene = match gene.split_once('_') {
+        Some((symbol, transcript)) if transcript.to_ascii_uppercase().starts_with("ENST") => symbol,
+        _ => gene,
+    };
+    gene.to_ascii_uppercase()
+}
+
+const THREE_LETTER_AMINO_ACIDS: &[(&str, char)] = &[
+    ("Ala", 'A'),
+    ("Arg", 'R'),
+    ("Asn", 'N'),
+    ("Asp", 'D'),
+    ("Cys", 'C'),
+    ("Gln", 'Q'),
+    ("Glu", 'E'),
+    ("Gly", 'G'),
+    ("His", 'H'),
+    ("Ile", 'I'),
+    ("Leu", 'L'),
+    ("Lys", 'K'),
+    ("Met", 'M'),
+    ("Phe", 'F'),
+    ("Pro", 'P'),
+    ("Ser", 'S'),
+    ("Thr", 'T'),
+    ("Trp", 'W'),
+    ("Tyr", 'Y'),
+    ("Val", 'V'),
+    ("Ter", '*'),
+    ("Sec", 'U'),
+    ("Pyl", 'O'),
+    ("Xaa", 'X'),
+];
+
+fn one_letter(code: &str) -> Option<char> {
+    THREE_LETTER_AMINO_ACIDS
+        .iter()
+        .find(|(three, _)| three.eq_ignore_ascii_case(code))
+        .map(|(_, one)| *one)
+}
+
+/// One-letter, upper-cased protein change without `p.`; `None` for empty,
+/// unknown (`p.?`) and synonymous (`p.=`, `p.G12=`) changes.
+pub fn normalise_aa_change(change: &str) -> Option<String> {
+    let change = change.trim();
+    let change = change
+        .strip_prefix("p.")
+        .or_else(|| change.strip_prefix("P."))
+        .unwrap_or(change)
+        .trim_matches(|c| c == '(' || c == ')');
+    if change.is_empty()
+        || change.contains(['?', '='])
+        || matches!(change.to_ascii_uppercase().as_str(), "NS" | "NA" | "-")
+    {
+        return None;
+    }
+
+    let chars: Vec<char> = change.chars().collect();
+    let mut out = String::with_capacity(change.len());
+    if chars.iter().any(char::is_ascii_lowercase) {
+        // HGVS three-letter codes are capitalised (`Gly12Asp`), which keeps
+        // them apart from lower-case edit words like `del` and `fs`.
+        let mut i = 0;
+        while i < chars.len() {
+            let code: String = chars[i..(i + 3).min(chars.len())].iter().collect();
+            match one_letter(&code) {
+                Some(one)
+                    if code.len() == 3
+                        && chars[i].is_ascii_uppercase()
+                        && chars[i + 1..i + 3].iter().all(char::is_ascii_lowercase) =>
+                {
+                    out.push(one);
+                    i += 3;
+                }
+                _ => {
+                    out.push(chars[i]);
+                    i += 1;
+                }
+            }
+        }
+    } else {
+        // All capitals: only a run of exactly three letters can be a code.
+        let mut run = String::new();
+        for c in chars.into_iter().chain(std::iter::once('\0')) {
+            if c.is_ascii_alphabetic() {
+                run.push(c);
+                continue;
+            }
+            match one_letter(&run) {
+                Some(one) if run.len() == 3 => out.push(one),
+                _ => out.push_str(&run),
+            }
+            run.clear();
+            if c != '\0' {
+                out.push(c);
+            }
+        }
+    }
+    Some(out.to_ascii_uppercase())
+}
+
+/// Sample count from `4102`, `1,204` or `861.0`; `None` for placeholders.
+fn parse_count(raw: &str) -> Option<u32> {
+    let cleaned: String = raw.trim().chars().filter(|c| *c != ',').collect();
+    let value = cleaned.parse::<f64>().ok()?;
+    (value.is_finite() && value >= 0.0).then(|| value.round().min(u32::MAX as f64) as u32)
+}
+
+/// Tier number from `1`, `1.0` or `Tier 1`; `None` for `Other`, `Non-CGC`
+/// and blanks.
+fn parse_tier(raw: &str) -> Option<u8> {
+    let raw = raw.trim().to_ascii_lowercase();
+    if raw.starts_with("non") {
+        return None;
+    }
+    raw.chars()
+        .find(char::is_ascii_digit)
+        .and_then(|c| c.to_digit(10))
+        .filter(|tier| (1..=3).contains(tier))
+        .map(|tier| tier as u8)
+}
+
+/// Disease names from `pancreas=6210;lung=1180`, `breast(1,020); colon(9)`
+/// or `lung:3|skin:2`, without their counts.
+fn parse_cancer_types(raw: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut item = String::new();
+    let mut depth = 0usize;
+    for c in raw.chars() {
+        match c {
+            '(' => {
+                depth += 1;
+                item.push(c);
+            }
+            ')' => {
+                depth = depth.saturating_sub(1);
+                item.push(c);
+            }
+            ';' | '|' | ',' if depth == 0 => items.push(std::mem::take(&mut item)),
+            c => item.push(c),
+        }
+    }
+    items.push(item);
+
+    let names: BTreeSet<String> = items
+        .iter()
+        .filter_map(|item| {
+            let name = item.split(['(', '=', ':']).next()?.trim().to_lowercase();
+            let placeholder = matches!(name.as_str(), "" | "ns" | "na" | "-");
+            (!placeholder).then_some(name)
+        })
+        .collect();
+    names.into_iter().collect()
+}
+
+fn min_tier(a: Option<u8>, b: Option<u8>) -> Option<u8> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CMC_TSV: &str = include_str!("../../tests/fixtures/cosmic_cmc_sample.tsv");
+    const LEGACY_CSV: &str = include_str!("../../tests/fixtures/cosmic_cmc_legacy.csv");
+
+    #[test]
+    fn parses_cmc_tsv_and_folds_transcripts() {
+        let census = CosmicCensus::parse(CMC_TSV).unwrap();
+        assert_eq!(census.genes(), ["BRAF", "KRAS", "NOTCH1", "TP53", "TTN"]);
+        assert_eq!(census.len(), 9);
+
+        let kras = census.hotspots_for_gene("kras");
+        let changes: Vec<&str> = kras.iter().map(|h| h.aa_change.as_str()).collect();
+        assert_eq!(changes, ["G12D", "G12C", "A146T"]);
+        assert_eq!(
+            kras[0],
+            HotspotRecord {
+                aa_change: "G12D".to_string(),
+                sample_count: 16523,
+                cancer_types: vec![
+                    "biliary_tract".to_string(),
+                    "large_intestine".to_string(),
+                    "lung".to_string(),
+                    "pancreas".to_string(),
+                ],
+                cgc_tier: Some(1),
+                significance_tier: Some(1),
+            }
+        );
+
+        let r248q = census.hotspot("TP53", "R248Q").unwrap();
+        assert!(r248q.cancer_types.is_empty());
+        let notch = census.hotspot("NOTCH1", "L1575P").unwrap();
+        assert_eq!(notch.sample_count, 0);
+        assert_eq!(notch.significance_tier, Some(3));
+        assert_eq!(census.hotspot("TTN", "A100V").unwrap().cgc_tier, None);
+        assert!(census.hotspots_for_gene("EGFR").is_empty());
+    }
+
+    #[test]
+    fn parses_legacy_csv_with_comments_quotes_and_three_letter_codes() {
+        let census = CosmicCensus::parse(LEGACY_CSV).unwrap();
+        let pik3ca = census.hotspots_for_gene("PIK3CA");
+        assert_eq!(pik3ca.len(), 2);
+        assert_eq!(pik3ca[0].aa_change, "H1047R");
+        assert_eq!(pik3ca[0].sample_count, 1204);
+        assert_eq!(pik3ca[0].cancer_types, ["breast", "large_intestine"]);
+        assert_eq!(pik3ca[0].cgc_tier, Some(1));
+        assert_eq!(pik3ca[0].significance_tier, None);
+        assert_eq!(pik3ca[1].aa_change, "E545K");
+        assert_eq!(pik3ca[1].sample_count, 861);
+
+        assert_eq!(census.hotspot("KRAS", "G12D").unwrap().sample_count, 4010);
+        assert!(census.hotspot("EGFR", "p.E746_A750del").is_some());
+        assert_eq!(census.hotspot("NRXN1", "R8C").unwrap().cgc_tier, None);
+    }
+
+    #[test]
+    fn known_drivers_follow_significance_then_cgc_recurrence() {
+        let cmc = CosmicCensus::parse(CMC_TSV).unwrap();
+        assert!(cmc.is_known_driver("KRAS", "G12D"));
+        assert!(cmc.is_known_driver("KRAS", "p.Gly12Asp"));
+        assert!(cmc.is_known_driver("KRAS", "A146T"));
+        assert!(!cmc.is_known_driver("NOTCH1", "L1575P"));
+        assert!(!cmc.is_known_driver("TTN", "A100V"));
+        assert!(!cmc.is_known_driver("TP53", "P72R"));
+        assert!(!cmc.is_known_driver("KRAS", "Q61H"));
+
+        let legacy = CosmicCensus::parse(LEGACY_CSV).unwrap();
+        assert!(legacy.is_known_driver("PIK3CA", "H1047R"));
+        assert!(legacy.is_known_driver("EGFR", "E746_A750DEL"));
+        assert!(!legacy.is_known_driver("EGFR", "L858R"));
+        assert!(!legacy.is_known_driver("NRXN1", "R8C"));
+    }
+
+    #[test]
+    fn rejects_files_without_gene_and_change_columns() {
+        assert!(CosmicCensus::parse("").is_err());
+        assert!(CosmicCensus::parse("gene\tcancer_code\nKRAS\tPAAD\n").is_err());
+        assert!(CosmicCensus::parse("# only a comment\n").is_err());
+    }
+
+    #[test]
+    fn normalises_protein_changes() {
+        let cases = [
+            ("p.G12D", Some("G12D")),
+            ("g12d", Some("G12D")),
+            ("p.Gly12Asp", Some("G12D")),
+            ("GLY12ASP", Some("G12D")),
+            ("p.(Val600Glu)", Some("V600E")),
+            ("p.Arg213Ter", Some("R213*")),
+            ("p.Gly12fsTer5", Some("G12FS*5")),
+            ("p.E746_A750del", Some("E746_A750DEL")),
+            ("p.Glu746_Ala750del", Some("E746_A750DEL")),
+            ("p.?", None),
+            ("p.=", None),
+            ("p.G12=", None),
+            ("NS", None),
+            ("", None),
+        ];
+        for (raw, expected) in cases {
+            assert_eq!(normalise_aa_change(raw).as_deref(), expected, "{raw}");
+        }
+    }
+
+    #[test]
+    fn parses_quirky_cells() {
+        assert_eq!(parse_count("1,204"), Some(1204));
+        assert_eq!(parse_count("861.0"), Some(861));
+        assert_eq!(parse_count("NS"), None);
+        assert_eq!(parse_tier("Tier 2"), Some(2));
+        assert_eq!(parse_tier("1.0"), Some(1));
+        assert_eq!(parse_tier("Non-CGC"), None);
+        assert_eq!(parse_tier("Other"), None);
+        assert_eq!(
+            parse_cancer_types("lung:3|Skin:2; lung=1"),
+            ["lung", "skin"]
+        );
+        assert_eq!(normalise_gene("KRAS_ENST00000311936"), "KRAS");
+        assert_eq!(normalise_gene("HLA_A"), "HLA_A");
+        assert_eq!(
+            split_fields(r#""a, b",c,"say ""hi""""#, ','),
+            ["a, b", "c", r#"say "hi""#]
+        );
+    }
+}
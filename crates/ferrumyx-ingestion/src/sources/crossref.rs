@@ -1,26 +1,66 @@
 //! CrossRef DOI resolution client.
 //!
-//! Used for two purposes:
+//! Used for three purposes:
 //! 1. Resolving bare DOIs to full metadata (title, authors, journal, date)
 //! 2. Enriching papers from other sources that have DOI but no abstract
+//! 3. Finding the DOI and citation count of papers ingested without one
 //!
 //! API: https://api.crossref.org/works/{doi}
 //! Polite pool: set User-Agent with mailto (see CrossRef etiquette)
 
 use async_trait::async_trait;
-use chrono::NaiveDate;
-use reqwest::Client;
+use chrono::{Datelike, NaiveDate};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
 use tracing::{debug, instrument};
 
 use super::LiteratureSource;
 use crate::models::{Author, IngestionSource, PaperMetadata};
 
-const CR_API_BASE: &str = "https://api.crossref.org/works";
-const CR_SEARCH_URL: &str = "https://api.crossref.org/works";
+const CR_API_BASE: &str = "https://api.crossref.org";
 const USER_AGENT: &str = "Ferrumyx/0.1 (mailto:ferrumyx@example.com)";
+/// Candidates fetched per metadata lookup.
+const MATCH_CANDIDATES: usize = 5;
+/// Title similarity (Jaro-Winkler) a candidate needs to count as a match.
+/// Same bar as the fuzzy duplicate check in `dedup`.
+const MATCH_THRESHOLD: f64 = 0.92;
 
 pub struct CrossRefClient {
     client: Client,
+    base_url: String,
+    mailto: Option<String>,
+    /// Minimum spacing between requests.
+    min_interval: Duration,
+    last_request: Mutex<Option<Instant>>,
+    /// On-disk response cache; `None` disables it.
+    cache_dir: Option<PathBuf>,
+    cache_ttl: Duration,
+}
+
+/// Best CrossRef work for a title/author/year lookup.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DoiMatch {
+    pub doi: String,
+    /// Title similarity in `[MATCH_THRESHOLD, 1.0]`.
+    pub score: f64,
+    pub citation_count: Option<u64>,
+    pub journal: Option<String>,
+    pub volume: Option<String>,
+    pub issue: Option<String>,
+    pub pages: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedResponse {
+    cached_at_epoch_secs: u64,
+    /// `None` records a 404 so unknown DOIs are not asked for again.
+    body: Option<serde_json::Value>,
 }
 
 impl CrossRefClient {
@@ -29,20 +69,147 @@ impl CrossRefClient {
             .user_agent(USER_AGENT)
             .build()
             .unwrap_or_else(|_| Client::new());
-        Self { client }
+        Self {
+            client,
+            base_url: CR_API_BASE.to_string(),
+            mailto: None,
+            min_interval: Duration::from_millis(200),
+            last_request: Mutex::new(None),
+            cache_dir: None,
+            cache_ttl: Duration::from_secs(7 * 86_400),
+        }
+    }
+
+    /// Identify as `mailto` so requests go to CrossRef's polite pool.
+    pub fn with_mailto(mut self, mailto: Option<String>) -> Self {
+        self.mailto = mailto
+            .map(|m| m.trim().to_string())
+            .filter(|m| !m.is_empty());
+        if let Some(mailto) = &self.mailto {
+            if let Ok(client) = Client::builder()
+                .user_agent(format!("Ferrumyx/0.1 (mailto:{mailto})"))
+                .build()
+            {
+                self.client = client;
+            }
+        }
+        self
+    }
+
+    /// Space requests so no more than `rps` are sent per second.
+    pub fn with_requests_per_second(mut self, rps: u32) -> Self {
+        self.min_interval = Duration::from_secs(1) / rps.max(1);
+        self
+    }
+
+    /// Cache responses as JSON files under `dir` for `ttl`.
+    pub fn with_cache_dir(mut self, dir: impl Into<PathBuf>, ttl: Duration) -> Self {
+        self.cache_dir = Some(dir.into());
+        self.cache_ttl = ttl;
+        self
+    }
+
+    /// Send requests to `base_url` instead of the public API.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
     }
 
     /// Resolve a single DOI → PaperMetadata.
     #[instrument(skip(self))]
     pub async fn resolve_doi(&self, doi: &str) -> anyhow::Result<Option<PaperMetadata>> {
-        let url = format!("{}/{}", CR_API_BASE, doi);
-        let resp = self.client.get(&url).send().await?;
-        if !resp.status().is_success() {
+        Ok(self
+            .get_work(doi)
+            .await?
+            .map(|body| work_to_paper(&body["message"])))
+    }
+
+    /// `is-referenced-by-count` for `doi`, or `None` if CrossRef does not
+    /// know the DOI.
+    #[instrument(skip(self))]
+    pub async fn get_citation_count(&self, doi: &str) -> anyhow::Result<Option<u64>> {
+        Ok(self
+            .get_work(doi)
+            .await?
+            .and_then(|body| body["message"]["is-referenced-by-count"].as_u64()))
+    }
+
+    /// Publisher PDF link CrossRef holds for `doi`, if any.
+    #[instrument(skip(self))]
+    pub async fn publisher_pdf_url(&self, doi: &str) -> anyhow::Result<Option<String>> {
+        Ok(self
+            .get_work(doi)
+            .await?
+            .and_then(|body| pdf_link(&body["message"])))
+    }
+
+    /// DOIs in the reference list CrossRef holds for `doi`, in order.
+    /// References deposited without a DOI are skipped.
+    pub async fn get_references(&self, doi: &str) -> anyhow::Result<Vec<String>> {
+        let Some(body) = self.get_work(doi).await? else {
+            return Ok(Vec::new());
+        };
+        Ok(body["message"]["reference"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|r| r["DOI"].as_str())
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// Find the DOI of the work with this title, first author and year.
+    /// Returns the closest candidate whose title similarity reaches the
+    /// match threshold, whose first author shares the surname and whose
+    /// year is within one of `year`; checks are skipped for missing inputs.
+    #[instrument(skip(self))]
+    pub async fn resolve_by_metadata(
+        &self,
+        title: &str,
+        first_author: Option<&str>,
+        year: Option<i32>,
+    ) -> anyhow::Result<Option<DoiMatch>> {
+        if normalise_title(title).is_empty() {
             return Ok(None);
         }
-        let body: serde_json::Value = resp.json().await?;
-        let work = &body["message"];
-        Ok(Some(work_to_paper(work)))
+        let mut params = vec![
+            ("query.bibliographic", title.trim().to_string()),
+            ("rows", MATCH_CANDIDATES.to_string()),
+            (
+                "select",
+                "DOI,title,author,published,is-referenced-by-count,container-title,volume,issue,page"
+                    .to_string(),
+            ),
+        ];
+        if let Some(author) = first_author.filter(|a| !a.trim().is_empty()) {
+            params.push(("query.author", author.trim().to_string()));
+        }
+        if let Some(year) = year {
+            params.push((
+                "filter",
+                format!("from-pub-date:{},until-pub-date:{}", year - 1, year + 1),
+            ));
+        }
+
+        let Some(body) = self.get_json("works", &params).await? else {
+            return Ok(None);
+        };
+        let items = body["message"]["items"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        let best = items
+            .iter()
+            .filter_map(|work| match_work(work, title, first_author, year))
+            .max_by(|a, b| a.score.total_cmp(&b.score));
+        debug!(
+            candidates = items.len(),
+            matched = best.is_some(),
+            "CrossRef metadata lookup"
+        );
+        Ok(best)
     }
 
     /// Search CrossRef by free-text query.
@@ -53,27 +220,88 @@ impl CrossRefClient {
         max_results: usize,
     ) -> anyhow::Result<Vec<serde_json::Value>> {
         let clean = query.replace("[tiab]", "").replace(" AND ", " ");
-        let resp = self
-            .client
-            .get(CR_SEARCH_URL)
-            .query(&[
-                ("query", clean.trim()),
-                ("rows", &max_results.to_string()),
-                (
-                    "select",
-                    "DOI,title,abstract,author,container-title,published,type",
-                ),
-            ])
-            .send()
-            .await?
-            .json::<serde_json::Value>()
-            .await?;
+        let params = [
+            ("query", clean.trim().to_string()),
+            ("rows", max_results.to_string()),
+            (
+                "select",
+                "DOI,title,abstract,author,container-title,published,type".to_string(),
+            ),
+        ];
+        let resp = self.get_json("works", &params).await?.unwrap_or_default();
 
         Ok(resp["message"]["items"]
             .as_array()
             .cloned()
             .unwrap_or_default())
     }
+
+    async fn get_work(&self, doi: &str) -> anyhow::Result<Option<serde_json::Value>> {
+        let doi = doi.trim();
+        if doi.is_empty() {
+            return Ok(None);
+        }
+        self.get_json(&format!("works/{doi}"), &[]).await
+    }
+
+    /// GET `{base_url}/{path}`, served from the cache when fresh. A 404 is
+    /// `Ok(None)`; other failures are errors and are not cached.
+    async fn get_json(
+        &self,
+        path: &str,
+        params: &[(&str, String)],
+    ) -> anyhow::Result<Option<serde_json::Value>> {
+        let url = format!("{}/{}", self.base_url, path);
+        let mut params = params.to_vec();
+        if let Some(mailto) = &self.mailto {
+            params.push(("mailto", mailto.clone()));
+        }
+
+        let cache_path = self.cache_path(&url, &params);
+        if let Some(cached) = cache_path.as_ref().and_then(|p| self.load_cached(p)) {
+            return Ok(cached.body);
+        }
+
+        self.throttle().await;
+        let resp = self.client.get(&url).query(&params).send().await?;
+        let body = if resp.status() == StatusCode::NOT_FOUND {
+            None
+        } else {
+            Some(resp.error_for_status()?.json::<serde_json::Value>().await?)
+        };
+
+        if let Some(path) = cache_path {
+            save_cached(&path, &body);
+        }
+        Ok(body)
+    }
+
+    /// Wait until `min_interval` has passed since the previous request.
+    async fn throttle(&self) {
+        let mut last = self.last_request.lock().await;
+        if let Some(at) = *last {
+            tokio::time::sleep_until(at + self.min_interval).await;
+        }
+        *last = Some(Instant::now());
+    }
+
+    fn cache_path(&self, url: &str, params: &[(&str, String)]) -> Option<PathBuf> {
+        let dir = self.cache_dir.as_ref()?;
+        let mut hasher = DefaultHasher::new();
+        url.hash(&mut hasher);
+        for (key, value) in params.iter().filter(|(key, _)| *key != "mailto") {
+            key.hash(&mut hasher);
+            value.hash(&mut hasher);
+        }
+        Some(dir.join(format!("{:016x}.json", hasher.finish())))
+    }
+
+    fn load_cached(&self, path: &Path) -> Option<CachedResponse> {
+        let payload = std::fs::read_to_string(path).ok()?;
+        let entry: CachedResponse = serde_json::from_str(&payload).ok()?;
+        let age = now_epoch_secs().saturating_sub(entry.cached_at_epoch_secs);
+        (age <= self.cache_ttl.as_secs()).then_some(entry)
+    }
 }
 
 impl Default for CrossRefClient {
@@ -82,6 +310,26 @@ impl Default for CrossRefClient {
     }
 }
 
+fn save_cached(path: &Path, body: &Option<serde_json::Value>) {
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    let entry = CachedResponse {
+        cached_at_epoch_secs: now_epoch_secs(),
+        body: body.clone(),
+    };
+    if let Ok(payload) = serde_json::to_string(&entry) {
+        let _ = std::fs::write(path, payload);
+    }
+}
+
+fn now_epoch_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|v| v.as_secs())
+        .unwrap_or(0)
+}
+
 #[async_trait]
 impl LiteratureSource for CrossRefClient {
     async fn search(&self, query: &str, max_results: usize) -> anyhow::Result<Vec<PaperMetadata>> {
@@ -173,20 +421,238 @@ fn work_to_paper(work: &serde_json::Value) -> PaperMetadata {
             .as_array()
             .map(|l| !l.is_empty())
             .unwrap_or(false),
-        full_text_url: work["link"].as_array().and_then(|links| {
-            links
-                .iter()
-                .find(|l| l["content-type"].as_str() == Some("application/pdf"))
-                .and_then(|l| l["URL"].as_str())
-                .map(String::from)
-        }),
+        full_text_url: pdf_link(work),
+        is_review: false,
+        preprint: None,
     }
 }
 
+/// The first `link[]` CrossRef lists as `application/pdf`.
+fn pdf_link(work: &serde_json::Value) -> Option<String> {
+    work["link"].as_array().and_then(|links| {
+        links
+            .iter()
+            .find(|l| l["content-type"].as_str() == Some("application/pdf"))
+            .and_then(|l| l["URL"].as_str())
+            .map(String::from)
+    })
+}
+
+// ── Metadata matching ─────────────────────────────────────────────────────
+
+/// Score `work` against the lookup inputs. `None` if it fails a check.
+fn match_work(
+    work: &serde_json::Value,
+    title: &str,
+    first_author: Option<&str>,
+    year: Option<i32>,
+) -> Option<DoiMatch> {
+    let doi = work["DOI"].as_str()?.trim();
+    let candidate_title = work["title"].as_array()?.first()?.as_str()?;
+    let score = strsim::jaro_winkler(&normalise_title(title), &normalise_title(candidate_title));
+    if doi.is_empty() || score < MATCH_THRESHOLD {
+        return None;
+    }
+
+    if let Some(surname) = first_author.and_then(surname) {
+        let candidate = work["author"]
+            .as_array()
+            .and_then(|a| a.first())
+            .and_then(|a| a["family"].as_str())
+            .map(|f| f.trim().to_lowercase());
+        if candidate.is_some_and(|c| c != surname) {
+            return None;
+        }
+    }
+
+    if let Some(year) = year {
+        let candidate = work_to_paper(work).pub_date.map(|d| d.year());
+        if candidate.is_some_and(|c| (c - year).abs() > 1) {
+            return None;
+        }
+    }
+
+    let first_string = |key: &str| {
+        work[key]
+            .as_array()
+            .and_then(|v| v.first())
+            .and_then(|v| v.as_str())
+            .map(String::from)
+    };
+    Some(DoiMatch {
+        doi: doi.to_string(),
+        score,
+        citation_count: work["is-referenced-by-count"].as_u64(),
+        journal: first_string("container-title"),
+        volume: work["volume"].as_str().map(String::from),
+        issue: work["issue"].as_str().map(String::from),
+        pages: work["page"].as_str().map(String::from),
+    })
+}
+
+/// Lowercase alphanumeric words separated by single spaces.
+fn normalise_title(title: &str) -> String {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Surname from "Given Family", the form authors are stored in.
+fn surname(author: &str) -> Option<String> {
+    author
+        .split_whitespace()
+        .last()
+        .map(|s| {
+            s.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .filter(|s| !s.is_empty())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Serve `responses` in order, one connection each. Returns the base
+    /// URL and the request lines received.
+    async fn serve(
+        responses: Vec<(&'static str, String)>,
+    ) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let mut seen = Vec::new();
+            for (status, body) in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let request = String::from_utf8_lossy(&request).to_string();
+                seen.push(request.lines().next().unwrap_or_default().to_string());
+                let head = format!(
+                    "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                socket.write_all(head.as_bytes()).await.unwrap();
+                socket.write_all(body.as_bytes()).await.unwrap();
+                socket.shutdown().await.unwrap();
+            }
+            seen
+        });
+        (url, handle)
+    }
+
+    fn test_client(url: String) -> CrossRefClient {
+        let mut client =
+            CrossRefClient::new().with_mailto(Some(" curator@example.org ".to_string()));
+        client.base_url = url;
+        client.min_interval = Duration::ZERO;
+        client
+    }
+
+    #[tokio::test]
+    async fn resolves_doi_by_metadata_and_caches_responses() {
+        let works = serde_json::json!({ "message": { "items": [
+            { "DOI": "10.1000/other-author", "title": ["KRAS G12D drives pancreatic cancer"],
+              "author": [{ "given": "Ann", "family": "Lee" }],
+              "published": { "date-parts": [[2023, 2, 1]] } },
+            { "DOI": "10.1000/too-old", "title": ["KRAS G12D drives pancreatic cancer"],
+              "author": [{ "given": "Jane", "family": "Doe" }],
+              "published": { "date-parts": [[2015]] } },
+            { "DOI": "10.1000/kras", "title": ["KRAS G12D Drives Pancreatic Cancer."],
+              "author": [{ "given": "Jane", "family": "Doe" }],
+              "published": { "date-parts": [[2023, 5]] },
+              "is-referenced-by-count": 57, "container-title": ["Cancer Cell"],
+              "volume": "41", "issue": "5", "page": "880-894" },
+            { "DOI": "10.1000/unrelated", "title": ["NRAS in melanoma"],
+              "author": [{ "given": "Jane", "family": "Doe" }] }
+        ] } })
+        .to_string();
+        let (url, server) = serve(vec![("200 OK", works)]).await;
+        let dir = std::env::temp_dir().join(format!("ferrumyx-crossref-{}", uuid::Uuid::new_v4()));
+        let client = test_client(url).with_cache_dir(&dir, Duration::from_secs(60));
+
+        let title = "KRAS G12D drives pancreatic cancer";
+        let found = client
+            .resolve_by_metadata(title, Some("Jane Doe"), Some(2023))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.doi, "10.1000/kras");
+        assert_eq!(found.citation_count, Some(57));
+        assert_eq!(found.journal.as_deref(), Some("Cancer Cell"));
+        assert_eq!(found.pages.as_deref(), Some("880-894"));
+        assert!(found.score >= MATCH_THRESHOLD);
+
+        // Answered from the cache: the server only ever serves one response.
+        let cached = client
+            .resolve_by_metadata(title, Some("Jane Doe"), Some(2023))
+            .await
+            .unwrap();
+        assert_eq!(cached, Some(found));
+
+        let seen = server.await.unwrap();
+        assert_eq!(seen.len(), 1);
+        assert!(seen[0].contains("query.author=Jane+Doe"));
+        assert!(seen[0].contains("mailto=curator%40example.org"));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn citation_count_reads_referenced_by_count() {
+        let work = serde_json::json!({ "message": {
+            "DOI": "10.1000/kras", "is-referenced-by-count": 42
+        } })
+        .to_string();
+        let (url, server) = serve(vec![("200 OK", work), ("404 Not Found", String::new())]).await;
+        let client = test_client(url);
+
+        assert_eq!(
+            client.get_citation_count("10.1000/kras").await.unwrap(),
+            Some(42)
+        );
+        assert_eq!(
+            client.get_citation_count("10.1000/missing").await.unwrap(),
+            None
+        );
+        let seen = server.await.unwrap();
+        assert!(seen[0].starts_with("GET /works/10.1000/kras?"));
+    }
+
+    #[tokio::test]
+    async fn references_keep_only_deposited_dois() {
+        let work = serde_json::json!({ "message": {
+            "DOI": "10.1000/kras",
+            "reference": [
+                { "key": "ref1", "DOI": "10.1000/A.1" },
+                { "key": "ref2", "unstructured": "Doe J. Unpublished." },
+                { "key": "ref3", "DOI": " 10.1000/b.2 " }
+            ]
+        } })
+        .to_string();
+        let (url, server) = serve(vec![("200 OK", work), ("404 Not Found", String::new())]).await;
+        let client = test_client(url);
+
+        assert_eq!(
+            client.get_references("10.1000/kras").await.unwrap(),
+            vec!["10.1000/A.1".to_string(), "10.1000/b.2".to_string()]
+        );
+        assert!(client
+            .get_references("10.1000/missing")
+            .await
+            .unwrap()
+            .is_empty());
+        server.await.unwrap();
+    }
+
     #[test]
     fn test_work_to_paper_minimal() {
         let work = serde_json::json!({
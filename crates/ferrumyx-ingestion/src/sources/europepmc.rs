@@ -2,26 +2,70 @@
 //! See ARCHITECTURE.md §2.1 (Europe PMC REST API)
 //!
 //! Endpoint: https://www.ebi.ac.uk/europepmc/webservices/rest/search
+//!
+//! Incremental runs bound the query by first publication date
+//! (`FIRST_PDATE`).
 
 use async_trait::async_trait;
 use reqwest::Client;
+use std::sync::Arc;
 use tracing::{debug, instrument};
 
 use super::LiteratureSource;
-use crate::models::{Author, IngestionSource, PaperMetadata};
+use crate::models::{is_review_publication_type, Author, IngestionSource, PaperMetadata};
+use crate::rate_limit::{source_bucket, TokenBucket};
 
-const EPMC_SEARCH_URL: &str = "https://www.ebi.ac.uk/europepmc/webservices/rest/search";
+const EPMC_REST_URL: &str = "https://www.ebi.ac.uk/europepmc/webservices/rest";
+/// Renders an open-access PMC article as a PDF.
+const EPMC_PDF_RENDER_URL: &str = "https://europepmc.org/backend/ptpmcrender.fcgi";
 
 pub struct EuropePmcClient {
     client: Client,
+    rest_url: String,
+    pdf_render_url: String,
+    /// Only match papers first published on or after this date.
+    since: Option<chrono::NaiveDate>,
+    /// Rate shared by search and full-text requests across all clients.
+    limiter: Arc<TokenBucket>,
 }
 
 impl EuropePmcClient {
     pub fn new() -> Self {
         Self {
             client: Client::new(),
+            rest_url: EPMC_REST_URL.to_string(),
+            pdf_render_url: EPMC_PDF_RENDER_URL.to_string(),
+            since: None,
+            limiter: source_bucket("europepmc"),
         }
     }
+
+    /// Restrict searches to papers first published on or after `since`.
+    pub fn with_since(mut self, since: Option<chrono::NaiveDate>) -> Self {
+        self.since = since;
+        self
+    }
+
+    /// Send REST and PDF render requests to `rest_url` and
+    /// `pdf_render_url` instead of Europe PMC.
+    pub fn with_base_urls(
+        mut self,
+        rest_url: impl Into<String>,
+        pdf_render_url: impl Into<String>,
+    ) -> Self {
+        self.rest_url = rest_url.into().trim_end_matches('/').to_string();
+        self.pdf_render_url = pdf_render_url.into();
+        self
+    }
+
+    /// URL of the PDF Europe PMC renders for an open-access `pmcid`.
+    pub fn pdf_render_url(&self, pmcid: &str) -> Option<String> {
+        let pmcid = normalise_pmcid(pmcid)?;
+        Some(format!(
+            "{}?accid={pmcid}&blobtype=pdf",
+            self.pdf_render_url
+        ))
+    }
 }
 
 impl Default for EuropePmcClient {
@@ -30,20 +74,58 @@ impl Default for EuropePmcClient {
     }
 }
 
+/// `PMC1234567` from `PMC1234567`, `pmc1234567` or a bare `1234567`.
+fn normalise_pmcid(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    let digits = match raw.get(..3) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("pmc") => &raw[3..],
+        _ => raw,
+    };
+    (!digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()))
+        .then(|| format!("PMC{}", digits))
+}
+
+/// `query` limited to papers first published on or after `since`.
+fn bounded_query(query: &str, since: Option<chrono::NaiveDate>) -> String {
+    match since {
+        Some(since) => format!(
+            "({}) AND FIRST_PDATE:[{} TO 3000-12-31]",
+            query,
+            since.format("%Y-%m-%d")
+        ),
+        None => query.to_string(),
+    }
+}
+
+/// Europe PMC lists publication types under `pubTypeList.pubType`, either
+/// as an array or a single string.
+fn is_review_result(r: &serde_json::Value) -> bool {
+    match &r["pubTypeList"]["pubType"] {
+        serde_json::Value::Array(types) => types
+            .iter()
+            .filter_map(|t| t.as_str())
+            .any(is_review_publication_type),
+        serde_json::Value::String(t) => is_review_publication_type(t),
+        _ => false,
+    }
+}
+
 #[async_trait]
 impl LiteratureSource for EuropePmcClient {
     #[instrument(skip(self))]
     async fn search(&self, query: &str, max_results: usize) -> anyhow::Result<Vec<PaperMetadata>> {
+        let query = bounded_query(query, self.since);
         let params = [
-            ("query", query),
+            ("query", query.as_str()),
             ("resultType", "core"),
             ("pageSize", &max_results.to_string()),
             ("format", "json"),
         ];
 
+        self.limiter.acquire().await;
         let resp = self
             .client
-            .get(EPMC_SEARCH_URL)
+            .get(format!("{}/search", self.rest_url))
             .query(&params)
             .send()
             .await?
@@ -79,7 +161,9 @@ impl LiteratureSource for EuropePmcClient {
                     abstract_text: r["abstractText"].as_str().map(String::from),
                     authors,
                     journal: r["journalTitle"].as_str().map(String::from),
-                    pub_date: None, // TODO: parse r["firstPublicationDate"]
+                    pub_date: r["firstPublicationDate"]
+                        .as_str()
+                        .and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()),
                     source: IngestionSource::EuropePmc,
                     open_access: r["isOpenAccess"].as_str() == Some("Y"),
                     full_text_url: r["fullTextUrlList"]["fullTextUrl"].as_array().and_then(
@@ -90,6 +174,8 @@ impl LiteratureSource for EuropePmcClient {
                                 .map(String::from)
                         },
                     ),
+                    is_review: is_review_result(r),
+                    preprint: None,
                 }
             })
             .collect();
@@ -97,20 +183,42 @@ impl LiteratureSource for EuropePmcClient {
         Ok(papers)
     }
 
+    /// JATS XML of an open-access paper, parsed by [`crate::jats`]. Papers
+    /// without full text answer 404; those give `None`, as does a body that
+    /// is not an article.
     async fn fetch_full_text(&self, pmcid: &str) -> anyhow::Result<Option<String>> {
-        let url = format!(
-            "https://www.ebi.ac.uk/europepmc/webservices/rest/{}/fullTextXML",
-            pmcid
-        );
+        let Some(pmcid) = normalise_pmcid(pmcid) else {
+            return Ok(None);
+        };
+        let url = format!("{}/{}/fullTextXML", self.rest_url, pmcid);
+        self.limiter.acquire().await;
         let resp = self.client.get(&url).send().await?;
         if !resp.status().is_success() {
             return Ok(None);
         }
         let xml = resp.text().await?;
-        Ok(if xml.trim().is_empty() {
-            None
-        } else {
-            Some(xml)
-        })
+        Ok(xml.contains("<article").then_some(xml))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalises_pmcids() {
+        assert_eq!(normalise_pmcid(" pmc4321 ").as_deref(), Some("PMC4321"));
+        assert_eq!(normalise_pmcid("4321").as_deref(), Some("PMC4321"));
+        assert_eq!(normalise_pmcid("PMC"), None);
+        assert_eq!(normalise_pmcid("10.1000/x"), None);
+    }
+
+    #[test]
+    fn since_bounds_query_by_first_publication_date() {
+        assert_eq!(bounded_query("KRAS AND PDAC", None), "KRAS AND PDAC");
+        assert_eq!(
+            bounded_query("KRAS OR NRAS", chrono::NaiveDate::from_ymd_opt(2026, 3, 9)),
+            "(KRAS OR NRAS) AND FIRST_PDATE:[2026-03-09 TO 3000-12-31]"
+        );
     }
 }
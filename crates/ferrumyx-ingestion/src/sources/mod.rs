@@ -6,6 +6,7 @@ pub mod cbioportal;
 pub mod chembl;
 pub mod clinicaltrials;
 pub mod cosmic;
+pub mod cosmic_census;
 pub mod crossref;
 pub mod depmap;
 pub mod depmap_cache;
@@ -23,8 +24,9 @@ use async_trait::async_trait;
 // Re-export types for convenience
 pub use arxiv::ArxivClient;
 pub use cbioportal::{CbioMutationFrequency, CbioPortalClient};
-pub use chembl::{ActivityRecord, ChemblClient, CompoundRecord, TargetRecord};
+pub use chembl::{ActivityRecord, ChemblClient, ChemblTargetSummary, CompoundRecord, TargetRecord};
 pub use cosmic::{CosmicClient, CosmicMutationFrequency, MutationRecord, MutationType};
+pub use cosmic_census::{CosmicCensus, HotspotRecord};
 pub use depmap::{DepMapClient, GeneDependency};
 pub use depmap_cache::DepMapCache;
 pub use gtex::GtexClient;
@@ -5,114 +5,218 @@
 //!   esearch: https://eutils.ncbi.nlm.nih.gov/entrez/eutils/esearch.fcgi
 //!   efetch:  https://eutils.ncbi.nlm.nih.gov/entrez/eutils/efetch.fcgi
 //!   elink:   for PMC ID resolution
+//!
+//! Searches go through the Entrez history server: esearch stores the result
+//! set (`usehistory=y`) and efetch pages through it by `WebEnv` and
+//! `query_key`, so `max_results` is not bound by esearch's 10,000-id
+//! `retmax` cap. Requests are spaced to NCBI's rate limit (3/s, or 10/s
+//! with an API key), share the process-wide PubMed
+//! [`source_bucket`](crate::rate_limit::source_bucket) with every other
+//! client, and are retried with backoff on 429 and 5xx. Incremental
+//! runs bound esearch by Entrez date (`datetype=edat`, `mindate`/`maxdate`).
 
 use async_trait::async_trait;
 use quick_xml::events::Event;
 use quick_xml::Reader;
-use reqwest::Client;
+use rand::Rng;
+use reqwest::{Client, Response, StatusCode};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
 use tracing::{debug, instrument, warn};
 
 use super::LiteratureSource;
-use crate::models::{Author, IngestionSource, PaperMetadata};
+use crate::models::{is_review_publication_type, Author, IngestionSource, PaperMetadata};
+use crate::rate_limit::{source_bucket, TokenBucket};
 
-const ESEARCH_URL: &str = "https://eutils.ncbi.nlm.nih.gov/entrez/eutils/esearch.fcgi";
-const EFETCH_URL: &str = "https://eutils.ncbi.nlm.nih.gov/entrez/eutils/efetch.fcgi";
+const EUTILS_URL: &str = "https://eutils.ncbi.nlm.nih.gov/entrez/eutils";
+/// Records per efetch request against the history server.
+const EFETCH_PAGE_SIZE: usize = 500;
+/// Retries after the first attempt on 429, 5xx or a connection failure.
+const MAX_RETRIES: u32 = 5;
+/// Longest wait between attempts, a server's `Retry-After` included.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
 
 pub struct PubMedClient {
     client: Client,
     api_key: Option<String>,
+    base_url: String,
+    page_size: usize,
+    /// Delay before the first retry; doubles with each further one.
+    retry_base: Duration,
+    /// Minimum spacing between requests.
+    min_interval: Duration,
+    last_request: Mutex<Option<Instant>>,
+    /// Rate shared by all PubMed clients in the process.
+    limiter: Arc<TokenBucket>,
+    /// Only match records added to PubMed on or after this date.
+    since: Option<chrono::NaiveDate>,
+}
+
+/// A result set stored on the Entrez history server.
+#[derive(Debug, Clone, PartialEq)]
+struct SearchHistory {
+    count: usize,
+    web_env: String,
+    query_key: String,
 }
 
 impl PubMedClient {
     pub fn new(api_key: Option<String>) -> Self {
+        let api_key = api_key
+            .map(|k| k.trim().to_string())
+            .filter(|k| !k.is_empty());
+        let min_interval = if api_key.is_some() {
+            Duration::from_millis(100)
+        } else {
+            Duration::from_millis(340)
+        };
         Self {
             client: Client::new(),
             api_key,
+            base_url: EUTILS_URL.to_string(),
+            page_size: EFETCH_PAGE_SIZE,
+            retry_base: Duration::from_secs(1),
+            min_interval,
+            last_request: Mutex::new(None),
+            limiter: source_bucket("pubmed"),
+            since: None,
         }
     }
 
-    fn base_params(&self) -> Vec<(&'static str, String)> {
-        let mut params = vec![("retmode", "json".to_string())];
+    /// Restrict searches to records added to PubMed on or after `since`.
+    pub fn with_since(mut self, since: Option<chrono::NaiveDate>) -> Self {
+        self.since = since;
+        self
+    }
+
+    fn base_params(&self, db: &str) -> Vec<(&'static str, String)> {
+        let mut params = vec![("db", db.to_string())];
         if let Some(key) = &self.api_key {
             params.push(("api_key", key.clone()));
         }
         params
     }
 
-    /// Search PubMed and return a list of PMIDs.
+    /// Run `query` and store its result set on the history server.
     #[instrument(skip(self))]
-    async fn esearch(&self, query: &str, max: usize) -> anyhow::Result<Vec<String>> {
-        let mut params = self.base_params();
-        params.push(("db", "pubmed".to_string()));
-        params.push(("term", query.to_string()));
-        params.push(("retmax", max.to_string()));
-        params.push(("usehistory", "n".to_string()));
-
-        let resp: serde_json::Value = self
-            .client
-            .get(ESEARCH_URL)
-            .query(&params)
-            .send()
-            .await?
-            .json()
-            .await?;
-
-        let ids = resp["esearchresult"]["idlist"]
-            .as_array()
-            .unwrap_or(&vec![])
-            .iter()
-            .filter_map(|v| v.as_str().map(String::from))
-            .collect();
+    async fn esearch(&self, query: &str) -> anyhow::Result<SearchHistory> {
+        let mut params = self.base_params("pubmed");
+        params.extend([
+            ("term", query.to_string()),
+            ("retmode", "json".to_string()),
+            ("retmax", "0".to_string()),
+            ("usehistory", "y".to_string()),
+        ]);
+        if let Some(since) = self.since {
+            params.extend(entrez_date_params(since));
+        }
 
-        debug!(?ids, "PubMed esearch returned PMIDs");
-        Ok(ids)
+        let resp: serde_json::Value = self.get("esearch.fcgi", &params).await?.json().await?;
+        let history = parse_esearch_history(&resp)?;
+        debug!(count = history.count, "PubMed esearch stored result set");
+        Ok(history)
     }
 
-    /// Fetch PubMed XML for a list of PMIDs and parse into PaperMetadata.
-    #[instrument(skip(self))]
-    async fn efetch_abstracts(&self, pmids: &[String]) -> anyhow::Result<Vec<PaperMetadata>> {
-        if pmids.is_empty() {
-            return Ok(vec![]);
-        }
-
-        let mut params = vec![
-            ("db", "pubmed".to_string()),
-            ("id", pmids.join(",")),
+    /// Fetch records `start..start + count` of a stored result set.
+    #[instrument(skip(self, history))]
+    async fn efetch_page(
+        &self,
+        history: &SearchHistory,
+        start: usize,
+        count: usize,
+    ) -> anyhow::Result<Vec<PaperMetadata>> {
+        let mut params = self.base_params("pubmed");
+        params.extend([
+            ("WebEnv", history.web_env.clone()),
+            ("query_key", history.query_key.clone()),
+            ("retstart", start.to_string()),
+            ("retmax", count.to_string()),
             ("rettype", "abstract".to_string()),
             ("retmode", "xml".to_string()),
-        ];
-        if let Some(key) = &self.api_key {
-            params.push(("api_key", key.clone()));
-        }
-
-        let xml = self
-            .client
-            .get(EFETCH_URL)
-            .query(&params)
-            .send()
-            .await?
-            .text()
-            .await?;
+        ]);
 
+        let xml = self.get("efetch.fcgi", &params).await?.text().await?;
         parse_pubmed_xml(&xml)
     }
+
+    /// GET an E-utility, spaced to the rate limit and retried on 429, 5xx
+    /// and connection failures. Waits for `Retry-After` when the server
+    /// sends one, otherwise backs off exponentially with jitter.
+    async fn get(&self, endpoint: &str, params: &[(&str, String)]) -> anyhow::Result<Response> {
+        let url = format!("{}/{}", self.base_url, endpoint);
+        let mut attempt = 0;
+        loop {
+            self.throttle().await;
+            let (server_delay, error) = match self.client.get(&url).query(params).send().await {
+                Ok(resp) if is_retryable_status(resp.status()) => (
+                    resp.headers()
+                        .get(reqwest::header::RETRY_AFTER)
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(|v| parse_retry_after(v, chrono::Utc::now())),
+                    anyhow::anyhow!("{} returned {}", endpoint, resp.status()),
+                ),
+                Ok(resp) => return Ok(resp.error_for_status()?),
+                Err(e) if e.is_connect() || e.is_timeout() => (None, e.into()),
+                Err(e) => return Err(e.into()),
+            };
+            if attempt >= MAX_RETRIES {
+                return Err(error.context(format!("giving up after {} attempts", attempt + 1)));
+            }
+            let delay = server_delay
+                .unwrap_or_else(|| backoff_delay(self.retry_base, attempt))
+                .min(MAX_RETRY_DELAY);
+            warn!(
+                attempt = attempt + 1,
+                delay_ms = delay.as_millis() as u64,
+                "PubMed request failed, retrying: {}",
+                error
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Wait for a token from the shared limiter, then until `min_interval`
+    /// has passed since this client's previous request.
+    async fn throttle(&self) {
+        self.limiter.acquire().await;
+        let mut last = self.last_request.lock().await;
+        if let Some(at) = *last {
+            tokio::time::sleep_until(at + self.min_interval).await;
+        }
+        *last = Some(Instant::now());
+    }
 }
 
 #[async_trait]
 impl LiteratureSource for PubMedClient {
     async fn search(&self, query: &str, max_results: usize) -> anyhow::Result<Vec<PaperMetadata>> {
-        let pmids = self.esearch(query, max_results).await?;
-        self.efetch_abstracts(&pmids).await
+        if max_results == 0 {
+            return Ok(vec![]);
+        }
+        let history = self.esearch(query).await?;
+        let total = history.count.min(max_results);
+        let mut papers = Vec::with_capacity(total);
+        let mut start = 0;
+        while start < total {
+            let count = self.page_size.min(total - start);
+            papers.extend(self.efetch_page(&history, start, count).await?);
+            start += count;
+        }
+        Ok(papers)
     }
 
     async fn fetch_full_text(&self, pmcid: &str) -> anyhow::Result<Option<String>> {
         // Fetch PMC full-text XML
-        let url = format!(
-            "https://eutils.ncbi.nlm.nih.gov/entrez/eutils/efetch.fcgi\
-             ?db=pmc&id={}&rettype=xml&retmode=xml",
-            pmcid
-        );
-        let xml = self.client.get(&url).send().await?.text().await?;
+        let mut params = self.base_params("pmc");
+        params.extend([
+            ("id", pmcid.to_string()),
+            ("rettype", "xml".to_string()),
+            ("retmode", "xml".to_string()),
+        ]);
+        let xml = self.get("efetch.fcgi", &params).await?.text().await?;
         if xml.trim().is_empty() || xml.contains("<error>") {
             return Ok(None);
         }
@@ -120,6 +224,58 @@ impl LiteratureSource for PubMedClient {
     }
 }
 
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// esearch parameters matching records whose Entrez date is `since` or
+/// later. E-utilities ignores `mindate` without `maxdate`, so the upper
+/// bound is left open.
+fn entrez_date_params(since: chrono::NaiveDate) -> [(&'static str, String); 3] {
+    [
+        ("datetype", "edat".to_string()),
+        ("mindate", since.format("%Y/%m/%d").to_string()),
+        ("maxdate", "3000/12/31".to_string()),
+    ]
+}
+
+/// `base * 2^attempt` with ±25% jitter.
+fn backoff_delay(base: Duration, attempt: u32) -> Duration {
+    let delay = base.saturating_mul(2u32.saturating_pow(attempt));
+    delay.mul_f64(rand::thread_rng().gen_range(0.75..=1.25))
+}
+
+/// `Retry-After` given as delay-seconds or as an HTTP date.
+fn parse_retry_after(value: &str, now: chrono::DateTime<chrono::Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (at.with_timezone(&chrono::Utc) - now)
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+fn parse_esearch_history(resp: &serde_json::Value) -> anyhow::Result<SearchHistory> {
+    let result = &resp["esearchresult"];
+    if let Some(error) = result["ERROR"].as_str() {
+        anyhow::bail!("PubMed esearch error: {}", error);
+    }
+    let field = |key: &str| result[key].as_str().unwrap_or("").to_string();
+    let history = SearchHistory {
+        count: field("count").parse().unwrap_or(0),
+        web_env: field("webenv"),
+        query_key: field("querykey"),
+    };
+    if history.count > 0 && (history.web_env.is_empty() || history.query_key.is_empty()) {
+        anyhow::bail!("PubMed esearch returned no history session");
+    }
+    Ok(history)
+}
+
 /// Parse PubMed XML (efetch abstract mode) into PaperMetadata list.
 /// Handles the <PubmedArticleSet><PubmedArticle> structure.
 fn parse_pubmed_xml(xml: &str) -> anyhow::Result<Vec<PaperMetadata>> {
@@ -138,6 +294,7 @@ fn parse_pubmed_xml(xml: &str) -> anyhow::Result<Vec<PaperMetadata>> {
     let mut in_journal = false;
     let mut in_doi = false;
     let mut in_pmc = false;
+    let mut in_pub_type = false;
     let mut current_last = String::new();
     let mut current_fore = String::new();
     let mut buf = Vec::new();
@@ -158,6 +315,8 @@ fn parse_pubmed_xml(xml: &str) -> anyhow::Result<Vec<PaperMetadata>> {
                         source: IngestionSource::PubMed,
                         open_access: false,
                         full_text_url: None,
+                        is_review: false,
+                        preprint: None,
                     });
                 }
                 b"PMID" => in_pmid = true,
@@ -171,6 +330,7 @@ fn parse_pubmed_xml(xml: &str) -> anyhow::Result<Vec<PaperMetadata>> {
                 b"LastName" => in_last_name = true,
                 b"ForeName" => in_fore_name = true,
                 b"Title" => in_journal = true,
+                b"PublicationType" => in_pub_type = true,
                 b"ArticleId" => {
                     for attr in e.attributes() {
                         if let Ok(attr) = attr {
@@ -213,6 +373,9 @@ fn parse_pubmed_xml(xml: &str) -> anyhow::Result<Vec<PaperMetadata>> {
                     if in_pmc {
                         p.pmcid = Some(text.clone());
                     }
+                    if in_pub_type && is_review_publication_type(&text) {
+                        p.is_review = true;
+                    }
                 }
             }
             Ok(Event::End(ref e)) => match e.name().as_ref() {
@@ -222,6 +385,7 @@ fn parse_pubmed_xml(xml: &str) -> anyhow::Result<Vec<PaperMetadata>> {
                 b"LastName" => in_last_name = false,
                 b"ForeName" => in_fore_name = false,
                 b"Title" => in_journal = false,
+                b"PublicationType" => in_pub_type = false,
                 b"ArticleId" => {
                     in_doi = false;
                     in_pmc = false;
@@ -295,5 +459,166 @@ mod tests {
         assert_eq!(papers[0].pmid, Some("12345678".to_string()));
         assert_eq!(papers[0].title, "KRAS G12D in pancreatic cancer");
         assert_eq!(papers[0].authors[0].name, "John Smith");
+        assert!(!papers[0].is_review);
+    }
+
+    #[test]
+    fn test_parse_review_publication_type() {
+        let xml = r#"<?xml version="1.0"?>
+<PubmedArticleSet>
+  <PubmedArticle>
+    <MedlineCitation>
+      <PMID>87654321</PMID>
+      <Article>
+        <ArticleTitle>KRAS inhibitors: a decade in review</ArticleTitle>
+        <PublicationTypeList>
+          <PublicationType UI="D016428">Journal Article</PublicationType>
+          <PublicationType UI="D016454">Review</PublicationType>
+        </PublicationTypeList>
+      </Article>
+    </MedlineCitation>
+  </PubmedArticle>
+</PubmedArticleSet>"#;
+
+        let papers = parse_pubmed_xml(xml).unwrap();
+        assert_eq!(papers.len(), 1);
+        assert!(papers[0].is_review);
+    }
+
+    #[test]
+    fn parses_retry_after_seconds_and_dates() {
+        let now = chrono::DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        assert_eq!(parse_retry_after(" 7 ", now), Some(Duration::from_secs(7)));
+        assert_eq!(
+            parse_retry_after("Thu, 01 Jan 2026 00:00:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 31 Dec 2025 23:59:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    fn efetch_xml(pmids: std::ops::Range<u32>) -> String {
+        let articles: String = pmids
+            .map(|id| {
+                format!(
+                    "<PubmedArticle><MedlineCitation><PMID>{id}</PMID><Article>\
+                     <ArticleTitle>Paper {id}</ArticleTitle></Article>\
+                     </MedlineCitation></PubmedArticle>"
+                )
+            })
+            .collect();
+        format!("<PubmedArticleSet>{articles}</PubmedArticleSet>")
+    }
+
+    /// Serve `responses` in order, one connection each. Returns the base
+    /// URL and the request lines received.
+    async fn serve(
+        responses: Vec<(&'static str, &'static str, String)>,
+    ) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let mut seen = Vec::new();
+            for (status, headers, body) in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let request = String::from_utf8_lossy(&request).to_string();
+                seen.push(request.lines().next().unwrap_or_default().to_string());
+                let head = format!(
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\n{headers}Connection: close\r\n\r\n",
+                    body.len()
+                );
+                socket.write_all(head.as_bytes()).await.unwrap();
+                socket.write_all(body.as_bytes()).await.unwrap();
+                socket.shutdown().await.unwrap();
+            }
+            seen
+        });
+        (url, handle)
+    }
+
+    #[tokio::test]
+    async fn retries_rate_limits_and_pages_through_history() {
+        let esearch = serde_json::json!({
+            "esearchresult": { "count": "5", "retmax": "0", "idlist": [],
+                               "webenv": "MCID_test", "querykey": "1" }
+        })
+        .to_string();
+        let (url, server) = serve(vec![
+            ("429 Too Many Requests", "Retry-After: 1\r\n", String::new()),
+            ("200 OK", "", esearch),
+            ("503 Service Unavailable", "", String::new()),
+            ("200 OK", "", efetch_xml(1..3)),
+            ("200 OK", "", efetch_xml(3..5)),
+            ("200 OK", "", efetch_xml(5..6)),
+        ])
+        .await;
+
+        let mut client = PubMedClient::new(Some(" test-key ".to_string()));
+        client.base_url = url;
+        client.page_size = 2;
+        client.retry_base = Duration::from_millis(10);
+        client.min_interval = Duration::ZERO;
+        client.limiter = Arc::new(TokenBucket::new(50, 16));
+
+        let started = std::time::Instant::now();
+        let papers = client.search("KRAS[tiab]", 20_000).await.unwrap();
+        assert!(
+            started.elapsed() >= Duration::from_secs(1),
+            "Retry-After ignored"
+        );
+
+        let pmids: Vec<&str> = papers.iter().filter_map(|p| p.pmid.as_deref()).collect();
+        assert_eq!(pmids, ["1", "2", "3", "4", "5"]);
+
+        let requests = server.await.unwrap();
+        assert_eq!(requests.len(), 6);
+        assert!(requests.iter().all(|r| r.contains("api_key=test-key")));
+        assert!(requests[..2]
+            .iter()
+            .all(|r| r.contains("/esearch.fcgi?") && r.contains("usehistory=y")));
+        let starts: Vec<&str> = requests[2..]
+            .iter()
+            .map(|r| {
+                assert!(r.contains("WebEnv=MCID_test") && r.contains("query_key=1"));
+                r.split(['?', '&', ' '])
+                    .find_map(|p| p.strip_prefix("retstart="))
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(starts, ["0", "0", "2", "4"]);
+    }
+
+    #[tokio::test]
+    async fn since_bounds_esearch_by_entrez_date() {
+        let esearch = serde_json::json!({
+            "esearchresult": { "count": "0", "retmax": "0", "idlist": [] }
+        })
+        .to_string();
+        let (url, server) = serve(vec![("200 OK", "", esearch)]).await;
+
+        let mut client =
+            PubMedClient::new(None).with_since(chrono::NaiveDate::from_ymd_opt(2026, 3, 9));
+        client.base_url = url;
+        client.min_interval = Duration::ZERO;
+        client.limiter = Arc::new(TokenBucket::new(50, 16));
+
+        assert!(client.search("KRAS[tiab]", 50).await.unwrap().is_empty());
+        let requests = server.await.unwrap();
+        assert!(requests[0].contains("datetype=edat"));
+        assert!(requests[0].contains("mindate=2026%2F03%2F09"));
+        assert!(requests[0].contains("maxdate=3000%2F12%2F31"));
     }
 }
@@ -1,13 +1,19 @@
 //! Sci-Hub client for downloading full-text PDFs.
 //!
 //! Optional fallback source for papers not resolved via OA channels.
-//! This module is best-effort and disabled by default.
+//! This module is best-effort and disabled by default: nothing calls it
+//! unless a run opts in (see [`crate::full_text`]).
+//!
+//! Mirror health is tracked per mirror in a [`MirrorHealth`] shared by
+//! every client, so a mirror that just failed is not asked again by the
+//! next paper.
 
 use reqwest::Client;
 use scraper::{Html, Selector};
 use std::collections::HashMap;
-use std::sync::{Mutex, OnceLock};
+use std::sync::{Arc, Mutex, OnceLock};
 use std::time::Duration;
+use tokio::time::Instant;
 use tracing::{debug, info, instrument, warn};
 use url::Url;
 
@@ -24,13 +30,104 @@ const DEFAULT_SCIHUB_DOMAINS: &[&str] = &[
     "http://sci-hub.st",
 ];
 
+/// Consecutive failures after which a mirror's backoff stops doubling.
+const MAX_BACKOFF_DOUBLINGS: u32 = 5;
+
 pub struct SciHubClient {
     client: Client,
     domains: Vec<String>,
+    health: Arc<MirrorHealth>,
+    domain_parallelism: usize,
+}
+
+static SHARED_MIRROR_HEALTH: OnceLock<Arc<MirrorHealth>> = OnceLock::new();
+
+/// Failure history of Sci-Hub mirrors.
+///
+/// A mirror that errors, times out or answers 429/5xx sits out for the
+/// cooldown, doubled for each further consecutive failure up to
+/// 2^[`MAX_BACKOFF_DOUBLINGS`] times the cooldown; any answer clears it.
+/// Mirrors are offered in list order starting from the one that answered
+/// last, so a failing mirror hands its turn to the next one.
+pub struct MirrorHealth {
+    cooldown: Duration,
+    state: Mutex<HealthState>,
+}
+
+#[derive(Default)]
+struct HealthState {
+    failures: HashMap<String, MirrorFailures>,
+    last_answered: Option<String>,
+}
+
+struct MirrorFailures {
+    consecutive: u32,
+    retry_at: Instant,
 }
 
-static SCIHUB_DOMAIN_COOLDOWN: OnceLock<Mutex<HashMap<String, std::time::Instant>>> =
-    OnceLock::new();
+impl MirrorHealth {
+    pub fn new(cooldown: Duration) -> Self {
+        Self {
+            cooldown,
+            state: Mutex::new(HealthState::default()),
+        }
+    }
+
+    /// `mirrors` that are not backing off, rotated to start at the mirror
+    /// that answered last.
+    pub fn available(&self, mirrors: &[String]) -> Vec<String> {
+        let now = Instant::now();
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let start = state
+            .last_answered
+            .as_ref()
+            .and_then(|last| mirrors.iter().position(|m| m == last))
+            .unwrap_or(0);
+        mirrors[start..]
+            .iter()
+            .chain(&mirrors[..start])
+            .filter(|m| state.failures.get(*m).is_none_or(|f| f.retry_at <= now))
+            .cloned()
+            .collect()
+    }
+
+    /// `mirror` answered, whether or not it had the paper.
+    pub fn record_answer(&self, mirror: &str) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.failures.remove(mirror);
+        state.last_answered = Some(mirror.to_string());
+    }
+
+    /// `mirror` failed; returns how long it now sits out.
+    pub fn record_failure(&self, mirror: &str) -> Duration {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let consecutive = state
+            .failures
+            .get(mirror)
+            .map_or(1, |f| f.consecutive.saturating_add(1));
+        let backoff = self.cooldown * 2u32.pow((consecutive - 1).min(MAX_BACKOFF_DOUBLINGS));
+        state.failures.insert(
+            mirror.to_string(),
+            MirrorFailures {
+                consecutive,
+                retry_at: Instant::now() + backoff,
+            },
+        );
+        backoff
+    }
+}
+
+/// The [`MirrorHealth`] every default client shares, with the cooldown
+/// from `FERRUMYX_SCIHUB_DOMAIN_COOLDOWN_SECS`.
+pub fn shared_mirror_health() -> Arc<MirrorHealth> {
+    SHARED_MIRROR_HEALTH
+        .get_or_init(|| {
+            Arc::new(MirrorHealth::new(Duration::from_secs(
+                resolve_domain_cooldown_secs(),
+            )))
+        })
+        .clone()
+}
 
 impl Default for SciHubClient {
     fn default() -> Self {
@@ -71,7 +168,12 @@ impl SciHubClient {
                     .collect()
             });
 
-        Self { client, domains }
+        Self {
+            client,
+            domains,
+            health: shared_mirror_health(),
+            domain_parallelism: resolve_domain_parallelism(),
+        }
     }
 
     pub fn with_retry_domains(mut self, domains: Vec<String>) -> Self {
@@ -82,25 +184,29 @@ impl SciHubClient {
         self
     }
 
+    /// Track mirror failures in `health` instead of the shared history.
+    pub fn with_mirror_health(mut self, health: Arc<MirrorHealth>) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Ask up to `parallelism` mirrors at once.
+    pub fn with_domain_parallelism(mut self, parallelism: usize) -> Self {
+        self.domain_parallelism = parallelism.max(1);
+        self
+    }
+
     #[instrument(skip(self))]
     pub async fn download_pdf(&self, identifier: &str) -> anyhow::Result<Option<Vec<u8>>> {
         info!("Attempting to fetch {} from Sci-Hub", identifier);
 
-        let candidate_domains: Vec<String> = self
-            .domains
-            .iter()
-            .filter(|domain| !domain_on_cooldown(domain))
-            .cloned()
-            .collect();
-
+        let candidate_domains = self.health.available(&self.domains);
         if candidate_domains.is_empty() {
-            debug!("Skipping Sci-Hub fetch: all mirrors are on cooldown");
+            debug!("Skipping Sci-Hub fetch: all mirrors are backing off");
             return Ok(None);
         }
 
-        let domain_parallelism = resolve_domain_parallelism()
-            .max(1)
-            .min(candidate_domains.len());
+        let domain_parallelism = self.domain_parallelism.min(candidate_domains.len());
         let mut set = tokio::task::JoinSet::new();
         let mut next_idx = 0usize;
         let identifier = identifier.to_string();
@@ -120,17 +226,22 @@ impl SciHubClient {
             if let Some(joined) = set.join_next().await {
                 match joined {
                     Ok((domain, Ok(Some(bytes)))) => {
-                        clear_domain_failure(&domain);
+                        self.health.record_answer(&domain);
                         set.abort_all();
                         return Ok(Some(bytes));
                     }
                     Ok((domain, Ok(None))) => {
-                        clear_domain_failure(&domain);
+                        self.health.record_answer(&domain);
                         debug!("Domain {} did not have the PDF", domain);
                     }
                     Ok((domain, Err(e))) => {
-                        mark_domain_failure(&domain);
-                        warn!("Error fetching from domain {}: {}", domain, e);
+                        let backoff = self.health.record_failure(&domain);
+                        warn!(
+                            "Error fetching from domain {}: {} (skipping it for {}s)",
+                            domain,
+                            e,
+                            backoff.as_secs()
+                        );
                     }
                     Err(e) => {
                         warn!("Sci-Hub mirror task failed: {}", e);
@@ -163,35 +274,6 @@ fn resolve_domain_cooldown_secs() -> u64 {
         .clamp(15, 3600)
 }
 
-fn domain_on_cooldown(domain: &str) -> bool {
-    let now = std::time::Instant::now();
-    let cache = SCIHUB_DOMAIN_COOLDOWN.get_or_init(|| Mutex::new(HashMap::new()));
-    let Ok(mut guard) = cache.lock() else {
-        return false;
-    };
-    guard.retain(|_, until| *until > now);
-    guard.get(domain).is_some_and(|until| *until > now)
-}
-
-fn mark_domain_failure(domain: &str) {
-    let cache = SCIHUB_DOMAIN_COOLDOWN.get_or_init(|| Mutex::new(HashMap::new()));
-    let Ok(mut guard) = cache.lock() else {
-        return;
-    };
-    guard.insert(
-        domain.to_string(),
-        std::time::Instant::now() + Duration::from_secs(resolve_domain_cooldown_secs()),
-    );
-}
-
-fn clear_domain_failure(domain: &str) {
-    let cache = SCIHUB_DOMAIN_COOLDOWN.get_or_init(|| Mutex::new(HashMap::new()));
-    let Ok(mut guard) = cache.lock() else {
-        return;
-    };
-    guard.remove(domain);
-}
-
 async fn try_download_from_domain(
     client: Client,
     domain: &str,
@@ -403,4 +485,116 @@ mod tests {
         assert!(cands.iter().any(|c| c.contains("doi.org")));
         assert!(cands.iter().any(|c| c.contains("downloads/abc.pdf")));
     }
+
+    /// Serve `responses` (status line, content type, body) in order, one
+    /// per connection; returns the base URL and the request paths received.
+    async fn serve(
+        responses: Vec<(&'static str, &'static str, String)>,
+    ) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let mut seen = Vec::new();
+            for (status, content_type, body) in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let request = String::from_utf8_lossy(&request).to_string();
+                let path = request.split_whitespace().nth(1).unwrap_or_default();
+                seen.push(path.to_string());
+                let head = format!(
+                    "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                socket.write_all(head.as_bytes()).await.unwrap();
+                socket.write_all(body.as_bytes()).await.unwrap();
+                socket.shutdown().await.unwrap();
+            }
+            seen
+        });
+        (url, handle)
+    }
+
+    fn mirrors(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn failing_mirrors_back_off_exponentially_and_hand_over_their_turn() {
+        let health = MirrorHealth::new(Duration::from_secs(10));
+        let all = mirrors(&["a", "b", "c"]);
+        assert_eq!(health.available(&all), all);
+
+        health.record_answer("b");
+        assert_eq!(health.available(&all), mirrors(&["b", "c", "a"]));
+        assert_eq!(health.record_failure("b"), Duration::from_secs(10));
+        assert_eq!(health.available(&all), mirrors(&["c", "a"]));
+
+        tokio::time::advance(Duration::from_secs(10)).await;
+        assert_eq!(health.available(&all), mirrors(&["b", "c", "a"]));
+        assert_eq!(health.record_failure("b"), Duration::from_secs(20));
+        tokio::time::advance(Duration::from_secs(10)).await;
+        assert!(!health.available(&all).contains(&"b".to_string()));
+        tokio::time::advance(Duration::from_secs(10)).await;
+        assert!(health.available(&all).contains(&"b".to_string()));
+
+        for _ in 0..10 {
+            health.record_failure("b");
+        }
+        assert_eq!(health.record_failure("b"), Duration::from_secs(320));
+        health.record_answer("b");
+        assert_eq!(health.record_failure("b"), Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn download_rotates_past_a_failing_mirror() {
+        let page =
+            r#"<html><body><embed type="application/pdf" src="/files/kras.pdf"></body></html>"#;
+        let pdf = "%PDF-1.4 kras".to_string();
+        let (down, down_seen) = serve(vec![(
+            "503 Service Unavailable",
+            "text/html",
+            String::new(),
+        )])
+        .await;
+        let (up, up_seen) = serve(vec![
+            ("200 OK", "text/html", page.to_string()),
+            ("200 OK", "application/pdf", pdf.clone()),
+            ("200 OK", "text/html", page.to_string()),
+            ("200 OK", "application/pdf", pdf.clone()),
+        ])
+        .await;
+        let health = Arc::new(MirrorHealth::new(Duration::from_secs(300)));
+        let client = SciHubClient::new()
+            .with_retry_domains(vec![down.clone(), format!("{up}/")])
+            .with_mirror_health(health.clone())
+            .with_domain_parallelism(1);
+
+        let first = client.download_pdf("10.1000/kras").await.unwrap();
+        assert_eq!(first.as_deref(), Some(pdf.as_bytes()));
+        assert_eq!(
+            health.available(&[down.clone(), up.clone()]),
+            vec![up.clone()]
+        );
+
+        // The failed mirror is backing off, so only the healthy one is asked.
+        let second = client.download_pdf("10.1000/kras").await.unwrap();
+        assert_eq!(second.as_deref(), Some(pdf.as_bytes()));
+        assert_eq!(down_seen.await.unwrap(), ["/10.1000/kras"]);
+        assert_eq!(
+            up_seen.await.unwrap(),
+            [
+                "/10.1000/kras",
+                "/files/kras.pdf",
+                "/10.1000/kras",
+                "/files/kras.pdf"
+            ]
+        );
+    }
 }
@@ -6,9 +6,11 @@ use async_trait::async_trait;
 use chrono::NaiveDate;
 use reqwest::Client;
 use serde::Deserialize;
+use std::sync::Arc;
 use tracing::{debug, instrument};
 
 use crate::models::{Author, IngestionSource, PaperMetadata};
+use crate::rate_limit::{source_bucket, TokenBucket};
 use crate::sources::LiteratureSource;
 
 const S2_SEARCH_URL: &str = "https://api.semanticscholar.org/graph/v1/paper/search";
@@ -91,6 +93,8 @@ struct EdgeResponse {
 pub struct SemanticScholarClient {
     client: Client,
     api_key: Option<String>,
+    /// Rate shared by all Semantic Scholar clients in the process.
+    limiter: Arc<TokenBucket>,
 }
 
 impl SemanticScholarClient {
@@ -100,6 +104,7 @@ impl SemanticScholarClient {
             api_key: api_key
                 .map(|k| k.trim().to_string())
                 .filter(|k| !k.is_empty()),
+            limiter: source_bucket("semanticscholar"),
         }
     }
 
@@ -152,6 +157,8 @@ impl SemanticScholarClient {
             source: IngestionSource::SemanticScholar,
             open_access: p.is_open_access.unwrap_or(full_text_url.is_some()),
             full_text_url,
+            is_review: false,
+            preprint: None,
         })
     }
 
@@ -265,6 +272,7 @@ impl SemanticScholarClient {
             .client
             .get(url)
             .query(&[("fields", fields), ("limit", &limit.to_string())]);
+        self.limiter.acquire().await;
         let resp = self.apply_auth(req).send().await?;
         if !resp.status().is_success() {
             return Ok(Vec::new());
@@ -321,6 +329,7 @@ impl SemanticScholarClient {
         let fields = "embedding.specter_v2";
         let url = format!("{}/{}", S2_PAPER_URL, paper_id);
         let req = self.client.get(url).query(&[("fields", fields)]);
+        self.limiter.acquire().await;
         let resp = self.apply_auth(req).send().await?;
         if !resp.status().is_success() {
             return Ok(None);
@@ -348,6 +357,7 @@ impl LiteratureSource for SemanticScholarClient {
             ("limit", &limit.to_string()),
             ("fields", fields),
         ]);
+        self.limiter.acquire().await;
         let resp = self.apply_auth(req).send().await?;
         if !resp.status().is_success() {
             let status = resp.status();
@@ -394,6 +404,7 @@ impl LiteratureSource for SemanticScholarClient {
         let fields = "openAccessPdf,embedding.specter_v2";
         let url = format!("{}/{}", S2_PAPER_URL, paper_id);
         let req = self.client.get(url).query(&[("fields", fields)]);
+        self.limiter.acquire().await;
         let resp = self.apply_auth(req).send().await?;
         if !resp.status().is_success() {
             return Ok(None);
@@ -4,8 +4,11 @@
 
 use reqwest::Client;
 use serde::Deserialize;
+use std::sync::Arc;
 use tracing::{debug, instrument};
 
+use crate::rate_limit::{source_bucket, TokenBucket};
+
 const UNPAYWALL_BASE_URL: &str = "https://api.unpaywall.org/v2";
 
 #[derive(Debug, Deserialize)]
@@ -22,17 +25,28 @@ struct OaLocation {
 
 pub struct UnpaywallClient {
     client: Client,
+    base_url: String,
     email: String,
+    /// Rate shared by all Unpaywall clients in the process.
+    limiter: Arc<TokenBucket>,
 }
 
 impl UnpaywallClient {
     pub fn new(email: impl Into<String>) -> Self {
         Self {
             client: Client::new(),
+            base_url: UNPAYWALL_BASE_URL.to_string(),
             email: email.into(),
+            limiter: source_bucket("unpaywall"),
         }
     }
 
+    /// Send requests to `base_url` instead of the public API.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
     #[instrument(skip(self))]
     pub async fn resolve_pdf_url(&self, doi: &str) -> anyhow::Result<Option<String>> {
         let doi = doi.trim();
@@ -43,7 +57,8 @@ impl UnpaywallClient {
             return Ok(None);
         }
 
-        let url = format!("{UNPAYWALL_BASE_URL}/{doi}");
+        let url = format!("{}/{doi}", self.base_url);
+        self.limiter.acquire().await;
         let resp = self
             .client
             .get(&url)
@@ -0,0 +1,458 @@
+//! Clinical trial records and their links into the knowledge graph.
+//!
+//! [`link_trial`] fills in a [`TrialRecord`]'s link fields: the known drugs
+//! among its interventions, the genes those drugs target and the OncoTree
+//! codes of its conditions. [`trial_facts`] turns a linked trial into
+//! TRIAL–`investigates`–DRUG and TRIAL–`investigates`–GENE facts, and
+//! [`ingest_trials`] fetches, links and stores a search's trials along with
+//! those facts.
+//!
+//! The ranker reads the stored trials back through [`summarise_trials`].
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use ferrumyx_common::cancer_type::CancerType;
+use ferrumyx_db::entities::EntityRepository;
+use ferrumyx_db::kg_facts::KgFactRepository;
+use ferrumyx_db::schema::{Entity as DbEntity, EntityType as DbEntityType, KgFact, TrialRecord};
+use ferrumyx_db::trials::TrialRepository;
+use ferrumyx_db::Database;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::normalise::drug::{drug_targets, normalise_drugs};
+use crate::pipeline::canonical_key;
+use crate::sources::clinicaltrials::{
+    condition_for_cancer_code, ClinicalTrialsClient, TrialSummary,
+};
+
+/// Predicate of the facts [`trial_facts`] writes.
+pub const INVESTIGATES_PREDICATE: &str = "investigates";
+
+/// `KgFact::study_type` of trial facts.
+pub const TRIAL_STUDY_TYPE: &str = "clinical_trial";
+
+/// The drug is one of the trial's listed interventions.
+const DRUG_FACT_CONFIDENCE: f32 = 0.9;
+
+/// The gene is inferred from the drug's known targets.
+const GENE_FACT_CONFIDENCE: f32 = 0.7;
+
+/// Words in a condition that stage or qualify a cancer rather than name it.
+const CONDITION_QUALIFIERS: &[&str] = &[
+    "metastatic",
+    "advanced",
+    "locally",
+    "recurrent",
+    "relapsed",
+    "refractory",
+    "unresectable",
+    "resectable",
+    "resected",
+    "stage",
+    "i",
+    "ii",
+    "iii",
+    "iv",
+];
+
+/// Fill in `trial`'s drugs, target genes and cancer codes from its
+/// interventions and conditions.
+pub fn link_trial(trial: &mut TrialRecord) {
+    let mut drugs: Vec<String> = Vec::new();
+    for intervention in &trial.interventions {
+        let names = std::iter::once(&intervention.name).chain(&intervention.other_names);
+        for drug in names.flat_map(|n| normalise_drugs(n)) {
+            if !drugs.iter().any(|d| d == drug.name) {
+                drugs.push(drug.name.to_string());
+            }
+        }
+    }
+
+    let mut genes: Vec<String> = Vec::new();
+    for gene in drugs.iter().flat_map(|d| drug_targets(d)) {
+        if !genes.iter().any(|g| g == gene) {
+            genes.push(gene.to_string());
+        }
+    }
+
+    let mut codes: Vec<String> = Vec::new();
+    for cancer in trial
+        .conditions
+        .iter()
+        .filter_map(|c| cancer_type_for_condition(c))
+    {
+        if !codes.iter().any(|c| c == cancer.code()) {
+            codes.push(cancer.code().to_string());
+        }
+    }
+
+    trial.drugs = drugs;
+    trial.target_genes = genes;
+    trial.cancer_codes = codes;
+}
+
+/// The OncoTree node a trial condition names. Registry conditions carry
+/// staging words ("Metastatic Colorectal Cancer") and MeSH inversions
+/// ("Carcinoma, Non-Small-Cell Lung") that the taxonomy does not list.
+pub fn cancer_type_for_condition(condition: &str) -> Option<CancerType> {
+    let uninverted = match condition.split_once(',') {
+        Some((head, tail)) => format!("{} {}", tail.trim(), head.trim()),
+        None => condition.to_string(),
+    };
+    let unqualified = |text: &str| -> String {
+        text.split_whitespace()
+            .filter(|w| !CONDITION_QUALIFIERS.contains(&w.to_ascii_lowercase().as_str()))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    [
+        condition.to_string(),
+        uninverted.clone(),
+        unqualified(condition),
+        unqualified(&uninverted),
+    ]
+    .iter()
+    .find_map(|text| CancerType::parse(text))
+}
+
+/// Whether `trial` enrolls patients with `cancer_code` (an OncoTree or TCGA
+/// code, or a name). A trial in a broader or narrower type counts.
+pub fn trial_in_cancer(trial: &TrialRecord, cancer_code: &str) -> bool {
+    let Some(wanted) = CancerType::parse(cancer_code) else {
+        return trial
+            .cancer_codes
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(cancer_code.trim()));
+    };
+    trial
+        .cancer_codes
+        .iter()
+        .filter_map(|c| CancerType::from_code(c))
+        .any(|c| c.is_within(wanted) || wanted.is_within(c))
+}
+
+/// Summary of the stored `trials` testing a drug against `gene` in
+/// `cancer_code`; `None` when none do, so callers can fall back to the
+/// ClinicalTrials.gov count.
+pub fn summarise_trials(
+    trials: &[TrialRecord],
+    gene: &str,
+    cancer_code: &str,
+) -> Option<TrialSummary> {
+    let matching: Vec<&TrialRecord> = trials
+        .iter()
+        .filter(|t| t.target_genes.iter().any(|g| g.eq_ignore_ascii_case(gene)))
+        .filter(|t| trial_in_cancer(t, cancer_code))
+        .collect();
+    (!matching.is_empty()).then(|| {
+        TrialSummary::from_records(gene, &condition_for_cancer_code(cancer_code), matching)
+    })
+}
+
+/// Entity type and name of each entity a linked trial's facts touch: the
+/// trial itself, then its drugs and target genes.
+fn trial_entities(trial: &TrialRecord) -> Vec<(DbEntityType, &str)> {
+    std::iter::once((DbEntityType::ClinicalTrial, trial.nct_id.as_str()))
+        .chain(
+            trial
+                .drugs
+                .iter()
+                .map(|d| (DbEntityType::Chemical, d.as_str())),
+        )
+        .chain(
+            trial
+                .target_genes
+                .iter()
+                .map(|g| (DbEntityType::Gene, g.as_str())),
+        )
+        .collect()
+}
+
+/// TRIAL–`investigates`–DRUG and TRIAL–`investigates`–GENE facts for a
+/// linked `trial`, given entity ids keyed by [`canonical_key`]. Facts whose
+/// entities have no id are skipped.
+pub fn trial_facts(trial: &TrialRecord, entity_ids: &HashMap<String, Uuid>) -> Vec<KgFact> {
+    let id = |entity_type, name: &str| entity_ids.get(&canonical_key(entity_type, name)).copied();
+    let Some(trial_id) = id(DbEntityType::ClinicalTrial, trial.nct_id.as_str()) else {
+        return Vec::new();
+    };
+    let objects = trial
+        .drugs
+        .iter()
+        .map(|d| (DbEntityType::Chemical, d, DRUG_FACT_CONFIDENCE))
+        .chain(
+            trial
+                .target_genes
+                .iter()
+                .map(|g| (DbEntityType::Gene, g, GENE_FACT_CONFIDENCE)),
+        );
+
+    let mut facts = Vec::new();
+    for (entity_type, name, confidence) in objects {
+        let Some(object_id) = id(entity_type, name.as_str()) else {
+            continue;
+        };
+        let mut fact = KgFact::new(
+            Uuid::nil(),
+            trial_id,
+            trial.nct_id.clone(),
+            INVESTIGATES_PREDICATE.to_string(),
+            object_id,
+            name.clone(),
+        );
+        fact.confidence = confidence;
+        fact.evidence_type = "provider_fact".to_string();
+        fact.study_type = Some(TRIAL_STUDY_TYPE.to_string());
+        fact.sample_size = trial.enrollment.and_then(|n| i32::try_from(n).ok());
+        fact.evidence = Some(format!(
+            "provider=clinicaltrials;nct_id={};phase={};status={};drugs={}",
+            trial.nct_id,
+            trial.phase,
+            trial.status,
+            trial.drugs.join(",")
+        ));
+        facts.push(fact);
+    }
+    facts
+}
+
+/// Counts from one [`ingest_trials`] run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrialIngestReport {
+    pub fetched: usize,
+    /// Trials with at least one known drug.
+    pub linked: usize,
+    pub facts: usize,
+}
+
+/// Fetch up to `max_results` trials for `term` (and `condition`), link
+/// them, store them in the `trials` table and write their facts.
+pub async fn ingest_trials(
+    db: Arc<Database>,
+    client: &ClinicalTrialsClient,
+    term: &str,
+    condition: Option<&str>,
+    max_results: usize,
+) -> anyhow::Result<TrialIngestReport> {
+    let mut trials = client.fetch_trials(term, condition, max_results).await?;
+    for trial in &mut trials {
+        link_trial(trial);
+    }
+    TrialRepository::new(db.clone())
+        .upsert_many(&trials)
+        .await?;
+
+    let entity_repo = EntityRepository::new(db.clone());
+    let mut entity_ids: HashMap<String, Uuid> = HashMap::new();
+    let mut facts = Vec::new();
+    for trial in trials.iter().filter(|t| !t.drugs.is_empty()) {
+        for (entity_type, name) in trial_entities(trial) {
+            let key = canonical_key(entity_type, name);
+            if !entity_ids.contains_key(&key) {
+                let id = resolve_entity(&entity_repo, entity_type, name).await?;
+                entity_ids.insert(key, id);
+            }
+        }
+        facts.extend(trial_facts(trial, &entity_ids));
+    }
+    let report = TrialIngestReport {
+        fetched: trials.len(),
+        linked: trials.iter().filter(|t| !t.drugs.is_empty()).count(),
+        facts: KgFactRepository::new(db).upsert_batch(&facts).await?,
+    };
+    info!(
+        fetched = report.fetched,
+        linked = report.linked,
+        facts = report.facts,
+        "Clinical trials stored"
+    );
+    Ok(report)
+}
+
+/// Id of the entity row for `name`: trials by NCT id, otherwise the row
+/// keyed by [`canonical_key`] or, failing that, an existing row of the same
+/// type and name (genes the NER stored under their HGNC id). Missing rows
+/// are created.
+async fn resolve_entity(
+    repo: &EntityRepository,
+    entity_type: DbEntityType,
+    name: &str,
+) -> anyhow::Result<Uuid> {
+    let external_id = match entity_type {
+        DbEntityType::ClinicalTrial => name.to_string(),
+        _ => format!("FERRUMYX:{}", canonical_key(entity_type, name)),
+    };
+    if let Some(existing) = repo
+        .find_by_external_id(&external_id)
+        .await?
+        .into_iter()
+        .next()
+    {
+        return Ok(existing.id);
+    }
+    let same_type = entity_type.to_string();
+    if let Some(existing) = repo
+        .find_by_name(name)
+        .await?
+        .into_iter()
+        .find(|e| e.entity_type == same_type)
+    {
+        return Ok(existing.id);
+    }
+    let source_db = match entity_type {
+        DbEntityType::ClinicalTrial => "clinicaltrials",
+        _ => "ferrumyx",
+    };
+    let mut entity = DbEntity::new(
+        entity_type,
+        name.to_string(),
+        external_id,
+        source_db.to_string(),
+    );
+    entity.canonical_name = Some(name.to_string());
+    repo.insert(&entity).await?;
+    Ok(entity.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::clinicaltrials::parse_study;
+
+    const PAGE_1: &str = include_str!("../tests/fixtures/clinicaltrials_v2_page1.json");
+    const PAGE_2: &str = include_str!("../tests/fixtures/clinicaltrials_v2_page2.json");
+
+    fn fixture_trials() -> Vec<TrialRecord> {
+        [PAGE_1, PAGE_2]
+            .iter()
+            .flat_map(|page| {
+                let page: serde_json::Value = serde_json::from_str(page).unwrap();
+                page["studies"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .filter_map(parse_study)
+                    .collect::<Vec<_>>()
+            })
+            .map(|mut trial| {
+                link_trial(&mut trial);
+                trial
+            })
+            .collect()
+    }
+
+    #[test]
+    fn links_drugs_targets_and_cancer_types() {
+        let trials = fixture_trials();
+        assert_eq!(trials.len(), 4);
+        assert_eq!(trials[0].drugs, ["sotorasib"]);
+        assert_eq!(trials[0].target_genes, ["KRAS"]);
+        assert_eq!(trials[0].cancer_codes, ["NSCLC"]);
+        // "Adagrasib" and its code name "MRTX849" are one drug.
+        assert_eq!(trials[1].drugs, ["adagrasib"]);
+        assert_eq!(trials[1].cancer_codes, ["COADREAD"]);
+        // A vaccine with an untargeted checkpoint inhibitor: cancers only.
+        assert!(trials[2].drugs.is_empty() && trials[2].target_genes.is_empty());
+        assert_eq!(trials[2].cancer_codes, ["COADREAD", "PAAD"]);
+        assert!(trials[3].cancer_codes.is_empty());
+
+        assert_eq!(
+            cancer_type_for_condition("Stage IV Pancreatic Cancer").map(|c| c.code()),
+            Some("PAAD")
+        );
+        assert_eq!(cancer_type_for_condition("KRAS p.G12C Mutation"), None);
+    }
+
+    #[test]
+    fn trial_facts_connect_the_trial_to_its_drugs_and_targets() {
+        let trial = &fixture_trials()[0];
+        let entity_ids: HashMap<String, Uuid> = trial_entities(trial)
+            .into_iter()
+            .map(|(t, name)| (canonical_key(t, name), Uuid::new_v4()))
+            .collect();
+        let facts = trial_facts(trial, &entity_ids);
+
+        let edges: Vec<(&str, &str, &str)> = facts
+            .iter()
+            .map(|f| {
+                (
+                    f.subject_name.as_str(),
+                    f.predicate.as_str(),
+                    f.object_name.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            edges,
+            [
+                ("NCT04303780", "investigates", "sotorasib"),
+                ("NCT04303780", "investigates", "KRAS"),
+            ]
+        );
+        assert!(facts.iter().all(|f| f.paper_id.is_nil()));
+        assert_eq!(facts[0].sample_size, Some(345));
+        assert!(facts[0].confidence > facts[1].confidence);
+        assert!(facts[1]
+            .evidence
+            .as_deref()
+            .is_some_and(|e| e.contains("phase=PHASE3")));
+
+        // Without an id for the trial there is nothing to connect.
+        let mut without_trial = entity_ids.clone();
+        without_trial.remove(&canonical_key(DbEntityType::ClinicalTrial, &trial.nct_id));
+        assert!(trial_facts(trial, &without_trial).is_empty());
+    }
+
+    #[test]
+    fn stored_trials_summarise_by_gene_and_cancer_type() {
+        let trials = fixture_trials();
+
+        let lung = summarise_trials(&trials, "kras", "LUAD").unwrap();
+        assert_eq!(lung.total, 1);
+        assert_eq!(lung.phase3_or_later(), 1);
+        assert_eq!(lung.top_trials[0].nct_id, "NCT04303780");
+
+        let colorectal = summarise_trials(&trials, "KRAS", "COAD").unwrap();
+        assert_eq!(colorectal.by_status["RECRUITING"], 1);
+        assert_eq!(colorectal.condition, "colorectal cancer");
+
+        assert_eq!(summarise_trials(&trials, "KRAS", "PAAD"), None);
+        assert_eq!(summarise_trials(&trials, "EGFR", "NSCLC"), None);
+    }
+
+    #[tokio::test]
+    async fn resolve_entity_reuses_genes_stored_under_their_hgnc_id() {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-trials-{}", Uuid::new_v4()));
+        let db = Arc::new(Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        let repo = EntityRepository::new(db);
+        let kras = DbEntity::new(
+            DbEntityType::Gene,
+            "KRAS".to_string(),
+            "HGNC:6407".to_string(),
+            "hgnc".to_string(),
+        );
+        repo.insert(&kras).await.unwrap();
+
+        let gene = resolve_entity(&repo, DbEntityType::Gene, "KRAS")
+            .await
+            .unwrap();
+        assert_eq!(gene, kras.id);
+        let trial = resolve_entity(&repo, DbEntityType::ClinicalTrial, "NCT04303780")
+            .await
+            .unwrap();
+        assert_eq!(
+            resolve_entity(&repo, DbEntityType::ClinicalTrial, "NCT04303780")
+                .await
+                .unwrap(),
+            trial
+        );
+        let stored = repo.find_by_external_id("NCT04303780").await.unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].entity_type, "clinical_trial");
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,242 @@
+//! Re-ranking over a small fixture corpus where vector retrieval puts a
+//! merely on-topic chunk above the one that answers the query.
+//!
+//! Run with: cargo test --package ferrumyx-ingestion --test test_rerank
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use ferrumyx_db::chunks::ChunkRepository;
+use ferrumyx_db::schema::{Chunk, EMBEDDING_DIM};
+use ferrumyx_db::Database;
+use ferrumyx_ingestion::embedding::{hybrid_search, HybridSearchConfig, SearchResult};
+use ferrumyx_ingestion::repository::IngestionRepository;
+use ferrumyx_ingestion::rerank::{
+    rerank_results, rerank_with, RelevanceScorer, RerankConfig, RerankerKind,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+struct Corpus {
+    query: String,
+    query_axes: Vec<usize>,
+    relevant: String,
+    chunks: Vec<FixtureChunk>,
+}
+
+#[derive(Deserialize)]
+struct FixtureChunk {
+    key: String,
+    content: String,
+    axes: Vec<usize>,
+    /// Grade the mock LLM gives this passage.
+    grade: u32,
+}
+
+fn load_corpus() -> Corpus {
+    let raw = std::fs::read_to_string(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/rerank_corpus.json"
+    ))
+    .unwrap();
+    serde_json::from_str(&raw).unwrap()
+}
+
+fn unit(axes: &[usize]) -> Vec<f32> {
+    let mut v = vec![0.0f32; EMBEDDING_DIM];
+    for &i in axes {
+        v[i] = 1.0;
+    }
+    let norm = (axes.len() as f32).sqrt();
+    v.into_iter().map(|x| x / norm).collect()
+}
+
+/// Index the corpus and return the vector-only hits plus fixture keys by
+/// chunk id.
+async fn vector_hits(
+    corpus: &Corpus,
+) -> (std::path::PathBuf, Vec<SearchResult>, Vec<(Uuid, String)>) {
+    let dir = std::env::temp_dir().join(format!("ferrumyx-rerank-{}", Uuid::new_v4()));
+    let db = Arc::new(Database::open(&dir).await.unwrap());
+    db.initialize().await.unwrap();
+
+    let paper_id = Uuid::new_v4();
+    let chunks: Vec<Chunk> = corpus
+        .chunks
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let mut chunk = Chunk::new(paper_id, i as i64, c.content.clone());
+            chunk.embedding = Some(unit(&c.axes));
+            chunk
+        })
+        .collect();
+    ChunkRepository::new(db.clone())
+        .insert_batch(&chunks)
+        .await
+        .unwrap();
+    let keys = chunks
+        .iter()
+        .zip(&corpus.chunks)
+        .map(|(chunk, c)| (chunk.id, c.key.clone()))
+        .collect();
+
+    let cfg = HybridSearchConfig {
+        limit: 50,
+        use_fts: false,
+        ..HybridSearchConfig::default()
+    };
+    let hits = hybrid_search(
+        &IngestionRepository::new(db),
+        &corpus.query,
+        Some(unit(&corpus.query_axes)),
+        &cfg,
+    )
+    .await
+    .unwrap();
+    (dir, hits, keys)
+}
+
+fn key_of<'a>(keys: &'a [(Uuid, String)], id: Uuid) -> &'a str {
+    keys.iter().find(|(k, _)| *k == id).map(|(_, v)| v).unwrap()
+}
+
+/// Minimal Ollama `/api/generate` stand-in that grades each passage by
+/// the fixture's `grade`.
+async fn serve_mock_ollama(corpus: &Corpus) -> String {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let grades: Vec<(String, u32)> = corpus
+        .chunks
+        .iter()
+        .map(|c| (c.content.clone(), c.grade))
+        .collect();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let grades = grades.clone();
+            tokio::spawn(async move {
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                let body_start = loop {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                    if let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                        let head = String::from_utf8_lossy(&request[..end]).to_lowercase();
+                        let len = head
+                            .lines()
+                            .find_map(|l| l.strip_prefix("content-length:"))
+                            .and_then(|v| v.trim().parse::<usize>().ok())
+                            .unwrap_or(0);
+                        while request.len() < end + 4 + len {
+                            let n = socket.read(&mut buf).await.unwrap();
+                            request.extend_from_slice(&buf[..n]);
+                        }
+                        break end + 4;
+                    }
+                };
+                let body: serde_json::Value =
+                    serde_json::from_slice(&request[body_start..]).unwrap();
+                let prompt = body["prompt"].as_str().unwrap_or_default();
+                let grade = grades
+                    .iter()
+                    .find(|(content, _)| prompt.contains(content.as_str()))
+                    .map(|(_, g)| *g)
+                    .unwrap_or(0);
+                let reply = serde_json::json!({ "response": grade.to_string() }).to_string();
+                let head = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    reply.len()
+                );
+                socket.write_all(head.as_bytes()).await.unwrap();
+                socket.write_all(reply.as_bytes()).await.unwrap();
+                socket.shutdown().await.unwrap();
+            });
+        }
+    });
+    url
+}
+
+#[tokio::test]
+async fn llm_reranker_fixes_vector_inversion() {
+    let corpus = load_corpus();
+    let (dir, hits, keys) = vector_hits(&corpus).await;
+    assert_ne!(key_of(&keys, hits[0].chunk_id), corpus.relevant);
+
+    let cfg = RerankConfig {
+        kind: RerankerKind::Llm,
+        ollama_base_url: serve_mock_ollama(&corpus).await,
+        timeout: Duration::from_secs(10),
+        ..RerankConfig::default()
+    };
+    let (rows, report) = rerank_results(&corpus.query, hits, 3, &cfg).await;
+
+    assert!(report.applied, "{:?}", report.fallback);
+    assert_eq!(report.reranker, RerankerKind::Llm);
+    assert_eq!(report.candidates, corpus.chunks.len());
+    assert_eq!(rows.len(), 3);
+    assert_eq!(key_of(&keys, rows[0].chunk_id), corpus.relevant);
+    assert_eq!(rows[0].rerank_score, Some(9.0));
+
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+struct SlowScorer;
+
+#[async_trait]
+impl RelevanceScorer for SlowScorer {
+    async fn score(&self, _query: &str, passages: &[String]) -> anyhow::Result<Vec<f32>> {
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        Ok(vec![1.0; passages.len()])
+    }
+}
+
+#[tokio::test]
+async fn reranker_timeout_keeps_vector_order() {
+    let corpus = load_corpus();
+    let (dir, hits, _keys) = vector_hits(&corpus).await;
+    let vector_order: Vec<Uuid> = hits.iter().take(3).map(|r| r.chunk_id).collect();
+
+    let cfg = RerankConfig {
+        kind: RerankerKind::Llm,
+        timeout: Duration::from_millis(50),
+        ..RerankConfig::default()
+    };
+    let (rows, report) = rerank_with(&corpus.query, hits, 3, &SlowScorer, &cfg).await;
+
+    assert!(!report.applied);
+    assert!(report
+        .fallback
+        .as_deref()
+        .is_some_and(|r| r.contains("timed out")));
+    assert!(report.latency_ms < 5000);
+    assert_eq!(
+        rows.iter().map(|r| r.chunk_id).collect::<Vec<_>>(),
+        vector_order
+    );
+
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+#[ignore] // Requires network access to download the cross-encoder
+async fn cross_encoder_reranker_fixes_vector_inversion() {
+    let corpus = load_corpus();
+    let (dir, hits, keys) = vector_hits(&corpus).await;
+
+    let cfg = RerankConfig {
+        kind: RerankerKind::CrossEncoder,
+        timeout: Duration::from_secs(300),
+        ..RerankConfig::default()
+    };
+    let (rows, report) = rerank_results(&corpus.query, hits, 3, &cfg).await;
+
+    assert!(report.applied, "{:?}", report.fallback);
+    assert_eq!(key_of(&keys, rows[0].chunk_id), corpus.relevant);
+
+    let _ = std::fs::remove_dir_all(dir);
+}
@@ -1,9 +1,39 @@
 //! Conflict detection and resolution for KG facts.
 //! See ARCHITECTURE.md §3.6
+//!
+//! [`detect_conflicts`] pairs facts about the same (subject, object) whose
+//! predicates contradict each other, e.g. `activates_pathway` against
+//! `suppresses_pathway`, or `sensitized_by` against `resistance_to` for the
+//! same gene–drug pair.
 
 use ferrumyx_common::confidence::contradictory_confidence;
+use ferrumyx_db::kg_facts::fact_supports;
+use ferrumyx_db::schema::KgFact;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
 use uuid::Uuid;
 
+/// Predicate pairs that cannot both hold for one (subject, object).
+pub const ANTONYMOUS_PREDICATES: &[(&str, &str)] = &[
+    ("activates", "inhibits"),
+    ("activates_pathway", "suppresses_pathway"),
+    ("sensitizes_to", "confers_resistance"),
+    ("sensitized_by", "resistance_to"),
+    ("upregulated_in", "downregulated_in"),
+    (
+        "prognostic_for_poor_outcome",
+        "prognostic_for_better_outcome",
+    ),
+];
+
+/// Whether `a` and `b` are an antonymous pair, in either order.
+pub fn are_antonymous(a: &str, b: &str) -> bool {
+    ANTONYMOUS_PREDICATES.iter().any(|(x, y)| {
+        (a.eq_ignore_ascii_case(x) && b.eq_ignore_ascii_case(y))
+            || (a.eq_ignore_ascii_case(y) && b.eq_ignore_ascii_case(x))
+    })
+}
+
 /// Classification of a detected conflict.
 #[derive(Debug, Clone, PartialEq)]
 pub enum ConflictType {
@@ -25,7 +55,7 @@ pub struct KgConflict {
     pub resolution: ConflictResolution,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum ConflictResolution {
     Unresolved,
     Resolved,
@@ -70,6 +100,137 @@ pub fn evaluate_conflict(
     })
 }
 
+/// How much a contradiction matters, from the confidence gap between the
+/// two facts: a close call is more serious than a weak claim against a
+/// strong one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictSeverity {
+    Low,
+    Medium,
+    High,
+}
+
+impl ConflictSeverity {
+    pub fn from_gap(gap: f64) -> Self {
+        if gap <= 0.15 {
+            Self::High
+        } else if gap <= 0.40 {
+            Self::Medium
+        } else {
+            Self::Low
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+}
+
+/// Two facts about the same (subject, object) with antonymous predicates.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Conflict {
+    pub fact_a_id: Uuid,
+    pub fact_b_id: Uuid,
+    pub subject_id: Uuid,
+    pub object_id: Uuid,
+    pub predicate_a: String,
+    pub predicate_b: String,
+    /// Papers behind either fact, sorted.
+    pub paper_ids: Vec<Uuid>,
+    pub confidence_gap: f64,
+    pub severity: ConflictSeverity,
+    pub net_confidence: f64,
+    pub resolution: ConflictResolution,
+}
+
+impl Conflict {
+    pub fn to_record(&self) -> ferrumyx_db::schema::KgConflict {
+        let mut record = ferrumyx_db::schema::KgConflict::new(
+            self.fact_a_id,
+            self.fact_b_id,
+            format!("{:?}", ConflictType::Directional),
+            self.net_confidence as f32,
+            format!("{:?}", self.resolution),
+        );
+        record.severity = Some(self.severity.as_str().to_string());
+        record.paper_ids = self.paper_ids.clone();
+        record
+    }
+}
+
+/// Contradictions among current (not superseded) `facts`, grouped by
+/// (subject_id, object_id). Each pair is reported once, with the lower
+/// fact id as `fact_a`.
+pub fn detect_conflicts(facts: &[KgFact]) -> Vec<Conflict> {
+    let mut groups: BTreeMap<(Uuid, Uuid), Vec<&KgFact>> = BTreeMap::new();
+    for fact in facts.iter().filter(|f| f.valid_until.is_none()) {
+        groups
+            .entry((fact.subject_id, fact.object_id))
+            .or_default()
+            .push(fact);
+    }
+
+    let mut out = Vec::new();
+    for ((subject_id, object_id), mut group) in groups {
+        group.sort_by_key(|f| f.id);
+        for (i, a) in group.iter().enumerate() {
+            for b in &group[i + 1..] {
+                if !are_antonymous(&a.predicate, &b.predicate) {
+                    continue;
+                }
+                let (ca, cb) = (a.confidence as f64, b.confidence as f64);
+                let Some(evaluated) = evaluate_conflict(ca, cb, true) else {
+                    continue;
+                };
+                let paper_ids: BTreeSet<Uuid> = fact_supports(a)
+                    .into_iter()
+                    .chain(fact_supports(b))
+                    .map(|s| s.paper_id)
+                    .collect();
+                let gap = (ca - cb).abs();
+                out.push(Conflict {
+                    fact_a_id: a.id,
+                    fact_b_id: b.id,
+                    subject_id,
+                    object_id,
+                    predicate_a: a.predicate.clone(),
+                    predicate_b: b.predicate.clone(),
+                    paper_ids: paper_ids.into_iter().collect(),
+                    confidence_gap: gap,
+                    severity: ConflictSeverity::from_gap(gap),
+                    net_confidence: evaluated.net_confidence,
+                    resolution: evaluated.resolution,
+                });
+            }
+        }
+    }
+    out
+}
+
+/// Confidence a fact contributes to scoring given the net confidences of
+/// its conflicts: None when a conflict excludes it, ×0.70 when one
+/// leaves it disputed.
+pub fn scoring_confidence(
+    confidence: f64,
+    conflict_net_confidences: impl IntoIterator<Item = f64>,
+) -> Option<f64> {
+    let mut adjusted = confidence;
+    for net in conflict_net_confidences {
+        if !should_include_in_scoring(net) {
+            return None;
+        }
+        if net <= 0.60 {
+            adjusted *= 0.70;
+        }
+    }
+    Some(adjusted)
+}
+
 /// Determine whether a conflicted fact should be included in scoring.
 /// See ARCHITECTURE.md §3.6 step 4.
 pub fn should_include_in_scoring(net_confidence: f64) -> bool {
@@ -99,6 +260,65 @@ mod tests {
         ));
     }
 
+    fn fact(id: u128, predicate: &str, object: u128, confidence: f32) -> KgFact {
+        let mut fact = KgFact::new(
+            Uuid::from_u128(100 + id),
+            Uuid::from_u128(1),
+            "KRAS".to_string(),
+            predicate.to_string(),
+            Uuid::from_u128(object),
+            "object".to_string(),
+        );
+        fact.id = Uuid::from_u128(id);
+        fact.confidence = confidence;
+        fact
+    }
+
+    #[test]
+    fn detects_antonymous_predicates_per_subject_object_pair() {
+        let mut superseded = fact(5, "inhibits", 30, 0.9);
+        superseded.valid_until = Some(chrono::Utc::now());
+        let facts = vec![
+            fact(1, "activates_pathway", 10, 0.85),
+            fact(2, "suppresses_pathway", 10, 0.80),
+            // Same predicates, different object: no conflict.
+            fact(3, "suppresses_pathway", 11, 0.9),
+            fact(4, "activates", 30, 0.9),
+            superseded,
+            fact(6, "sensitized_by", 20, 0.9),
+            fact(7, "resistance_to", 20, 0.4),
+            fact(8, "targeted_by", 20, 0.9),
+        ];
+        let conflicts = detect_conflicts(&facts);
+
+        let pairs: Vec<(u128, u128, ConflictSeverity)> = conflicts
+            .iter()
+            .map(|c| (c.fact_a_id.as_u128(), c.fact_b_id.as_u128(), c.severity))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                (1, 2, ConflictSeverity::High),
+                (6, 7, ConflictSeverity::Low),
+            ]
+        );
+        assert_eq!(
+            conflicts[0].paper_ids,
+            vec![Uuid::from_u128(101), Uuid::from_u128(102)]
+        );
+        let record = conflicts[0].to_record();
+        assert_eq!(record.severity.as_deref(), Some("high"));
+        assert_eq!(record.conflict_type, "Directional");
+    }
+
+    #[test]
+    fn conflicts_down_weight_scoring_confidence() {
+        assert_eq!(scoring_confidence(0.8, []), Some(0.8));
+        let disputed = scoring_confidence(0.8, [0.45]).unwrap();
+        assert!((disputed - 0.56).abs() < 1e-9);
+        assert_eq!(scoring_confidence(0.8, [0.9, 0.1]), None);
+    }
+
     #[test]
     fn test_low_net_confidence_excluded_from_scoring() {
         assert!(!should_include_in_scoring(0.15));
@@ -1,8 +1,12 @@
 //! Knowledge graph fact extraction from text.
 //! Ported from Python scripts/build_kg.py and extended for typed pair extraction.
 
+use crate::ner::hgvs::NormalisedMutation;
+use crate::ner::{EntityType, ExtractedEntity, HgvsMutationNormaliser};
+use ferrumyx_db::schema::KgFact;
 use regex::Regex;
 use std::collections::HashSet;
+use uuid::Uuid;
 
 /// Cancer type mappings from keywords to TCGA codes.
 const CANCER_KEYWORDS: &[(&str, &str)] = &[
@@ -107,28 +111,24 @@ pub fn extract_cancer_type_from_lower(text_lower: &str) -> Option<String> {
     None
 }
 
-/// Extract mutation patterns (e.g., G12D, V600E, KRAS G12C).
+/// Extract mutation mentions (e.g., G12D, p.Gly12Asp, KRAS G12C, exon 19
+/// deletion), one per allele of multi-allele shorthand.
 pub fn extract_mutations(text: &str) -> Vec<MutationMention> {
-    let re = lazy_mutation_regex();
-    let mut mutations = Vec::new();
-    for cap in re.captures_iter(text) {
-        let full_match = cap[0].to_string();
-        let protein_change = cap.get(1).map(|m| m.as_str().to_string());
-        mutations.push(MutationMention {
-            text: full_match,
-            protein_change,
-        });
-    }
-    mutations
+    lazy_mutation_normaliser()
+        .extract(text)
+        .into_iter()
+        .map(|(span, mutation)| MutationMention {
+            text: text[span].to_string(),
+            protein_change: mutation.short_form(),
+            mutation,
+        })
+        .collect()
 }
 
-fn lazy_mutation_regex() -> &'static Regex {
+fn lazy_mutation_normaliser() -> &'static HgvsMutationNormaliser {
     use std::sync::OnceLock;
-    static RE: OnceLock<Regex> = OnceLock::new();
-    RE.get_or_init(|| {
-        // Match patterns like: G12D, V600E, KRAS G12C, KRAS_G12D
-        Regex::new(r"(?i)\b([A-Z]\d+[A-Z])\b").unwrap()
-    })
+    static NORMALISER: OnceLock<HgvsMutationNormaliser> = OnceLock::new();
+    NORMALISER.get_or_init(HgvsMutationNormaliser::new)
 }
 
 fn lazy_sentence_split_regex() -> &'static Regex {
@@ -165,20 +165,32 @@ fn lazy_cell_line_regex() -> &'static Regex {
     RE.get_or_init(|| Regex::new(r"\b([A-Z]{1,5}-?\d{2,4}[A-Z0-9]*)\b").unwrap())
 }
 
-fn split_into_sentences(text: &str) -> Vec<String> {
-    lazy_sentence_split_regex()
-        .split(text)
-        .map(str::trim)
-        .filter(|s| !s.is_empty())
-        .map(ToString::to_string)
-        .collect()
+/// Trimmed, non-empty sentences of `text` with their byte offsets.
+fn split_into_sentences(text: &str) -> Vec<(usize, &str)> {
+    let mut out = Vec::new();
+    let mut push = |start: usize, end: usize| {
+        let piece = &text[start..end];
+        let trimmed = piece.trim();
+        if !trimmed.is_empty() {
+            out.push((start + piece.len() - piece.trim_start().len(), trimmed));
+        }
+    };
+    let mut start = 0;
+    for sep in lazy_sentence_split_regex().find_iter(text) {
+        push(start, sep.start());
+        start = sep.end();
+    }
+    push(start, text.len());
+    out
 }
 
 /// A mention of a mutation in text.
 #[derive(Debug, Clone)]
 pub struct MutationMention {
     pub text: String,
+    /// One-letter protein change of a substitution, e.g. "G12D".
     pub protein_change: Option<String>,
+    pub mutation: NormalisedMutation,
 }
 
 #[derive(Debug, Clone)]
@@ -187,6 +199,8 @@ pub struct ExtractedFact {
     pub subject: String,
     pub object: String,
     pub evidence_count: i32,
+    /// Byte range in the input text of the sentence the fact was read from.
+    pub evidence_span: Option<(usize, usize)>,
 }
 
 /// Fast pattern-based relation extraction.
@@ -665,9 +679,9 @@ pub fn build_facts_batch(gene_symbols: &[String], text: &str) -> Vec<ExtractedFa
     let extractor = lazy_relation_extractor();
     let text_lower = text.to_lowercase();
     let global_cancer = extract_cancer_type_from_lower(&text_lower);
-    let global_mutations: Vec<String> = extract_mutations(text)
+    let global_mutations: Vec<NormalisedMutation> = extract_mutations(text)
         .into_iter()
-        .filter_map(|m| m.protein_change)
+        .map(|m| m.mutation)
         .collect();
 
     let mut out = Vec::new();
@@ -677,19 +691,21 @@ pub fn build_facts_batch(gene_symbols: &[String], text: &str) -> Vec<ExtractedFa
         .map(|g| (g.trim().to_uppercase(), g.trim().to_lowercase()))
         .filter(|(up, lc)| !up.is_empty() && !lc.is_empty())
         .collect();
+    let gene_set: HashSet<&str> = normalized_genes.iter().map(|(up, _)| up.as_str()).collect();
 
-    for sentence in split_into_sentences(text) {
+    for (offset, sentence) in split_into_sentences(text) {
+        let span = Some((offset, offset + sentence.len()));
         let sentence_lower = sentence.to_lowercase();
-        let matched_predicates = extractor.matched_predicates(&sentence);
+        let matched_predicates = extractor.matched_predicates(sentence);
         let sentence_cancer =
             extract_cancer_type_from_lower(&sentence_lower).or(global_cancer.clone());
-        let sentence_mutations: Vec<String> = extract_mutations(&sentence)
+        let sentence_mutations: Vec<NormalisedMutation> = extract_mutations(sentence)
             .into_iter()
-            .filter_map(|m| m.protein_change)
+            .map(|m| m.mutation)
             .collect();
-        let chemicals = detect_chemical_mentions(&sentence, &sentence_lower, 6);
-        let pathways = detect_pathway_mentions(&sentence, &sentence_lower, 6);
-        let cell_lines = detect_cell_line_mentions(&sentence, &sentence_lower, 6);
+        let chemicals = detect_chemical_mentions(sentence, &sentence_lower, 6);
+        let pathways = detect_pathway_mentions(sentence, &sentence_lower, 6);
+        let cell_lines = detect_cell_line_mentions(sentence, &sentence_lower, 6);
 
         for (gene_up, gene_lower) in &normalized_genes {
             if !contains_symbol_ci(&sentence_lower, gene_lower) {
@@ -707,6 +723,7 @@ pub fn build_facts_batch(gene_symbols: &[String], text: &str) -> Vec<ExtractedFa
                             subject: key.1,
                             object: key.2,
                             evidence_count: 1,
+                            evidence_span: span,
                         });
                     }
                     if pred != "associated_with" {
@@ -715,11 +732,14 @@ pub fn build_facts_batch(gene_symbols: &[String], text: &str) -> Vec<ExtractedFa
                 }
             }
 
-            for mutation in &sentence_mutations {
+            for mutation in sentence_mutations
+                .iter()
+                .filter(|m| mutation_applies(m, gene_up, &gene_set))
+            {
                 let key = (
                     "has_mutation".to_string(),
                     gene_up.clone(),
-                    mutation.to_uppercase(),
+                    mutation.label(),
                 );
                 if seen_rel.insert(key.clone()) {
                     out.push(ExtractedFact {
@@ -727,6 +747,7 @@ pub fn build_facts_batch(gene_symbols: &[String], text: &str) -> Vec<ExtractedFa
                         subject: key.1,
                         object: key.2,
                         evidence_count: 1,
+                        evidence_span: span,
                     });
                 }
                 has_typed_relation = true;
@@ -734,7 +755,7 @@ pub fn build_facts_batch(gene_symbols: &[String], text: &str) -> Vec<ExtractedFa
                     let key = (
                         "mutation_confers_resistance".to_string(),
                         gene_up.clone(),
-                        mutation.to_uppercase(),
+                        mutation.label(),
                     );
                     if seen_rel.insert(key.clone()) {
                         out.push(ExtractedFact {
@@ -742,6 +763,7 @@ pub fn build_facts_batch(gene_symbols: &[String], text: &str) -> Vec<ExtractedFa
                             subject: key.1,
                             object: key.2,
                             evidence_count: 1,
+                            evidence_span: span,
                         });
                     }
                 }
@@ -756,6 +778,7 @@ pub fn build_facts_batch(gene_symbols: &[String], text: &str) -> Vec<ExtractedFa
                             subject: key.1,
                             object: key.2,
                             evidence_count: 1,
+                            evidence_span: span,
                         });
                     }
                     has_typed_relation = true;
@@ -771,6 +794,7 @@ pub fn build_facts_batch(gene_symbols: &[String], text: &str) -> Vec<ExtractedFa
                             subject: key.1,
                             object: key.2,
                             evidence_count: 1,
+                            evidence_span: span,
                         });
                     }
                     has_typed_relation = true;
@@ -786,6 +810,7 @@ pub fn build_facts_batch(gene_symbols: &[String], text: &str) -> Vec<ExtractedFa
                             subject: key.1,
                             object: key.2,
                             evidence_count: 1,
+                            evidence_span: span,
                         });
                     }
                     has_typed_relation = true;
@@ -806,6 +831,7 @@ pub fn build_facts_batch(gene_symbols: &[String], text: &str) -> Vec<ExtractedFa
                             subject: key.1,
                             object: key.2,
                             evidence_count: 1,
+                            evidence_span: span,
                         });
                     }
                 }
@@ -815,12 +841,15 @@ pub fn build_facts_batch(gene_symbols: &[String], text: &str) -> Vec<ExtractedFa
 
     if out.is_empty() && !global_mutations.is_empty() {
         // Safety fallback for sparse text: at least preserve mutation observations.
-        for (gene_up, _) in normalized_genes {
-            for mutation in &global_mutations {
+        for (gene_up, _) in &normalized_genes {
+            for mutation in global_mutations
+                .iter()
+                .filter(|m| mutation_applies(m, gene_up, &gene_set))
+            {
                 let key = (
                     "has_mutation".to_string(),
                     gene_up.clone(),
-                    mutation.to_uppercase(),
+                    mutation.label(),
                 );
                 if seen_rel.insert(key.clone()) {
                     out.push(ExtractedFact {
@@ -828,6 +857,7 @@ pub fn build_facts_batch(gene_symbols: &[String], text: &str) -> Vec<ExtractedFa
                         subject: key.1,
                         object: key.2,
                         evidence_count: 1,
+                        evidence_span: None,
                     });
                 }
             }
@@ -837,9 +867,249 @@ pub fn build_facts_batch(gene_symbols: &[String], text: &str) -> Vec<ExtractedFa
     out
 }
 
+/// Whether `mutation` can be read as a mutation of `gene`. A fusion needs
+/// the gene as a partner, and a change written against another of the
+/// genes ("BRAF V600E" next to KRAS) stays with that gene.
+fn mutation_applies(mutation: &NormalisedMutation, gene: &str, genes: &HashSet<&str>) -> bool {
+    if let Some((five, three)) = &mutation.fusion_partners {
+        return five == gene || three == gene;
+    }
+    match mutation.gene.as_deref() {
+        Some(context) => context == gene || !genes.contains(context),
+        None => true,
+    }
+}
+
+/// Confidence of a gene–disease link through a mutation entity.
+const MUTATION_LINK_CONFIDENCE: f32 = 0.90;
+/// Confidence of a gene–disease link through "mutated in" phrasing only.
+const MUTATION_PHRASE_CONFIDENCE: f32 = 0.75;
+const INHIBITION_VERB_CONFIDENCE: f32 = 0.90;
+/// Confidence of "an EGFR inhibitor" style noun phrases.
+const INHIBITOR_NOUN_CONFIDENCE: f32 = 0.85;
+const BIOMARKER_CONFIDENCE: f32 = 0.80;
+const CO_OCCURRENCE_CONFIDENCE: f32 = 0.40;
+
+/// A typed relation between two entities of one sentence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SentenceRelation {
+    pub predicate: &'static str,
+    pub subject: String,
+    pub subject_type: EntityType,
+    pub object: String,
+    pub object_type: EntityType,
+    /// The sentence the relation was read from.
+    pub evidence: String,
+    /// Higher for more specific rules; `co_occurs_with` is lowest.
+    pub confidence: f32,
+}
+
+impl SentenceRelation {
+    /// KG fact for this relation between resolved entity rows.
+    pub fn to_kg_fact(&self, paper_id: Uuid, subject_id: Uuid, object_id: Uuid) -> KgFact {
+        let mut fact = KgFact::new(
+            paper_id,
+            subject_id,
+            self.subject.clone(),
+            self.predicate.to_string(),
+            object_id,
+            self.object.clone(),
+        );
+        fact.confidence = self.confidence;
+        fact.evidence = Some(self.evidence.clone());
+        fact.evidence_type = if self.predicate == "co_occurs_with" {
+            "generic_relation"
+        } else {
+            "typed_relation"
+        }
+        .to_string();
+        fact
+    }
+}
+
+fn lazy_inhibition_regex() -> &'static Regex {
+    use std::sync::OnceLock;
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(?i)\b(?:inhibit(?:s|ed|ing)?|suppress(?:es|ed|ing)?|block(?:s|ed|ing)?|inhibitors? of)\b",
+        )
+        .unwrap()
+    })
+}
+
+fn lazy_inhibitor_noun_regex() -> &'static Regex {
+    use std::sync::OnceLock;
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?i)^[\s-]*(?:[\w-]+\s+)?inhibitors?\b").unwrap())
+}
+
+fn lazy_negation_regex() -> &'static Regex {
+    use std::sync::OnceLock;
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)\b(?:not|no|neither|nor|without|fail(?:s|ed)? to)\b").unwrap()
+    })
+}
+
+fn lazy_mutation_phrase_regex() -> &'static Regex {
+    use std::sync::OnceLock;
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?i)\bmutat(?:ed|ions?|ant)\b").unwrap())
+}
+
+fn lazy_biomarker_regex() -> &'static Regex {
+    use std::sync::OnceLock;
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(?i)\b(?:biomarkers?|prognos(?:is|tic)|predict(?:s|ed|ive)?\b.{0,40}?\b(?:response|benefit|survival|outcome)|(?:overall|progression-free|poor|worse|improved) survival|response to)\b",
+        )
+        .unwrap()
+    })
+}
+
+/// Typed relations between the `entities` of one `sentence` (offsets into
+/// the sentence):
+///
+/// * `GENE mutated_in DISEASE` when a mutation entity next to the gene, or
+///   between gene and disease, links them, or on "mutated in" phrasing;
+/// * `CHEMICAL inhibits GENE` on an un-negated inhibition verb between
+///   them, or a "GENE inhibitor" noun phrase;
+/// * `GENE biomarker_for DISEASE` on response or prognosis phrasing;
+/// * `co_occurs_with` for any other gene, disease or chemical pair.
+///
+/// Cancer types are named by their OncoTree code, other entities by their
+/// canonical name, falling back to the matched text.
+pub fn extract_sentence_relations(
+    sentence: &str,
+    entities: &[ExtractedEntity],
+) -> Vec<SentenceRelation> {
+    let is_disease =
+        |e: &ExtractedEntity| matches!(e.label, EntityType::Disease | EntityType::CancerType);
+    let genes: Vec<&ExtractedEntity> = entities
+        .iter()
+        .filter(|e| e.label == EntityType::Gene)
+        .collect();
+    let diseases: Vec<&ExtractedEntity> = entities.iter().filter(|e| is_disease(e)).collect();
+    let chemicals: Vec<&ExtractedEntity> = entities
+        .iter()
+        .filter(|e| e.label == EntityType::Chemical)
+        .collect();
+    let mutations: Vec<&ExtractedEntity> = entities
+        .iter()
+        .filter(|e| e.label == EntityType::Mutation)
+        .collect();
+    let biomarker_phrase = lazy_biomarker_regex().is_match(sentence);
+
+    let mut out: Vec<SentenceRelation> = Vec::new();
+    let mut push = |predicate: &'static str,
+                    subject: &ExtractedEntity,
+                    object: &ExtractedEntity,
+                    confidence: f32| {
+        let (subject_name, object_name) = (relation_name(subject), relation_name(object));
+        if let Some(existing) = out.iter_mut().find(|r| {
+            r.predicate == predicate && r.subject == subject_name && r.object == object_name
+        }) {
+            existing.confidence = existing.confidence.max(confidence);
+            return;
+        }
+        out.push(SentenceRelation {
+            predicate,
+            subject: subject_name,
+            subject_type: subject.label,
+            object: object_name,
+            object_type: object.label,
+            evidence: sentence.trim().to_string(),
+            confidence,
+        });
+    };
+
+    for gene in &genes {
+        for disease in &diseases {
+            let mut typed = false;
+            let linked_by_mutation = mutations
+                .iter()
+                .any(|m| adjacent(sentence, gene, m) || between(gene, m, disease));
+            if linked_by_mutation {
+                push("mutated_in", gene, disease, MUTATION_LINK_CONFIDENCE);
+                typed = true;
+            } else if lazy_mutation_phrase_regex().is_match(gap(sentence, gene, disease)) {
+                push("mutated_in", gene, disease, MUTATION_PHRASE_CONFIDENCE);
+                typed = true;
+            }
+            if biomarker_phrase {
+                push("biomarker_for", gene, disease, BIOMARKER_CONFIDENCE);
+                typed = true;
+            }
+            if !typed {
+                push("co_occurs_with", gene, disease, CO_OCCURRENCE_CONFIDENCE);
+            }
+        }
+    }
+
+    for chemical in &chemicals {
+        for gene in &genes {
+            let between_text = gap(sentence, chemical, gene);
+            let inhibition = if lazy_negation_regex().is_match(between_text) {
+                None
+            } else if lazy_inhibition_regex().is_match(between_text) {
+                Some(INHIBITION_VERB_CONFIDENCE)
+            } else if lazy_inhibitor_noun_regex().is_match(sentence.get(gene.end..).unwrap_or("")) {
+                Some(INHIBITOR_NOUN_CONFIDENCE)
+            } else {
+                None
+            };
+            match inhibition {
+                Some(confidence) => push("inhibits", chemical, gene, confidence),
+                None => push("co_occurs_with", chemical, gene, CO_OCCURRENCE_CONFIDENCE),
+            }
+        }
+        for disease in &diseases {
+            push(
+                "co_occurs_with",
+                chemical,
+                disease,
+                CO_OCCURRENCE_CONFIDENCE,
+            );
+        }
+    }
+
+    out
+}
+
+fn relation_name(entity: &ExtractedEntity) -> String {
+    let canonical = match entity.label {
+        EntityType::CancerType => entity.canonical_id.as_ref(),
+        _ => entity.canonical_name.as_ref(),
+    };
+    canonical.unwrap_or(&entity.text).clone()
+}
+
+/// Text strictly between two non-overlapping spans, or "" if they overlap.
+fn gap<'a>(sentence: &'a str, a: &ExtractedEntity, b: &ExtractedEntity) -> &'a str {
+    let (first, second) = if a.start <= b.start { (a, b) } else { (b, a) };
+    sentence.get(first.end..second.start).unwrap_or("")
+}
+
+/// Whether `m` follows or precedes `gene` with only separators between,
+/// as in "KRAS G12D" or "KRAS-G12D".
+fn adjacent(sentence: &str, gene: &ExtractedEntity, m: &ExtractedEntity) -> bool {
+    (gene.end <= m.start || m.end <= gene.start)
+        && gap(sentence, gene, m)
+            .chars()
+            .all(|c| c.is_whitespace() || matches!(c, '-' | '_' | ':'))
+}
+
+fn between(a: &ExtractedEntity, m: &ExtractedEntity, b: &ExtractedEntity) -> bool {
+    let (first, second) = if a.start <= b.start { (a, b) } else { (b, a) };
+    first.end <= m.start && m.end <= second.start
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
 
     #[test]
     fn test_extract_cancer_type() {
@@ -873,4 +1143,286 @@ mod tests {
             .any(|f| f.fact_type == "targeted_by" || f.fact_type == "sensitized_by"));
         assert!(facts.iter().any(|f| f.object == "PAAD"));
     }
+
+    #[test]
+    fn facts_carry_the_span_of_their_sentence() {
+        let text = "Background.  KRAS G12D is frequent in pancreatic cancer; see methods";
+        let facts = build_facts_batch(&["KRAS".to_string()], text);
+        let mutation = facts
+            .iter()
+            .find(|f| f.fact_type == "has_mutation")
+            .unwrap();
+        let (start, end) = mutation.evidence_span.unwrap();
+        assert_eq!(
+            &text[start..end],
+            "KRAS G12D is frequent in pancreatic cancer"
+        );
+    }
+
+    #[test]
+    fn mutation_facts_use_the_normalised_change() {
+        let text = "Tumours carried KRAS p.Gly12Asp, and BRAF V600E was rare.";
+        let facts = build_facts_batch(&["KRAS".to_string(), "BRAF".to_string()], text);
+        let mutations: Vec<(&str, &str)> = facts
+            .iter()
+            .filter(|f| f.fact_type == "has_mutation")
+            .map(|f| (f.subject.as_str(), f.object.as_str()))
+            .collect();
+        assert_eq!(mutations, [("KRAS", "G12D"), ("BRAF", "V600E")]);
+
+        // The focus mutation from the config names the same fact.
+        let focus = HgvsMutationNormaliser::new()
+            .normalise("KRAS_G12D", None)
+            .unwrap();
+        assert_eq!(
+            (focus.gene.as_deref(), focus.label().as_str()),
+            (Some("KRAS"), "G12D")
+        );
+    }
+
+    fn ent(
+        sentence: &str,
+        needle: &str,
+        label: EntityType,
+        canonical: Option<&str>,
+    ) -> ExtractedEntity {
+        let start = sentence.find(needle).unwrap();
+        let canonical = canonical.map(str::to_string);
+        let (canonical_id, canonical_name) = match label {
+            EntityType::CancerType => (canonical, None),
+            _ => (None, canonical),
+        };
+        ExtractedEntity {
+            text: needle.to_string(),
+            label,
+            start,
+            end: start + needle.len(),
+            confidence: 0.9,
+            canonical_id,
+            canonical_name,
+        }
+    }
+
+    type Mention = (&'static str, EntityType, Option<&'static str>);
+    type Relation = (&'static str, &'static str, &'static str);
+
+    /// Hand-labelled sentences: entities as an upstream NER would report
+    /// them, and the specific relations a reader takes from the sentence.
+    fn corpus() -> Vec<(&'static str, Vec<Mention>, Vec<Relation>)> {
+        use EntityType::*;
+        vec![
+            (
+                "KRAS G12D mutations are frequent in pancreatic adenocarcinoma.",
+                vec![
+                    ("KRAS", Gene, None),
+                    ("G12D", Mutation, None),
+                    ("pancreatic adenocarcinoma", CancerType, Some("PAAD")),
+                ],
+                vec![("mutated_in", "KRAS", "PAAD")],
+            ),
+            (
+                "EGFR L858R was detected in lung adenocarcinoma patients.",
+                vec![
+                    ("EGFR", Gene, None),
+                    ("L858R", Mutation, None),
+                    ("lung adenocarcinoma", CancerType, Some("LUAD")),
+                ],
+                vec![("mutated_in", "EGFR", "LUAD")],
+            ),
+            (
+                "BRAF V600E defines a distinct subset of melanoma.",
+                vec![
+                    ("BRAF", Gene, None),
+                    ("V600E", Mutation, None),
+                    ("melanoma", CancerType, Some("SKCM")),
+                ],
+                vec![("mutated_in", "BRAF", "SKCM")],
+            ),
+            (
+                "TP53 is mutated in most high-grade serous ovarian cancers.",
+                vec![
+                    ("TP53", Gene, None),
+                    ("ovarian cancers", CancerType, Some("OV")),
+                ],
+                vec![("mutated_in", "TP53", "OV")],
+            ),
+            (
+                "Sotorasib inhibits KRAS G12C.",
+                vec![
+                    ("Sotorasib", Chemical, None),
+                    ("KRAS", Gene, None),
+                    ("G12C", Mutation, None),
+                ],
+                vec![("inhibits", "Sotorasib", "KRAS")],
+            ),
+            (
+                "Erlotinib, an EGFR inhibitor, was given to all patients.",
+                vec![("Erlotinib", Chemical, None), ("EGFR", Gene, None)],
+                vec![("inhibits", "Erlotinib", "EGFR")],
+            ),
+            (
+                "Trametinib blocks MEK1 signalling in colorectal cancer cells.",
+                vec![
+                    ("Trametinib", Chemical, None),
+                    ("MEK1", Gene, Some("MAP2K1")),
+                    ("colorectal cancer", CancerType, Some("COAD")),
+                ],
+                vec![("inhibits", "Trametinib", "MAP2K1")],
+            ),
+            (
+                "Olaparib suppresses PARP1 activity in repair-deficient cells.",
+                vec![("Olaparib", Chemical, None), ("PARP1", Gene, None)],
+                vec![("inhibits", "Olaparib", "PARP1")],
+            ),
+            (
+                "Imatinib is an inhibitor of ABL1.",
+                vec![("Imatinib", Chemical, None), ("ABL1", Gene, None)],
+                vec![("inhibits", "Imatinib", "ABL1")],
+            ),
+            (
+                "Gefitinib did not inhibit KRAS-mutant tumour growth.",
+                vec![("Gefitinib", Chemical, None), ("KRAS", Gene, None)],
+                vec![],
+            ),
+            (
+                "High MKI67 expression is a prognostic biomarker for breast cancer.",
+                vec![
+                    ("MKI67", Gene, None),
+                    ("breast cancer", CancerType, Some("BRCA")),
+                ],
+                vec![("biomarker_for", "MKI67", "BRCA")],
+            ),
+            (
+                "CDKN2A loss predicts poor survival in pancreatic cancer.",
+                vec![
+                    ("CDKN2A", Gene, None),
+                    ("pancreatic cancer", CancerType, Some("PAAD")),
+                ],
+                vec![("biomarker_for", "CDKN2A", "PAAD")],
+            ),
+            (
+                "ERBB2 amplification predicts response to trastuzumab in breast cancer.",
+                vec![
+                    ("ERBB2", Gene, None),
+                    ("trastuzumab", Chemical, None),
+                    ("breast cancer", CancerType, Some("BRCA")),
+                ],
+                vec![("biomarker_for", "ERBB2", "BRCA")],
+            ),
+            (
+                "PD-L1 expression was associated with improved overall survival in lung cancer.",
+                vec![
+                    ("PD-L1", Gene, Some("CD274")),
+                    ("lung cancer", CancerType, Some("LUAD")),
+                ],
+                vec![("biomarker_for", "CD274", "LUAD")],
+            ),
+            (
+                "MYC and BRCA2 were both studied in prostate cancer cohorts.",
+                vec![
+                    ("MYC", Gene, None),
+                    ("BRCA2", Gene, None),
+                    ("prostate cancer", CancerType, Some("PRAD")),
+                ],
+                vec![],
+            ),
+            (
+                "Cisplatin was combined with paclitaxel in ovarian cancer.",
+                vec![
+                    ("Cisplatin", Chemical, None),
+                    ("paclitaxel", Chemical, None),
+                    ("ovarian cancer", CancerType, Some("OV")),
+                ],
+                vec![],
+            ),
+            (
+                "SMAD4 status was recorded for each pancreatic cancer sample.",
+                vec![
+                    ("SMAD4", Gene, None),
+                    ("pancreatic cancer", CancerType, Some("PAAD")),
+                ],
+                vec![],
+            ),
+            (
+                "Vemurafenib blocks BRAF V600E in melanoma.",
+                vec![
+                    ("Vemurafenib", Chemical, None),
+                    ("BRAF", Gene, None),
+                    ("V600E", Mutation, None),
+                    ("melanoma", CancerType, Some("SKCM")),
+                ],
+                vec![
+                    ("inhibits", "Vemurafenib", "BRAF"),
+                    ("mutated_in", "BRAF", "SKCM"),
+                ],
+            ),
+            (
+                "IDH1 mutations predict a favourable prognosis in glioma.",
+                vec![("IDH1", Gene, None), ("glioma", CancerType, Some("LGG"))],
+                vec![
+                    ("mutated_in", "IDH1", "LGG"),
+                    ("biomarker_for", "IDH1", "LGG"),
+                ],
+            ),
+            (
+                "Dasatinib inhibits SRC and reduced invasion of PANC-1 cells.",
+                vec![("Dasatinib", Chemical, None), ("SRC", Gene, None)],
+                vec![("inhibits", "Dasatinib", "SRC")],
+            ),
+        ]
+    }
+
+    #[test]
+    fn sentence_relations_are_precise_on_labelled_corpus() {
+        let mut predicted: HashMap<&str, (usize, usize)> = HashMap::new();
+        let mut missed = Vec::new();
+        for (sentence, mentions, gold) in corpus() {
+            let entities: Vec<ExtractedEntity> = mentions
+                .iter()
+                .map(|(needle, label, canonical)| ent(sentence, needle, *label, *canonical))
+                .collect();
+            let relations = extract_sentence_relations(sentence, &entities);
+            assert!(relations.iter().all(|r| r.evidence == sentence));
+
+            for r in relations.iter().filter(|r| r.predicate != "co_occurs_with") {
+                let hit = gold.contains(&(r.predicate, r.subject.as_str(), r.object.as_str()));
+                let counts = predicted.entry(r.predicate).or_default();
+                counts.0 += hit as usize;
+                counts.1 += 1;
+            }
+            for (predicate, subject, object) in gold {
+                if !relations
+                    .iter()
+                    .any(|r| r.predicate == predicate && r.subject == subject && r.object == object)
+                {
+                    missed.push((sentence, predicate));
+                }
+            }
+        }
+
+        for predicate in ["mutated_in", "inhibits", "biomarker_for"] {
+            let (hits, total) = predicted.get(predicate).copied().unwrap_or_default();
+            assert!(total > 0, "no {predicate} predicted");
+            let precision = hits as f64 / total as f64;
+            assert!(precision >= 0.9, "{predicate} precision {precision:.2}");
+        }
+        assert!(missed.len() <= 1, "missed {missed:?}");
+    }
+
+    #[test]
+    fn unmatched_pairs_fall_back_to_co_occurrence() {
+        let sentence = "Gefitinib did not inhibit KRAS-mutant tumour growth.";
+        let entities = [
+            ent(sentence, "Gefitinib", EntityType::Chemical, None),
+            ent(sentence, "KRAS", EntityType::Gene, None),
+        ];
+        let relations = extract_sentence_relations(sentence, &entities);
+        assert_eq!(relations.len(), 1);
+        assert_eq!(relations[0].predicate, "co_occurs_with");
+        assert!(relations[0].confidence < MUTATION_PHRASE_CONFIDENCE);
+
+        let fact = relations[0].to_kg_fact(Uuid::nil(), Uuid::nil(), Uuid::nil());
+        assert_eq!(fact.evidence.as_deref(), Some(sentence));
+        assert_eq!(fact.evidence_type, "generic_relation");
+    }
 }
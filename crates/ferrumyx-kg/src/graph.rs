@@ -0,0 +1,572 @@
+//! In-memory traversal index over `kg_facts`.
+//!
+//! [`KgFactRepository`] answers flat lookups; questions like "how is PTPN11
+//! connected to KRAS" need the graph. [`KgGraph`] is an adjacency map keyed
+//! by entity id, with every edge annotated by the facts behind it, and
+//! [`KgGraphIndex`] keeps one loaded for the process: it is built on first
+//! use, refreshed per subject from [`KgUpdateTrigger`] events, and rebuilt
+//! in full once it is older than its maximum age so writes that bypass the
+//! event queue still show up.
+//!
+//! Edges are traversed in both directions; each [`GraphEdge`] keeps the
+//! fact's own subject and object. Paper mention facts and superseded facts
+//! are left out.
+
+use crate::update::KgUpdateTrigger;
+use anyhow::Result;
+use ferrumyx_db::kg_facts::KgFactRepository;
+use ferrumyx_db::schema::KgFact;
+use ferrumyx_db::Database;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, RwLock, RwLockReadGuard};
+use tracing::{debug, info};
+use uuid::Uuid;
+
+/// Facts read per page while building the index.
+const LOAD_PAGE_SIZE: usize = 10_000;
+
+/// Age after which [`KgGraphIndex`] rebuilds from the table.
+pub const DEFAULT_MAX_AGE: Duration = Duration::from_secs(600);
+
+/// One fact on a graph edge.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphEdge {
+    pub fact_id: Uuid,
+    pub subject_id: Uuid,
+    pub subject_name: String,
+    pub predicate: String,
+    pub object_id: Uuid,
+    pub object_name: String,
+    pub confidence: f32,
+    pub support_count: i64,
+}
+
+impl GraphEdge {
+    fn from_fact(fact: &KgFact) -> Self {
+        Self {
+            fact_id: fact.id,
+            subject_id: fact.subject_id,
+            subject_name: fact.subject_name.clone(),
+            predicate: fact.predicate.clone(),
+            object_id: fact.object_id,
+            object_name: fact.object_name.clone(),
+            confidence: fact.confidence,
+            support_count: fact.support_count,
+        }
+    }
+
+    fn matches(&self, predicate_filter: Option<&str>) -> bool {
+        predicate_filter.is_none_or(|p| self.predicate.eq_ignore_ascii_case(p.trim()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphNode {
+    pub id: Uuid,
+    pub name: String,
+    /// Hops from the queried entity.
+    pub depth: usize,
+}
+
+/// Entities within some hops of a centre and the facts between them.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Neighborhood {
+    pub center: Uuid,
+    /// Ordered by depth, then id.
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+/// One hop of a [`KgPath`] with every fact linking its two entities.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PathHop {
+    pub from: Uuid,
+    pub to: Uuid,
+    /// Strongest first.
+    pub facts: Vec<GraphEdge>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct KgPath {
+    pub nodes: Vec<GraphNode>,
+    pub hops: Vec<PathHop>,
+    /// Product of the strongest fact confidence on each hop.
+    pub confidence: f64,
+}
+
+/// Adjacency map over KG facts.
+#[derive(Debug, Clone, Default)]
+pub struct KgGraph {
+    facts: HashMap<Uuid, GraphEdge>,
+    /// Entity → neighbour → ids of the facts between them.
+    adjacency: HashMap<Uuid, BTreeMap<Uuid, Vec<Uuid>>>,
+    names: HashMap<Uuid, String>,
+}
+
+impl KgGraph {
+    pub fn from_facts<'a>(facts: impl IntoIterator<Item = &'a KgFact>) -> Self {
+        let mut graph = Self::default();
+        for fact in facts {
+            graph.insert(fact);
+        }
+        graph
+    }
+
+    /// Add or replace `fact`. Superseded, mention and self-referencing facts
+    /// only remove an earlier version.
+    pub fn insert(&mut self, fact: &KgFact) {
+        self.remove_fact(fact.id);
+        if fact.valid_until.is_some()
+            || fact.evidence_type == "mention"
+            || fact.subject_id == fact.object_id
+        {
+            return;
+        }
+        for (a, b) in [
+            (fact.subject_id, fact.object_id),
+            (fact.object_id, fact.subject_id),
+        ] {
+            self.adjacency
+                .entry(a)
+                .or_default()
+                .entry(b)
+                .or_default()
+                .push(fact.id);
+        }
+        self.names
+            .insert(fact.subject_id, fact.subject_name.clone());
+        self.names.insert(fact.object_id, fact.object_name.clone());
+        self.facts.insert(fact.id, GraphEdge::from_fact(fact));
+    }
+
+    pub fn remove_fact(&mut self, fact_id: Uuid) {
+        let Some(edge) = self.facts.remove(&fact_id) else {
+            return;
+        };
+        for (a, b) in [
+            (edge.subject_id, edge.object_id),
+            (edge.object_id, edge.subject_id),
+        ] {
+            let Some(neighbours) = self.adjacency.get_mut(&a) else {
+                continue;
+            };
+            if let Some(ids) = neighbours.get_mut(&b) {
+                ids.retain(|id| *id != fact_id);
+                if ids.is_empty() {
+                    neighbours.remove(&b);
+                }
+            }
+            if neighbours.is_empty() {
+                self.adjacency.remove(&a);
+                self.names.remove(&a);
+            }
+        }
+    }
+
+    /// Replace every fact with `subject_id` as subject by `facts`.
+    pub fn replace_subject(&mut self, subject_id: Uuid, facts: &[KgFact]) {
+        let stale: Vec<Uuid> = self
+            .edges_of(subject_id)
+            .filter(|e| e.subject_id == subject_id)
+            .map(|e| e.fact_id)
+            .collect();
+        for id in stale {
+            self.remove_fact(id);
+        }
+        for fact in facts.iter().filter(|f| f.subject_id == subject_id) {
+            self.insert(fact);
+        }
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.facts.len()
+    }
+
+    pub fn name(&self, entity_id: Uuid) -> Option<&str> {
+        self.names.get(&entity_id).map(String::as_str)
+    }
+
+    /// Entity named `name` (case-insensitive); the best connected one when
+    /// several share it.
+    pub fn find_entity(&self, name: &str) -> Option<Uuid> {
+        let name = name.trim();
+        self.names
+            .iter()
+            .filter(|(_, n)| n.eq_ignore_ascii_case(name))
+            .map(|(id, _)| *id)
+            .max_by_key(|id| (self.degree(*id), std::cmp::Reverse(*id)))
+    }
+
+    fn degree(&self, entity_id: Uuid) -> usize {
+        self.adjacency.get(&entity_id).map_or(0, |n| n.len())
+    }
+
+    fn edges_of(&self, entity_id: Uuid) -> impl Iterator<Item = &GraphEdge> + '_ {
+        self.adjacency
+            .get(&entity_id)
+            .into_iter()
+            .flat_map(|n| n.values().flatten())
+            .filter_map(|id| self.facts.get(id))
+    }
+
+    /// Neighbours of `entity_id` with the facts to each that match
+    /// `predicate_filter` (case-insensitive).
+    fn hops<'a>(
+        &'a self,
+        entity_id: Uuid,
+        predicate_filter: Option<&'a str>,
+    ) -> impl Iterator<Item = (Uuid, Vec<&'a GraphEdge>)> + 'a {
+        self.adjacency
+            .get(&entity_id)
+            .into_iter()
+            .flatten()
+            .filter_map(move |(neighbour, ids)| {
+                let edges: Vec<&GraphEdge> = ids
+                    .iter()
+                    .filter_map(|id| self.facts.get(id))
+                    .filter(|e| e.matches(predicate_filter))
+                    .collect();
+                (!edges.is_empty()).then_some((*neighbour, edges))
+            })
+    }
+
+    /// Entities within `depth` hops of `entity_id` over facts matching
+    /// `predicate_filter`, and every such fact between two of them. None
+    /// when the entity is not in the graph.
+    pub fn neighbors(
+        &self,
+        entity_id: Uuid,
+        depth: usize,
+        predicate_filter: Option<&str>,
+    ) -> Option<Neighborhood> {
+        let name = self.names.get(&entity_id)?;
+        let mut depths: BTreeMap<Uuid, usize> = BTreeMap::from([(entity_id, 0)]);
+        let mut nodes = vec![GraphNode {
+            id: entity_id,
+            name: name.clone(),
+            depth: 0,
+        }];
+        let mut frontier = vec![entity_id];
+        for d in 1..=depth {
+            let mut next = Vec::new();
+            for node in &frontier {
+                for (neighbour, _) in self.hops(*node, predicate_filter) {
+                    if depths.contains_key(&neighbour) {
+                        continue;
+                    }
+                    depths.insert(neighbour, d);
+                    next.push(neighbour);
+                }
+            }
+            next.sort();
+            nodes.extend(next.iter().map(|id| GraphNode {
+                id: *id,
+                name: self.names.get(id).cloned().unwrap_or_default(),
+                depth: d,
+            }));
+            frontier = next;
+        }
+
+        let mut edges: Vec<GraphEdge> = depths
+            .keys()
+            .flat_map(|id| self.edges_of(*id))
+            .filter(|e| {
+                e.matches(predicate_filter)
+                    && depths.contains_key(&e.subject_id)
+                    && depths.contains_key(&e.object_id)
+            })
+            .cloned()
+            .collect();
+        edges.sort_by_key(|e| e.fact_id);
+        edges.dedup_by_key(|e| e.fact_id);
+        Some(Neighborhood {
+            center: entity_id,
+            nodes,
+            edges,
+        })
+    }
+
+    /// Fewest-hop path from `from` to `to` of at most `max_depth` hops.
+    /// Among equally short paths the one with the highest
+    /// [`KgPath::confidence`] wins.
+    pub fn shortest_path(&self, from: Uuid, to: Uuid, max_depth: usize) -> Option<KgPath> {
+        if !self.names.contains_key(&from) || !self.names.contains_key(&to) {
+            return None;
+        }
+        // Entity → (best path confidence, predecessor).
+        let mut best: HashMap<Uuid, (f64, Option<Uuid>)> = HashMap::from([(from, (1.0, None))]);
+        let mut frontier = vec![from];
+        for _ in 0..max_depth {
+            if best.contains_key(&to) || frontier.is_empty() {
+                break;
+            }
+            let mut next: BTreeMap<Uuid, (f64, Uuid)> = BTreeMap::new();
+            for node in &frontier {
+                let reach = best[node].0;
+                for (neighbour, edges) in self.hops(*node, None) {
+                    if best.contains_key(&neighbour) {
+                        continue;
+                    }
+                    let strongest = edges.iter().map(|e| e.confidence).fold(0.0, f32::max);
+                    let confidence = reach * strongest as f64;
+                    let entry = next.entry(neighbour).or_insert((confidence, *node));
+                    if confidence > entry.0 {
+                        *entry = (confidence, *node);
+                    }
+                }
+            }
+            for (id, (confidence, previous)) in &next {
+                best.insert(*id, (*confidence, Some(*previous)));
+            }
+            frontier = next.into_keys().collect();
+        }
+
+        let (confidence, _) = *best.get(&to)?;
+        let mut ids = vec![to];
+        while let Some((_, Some(previous))) = best.get(ids.last()?) {
+            ids.push(*previous);
+        }
+        ids.reverse();
+
+        let hops = ids
+            .windows(2)
+            .map(|pair| {
+                let mut facts: Vec<GraphEdge> = self.adjacency[&pair[0]][&pair[1]]
+                    .iter()
+                    .filter_map(|id| self.facts.get(id))
+                    .cloned()
+                    .collect();
+                facts.sort_by(|a, b| {
+                    b.confidence
+                        .total_cmp(&a.confidence)
+                        .then(a.fact_id.cmp(&b.fact_id))
+                });
+                PathHop {
+                    from: pair[0],
+                    to: pair[1],
+                    facts,
+                }
+            })
+            .collect();
+        let nodes = ids
+            .iter()
+            .enumerate()
+            .map(|(depth, id)| GraphNode {
+                id: *id,
+                name: self.names.get(id).cloned().unwrap_or_default(),
+                depth,
+            })
+            .collect();
+        Some(KgPath {
+            nodes,
+            hops,
+            confidence,
+        })
+    }
+}
+
+/// Process-wide [`KgGraph`] over the `kg_facts` table.
+pub struct KgGraphIndex {
+    db: Arc<Database>,
+    graph: RwLock<KgGraph>,
+    loaded_at: RwLock<Option<Instant>>,
+    /// Serialises full rebuilds.
+    reload: Mutex<()>,
+    max_age: Duration,
+}
+
+impl KgGraphIndex {
+    /// Unloaded index; the first [`Self::graph`] call builds it.
+    pub fn new(db: Arc<Database>) -> Self {
+        Self {
+            db,
+            graph: RwLock::default(),
+            loaded_at: RwLock::default(),
+            reload: Mutex::new(()),
+            max_age: DEFAULT_MAX_AGE,
+        }
+    }
+
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// The loaded graph, rebuilding it first if it was never built or is
+    /// older than the maximum age.
+    pub async fn graph(&self) -> Result<RwLockReadGuard<'_, KgGraph>> {
+        if !self.is_fresh().await {
+            let _reload = self.reload.lock().await;
+            // Another caller may have finished a rebuild while we waited.
+            if !self.is_fresh().await {
+                self.rebuild().await?;
+            }
+        }
+        Ok(self.graph.read().await)
+    }
+
+    async fn is_fresh(&self) -> bool {
+        self.loaded_at
+            .read()
+            .await
+            .is_some_and(|at| at.elapsed() < self.max_age)
+    }
+
+    /// Build the graph from every fact in the table.
+    pub async fn rebuild(&self) -> Result<()> {
+        let started = Instant::now();
+        let repo = KgFactRepository::new(self.db.clone());
+        let mut graph = KgGraph::default();
+        let mut offset = 0;
+        loop {
+            let page = repo.list(offset, LOAD_PAGE_SIZE).await?;
+            for fact in &page {
+                graph.insert(fact);
+            }
+            if page.len() < LOAD_PAGE_SIZE {
+                break;
+            }
+            offset += page.len();
+        }
+        info!(
+            nodes = graph.node_count(),
+            edges = graph.edge_count(),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "Built KG graph index"
+        );
+        *self.graph.write().await = graph;
+        *self.loaded_at.write().await = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Reload the facts of `subject_ids` from the table. A no-op before the
+    /// first build, which reads them anyway.
+    pub async fn refresh_subjects(&self, subject_ids: &[Uuid]) -> Result<()> {
+        if self.loaded_at.read().await.is_none() || subject_ids.is_empty() {
+            return Ok(());
+        }
+        let facts = KgFactRepository::new(self.db.clone())
+            .find_by_subject_ids(subject_ids, 32)
+            .await?;
+        let mut by_subject: HashMap<Uuid, Vec<KgFact>> = HashMap::new();
+        for fact in facts {
+            by_subject.entry(fact.subject_id).or_default().push(fact);
+        }
+        let mut graph = self.graph.write().await;
+        for subject_id in subject_ids {
+            graph.replace_subject(
+                *subject_id,
+                by_subject.get(subject_id).map_or(&[], Vec::as_slice),
+            );
+        }
+        debug!(subjects = subject_ids.len(), "Refreshed KG graph index");
+        Ok(())
+    }
+
+    /// Apply a KG event queue trigger.
+    pub async fn apply(&self, trigger: &KgUpdateTrigger) -> Result<()> {
+        self.refresh_subjects(&trigger.entity_ids()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(n: u128) -> Uuid {
+        Uuid::from_u128(n)
+    }
+
+    fn fact(subject: u128, predicate: &str, object: u128, confidence: f32) -> KgFact {
+        let name = |n: u128| ["", "PTPN11", "KRAS", "SOS1", "EGFR", "PAAD", "MAPK1"][n as usize];
+        let mut fact = KgFact::new(
+            id(100),
+            id(subject),
+            name(subject).to_string(),
+            predicate.to_string(),
+            id(object),
+            name(object).to_string(),
+        );
+        fact.confidence = confidence;
+        fact
+    }
+
+    fn graph() -> (KgGraph, Vec<KgFact>) {
+        let facts = vec![
+            fact(1, "activates", 3, 0.6),
+            fact(3, "activates", 2, 0.9),
+            fact(1, "interacts_with", 4, 0.9),
+            fact(4, "activates", 2, 0.8),
+            fact(2, "associated_with", 5, 0.7),
+            fact(2, "activates", 6, 0.5),
+        ];
+        (KgGraph::from_facts(&facts), facts)
+    }
+
+    #[test]
+    fn neighborhood_expands_by_depth_and_predicate() {
+        let (graph, _) = graph();
+        let one = graph.neighbors(id(2), 1, None).unwrap();
+        let found: Vec<(&str, usize)> = one
+            .nodes
+            .iter()
+            .map(|n| (n.name.as_str(), n.depth))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("KRAS", 0),
+                ("SOS1", 1),
+                ("EGFR", 1),
+                ("PAAD", 1),
+                ("MAPK1", 1)
+            ]
+        );
+        assert_eq!(one.edges.len(), 4);
+
+        let activation = graph.neighbors(id(2), 2, Some("Activates")).unwrap();
+        let ids: Vec<Uuid> = activation.nodes.iter().map(|n| n.id).collect();
+        assert_eq!(ids, vec![id(2), id(3), id(4), id(6), id(1)]);
+        assert!(activation.edges.iter().all(|e| e.predicate == "activates"));
+        assert!(graph.neighbors(id(99), 1, None).is_none());
+    }
+
+    #[test]
+    fn shortest_path_prefers_stronger_evidence_and_annotates_hops() {
+        let (graph, _) = graph();
+        let path = graph.shortest_path(id(1), id(5), 3).unwrap();
+        let names: Vec<&str> = path.nodes.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["PTPN11", "EGFR", "KRAS", "PAAD"]);
+        assert!((path.confidence - 0.9 * 0.8 * 0.7).abs() < 1e-6);
+        assert_eq!(path.hops[1].facts[0].subject_name, "EGFR");
+        assert_eq!(path.hops[1].facts[0].predicate, "activates");
+
+        assert!(graph.shortest_path(id(1), id(5), 2).is_none());
+        assert_eq!(graph.shortest_path(id(1), id(1), 0).unwrap().hops, vec![]);
+        assert_eq!(graph.find_entity("kras"), Some(id(2)));
+    }
+
+    #[test]
+    fn refreshing_a_subject_replaces_its_facts() {
+        let (mut graph, facts) = graph();
+        assert_eq!(graph.edge_count(), 6);
+
+        let mut superseded = facts[3].clone();
+        superseded.valid_until = Some(chrono::Utc::now());
+        graph.replace_subject(id(4), &[superseded]);
+        assert_eq!(graph.edge_count(), 5);
+        let path = graph.shortest_path(id(1), id(5), 3).unwrap();
+        assert_eq!(path.nodes[1].name, "SOS1");
+
+        graph.replace_subject(id(1), &[]);
+        assert!(graph.shortest_path(id(1), id(5), 5).is_none());
+        assert_eq!(graph.name(id(1)), None);
+    }
+}
@@ -3,12 +3,17 @@
 
 pub mod conflict;
 pub mod extraction;
+pub mod graph;
 pub mod ner;
 pub mod repository;
 pub mod scoring;
 pub mod update;
 
-pub use extraction::{build_facts, extract_cancer_type, extract_mutations, ExtractedFact};
+pub use extraction::{
+    build_facts, extract_cancer_type, extract_mutations, extract_sentence_relations, ExtractedFact,
+    SentenceRelation,
+};
+pub use graph::{KgGraph, KgGraphIndex};
 pub use repository::KgRepository;
 pub use scoring::{
     compute_target_scores, compute_target_scores_for_gene_ids, compute_target_scores_for_gene_names,
@@ -0,0 +1,332 @@
+//! Document-local abbreviations (Schwartz & Hearst, 2003).
+//!
+//! Papers define a short form once, as in "pancreatic ductal adenocarcinoma
+//! (PDAC)", and use it from then on. [`detect_abbreviations`] finds such
+//! `long form (short form)` pairs with plain string processing;
+//! [`AbbreviationMap`] links each pair to the entity the dictionary found in
+//! the long form, so later short-form mentions get its type and canonical
+//! id even when the short form is not in the dictionary, or is in it as
+//! something else.
+
+use super::entity_types::EntityType;
+use super::trie_ner::ExtractedEntity;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// A `long form (short form)` definition in one text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Abbreviation {
+    pub short_form: String,
+    pub long_form: String,
+    /// Byte offset of the long form.
+    pub long_start: usize,
+    pub long_end: usize,
+}
+
+/// Short-form definitions in `text`, in order of appearance.
+///
+/// The short form is the parenthesised text up to the first `,` or `;`,
+/// ignoring a nested parenthetical, e.g. `(PDAC; n = 12)` or
+/// `(TAMs (ref. 4))`. It has at most two words, 2–10 chars, and starts
+/// with a letter or digit. The long form is the shortest run of preceding
+/// words whose characters contain the short form's in order, with its
+/// first character starting a word.
+pub fn detect_abbreviations(text: &str) -> Vec<Abbreviation> {
+    let mut out = Vec::new();
+    for (open, _) in text.match_indices('(') {
+        let Some(close) = matching_paren(text, open) else {
+            continue;
+        };
+        let inner = &text[open + 1..close];
+        let short = inner.split([',', ';', '(']).next().unwrap_or("").trim();
+        if !is_short_form(short) {
+            continue;
+        }
+
+        let window = long_form_window(text, open, short.chars().count());
+        let Some(offset) = best_long_form(short, &text[window.clone()]) else {
+            continue;
+        };
+        let long_start = window.start + offset;
+        let long = text[long_start..window.end].trim_end();
+        if long.chars().count() <= short.chars().count() || long.contains(short) {
+            continue;
+        }
+        out.push(Abbreviation {
+            short_form: short.to_string(),
+            long_form: long.to_string(),
+            long_start,
+            long_end: long_start + long.len(),
+        });
+    }
+    out
+}
+
+fn matching_paren(text: &str, open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in text[open..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn is_short_form(short: &str) -> bool {
+    let len = short.chars().count();
+    (2..=10).contains(&len)
+        && short.split_whitespace().count() <= 2
+        && short.chars().next().is_some_and(char::is_alphanumeric)
+        && short.chars().any(char::is_alphabetic)
+}
+
+/// Byte range of the words before `open` that may hold the long form: at
+/// most `min(|S| + 5, 2|S|)` words, not reaching back past the previous
+/// sentence or parenthetical.
+fn long_form_window(text: &str, open: usize, short_len: usize) -> std::ops::Range<usize> {
+    let before = &text[..open];
+    let floor = before
+        .rfind(['(', ')', ';', '[', ']'])
+        .map(|i| i + 1)
+        .into_iter()
+        .chain(before.rfind(". ").map(|i| i + 2))
+        .max()
+        .unwrap_or(0);
+    let end = before.trim_end().len().max(floor);
+    let segment = &text[floor..end];
+    let word_starts: Vec<usize> = segment
+        .char_indices()
+        .filter(|&(i, c)| {
+            !c.is_whitespace()
+                && segment[..i]
+                    .chars()
+                    .next_back()
+                    .is_none_or(char::is_whitespace)
+        })
+        .map(|(i, _)| i)
+        .collect();
+    let max_words = (short_len + 5).min(short_len * 2);
+    let first = word_starts.len().saturating_sub(max_words);
+    let start = word_starts.get(first).map_or(end, |i| floor + i);
+    start..end
+}
+
+/// Byte offset in `candidate` where the long form for `short` starts.
+fn best_long_form(short: &str, candidate: &str) -> Option<usize> {
+    let short: Vec<char> = short.to_lowercase().chars().collect();
+    let long: Vec<(usize, char)> = candidate
+        .char_indices()
+        .flat_map(|(i, c)| c.to_lowercase().map(move |l| (i, l)))
+        .collect();
+
+    let mut l = long.len();
+    for s in (0..short.len()).rev() {
+        let c = short[s];
+        if !c.is_alphanumeric() {
+            continue;
+        }
+        loop {
+            l = l.checked_sub(1)?;
+            let starts_word = l == 0 || !long[l - 1].1.is_alphanumeric();
+            if long[l].1 == c && (s > 0 || starts_word) {
+                break;
+            }
+        }
+    }
+    let start = long.get(l)?.0;
+    // Back up to the start of the word the first character sits in.
+    Some(
+        candidate[..start]
+            .rfind(char::is_whitespace)
+            .map_or(0, |i| i + 1),
+    )
+}
+
+/// Entity a short form stands for, taken from its long form.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AbbreviationEntity {
+    pub long_form: String,
+    pub label: EntityType,
+    pub canonical_id: Option<String>,
+    pub canonical_name: Option<String>,
+    pub confidence: f32,
+}
+
+/// Short forms defined in one document, keyed by short form.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AbbreviationMap {
+    entries: BTreeMap<String, AbbreviationEntity>,
+}
+
+impl AbbreviationMap {
+    /// Record the definitions in `text` whose long form contains one of
+    /// `entities` (matches over the same text). The widest entity wins, and
+    /// the first definition of a short form in a document is kept.
+    pub fn learn(&mut self, text: &str, entities: &[ExtractedEntity]) {
+        for abbreviation in detect_abbreviations(text) {
+            let Some(entity) = entities
+                .iter()
+                .filter(|e| e.start >= abbreviation.long_start && e.end <= abbreviation.long_end)
+                .max_by_key(|e| (e.end - e.start, e.end))
+            else {
+                continue;
+            };
+            self.entries
+                .entry(abbreviation.short_form)
+                .or_insert_with(|| AbbreviationEntity {
+                    long_form: abbreviation.long_form,
+                    label: entity.label,
+                    canonical_id: entity.canonical_id.clone(),
+                    canonical_name: entity.canonical_name.clone(),
+                    confidence: entity.confidence,
+                });
+        }
+    }
+
+    /// Add an entity for every whole-word, case-sensitive short-form
+    /// mention in `text`. Matches that lie inside a mention, such as a
+    /// gene symbol that doubles as the short form, are replaced; a longer
+    /// match around the mention is kept and the mention skipped.
+    pub fn apply(&self, text: &str, entities: &mut Vec<ExtractedEntity>) {
+        if self.entries.is_empty() {
+            return;
+        }
+        for (short, target) in &self.entries {
+            for (start, _) in text.match_indices(short.as_str()) {
+                let end = start + short.len();
+                let bounded = !text[..start]
+                    .chars()
+                    .next_back()
+                    .is_some_and(char::is_alphanumeric)
+                    && !text[end..]
+                        .chars()
+                        .next()
+                        .is_some_and(char::is_alphanumeric);
+                if !bounded
+                    || entities
+                        .iter()
+                        .any(|e| e.start < end && start < e.end && (e.start < start || e.end > end))
+                {
+                    continue;
+                }
+                entities.retain(|e| !(e.start >= start && e.end <= end));
+                entities.push(ExtractedEntity {
+                    text: short.clone(),
+                    label: target.label,
+                    start,
+                    end,
+                    confidence: target.confidence,
+                    canonical_id: target.canonical_id.clone(),
+                    canonical_name: target.canonical_name.clone(),
+                });
+            }
+        }
+        entities.sort_by_key(|e| (e.start, e.end));
+    }
+
+    pub fn get(&self, short_form: &str) -> Option<&AbbreviationEntity> {
+        self.entries.get(short_form)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ner::trie_ner::tests::hgnc_row;
+    use crate::ner::{CancerNormaliser, HgncNormaliser, TrieNer};
+
+    fn pairs(text: &str) -> Vec<(String, String)> {
+        detect_abbreviations(text)
+            .into_iter()
+            .map(|a| (a.short_form, a.long_form))
+            .collect()
+    }
+
+    #[test]
+    fn detects_definitions_with_nested_parens() {
+        let text = "Tumour-associated macrophages (TAMs (ref. 4)) infiltrate \
+                    pancreatic ductal adenocarcinoma (PDAC; n = 12). In PDAC, \
+                    cancer-associated fibroblasts (CAFs) and the extracellular \
+                    matrix (ECM) limit drug delivery (see Methods).";
+        assert_eq!(
+            pairs(text),
+            vec![
+                (
+                    "TAMs".to_string(),
+                    "Tumour-associated macrophages".to_string()
+                ),
+                (
+                    "PDAC".to_string(),
+                    "pancreatic ductal adenocarcinoma".to_string()
+                ),
+                (
+                    "CAFs".to_string(),
+                    "cancer-associated fibroblasts".to_string()
+                ),
+                ("ECM".to_string(), "extracellular matrix".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn short_form_takes_the_long_form_entity_over_a_gene_symbol() {
+        let tsv = [
+            "header".to_string(),
+            hgnc_row("HGNC:8636", "PC", ""),
+            hgnc_row("HGNC:6407", "KRAS", ""),
+        ]
+        .join("\n");
+        let ner = TrieNer::from_normalisers(
+            HgncNormaliser::from_tsv(&tsv).unwrap(),
+            CancerNormaliser::from_json(&serde_json::json!([
+                { "code": "PRAD", "name": "Prostate Adenocarcinoma" }
+            ]))
+            .unwrap(),
+        )
+        .unwrap();
+        let text = "KRAS is rarely mutated in Prostate Adenocarcinoma (PC). \
+                    PC progression was slower, unlike PCa.";
+
+        let mut entities = ner.extract(text);
+        let mut abbreviations = AbbreviationMap::default();
+        abbreviations.learn(text, &entities);
+        abbreviations.apply(text, &mut entities);
+
+        let found: Vec<(&str, EntityType, Option<&str>)> = entities
+            .iter()
+            .map(|e| (e.text.as_str(), e.label, e.canonical_id.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("KRAS", EntityType::Gene, Some("HGNC:6407")),
+                (
+                    "Prostate Adenocarcinoma",
+                    EntityType::CancerType,
+                    Some("PRAD")
+                ),
+                ("PC", EntityType::CancerType, Some("PRAD")),
+                ("PC", EntityType::CancerType, Some("PRAD")),
+            ]
+        );
+        assert_eq!(
+            abbreviations.get("PC").map(|a| a.long_form.as_str()),
+            Some("Prostate Adenocarcinoma")
+        );
+    }
+}
@@ -3,12 +3,14 @@
 use anyhow::{Context, Result};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tracing::info;
 
+use super::dictionaries;
+
 /// Whether a cancer pattern is a short OncoTree code or a full name.
 /// Used by the NER trie to assign different confidence levels.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum CancerPatternKind {
     /// Short OncoTree code, e.g. "LUAD", "COAD" — controlled vocab, unambiguous
     Code,
@@ -40,7 +42,7 @@ pub struct CancerNormaliser {
 }
 
 impl CancerNormaliser {
-    fn cache_path() -> PathBuf {
+    pub(crate) fn cache_path() -> PathBuf {
         let root =
             std::env::var("FERRUMYX_CACHE_DIR").unwrap_or_else(|_| "./data/cache/ner".to_string());
         PathBuf::from(root).join("oncotree_latest_stable.json")
@@ -48,8 +50,13 @@ impl CancerNormaliser {
 
     /// Build from OncoTree download.
     pub async fn from_download() -> Result<Self> {
-        let cache_path = Self::cache_path();
-        if let Ok(raw) = fs::read_to_string(&cache_path) {
+        Self::from_cache_or_download(&Self::cache_path(), dictionaries::offline()).await
+    }
+
+    /// Build from the OncoTree JSON at `cache_path`, downloading it there
+    /// first unless `offline`.
+    pub(crate) async fn from_cache_or_download(cache_path: &Path, offline: bool) -> Result<Self> {
+        if let Ok(raw) = fs::read_to_string(cache_path) {
             if let Ok(json) = serde_json::from_str::<serde_json::Value>(&raw) {
                 info!(
                     "Loaded OncoTree dataset from cache: {}",
@@ -58,6 +65,9 @@ impl CancerNormaliser {
                 return Self::from_json(&json);
             }
         }
+        if offline {
+            return Err(dictionaries::not_cached("OncoTree", cache_path));
+        }
 
         info!("Downloading OncoTree dataset from {}", ONCOTREE_JSON_URL);
         let resp = reqwest::get(ONCOTREE_JSON_URL)
@@ -71,7 +81,7 @@ impl CancerNormaliser {
             let _ = fs::create_dir_all(parent);
         }
         if let Ok(raw) = serde_json::to_string(&resp) {
-            let _ = fs::write(&cache_path, raw);
+            let _ = fs::write(cache_path, raw);
         }
 
         Self::from_json(&resp)
@@ -0,0 +1,93 @@
+//! Reference dictionaries the trie NER is built from.
+//!
+//! HGNC and OncoTree are downloaded on first use and cached under
+//! `FERRUMYX_CACHE_DIR`. With `FERRUMYX_NER_OFFLINE` set, a missing file is
+//! an error naming it rather than a download.
+
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+use super::{CancerNormaliser, HgncNormaliser};
+
+/// Environment switch that forbids dictionary downloads.
+pub const OFFLINE_ENV: &str = "FERRUMYX_NER_OFFLINE";
+
+/// Whether [`OFFLINE_ENV`] is set to a true value.
+pub fn offline() -> bool {
+    std::env::var(OFFLINE_ENV)
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+/// Cache files of every dictionary.
+pub fn cache_paths() -> Vec<PathBuf> {
+    vec![HgncNormaliser::cache_path(), CancerNormaliser::cache_path()]
+}
+
+/// Cache files that have not been downloaded yet.
+pub fn missing() -> Vec<PathBuf> {
+    cache_paths().into_iter().filter(|p| !p.is_file()).collect()
+}
+
+/// Downloads every dictionary not yet cached, even when offline, and
+/// returns the cache files.
+pub async fn prefetch() -> Result<Vec<PathBuf>> {
+    HgncNormaliser::from_cache_or_download(&HgncNormaliser::cache_path(), false).await?;
+    CancerNormaliser::from_cache_or_download(&CancerNormaliser::cache_path(), false).await?;
+    Ok(cache_paths())
+}
+
+pub(crate) fn not_cached(dictionary: &str, path: &Path) -> anyhow::Error {
+    anyhow::anyhow!(
+        "NER dictionary {dictionary} not found locally (missing {}); \
+         run `ferrumyx models download` to fetch it",
+        path.display()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn offline_loads_only_from_the_cache() {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-ner-{}", uuid::Uuid::new_v4()));
+        let hgnc = dir.join("hgnc_complete_set.txt");
+        let oncotree = dir.join("oncotree_latest_stable.json");
+
+        let err = HgncNormaliser::from_cache_or_download(&hgnc, true)
+            .await
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("HGNC complete set not found locally"), "{err}");
+        assert!(err.contains(&hgnc.display().to_string()), "{err}");
+        assert!(err.contains("ferrumyx models download"), "{err}");
+        assert!(CancerNormaliser::from_cache_or_download(&oncotree, true)
+            .await
+            .is_err());
+
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            &hgnc,
+            "hgnc_id\tsymbol\tname\tlocus_group\tlocus_type\tstatus\n\
+             HGNC:6407\tKRAS\tKRAS proto-oncogene\tprotein-coding gene\tgene with protein product\tApproved\n",
+        )
+        .unwrap();
+        std::fs::write(
+            &oncotree,
+            r#"[{"code": "PAAD", "name": "Pancreatic Adenocarcinoma"}]"#,
+        )
+        .unwrap();
+
+        let genes = HgncNormaliser::from_cache_or_download(&hgnc, true)
+            .await
+            .unwrap();
+        assert_eq!(genes.normalise_symbol("KRAS").as_deref(), Some("KRAS"));
+        let cancers = CancerNormaliser::from_cache_or_download(&oncotree, true)
+            .await
+            .unwrap();
+        assert!(cancers.get_record("PAAD").is_some());
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
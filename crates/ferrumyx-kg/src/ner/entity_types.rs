@@ -28,6 +28,22 @@ impl EntityType {
             EntityType::Other => "OTHER",
         }
     }
+
+    /// Inverse of [`Self::as_str`], ignoring case.
+    pub fn from_label(label: &str) -> Option<EntityType> {
+        [
+            EntityType::Gene,
+            EntityType::Disease,
+            EntityType::CancerType,
+            EntityType::Chemical,
+            EntityType::Mutation,
+            EntityType::CellLine,
+            EntityType::Pathway,
+            EntityType::Other,
+        ]
+        .into_iter()
+        .find(|t| t.as_str().eq_ignore_ascii_case(label.trim()))
+    }
 }
 
 pub fn normalize_entity_label(label: &str) -> EntityType {
@@ -0,0 +1,599 @@
+//! Scoring NER extractors against an annotated golden set.
+//!
+//! The golden set is JSONL, one [`GoldSentence`] per line. [`evaluate`]
+//! runs any [`EntityExtractor`] over it and counts exact matches (same
+//! byte span, same type) per entity type, plus how often a matched entity
+//! carries the expected canonical id. [`Baselines`] holds the committed
+//! minimums per extractor, and [`render_report`] prints the scores with
+//! their margin over those minimums.
+
+use super::entity_types::EntityType;
+use super::hybrid::HybridNer;
+use super::tagger::SpanTagger;
+use super::trie_ner::{ExtractedEntity, TrieNer};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{self, Write as _};
+use std::path::Path;
+use std::sync::Arc;
+
+/// One annotated sentence.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoldSentence {
+    pub id: String,
+    pub text: String,
+    /// Every entity a perfect extractor finds; empty for negatives.
+    #[serde(default)]
+    pub entities: Vec<GoldEntity>,
+    /// What the sentence exercises, e.g. `alias` or `negative`.
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoldEntity {
+    pub text: String,
+    /// An [`EntityType::as_str`] label such as `GENE`.
+    #[serde(rename = "type")]
+    pub label: String,
+    /// Byte offsets into the sentence.
+    pub start: usize,
+    pub end: usize,
+    /// HGNC id for genes, OncoTree code for cancer types.
+    #[serde(default)]
+    pub canonical_id: Option<String>,
+}
+
+/// Reads a JSONL golden set, checking that every span covers its text and
+/// every label is a known type.
+pub fn load_golden_set(path: &Path) -> Result<Vec<GoldSentence>> {
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("reading golden set {}", path.display()))?;
+    let mut sentences = Vec::new();
+    let mut ids = HashSet::new();
+    for (line_no, line) in raw.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let sentence: GoldSentence = serde_json::from_str(line)
+            .with_context(|| format!("{}:{}", path.display(), line_no + 1))?;
+        validate(&sentence).with_context(|| format!("{}:{}", path.display(), line_no + 1))?;
+        if !ids.insert(sentence.id.clone()) {
+            anyhow::bail!(
+                "{}:{}: duplicate id {}",
+                path.display(),
+                line_no + 1,
+                sentence.id
+            );
+        }
+        sentences.push(sentence);
+    }
+    Ok(sentences)
+}
+
+fn validate(sentence: &GoldSentence) -> Result<()> {
+    for entity in &sentence.entities {
+        if EntityType::from_label(&entity.label).is_none() {
+            anyhow::bail!("{}: unknown entity type {}", sentence.id, entity.label);
+        }
+        let covered = sentence.text.get(entity.start..entity.end);
+        if covered != Some(entity.text.as_str()) {
+            anyhow::bail!(
+                "{}: span {}..{} is {:?}, not {:?}",
+                sentence.id,
+                entity.start,
+                entity.end,
+                covered,
+                entity.text
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Anything that finds entities in a sentence.
+pub trait EntityExtractor {
+    /// Key of this extractor's minimums in [`Baselines`].
+    fn name(&self) -> &str;
+
+    fn extract_entities(&self, text: &str) -> Result<Vec<ExtractedEntity>>;
+}
+
+impl EntityExtractor for TrieNer {
+    fn name(&self) -> &str {
+        "trie"
+    }
+
+    fn extract_entities(&self, text: &str) -> Result<Vec<ExtractedEntity>> {
+        Ok(self.extract(text))
+    }
+}
+
+/// A statistical tagger alone, as `model`.
+pub struct ModelExtractor {
+    tagger: Arc<dyn SpanTagger>,
+}
+
+impl ModelExtractor {
+    pub fn new(tagger: Arc<dyn SpanTagger>) -> Self {
+        Self { tagger }
+    }
+}
+
+impl EntityExtractor for ModelExtractor {
+    fn name(&self) -> &str {
+        "model"
+    }
+
+    fn extract_entities(&self, text: &str) -> Result<Vec<ExtractedEntity>> {
+        self.tagger.tag_windowed(text)
+    }
+}
+
+/// A tagger's spans merged with dictionary matches by [`HybridNer`], as
+/// `hybrid`.
+pub struct HybridExtractor {
+    hybrid: HybridNer,
+    tagger: Arc<dyn SpanTagger>,
+}
+
+impl HybridExtractor {
+    pub fn new(hybrid: HybridNer, tagger: Arc<dyn SpanTagger>) -> Self {
+        Self { hybrid, tagger }
+    }
+}
+
+impl EntityExtractor for HybridExtractor {
+    fn name(&self) -> &str {
+        "hybrid"
+    }
+
+    fn extract_entities(&self, text: &str) -> Result<Vec<ExtractedEntity>> {
+        let spans = self.tagger.tag_windowed(text)?;
+        Ok(self
+            .hybrid
+            .merge(text, spans)
+            .into_iter()
+            .map(|e| e.entity)
+            .collect())
+    }
+}
+
+/// Counts for one entity type, or for all of them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct TypeCounts {
+    pub true_positives: usize,
+    pub false_positives: usize,
+    pub false_negatives: usize,
+    /// True positives whose gold entity has a canonical id.
+    pub with_gold_id: usize,
+    /// Of those, the ones the extractor gave the same id.
+    pub id_matches: usize,
+}
+
+impl TypeCounts {
+    pub fn precision(&self) -> f64 {
+        ratio(
+            self.true_positives,
+            self.true_positives + self.false_positives,
+        )
+    }
+
+    pub fn recall(&self) -> f64 {
+        ratio(
+            self.true_positives,
+            self.true_positives + self.false_negatives,
+        )
+    }
+
+    pub fn f1(&self) -> f64 {
+        let (p, r) = (self.precision(), self.recall());
+        if p + r == 0.0 {
+            0.0
+        } else {
+            2.0 * p * r / (p + r)
+        }
+    }
+
+    /// Share of matched entities normalised to the gold id; `None` when no
+    /// match had one to check.
+    pub fn normalisation(&self) -> Option<f64> {
+        (self.with_gold_id > 0).then(|| ratio(self.id_matches, self.with_gold_id))
+    }
+
+    fn add(&mut self, other: &TypeCounts) {
+        self.true_positives += other.true_positives;
+        self.false_positives += other.false_positives;
+        self.false_negatives += other.false_negatives;
+        self.with_gold_id += other.with_gold_id;
+        self.id_matches += other.id_matches;
+    }
+}
+
+fn ratio(num: usize, den: usize) -> f64 {
+    if den == 0 {
+        0.0
+    } else {
+        num as f64 / den as f64
+    }
+}
+
+/// One extractor's scores over a golden set.
+#[derive(Debug, Clone, Serialize)]
+pub struct EvalReport {
+    pub extractor: String,
+    pub sentences: usize,
+    /// Keyed by [`EntityType::as_str`]; a type appears once it is in the
+    /// gold set or among the predictions.
+    pub per_type: BTreeMap<String, TypeCounts>,
+    /// Ids of the sentences with any false positive or false negative.
+    pub failed_sentences: Vec<String>,
+}
+
+impl EvalReport {
+    /// Counts summed over every type.
+    pub fn micro(&self) -> TypeCounts {
+        let mut total = TypeCounts::default();
+        for counts in self.per_type.values() {
+            total.add(counts);
+        }
+        total
+    }
+}
+
+/// Scores `extractor` over `sentences`. A prediction is a true positive
+/// when a gold entity of the same type has exactly its span; each gold
+/// entity is matched at most once.
+pub fn evaluate(extractor: &dyn EntityExtractor, sentences: &[GoldSentence]) -> Result<EvalReport> {
+    let mut per_type: BTreeMap<String, TypeCounts> = BTreeMap::new();
+    let mut failed_sentences = Vec::new();
+    for sentence in sentences {
+        let predicted = extractor
+            .extract_entities(&sentence.text)
+            .with_context(|| format!("{} on {}", extractor.name(), sentence.id))?;
+        let mut unmatched: Vec<&GoldEntity> = sentence.entities.iter().collect();
+        let mut clean = true;
+        for entity in &predicted {
+            let label = entity.label.as_str();
+            let hit = unmatched.iter().position(|g| {
+                g.start == entity.start
+                    && g.end == entity.end
+                    && EntityType::from_label(&g.label) == Some(entity.label)
+            });
+            let counts = per_type.entry(label.to_string()).or_default();
+            match hit {
+                Some(i) => {
+                    let gold = unmatched.swap_remove(i);
+                    counts.true_positives += 1;
+                    if let Some(id) = &gold.canonical_id {
+                        counts.with_gold_id += 1;
+                        if entity.canonical_id.as_ref() == Some(id) {
+                            counts.id_matches += 1;
+                        } else {
+                            clean = false;
+                        }
+                    }
+                }
+                None => {
+                    counts.false_positives += 1;
+                    clean = false;
+                }
+            }
+        }
+        for gold in unmatched {
+            let label = EntityType::from_label(&gold.label).map_or("OTHER", |t| t.as_str());
+            per_type
+                .entry(label.to_string())
+                .or_default()
+                .false_negatives += 1;
+            clean = false;
+        }
+        if !clean {
+            failed_sentences.push(sentence.id.clone());
+        }
+    }
+    Ok(EvalReport {
+        extractor: extractor.name().to_string(),
+        sentences: sentences.len(),
+        per_type,
+        failed_sentences,
+    })
+}
+
+/// Committed minimums for every extractor, keyed by
+/// [`EntityExtractor::name`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Baselines(pub BTreeMap<String, Baseline>);
+
+impl Baselines {
+    pub fn load(path: &Path) -> Result<Self> {
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("reading baseline {}", path.display()))?;
+        toml::from_str(&raw).with_context(|| format!("parsing baseline {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let raw = toml::to_string_pretty(self)?;
+        std::fs::write(path, raw).with_context(|| format!("writing baseline {}", path.display()))
+    }
+
+    pub fn get(&self, extractor: &str) -> Option<&Baseline> {
+        self.0.get(extractor)
+    }
+
+    pub fn set(&mut self, extractor: impl Into<String>, baseline: Baseline) {
+        self.0.insert(extractor.into(), baseline);
+    }
+}
+
+/// Minimum scores one extractor must keep.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub micro_f1: f64,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub types: BTreeMap<String, TypeMinimum>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TypeMinimum {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub f1: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub normalisation: Option<f64>,
+}
+
+/// A score below its committed minimum.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regression {
+    /// `micro_f1`, or `<TYPE>.f1` / `<TYPE>.normalisation`.
+    pub metric: String,
+    pub minimum: f64,
+    pub actual: f64,
+}
+
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is {:.3}, below the baseline {:.3}",
+            self.metric, self.actual, self.minimum
+        )
+    }
+}
+
+/// Floating-point slack when comparing a score with its minimum.
+const TOLERANCE: f64 = 1e-9;
+
+impl Baseline {
+    /// `report`'s scores, each rounded down to two decimals, as the new
+    /// minimums.
+    pub fn from_report(report: &EvalReport) -> Self {
+        let floor = |v: f64| (v * 100.0 + TOLERANCE).floor() / 100.0;
+        Self {
+            micro_f1: floor(report.micro().f1()),
+            types: report
+                .per_type
+                .iter()
+                .map(|(label, counts)| {
+                    let minimum = TypeMinimum {
+                        f1: Some(floor(counts.f1())),
+                        normalisation: counts.normalisation().map(floor),
+                    };
+                    (label.clone(), minimum)
+                })
+                .collect(),
+        }
+    }
+
+    /// Every minimum `report` falls below. A type missing from the report
+    /// scores zero.
+    pub fn check(&self, report: &EvalReport) -> Vec<Regression> {
+        let mut regressions = Vec::new();
+        let mut require = |metric: String, minimum: f64, actual: f64| {
+            if actual + TOLERANCE < minimum {
+                regressions.push(Regression {
+                    metric,
+                    minimum,
+                    actual,
+                });
+            }
+        };
+        require("micro_f1".to_string(), self.micro_f1, report.micro().f1());
+        for (label, minimum) in &self.types {
+            let counts = report.per_type.get(label).copied().unwrap_or_default();
+            if let Some(f1) = minimum.f1 {
+                require(format!("{label}.f1"), f1, counts.f1());
+            }
+            if let Some(norm) = minimum.normalisation {
+                require(
+                    format!("{label}.normalisation"),
+                    norm,
+                    counts.normalisation().unwrap_or(0.0),
+                );
+            }
+        }
+        regressions
+    }
+}
+
+/// A plain-text table of `report`, with each F1 and normalisation score's
+/// margin over `baseline` where it sets a minimum.
+pub fn render_report(report: &EvalReport, baseline: Option<&Baseline>) -> String {
+    let delta = |actual: f64, minimum: Option<f64>| {
+        minimum.map_or_else(String::new, |m| format!("{:+.3}", actual - m))
+    };
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{} over {} sentences",
+        report.extractor, report.sentences
+    );
+    let _ = writeln!(
+        out,
+        "{:<12} {:>4} {:>4} {:>4} {:>6} {:>6} {:>6} {:>7} {:>6} {:>7}",
+        "type", "tp", "fp", "fn", "prec", "rec", "f1", "Δf1", "norm", "Δnorm"
+    );
+    let micro = report.micro();
+    let rows = report
+        .per_type
+        .iter()
+        .map(|(label, counts)| {
+            (
+                label.as_str(),
+                *counts,
+                baseline.and_then(|b| b.types.get(label)),
+            )
+        })
+        .chain(std::iter::once(("micro", micro, None)));
+    for (label, counts, minimum) in rows {
+        let (f1_min, norm_min) = match (label, minimum) {
+            ("micro", _) => (baseline.map(|b| b.micro_f1), None),
+            (_, Some(m)) => (m.f1, m.normalisation),
+            (_, None) => (None, None),
+        };
+        let norm = counts.normalisation();
+        let _ = writeln!(
+            out,
+            "{:<12} {:>4} {:>4} {:>4} {:>6.3} {:>6.3} {:>6.3} {:>7} {:>6} {:>7}",
+            label,
+            counts.true_positives,
+            counts.false_positives,
+            counts.false_negatives,
+            counts.precision(),
+            counts.recall(),
+            counts.f1(),
+            delta(counts.f1(), f1_min),
+            norm.map_or_else(|| "-".to_string(), |n| format!("{n:.3}")),
+            norm.map_or_else(String::new, |n| delta(n, norm_min)),
+        );
+    }
+    if !report.failed_sentences.is_empty() {
+        let _ = writeln!(out, "imperfect: {}", report.failed_sentences.join(", "));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ner::trie_ner::tests::test_ner;
+
+    fn gold(id: &str, text: &str, entities: &[(&str, &str, Option<&str>)]) -> GoldSentence {
+        GoldSentence {
+            id: id.to_string(),
+            text: text.to_string(),
+            entities: entities
+                .iter()
+                .map(|(needle, label, id)| {
+                    let start = text.find(needle).unwrap();
+                    GoldEntity {
+                        text: needle.to_string(),
+                        label: label.to_string(),
+                        start,
+                        end: start + needle.len(),
+                        canonical_id: id.map(str::to_string),
+                    }
+                })
+                .collect(),
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn exact_spans_score_per_type_and_ids_are_checked() {
+        let sentences = vec![
+            gold(
+                "s1",
+                "KRAS and LFS1 loss in PAAD",
+                &[
+                    ("KRAS", "GENE", Some("HGNC:6407")),
+                    ("LFS1", "GENE", Some("HGNC:1100")),
+                    ("PAAD", "CANCER_TYPE", Some("PAAD")),
+                ],
+            ),
+            gold(
+                "s2",
+                "SMAD4 protein loss",
+                &[("SMAD4 protein", "GENE", None)],
+            ),
+            gold("s3", "he was all smiles", &[]),
+        ];
+        let report = evaluate(&test_ner(), &sentences).unwrap();
+
+        let genes = report.per_type["GENE"];
+        assert_eq!(
+            (
+                genes.true_positives,
+                genes.false_positives,
+                genes.false_negatives
+            ),
+            (2, 1, 1)
+        );
+        // LFS1 resolves to TP53, not the id the gold set expects.
+        assert_eq!(genes.normalisation(), Some(0.5));
+        assert_eq!(report.per_type["CANCER_TYPE"].f1(), 1.0);
+        let micro = report.micro();
+        assert_eq!(micro.true_positives, 3);
+        assert!((micro.precision() - 0.75).abs() < 1e-9);
+        assert!((micro.recall() - 0.75).abs() < 1e-9);
+        assert_eq!(report.failed_sentences, vec!["s1", "s2"]);
+    }
+
+    #[test]
+    fn baselines_flag_scores_below_their_minimums() {
+        let sentences = vec![gold(
+            "s1",
+            "KRAS in PAAD",
+            &[("KRAS", "GENE", None), ("PAAD", "CANCER_TYPE", None)],
+        )];
+        let report = evaluate(&test_ner(), &sentences).unwrap();
+        assert!(Baseline::from_report(&report).check(&report).is_empty());
+
+        let mut strict = Baseline {
+            micro_f1: 0.9,
+            ..Baseline::default()
+        };
+        strict.types.insert(
+            "CHEMICAL".to_string(),
+            TypeMinimum {
+                f1: Some(0.5),
+                normalisation: None,
+            },
+        );
+        let regressions = strict.check(&report);
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].metric, "CHEMICAL.f1");
+        assert_eq!(regressions[0].actual, 0.0);
+
+        let mut baselines = Baselines::default();
+        baselines.set("trie", strict.clone());
+        let parsed: Baselines =
+            toml::from_str(&toml::to_string_pretty(&baselines).unwrap()).unwrap();
+        assert_eq!(parsed.get("trie"), Some(&strict));
+
+        let table = render_report(&report, Some(&strict));
+        assert!(table.contains("micro"), "{table}");
+        assert!(table.contains("+0.100"), "{table}");
+    }
+
+    #[test]
+    fn loader_rejects_spans_that_miss_their_text() {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-golden-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("golden.jsonl");
+        let good = r#"{"id":"a","text":"KRAS in PAAD","entities":[{"text":"KRAS","type":"GENE","start":0,"end":4}]}"#;
+        std::fs::write(&path, format!("{good}\n\n")).unwrap();
+        assert_eq!(load_golden_set(&path).unwrap().len(), 1);
+
+        let shifted = good.replace(r#""start":0,"end":4"#, r#""start":1,"end":5"#);
+        std::fs::write(&path, &shifted).unwrap();
+        assert!(load_golden_set(&path).is_err());
+        std::fs::write(&path, good.replace("GENE", "PROTEIN")).unwrap();
+        assert!(load_golden_set(&path).is_err());
+        std::fs::write(&path, format!("{good}\n{good}")).unwrap();
+        assert!(load_golden_set(&path).is_err());
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
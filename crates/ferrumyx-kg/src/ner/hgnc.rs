@@ -5,21 +5,92 @@ use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use super::dictionaries;
 
 /// Tier of a gene symbol match — affects confidence scoring.
 /// Preferred = approved HGNC symbol (highest signal).
 /// Alias     = known alternate symbol (may have false positives).
 /// Previous  = old/deprecated symbol (more noise, kept for coverage).
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum SymbolTier {
     Preferred,
     Alias,
     Previous,
 }
 
+/// How a queried symbol matched a gene, best first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchQuality {
+    /// The approved symbol, ignoring case, hyphens and spaces.
+    Exact,
+    Alias,
+    Previous,
+    /// One edit away from a known symbol (symbols of 4+ characters only).
+    Fuzzy,
+}
+
+impl MatchQuality {
+    /// The dictionary tier behind an indexed match; `None` for fuzzy ones.
+    pub fn tier(self) -> Option<SymbolTier> {
+        match self {
+            Self::Exact => Some(SymbolTier::Preferred),
+            Self::Alias => Some(SymbolTier::Alias),
+            Self::Previous => Some(SymbolTier::Previous),
+            Self::Fuzzy => None,
+        }
+    }
+}
+
+impl From<SymbolTier> for MatchQuality {
+    fn from(tier: SymbolTier) -> Self {
+        match tier {
+            SymbolTier::Preferred => Self::Exact,
+            SymbolTier::Alias => Self::Alias,
+            SymbolTier::Previous => Self::Previous,
+        }
+    }
+}
+
+/// A gene a symbol resolved to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolMatch<'a> {
+    pub record: &'a HgncRecord,
+    pub quality: MatchQuality,
+}
+
+/// Outcome of [`HgncNormaliser::resolve`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SymbolResolution<'a> {
+    Found(SymbolMatch<'a>),
+    /// The symbol names several genes; candidates are ordered by quality,
+    /// then approved symbol.
+    Ambiguous(Vec<SymbolMatch<'a>>),
+    NotFound,
+}
+
+impl<'a> SymbolResolution<'a> {
+    /// The match, when exactly one gene fits.
+    pub fn unique(&self) -> Option<&SymbolMatch<'a>> {
+        match self {
+            Self::Found(m) => Some(m),
+            _ => None,
+        }
+    }
+
+    /// Every gene the symbol may refer to.
+    pub fn candidates(&self) -> &[SymbolMatch<'a>] {
+        match self {
+            Self::Found(m) => std::slice::from_ref(m),
+            Self::Ambiguous(candidates) => candidates,
+            Self::NotFound => &[],
+        }
+    }
+}
+
 /// A canonical HGNC gene record.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HgncRecord {
     /// HGNC accession, e.g. "HGNC:6407"
     pub hgnc_id: String,
@@ -31,22 +102,34 @@ pub struct HgncRecord {
     pub entrez_id: Option<String>,
     /// Ensembl gene ID
     pub ensembl_id: Option<String>,
+    /// UniProt accessions, e.g. ["P01116"]
+    #[serde(default)]
+    pub uniprot_ids: Vec<String>,
 }
 
+/// Shortest symbol, after normalisation, that fuzzy matching applies to.
+const FUZZY_MIN_LEN: usize = 4;
+
 /// HGNC bulk download URL (approved complete set, TSV).
 const HGNC_COMPLETE_SET_URL: &str =
     "https://storage.googleapis.com/public-download-files/hgnc/tsv/tsv/hgnc_complete_set.txt";
 
+/// Genes indexed under a symbol, as (index into `records`, tier).
+type Entries = Vec<(usize, SymbolTier)>;
+
 /// In-memory HGNC normaliser.
 pub struct HgncNormaliser {
-    /// Map from any known symbol/alias/prev symbol → (canonical record, tier).
-    lookup: HashMap<String, (HgncRecord, SymbolTier)>,
-    /// Total records loaded.
-    n_records: usize,
+    records: Vec<HgncRecord>,
+    /// Map from any known symbol/alias/prev symbol (upper-cased) → genes.
+    lookup: HashMap<String, Entries>,
+    /// The same symbols with hyphens and spaces stripped.
+    normalised: HashMap<String, Entries>,
+    /// Characters of the normalised symbols, for fuzzy candidates.
+    alphabet: Vec<char>,
 }
 
 impl HgncNormaliser {
-    fn cache_path() -> PathBuf {
+    pub(crate) fn cache_path() -> PathBuf {
         let root =
             std::env::var("FERRUMYX_CACHE_DIR").unwrap_or_else(|_| "./data/cache/ner".to_string());
         PathBuf::from(root).join("hgnc_complete_set.txt")
@@ -54,11 +137,19 @@ impl HgncNormaliser {
 
     /// Build from the HGNC complete set downloaded at runtime.
     pub async fn from_download() -> Result<Self> {
-        let cache_path = Self::cache_path();
-        if let Ok(tsv) = fs::read_to_string(&cache_path) {
+        Self::from_cache_or_download(&Self::cache_path(), dictionaries::offline()).await
+    }
+
+    /// Build from the TSV at `cache_path`, downloading it there first
+    /// unless `offline`.
+    pub(crate) async fn from_cache_or_download(cache_path: &Path, offline: bool) -> Result<Self> {
+        if let Ok(tsv) = fs::read_to_string(cache_path) {
             tracing::info!("Loaded HGNC dataset from cache: {}", cache_path.display());
             return Self::from_tsv(&tsv);
         }
+        if offline {
+            return Err(dictionaries::not_cached("HGNC complete set", cache_path));
+        }
 
         tracing::info!(
             "Downloading HGNC complete set from {}",
@@ -74,7 +165,7 @@ impl HgncNormaliser {
         if let Some(parent) = cache_path.parent() {
             let _ = fs::create_dir_all(parent);
         }
-        let _ = fs::write(&cache_path, &resp);
+        let _ = fs::write(cache_path, &resp);
 
         Self::from_tsv(&resp)
     }
@@ -89,8 +180,8 @@ impl HgncNormaliser {
 
     /// Build from a pre-downloaded TSV string (for testing / offline use).
     pub fn from_tsv(tsv: &str) -> Result<Self> {
-        let mut lookup: HashMap<String, (HgncRecord, SymbolTier)> = HashMap::new();
-        let mut n_records = 0usize;
+        let mut records = Vec::new();
+        let mut lookup: HashMap<String, Entries> = HashMap::new();
 
         for (line_no, line) in tsv.lines().enumerate() {
             if line_no == 0 {
@@ -110,6 +201,12 @@ impl HgncNormaliser {
             let status = get(5);
             let entrez_id = non_empty(get(18));
             let ensembl_id = non_empty(get(19));
+            let uniprot_ids: Vec<String> = get(25)
+                .split('|')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+                .collect();
 
             if !status.contains("Approved") {
                 continue;
@@ -118,58 +215,175 @@ impl HgncNormaliser {
                 continue;
             }
 
-            let record = HgncRecord {
-                hgnc_id: hgnc_id.clone(),
-                symbol: symbol.clone(),
-                name: name.clone(),
-                entrez_id,
-                ensembl_id,
+            let index = records.len();
+            let mut index_as = |key: &str, tier: SymbolTier| {
+                let key = key.trim().to_uppercase();
+                if !key.is_empty() {
+                    lookup.entry(key).or_default().push((index, tier));
+                }
             };
-
-            // Preferred symbol — highest tier
-            lookup.insert(
-                symbol.to_uppercase(),
-                (record.clone(), SymbolTier::Preferred),
-            );
-
-            // Alias symbols — second tier
-            for alias in get(8).split('|').filter(|s| !s.is_empty()) {
-                let key = alias.trim().to_uppercase();
-                lookup
-                    .entry(key)
-                    .or_insert_with(|| (record.clone(), SymbolTier::Alias));
+            index_as(&symbol, SymbolTier::Preferred);
+            for alias in get(8).split('|') {
+                index_as(alias, SymbolTier::Alias);
             }
-
-            // Previous symbols — lowest tier
-            for prev in get(10).split('|').filter(|s| !s.is_empty()) {
-                let key = prev.trim().to_uppercase();
-                lookup
-                    .entry(key)
-                    .or_insert_with(|| (record.clone(), SymbolTier::Previous));
+            for prev in get(10).split('|') {
+                index_as(prev, SymbolTier::Previous);
             }
 
-            n_records += 1;
+            records.push(HgncRecord {
+                hgnc_id,
+                symbol,
+                name,
+                entrez_id,
+                ensembl_id,
+                uniprot_ids,
+            });
+        }
+
+        let mut normalised: HashMap<String, Entries> = HashMap::new();
+        for (key, entries) in &lookup {
+            normalised
+                .entry(normalise_key(key))
+                .or_default()
+                .extend(entries);
         }
+        let mut alphabet: Vec<char> = normalised.keys().flat_map(|k| k.chars()).collect();
+        alphabet.sort_unstable();
+        alphabet.dedup();
 
         tracing::info!(
             "HGNC normaliser built: {} records, {} lookup entries",
-            n_records,
+            records.len(),
             lookup.len()
         );
-        Ok(Self { lookup, n_records })
+        Ok(Self {
+            records,
+            lookup,
+            normalised,
+            alphabet,
+        })
     }
 
-    /// Look up a symbol, returning both the record and its tier.
+    /// Resolve a symbol as written in the literature. Tries, in order, the
+    /// symbol itself, the symbol with case, hyphens and spaces ignored, each
+    /// half of a slash pair ("HER2/neu") when both name the same gene, and
+    /// finally every indexed symbol one edit away. A symbol that is an alias
+    /// or previous symbol of several genes is [`SymbolResolution::Ambiguous`]
+    /// rather than resolved to one of them.
+    pub fn resolve(&self, symbol: &str) -> SymbolResolution<'_> {
+        match self.resolve_indexed(symbol) {
+            SymbolResolution::NotFound => self.resolve_fuzzy(symbol),
+            resolved => resolved,
+        }
+    }
+
+    /// [`Self::resolve`] without the fuzzy fallback.
+    fn resolve_indexed(&self, symbol: &str) -> SymbolResolution<'_> {
+        if let Some(entries) = self.lookup.get(&symbol.trim().to_uppercase()) {
+            return self.collapse(entries.iter().copied(), false);
+        }
+        if let Some(entries) = self.normalised.get(&normalise_key(symbol)) {
+            return self.collapse(entries.iter().copied(), false);
+        }
+        if symbol.contains('/') {
+            return self.resolve_slash_pair(symbol);
+        }
+        SymbolResolution::NotFound
+    }
+
+    /// "HER2/neu": found only when every part names the same single gene.
+    fn resolve_slash_pair(&self, symbol: &str) -> SymbolResolution<'_> {
+        let mut best: Option<SymbolMatch<'_>> = None;
+        for part in symbol.split('/').map(str::trim).filter(|p| !p.is_empty()) {
+            let resolved = match self.resolve_indexed(part) {
+                SymbolResolution::Found(m) => m,
+                _ => return SymbolResolution::NotFound,
+            };
+            match &best {
+                Some(b) if b.record.hgnc_id != resolved.record.hgnc_id => {
+                    return SymbolResolution::NotFound;
+                }
+                Some(b) if b.quality <= resolved.quality => {}
+                _ => best = Some(resolved),
+            }
+        }
+        best.map_or(SymbolResolution::NotFound, SymbolResolution::Found)
+    }
+
+    /// Genes whose normalised symbols are one insertion, deletion or
+    /// substitution away from `symbol`.
+    fn resolve_fuzzy(&self, symbol: &str) -> SymbolResolution<'_> {
+        let query: Vec<char> = normalise_key(symbol).chars().collect();
+        if query.len() < FUZZY_MIN_LEN {
+            return SymbolResolution::NotFound;
+        }
+        let mut variants = Vec::new();
+        for i in 0..=query.len() {
+            let (head, tail) = query.split_at(i);
+            if let Some((_, rest)) = tail.split_first() {
+                variants.push(head.iter().chain(rest).collect::<String>());
+                for &c in &self.alphabet {
+                    variants.push(head.iter().chain([&c]).chain(rest).collect());
+                }
+            }
+            for &c in &self.alphabet {
+                variants.push(head.iter().chain([&c]).chain(tail).collect());
+            }
+        }
+        let entries = variants
+            .iter()
+            .filter(|v| v.chars().count() >= FUZZY_MIN_LEN)
+            .filter_map(|v| self.normalised.get(v))
+            .flatten()
+            .copied();
+        self.collapse(entries, true)
+    }
+
+    /// One result per gene among `entries`, keeping its best tier. An
+    /// approved symbol outranks other genes' aliases, except fuzzily.
+    fn collapse(
+        &self,
+        entries: impl Iterator<Item = (usize, SymbolTier)>,
+        fuzzy: bool,
+    ) -> SymbolResolution<'_> {
+        let mut best: HashMap<usize, SymbolTier> = HashMap::new();
+        for (index, tier) in entries {
+            best.entry(index)
+                .and_modify(|t| *t = (*t).min(tier))
+                .or_insert(tier);
+        }
+        if !fuzzy && best.values().any(|t| *t == SymbolTier::Preferred) {
+            best.retain(|_, t| *t == SymbolTier::Preferred);
+        }
+        let mut matches: Vec<SymbolMatch<'_>> = best
+            .into_iter()
+            .map(|(index, tier)| SymbolMatch {
+                record: &self.records[index],
+                quality: if fuzzy {
+                    MatchQuality::Fuzzy
+                } else {
+                    tier.into()
+                },
+            })
+            .collect();
+        matches.sort_by(|a, b| (a.quality, &a.record.symbol).cmp(&(b.quality, &b.record.symbol)));
+        match matches.len() {
+            0 => SymbolResolution::NotFound,
+            1 => SymbolResolution::Found(matches.remove(0)),
+            _ => SymbolResolution::Ambiguous(matches),
+        }
+    }
+
+    /// Look up a symbol, returning both the record and its tier. Ambiguous
+    /// symbols and fuzzy matches return `None`; see [`Self::resolve`].
     pub fn lookup_with_tier(&self, symbol: &str) -> Option<(&HgncRecord, SymbolTier)> {
-        self.lookup
-            .get(&symbol.trim().to_uppercase())
-            .map(|(rec, tier)| (rec, *tier))
+        let resolved = self.resolve_indexed(symbol);
+        let m = resolved.unique()?;
+        Some((m.record, m.quality.tier()?))
     }
 
     pub fn lookup(&self, symbol: &str) -> Option<&HgncRecord> {
-        self.lookup
-            .get(&symbol.trim().to_uppercase())
-            .map(|(rec, _)| rec)
+        self.lookup_with_tier(symbol).map(|(rec, _)| rec)
     }
 
     pub fn normalise_symbol(&self, symbol: &str) -> Option<String> {
@@ -180,18 +394,24 @@ impl HgncNormaliser {
         self.lookup(symbol).map(|r| r.hgnc_id.clone())
     }
 
+    /// First UniProt accession of the gene behind `symbol`.
+    pub fn to_uniprot_id(&self, symbol: &str) -> Option<String> {
+        self.lookup(symbol)
+            .and_then(|r| r.uniprot_ids.first().cloned())
+    }
+
     pub fn n_records(&self) -> usize {
-        self.n_records
+        self.records.len()
     }
     pub fn n_lookup_entries(&self) -> usize {
         self.lookup.len()
     }
 
-    /// All patterns for the trie, paired with their tier.
+    /// All patterns for the trie, paired with their best tier.
     pub fn all_patterns_with_tier(&self) -> Vec<(String, SymbolTier)> {
         self.lookup
             .iter()
-            .map(|(k, (_, tier))| (k.clone(), *tier))
+            .filter_map(|(k, entries)| Some((k.clone(), entries.iter().map(|(_, t)| *t).min()?)))
             .collect()
     }
 
@@ -201,6 +421,18 @@ impl HgncNormaliser {
     }
 }
 
+const DASHES: &[char] = &['-', '\u{2010}', '\u{2011}', '\u{2013}'];
+
+/// Upper-cased with hyphens (including Unicode dashes) and whitespace removed,
+/// so "NF-kB", "NF kB" and "NFKB" share a key.
+fn normalise_key(symbol: &str) -> String {
+    symbol
+        .chars()
+        .filter(|c| !c.is_whitespace() && !DASHES.contains(c))
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
 fn non_empty(s: &str) -> Option<String> {
     if s.is_empty() {
         None
@@ -208,3 +440,136 @@ fn non_empty(s: &str) -> Option<String> {
         Some(s.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(id: &str, symbol: &str, aliases: &str, previous: &str) -> String {
+        let mut fields = vec![""; 26];
+        fields[0] = id;
+        fields[1] = symbol;
+        fields[2] = symbol;
+        fields[5] = "Approved";
+        fields[8] = aliases;
+        fields[10] = previous;
+        fields.join("\t")
+    }
+
+    fn fixture() -> HgncNormaliser {
+        let tsv = [
+            "header".to_string(),
+            row("HGNC:3430", "ERBB2", "NEU|HER-2|CD340|HER2|MLN 19", "NGL"),
+            row("HGNC:3236", "EGFR", "ERBB|ERBB1|HER1", ""),
+            row("HGNC:7794", "NFKB1", "KBF1|NF-kappaB|NFKB-p50|p105|p50", ""),
+            row("HGNC:7795", "NFKB2", "p52|p100|LYT-10", "LYT10"),
+            row("HGNC:6407", "KRAS", "RASK2|K-ras|c-Ki-ras", "KRAS2"),
+            row("HGNC:5173", "HRAS", "p21ras|C-H-RAS", "HRAS1"),
+            row("HGNC:7989", "NRAS", "N-ras", ""),
+            row("HGNC:1097", "BRAF", "BRAF1|B-RAF1", ""),
+            row("HGNC:11998", "TP53", "p53|LFS1", ""),
+            row("HGNC:9588", "PTEN", "MMAC1|TEP1", ""),
+            row("HGNC:7029", "MET", "HGFR|c-Met", ""),
+            row("HGNC:6770", "SMAD4", "DPC4", "MADH4"),
+            row("HGNC:1787", "CDKN2A", "p16|INK4A|p14ARF|p19", "CDKN2|MLM"),
+            row("HGNC:1791", "CDKN2C", "p18|INK4C", ""),
+            row("HGNC:1790", "CDKN2D", "p19|INK4D", ""),
+            row("HGNC:583", "APC", "DP2.5", ""),
+        ]
+        .join("\n");
+        HgncNormaliser::from_tsv(&tsv).unwrap()
+    }
+
+    enum Expected {
+        Found(&'static str, MatchQuality),
+        Ambiguous(&'static [&'static str]),
+        NotFound,
+    }
+
+    #[test]
+    fn tricky_symbols_resolve_as_expected() {
+        use Expected::*;
+        use MatchQuality::*;
+
+        let hgnc = fixture();
+        let table = [
+            ("KRAS", Found("KRAS", Exact)),
+            ("kras", Found("KRAS", Exact)),
+            (" EGFR ", Found("EGFR", Exact)),
+            ("tp-53", Found("TP53", Exact)),
+            ("p53", Found("TP53", Alias)),
+            ("K-ras", Found("KRAS", Alias)),
+            ("Kras2", Found("KRAS", Previous)),
+            ("c-Met", Found("MET", Alias)),
+            ("cMet", Found("MET", Alias)),
+            ("HER2", Found("ERBB2", Alias)),
+            ("her 2", Found("ERBB2", Alias)),
+            ("HER2/neu", Found("ERBB2", Alias)),
+            ("MLN19", Found("ERBB2", Alias)),
+            ("NGL", Found("ERBB2", Previous)),
+            ("HER1", Found("EGFR", Alias)),
+            ("MADH4", Found("SMAD4", Previous)),
+            ("CDKN2", Found("CDKN2A", Previous)),
+            ("lyt-10", Found("NFKB2", Alias)),
+            ("LYT10", Found("NFKB2", Previous)),
+            ("KRASS", Found("KRAS", Fuzzy)),
+            ("PTEM", Found("PTEN", Fuzzy)),
+            ("HRAZ", Found("HRAS", Fuzzy)),
+            ("p19", Ambiguous(&["CDKN2A", "CDKN2D"])),
+            ("NF-kB", Ambiguous(&["NFKB1", "NFKB2"])),
+            ("XRAS", Ambiguous(&["HRAS", "KRAS", "NRAS"])),
+            ("ERBB3", Ambiguous(&["EGFR", "ERBB2"])),
+            ("CDKN2B", Ambiguous(&["CDKN2A", "CDKN2C", "CDKN2D"])),
+            ("PTNE", NotFound),
+            ("APCC", NotFound),
+            ("TP5", NotFound),
+            ("HER2/EGFR", NotFound),
+            ("", NotFound),
+        ];
+
+        for (symbol, expected) in table {
+            let resolved = hgnc.resolve(symbol);
+            match expected {
+                Found(approved, quality) => {
+                    let m = resolved
+                        .unique()
+                        .unwrap_or_else(|| panic!("{symbol:?}: {resolved:?}"));
+                    assert_eq!(m.record.symbol, approved, "{symbol:?}");
+                    assert_eq!(m.quality, quality, "{symbol:?}");
+                }
+                Ambiguous(approved) => {
+                    assert!(
+                        matches!(resolved, SymbolResolution::Ambiguous(_)),
+                        "{symbol:?}: {resolved:?}"
+                    );
+                    let symbols: Vec<&str> = resolved
+                        .candidates()
+                        .iter()
+                        .map(|m| m.record.symbol.as_str())
+                        .collect();
+                    assert_eq!(symbols, approved, "{symbol:?}");
+                }
+                NotFound => assert_eq!(resolved, SymbolResolution::NotFound, "{symbol:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn plain_lookup_skips_ambiguous_and_fuzzy_matches() {
+        let hgnc = fixture();
+        assert_eq!(hgnc.normalise_symbol("NF-kappaB").as_deref(), Some("NFKB1"));
+        assert_eq!(
+            hgnc.lookup_with_tier("KRAS2")
+                .map(|(r, t)| (r.symbol.as_str(), t)),
+            Some(("KRAS", SymbolTier::Previous))
+        );
+        assert!(hgnc.lookup("p19").is_none());
+        assert!(hgnc.lookup("KRASS").is_none());
+
+        let tiers: HashMap<String, SymbolTier> =
+            hgnc.all_patterns_with_tier().into_iter().collect();
+        assert_eq!(tiers["P19"], SymbolTier::Alias);
+        assert_eq!(tiers["LYT10"], SymbolTier::Previous);
+        assert_eq!(hgnc.n_records(), 16);
+    }
+}
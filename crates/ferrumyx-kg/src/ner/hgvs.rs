@@ -1,9 +1,16 @@
 //! HGVS mutation notation normalisation.
 //! Ported from ferrumyx-ingestion to ferrumyx-kg.
+//!
+//! Papers write the same variant many ways: "KRAS G12D", "p.Gly12Asp",
+//! "Gly12→Asp", "c.35G>A", multi-allele shorthand ("G12D/V"), protein
+//! indels ("E746_A750del"), exon-level events ("exon 19 deletion") and
+//! fusions ("EML4-ALK"). [`HgvsMutationNormaliser`] parses all of them into
+//! [`NormalisedMutation`]s, one per allele.
 
-use regex::Regex;
+use regex::{Captures, Regex};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::ops::Range;
 
 /// Single-letter → three-letter amino acid map.
 fn aa1_to_aa3(aa: &str) -> Option<&'static str> {
@@ -28,22 +35,145 @@ fn aa1_to_aa3(aa: &str) -> Option<&'static str> {
         "V" => Some("Val"),
         "W" => Some("Trp"),
         "Y" => Some("Tyr"),
-        "*" => Some("Ter"),
+        "*" | "X" => Some("Ter"),
         _ => None,
     }
 }
 
+/// Three-letter → single-letter amino acid code, "*" for a stop.
+fn aa3_to_aa1(aa: &str) -> Option<char> {
+    let aa1 = match aa {
+        "Ala" => 'A',
+        "Cys" => 'C',
+        "Asp" => 'D',
+        "Glu" => 'E',
+        "Phe" => 'F',
+        "Gly" => 'G',
+        "His" => 'H',
+        "Ile" => 'I',
+        "Lys" => 'K',
+        "Leu" => 'L',
+        "Met" => 'M',
+        "Asn" => 'N',
+        "Pro" => 'P',
+        "Gln" => 'Q',
+        "Arg" => 'R',
+        "Ser" => 'S',
+        "Thr" => 'T',
+        "Val" => 'V',
+        "Trp" => 'W',
+        "Tyr" => 'Y',
+        "Ter" => '*',
+        _ => return None,
+    };
+    Some(aa1)
+}
+
+/// Either amino acid notation → the three-letter code.
+fn to_aa3(aa: &str) -> Option<&'static str> {
+    if aa.len() == 1 {
+        aa1_to_aa3(aa)
+    } else {
+        normalise_aa3(aa)
+    }
+}
+
+/// What kind of event a mutation mention describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MutationKind {
+    /// A single residue or base change, including nonsense changes.
+    Substitution,
+    Deletion,
+    Insertion,
+    Duplication,
+    DelIns,
+    Frameshift,
+    /// Exon skipping, e.g. "MET exon 14 skipping".
+    ExonSkipping,
+    /// Gene fusion, e.g. "EML4-ALK".
+    Fusion,
+}
+
 /// Normalised mutation result.
+///
+/// Protein fields are `None` for events written only at the DNA, exon or
+/// fusion level.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct NormalisedMutation {
     pub raw: String,
-    pub hgvs_p: String,
-    pub position: u32,
-    pub ref_aa: String,
-    pub alt_aa: String,
+    pub kind: MutationKind,
+    /// Gene the change was written against ("KRAS G12D") or given with it.
+    pub gene: Option<String>,
+    /// Three-letter reference residue, e.g. "Gly".
+    pub ref_aa: Option<String>,
+    pub position: Option<u32>,
+    /// Three-letter alternate residue, e.g. "Asp"; "Ter" for a stop.
+    pub alt_aa: Option<String>,
+    /// e.g. "p.Gly12Asp", "p.Glu746_Ala750del"
+    pub hgvs_p: Option<String>,
+    /// e.g. "c.35G>A"
+    pub hgvs_c: Option<String>,
+    /// Exon of an exon-level event.
+    pub exon: Option<u32>,
+    /// 5' and 3' partners of a fusion.
+    pub fusion_partners: Option<(String, String)>,
     pub rs_id: Option<String>,
 }
 
+impl NormalisedMutation {
+    fn new(raw: &str, kind: MutationKind) -> Self {
+        Self {
+            raw: raw.to_string(),
+            kind,
+            gene: None,
+            ref_aa: None,
+            position: None,
+            alt_aa: None,
+            hgvs_p: None,
+            hgvs_c: None,
+            exon: None,
+            fusion_partners: None,
+            rs_id: None,
+        }
+    }
+
+    /// One-letter protein change ("G12D", "R213*") of a substitution.
+    pub fn short_form(&self) -> Option<String> {
+        if self.kind != MutationKind::Substitution {
+            return None;
+        }
+        let ref_aa = aa3_to_aa1(self.ref_aa.as_deref()?)?;
+        let alt_aa = aa3_to_aa1(self.alt_aa.as_deref()?)?;
+        Some(format!("{ref_aa}{}{alt_aa}", self.position?))
+    }
+
+    /// Compact name for the KG: the one-letter form of a substitution
+    /// ("G12D", matching `KRAS_G12D` in the config), otherwise the `p.` or
+    /// `c.` string, "exon 19 deletion" or "EML4::ALK".
+    pub fn label(&self) -> String {
+        if let Some(short) = self.short_form() {
+            return short;
+        }
+        if let Some((five, three)) = &self.fusion_partners {
+            return format!("{five}::{three}");
+        }
+        if let Some(exon) = self.exon {
+            let event = match self.kind {
+                MutationKind::Insertion => "insertion",
+                MutationKind::Duplication => "duplication",
+                MutationKind::ExonSkipping => "skipping",
+                _ => "deletion",
+            };
+            return format!("exon {exon} {event}");
+        }
+        self.hgvs_p
+            .clone()
+            .or_else(|| self.hgvs_c.clone())
+            .unwrap_or_else(|| self.raw.clone())
+    }
+}
+
 /// Static table of well-characterised variants.
 fn build_rsid_table() -> HashMap<(&'static str, &'static str), &'static str> {
     let mut m = HashMap::new();
@@ -92,86 +222,171 @@ fn normalise_aa3(aa: &str) -> Option<&'static str> {
     }
 }
 
+const AA3: &str =
+    "Ala|Arg|Asn|Asp|Cys|Gln|Glu|Gly|His|Ile|Leu|Lys|Met|Phe|Pro|Ser|Thr|Trp|Tyr|Val|Ter";
+const AA1: &str = "[ACDEFGHIKLMNPQRSTVWY]";
+const ALT1: &str = r"[ACDEFGHIKLMNPQRSTVWY*X]";
+const GENE: &str = "[A-Z][A-Z0-9]{1,9}";
+const FUSION_WORD: &str = r"\s+(?i:fusion|rearrangement|translocation)s?";
+
+/// Every notation as one alternation. In running text (`strict_fusions`)
+/// "A-B" only counts as a fusion when followed by "fusion" or a synonym;
+/// "A::B" always does.
+fn mutation_pattern(strict_fusions: bool) -> String {
+    let aa = format!("(?:{AA3}|{AA1})");
+    let alt = format!("(?:{AA3}|{ALT1})");
+    let indel = format!(
+        r"(?:p\.\(?)?(?P<iref>{aa})(?P<ipos>\d{{1,5}})(?:_(?P<iref2>{aa})(?P<ipos2>\d{{1,5}}))?(?:(?P<op>delins|del|dup|ins)|(?P<falt>{aa})?fs)(?P<tail>(?:{AA3}|[A-Z*])*\d*)\)?"
+    );
+    let substitution = format!(
+        r"(?:p\.\(?)?(?P<sref>{aa})(?P<spos>\d{{1,5}})(?P<salt>{alt})(?P<more>(?:/(?:{aa}\d{{1,5}})?{alt})*)\)?"
+    );
+    let arrow = format!(r"(?P<aref>{aa})[\s-]?(?P<apos>\d{{1,5}})\s*(?:→|->|⟶)\s*(?P<aalt>{alt})");
+    let cdna = r"c\.(?P<cpos>[0-9*\-][0-9_+\-*]*)(?:(?P<cref>[ACGT])>(?P<calt>[ACGT])|(?P<cop>delins|del|dup|ins)(?P<ctail>[ACGT]*\d*))";
+    let exon = r"(?i:ex(?:on)?\s*(?P<exon>\d{1,2})\s*(?P<eop>deletion|del|insertion|ins|duplication|dup|skipping|skip)[a-z]*)|(?i:(?P<rop>deletion|insertion|duplication)s?\s+(?:in|of|within)\s+exon\s*(?P<rexon>\d{1,2}))";
+    let fusion = format!(
+        r"(?P<g5>{GENE})(?:::(?P<g3c>{GENE})(?:{FUSION_WORD})?|[-–/](?P<g3>{GENE})(?P<fword>{FUSION_WORD}){})",
+        if strict_fusions { "" } else { "?" }
+    );
+    format!("{indel}|{substitution}|{arrow}|{cdna}|{exon}|{fusion}")
+}
+
 pub struct HgvsMutationNormaliser {
     rsid_table: HashMap<(&'static str, &'static str), &'static str>,
-    re_single: Regex,
-    re_hgvs: Regex,
+    /// One whole mention, e.g. "p.Gly12Asp".
+    re_mention: Regex,
+    /// A gene followed by a mention, e.g. "KRAS G12D".
+    re_gene_prefix: Regex,
+    /// Mentions in running text.
+    re_text: Regex,
 }
 
 impl HgvsMutationNormaliser {
     pub fn new() -> Self {
         Self {
             rsid_table: build_rsid_table(),
-            re_single: Regex::new(r"^([A-Z\*])(\d+)([A-Z\*])$").unwrap(),
-            re_hgvs: Regex::new(
-                r"^(?:p\.)?([A-Z][a-z]{0,2}|[A-Z\*])(\d+)([A-Z][a-z]{0,2}|[A-Z\*])$",
-            )
-            .unwrap(),
+            re_mention: Regex::new(&format!(r"^(?:{})$", mutation_pattern(false))).unwrap(),
+            re_gene_prefix: Regex::new(r"^(?P<gene>[A-Z][A-Za-z0-9]{1,9})[\s_:-]+(?P<rest>.+)$")
+                .unwrap(),
+            re_text: Regex::new(&mutation_pattern(true)).unwrap(),
         }
     }
 
+    /// Normalise one mention, e.g. "KRAS G12D" or "p.Gly12Asp". For
+    /// multi-allele shorthand this is the first allele; see
+    /// [`Self::normalise_all`].
     pub fn normalise(&self, raw: &str, gene: Option<&str>) -> Option<NormalisedMutation> {
+        self.normalise_all(raw, gene).into_iter().next()
+    }
+
+    /// Normalise one mention into a record per allele ("G12D/V" → G12D and
+    /// G12V). A gene written in the mention takes precedence over `gene`.
+    pub fn normalise_all(&self, raw: &str, gene: Option<&str>) -> Vec<NormalisedMutation> {
         let raw = raw.trim();
+        let mut found = match self.re_mention.captures(raw) {
+            Some(caps) => self.from_captures(&caps, raw, gene, false),
+            None => Vec::new(),
+        };
+        if found.is_empty() {
+            if let Some(caps) = self.re_gene_prefix.captures(raw) {
+                let prefix = &caps["gene"];
+                if let Some(rest) = self.re_mention.captures(caps["rest"].trim()) {
+                    if !self.re_mention.is_match(prefix) {
+                        found = self.from_captures(&rest, raw, Some(prefix), false);
+                    }
+                }
+            }
+        }
+        found.into_iter().map(|m| self.with_rs_id(m)).collect()
+    }
 
-        if let Some(caps) = self.re_single.captures(raw) {
-            let ref_1 = caps.get(1)?.as_str();
-            let pos: u32 = caps.get(2)?.as_str().parse().ok()?;
-            let alt_1 = caps.get(3)?.as_str();
-
-            let ref_aa = aa1_to_aa3(ref_1)?;
-            let alt_aa = aa1_to_aa3(alt_1)?;
-            let hgvs_p = format!("p.{}{}{}", ref_aa, pos, alt_aa);
-
-            let rs_id = gene.and_then(|g| {
-                self.rsid_table
-                    .get(&(g, hgvs_p.as_str()))
-                    .map(|s| s.to_string())
-            });
-
-            return Some(NormalisedMutation {
-                raw: raw.to_string(),
-                hgvs_p,
-                position: pos,
-                ref_aa: ref_aa.to_string(),
-                alt_aa: alt_aa.to_string(),
-                rs_id,
-            });
-        }
-
-        if let Some(caps) = self.re_hgvs.captures(raw) {
-            let ref_raw = caps.get(1)?.as_str();
-            let pos: u32 = caps.get(2)?.as_str().parse().ok()?;
-            let alt_raw = caps.get(3)?.as_str();
-
-            let ref_aa = if ref_raw.len() == 1 {
-                aa1_to_aa3(ref_raw)?
-            } else {
-                normalise_aa3(ref_raw)?
-            };
-            let alt_aa = if alt_raw.len() == 1 {
-                aa1_to_aa3(alt_raw)?
-            } else {
-                normalise_aa3(alt_raw)?
-            };
+    /// Every mutation mentioned in `text`, with its byte range. The gene
+    /// written just before a change ("KRAS G12D") is kept as its context,
+    /// and a DNA change next to its protein change ("c.35G>A (p.G12D)") is
+    /// merged into one record.
+    pub fn extract(&self, text: &str) -> Vec<(Range<usize>, NormalisedMutation)> {
+        let mut found: Vec<(Range<usize>, NormalisedMutation)> = Vec::new();
+        for caps in self.re_text.captures_iter(text) {
+            let m = caps.get(0).expect("whole match");
+            let mut span = m.range();
+            if m.as_str().ends_with(')') && !m.as_str().contains('(') {
+                span.end -= 1;
+            }
+            let before = text[..span.start].chars().next_back();
+            let after = text[span.end..].chars().next();
+            if before.is_some_and(char::is_alphanumeric) || after.is_some_and(char::is_alphanumeric)
+            {
+                continue;
+            }
+            let gene = self.gene_before(&text[..span.start]);
+            let raw = &text[span.clone()];
+            for mutation in self.from_captures(&caps, raw, gene.as_deref(), true) {
+                found.push((span.clone(), mutation));
+            }
+        }
+        merge_dna_into_protein(text, found)
+            .into_iter()
+            .map(|(span, m)| (span, self.with_rs_id(m)))
+            .collect()
+    }
 
-            let hgvs_p = format!("p.{}{}{}", ref_aa, pos, alt_aa);
-            let rs_id = gene.and_then(|g| {
-                self.rsid_table
-                    .get(&(g, hgvs_p.as_str()))
-                    .map(|s| s.to_string())
-            });
+    /// Uppercase gene-like token directly before a mention.
+    fn gene_before(&self, text: &str) -> Option<String> {
+        let trimmed = text.trim_end_matches([' ', '_', '-']);
+        if trimmed.len() == text.len() {
+            return None;
+        }
+        let start = trimmed
+            .char_indices()
+            .rev()
+            .find(|(_, c)| !c.is_ascii_alphanumeric())
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        let token = &trimmed[start..];
+        let gene_like = (2..=10).contains(&token.len())
+            && token.starts_with(|c: char| c.is_ascii_uppercase())
+            && token
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+        (gene_like && !self.re_mention.is_match(token)).then(|| token.to_string())
+    }
 
-            return Some(NormalisedMutation {
-                raw: raw.to_string(),
-                hgvs_p,
-                position: pos,
-                ref_aa: ref_aa.to_string(),
-                alt_aa: alt_aa.to_string(),
-                rs_id,
-            });
+    /// Records for one match of a mutation pattern.
+    fn from_captures(
+        &self,
+        caps: &Captures<'_>,
+        raw: &str,
+        gene: Option<&str>,
+        strict_fusions: bool,
+    ) -> Vec<NormalisedMutation> {
+        let gene = gene.map(str::to_uppercase);
+        let mut found = if caps.name("iref").is_some() {
+            parse_indel(caps, raw).into_iter().collect()
+        } else if caps.name("sref").is_some() {
+            parse_substitutions(caps, raw)
+        } else if let Some(aref) = caps.name("aref") {
+            substitution(raw, aref.as_str(), &caps["apos"], &caps["aalt"])
+                .into_iter()
+                .collect()
+        } else if caps.name("cpos").is_some() {
+            vec![parse_cdna(caps, raw)]
+        } else if caps.name("exon").is_some() || caps.name("rexon").is_some() {
+            parse_exon(caps, raw).into_iter().collect()
+        } else {
+            parse_fusion(caps, raw, strict_fusions, &self.re_mention)
+                .into_iter()
+                .collect()
+        };
+        for mutation in &mut found {
+            mutation.gene = gene.clone();
         }
+        found
+    }
 
-        None
+    fn with_rs_id(&self, mut mutation: NormalisedMutation) -> NormalisedMutation {
+        if let (Some(gene), Some(hgvs_p)) = (mutation.gene.as_deref(), mutation.hgvs_p.as_deref()) {
+            mutation.rs_id = self.rsid_table.get(&(gene, hgvs_p)).map(|s| s.to_string());
+        }
+        mutation
     }
 
     pub fn all_patterns(&self) -> Vec<String> {
@@ -182,8 +397,373 @@ impl HgvsMutationNormaliser {
     }
 }
 
+fn substitution(raw: &str, ref_raw: &str, pos: &str, alt_raw: &str) -> Option<NormalisedMutation> {
+    let ref_aa = to_aa3(ref_raw)?;
+    let alt_aa = to_aa3(alt_raw)?;
+    let position: u32 = pos.parse().ok()?;
+    let mut mutation = NormalisedMutation::new(raw, MutationKind::Substitution);
+    mutation.hgvs_p = Some(format!("p.{ref_aa}{position}{alt_aa}"));
+    mutation.ref_aa = Some(ref_aa.to_string());
+    mutation.position = Some(position);
+    mutation.alt_aa = Some(alt_aa.to_string());
+    Some(mutation)
+}
+
+/// "G12D", plus one record per extra allele of "G12D/V" or "G12D/G13D".
+fn parse_substitutions(caps: &Captures<'_>, raw: &str) -> Vec<NormalisedMutation> {
+    let (ref_raw, pos) = (&caps["sref"], &caps["spos"]);
+    let mut found: Vec<NormalisedMutation> = substitution(raw, ref_raw, pos, &caps["salt"])
+        .into_iter()
+        .collect();
+    for extra in caps["more"].split('/').filter(|a| !a.is_empty()) {
+        // "G13D" restates residue and position; "V" only the new residue.
+        let (r, p, a) = match extra.find(|c: char| c.is_ascii_digit()) {
+            Some(i) => {
+                let end = extra[i..]
+                    .find(|c: char| !c.is_ascii_digit())
+                    .map_or(extra.len(), |j| i + j);
+                (&extra[..i], &extra[i..end], &extra[end..])
+            }
+            None => (ref_raw, pos, extra),
+        };
+        found.extend(substitution(raw, r, p, a));
+    }
+    found
+}
+
+/// Amino acid sequence after `ins`, `delins` or `fs`, in three-letter codes.
+fn aa_tail(tail: &str) -> String {
+    let mut out = String::new();
+    let mut rest = tail;
+    while let Some(c) = rest.chars().next() {
+        let three = rest
+            .get(..3)
+            .filter(|t| c.is_ascii_uppercase() && t[1..].chars().all(|c| c.is_ascii_lowercase()));
+        if let Some(aa3) = three.and_then(normalise_aa3) {
+            out.push_str(aa3);
+            rest = &rest[3..];
+            continue;
+        }
+        match aa1_to_aa3(&c.to_string()) {
+            Some(aa3) => out.push_str(aa3),
+            None => out.push(c),
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    out
+}
+
+fn parse_indel(caps: &Captures<'_>, raw: &str) -> Option<NormalisedMutation> {
+    let ref_aa = to_aa3(&caps["iref"])?;
+    let position: u32 = caps["ipos"].parse().ok()?;
+    let mut hgvs_p = format!("p.{ref_aa}{position}");
+    if let (Some(ref2), Some(pos2)) = (caps.name("iref2"), caps.name("ipos2")) {
+        hgvs_p.push_str(&format!("_{}{}", to_aa3(ref2.as_str())?, pos2.as_str()));
+    }
+    let (kind, alt_aa) = match caps.name("op").map(|m| m.as_str()) {
+        Some(op) => {
+            hgvs_p.push_str(op);
+            let kind = match op {
+                "del" => MutationKind::Deletion,
+                "ins" => MutationKind::Insertion,
+                "dup" => MutationKind::Duplication,
+                _ => MutationKind::DelIns,
+            };
+            (kind, None)
+        }
+        None => {
+            let alt_aa = caps.name("falt").and_then(|m| to_aa3(m.as_str()));
+            hgvs_p.push_str(alt_aa.unwrap_or(""));
+            hgvs_p.push_str("fs");
+            (MutationKind::Frameshift, alt_aa)
+        }
+    };
+    hgvs_p.push_str(&aa_tail(&caps["tail"]));
+
+    let mut mutation = NormalisedMutation::new(raw, kind);
+    mutation.hgvs_p = Some(hgvs_p);
+    mutation.ref_aa = Some(ref_aa.to_string());
+    mutation.position = Some(position);
+    mutation.alt_aa = alt_aa.map(str::to_string);
+    Some(mutation)
+}
+
+fn parse_cdna(caps: &Captures<'_>, raw: &str) -> NormalisedMutation {
+    let pos = &caps["cpos"];
+    let (kind, change) = match caps.name("cop").map(|m| m.as_str()) {
+        Some(op) => {
+            let kind = match op {
+                "del" => MutationKind::Deletion,
+                "ins" => MutationKind::Insertion,
+                "dup" => MutationKind::Duplication,
+                _ => MutationKind::DelIns,
+            };
+            (kind, format!("{op}{}", &caps["ctail"]))
+        }
+        None => (
+            MutationKind::Substitution,
+            format!("{}>{}", &caps["cref"], &caps["calt"]),
+        ),
+    };
+    let mut mutation = NormalisedMutation::new(raw, kind);
+    mutation.hgvs_c = Some(format!("c.{pos}{change}"));
+    mutation
+}
+
+fn parse_exon(caps: &Captures<'_>, raw: &str) -> Option<NormalisedMutation> {
+    let (exon, event) = match caps.name("exon") {
+        Some(exon) => (exon.as_str(), &caps["eop"]),
+        None => (&caps["rexon"], &caps["rop"]),
+    };
+    let kind = match event.to_ascii_lowercase().get(..3)? {
+        "ins" => MutationKind::Insertion,
+        "dup" => MutationKind::Duplication,
+        "ski" => MutationKind::ExonSkipping,
+        _ => MutationKind::Deletion,
+    };
+    let mut mutation = NormalisedMutation::new(raw, kind);
+    mutation.exon = Some(exon.parse().ok()?);
+    Some(mutation)
+}
+
+/// "EML4-ALK" or "EML4::ALK". Without "fusion" after it, a hyphenated pair
+/// needs both names to be 3+ characters ("PD-L1" is not a fusion), and
+/// neither may itself be a mutation ("KRAS-G12D").
+fn parse_fusion(
+    caps: &Captures<'_>,
+    raw: &str,
+    strict: bool,
+    re_mention: &Regex,
+) -> Option<NormalisedMutation> {
+    let five = caps.name("g5")?.as_str();
+    let three = caps.name("g3c").or_else(|| caps.name("g3"))?.as_str();
+    if re_mention.is_match(five) || re_mention.is_match(three) {
+        return None;
+    }
+    let hyphenated = caps.name("g3").is_some() && caps.name("fword").is_none();
+    if hyphenated && (strict || five.len() < 3 || three.len() < 3) {
+        return None;
+    }
+    let mut mutation = NormalisedMutation::new(raw, MutationKind::Fusion);
+    mutation.fusion_partners = Some((five.to_string(), three.to_string()));
+    Some(mutation)
+}
+
+/// Folds a DNA-only record into the protein change written right next to
+/// it, in either order.
+fn merge_dna_into_protein(
+    text: &str,
+    found: Vec<(Range<usize>, NormalisedMutation)>,
+) -> Vec<(Range<usize>, NormalisedMutation)> {
+    let dna_only = |m: &NormalisedMutation| m.hgvs_c.is_some() && m.hgvs_p.is_none();
+    let adjacent = |a: &Range<usize>, b: &Range<usize>| {
+        a.end <= b.start
+            && text[a.end..b.start]
+                .trim_matches(|c: char| c.is_whitespace() || "([;,:".contains(c))
+                .is_empty()
+            && b.start - a.end <= 4
+    };
+    let mut out: Vec<(Range<usize>, NormalisedMutation)> = Vec::with_capacity(found.len());
+    for (span, mutation) in found {
+        if let Some((prev_span, prev)) = out.last_mut() {
+            if dna_only(prev) && mutation.hgvs_p.is_some() && adjacent(prev_span, &span) {
+                let hgvs_c = prev.hgvs_c.take();
+                let gene = mutation.gene.clone().or_else(|| prev.gene.take());
+                *prev = NormalisedMutation {
+                    hgvs_c,
+                    gene,
+                    ..mutation
+                };
+                prev_span.end = span.end;
+                continue;
+            }
+            if dna_only(&mutation)
+                && prev.hgvs_p.is_some()
+                && prev.hgvs_c.is_none()
+                && adjacent(prev_span, &span)
+            {
+                prev.hgvs_c = mutation.hgvs_c;
+                prev_span.end = span.end;
+                continue;
+            }
+        }
+        out.push((span, mutation));
+    }
+    out
+}
+
 impl Default for HgvsMutationNormaliser {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use MutationKind::*;
+
+    /// Mentions as written in abstracts → (kind, gene context, label).
+    const MENTIONS: &[(&str, MutationKind, Option<&str>, &str)] = &[
+        ("KRAS G12D", Substitution, Some("KRAS"), "G12D"),
+        ("KRAS_G12D", Substitution, Some("KRAS"), "G12D"),
+        ("KRAS-G12D", Substitution, Some("KRAS"), "G12D"),
+        ("KRAS p.G12C", Substitution, Some("KRAS"), "G12C"),
+        ("p.Gly12Asp", Substitution, None, "G12D"),
+        ("p.G12D", Substitution, None, "G12D"),
+        ("Gly12→Asp", Substitution, None, "G12D"),
+        ("Gly 12 → Asp", Substitution, None, "G12D"),
+        ("Gly12->Val", Substitution, None, "G12V"),
+        ("BRAF V600E", Substitution, Some("BRAF"), "V600E"),
+        ("p.(Val600Glu)", Substitution, None, "V600E"),
+        ("EGFR L858R", Substitution, Some("EGFR"), "L858R"),
+        ("T790M", Substitution, None, "T790M"),
+        ("EGFR C797S", Substitution, Some("EGFR"), "C797S"),
+        ("PIK3CA H1047R", Substitution, Some("PIK3CA"), "H1047R"),
+        ("PIK3CA E545K", Substitution, Some("PIK3CA"), "E545K"),
+        ("IDH1 R132H", Substitution, Some("IDH1"), "R132H"),
+        ("TP53 R175H", Substitution, Some("TP53"), "R175H"),
+        ("p.Arg273His", Substitution, None, "R273H"),
+        ("TP53 R213X", Substitution, Some("TP53"), "R213*"),
+        ("p.Arg213*", Substitution, None, "R213*"),
+        ("NRAS Q61K", Substitution, Some("NRAS"), "Q61K"),
+        ("ALK F1174L", Substitution, Some("ALK"), "F1174L"),
+        ("H3F3A K27M", Substitution, Some("H3F3A"), "K27M"),
+        ("JAK2 V617F", Substitution, Some("JAK2"), "V617F"),
+        ("FLT3 D835Y", Substitution, Some("FLT3"), "D835Y"),
+        ("c.35G>A", Substitution, None, "c.35G>A"),
+        ("KRAS c.35G>T", Substitution, Some("KRAS"), "c.35G>T"),
+        ("TERT c.-124C>T", Substitution, Some("TERT"), "c.-124C>T"),
+        ("c.2235_2249del", Deletion, None, "c.2235_2249del"),
+        ("BRCA1 c.5266dupC", Duplication, Some("BRCA1"), "c.5266dupC"),
+        ("BRCA2 c.6174delT", Deletion, Some("BRCA2"), "c.6174delT"),
+        (
+            "EGFR E746_A750del",
+            Deletion,
+            Some("EGFR"),
+            "p.Glu746_Ala750del",
+        ),
+        ("L747_P753delinsS", DelIns, None, "p.Leu747_Pro753delinsSer"),
+        (
+            "EGFR D770_N771insSVD",
+            Insertion,
+            Some("EGFR"),
+            "p.Asp770_Asn771insSerValAsp",
+        ),
+        (
+            "ERBB2 A775_G776insYVMA",
+            Insertion,
+            Some("ERBB2"),
+            "p.Ala775_Gly776insTyrValMetAla",
+        ),
+        ("p.Arg248Glnfs*12", Frameshift, None, "p.Arg248GlnfsTer12"),
+        ("exon 19 deletion", Deletion, None, "exon 19 deletion"),
+        ("ex19del", Deletion, None, "exon 19 deletion"),
+        ("deletions in exon 19", Deletion, None, "exon 19 deletion"),
+        (
+            "EGFR exon 20 insertion",
+            Insertion,
+            Some("EGFR"),
+            "exon 20 insertion",
+        ),
+        (
+            "MET exon 14 skipping",
+            ExonSkipping,
+            Some("MET"),
+            "exon 14 skipping",
+        ),
+        ("EML4-ALK", Fusion, None, "EML4::ALK"),
+        ("EML4::ALK", Fusion, None, "EML4::ALK"),
+        ("KIF5B-RET fusion", Fusion, None, "KIF5B::RET"),
+        ("BCR-ABL1", Fusion, None, "BCR::ABL1"),
+        ("TMPRSS2-ERG", Fusion, None, "TMPRSS2::ERG"),
+        ("CD74-ROS1 rearrangement", Fusion, None, "CD74::ROS1"),
+    ];
+
+    #[test]
+    fn real_world_mentions_round_trip() {
+        let normaliser = HgvsMutationNormaliser::new();
+        for &(raw, kind, gene, label) in MENTIONS {
+            let m = normaliser
+                .normalise(raw, None)
+                .unwrap_or_else(|| panic!("{raw:?} did not parse"));
+            assert_eq!(m.kind, kind, "{raw:?}");
+            assert_eq!(m.gene.as_deref(), gene, "{raw:?}");
+            assert_eq!(m.label(), label, "{raw:?}");
+
+            // The normalised form parses back to the same mutation.
+            let canonical = m.hgvs_p.clone().unwrap_or_else(|| m.label());
+            let again = normaliser
+                .normalise(&canonical, None)
+                .unwrap_or_else(|| panic!("{canonical:?} (from {raw:?}) did not parse"));
+            assert_eq!(again.kind, kind, "{canonical:?}");
+            assert_eq!(again.label(), label, "{canonical:?}");
+            assert_eq!(again.hgvs_p, m.hgvs_p, "{canonical:?}");
+        }
+
+        let m = normaliser.normalise("KRAS G12D", None).unwrap();
+        assert_eq!(m.hgvs_p.as_deref(), Some("p.Gly12Asp"));
+        assert_eq!(
+            (m.ref_aa.as_deref(), m.position, m.alt_aa.as_deref()),
+            (Some("Gly"), Some(12), Some("Asp"))
+        );
+        assert_eq!(m.rs_id.as_deref(), Some("rs121913529"));
+        let m = normaliser.normalise("p.Val600Glu", Some("BRAF")).unwrap();
+        assert_eq!(m.gene.as_deref(), Some("BRAF"));
+        assert_eq!(m.rs_id.as_deref(), Some("rs113488022"));
+
+        for raw in ["PD-L1", "HER2/neu", "CD8", "hello", ""] {
+            assert!(normaliser.normalise(raw, None).is_none(), "{raw:?}");
+        }
+    }
+
+    #[test]
+    fn multi_allele_shorthand_expands() {
+        let normaliser = HgvsMutationNormaliser::new();
+        let labels = |raw: &str| -> Vec<String> {
+            normaliser
+                .normalise_all(raw, None)
+                .iter()
+                .map(NormalisedMutation::label)
+                .collect()
+        };
+        assert_eq!(labels("G12D/V"), ["G12D", "G12V"]);
+        assert_eq!(labels("Gly12Asp/Val"), ["G12D", "G12V"]);
+        assert_eq!(labels("G12D/G13D"), ["G12D", "G13D"]);
+        assert_eq!(labels("BRAF V600E/K"), ["V600E", "V600K"]);
+
+        let alleles = normaliser.normalise_all("KRAS G12C/D/V", None);
+        assert_eq!(alleles.len(), 3);
+        assert!(alleles.iter().all(|m| m.gene.as_deref() == Some("KRAS")));
+        assert_eq!(alleles[2].hgvs_p.as_deref(), Some("p.Gly12Val"));
+    }
+
+    #[test]
+    fn extracts_mentions_from_running_text() {
+        let normaliser = HgvsMutationNormaliser::new();
+        let text = "Activating KRAS G12D and BRAF V600E mutations; the c.35G>A (p.Gly12Asp) \
+                    change, EGFR exon 19 deletions and EML4-ALK fusions, but not PD-L1.";
+        let extracted = normaliser.extract(text);
+        let found: Vec<(&str, Option<&str>, String)> = extracted
+            .iter()
+            .map(|(span, m)| (&text[span.clone()], m.gene.as_deref(), m.label()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("G12D", Some("KRAS"), "G12D".to_string()),
+                ("V600E", Some("BRAF"), "V600E".to_string()),
+                ("c.35G>A (p.Gly12Asp", None, "G12D".to_string()),
+                (
+                    "exon 19 deletions",
+                    Some("EGFR"),
+                    "exon 19 deletion".to_string()
+                ),
+                ("EML4-ALK fusions", None, "EML4::ALK".to_string()),
+            ]
+        );
+        let merged = &extracted[2].1;
+        assert_eq!(merged.hgvs_c.as_deref(), Some("c.35G>A"));
+        assert_eq!(merged.hgvs_p.as_deref(), Some("p.Gly12Asp"));
+    }
+}
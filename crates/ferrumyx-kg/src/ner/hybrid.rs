@@ -0,0 +1,254 @@
+//! Reconcile dictionary matches with spans from a statistical tagger.
+//!
+//! [`TrieNer`] only finds names it has a pattern for but can normalise
+//! them; a model finds unfamiliar phrasing but returns bare spans.
+//! [`HybridNer::merge`] combines the two: overlapping spans are collapsed
+//! to one entity, the dictionary supplies canonical ids wherever it can
+//! resolve the text, and each entity records where it came from.
+
+use super::tagger::SpanTagger;
+use super::trie_ner::{ExtractedEntity, TrieNer};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Which source found an entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Provenance {
+    Trie,
+    Model,
+    Both,
+}
+
+/// Whose span and label win when the two sources overlap.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Precedence {
+    #[default]
+    Trie,
+    Model,
+}
+
+/// Which extractor ingestion runs over chunk text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NerMode {
+    /// Dictionary matches only.
+    #[default]
+    Trie,
+    /// Dictionary matches merged with a statistical tagger's spans.
+    Hybrid,
+}
+
+impl NerMode {
+    pub const ENV: &'static str = "FERRUMYX_NER_MODE";
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "trie" => Some(Self::Trie),
+            "hybrid" => Some(Self::Hybrid),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Trie => "trie",
+            Self::Hybrid => "hybrid",
+        }
+    }
+
+    /// Mode from [`Self::ENV`]; unset or unknown values mean [`Self::Trie`].
+    pub fn from_env() -> Self {
+        std::env::var(Self::ENV)
+            .ok()
+            .and_then(|v| Self::parse(&v))
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HybridEntity {
+    pub entity: ExtractedEntity,
+    /// HGNC id for genes, OncoTree code for cancer types.
+    pub normalized_id: Option<String>,
+    pub provenance: Provenance,
+}
+
+pub struct HybridNer {
+    trie: Arc<TrieNer>,
+    precedence: Precedence,
+}
+
+impl HybridNer {
+    pub fn new(trie: Arc<TrieNer>) -> Self {
+        Self {
+            trie,
+            precedence: Precedence::default(),
+        }
+    }
+
+    pub fn with_precedence(mut self, precedence: Precedence) -> Self {
+        self.precedence = precedence;
+        self
+    }
+
+    /// Dictionary matches in `text` merged with `model` spans over the same
+    /// text, ordered by start offset.
+    pub fn merge(&self, text: &str, model: Vec<ExtractedEntity>) -> Vec<HybridEntity> {
+        let mut model: Vec<Option<ExtractedEntity>> = model.into_iter().map(Some).collect();
+        let mut merged = Vec::new();
+
+        for trie_hit in self.trie.extract(text) {
+            let overlapping: Vec<ExtractedEntity> = model
+                .iter_mut()
+                .filter(|m| m.as_ref().is_some_and(|m| overlaps(m, &trie_hit)))
+                .filter_map(Option::take)
+                .collect();
+            if overlapping.is_empty() {
+                merged.push(self.resolve(trie_hit, Provenance::Trie));
+                continue;
+            }
+
+            let confidence = overlapping
+                .iter()
+                .map(|m| m.confidence)
+                .fold(trie_hit.confidence, f32::max);
+            let mut winner = match self.precedence {
+                Precedence::Trie => trie_hit.clone(),
+                Precedence::Model => overlapping
+                    .into_iter()
+                    .max_by_key(|m| m.end - m.start)
+                    .unwrap_or_else(|| trie_hit.clone()),
+            };
+            winner.confidence = confidence;
+            // The dictionary id still applies if the model agrees on the type.
+            if winner.label == trie_hit.label {
+                winner.canonical_id = trie_hit.canonical_id;
+                winner.canonical_name = trie_hit.canonical_name;
+            }
+            merged.push(self.resolve(winner, Provenance::Both));
+        }
+
+        for model_hit in model.into_iter().flatten() {
+            merged.push(self.resolve(model_hit, Provenance::Model));
+        }
+        merged.sort_by_key(|e| (e.entity.start, e.entity.end));
+        merged
+    }
+
+    /// [`Self::merge`] with the spans `tagger` finds in `text`.
+    pub fn extract(
+        &self,
+        text: &str,
+        tagger: &dyn SpanTagger,
+    ) -> anyhow::Result<Vec<HybridEntity>> {
+        Ok(self.merge(text, tagger.tag_windowed(text)?))
+    }
+
+    /// `model` spans alone, with dictionary ids filled in where the
+    /// dictionary resolves their text.
+    pub fn normalise(&self, model: Vec<ExtractedEntity>) -> Vec<HybridEntity> {
+        model
+            .into_iter()
+            .map(|span| self.resolve(span, Provenance::Model))
+            .collect()
+    }
+
+    /// Fill in the dictionary id of a span that has none yet.
+    fn resolve(&self, mut entity: ExtractedEntity, provenance: Provenance) -> HybridEntity {
+        if entity.canonical_id.is_none() {
+            (entity.canonical_id, entity.canonical_name) =
+                self.trie.canonical(entity.label, &entity.text).unzip();
+        }
+        HybridEntity {
+            normalized_id: entity.canonical_id.clone(),
+            entity,
+            provenance,
+        }
+    }
+}
+
+fn overlaps(a: &ExtractedEntity, b: &ExtractedEntity) -> bool {
+    a.start < b.end && b.start < a.end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ner::entity_types::EntityType;
+    use crate::ner::trie_ner::tests::test_ner;
+
+    fn span(text: &str, needle: &str, label: EntityType) -> ExtractedEntity {
+        let start = text.find(needle).unwrap();
+        ExtractedEntity {
+            text: needle.to_string(),
+            label,
+            start,
+            end: start + needle.len(),
+            confidence: 0.9,
+            canonical_id: None,
+            canonical_name: None,
+        }
+    }
+
+    #[test]
+    fn dictionary_normalises_model_spans_and_keeps_novel_phrases() {
+        let hybrid = HybridNer::new(Arc::new(test_ner()));
+        let text = "Loss of p53 and KRAS activation drive treatment-refractory ductal carcinoma";
+        let phrase = "treatment-refractory ductal carcinoma";
+        let model = vec![
+            span(text, "p53", EntityType::Gene),
+            span(text, phrase, EntityType::Disease),
+        ];
+        let merged = hybrid.merge(text, model);
+
+        let found: Vec<(&str, Option<&str>, Provenance)> = merged
+            .iter()
+            .map(|e| {
+                (
+                    e.entity.text.as_str(),
+                    e.normalized_id.as_deref(),
+                    e.provenance,
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("p53", Some("HGNC:11998"), Provenance::Model),
+                ("KRAS", Some("HGNC:6407"), Provenance::Trie),
+                (phrase, None, Provenance::Model),
+            ]
+        );
+    }
+
+    #[test]
+    fn precedence_decides_overlapping_spans() {
+        let text = "SMAD4 protein loss";
+        let model = || vec![span(text, "SMAD4 protein", EntityType::Gene)];
+
+        let trie_first = HybridNer::new(Arc::new(test_ner())).merge(text, model());
+        assert_eq!(trie_first.len(), 1);
+        assert_eq!(trie_first[0].entity.text, "SMAD4");
+        assert_eq!(trie_first[0].provenance, Provenance::Both);
+        assert_eq!(trie_first[0].normalized_id.as_deref(), Some("HGNC:6770"));
+
+        let model_first = HybridNer::new(Arc::new(test_ner()))
+            .with_precedence(Precedence::Model)
+            .merge(text, model());
+        assert_eq!(model_first[0].entity.text, "SMAD4 protein");
+        assert_eq!(model_first[0].normalized_id.as_deref(), Some("HGNC:6770"));
+    }
+
+    #[test]
+    fn ner_mode_parses_config_values() {
+        assert_eq!(NerMode::parse("hybrid"), Some(NerMode::Hybrid));
+        assert_eq!(NerMode::parse(" Trie "), Some(NerMode::Trie));
+        assert_eq!(NerMode::parse("scispacy"), None);
+        assert_eq!(
+            NerMode::parse(NerMode::Hybrid.as_str()),
+            Some(NerMode::Hybrid)
+        );
+    }
+}
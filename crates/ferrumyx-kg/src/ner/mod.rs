@@ -1,12 +1,19 @@
+pub mod abbreviations;
 pub mod cancer_normaliser;
+pub mod dictionaries;
 pub mod entity_aggregator;
 pub mod entity_db;
 pub mod entity_loader;
 pub mod entity_types;
+#[cfg(feature = "eval")]
+pub mod eval;
 pub mod hgnc;
 pub mod hgvs;
+pub mod hybrid;
+pub mod tagger;
 pub mod trie_ner;
 
+pub use abbreviations::{detect_abbreviations, Abbreviation, AbbreviationEntity, AbbreviationMap};
 pub use cancer_normaliser::CancerNormaliser;
 pub use entity_aggregator::{
     AggregationResult, BatchAggregationResult, EntityAggregator, KgTriple,
@@ -16,6 +23,8 @@ pub use entity_loader::{
     BiomedicalDatabase, ChemicalEntry, DiseaseCategory, DiseaseEntry, GeneEntry,
 };
 pub use entity_types::EntityType;
-pub use hgnc::HgncNormaliser;
-pub use hgvs::HgvsMutationNormaliser;
-pub use trie_ner::{ExtractedEntity, TrieNer};
+pub use hgnc::{HgncNormaliser, MatchQuality, SymbolMatch, SymbolResolution};
+pub use hgvs::{HgvsMutationNormaliser, MutationKind, NormalisedMutation};
+pub use hybrid::{HybridEntity, HybridNer, NerMode, Precedence, Provenance};
+pub use tagger::SpanTagger;
+pub use trie_ner::{ExtractedEntity, NerFilter, NerWindow, TrieNer};
@@ -0,0 +1,207 @@
+//! Statistical taggers and the sliding window that feeds them long texts.
+//!
+//! A model only takes a bounded input, while abstracts and full-text
+//! chunks can be far longer. [`SpanTagger::tag_windowed`] cuts such texts
+//! into overlapping windows, tags each one and stitches the spans back
+//! together at offsets into the original text.
+
+use super::trie_ner::ExtractedEntity;
+use std::ops::Range;
+
+/// A model that returns bare entity spans, such as a transformer NER model.
+pub trait SpanTagger: Send + Sync {
+    /// Longest input, in bytes, the model takes in one pass.
+    fn max_len(&self) -> usize;
+
+    /// Spans in `text`, which is at most [`Self::max_len`] bytes long.
+    /// Offsets are byte positions into `text`.
+    fn tag(&self, text: &str) -> anyhow::Result<Vec<ExtractedEntity>>;
+
+    /// [`Self::tag`] over a text of any length, through windows of
+    /// [`Self::max_len`] bytes that overlap by a quarter window. Spans seen
+    /// in two windows are reported once.
+    fn tag_windowed(&self, text: &str) -> anyhow::Result<Vec<ExtractedEntity>> {
+        let max_len = self.max_len();
+        let mut spans = Vec::new();
+        for window in windows(text, max_len, max_len / 4) {
+            for mut span in self.tag(&text[window.clone()])? {
+                span.start += window.start;
+                span.end += window.start;
+                spans.push(span);
+            }
+        }
+        Ok(merge_window_spans(text, spans))
+    }
+}
+
+/// Byte ranges of windows of at most `max_len` bytes covering `text`, each
+/// starting `overlap` bytes (at most half a window) before the previous one
+/// ended, moved to the start of a word. Windows end on whitespace where the
+/// text allows, and always on char boundaries.
+pub fn windows(text: &str, max_len: usize, overlap: usize) -> Vec<Range<usize>> {
+    let max_len = max_len.max(1);
+    let overlap = overlap.min(max_len / 2);
+    let mut windows = Vec::new();
+    let mut start = 0;
+    while text.len() - start > max_len {
+        let limit = floor_boundary(text, start + max_len);
+        let end = text[start..limit]
+            .rfind(char::is_whitespace)
+            .map(|i| start + i)
+            .filter(|&end| end > start + max_len / 2)
+            .unwrap_or(limit);
+        let end = if end > start {
+            end
+        } else {
+            ceil_boundary(text, start + 1)
+        };
+        windows.push(start..end);
+
+        // Back up by the overlap, then to the start of the word there, or
+        // of the next word if that one began with this window.
+        let back = ceil_boundary(text, end.saturating_sub(overlap));
+        let word_start = |from: usize, (i, c): (usize, char)| from + i + c.len_utf8();
+        let next = text[start..back]
+            .char_indices()
+            .rfind(|(_, c)| c.is_whitespace())
+            .map(|hit| word_start(start, hit))
+            .or_else(|| {
+                text[back..end]
+                    .char_indices()
+                    .find(|(_, c)| c.is_whitespace())
+                    .map(|hit| word_start(back, hit))
+            })
+            .unwrap_or(back);
+        start = if next > start && next < end {
+            next
+        } else {
+            end
+        };
+    }
+    windows.push(start..text.len());
+    windows
+}
+
+/// Collapses spans of one label that overlap, as the same entity seen from
+/// two windows, into the union of both.
+fn merge_window_spans(text: &str, mut spans: Vec<ExtractedEntity>) -> Vec<ExtractedEntity> {
+    spans.sort_by_key(|s| (s.start, std::cmp::Reverse(s.end)));
+    let mut merged: Vec<ExtractedEntity> = Vec::with_capacity(spans.len());
+    for span in spans {
+        if let Some(last) = merged.last_mut() {
+            if last.label == span.label && span.start < last.end {
+                if span.end > last.end {
+                    if let Some(joined) = text.get(last.start..span.end) {
+                        last.end = span.end;
+                        last.text = joined.to_string();
+                    }
+                }
+                last.confidence = last.confidence.max(span.confidence);
+                continue;
+            }
+        }
+        merged.push(span);
+    }
+    merged
+}
+
+fn floor_boundary(text: &str, i: usize) -> usize {
+    let mut i = i.min(text.len());
+    while !text.is_char_boundary(i) {
+        i -= 1;
+    }
+    i
+}
+
+fn ceil_boundary(text: &str, i: usize) -> usize {
+    let mut i = i.min(text.len());
+    while !text.is_char_boundary(i) {
+        i += 1;
+    }
+    i
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ner::entity_types::EntityType;
+    use std::sync::Mutex;
+
+    /// Tags every "KRAS" and "pancreatic cancer", recording its inputs.
+    struct Needles {
+        max_len: usize,
+        inputs: Mutex<Vec<String>>,
+    }
+
+    impl SpanTagger for Needles {
+        fn max_len(&self) -> usize {
+            self.max_len
+        }
+
+        fn tag(&self, text: &str) -> anyhow::Result<Vec<ExtractedEntity>> {
+            assert!(text.len() <= self.max_len, "window of {} bytes", text.len());
+            self.inputs.lock().unwrap().push(text.to_string());
+            let hits = [
+                ("KRAS", EntityType::Gene),
+                ("pancreatic cancer", EntityType::CancerType),
+            ];
+            Ok(hits
+                .iter()
+                .flat_map(|(needle, label)| {
+                    text.match_indices(needle)
+                        .map(move |(start, _)| ExtractedEntity {
+                            text: needle.to_string(),
+                            label: *label,
+                            start,
+                            end: start + needle.len(),
+                            confidence: 0.8,
+                            canonical_id: None,
+                            canonical_name: None,
+                        })
+                })
+                .collect())
+        }
+    }
+
+    #[test]
+    fn windows_cover_text_on_char_boundaries() {
+        let text = "TGF-β signalling — KRAS-driven PAAD 🧬 with SMAD4 loss and more";
+        for max_len in [1, 5, 12, 30] {
+            let windows = windows(text, max_len, max_len / 4);
+            assert_eq!(windows.first().unwrap().start, 0);
+            assert_eq!(windows.last().unwrap().end, text.len());
+            for pair in windows.windows(2) {
+                assert!(pair[1].start > pair[0].start && pair[1].start <= pair[0].end);
+            }
+            for w in &windows {
+                assert!(text.get(w.clone()).is_some(), "{w:?} splits a char");
+            }
+        }
+        assert_eq!(windows("short", 64, 16), vec![0..5]);
+    }
+
+    #[test]
+    fn long_texts_are_tagged_window_by_window_without_duplicates() {
+        let tagger = Needles {
+            max_len: 32,
+            inputs: Mutex::default(),
+        };
+        let text = "KRAS G12D is common in pancreatic cancer, and KRAS inhibitors are \
+                    now in trials for pancreatic cancer patients.";
+        let spans = tagger.tag_windowed(text).unwrap();
+
+        assert!(tagger.inputs.lock().unwrap().len() > 1);
+        let found: Vec<(&str, usize)> = spans.iter().map(|s| (s.text.as_str(), s.start)).collect();
+        let expected: Vec<(&str, usize)> = [
+            ("KRAS", 0),
+            ("pancreatic cancer", text.find("pancreatic").unwrap()),
+            ("KRAS", text.rfind("KRAS").unwrap()),
+            ("pancreatic cancer", text.rfind("pancreatic").unwrap()),
+        ]
+        .into();
+        assert_eq!(found, expected);
+        for span in &spans {
+            assert_eq!(&text[span.start..span.end], span.text);
+        }
+    }
+}
@@ -5,10 +5,13 @@ use super::entity_types::EntityType;
 use super::hgnc::{HgncNormaliser, SymbolTier};
 use super::hgvs::HgvsMutationNormaliser;
 use aho_corasick::{AhoCorasick, MatchKind};
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
-use tracing::info;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
 
-#[derive(Clone, Debug, Copy)]
+#[derive(Clone, Debug, Copy, PartialEq, Serialize, Deserialize)]
 enum ConfidenceClass {
     Gene(SymbolTier),
     Cancer(CancerPatternKind),
@@ -18,11 +21,178 @@ enum ConfidenceClass {
     CellLine,
 }
 
-#[derive(Clone, Debug, Copy)]
+#[derive(Clone, Debug, Copy, PartialEq, Serialize, Deserialize)]
 struct PatternMeta {
     pub entity_type: EntityType,
     pub class: ConfidenceClass,
     pub requires_word_boundary: bool,
+    /// Short symbols only count when written in capitals, so "met" and
+    /// "all" in running text are not genes or cancer codes.
+    pub case_sensitive: bool,
+}
+
+/// Symbols that are also ordinary words; accepted only next to a cue word.
+/// Replaced by `FERRUMYX_NER_AMBIGUOUS_SYMBOLS` (comma-separated).
+const DEFAULT_AMBIGUOUS_SYMBOLS: &[&str] = &[
+    "MET", "KIT", "ALL", "CA2", "SET", "MAX", "REST", "CAT", "CAR", "WAS", "CAN",
+];
+
+/// Stems of words that mark a nearby symbol as a gene mention.
+const CUE_WORD_STEMS: &[&str] = &[
+    "mutat",
+    "express",
+    "amplif",
+    "fusion",
+    "delet",
+    "rearrang",
+    "knockdown",
+    "knockout",
+    "inhibit",
+    "protein",
+    "receptor",
+    "kinase",
+    "signal",
+    "variant",
+];
+
+/// Words either side of a match searched for a cue.
+const CUE_WORD_WINDOW: usize = 3;
+
+/// Token windows [`TrieNer::extract`] matches long texts through, counted
+/// in whitespace-separated words.
+///
+/// Consecutive windows share `overlap` tokens, so a name cut off at the end
+/// of one window is whole in the next; a `stride` of 0 matches every text
+/// in one pass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NerWindow {
+    /// Tokens from the start of one window to the start of the next.
+    pub stride: usize,
+    /// Tokens at the end of each window repeated at the start of the next.
+    pub overlap: usize,
+}
+
+impl Default for NerWindow {
+    /// 512-token windows, a quarter of which overlap.
+    fn default() -> Self {
+        Self {
+            stride: 384,
+            overlap: 128,
+        }
+    }
+}
+
+impl NerWindow {
+    pub const STRIDE_ENV: &'static str = "FERRUMYX_NER_WINDOW_STRIDE";
+    pub const OVERLAP_ENV: &'static str = "FERRUMYX_NER_WINDOW_OVERLAP";
+
+    /// Window from [`Self::STRIDE_ENV`] and [`Self::OVERLAP_ENV`], each
+    /// falling back to the default when unset or not a number.
+    pub fn from_env() -> Self {
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse::<usize>().ok())
+        };
+        let default = Self::default();
+        Self {
+            stride: var(Self::STRIDE_ENV).unwrap_or(default.stride),
+            overlap: var(Self::OVERLAP_ENV).unwrap_or(default.overlap),
+        }
+    }
+
+    /// Byte ranges of the windows over `text`. The first starts at 0 and
+    /// the last ends at `text.len()`; a text of at most one window's worth
+    /// of tokens is a single range.
+    pub fn ranges(&self, text: &str) -> Vec<Range<usize>> {
+        let len = self.stride.saturating_add(self.overlap);
+        let tokens = token_spans(text);
+        if self.stride == 0 || tokens.len() <= len {
+            return std::iter::once(0..text.len()).collect();
+        }
+        let mut ranges = Vec::new();
+        let mut first = 0;
+        loop {
+            let last = (first + len).min(tokens.len()) - 1;
+            let start = if first == 0 { 0 } else { tokens[first].start };
+            if last + 1 == tokens.len() {
+                ranges.push(start..text.len());
+                return ranges;
+            }
+            ranges.push(start..tokens[last].end);
+            first += self.stride;
+        }
+    }
+}
+
+/// [`TrieNer::BATCH_SIZE_ENV`], or the default when unset or 0.
+fn env_batch_size() -> usize {
+    std::env::var(TrieNer::BATCH_SIZE_ENV)
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .filter(|&n| n > 0)
+        .unwrap_or(TrieNer::DEFAULT_BATCH_SIZE)
+}
+
+/// Byte spans of the whitespace-separated tokens of `text`.
+fn token_spans(text: &str) -> Vec<Range<usize>> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (c.is_whitespace(), start) {
+            (true, Some(s)) => {
+                spans.push(s..i);
+                start = None;
+            }
+            (false, None) => start = Some(i),
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        spans.push(s..text.len());
+    }
+    spans
+}
+
+/// Which extracted entities are kept downstream: a minimum confidence and,
+/// optionally, an allow-list of types.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NerFilter {
+    pub min_score: f32,
+    pub allowed_types: Option<HashSet<EntityType>>,
+}
+
+impl NerFilter {
+    /// Filter from `FERRUMYX_NER_MIN_SCORE` and `FERRUMYX_NER_ALLOWED_TYPES`
+    /// (comma-separated labels, e.g. `GENE,DISEASE`).
+    pub fn from_env() -> Self {
+        Self {
+            min_score: std::env::var("FERRUMYX_NER_MIN_SCORE")
+                .ok()
+                .and_then(|v| v.trim().parse::<f32>().ok())
+                .unwrap_or(0.0)
+                .clamp(0.0, 1.0),
+            allowed_types: std::env::var("FERRUMYX_NER_ALLOWED_TYPES")
+                .ok()
+                .and_then(|v| Self::parse_types(&v)),
+        }
+    }
+
+    /// Comma-separated [`EntityType::as_str`] labels. Unknown labels are
+    /// ignored; None when no label is recognised.
+    pub fn parse_types(list: &str) -> Option<HashSet<EntityType>> {
+        let types: HashSet<EntityType> =
+            list.split(',').filter_map(EntityType::from_label).collect();
+        (!types.is_empty()).then_some(types)
+    }
+
+    pub fn allows(&self, entity: &ExtractedEntity) -> bool {
+        entity.confidence >= self.min_score
+            && self
+                .allowed_types
+                .as_ref()
+                .is_none_or(|types| types.contains(&entity.label))
+    }
 }
 
 pub struct TrieNer {
@@ -30,6 +200,10 @@ pub struct TrieNer {
     pattern_info: Vec<PatternMeta>,
     hgnc: HgncNormaliser,
     cancers: CancerNormaliser,
+    /// Upper-case symbols from [`DEFAULT_AMBIGUOUS_SYMBOLS`] or the env.
+    ambiguous_symbols: HashSet<String>,
+    window: NerWindow,
+    batch_size: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -39,156 +213,162 @@ pub struct ExtractedEntity {
     pub start: usize,
     pub end: usize,
     pub confidence: f32,
+    /// HGNC id for genes, OncoTree code for cancer types.
+    pub canonical_id: Option<String>,
+    /// Approved symbol for genes, OncoTree name for cancer types.
+    pub canonical_name: Option<String>,
 }
 
 impl TrieNer {
+    /// Texts each rayon task of [`Self::extract_batch`] matches in turn.
+    pub const DEFAULT_BATCH_SIZE: usize = 8;
+    /// Replaces [`Self::DEFAULT_BATCH_SIZE`].
+    pub const BATCH_SIZE_ENV: &'static str = "FERRUMYX_NER_BATCH_SIZE";
+
+    /// Build from HGNC and OncoTree, downloading them on first use. The
+    /// collected patterns are cached under `FERRUMYX_CACHE_DIR` and reused
+    /// until a source file changes; building the automaton runs on the
+    /// blocking pool.
     pub async fn with_complete_databases_async() -> anyhow::Result<Self> {
         info!("Building TrieNer with complete databases (HGNC + OncoTree)...");
         let hgnc = HgncNormaliser::from_download().await?;
         let cancers = CancerNormaliser::from_download().await?;
-        Self::from_normalisers(hgnc, cancers)
+        tokio::task::spawn_blocking(move || {
+            Self::from_normalisers_cached(hgnc, cancers, &pattern_cache_path(), source_checksum())
+        })
+        .await?
     }
 
+    /// Blocking [`Self::with_complete_databases_async`]. Inside a tokio
+    /// runtime the work moves to its own thread, since the downloads start
+    /// a runtime of their own; async callers should still prefer the async
+    /// constructor so the calling worker is not held up.
     pub fn with_complete_databases() -> anyhow::Result<Self> {
+        if tokio::runtime::Handle::try_current().is_ok() {
+            return std::thread::scope(|scope| {
+                scope
+                    .spawn(Self::with_complete_databases_on_this_thread)
+                    .join()
+                    .map_err(|_| anyhow::anyhow!("TrieNer build thread panicked"))?
+            });
+        }
+        Self::with_complete_databases_on_this_thread()
+    }
+
+    fn with_complete_databases_on_this_thread() -> anyhow::Result<Self> {
         info!("Building TrieNer with complete databases (HGNC + OncoTree)...");
         let hgnc = HgncNormaliser::from_download_blocking()?;
         let cancers = CancerNormaliser::from_download_blocking()?;
-        Self::from_normalisers(hgnc, cancers)
-    }
-
-    fn from_normalisers(hgnc: HgncNormaliser, cancers: CancerNormaliser) -> anyhow::Result<Self> {
-        let mut patterns = Vec::new();
-        let mut pattern_info = Vec::new();
-        let mut seen_patterns = HashSet::new();
-
-        // 1. Genes from HGNC
-        for (sym, tier) in hgnc.all_patterns_with_tier() {
-            let len = sym.len();
-            let req_word_bound = len <= 3;
-
-            push_pattern(
-                &mut patterns,
-                &mut pattern_info,
-                &mut seen_patterns,
-                sym.clone(),
-                PatternMeta {
-                    entity_type: EntityType::Gene,
-                    class: ConfidenceClass::Gene(tier),
-                    requires_word_boundary: req_word_bound,
-                },
-            );
-        }
-
-        // 2. Cancer Types from OncoTree
-        for (name, kind) in cancers.all_patterns_with_kind() {
-            let len = name.len();
-            push_pattern(
-                &mut patterns,
-                &mut pattern_info,
-                &mut seen_patterns,
-                name.clone(),
-                PatternMeta {
-                    entity_type: EntityType::CancerType,
-                    class: ConfidenceClass::Cancer(kind),
-                    requires_word_boundary: kind == CancerPatternKind::Code || len <= 4,
-                },
-            );
-        }
+        Self::from_normalisers_cached(hgnc, cancers, &pattern_cache_path(), source_checksum())
+    }
 
-        // 3. Mutations
-        let mutations = HgvsMutationNormaliser::new();
-        for mut_p in mutations.all_patterns() {
-            push_pattern(
-                &mut patterns,
-                &mut pattern_info,
-                &mut seen_patterns,
-                mut_p,
-                PatternMeta {
-                    entity_type: EntityType::Mutation,
-                    class: ConfidenceClass::Mutation,
-                    requires_word_boundary: true,
-                },
-            );
-        }
+    /// Build from normalisers that are already loaded, e.g. small embedded
+    /// dictionaries in tests or offline tools. Nothing is cached.
+    pub fn from_normalisers(
+        hgnc: HgncNormaliser,
+        cancers: CancerNormaliser,
+    ) -> anyhow::Result<Self> {
+        let (patterns, pattern_info) = collect_patterns(&hgnc, &cancers);
+        Self::from_patterns(patterns, pattern_info, hgnc, cancers)
+    }
 
-        // 4. Chemicals/pathways/cell lines from dictionaries and env hints.
-        for term in load_dictionary_terms(
-            "FERRUMYX_KG_CHEMICAL_HINTS",
-            "data/dictionaries/chemicals.txt",
-            BUILTIN_CHEMICALS,
-            8000,
-        ) {
-            let requires_boundary = term.len() <= 5 || term.contains(' ');
-            push_pattern(
-                &mut patterns,
-                &mut pattern_info,
-                &mut seen_patterns,
-                term,
-                PatternMeta {
-                    entity_type: EntityType::Chemical,
-                    class: ConfidenceClass::Chemical,
-                    requires_word_boundary: requires_boundary,
-                },
+    /// [`Self::from_normalisers`], reusing the patterns cached at
+    /// `cache_path` when they were collected from sources with the same
+    /// checksum. A fresh collection is written back to the cache.
+    fn from_normalisers_cached(
+        hgnc: HgncNormaliser,
+        cancers: CancerNormaliser,
+        cache_path: &Path,
+        source_checksum: u64,
+    ) -> anyhow::Result<Self> {
+        if let Some(cache) = load_pattern_cache(cache_path, source_checksum) {
+            info!(
+                "Loaded {} NER patterns from {}",
+                cache.patterns.len(),
+                cache_path.display()
             );
+            return Self::from_patterns(cache.patterns, cache.pattern_info, hgnc, cancers);
         }
-
-        for term in load_dictionary_terms(
-            "FERRUMYX_KG_PATHWAY_HINTS",
-            "data/dictionaries/pathways.txt",
-            BUILTIN_PATHWAYS,
-            8000,
-        ) {
-            let requires_boundary = true;
-            push_pattern(
-                &mut patterns,
-                &mut pattern_info,
-                &mut seen_patterns,
-                term,
-                PatternMeta {
-                    entity_type: EntityType::Pathway,
-                    class: ConfidenceClass::Pathway,
-                    requires_word_boundary: requires_boundary,
-                },
-            );
-        }
-
-        for term in load_dictionary_terms(
-            "FERRUMYX_KG_CELL_LINE_HINTS",
-            "data/dictionaries/cell_lines.txt",
-            BUILTIN_CELL_LINES,
-            6000,
-        ) {
-            let requires_boundary = true;
-            push_pattern(
-                &mut patterns,
-                &mut pattern_info,
-                &mut seen_patterns,
-                term,
-                PatternMeta {
-                    entity_type: EntityType::CellLine,
-                    class: ConfidenceClass::CellLine,
-                    requires_word_boundary: requires_boundary,
-                },
+        let (patterns, pattern_info) = collect_patterns(&hgnc, &cancers);
+        if let Err(e) = save_pattern_cache(cache_path, source_checksum, &patterns, &pattern_info) {
+            warn!(
+                "Could not write NER pattern cache {}: {e}",
+                cache_path.display()
             );
         }
+        Self::from_patterns(patterns, pattern_info, hgnc, cancers)
+    }
 
+    fn from_patterns(
+        patterns: Vec<String>,
+        pattern_info: Vec<PatternMeta>,
+        hgnc: HgncNormaliser,
+        cancers: CancerNormaliser,
+    ) -> anyhow::Result<Self> {
         if patterns.is_empty() {
             anyhow::bail!("No NER patterns loaded. Check database availability.");
         }
 
+        // Case-insensitive by default so lower-cased dictionary terms still
+        // match capitalised mentions; short symbols are re-checked in
+        // `extract`.
+        let case_insensitive = !std::env::var("FERRUMYX_NER_CASE_INSENSITIVE")
+            .ok()
+            .is_some_and(|v| v == "0" || v.eq_ignore_ascii_case("false"));
         let automaton = AhoCorasick::builder()
             .match_kind(MatchKind::LeftmostLongest)
-            .ascii_case_insensitive(true)
+            .ascii_case_insensitive(case_insensitive)
             .build(&patterns)?;
 
+        let ambiguous_symbols = match std::env::var("FERRUMYX_NER_AMBIGUOUS_SYMBOLS") {
+            Ok(list) => list
+                .split(',')
+                .map(|s| s.trim().to_uppercase())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            Err(_) => DEFAULT_AMBIGUOUS_SYMBOLS
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        };
+
         Ok(Self {
             automaton,
             pattern_info,
             hgnc,
             cancers,
+            ambiguous_symbols,
+            window: NerWindow::from_env(),
+            batch_size: env_batch_size(),
         })
     }
 
+    /// Match long texts through `window` instead of the one from the env.
+    pub fn with_window(mut self, window: NerWindow) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Hand [`Self::extract_batch`] texts to the rayon pool `batch_size` at
+    /// a time instead of the env setting.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Replace the symbols that need a cue word nearby to be accepted.
+    pub fn with_ambiguous_symbols<I, S>(mut self, symbols: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.ambiguous_symbols = symbols
+            .into_iter()
+            .map(|s| s.as_ref().trim().to_uppercase())
+            .collect();
+        self
+    }
+
     pub fn hgnc(&self) -> &HgncNormaliser {
         &self.hgnc
     }
@@ -197,6 +377,27 @@ impl TrieNer {
         &self.cancers
     }
 
+    /// Canonical `(id, name)` for `text` read as `label`: the HGNC id and
+    /// approved symbol for genes, the OncoTree code and name for cancer
+    /// types. Other types have no dictionary id.
+    pub fn canonical(&self, label: EntityType, text: &str) -> Option<(String, String)> {
+        match label {
+            EntityType::Gene => self
+                .hgnc
+                .lookup(text)
+                .map(|r| (r.hgnc_id.clone(), r.symbol.clone())),
+            EntityType::CancerType => {
+                let code = self.cancers.normalise(text)?;
+                let name = self
+                    .cancers
+                    .get_record(&code)
+                    .map_or_else(|| code.clone(), |r| r.name.clone());
+                Some((code, name))
+            }
+            _ => None,
+        }
+    }
+
     pub fn stats(&self) -> NerStats {
         let mut stats = NerStats {
             gene_count: 0,
@@ -220,12 +421,73 @@ impl TrieNer {
         stats
     }
 
+    /// [`Self::extract`] keeping only entities `filter` allows.
+    pub fn extract_filtered(&self, text: &str, filter: &NerFilter) -> Vec<ExtractedEntity> {
+        let mut entities = self.extract(text);
+        entities.retain(|e| filter.allows(e));
+        entities
+    }
+
+    /// [`Self::extract`] over several texts in parallel, in input order.
+    ///
+    /// Matching needs no padding of its own; texts go to the rayon pool in
+    /// batches of the configured size, each matched in turn (and long ones
+    /// window by window) by one task.
+    pub fn extract_batch(&self, texts: &[&str]) -> Vec<Vec<ExtractedEntity>> {
+        use rayon::prelude::*;
+        texts
+            .par_chunks(self.batch_size)
+            .flat_map_iter(|batch| batch.iter().map(|text| self.extract(text)))
+            .collect()
+    }
+
+    /// Dictionary matches in `text`, window by window (see [`NerWindow`]).
+    ///
+    /// Offsets are byte positions into `text`. A name seen in two windows
+    /// is reported once; where windows disagree, as when one cut a name
+    /// short, the longest and then highest-scoring match wins. Every slice
+    /// is taken with `str::get`, so a match that does not fall on char
+    /// boundaries is dropped rather than panicking.
     pub fn extract(&self, text: &str) -> Vec<ExtractedEntity> {
+        let ranges = self.window.ranges(text);
+        if ranges.len() == 1 {
+            return self.extract_range(text, 0..text.len());
+        }
+        let mut found: Vec<ExtractedEntity> = ranges
+            .into_iter()
+            .flat_map(|range| self.extract_range(text, range))
+            .collect();
+        found.sort_by(|a, b| {
+            a.start
+                .cmp(&b.start)
+                .then(b.end.cmp(&a.end))
+                .then(b.confidence.total_cmp(&a.confidence))
+        });
+        let mut entities: Vec<ExtractedEntity> = Vec::with_capacity(found.len());
+        for entity in found {
+            if entities.last().is_some_and(|last| entity.start < last.end) {
+                continue;
+            }
+            entities.push(entity);
+        }
+        entities
+    }
+
+    /// Matches inside `text[range]`. Word boundaries and cue words are
+    /// checked against the whole text, so a window edge never makes a
+    /// match look like a separate word.
+    fn extract_range(&self, text: &str, range: Range<usize>) -> Vec<ExtractedEntity> {
         let mut entities = Vec::new();
-        for mat in self.automaton.find_iter(text) {
+        let Some(window) = text.get(range.clone()) else {
+            return entities;
+        };
+        for mat in self.automaton.find_iter(window) {
             let pattern_idx = mat.pattern().as_usize();
             let meta = &self.pattern_info[pattern_idx];
-            let matched_text = &text[mat.start()..mat.end()];
+            let (start, end) = (range.start + mat.start(), range.start + mat.end());
+            let Some(matched_text) = text.get(start..end) else {
+                continue;
+            };
             let matched_len = matched_text.chars().count();
 
             let mut confidence = match meta.class {
@@ -252,37 +514,68 @@ impl TrieNer {
                 continue;
             }
 
-            let start = mat.start();
-            let end = mat.end();
+            if meta.case_sensitive && matched_text.chars().any(|c| c.is_ascii_lowercase()) {
+                continue;
+            }
 
             // Word-boundary check for short / ambiguous symbols
             if matched_len <= 3 || meta.requires_word_boundary {
-                let prev_char = if start > 0 {
-                    text[..start].chars().next_back()
-                } else {
-                    None
-                };
-                let next_char = text[end..].chars().next();
+                let prev_char = text.get(..start).and_then(|t| t.chars().next_back());
+                let next_char = text.get(end..).and_then(|t| t.chars().next());
 
-                if prev_char.is_some_and(|c| c.is_alphabetic())
-                    || next_char.is_some_and(|c| c.is_alphabetic())
+                if prev_char.is_some_and(|c| c.is_alphanumeric())
+                    || next_char.is_some_and(|c| c.is_alphanumeric())
                 {
                     continue; // Skip if part of a larger word
                 }
             }
 
+            if self
+                .ambiguous_symbols
+                .contains(&matched_text.to_uppercase())
+                && !has_cue_word(text, start, end)
+            {
+                continue;
+            }
+
+            let (canonical_id, canonical_name) =
+                self.canonical(meta.entity_type, matched_text).unzip();
             entities.push(ExtractedEntity {
-                text: text[start..end].to_string(),
+                text: matched_text.to_string(),
                 label: meta.entity_type,
                 start,
                 end,
                 confidence,
+                canonical_id,
+                canonical_name,
             });
         }
         entities
     }
 }
 
+const CHEMICAL_HINTS_ENV: &str = "FERRUMYX_KG_CHEMICAL_HINTS";
+const CHEMICAL_DICTIONARY: &str = "data/dictionaries/chemicals.txt";
+const PATHWAY_HINTS_ENV: &str = "FERRUMYX_KG_PATHWAY_HINTS";
+const PATHWAY_DICTIONARY: &str = "data/dictionaries/pathways.txt";
+const CELL_LINE_HINTS_ENV: &str = "FERRUMYX_KG_CELL_LINE_HINTS";
+const CELL_LINE_DICTIONARY: &str = "data/dictionaries/cell_lines.txt";
+
+/// Bumped whenever the cached pattern layout or the way patterns are
+/// collected changes, so older caches are rebuilt.
+const PATTERN_CACHE_VERSION: u32 = 3;
+
+/// Collected patterns saved after a build, so later starts skip walking
+/// the HGNC and OncoTree tables.
+#[derive(Debug, Serialize, Deserialize)]
+struct PatternCache {
+    version: u32,
+    /// [`source_checksum`] of the inputs the patterns came from.
+    source_checksum: u64,
+    patterns: Vec<String>,
+    pattern_info: Vec<PatternMeta>,
+}
+
 const BUILTIN_CHEMICALS: &[&str] = &[
     "erlotinib",
     "gefitinib",
@@ -337,6 +630,156 @@ const BUILTIN_CELL_LINES: &[&str] = &[
     "hct116",
 ];
 
+/// Every dictionary pattern with its metadata, in automaton order.
+fn collect_patterns(
+    hgnc: &HgncNormaliser,
+    cancers: &CancerNormaliser,
+) -> (Vec<String>, Vec<PatternMeta>) {
+    let mut patterns = Vec::new();
+    let mut pattern_info = Vec::new();
+    let mut seen_patterns = HashSet::new();
+
+    // 1. Genes from HGNC
+    for (sym, tier) in hgnc.all_patterns_with_tier() {
+        let len = sym.len();
+        let req_word_bound = len <= 4;
+
+        push_pattern(
+            &mut patterns,
+            &mut pattern_info,
+            &mut seen_patterns,
+            sym.clone(),
+            PatternMeta {
+                entity_type: EntityType::Gene,
+                class: ConfidenceClass::Gene(tier),
+                requires_word_boundary: req_word_bound,
+                case_sensitive: len <= 4,
+            },
+        );
+    }
+
+    // 2. Cancer Types from OncoTree
+    for (name, kind) in cancers.all_patterns_with_kind() {
+        let len = name.len();
+        push_pattern(
+            &mut patterns,
+            &mut pattern_info,
+            &mut seen_patterns,
+            name.clone(),
+            PatternMeta {
+                entity_type: EntityType::CancerType,
+                class: ConfidenceClass::Cancer(kind),
+                requires_word_boundary: kind == CancerPatternKind::Code || len <= 4,
+                case_sensitive: kind == CancerPatternKind::Code && len <= 4,
+            },
+        );
+    }
+
+    // 3. Mutations
+    let mutations = HgvsMutationNormaliser::new();
+    for mut_p in mutations.all_patterns() {
+        push_pattern(
+            &mut patterns,
+            &mut pattern_info,
+            &mut seen_patterns,
+            mut_p,
+            PatternMeta {
+                entity_type: EntityType::Mutation,
+                class: ConfidenceClass::Mutation,
+                requires_word_boundary: true,
+                case_sensitive: false,
+            },
+        );
+    }
+
+    // 4. Chemicals/pathways/cell lines from dictionaries and env hints.
+    for term in load_dictionary_terms(
+        CHEMICAL_HINTS_ENV,
+        CHEMICAL_DICTIONARY,
+        BUILTIN_CHEMICALS,
+        8000,
+    ) {
+        let requires_boundary = term.len() <= 5 || term.contains(' ');
+        push_pattern(
+            &mut patterns,
+            &mut pattern_info,
+            &mut seen_patterns,
+            term,
+            PatternMeta {
+                entity_type: EntityType::Chemical,
+                class: ConfidenceClass::Chemical,
+                requires_word_boundary: requires_boundary,
+                case_sensitive: false,
+            },
+        );
+    }
+
+    for term in load_dictionary_terms(
+        PATHWAY_HINTS_ENV,
+        PATHWAY_DICTIONARY,
+        BUILTIN_PATHWAYS,
+        8000,
+    ) {
+        let requires_boundary = true;
+        push_pattern(
+            &mut patterns,
+            &mut pattern_info,
+            &mut seen_patterns,
+            term,
+            PatternMeta {
+                entity_type: EntityType::Pathway,
+                class: ConfidenceClass::Pathway,
+                requires_word_boundary: requires_boundary,
+                case_sensitive: false,
+            },
+        );
+    }
+
+    for term in load_dictionary_terms(
+        CELL_LINE_HINTS_ENV,
+        CELL_LINE_DICTIONARY,
+        BUILTIN_CELL_LINES,
+        6000,
+    ) {
+        let requires_boundary = true;
+        push_pattern(
+            &mut patterns,
+            &mut pattern_info,
+            &mut seen_patterns,
+            term,
+            PatternMeta {
+                entity_type: EntityType::CellLine,
+                class: ConfidenceClass::CellLine,
+                requires_word_boundary: requires_boundary,
+                case_sensitive: false,
+            },
+        );
+    }
+
+    (patterns, pattern_info)
+}
+
+/// Whether a cue word sits within [`CUE_WORD_WINDOW`] words of
+/// `text[start..end]`.
+fn has_cue_word(text: &str, start: usize, end: usize) -> bool {
+    let words = |s: &str| -> Vec<String> {
+        s.split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    };
+    let before = words(text.get(..start).unwrap_or(""));
+    let after = words(text.get(end..).unwrap_or(""));
+    before
+        .iter()
+        .rev()
+        .take(CUE_WORD_WINDOW)
+        .chain(after.iter().take(CUE_WORD_WINDOW))
+        .any(|w| {
+            w == "gene" || w == "genes" || CUE_WORD_STEMS.iter().any(|stem| w.starts_with(stem))
+        })
+}
+
 fn push_pattern(
     patterns: &mut Vec<String>,
     pattern_info: &mut Vec<PatternMeta>,
@@ -348,10 +791,31 @@ fn push_pattern(
     if normalized.is_empty() || normalized.len() < 2 || !seen_patterns.insert(normalized) {
         return;
     }
+    // The automaton only folds ASCII case, and dictionary keys come
+    // upper-cased ("TGF-Β"), so also match the usual lower-case Greek
+    // letters and other non-ASCII characters as written ("TGF-β").
+    let folded = fold_non_ascii(&pattern);
+    if folded != pattern {
+        patterns.push(folded);
+        pattern_info.push(meta);
+    }
     patterns.push(pattern);
     pattern_info.push(meta);
 }
 
+/// `pattern` with its non-ASCII characters lower-cased, ASCII left as is.
+fn fold_non_ascii(pattern: &str) -> String {
+    let mut folded = String::with_capacity(pattern.len());
+    for c in pattern.chars() {
+        if c.is_ascii() {
+            folded.push(c);
+        } else {
+            folded.extend(c.to_lowercase());
+        }
+    }
+    folded
+}
+
 fn load_dictionary_terms(
     env_var: &str,
     file_path: &str,
@@ -401,6 +865,102 @@ fn load_dictionary_terms(
     out
 }
 
+/// `$FERRUMYX_CACHE_DIR/trie_ner_patterns.bin`, next to the HGNC and
+/// OncoTree downloads.
+fn pattern_cache_path() -> PathBuf {
+    let root =
+        std::env::var("FERRUMYX_CACHE_DIR").unwrap_or_else(|_| "./data/cache/ner".to_string());
+    PathBuf::from(root).join("trie_ner_patterns.bin")
+}
+
+/// FNV-1a over everything [`collect_patterns`] reads: the cached HGNC and
+/// OncoTree downloads, the dictionary files, the hint env vars and the
+/// builtin lists. Missing files hash as empty.
+fn source_checksum() -> u64 {
+    let mut hash = Fnv1a::default();
+    hash.write(&PATTERN_CACHE_VERSION.to_le_bytes());
+    for path in [HgncNormaliser::cache_path(), CancerNormaliser::cache_path()] {
+        hash.write(&std::fs::read(path).unwrap_or_default());
+    }
+    for (env_var, file_path, builtin) in [
+        (CHEMICAL_HINTS_ENV, CHEMICAL_DICTIONARY, BUILTIN_CHEMICALS),
+        (PATHWAY_HINTS_ENV, PATHWAY_DICTIONARY, BUILTIN_PATHWAYS),
+        (
+            CELL_LINE_HINTS_ENV,
+            CELL_LINE_DICTIONARY,
+            BUILTIN_CELL_LINES,
+        ),
+    ] {
+        hash.write(&std::fs::read(file_path).unwrap_or_default());
+        hash.write(std::env::var(env_var).unwrap_or_default().as_bytes());
+        for term in builtin {
+            hash.write(term.as_bytes());
+        }
+    }
+    hash.0
+}
+
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv1a {
+    /// Hash `bytes` prefixed by their length, so adjacent inputs cannot run
+    /// into each other.
+    fn write(&mut self, bytes: &[u8]) {
+        for b in (bytes.len() as u64).to_le_bytes().iter().chain(bytes) {
+            self.0 ^= *b as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+/// Cached patterns at `path`, if written by this version from sources with
+/// `source_checksum`.
+fn load_pattern_cache(path: &Path, source_checksum: u64) -> Option<PatternCache> {
+    let bytes = std::fs::read(path).ok()?;
+    let cache: PatternCache = match bincode::deserialize(&bytes) {
+        Ok(cache) => cache,
+        Err(e) => {
+            warn!(
+                "Ignoring unreadable NER pattern cache {}: {e}",
+                path.display()
+            );
+            return None;
+        }
+    };
+    (cache.version == PATTERN_CACHE_VERSION
+        && cache.source_checksum == source_checksum
+        && cache.patterns.len() == cache.pattern_info.len())
+    .then_some(cache)
+}
+
+fn save_pattern_cache(
+    path: &Path,
+    source_checksum: u64,
+    patterns: &[String],
+    pattern_info: &[PatternMeta],
+) -> anyhow::Result<()> {
+    let cache = PatternCache {
+        version: PATTERN_CACHE_VERSION,
+        source_checksum,
+        patterns: patterns.to_vec(),
+        pattern_info: pattern_info.to_vec(),
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // Write then rename so a concurrent start never reads half a file.
+    let tmp = path.with_extension("bin.tmp");
+    std::fs::write(&tmp, bincode::serialize(&cache)?)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
 pub struct NerStats {
     pub gene_count: usize,
     pub disease_count: usize,
@@ -409,3 +969,316 @@ pub struct NerStats {
     pub cell_line_count: usize,
     pub total_patterns: usize,
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn hgnc_row(id: &str, symbol: &str, aliases: &str) -> String {
+        let mut fields = vec![""; 26];
+        fields[0] = id;
+        fields[1] = symbol;
+        fields[2] = symbol;
+        fields[5] = "Approved";
+        fields[8] = aliases;
+        fields.join("\t")
+    }
+
+    fn fixture_normalisers() -> (HgncNormaliser, CancerNormaliser) {
+        let tsv = [
+            "header".to_string(),
+            hgnc_row("HGNC:11766", "TGFB1", "TGF-β"),
+            hgnc_row("HGNC:5438", "IFNG", "IFN-γ"),
+            hgnc_row("HGNC:6407", "KRAS", ""),
+            hgnc_row("HGNC:6770", "SMAD4", ""),
+            hgnc_row("HGNC:11998", "TP53", "p53|LFS1"),
+            hgnc_row("HGNC:7029", "MET", ""),
+        ]
+        .join("\n");
+        let hgnc = HgncNormaliser::from_tsv(&tsv).unwrap();
+        let cancers = CancerNormaliser::from_json(&serde_json::json!([
+            { "code": "PAAD", "name": "Pancreatic Adenocarcinoma" },
+            { "code": "NSCLC", "name": "Non-Small Cell Lung Cancer" },
+            { "code": "ALL", "name": "Acute Lymphoid Leukemia" }
+        ]))
+        .unwrap();
+        (hgnc, cancers)
+    }
+
+    /// Small offline TrieNer; also used by the hybrid tests.
+    pub(crate) fn test_ner() -> TrieNer {
+        let (hgnc, cancers) = fixture_normalisers();
+        TrieNer::from_normalisers(hgnc, cancers).unwrap()
+    }
+
+    fn genes(ner: &TrieNer, text: &str) -> Vec<String> {
+        ner.extract(text)
+            .into_iter()
+            .inspect(|e| assert_eq!(&text[e.start..e.end], e.text))
+            .filter(|e| e.label == EntityType::Gene)
+            .map(|e| e.text)
+            .collect()
+    }
+
+    #[test]
+    fn multibyte_text_does_not_break_offsets() {
+        let ner = test_ner();
+        assert_eq!(
+            genes(&ner, "TGF-β signalling through SMAD4"),
+            vec!["TGF-β", "SMAD4"]
+        );
+        assert_eq!(genes(&ner, "IFN-γ–driven KRAS loss"), vec!["IFN-γ", "KRAS"]);
+        assert_eq!(
+            genes(&ner, "🧬KRAS🧬 mutant PAAD, SMAD4 lost 🧪"),
+            vec!["KRAS", "SMAD4"]
+        );
+    }
+
+    #[test]
+    fn windows_share_overlap_tokens_and_cover_the_text() {
+        let text = (0..10)
+            .map(|i| format!("w{i}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let window = NerWindow {
+            stride: 3,
+            overlap: 1,
+        };
+        let words: Vec<Vec<&str>> = window
+            .ranges(&text)
+            .into_iter()
+            .map(|r| text[r].split_whitespace().collect())
+            .collect();
+        assert_eq!(
+            words,
+            vec![
+                vec!["w0", "w1", "w2", "w3"],
+                vec!["w3", "w4", "w5", "w6"],
+                vec!["w6", "w7", "w8", "w9"],
+            ]
+        );
+        assert_eq!(window.ranges("a b c"), vec![0..5]);
+        let whole = NerWindow {
+            stride: 0,
+            overlap: 0,
+        };
+        assert_eq!(whole.ranges(&text), vec![0..text.len()]);
+    }
+
+    #[test]
+    fn entity_at_the_end_of_a_long_text_is_found() {
+        let mut text = "tumour cells were profiled ".repeat(500);
+        text.push_str("KRAS");
+        let ner = test_ner().with_window(NerWindow::default());
+        assert!(NerWindow::default().ranges(&text).len() > 1);
+
+        let found = ner.extract(&text);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].text, "KRAS");
+        assert_eq!(found[0].start, text.len() - 4);
+        assert_eq!(&text[found[0].start..found[0].end], "KRAS");
+    }
+
+    #[test]
+    fn windowed_matches_equal_a_single_pass() {
+        // Names land on every window boundary; each must come out once.
+        let sentence = "KRAS and TP53 in Pancreatic Adenocarcinoma and NSCLC, ";
+        let text = sentence.repeat(40);
+        let whole = test_ner().with_window(NerWindow {
+            stride: 0,
+            overlap: 0,
+        });
+        let spans = |ner: &TrieNer| -> Vec<(String, usize, usize)> {
+            ner.extract(&text)
+                .into_iter()
+                .map(|e| (e.text, e.start, e.end))
+                .collect()
+        };
+        let expected = spans(&whole);
+        assert_eq!(expected.len(), 40 * 4);
+        for stride in [2, 5, 7] {
+            let windowed = test_ner().with_window(NerWindow { stride, overlap: 3 });
+            assert_eq!(spans(&windowed), expected, "stride {stride}");
+        }
+    }
+
+    #[test]
+    fn greek_letter_aliases_resolve_with_byte_offsets() {
+        let ner = test_ner();
+        let text = "Both TGF-β and IFN-γ rise; TGF-Β is the same gene.";
+        let found: Vec<(&str, usize, usize, Option<String>)> = ner
+            .extract(text)
+            .into_iter()
+            .map(|e| {
+                let slice = &text[e.start..e.end];
+                (slice, e.start, e.end, e.canonical_name)
+            })
+            .collect();
+        let tgfb1 = Some("TGFB1".to_string());
+        assert_eq!(
+            found,
+            vec![
+                ("TGF-β", 5, 11, tgfb1.clone()),
+                ("IFN-γ", 16, 22, Some("IFNG".to_string())),
+                ("TGF-Β", 29, 35, tgfb1),
+            ]
+        );
+    }
+
+    /// 64 abstract-sized texts, one in four well past a single window.
+    fn abstracts() -> Vec<String> {
+        (0..64)
+            .map(|i| {
+                let sentence = match i % 3 {
+                    0 => "KRAS G12D drives Pancreatic Adenocarcinoma in most patients. ",
+                    1 => "Loss of SMAD4 and p53 marks aggressive NSCLC with MET amplification. ",
+                    _ => "TGF-β and IFN-γ signalling shape the tumour microenvironment. ",
+                };
+                sentence.repeat(if i % 4 == 0 { 120 } else { 25 })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn batch_matches_looped_extract_in_order() {
+        let texts = abstracts();
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        let spans = |entities: Vec<ExtractedEntity>| -> Vec<(String, EntityType, usize, usize)> {
+            entities
+                .into_iter()
+                .map(|e| (e.text, e.label, e.start, e.end))
+                .collect()
+        };
+        let ner = test_ner();
+        let looped: Vec<_> = texts.iter().map(|t| spans(ner.extract(t))).collect();
+        assert!(looped.iter().all(|entities| !entities.is_empty()));
+        for batch_size in [1, 3, 64, 100] {
+            let ner = test_ner().with_batch_size(batch_size);
+            let batched: Vec<_> = ner.extract_batch(&texts).into_iter().map(spans).collect();
+            assert_eq!(batched, looped, "batch size {batch_size}");
+        }
+        assert!(ner.extract_batch(&[]).is_empty());
+    }
+
+    /// `cargo test -p ferrumyx-kg --release -- --ignored extract_batch_benchmark --nocapture`
+    #[test]
+    #[ignore = "benchmark"]
+    fn extract_batch_benchmark() {
+        let texts = abstracts();
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        let ner = test_ner();
+        const ROUNDS: u32 = 20;
+
+        let started = std::time::Instant::now();
+        for _ in 0..ROUNDS {
+            std::hint::black_box(texts.iter().map(|t| ner.extract(t)).collect::<Vec<_>>());
+        }
+        let looped = started.elapsed() / ROUNDS;
+
+        let started = std::time::Instant::now();
+        for _ in 0..ROUNDS {
+            std::hint::black_box(ner.extract_batch(&texts));
+        }
+        let batched = started.elapsed() / ROUNDS;
+        println!(
+            "{} abstracts: looped extract {looped:?}, extract_batch {batched:?} ({:.1}x)",
+            texts.len(),
+            looped.as_secs_f64() / batched.as_secs_f64()
+        );
+    }
+
+    #[test]
+    fn filter_drops_low_scores_and_unlisted_types() {
+        let ner = test_ner();
+        let text = "KRAS mutant PAAD";
+        assert_eq!(ner.extract(text).len(), 2);
+
+        let genes_only = NerFilter {
+            min_score: 0.0,
+            allowed_types: NerFilter::parse_types("gene, unknown"),
+        };
+        let kept = ner.extract_filtered(text, &genes_only);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].text, "KRAS");
+
+        let strict = NerFilter {
+            min_score: 1.01,
+            allowed_types: None,
+        };
+        assert!(ner.extract_filtered(text, &strict).is_empty());
+        assert!(NerFilter::parse_types("nope").is_none());
+    }
+
+    #[test]
+    fn short_symbols_need_capitals_boundaries_and_cues() {
+        let ner = test_ner();
+        assert!(ner.extract("metastasis").is_empty());
+        assert!(ner.extract("he was all smiles").is_empty());
+        assert!(genes(&ner, "MET levels were high").is_empty());
+
+        let found: Vec<(String, EntityType)> = ner
+            .extract("MET amplification in NSCLC")
+            .into_iter()
+            .map(|e| (e.text, e.label))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("MET".to_string(), EntityType::Gene),
+                ("NSCLC".to_string(), EntityType::CancerType),
+            ]
+        );
+
+        let no_stoplist = test_ner().with_ambiguous_symbols(Vec::<String>::new());
+        assert_eq!(genes(&no_stoplist, "MET levels were high"), vec!["MET"]);
+    }
+
+    #[test]
+    fn matches_carry_canonical_ids() {
+        let ner = test_ner();
+        let found: Vec<(String, Option<String>, Option<String>)> = ner
+            .extract("LFS1 and TP53 loss in Pancreatic Adenocarcinoma")
+            .into_iter()
+            .map(|e| (e.text, e.canonical_id, e.canonical_name))
+            .collect();
+        let tp53 = (Some("HGNC:11998".to_string()), Some("TP53".to_string()));
+        assert_eq!(
+            found,
+            vec![
+                ("LFS1".to_string(), tp53.0.clone(), tp53.1.clone()),
+                ("TP53".to_string(), tp53.0, tp53.1),
+                (
+                    "Pancreatic Adenocarcinoma".to_string(),
+                    Some("PAAD".to_string()),
+                    Some("Pancreatic Adenocarcinoma".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn pattern_cache_round_trips_a_fresh_build() {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-ner-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("trie_ner_patterns.bin");
+        let (hgnc, cancers) = fixture_normalisers();
+        let (patterns, pattern_info) = collect_patterns(&hgnc, &cancers);
+        save_pattern_cache(&path, 42, &patterns, &pattern_info).unwrap();
+
+        let cache = load_pattern_cache(&path, 42).unwrap();
+        assert_eq!(cache.patterns, patterns);
+        assert_eq!(cache.pattern_info, pattern_info);
+        assert!(load_pattern_cache(&path, 43).is_none());
+
+        let (hgnc, cancers) = fixture_normalisers();
+        let cached = TrieNer::from_normalisers_cached(hgnc, cancers, &path, 42).unwrap();
+        let text = "KRAS and p53 in PAAD; MET amplification in NSCLC";
+        let spans = |ner: &TrieNer| -> Vec<(String, EntityType, usize, usize)> {
+            ner.extract(text)
+                .into_iter()
+                .map(|e| (e.text, e.label, e.start, e.end))
+                .collect()
+        };
+        assert_eq!(spans(&cached), spans(&test_ner()));
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
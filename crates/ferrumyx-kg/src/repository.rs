@@ -4,6 +4,7 @@
 
 use anyhow::Result;
 use async_trait::async_trait;
+use ferrumyx_db::kg_conflicts::KgConflictRepository;
 use ferrumyx_db::kg_facts::KgFactRepository;
 use ferrumyx_db::schema::{KgConflict, KgFact};
 use ferrumyx_db::Database;
@@ -69,32 +70,41 @@ impl KgRepository {
     pub async fn insert_fact(&self, fact: &KgFact) -> Result<()> {
         let fact_repo = self.fact_repo();
         fact_repo.insert(fact).await?;
+        if let Some(tx) = &self.event_queue {
+            let _ = tx.send(crate::update::KgUpdateTrigger::NewFact {
+                subject_id: fact.subject_id,
+                predicate: fact.predicate.clone(),
+                object_id: fact.object_id,
+                new_confidence: fact.confidence as f64,
+            });
+        }
         self.handle_post_insert(fact).await?;
         Ok(())
     }
 
-    /// Bulk insert facts.
+    /// Bulk insert facts, sending one event for the whole batch.
     pub async fn insert_facts(&self, facts: &[KgFact]) -> Result<()> {
         let fact_repo = self.fact_repo();
         fact_repo.insert_batch(facts).await?;
         for fact in facts {
             self.handle_post_insert(fact).await?;
         }
+        if let (Some(tx), false) = (&self.event_queue, facts.is_empty()) {
+            let entity_ids: std::collections::BTreeSet<Uuid> =
+                facts.iter().map(|f| f.subject_id).collect();
+            let _ = tx.send(crate::update::KgUpdateTrigger::FactsInserted {
+                entity_ids: entity_ids.into_iter().collect(),
+                max_confidence: facts
+                    .iter()
+                    .map(|f| f.confidence as f64)
+                    .fold(0.0, f64::max),
+            });
+        }
         Ok(())
     }
 
+    /// Record conflicts between `fact` and stored facts on the same pair.
     async fn handle_post_insert(&self, fact: &KgFact) -> Result<()> {
-        if let Some(tx) = &self.event_queue {
-            let trigger = crate::update::KgUpdateTrigger::NewFact {
-                subject_id: fact.subject_id,
-                predicate: fact.predicate.clone(),
-                object_id: fact.object_id,
-                new_confidence: fact.confidence as f64,
-            };
-
-            let _ = tx.send(trigger);
-        }
-
         // Detect conflicts with existing facts
         let existing = self
             .db
@@ -157,6 +167,23 @@ impl KgRepository {
         Ok(())
     }
 
+    /// Re-run [`crate::conflict::detect_conflicts`] over every fact about
+    /// `subject_ids` and store what it finds. Returns the number of
+    /// conflicts stored.
+    pub async fn refresh_conflicts(&self, subject_ids: &[Uuid]) -> Result<usize> {
+        let facts = self
+            .fact_repo()
+            .find_by_subject_ids(subject_ids, 32)
+            .await?;
+        let records: Vec<KgConflict> = crate::conflict::detect_conflicts(&facts)
+            .iter()
+            .map(crate::conflict::Conflict::to_record)
+            .collect();
+        Ok(KgConflictRepository::new(self.db.clone())
+            .upsert_batch(&records)
+            .await?)
+    }
+
     /// Find facts by subject entity.
     pub async fn find_by_subject(&self, subject_id: Uuid) -> Result<Vec<KgFact>> {
         let fact_repo = self.fact_repo();
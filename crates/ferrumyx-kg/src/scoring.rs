@@ -4,8 +4,10 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use ferrumyx_common::confidence::{is_review_study_type, review_evidence_weight};
 use ferrumyx_db::entities::EntityRepository;
 use ferrumyx_db::kg_facts::KgFactRepository;
+use ferrumyx_db::schema::TargetScore;
 use ferrumyx_db::target_scores::TargetScoreRepository;
 use ferrumyx_db::Database;
 
@@ -16,24 +18,43 @@ pub struct GeneEvidence {
     pub mutation_evidence: u32,
     pub total_evidence: u32,
     pub confidence_sum: f64,
+    /// Review-sourced facts, kept out of the independent counts above.
+    pub review_evidence: u32,
+    pub review_confidence_sum: f64,
     pub cancer_id: Option<uuid::Uuid>,
     pub cancer_code: Option<String>,
 }
 
+impl GeneEvidence {
+    /// Total evidence with reviews counted at the review weight.
+    pub fn effective_evidence(&self, review_weight: f64) -> f64 {
+        self.total_evidence as f64 + review_weight * self.review_evidence as f64
+    }
+
+    fn record_review(&mut self, confidence: f64) {
+        self.review_evidence += 1;
+        self.review_confidence_sum += confidence;
+    }
+}
+
 fn score_row_from_evidence(
     gene_id: uuid::Uuid,
     gene_name: String,
     evidence: GeneEvidence,
 ) -> Option<ferrumyx_db::schema::TargetScore> {
-    if evidence.total_evidence == 0 {
+    let review_weight = review_evidence_weight();
+    let effective_evidence = evidence.effective_evidence(review_weight);
+    if effective_evidence <= 0.0 {
         return None;
     }
 
-    let literature_score = normalise_count(evidence.total_evidence, 30.0);
-    let mutation_score = normalise_count(evidence.mutation_evidence, 12.0);
-    let cancer_score = normalise_count(evidence.cancer_evidence, 16.0);
-    let confidence_mean =
-        (evidence.confidence_sum / evidence.total_evidence as f64).clamp(0.0, 1.0);
+    let literature_score = normalise_count(effective_evidence, 30.0);
+    let mutation_score = normalise_count(evidence.mutation_evidence as f64, 12.0);
+    let cancer_score = normalise_count(evidence.cancer_evidence as f64, 16.0);
+    let confidence_mean = ((evidence.confidence_sum
+        + review_weight * evidence.review_confidence_sum)
+        / effective_evidence)
+        .clamp(0.0, 1.0);
 
     let base_weighted =
         (0.50 * literature_score + 0.30 * mutation_score + 0.20 * cancer_score).clamp(0.0, 1.0);
@@ -67,6 +88,7 @@ fn score_row_from_evidence(
         "gene": gene_name,
         "cancer_code": evidence.cancer_code,
         "total_evidence": evidence.total_evidence,
+        "review_evidence": evidence.review_evidence,
         "mutation_evidence": evidence.mutation_evidence,
         "cancer_evidence": evidence.cancer_evidence,
         "confidence_mean": confidence_mean,
@@ -84,12 +106,27 @@ fn score_row_from_evidence(
     Some(row)
 }
 
+/// Order facts by content so aggregation does not depend on table order.
+fn sort_facts_for_scoring(facts: &mut [ferrumyx_db::schema::KgFact]) {
+    facts.sort_by(|a, b| {
+        a.paper_id
+            .cmp(&b.paper_id)
+            .then_with(|| a.subject_name.cmp(&b.subject_name))
+            .then_with(|| a.predicate.cmp(&b.predicate))
+            .then_with(|| a.object_name.cmp(&b.object_name))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+}
+
 /// Compute target scores for all genes.
 pub async fn compute_target_scores(db: Arc<Database>) -> anyhow::Result<u32> {
     let fact_repo = KgFactRepository::new(db.clone());
 
     // Keep this bounded for predictable latency under event-driven recompute.
-    let facts = fact_repo.list(0, 50_000).await?;
+    let mut facts = fact_repo.list(0, 50_000).await?;
+    // Table order follows insert order, which varies with concurrency; the
+    // last cancer seen per gene wins below, so fix the order first.
+    sort_facts_for_scoring(&mut facts);
 
     let mut by_gene: HashMap<(uuid::Uuid, String), GeneEvidence> = HashMap::new();
     for fact in facts {
@@ -97,19 +134,24 @@ pub async fn compute_target_scores(db: Arc<Database>) -> anyhow::Result<u32> {
             continue;
         }
         let pred_lc = fact.predicate.to_lowercase();
+        let is_review = is_review_study_type(fact.study_type.as_deref());
 
         if is_gene_like(&fact.subject_name) {
             let key = (fact.subject_id, fact.subject_name.clone());
             let entry = by_gene.entry(key).or_default();
-            entry.total_evidence += 1;
-            entry.confidence_sum += fact.confidence as f64;
-            if pred_lc.contains("mutation") || pred_lc == "has_mutation" {
-                entry.mutation_evidence += 1;
-            }
-            if is_cancer_like(&fact.object_name) {
-                entry.cancer_evidence += 1;
-                entry.cancer_id = Some(fact.object_id);
-                entry.cancer_code = Some(fact.object_name.clone());
+            if is_review {
+                entry.record_review(fact.confidence as f64);
+            } else {
+                entry.total_evidence += 1;
+                entry.confidence_sum += fact.confidence as f64;
+                if pred_lc.contains("mutation") || pred_lc == "has_mutation" {
+                    entry.mutation_evidence += 1;
+                }
+                if is_cancer_like(&fact.object_name) {
+                    entry.cancer_evidence += 1;
+                    entry.cancer_id = Some(fact.object_id);
+                    entry.cancer_code = Some(fact.object_name.clone());
+                }
             }
         }
 
@@ -118,6 +160,10 @@ pub async fn compute_target_scores(db: Arc<Database>) -> anyhow::Result<u32> {
         if !fact.object_id.is_nil() && is_gene_like(&fact.object_name) {
             let key = (fact.object_id, fact.object_name.clone());
             let entry = by_gene.entry(key).or_default();
+            if is_review {
+                entry.record_review(fact.confidence as f64);
+                continue;
+            }
             entry.total_evidence += 1;
             entry.confidence_sum += fact.confidence as f64;
             if is_cancer_like(&fact.subject_name) {
@@ -128,6 +174,8 @@ pub async fn compute_target_scores(db: Arc<Database>) -> anyhow::Result<u32> {
         }
     }
 
+    let mut by_gene: Vec<_> = by_gene.into_iter().collect();
+    by_gene.sort_by(|a, b| a.0.cmp(&b.0));
     let mut rows = Vec::new();
     for ((gene_id, gene_name), evidence) in by_gene {
         if let Some(row) = score_row_from_evidence(gene_id, gene_name, evidence) {
@@ -145,15 +193,24 @@ pub async fn compute_target_scores_for_gene_ids(
     db: Arc<Database>,
     gene_ids: &[uuid::Uuid],
 ) -> anyhow::Result<u32> {
+    Ok(rescore_target_genes(db, gene_ids).await?.len() as u32)
+}
+
+/// [`compute_target_scores_for_gene_ids`] returning the rows written.
+pub async fn rescore_target_genes(
+    db: Arc<Database>,
+    gene_ids: &[uuid::Uuid],
+) -> anyhow::Result<Vec<TargetScore>> {
     let mut uniq = gene_ids.to_vec();
     uniq.sort_unstable();
     uniq.dedup();
     if uniq.is_empty() {
-        return Ok(0);
+        return Ok(Vec::new());
     }
 
     let fact_repo = KgFactRepository::new(db.clone());
-    let facts = fact_repo.find_by_subject_ids(&uniq, 80).await?;
+    let mut facts = fact_repo.find_by_subject_ids(&uniq, 80).await?;
+    sort_facts_for_scoring(&mut facts);
 
     let mut by_gene: HashMap<uuid::Uuid, (String, GeneEvidence)> = HashMap::new();
     for fact in facts {
@@ -170,6 +227,10 @@ pub async fn compute_target_scores_for_gene_ids(
             entry.0 = fact.subject_name.clone();
         }
         let evidence = &mut entry.1;
+        if is_review_study_type(fact.study_type.as_deref()) {
+            evidence.record_review(fact.confidence as f64);
+            continue;
+        }
         evidence.total_evidence += 1;
         evidence.confidence_sum += fact.confidence as f64;
 
@@ -196,8 +257,8 @@ pub async fn compute_target_scores_for_gene_ids(
     let score_repo = TargetScoreRepository::new(db);
     // Clear impacted genes first so removed/changed evidence does not leave stale rows.
     let _ = score_repo.delete_by_gene_ids(&uniq, 200).await?;
-    let upserted = score_repo.upsert_batch(&rows).await?;
-    Ok(upserted as u32)
+    score_repo.upsert_batch(&rows).await?;
+    Ok(rows)
 }
 
 /// Resolve gene names to entity IDs and recompute only those targets.
@@ -247,6 +308,10 @@ pub async fn get_gene_evidence(db: Arc<Database>, gene: &str) -> anyhow::Result<
         if fact.predicate.eq_ignore_ascii_case("mentions") {
             continue;
         }
+        if is_review_study_type(fact.study_type.as_deref()) {
+            out.record_review(fact.confidence as f64);
+            continue;
+        }
         out.total_evidence += 1;
         out.confidence_sum += fact.confidence as f64;
         let p = fact.predicate.to_lowercase();
@@ -263,9 +328,8 @@ pub async fn get_gene_evidence(db: Arc<Database>, gene: &str) -> anyhow::Result<
     Ok(out)
 }
 
-fn normalise_count(v: u32, scale: f64) -> f64 {
-    let x = v as f64;
-    (x.ln_1p() / scale.ln_1p()).clamp(0.0, 1.0)
+fn normalise_count(x: f64, scale: f64) -> f64 {
+    (x.max(0.0).ln_1p() / scale.ln_1p()).clamp(0.0, 1.0)
 }
 
 fn is_cancer_like(name: &str) -> bool {
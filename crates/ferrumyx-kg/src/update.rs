@@ -1,12 +1,22 @@
 //! Event-driven KG update rules.
 //! See ARCHITECTURE.md §3.4
 
+use crate::graph::KgGraphIndex;
+use async_trait::async_trait;
 use ferrumyx_common::confidence::aggregate_confidence;
+use ferrumyx_db::schema::TargetScore;
 use ferrumyx_db::Database;
+use serde::Serialize;
+use std::collections::BTreeSet;
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
 use tracing::{info, warn};
 
+/// How long the queue keeps collecting events after the first one before
+/// re-scoring, so a burst of inserts for one gene scores it once.
+pub const SCORING_DEBOUNCE: Duration = Duration::from_secs(2);
+
 /// Represents a trigger event from the routines engine.
 #[derive(Debug, Clone)]
 pub enum KgUpdateTrigger {
@@ -17,6 +27,14 @@ pub enum KgUpdateTrigger {
         object_id: uuid::Uuid,
         new_confidence: f64,
     },
+    /// A batch of facts was inserted.
+    FactsInserted {
+        /// Subjects of the new facts, i.e. the entities whose scores they
+        /// feed.
+        entity_ids: Vec<uuid::Uuid>,
+        /// Highest confidence in the batch.
+        max_confidence: f64,
+    },
     /// An existing fact's confidence changed significantly.
     FactConfidenceChanged {
         fact_id: uuid::Uuid,
@@ -24,6 +42,52 @@ pub enum KgUpdateTrigger {
         new_confidence: f64,
         subject_id: uuid::Uuid,
     },
+    /// Facts were deleted or lost supporting papers, e.g. when papers were
+    /// removed.
+    FactsRetracted {
+        /// Subjects of the changed facts.
+        entity_ids: Vec<uuid::Uuid>,
+    },
+}
+
+impl KgUpdateTrigger {
+    /// Entities whose facts the event changed.
+    pub fn entity_ids(&self) -> Vec<uuid::Uuid> {
+        match self {
+            Self::NewFact { subject_id, .. } | Self::FactConfidenceChanged { subject_id, .. } => {
+                vec![*subject_id]
+            }
+            Self::FactsInserted { entity_ids, .. } | Self::FactsRetracted { entity_ids } => {
+                entity_ids.clone()
+            }
+        }
+    }
+
+    /// Whether the change is large enough to re-score its entities.
+    pub fn requires_rescore(&self) -> bool {
+        match self {
+            Self::NewFact { new_confidence, .. } => should_requeue_scoring(0.0, *new_confidence),
+            Self::FactsInserted { max_confidence, .. } => {
+                should_requeue_scoring(0.0, *max_confidence)
+            }
+            Self::FactConfidenceChanged {
+                old_confidence,
+                new_confidence,
+                ..
+            } => should_requeue_scoring(*old_confidence, *new_confidence),
+            // Lost evidence always moves the score.
+            Self::FactsRetracted { .. } => true,
+        }
+    }
+}
+
+/// Entities to re-score for a debounced batch of events, each once.
+pub fn coalesce_rescore_targets(events: &[KgUpdateTrigger]) -> BTreeSet<uuid::Uuid> {
+    events
+        .iter()
+        .filter(|e| e.requires_rescore())
+        .flat_map(KgUpdateTrigger::entity_ids)
+        .collect()
 }
 
 /// Determines whether a target re-scoring should be queued.
@@ -32,63 +96,150 @@ pub fn should_requeue_scoring(old_confidence: f64, new_confidence: f64) -> bool
     (new_confidence - old_confidence).abs() > 0.05
 }
 
-/// Start the background event-driven scoring queue constraint.
-/// Listens to new kg_facts insertion events, and if they breach the confidence threshold,
-/// triggers the Target Prioritisation engine natively.
-pub fn start_scoring_event_queue(db: Arc<Database>) -> mpsc::UnboundedSender<KgUpdateTrigger> {
-    let (tx, mut rx) = mpsc::unbounded_channel::<KgUpdateTrigger>();
-
-    tokio::spawn(async move {
-        info!("Started event-driven KG scoring queue worker");
-
-        while let Some(event) = rx.recv().await {
-            match event {
-                KgUpdateTrigger::FactConfidenceChanged {
-                    old_confidence,
-                    new_confidence,
-                    subject_id,
-                    ..
-                } => {
-                    if should_requeue_scoring(old_confidence, new_confidence) {
-                        info!(
-                            "Confidence delta > 0.05 for target {:?}. Queuing re-score...",
-                            subject_id
-                        );
-                        if let Err(e) = crate::scoring::compute_target_scores_for_gene_ids(
-                            db.clone(),
-                            &[subject_id],
-                        )
-                        .await
-                        {
-                            warn!("Failed to re-score targets: {}", e);
-                        }
+/// Recomputes and persists the target scores of one gene.
+#[async_trait]
+pub trait TargetRescorer: Send + Sync {
+    /// Returns the `target_scores` rows written for `gene_id`, one per
+    /// gene–cancer pair.
+    async fn rescore(&self, gene_id: uuid::Uuid) -> anyhow::Result<Vec<TargetScore>>;
+}
+
+/// [`TargetRescorer`] over [`crate::scoring::rescore_target_genes`].
+pub struct DbRescorer {
+    db: Arc<Database>,
+}
+
+impl DbRescorer {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl TargetRescorer for DbRescorer {
+    async fn rescore(&self, gene_id: uuid::Uuid) -> anyhow::Result<Vec<TargetScore>> {
+        crate::scoring::rescore_target_genes(self.db.clone(), &[gene_id]).await
+    }
+}
+
+/// A target score the queue rewrote.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScoreUpdate {
+    pub gene_id: uuid::Uuid,
+    pub gene: String,
+    pub cancer_id: uuid::Uuid,
+    pub cancer_code: Option<String>,
+    pub score: f64,
+    pub tier: String,
+}
+
+impl ScoreUpdate {
+    pub fn from_row(row: &TargetScore) -> Self {
+        let raw: serde_json::Value = serde_json::from_str(&row.components_raw).unwrap_or_default();
+        let text = |key: &str| raw[key].as_str().map(str::to_string);
+        Self {
+            gene_id: row.gene_id,
+            gene: text("gene").unwrap_or_default(),
+            cancer_id: row.cancer_id,
+            cancer_code: text("cancer_code"),
+            score: row.confidence_adjusted_score,
+            tier: row.shortlist_tier.clone(),
+        }
+    }
+}
+
+/// Handle to a running event queue.
+///
+/// The worker stops once every clone of [`Self::sender`] and the handle
+/// itself are dropped.
+pub struct KgEventQueue {
+    tx: mpsc::UnboundedSender<KgUpdateTrigger>,
+    updates: broadcast::Sender<ScoreUpdate>,
+}
+
+impl KgEventQueue {
+    /// Spawn the worker. Events are collected for `debounce` after the
+    /// first one; `graph`, if given, is refreshed for every touched entity
+    /// and `rescorer` runs once per entity that [`coalesce_rescore_targets`]
+    /// keeps.
+    pub fn start(
+        rescorer: Arc<dyn TargetRescorer>,
+        graph: Option<Arc<KgGraphIndex>>,
+        debounce: Duration,
+    ) -> Self {
+        let (tx, mut rx) = mpsc::unbounded_channel::<KgUpdateTrigger>();
+        let (updates, _) = broadcast::channel(256);
+        let score_tx = updates.clone();
+
+        tokio::spawn(async move {
+            info!("Started event-driven KG scoring queue worker");
+
+            while let Some(first) = rx.recv().await {
+                let mut events = vec![first];
+                let deadline = tokio::time::Instant::now() + debounce;
+                while let Ok(Some(event)) = tokio::time::timeout_at(deadline, rx.recv()).await {
+                    events.push(event);
+                }
+
+                if let Some(graph) = &graph {
+                    let touched: Vec<uuid::Uuid> = events
+                        .iter()
+                        .flat_map(KgUpdateTrigger::entity_ids)
+                        .collect::<BTreeSet<_>>()
+                        .into_iter()
+                        .collect();
+                    if let Err(e) = graph.refresh_subjects(&touched).await {
+                        warn!("Failed to refresh KG graph index: {}", e);
                     }
                 }
-                KgUpdateTrigger::NewFact {
-                    subject_id,
-                    new_confidence,
-                    ..
-                } => {
-                    if should_requeue_scoring(0.0, new_confidence) {
-                        info!(
-                            "New strong fact added for target {:?}. Queuing re-score...",
-                            subject_id
-                        );
-                        if let Err(e) = crate::scoring::compute_target_scores_for_gene_ids(
-                            db.clone(),
-                            &[subject_id],
-                        )
-                        .await
-                        {
-                            warn!("Failed to re-score targets: {}", e);
+
+                let targets = coalesce_rescore_targets(&events);
+                if targets.is_empty() {
+                    continue;
+                }
+                info!(
+                    events = events.len(),
+                    targets = targets.len(),
+                    "Re-scoring targets touched by KG updates"
+                );
+                for gene_id in targets {
+                    match rescorer.rescore(gene_id).await {
+                        Ok(rows) => {
+                            for row in &rows {
+                                // No subscribers is fine; the rows are stored.
+                                let _ = score_tx.send(ScoreUpdate::from_row(row));
+                            }
                         }
+                        Err(e) => warn!("Failed to re-score target {}: {}", gene_id, e),
                     }
                 }
             }
-        }
-    });
+        });
+
+        Self { tx, updates }
+    }
+
+    pub fn sender(&self) -> mpsc::UnboundedSender<KgUpdateTrigger> {
+        self.tx.clone()
+    }
+
+    /// Scores rewritten from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<ScoreUpdate> {
+        self.updates.subscribe()
+    }
+}
+
+/// Start the background event-driven scoring queue constraint.
+/// Listens to new kg_facts insertion events, and if they breach the confidence threshold,
+/// re-scores only the touched genes after [`SCORING_DEBOUNCE`].
+pub fn start_scoring_event_queue(db: Arc<Database>) -> mpsc::UnboundedSender<KgUpdateTrigger> {
+    KgEventQueue::start(Arc::new(DbRescorer::new(db)), None, SCORING_DEBOUNCE).sender()
+}
 
-    tx
+/// [`start_scoring_event_queue`] that also keeps `graph` in step with each
+/// batch of events before re-scoring.
+pub fn start_event_queue_with_graph(db: Arc<Database>, graph: Arc<KgGraphIndex>) -> KgEventQueue {
+    KgEventQueue::start(Arc::new(DbRescorer::new(db)), Some(graph), SCORING_DEBOUNCE)
 }
 
 /// Recompute aggregate confidence after adding a new evidence item.
@@ -102,6 +253,27 @@ pub fn recompute_aggregate(existing_confidences: &[f64], new_confidence: f64) ->
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct CountingRescorer {
+        calls: Mutex<Vec<uuid::Uuid>>,
+    }
+
+    #[async_trait]
+    impl TargetRescorer for CountingRescorer {
+        async fn rescore(&self, gene_id: uuid::Uuid) -> anyhow::Result<Vec<TargetScore>> {
+            self.calls.lock().unwrap().push(gene_id);
+            Ok(vec![TargetScore::new(
+                gene_id,
+                uuid::Uuid::nil(),
+                0.6,
+                0.55,
+                0.0,
+                "secondary".to_string(),
+            )])
+        }
+    }
 
     #[test]
     fn test_requeue_on_large_delta() {
@@ -116,4 +288,77 @@ mod tests {
         let new_agg = recompute_aggregate(&existing, 0.8);
         assert!(new_agg > old_agg);
     }
+
+    #[tokio::test]
+    async fn debounced_queue_rescores_each_touched_gene_once() {
+        let rescorer = Arc::new(CountingRescorer::default());
+        let queue = KgEventQueue::start(rescorer.clone(), None, Duration::from_millis(100));
+        let mut updates = queue.subscribe();
+        let genes: Vec<uuid::Uuid> = (1..=3).map(uuid::Uuid::from_u128).collect();
+
+        let tx = queue.sender();
+        for i in 0..100 {
+            tx.send(KgUpdateTrigger::NewFact {
+                subject_id: genes[i % 3],
+                predicate: "associated_with".to_string(),
+                object_id: uuid::Uuid::from_u128(100),
+                new_confidence: 0.8,
+            })
+            .unwrap();
+        }
+
+        let mut updated = Vec::new();
+        for _ in 0..3 {
+            let update = tokio::time::timeout(Duration::from_secs(5), updates.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            updated.push(update.gene_id);
+        }
+        // Give a stray second batch time to show up.
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        let mut calls = rescorer.calls.lock().unwrap().clone();
+        calls.sort();
+        assert_eq!(calls, genes);
+        updated.sort();
+        assert_eq!(updated, genes);
+    }
+
+    #[test]
+    fn weak_events_do_not_rescore() {
+        let gene = uuid::Uuid::from_u128(1);
+        let events = [
+            KgUpdateTrigger::FactsInserted {
+                entity_ids: vec![gene, uuid::Uuid::from_u128(2)],
+                max_confidence: 0.03,
+            },
+            KgUpdateTrigger::FactConfidenceChanged {
+                fact_id: uuid::Uuid::from_u128(9),
+                old_confidence: 0.5,
+                new_confidence: 0.9,
+                subject_id: gene,
+            },
+        ];
+        assert_eq!(
+            coalesce_rescore_targets(&events)
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![gene]
+        );
+    }
+
+    #[test]
+    fn retracted_facts_always_rescore() {
+        let genes: Vec<uuid::Uuid> = (1..=2).map(uuid::Uuid::from_u128).collect();
+        let events = [KgUpdateTrigger::FactsRetracted {
+            entity_ids: genes.clone(),
+        }];
+        assert_eq!(
+            coalesce_rescore_targets(&events)
+                .into_iter()
+                .collect::<Vec<_>>(),
+            genes
+        );
+    }
 }
@@ -0,0 +1,164 @@
+//! Golden-set regression test for NER and normalisation.
+//!
+//! Scores the dictionary extractor over `sentences.jsonl` and fails when
+//! any score falls below its minimum in `baseline.toml`. The dictionaries
+//! are the HGNC and OncoTree subsets next to it, so the run is offline.
+//!
+//! Run with: cargo test -p ferrumyx-kg --features eval --test golden -- --nocapture
+//!
+//! After an intended change in quality, rewrite the baseline from the new
+//! scores with `FERRUMYX_NER_GOLDEN_UPDATE=1`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use ferrumyx_kg::ner::eval::{
+    evaluate, load_golden_set, render_report, Baseline, Baselines, EntityExtractor, EvalReport,
+    GoldSentence, HybridExtractor,
+};
+use ferrumyx_kg::ner::{
+    CancerNormaliser, EntityType, ExtractedEntity, HgncNormaliser, HybridNer, SpanTagger, TrieNer,
+};
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
+
+fn golden_set() -> Vec<GoldSentence> {
+    load_golden_set(&golden_dir().join("sentences.jsonl")).unwrap()
+}
+
+fn fixture_trie() -> TrieNer {
+    let dir = golden_dir();
+    let hgnc = std::fs::read_to_string(dir.join("hgnc_subset.tsv")).unwrap();
+    let oncotree = std::fs::read_to_string(dir.join("oncotree_subset.json")).unwrap();
+    TrieNer::from_normalisers(
+        HgncNormaliser::from_tsv(&hgnc).unwrap(),
+        CancerNormaliser::from_json(&serde_json::from_str(&oncotree).unwrap()).unwrap(),
+    )
+    .unwrap()
+}
+
+/// Replays the gold spans without their ids, standing in for a perfect
+/// statistical tagger so the hybrid merge is scored on its own.
+struct GoldTagger {
+    spans: HashMap<String, Vec<ExtractedEntity>>,
+}
+
+impl GoldTagger {
+    fn new(sentences: &[GoldSentence]) -> Self {
+        let spans = sentences
+            .iter()
+            .map(|s| {
+                let spans = s
+                    .entities
+                    .iter()
+                    .map(|g| ExtractedEntity {
+                        text: g.text.clone(),
+                        label: EntityType::from_label(&g.label).unwrap(),
+                        start: g.start,
+                        end: g.end,
+                        confidence: 0.9,
+                        canonical_id: None,
+                        canonical_name: None,
+                    })
+                    .collect();
+                (s.text.clone(), spans)
+            })
+            .collect();
+        Self { spans }
+    }
+}
+
+impl SpanTagger for GoldTagger {
+    fn max_len(&self) -> usize {
+        usize::MAX
+    }
+
+    fn tag(&self, text: &str) -> anyhow::Result<Vec<ExtractedEntity>> {
+        Ok(self.spans.get(text).cloned().unwrap_or_default())
+    }
+}
+
+/// Held while a test reads and rewrites `baseline.toml`.
+static BASELINE_FILE: Mutex<()> = Mutex::new(());
+
+/// Scores `extractor`, prints the table and checks it against the committed
+/// baseline, or rewrites that baseline when asked to.
+fn check_against_baseline(
+    extractor: &dyn EntityExtractor,
+    sentences: &[GoldSentence],
+) -> EvalReport {
+    let report = evaluate(extractor, sentences).unwrap();
+    let path = golden_dir().join("baseline.toml");
+    let baselines = {
+        let _file = BASELINE_FILE.lock().unwrap_or_else(|e| e.into_inner());
+        let mut baselines = Baselines::load(&path).unwrap();
+        if std::env::var("FERRUMYX_NER_GOLDEN_UPDATE").is_ok_and(|v| v == "1") {
+            baselines.set(extractor.name(), Baseline::from_report(&report));
+            baselines.save(&path).unwrap();
+        }
+        baselines
+    };
+    let baseline = baselines
+        .get(extractor.name())
+        .unwrap_or_else(|| panic!("baseline.toml has no [{}] table", extractor.name()));
+    let table = render_report(&report, Some(baseline));
+    println!("{table}");
+    let regressions = baseline.check(&report);
+    assert!(
+        regressions.is_empty(),
+        "{} regressed:\n  {}\n\n{table}",
+        extractor.name(),
+        regressions
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("\n  ")
+    );
+    report
+}
+
+#[test]
+fn golden_set_is_large_and_varied_enough() {
+    let sentences = golden_set();
+    assert!(sentences.len() >= 100, "{} sentences", sentences.len());
+    for tag in [
+        "gene",
+        "alias",
+        "previous",
+        "cancer",
+        "disease",
+        "chemical",
+        "mutation",
+        "cell_line",
+        "pathway",
+        "negative",
+    ] {
+        assert!(
+            sentences.iter().any(|s| s.tags.iter().any(|t| t == tag)),
+            "no sentence tagged {tag}"
+        );
+    }
+}
+
+#[test]
+fn trie_extractor_keeps_its_baseline() {
+    check_against_baseline(&fixture_trie(), &golden_set());
+}
+
+#[test]
+fn hybrid_extractor_keeps_its_baseline() {
+    let sentences = golden_set();
+    let trie = Arc::new(fixture_trie());
+    let hybrid = HybridExtractor::new(
+        HybridNer::new(trie.clone()),
+        Arc::new(GoldTagger::new(&sentences)),
+    );
+    let hybrid_report = check_against_baseline(&hybrid, &sentences);
+
+    // Model spans should only ever add to what the dictionary finds.
+    let trie_report = evaluate(trie.as_ref(), &sentences).unwrap();
+    assert!(hybrid_report.micro().recall() >= trie_report.micro().recall());
+}
@@ -1,16 +1,175 @@
-//! ADMET prediction for molecules.
+//! Rule-based ADMET assessment computed natively from SMILES.
+//!
+//! [`score_admet`] runs Lipinski's rule of five, Veber's oral
+//! bioavailability rules, a synthetic-accessibility estimate and the PAINS
+//! substructure alerts over pure-Rust [`Descriptors`], so ranking does not
+//! depend on the admet-ai container. Model-based endpoints such as hERG and
+//! hepatotoxicity still need that container; they are not estimated here.
 
+use crate::descriptors::Descriptors;
 use crate::ligand::Molecule;
-use anyhow::Result;
+use crate::smarts::Pattern;
+use crate::smiles::{MolGraph, SmilesError};
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
 
-/// ADMET properties for a molecule.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AdmetProperties {
-    pub ro5_violations: u32,
-    pub qed_estimate: f64,
-    pub mw: f64,
-    pub logp: f64,
+const PAINS: &str = include_str!("../data/pains.txt");
+
+/// Score lost per broken Lipinski or Veber rule.
+const RULE_PENALTY: f64 = 0.15;
+/// Score lost per PAINS alert.
+const ALERT_PENALTY: f64 = 0.2;
+/// Score lost at the hardest synthetic accessibility of 10.
+const SA_PENALTY: f64 = 0.3;
+
+/// Outcome of one rule set, with a line per broken rule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleCheck {
+    pub passed: bool,
+    pub violations: Vec<String>,
+}
+
+/// ADMET assessment of one molecule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AdmetProfile {
+    pub smiles: String,
+    pub descriptors: Descriptors,
+    /// MW ≤ 500, logP ≤ 5, HBD ≤ 5, HBA ≤ 10; one violation still passes.
+    pub lipinski: RuleCheck,
+    /// Rotatable bonds ≤ 10 and TPSA ≤ 140 Å².
+    pub veber: RuleCheck,
+    /// 1 (easy) to 10 (hard).
+    pub sa_score: f64,
+    /// Names of the PAINS families that matched, in list order.
+    pub pains_alerts: Vec<String>,
+    /// 0 to 1, higher is better; see [`score_admet`].
+    pub score: f64,
+}
+
+impl AdmetProfile {
+    /// Passes Lipinski and Veber and raises no PAINS alert.
+    pub fn passes(&self) -> bool {
+        self.lipinski.passed && self.veber.passed && self.pains_alerts.is_empty()
+    }
+}
+
+/// Assess `smiles`. The score starts at 1 and loses 0.15 per broken Lipinski
+/// or Veber rule, 0.2 per PAINS alert and up to 0.3 for synthetic
+/// accessibility, floored at 0.
+pub fn score_admet(smiles: &str) -> Result<AdmetProfile, SmilesError> {
+    let mol = MolGraph::parse(smiles)?;
+    let descriptors = Descriptors::of(&mol);
+    let lipinski = lipinski(&descriptors);
+    let veber = veber(&descriptors);
+    let sa_score = synthetic_accessibility(&descriptors);
+    let pains_alerts = pains_alerts(&mol);
+
+    let broken = (lipinski.violations.len() + veber.violations.len()) as f64;
+    let score = (1.0
+        - RULE_PENALTY * broken
+        - ALERT_PENALTY * pains_alerts.len() as f64
+        - SA_PENALTY * (sa_score - 1.0) / 9.0)
+        .clamp(0.0, 1.0);
+
+    Ok(AdmetProfile {
+        smiles: smiles.trim().to_string(),
+        descriptors,
+        lipinski,
+        veber,
+        sa_score,
+        pains_alerts,
+        score,
+    })
+}
+
+pub fn lipinski(d: &Descriptors) -> RuleCheck {
+    let mut violations = Vec::new();
+    if d.mw > 500.0 {
+        violations.push(format!("MW {:.1} > 500", d.mw));
+    }
+    if d.logp > 5.0 {
+        violations.push(format!("logP {:.2} > 5", d.logp));
+    }
+    if d.hbd > 5 {
+        violations.push(format!("HBD {} > 5", d.hbd));
+    }
+    if d.hba > 10 {
+        violations.push(format!("HBA {} > 10", d.hba));
+    }
+    RuleCheck {
+        passed: violations.len() <= 1,
+        violations,
+    }
+}
+
+pub fn veber(d: &Descriptors) -> RuleCheck {
+    let mut violations = Vec::new();
+    if d.rotatable_bonds > 10 {
+        violations.push(format!("{} rotatable bonds > 10", d.rotatable_bonds));
+    }
+    if d.tpsa > 140.0 {
+        violations.push(format!("TPSA {:.1} > 140", d.tpsa));
+    }
+    RuleCheck {
+        passed: violations.is_empty(),
+        violations,
+    }
+}
+
+/// Synthetic accessibility from the complexity terms of Ertl and
+/// Schuffenhauer's SAscore (size, stereocentres, spiro and bridgehead
+/// atoms, macrocycles) plus 0.1 per ring. The fragment-frequency term needs
+/// a PubChem-derived table and is left out, so common but large scaffolds
+/// read a little easier than under RDKit.
+pub fn synthetic_accessibility(d: &Descriptors) -> f64 {
+    let n = f64::from(d.heavy_atoms);
+    let size = n.powf(1.005) - n;
+    let macrocycle = if d.largest_ring > 8 {
+        2f64.log10()
+    } else {
+        0.0
+    };
+    let topology = f64::from(d.stereocenters + 1).log10()
+        + f64::from(d.spiro_atoms + 1).log10()
+        + f64::from(d.bridgehead_atoms + 1).log10()
+        + macrocycle;
+    (1.0 + size + 2.0 * topology + 0.1 * f64::from(d.rings)).clamp(1.0, 10.0)
+}
+
+/// The embedded PAINS families matching `mol`, each named once.
+pub fn pains_alerts(mol: &MolGraph) -> Vec<String> {
+    let mut alerts: Vec<String> = Vec::new();
+    for (name, pattern) in pains_patterns() {
+        if !alerts.contains(name) && pattern.matches(mol) {
+            alerts.push(name.clone());
+        }
+    }
+    alerts
+}
+
+fn pains_patterns() -> &'static [(String, Pattern)] {
+    static PATTERNS: OnceLock<Vec<(String, Pattern)>> = OnceLock::new();
+    PATTERNS.get_or_init(|| parse_alerts(PAINS))
+}
+
+/// `name<TAB>smarts` lines; `#` comments and blank lines are skipped, and
+/// malformed lines are logged and dropped.
+fn parse_alerts(text: &str) -> Vec<(String, Pattern)> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (name, smarts) = line.split_once('\t')?;
+            match Pattern::parse(smarts.trim()) {
+                Ok(pattern) => Some((name.trim().to_string(), pattern)),
+                Err(e) => {
+                    tracing::warn!("Skipping PAINS alert {}: {}", name, e);
+                    None
+                }
+            }
+        })
+        .collect()
 }
 
 /// Predictor for ADMET properties.
@@ -22,36 +181,87 @@ impl AdmetPredictor {
         Self {}
     }
 
-    /// Predict ADMET properties for a given molecule.
-    pub async fn predict(&self, molecule: &Molecule) -> Result<AdmetProperties> {
-        let mw = molecule.mw.unwrap_or(400.0);
-        let logp = molecule.logp.unwrap_or(3.0);
+    /// Predict ADMET properties for a given molecule from its SMILES.
+    pub async fn predict(&self, molecule: &Molecule) -> Result<AdmetProfile> {
+        score_admet(&molecule.smiles).with_context(|| format!("cannot assess {}", molecule.smiles))
+    }
+}
 
-        let mut violations = 0;
-        if mw > 500.0 {
-            violations += 1;
-        }
-        if logp > 5.0 {
-            violations += 1;
-        }
-        if let Some(hbd) = molecule.hbd {
-            if hbd > 5 {
-                violations += 1;
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IMATINIB: &str = "Cc1ccc(NC(=O)c2ccc(CN3CCN(C)CC3)cc2)cc1Nc1nccc(-c2cccnc2)n1";
+
+    #[test]
+    fn imatinib_passes() {
+        let profile = score_admet(IMATINIB).unwrap();
+        assert!(profile.passes(), "{profile:?}");
+        assert!(profile.lipinski.violations.is_empty());
+        assert!(
+            (2.0..2.5).contains(&profile.sa_score),
+            "{}",
+            profile.sa_score
+        );
+        assert!(profile.score > 0.9, "{}", profile.score);
+    }
+
+    #[test]
+    fn pains_offenders_are_flagged() {
+        for (smiles, alert) in [
+            ("O=C1NC(=S)SC1=Cc1ccccc1", "ene_rhod_A"),
+            ("Oc1ccccc1O", "catechol_A"),
+            ("O=C1C=CC(=O)C=C1", "quinone_A"),
+            ("O=C1C(=O)c2ccccc2C=C1", "quinone_A"),
+            ("c1ccc(cc1)/N=N/c1ccccc1", "azo_A"),
+            ("N#CC(C#N)=Cc1ccccc1", "ene_cyano_A"),
+            ("CCOC(=O)c1c(N)sc(C)c1C", "thiophene_amino_Aa"),
+        ] {
+            let profile = score_admet(smiles).unwrap();
+            assert!(
+                profile.pains_alerts.iter().any(|a| a == alert),
+                "{smiles}: {:?}",
+                profile.pains_alerts
+            );
+            assert!(!profile.passes());
         }
-        if let Some(hba) = molecule.hba {
-            if hba > 10 {
-                violations += 1;
-            }
+    }
+
+    #[test]
+    fn approved_drugs_raise_no_alerts() {
+        for smiles in [
+            IMATINIB,
+            "CC(=O)Oc1ccccc1C(=O)O",
+            "CC(C)Cc1ccc(C(C)C(=O)O)cc1",
+            "CN1C(=O)CN=C(c2ccccc2)c2cc(Cl)ccc21",
+        ] {
+            assert_eq!(
+                score_admet(smiles).unwrap().pains_alerts,
+                Vec::<String>::new()
+            );
         }
+    }
+
+    #[test]
+    fn greasy_flexible_chains_break_lipinski_and_veber() {
+        let profile = score_admet("CCCCCCCCCCCCCCCCCCCCCCCCCC(=O)O").unwrap();
+        assert_eq!(profile.lipinski.violations.len(), 1);
+        assert!(profile.lipinski.violations[0].starts_with("logP"));
+        assert!(!profile.veber.passed);
+        assert!(profile.score < score_admet(IMATINIB).unwrap().score);
+    }
 
-        let qed = 1.0 - (violations as f64 * 0.2).min(0.8);
+    #[test]
+    fn embedded_alerts_all_parse() {
+        let lines = PAINS
+            .lines()
+            .filter(|l| !l.trim().is_empty() && !l.starts_with('#'))
+            .count();
+        assert_eq!(pains_patterns().len(), lines);
+    }
 
-        Ok(AdmetProperties {
-            ro5_violations: violations,
-            qed_estimate: qed,
-            mw,
-            logp,
-        })
+    #[test]
+    fn invalid_smiles_is_an_error() {
+        assert!(score_admet("C1CC").is_err());
     }
 }
@@ -0,0 +1,550 @@
+//! Candidate ligands enumerated from a target's known actives.
+//!
+//! [`enumerate_candidates`] groups ChEMBL actives by Bemis–Murcko scaffold,
+//! keeps a few structurally diverse representatives of each scaffold, and
+//! decorates their aromatic carbons with the substituents of [`R_GROUPS`]:
+//! one is added where the carbon carries a hydrogen, or swapped in for a
+//! single-atom substituent. Everything is keyed by canonical SMILES, so a
+//! variation that reproduces a known active or an earlier variation is
+//! dropped.
+
+use crate::smiles::{Bond, BondOrder, MolGraph};
+use ferrumyx_db::schema::CandidateLigandRecord;
+use ferrumyx_ingestion::sources::CompoundRecord;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::sync::OnceLock;
+use tracing::debug;
+
+/// Substituents tried at each aromatic carbon, attached through their
+/// first atom.
+pub const R_GROUPS: &[&str] = &["F", "Cl", "C", "OC", "C(F)(F)F", "C#N", "O", "N"];
+
+/// Known actives kept per scaffold as parents for enumeration.
+const REPRESENTATIVES_PER_SCAFFOLD: usize = 3;
+
+/// Bonds out from each atom hashed into the fingerprint (ECFP4).
+const FINGERPRINT_RADIUS: usize = 2;
+
+/// A ligand proposed for docking.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CandidateLigand {
+    /// Canonical SMILES.
+    pub smiles: String,
+    /// The known active this is, or was enumerated from.
+    pub parent_chembl_id: String,
+    /// Canonical SMILES of the Murcko scaffold; empty for acyclic ligands.
+    pub scaffold_id: String,
+    /// Not one of the target's known actives.
+    pub novelty: bool,
+}
+
+impl CandidateLigand {
+    /// The row stored for `target_chembl_id`.
+    pub fn record(&self, target_chembl_id: &str) -> CandidateLigandRecord {
+        CandidateLigandRecord {
+            target_chembl_id: target_chembl_id.to_string(),
+            smiles: self.smiles.clone(),
+            parent_chembl_id: self.parent_chembl_id.clone(),
+            scaffold_id: self.scaffold_id.clone(),
+            novelty: self.novelty,
+            generated_at: chrono::Utc::now(),
+        }
+    }
+}
+
+/// Up to `max_n` candidates from `actives`, which should be most potent
+/// first. The scaffold representatives come first as known actives
+/// (`novelty: false`), then their analogs, taken from each representative
+/// in turn.
+pub fn enumerate_candidates(actives: &[CompoundRecord], max_n: usize) -> Vec<CandidateLigand> {
+    let known = known_actives(actives);
+    let known_smiles: HashSet<String> = known.iter().map(|a| a.smiles.clone()).collect();
+    let clusters = cluster_by_scaffold(known);
+
+    let mut candidates = Vec::new();
+    let mut queues = Vec::new();
+    for cluster in &clusters {
+        for parent in representatives(&cluster.members, REPRESENTATIVES_PER_SCAFFOLD) {
+            candidates.push(CandidateLigand {
+                smiles: parent.smiles.clone(),
+                parent_chembl_id: parent.chembl_id.clone(),
+                scaffold_id: cluster.scaffold_id.clone(),
+                novelty: false,
+            });
+            queues.push((
+                parent,
+                &cluster.scaffold_id,
+                analogs(&parent.mol).into_iter(),
+            ));
+        }
+    }
+    candidates.truncate(max_n);
+
+    let mut seen = known_smiles;
+    while candidates.len() < max_n && !queues.is_empty() {
+        queues.retain_mut(|(parent, scaffold_id, analogs)| {
+            if candidates.len() >= max_n {
+                return true;
+            }
+            let Some(smiles) = analogs
+                .map(|analog| analog.canonical_smiles())
+                .find(|smiles| !seen.contains(smiles))
+            else {
+                return false;
+            };
+            seen.insert(smiles.clone());
+            candidates.push(CandidateLigand {
+                smiles,
+                parent_chembl_id: parent.chembl_id.clone(),
+                scaffold_id: scaffold_id.to_string(),
+                novelty: true,
+            });
+            true
+        });
+    }
+    candidates
+}
+
+/// The ring systems of `mol` and the chains linking them, with atoms
+/// double-bonded to those kept as well (so carbonyls stay), as RDKit's
+/// `MurckoScaffold` does. Removed substituents become hydrogens. `None`
+/// when `mol` has no ring.
+pub fn murcko_scaffold(mol: &MolGraph) -> Option<MolGraph> {
+    if mol.rings().is_empty() {
+        return None;
+    }
+    let n = mol.atoms.len();
+    let mut keep = vec![true; n];
+    loop {
+        let terminal: Vec<usize> = (0..n)
+            .filter(|&i| {
+                keep[i]
+                    && !mol.is_ring_atom(i)
+                    && mol.neighbors(i).filter(|&(j, _)| keep[j]).count() <= 1
+            })
+            .collect();
+        if terminal.is_empty() {
+            break;
+        }
+        for i in terminal {
+            keep[i] = false;
+        }
+    }
+    let exocyclic: Vec<usize> = (0..n)
+        .filter(|&i| {
+            !keep[i]
+                && mol.degree(i) == 1
+                && mol
+                    .neighbors(i)
+                    .any(|(j, bond)| keep[j] && bond.order == BondOrder::Double)
+        })
+        .collect();
+    for i in exocyclic {
+        keep[i] = true;
+    }
+
+    let mut index = vec![usize::MAX; n];
+    let mut atoms = Vec::new();
+    for i in (0..n).filter(|&i| keep[i]) {
+        index[i] = atoms.len();
+        let mut atom = mol.atoms[i].clone();
+        atom.hydrogens += mol
+            .neighbors(i)
+            .filter(|&(j, _)| !keep[j])
+            .map(|(_, bond)| bond.order.valence())
+            .sum::<u8>();
+        atom.chiral = false;
+        atoms.push(atom);
+    }
+    let bonds = mol
+        .bonds
+        .iter()
+        .filter(|b| b.atoms.iter().all(|&a| keep[a]))
+        .map(|b| Bond {
+            atoms: b.atoms.map(|a| index[a]),
+            order: b.order,
+        })
+        .collect();
+    Some(MolGraph::new(atoms, bonds))
+}
+
+struct Active {
+    chembl_id: String,
+    /// Canonical SMILES.
+    smiles: String,
+    /// Read back from `smiles`, so atoms are in canonical order.
+    mol: MolGraph,
+    fingerprint: Vec<u64>,
+}
+
+/// Known actives sharing a Murcko scaffold, most potent first.
+struct Cluster {
+    scaffold_id: String,
+    members: Vec<Active>,
+}
+
+/// Parseable compounds, each structure once: a repeat InChIKey (a salt or
+/// tautomer) or canonical SMILES keeps only the first, most potent, entry.
+fn known_actives(compounds: &[CompoundRecord]) -> Vec<Active> {
+    let mut inchi_keys = HashSet::new();
+    let mut smiles_seen = HashSet::new();
+    compounds
+        .iter()
+        .filter_map(|compound| {
+            let smiles = compound.smiles.as_deref()?;
+            let mol = match MolGraph::parse(smiles) {
+                Ok(mol) => mol,
+                Err(e) => {
+                    debug!("Skipping {}: {}", compound.chembl_id, e);
+                    return None;
+                }
+            };
+            let canonical = mol.canonical_smiles();
+            if compound
+                .inchi_key
+                .as_ref()
+                .is_some_and(|key| !inchi_keys.insert(key.clone()))
+                || !smiles_seen.insert(canonical.clone())
+            {
+                return None;
+            }
+            let mol = MolGraph::parse(&canonical).ok()?;
+            Some(Active {
+                chembl_id: compound.chembl_id.clone(),
+                fingerprint: fingerprint(&mol),
+                smiles: canonical,
+                mol,
+            })
+        })
+        .collect()
+}
+
+/// Clusters in order of their most potent member.
+fn cluster_by_scaffold(actives: Vec<Active>) -> Vec<Cluster> {
+    let mut clusters: Vec<Cluster> = Vec::new();
+    for active in actives {
+        let scaffold_id = murcko_scaffold(&active.mol)
+            .map(|scaffold| scaffold.canonical_smiles())
+            .unwrap_or_default();
+        match clusters.iter_mut().find(|c| c.scaffold_id == scaffold_id) {
+            Some(cluster) => cluster.members.push(active),
+            None => clusters.push(Cluster {
+                scaffold_id,
+                members: vec![active],
+            }),
+        }
+    }
+    clusters
+}
+
+/// The most potent member, then repeatedly the member least similar to
+/// any already picked (MaxMin).
+fn representatives(members: &[Active], n: usize) -> Vec<&Active> {
+    let mut picked: Vec<usize> = Vec::new();
+    while picked.len() < n.min(members.len()) {
+        let nearest = |i: usize| {
+            picked
+                .iter()
+                .map(|&p| tanimoto(&members[i].fingerprint, &members[p].fingerprint))
+                .fold(0.0, f64::max)
+        };
+        let next = (0..members.len())
+            .filter(|i| !picked.contains(i))
+            .min_by(|&a, &b| nearest(a).total_cmp(&nearest(b)))
+            .unwrap_or_default();
+        picked.push(next);
+    }
+    picked.into_iter().map(|i| &members[i]).collect()
+}
+
+/// Morgan-style circular fingerprint: the sorted, distinct hashes of every
+/// atom's environment out to [`FINGERPRINT_RADIUS`] bonds.
+fn fingerprint(mol: &MolGraph) -> Vec<u64> {
+    let mut ids: Vec<u64> = mol
+        .atoms
+        .iter()
+        .enumerate()
+        .map(|(i, a)| {
+            hash(&(
+                a.atomic_number,
+                a.aromatic,
+                a.charge,
+                a.hydrogens,
+                mol.degree(i),
+                mol.is_ring_atom(i),
+            ))
+        })
+        .collect();
+    let mut bits = ids.clone();
+    for _ in 0..FINGERPRINT_RADIUS {
+        ids = (0..ids.len())
+            .map(|i| {
+                let mut around: Vec<(u8, u64)> = mol
+                    .neighbors(i)
+                    .map(|(j, bond)| (bond.order as u8, ids[j]))
+                    .collect();
+                around.sort_unstable();
+                hash(&(ids[i], around))
+            })
+            .collect();
+        bits.extend(&ids);
+    }
+    bits.sort_unstable();
+    bits.dedup();
+    bits
+}
+
+fn hash<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Tanimoto similarity of two sorted fingerprints.
+fn tanimoto(a: &[u64], b: &[u64]) -> f64 {
+    let (mut i, mut j, mut common) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                common += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    let union = a.len() + b.len() - common;
+    if union == 0 {
+        1.0
+    } else {
+        common as f64 / union as f64
+    }
+}
+
+/// Every single R-group variation of `mol`'s aromatic carbons, in atom
+/// order. Repeats are left to the caller's canonical-SMILES check.
+fn analogs(mol: &MolGraph) -> Vec<MolGraph> {
+    let mut out = Vec::new();
+    for (site, atom) in mol.atoms.iter().enumerate() {
+        if !atom.aromatic || atom.atomic_number != 6 {
+            continue;
+        }
+        let leaving = if atom.hydrogens > 0 {
+            None
+        } else {
+            match swappable_substituent(mol, site) {
+                Some(leaving) => Some(leaving),
+                None => continue,
+            }
+        };
+        for group in r_groups() {
+            out.push(substitute(mol, site, leaving, group));
+        }
+    }
+    out
+}
+
+/// A neutral single-atom substituent on `site`, such as a methyl, hydroxyl
+/// or halogen, that an R-group may replace.
+fn swappable_substituent(mol: &MolGraph, site: usize) -> Option<usize> {
+    mol.neighbors(site)
+        .find(|&(j, bond)| {
+            let atom = &mol.atoms[j];
+            bond.order == BondOrder::Single
+                && mol.degree(j) == 1
+                && atom.charge == 0
+                && !atom.aromatic
+                && matches!(atom.atomic_number, 6..=9 | 17 | 35 | 53)
+        })
+        .map(|(j, _)| j)
+}
+
+/// `mol` with `group` bonded to `site`, in place of `leaving` or of one of
+/// the site's hydrogens.
+fn substitute(mol: &MolGraph, site: usize, leaving: Option<usize>, group: &MolGraph) -> MolGraph {
+    let mut atoms = mol.atoms.clone();
+    let mut bonds = mol.bonds.clone();
+    if leaving.is_none() {
+        atoms[site].hydrogens -= 1;
+    }
+    let offset = atoms.len();
+    atoms.extend(group.atoms.iter().cloned());
+    atoms[offset].hydrogens -= 1;
+    bonds.extend(group.bonds.iter().map(|b| Bond {
+        atoms: b.atoms.map(|a| a + offset),
+        order: b.order,
+    }));
+    bonds.push(Bond {
+        atoms: [site, offset],
+        order: BondOrder::Single,
+    });
+    if let Some(leaving) = leaving {
+        atoms.remove(leaving);
+        bonds.retain(|b| !b.atoms.contains(&leaving));
+        for bond in &mut bonds {
+            bond.atoms = bond.atoms.map(|a| if a > leaving { a - 1 } else { a });
+        }
+    }
+    MolGraph::new(atoms, bonds)
+}
+
+/// [`R_GROUPS`] parsed; each attaching atom has a hydrogen to give up.
+fn r_groups() -> &'static [MolGraph] {
+    static GROUPS: OnceLock<Vec<MolGraph>> = OnceLock::new();
+    GROUPS.get_or_init(|| {
+        R_GROUPS
+            .iter()
+            .filter_map(|smiles| MolGraph::parse(smiles).ok())
+            .filter(|group| group.atoms.first().is_some_and(|a| a.hydrogens > 0))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ACTIVES: &str = include_str!("../tests/fixtures/chembl/actives.tsv");
+
+    fn fixture() -> Vec<CompoundRecord> {
+        ACTIVES
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .map(|line| {
+                let fields: Vec<&str> = line.split('\t').collect();
+                let field = |i: usize| {
+                    fields
+                        .get(i)
+                        .filter(|f| !f.is_empty())
+                        .map(|f| f.to_string())
+                };
+                CompoundRecord {
+                    chembl_id: fields[0].to_string(),
+                    name: None,
+                    smiles: field(1),
+                    inchi_key: field(2),
+                    molecular_weight: None,
+                    alogp: None,
+                    max_phase: None,
+                    indication_class: None,
+                }
+            })
+            .collect()
+    }
+
+    fn canonical(smiles: &str) -> String {
+        MolGraph::parse(smiles).unwrap().canonical_smiles()
+    }
+
+    fn scaffold(smiles: &str) -> Option<String> {
+        murcko_scaffold(&MolGraph::parse(smiles).unwrap()).map(|s| s.canonical_smiles())
+    }
+
+    #[test]
+    fn murcko_scaffolds_keep_rings_linkers_and_ring_carbonyls() {
+        assert_eq!(scaffold("CC(=O)c1ccccc1"), Some(canonical("c1ccccc1")));
+        assert_eq!(scaffold("CCC1CCCC(=O)C1"), Some(canonical("O=C1CCCCC1")));
+        assert_eq!(scaffold("Cn1cccc1"), Some(canonical("c1cc[nH]c1")));
+        assert_eq!(
+            scaffold("Cc1ccc(NC(=O)c2ccc(CN3CCN(C)CC3)cc2)cc1Nc1nccc(-c2cccnc2)n1"),
+            Some(canonical(
+                "O=C(Nc1cccc(Nc2nccc(-c3cccnc3)n2)c1)c1ccc(CN2CCNCC2)cc1"
+            ))
+        );
+        assert_eq!(scaffold("CCCCC(=O)O"), None);
+    }
+
+    #[test]
+    fn clusters_actives_by_murcko_scaffold() {
+        let clusters = cluster_by_scaffold(known_actives(&fixture()));
+        let summary: Vec<(&str, Vec<&str>)> = clusters
+            .iter()
+            .map(|c| {
+                let ids = c.members.iter().map(|m| m.chembl_id.as_str()).collect();
+                (c.scaffold_id.as_str(), ids)
+            })
+            .collect();
+        let quinazoline = canonical("c1ccc(Nc2ncnc3ccccc23)cc1");
+        let benzene = canonical("c1ccccc1");
+        let pyridine = canonical("c1ccncc1");
+        assert_eq!(
+            summary,
+            vec![
+                (quinazoline.as_str(), vec!["TEST01", "TEST02", "TEST03"]),
+                (clusters[1].scaffold_id.as_str(), vec!["TEST04", "TEST05"]),
+                (benzene.as_str(), vec!["TEST07", "TEST08"]),
+                ("", vec!["TEST09"]),
+                (pyridine.as_str(), vec!["TEST10"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn representatives_start_with_the_most_potent_and_spread_out() {
+        let actives = known_actives(&fixture()[..3]);
+        let picked: Vec<_> = representatives(&actives, 2)
+            .iter()
+            .map(|a| a.chembl_id.as_str())
+            .collect();
+        // The fluorine TEST03 adds to TEST02 takes it further from erlotinib.
+        assert_eq!(picked, ["TEST01", "TEST03"]);
+        let erlotinib = &actives[0].fingerprint;
+        assert_eq!(tanimoto(erlotinib, erlotinib), 1.0);
+        assert!(
+            tanimoto(erlotinib, &actives[2].fingerprint)
+                < tanimoto(erlotinib, &actives[1].fingerprint)
+        );
+    }
+
+    #[test]
+    fn enumerated_analogs_are_valid_novel_and_keep_their_scaffold() {
+        let actives = fixture();
+        let known: HashSet<String> = actives
+            .iter()
+            .filter_map(|a| a.smiles.as_deref())
+            .map(canonical)
+            .collect();
+        let candidates = enumerate_candidates(&actives, 200);
+        assert_eq!(candidates.len(), 200);
+
+        let parents: Vec<_> = candidates.iter().take_while(|c| !c.novelty).collect();
+        assert_eq!(parents.len(), 9);
+        assert!(candidates[parents.len()..].iter().all(|c| c.novelty));
+
+        let mut seen = HashSet::new();
+        for candidate in &candidates {
+            assert!(seen.insert(&candidate.smiles), "{}", candidate.smiles);
+            assert_eq!(canonical(&candidate.smiles), candidate.smiles);
+            assert_eq!(known.contains(&candidate.smiles), !candidate.novelty);
+            assert_eq!(
+                scaffold(&candidate.smiles).unwrap_or_default(),
+                candidate.scaffold_id,
+                "{}",
+                candidate.smiles
+            );
+        }
+        // Each representative contributes before any contributes twice.
+        let first_round: HashSet<_> = candidates[9..17]
+            .iter()
+            .map(|c| c.parent_chembl_id.as_str())
+            .collect();
+        assert_eq!(first_round.len(), 8);
+
+        assert_eq!(enumerate_candidates(&actives, 4).len(), 4);
+        assert!(enumerate_candidates(&actives, 4).iter().all(|c| !c.novelty));
+    }
+
+    #[test]
+    fn r_groups_all_attach() {
+        assert_eq!(r_groups().len(), R_GROUPS.len());
+        let analogs = analogs(&MolGraph::parse("Cc1ccccc1").unwrap());
+        let smiles: HashSet<_> = analogs.iter().map(MolGraph::canonical_smiles).collect();
+        assert!(smiles.contains(&canonical("Cc1ccccc1F")));
+        assert!(smiles.contains(&canonical("FC(F)(F)c1ccccc1")));
+        assert!(smiles.contains(&canonical("Cc1ccc(C#N)cc1")));
+    }
+}
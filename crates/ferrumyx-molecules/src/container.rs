@@ -0,0 +1,158 @@
+//! Running the structural tools inside Docker containers.
+//!
+//! Each directory a tool reads or writes is bind-mounted at the same
+//! absolute path inside the container, so paths on the command line mean
+//! the same thing on both sides.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::process::Command;
+
+/// Command running `program` on the host, or inside `image` with each of
+/// `dirs` mounted read-write when an image is given.
+pub(crate) fn command(program: &Path, image: Option<&str>, dirs: &[&Path]) -> Command {
+    container_command(program, image, dirs, None)
+}
+
+/// [`command`] with the container called `name`, so [`remove`] can stop
+/// it if the run is abandoned.
+pub(crate) fn named_command(
+    program: &Path,
+    image: Option<&str>,
+    dirs: &[&Path],
+    name: &str,
+) -> Command {
+    container_command(program, image, dirs, Some(name))
+}
+
+fn container_command(
+    program: &Path,
+    image: Option<&str>,
+    dirs: &[&Path],
+    name: Option<&str>,
+) -> Command {
+    let Some(image) = image else {
+        return Command::new(program);
+    };
+    let mut cmd = Command::new("docker");
+    cmd.args(["run", "--rm"]);
+    if let Some(name) = name {
+        cmd.arg("--name").arg(name);
+    }
+    let mut mounted: Vec<PathBuf> = Vec::new();
+    for dir in dirs {
+        let dir = absolute(dir);
+        if !mounted.contains(&dir) {
+            cmd.arg("-v").arg(format!("{0}:{0}", dir.display()));
+            mounted.push(dir);
+        }
+    }
+    if let Some(workdir) = mounted.first() {
+        cmd.arg("-w").arg(workdir);
+    }
+    cmd.arg(image).arg(program);
+    cmd
+}
+
+/// `path` made absolute against the current directory.
+pub(crate) fn absolute(path: &Path) -> PathBuf {
+    std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Force-remove the container called `name`. Killing `docker run` leaves
+/// its container running.
+pub(crate) async fn remove(name: &str) {
+    let _ = Command::new("docker")
+        .args(["rm", "--force", name])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await;
+}
+
+/// Whether a failed `docker run` failed because its image is neither
+/// present nor pullable. Docker itself exits 125; the tool's own exit
+/// codes are passed through.
+pub(crate) fn image_missing(exit_code: Option<i32>, stderr: &str) -> bool {
+    exit_code == Some(125)
+        && (stderr.contains("Unable to find image") || stderr.contains("No such image"))
+}
+
+/// Whether a Docker daemon answers on this host.
+pub async fn docker_available() -> bool {
+    Command::new("docker")
+        .arg("info")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn docker_command_mounts_each_directory_once() {
+        let cmd = command(
+            Path::new("fpocket"),
+            Some("ferrumyx/fpocket:latest"),
+            &[Path::new("/data/structures"), Path::new("/data/structures")],
+        );
+        let args: Vec<_> = cmd
+            .as_std()
+            .get_args()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(cmd.as_std().get_program(), "docker");
+        assert_eq!(
+            args,
+            [
+                "run",
+                "--rm",
+                "-v",
+                "/data/structures:/data/structures",
+                "-w",
+                "/data/structures",
+                "ferrumyx/fpocket:latest",
+                "fpocket",
+            ]
+        );
+
+        let host = command(Path::new("fpocket"), None, &[Path::new("/data")]);
+        assert_eq!(host.as_std().get_program(), "fpocket");
+        assert_eq!(host.as_std().get_args().count(), 0);
+
+        let named = named_command(
+            Path::new("fpocket"),
+            Some("ferrumyx/fpocket:latest"),
+            &[Path::new("/data")],
+            "ferrumyx-fpocket-1",
+        );
+        let args: Vec<_> = named
+            .as_std()
+            .get_args()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(args[..4], ["run", "--rm", "--name", "ferrumyx-fpocket-1"]);
+    }
+
+    #[test]
+    fn missing_images_are_told_apart_from_tool_failures() {
+        let pull_failed = "Unable to find image 'ferrumyx/fpocket:latest' locally\n\
+                           docker: Error response from daemon: pull access denied for ferrumyx/fpocket.";
+        assert!(image_missing(Some(125), pull_failed));
+        assert!(image_missing(
+            Some(125),
+            "Error response from daemon: No such image: ferrumyx/fpocket:latest"
+        ));
+        // Pulled, then the tool itself failed.
+        assert!(!image_missing(Some(1), pull_failed));
+        assert!(!image_missing(
+            Some(125),
+            "docker: invalid reference format."
+        ));
+    }
+}
@@ -0,0 +1,597 @@
+//! Physicochemical descriptors computed natively from a [`MolGraph`].
+//!
+//! LogP uses the Wildman–Crippen atom contributions (J. Chem. Inf. Comput.
+//! Sci. 1999, 39, 868) and TPSA the Ertl polar fragment values (J. Med.
+//! Chem. 2000, 43, 3714) over N and O, which are the same tables RDKit's
+//! `MolLogP` and `TPSA` use, so values agree with the rdkit-service for
+//! molecules written in aromatic form. HBD and HBA are Lipinski's own
+//! NH/OH and N/O counts.
+
+use crate::smiles::{BondOrder, MolGraph};
+use serde::{Deserialize, Serialize};
+
+/// Descriptors of one molecule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Descriptors {
+    /// Average molecular weight, Da.
+    pub mw: f64,
+    /// Wildman–Crippen logP.
+    pub logp: f64,
+    /// Hydrogens on N and O.
+    pub hbd: u32,
+    /// N and O atoms.
+    pub hba: u32,
+    /// Topological polar surface area, Å².
+    pub tpsa: f64,
+    pub rotatable_bonds: u32,
+    pub heavy_atoms: u32,
+    pub rings: u32,
+    pub aromatic_rings: u32,
+    /// Atoms written with `@` or `@@`.
+    pub stereocenters: u32,
+    /// Atoms shared by two rings that meet only there.
+    pub spiro_atoms: u32,
+    /// Ends of the shared path of two bridged rings.
+    pub bridgehead_atoms: u32,
+    /// Size of the largest ring, 0 when acyclic.
+    pub largest_ring: u32,
+}
+
+impl Descriptors {
+    pub fn of(mol: &MolGraph) -> Self {
+        let hydrogens: u32 = mol.atoms.iter().map(|a| u32::from(a.hydrogens)).sum();
+        let mw = mol
+            .atoms
+            .iter()
+            .map(|a| atomic_weight(a.atomic_number))
+            .sum::<f64>()
+            + f64::from(hydrogens) * atomic_weight(1);
+        let logp = (0..mol.atoms.len())
+            .map(|i| {
+                crippen_heavy(mol, i) + f64::from(mol.atoms[i].hydrogens) * crippen_hydrogen(mol, i)
+            })
+            .sum();
+        let polar = |i: &usize| matches!(mol.atoms[*i].atomic_number, 7 | 8);
+        let hbd = (0..mol.atoms.len())
+            .filter(polar)
+            .map(|i| u32::from(mol.atoms[i].hydrogens))
+            .sum();
+        let hba = (0..mol.atoms.len()).filter(polar).count() as u32;
+        let tpsa = (0..mol.atoms.len())
+            .map(|i| tpsa_contribution(mol, i))
+            .sum();
+        let rings = mol.rings();
+        let aromatic_rings = rings
+            .iter()
+            .filter(|r| r.iter().all(|&a| mol.atoms[a].aromatic))
+            .count() as u32;
+        let (spiro_atoms, bridgehead_atoms) = spiro_and_bridgeheads(mol);
+
+        Self {
+            mw,
+            logp,
+            hbd,
+            hba,
+            tpsa,
+            rotatable_bonds: rotatable_bonds(mol),
+            heavy_atoms: mol.atoms.iter().filter(|a| a.atomic_number > 1).count() as u32,
+            rings: rings.len() as u32,
+            aromatic_rings,
+            stereocenters: mol.atoms.iter().filter(|a| a.chiral).count() as u32,
+            spiro_atoms,
+            bridgehead_atoms,
+            largest_ring: rings.iter().map(Vec::len).max().unwrap_or(0) as u32,
+        }
+    }
+}
+
+fn atomic_weight(atomic_number: u8) -> f64 {
+    match atomic_number {
+        1 => 1.008,
+        5 => 10.81,
+        6 => 12.011,
+        7 => 14.007,
+        8 => 15.999,
+        9 => 18.998,
+        11 => 22.990,
+        14 => 28.085,
+        15 => 30.974,
+        16 => 32.06,
+        17 => 35.453,
+        19 => 39.098,
+        34 => 78.971,
+        35 => 79.904,
+        53 => 126.904,
+        _ => 0.0,
+    }
+}
+
+/// Single, non-ring bonds between non-terminal heavy atoms, leaving out
+/// bonds next to a triple bond and amide C–N bonds.
+fn rotatable_bonds(mol: &MolGraph) -> u32 {
+    let has_triple = |a: usize| mol.neighbors(a).any(|(_, b)| b.order == BondOrder::Triple);
+    let is_carbonyl = |a: usize| {
+        mol.atoms[a].atomic_number == 6
+            && mol.neighbors(a).any(|(n, b)| {
+                b.order == BondOrder::Double && matches!(mol.atoms[n].atomic_number, 8 | 16)
+            })
+    };
+    let is_amide = |a: usize, b: usize| {
+        let nitrogen = |x: usize| mol.atoms[x].atomic_number == 7 && !mol.atoms[x].aromatic;
+        (nitrogen(a) && is_carbonyl(b)) || (nitrogen(b) && is_carbonyl(a))
+    };
+    mol.bonds
+        .iter()
+        .enumerate()
+        .filter(|&(i, bond)| {
+            let [a, b] = bond.atoms;
+            bond.order == BondOrder::Single
+                && !mol.is_ring_bond(i)
+                && mol.degree(a) > 1
+                && mol.degree(b) > 1
+                && !has_triple(a)
+                && !has_triple(b)
+                && !is_amide(a, b)
+        })
+        .count() as u32
+}
+
+fn spiro_and_bridgeheads(mol: &MolGraph) -> (u32, u32) {
+    let rings = mol.rings();
+    let mut spiro = vec![false; mol.atoms.len()];
+    let mut bridgehead = vec![false; mol.atoms.len()];
+    for (i, first) in rings.iter().enumerate() {
+        for second in &rings[i + 1..] {
+            let shared: Vec<usize> = first
+                .iter()
+                .copied()
+                .filter(|a| second.contains(a))
+                .collect();
+            match shared.len() {
+                1 => spiro[shared[0]] = true,
+                len if len > 2 => {
+                    // The path ends are the shared atoms with neighbours on
+                    // both sides.
+                    for &atom in &shared {
+                        let outside = |ring: &Vec<usize>| {
+                            mol.neighbors(atom)
+                                .any(|(n, _)| ring.contains(&n) && !shared.contains(&n))
+                        };
+                        if outside(first) && outside(second) {
+                            bridgehead[atom] = true;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    let count = |flags: Vec<bool>| flags.into_iter().filter(|&f| f).count() as u32;
+    (count(spiro), count(bridgehead))
+}
+
+/// Element-level facts about one atom's neighbourhood.
+struct Environment<'a> {
+    mol: &'a MolGraph,
+    atom: usize,
+}
+
+impl Environment<'_> {
+    fn neighbors(&self) -> impl Iterator<Item = (usize, BondOrder)> + '_ {
+        self.mol.neighbors(self.atom).map(|(n, b)| (n, b.order))
+    }
+
+    fn element(&self, atom: usize) -> u8 {
+        self.mol.atoms[atom].atomic_number
+    }
+
+    fn aromatic(&self, atom: usize) -> bool {
+        self.mol.atoms[atom].aromatic
+    }
+
+    fn hydrogens(&self) -> u8 {
+        self.mol.atoms[self.atom].hydrogens
+    }
+
+    fn degree(&self) -> usize {
+        self.mol.degree(self.atom)
+    }
+
+    /// Total connections, hydrogens included (SMARTS `X`).
+    fn connections(&self) -> usize {
+        self.degree() + usize::from(self.hydrogens())
+    }
+
+    fn any(&self, f: impl Fn(usize, BondOrder) -> bool) -> bool {
+        self.neighbors().any(|(n, o)| f(n, o))
+    }
+
+    fn all(&self, f: impl Fn(usize, BondOrder) -> bool) -> bool {
+        self.neighbors().all(|(n, o)| f(n, o))
+    }
+
+    fn count(&self, order: BondOrder) -> usize {
+        self.neighbors().filter(|&(_, o)| o == order).count()
+    }
+
+    /// Aliphatic N, O, P, S or halogen.
+    fn heteroatom(&self, atom: usize) -> bool {
+        !self.aromatic(atom) && matches!(self.element(atom), 7 | 8 | 9 | 15 | 16 | 17 | 35 | 53)
+    }
+
+    fn common_element(&self, atom: usize) -> bool {
+        matches!(self.element(atom), 6 | 7 | 8 | 9 | 15 | 16 | 17 | 35 | 53)
+    }
+}
+
+/// Wildman–Crippen contribution of a heavy atom, hydrogens excluded.
+fn crippen_heavy(mol: &MolGraph, atom: usize) -> f64 {
+    let env = Environment { mol, atom };
+    let a = &mol.atoms[atom];
+    match a.atomic_number {
+        6 if a.aromatic => crippen_aromatic_carbon(&env),
+        6 => crippen_carbon(&env),
+        7 => crippen_nitrogen(&env),
+        8 => crippen_oxygen(&env),
+        9 | 17 | 35 | 53 if a.charge < 0 => -2.996,
+        9 => 0.4202,
+        17 => 0.6895,
+        35 => 0.8456,
+        53 => 0.8857,
+        16 if a.aromatic => 0.6237,
+        16 if a.charge != 0 => -0.0024,
+        16 => 0.6482,
+        15 => 0.8612,
+        _ => 0.0,
+    }
+}
+
+fn crippen_carbon(env: &Environment) -> f64 {
+    let h = env.hydrogens();
+    let x4 = env.connections() == 4;
+    let aliphatic_carbon = |n: usize| env.element(n) == 6 && !env.aromatic(n);
+    if env.all(|_, o| o == BondOrder::Single) {
+        if h == 4 || (env.degree() > 0 && env.all(|n, _| aliphatic_carbon(n))) {
+            // C1: CH4, CH3R, CH2R2; C2: CHR3, CR4.
+            return if h >= 2 { 0.1441 } else { 0.0 };
+        }
+        if x4 && env.any(|n, _| env.heteroatom(n)) && env.all(|n, _| !env.aromatic(n)) {
+            return if h >= 2 {
+                -0.2035 // C3
+            } else {
+                -0.2051 // C4
+            };
+        }
+    }
+    if env.any(|n, o| o == BondOrder::Double && !env.aromatic(n) && env.element(n) != 6) {
+        return -0.2783; // C5
+    }
+    let alkene = env.any(|n, o| o == BondOrder::Double && aliphatic_carbon(n));
+    if alkene && env.all(|n, _| !env.aromatic(n)) {
+        return 0.1551; // C6
+    }
+    if env.connections() == 2 && env.any(|n, o| o == BondOrder::Triple && !env.aromatic(n)) {
+        return 0.0017; // C7
+    }
+    if h == 3 && env.any(|n, _| env.aromatic(n)) {
+        return if env.any(|n, _| env.element(n) == 6) {
+            0.08452 // C8
+        } else {
+            -0.1444 // C9
+        };
+    }
+    if x4 && env.any(|n, _| env.aromatic(n)) {
+        return match h {
+            2 => -0.0516, // C10
+            1 => 0.1193,  // C11
+            _ => -0.0967, // C12
+        };
+    }
+    if h == 3 && env.all(|n, _| !env.common_element(n)) {
+        return -0.5443; // C13
+    }
+    if alkene || env.any(|n, o| o == BondOrder::Double && env.aromatic(n)) {
+        return 0.2640; // C26
+    }
+    if x4 && env.any(|n, _| !env.aromatic(n) && !env.common_element(n)) {
+        return 0.2148; // C27
+    }
+    0.08129
+}
+
+fn crippen_aromatic_carbon(env: &Environment) -> f64 {
+    for (n, _) in env.neighbors() {
+        match env.element(n) {
+            9 => return 0.0,
+            17 => return 0.2450,
+            35 => return 0.1980,
+            53 => return 0.0,
+            _ => {}
+        }
+    }
+    if env.hydrogens() > 0 {
+        return 0.1581; // C18
+    }
+    if env.count(BondOrder::Aromatic) < 2 {
+        return 0.08129;
+    }
+    if env.count(BondOrder::Aromatic) >= 3 {
+        return 0.2955; // C19
+    }
+    let Some((n, order)) = env.neighbors().find(|&(_, o)| o != BondOrder::Aromatic) else {
+        return 0.08129;
+    };
+    match (order, env.aromatic(n), env.element(n)) {
+        (BondOrder::Single, true, _) => 0.2713,       // C20
+        (BondOrder::Single, false, 6) => 0.1360,      // C21
+        (BondOrder::Single, false, 7) => 0.4619,      // C22
+        (BondOrder::Single, false, 8) => 0.5437,      // C23
+        (BondOrder::Single, false, 16) => 0.1893,     // C24
+        (BondOrder::Double, false, 6..=8) => -0.8186, // C25
+        _ => 0.08129,
+    }
+}
+
+fn crippen_nitrogen(env: &Environment) -> f64 {
+    let charge = env.mol.atoms[env.atom].charge;
+    let h = env.hydrogens();
+    if env.aromatic(env.atom) {
+        return if charge == 0 { -0.3239 } else { -1.119 };
+    }
+    if charge > 0 {
+        if h > 0 {
+            return -1.950; // N10
+        }
+        let quaternary = env.degree() == 4;
+        let oxide = env.degree() == 3 && env.count(BondOrder::Double) == 1;
+        let triple = env.count(BondOrder::Triple) > 0;
+        return if quaternary || oxide || triple {
+            -0.3396 // N13
+        } else {
+            0.2887 // N14
+        };
+    }
+    if charge < 0 {
+        return -0.3396;
+    }
+    let any_aromatic = env.any(|n, _| env.aromatic(n));
+    if env.count(BondOrder::Triple) > 0 {
+        return 0.01508; // N9
+    }
+    match h {
+        2 if env.degree() == 1 => {
+            if any_aromatic {
+                -1.027 // N3
+            } else {
+                -1.019 // N1
+            }
+        }
+        1 if env.count(BondOrder::Double) > 0 => 0.08387, // N5
+        1 if env.degree() == 2 => {
+            if any_aromatic {
+                -0.5188 // N4
+            } else {
+                -0.7096 // N2
+            }
+        }
+        0 if env.count(BondOrder::Double) > 0 => 0.1836, // N6
+        0 if env.degree() == 3 => {
+            if any_aromatic {
+                -0.4458 // N8
+            } else {
+                -0.3187 // N7
+            }
+        }
+        _ => -0.4806,
+    }
+}
+
+fn crippen_oxygen(env: &Environment) -> f64 {
+    let charge = env.mol.atoms[env.atom].charge;
+    if env.aromatic(env.atom) {
+        return 0.1552; // O1
+    }
+    if env.hydrogens() > 0 {
+        return -0.2893; // O2
+    }
+    if env.degree() == 2 && env.all(|_, o| o == BondOrder::Single) {
+        return if env.any(|n, _| env.aromatic(n)) {
+            -0.4195 // O4
+        } else {
+            -0.0684 // O3
+        };
+    }
+    let Some((partner, order)) = env.neighbors().next() else {
+        return -0.1188;
+    };
+    let partner_element = env.element(partner);
+    if charge < 0 {
+        let carboxylate = partner_element == 6
+            && env
+                .mol
+                .neighbors(partner)
+                .any(|(n, b)| n != env.atom && b.order == BondOrder::Double && env.element(n) == 8);
+        return match partner_element {
+            7 => 0.0335,                // O5
+            16 => -0.3339,              // O6
+            _ if carboxylate => -1.326, // O12
+            _ => -1.189,                // O7
+        };
+    }
+    if order != BondOrder::Double {
+        return -0.1188;
+    }
+    if matches!(partner_element, 7 | 8) {
+        return 0.0335; // O5
+    }
+    if partner_element != 6 {
+        return -0.1188;
+    }
+    if env.aromatic(partner) {
+        return 0.1788; // O8
+    }
+    let partner_env = Environment {
+        mol: env.mol,
+        atom: partner,
+    };
+    let substituents: Vec<usize> = partner_env
+        .neighbors()
+        .map(|(n, _)| n)
+        .filter(|&n| n != env.atom)
+        .collect();
+    let aliphatic = |n: &usize| !env.aromatic(*n);
+    let carbon = |n: &usize| env.element(*n) == 6;
+    if substituents.iter().any(|n| carbon(n) && aliphatic(n)) && substituents.iter().all(aliphatic)
+        || partner_env.hydrogens() > 0 && substituents.iter().all(aliphatic)
+    {
+        return -0.1526; // O9
+    }
+    if substituents.iter().any(|n| env.aromatic(*n)) && substituents.iter().any(carbon) {
+        return 0.1129; // O10
+    }
+    if substituents.len() >= 2 && !substituents.iter().any(carbon) {
+        return 0.4833; // O11
+    }
+    -0.1188
+}
+
+/// Wildman–Crippen contribution of each hydrogen on `atom`.
+fn crippen_hydrogen(mol: &MolGraph, atom: usize) -> f64 {
+    let env = Environment { mol, atom };
+    match mol.atoms[atom].atomic_number {
+        6 => 0.1230, // H1
+        7 => 0.2142, // H3
+        8 => {
+            let Some((n, _)) = env.neighbors().next() else {
+                return 0.1125;
+            };
+            let partner = Environment { mol, atom: n };
+            match env.element(n) {
+                6 if env.aromatic(n) || partner.connections() == 4 => -0.2677, // H2
+                7 => 0.2142,                                                   // H3
+                8 | 16 => 0.2980,                                              // H4
+                6 if partner.any(|m, o| {
+                    o == BondOrder::Double && matches!(env.element(m), 6 | 7 | 8 | 16)
+                }) =>
+                {
+                    0.2980 // H4, acids and enols
+                }
+                6 => 0.1125,
+                _ => -0.2677,
+            }
+        }
+        _ => -0.2677,
+    }
+}
+
+/// Ertl polar surface contribution of an N or O atom.
+fn tpsa_contribution(mol: &MolGraph, atom: usize) -> f64 {
+    let a = &mol.atoms[atom];
+    let env = Environment { mol, atom };
+    let single = env.count(BondOrder::Single);
+    let double = env.count(BondOrder::Double);
+    let triple = env.count(BondOrder::Triple);
+    let aromatic = env.count(BondOrder::Aromatic);
+    let h = a.hydrogens;
+    let in_three_ring = mol.rings_containing(atom).any(|r| r.len() == 3);
+    match (a.atomic_number, a.aromatic, a.charge) {
+        (7, false, 0) => match (h, single, double, triple) {
+            (0, 3, 0, 0) if in_three_ring => 3.01,
+            (0, 3, 0, 0) => 3.24,
+            (0, 1, 1, 0) => 12.36,
+            (0, 0, 0, 1) => 23.79,
+            (0, 1, 2, 0) => 11.68,
+            (0, 0, 1, 1) | (0, 0, 2, 0) => 13.60,
+            (1, 2, 0, 0) if in_three_ring => 21.94,
+            (1, 2, 0, 0) => 12.03,
+            (1, 0, 1, 0) => 23.85,
+            (2, 1, 0, 0) => 26.02,
+            _ => 0.0,
+        },
+        (7, false, 1) => match (h, single, double, triple) {
+            (0, 4, 0, 0) => 0.0,
+            (0, 2, 1, 0) => 3.01,
+            (0, 1, 0, 1) => 4.36,
+            (1, 3, 0, 0) => 4.44,
+            (1, 1, 1, 0) => 13.97,
+            (2, 2, 0, 0) => 16.61,
+            (2, 0, 1, 0) => 25.59,
+            (3, 1, 0, 0) => 27.64,
+            _ => 0.0,
+        },
+        (7, true, 0) => match (h, single, double, aromatic) {
+            (0, 0, 0, 2) => 12.89,
+            (0, 0, 0, 3) => 4.41,
+            (0, 1, 0, 2) => 4.93,
+            (0, 0, 1, 2) => 8.39,
+            (1, 0, 0, 2) => 15.79,
+            _ => 0.0,
+        },
+        (7, true, 1) => match (h, single, aromatic) {
+            (0, 0, 3) => 4.10,
+            (0, 1, 2) => 3.88,
+            (1, 0, 2) => 14.14,
+            _ => 0.0,
+        },
+        (8, false, 0) => match (h, single, double) {
+            (0, 2, 0) if in_three_ring => 12.53,
+            (0, 2, 0) => 9.23,
+            (0, 0, 1) => 17.07,
+            (1, 1, 0) => 20.23,
+            _ => 0.0,
+        },
+        (8, false, -1) if single == 1 => 23.06,
+        (8, true, 0) if aromatic == 2 => 13.14,
+        _ => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn descriptors(smiles: &str) -> Descriptors {
+        Descriptors::of(&MolGraph::parse(smiles).unwrap())
+    }
+
+    #[test]
+    fn imatinib_matches_rdkit() {
+        let d = descriptors("Cc1ccc(NC(=O)c2ccc(CN3CCN(C)CC3)cc2)cc1Nc1nccc(-c2cccnc2)n1");
+        assert!((d.mw - 493.615).abs() < 0.01, "mw {}", d.mw);
+        assert!((d.logp - 4.5903).abs() < 1e-3, "logp {}", d.logp);
+        assert!((d.tpsa - 86.28).abs() < 1e-6, "tpsa {}", d.tpsa);
+        assert_eq!((d.hbd, d.hba), (2, 8));
+        assert_eq!(d.rotatable_bonds, 7);
+        assert_eq!((d.heavy_atoms, d.rings, d.aromatic_rings), (37, 5, 4));
+    }
+
+    #[test]
+    fn crippen_logp_matches_reference_values() {
+        for (smiles, logp) in [
+            ("c1ccccc1", 1.6866),
+            ("c1ccncc1", 1.0816),
+            ("CC(=O)Oc1ccccc1C(=O)O", 1.3101),
+            ("CCO", -0.0014),
+        ] {
+            let d = descriptors(smiles);
+            assert!((d.logp - logp).abs() < 1e-3, "{smiles}: {}", d.logp);
+        }
+    }
+
+    #[test]
+    fn counts_ring_topology() {
+        let spiro = descriptors("C1CCC2(CC1)CCCC2");
+        assert_eq!(
+            (spiro.rings, spiro.spiro_atoms, spiro.bridgehead_atoms),
+            (2, 1, 0)
+        );
+        let norbornane = descriptors("C1CC2CCC1C2");
+        assert_eq!(
+            (norbornane.spiro_atoms, norbornane.bridgehead_atoms),
+            (0, 2)
+        );
+        let macrocycle = descriptors("C1CCCCCCCCCCC1");
+        assert_eq!(macrocycle.largest_ring, 12);
+        assert_eq!(descriptors("C[C@H](N)C(=O)O").stereocenters, 1);
+    }
+}
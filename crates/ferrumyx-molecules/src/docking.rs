@@ -1,9 +1,29 @@
 //! Molecular docking using AutoDock Vina.
+//!
+//! [`DockingBatch`] docks a list of ligands into one fpocket [`Pocket`].
+//! The receptor is prepared once, or used as-is when it is already PDBQT.
+//! Each ligand gets a 3D conformer and PDBQT from a [`MoleculePreparer`],
+//! and Vina searches the pocket's box, a few ligands at a time. A ligand
+//! that fails to prepare or dock keeps its error in its [`LigandDocking`]
+//! and the rest of the batch carries on.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use ferrumyx_db::schema::{DockedPose, DockingResultRecord};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
-use tokio::process::Command;
-use tracing::{debug, info};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+use crate::container;
+use crate::pocket::Pocket;
+
+/// Ligands [`DockingBatch`] docks at once unless told otherwise. Vina
+/// already uses every core for one ligand, so more mostly adds memory.
+pub const DEFAULT_DOCKING_PARALLELISM: usize = 2;
 
 /// Configuration for a docking run.
 #[derive(Debug, Clone)]
@@ -20,9 +40,51 @@ pub struct DockingConfig {
     pub out: PathBuf,
 }
 
+impl DockingConfig {
+    /// Search box centred on `pocket` and sized to its box.
+    pub fn for_pocket(
+        receptor: PathBuf,
+        ligand: PathBuf,
+        pocket: &Pocket,
+        exhaustiveness: u32,
+        out: PathBuf,
+    ) -> Self {
+        let [center_x, center_y, center_z] = pocket.center;
+        let [size_x, size_y, size_z] = pocket.size;
+        Self {
+            receptor,
+            ligand,
+            center_x,
+            center_y,
+            center_z,
+            size_x,
+            size_y,
+            size_z,
+            exhaustiveness,
+            out,
+        }
+    }
+}
+
+/// One binding mode from a Vina output file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DockingPose {
+    pub ligand_id: String,
+    /// 1 is Vina's best mode.
+    pub mode: u32,
+    /// Predicted binding affinity, kcal/mol; lower binds tighter.
+    pub affinity_kcal: f64,
+    /// RMSD lower and upper bounds from the best mode, Å.
+    pub rmsd_lb: f64,
+    pub rmsd_ub: f64,
+    /// The mode's `MODEL` … `ENDMDL` block.
+    pub pose_pdbqt: String,
+}
+
 /// Wrapper for AutoDock Vina execution.
 pub struct VinaRunner {
     executable_path: PathBuf,
+    docker_image: Option<String>,
 }
 
 impl VinaRunner {
@@ -30,18 +92,32 @@ impl VinaRunner {
     pub fn new<P: AsRef<Path>>(executable_path: P) -> Self {
         Self {
             executable_path: executable_path.as_ref().to_path_buf(),
+            docker_image: None,
         }
     }
 
+    /// Run the executable inside `image` instead of on the host.
+    pub fn in_docker(mut self, image: impl Into<String>) -> Self {
+        self.docker_image = Some(image.into());
+        self
+    }
+
     /// Run AutoDock Vina with the given configuration.
     pub async fn run(&self, config: &DockingConfig) -> Result<PathBuf> {
         info!("Running AutoDock Vina on {:?}", config.ligand);
 
-        let output = Command::new(&self.executable_path)
+        let receptor = container::absolute(&config.receptor);
+        let ligand = container::absolute(&config.ligand);
+        let out = container::absolute(&config.out);
+        let dirs: Vec<&Path> = [&receptor, &ligand, &out]
+            .into_iter()
+            .filter_map(|p| p.parent())
+            .collect();
+        let output = container::command(&self.executable_path, self.docker_image.as_deref(), &dirs)
             .arg("--receptor")
-            .arg(&config.receptor)
+            .arg(&receptor)
             .arg("--ligand")
-            .arg(&config.ligand)
+            .arg(&ligand)
             .arg("--center_x")
             .arg(config.center_x.to_string())
             .arg("--center_y")
@@ -57,7 +133,7 @@ impl VinaRunner {
             .arg("--exhaustiveness")
             .arg(config.exhaustiveness.to_string())
             .arg("--out")
-            .arg(&config.out)
+            .arg(&out)
             .output()
             .await?;
 
@@ -73,3 +149,701 @@ impl VinaRunner {
         Ok(config.out.clone())
     }
 }
+
+/// Turns structures and SMILES into the PDBQT files Vina docks.
+#[async_trait]
+pub trait MoleculePreparer: Send + Sync {
+    /// Rigid receptor PDBQT for the structure at `pdb_path`.
+    async fn receptor_pdbqt(&self, pdb_path: &Path) -> Result<PathBuf>;
+
+    /// PDBQT of one 3D conformer of `smiles`, written to `out`.
+    async fn ligand_pdbqt(&self, smiles: &str, out: &Path) -> Result<PathBuf>;
+}
+
+/// Open Babel conversions Vina needs: receptor and ligands as PDBQT.
+pub struct OpenBabel {
+    executable_path: PathBuf,
+    docker_image: Option<String>,
+}
+
+impl OpenBabel {
+    pub fn new<P: AsRef<Path>>(executable_path: P) -> Self {
+        Self {
+            executable_path: executable_path.as_ref().to_path_buf(),
+            docker_image: None,
+        }
+    }
+
+    /// Run the executable inside `image` instead of on the host.
+    pub fn in_docker(mut self, image: impl Into<String>) -> Self {
+        self.docker_image = Some(image.into());
+        self
+    }
+
+    /// Rigid receptor PDBQT next to `pdb_path`, with hydrogens added,
+    /// reused when already converted.
+    pub async fn receptor_pdbqt(&self, pdb_path: &Path) -> Result<PathBuf> {
+        let pdb_path = container::absolute(pdb_path);
+        let out = pdb_path.with_extension("pdbqt");
+        if out.exists() {
+            return Ok(out);
+        }
+        self.convert(&[
+            pdb_path.as_os_str(),
+            OsStr::new("-xr"),
+            OsStr::new("-h"),
+            OsStr::new("-O"),
+            out.as_os_str(),
+        ])
+        .await?;
+        Ok(out)
+    }
+
+    /// 3D ligand PDBQT for `smiles` written to `out`.
+    pub async fn ligand_pdbqt(&self, smiles: &str, out: &Path) -> Result<PathBuf> {
+        let out = container::absolute(out);
+        let input = format!("-:{}", smiles);
+        // A file left by an earlier run would hide a failed conversion.
+        let _ = tokio::fs::remove_file(&out).await;
+        self.convert(&[
+            OsStr::new(&input),
+            OsStr::new("--gen3d"),
+            OsStr::new("-h"),
+            OsStr::new("-opdbqt"),
+            OsStr::new("-O"),
+            out.as_os_str(),
+        ])
+        .await?;
+        Ok(out)
+    }
+
+    async fn convert(&self, args: &[&OsStr]) -> Result<()> {
+        let out = args.last().map(Path::new).unwrap_or(Path::new("."));
+        // obabel exits 0 on some conversion failures, so the output is
+        // checked as well as the exit status.
+        prepare(
+            "Open Babel",
+            &self.executable_path,
+            self.docker_image.as_deref(),
+            args,
+            out,
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl MoleculePreparer for OpenBabel {
+    async fn receptor_pdbqt(&self, pdb_path: &Path) -> Result<PathBuf> {
+        OpenBabel::receptor_pdbqt(self, pdb_path).await
+    }
+
+    async fn ligand_pdbqt(&self, smiles: &str, out: &Path) -> Result<PathBuf> {
+        OpenBabel::ligand_pdbqt(self, smiles, out).await
+    }
+}
+
+/// The rdkit-service image's `rdkit-prep` command, which prepares with
+/// RDKit and Meeko rather than Open Babel:
+///
+/// - `rdkit-prep ligand --smiles <SMILES> --out <file.pdbqt>` protonates
+///   the molecule, embeds one ETKDG conformer, minimises it with MMFF and
+///   writes it with its rotatable bonds;
+/// - `rdkit-prep receptor --pdb <file.pdb> --out <file.pdbqt>` adds polar
+///   hydrogens and charges and writes a rigid receptor.
+///
+/// Either exits non-zero, without writing `--out`, when it cannot.
+pub struct RdkitService {
+    executable_path: PathBuf,
+    docker_image: Option<String>,
+}
+
+impl RdkitService {
+    pub fn new<P: AsRef<Path>>(executable_path: P) -> Self {
+        Self {
+            executable_path: executable_path.as_ref().to_path_buf(),
+            docker_image: None,
+        }
+    }
+
+    /// Run the executable inside `image` instead of on the host.
+    pub fn in_docker(mut self, image: impl Into<String>) -> Self {
+        self.docker_image = Some(image.into());
+        self
+    }
+}
+
+#[async_trait]
+impl MoleculePreparer for RdkitService {
+    /// Rigid receptor PDBQT next to `pdb_path`, reused when already
+    /// prepared.
+    async fn receptor_pdbqt(&self, pdb_path: &Path) -> Result<PathBuf> {
+        let pdb_path = container::absolute(pdb_path);
+        let out = pdb_path.with_extension("pdbqt");
+        if out.exists() {
+            return Ok(out);
+        }
+        let args = [
+            OsStr::new("receptor"),
+            OsStr::new("--pdb"),
+            pdb_path.as_os_str(),
+            OsStr::new("--out"),
+            out.as_os_str(),
+        ];
+        // The output sits next to the structure, so one mount covers both.
+        prepare(
+            "rdkit-prep",
+            &self.executable_path,
+            self.docker_image.as_deref(),
+            &args,
+            &out,
+        )
+        .await?;
+        Ok(out)
+    }
+
+    async fn ligand_pdbqt(&self, smiles: &str, out: &Path) -> Result<PathBuf> {
+        let out = container::absolute(out);
+        let _ = tokio::fs::remove_file(&out).await;
+        let args = [
+            OsStr::new("ligand"),
+            OsStr::new("--smiles"),
+            OsStr::new(smiles),
+            OsStr::new("--out"),
+            out.as_os_str(),
+        ];
+        prepare(
+            "rdkit-prep",
+            &self.executable_path,
+            self.docker_image.as_deref(),
+            &args,
+            &out,
+        )
+        .await?;
+        Ok(out)
+    }
+}
+
+/// Run a preparation command that writes `out`, with `out`'s directory
+/// mounted.
+async fn prepare(
+    tool: &str,
+    program: &Path,
+    image: Option<&str>,
+    args: &[&OsStr],
+    out: &Path,
+) -> Result<()> {
+    let dir = out.parent().unwrap_or(Path::new("/"));
+    let output = container::command(program, image, &[dir])
+        .args(args)
+        .output()
+        .await?;
+    if !output.status.success() || !out.exists() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("{} conversion to {:?} failed: {}", tool, out, stderr);
+    }
+    Ok(())
+}
+
+/// The receptor of a docking batch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReceptorInput {
+    /// Already prepared for Vina; used as-is.
+    Pdbqt(PathBuf),
+    /// A PDB structure, prepared by the batch's [`MoleculePreparer`].
+    Structure(PathBuf),
+}
+
+impl ReceptorInput {
+    /// [`Pdbqt`](Self::Pdbqt) for a `.pdbqt` file, otherwise
+    /// [`Structure`](Self::Structure).
+    pub fn from_path(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let is_pdbqt = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("pdbqt"));
+        if is_pdbqt {
+            Self::Pdbqt(path)
+        } else {
+            Self::Structure(path)
+        }
+    }
+}
+
+/// A ligand to dock.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LigandInput {
+    /// Identifies the ligand in results and in the docking_results table.
+    pub id: String,
+    pub smiles: String,
+}
+
+impl LigandInput {
+    pub fn new(id: impl Into<String>, smiles: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            smiles: smiles.into(),
+        }
+    }
+}
+
+/// How one ligand of a batch docked.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LigandDocking {
+    pub ligand_id: String,
+    pub smiles: String,
+    /// Best first; empty when docking failed.
+    pub poses: Vec<DockingPose>,
+    /// Vina's output PDBQT.
+    pub pose_file: Option<PathBuf>,
+    /// Why preparation or docking failed, if it did.
+    pub error: Option<String>,
+}
+
+impl LigandDocking {
+    pub fn best_affinity_kcal(&self) -> Option<f64> {
+        self.poses.first().map(|p| p.affinity_kcal)
+    }
+}
+
+/// Every ligand of one [`DockingBatch::dock`] call, in the order given.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DockingRun {
+    /// The PDBQT the ligands were docked against.
+    pub receptor: PathBuf,
+    pub pocket_rank: u32,
+    pub results: Vec<LigandDocking>,
+}
+
+impl DockingRun {
+    /// Rows for the docking_results table, with `target` naming the
+    /// receptor's gene or structure.
+    pub fn records(&self, target: &str) -> Vec<DockingResultRecord> {
+        let docked_at = Utc::now();
+        let structure = self
+            .receptor
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        self.results
+            .iter()
+            .map(|result| DockingResultRecord {
+                target: target.to_string(),
+                pocket_rank: self.pocket_rank,
+                ligand_id: result.ligand_id.clone(),
+                smiles: result.smiles.clone(),
+                structure: structure.clone(),
+                best_affinity_kcal: result.best_affinity_kcal(),
+                poses: result
+                    .poses
+                    .iter()
+                    .map(|p| DockedPose {
+                        mode: p.mode,
+                        affinity_kcal: p.affinity_kcal,
+                        rmsd_lb: p.rmsd_lb,
+                        rmsd_ub: p.rmsd_ub,
+                        pose_pdbqt: p.pose_pdbqt.clone(),
+                    })
+                    .collect(),
+                error: result.error.clone(),
+                docked_at,
+            })
+            .collect()
+    }
+}
+
+/// Docks ligands into a pocket with Vina, preparing their PDBQT first.
+pub struct DockingBatch {
+    vina: VinaRunner,
+    preparer: Arc<dyn MoleculePreparer>,
+    exhaustiveness: u32,
+    parallelism: usize,
+}
+
+impl DockingBatch {
+    pub fn new(vina: VinaRunner, preparer: impl MoleculePreparer + 'static) -> Self {
+        Self {
+            vina,
+            preparer: Arc::new(preparer),
+            exhaustiveness: 8,
+            parallelism: DEFAULT_DOCKING_PARALLELISM,
+        }
+    }
+
+    /// Vina's search exhaustiveness; 8 by default.
+    pub fn with_exhaustiveness(mut self, exhaustiveness: u32) -> Self {
+        self.exhaustiveness = exhaustiveness.max(1);
+        self
+    }
+
+    /// Most ligands prepared and docked at once.
+    pub fn with_parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = parallelism.max(1);
+        self
+    }
+
+    /// PDBQT for `receptor`.
+    pub async fn prepare_receptor(&self, receptor: &ReceptorInput) -> Result<PathBuf> {
+        match receptor {
+            ReceptorInput::Pdbqt(path) => Ok(container::absolute(path)),
+            ReceptorInput::Structure(path) => self.preparer.receptor_pdbqt(path).await,
+        }
+    }
+
+    /// Dock each of `ligands` into `pocket` of `receptor`, writing ligand
+    /// and pose files to `work_dir` as `pocket<rank>_ligand<n>[_out].pdbqt`.
+    /// Fails only when the receptor cannot be prepared; ligand failures are
+    /// reported in their [`LigandDocking`].
+    pub async fn dock(
+        &self,
+        receptor: &ReceptorInput,
+        pocket: &Pocket,
+        ligands: &[LigandInput],
+        work_dir: &Path,
+    ) -> Result<DockingRun> {
+        let receptor = self
+            .prepare_receptor(receptor)
+            .await
+            .context("receptor preparation failed")?;
+        let work_dir = container::absolute(work_dir);
+        tokio::fs::create_dir_all(&work_dir)
+            .await
+            .with_context(|| format!("cannot create {work_dir:?}"))?;
+
+        let results = futures::stream::iter(ligands.iter().enumerate())
+            .map(|(i, ligand)| {
+                let stem = format!("pocket{}_ligand{}", pocket.rank, i + 1);
+                self.dock_ligand(&receptor, pocket, ligand, work_dir.join(stem))
+            })
+            .buffered(self.parallelism)
+            .collect()
+            .await;
+        Ok(DockingRun {
+            receptor,
+            pocket_rank: pocket.rank,
+            results,
+        })
+    }
+
+    async fn dock_ligand(
+        &self,
+        receptor: &Path,
+        pocket: &Pocket,
+        ligand: &LigandInput,
+        stem: PathBuf,
+    ) -> LigandDocking {
+        let docked = async {
+            let prepared = self
+                .preparer
+                .ligand_pdbqt(&ligand.smiles, &stem.with_extension("pdbqt"))
+                .await
+                .context("ligand preparation failed")?;
+            let mut out = stem.into_os_string();
+            out.push("_out.pdbqt");
+            let config = DockingConfig::for_pocket(
+                receptor.to_path_buf(),
+                prepared,
+                pocket,
+                self.exhaustiveness,
+                out.into(),
+            );
+            let out = self.vina.run(&config).await?;
+            let poses = parse_vina_poses(&ligand.id, &tokio::fs::read_to_string(&out).await?);
+            if poses.is_empty() {
+                anyhow::bail!("no binding modes in {out:?}");
+            }
+            Ok((out, poses))
+        }
+        .await;
+
+        match docked {
+            Ok((out, poses)) => LigandDocking {
+                ligand_id: ligand.id.clone(),
+                smiles: ligand.smiles.clone(),
+                poses,
+                pose_file: Some(out),
+                error: None,
+            },
+            Err(e) => {
+                warn!("Docking {} failed: {:#}", ligand.id, e);
+                LigandDocking {
+                    ligand_id: ligand.id.clone(),
+                    smiles: ligand.smiles.clone(),
+                    poses: Vec::new(),
+                    pose_file: None,
+                    error: Some(format!("{e:#}")),
+                }
+            }
+        }
+    }
+}
+
+/// Binding modes of `ligand_id` in a Vina output PDBQT, in file order.
+/// A mode is a `MODEL` block with a `REMARK VINA RESULT:` line; blocks
+/// without one are skipped.
+pub fn parse_vina_poses(ligand_id: &str, pdbqt: &str) -> Vec<DockingPose> {
+    let mut poses = Vec::new();
+    let mut block = String::new();
+    let mut result = None;
+    for line in pdbqt.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("MODEL") {
+            block.clear();
+            result = None;
+        }
+        block.push_str(line);
+        block.push('\n');
+        if let Some(values) = trimmed.strip_prefix("REMARK VINA RESULT:") {
+            result = parse_vina_result(values);
+        }
+        if trimmed == "ENDMDL" {
+            if let Some((affinity_kcal, rmsd_lb, rmsd_ub)) = result.take() {
+                poses.push(DockingPose {
+                    ligand_id: ligand_id.to_string(),
+                    mode: poses.len() as u32 + 1,
+                    affinity_kcal,
+                    rmsd_lb,
+                    rmsd_ub,
+                    pose_pdbqt: std::mem::take(&mut block),
+                });
+            }
+            block.clear();
+        }
+    }
+    poses
+}
+
+/// Affinity and the two RMSD bounds of a `REMARK VINA RESULT:` line.
+fn parse_vina_result(values: &str) -> Option<(f64, f64, f64)> {
+    let mut values = values.split_whitespace().map(str::parse::<f64>);
+    Some((
+        values.next()?.ok()?,
+        values.next()?.ok()?,
+        values.next()?.ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ETHANOL_OUT: &str = include_str!("../tests/fixtures/vina/ethanol_out.pdbqt");
+
+    fn pocket(rank: u32) -> Pocket {
+        Pocket {
+            rank,
+            score: 0.5,
+            druggability_score: 0.8,
+            volume: None,
+            hydrophobicity: None,
+            polarity: None,
+            residues: Vec::new(),
+            center: [0.0, 0.0, 0.0],
+            size: [16.0, 16.0, 16.0],
+        }
+    }
+
+    #[test]
+    fn parses_vina_binding_modes() {
+        let poses = parse_vina_poses("ethanol", ETHANOL_OUT);
+        assert_eq!(poses.len(), 3);
+        assert!(poses.iter().all(|p| p.ligand_id == "ethanol"));
+        assert_eq!(poses.iter().map(|p| p.mode).collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(poses[0].affinity_kcal, -2.6);
+        assert_eq!((poses[0].rmsd_lb, poses[0].rmsd_ub), (0.0, 0.0));
+        assert_eq!(poses[2].affinity_kcal, -2.1);
+        assert_eq!((poses[2].rmsd_lb, poses[2].rmsd_ub), (2.037, 2.745));
+
+        let model = &poses[1].pose_pdbqt;
+        assert!(model.starts_with("MODEL 2\n"), "{model}");
+        assert!(model.ends_with("ENDMDL\n"), "{model}");
+        assert_eq!(model.lines().filter(|l| l.starts_with("ATOM")).count(), 4);
+        assert!(model.contains("1.402"));
+        assert!(!model.contains("MODEL 1"));
+
+        assert!(parse_vina_poses("x", "MODEL 1\nENDMDL\n").is_empty());
+        assert!(parse_vina_poses("x", "").is_empty());
+    }
+
+    #[test]
+    fn receptor_input_follows_the_extension() {
+        assert_eq!(
+            ReceptorInput::from_path("/s/6oim.PDBQT"),
+            ReceptorInput::Pdbqt("/s/6oim.PDBQT".into())
+        );
+        assert_eq!(
+            ReceptorInput::from_path("/s/6oim.pdb"),
+            ReceptorInput::Structure("/s/6oim.pdb".into())
+        );
+    }
+
+    /// Writes a stub PDBQT for any SMILES except `not-a-smiles`.
+    struct FakePreparer;
+
+    #[async_trait]
+    impl MoleculePreparer for FakePreparer {
+        async fn receptor_pdbqt(&self, pdb_path: &Path) -> Result<PathBuf> {
+            let out = pdb_path.with_extension("pdbqt");
+            tokio::fs::write(&out, "ATOM\n").await?;
+            Ok(out)
+        }
+
+        async fn ligand_pdbqt(&self, smiles: &str, out: &Path) -> Result<PathBuf> {
+            if smiles == "not-a-smiles" {
+                anyhow::bail!("cannot parse SMILES {smiles}");
+            }
+            tokio::fs::write(out, "ROOT\nENDROOT\n").await?;
+            Ok(out.to_path_buf())
+        }
+    }
+
+    /// Executable standing in for Vina: copies the ethanol fixture to
+    /// `--out`.
+    #[cfg(unix)]
+    fn fake_vina(dir: &Path) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.join("vina");
+        let script = format!(
+            "#!/bin/sh\n\
+             while [ $# -gt 0 ]; do [ \"$1\" = --out ] && out=\"$2\"; shift; done\n\
+             cp {}/tests/fixtures/vina/ethanol_out.pdbqt \"$out\"\n",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        std::fs::write(&path, script).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn failed_ligands_do_not_abort_the_batch() {
+        let dir = tempfile::tempdir().unwrap();
+        let structure = dir.path().join("6oim.pdb");
+        std::fs::write(&structure, "ATOM\n").unwrap();
+        let batch = DockingBatch::new(VinaRunner::new(fake_vina(dir.path())), FakePreparer)
+            .with_parallelism(2);
+        let ligands = [
+            LigandInput::new("ethanol", "CCO"),
+            LigandInput::new("broken", "not-a-smiles"),
+            LigandInput::new("methanol", "CO"),
+        ];
+
+        let run = batch
+            .dock(
+                &ReceptorInput::from_path(&structure),
+                &pocket(3),
+                &ligands,
+                &dir.path().join("docking"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(run.receptor, dir.path().join("6oim.pdbqt"));
+        assert_eq!(run.pocket_rank, 3);
+        let ids: Vec<_> = run.results.iter().map(|r| r.ligand_id.as_str()).collect();
+        assert_eq!(ids, ["ethanol", "broken", "methanol"]);
+
+        let ethanol = &run.results[0];
+        assert_eq!(ethanol.error, None);
+        assert_eq!(ethanol.best_affinity_kcal(), Some(-2.6));
+        assert_eq!(
+            ethanol.pose_file.as_deref(),
+            Some(
+                dir.path()
+                    .join("docking/pocket3_ligand1_out.pdbqt")
+                    .as_path()
+            )
+        );
+        let broken = &run.results[1];
+        assert!(broken.poses.is_empty());
+        assert!(
+            broken
+                .error
+                .as_deref()
+                .unwrap()
+                .contains("cannot parse SMILES"),
+            "{:?}",
+            broken.error
+        );
+        assert_eq!(run.results[2].poses[0].ligand_id, "methanol");
+
+        let records = run.records("KRAS");
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].structure, "6oim.pdbqt");
+        assert_eq!(records[0].poses.len(), 3);
+        assert_eq!(records[0].best_affinity_kcal, Some(-2.6));
+        assert_eq!(records[1].best_affinity_kcal, None);
+        assert!(records[1].error.is_some());
+        assert!(records
+            .iter()
+            .all(|r| r.target == "KRAS" && r.pocket_rank == 3));
+    }
+
+    #[tokio::test]
+    async fn receptor_failures_fail_the_batch() {
+        let dir = tempfile::tempdir().unwrap();
+        let batch = DockingBatch::new(VinaRunner::new("vina"), FakePreparer);
+        let missing = ReceptorInput::Structure(dir.path().join("no-such-dir/6oim.pdb"));
+        let err = batch
+            .dock(
+                &missing,
+                &pocket(1),
+                &[LigandInput::new("ethanol", "CCO")],
+                dir.path(),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("receptor preparation"), "{err:#}");
+    }
+
+    /// Docks ethanol into the synthetic cavity's prepared receptor.
+    /// Needs Docker and `ferrumyx/autodock-vina:latest`, which also
+    /// provides Open Babel.
+    #[cfg(feature = "docker-tests")]
+    #[tokio::test]
+    async fn docks_ethanol_into_the_cavity_in_docker() {
+        if !container::docker_available().await {
+            eprintln!("Docker unavailable, skipping");
+            return;
+        }
+        let image = "ferrumyx/autodock-vina:latest";
+        let dir = tempfile::tempdir().unwrap();
+        let receptor = dir.path().join("cavity_receptor.pdbqt");
+        std::fs::copy(
+            concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/fixtures/vina/cavity_receptor.pdbqt"
+            ),
+            &receptor,
+        )
+        .unwrap();
+        let batch = DockingBatch::new(
+            VinaRunner::new("vina").in_docker(image),
+            OpenBabel::new("obabel").in_docker(image),
+        )
+        .with_exhaustiveness(1);
+        let ligands = [
+            LigandInput::new("ethanol", "CCO"),
+            LigandInput::new("broken", "not-a-smiles"),
+        ];
+
+        let run = batch
+            .dock(
+                &ReceptorInput::from_path(&receptor),
+                &pocket(1),
+                &ligands,
+                &dir.path().join("docking"),
+            )
+            .await
+            .unwrap();
+        let ethanol = &run.results[0];
+        assert_eq!(ethanol.error, None);
+        let affinities: Vec<_> = ethanol.poses.iter().map(|p| p.affinity_kcal).collect();
+        assert!(affinities[0] < 0.0, "{affinities:?}");
+        assert!(
+            affinities.windows(2).all(|w| w[0] <= w[1]),
+            "{affinities:?}"
+        );
+        assert!(ethanol.poses[0].pose_pdbqt.contains("ATOM"));
+        assert!(run.results[1].error.is_some());
+    }
+}
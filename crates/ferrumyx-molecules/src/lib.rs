@@ -3,17 +3,28 @@
 //! This crate handles Phase 5 of the Ferrumyx architecture:
 //! 1. Fetching protein structures (PDB / AlphaFold)
 //! 2. Detecting binding pockets (fpocket)
-//! 3. Generating potential ligands
+//! 3. Generating potential ligands (known ChEMBL actives and their
+//!    [`analogs`])
 //! 4. Molecular docking (AutoDock Vina)
-//! 5. ADMET prediction
+//! 5. ADMET prediction (rule-based, from [`smiles`], [`descriptors`] and
+//!    [`smarts`] alerts)
 //! 6. Scoring and ranking molecules
+//!
+//! [`tractability`] summarises the structure and pocket evidence for one
+//! target so the ranker can score it without running the full pipeline.
 
 pub mod admet;
+pub mod analogs;
+pub mod container;
+pub mod descriptors;
 pub mod docking;
 pub mod ligand;
 pub mod pdb;
 pub mod pipeline;
 pub mod pocket;
 pub mod scoring;
+pub mod smarts;
+pub mod smiles;
+pub mod tractability;
 
 pub type Result<T> = anyhow::Result<T>;
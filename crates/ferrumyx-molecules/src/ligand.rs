@@ -1,6 +1,8 @@
 //! Ligand generation and retrieval.
 
+use crate::analogs::{enumerate_candidates, CandidateLigand};
 use anyhow::Result;
+use ferrumyx_ingestion::sources::ChemblClient;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
@@ -46,9 +48,16 @@ impl Molecule {
     }
 }
 
+/// IC50/Ki cut-off in nM for the known actives analogs start from.
+const ACTIVE_THRESHOLD_NM: f64 = 1000.0;
+
+/// Known actives fetched per target before clustering.
+const MAX_KNOWN_ACTIVES: usize = 200;
+
 /// Generator for potential ligands.
 pub struct LigandGenerator {
     client: Client,
+    chembl: ChemblClient,
 }
 
 #[derive(Deserialize)]
@@ -97,9 +106,32 @@ impl LigandGenerator {
                 .timeout(Duration::from_secs(30))
                 .build()
                 .unwrap(),
+            chembl: ChemblClient::new(),
         }
     }
 
+    /// Up to `max_n` docking candidates for `target_chembl_id`: diverse
+    /// known actives, one or a few per Murcko scaffold, then analogs
+    /// enumerated from them. See [`enumerate_candidates`].
+    pub async fn generate_candidates(
+        &self,
+        target_chembl_id: &str,
+        max_n: usize,
+    ) -> Result<Vec<CandidateLigand>> {
+        let actives = self
+            .chembl
+            .fetch_active_compounds(target_chembl_id, ACTIVE_THRESHOLD_NM, MAX_KNOWN_ACTIVES)
+            .await?;
+        let candidates = enumerate_candidates(&actives, max_n);
+        info!(
+            "Generated {} candidates for {} from {} known actives",
+            candidates.len(),
+            target_chembl_id,
+            actives.len()
+        );
+        Ok(candidates)
+    }
+
     /// Generate ligands for a given target pocket / protein.
     /// In this implementation we fetch known inhibitors from ChEMBL for the target.
     pub async fn generate(&self, target_uniprot_id: &str) -> Result<Vec<Molecule>> {
@@ -1,6 +1,7 @@
 //! PDB and AlphaFold structure fetching.
 
 use anyhow::Result;
+use ferrumyx_common::sandbox::file_name;
 use reqwest::Client;
 use std::path::{Path, PathBuf};
 use tokio::fs;
@@ -21,10 +22,15 @@ impl StructureFetcher {
         }
     }
 
+    /// Directory structures are downloaded into.
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
     /// Fetch a PDB file by its ID.
     pub async fn fetch_pdb(&self, pdb_id: &str) -> Result<PathBuf> {
-        let file_name = format!("{}.pdb", pdb_id.to_lowercase());
-        let file_path = self.cache_dir.join(&file_name);
+        let name = format!("{}.pdb", pdb_id.to_lowercase());
+        let file_path = self.cache_dir.join(file_name(&name)?);
 
         if file_path.exists() {
             debug!("PDB {} found in cache", pdb_id);
@@ -32,7 +38,7 @@ impl StructureFetcher {
         }
 
         info!("Fetching PDB {} from RCSB", pdb_id);
-        let url = format!("https://files.rcsb.org/download/{}", file_name);
+        let url = format!("https://files.rcsb.org/download/{}", name);
         let response = self.client.get(&url).send().await?.error_for_status()?;
         let content = response.bytes().await?;
 
@@ -44,8 +50,8 @@ impl StructureFetcher {
 
     /// Fetch an AlphaFold structure by UniProt ID.
     pub async fn fetch_alphafold(&self, uniprot_id: &str) -> Result<PathBuf> {
-        let file_name = format!("AF-{}-F1-model_v4.pdb", uniprot_id);
-        let file_path = self.cache_dir.join(&file_name);
+        let name = format!("AF-{}-F1-model_v4.pdb", uniprot_id);
+        let file_path = self.cache_dir.join(file_name(&name)?);
 
         if file_path.exists() {
             debug!("AlphaFold structure for {} found in cache", uniprot_id);
@@ -53,7 +59,7 @@ impl StructureFetcher {
         }
 
         info!("Fetching AlphaFold structure for {} from EBI", uniprot_id);
-        let url = format!("https://alphafold.ebi.ac.uk/files/{}", file_name);
+        let url = format!("https://alphafold.ebi.ac.uk/files/{}", name);
         let response = self.client.get(&url).send().await?.error_for_status()?;
         let content = response.bytes().await?;
 
@@ -64,11 +70,44 @@ impl StructureFetcher {
     }
 }
 
+/// Mean pLDDT of an AlphaFold model, read from the B-factor column of its
+/// ATOM records.
+pub fn mean_plddt(pdb_text: &str) -> Option<f64> {
+    let values: Vec<f64> = pdb_text
+        .lines()
+        .filter(|line| line.starts_with("ATOM"))
+        .filter_map(|line| line.get(60..66)?.trim().parse::<f64>().ok())
+        .collect();
+    if values.is_empty() {
+        return None;
+    }
+    Some((values.iter().sum::<f64>() / values.len() as f64).clamp(0.0, 100.0))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use tempfile::tempdir;
 
+    #[test]
+    fn mean_plddt_reads_bfactor_column() {
+        let model = "ATOM      1  N   MET A   1      11.104  13.207   6.204  1.00 75.00           N\n\
+                     ATOM      2  CA  MET A   1      12.400  13.800   6.700  1.00 85.00           C\n\
+                     TER\n";
+        assert!((mean_plddt(model).unwrap() - 80.0).abs() < 1e-9);
+        assert!(mean_plddt("HEADER\n").is_none());
+    }
+
+    #[tokio::test]
+    async fn ids_cannot_name_files_outside_the_cache() {
+        let dir = tempdir().unwrap();
+        let fetcher = StructureFetcher::new(dir.path().join("structures"));
+
+        assert!(fetcher.fetch_pdb("../../etc/cron.d/x").await.is_err());
+        assert!(fetcher.fetch_alphafold("../P01116").await.is_err());
+        assert!(!dir.path().join("structures").exists());
+    }
+
     #[tokio::test]
     async fn test_fetch_pdb() {
         let dir = tempdir().unwrap();
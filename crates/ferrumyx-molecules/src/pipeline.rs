@@ -1,13 +1,561 @@
 //! Orchestrator for the molecules pipeline (Phase 5).
+//!
+//! [`run_molecule_pipeline`] runs the stages for one gene in order:
+//! structure, pockets, ligands, docking into the top pockets, ADMET and
+//! ranking. Each stage writes its output to
+//! `<workspace>/<gene>/<cancer_type>/checkpoints/` as it completes, and
+//! docking writes one checkpoint per pocket, so rerunning after a crash
+//! starts from the last completed step instead of redoing hours of Vina.
+//! The stages that call services and containers sit behind
+//! [`PipelineStages`]; the agent's `design_molecules` tool supplies the
+//! Docker-backed ones. With [`PipelineConfig::with_sandbox`] the run
+//! directory has to lie in the workspace sandbox and everything the run
+//! writes counts against the job's quota.
+//!
+//! [`MoleculesPipeline`] is the older single-call path, which estimates
+//! docking scores instead of running Vina.
 
-use anyhow::Result;
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use ferrumyx_common::sandbox::{disk_usage, Sandbox, SandboxError};
+use ferrumyx_db::schema::MoleculeCandidateRecord;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::future::Future;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tracing::{info, warn};
 
-use crate::admet::AdmetPredictor;
-use crate::ligand::LigandGenerator;
+use crate::admet::{score_admet, AdmetPredictor, AdmetProfile};
+use crate::analogs::CandidateLigand;
+use crate::docking::DockingRun;
+use crate::ligand::{LigandGenerator, Molecule};
 use crate::pdb::StructureFetcher;
+use crate::pocket::Pocket;
 use crate::scoring::{MoleculeScorer, ScoredMolecule};
+use crate::tractability::FetchedStructure;
+
+/// Pockets docked into unless configured otherwise.
+pub const DEFAULT_MAX_POCKETS: usize = 2;
+
+/// Ligands generated and docked unless configured otherwise.
+pub const DEFAULT_MAX_LIGANDS: usize = 50;
+
+/// The stages of a run, in the order they run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStage {
+    Structure,
+    Pockets,
+    Ligands,
+    Docking,
+    Admet,
+    Ranking,
+}
+
+impl PipelineStage {
+    pub const ALL: [PipelineStage; 6] = [
+        Self::Structure,
+        Self::Pockets,
+        Self::Ligands,
+        Self::Docking,
+        Self::Admet,
+        Self::Ranking,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Structure => "structure",
+            Self::Pockets => "pockets",
+            Self::Ligands => "ligands",
+            Self::Docking => "docking",
+            Self::Admet => "admet",
+            Self::Ranking => "ranking",
+        }
+    }
+}
+
+impl fmt::Display for PipelineStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// `done` of `total` items of `stage` finished. Docking counts ligand and
+/// pocket pairs, ADMET counts ligands, the other stages count 1.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PipelineProgress {
+    pub gene: String,
+    pub cancer_type: String,
+    pub stage: PipelineStage,
+    pub done: usize,
+    pub total: usize,
+    /// Read back from a checkpoint rather than computed.
+    pub resumed: bool,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PipelineError {
+    #[error("{stage} stage failed: {message}")]
+    Stage {
+        stage: PipelineStage,
+        message: String,
+    },
+    #[error("cannot write checkpoint {}: {source}", path.display())]
+    Checkpoint {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    Sandbox(#[from] SandboxError),
+}
+
+impl PipelineError {
+    fn stage(stage: PipelineStage, error: anyhow::Error) -> Self {
+        Self::Stage {
+            stage,
+            message: format!("{error:#}"),
+        }
+    }
+}
+
+/// Where a run keeps its files and how much of the target it covers.
+#[derive(Debug, Clone)]
+pub struct PipelineConfig {
+    pub workspace: PathBuf,
+    /// Top-ranked pockets to dock into.
+    pub max_pockets: usize,
+    /// Ligands to generate and dock into each pocket.
+    pub max_ligands: usize,
+    /// The sandbox the run's files must stay in, and the job they are
+    /// charged to.
+    pub sandbox: Option<(Arc<Sandbox>, String)>,
+}
+
+impl PipelineConfig {
+    pub fn new(workspace: impl Into<PathBuf>) -> Self {
+        Self {
+            workspace: workspace.into(),
+            max_pockets: DEFAULT_MAX_POCKETS,
+            max_ligands: DEFAULT_MAX_LIGANDS,
+            sandbox: None,
+        }
+    }
+
+    pub fn with_max_pockets(mut self, max_pockets: usize) -> Self {
+        self.max_pockets = max_pockets.max(1);
+        self
+    }
+
+    pub fn with_max_ligands(mut self, max_ligands: usize) -> Self {
+        self.max_ligands = max_ligands.max(1);
+        self
+    }
+
+    /// Keep the run inside `sandbox`, charging checkpoints and docking
+    /// output to the quota of `job_id`.
+    pub fn with_sandbox(mut self, sandbox: Arc<Sandbox>, job_id: impl Into<String>) -> Self {
+        self.sandbox = Some((sandbox, job_id.into()));
+        self
+    }
+
+    /// The directory of the run for `gene` in `cancer_type`. Checkpoints
+    /// stay valid for as long as it exists; delete it to start over, for
+    /// example after changing the limits.
+    pub fn run_dir(&self, gene: &str, cancer_type: &str) -> PathBuf {
+        self.workspace
+            .join(path_component(gene))
+            .join(path_component(cancer_type))
+    }
+}
+
+/// `name` with everything but ASCII letters, digits, `-` and `_` replaced,
+/// so a gene or cancer type cannot leave the workspace.
+fn path_component(name: &str) -> String {
+    let safe: String = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if safe.is_empty() {
+        "_".to_string()
+    } else {
+        safe
+    }
+}
+
+/// The stages that depend on external services or containers.
+#[async_trait]
+pub trait PipelineStages: Send + Sync {
+    async fn fetch_structure(&self, gene: &str) -> Result<FetchedStructure>;
+
+    /// Pockets of `structure`, best first.
+    async fn detect_pockets(&self, structure: &FetchedStructure) -> Result<Vec<Pocket>>;
+
+    async fn generate_ligands(&self, gene: &str, max_n: usize) -> Result<Vec<CandidateLigand>>;
+
+    /// Dock `ligands` into `pocket`, keeping files under `work_dir`. Each
+    /// [`LigandDocking`](crate::docking::LigandDocking) should carry the
+    /// ligand's SMILES; failures of single ligands belong in their results.
+    async fn dock(
+        &self,
+        structure: &FetchedStructure,
+        pocket: &Pocket,
+        ligands: &[CandidateLigand],
+        work_dir: &Path,
+    ) -> Result<DockingRun>;
+
+    async fn admet(&self, smiles: &str) -> Result<AdmetProfile> {
+        Ok(score_admet(smiles)?)
+    }
+}
+
+/// A docked ligand of the final ranking.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MoleculeCandidate {
+    /// 1 is the best composite score.
+    pub rank: u32,
+    pub ligand: CandidateLigand,
+    /// The pocket the ligand docked best into.
+    pub pocket_rank: u32,
+    pub best_affinity_kcal: f64,
+    pub admet: AdmetProfile,
+    /// See [`MoleculeScorer::score`].
+    pub composite_score: f64,
+}
+
+impl MoleculeCandidate {
+    /// The row stored for the run of `gene` in `cancer_type`.
+    pub fn record(&self, gene: &str, cancer_type: &str) -> MoleculeCandidateRecord {
+        MoleculeCandidateRecord {
+            gene: gene.to_string(),
+            cancer_type: cancer_type.to_string(),
+            rank: self.rank,
+            smiles: self.ligand.smiles.clone(),
+            parent_chembl_id: self.ligand.parent_chembl_id.clone(),
+            scaffold_id: self.ligand.scaffold_id.clone(),
+            novelty: self.ligand.novelty,
+            pocket_rank: self.pocket_rank,
+            best_affinity_kcal: self.best_affinity_kcal,
+            admet_score: self.admet.score,
+            sa_score: self.admet.sa_score,
+            pains_alerts: self.admet.pains_alerts.clone(),
+            composite_score: self.composite_score,
+            ranked_at: chrono::Utc::now(),
+        }
+    }
+}
+
+/// Run the pipeline for `gene` in `cancer_type`, resuming from the
+/// checkpoints of an earlier run in the same workspace, and return the
+/// docked ligands best first. A stage that fails, or produces nothing to
+/// carry on with, stops the run with [`PipelineError::Stage`].
+pub async fn run_molecule_pipeline(
+    gene: &str,
+    cancer_type: &str,
+    config: &PipelineConfig,
+    stages: &dyn PipelineStages,
+    progress: &(dyn Fn(PipelineProgress) + Send + Sync),
+) -> Result<Vec<MoleculeCandidate>, PipelineError> {
+    let mut dir = config.run_dir(gene, cancer_type);
+    if let Some((sandbox, _)) = &config.sandbox {
+        dir = sandbox.confine(&dir)?;
+    }
+    let run = Run {
+        gene,
+        cancer_type,
+        checkpoints: dir.join("checkpoints"),
+        sandbox: config
+            .sandbox
+            .as_ref()
+            .map(|(sandbox, job_id)| (sandbox.as_ref(), job_id.as_str())),
+        progress,
+    };
+    info!("Designing molecules for {} in {}", gene, cancer_type);
+
+    let structure = run
+        .once(PipelineStage::Structure, stages.fetch_structure(gene))
+        .await?;
+    let pockets = run
+        .once(PipelineStage::Pockets, async {
+            let mut pockets = stages.detect_pockets(&structure).await?;
+            if pockets.is_empty() {
+                bail!("fpocket found no pockets in {}", structure.path.display());
+            }
+            pockets.truncate(config.max_pockets);
+            Ok(pockets)
+        })
+        .await?;
+    let ligands = run
+        .once(PipelineStage::Ligands, async {
+            let ligands = stages.generate_ligands(gene, config.max_ligands).await?;
+            if ligands.is_empty() {
+                bail!("no ligands to dock for {gene}");
+            }
+            Ok(ligands)
+        })
+        .await?;
+
+    let runs = run
+        .dock(stages, &structure, &pockets, &ligands, &dir.join("docking"))
+        .await?;
+    let profiles = run.admet(stages, &docked(&ligands, &runs)).await?;
+    run.once(PipelineStage::Ranking, async {
+        Ok(rank(&ligands, &runs, &profiles))
+    })
+    .await
+}
+
+/// The ligands that docked into at least one pocket, in generation order.
+fn docked<'a>(ligands: &'a [CandidateLigand], runs: &[DockingRun]) -> Vec<&'a CandidateLigand> {
+    let posed: HashSet<&str> = runs
+        .iter()
+        .flat_map(|run| &run.results)
+        .filter(|result| !result.poses.is_empty())
+        .map(|result| result.smiles.as_str())
+        .collect();
+    ligands
+        .iter()
+        .filter(|ligand| posed.contains(ligand.smiles.as_str()))
+        .collect()
+}
+
+/// Score each assessed ligand on its best pose across pockets, best
+/// composite first; ties go to the tighter binder.
+fn rank(
+    ligands: &[CandidateLigand],
+    runs: &[DockingRun],
+    profiles: &[AdmetProfile],
+) -> Vec<MoleculeCandidate> {
+    let scorer = MoleculeScorer::new();
+    let mut candidates: Vec<MoleculeCandidate> = ligands
+        .iter()
+        .filter_map(|ligand| {
+            let admet = profiles.iter().find(|p| p.smiles == ligand.smiles)?;
+            let (pocket_rank, affinity) = runs
+                .iter()
+                .flat_map(|run| {
+                    run.results
+                        .iter()
+                        .filter(|result| result.smiles == ligand.smiles)
+                        .filter_map(|result| Some((run.pocket_rank, result.best_affinity_kcal()?)))
+                })
+                .min_by(|a, b| a.1.total_cmp(&b.1))?;
+            let scored = scorer.score(
+                Molecule::new(&ligand.smiles, "design"),
+                affinity,
+                admet.clone(),
+            );
+            Some(MoleculeCandidate {
+                rank: 0,
+                ligand: ligand.clone(),
+                pocket_rank,
+                best_affinity_kcal: affinity,
+                admet: scored.admet_properties,
+                composite_score: scored.composite_score,
+            })
+        })
+        .collect();
+    candidates.sort_by(|a, b| {
+        b.composite_score
+            .total_cmp(&a.composite_score)
+            .then(a.best_affinity_kcal.total_cmp(&b.best_affinity_kcal))
+    });
+    for (i, candidate) in candidates.iter_mut().enumerate() {
+        candidate.rank = i as u32 + 1;
+    }
+    candidates
+}
+
+struct Run<'a> {
+    gene: &'a str,
+    cancer_type: &'a str,
+    checkpoints: PathBuf,
+    sandbox: Option<(&'a Sandbox, &'a str)>,
+    progress: &'a (dyn Fn(PipelineProgress) + Send + Sync),
+}
+
+impl Run<'_> {
+    fn report(&self, stage: PipelineStage, done: usize, total: usize, resumed: bool) {
+        (self.progress)(PipelineProgress {
+            gene: self.gene.to_string(),
+            cancer_type: self.cancer_type.to_string(),
+            stage,
+            done,
+            total,
+            resumed,
+        });
+    }
+
+    fn charge(&self, bytes: u64) -> Result<(), PipelineError> {
+        match self.sandbox {
+            Some((sandbox, job_id)) => Ok(sandbox.charge(job_id, bytes)?),
+            None => Ok(()),
+        }
+    }
+
+    /// Bytes under `dir` when there is a quota to charge them to.
+    fn usage(&self, dir: &Path) -> u64 {
+        if self.sandbox.is_none() {
+            return 0;
+        }
+        disk_usage(dir).unwrap_or_else(|e| {
+            warn!("Cannot measure {}: {}", dir.display(), e);
+            0
+        })
+    }
+
+    /// A stage with a single output, or that output read back from its
+    /// checkpoint. `compute` is only polled when there is none.
+    async fn once<T>(
+        &self,
+        stage: PipelineStage,
+        compute: impl Future<Output = Result<T>>,
+    ) -> Result<T, PipelineError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        if let Some(done) = self.load(stage.as_str()).await {
+            self.report(stage, 1, 1, true);
+            return Ok(done);
+        }
+        self.report(stage, 0, 1, false);
+        let value = compute.await.map_err(|e| PipelineError::stage(stage, e))?;
+        self.save(stage.as_str(), &value).await?;
+        self.report(stage, 1, 1, false);
+        Ok(value)
+    }
+
+    /// Dock every ligand into each pocket in turn, checkpointing pockets
+    /// one by one.
+    async fn dock(
+        &self,
+        stages: &dyn PipelineStages,
+        structure: &FetchedStructure,
+        pockets: &[Pocket],
+        ligands: &[CandidateLigand],
+        work_dir: &Path,
+    ) -> Result<Vec<DockingRun>, PipelineError> {
+        let stage = PipelineStage::Docking;
+        let total = pockets.len() * ligands.len();
+        self.report(stage, 0, total, false);
+        let mut runs = Vec::with_capacity(pockets.len());
+        for pocket in pockets {
+            let name = format!("docking_pocket{}", pocket.rank);
+            let (run, resumed) = match self.load::<DockingRun>(&name).await {
+                Some(run) => (run, true),
+                None => {
+                    // Vina writes from inside its container, so its output
+                    // is charged once the pocket is done.
+                    let before = self.usage(work_dir);
+                    let run = stages
+                        .dock(structure, pocket, ligands, work_dir)
+                        .await
+                        .map_err(|e| PipelineError::stage(stage, e))?;
+                    self.charge(self.usage(work_dir).saturating_sub(before))?;
+                    self.save(&name, &run).await?;
+                    (run, false)
+                }
+            };
+            runs.push(run);
+            self.report(stage, runs.len() * ligands.len(), total, resumed);
+        }
+        if runs
+            .iter()
+            .all(|run| run.results.iter().all(|r| r.poses.is_empty()))
+        {
+            return Err(PipelineError::stage(
+                stage,
+                anyhow::anyhow!("no ligand docked into any pocket"),
+            ));
+        }
+        Ok(runs)
+    }
+
+    /// Assess each docked ligand, skipping those that cannot be assessed.
+    async fn admet(
+        &self,
+        stages: &dyn PipelineStages,
+        ligands: &[&CandidateLigand],
+    ) -> Result<Vec<AdmetProfile>, PipelineError> {
+        let stage = PipelineStage::Admet;
+        if let Some(done) = self.load::<Vec<AdmetProfile>>(stage.as_str()).await {
+            self.report(stage, ligands.len(), ligands.len(), true);
+            return Ok(done);
+        }
+        self.report(stage, 0, ligands.len(), false);
+        let mut profiles = Vec::with_capacity(ligands.len());
+        let mut last_error = None;
+        for (i, ligand) in ligands.iter().enumerate() {
+            match stages.admet(&ligand.smiles).await {
+                Ok(profile) => profiles.push(profile),
+                Err(e) => {
+                    warn!("Skipping ADMET for {}: {:#}", ligand.smiles, e);
+                    last_error = Some(e);
+                }
+            }
+            self.report(stage, i + 1, ligands.len(), false);
+        }
+        if profiles.is_empty() {
+            let error = last_error.unwrap_or_else(|| anyhow::anyhow!("no ligand to assess"));
+            return Err(PipelineError::stage(stage, error));
+        }
+        self.save(stage.as_str(), &profiles).await?;
+        Ok(profiles)
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.checkpoints.join(format!("{name}.json"))
+    }
+
+    /// The checkpoint `name` of an earlier run. One that cannot be read
+    /// back is logged and its step redone.
+    async fn load<T: DeserializeOwned>(&self, name: &str) -> Option<T> {
+        let path = self.path(name);
+        let bytes = tokio::fs::read(&path).await.ok()?;
+        match serde_json::from_slice(&bytes) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                warn!("Ignoring unreadable checkpoint {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// Write through a temporary file so a crash mid-write cannot leave a
+    /// truncated checkpoint behind.
+    async fn save<T: Serialize>(&self, name: &str, value: &T) -> Result<(), PipelineError> {
+        let path = self.path(name);
+        let tmp = path.with_extension("json.tmp");
+        let bytes = match serde_json::to_vec_pretty(value) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                return Err(PipelineError::Checkpoint {
+                    path,
+                    source: e.into(),
+                })
+            }
+        };
+        self.charge(bytes.len() as u64)?;
+        let write = async {
+            tokio::fs::create_dir_all(&self.checkpoints).await?;
+            tokio::fs::write(&tmp, &bytes).await?;
+            tokio::fs::rename(&tmp, &path).await
+        };
+        write
+            .await
+            .map_err(|source| PipelineError::Checkpoint { path, source })
+    }
+}
 
 pub struct MoleculesPipeline {
     cache_dir: PathBuf,
@@ -47,7 +595,13 @@ impl MoleculesPipeline {
         let mut scored = Vec::new();
 
         for ligand in ligands {
-            let props = admet.predict(&ligand).await?;
+            let props = match admet.predict(&ligand).await {
+                Ok(props) => props,
+                Err(e) => {
+                    warn!("Skipping ligand: {:#}", e);
+                    continue;
+                }
+            };
             // Simulate molecular docking to ensure system runs without AutoDock Vina binary dependency in MVP
             let mock_docking_score = -6.0 - (ligand.mw.unwrap_or(400.0) % 3.0);
 
@@ -59,3 +613,401 @@ impl MoleculesPipeline {
         Ok(ranked)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docking::{DockingPose, LigandDocking};
+    use crate::tractability::StructureSource;
+    use std::sync::Mutex;
+
+    const IMATINIB: &str = "Cc1ccc(NC(=O)c2ccc(CN3CCN(C)CC3)cc2)cc1Nc1nccc(-c2cccnc2)n1";
+    const ASPIRIN: &str = "CC(=O)Oc1ccccc1C(=O)O";
+    const IBUPROFEN: &str = "CC(C)Cc1ccc(C(C)C(=O)O)cc1";
+
+    /// Stages that record their calls, dock each ligand at a fixed
+    /// affinity per pocket and fail where told to.
+    #[derive(Default)]
+    struct MockStages {
+        calls: Mutex<Vec<String>>,
+        fail: Option<PipelineStage>,
+        fail_pocket: Option<u32>,
+        no_pockets: bool,
+        /// Ligands to generate instead of aspirin, imatinib and ibuprofen.
+        ligands: &'static [&'static str],
+    }
+
+    impl MockStages {
+        fn failing(stage: PipelineStage) -> Self {
+            Self {
+                fail: Some(stage),
+                ..Self::default()
+            }
+        }
+
+        fn call(&self, stage: PipelineStage, call: String) -> Result<()> {
+            self.calls.lock().unwrap().push(call);
+            if self.fail == Some(stage) {
+                bail!("mock {stage} failure");
+            }
+            Ok(())
+        }
+
+        fn calls(&self) -> Vec<String> {
+            std::mem::take(&mut self.calls.lock().unwrap())
+        }
+    }
+
+    fn pocket(rank: u32) -> Pocket {
+        Pocket {
+            rank,
+            score: 1.0 / f64::from(rank),
+            druggability_score: 0.8,
+            volume: Some(500.0),
+            hydrophobicity: None,
+            polarity: None,
+            residues: Vec::new(),
+            center: [0.0; 3],
+            size: [20.0; 3],
+        }
+    }
+
+    /// Imatinib binds best in pocket 2, the others in pocket 1; ibuprofen
+    /// never docks.
+    fn affinity(smiles: &str, pocket_rank: u32) -> Option<f64> {
+        match (smiles, pocket_rank) {
+            (IMATINIB, 1) => Some(-8.0),
+            (IMATINIB, _) => Some(-10.5),
+            (ASPIRIN, 1) => Some(-6.0),
+            (ASPIRIN, _) => Some(-5.0),
+            _ => None,
+        }
+    }
+
+    #[async_trait]
+    impl PipelineStages for MockStages {
+        async fn fetch_structure(&self, gene: &str) -> Result<FetchedStructure> {
+            self.call(PipelineStage::Structure, format!("fetch_structure {gene}"))?;
+            Ok(FetchedStructure {
+                path: PathBuf::from(format!("AF-{gene}-F1-model_v4.pdb")),
+                source: StructureSource::AlphaFold,
+                pdb_id: None,
+                resolution: None,
+                plddt: Some(91.0),
+            })
+        }
+
+        async fn detect_pockets(&self, _structure: &FetchedStructure) -> Result<Vec<Pocket>> {
+            self.call(PipelineStage::Pockets, "detect_pockets".to_string())?;
+            if self.no_pockets {
+                return Ok(Vec::new());
+            }
+            Ok((1..=3).map(pocket).collect())
+        }
+
+        async fn generate_ligands(&self, gene: &str, max_n: usize) -> Result<Vec<CandidateLigand>> {
+            self.call(PipelineStage::Ligands, format!("generate_ligands {gene}"))?;
+            let ligands = match self.ligands {
+                [] => &[ASPIRIN, IMATINIB, IBUPROFEN],
+                ligands => ligands,
+            };
+            Ok(ligands
+                .iter()
+                .take(max_n)
+                .map(|smiles| CandidateLigand {
+                    smiles: smiles.to_string(),
+                    parent_chembl_id: "CHEMBL941".to_string(),
+                    scaffold_id: String::new(),
+                    novelty: false,
+                })
+                .collect())
+        }
+
+        async fn dock(
+            &self,
+            _structure: &FetchedStructure,
+            pocket: &Pocket,
+            ligands: &[CandidateLigand],
+            work_dir: &Path,
+        ) -> Result<DockingRun> {
+            self.call(
+                PipelineStage::Docking,
+                format!("dock pocket{}", pocket.rank),
+            )?;
+            if self.fail_pocket == Some(pocket.rank) {
+                bail!("vina crashed");
+            }
+            let results = ligands
+                .iter()
+                .map(|ligand| {
+                    let affinity = affinity(&ligand.smiles, pocket.rank);
+                    LigandDocking {
+                        ligand_id: ligand.smiles.clone(),
+                        smiles: ligand.smiles.clone(),
+                        poses: affinity
+                            .map(|affinity_kcal| DockingPose {
+                                ligand_id: ligand.smiles.clone(),
+                                mode: 1,
+                                affinity_kcal,
+                                rmsd_lb: 0.0,
+                                rmsd_ub: 0.0,
+                                pose_pdbqt: String::new(),
+                            })
+                            .into_iter()
+                            .collect(),
+                        pose_file: None,
+                        error: affinity.is_none().then(|| "no pose".to_string()),
+                    }
+                })
+                .collect();
+            Ok(DockingRun {
+                receptor: work_dir.join("receptor.pdbqt"),
+                pocket_rank: pocket.rank,
+                results,
+            })
+        }
+
+        async fn admet(&self, smiles: &str) -> Result<AdmetProfile> {
+            self.call(PipelineStage::Admet, format!("admet {smiles}"))?;
+            Ok(score_admet(smiles)?)
+        }
+    }
+
+    async fn run(
+        stages: &MockStages,
+        config: &PipelineConfig,
+    ) -> (
+        Result<Vec<MoleculeCandidate>, PipelineError>,
+        Vec<PipelineProgress>,
+    ) {
+        let events = Mutex::new(Vec::new());
+        let result = run_molecule_pipeline("KRAS", "PAAD", config, stages, &|p| {
+            events.lock().unwrap().push(p)
+        })
+        .await;
+        (result, events.into_inner().unwrap())
+    }
+
+    #[tokio::test]
+    async fn stages_run_in_order_and_rank_docked_ligands() {
+        let workspace = tempfile::tempdir().unwrap();
+        let config = PipelineConfig::new(workspace.path());
+        let stages = MockStages::default();
+
+        let (ranked, events) = run(&stages, &config).await;
+        let ranked = ranked.unwrap();
+        assert_eq!(
+            stages.calls(),
+            [
+                "fetch_structure KRAS",
+                "detect_pockets",
+                "generate_ligands KRAS",
+                "dock pocket1",
+                "dock pocket2",
+                &format!("admet {ASPIRIN}"),
+                &format!("admet {IMATINIB}"),
+            ]
+        );
+
+        let summary: Vec<_> = ranked
+            .iter()
+            .map(|c| (c.rank, c.ligand.smiles.as_str(), c.pocket_rank))
+            .collect();
+        assert_eq!(summary, [(1, IMATINIB, 2), (2, ASPIRIN, 1)]);
+        assert_eq!(ranked[0].best_affinity_kcal, -10.5);
+
+        let order: Vec<_> = events.iter().map(|e| e.stage).collect();
+        let mut sorted = order.clone();
+        sorted.sort_by_key(|s| PipelineStage::ALL.iter().position(|a| a == s));
+        assert_eq!(order, sorted);
+        assert!(events.iter().all(|e| !e.resumed && e.done <= e.total));
+        let docking: Vec<_> = events
+            .iter()
+            .filter(|e| e.stage == PipelineStage::Docking)
+            .map(|e| (e.done, e.total))
+            .collect();
+        assert_eq!(docking, [(0, 6), (3, 6), (6, 6)]);
+        assert_eq!(
+            events.last().map(|e| (e.stage, e.done, e.total)),
+            Some((PipelineStage::Ranking, 1, 1))
+        );
+
+        let record = ranked[0].record("KRAS", "PAAD");
+        assert_eq!((record.rank, record.pocket_rank), (1, 2));
+        assert_eq!(record.composite_score, ranked[0].composite_score);
+    }
+
+    #[tokio::test]
+    async fn a_rerun_resumes_after_the_last_completed_step() {
+        let workspace = tempfile::tempdir().unwrap();
+        let config = PipelineConfig::new(workspace.path());
+
+        let crashing = MockStages {
+            fail_pocket: Some(2),
+            ..MockStages::default()
+        };
+        let (result, _) = run(&crashing, &config).await;
+        assert!(matches!(
+            result,
+            Err(PipelineError::Stage {
+                stage: PipelineStage::Docking,
+                ..
+            })
+        ));
+
+        let stages = MockStages::default();
+        let (resumed, events) = run(&stages, &config).await;
+        let resumed = resumed.unwrap();
+        assert_eq!(
+            stages.calls(),
+            [
+                "dock pocket2",
+                &format!("admet {ASPIRIN}"),
+                &format!("admet {IMATINIB}"),
+            ]
+        );
+        let resumed_stages: Vec<_> = events
+            .iter()
+            .filter(|e| e.resumed)
+            .map(|e| (e.stage, e.done))
+            .collect();
+        assert_eq!(
+            resumed_stages,
+            [
+                (PipelineStage::Structure, 1),
+                (PipelineStage::Pockets, 1),
+                (PipelineStage::Ligands, 1),
+                (PipelineStage::Docking, 3),
+            ]
+        );
+
+        let (again, _) = run(&stages, &config).await;
+        assert!(stages.calls().is_empty());
+        let smiles = |c: &[MoleculeCandidate]| -> Vec<String> {
+            c.iter().map(|c| c.ligand.smiles.clone()).collect()
+        };
+        assert_eq!(smiles(&again.unwrap()), smiles(&resumed));
+    }
+
+    #[tokio::test]
+    async fn a_failing_stage_stops_the_run_under_its_name() {
+        for stage in [
+            PipelineStage::Structure,
+            PipelineStage::Pockets,
+            PipelineStage::Ligands,
+            PipelineStage::Docking,
+            PipelineStage::Admet,
+        ] {
+            let workspace = tempfile::tempdir().unwrap();
+            let config = PipelineConfig::new(workspace.path());
+            let stages = MockStages::failing(stage);
+
+            let (result, events) = run(&stages, &config).await;
+            match result {
+                Err(PipelineError::Stage {
+                    stage: failed,
+                    message,
+                }) => {
+                    assert_eq!(failed, stage);
+                    assert!(message.contains("mock"), "{message}");
+                }
+                other => panic!("{stage}: {other:?}"),
+            }
+            let after = |s: &PipelineStage| {
+                PipelineStage::ALL.iter().position(|a| a == s)
+                    > PipelineStage::ALL.iter().position(|a| *a == stage)
+            };
+            assert!(!events.iter().any(|e| after(&e.stage)), "{stage}");
+            let checkpoint = config
+                .run_dir("KRAS", "PAAD")
+                .join("checkpoints")
+                .join(format!("{stage}.json"));
+            assert!(!checkpoint.exists(), "{}", checkpoint.display());
+        }
+    }
+
+    #[tokio::test]
+    async fn stages_with_nothing_to_pass_on_fail() {
+        for (stages, stage) in [
+            (
+                MockStages {
+                    no_pockets: true,
+                    ..MockStages::default()
+                },
+                PipelineStage::Pockets,
+            ),
+            (
+                MockStages {
+                    ligands: &[IBUPROFEN],
+                    ..MockStages::default()
+                },
+                PipelineStage::Docking,
+            ),
+        ] {
+            let workspace = tempfile::tempdir().unwrap();
+            let (result, _) = run(&stages, &PipelineConfig::new(workspace.path())).await;
+            match result {
+                Err(PipelineError::Stage { stage: failed, .. }) => assert_eq!(failed, stage),
+                other => panic!("{stage}: {other:?}"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn sandboxed_runs_stop_at_the_job_quota() {
+        let workspace = tempfile::tempdir().unwrap();
+        let sandbox = Arc::new(Sandbox::new(workspace.path()).unwrap());
+        let config = PipelineConfig::new(workspace.path().join("molecules"))
+            .with_sandbox(sandbox.clone(), "job-1");
+        let (ranked, _) = run(&MockStages::default(), &config).await;
+        ranked.unwrap();
+        let used = sandbox.job_usage("job-1");
+        assert!(used > 0);
+
+        // Half of that runs out partway through.
+        let sandbox = Arc::new(
+            Sandbox::new(workspace.path())
+                .unwrap()
+                .with_max_job_bytes(used / 2),
+        );
+        let config = PipelineConfig::new(workspace.path().join("other"))
+            .with_sandbox(sandbox.clone(), "job-2");
+        let stages = MockStages::default();
+        let (result, _) = run(&stages, &config).await;
+        assert!(
+            matches!(
+                result,
+                Err(PipelineError::Sandbox(SandboxError::QuotaExceeded { .. }))
+            ),
+            "{result:?}"
+        );
+        assert!(sandbox.job_usage("job-2") <= used / 2);
+    }
+
+    #[tokio::test]
+    async fn sandboxed_runs_stay_in_the_sandbox() {
+        let workspace = tempfile::tempdir().unwrap();
+        let sandbox = Arc::new(Sandbox::new(workspace.path().join("sandbox")).unwrap());
+        let config =
+            PipelineConfig::new(workspace.path().join("elsewhere")).with_sandbox(sandbox, "job");
+        let stages = MockStages::default();
+
+        let (result, _) = run(&stages, &config).await;
+        assert!(
+            matches!(result, Err(PipelineError::Sandbox(SandboxError::Escape(_)))),
+            "{result:?}"
+        );
+        assert!(stages.calls().is_empty());
+        assert!(!workspace.path().join("elsewhere").exists());
+    }
+
+    #[test]
+    fn run_dirs_stay_inside_the_workspace() {
+        let config = PipelineConfig::new("/work");
+        assert_eq!(
+            config.run_dir("../etc", "a/b"),
+            PathBuf::from("/work/___etc/a_b")
+        );
+        assert_eq!(config.run_dir("", "PAAD"), PathBuf::from("/work/_/PAAD"));
+    }
+}
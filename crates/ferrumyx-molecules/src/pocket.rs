@@ -1,13 +1,124 @@
 //! Binding pocket detection using fpocket.
+//!
+//! [`FPocketRunner::detect`] copies a structure into a scratch directory,
+//! runs fpocket on it there (inside Docker when an image is set) and reads
+//! the `<name>_info.txt` and `pockets/pocketN_atm.pdb` files it writes into
+//! ranked [`Pocket`]s. With a cache directory the pockets are stored under
+//! the SHA-256 of the structure file, so scoring the same structure again
+//! does not rerun fpocket.
 
-use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
-use tokio::process::Command;
-use tracing::{debug, info};
+use std::process::ExitStatus;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use crate::container;
+
+/// Default limit on one fpocket run.
+pub const DEFAULT_FPOCKET_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Why an fpocket run produced no pockets.
+#[derive(Debug, thiserror::Error)]
+pub enum FpocketError {
+    #[error("cannot start {program}: {source}")]
+    Launch {
+        program: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Docker image {0} is not available and could not be pulled")]
+    ImageMissing(String),
+    #[error("fpocket failed ({status}): {stderr}")]
+    Failed { status: ExitStatus, stderr: String },
+    #[error("fpocket did not finish within {}s", .0.as_secs())]
+    TimedOut(Duration),
+    #[error("fpocket output directory not found: {0:?}")]
+    MissingOutput(PathBuf),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Pockets found by one fpocket run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PocketSummary {
+    pub pocket_count: u32,
+    /// Highest fpocket druggability score, 0.0–1.0.
+    pub best_druggability: f64,
+}
+
+impl PocketSummary {
+    /// Summary of `pockets`; None when there are none.
+    pub fn of(pockets: &[Pocket]) -> Option<Self> {
+        (!pockets.is_empty()).then(|| Self {
+            pocket_count: pockets.len() as u32,
+            best_druggability: pockets
+                .iter()
+                .map(|p| p.druggability_score)
+                .fold(0.0, f64::max),
+        })
+    }
+}
+
+/// A residue lining a pocket.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ResidueRef {
+    pub chain: String,
+    /// Three-letter residue name, e.g. `GLY`.
+    pub name: String,
+    pub number: i32,
+}
+
+impl ResidueRef {
+    /// Residue of a PDB `ATOM` or `HETATM` record.
+    fn from_atom_record(line: &str) -> Option<Self> {
+        Some(Self {
+            chain: line.get(21..22)?.trim().to_string(),
+            name: line.get(17..20)?.trim().to_string(),
+            number: line.get(22..26)?.trim().parse().ok()?,
+        })
+    }
+}
+
+impl std::fmt::Display for ResidueRef {
+    /// `<chain>:<name><number>`, e.g. `A:GLY12`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}{}", self.chain, self.name, self.number)
+    }
+}
+
+/// One pocket of an fpocket run, with the box docking should search.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pocket {
+    /// fpocket's pocket number; 1 is the pocket it ranks best.
+    pub rank: u32,
+    pub score: f64,
+    /// 0.0–1.0.
+    pub druggability_score: f64,
+    /// Å³, when fpocket reported it.
+    pub volume: Option<f64>,
+    /// fpocket's hydrophobicity score: mean hydrophobicity of the lining
+    /// residues.
+    pub hydrophobicity: Option<f64>,
+    /// fpocket's polarity score: number of polar lining residues.
+    pub polarity: Option<f64>,
+    pub residues: Vec<ResidueRef>,
+    /// Centre of the pocket atoms, Å.
+    pub center: [f64; 3],
+    /// Extent of the pocket atoms plus [`POCKET_BOX_PADDING`] on each side.
+    pub size: [f64; 3],
+}
+
+/// Margin added around the pocket atoms on each side of the docking box, Å.
+pub const POCKET_BOX_PADDING: f64 = 4.0;
 
 /// Wrapper for fpocket execution.
 pub struct FPocketRunner {
     executable_path: PathBuf,
+    docker_image: Option<String>,
+    timeout: Duration,
+    cache_dir: Option<PathBuf>,
 }
 
 impl FPocketRunner {
@@ -15,33 +126,522 @@ impl FPocketRunner {
     pub fn new<P: AsRef<Path>>(executable_path: P) -> Self {
         Self {
             executable_path: executable_path.as_ref().to_path_buf(),
+            docker_image: None,
+            timeout: DEFAULT_FPOCKET_TIMEOUT,
+            cache_dir: None,
+        }
+    }
+
+    /// Run the executable inside `image` instead of on the host.
+    pub fn in_docker(mut self, image: impl Into<String>) -> Self {
+        self.docker_image = Some(image.into());
+        self
+    }
+
+    /// Give up on a run after `timeout` (default
+    /// [`DEFAULT_FPOCKET_TIMEOUT`]).
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Keep [`Self::detect`] results in `dir`, one JSON file per structure.
+    pub fn with_cache_dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.cache_dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Pockets of the structure at `pdb_path`, best score first. Served
+    /// from the cache when this structure has been run before.
+    pub async fn detect(&self, pdb_path: &Path) -> Result<Vec<Pocket>, FpocketError> {
+        let structure = tokio::fs::read(pdb_path).await?;
+        let key = structure_key(&structure);
+        if let Some(pockets) = self.load_cached(&key) {
+            debug!("fpocket result for {:?} found in cache", pdb_path);
+            return Ok(pockets);
         }
+
+        let scratch = tempfile::Builder::new()
+            .prefix("ferrumyx-fpocket-")
+            .tempdir()?;
+        let file_name = pdb_path
+            .file_name()
+            .map(|n| n.to_os_string())
+            .unwrap_or_else(|| "structure.pdb".into());
+        let input = scratch.path().join(file_name);
+        tokio::fs::write(&input, &structure).await?;
+        let out_dir = self.run(&input).await?;
+        let pockets = read_pockets(&out_dir)?;
+        self.store_cached(&key, &pockets);
+        Ok(pockets)
+    }
+
+    /// Pockets an earlier [`Self::detect`] stored for the structure at
+    /// `pdb_path`, without running fpocket.
+    pub fn cached(&self, pdb_path: &Path) -> Result<Option<Vec<Pocket>>, FpocketError> {
+        let structure = std::fs::read(pdb_path)?;
+        Ok(self.load_cached(&structure_key(&structure)))
     }
 
-    /// Run fpocket on a given PDB file.
-    pub async fn run(&self, pdb_path: &Path) -> Result<PathBuf> {
+    /// Run fpocket on a given PDB file. Its output goes to `<name>_out`
+    /// next to the file.
+    pub async fn run(&self, pdb_path: &Path) -> Result<PathBuf, FpocketError> {
         info!("Running fpocket on {:?}", pdb_path);
 
-        let output = Command::new(&self.executable_path)
-            .arg("-f")
-            .arg(pdb_path)
-            .output()
-            .await?;
+        let pdb_path = container::absolute(pdb_path);
+        let dir = pdb_path.parent().unwrap_or(Path::new("/"));
+        let container_name = format!("ferrumyx-fpocket-{}", uuid::Uuid::new_v4());
+        let mut cmd = container::named_command(
+            &self.executable_path,
+            self.docker_image.as_deref(),
+            &[dir],
+            &container_name,
+        );
+        cmd.arg("-f").arg(&pdb_path).kill_on_drop(true);
+        let program = cmd.as_std().get_program().to_string_lossy().into_owned();
+
+        let output = match tokio::time::timeout(self.timeout, cmd.output()).await {
+            Ok(output) => output.map_err(|source| FpocketError::Launch { program, source })?,
+            Err(_) => {
+                if self.docker_image.is_some() {
+                    container::remove(&container_name).await;
+                }
+                return Err(FpocketError::TimedOut(self.timeout));
+            }
+        };
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("fpocket failed: {}", stderr);
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            if let Some(image) = &self.docker_image {
+                if container::image_missing(output.status.code(), &stderr) {
+                    return Err(FpocketError::ImageMissing(image.clone()));
+                }
+            }
+            return Err(FpocketError::Failed {
+                status: output.status,
+                stderr,
+            });
         }
 
         // fpocket creates a directory named <pdb_name>_out
-        let pdb_name = pdb_path.file_stem().unwrap().to_string_lossy();
+        let pdb_name = pdb_path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
         let out_dir = pdb_path.with_file_name(format!("{}_out", pdb_name));
 
         if !out_dir.exists() {
-            anyhow::bail!("fpocket output directory not found: {:?}", out_dir);
+            return Err(FpocketError::MissingOutput(out_dir));
         }
 
         debug!("fpocket completed successfully. Output in {:?}", out_dir);
         Ok(out_dir)
     }
+
+    /// Read the `<name>_info.txt` file of an fpocket output directory.
+    pub fn summarise(&self, out_dir: &Path) -> anyhow::Result<Option<PocketSummary>> {
+        let text = std::fs::read_to_string(info_path(out_dir))?;
+        Ok(parse_pocket_info(&text))
+    }
+
+    /// Every pocket of an fpocket output directory, best score first.
+    pub fn pockets(&self, out_dir: &Path) -> anyhow::Result<Vec<Pocket>> {
+        Ok(read_pockets(out_dir)?)
+    }
+
+    fn cache_path(&self, key: &str) -> Option<PathBuf> {
+        self.cache_dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{key}.json")))
+    }
+
+    fn load_cached(&self, key: &str) -> Option<Vec<Pocket>> {
+        let text = std::fs::read_to_string(self.cache_path(key)?).ok()?;
+        serde_json::from_str(&text).ok()
+    }
+
+    /// A cache that cannot be written only costs a rerun next time.
+    fn store_cached(&self, key: &str, pockets: &[Pocket]) {
+        let Some(path) = self.cache_path(key) else {
+            return;
+        };
+        let written = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(&path, serde_json::to_vec(pockets)?));
+        if let Err(e) = written {
+            warn!("Failed to cache fpocket result at {:?}: {}", path, e);
+        }
+    }
+}
+
+/// Hex SHA-256 of a structure file's bytes.
+fn structure_key(structure: &[u8]) -> String {
+    Sha256::digest(structure)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// `<out_dir>/<name>_info.txt` of an `<name>_out` directory.
+fn info_path(out_dir: &Path) -> PathBuf {
+    let name = out_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().trim_end_matches("_out").to_string())
+        .unwrap_or_default();
+    out_dir.join(format!("{}_info.txt", name))
+}
+
+/// Every pocket of an fpocket output directory, best score first. A
+/// pocket without an atom file keeps its scores but no residues or box.
+pub fn read_pockets(out_dir: &Path) -> std::io::Result<Vec<Pocket>> {
+    let text = std::fs::read_to_string(info_path(out_dir))?;
+    let mut pockets = parse_pocket_blocks(&text);
+    for pocket in &mut pockets {
+        let atoms_path = out_dir
+            .join("pockets")
+            .join(format!("pocket{}_atm.pdb", pocket.rank));
+        let atoms = std::fs::read_to_string(&atoms_path).unwrap_or_else(|e| {
+            debug!(
+                "No atoms for pocket {} at {:?}: {}",
+                pocket.rank, atoms_path, e
+            );
+            String::new()
+        });
+        (pocket.residues, pocket.center, pocket.size) = pocket_geometry(&atoms);
+    }
+    pockets.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.rank.cmp(&b.rank)));
+    Ok(pockets)
+}
+
+/// The `Pocket N :` blocks of fpocket's `_info.txt`, in file order and
+/// without geometry.
+pub fn parse_pocket_blocks(text: &str) -> Vec<Pocket> {
+    let mut pockets: Vec<Pocket> = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if let Some(rank) = line
+            .strip_prefix("Pocket ")
+            .and_then(|rest| rest.strip_suffix(':'))
+        {
+            if let Ok(rank) = rank.trim().parse() {
+                pockets.push(Pocket {
+                    rank,
+                    score: 0.0,
+                    druggability_score: 0.0,
+                    volume: None,
+                    hydrophobicity: None,
+                    polarity: None,
+                    residues: Vec::new(),
+                    center: [0.0; 3],
+                    size: [0.0; 3],
+                });
+            }
+            continue;
+        }
+        let Some(current) = pockets.last_mut() else {
+            continue;
+        };
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let Ok(value) = value.trim().parse::<f64>() else {
+            continue;
+        };
+        match key.trim() {
+            "Score" => current.score = value,
+            "Druggability Score" => current.druggability_score = value.clamp(0.0, 1.0),
+            "Volume" => current.volume = Some(value),
+            "Hydrophobicity score" => current.hydrophobicity = Some(value),
+            "Polarity score" => current.polarity = Some(value),
+            _ => {}
+        }
+    }
+    pockets
+}
+
+/// Lining residues, centre and padded box of a `pocketN_atm.pdb` file.
+fn pocket_geometry(pdb_text: &str) -> (Vec<ResidueRef>, [f64; 3], [f64; 3]) {
+    let mut residues: Vec<ResidueRef> = Vec::new();
+    let mut min = [f64::INFINITY; 3];
+    let mut max = [f64::NEG_INFINITY; 3];
+    for line in pdb_text
+        .lines()
+        .filter(|l| l.starts_with("ATOM") || l.starts_with("HETATM"))
+    {
+        if let Some(residue) = ResidueRef::from_atom_record(line) {
+            if !residues.contains(&residue) {
+                residues.push(residue);
+            }
+        }
+        let coords = [30..38, 38..46, 46..54]
+            .map(|cols| line.get(cols).and_then(|v| v.trim().parse::<f64>().ok()));
+        if let [Some(x), Some(y), Some(z)] = coords {
+            for (axis, v) in [x, y, z].into_iter().enumerate() {
+                min[axis] = min[axis].min(v);
+                max[axis] = max[axis].max(v);
+            }
+        }
+    }
+    if min[0] > max[0] {
+        return (residues, [0.0; 3], [0.0; 3]);
+    }
+    let center = [0, 1, 2].map(|axis| (min[axis] + max[axis]) / 2.0);
+    let size = [0, 1, 2].map(|axis| max[axis] - min[axis] + 2.0 * POCKET_BOX_PADDING);
+    (residues, center, size)
+}
+
+/// Parse fpocket's `_info.txt`: one `Pocket N :` block per pocket, each
+/// with a `Druggability Score :` line. None when no pocket was found.
+pub fn parse_pocket_info(text: &str) -> Option<PocketSummary> {
+    let mut pocket_count = 0u32;
+    let mut best: Option<f64> = None;
+    for line in text.lines().map(str::trim) {
+        if line.starts_with("Pocket ") && line.ends_with(':') {
+            pocket_count += 1;
+        } else if let Some(value) = line.strip_prefix("Druggability Score") {
+            let score = value
+                .trim_start_matches([' ', ':', '\t'])
+                .trim()
+                .parse::<f64>();
+            if let Ok(score) = score {
+                best = Some(best.map_or(score, |b: f64| b.max(score)));
+            }
+        }
+    }
+    (pocket_count > 0).then(|| PocketSummary {
+        pocket_count,
+        best_druggability: best.unwrap_or(0.0).clamp(0.0, 1.0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// fpocket 4 output for a KRAS structure: the switch-II pocket and a
+    /// shallow groove.
+    const KRAS_OUT: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/fpocket/kras_out"
+    );
+
+    fn residue(chain: &str, name: &str, number: i32) -> ResidueRef {
+        ResidueRef {
+            chain: chain.to_string(),
+            name: name.to_string(),
+            number,
+        }
+    }
+
+    #[test]
+    fn parses_pocket_count_and_best_druggability() {
+        let info = "Pocket 1 :\n\tScore : \t0.361\n\tDruggability Score : \t0.923\n\n\
+                    Pocket 2 :\n\tScore : \t0.290\n\tDruggability Score : \t0.041\n";
+        let summary = parse_pocket_info(info).unwrap();
+        assert_eq!(summary.pocket_count, 2);
+        assert!((summary.best_druggability - 0.923).abs() < 1e-9);
+        assert!(parse_pocket_info("").is_none());
+    }
+
+    #[test]
+    fn reads_ranked_pockets_with_residues_and_box() {
+        let dir = tempfile::tempdir().unwrap();
+        let out_dir = dir.path().join("kras_out");
+        std::fs::create_dir_all(out_dir.join("pockets")).unwrap();
+        std::fs::write(
+            out_dir.join("kras_info.txt"),
+            "Pocket 1 :\n\tScore : \t0.290\n\tDruggability Score : \t0.041\n\tVolume : \t312.5\n\n\
+             Pocket 2 :\n\tScore : \t0.361\n\tDruggability Score : \t0.923\n",
+        )
+        .unwrap();
+        std::fs::write(
+            out_dir.join("pockets").join("pocket1_atm.pdb"),
+            "ATOM     92  CA  GLY A  12      10.000  20.000  30.000  0.00  0.00           C\n\
+             ATOM     93  O   GLY A  12      12.000  24.000  30.000  0.00  0.00           O\n\
+             ATOM    150  NZ  LYS A  16      14.000  22.000  34.000  0.00  0.00           N\n",
+        )
+        .unwrap();
+
+        let pockets = FPocketRunner::new("fpocket").pockets(&out_dir).unwrap();
+        assert_eq!(pockets.iter().map(|p| p.rank).collect::<Vec<_>>(), [2, 1]);
+        let first = &pockets[1];
+        assert_eq!(first.volume, Some(312.5));
+        assert_eq!(
+            first
+                .residues
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            ["A:GLY12", "A:LYS16"]
+        );
+        assert_eq!(first.center, [12.0, 22.0, 32.0]);
+        assert_eq!(first.size, [12.0, 12.0, 12.0]);
+        // Pocket 2 has no atom file.
+        assert!(pockets[0].residues.is_empty());
+    }
+
+    #[test]
+    fn parses_checked_in_fpocket_output() {
+        let pockets = read_pockets(Path::new(KRAS_OUT)).unwrap();
+        assert_eq!(pockets.len(), 2);
+
+        let switch_ii = &pockets[0];
+        assert_eq!(switch_ii.rank, 1);
+        assert_eq!(switch_ii.score, 0.468);
+        assert_eq!(switch_ii.druggability_score, 0.871);
+        assert_eq!(switch_ii.volume, Some(601.225));
+        assert_eq!(switch_ii.hydrophobicity, Some(28.846));
+        assert_eq!(switch_ii.polarity, Some(7.0));
+        assert_eq!(switch_ii.residues.len(), 10);
+        assert_eq!(switch_ii.residues[0], residue("A", "GLY", 10));
+        assert!(switch_ii.residues.contains(&residue("A", "GLN", 61)));
+        assert!(switch_ii.residues.contains(&residue("A", "TYR", 96)));
+        for (got, expected) in switch_ii.center.iter().zip([5.24, 5.95, -1.81]) {
+            assert!((got - expected).abs() < 1e-9, "{:?}", switch_ii.center);
+        }
+
+        let groove = &pockets[1];
+        assert_eq!(groove.rank, 2);
+        assert_eq!(groove.polarity, Some(5.0));
+        assert_eq!(
+            groove.residues,
+            [
+                residue("A", "LYS", 147),
+                residue("A", "ASP", 154),
+                residue("B", "LYS", 5)
+            ]
+        );
+
+        let summary = PocketSummary::of(&pockets).unwrap();
+        assert_eq!(summary.pocket_count, 2);
+        assert_eq!(summary.best_druggability, 0.871);
+        assert_eq!(
+            Some(summary),
+            parse_pocket_info(&std::fs::read_to_string(info_path(Path::new(KRAS_OUT))).unwrap())
+        );
+        assert_eq!(PocketSummary::of(&[]), None);
+    }
+
+    /// Executable standing in for fpocket: writes `body` as a shell script.
+    #[cfg(unix)]
+    fn fake_fpocket(dir: &Path, body: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.join("fpocket");
+        std::fs::write(&path, format!("#!/bin/sh\n{body}\n")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn fpocket_runs_once_per_structure() {
+        let dir = tempfile::tempdir().unwrap();
+        let runs = dir.path().join("runs");
+        // Copy the fixture output where fpocket would write it for `$2`.
+        let fpocket = fake_fpocket(
+            dir.path(),
+            &format!(
+                "out=\"${{2%.*}}_out\"\n\
+                 mkdir -p \"$out/pockets\"\n\
+                 cp {fixture}/kras_info.txt \"$out/$(basename \"${{2%.*}}\")_info.txt\"\n\
+                 cp {fixture}/pockets/* \"$out/pockets/\"\n\
+                 echo run >> {runs}",
+                fixture = KRAS_OUT,
+                runs = runs.display()
+            ),
+        );
+        let structure = dir.path().join("6oim.pdb");
+        std::fs::write(&structure, "ATOM\n").unwrap();
+        let runner = FPocketRunner::new(&fpocket).with_cache_dir(dir.path().join("cache"));
+
+        assert_eq!(runner.cached(&structure).unwrap(), None);
+        let first = runner.detect(&structure).await.unwrap();
+        assert_eq!(first.len(), 2);
+        let again = runner.detect(&structure).await.unwrap();
+        let ranks = |pockets: &[Pocket]| pockets.iter().map(|p| p.rank).collect::<Vec<_>>();
+        assert_eq!(ranks(&again), ranks(&first));
+        assert_eq!(again[0].residues, first[0].residues);
+        assert_eq!(runner.cached(&structure).unwrap(), Some(again));
+        assert_eq!(std::fs::read_to_string(&runs).unwrap().lines().count(), 1);
+        // The scratch copy, not the structure's own directory, got the output.
+        assert!(!dir.path().join("6oim_out").exists());
+
+        // A changed structure is a new cache entry.
+        std::fs::write(&structure, "ATOM\nEND\n").unwrap();
+        runner.detect(&structure).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&runs).unwrap().lines().count(), 2);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn failed_runs_come_back_as_typed_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let structure = dir.path().join("1crn.pdb");
+        std::fs::write(&structure, "ATOM\n").unwrap();
+
+        let crashing = fake_fpocket(dir.path(), "echo 'segmentation fault' >&2\nexit 139");
+        match FPocketRunner::new(&crashing).run(&structure).await {
+            Err(FpocketError::Failed { status, stderr }) => {
+                assert_eq!(status.code(), Some(139));
+                assert_eq!(stderr, "segmentation fault");
+            }
+            other => panic!("expected a failure, got {other:?}"),
+        }
+
+        let silent = fake_fpocket(dir.path(), "exit 0");
+        assert!(matches!(
+            FPocketRunner::new(&silent).run(&structure).await,
+            Err(FpocketError::MissingOutput(_))
+        ));
+
+        let hanging = fake_fpocket(dir.path(), "sleep 30");
+        let started = std::time::Instant::now();
+        assert!(matches!(
+            FPocketRunner::new(&hanging)
+                .with_timeout(Duration::from_millis(200))
+                .run(&structure)
+                .await,
+            Err(FpocketError::TimedOut(_))
+        ));
+        assert!(started.elapsed() < Duration::from_secs(10));
+
+        assert!(matches!(
+            FPocketRunner::new(dir.path().join("no-such-fpocket"))
+                .detect(&structure)
+                .await,
+            Err(FpocketError::Launch { .. })
+        ));
+    }
+
+    /// Runs the configured fpocket image on a synthetic 300-atom shell
+    /// around a cavity. Needs Docker and `ferrumyx/fpocket:latest`.
+    #[cfg(feature = "docker-tests")]
+    #[tokio::test]
+    async fn detects_the_cavity_in_docker() {
+        if !container::docker_available().await {
+            eprintln!("Docker unavailable, skipping");
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let structure = dir.path().join("cavity.pdb");
+        std::fs::copy(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/cavity.pdb"),
+            &structure,
+        )
+        .unwrap();
+        let runner = FPocketRunner::new("fpocket")
+            .in_docker("ferrumyx/fpocket:latest")
+            .with_cache_dir(dir.path().join("cache"));
+
+        let pockets = runner.detect(&structure).await.unwrap();
+        let best = pockets.first().expect("a pocket in the cavity");
+        assert!(!best.residues.is_empty());
+        assert!(
+            best.center.iter().all(|c| c.abs() < 9.0),
+            "{:?}",
+            best.center
+        );
+        assert_eq!(runner.cached(&structure).unwrap(), Some(pockets));
+    }
 }
@@ -1,15 +1,28 @@
 //! Scoring and ranking of generated molecules.
+//!
+//! The composite follows the multi-objective score of ARCHITECTURE.md §5.3
+//! over the terms computed today: Vina affinity, the rule-based ADMET score
+//! and synthetic accessibility. GNINA rescoring and ChEMBL novelty are not
+//! computed yet, so the weights are renormalised over the three present.
 
-use crate::admet::AdmetProperties;
+use crate::admet::AdmetProfile;
 use crate::ligand::Molecule;
 use serde::{Deserialize, Serialize};
 
+const VINA_WEIGHT: f64 = 0.40;
+const ADMET_WEIGHT: f64 = 0.20;
+const SA_WEIGHT: f64 = 0.10;
+
+/// Affinity that normalises to 1; anything stronger saturates.
+const BEST_AFFINITY_KCAL: f64 = -12.0;
+
 /// A scored molecule with its docking and ADMET results.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScoredMolecule {
     pub molecule: Molecule,
     pub docking_score: f64,
-    pub admet_properties: AdmetProperties,
+    pub admet_properties: AdmetProfile,
+    /// 0 to 1, higher is better.
     pub composite_score: f64,
 }
 
@@ -22,16 +35,19 @@ impl MoleculeScorer {
         Self {}
     }
 
-    /// Score a molecule based on its docking score and ADMET properties.
+    /// Score a molecule based on its docking score and ADMET profile.
     pub fn score(
         &self,
         molecule: Molecule,
         docking_score: f64,
-        admet_properties: AdmetProperties,
+        admet_properties: AdmetProfile,
     ) -> ScoredMolecule {
-        let base_score = docking_score * -1.0;
-        let penalty = admet_properties.ro5_violations as f64 * 2.5;
-        let composite_score = (base_score * admet_properties.qed_estimate) - penalty;
+        let vina = (docking_score / BEST_AFFINITY_KCAL).clamp(0.0, 1.0);
+        // norm(1 / sa): SA runs 1 to 10, so 1 / sa runs 0.1 to 1.
+        let sa = ((1.0 / admet_properties.sa_score - 0.1) / 0.9).clamp(0.0, 1.0);
+        let composite_score =
+            (VINA_WEIGHT * vina + ADMET_WEIGHT * admet_properties.score + SA_WEIGHT * sa)
+                / (VINA_WEIGHT + ADMET_WEIGHT + SA_WEIGHT);
 
         ScoredMolecule {
             molecule,
@@ -51,3 +67,33 @@ impl MoleculeScorer {
         molecules
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::admet::score_admet;
+
+    fn scored(smiles: &str, affinity: f64) -> ScoredMolecule {
+        MoleculeScorer::new().score(
+            Molecule::new(smiles, "test"),
+            affinity,
+            score_admet(smiles).unwrap(),
+        )
+    }
+
+    #[test]
+    fn alerts_and_weak_affinity_rank_lower() {
+        let imatinib = "Cc1ccc(NC(=O)c2ccc(CN3CCN(C)CC3)cc2)cc1Nc1nccc(-c2cccnc2)n1";
+        let ranked = MoleculeScorer::new().rank(vec![
+            scored("O=C1NC(=S)SC1=Cc1ccccc1", -9.0),
+            scored(imatinib, -6.0),
+            scored(imatinib, -9.0),
+        ]);
+        let order: Vec<_> = ranked.iter().map(|m| m.docking_score).collect();
+        assert_eq!(order, [-9.0, -9.0, -6.0]);
+        assert_eq!(ranked[0].molecule.smiles, imatinib);
+        assert!(ranked
+            .iter()
+            .all(|m| (0.0..=1.0).contains(&m.composite_score)));
+    }
+}
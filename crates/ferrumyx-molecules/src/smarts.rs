@@ -0,0 +1,560 @@
+//! SMARTS substructure queries over a [`MolGraph`].
+//!
+//! Covers what structural-alert lists are written in: elements, `a`/`A`/`*`,
+//! `#n`, `H`, `D`, `X`, `R`, `r`, charges, the `! & , ;` operators, the
+//! `- = # : ~ @` bonds and ring closures. Recursive `$()` queries are not
+//! supported and come back as a parse error, as does anything unknown.
+
+use crate::smiles::{atomic_number, capitalised, BondOrder, MolGraph};
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid SMARTS at position {position}: {reason}")]
+pub struct SmartsError {
+    pub position: usize,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum AtomPrimitive {
+    Any,
+    Aromatic(bool),
+    Element {
+        number: u8,
+        aromatic: Option<bool>,
+    },
+    Hydrogens(u8),
+    Degree(u8),
+    Connections(u8),
+    /// `R` alone means any ring, `R0` none, `Rn` membership of n rings.
+    RingMembership(Option<u8>),
+    RingSize(u8),
+    Charge(i8),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr<P> {
+    Primitive(P),
+    Not(Box<Expr<P>>),
+    And(Vec<Expr<P>>),
+    Or(Vec<Expr<P>>),
+}
+
+impl<P> Expr<P> {
+    fn eval(&self, test: &impl Fn(&P) -> bool) -> bool {
+        match self {
+            Expr::Primitive(p) => test(p),
+            Expr::Not(e) => !e.eval(test),
+            Expr::And(es) => es.iter().all(|e| e.eval(test)),
+            Expr::Or(es) => es.iter().any(|e| e.eval(test)),
+        }
+    }
+
+    fn combine(mut items: Vec<Expr<P>>, join: fn(Vec<Expr<P>>) -> Expr<P>) -> Expr<P> {
+        if items.len() == 1 {
+            items.remove(0)
+        } else {
+            join(items)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BondPrimitive {
+    Single,
+    Double,
+    Triple,
+    Aromatic,
+    Any,
+    Ring,
+    /// No bond written: single or aromatic.
+    Implicit,
+}
+
+#[derive(Debug, Clone)]
+struct QueryBond {
+    atoms: [usize; 2],
+    expr: Expr<BondPrimitive>,
+}
+
+/// A parsed SMARTS pattern.
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    atoms: Vec<Expr<AtomPrimitive>>,
+    bonds: Vec<QueryBond>,
+}
+
+impl Pattern {
+    pub fn parse(smarts: &str) -> Result<Self, SmartsError> {
+        Parser {
+            chars: smarts.chars().collect(),
+            pos: 0,
+        }
+        .parse()
+    }
+
+    /// Whether `pattern` occurs anywhere in `mol`.
+    pub fn matches(&self, mol: &MolGraph) -> bool {
+        if self.atoms.is_empty() {
+            return false;
+        }
+        let order = self.search_order();
+        let mut assigned = vec![usize::MAX; self.atoms.len()];
+        let mut used = vec![false; mol.atoms.len()];
+        self.extend(mol, &order, 0, &mut assigned, &mut used)
+    }
+
+    /// Query atoms so that each one after the first of its fragment is
+    /// bonded to an earlier one, which keeps the backtracking local.
+    fn search_order(&self) -> Vec<usize> {
+        let mut order = Vec::with_capacity(self.atoms.len());
+        let mut seen = vec![false; self.atoms.len()];
+        for start in 0..self.atoms.len() {
+            if seen[start] {
+                continue;
+            }
+            seen[start] = true;
+            let mut stack = vec![start];
+            while let Some(atom) = stack.pop() {
+                order.push(atom);
+                for bond in self.bonds.iter().filter(|b| b.atoms.contains(&atom)) {
+                    let next = bond.atoms[usize::from(bond.atoms[0] == atom)];
+                    if !seen[next] {
+                        seen[next] = true;
+                        stack.push(next);
+                    }
+                }
+            }
+        }
+        order
+    }
+
+    fn extend(
+        &self,
+        mol: &MolGraph,
+        order: &[usize],
+        depth: usize,
+        assigned: &mut [usize],
+        used: &mut [bool],
+    ) -> bool {
+        let Some(&query) = order.get(depth) else {
+            return true;
+        };
+        for target in 0..mol.atoms.len() {
+            if used[target] || !self.atom_matches(query, mol, target) {
+                continue;
+            }
+            let bonds_match = self.bonds.iter().all(|bond| {
+                let other = match bond.atoms {
+                    [a, b] if a == query => b,
+                    [a, b] if b == query => a,
+                    _ => return true,
+                };
+                if assigned[other] == usize::MAX {
+                    return true;
+                }
+                mol.bond_index(target, assigned[other])
+                    .is_some_and(|b| bond_matches(&bond.expr, mol, b))
+            });
+            if !bonds_match {
+                continue;
+            }
+            assigned[query] = target;
+            used[target] = true;
+            if self.extend(mol, order, depth + 1, assigned, used) {
+                return true;
+            }
+            assigned[query] = usize::MAX;
+            used[target] = false;
+        }
+        false
+    }
+
+    fn atom_matches(&self, query: usize, mol: &MolGraph, target: usize) -> bool {
+        let atom = &mol.atoms[target];
+        self.atoms[query].eval(&|p: &AtomPrimitive| match *p {
+            AtomPrimitive::Any => true,
+            AtomPrimitive::Aromatic(aromatic) => atom.aromatic == aromatic,
+            AtomPrimitive::Element { number, aromatic } => {
+                atom.atomic_number == number && aromatic.is_none_or(|a| a == atom.aromatic)
+            }
+            AtomPrimitive::Hydrogens(n) => atom.hydrogens == n,
+            AtomPrimitive::Degree(n) => mol.degree(target) == usize::from(n),
+            AtomPrimitive::Connections(n) => {
+                mol.degree(target) + usize::from(atom.hydrogens) == usize::from(n)
+            }
+            AtomPrimitive::RingMembership(None) => mol.is_ring_atom(target),
+            AtomPrimitive::RingMembership(Some(n)) => {
+                mol.rings_containing(target).count() == usize::from(n)
+            }
+            AtomPrimitive::RingSize(n) => mol
+                .rings_containing(target)
+                .any(|r| r.len() == usize::from(n)),
+            AtomPrimitive::Charge(c) => atom.charge == c,
+        })
+    }
+}
+
+fn bond_matches(expr: &Expr<BondPrimitive>, mol: &MolGraph, bond: usize) -> bool {
+    let order = mol.bonds[bond].order;
+    expr.eval(&|p: &BondPrimitive| match p {
+        BondPrimitive::Single => order == BondOrder::Single,
+        BondPrimitive::Double => order == BondOrder::Double,
+        BondPrimitive::Triple => order == BondOrder::Triple,
+        BondPrimitive::Aromatic => order == BondOrder::Aromatic,
+        BondPrimitive::Any => true,
+        BondPrimitive::Ring => mol.is_ring_bond(bond),
+        BondPrimitive::Implicit => matches!(order, BondOrder::Single | BondOrder::Aromatic),
+    })
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn error(&self, reason: impl Into<String>) -> SmartsError {
+        SmartsError {
+            position: self.pos,
+            reason: reason.into(),
+        }
+    }
+
+    fn parse(mut self) -> Result<Pattern, SmartsError> {
+        let mut atoms = Vec::new();
+        let mut bonds = Vec::new();
+        let mut previous: Option<usize> = None;
+        let mut branches = Vec::new();
+        let mut pending: Option<Expr<BondPrimitive>> = None;
+        let mut open_rings: Vec<(u32, usize, Option<Expr<BondPrimitive>>)> = Vec::new();
+
+        while let Some(c) = self.peek() {
+            match c {
+                '(' => {
+                    branches.push(previous.ok_or_else(|| self.error("branch before any atom"))?);
+                    self.pos += 1;
+                }
+                ')' => {
+                    previous = Some(branches.pop().ok_or_else(|| self.error("unbalanced ')'"))?);
+                    self.pos += 1;
+                }
+                '.' => {
+                    previous = None;
+                    self.pos += 1;
+                }
+                '-' | '=' | '#' | ':' | '~' | '@' | '!' | '/' | '\\' => {
+                    pending = Some(self.bond_expr()?);
+                }
+                '0'..='9' | '%' => {
+                    let atom = previous.ok_or_else(|| self.error("ring bond before any atom"))?;
+                    let number = self.ring_number()?;
+                    let expr = pending.take();
+                    match open_rings.iter().position(|(n, _, _)| *n == number) {
+                        Some(i) => {
+                            let (_, other, first) = open_rings.remove(i);
+                            bonds.push(QueryBond {
+                                atoms: [other, atom],
+                                expr: expr
+                                    .or(first)
+                                    .unwrap_or(Expr::Primitive(BondPrimitive::Implicit)),
+                            });
+                        }
+                        None => open_rings.push((number, atom, expr)),
+                    }
+                }
+                _ => {
+                    atoms.push(self.atom()?);
+                    let atom = atoms.len() - 1;
+                    if let Some(previous) = previous {
+                        bonds.push(QueryBond {
+                            atoms: [previous, atom],
+                            expr: pending
+                                .take()
+                                .unwrap_or(Expr::Primitive(BondPrimitive::Implicit)),
+                        });
+                    }
+                    previous = Some(atom);
+                }
+            }
+        }
+        if !branches.is_empty() {
+            return Err(self.error("unclosed '('"));
+        }
+        if let Some((number, _, _)) = open_rings.first() {
+            return Err(self.error(format!("ring bond {number} is never closed")));
+        }
+        if atoms.is_empty() {
+            return Err(self.error("no atoms"));
+        }
+        Ok(Pattern { atoms, bonds })
+    }
+
+    fn ring_number(&mut self) -> Result<u32, SmartsError> {
+        let c = self
+            .peek()
+            .ok_or_else(|| self.error("expected ring bond"))?;
+        self.pos += 1;
+        if let Some(d) = c.to_digit(10) {
+            return Ok(d);
+        }
+        let digits: String = self.chars[self.pos..].iter().take(2).collect();
+        self.pos += 2;
+        digits
+            .parse()
+            .map_err(|_| self.error("expected two digits after '%'"))
+    }
+
+    fn bond_expr(&mut self) -> Result<Expr<BondPrimitive>, SmartsError> {
+        let mut low = Vec::new();
+        loop {
+            let mut or = Vec::new();
+            loop {
+                let mut and = Vec::new();
+                while let Some(term) = self.bond_term()? {
+                    and.push(term);
+                    if self.peek() == Some('&') {
+                        self.pos += 1;
+                    }
+                }
+                if and.is_empty() {
+                    return Err(self.error("expected a bond"));
+                }
+                or.push(Expr::combine(and, Expr::And));
+                if self.peek() != Some(',') {
+                    break;
+                }
+                self.pos += 1;
+            }
+            low.push(Expr::combine(or, Expr::Or));
+            if self.peek() != Some(';') {
+                break;
+            }
+            self.pos += 1;
+        }
+        Ok(Expr::combine(low, Expr::And))
+    }
+
+    fn bond_term(&mut self) -> Result<Option<Expr<BondPrimitive>>, SmartsError> {
+        let Some(c) = self.peek() else {
+            return Ok(None);
+        };
+        if c == '!' {
+            self.pos += 1;
+            let inner = self
+                .bond_term()?
+                .ok_or_else(|| self.error("expected a bond after '!'"))?;
+            return Ok(Some(Expr::Not(Box::new(inner))));
+        }
+        let primitive = match c {
+            '-' | '/' | '\\' => BondPrimitive::Single,
+            '=' => BondPrimitive::Double,
+            '#' => BondPrimitive::Triple,
+            ':' => BondPrimitive::Aromatic,
+            '~' => BondPrimitive::Any,
+            '@' => BondPrimitive::Ring,
+            _ => return Ok(None),
+        };
+        self.pos += 1;
+        Ok(Some(Expr::Primitive(primitive)))
+    }
+
+    fn atom(&mut self) -> Result<Expr<AtomPrimitive>, SmartsError> {
+        if self.peek() == Some('[') {
+            self.pos += 1;
+            let expr = self.atom_expr()?;
+            if self.peek() != Some(']') {
+                return Err(self.error("expected ']'"));
+            }
+            self.pos += 1;
+            return Ok(expr);
+        }
+        let two: String = self.chars[self.pos..].iter().take(2).collect();
+        let (primitive, len) = match self.peek() {
+            _ if two == "Cl" => (element(17, Some(false)), 2),
+            _ if two == "Br" => (element(35, Some(false)), 2),
+            Some('*') => (AtomPrimitive::Any, 1),
+            Some('a') => (AtomPrimitive::Aromatic(true), 1),
+            Some('A') => (AtomPrimitive::Aromatic(false), 1),
+            Some(c @ ('B' | 'C' | 'N' | 'O' | 'P' | 'S' | 'F' | 'I')) => {
+                (element(symbol_number(c), Some(false)), 1)
+            }
+            Some(c @ ('b' | 'c' | 'n' | 'o' | 'p' | 's')) => (
+                element(symbol_number(c.to_ascii_uppercase()), Some(true)),
+                1,
+            ),
+            _ => return Err(self.error("expected an atom")),
+        };
+        self.pos += len;
+        Ok(Expr::Primitive(primitive))
+    }
+
+    fn atom_expr(&mut self) -> Result<Expr<AtomPrimitive>, SmartsError> {
+        let mut low = Vec::new();
+        loop {
+            let mut or = Vec::new();
+            loop {
+                let mut and = Vec::new();
+                loop {
+                    and.push(self.atom_term()?);
+                    match self.peek() {
+                        Some('&') => self.pos += 1,
+                        Some(',' | ';' | ']') | None => break,
+                        Some(_) => {}
+                    }
+                }
+                or.push(Expr::combine(and, Expr::And));
+                if self.peek() != Some(',') {
+                    break;
+                }
+                self.pos += 1;
+            }
+            low.push(Expr::combine(or, Expr::Or));
+            if self.peek() != Some(';') {
+                break;
+            }
+            self.pos += 1;
+        }
+        Ok(Expr::combine(low, Expr::And))
+    }
+
+    fn atom_term(&mut self) -> Result<Expr<AtomPrimitive>, SmartsError> {
+        let c = self.peek().ok_or_else(|| self.error("unclosed '['"))?;
+        if c == '!' {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.atom_term()?)));
+        }
+        self.pos += 1;
+        let primitive = match c {
+            '*' => AtomPrimitive::Any,
+            'a' => AtomPrimitive::Aromatic(true),
+            'A' => AtomPrimitive::Aromatic(false),
+            '#' => {
+                let number = self
+                    .number()
+                    .ok_or_else(|| self.error("expected an atomic number"))?;
+                element(number as u8, None)
+            }
+            'H' => AtomPrimitive::Hydrogens(self.number().unwrap_or(1) as u8),
+            'D' => AtomPrimitive::Degree(self.number().unwrap_or(1) as u8),
+            'X' => AtomPrimitive::Connections(self.number().unwrap_or(1) as u8),
+            'R' => AtomPrimitive::RingMembership(self.number().map(|n| n as u8)),
+            'r' => AtomPrimitive::RingSize(
+                self.number()
+                    .ok_or_else(|| self.error("expected a ring size"))? as u8,
+            ),
+            '+' | '-' => {
+                let sign: i32 = if c == '+' { 1 } else { -1 };
+                let mut magnitude = 1;
+                while self.peek() == Some(c) {
+                    self.pos += 1;
+                    magnitude += 1;
+                }
+                if let Some(n) = self.number() {
+                    magnitude = n as i32;
+                }
+                AtomPrimitive::Charge((sign * magnitude) as i8)
+            }
+            '$' => return Err(self.error("recursive SMARTS is not supported")),
+            c if c.is_ascii_lowercase() => {
+                let two: String = [c]
+                    .into_iter()
+                    .chain(self.peek().filter(char::is_ascii_lowercase))
+                    .collect();
+                let (symbol, len) = if matches!(two.as_str(), "se" | "as" | "te") {
+                    (two, 1)
+                } else {
+                    (c.to_string(), 0)
+                };
+                let number = atomic_number(&capitalised(&symbol))
+                    .filter(|_| matches!(c, 'b' | 'c' | 'n' | 'o' | 'p' | 's' | 'a' | 't'))
+                    .ok_or_else(|| self.error(format!("unknown aromatic atom {symbol:?}")))?;
+                self.pos += len;
+                element(number, Some(true))
+            }
+            c if c.is_ascii_uppercase() => {
+                let two: Option<String> = self
+                    .peek()
+                    .filter(char::is_ascii_lowercase)
+                    .map(|l| format!("{c}{l}"));
+                match two.as_deref().and_then(atomic_number) {
+                    Some(number) => {
+                        self.pos += 1;
+                        element(number, Some(false))
+                    }
+                    None => element(
+                        atomic_number(&c.to_string())
+                            .ok_or_else(|| self.error(format!("unknown element {c:?}")))?,
+                        Some(false),
+                    ),
+                }
+            }
+            _ => {
+                self.pos -= 1;
+                return Err(self.error(format!("unexpected {c:?}")));
+            }
+        };
+        Ok(Expr::Primitive(primitive))
+    }
+
+    fn number(&mut self) -> Option<u32> {
+        let digits: String = self.chars[self.pos..]
+            .iter()
+            .take_while(|c| c.is_ascii_digit())
+            .collect();
+        self.pos += digits.len();
+        digits.parse().ok()
+    }
+}
+
+fn element(number: u8, aromatic: Option<bool>) -> AtomPrimitive {
+    AtomPrimitive::Element { number, aromatic }
+}
+
+fn symbol_number(c: char) -> u8 {
+    atomic_number(&c.to_string()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(smarts: &str, smiles: &str) -> bool {
+        Pattern::parse(smarts)
+            .unwrap()
+            .matches(&MolGraph::parse(smiles).unwrap())
+    }
+
+    #[test]
+    fn matches_atoms_bonds_and_rings() {
+        assert!(matches("c1ccccc1", "Cc1ccccc1"));
+        assert!(!matches("c1ccccc1", "C1CCCCC1"));
+        assert!(matches("C(=O)[OH]", "CC(=O)O"));
+        assert!(!matches("C(=O)[OH]", "CC(=O)OC"));
+        assert!(matches("[#7;R]", "C1CCNCC1"));
+        assert!(!matches("[#7;R]", "CCN"));
+        assert!(matches("[c;r5]", "c1ccsc1"));
+        assert!(matches("[CX4][NH2]", "CCN"));
+        assert!(matches("[N+](=O)[O-]", "c1ccccc1[N+](=O)[O-]"));
+        assert!(matches("[C,N]~[O;D1]", "CC=O"));
+        assert!(matches("[!#6;!#1]", "CO"));
+        assert!(!matches("*@*", "CCCC"));
+    }
+
+    #[test]
+    fn each_target_atom_is_used_once() {
+        assert!(!matches("CCC", "CC"));
+        assert!(matches("C(C)(C)C", "CC(C)C"));
+        assert!(!matches("C(C)(C)(C)C", "CC(C)C"));
+    }
+
+    #[test]
+    fn unsupported_syntax_is_an_error() {
+        assert!(Pattern::parse("[$(CC)]").is_err());
+        assert!(Pattern::parse("C1CC").is_err());
+        assert!(Pattern::parse("[Qq]").is_err());
+    }
+}
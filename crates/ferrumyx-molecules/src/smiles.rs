@@ -0,0 +1,1106 @@
+//! SMILES parsing into a molecular graph.
+//!
+//! [`MolGraph`] holds heavy atoms with their hydrogen counts, bonds, and
+//! the smallest set of smallest rings: what [`descriptors`](crate::descriptors)
+//! and the [`smarts`](crate::smarts) matcher need. Aromaticity is taken as
+//! written. Lowercase atoms are aromatic and Kekulé rings are not
+//! re-perceived; ChEMBL and PubChem canonical SMILES are aromatic already.
+//! Chirality marks are kept only as a flag.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Why a SMILES string could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SmilesError {
+    #[error("empty SMILES")]
+    Empty,
+    #[error("unexpected {found:?} at position {position}")]
+    Unexpected { found: char, position: usize },
+    #[error("unknown element {symbol:?} at position {position}")]
+    UnknownElement { symbol: String, position: usize },
+    #[error("unbalanced parenthesis at position {0}")]
+    UnbalancedBranch(usize),
+    #[error("ring bond {0} is never closed")]
+    UnclosedRing(u32),
+}
+
+/// A heavy atom. Hydrogens are folded into their neighbour's count.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Atom {
+    /// 0 for the `*` wildcard.
+    pub atomic_number: u8,
+    pub aromatic: bool,
+    pub charge: i8,
+    /// Attached hydrogens: implicit ones for unbracketed atoms, the written
+    /// count for bracket atoms.
+    pub hydrogens: u8,
+    /// Written with `@` or `@@`.
+    pub chiral: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BondOrder {
+    Single,
+    Double,
+    Triple,
+    Aromatic,
+}
+
+impl BondOrder {
+    /// Contribution to an atom's valence; aromatic bonds count one here and
+    /// the extra electron is added per atom.
+    pub(crate) fn valence(self) -> u8 {
+        match self {
+            BondOrder::Single | BondOrder::Aromatic => 1,
+            BondOrder::Double => 2,
+            BondOrder::Triple => 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bond {
+    pub atoms: [usize; 2],
+    pub order: BondOrder,
+}
+
+impl Bond {
+    /// The atom at the other end from `atom`.
+    pub fn other(&self, atom: usize) -> usize {
+        if self.atoms[0] == atom {
+            self.atoms[1]
+        } else {
+            self.atoms[0]
+        }
+    }
+}
+
+/// A parsed molecule.
+#[derive(Debug, Clone)]
+pub struct MolGraph {
+    pub atoms: Vec<Atom>,
+    pub bonds: Vec<Bond>,
+    /// Bond indices per atom.
+    adjacency: Vec<Vec<usize>>,
+    ring_bonds: Vec<bool>,
+    /// Smallest set of smallest rings, each as atoms in ring order.
+    rings: Vec<Vec<usize>>,
+}
+
+impl MolGraph {
+    /// Parse `smiles`; only its first whitespace-separated field is read,
+    /// so a trailing name is ignored.
+    pub fn parse(smiles: &str) -> Result<Self, SmilesError> {
+        let smiles = smiles.split_whitespace().next().ok_or(SmilesError::Empty)?;
+        Parser::new(smiles).parse()
+    }
+
+    /// `(neighbour, bond)` pairs of `atom`.
+    pub fn neighbors(&self, atom: usize) -> impl Iterator<Item = (usize, &Bond)> + '_ {
+        self.adjacency[atom].iter().map(move |&b| {
+            let bond = &self.bonds[b];
+            (bond.other(atom), bond)
+        })
+    }
+
+    /// Heavy-atom neighbours of `atom`.
+    pub fn degree(&self, atom: usize) -> usize {
+        self.adjacency[atom].len()
+    }
+
+    pub fn bond_between(&self, a: usize, b: usize) -> Option<&Bond> {
+        self.adjacency[a]
+            .iter()
+            .map(|&i| &self.bonds[i])
+            .find(|bond| bond.other(a) == b)
+    }
+
+    pub fn is_ring_bond(&self, bond: usize) -> bool {
+        self.ring_bonds[bond]
+    }
+
+    pub fn bond_index(&self, a: usize, b: usize) -> Option<usize> {
+        self.adjacency[a]
+            .iter()
+            .copied()
+            .find(|&i| self.bonds[i].other(a) == b)
+    }
+
+    pub fn is_ring_atom(&self, atom: usize) -> bool {
+        self.adjacency[atom].iter().any(|&b| self.ring_bonds[b])
+    }
+
+    /// Smallest set of smallest rings.
+    pub fn rings(&self) -> &[Vec<usize>] {
+        &self.rings
+    }
+
+    /// Rings of [`rings`](Self::rings) containing `atom`.
+    pub fn rings_containing(&self, atom: usize) -> impl Iterator<Item = &Vec<usize>> + '_ {
+        self.rings.iter().filter(move |ring| ring.contains(&atom))
+    }
+
+    /// Build a graph from atoms whose hydrogen counts are already set, as
+    /// when editing a parsed molecule; ring bonds and rings are recomputed.
+    pub fn new(atoms: Vec<Atom>, bonds: Vec<Bond>) -> Self {
+        Self::from_parts(atoms, bonds)
+    }
+
+    /// SMILES that is the same however the molecule was written, to key
+    /// deduplication on. Chirality is not written.
+    pub fn canonical_smiles(&self) -> String {
+        Writer::new(self).write()
+    }
+
+    fn from_parts(atoms: Vec<Atom>, bonds: Vec<Bond>) -> Self {
+        let mut adjacency = vec![Vec::new(); atoms.len()];
+        for (i, bond) in bonds.iter().enumerate() {
+            adjacency[bond.atoms[0]].push(i);
+            adjacency[bond.atoms[1]].push(i);
+        }
+        let mut mol = Self {
+            atoms,
+            bonds,
+            adjacency,
+            ring_bonds: Vec::new(),
+            rings: Vec::new(),
+        };
+        mol.ring_bonds = (0..mol.bonds.len())
+            .map(|b| mol.shortest_path_avoiding(b).is_some())
+            .collect();
+        // An unmarked bond between two aromatic atoms of different rings,
+        // as in `c1ccccc1c1ccccc1`, is a single bond.
+        for (bond, &in_ring) in mol.bonds.iter_mut().zip(&mol.ring_bonds) {
+            if !in_ring && bond.order == BondOrder::Aromatic {
+                bond.order = BondOrder::Single;
+            }
+        }
+        mol.rings = mol.smallest_rings();
+        mol
+    }
+
+    /// Atoms from one end of `bond` to the other without crossing it.
+    fn shortest_path_avoiding(&self, bond: usize) -> Option<Vec<usize>> {
+        let [start, goal] = self.bonds[bond].atoms;
+        let mut previous: HashMap<usize, usize> = HashMap::from([(start, start)]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(atom) = queue.pop_front() {
+            if atom == goal {
+                let mut path = vec![goal];
+                let mut at = goal;
+                while at != start {
+                    at = previous[&at];
+                    path.push(at);
+                }
+                return Some(path);
+            }
+            for &b in &self.adjacency[atom] {
+                let next = self.bonds[b].other(atom);
+                if b != bond && !previous.contains_key(&next) {
+                    previous.insert(next, atom);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Shortest cycle through each ring bond, kept smallest first while
+    /// independent of those already kept, up to the cycle rank.
+    fn smallest_rings(&self) -> Vec<Vec<usize>> {
+        let ring_bond_count = self.ring_bonds.iter().filter(|&&r| r).count();
+        if ring_bond_count == 0 {
+            return Vec::new();
+        }
+        let mut candidates: Vec<Vec<usize>> = Vec::new();
+        let mut seen: HashSet<Vec<usize>> = HashSet::new();
+        for bond in (0..self.bonds.len()).filter(|&b| self.ring_bonds[b]) {
+            if let Some(path) = self.shortest_path_avoiding(bond) {
+                let mut key = path.clone();
+                key.sort_unstable();
+                if seen.insert(key) {
+                    candidates.push(path);
+                }
+            }
+        }
+        candidates.sort_by_key(Vec::len);
+
+        let rank = self.bonds.len() + self.components() - self.atoms.len();
+        let words = self.bonds.len().div_ceil(64);
+        let mut basis: Vec<Vec<u64>> = Vec::new();
+        let mut rings = Vec::new();
+        for ring in candidates {
+            if rings.len() == rank {
+                break;
+            }
+            let mut bits = vec![0u64; words];
+            for (i, &atom) in ring.iter().enumerate() {
+                let next = ring[(i + 1) % ring.len()];
+                if let Some(b) = self.bond_index(atom, next) {
+                    bits[b / 64] |= 1 << (b % 64);
+                }
+            }
+            if reduce(&mut bits, &basis) {
+                basis.push(bits);
+                rings.push(ring);
+            }
+        }
+        rings
+    }
+
+    fn components(&self) -> usize {
+        let mut seen = vec![false; self.atoms.len()];
+        let mut count = 0;
+        for start in 0..self.atoms.len() {
+            if seen[start] {
+                continue;
+            }
+            count += 1;
+            seen[start] = true;
+            let mut stack = vec![start];
+            while let Some(atom) = stack.pop() {
+                for (next, _) in self.neighbors(atom) {
+                    if !seen[next] {
+                        seen[next] = true;
+                        stack.push(next);
+                    }
+                }
+            }
+        }
+        count
+    }
+}
+
+/// Reduce `bits` by the GF(2) row-echelon `basis`; true when something
+/// independent is left, which then joins the basis in echelon form.
+fn reduce(bits: &mut [u64], basis: &[Vec<u64>]) -> bool {
+    for row in basis {
+        let pivot = leading_bit(row);
+        if pivot.is_some_and(|p| bits[p / 64] & (1 << (p % 64)) != 0) {
+            for (b, r) in bits.iter_mut().zip(row) {
+                *b ^= r;
+            }
+        }
+    }
+    leading_bit(bits).is_some()
+}
+
+fn leading_bit(bits: &[u64]) -> Option<usize> {
+    bits.iter()
+        .enumerate()
+        .find(|(_, &w)| w != 0)
+        .map(|(i, w)| i * 64 + w.trailing_zeros() as usize)
+}
+
+/// `(symbol, atomic number)` of the elements SMILES may name.
+const ELEMENTS: &[(&str, u8)] = &[
+    ("H", 1),
+    ("He", 2),
+    ("Li", 3),
+    ("Be", 4),
+    ("B", 5),
+    ("C", 6),
+    ("N", 7),
+    ("O", 8),
+    ("F", 9),
+    ("Ne", 10),
+    ("Na", 11),
+    ("Mg", 12),
+    ("Al", 13),
+    ("Si", 14),
+    ("P", 15),
+    ("S", 16),
+    ("Cl", 17),
+    ("Ar", 18),
+    ("K", 19),
+    ("Ca", 20),
+    ("Fe", 26),
+    ("Co", 27),
+    ("Cu", 29),
+    ("Zn", 30),
+    ("Ga", 31),
+    ("Ge", 32),
+    ("As", 33),
+    ("Se", 34),
+    ("Br", 35),
+    ("Kr", 36),
+    ("Pt", 78),
+    ("Sn", 50),
+    ("Te", 52),
+    ("I", 53),
+    ("Xe", 54),
+    ("Gd", 64),
+    ("Au", 79),
+    ("Hg", 80),
+];
+
+pub(crate) fn atomic_number(symbol: &str) -> Option<u8> {
+    ELEMENTS.iter().find(|(s, _)| *s == symbol).map(|&(_, n)| n)
+}
+
+pub(crate) fn capitalised(symbol: &str) -> String {
+    let mut s = symbol.to_string();
+    s[..1].make_ascii_uppercase();
+    s
+}
+
+/// Symbols written in lowercase for aromatic atoms.
+const AROMATIC: &[&str] = &["b", "c", "n", "o", "p", "s", "se", "as", "te"];
+
+/// Normal valences of the unbracketed "organic subset" atoms.
+fn default_valences(atomic_number: u8, aromatic: bool) -> &'static [u8] {
+    match (atomic_number, aromatic) {
+        (5, _) => &[3],
+        (6, _) => &[4],
+        (7, true) => &[3],
+        (7, false) => &[3, 5],
+        (8, _) => &[2],
+        (15, true) => &[3],
+        (15, false) => &[3, 5],
+        (16, true) => &[2],
+        (16, false) => &[2, 4, 6],
+        (9 | 17 | 35 | 53, _) => &[1],
+        _ => &[],
+    }
+}
+
+/// Hydrogens an unbracketed atom carries when its bonds use `used` valence.
+fn implicit_hydrogens(atom: &Atom, used: u8, has_aromatic_bond: bool) -> u8 {
+    // Aromatic b, c, n and p spend one more electron on the ring; o and s
+    // donate a lone pair instead.
+    let pi = u8::from(
+        atom.aromatic && has_aromatic_bond && matches!(atom.atomic_number, 5 | 6 | 7 | 15),
+    );
+    let used = used + pi;
+    default_valences(atom.atomic_number, atom.aromatic)
+        .iter()
+        .find(|&&v| v >= used)
+        .map_or(0, |v| v - used)
+}
+
+struct PendingAtom {
+    atom: Atom,
+    /// Bracket atoms carry their own hydrogen count.
+    bracket: bool,
+}
+
+struct Parser<'a> {
+    chars: Vec<char>,
+    pos: usize,
+    atoms: Vec<PendingAtom>,
+    bonds: Vec<Bond>,
+    source: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            chars: source.chars().collect(),
+            pos: 0,
+            atoms: Vec::new(),
+            bonds: Vec::new(),
+            source,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn unexpected(&self) -> SmilesError {
+        match self.peek() {
+            Some(found) => SmilesError::Unexpected {
+                found,
+                position: self.pos,
+            },
+            None => SmilesError::Unexpected {
+                found: self.source.chars().last().unwrap_or(' '),
+                position: self.pos.saturating_sub(1),
+            },
+        }
+    }
+
+    fn parse(mut self) -> Result<MolGraph, SmilesError> {
+        let mut previous: Option<usize> = None;
+        let mut branches: Vec<Option<usize>> = Vec::new();
+        let mut pending: Option<Option<BondOrder>> = None;
+        let mut open_rings: HashMap<u32, (usize, Option<BondOrder>)> = HashMap::new();
+
+        while let Some(c) = self.peek() {
+            match c {
+                '(' => {
+                    if previous.is_none() {
+                        return Err(self.unexpected());
+                    }
+                    branches.push(previous);
+                    self.pos += 1;
+                }
+                ')' => {
+                    previous = branches
+                        .pop()
+                        .ok_or(SmilesError::UnbalancedBranch(self.pos))?;
+                    self.pos += 1;
+                }
+                '.' => {
+                    previous = None;
+                    self.pos += 1;
+                }
+                '-' | '=' | '#' | ':' | '/' | '\\' | '$' => {
+                    pending = Some(Some(match c {
+                        '=' => BondOrder::Double,
+                        '#' | '$' => BondOrder::Triple,
+                        ':' => BondOrder::Aromatic,
+                        _ => BondOrder::Single,
+                    }));
+                    self.pos += 1;
+                }
+                '0'..='9' | '%' => {
+                    let atom = previous.ok_or_else(|| self.unexpected())?;
+                    let number = self.ring_number()?;
+                    let order = pending.take().flatten();
+                    match open_rings.remove(&number) {
+                        Some((other, first)) => self.bond(other, atom, order.or(first)),
+                        None => {
+                            open_rings.insert(number, (atom, order));
+                        }
+                    }
+                }
+                _ => {
+                    let atom = self.atom()?;
+                    if let Some(previous) = previous {
+                        let order = pending.take().flatten();
+                        self.bond(previous, atom, order);
+                    } else if pending.is_some() {
+                        return Err(self.unexpected());
+                    }
+                    previous = Some(atom);
+                }
+            }
+        }
+        if !branches.is_empty() {
+            return Err(SmilesError::UnbalancedBranch(self.pos));
+        }
+        if let Some(&number) = open_rings.keys().min() {
+            return Err(SmilesError::UnclosedRing(number));
+        }
+        if self.atoms.is_empty() {
+            return Err(SmilesError::Empty);
+        }
+        Ok(self.finish())
+    }
+
+    fn ring_number(&mut self) -> Result<u32, SmilesError> {
+        let c = self.peek().ok_or_else(|| self.unexpected())?;
+        self.pos += 1;
+        if c != '%' {
+            return Ok(c.to_digit(10).unwrap_or_default());
+        }
+        let digits: String = self.chars[self.pos..]
+            .iter()
+            .take(2)
+            .take_while(|c| c.is_ascii_digit())
+            .collect();
+        if digits.len() != 2 {
+            return Err(self.unexpected());
+        }
+        self.pos += 2;
+        Ok(digits.parse().unwrap_or_default())
+    }
+
+    fn bond(&mut self, a: usize, b: usize, order: Option<BondOrder>) {
+        let order = order.unwrap_or(
+            if self.atoms[a].atom.aromatic && self.atoms[b].atom.aromatic {
+                BondOrder::Aromatic
+            } else {
+                BondOrder::Single
+            },
+        );
+        self.bonds.push(Bond {
+            atoms: [a, b],
+            order,
+        });
+    }
+
+    fn atom(&mut self) -> Result<usize, SmilesError> {
+        let pending = if self.peek() == Some('[') {
+            self.pos += 1;
+            self.bracket_atom()?
+        } else {
+            self.organic_atom()?
+        };
+        self.atoms.push(pending);
+        Ok(self.atoms.len() - 1)
+    }
+
+    fn organic_atom(&mut self) -> Result<PendingAtom, SmilesError> {
+        let start = self.pos;
+        let c = self.peek().ok_or_else(|| self.unexpected())?;
+        let two: String = self.chars[self.pos..].iter().take(2).collect();
+        let (symbol, aromatic) = match c {
+            _ if two == "Cl" || two == "Br" => (two.as_str(), false),
+            'B' | 'C' | 'N' | 'O' | 'P' | 'S' | 'F' | 'I' => (&two[..1], false),
+            'b' | 'c' | 'n' | 'o' | 'p' | 's' => (&two[..1], true),
+            '*' => ("*", false),
+            _ => return Err(self.unexpected()),
+        };
+        let atomic_number = match symbol {
+            "*" => 0,
+            _ => {
+                atomic_number(&capitalised(symbol)).ok_or_else(|| SmilesError::UnknownElement {
+                    symbol: symbol.to_string(),
+                    position: start,
+                })?
+            }
+        };
+        self.pos += symbol.len();
+        Ok(PendingAtom {
+            atom: Atom {
+                atomic_number,
+                aromatic,
+                charge: 0,
+                hydrogens: 0,
+                chiral: false,
+            },
+            bracket: false,
+        })
+    }
+
+    fn bracket_atom(&mut self) -> Result<PendingAtom, SmilesError> {
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1; // isotope
+        }
+        let start = self.pos;
+        let first = self.peek().ok_or_else(|| self.unexpected())?;
+        let (atomic_number, aromatic) = if first == '*' {
+            self.pos += 1;
+            (0, false)
+        } else if first.is_ascii_lowercase() {
+            let two: String = self.chars[self.pos..].iter().take(2).collect();
+            let symbol = if AROMATIC.contains(&two.as_str()) {
+                two
+            } else {
+                first.to_string()
+            };
+            if !AROMATIC.contains(&symbol.as_str()) {
+                return Err(self.unexpected());
+            }
+            self.pos += symbol.len();
+            (
+                atomic_number(&capitalised(&symbol)).unwrap_or_default(),
+                true,
+            )
+        } else if first.is_ascii_uppercase() {
+            let second = self.chars.get(self.pos + 1).copied();
+            let two = second
+                .filter(char::is_ascii_lowercase)
+                .map(|s| format!("{first}{s}"));
+            let (symbol, n) = match two.as_deref().and_then(|s| Some((s, atomic_number(s)?))) {
+                Some((s, n)) => (s.to_string(), n),
+                None => {
+                    let s = first.to_string();
+                    let n = atomic_number(&s).ok_or_else(|| SmilesError::UnknownElement {
+                        symbol: s.clone(),
+                        position: start,
+                    })?;
+                    (s, n)
+                }
+            };
+            self.pos += symbol.len();
+            (n, false)
+        } else {
+            return Err(self.unexpected());
+        };
+
+        let mut chiral = false;
+        while self.peek() == Some('@') {
+            chiral = true;
+            self.pos += 1;
+        }
+        // @TH1, @SP2 and the like.
+        while chiral
+            && self
+                .peek()
+                .is_some_and(|c| c.is_ascii_uppercase() && c != 'H')
+        {
+            self.pos += 1;
+        }
+        while chiral && self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+
+        let mut hydrogens = 0;
+        if self.peek() == Some('H') {
+            self.pos += 1;
+            hydrogens = self.number().unwrap_or(1) as u8;
+        }
+
+        let mut charge: i32 = 0;
+        while let Some(sign @ ('+' | '-')) = self.peek() {
+            self.pos += 1;
+            let unit = if sign == '+' { 1 } else { -1 };
+            charge += unit * self.number().unwrap_or(1) as i32;
+        }
+
+        if self.peek() == Some(':') {
+            self.pos += 1;
+            self.number();
+        }
+        if self.peek() != Some(']') {
+            return Err(self.unexpected());
+        }
+        self.pos += 1;
+        Ok(PendingAtom {
+            atom: Atom {
+                atomic_number,
+                aromatic,
+                charge: charge.clamp(-8, 8) as i8,
+                hydrogens,
+                chiral,
+            },
+            bracket: true,
+        })
+    }
+
+    fn number(&mut self) -> Option<u32> {
+        let digits: String = self.chars[self.pos..]
+            .iter()
+            .take_while(|c| c.is_ascii_digit())
+            .collect();
+        self.pos += digits.len();
+        digits.parse().ok()
+    }
+
+    /// Implicit hydrogens, then explicit `[H]` atoms folded into their
+    /// neighbours.
+    fn finish(self) -> MolGraph {
+        let mut used = vec![0u8; self.atoms.len()];
+        let mut has_aromatic_bond = vec![false; self.atoms.len()];
+        for bond in &self.bonds {
+            for &atom in &bond.atoms {
+                used[atom] += bond.order.valence();
+                has_aromatic_bond[atom] |= bond.order == BondOrder::Aromatic;
+            }
+        }
+        let mut atoms: Vec<Atom> = self
+            .atoms
+            .into_iter()
+            .enumerate()
+            .map(|(i, pending)| {
+                let mut atom = pending.atom;
+                if !pending.bracket {
+                    atom.hydrogens = implicit_hydrogens(&atom, used[i], has_aromatic_bond[i]);
+                }
+                atom
+            })
+            .collect();
+
+        let explicit_h: Vec<bool> = atoms
+            .iter()
+            .enumerate()
+            .map(|(i, a)| {
+                a.atomic_number == 1
+                    && a.charge == 0
+                    && self.bonds.iter().filter(|b| b.atoms.contains(&i)).count() == 1
+            })
+            .collect();
+        let mut bonds = Vec::with_capacity(self.bonds.len());
+        for bond in self.bonds {
+            let [a, b] = bond.atoms;
+            match (explicit_h[a], explicit_h[b]) {
+                (true, false) => atoms[b].hydrogens += 1,
+                (false, true) => atoms[a].hydrogens += 1,
+                _ => bonds.push(bond),
+            }
+        }
+        let mut index = vec![usize::MAX; atoms.len()];
+        let mut kept = Vec::with_capacity(atoms.len());
+        for (i, atom) in atoms.into_iter().enumerate() {
+            if !explicit_h[i] {
+                index[i] = kept.len();
+                kept.push(atom);
+            }
+        }
+        for bond in &mut bonds {
+            bond.atoms = bond.atoms.map(|a| index[a]);
+        }
+        bonds.retain(|b| b.atoms.iter().all(|&a| a != usize::MAX));
+        MolGraph::from_parts(kept, bonds)
+    }
+}
+
+/// Canonical SMILES output. Atoms are ranked by their invariants, refined
+/// by their neighbours' ranks until stable, with ties among symmetric atoms
+/// broken one at a time; the depth-first walk then starts from the
+/// lowest-ranked atom of least degree and takes neighbours in rank order.
+struct Writer<'a> {
+    mol: &'a MolGraph,
+    rank: Vec<usize>,
+    visited: Vec<bool>,
+    seen_bond: Vec<bool>,
+    /// Tree edges as `(child, bond)`, in walk order.
+    children: Vec<Vec<(usize, usize)>>,
+    /// Ring-closure bonds at each atom.
+    closures: Vec<Vec<usize>>,
+    /// Open ring-closure bond to the digit it was given.
+    open: HashMap<usize, u32>,
+}
+
+impl<'a> Writer<'a> {
+    fn new(mol: &'a MolGraph) -> Self {
+        let n = mol.atoms.len();
+        Self {
+            mol,
+            rank: canonical_ranks(mol),
+            visited: vec![false; n],
+            seen_bond: vec![false; mol.bonds.len()],
+            children: vec![Vec::new(); n],
+            closures: vec![Vec::new(); n],
+            open: HashMap::new(),
+        }
+    }
+
+    fn write(mut self) -> String {
+        let mut order: Vec<usize> = (0..self.mol.atoms.len()).collect();
+        // Starting from a terminal atom keeps the first branch short.
+        order.sort_by_key(|&i| (self.mol.degree(i), self.rank[i]));
+        let mut roots = Vec::new();
+        for start in order {
+            if !self.visited[start] {
+                roots.push(start);
+                self.walk(start, None);
+            }
+        }
+        let mut out = String::new();
+        for (i, root) in roots.into_iter().enumerate() {
+            if i > 0 {
+                out.push('.');
+            }
+            self.emit(root, &mut out);
+        }
+        out
+    }
+
+    fn walk(&mut self, atom: usize, parent_bond: Option<usize>) {
+        self.visited[atom] = true;
+        let mut next: Vec<(usize, usize)> = self
+            .mol
+            .neighbors(atom)
+            .map(|(other, _)| (other, self.mol.bond_index(atom, other).unwrap_or_default()))
+            .collect();
+        next.sort_by_key(|&(other, _)| self.rank[other]);
+        for (other, bond) in next {
+            if Some(bond) == parent_bond || self.seen_bond[bond] {
+                continue;
+            }
+            self.seen_bond[bond] = true;
+            if self.visited[other] {
+                self.closures[atom].push(bond);
+                self.closures[other].push(bond);
+            } else {
+                self.children[atom].push((other, bond));
+                self.walk(other, Some(bond));
+            }
+        }
+    }
+
+    fn emit(&mut self, atom: usize, out: &mut String) {
+        out.push_str(&atom_symbol(self.mol, atom));
+        let mut closures = std::mem::take(&mut self.closures[atom]);
+        closures.sort_by_key(|&b| self.rank[self.mol.bonds[b].other(atom)]);
+        for bond in closures {
+            let digit = match self.open.remove(&bond) {
+                Some(digit) => digit,
+                None => {
+                    let digit = (1..)
+                        .find(|d| !self.open.values().any(|v| v == d))
+                        .unwrap_or(1);
+                    self.open.insert(bond, digit);
+                    out.push_str(bond_symbol(self.mol, bond));
+                    digit
+                }
+            };
+            if digit < 10 {
+                out.push_str(&digit.to_string());
+            } else {
+                out.push_str(&format!("%{digit}"));
+            }
+        }
+        let children = std::mem::take(&mut self.children[atom]);
+        let last = children.len().saturating_sub(1);
+        for (i, (child, bond)) in children.into_iter().enumerate() {
+            if i < last {
+                out.push('(');
+            }
+            out.push_str(bond_symbol(self.mol, bond));
+            self.emit(child, out);
+            if i < last {
+                out.push(')');
+            }
+        }
+    }
+}
+
+fn canonical_ranks(mol: &MolGraph) -> Vec<usize> {
+    let invariants: Vec<_> = mol
+        .atoms
+        .iter()
+        .enumerate()
+        .map(|(i, a)| {
+            (
+                a.atomic_number,
+                a.aromatic,
+                a.charge,
+                a.hydrogens,
+                mol.degree(i),
+                mol.is_ring_atom(i),
+            )
+        })
+        .collect();
+    let mut rank = dense_ranks(&invariants);
+    loop {
+        rank = refine_ranks(mol, rank);
+        let mut counts = vec![0usize; rank.len()];
+        for &r in &rank {
+            counts[r] += 1;
+        }
+        let Some(tied) = counts.iter().position(|&c| c > 1) else {
+            return rank;
+        };
+        let chosen = rank.iter().position(|&r| r == tied).unwrap_or_default();
+        let split: Vec<_> = rank
+            .iter()
+            .enumerate()
+            .map(|(i, &r)| (r, i != chosen))
+            .collect();
+        rank = dense_ranks(&split);
+    }
+}
+
+/// Split ranks by the sorted `(rank, bond order)` of each atom's
+/// neighbours until no class splits further.
+fn refine_ranks(mol: &MolGraph, mut rank: Vec<usize>) -> Vec<usize> {
+    let classes = |rank: &[usize]| rank.iter().max().map_or(0, |&r| r + 1);
+    loop {
+        let keys: Vec<(usize, Vec<(usize, u8)>)> = (0..rank.len())
+            .map(|i| {
+                let mut around: Vec<_> = mol
+                    .neighbors(i)
+                    .map(|(j, bond)| (rank[j], bond_code(bond.order)))
+                    .collect();
+                around.sort_unstable();
+                (rank[i], around)
+            })
+            .collect();
+        let next = dense_ranks(&keys);
+        if classes(&next) == classes(&rank) {
+            return next;
+        }
+        rank = next;
+    }
+}
+
+/// Position of each key among the distinct keys in sorted order.
+fn dense_ranks<T: Ord>(keys: &[T]) -> Vec<usize> {
+    let mut distinct: Vec<&T> = keys.iter().collect();
+    distinct.sort();
+    distinct.dedup();
+    keys.iter()
+        .map(|k| distinct.binary_search(&k).unwrap_or_default())
+        .collect()
+}
+
+fn bond_code(order: BondOrder) -> u8 {
+    match order {
+        BondOrder::Single => 1,
+        BondOrder::Double => 2,
+        BondOrder::Triple => 3,
+        BondOrder::Aromatic => 4,
+    }
+}
+
+/// Bond symbols are left out where the parser would infer the same order.
+fn bond_symbol(mol: &MolGraph, bond: usize) -> &'static str {
+    let bond = &mol.bonds[bond];
+    let aromatic = bond.atoms.iter().all(|&a| mol.atoms[a].aromatic);
+    match bond.order {
+        BondOrder::Double => "=",
+        BondOrder::Triple => "#",
+        BondOrder::Single if aromatic => "-",
+        BondOrder::Aromatic if !aromatic => ":",
+        BondOrder::Single | BondOrder::Aromatic => "",
+    }
+}
+
+/// The atom unbracketed when the parser would read back the same charge
+/// and hydrogens, bracketed otherwise.
+fn atom_symbol(mol: &MolGraph, i: usize) -> String {
+    let atom = &mol.atoms[i];
+    let element = ELEMENTS
+        .iter()
+        .find(|&&(_, n)| n == atom.atomic_number)
+        .map_or("*", |&(s, _)| s);
+    let symbol = if atom.aromatic {
+        element.to_ascii_lowercase()
+    } else {
+        element.to_string()
+    };
+    let organic = if atom.aromatic {
+        matches!(atom.atomic_number, 5..=8 | 15 | 16)
+    } else {
+        matches!(atom.atomic_number, 0 | 5..=9 | 15..=17 | 35 | 53)
+    };
+    let used: u8 = mol.neighbors(i).map(|(_, b)| b.order.valence()).sum();
+    let has_aromatic_bond = mol
+        .neighbors(i)
+        .any(|(_, b)| b.order == BondOrder::Aromatic);
+    if organic
+        && atom.charge == 0
+        && atom.hydrogens == implicit_hydrogens(atom, used, has_aromatic_bond)
+    {
+        return symbol;
+    }
+    let mut out = format!("[{symbol}");
+    match atom.hydrogens {
+        0 => {}
+        1 => out.push('H'),
+        h => out.push_str(&format!("H{h}")),
+    }
+    match atom.charge {
+        0 => {}
+        1 => out.push('+'),
+        -1 => out.push('-'),
+        c if c > 0 => out.push_str(&format!("+{c}")),
+        c => out.push_str(&format!("-{}", -c)),
+    }
+    out.push(']');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hydrogens(smiles: &str) -> Vec<u8> {
+        MolGraph::parse(smiles)
+            .unwrap()
+            .atoms
+            .iter()
+            .map(|a| a.hydrogens)
+            .collect()
+    }
+
+    #[test]
+    fn implicit_hydrogens_follow_normal_valences() {
+        assert_eq!(hydrogens("CCO"), [3, 2, 1]);
+        assert_eq!(hydrogens("C=CC#N"), [2, 1, 0, 0]);
+        assert_eq!(hydrogens("c1ccncc1"), [1, 1, 1, 0, 1, 1]);
+        assert_eq!(hydrogens("c1cc[nH]c1"), [1, 1, 1, 1, 1]);
+        assert_eq!(hydrogens("c1ccsc1"), [1, 1, 1, 0, 1]);
+        assert_eq!(hydrogens("CN(=O)=O"), [3, 0, 0, 0]);
+        assert_eq!(hydrogens("C[N+](=O)[O-]"), [3, 0, 0, 0]);
+        assert_eq!(hydrogens("[H]C([H])([H])O"), [3, 1]);
+        assert_eq!(hydrogens("CS(=O)(=O)N"), [3, 0, 0, 0, 2]);
+        assert_eq!(hydrogens("ClC(Br)I"), [0, 1, 0, 0]);
+    }
+
+    #[test]
+    fn reads_rings_branches_and_charges() {
+        let naphthalene = MolGraph::parse("c1ccc2ccccc2c1").unwrap();
+        assert_eq!(naphthalene.atoms.len(), 10);
+        assert_eq!(naphthalene.bonds.len(), 11);
+        let mut sizes: Vec<_> = naphthalene.rings().iter().map(Vec::len).collect();
+        sizes.sort_unstable();
+        assert_eq!(sizes, [6, 6]);
+        assert_eq!(naphthalene.rings_containing(3).count(), 2);
+
+        let cubane = MolGraph::parse("C12C3C4C1C5C2C3C45").unwrap();
+        assert_eq!(cubane.rings().len(), 5);
+        assert!(cubane.rings().iter().all(|r| r.len() == 4));
+
+        let ester = MolGraph::parse("CC(=O)OC1CC1 ethyl").unwrap();
+        assert_eq!(ester.bond_between(1, 2).unwrap().order, BondOrder::Double);
+        assert!(!ester.is_ring_atom(3));
+        assert!(ester.is_ring_atom(4));
+
+        let biphenyl = MolGraph::parse("c1ccccc1c1ccccc1").unwrap();
+        assert_eq!(
+            biphenyl.bond_between(5, 6).unwrap().order,
+            BondOrder::Single
+        );
+
+        let salt = MolGraph::parse("[NH4+].[Cl-]").unwrap();
+        assert_eq!((salt.atoms[0].charge, salt.atoms[0].hydrogens), (1, 4));
+        assert_eq!(salt.atoms[1].charge, -1);
+
+        let alanine = MolGraph::parse("C[C@@H](N)C(=O)O").unwrap();
+        assert!(alanine.atoms[1].chiral);
+        assert_eq!(alanine.atoms[1].hydrogens, 1);
+    }
+
+    #[test]
+    fn canonical_smiles_ignores_how_the_molecule_was_written() {
+        let canonical = |s: &str| MolGraph::parse(s).unwrap().canonical_smiles();
+        for group in [
+            &["CCO", "OCC", "C(O)C", "[CH3][CH2][OH]"][..],
+            &["c1ccncc1", "n1ccccc1", "c1cnccc1"],
+            &["CC(=O)Oc1ccccc1C(=O)O", "OC(=O)c1ccccc1OC(C)=O"],
+            &[
+                "Cc1ccc(NC(=O)c2ccc(CN3CCN(C)CC3)cc2)cc1Nc1nccc(-c2cccnc2)n1",
+                "CN1CCN(Cc2ccc(cc2)C(=O)Nc2ccc(C)c(Nc3nccc(n3)-c3cccnc3)c2)CC1",
+            ],
+            &["[NH4+].[Cl-]", "[Cl-].[NH4+]"],
+            &["C[C@@H](N)C(=O)O", "NC(C)C(O)=O"],
+        ] {
+            let expected = canonical(group[0]);
+            for smiles in group {
+                assert_eq!(canonical(smiles), expected, "{smiles}");
+            }
+        }
+        assert_ne!(canonical("Oc1ccccc1C"), canonical("Oc1ccc(C)cc1"));
+    }
+
+    #[test]
+    fn canonical_smiles_reads_back_as_the_same_molecule() {
+        for smiles in [
+            "c1ccc2ccccc2c1",
+            "C12C3C4C1C5C2C3C45",
+            "c1ccccc1-c1ccccc1",
+            "c1cc[nH]c1",
+            "C[N+](=O)[O-]",
+            "CS(=O)(=O)N",
+            "C#Cc1cccc(Nc2ncnc3cc(OCCOC)c(OCCOC)cc23)c1",
+            "[Na+].[O-]C(=O)C",
+        ] {
+            let mol = MolGraph::parse(smiles).unwrap();
+            let canonical = mol.canonical_smiles();
+            let again = MolGraph::parse(&canonical).unwrap();
+            assert_eq!(again.atoms.len(), mol.atoms.len(), "{canonical}");
+            assert_eq!(again.bonds.len(), mol.bonds.len(), "{canonical}");
+            let hydrogens =
+                |m: &MolGraph| m.atoms.iter().map(|a| u32::from(a.hydrogens)).sum::<u32>();
+            assert_eq!(hydrogens(&again), hydrogens(&mol), "{canonical}");
+            assert_eq!(again.canonical_smiles(), canonical);
+        }
+    }
+
+    #[test]
+    fn malformed_smiles_are_errors() {
+        assert_eq!(MolGraph::parse("  ").unwrap_err(), SmilesError::Empty);
+        assert_eq!(
+            MolGraph::parse("c1ccccc").unwrap_err(),
+            SmilesError::UnclosedRing(1)
+        );
+        assert!(matches!(
+            MolGraph::parse("CC(C"),
+            Err(SmilesError::UnbalancedBranch(_))
+        ));
+        assert!(matches!(
+            MolGraph::parse("CXC"),
+            Err(SmilesError::Unexpected { found: 'X', .. })
+        ));
+        assert!(matches!(
+            MolGraph::parse("C[Qq]"),
+            Err(SmilesError::UnknownElement { .. })
+        ));
+    }
+}
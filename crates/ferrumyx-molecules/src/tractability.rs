@@ -0,0 +1,407 @@
+//! Structural tractability of a target protein.
+//!
+//! [`StructuralProvider`] looks up experimental structures for a UniProt
+//! accession in RCSB, falls back to the AlphaFold model's pLDDT when none
+//! exist, and runs fpocket on the best available structure. The result is
+//! cached in `ent_structural_assessments`, since fpocket takes seconds per
+//! protein.
+
+use crate::pdb::{mean_plddt, StructureFetcher};
+use crate::pocket::{FPocketRunner, PocketSummary};
+use anyhow::Result;
+use ferrumyx_db::schema::EntStructuralAssessment;
+use ferrumyx_db::Phase4SignalRepository;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+const RCSB_SEARCH_URL: &str = "https://search.rcsb.org/rcsbsearch/v2/query";
+const RCSB_ENTRY_URL: &str = "https://data.rcsb.org/rest/v1/core/entry";
+
+/// Structure and pocket evidence for one protein.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StructuralAssessment {
+    pub uniprot_id: String,
+    pub has_pdb: bool,
+    /// Experimental RCSB entries that contain the protein.
+    pub pdb_count: u32,
+    /// Entry with the best resolution.
+    pub best_pdb_id: Option<String>,
+    /// Best experimental resolution in Å.
+    pub best_resolution: Option<f64>,
+    /// Mean pLDDT of the AlphaFold model; only read without an experimental
+    /// structure.
+    pub alphafold_plddt: Option<f64>,
+    /// Highest fpocket druggability score, 0.0–1.0.
+    pub best_pocket_score: Option<f64>,
+    pub pocket_count: Option<u32>,
+}
+
+impl StructuralAssessment {
+    /// One-line evidence summary, e.g. `experimental structure: 4 (best
+    /// 1.9Å)`.
+    pub fn evidence_line(&self) -> String {
+        let mut line = if self.has_pdb {
+            match self.best_resolution {
+                Some(res) => format!(
+                    "experimental structure: {} (best {:.1}Å)",
+                    self.pdb_count, res
+                ),
+                None => format!("experimental structure: {}", self.pdb_count),
+            }
+        } else {
+            match self.alphafold_plddt {
+                Some(plddt) => format!("AlphaFold model only (mean pLDDT {:.0})", plddt),
+                None => "no structure".to_string(),
+            }
+        };
+        if let (Some(count), Some(score)) = (self.pocket_count, self.best_pocket_score) {
+            line.push_str(&format!(
+                "; {} pockets (best druggability {:.2})",
+                count, score
+            ));
+        }
+        line
+    }
+
+    fn source(&self) -> String {
+        let mut source = if self.has_pdb { "rcsb" } else { "alphafold" }.to_string();
+        if self.pocket_count.is_some() {
+            source.push_str("+fpocket");
+        }
+        source
+    }
+
+    pub fn to_record(&self) -> EntStructuralAssessment {
+        EntStructuralAssessment {
+            id: uuid::Uuid::new_v4(),
+            uniprot_id: self.uniprot_id.clone(),
+            has_pdb: self.has_pdb,
+            pdb_count: self.pdb_count as i64,
+            best_pdb_id: self.best_pdb_id.clone(),
+            best_resolution: self.best_resolution,
+            alphafold_plddt: self.alphafold_plddt,
+            best_pocket_score: self.best_pocket_score,
+            pocket_count: self.pocket_count.map(|c| c as i64),
+            source: self.source(),
+            fetched_at: chrono::Utc::now(),
+        }
+    }
+}
+
+impl From<EntStructuralAssessment> for StructuralAssessment {
+    fn from(record: EntStructuralAssessment) -> Self {
+        Self {
+            uniprot_id: record.uniprot_id,
+            has_pdb: record.has_pdb,
+            pdb_count: record.pdb_count.max(0) as u32,
+            best_pdb_id: record.best_pdb_id,
+            best_resolution: record.best_resolution,
+            alphafold_plddt: record.alphafold_plddt,
+            best_pocket_score: record.best_pocket_score,
+            pocket_count: record.pocket_count.map(|c| c.max(0) as u32),
+        }
+    }
+}
+
+/// Where a structure file came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StructureSource {
+    Pdb,
+    AlphaFold,
+}
+
+/// A downloaded structure file and what is known about it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FetchedStructure {
+    pub path: PathBuf,
+    pub source: StructureSource,
+    pub pdb_id: Option<String>,
+    /// Experimental resolution in Å.
+    pub resolution: Option<f64>,
+    /// Mean pLDDT of an AlphaFold model.
+    pub plddt: Option<f64>,
+}
+
+/// Builds [`StructuralAssessment`]s from RCSB, AlphaFold and fpocket.
+pub struct StructuralProvider {
+    client: Client,
+    fetcher: StructureFetcher,
+    fpocket: Option<FPocketRunner>,
+}
+
+impl StructuralProvider {
+    /// Provider that downloads structures into `cache_dir` and skips
+    /// fpocket.
+    pub fn new<P: AsRef<Path>>(cache_dir: P) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(std::time::Duration::from_secs(20))
+                .build()
+                .unwrap_or_default(),
+            fetcher: StructureFetcher::new(cache_dir),
+            fpocket: None,
+        }
+    }
+
+    /// Run fpocket from `executable_path` on the best structure. Its
+    /// pockets are cached under `<cache_dir>/fpocket`.
+    pub fn with_fpocket<P: AsRef<Path>>(mut self, executable_path: P) -> Self {
+        let cache_dir = self.fetcher.cache_dir().join("fpocket");
+        self.fpocket = Some(FPocketRunner::new(executable_path).with_cache_dir(cache_dir));
+        self
+    }
+
+    /// Cached assessment for `uniprot_id`, if fetched within
+    /// `max_age_days`.
+    pub async fn cached(
+        repo: &Phase4SignalRepository,
+        uniprot_id: &str,
+        max_age_days: i64,
+    ) -> Result<Option<StructuralAssessment>> {
+        Ok(repo
+            .find_structural_assessment_fresh(uniprot_id, max_age_days)
+            .await?
+            .map(StructuralAssessment::from))
+    }
+
+    /// Cached assessment when fresh, otherwise [`Self::assess`] and store
+    /// the result.
+    pub async fn assess_cached(
+        &self,
+        repo: &Phase4SignalRepository,
+        uniprot_id: &str,
+        max_age_days: i64,
+    ) -> Result<StructuralAssessment> {
+        if let Some(cached) = Self::cached(repo, uniprot_id, max_age_days).await? {
+            return Ok(cached);
+        }
+        let assessment = self.assess(uniprot_id).await?;
+        if let Err(e) = repo
+            .upsert_structural_assessment(&assessment.to_record())
+            .await
+        {
+            warn!(
+                "Failed to cache structural assessment for {}: {}",
+                uniprot_id, e
+            );
+        }
+        Ok(assessment)
+    }
+
+    /// Query RCSB, AlphaFold and fpocket for `uniprot_id`.
+    ///
+    /// Only the RCSB search is required; a missing AlphaFold model or a
+    /// failed fpocket run leaves the corresponding fields empty.
+    pub async fn assess(&self, uniprot_id: &str) -> Result<StructuralAssessment> {
+        let uniprot_id = uniprot_id.trim().to_uppercase();
+        if uniprot_id.is_empty() {
+            anyhow::bail!("empty UniProt accession");
+        }
+
+        let (pdb_count, best_pdb_id) = self.search_experimental(&uniprot_id).await?;
+        let mut best_resolution = None;
+        let mut structure: Option<PathBuf> = None;
+        if let Some(id) = &best_pdb_id {
+            best_resolution = self.entry_resolution(id).await.unwrap_or_else(|e| {
+                debug!("No resolution for {}: {}", id, e);
+                None
+            });
+            structure = self.fetcher.fetch_pdb(id).await.ok();
+        }
+
+        let mut alphafold_plddt = None;
+        if pdb_count == 0 || structure.is_none() {
+            match self.fetcher.fetch_alphafold(&uniprot_id).await {
+                Ok(path) => {
+                    if pdb_count == 0 {
+                        alphafold_plddt = tokio::fs::read_to_string(&path)
+                            .await
+                            .ok()
+                            .and_then(|text| mean_plddt(&text));
+                    }
+                    structure = structure.or(Some(path));
+                }
+                Err(e) => debug!("No AlphaFold model for {}: {}", uniprot_id, e),
+            }
+        }
+
+        let pockets = match (&self.fpocket, &structure) {
+            (Some(runner), Some(path)) => match runner.detect(path).await {
+                Ok(pockets) => PocketSummary::of(&pockets),
+                Err(e) => {
+                    warn!("fpocket failed for {}: {}", uniprot_id, e);
+                    None
+                }
+            },
+            _ => None,
+        };
+
+        Ok(StructuralAssessment {
+            uniprot_id,
+            has_pdb: pdb_count > 0,
+            pdb_count,
+            best_pdb_id,
+            best_resolution,
+            alphafold_plddt,
+            best_pocket_score: pockets.map(|p| p.best_druggability),
+            pocket_count: pockets.map(|p| p.pocket_count),
+        })
+    }
+
+    /// The best-resolution experimental structure of `uniprot_id`, or its
+    /// AlphaFold model when RCSB has none.
+    pub async fn fetch_best_structure(&self, uniprot_id: &str) -> Result<FetchedStructure> {
+        let uniprot_id = uniprot_id.trim().to_uppercase();
+        if uniprot_id.is_empty() {
+            anyhow::bail!("empty UniProt accession");
+        }
+        if let (_, Some(pdb_id)) = self.search_experimental(&uniprot_id).await? {
+            match self.fetch_pdb_entry(&pdb_id).await {
+                Ok(structure) => return Ok(structure),
+                Err(e) => warn!("Failed to fetch PDB {} for {}: {}", pdb_id, uniprot_id, e),
+            }
+        }
+        let path = self.fetcher.fetch_alphafold(&uniprot_id).await?;
+        let plddt = tokio::fs::read_to_string(&path)
+            .await
+            .ok()
+            .and_then(|text| mean_plddt(&text));
+        Ok(FetchedStructure {
+            path,
+            source: StructureSource::AlphaFold,
+            pdb_id: None,
+            resolution: None,
+            plddt,
+        })
+    }
+
+    /// The RCSB entry `pdb_id` and its resolution.
+    pub async fn fetch_pdb_entry(&self, pdb_id: &str) -> Result<FetchedStructure> {
+        let pdb_id = pdb_id.trim().to_uppercase();
+        let path = self.fetcher.fetch_pdb(&pdb_id).await?;
+        let resolution = self.entry_resolution(&pdb_id).await.unwrap_or_else(|e| {
+            debug!("No resolution for {}: {}", pdb_id, e);
+            None
+        });
+        Ok(FetchedStructure {
+            path,
+            source: StructureSource::Pdb,
+            pdb_id: Some(pdb_id),
+            resolution,
+            plddt: None,
+        })
+    }
+
+    /// Number of experimental entries for the accession and the one with
+    /// the best resolution.
+    async fn search_experimental(&self, uniprot_id: &str) -> Result<(u32, Option<String>)> {
+        let response = self
+            .client
+            .post(RCSB_SEARCH_URL)
+            .json(&search_query(uniprot_id))
+            .send()
+            .await?
+            .error_for_status()?;
+        // RCSB answers 204 with an empty body when nothing matches.
+        if response.status() == reqwest::StatusCode::NO_CONTENT {
+            return Ok((0, None));
+        }
+        let body: serde_json::Value = response.json().await?;
+        Ok(parse_search_response(&body))
+    }
+
+    async fn entry_resolution(&self, pdb_id: &str) -> Result<Option<f64>> {
+        let url = format!("{}/{}", RCSB_ENTRY_URL, pdb_id);
+        let body: serde_json::Value = self
+            .client
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(body["rcsb_entry_info"]["resolution_combined"]
+            .as_array()
+            .and_then(|values| values.iter().filter_map(|v| v.as_f64()).reduce(f64::min)))
+    }
+}
+
+/// Experimental entries containing `uniprot_id`, best resolution first.
+fn search_query(uniprot_id: &str) -> serde_json::Value {
+    serde_json::json!({
+        "query": {
+            "type": "terminal",
+            "service": "text",
+            "parameters": {
+                "attribute": "rcsb_polymer_entity_container_identifiers.reference_sequence_identifiers.database_accession",
+                "operator": "exact_match",
+                "value": uniprot_id
+            }
+        },
+        "return_type": "entry",
+        "request_options": {
+            "results_content_type": ["experimental"],
+            "sort": [{ "sort_by": "rcsb_entry_info.resolution_combined", "direction": "asc" }],
+            "paginate": { "start": 0, "rows": 1 }
+        }
+    })
+}
+
+fn parse_search_response(body: &serde_json::Value) -> (u32, Option<String>) {
+    let count = body["total_count"]
+        .as_u64()
+        .unwrap_or(0)
+        .min(u32::MAX as u64) as u32;
+    let best = body["result_set"]
+        .as_array()
+        .and_then(|rows| rows.first())
+        .and_then(|row| row["identifier"].as_str())
+        .map(str::to_string);
+    (count, best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rcsb_search_response() {
+        let body = serde_json::json!({
+            "total_count": 4,
+            "result_set": [{ "identifier": "6OIM", "score": 1.0 }]
+        });
+        assert_eq!(parse_search_response(&body), (4, Some("6OIM".to_string())));
+        assert_eq!(parse_search_response(&serde_json::json!({})), (0, None));
+    }
+
+    #[test]
+    fn evidence_line_reports_structures_and_pockets() {
+        let mut assessment = StructuralAssessment {
+            uniprot_id: "P01116".to_string(),
+            has_pdb: true,
+            pdb_count: 4,
+            best_pdb_id: Some("6OIM".to_string()),
+            best_resolution: Some(1.9),
+            alphafold_plddt: None,
+            best_pocket_score: None,
+            pocket_count: None,
+        };
+        assert_eq!(
+            assessment.evidence_line(),
+            "experimental structure: 4 (best 1.9Å)"
+        );
+
+        assessment.has_pdb = false;
+        assessment.pdb_count = 0;
+        assessment.alphafold_plddt = Some(71.4);
+        assessment.pocket_count = Some(3);
+        assessment.best_pocket_score = Some(0.82);
+        assert_eq!(
+            assessment.evidence_line(),
+            "AlphaFold model only (mean pLDDT 71); 3 pockets (best druggability 0.82)"
+        );
+    }
+}
@@ -0,0 +1,729 @@
+//! Explain why a gene is absent from ranking output.
+//!
+//! Walks the same gates a gene passes on its way into a shortlist — symbol
+//! resolution, the scored universe, hard and campaign constraints, provider
+//! coverage and the secondary score floor — and stops at the first one that
+//! removed it. The result is a single structured narrative the UI renders as
+//! a short explanation.
+
+use crate::scorer::{hard_exclusion_constraint, PenaltyInputs};
+use crate::weights::WeightVector;
+use ferrumyx_common::Constraints;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// Canonical component names paired with the keys they are persisted under
+/// in `components_normed` by the different scorers.
+pub const COMPONENT_KEYS: &[(&str, &[&str])] = &[
+    (
+        "mutation_freq",
+        &["mutation_freq", "mutation_score", "n1_mutation_freq"],
+    ),
+    (
+        "crispr_dependency",
+        &["crispr_dependency", "crispr_score", "n2_crispr_dependency"],
+    ),
+    (
+        "survival_correlation",
+        &[
+            "survival_correlation",
+            "survival_score",
+            "n3_survival_correlation",
+        ],
+    ),
+    (
+        "expression_specificity",
+        &[
+            "expression_specificity",
+            "expression_score",
+            "n4_expression_specificity",
+        ],
+    ),
+    (
+        "structural_tractability",
+        &[
+            "structural_tractability",
+            "tractability_score",
+            "n5_structural_tractability",
+        ],
+    ),
+    (
+        "pocket_detectability",
+        &[
+            "pocket_detectability",
+            "pocket_score",
+            "n6_pocket_detectability",
+        ],
+    ),
+    ("novelty_score", &["novelty_score", "n7_novelty_score"]),
+    (
+        "pathway_independence",
+        &["pathway_independence", "n8_pathway_independence"],
+    ),
+    (
+        "literature_novelty",
+        &[
+            "literature_novelty",
+            "literature_score",
+            "n9_literature_novelty",
+        ],
+    ),
+];
+
+/// Maximum edit distance for a symbol correction to be suggested.
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+/// Maximum number of symbol corrections returned.
+const MAX_SUGGESTIONS: usize = 5;
+
+/// Which gate removed the gene from ranking output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AbsenceCategory {
+    /// The symbol does not resolve to an approved gene.
+    UnresolvedSymbol,
+    /// The gene was never scored for this cancer type.
+    NotInUniverse,
+    /// A hard or campaign constraint excluded the gene.
+    ExcludedByConstraint,
+    /// Too few score components had provider data.
+    InsufficientCoverage,
+    /// The gene was scored but fell below the secondary threshold.
+    BelowThreshold,
+    /// The gene is not absent: it sits in a shortlist tier.
+    Ranked,
+}
+
+/// Tunable gates for [`explain_absence`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbsencePolicy {
+    /// Adjusted score a gene needs to reach the secondary shortlist.
+    pub secondary_threshold: f64,
+    /// Fewest components with provider data before coverage is the reason.
+    pub min_covered_components: usize,
+}
+
+impl Default for AbsencePolicy {
+    fn default() -> Self {
+        Self {
+            secondary_threshold: 0.50,
+            min_covered_components: 3,
+        }
+    }
+}
+
+/// Case-insensitive symbol → approved symbol index used for resolution and
+/// correction suggestions.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolIndex {
+    lookup: HashMap<String, String>,
+}
+
+impl SymbolIndex {
+    /// Build from `(any known symbol, approved symbol)` pairs.
+    pub fn from_pairs<I, K, V>(pairs: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: Into<String>,
+    {
+        let lookup = pairs
+            .into_iter()
+            .map(|(k, v)| (k.as_ref().trim().to_ascii_uppercase(), v.into()))
+            .filter(|(k, _)| !k.is_empty())
+            .collect();
+        Self { lookup }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lookup.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lookup.is_empty()
+    }
+
+    /// Approved symbol for any known symbol, alias or previous symbol.
+    pub fn resolve(&self, symbol: &str) -> Option<&str> {
+        self.lookup
+            .get(&symbol.trim().to_ascii_uppercase())
+            .map(String::as_str)
+    }
+
+    /// Approved symbols whose known spellings are within a small edit
+    /// distance of `symbol`, closest first.
+    pub fn suggest(&self, symbol: &str) -> Vec<String> {
+        let query = symbol.trim().to_ascii_uppercase();
+        let mut scored: Vec<(usize, &str)> = self
+            .lookup
+            .iter()
+            .filter_map(|(k, v)| {
+                let d = edit_distance(&query, k);
+                (d <= MAX_SUGGESTION_DISTANCE).then_some((d, v.as_str()))
+            })
+            .collect();
+        scored.sort();
+        let mut seen = BTreeSet::new();
+        scored
+            .into_iter()
+            .filter(|(_, v)| seen.insert(*v))
+            .take(MAX_SUGGESTIONS)
+            .map(|(_, v)| v.to_string())
+            .collect()
+    }
+}
+
+/// One persisted target score, as read back from the score table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredGeneScore {
+    pub gene: String,
+    pub cancer_type: String,
+    pub tier: String,
+    pub confidence_adjusted_score: f64,
+    pub components_raw: serde_json::Value,
+    pub components_normed: serde_json::Value,
+}
+
+impl StoredGeneScore {
+    /// Normalised value of a canonical component, if any provider supplied it.
+    pub fn component(&self, name: &str) -> Option<f64> {
+        let (_, keys) = COMPONENT_KEYS.iter().find(|(n, _)| *n == name)?;
+        keys.iter()
+            .find_map(|k| self.components_normed.get(*k).and_then(|v| v.as_f64()))
+    }
+
+    fn raw_f64(&self, keys: &[&str]) -> Option<f64> {
+        keys.iter()
+            .find_map(|k| self.components_raw.get(*k).and_then(|v| v.as_f64()))
+    }
+
+    fn is_shortlisted(&self) -> bool {
+        self.tier.eq_ignore_ascii_case("primary") || self.tier.eq_ignore_ascii_case("secondary")
+    }
+}
+
+/// The constraint that excluded a gene, with the value that failed it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConstraintViolation {
+    pub constraint: String,
+    pub observed: Option<f64>,
+    pub required: Option<f64>,
+}
+
+/// The component contributing most to the gap below the threshold.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComponentDeficit {
+    pub component: String,
+    pub value: f64,
+    pub weight: f64,
+    /// Weighted shortfall from a perfect component score: `weight * (1 - value)`.
+    pub weighted_deficit: f64,
+}
+
+/// Structured narrative explaining a gene's absence from ranking output.
+#[derive(Debug, Clone, Serialize)]
+pub struct AbsenceExplanation {
+    pub query: String,
+    pub gene: Option<String>,
+    pub cancer_type: Option<String>,
+    pub category: AbsenceCategory,
+    /// One-sentence explanation suitable for direct display.
+    pub summary: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub suggestions: Vec<String>,
+    /// Cancer types the gene was scored under, when not this one.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub scored_in: Vec<String>,
+    pub universe_size: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub constraint: Option<ConstraintViolation>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub missing_components: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tier: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub biggest_deficit: Option<ComponentDeficit>,
+}
+
+impl AbsenceExplanation {
+    fn new(query: &str, cancer_type: Option<&str>, category: AbsenceCategory) -> Self {
+        Self {
+            query: query.trim().to_string(),
+            gene: None,
+            cancer_type: cancer_type.map(str::to_string),
+            category,
+            summary: String::new(),
+            suggestions: Vec::new(),
+            scored_in: Vec::new(),
+            universe_size: 0,
+            constraint: None,
+            missing_components: Vec::new(),
+            score: None,
+            threshold: None,
+            tier: None,
+            biggest_deficit: None,
+        }
+    }
+}
+
+/// Explain why `query` is missing from the ranking for `cancer_type`.
+///
+/// Gates are checked in shortlist order and the first failing one wins:
+/// symbol resolution, scored universe, constraints, provider coverage, and
+/// finally the secondary threshold.
+pub fn explain_absence(
+    query: &str,
+    cancer_type: Option<&str>,
+    symbols: &SymbolIndex,
+    scores: &[StoredGeneScore],
+    constraints: Option<&Constraints>,
+    policy: &AbsencePolicy,
+) -> AbsenceExplanation {
+    let in_cancer =
+        |s: &StoredGeneScore| cancer_type.is_none_or(|c| s.cancer_type.eq_ignore_ascii_case(c));
+
+    // 1. Symbol resolution
+    let Some(gene) = symbols.resolve(query).map(str::to_string) else {
+        let mut out =
+            AbsenceExplanation::new(query, cancer_type, AbsenceCategory::UnresolvedSymbol);
+        out.suggestions = symbols.suggest(query);
+        out.summary = if out.suggestions.is_empty() {
+            format!("'{}' is not a recognised HGNC gene symbol.", out.query)
+        } else {
+            format!(
+                "'{}' is not a recognised HGNC gene symbol; did you mean {}?",
+                out.query,
+                out.suggestions.join(", ")
+            )
+        };
+        return out;
+    };
+
+    let matches_gene = |s: &StoredGeneScore| {
+        s.gene.eq_ignore_ascii_case(&gene) || s.gene.eq_ignore_ascii_case(query.trim())
+    };
+    let universe_size = scores
+        .iter()
+        .filter(|s| in_cancer(s))
+        .map(|s| s.gene.to_ascii_uppercase())
+        .collect::<BTreeSet<_>>()
+        .len();
+    let scope = cancer_type.unwrap_or("any cancer type");
+
+    // 2. Scored universe
+    let best = scores
+        .iter()
+        .filter(|s| in_cancer(s) && matches_gene(s))
+        .max_by(|a, b| {
+            a.confidence_adjusted_score
+                .total_cmp(&b.confidence_adjusted_score)
+        });
+    let Some(best) = best else {
+        let mut out = AbsenceExplanation::new(query, cancer_type, AbsenceCategory::NotInUniverse);
+        out.gene = Some(gene.clone());
+        out.universe_size = universe_size;
+        out.scored_in = scores
+            .iter()
+            .filter(|s| matches_gene(s))
+            .map(|s| s.cancer_type.clone())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        out.summary = if out.scored_in.is_empty() {
+            format!(
+                "{gene} was never scored: it has no evidence in the corpus or provider data for {scope}."
+            )
+        } else {
+            format!(
+                "{gene} is not in the scored universe for {scope}; it was scored for {}.",
+                out.scored_in.join(", ")
+            )
+        };
+        return out;
+    };
+
+    let mut out = AbsenceExplanation::new(query, cancer_type, AbsenceCategory::Ranked);
+    out.gene = Some(gene.clone());
+    out.universe_size = universe_size;
+    out.score = Some(best.confidence_adjusted_score);
+    out.threshold = Some(policy.secondary_threshold);
+    out.tier = Some(best.tier.clone());
+
+    if best.is_shortlisted() {
+        out.summary = format!(
+            "{gene} is ranked in the {} tier for {scope} (adjusted score {:.3}).",
+            best.tier, best.confidence_adjusted_score
+        );
+        return out;
+    }
+
+    // 3. Constraints
+    if let Some(violation) = constraint_violation(best, constraints) {
+        out.category = AbsenceCategory::ExcludedByConstraint;
+        out.summary = match (violation.observed, violation.required) {
+            (Some(observed), Some(required)) => format!(
+                "{gene} was excluded by the {} constraint (observed {observed:.3}, required {required:.3}).",
+                violation.constraint
+            ),
+            _ => format!(
+                "{gene} was excluded by the {} constraint.",
+                violation.constraint
+            ),
+        };
+        out.constraint = Some(violation);
+        return out;
+    }
+
+    // 4. Provider coverage
+    let missing: Vec<String> = COMPONENT_KEYS
+        .iter()
+        .filter(|(name, _)| best.component(name).is_none())
+        .map(|(name, _)| name.to_string())
+        .collect();
+    let covered = COMPONENT_KEYS.len() - missing.len();
+    if covered < policy.min_covered_components {
+        out.category = AbsenceCategory::InsufficientCoverage;
+        out.summary = format!(
+            "{gene} had provider data for only {covered} of {} score components (need {}); missing: {}.",
+            COMPONENT_KEYS.len(),
+            policy.min_covered_components,
+            missing.join(", ")
+        );
+        out.missing_components = missing;
+        return out;
+    }
+    out.missing_components = missing;
+
+    // 5. Score floor
+    out.biggest_deficit = biggest_deficit(best);
+    if best.confidence_adjusted_score < policy.secondary_threshold {
+        out.category = AbsenceCategory::BelowThreshold;
+        out.summary = match &out.biggest_deficit {
+            Some(d) => format!(
+                "{gene} scored {:.3}, below the secondary threshold of {:.2}; the largest shortfall is {} ({:.3}).",
+                best.confidence_adjusted_score, policy.secondary_threshold, d.component, d.value
+            ),
+            None => format!(
+                "{gene} scored {:.3}, below the secondary threshold of {:.2}.",
+                best.confidence_adjusted_score, policy.secondary_threshold
+            ),
+        };
+        return out;
+    }
+
+    // Stored tier says excluded but no gate above explains it.
+    out.category = AbsenceCategory::ExcludedByConstraint;
+    out.constraint = Some(ConstraintViolation {
+        constraint: "stored_tier".to_string(),
+        observed: None,
+        required: None,
+    });
+    out.summary = format!(
+        "{gene} scored {:.3} but its stored tier is '{}'; the excluding rule was not recorded with the score.",
+        best.confidence_adjusted_score, best.tier
+    );
+    out
+}
+
+/// First hard or campaign constraint the stored score fails. Constraints are
+/// only checked when the score carries the value they test.
+fn constraint_violation(
+    score: &StoredGeneScore,
+    constraints: Option<&Constraints>,
+) -> Option<ConstraintViolation> {
+    let violation = |name: &str, observed: f64, required: f64| ConstraintViolation {
+        constraint: name.to_string(),
+        observed: Some(observed),
+        required: Some(required),
+    };
+
+    if let Some(inhibitors) = score.raw_f64(&["chembl_inhibitor_count"]) {
+        let novelty = score.component("novelty_score").unwrap_or(0.0);
+        let penalty_inputs = PenaltyInputs {
+            chembl_inhibitor_count: inhibitors.max(0.0) as u32,
+            expression_ratio: score.raw_f64(&["expression_ratio"]).unwrap_or(1.0),
+            has_pdb: false,
+            alphafold_plddt: None,
+            has_approved_drug: false,
+            phase3_trial_count: 0,
+        };
+        if let Some(name) = hard_exclusion_constraint(&penalty_inputs, novelty) {
+            return Some(violation(name, inhibitors, 50.0));
+        }
+    }
+
+    let c = constraints?;
+    if let Some(v) = score.component("structural_tractability") {
+        if c.require_structure && v <= 0.0 {
+            return Some(ConstraintViolation {
+                constraint: "require_structure".to_string(),
+                observed: Some(v),
+                required: None,
+            });
+        }
+        if v < c.min_druggability_score as f64 {
+            return Some(violation(
+                "min_druggability_score",
+                v,
+                c.min_druggability_score as f64,
+            ));
+        }
+    }
+    if let Some(v) = score.raw_f64(&["ceres_score", "crispr_ceres", "gene_effect"]) {
+        // More negative is more essential; the constraint is an upper bound.
+        if v > c.min_ceres_score as f64 {
+            return Some(violation("min_ceres_score", v, c.min_ceres_score as f64));
+        }
+    }
+    if let Some(v) = score.component("expression_specificity") {
+        if v < c.min_cancer_specificity as f64 {
+            return Some(violation(
+                "min_cancer_specificity",
+                v,
+                c.min_cancer_specificity as f64,
+            ));
+        }
+    }
+    if let Some(v) = score.raw_f64(&["paper_count", "total_evidence"]) {
+        if v < c.min_papers as f64 {
+            return Some(violation("min_papers", v, c.min_papers as f64));
+        }
+    }
+    None
+}
+
+fn component_weight(weights: &WeightVector, name: &str) -> f64 {
+    match name {
+        "mutation_freq" => weights.mutation_freq,
+        "crispr_dependency" => weights.crispr_dependency,
+        "survival_correlation" => weights.survival_correlation,
+        "expression_specificity" => weights.expression_specificity,
+        "structural_tractability" => weights.structural_tractability,
+        "pocket_detectability" => weights.pocket_detectability,
+        "novelty_score" => weights.novelty_score,
+        "pathway_independence" => weights.pathway_independence,
+        "literature_novelty" => weights.literature_novelty,
+        _ => 0.0,
+    }
+}
+
+/// Present component with the largest weighted shortfall from 1.0.
+fn biggest_deficit(score: &StoredGeneScore) -> Option<ComponentDeficit> {
+    let weights = WeightVector::default();
+    COMPONENT_KEYS
+        .iter()
+        .filter_map(|(name, _)| {
+            let value = score.component(name)?.clamp(0.0, 1.0);
+            let weight = component_weight(&weights, name);
+            Some(ComponentDeficit {
+                component: name.to_string(),
+                value,
+                weight,
+                weighted_deficit: weight * (1.0 - value),
+            })
+        })
+        .max_by(|a, b| a.weighted_deficit.total_cmp(&b.weighted_deficit))
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > MAX_SUGGESTION_DISTANCE {
+        return usize::MAX;
+    }
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        cur[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn symbols() -> SymbolIndex {
+        SymbolIndex::from_pairs([
+            ("KRAS", "KRAS"),
+            ("KRAS2", "KRAS"),
+            ("EGFR", "EGFR"),
+            ("ERBB1", "EGFR"),
+            ("TP53", "TP53"),
+            ("BRCA2", "BRCA2"),
+            ("MYC", "MYC"),
+            ("CDK4", "CDK4"),
+        ])
+    }
+
+    fn row(
+        gene: &str,
+        cancer: &str,
+        tier: &str,
+        score: f64,
+        raw: serde_json::Value,
+        normed: serde_json::Value,
+    ) -> StoredGeneScore {
+        StoredGeneScore {
+            gene: gene.to_string(),
+            cancer_type: cancer.to_string(),
+            tier: tier.to_string(),
+            confidence_adjusted_score: score,
+            components_raw: raw,
+            components_normed: normed,
+        }
+    }
+
+    fn fixture() -> Vec<StoredGeneScore> {
+        vec![
+            row(
+                "KRAS",
+                "PAAD",
+                "primary",
+                0.81,
+                json!({"gene": "KRAS"}),
+                json!({"mutation_freq": 0.95, "crispr_dependency": 0.9, "structural_tractability": 0.7}),
+            ),
+            // Saturated chemical matter with low novelty.
+            row(
+                "EGFR",
+                "PAAD",
+                "excluded",
+                0.58,
+                json!({"chembl_inhibitor_count": 240}),
+                json!({"mutation_freq": 0.4, "structural_tractability": 0.9, "novelty_score": 0.05}),
+            ),
+            // Literature-only evidence.
+            row(
+                "TP53",
+                "PAAD",
+                "excluded",
+                0.44,
+                json!({"total_evidence": 30}),
+                json!({"literature_score": 0.6, "mutation_score": 0.9}),
+            ),
+            // Well covered but weak everywhere that matters.
+            row(
+                "MYC",
+                "PAAD",
+                "excluded",
+                0.31,
+                json!({}),
+                json!({
+                    "mutation_freq": 0.8,
+                    "crispr_dependency": 0.1,
+                    "survival_correlation": 0.5,
+                    "expression_specificity": 0.6,
+                    "literature_novelty": 0.2
+                }),
+            ),
+            // Scored, but only for a different cancer type.
+            row(
+                "BRCA2",
+                "BRCA",
+                "secondary",
+                0.55,
+                json!({}),
+                json!({"mutation_freq": 0.5}),
+            ),
+        ]
+    }
+
+    fn run(gene: &str) -> AbsenceExplanation {
+        explain_absence(
+            gene,
+            Some("PAAD"),
+            &symbols(),
+            &fixture(),
+            None,
+            &AbsencePolicy::default(),
+        )
+    }
+
+    #[test]
+    fn unresolved_symbol_suggests_corrections() {
+        let out = run("KRAZ");
+        assert_eq!(out.category, AbsenceCategory::UnresolvedSymbol);
+        assert_eq!(out.suggestions.first().map(String::as_str), Some("KRAS"));
+        assert!(out.summary.contains("did you mean KRAS"));
+    }
+
+    #[test]
+    fn gene_scored_elsewhere_is_outside_universe() {
+        let out = run("brca2");
+        assert_eq!(out.category, AbsenceCategory::NotInUniverse);
+        assert_eq!(out.gene.as_deref(), Some("BRCA2"));
+        assert_eq!(out.scored_in, vec!["BRCA".to_string()]);
+        assert_eq!(out.universe_size, 4);
+    }
+
+    #[test]
+    fn hard_exclusion_names_constraint() {
+        // Alias resolves to the approved symbol before the lookup.
+        let out = run("ERBB1");
+        assert_eq!(out.category, AbsenceCategory::ExcludedByConstraint);
+        let c = out.constraint.expect("constraint");
+        assert_eq!(c.constraint, "saturated_chemical_matter");
+        assert_eq!(c.observed, Some(240.0));
+    }
+
+    #[test]
+    fn sparse_components_report_missing_coverage() {
+        let out = run("TP53");
+        assert_eq!(out.category, AbsenceCategory::InsufficientCoverage);
+        assert_eq!(out.missing_components.len(), 7);
+        assert!(out
+            .missing_components
+            .contains(&"crispr_dependency".to_string()));
+        assert!(!out
+            .missing_components
+            .contains(&"mutation_freq".to_string()));
+    }
+
+    #[test]
+    fn low_score_reports_biggest_deficit() {
+        let out = run("MYC");
+        assert_eq!(out.category, AbsenceCategory::BelowThreshold);
+        assert_eq!(out.score, Some(0.31));
+        assert_eq!(out.threshold, Some(0.50));
+        let d = out.biggest_deficit.expect("deficit");
+        assert_eq!(d.component, "crispr_dependency");
+        assert!((d.weighted_deficit - 0.18 * 0.9).abs() < 1e-12);
+    }
+
+    #[test]
+    fn shortlisted_gene_is_reported_as_ranked() {
+        let out = run("KRAS2");
+        assert_eq!(out.category, AbsenceCategory::Ranked);
+        assert_eq!(out.tier.as_deref(), Some("primary"));
+    }
+
+    #[test]
+    fn campaign_constraints_apply_when_supplied() {
+        let constraints = Constraints {
+            min_papers: 50,
+            ..Constraints::default()
+        };
+        let out = explain_absence(
+            "TP53",
+            Some("PAAD"),
+            &symbols(),
+            &fixture(),
+            Some(&constraints),
+            &AbsencePolicy::default(),
+        );
+        assert_eq!(out.category, AbsenceCategory::ExcludedByConstraint);
+        let c = out.constraint.expect("constraint");
+        assert_eq!(c.constraint, "min_papers");
+        assert_eq!(c.observed, Some(30.0));
+    }
+}
@@ -4,6 +4,8 @@
 //! to query gene dependency scores without being tightly coupled to the
 //! ingestion module's implementation.
 
+use ferrumyx_common::CancerType;
+
 /// Trait for accessing CRISPR gene dependency data.
 ///
 /// Implementations can use:
@@ -17,34 +19,67 @@ pub trait DepMapProvider: Send + Sync {
     /// - Gene not in DepMap
     /// - Cancer type has no cell lines
     /// - No data available for this gene-cancer pair
-    fn get_mean_ceres(&self, gene: &str, cancer_type: &str) -> Option<f64>;
+    fn get_mean_ceres(&self, gene: &str, cancer: CancerType) -> Option<f64>;
 
     /// Get median CERES score (more robust to outliers).
-    fn get_median_ceres(&self, gene: &str, cancer_type: &str) -> Option<f64>;
+    fn get_median_ceres(&self, gene: &str, cancer: CancerType) -> Option<f64>;
 
     /// Get top N dependencies for a cancer type.
     ///
     /// Returns genes ranked by mean CERES (most negative = most essential).
-    fn get_top_dependencies(&self, cancer_type: &str, n: usize) -> Vec<(String, f64)>;
+    fn get_top_dependencies(&self, cancer: CancerType, n: usize) -> Vec<(String, f64)>;
+
+    /// Mean CERES in the cancer type minus the mean across all other cell
+    /// lines. Negative values mark selective (not pan-essential) dependencies.
+    ///
+    /// Providers without a pan-cancer background return None.
+    fn get_selective_delta(&self, _gene: &str, _cancer: CancerType) -> Option<f64> {
+        None
+    }
+
+    /// Mean CERES of `gene` in cell lines of the cancer type carrying
+    /// `mutation` (e.g. "KRAS_G12D") minus the mean in wild-type lines.
+    ///
+    /// Providers without mutation calls return None.
+    fn get_mutant_vs_wt_delta(
+        &self,
+        _gene: &str,
+        _mutation: &str,
+        _cancer: CancerType,
+    ) -> Option<f64> {
+        None
+    }
+
+    /// Per-cell-line CERES scores behind the mean, for uncertainty
+    /// estimates. Providers that only expose aggregates return an empty list.
+    fn get_cell_line_ceres(&self, _gene: &str, _cancer: CancerType) -> Vec<f64> {
+        Vec::new()
+    }
 
     /// Check if a gene has dependency data.
     fn has_gene(&self, gene: &str) -> bool;
 
     /// Check if a cancer type has cell lines.
-    fn has_cancer_type(&self, cancer_type: &str) -> bool;
+    fn has_cancer_type(&self, cancer: CancerType) -> bool;
 }
 
 // ── Mock Implementation for Testing ────────────────────────────────────────
 
-/// Mock provider with hardcoded data for unit tests.
+/// Mock provider with hardcoded data for unit tests, keyed by OncoTree code.
 pub struct MockDepMapProvider {
     data: std::collections::HashMap<(String, String), f64>,
+    selective: std::collections::HashMap<(String, String), f64>,
+    mutant_deltas: std::collections::HashMap<(String, String, String), f64>,
+    cell_lines: std::collections::HashMap<(String, String), Vec<f64>>,
 }
 
 impl MockDepMapProvider {
     pub fn new() -> Self {
         Self {
             data: std::collections::HashMap::new(),
+            selective: std::collections::HashMap::new(),
+            mutant_deltas: std::collections::HashMap::new(),
+            cell_lines: std::collections::HashMap::new(),
         }
     }
 
@@ -54,6 +89,44 @@ impl MockDepMapProvider {
             .insert((gene.to_string(), cancer_type.to_string()), ceres);
         self
     }
+
+    /// Add a selective-dependency delta for a gene-cancer pair.
+    pub fn with_selective(mut self, gene: &str, cancer_type: &str, delta: f64) -> Self {
+        self.selective
+            .insert((gene.to_string(), cancer_type.to_string()), delta);
+        self
+    }
+
+    /// Add a mutant-vs-wild-type delta for a gene, mutation and cancer type.
+    pub fn with_mutant_delta(
+        mut self,
+        gene: &str,
+        mutation: &str,
+        cancer_type: &str,
+        delta: f64,
+    ) -> Self {
+        self.mutant_deltas.insert(
+            (
+                gene.to_string(),
+                mutation.to_string(),
+                cancer_type.to_string(),
+            ),
+            delta,
+        );
+        self
+    }
+
+    /// Add per-cell-line scores for a gene-cancer pair; the mean is set to
+    /// their average.
+    pub fn with_cell_lines(mut self, gene: &str, cancer_type: &str, ceres: &[f64]) -> Self {
+        if !ceres.is_empty() {
+            let mean = ceres.iter().sum::<f64>() / ceres.len() as f64;
+            self = self.with(gene, cancer_type, mean);
+        }
+        self.cell_lines
+            .insert((gene.to_string(), cancer_type.to_string()), ceres.to_vec());
+        self
+    }
 }
 
 impl Default for MockDepMapProvider {
@@ -63,26 +136,54 @@ impl Default for MockDepMapProvider {
 }
 
 impl DepMapProvider for MockDepMapProvider {
-    fn get_mean_ceres(&self, gene: &str, cancer_type: &str) -> Option<f64> {
+    fn get_mean_ceres(&self, gene: &str, cancer: CancerType) -> Option<f64> {
         self.data
-            .get(&(gene.to_string(), cancer_type.to_string()))
+            .get(&(gene.to_string(), cancer.code().to_string()))
             .copied()
     }
 
-    fn get_median_ceres(&self, gene: &str, cancer_type: &str) -> Option<f64> {
-        self.get_mean_ceres(gene, cancer_type)
+    fn get_median_ceres(&self, gene: &str, cancer: CancerType) -> Option<f64> {
+        self.get_mean_ceres(gene, cancer)
     }
 
-    fn get_top_dependencies(&self, _cancer_type: &str, _n: usize) -> Vec<(String, f64)> {
+    fn get_top_dependencies(&self, _cancer: CancerType, _n: usize) -> Vec<(String, f64)> {
         vec![]
     }
 
+    fn get_selective_delta(&self, gene: &str, cancer: CancerType) -> Option<f64> {
+        self.selective
+            .get(&(gene.to_string(), cancer.code().to_string()))
+            .copied()
+    }
+
+    fn get_mutant_vs_wt_delta(
+        &self,
+        gene: &str,
+        mutation: &str,
+        cancer: CancerType,
+    ) -> Option<f64> {
+        self.mutant_deltas
+            .get(&(
+                gene.to_string(),
+                mutation.to_string(),
+                cancer.code().to_string(),
+            ))
+            .copied()
+    }
+
+    fn get_cell_line_ceres(&self, gene: &str, cancer: CancerType) -> Vec<f64> {
+        self.cell_lines
+            .get(&(gene.to_string(), cancer.code().to_string()))
+            .cloned()
+            .unwrap_or_default()
+    }
+
     fn has_gene(&self, gene: &str) -> bool {
         self.data.keys().any(|(g, _)| g == gene)
     }
 
-    fn has_cancer_type(&self, cancer_type: &str) -> bool {
-        self.data.keys().any(|(_, c)| c == cancer_type)
+    fn has_cancer_type(&self, cancer: CancerType) -> bool {
+        self.data.keys().any(|(_, c)| c == cancer.code())
     }
 }
 
@@ -115,27 +216,71 @@ impl DepMapClientAdapter {
     }
 }
 
+/// Tissues query DepMap by lineage (e.g. "Pancreas"), pooling all of its
+/// cell lines; every other node queries by its OncoTree code.
 impl DepMapProvider for DepMapClientAdapter {
-    fn get_mean_ceres(&self, gene: &str, cancer_type: &str) -> Option<f64> {
-        self.client.get_mean_ceres(gene, cancer_type)
+    fn get_mean_ceres(&self, gene: &str, cancer: CancerType) -> Option<f64> {
+        if cancer.is_tissue() {
+            self.client.get_mean_ceres_by_lineage(gene, cancer.tissue())
+        } else {
+            self.client.get_mean_ceres(gene, cancer.code())
+        }
+    }
+
+    fn get_median_ceres(&self, gene: &str, cancer: CancerType) -> Option<f64> {
+        if cancer.is_tissue() {
+            self.client
+                .get_median_ceres_by_lineage(gene, cancer.tissue())
+        } else {
+            self.client.get_median_ceres(gene, cancer.code())
+        }
     }
 
-    fn get_median_ceres(&self, gene: &str, cancer_type: &str) -> Option<f64> {
-        self.client.get_median_ceres(gene, cancer_type)
+    fn get_top_dependencies(&self, cancer: CancerType, n: usize) -> Vec<(String, f64)> {
+        if cancer.is_tissue() {
+            self.client
+                .get_top_dependencies_by_lineage(cancer.tissue(), n)
+        } else {
+            self.client.get_top_dependencies(cancer.code(), n)
+        }
+    }
+
+    fn get_selective_delta(&self, gene: &str, cancer: CancerType) -> Option<f64> {
+        // Selectivity is defined against other OncoTree codes only.
+        self.client
+            .get_selective_dependency(gene, cancer.code())
+            .map(|s| s.delta)
+    }
+
+    fn get_mutant_vs_wt_delta(
+        &self,
+        gene: &str,
+        mutation: &str,
+        cancer: CancerType,
+    ) -> Option<f64> {
+        self.client
+            .get_mutant_vs_wt_delta(gene, mutation, Some(cancer.code()))
     }
 
-    fn get_top_dependencies(&self, cancer_type: &str, n: usize) -> Vec<(String, f64)> {
-        self.client.get_top_dependencies(cancer_type, n)
+    fn get_cell_line_ceres(&self, gene: &str, cancer: CancerType) -> Vec<f64> {
+        if cancer.is_tissue() {
+            self.client
+                .get_gene_scores_by_lineage(gene, cancer.tissue())
+        } else {
+            self.client.get_gene_scores(gene, cancer.code())
+        }
     }
 
     fn has_gene(&self, gene: &str) -> bool {
         self.client.has_gene(gene)
     }
 
-    fn has_cancer_type(&self, cancer_type: &str) -> bool {
-        self.client
-            .cancer_types()
-            .contains(&cancer_type.to_uppercase())
+    fn has_cancer_type(&self, cancer: CancerType) -> bool {
+        if cancer.is_tissue() {
+            self.client.has_lineage(cancer.tissue())
+        } else {
+            self.client.has_cancer_type(cancer.code())
+        }
     }
 }
 
@@ -151,9 +296,11 @@ mod tests {
             .with("KRAS", "PAAD", -1.2)
             .with("TP53", "PAAD", -0.8);
 
-        assert_eq!(provider.get_mean_ceres("KRAS", "PAAD"), Some(-1.2));
-        assert_eq!(provider.get_mean_ceres("TP53", "PAAD"), Some(-0.8));
-        assert_eq!(provider.get_mean_ceres("MYC", "PAAD"), None);
+        let paad = CancerType::parse("PAAD").unwrap();
+        assert_eq!(provider.get_mean_ceres("KRAS", paad), Some(-1.2));
+        assert_eq!(provider.get_mean_ceres("TP53", paad), Some(-0.8));
+        assert_eq!(provider.get_mean_ceres("MYC", paad), None);
+        assert!(provider.has_cancer_type(paad));
         assert!(provider.has_gene("KRAS"));
         assert!(!provider.has_gene("MYC"));
     }
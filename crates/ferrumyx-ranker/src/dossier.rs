@@ -0,0 +1,1479 @@
+//! Everything known about one gene–cancer pair, in one document.
+//!
+//! [`build_target_dossier`] gathers the persisted score and its explanation,
+//! DepMap dependency and co-dependencies, TCGA mutations and hotspots,
+//! expression, ChEMBL chemistry, structure, clinical trials, the strongest
+//! KG facts with their citations, any conflicts between them and the gene
+//! sets enriched in the cancer type's shortlist. Every
+//! section is a [`Section`]: the data and the source it came from, or the
+//! reason it is missing, so an absent provider never reads as a zero.
+//! [`render_markdown`] and [`render_html`] format a dossier for people.
+
+use crate::enrichment::{bundled_gene_sets, run_enrichment, EnrichedSet, EnrichmentConfig};
+use crate::providers::depmap::{
+    CoDependency, DepMapClient, SelectiveDependency, MIN_CODEPENDENCY_CELL_LINES,
+};
+use crate::providers::tcga::MutationFrequency;
+use crate::report::{cell, data_releases, load_export_rows, DataRelease, ScoreExportRow};
+use crate::scorer::{
+    compute_composite_score_explained, penalty_inputs_from_providers, penalty_reasons,
+    ComponentScoresNormed, ComponentScoresRaw, ScoreExplanation,
+};
+use crate::stats::{self, DependencyCi};
+use crate::weights::WeightVector;
+use crate::{lookup_provider_components, tcga_mutation_index, ProviderComponents};
+use chrono::{DateTime, Utc};
+use ferrumyx_db::entities::EntityRepository;
+use ferrumyx_db::kg_conflicts::KgConflictRepository;
+use ferrumyx_db::kg_facts::{fact_supports, KgFactRepository};
+use ferrumyx_db::papers::PaperRepository;
+use ferrumyx_db::schema::{EntityType as DbEntityType, KgConflict, KgFact};
+use ferrumyx_db::Database;
+use ferrumyx_ingestion::sources::clinicaltrials::TrialSummary;
+use ferrumyx_ingestion::sources::ChemblTargetSummary;
+use ferrumyx_molecules::tractability::StructuralAssessment;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// KG facts listed in a dossier.
+pub const DOSSIER_TOP_FACTS: usize = 10;
+
+/// Co-dependent genes listed in a dossier.
+pub const DOSSIER_TOP_CODEPENDENCIES: usize = 10;
+
+/// Enriched gene sets listed in a dossier.
+pub const DOSSIER_TOP_ENRICHED_SETS: usize = 10;
+
+/// Protein changes listed in the hotspot table.
+const HOTSPOT_ROWS: usize = 10;
+
+/// Trials listed under the phase counts.
+const TRIAL_ROWS: usize = 5;
+
+const SOURCE_SCORES: &str = "target_scores";
+const SOURCE_KG: &str = "knowledge_graph";
+
+/// One part of a dossier: its data and where it came from, or why there is
+/// none.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Section<T> {
+    Available { source: String, data: T },
+    Missing { reason: String },
+}
+
+impl<T> Section<T> {
+    fn available(source: impl Into<String>, data: T) -> Self {
+        Self::Available {
+            source: source.into(),
+            data,
+        }
+    }
+
+    fn missing(reason: impl Into<String>) -> Self {
+        Self::Missing {
+            reason: reason.into(),
+        }
+    }
+
+    pub fn data(&self) -> Option<&T> {
+        match self {
+            Self::Available { data, .. } => Some(data),
+            Self::Missing { .. } => None,
+        }
+    }
+
+    pub fn is_available(&self) -> bool {
+        matches!(self, Self::Available { .. })
+    }
+}
+
+/// The evidence for one gene in one cancer type.
+#[derive(Debug, Clone, Serialize)]
+pub struct TargetDossier {
+    pub gene: String,
+    pub cancer_type: String,
+    pub generated_at: DateTime<Utc>,
+    pub score: Section<ScoreSection>,
+    pub dependency: Section<DependencySection>,
+    pub mutations: Section<MutationSection>,
+    pub expression: Section<ExpressionSection>,
+    pub chemistry: Section<ChemistrySection>,
+    pub structure: Section<StructureSection>,
+    pub trials: Section<TrialSummary>,
+    pub kg_facts: Section<Vec<DossierFact>>,
+    pub conflicts: Section<Vec<DossierConflict>>,
+    /// Gene sets over-represented in the cancer type's primary and secondary
+    /// tiers, most significant first.
+    pub enrichment: Section<Vec<EnrichedSet>>,
+    pub releases: Vec<DataRelease>,
+}
+
+impl TargetDossier {
+    /// Names of the sections without data, in document order.
+    pub fn missing_sections(&self) -> Vec<&'static str> {
+        [
+            ("score", self.score.is_available()),
+            ("dependency", self.dependency.is_available()),
+            ("mutations", self.mutations.is_available()),
+            ("expression", self.expression.is_available()),
+            ("chemistry", self.chemistry.is_available()),
+            ("structure", self.structure.is_available()),
+            ("trials", self.trials.is_available()),
+            ("kg_facts", self.kg_facts.is_available()),
+            ("conflicts", self.conflicts.is_available()),
+            ("enrichment", self.enrichment.is_available()),
+        ]
+        .into_iter()
+        .filter(|(_, available)| !available)
+        .map(|(name, _)| name)
+        .collect()
+    }
+}
+
+/// The persisted score, explained under the current weights.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoreSection {
+    pub tier: String,
+    pub composite_score: f64,
+    pub confidence_adjusted_score: f64,
+    pub penalty: f64,
+    /// Normalised components as persisted; `None` means no data.
+    pub components: BTreeMap<String, Option<f64>>,
+    pub data_sources: BTreeMap<String, String>,
+    /// Breakdown of the persisted components, with missing ones counted as
+    /// zero.
+    pub explanation: ScoreExplanation,
+    pub rationale: String,
+    pub scored_at: DateTime<Utc>,
+}
+
+/// CRISPR dependency of the gene.
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencySection {
+    pub mean_ceres: f64,
+    pub median_ceres: Option<f64>,
+    pub num_cell_lines: Option<usize>,
+    pub ci: Option<DependencyCi>,
+    /// `None` when the source has no pan-cancer background.
+    pub selective: Option<SelectiveDependency>,
+    /// `None` when the source has no full gene effect matrix to correlate.
+    pub codependencies: Option<Vec<CoDependency>>,
+}
+
+/// Somatic mutation frequency of the gene in the cohort.
+#[derive(Debug, Clone, Serialize)]
+pub struct MutationSection {
+    pub frequency: f64,
+    pub mutated_patients: Option<u32>,
+    pub cohort_patients: Option<u32>,
+    /// Most frequent protein changes; `None` when the source only reports a
+    /// frequency.
+    pub hotspots: Option<Vec<Hotspot>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Hotspot {
+    pub protein_change: String,
+    pub patients: u32,
+}
+
+/// Expression-derived signals.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExpressionSection {
+    /// GTEx therapeutic-window score, 0.0–1.0.
+    pub gtex_specificity: Option<f64>,
+    /// TCGA survival score, 0.0–1.0.
+    pub tcga_survival: Option<f64>,
+}
+
+/// Known chemistry against the target.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChemistrySection {
+    pub bioactive_compounds: u32,
+    /// `None` when only a compound count is cached.
+    pub target: Option<ChemblTargetSummary>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StructureSection {
+    pub summary: String,
+    pub assessment: StructuralAssessment,
+}
+
+/// One KG triple about the gene with the papers behind it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DossierFact {
+    pub fact_id: Uuid,
+    pub predicate: String,
+    pub object: String,
+    pub confidence: f32,
+    pub citations: Vec<Citation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Citation {
+    pub paper_id: Uuid,
+    pub title: Option<String>,
+    pub pmid: Option<String>,
+    pub doi: Option<String>,
+    /// The paper is a review, so it restates rather than adds evidence.
+    pub is_review: bool,
+}
+
+impl Citation {
+    fn label(&self) -> String {
+        let id = if let Some(pmid) = &self.pmid {
+            format!("PMID:{pmid}")
+        } else if let Some(doi) = &self.doi {
+            format!("doi:{doi}")
+        } else {
+            self.title
+                .clone()
+                .unwrap_or_else(|| self.paper_id.to_string())
+        };
+        if self.is_review {
+            format!("{id} (review)")
+        } else {
+            id
+        }
+    }
+}
+
+/// A detected conflict involving one of the gene's facts.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DossierConflict {
+    pub conflict_type: String,
+    pub severity: Option<String>,
+    pub net_confidence: f32,
+    pub resolution: String,
+    pub fact_a: String,
+    pub fact_b: String,
+    pub paper_count: usize,
+}
+
+/// Dossier for `gene` in `cancer_type`.
+///
+/// Provider signals are read as [`lookup_provider_components`] reads them;
+/// `depmap`, when loaded, adds selectivity and co-dependencies on top of the
+/// cached CERES mean. A failing source marks its section missing rather
+/// than failing the dossier.
+pub async fn build_target_dossier(
+    db: Arc<Database>,
+    gene: &str,
+    cancer_type: &str,
+    weights: &WeightVector,
+    depmap: Option<&DepMapClient>,
+    allow_live_fetch: bool,
+) -> TargetDossier {
+    let gene = gene.trim().to_uppercase();
+    let cancer_type = cancer_type.trim().to_uppercase();
+    let (providers, scores, (kg_facts, conflicts), releases) = tokio::join!(
+        lookup_provider_components(db.clone(), &gene, Some(&cancer_type), allow_live_fetch),
+        load_export_rows(db.clone(), Some(&cancer_type)),
+        load_kg_evidence(db.clone(), &gene),
+        data_releases(db),
+    );
+
+    let (score, enrichment) = match scores {
+        Ok(rows) => {
+            let enrichment = enrichment_section(&cancer_type, &rows);
+            let score = match rows
+                .into_iter()
+                .find(|r| r.gene.eq_ignore_ascii_case(&gene))
+            {
+                Some(row) => {
+                    Section::available(SOURCE_SCORES, score_section(row, &providers, weights))
+                }
+                None => Section::missing(format!(
+                    "no persisted score for {gene} in {cancer_type}; run the ranker first"
+                )),
+            };
+            (score, enrichment)
+        }
+        Err(e) => {
+            let reason = format!("target_scores unreadable: {e}");
+            (Section::missing(reason.clone()), Section::missing(reason))
+        }
+    };
+    let mutations =
+        match tcga_mutation_index().and_then(|i| i.mutation_frequency(&gene, &cancer_type)) {
+            Some(freq) => Section::available("tcga", tcga_mutations(freq)),
+            None => mutation_fallback(&gene, &cancer_type, &providers),
+        };
+
+    TargetDossier {
+        dependency: dependency_section(&gene, &cancer_type, depmap, &providers),
+        mutations,
+        expression: expression_section(&gene, &cancer_type, &providers),
+        chemistry: chemistry_section(&gene, &providers),
+        structure: structure_section(&gene, &providers),
+        trials: match &providers.trials {
+            Some(trials) => Section::available("clinicaltrials", trials.clone()),
+            None => Section::missing(format!(
+                "no ClinicalTrials.gov summary for {gene} in {cancer_type}"
+            )),
+        },
+        score,
+        kg_facts,
+        conflicts,
+        enrichment,
+        releases,
+        gene,
+        cancer_type,
+        generated_at: Utc::now(),
+    }
+}
+
+fn score_section(
+    row: ScoreExportRow,
+    providers: &ProviderComponents,
+    weights: &WeightVector,
+) -> ScoreSection {
+    let value = |name: &str| row.components.get(name).copied().flatten().unwrap_or(0.0);
+    let normed = ComponentScoresNormed {
+        mutation_freq: value("mutation_freq"),
+        crispr_dependency: value("crispr_dependency"),
+        survival_correlation: value("survival_correlation"),
+        expression_specificity: value("expression_specificity"),
+        structural_tractability: value("structural_tractability"),
+        pocket_detectability: value("pocket_detectability"),
+        novelty_score: value("novelty_score"),
+        pathway_independence: value("pathway_independence"),
+        literature_novelty: value("literature_novelty"),
+    };
+    let raw = ComponentScoresRaw {
+        mutation_freq: providers.mutation_freq,
+        crispr_dependency: providers.crispr_ceres,
+        survival_correlation: providers.survival_score,
+        expression_specificity: providers.expression_score,
+        structural_tractability: providers.structural.as_ref().map(|s| s.pdb_count as f64),
+        pocket_detectability: providers
+            .structural
+            .as_ref()
+            .and_then(|s| s.best_pocket_score),
+        novelty_score: providers.chembl_inhibitor_count.map(f64::from),
+        pathway_independence: None,
+        literature_novelty: None,
+    };
+    let explanation = compute_composite_score_explained(
+        Some(&raw),
+        &normed,
+        weights,
+        penalty_reasons(&penalty_inputs_from_providers(providers)),
+        1.0,
+    );
+    ScoreSection {
+        tier: row.tier,
+        composite_score: row.composite_score,
+        confidence_adjusted_score: row.confidence_adjusted_score,
+        penalty: row.penalty,
+        components: row.components,
+        data_sources: row.data_sources,
+        rationale: explanation.rationale(),
+        explanation,
+        scored_at: row.scored_at,
+    }
+}
+
+/// Over-representation of the primary and secondary tiers of `rows`, with
+/// every scored gene as the universe.
+fn enrichment_section(cancer_type: &str, rows: &[ScoreExportRow]) -> Section<Vec<EnrichedSet>> {
+    let universe: Vec<String> = rows.iter().map(|r| r.gene.clone()).collect();
+    let shortlist: Vec<String> = rows
+        .iter()
+        .filter(|r| {
+            ["primary", "secondary"]
+                .iter()
+                .any(|t| r.tier.eq_ignore_ascii_case(t))
+        })
+        .map(|r| r.gene.clone())
+        .collect();
+    if shortlist.is_empty() {
+        return Section::missing(format!(
+            "no primary or secondary targets scored in {cancer_type}"
+        ));
+    }
+    let mut sets = run_enrichment(
+        &shortlist,
+        &universe,
+        &bundled_gene_sets(),
+        &EnrichmentConfig::from_env(),
+    );
+    if sets.is_empty() {
+        return Section::missing(format!("no gene set overlaps the {cancer_type} shortlist"));
+    }
+    sets.truncate(DOSSIER_TOP_ENRICHED_SETS);
+    Section::available("gene_sets", sets)
+}
+
+fn dependency_section(
+    gene: &str,
+    cancer_type: &str,
+    depmap: Option<&DepMapClient>,
+    providers: &ProviderComponents,
+) -> Section<DependencySection> {
+    if let Some(client) = depmap {
+        let scores = client.get_gene_scores(gene, cancer_type);
+        let Some(mean_ceres) = client.get_mean_ceres(gene, cancer_type) else {
+            return Section::missing(format!(
+                "{gene} has no CERES scores in DepMap {cancer_type} cell lines"
+            ));
+        };
+        let source = match client.release_info() {
+            Some(release) => format!("depmap {}", release.release),
+            None => "depmap".to_string(),
+        };
+        return Section::available(
+            source,
+            DependencySection {
+                mean_ceres,
+                median_ceres: client.get_median_ceres(gene, cancer_type),
+                num_cell_lines: Some(scores.len()),
+                ci: stats::bootstrap_dependency_ci(
+                    &scores,
+                    stats::DEFAULT_BOOTSTRAP_RESAMPLES,
+                    stats::DEFAULT_BOOTSTRAP_SEED,
+                ),
+                selective: client.get_selective_dependency(gene, cancer_type),
+                codependencies: client
+                    .get_codependencies(
+                        gene,
+                        DOSSIER_TOP_CODEPENDENCIES,
+                        MIN_CODEPENDENCY_CELL_LINES,
+                    )
+                    .map(|c| c.correlated),
+            },
+        );
+    }
+    match providers.crispr_ceres {
+        Some(mean_ceres) => Section::available(
+            "depmap_cache",
+            DependencySection {
+                mean_ceres,
+                median_ceres: None,
+                num_cell_lines: providers.crispr_ci.map(|ci| ci.num_cell_lines),
+                ci: providers.crispr_ci,
+                selective: None,
+                codependencies: None,
+            },
+        ),
+        None => Section::missing(format!(
+            "DepMap is not loaded and the local cache has no CERES score for {gene} in {cancer_type}"
+        )),
+    }
+}
+
+fn tcga_mutations(freq: MutationFrequency) -> MutationSection {
+    let mut hotspots: Vec<Hotspot> = freq
+        .hotspots
+        .into_iter()
+        .map(|(protein_change, patients)| Hotspot {
+            protein_change,
+            patients,
+        })
+        .collect();
+    hotspots.sort_by(|a, b| {
+        b.patients
+            .cmp(&a.patients)
+            .then_with(|| a.protein_change.cmp(&b.protein_change))
+    });
+    hotspots.truncate(HOTSPOT_ROWS);
+    MutationSection {
+        frequency: freq.frequency,
+        mutated_patients: Some(freq.mutated),
+        cohort_patients: Some(freq.total),
+        hotspots: Some(hotspots),
+    }
+}
+
+fn mutation_fallback(
+    gene: &str,
+    cancer_type: &str,
+    providers: &ProviderComponents,
+) -> Section<MutationSection> {
+    match (
+        providers.mutation_freq,
+        providers.sources.get("mutation_freq"),
+    ) {
+        (Some(frequency), Some(source)) => Section::available(
+            source.clone(),
+            MutationSection {
+                frequency,
+                mutated_patients: None,
+                cohort_patients: None,
+                hotspots: None,
+            },
+        ),
+        _ => Section::missing(format!(
+            "no TCGA, cBioPortal or COSMIC mutation frequency for {gene} in {cancer_type}"
+        )),
+    }
+}
+
+fn expression_section(
+    gene: &str,
+    cancer_type: &str,
+    providers: &ProviderComponents,
+) -> Section<ExpressionSection> {
+    if providers.expression_score.is_none() && providers.survival_score.is_none() {
+        return Section::missing(format!(
+            "no GTEx expression or TCGA survival signal for {gene} in {cancer_type}"
+        ));
+    }
+    let sources: Vec<&str> = ["expression_specificity", "survival_correlation"]
+        .iter()
+        .filter_map(|component| providers.sources.get(*component).map(String::as_str))
+        .collect();
+    Section::available(
+        sources.join(", "),
+        ExpressionSection {
+            gtex_specificity: providers.expression_score,
+            tcga_survival: providers.survival_score,
+        },
+    )
+}
+
+fn chemistry_section(gene: &str, providers: &ProviderComponents) -> Section<ChemistrySection> {
+    let (Some(count), Some(source)) = (
+        providers.chembl_inhibitor_count,
+        providers.sources.get("novelty_score"),
+    ) else {
+        return Section::missing(format!("no ChEMBL bioactivity data for {gene}"));
+    };
+    Section::available(
+        source.clone(),
+        ChemistrySection {
+            bioactive_compounds: count,
+            target: providers.chembl.clone(),
+        },
+    )
+}
+
+fn structure_section(gene: &str, providers: &ProviderComponents) -> Section<StructureSection> {
+    let Some(assessment) = &providers.structural else {
+        return Section::missing(format!(
+            "no RCSB or AlphaFold structure assessed for {gene}"
+        ));
+    };
+    let source = ["structural_tractability", "pocket_detectability"]
+        .iter()
+        .filter_map(|component| providers.sources.get(*component).map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Section::available(
+        source,
+        StructureSection {
+            summary: assessment.evidence_line(),
+            assessment: assessment.clone(),
+        },
+    )
+}
+
+/// The gene's strongest KG facts and the conflicts among all of its facts.
+async fn load_kg_evidence(
+    db: Arc<Database>,
+    gene: &str,
+) -> (Section<Vec<DossierFact>>, Section<Vec<DossierConflict>>) {
+    let both_missing =
+        |reason: String| (Section::missing(reason.clone()), Section::missing(reason));
+    let entity = match EntityRepository::new(db.clone()).find_by_name(gene).await {
+        Ok(found) => found
+            .into_iter()
+            .find(|e| e.entity_type == DbEntityType::Gene.to_string()),
+        Err(e) => return both_missing(format!("knowledge graph unreadable: {e}")),
+    };
+    let Some(entity) = entity else {
+        return both_missing(format!("{gene} is not in the knowledge graph"));
+    };
+    let facts = match KgFactRepository::new(db.clone())
+        .find_by_subject(entity.id)
+        .await
+    {
+        Ok(facts) if facts.is_empty() => {
+            return both_missing(format!("no KG facts about {gene}"));
+        }
+        Ok(facts) => facts,
+        Err(e) => return both_missing(format!("KG facts unreadable: {e}")),
+    };
+
+    let top = top_facts(&facts, DOSSIER_TOP_FACTS);
+    let paper_ids: Vec<Uuid> = top
+        .iter()
+        .flat_map(|(_, papers)| papers.iter().copied())
+        .collect();
+    let references = PaperRepository::new(db.clone())
+        .find_references_by_ids(&paper_ids)
+        .await
+        .map_err(|e| tracing::warn!("Citation lookup failed for {gene}: {e}"))
+        .unwrap_or_default();
+    let dossier_facts = top
+        .into_iter()
+        .map(|(fact, papers)| DossierFact {
+            fact_id: fact.id,
+            predicate: fact.predicate.clone(),
+            object: fact.object_name.clone(),
+            confidence: fact.confidence,
+            citations: papers
+                .into_iter()
+                .map(|paper_id| {
+                    let reference = references.get(&paper_id);
+                    Citation {
+                        paper_id,
+                        title: reference.map(|r| r.title.clone()),
+                        pmid: reference.and_then(|r| r.pmid.clone()),
+                        doi: reference.and_then(|r| r.doi.clone()),
+                        is_review: reference.is_some_and(|r| r.is_review),
+                    }
+                })
+                .collect(),
+        })
+        .collect();
+
+    let fact_ids: Vec<Uuid> = facts.iter().map(|f| f.id).collect();
+    let conflicts = match KgConflictRepository::new(db)
+        .find_by_fact_ids(&fact_ids)
+        .await
+    {
+        Ok(conflicts) => Section::available(SOURCE_KG, dossier_conflicts(&conflicts, &facts)),
+        Err(e) => Section::missing(format!("KG conflicts unreadable: {e}")),
+    };
+    (Section::available(SOURCE_KG, dossier_facts), conflicts)
+}
+
+/// The `n` most confident distinct triples in `facts`, each with every
+/// paper behind any of its rows. Ties go to the better supported triple.
+fn top_facts(facts: &[KgFact], n: usize) -> Vec<(&KgFact, Vec<Uuid>)> {
+    let mut triples: HashMap<(&str, Uuid), (&KgFact, Vec<Uuid>)> = HashMap::new();
+    for fact in facts {
+        let papers = fact_supports(fact)
+            .into_iter()
+            .map(|s| s.paper_id)
+            .filter(|id| !id.is_nil());
+        let entry = triples
+            .entry((fact.predicate.as_str(), fact.object_id))
+            .or_insert((fact, Vec::new()));
+        if fact.confidence > entry.0.confidence {
+            entry.0 = fact;
+        }
+        for paper in papers {
+            if !entry.1.contains(&paper) {
+                entry.1.push(paper);
+            }
+        }
+    }
+    let mut top: Vec<(&KgFact, Vec<Uuid>)> = triples.into_values().collect();
+    top.sort_by(|a, b| {
+        b.0.confidence
+            .total_cmp(&a.0.confidence)
+            .then_with(|| b.1.len().cmp(&a.1.len()))
+            .then_with(|| {
+                (&a.0.predicate, &a.0.object_name).cmp(&(&b.0.predicate, &b.0.object_name))
+            })
+    });
+    top.truncate(n);
+    top
+}
+
+/// `conflicts` described by their facts, most severe first.
+fn dossier_conflicts(conflicts: &[KgConflict], facts: &[KgFact]) -> Vec<DossierConflict> {
+    let by_id: HashMap<Uuid, &KgFact> = facts.iter().map(|f| (f.id, f)).collect();
+    let describe = |id: Uuid| match by_id.get(&id) {
+        Some(f) => format!("{} {} {}", f.subject_name, f.predicate, f.object_name),
+        None => id.to_string(),
+    };
+    let severity_rank = |severity: &Option<String>| match severity.as_deref() {
+        Some("high") => 0,
+        Some("medium") => 1,
+        Some("low") => 2,
+        _ => 3,
+    };
+    let mut out: Vec<DossierConflict> = conflicts
+        .iter()
+        .map(|c| DossierConflict {
+            conflict_type: c.conflict_type.clone(),
+            severity: c.severity.clone(),
+            net_confidence: c.net_confidence,
+            resolution: c.resolution.clone(),
+            fact_a: describe(c.fact_a_id),
+            fact_b: describe(c.fact_b_id),
+            paper_count: c.paper_ids.len(),
+        })
+        .collect();
+    out.sort_by(|a, b| {
+        severity_rank(&a.severity)
+            .cmp(&severity_rank(&b.severity))
+            .then_with(|| b.net_confidence.total_cmp(&a.net_confidence))
+    });
+    out
+}
+
+/// A rendered chunk of a section, shared by the Markdown and HTML output.
+enum Block {
+    Text(String),
+    Table {
+        header: &'static [&'static str],
+        rows: Vec<Vec<String>>,
+    },
+}
+
+struct Part {
+    title: &'static str,
+    /// Source line, or the reason the section is missing.
+    status: Result<String, String>,
+    blocks: Vec<Block>,
+}
+
+fn part<T>(
+    title: &'static str,
+    section: &Section<T>,
+    blocks: impl FnOnce(&T) -> Vec<Block>,
+) -> Part {
+    match section {
+        Section::Available { source, data } => Part {
+            title,
+            status: Ok(source.clone()),
+            blocks: blocks(data),
+        },
+        Section::Missing { reason } => Part {
+            title,
+            status: Err(reason.clone()),
+            blocks: Vec::new(),
+        },
+    }
+}
+
+fn or_no_data(value: Option<f64>, precision: usize) -> String {
+    value.map_or_else(|| "no data".to_string(), |v| format!("{v:.precision$}"))
+}
+
+fn parts(dossier: &TargetDossier) -> Vec<Part> {
+    let mut parts = vec![
+        part("Score", &dossier.score, |s| {
+            vec![
+                Block::Text(format!(
+                    "Tier {}: composite {:.3}, confidence-adjusted {:.3}, penalty {:.3}, scored {}.",
+                    s.tier,
+                    s.composite_score,
+                    s.confidence_adjusted_score,
+                    s.penalty,
+                    s.scored_at.format("%Y-%m-%d"),
+                )),
+                Block::Text(format!("{}.", s.rationale)),
+                Block::Table {
+                    header: &["Component", "Normalised", "Weight", "Contribution", "Source"],
+                    rows: s
+                        .explanation
+                        .components
+                        .iter()
+                        .map(|c| {
+                            vec![
+                                c.component.to_string(),
+                                or_no_data(s.components.get(c.component).copied().flatten(), 3),
+                                format!("{:.3}", c.weight),
+                                format!("{:.3}", c.contribution),
+                                s.data_sources
+                                    .get(c.component)
+                                    .cloned()
+                                    .unwrap_or_default(),
+                            ]
+                        })
+                        .collect(),
+                },
+            ]
+        }),
+        part("CRISPR dependency", &dossier.dependency, |d| {
+            let mut line = format!("Mean CERES {:.3}", d.mean_ceres);
+            if let Some(median) = d.median_ceres {
+                let _ = write!(line, ", median {median:.3}");
+            }
+            if let Some(n) = d.num_cell_lines {
+                let _ = write!(line, " over {n} cell lines");
+            }
+            if let Some(ci) = d.ci {
+                let _ = write!(
+                    line,
+                    "; normalised dependency {:.2} (95% CI {:.2}–{:.2})",
+                    ci.estimate, ci.lo, ci.hi
+                );
+            }
+            line.push('.');
+            let mut blocks = vec![Block::Text(line)];
+            blocks.push(Block::Text(match &d.selective {
+                Some(s) => format!(
+                    "Selectivity: mean {:.3} in {} vs {:.3} in {} other cell lines (delta {:.3}, t {}, d {}).",
+                    s.mean_in_type,
+                    s.cancer_type,
+                    s.mean_elsewhere,
+                    s.n_elsewhere,
+                    s.delta,
+                    or_no_data(s.t_statistic, 2),
+                    or_no_data(s.effect_size, 2),
+                ),
+                None => "Selectivity: not available from this source.".to_string(),
+            }));
+            match &d.codependencies {
+                None => blocks.push(Block::Text(
+                    "Co-dependencies: not available from this source.".to_string(),
+                )),
+                Some(genes) if genes.is_empty() => {
+                    blocks.push(Block::Text("No co-dependent genes found.".to_string()))
+                }
+                Some(genes) => blocks.push(Block::Table {
+                    header: &["Co-dependent gene", "Correlation", "Cell lines"],
+                    rows: genes
+                        .iter()
+                        .map(|g| {
+                            vec![
+                                g.gene_symbol.clone(),
+                                format!("{:.3}", g.correlation),
+                                g.n_cell_lines.to_string(),
+                            ]
+                        })
+                        .collect(),
+                }),
+            }
+            blocks
+        }),
+        part("Mutations", &dossier.mutations, |m| {
+            let mut line = format!("Mutated in {:.1}% of patients", 100.0 * m.frequency);
+            if let (Some(mutated), Some(total)) = (m.mutated_patients, m.cohort_patients) {
+                let _ = write!(line, " ({mutated}/{total})");
+            }
+            line.push('.');
+            let mut blocks = vec![Block::Text(line)];
+            match &m.hotspots {
+                None => blocks.push(Block::Text(
+                    "Hotspots: not available from this source.".to_string(),
+                )),
+                Some(hotspots) if hotspots.is_empty() => {
+                    blocks.push(Block::Text("No recurrent protein changes.".to_string()))
+                }
+                Some(hotspots) => blocks.push(Block::Table {
+                    header: &["Protein change", "Patients", "Share of cohort"],
+                    rows: hotspots
+                        .iter()
+                        .map(|h| {
+                            let share = m
+                                .cohort_patients
+                                .filter(|&total| total > 0)
+                                .map(|total| f64::from(h.patients) / f64::from(total));
+                            vec![
+                                h.protein_change.clone(),
+                                h.patients.to_string(),
+                                share.map_or_else(
+                                    || "no data".to_string(),
+                                    |s| format!("{:.1}%", 100.0 * s),
+                                ),
+                            ]
+                        })
+                        .collect(),
+                }),
+            }
+            blocks
+        }),
+        part("Expression", &dossier.expression, |e| {
+            vec![Block::Table {
+                header: &["Signal", "Score"],
+                rows: vec![
+                    vec![
+                        "GTEx therapeutic window".to_string(),
+                        or_no_data(e.gtex_specificity, 3),
+                    ],
+                    vec!["TCGA survival".to_string(), or_no_data(e.tcga_survival, 3)],
+                ],
+            }]
+        }),
+        part("Known chemistry", &dossier.chemistry, |c| {
+            let mut blocks = vec![];
+            match &c.target {
+                Some(target) => {
+                    blocks.push(Block::Text(format!(
+                        "{} bioactive compounds at or below {} nM against {}; approved drug: {}.",
+                        c.bioactive_compounds,
+                        target.activity_threshold_nm,
+                        target.target_chembl_id,
+                        if target.has_approved_drug {
+                            "yes"
+                        } else {
+                            "no"
+                        },
+                    )));
+                    if !target.top_compounds.is_empty() {
+                        blocks.push(Block::Text(format!(
+                            "Most potent: {}.",
+                            target.top_compounds.join(", ")
+                        )));
+                    }
+                }
+                None => blocks.push(Block::Text(format!(
+                    "{} bioactive compounds.",
+                    c.bioactive_compounds
+                ))),
+            }
+            blocks
+        }),
+        part("Structure", &dossier.structure, |s| {
+            vec![Block::Text(format!(
+                "UniProt {}: {}.",
+                s.assessment.uniprot_id, s.summary
+            ))]
+        }),
+        part("Clinical trials", &dossier.trials, |t| {
+            let mut blocks = vec![Block::Text(format!(
+                "{} interventional trials for {}.",
+                t.total, t.condition
+            ))];
+            if !t.by_phase.is_empty() {
+                blocks.push(Block::Table {
+                    header: &["Phase", "Trials"],
+                    rows: t
+                        .by_phase
+                        .iter()
+                        .map(|(phase, n)| vec![phase.clone(), n.to_string()])
+                        .collect(),
+                });
+            }
+            if !t.top_trials.is_empty() {
+                blocks.push(Block::Table {
+                    header: &["Trial", "Phase", "Status", "Title"],
+                    rows: t
+                        .top_trials
+                        .iter()
+                        .take(TRIAL_ROWS)
+                        .map(|r| {
+                            vec![
+                                r.nct_id.clone(),
+                                r.phase.clone(),
+                                r.status.clone(),
+                                r.title.clone(),
+                            ]
+                        })
+                        .collect(),
+                });
+            }
+            blocks
+        }),
+        part("Knowledge graph facts", &dossier.kg_facts, |facts| {
+            vec![Block::Table {
+                header: &["Predicate", "Object", "Confidence", "Citations"],
+                rows: facts
+                    .iter()
+                    .map(|f| {
+                        let citations = if f.citations.is_empty() {
+                            "none".to_string()
+                        } else {
+                            f.citations
+                                .iter()
+                                .map(Citation::label)
+                                .collect::<Vec<_>>()
+                                .join("; ")
+                        };
+                        vec![
+                            f.predicate.clone(),
+                            f.object.clone(),
+                            format!("{:.2}", f.confidence),
+                            citations,
+                        ]
+                    })
+                    .collect(),
+            }]
+        }),
+        part("Conflicts", &dossier.conflicts, |conflicts| {
+            if conflicts.is_empty() {
+                return vec![Block::Text("No conflicts detected.".to_string())];
+            }
+            vec![Block::Table {
+                header: &["Type", "Severity", "Fact", "Conflicting fact", "Resolution"],
+                rows: conflicts
+                    .iter()
+                    .map(|c| {
+                        vec![
+                            c.conflict_type.clone(),
+                            c.severity.clone().unwrap_or_else(|| "unrated".to_string()),
+                            c.fact_a.clone(),
+                            c.fact_b.clone(),
+                            c.resolution.clone(),
+                        ]
+                    })
+                    .collect(),
+            }]
+        }),
+        part("Shortlist enrichment", &dossier.enrichment, |sets| {
+            vec![Block::Table {
+                header: &["Gene set", "Overlap", "Odds ratio", "Adjusted p", "Genes"],
+                rows: sets
+                    .iter()
+                    .map(|s| {
+                        vec![
+                            s.name.clone(),
+                            format!("{}/{}", s.overlap, s.set_size),
+                            format!("{:.2}", s.odds_ratio),
+                            format!("{:.2e}", s.adjusted_p_value),
+                            s.overlap_genes.join(", "),
+                        ]
+                    })
+                    .collect(),
+            }]
+        }),
+    ];
+    parts.push(Part {
+        title: "Data releases",
+        status: Ok(String::new()),
+        blocks: vec![if dossier.releases.is_empty() {
+            Block::Text("No provider release information recorded.".to_string())
+        } else {
+            Block::Table {
+                header: &["Source", "Release", "As of"],
+                rows: dossier
+                    .releases
+                    .iter()
+                    .map(|r| {
+                        vec![
+                            r.source.clone(),
+                            r.release.clone(),
+                            r.as_of
+                                .map(|t| t.format("%Y-%m-%d").to_string())
+                                .unwrap_or_else(|| "unknown".to_string()),
+                        ]
+                    })
+                    .collect(),
+            }
+        }],
+    });
+    parts
+}
+
+fn summary_line(dossier: &TargetDossier) -> String {
+    let missing = dossier.missing_sections();
+    let mut line = format!(
+        "Generated {}.",
+        dossier.generated_at.format("%Y-%m-%d %H:%M UTC")
+    );
+    if missing.is_empty() {
+        line.push_str(" Every section has data.");
+    } else {
+        let _ = write!(line, " No data for: {}.", missing.join(", "));
+    }
+    line
+}
+
+/// `dossier` as a Markdown document.
+pub fn render_markdown(dossier: &TargetDossier) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "# Target dossier: {} in {}\n",
+        cell(&dossier.gene),
+        cell(&dossier.cancer_type)
+    );
+    let _ = writeln!(out, "{}\n", summary_line(dossier));
+    for part in parts(dossier) {
+        let _ = writeln!(out, "## {}\n", part.title);
+        match &part.status {
+            Ok(source) if source.is_empty() => {}
+            Ok(source) => {
+                let _ = writeln!(out, "Source: {}\n", cell(source));
+            }
+            Err(reason) => {
+                let _ = writeln!(out, "**Missing:** {}\n", cell(reason));
+            }
+        }
+        for block in &part.blocks {
+            match block {
+                Block::Text(text) => {
+                    let _ = writeln!(out, "{}\n", cell(text));
+                }
+                Block::Table { header, rows } => {
+                    let _ = writeln!(out, "| {} |", header.join(" | "));
+                    let _ = writeln!(out, "|{}", "---|".repeat(header.len()));
+                    for row in rows {
+                        let cells: Vec<String> = row.iter().map(|c| cell(c)).collect();
+                        let _ = writeln!(out, "| {} |", cells.join(" | "));
+                    }
+                    out.push('\n');
+                }
+            }
+        }
+    }
+    out
+}
+
+/// `dossier` as an HTML fragment, wrapped in `<article class="target-dossier">`.
+pub fn render_html(dossier: &TargetDossier) -> String {
+    let mut out = String::from("<article class=\"target-dossier\">\n");
+    let _ = writeln!(
+        out,
+        "<h1>Target dossier: {} in {}</h1>\n<p class=\"summary\">{}</p>",
+        escape_html(&dossier.gene),
+        escape_html(&dossier.cancer_type),
+        escape_html(&summary_line(dossier))
+    );
+    for part in parts(dossier) {
+        let _ = writeln!(out, "<section>\n<h2>{}</h2>", part.title);
+        match &part.status {
+            Ok(source) if source.is_empty() => {}
+            Ok(source) => {
+                let _ = writeln!(
+                    out,
+                    "<p class=\"source\">Source: {}</p>",
+                    escape_html(source)
+                );
+            }
+            Err(reason) => {
+                let _ = writeln!(
+                    out,
+                    "<p class=\"missing\">Missing: {}</p>",
+                    escape_html(reason)
+                );
+            }
+        }
+        for block in &part.blocks {
+            match block {
+                Block::Text(text) => {
+                    let _ = writeln!(out, "<p>{}</p>", escape_html(text));
+                }
+                Block::Table { header, rows } => {
+                    out.push_str("<table>\n<thead><tr>");
+                    for h in *header {
+                        let _ = write!(out, "<th>{h}</th>");
+                    }
+                    out.push_str("</tr></thead>\n<tbody>\n");
+                    for row in rows {
+                        out.push_str("<tr>");
+                        for c in row {
+                            let _ = write!(out, "<td>{}</td>", escape_html(c));
+                        }
+                        out.push_str("</tr>\n");
+                    }
+                    out.push_str("</tbody>\n</table>\n");
+                }
+            }
+        }
+        out.push_str("</section>\n");
+    }
+    out.push_str("</article>\n");
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fact(predicate: &str, object: &str, confidence: f32, paper: Uuid) -> KgFact {
+        let mut fact = KgFact::new(
+            paper,
+            Uuid::nil(),
+            "KRAS".to_string(),
+            predicate.to_string(),
+            Uuid::new_v5(&Uuid::NAMESPACE_OID, object.as_bytes()),
+            object.to_string(),
+        );
+        fact.id = Uuid::new_v4();
+        fact.confidence = confidence;
+        fact
+    }
+
+    fn fixture() -> TargetDossier {
+        let weights = WeightVector::default();
+        let normed = ComponentScoresNormed {
+            mutation_freq: 0.9,
+            crispr_dependency: 0.7,
+            survival_correlation: 0.0,
+            expression_specificity: 0.0,
+            structural_tractability: 0.0,
+            pocket_detectability: 0.0,
+            novelty_score: 0.0,
+            pathway_independence: 0.0,
+            literature_novelty: 0.0,
+        };
+        let explanation = compute_composite_score_explained(None, &normed, &weights, vec![], 1.0);
+        let mut components: BTreeMap<String, Option<f64>> = WeightVector::NAMES
+            .iter()
+            .map(|n| (n.to_string(), None))
+            .collect();
+        components.insert("mutation_freq".to_string(), Some(0.9));
+        components.insert("crispr_dependency".to_string(), Some(0.7));
+        let paper = Uuid::new_v4();
+        TargetDossier {
+            gene: "KRAS".to_string(),
+            cancer_type: "PAAD".to_string(),
+            generated_at: Utc::now(),
+            score: Section::available(
+                SOURCE_SCORES,
+                ScoreSection {
+                    tier: "primary".to_string(),
+                    composite_score: 0.75,
+                    confidence_adjusted_score: 0.7,
+                    penalty: 0.05,
+                    components,
+                    data_sources: BTreeMap::from([(
+                        "mutation_freq".to_string(),
+                        "tcga".to_string(),
+                    )]),
+                    rationale: explanation.rationale(),
+                    explanation,
+                    scored_at: Utc::now(),
+                },
+            ),
+            dependency: Section::available(
+                "depmap 24Q4",
+                DependencySection {
+                    mean_ceres: -1.2,
+                    median_ceres: Some(-1.1),
+                    num_cell_lines: Some(40),
+                    ci: None,
+                    selective: None,
+                    codependencies: Some(vec![CoDependency {
+                        gene_symbol: "RAF1".to_string(),
+                        correlation: 0.41,
+                        n_cell_lines: 1_000,
+                    }]),
+                },
+            ),
+            mutations: Section::available(
+                "tcga",
+                tcga_mutations(MutationFrequency {
+                    frequency: 0.9,
+                    mutated: 9,
+                    total: 10,
+                    hotspots: BTreeMap::from([("G12D".to_string(), 4), ("G12V".to_string(), 5)]),
+                }),
+            ),
+            expression: Section::missing("no GTEx expression or TCGA survival signal"),
+            chemistry: Section::missing("no ChEMBL bioactivity data for KRAS"),
+            structure: Section::missing("no RCSB or AlphaFold structure assessed for KRAS"),
+            trials: Section::available(
+                "clinicaltrials",
+                TrialSummary {
+                    gene: "KRAS".to_string(),
+                    condition: "pancreatic cancer".to_string(),
+                    total: 3,
+                    by_phase: BTreeMap::from([
+                        ("PHASE1".to_string(), 2),
+                        ("PHASE2".to_string(), 1),
+                    ]),
+                    ..TrialSummary::default()
+                },
+            ),
+            kg_facts: Section::available(
+                SOURCE_KG,
+                vec![DossierFact {
+                    fact_id: Uuid::new_v4(),
+                    predicate: "drives".to_string(),
+                    object: "PDAC <initiation> | G12D".to_string(),
+                    confidence: 0.9,
+                    citations: vec![
+                        Citation {
+                            paper_id: paper,
+                            title: Some("KRAS in PDAC".to_string()),
+                            pmid: Some("123".to_string()),
+                            doi: None,
+                            is_review: false,
+                        },
+                        Citation {
+                            paper_id: Uuid::new_v4(),
+                            title: Some("RAS signalling revisited".to_string()),
+                            pmid: None,
+                            doi: Some("10.1000/review".to_string()),
+                            is_review: true,
+                        },
+                    ],
+                }],
+            ),
+            conflicts: Section::available(SOURCE_KG, vec![]),
+            enrichment: Section::available(
+                "gene_sets",
+                vec![EnrichedSet {
+                    name: "REACTOME_MAPK1_MAPK3_SIGNALING".to_string(),
+                    description: "MAPK1/MAPK3 (ERK) signalling cascade".to_string(),
+                    set_size: 22,
+                    overlap: 3,
+                    overlap_genes: vec!["BRAF".into(), "KRAS".into(), "RAF1".into()],
+                    expected_overlap: 0.4,
+                    odds_ratio: 12.5,
+                    p_value: 0.0004,
+                    adjusted_p_value: 0.0021,
+                }],
+            ),
+            releases: vec![],
+        }
+    }
+
+    #[test]
+    fn top_facts_merge_rows_of_a_triple_and_rank_by_confidence() {
+        let (p1, p2, p3) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let facts = vec![
+            fact("inhibits", "MAPK", 0.4, p1),
+            fact("drives", "PDAC", 0.6, p1),
+            fact("drives", "PDAC", 0.8, p2),
+            fact("binds", "SOS1", 0.8, p3),
+            fact("binds", "RAF1", 0.3, Uuid::nil()),
+        ];
+        let top = top_facts(&facts, 3);
+        let names: Vec<&str> = top.iter().map(|(f, _)| f.object_name.as_str()).collect();
+        // PDAC and SOS1 tie at 0.8; PDAC has two papers behind it.
+        assert_eq!(names, ["PDAC", "SOS1", "MAPK"]);
+        assert_eq!(top[0].0.confidence, 0.8);
+        assert_eq!(top[0].1, vec![p1, p2]);
+        assert!(top_facts(&facts, 5)[3].1.is_empty());
+    }
+
+    fn scored(gene: &str, tier: &str) -> ScoreExportRow {
+        ScoreExportRow {
+            gene: gene.to_string(),
+            cancer_type: "PAAD".to_string(),
+            tier: tier.to_string(),
+            composite_score: 0.5,
+            confidence_adjusted_score: 0.5,
+            components: BTreeMap::new(),
+            penalty: 0.0,
+            kg_fact_count: 0,
+            kg_support_count: 0,
+            chembl_inhibitor_count: None,
+            data_sources: BTreeMap::new(),
+            scored_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn enrichment_lists_the_top_sets_of_the_shortlist() {
+        let mapk = [
+            "BRAF", "RAF1", "ARAF", "MAP2K1", "MAP2K2", "MAPK1", "MAPK3", "NRAS", "DUSP4",
+            "SPRED1", "KSR1", "SHOC2",
+        ];
+        let universe: std::collections::BTreeSet<String> = bundled_gene_sets()
+            .into_iter()
+            .flat_map(|s| s.genes)
+            .collect();
+        let mut rows: Vec<ScoreExportRow> = universe
+            .iter()
+            .map(|g| {
+                let tier = if mapk.contains(&g.as_str()) {
+                    "primary"
+                } else {
+                    "excluded"
+                };
+                scored(g, tier)
+            })
+            .collect();
+
+        let Section::Available { source, data } = enrichment_section("PAAD", &rows) else {
+            panic!("a MAPK shortlist is enriched");
+        };
+        assert_eq!(source, "gene_sets");
+        assert!(data.len() <= DOSSIER_TOP_ENRICHED_SETS);
+        assert_eq!(data[0].name, "REACTOME_MAPK1_MAPK3_SIGNALING");
+
+        for row in &mut rows {
+            row.tier = "excluded".to_string();
+        }
+        let missing = enrichment_section("PAAD", &rows);
+        assert!(
+            matches!(&missing, Section::Missing { reason }
+                if reason == "no primary or secondary targets scored in PAAD"),
+            "{missing:?}"
+        );
+    }
+
+    #[test]
+    fn conflicts_are_described_by_their_facts_most_severe_first() {
+        let facts = vec![
+            fact("activates", "MAPK", 0.7, Uuid::new_v4()),
+            fact("inhibits", "MAPK", 0.5, Uuid::new_v4()),
+        ];
+        let elsewhere = Uuid::new_v4();
+        let mut low = KgConflict::new(
+            facts[0].id,
+            facts[1].id,
+            "contradiction".into(),
+            0.2,
+            "unresolved".into(),
+        );
+        low.severity = Some("low".to_string());
+        let mut high = KgConflict::new(
+            facts[1].id,
+            elsewhere,
+            "contradiction".into(),
+            0.1,
+            "unresolved".into(),
+        );
+        high.severity = Some("high".to_string());
+        high.paper_ids = vec![Uuid::new_v4(), Uuid::new_v4()];
+
+        let described = dossier_conflicts(&[low, high], &facts);
+        assert_eq!(described[0].severity.as_deref(), Some("high"));
+        assert_eq!(described[0].fact_a, "KRAS inhibits MAPK");
+        assert_eq!(described[0].fact_b, elsewhere.to_string());
+        assert_eq!(described[0].paper_count, 2);
+        assert_eq!(described[1].fact_a, "KRAS activates MAPK");
+    }
+
+    #[test]
+    fn missing_sections_serialize_with_their_reason() {
+        let dossier = fixture();
+        assert_eq!(
+            dossier.missing_sections(),
+            ["expression", "chemistry", "structure"]
+        );
+        let json = serde_json::to_value(&dossier).unwrap();
+        assert_eq!(
+            json["chemistry"],
+            serde_json::json!({"status": "missing", "reason": "no ChEMBL bioactivity data for KRAS"})
+        );
+        assert_eq!(json["dependency"]["status"], "available");
+        assert_eq!(json["dependency"]["source"], "depmap 24Q4");
+        assert_eq!(json["dependency"]["data"]["mean_ceres"], -1.2);
+        let hotspots = &json["mutations"]["data"]["hotspots"];
+        assert_eq!(hotspots[0]["protein_change"], "G12V");
+        assert_eq!(hotspots[1]["patients"], 4);
+    }
+
+    #[test]
+    fn markdown_marks_missing_sections_and_escapes_cells() {
+        let markdown = render_markdown(&fixture());
+        assert!(markdown.starts_with("# Target dossier: KRAS in PAAD\n"));
+        assert!(markdown.contains("No data for: expression, chemistry, structure."));
+        assert!(markdown
+            .contains("## Known chemistry\n\n**Missing:** no ChEMBL bioactivity data for KRAS\n"));
+        assert!(markdown.contains("Source: depmap 24Q4"));
+        assert!(markdown.contains("| mutation_freq | 0.900 |"));
+        assert!(markdown.contains("| survival_correlation | no data |"));
+        assert!(markdown.contains("| RAF1 | 0.410 | 1000 |"));
+        assert!(markdown.contains("| G12V | 5 | 50.0% |"));
+        assert!(markdown.contains("Selectivity: not available from this source."));
+        assert!(markdown.contains("| PHASE1 | 2 |"));
+        assert!(markdown.contains(
+            "| drives | PDAC <initiation> \\| G12D | 0.90 | PMID:123; doi:10.1000/review (review) |"
+        ));
+        assert!(markdown.contains(
+            "| REACTOME_MAPK1_MAPK3_SIGNALING | 3/22 | 12.50 | 2.10e-3 | BRAF, KRAS, RAF1 |"
+        ));
+        assert!(markdown.contains("No conflicts detected."));
+        assert!(markdown.contains("No provider release information recorded."));
+    }
+
+    #[test]
+    fn html_escapes_text_and_marks_missing_sections() {
+        let html = render_html(&fixture());
+        assert!(html.starts_with(
+            "<article class=\"target-dossier\">\n<h1>Target dossier: KRAS in PAAD</h1>"
+        ));
+        assert!(html.contains(
+            "<p class=\"missing\">Missing: no RCSB or AlphaFold structure assessed for KRAS</p>"
+        ));
+        assert!(html.contains("<td>PDAC &lt;initiation&gt; | G12D</td>"));
+        assert!(!html.contains("<initiation>"));
+        assert!(html.trim_end().ends_with("</article>"));
+    }
+}
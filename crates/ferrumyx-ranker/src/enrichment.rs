@@ -0,0 +1,363 @@
+//! Gene set over-representation analysis for the scored shortlist.
+//!
+//! Answers "what biology is the shortlist pointing at?" by testing the
+//! primary/secondary shortlist against bundled hallmark + Reactome gene sets
+//! (`data/gene_sets.gmt`) with a one-sided hypergeometric test and
+//! Benjamini–Hochberg correction. The universe is the scored gene universe,
+//! not the whole genome, so sets are restricted to genes we actually scored.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+
+const BUNDLED_GENE_SETS_GMT: &str = include_str!("../data/gene_sets.gmt");
+
+pub const DEFAULT_MIN_SET_SIZE: usize = 5;
+pub const DEFAULT_MAX_SET_SIZE: usize = 500;
+
+/// A named gene set parsed from a GMT line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneSet {
+    pub name: String,
+    pub description: String,
+    pub genes: Vec<String>,
+}
+
+/// Size bounds applied to gene sets after restricting them to the universe.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct EnrichmentConfig {
+    pub min_set_size: usize,
+    pub max_set_size: usize,
+}
+
+impl Default for EnrichmentConfig {
+    fn default() -> Self {
+        Self {
+            min_set_size: DEFAULT_MIN_SET_SIZE,
+            max_set_size: DEFAULT_MAX_SET_SIZE,
+        }
+    }
+}
+
+impl EnrichmentConfig {
+    /// Defaults overridden by `FERRUMYX_ENRICHMENT_MIN_SET_SIZE` /
+    /// `FERRUMYX_ENRICHMENT_MAX_SET_SIZE` when set.
+    pub fn from_env() -> Self {
+        let read = |key: &str, default: usize| {
+            std::env::var(key)
+                .ok()
+                .and_then(|v| v.trim().parse::<usize>().ok())
+                .unwrap_or(default)
+        };
+        let min_set_size = read("FERRUMYX_ENRICHMENT_MIN_SET_SIZE", DEFAULT_MIN_SET_SIZE).max(1);
+        let max_set_size =
+            read("FERRUMYX_ENRICHMENT_MAX_SET_SIZE", DEFAULT_MAX_SET_SIZE).max(min_set_size);
+        Self {
+            min_set_size,
+            max_set_size,
+        }
+    }
+}
+
+/// One tested gene set with overlap statistics.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnrichedSet {
+    pub name: String,
+    pub description: String,
+    /// Set size after restricting to the scored universe.
+    pub set_size: usize,
+    pub overlap: usize,
+    pub overlap_genes: Vec<String>,
+    pub expected_overlap: f64,
+    pub odds_ratio: f64,
+    pub p_value: f64,
+    pub adjusted_p_value: f64,
+}
+
+/// Parse GMT text: `name<TAB>description<TAB>gene1<TAB>gene2...` per line.
+/// Gene symbols are upper-cased and de-duplicated; blank/short lines are skipped.
+pub fn parse_gmt(text: &str) -> Vec<GeneSet> {
+    text.lines()
+        .filter_map(|line| {
+            let mut fields = line.trim_end_matches(['\r', '\n']).split('\t');
+            let name = fields.next()?.trim();
+            let description = fields.next()?.trim();
+            if name.is_empty() {
+                return None;
+            }
+            let mut seen = HashSet::new();
+            let genes: Vec<String> = fields
+                .map(|g| g.trim().to_ascii_uppercase())
+                .filter(|g| !g.is_empty() && seen.insert(g.clone()))
+                .collect();
+            if genes.is_empty() {
+                return None;
+            }
+            Some(GeneSet {
+                name: name.to_string(),
+                description: description.to_string(),
+                genes,
+            })
+        })
+        .collect()
+}
+
+/// Bundled hallmark + Reactome sets, plus any extra GMT file pointed to by
+/// `FERRUMYX_ENRICHMENT_GMT_PATH`.
+pub fn bundled_gene_sets() -> Vec<GeneSet> {
+    let mut sets = parse_gmt(BUNDLED_GENE_SETS_GMT);
+    if let Ok(path) = std::env::var("FERRUMYX_ENRICHMENT_GMT_PATH") {
+        match std::fs::read_to_string(path.trim()) {
+            Ok(text) => sets.extend(parse_gmt(&text)),
+            Err(e) => tracing::warn!("Failed to read extra gene sets from {}: {}", path, e),
+        }
+    }
+    sets
+}
+
+fn ln_factorial_table(n: usize) -> Vec<f64> {
+    let mut table = Vec::with_capacity(n + 1);
+    table.push(0.0);
+    let mut acc = 0.0f64;
+    for i in 1..=n {
+        acc += (i as f64).ln();
+        table.push(acc);
+    }
+    table
+}
+
+fn ln_choose(table: &[f64], n: usize, k: usize) -> f64 {
+    table[n] - table[k] - table[n - k]
+}
+
+/// Upper-tail hypergeometric probability P(X >= k) for a draw of `draws`
+/// items from a population of `population` containing `successes` successes
+/// (same parameterisation as `scipy.stats.hypergeom.sf(k - 1, M, n, N)`).
+pub fn hypergeometric_sf(k: usize, population: usize, successes: usize, draws: usize) -> f64 {
+    if successes > population || draws > population {
+        return f64::NAN;
+    }
+    let lo = draws.saturating_sub(population - successes);
+    let hi = successes.min(draws);
+    if k <= lo {
+        return 1.0;
+    }
+    if k > hi {
+        return 0.0;
+    }
+    let table = ln_factorial_table(population);
+    let ln_total = ln_choose(&table, population, draws);
+    let log_terms: Vec<f64> = (k..=hi)
+        .map(|i| {
+            ln_choose(&table, successes, i) + ln_choose(&table, population - successes, draws - i)
+                - ln_total
+        })
+        .collect();
+    // Log-sum-exp keeps tiny tails (1e-100 and below) from underflowing mid-sum.
+    let max = log_terms.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let sum: f64 = log_terms.iter().map(|t| (t - max).exp()).sum();
+    (max + sum.ln()).exp().clamp(0.0, 1.0)
+}
+
+/// Benjamini–Hochberg adjusted p-values, returned in input order.
+pub fn benjamini_hochberg(p_values: &[f64]) -> Vec<f64> {
+    let m = p_values.len();
+    if m == 0 {
+        return vec![];
+    }
+    let mut order: Vec<usize> = (0..m).collect();
+    order.sort_by(|&a, &b| {
+        p_values[a]
+            .partial_cmp(&p_values[b])
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let mut adjusted = vec![1.0f64; m];
+    let mut running_min = 1.0f64;
+    for (rank, &idx) in order.iter().enumerate().rev() {
+        let q = p_values[idx] * m as f64 / (rank + 1) as f64;
+        running_min = running_min.min(q);
+        adjusted[idx] = running_min.clamp(0.0, 1.0);
+    }
+    adjusted
+}
+
+/// Run over-representation analysis of `shortlist` against `sets` within
+/// `universe`. Returns sets with at least one overlapping gene, sorted by
+/// p-value; BH correction is applied over every set that passed the size filter.
+pub fn run_enrichment(
+    shortlist: &[String],
+    universe: &[String],
+    sets: &[GeneSet],
+    config: &EnrichmentConfig,
+) -> Vec<EnrichedSet> {
+    let universe: HashSet<String> = universe
+        .iter()
+        .map(|g| g.trim().to_ascii_uppercase())
+        .filter(|g| !g.is_empty())
+        .collect();
+    let shortlist: HashSet<String> = shortlist
+        .iter()
+        .map(|g| g.trim().to_ascii_uppercase())
+        .filter(|g| universe.contains(g))
+        .collect();
+    let population = universe.len();
+    let draws = shortlist.len();
+    if population == 0 || draws == 0 {
+        return vec![];
+    }
+
+    let mut tested = Vec::new();
+    for set in sets {
+        let members: HashSet<&String> =
+            set.genes.iter().filter(|g| universe.contains(*g)).collect();
+        let set_size = members.len();
+        if set_size < config.min_set_size || set_size > config.max_set_size {
+            continue;
+        }
+        let overlap_genes: BTreeSet<String> = members
+            .iter()
+            .filter(|g| shortlist.contains(**g))
+            .map(|g| (*g).clone())
+            .collect();
+        let overlap = overlap_genes.len();
+        let p_value = hypergeometric_sf(overlap, population, set_size, draws);
+        tested.push(EnrichedSet {
+            name: set.name.clone(),
+            description: set.description.clone(),
+            set_size,
+            overlap,
+            overlap_genes: overlap_genes.into_iter().collect(),
+            expected_overlap: draws as f64 * set_size as f64 / population as f64,
+            odds_ratio: odds_ratio(overlap, draws, set_size, population),
+            p_value,
+            adjusted_p_value: 1.0,
+        });
+    }
+
+    let p_values: Vec<f64> = tested.iter().map(|s| s.p_value).collect();
+    for (set, q) in tested.iter_mut().zip(benjamini_hochberg(&p_values)) {
+        set.adjusted_p_value = q;
+    }
+
+    let mut out: Vec<EnrichedSet> = tested.into_iter().filter(|s| s.overlap > 0).collect();
+    out.sort_by(|a, b| {
+        a.p_value
+            .partial_cmp(&b.p_value)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| b.overlap.cmp(&a.overlap))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    out
+}
+
+/// 2x2 odds ratio with a Haldane–Anscombe 0.5 correction when any cell is empty.
+fn odds_ratio(overlap: usize, draws: usize, set_size: usize, population: usize) -> f64 {
+    let a = overlap as f64;
+    let b = (draws - overlap) as f64;
+    let c = (set_size - overlap) as f64;
+    let d = (population + overlap - draws - set_size) as f64;
+    if a == 0.0 || b == 0.0 || c == 0.0 || d == 0.0 {
+        ((a + 0.5) * (d + 0.5)) / ((b + 0.5) * (c + 0.5))
+    } else {
+        (a * d) / (b * c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_rel_close(actual: f64, expected: f64) {
+        let rel = ((actual - expected) / expected).abs();
+        assert!(rel < 1e-9, "expected {expected:e}, got {actual:e}");
+    }
+
+    #[test]
+    fn test_hypergeometric_sf_matches_reference_values() {
+        // Reference values for scipy.stats.hypergeom.sf(k - 1, M, n, N),
+        // computed as exact sums of binomial coefficients.
+        assert_rel_close(hypergeometric_sf(5, 100, 20, 10), 0.025464546427043124);
+        assert_rel_close(hypergeometric_sf(1, 50, 5, 10), 0.6894372179954313);
+        assert_rel_close(hypergeometric_sf(3, 1000, 50, 20), 0.07357608135605234);
+        assert_rel_close(
+            hypergeometric_sf(10, 20000, 200, 50),
+            5.7898813585951696e-11,
+        );
+        assert_eq!(hypergeometric_sf(0, 100, 20, 10), 1.0);
+        assert_eq!(hypergeometric_sf(11, 100, 20, 10), 0.0);
+    }
+
+    #[test]
+    fn test_benjamini_hochberg_matches_reference_values() {
+        // Same as statsmodels multipletests(method="fdr_bh").
+        let adjusted = benjamini_hochberg(&[0.01, 0.04, 0.03, 0.005]);
+        let expected = [0.02, 0.04, 0.04, 0.02];
+        for (a, e) in adjusted.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-12, "expected {e}, got {a}");
+        }
+        // Monotone step-up with clamping to 1.0.
+        let adjusted = benjamini_hochberg(&[0.9, 0.8, 0.95]);
+        assert!(adjusted.iter().all(|q| *q <= 1.0 && *q >= 0.9));
+    }
+
+    #[test]
+    fn test_parse_gmt_skips_blank_and_dedups() {
+        let sets = parse_gmt("A\tdesc\tkras\tKRAS\tBRAF\n\nB\tonly-desc\n");
+        assert_eq!(sets.len(), 1);
+        assert_eq!(sets[0].genes, vec!["KRAS", "BRAF"]);
+    }
+
+    #[test]
+    fn test_size_filter_excludes_small_and_large_sets() {
+        let universe: Vec<String> = (0..100).map(|i| format!("G{i}")).collect();
+        let sets = vec![
+            GeneSet {
+                name: "TINY".into(),
+                description: String::new(),
+                genes: universe[..3].to_vec(),
+            },
+            GeneSet {
+                name: "OK".into(),
+                description: String::new(),
+                genes: universe[..10].to_vec(),
+            },
+        ];
+        let config = EnrichmentConfig {
+            min_set_size: 5,
+            max_set_size: 8,
+        };
+        let out = run_enrichment(&universe[..5], &universe, &sets, &config);
+        assert!(out.is_empty());
+        let config = EnrichmentConfig {
+            min_set_size: 5,
+            max_set_size: 500,
+        };
+        let out = run_enrichment(&universe[..5], &universe, &sets, &config);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].name, "OK");
+    }
+
+    #[test]
+    fn test_mapk_enriched_shortlist_surfaces_mapk_set() {
+        let sets = bundled_gene_sets();
+        let universe: Vec<String> = sets
+            .iter()
+            .flat_map(|s| s.genes.iter().cloned())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let shortlist: Vec<String> = [
+            "BRAF", "RAF1", "ARAF", "MAP2K1", "MAP2K2", "MAPK1", "MAPK3", "NRAS", "DUSP4",
+            "SPRED1", "KSR1", "SHOC2", "CDH2", "IDO1",
+        ]
+        .iter()
+        .map(|g| g.to_string())
+        .collect();
+
+        let out = run_enrichment(&shortlist, &universe, &sets, &EnrichmentConfig::default());
+        let top = out.first().expect("at least one enriched set");
+        assert_eq!(top.name, "REACTOME_MAPK1_MAPK3_SIGNALING");
+        assert!(top.adjusted_p_value < 0.01);
+        assert!(top.odds_ratio > 1.0);
+        assert!(top.overlap_genes.contains(&"MAP2K1".to_string()));
+    }
+}
@@ -2,10 +2,38 @@
 
 use std::collections::HashMap;
 
+use ferrumyx_common::CancerType;
+
 /// Trait for accessing GTEx normal tissue expression.
 pub trait GtexProvider: Send + Sync {
     /// Get median gene expression in normal tissues.
     fn get_median_expression(&self, gene_symbol: &str) -> Option<HashMap<String, f64>>;
+
+    /// Median expression in the normal tissue the cancer type arises from,
+    /// averaged over GTEx sub-sites ("Esophagus - Mucosa", "Esophagus -
+    /// Muscularis"). None when GTEx has no tissue under that name.
+    fn get_matched_normal_expression(&self, gene_symbol: &str, cancer: CancerType) -> Option<f64> {
+        let expression = self.get_median_expression(gene_symbol)?;
+        let tissues: Vec<String> = cancer
+            .tissue()
+            .split('/')
+            .map(|t| t.trim().to_ascii_lowercase())
+            .collect();
+        let matched: Vec<f64> = expression
+            .iter()
+            .filter(|(name, _)| {
+                let site = name
+                    .split(" - ")
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_ascii_lowercase();
+                tissues.contains(&site)
+            })
+            .map(|(_, tpm)| *tpm)
+            .collect();
+        (!matched.is_empty()).then(|| matched.iter().sum::<f64>() / matched.len() as f64)
+    }
 }
 
 // ── Mock Implementation for Testing ────────────────────────────────────────
@@ -65,3 +93,25 @@ impl GtexProvider for GtexClientAdapter {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matched_normal_expression_uses_the_cancer_tissue() {
+        let gtex = MockGtexProvider::new()
+            .with("ERBB2", "Breast - Mammary Tissue", 40.0)
+            .with("ERBB2", "Esophagus - Mucosa", 10.0)
+            .with("ERBB2", "Stomach", 20.0)
+            .with("ERBB2", "Pancreas", 5.0);
+        let expr =
+            |code| gtex.get_matched_normal_expression("ERBB2", CancerType::parse(code).unwrap());
+
+        assert_eq!(expr("IDC"), Some(40.0));
+        assert_eq!(expr("PAAD"), Some(5.0));
+        // Esophagus/Stomach covers both GTEx tissues.
+        assert_eq!(expr("STAD"), Some(15.0));
+        assert_eq!(expr("LUAD"), None);
+    }
+}
@@ -1,14 +1,25 @@
 //! ferrumyx-ranker — Target prioritization scoring engine.
 //! Implements Phase 4 of ARCHITECTURE.md.
 
+pub mod absence;
 pub mod depmap_provider;
+pub mod dossier;
+pub mod enrichment;
 pub mod gtex_provider;
 pub mod normalise;
 pub mod providers;
+pub mod report;
 pub mod scorer;
+pub mod sensitivity;
+pub mod stats;
 pub mod tcga_provider;
 pub mod weights;
 
+use crate::providers::depmap::{CoDependency, DepMapClient, MIN_CODEPENDENCY_CELL_LINES};
+use crate::providers::tcga::TcgaMutationIndex;
+use ferrumyx_common::confidence::{
+    evidence_decay, is_review_study_type, review_evidence_weight, EvidenceDecay,
+};
 use ferrumyx_common::query::{QueryRequest, QueryResult, TargetMetrics};
 use ferrumyx_db::entities::EntityRepository;
 use ferrumyx_db::kg_conflicts::KgConflictRepository;
@@ -21,14 +32,22 @@ use ferrumyx_db::Database;
 use ferrumyx_db::{
     EntCbioMutationFrequency, EntChemblTarget, EntCosmicMutationFrequency, EntGtexExpression,
     EntProviderRefreshRun, EntReactomeGene, EntStageRepository, EntTcgaSurvival,
-    Phase4SignalRepository,
+    Phase4SignalRepository, TrialRepository,
 };
+use ferrumyx_ingestion::sources::clinicaltrials::{ClinicalTrialsClient, TrialSummary};
 use ferrumyx_ingestion::sources::CbioPortalClient;
 use ferrumyx_ingestion::sources::ChemblClient;
+use ferrumyx_ingestion::sources::ChemblTargetSummary;
+use ferrumyx_ingestion::sources::CosmicCensus;
 use ferrumyx_ingestion::sources::CosmicClient;
 use ferrumyx_ingestion::sources::DepMapCache;
 use ferrumyx_ingestion::sources::GtexClient;
+use ferrumyx_ingestion::sources::HotspotRecord;
 use ferrumyx_ingestion::sources::TcgaClient;
+use ferrumyx_ingestion::trials::{summarise_trials, INVESTIGATES_PREDICATE};
+use ferrumyx_kg::ner::HgncNormaliser;
+use ferrumyx_molecules::tractability::{StructuralAssessment, StructuralProvider};
+use rayon::prelude::*;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap, HashSet};
@@ -39,6 +58,14 @@ use std::time::{Duration, Instant};
 use tracing::{info, warn};
 
 const PROVIDER_SIGNAL_TTL_DAYS: i64 = 14;
+/// Strongest DepMap co-dependencies summed into a gene's pathway redundancy.
+const PATHWAY_REDUNDANCY_PARTNERS: usize = 20;
+/// Weakest co-dependency counted as a partner that could stand in for a gene.
+const PATHWAY_REDUNDANCY_MIN_CORRELATION: f64 = 0.5;
+/// IC50/Ki cut-off for counting a ChEMBL compound as bioactive (1 µM).
+const CHEMBL_ACTIVITY_THRESHOLD_NM: f64 = 1_000.0;
+/// Gene entity metadata key holding the cached ChEMBL target summary.
+const CHEMBL_METADATA_KEY: &str = "chembl_target";
 const IN_PROCESS_PROVIDER_CACHE_TTL_SECS: u64 = 60 * 60;
 const IN_PROCESS_PROVIDER_CACHE_MAX_ENTRIES: usize = 4096;
 
@@ -354,7 +381,8 @@ impl TargetQueryEngine {
 
         // Fetch a bounded cohort of facts, then rank unique gene candidates.
         let fact_limit = (req.max_results.saturating_mul(250)).clamp(200, 5000);
-        let facts = kg_repo.list(0, fact_limit).await.unwrap_or_default();
+        let mut facts = kg_repo.list(0, fact_limit).await.unwrap_or_default();
+        facts.sort_by_key(|f| f.id);
         let t_facts = Instant::now();
         let gene_filter = req.gene_symbol.as_deref().unwrap_or("").to_lowercase();
         let cancer_filter = req.cancer_code.as_deref().unwrap_or("").to_lowercase();
@@ -373,10 +401,17 @@ impl TargetQueryEngine {
             conflicts_by_fact.entry(c.fact_b_id).or_default().push(c);
         }
 
-        let mut candidates: HashMap<uuid::Uuid, GeneCandidate> = HashMap::with_capacity(fact_limit);
+        // Ordered by gene id so cohort scoring and tie order are reproducible.
+        let mut candidates: BTreeMap<uuid::Uuid, GeneCandidate> = BTreeMap::new();
+        let review_weight = review_evidence_weight();
+        let decay = evidence_decay();
 
         for f in facts {
-            if f.predicate.eq_ignore_ascii_case("mentions") || !is_gene_like(&f.subject_name) {
+            // Trial facts have the trial, not a gene, as subject.
+            if f.predicate.eq_ignore_ascii_case("mentions")
+                || f.predicate.eq_ignore_ascii_case(INVESTIGATES_PREDICATE)
+                || !is_gene_like(&f.subject_name)
+            {
                 continue;
             }
 
@@ -433,7 +468,13 @@ impl TargetQueryEngine {
                 .or_insert_with(|| GeneCandidate::new(f.subject_name.clone()));
             let provenance = classify_fact_provenance(&f);
             let tier = classify_confidence_tier(&f, provenance);
-            let signal_weight = fact_signal_weight(provenance, tier, &f.predicate);
+            let is_review = is_review_study_type(f.study_type.as_deref());
+            let base_signal_weight = fact_signal_weight(provenance, tier, &f.predicate);
+            let signal_weight = if is_review {
+                base_signal_weight * review_weight
+            } else {
+                base_signal_weight
+            };
             let weighted_confidence =
                 (confidence_adj.clamp(0.0, 1.0) * signal_weight).clamp(0.0, 1.0);
 
@@ -453,7 +494,14 @@ impl TargetQueryEngine {
             if matches!(tier, ConfidenceTier::High) {
                 entry.high_tier_fact_count += 1;
             }
-            entry.paper_ids.insert(f.paper_id);
+            if is_review {
+                entry.review_paper_ids.insert(f.paper_id);
+                entry.review_evidence_sum += base_signal_weight;
+                *entry.review_paper_evidence.entry(f.paper_id).or_default() += base_signal_weight;
+            } else {
+                entry.paper_ids.insert(f.paper_id);
+                *entry.paper_evidence.entry(f.paper_id).or_default() += signal_weight;
+            }
 
             let predicate_lc =
                 predicate_lc_for_filter.unwrap_or_else(|| f.predicate.to_lowercase());
@@ -571,16 +619,22 @@ impl TargetQueryEngine {
             .get_enrichment_by_symbol(&symbol_list)
             .await
             .unwrap_or_default();
+        // Reviews are fetched too: their publication dates feed evidence decay.
         let mut all_paper_ids_set = HashSet::new();
         let all_paper_ids: Vec<uuid::Uuid> = candidates
             .values()
-            .flat_map(|c| c.paper_ids.iter().copied())
+            .flat_map(|c| c.paper_ids.iter().chain(&c.review_paper_ids).copied())
             .filter(|id| all_paper_ids_set.insert(*id))
             .collect();
         let paper_novelty_signals = paper_repo
             .find_novelty_signals_by_ids(&all_paper_ids)
             .await
             .unwrap_or_default();
+        let pathway_redundancy = if should_fetch_codependency(candidate_count) {
+            pathway_redundancy_by_symbol(symbol_list.clone()).await
+        } else {
+            HashMap::new()
+        };
         let t_enrich = Instant::now();
 
         let mut cohort_metrics = Vec::with_capacity(candidate_count);
@@ -604,6 +658,15 @@ impl TargetQueryEngine {
             } else {
                 candidate.to_target_metrics()
             };
+            if !source_backed_only {
+                if let Some(novelty) = candidate.decayed_literature_novelty(
+                    &paper_novelty_signals,
+                    decay,
+                    review_weight,
+                ) {
+                    metrics.literature_novelty_velocity = novelty;
+                }
+            }
             let mut component_sources = default_component_sources(source_backed_only);
             if !source_backed_only && disable_structural_proxy {
                 // For larger cohorts we avoid KG-derived structural proxies and
@@ -690,8 +753,16 @@ impl TargetQueryEngine {
 
             if fetch_cbio {
                 let mut cbio_source: Option<&str> = None;
-                let mut cbio_mutation = None;
-                if let Some(cancer_code) = provider_cancer.as_deref() {
+                let mut cbio_mutation = provider_cancer
+                    .as_deref()
+                    .and_then(|cc| tcga_mutation_frequency(&candidate.gene_symbol, cc));
+                if cbio_mutation.is_some() {
+                    cbio_source = Some("tcga_maf");
+                }
+                if let Some(cancer_code) = provider_cancer
+                    .as_deref()
+                    .filter(|_| cbio_mutation.is_none())
+                {
                     cbio_mutation = get_cached_cbio_mutation_frequency(
                         &signal_repo,
                         &candidate.gene_symbol,
@@ -746,8 +817,23 @@ impl TargetQueryEngine {
                 }
             }
 
+            if let (Some(focus), Some(census)) = (req.mutation.as_deref(), cosmic_census()) {
+                let boosted = scorer::apply_known_driver_boost(
+                    metrics.mutation_freq,
+                    &candidate.gene_symbol,
+                    focus,
+                    census,
+                );
+                if boosted > metrics.mutation_freq {
+                    metrics.mutation_freq = boosted;
+                    if let Some(src) = component_sources.get_mut("n1_mutation_freq") {
+                        src.push_str("+cosmic_cmc_driver");
+                    }
+                }
+            }
+
             if let Some((novelty, used_citations)) =
-                candidate.source_backed_literature_novelty(&paper_novelty_signals)
+                candidate.source_backed_literature_novelty(&paper_novelty_signals, decay)
             {
                 metrics.literature_novelty_velocity = novelty;
                 component_sources.insert(
@@ -874,6 +960,17 @@ impl TargetQueryEngine {
                 }
             }
 
+            if let Some(&redundancy) = pathway_redundancy.get(&candidate.gene_symbol) {
+                metrics.pathway_redundancy = redundancy;
+                if let Some(src) = component_sources.get_mut("n8_pathway_independence") {
+                    if src == "source_missing" {
+                        *src = "depmap_codependency".to_string();
+                    } else {
+                        src.push_str("+depmap_codependency");
+                    }
+                }
+            }
+
             cohort_metrics.push((*gene_id, metrics.clone()));
             by_gene_metrics.insert(*gene_id, metrics);
             component_sources_by_gene.insert(*gene_id, component_sources);
@@ -881,6 +978,10 @@ impl TargetQueryEngine {
 
         let cohort_scores = scorer::PrioritizationEngine::calculate_scores(&cohort_metrics);
 
+        let stored_trials = TrialRepository::new(self.db.clone())
+            .list()
+            .await
+            .unwrap_or_default();
         let mut results = Vec::with_capacity(candidate_count);
 
         for (gene_id, candidate) in &candidates {
@@ -905,6 +1006,17 @@ impl TargetQueryEngine {
                     expression_ratio: metrics.expression_specificity,
                     has_pdb: metrics.pdb_structure_count > 0,
                     alphafold_plddt: Some(metrics.af_plddt_mean),
+                    has_approved_drug: false,
+                    phase3_trial_count: summarise_trials(
+                        &stored_trials,
+                        &candidate.gene_symbol,
+                        &inferred_cancer,
+                    )
+                    .or_else(|| {
+                        clinical_trials_client()
+                            .cached_trial_summary(&candidate.gene_symbol, &inferred_cancer)
+                    })
+                    .map_or(0, |t| t.phase3_or_later()),
                 };
 
                 let tier = scorer::determine_shortlist_tier(
@@ -965,6 +1077,7 @@ impl TargetQueryEngine {
                         .partial_cmp(&a.confidence_adj)
                         .unwrap_or(std::cmp::Ordering::Equal)
                 })
+                .then_with(|| a.gene_symbol.cmp(&b.gene_symbol))
         });
 
         let total = results.len().max(1) as f64;
@@ -1480,6 +1593,142 @@ async fn materialize_provider_cache_facts_to_kg(
     Ok(stats)
 }
 
+/// KG predicate linking a gene to a recurrent COSMIC hotspot mutation.
+pub const HOTSPOT_PREDICATE: &str = "has_hotspot";
+
+/// COSMIC samples a hotspot that is not a known driver needs before it is
+/// written to the KG; without a floor every private mutation in the census
+/// would become a fact.
+const HOTSPOT_FACT_MIN_SAMPLES: u32 = 25;
+
+/// Outcome of [`bootstrap_cosmic_hotspot_facts`].
+#[derive(Debug, Default, Clone, Copy, Serialize)]
+pub struct HotspotBootstrapReport {
+    /// Genes with at least one hotspot written.
+    pub genes: usize,
+    /// `has_hotspot` facts written.
+    pub inserted: usize,
+    /// Earlier `has_hotspot` facts replaced.
+    pub deleted: usize,
+    /// Hotspots written that the census marks as known drivers.
+    pub known_drivers: usize,
+}
+
+/// Reference-data bootstrap for the configured COSMIC census; `Ok(None)`
+/// when `FERRUMYX_COSMIC_CENSUS_PATH` is unset or the export fails to load.
+pub async fn bootstrap_configured_cosmic_hotspots(
+    db: Arc<Database>,
+) -> anyhow::Result<Option<HotspotBootstrapReport>> {
+    match cosmic_census() {
+        Some(census) => bootstrap_cosmic_hotspot_facts(db, census).await.map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Write a GENE–`has_hotspot`–MUTATION fact for every known driver and
+/// recurrent hotspot in `census`, replacing the gene's earlier hotspot
+/// facts so a newer census release refreshes counts and tiers.
+pub async fn bootstrap_cosmic_hotspot_facts(
+    db: Arc<Database>,
+    census: &CosmicCensus,
+) -> anyhow::Result<HotspotBootstrapReport> {
+    let entity_repo = EntityRepository::new(db.clone());
+    let fact_repo = KgFactRepository::new(db);
+    let mut report = HotspotBootstrapReport::default();
+
+    for gene_symbol in census.genes() {
+        let hotspots: Vec<(HotspotRecord, bool)> = census
+            .hotspots_for_gene(gene_symbol)
+            .into_iter()
+            .map(|h| {
+                let driver = census.is_driver_record(&h);
+                (h, driver)
+            })
+            .filter(|(h, driver)| *driver || h.sample_count >= HOTSPOT_FACT_MIN_SAMPLES)
+            .collect();
+        if hotspots.is_empty() {
+            continue;
+        }
+        let Some(gene_id) =
+            ensure_entity_id(&entity_repo, DbEntityType::Gene, gene_symbol, "cosmic_cmc").await?
+        else {
+            continue;
+        };
+
+        for fact in fact_repo
+            .find_by_subject_and_predicate(gene_id, HOTSPOT_PREDICATE)
+            .await
+            .unwrap_or_default()
+        {
+            if fact_repo.delete(fact.id).await.is_ok() {
+                report.deleted += 1;
+            }
+        }
+
+        let mut new_facts = Vec::with_capacity(hotspots.len());
+        for (hotspot, driver) in hotspots {
+            let mutation = format!("{}_{}", gene_symbol, hotspot.aa_change);
+            let Some(mutation_id) = ensure_entity_id(
+                &entity_repo,
+                DbEntityType::Mutation,
+                &mutation,
+                "cosmic_cmc",
+            )
+            .await?
+            else {
+                continue;
+            };
+            let mut fact = KgFact::new(
+                uuid::Uuid::nil(),
+                gene_id,
+                gene_symbol.to_string(),
+                HOTSPOT_PREDICATE.to_string(),
+                mutation_id,
+                mutation,
+            );
+            fact.confidence = if driver {
+                0.95
+            } else {
+                (hotspot.sample_count as f32 / 1000.0).clamp(0.3, 0.8)
+            };
+            fact.evidence_type = "provider_fact".to_string();
+            fact.evidence = Some(format!(
+                concat!(
+                    "provider=cosmic_cmc;aa_change={};samples={};cgc_tier={};",
+                    "significance_tier={};known_driver={};cancer_types={}"
+                ),
+                hotspot.aa_change,
+                hotspot.sample_count,
+                hotspot.cgc_tier.map(|t| t.to_string()).unwrap_or_default(),
+                hotspot
+                    .significance_tier
+                    .map(|t| t.to_string())
+                    .unwrap_or_default(),
+                driver,
+                hotspot.cancer_types.join(",")
+            ));
+            report.known_drivers += usize::from(driver);
+            new_facts.push(fact);
+        }
+
+        if new_facts.is_empty() {
+            continue;
+        }
+        fact_repo.insert_batch(&new_facts).await?;
+        report.genes += 1;
+        report.inserted += new_facts.len();
+    }
+
+    info!(
+        genes = report.genes,
+        inserted = report.inserted,
+        deleted = report.deleted,
+        known_drivers = report.known_drivers,
+        "COSMIC hotspot facts bootstrapped"
+    );
+    Ok(report)
+}
+
 async fn ensure_entity_id(
     entity_repo: &EntityRepository,
     entity_type: DbEntityType,
@@ -1531,6 +1780,309 @@ fn depmap_cache() -> Option<&'static DepMapCache> {
         .as_ref()
 }
 
+/// The cached DepMap release, loaded on first use.
+async fn depmap_client() -> Option<&'static DepMapClient> {
+    static CLIENT: tokio::sync::OnceCell<Option<DepMapClient>> = tokio::sync::OnceCell::const_new();
+    CLIENT
+        .get_or_init(|| async {
+            DepMapClient::load_cached()
+                .await
+                .map_err(|e| warn!("DepMap co-dependencies unavailable: {e:#}"))
+                .ok()
+                .flatten()
+        })
+        .await
+        .as_ref()
+}
+
+/// Pathway redundancy of each symbol with a strong enough DepMap partner.
+/// Each gene is one sweep of the gene effect matrix, so results are kept
+/// for the lifetime of the process.
+async fn pathway_redundancy_by_symbol(symbols: Vec<String>) -> HashMap<String, f64> {
+    static MEMO: OnceLock<Mutex<HashMap<String, Option<f64>>>> = OnceLock::new();
+    let Some(client) = depmap_client().await else {
+        return HashMap::new();
+    };
+    tokio::task::spawn_blocking(move || {
+        let memo = MEMO.get_or_init(Mutex::default);
+        let missing: Vec<&String> = {
+            let memo = memo.lock().unwrap_or_else(|e| e.into_inner());
+            symbols.iter().filter(|s| !memo.contains_key(*s)).collect()
+        };
+        let fresh: Vec<(String, Option<f64>)> = missing
+            .into_par_iter()
+            .map(|symbol| {
+                let redundancy = client
+                    .get_codependencies(
+                        symbol,
+                        PATHWAY_REDUNDANCY_PARTNERS,
+                        MIN_CODEPENDENCY_CELL_LINES,
+                    )
+                    .map(|c| pathway_redundancy(&c.correlated))
+                    .filter(|&r| r > 0.0);
+                (symbol.clone(), redundancy)
+            })
+            .collect();
+        let mut memo = memo.lock().unwrap_or_else(|e| e.into_inner());
+        memo.extend(fresh);
+        symbols
+            .into_iter()
+            .filter_map(|symbol| {
+                let redundancy = (*memo.get(&symbol)?)?;
+                Some((symbol, redundancy))
+            })
+            .collect()
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// Summed correlation of the partners at or above
+/// [`PATHWAY_REDUNDANCY_MIN_CORRELATION`]: a gene with several strong
+/// co-dependencies likely sits in a pathway that can route around it.
+fn pathway_redundancy(correlated: &[CoDependency]) -> f64 {
+    correlated
+        .iter()
+        .map(|c| c.correlation)
+        .filter(|&r| r >= PATHWAY_REDUNDANCY_MIN_CORRELATION)
+        .sum()
+}
+
+fn tcga_mutation_index() -> Option<&'static TcgaMutationIndex> {
+    static INDEX: OnceLock<Option<TcgaMutationIndex>> = OnceLock::new();
+    INDEX
+        .get_or_init(|| {
+            TcgaMutationIndex::load_or_build(&TcgaMutationIndex::default_data_dir())
+                .map_err(|e| warn!("TCGA mutation index unavailable: {e:#}"))
+                .ok()
+                .flatten()
+        })
+        .as_ref()
+}
+
+/// The COSMIC Cancer Mutation Census export at `FERRUMYX_COSMIC_CENSUS_PATH`,
+/// loaded on first use.
+fn cosmic_census() -> Option<&'static CosmicCensus> {
+    static CENSUS: OnceLock<Option<CosmicCensus>> = OnceLock::new();
+    CENSUS
+        .get_or_init(|| {
+            CosmicCensus::load_configured()
+                .map_err(|e| warn!("COSMIC census unavailable: {e:#}"))
+                .ok()
+                .flatten()
+        })
+        .as_ref()
+}
+
+async fn hgnc_normaliser() -> Option<&'static HgncNormaliser> {
+    static HGNC: tokio::sync::OnceCell<Option<HgncNormaliser>> = tokio::sync::OnceCell::const_new();
+    HGNC.get_or_init(|| async {
+        HgncNormaliser::from_download()
+            .await
+            .map_err(|e| warn!("HGNC normaliser unavailable for UniProt lookups: {e:#}"))
+            .ok()
+    })
+    .await
+    .as_ref()
+}
+
+/// Bioactive ChEMBL compounds against `gene_symbol`'s target.
+///
+/// Cached per target in the gene entity's metadata; a live lookup resolves
+/// the target through the HGNC UniProt accession.
+async fn get_cached_chembl_target_summary(
+    db: Arc<Database>,
+    gene_symbol: &str,
+    allow_live_fetch: bool,
+) -> Option<ChemblTargetSummary> {
+    let symbol = gene_symbol.trim().to_uppercase();
+    if symbol.is_empty() {
+        return None;
+    }
+    let entity_repo = EntityRepository::new(db);
+    let mut entity = entity_repo
+        .find_by_name(&symbol)
+        .await
+        .ok()
+        .and_then(|found| {
+            found
+                .into_iter()
+                .find(|e| e.entity_type == DbEntityType::Gene.to_string())
+        });
+    if let Some(cached) = entity
+        .as_ref()
+        .and_then(|e| chembl_summary_from_metadata(e.metadata.as_deref(), PROVIDER_SIGNAL_TTL_DAYS))
+    {
+        return Some(cached);
+    }
+    if !allow_live_fetch {
+        return None;
+    }
+
+    let uniprot = match hgnc_normaliser().await {
+        Some(hgnc) => hgnc.to_uniprot_id(&symbol),
+        None => None,
+    };
+    let summary = tokio::time::timeout(
+        Duration::from_secs(20),
+        ChemblClient::new().count_bioactive_compounds(
+            &symbol,
+            uniprot.as_deref(),
+            CHEMBL_ACTIVITY_THRESHOLD_NM,
+        ),
+    )
+    .await
+    .ok()?
+    .map_err(|e| warn!("ChEMBL target lookup failed for {symbol}: {e:#}"))
+    .ok()??;
+
+    if let Some(entity) = entity.as_mut() {
+        entity.metadata = Some(with_chembl_summary(
+            entity.metadata.as_deref(),
+            &summary,
+            chrono::Utc::now(),
+        ));
+        entity.updated_at = chrono::Utc::now();
+        if let Err(e) = entity_repo.update(entity).await {
+            warn!("Could not cache ChEMBL summary for {symbol}: {e}");
+        }
+    }
+    Some(summary)
+}
+
+/// ChEMBL summary cached in entity `metadata`, if younger than
+/// `max_age_days`.
+fn chembl_summary_from_metadata(
+    metadata: Option<&str>,
+    max_age_days: i64,
+) -> Option<ChemblTargetSummary> {
+    let meta: serde_json::Value = serde_json::from_str(metadata?).ok()?;
+    let cached = &meta[CHEMBL_METADATA_KEY];
+    let fetched_at: chrono::DateTime<chrono::Utc> =
+        serde_json::from_value(cached["fetched_at"].clone()).ok()?;
+    if chrono::Utc::now() - fetched_at > chrono::Duration::days(max_age_days) {
+        return None;
+    }
+    serde_json::from_value(cached["summary"].clone()).ok()
+}
+
+/// Entity metadata with the ChEMBL summary stored under
+/// [`CHEMBL_METADATA_KEY`]; other keys are kept.
+fn with_chembl_summary(
+    metadata: Option<&str>,
+    summary: &ChemblTargetSummary,
+    fetched_at: chrono::DateTime<chrono::Utc>,
+) -> String {
+    let mut meta = metadata
+        .and_then(|m| serde_json::from_str::<serde_json::Value>(m).ok())
+        .filter(|m| m.is_object())
+        .unwrap_or_else(|| serde_json::json!({}));
+    meta[CHEMBL_METADATA_KEY] = serde_json::json!({
+        "fetched_at": fetched_at,
+        "summary": summary,
+    });
+    meta.to_string()
+}
+
+fn structural_provider() -> &'static StructuralProvider {
+    static PROVIDER: OnceLock<StructuralProvider> = OnceLock::new();
+    PROVIDER.get_or_init(|| {
+        let provider = StructuralProvider::new(structural_cache_dir());
+        if fpocket_enabled() {
+            provider.with_fpocket(
+                std::env::var("FERRUMYX_FPOCKET_BIN").unwrap_or_else(|_| "fpocket".to_string()),
+            )
+        } else {
+            provider
+        }
+    })
+}
+
+/// Structure and pocket evidence for `gene_symbol`'s protein, cached per
+/// UniProt accession in `ent_structural_assessments`.
+///
+/// The accession comes from the stored gene row, or from HGNC when live
+/// fetches are allowed.
+async fn get_cached_structural_assessment(
+    db: Arc<Database>,
+    gene_symbol: &str,
+    allow_live_fetch: bool,
+) -> Option<StructuralAssessment> {
+    let symbol = gene_symbol.trim().to_uppercase();
+    if symbol.is_empty() {
+        return None;
+    }
+    let mut uniprot = EntStageRepository::new(db.clone())
+        .find_genes_by_symbol(std::slice::from_ref(&symbol))
+        .await
+        .ok()
+        .and_then(|rows| rows.get(&symbol)?.uniprot_id.clone())
+        .filter(|u| !u.trim().is_empty());
+    if uniprot.is_none() && allow_live_fetch {
+        uniprot = hgnc_normaliser()
+            .await
+            .and_then(|hgnc| hgnc.to_uniprot_id(&symbol));
+    }
+    let uniprot = uniprot?;
+
+    let repo = Phase4SignalRepository::new(db);
+    if !allow_live_fetch {
+        return StructuralProvider::cached(&repo, &uniprot, PROVIDER_SIGNAL_TTL_DAYS)
+            .await
+            .ok()
+            .flatten();
+    }
+    tokio::time::timeout(
+        Duration::from_secs(60),
+        structural_provider().assess_cached(&repo, &uniprot, PROVIDER_SIGNAL_TTL_DAYS),
+    )
+    .await
+    .ok()?
+    .map_err(|e| warn!("Structural assessment failed for {symbol} ({uniprot}): {e:#}"))
+    .ok()
+}
+
+fn clinical_trials_client() -> &'static ClinicalTrialsClient {
+    static CLIENT: OnceLock<ClinicalTrialsClient> = OnceLock::new();
+    CLIENT.get_or_init(ClinicalTrialsClient::new)
+}
+
+/// Interventional trials for `gene` in `cancer_code`: the stored trials
+/// when any match, otherwise the ClinicalTrials.gov count. Without
+/// `allow_live_fetch` only the client's disk cache is read for the latter.
+async fn lookup_trial_summary(
+    db: Arc<Database>,
+    gene: &str,
+    cancer_code: &str,
+    allow_live_fetch: bool,
+) -> Option<TrialSummary> {
+    let stored = TrialRepository::new(db)
+        .find_by_gene(gene)
+        .await
+        .map_err(|e| warn!("Stored trial lookup failed for {gene}: {e}"))
+        .unwrap_or_default();
+    if let Some(summary) = summarise_trials(&stored, gene, cancer_code) {
+        return Some(summary);
+    }
+    let client = clinical_trials_client();
+    if !allow_live_fetch {
+        return client.cached_trial_summary(gene, cancer_code);
+    }
+    client
+        .count_trials_for_target(gene, cancer_code)
+        .await
+        .map_err(|e| warn!("ClinicalTrials.gov lookup failed for {gene} in {cancer_code}: {e:#}"))
+        .ok()
+}
+
+/// Fraction of TCGA patients in `cancer_code` with a non-silent mutation in
+/// `gene`, from the local MAF index.
+fn tcga_mutation_frequency(gene: &str, cancer_code: &str) -> Option<f64> {
+    tcga_mutation_index()?
+        .mutation_frequency(gene, cancer_code)
+        .map(|f| f.frequency.clamp(0.0, 1.0))
+}
+
 fn normalize_provider_cancer_code(cancer_code: &str) -> Option<String> {
     let mut code = cancer_code.trim().to_uppercase();
     if code.is_empty() {
@@ -1614,6 +2166,16 @@ fn should_fetch_reactome(candidate_count: usize, _req: &QueryRequest) -> bool {
     candidate_count > 0
 }
 
+/// Co-dependency sweeps run for cohorts up to
+/// `FERRUMYX_PHASE4_CODEPENDENCY_MAX_CANDIDATES` genes (0 disables them).
+fn should_fetch_codependency(candidate_count: usize) -> bool {
+    let max_candidates = std::env::var("FERRUMYX_PHASE4_CODEPENDENCY_MAX_CANDIDATES")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(200);
+    candidate_count > 0 && candidate_count <= max_candidates
+}
+
 fn should_allow_live_provider_fetch(candidate_count: usize) -> bool {
     let max_candidates = std::env::var("FERRUMYX_PHASE4_PROVIDER_LIVE_FETCH_MAX_CANDIDATES")
         .ok()
@@ -2764,6 +3326,159 @@ async fn get_cached_reactome_pathway_count(
     resolved
 }
 
+/// Provider-backed signals for one gene-cancer pair, for callers that show
+/// a single persisted score and need the components it did not store.
+///
+/// Every value is `None` when no provider (or cached provider signal) had
+/// data; `sources` names the provider behind each value that was found.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProviderComponents {
+    /// Fraction of samples with a mutation (cBioPortal, then COSMIC).
+    pub mutation_freq: Option<f64>,
+    /// Mean CERES score from the local DepMap cache.
+    pub crispr_ceres: Option<f64>,
+    /// TCGA survival score, 0.0–1.0.
+    pub survival_score: Option<f64>,
+    /// GTEx therapeutic-window proxy, 0.0–1.0.
+    pub expression_score: Option<f64>,
+    /// Distinct ChEMBL compounds with activity against the target.
+    pub chembl_inhibitor_count: Option<u32>,
+    /// Bootstrap interval over the cell lines behind `crispr_ceres`.
+    pub crispr_ci: Option<stats::DependencyCi>,
+    /// Interventional ClinicalTrials.gov trials in the indication.
+    pub trials: Option<TrialSummary>,
+    /// Potent ChEMBL chemistry behind `chembl_inhibitor_count`, when the
+    /// gene resolved to a ChEMBL target.
+    pub chembl: Option<ChemblTargetSummary>,
+    /// RCSB / AlphaFold / fpocket evidence for the gene's protein.
+    pub structural: Option<StructuralAssessment>,
+    pub sources: BTreeMap<String, String>,
+}
+
+impl ProviderComponents {
+    /// Normalised CRISPR component, shrunk towards the prior when only a few
+    /// cell lines back it.
+    pub fn crispr_dependency(&self) -> Option<f64> {
+        let score = normalise::normalise_ceres(self.crispr_ceres?);
+        Some(match self.crispr_ci {
+            Some(ci) => stats::shrink_dependency(score, ci.num_cell_lines),
+            None => score,
+        })
+    }
+
+    /// Structural tractability component, scored as in the main ranking.
+    pub fn structural_tractability(&self) -> Option<f64> {
+        let s = self.structural.as_ref()?;
+        Some(scorer::structural_tractability_score(
+            s.pdb_count,
+            s.alphafold_plddt.unwrap_or(0.0),
+            s.best_pocket_score.unwrap_or(0.0),
+        ))
+    }
+
+    /// Pocket detectability component; None without an fpocket run.
+    pub fn pocket_detectability(&self) -> Option<f64> {
+        self.structural
+            .as_ref()?
+            .best_pocket_score
+            .map(|s| s.clamp(0.0, 1.0))
+    }
+}
+
+/// Look up provider signals for `gene` in `cancer_code`.
+///
+/// Reads the phase-4 signal cache first; with `allow_live_fetch` each
+/// provider missing from the cache is queried once under the usual
+/// per-provider timeout.
+pub async fn lookup_provider_components(
+    db: Arc<Database>,
+    gene: &str,
+    cancer_code: Option<&str>,
+    allow_live_fetch: bool,
+) -> ProviderComponents {
+    let signal_repo = Phase4SignalRepository::new(db.clone());
+    let cancer = cancer_code.map(str::trim).filter(|c| !c.is_empty());
+    let mut out = ProviderComponents::default();
+
+    if let Some(cancer) = cancer {
+        let (cbio, tcga, trials) = tokio::join!(
+            get_cached_cbio_mutation_frequency(&signal_repo, gene, cancer, allow_live_fetch),
+            get_cached_tcga_survival_score(&signal_repo, gene, cancer, allow_live_fetch),
+            lookup_trial_summary(db.clone(), gene, cancer, allow_live_fetch),
+        );
+        out.trials = trials;
+        if let Some(freq) = tcga_mutation_frequency(gene, cancer) {
+            out.mutation_freq = Some(freq);
+            out.sources
+                .insert("mutation_freq".to_string(), "tcga".to_string());
+        } else if let Some(freq) = cbio {
+            out.mutation_freq = Some(freq);
+            out.sources
+                .insert("mutation_freq".to_string(), "cbioportal".to_string());
+        } else if let Some(freq) =
+            get_cached_cosmic_mutation_frequency(&signal_repo, gene, cancer, allow_live_fetch).await
+        {
+            out.mutation_freq = Some(freq);
+            out.sources
+                .insert("mutation_freq".to_string(), "cosmic".to_string());
+        }
+        if let Some(score) = tcga {
+            out.survival_score = Some(score);
+            out.sources
+                .insert("survival_correlation".to_string(), "tcga".to_string());
+        }
+        if let Some(depmap) = depmap_cache() {
+            if let Some(ceres) = depmap.get_mean_ceres(gene, cancer) {
+                out.crispr_ceres = Some(ceres);
+                out.crispr_ci = stats::bootstrap_dependency_ci(
+                    &depmap.get_gene_scores(gene, cancer),
+                    stats::DEFAULT_BOOTSTRAP_RESAMPLES,
+                    stats::DEFAULT_BOOTSTRAP_SEED,
+                );
+                out.sources
+                    .insert("crispr_dependency".to_string(), "depmap_cache".to_string());
+            }
+        }
+    }
+
+    let (gtex, chembl, chembl_target, structural) = tokio::join!(
+        get_cached_gtex_expression_score(&signal_repo, gene, allow_live_fetch),
+        get_cached_chembl_inhibitor_count(&signal_repo, gene, allow_live_fetch),
+        get_cached_chembl_target_summary(db.clone(), gene, allow_live_fetch),
+        get_cached_structural_assessment(db, gene, allow_live_fetch),
+    );
+    if let Some(score) = gtex {
+        out.expression_score = Some(score);
+        out.sources
+            .insert("expression_specificity".to_string(), "gtex".to_string());
+    }
+    if let Some(summary) = chembl_target {
+        out.chembl_inhibitor_count = Some(summary.bioactive_compound_count);
+        out.sources
+            .insert("novelty_score".to_string(), "chembl_target".to_string());
+        out.chembl = Some(summary);
+    } else if let Some(count) = chembl {
+        out.chembl_inhibitor_count = Some(count);
+        out.sources
+            .insert("novelty_score".to_string(), "chembl".to_string());
+    }
+    if let Some(assessment) = structural {
+        let source = if assessment.has_pdb {
+            "rcsb"
+        } else {
+            "alphafold"
+        };
+        out.sources
+            .insert("structural_tractability".to_string(), source.to_string());
+        if assessment.best_pocket_score.is_some() {
+            out.sources
+                .insert("pocket_detectability".to_string(), "fpocket".to_string());
+        }
+        out.structural = Some(assessment);
+    }
+    out
+}
+
 fn source_backed_default_metrics() -> TargetMetrics {
     TargetMetrics {
         mutation_freq: 0.0,
@@ -2776,6 +3491,7 @@ fn source_backed_default_metrics() -> TargetMetrics {
         chembl_inhibitor_count: 1,
         reactome_escape_pathway_count: 1,
         literature_novelty_velocity: 0.5,
+        pathway_redundancy: 0.0,
     }
 }
 
@@ -2817,7 +3533,15 @@ fn default_component_sources(source_backed_only: bool) -> BTreeMap<String, Strin
 struct GeneCandidate {
     gene_symbol: String,
     flags: HashSet<String>,
+    /// Primary (non-review) supporting papers.
     paper_ids: HashSet<uuid::Uuid>,
+    review_paper_ids: HashSet<uuid::Uuid>,
+    /// Unweighted signal from review-sourced facts, counted separately for n9.
+    review_evidence_sum: f64,
+    /// Signal contributed by each primary paper, decayed by age for n9.
+    paper_evidence: HashMap<uuid::Uuid, f64>,
+    /// Unweighted signal contributed by each review.
+    review_paper_evidence: HashMap<uuid::Uuid, f64>,
     fact_count: u32,
     weighted_evidence_sum: f64,
     confidence_sum: f64,
@@ -2859,6 +3583,10 @@ impl GeneCandidate {
             gene_symbol,
             flags: HashSet::new(),
             paper_ids: HashSet::new(),
+            review_paper_ids: HashSet::new(),
+            review_evidence_sum: 0.0,
+            paper_evidence: HashMap::new(),
+            review_paper_evidence: HashMap::new(),
             fact_count: 0,
             weighted_evidence_sum: 0.0,
             confidence_sum: 0.0,
@@ -2918,9 +3646,10 @@ impl GeneCandidate {
     }
 
     fn infer_cancer_code(&self) -> Option<String> {
+        // Ties go to the alphabetically first code, not hash order.
         self.cancer_codes
             .iter()
-            .max_by_key(|(_, cnt)| **cnt)
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
             .map(|(code, _)| code.clone())
     }
 
@@ -3042,7 +3771,15 @@ impl GeneCandidate {
             pathway_mentions.round().clamp(0.0, u32::MAX as f64) as u32;
 
         // Inverted evidence velocity proxy: underexplored genes score higher.
-        let literature_novelty_velocity = 1.0 / (1.0 + evidence.ln_1p());
+        // Review facts already enter `evidence` at the review weight; split them
+        // back out so the documented formula sees P and R separately.
+        let review_weight = review_evidence_weight();
+        let primary_evidence = (evidence - review_weight * self.review_evidence_sum).max(0.0);
+        let literature_novelty_velocity = normalise::literature_novelty(
+            primary_evidence,
+            self.review_evidence_sum,
+            review_weight,
+        );
 
         TargetMetrics {
             mutation_freq,
@@ -3055,19 +3792,52 @@ impl GeneCandidate {
             chembl_inhibitor_count,
             reactome_escape_pathway_count,
             literature_novelty_velocity,
+            pathway_redundancy: 0.0,
+        }
+    }
+
+    /// The count-based n9 proxy with each paper's evidence scaled by its
+    /// `decay` weight, so a target studied only long ago reads as
+    /// underexplored. `None` while decay is off.
+    fn decayed_literature_novelty(
+        &self,
+        signals_by_paper: &HashMap<uuid::Uuid, PaperNoveltySignal>,
+        decay: EvidenceDecay,
+        review_weight: f64,
+    ) -> Option<f64> {
+        if !decay.is_enabled() {
+            return None;
         }
+        let decayed = |evidence: &HashMap<uuid::Uuid, f64>| -> f64 {
+            evidence
+                .iter()
+                .map(|(paper_id, signal)| {
+                    let published_at = signals_by_paper.get(paper_id).and_then(|s| s.published_at);
+                    signal * decay.weight(published_at)
+                })
+                .sum()
+        };
+        Some(normalise::literature_novelty(
+            decayed(&self.paper_evidence),
+            decayed(&self.review_paper_evidence),
+            review_weight,
+        ))
     }
 
+    /// Citation/recency novelty over primary papers only, averaged with each
+    /// paper weighted by `decay`; a target backed solely by reviews falls back
+    /// to the count-based proxy.
     fn source_backed_literature_novelty(
         &self,
         signals_by_paper: &HashMap<uuid::Uuid, PaperNoveltySignal>,
+        decay: EvidenceDecay,
     ) -> Option<(f64, bool)> {
         if self.paper_ids.is_empty() {
             return None;
         }
 
         let now = chrono::Utc::now();
-        let mut total = 0usize;
+        let mut total = 0.0f64;
         let mut sum = 0.0f64;
         let mut used_citations = false;
         for paper_id in &self.paper_ids {
@@ -3089,15 +3859,16 @@ impl GeneCandidate {
                 })
                 .unwrap_or(recency);
             let novelty = (0.55 * citation_novelty + 0.45 * recency).clamp(0.0, 1.0);
-            total += 1;
-            sum += novelty;
+            let weight = decay.weight(signal.published_at);
+            total += weight;
+            sum += weight * novelty;
         }
 
-        if total == 0 {
+        if total == 0.0 {
             return None;
         }
 
-        let score = (sum / total as f64).clamp(0.0, 1.0);
+        let score = (sum / total).clamp(0.0, 1.0);
         Some((score, used_citations))
     }
 }
@@ -3524,6 +4295,97 @@ mod tests {
         ));
     }
 
+    /// One primary paper per age in years, `None` for an unknown date.
+    fn papers_aged(
+        candidate: &mut GeneCandidate,
+        ages: &[Option<i64>],
+    ) -> HashMap<uuid::Uuid, PaperNoveltySignal> {
+        let now = chrono::Utc::now();
+        let mut signals = HashMap::new();
+        for (i, age) in ages.iter().enumerate() {
+            let paper_id = uuid::Uuid::from_u128(100 + i as u128);
+            candidate.paper_ids.insert(paper_id);
+            candidate.paper_evidence.insert(paper_id, 1.0);
+            let published_at =
+                age.map(|years| now - chrono::Duration::days((years as f64 * 365.25) as i64));
+            signals.insert(
+                paper_id,
+                PaperNoveltySignal {
+                    published_at,
+                    citation_count: None,
+                },
+            );
+        }
+        signals
+    }
+
+    #[test]
+    fn decayed_literature_novelty_discounts_old_papers() {
+        let decay = EvidenceDecay::with_half_life(10.0);
+        let mut candidate = GeneCandidate::new("KRAS".to_string());
+        let signals = papers_aged(&mut candidate, &[Some(0), Some(5), Some(10), Some(30)]);
+
+        // 1 + √½ + ½ + ⅛ papers' worth of evidence instead of 4.
+        let decayed = candidate
+            .decayed_literature_novelty(&signals, decay, 0.3)
+            .unwrap();
+        let expected = normalise::literature_novelty(1.0 + 0.5_f64.sqrt() + 0.5 + 0.125, 0.0, 0.3);
+        assert!((decayed - expected).abs() < 1e-3);
+        assert!(decayed > normalise::literature_novelty(4.0, 0.0, 0.3));
+        assert!(candidate
+            .decayed_literature_novelty(&signals, EvidenceDecay::NONE, 0.3)
+            .is_none());
+
+        // A paper without a date keeps its full weight.
+        let mut undated = GeneCandidate::new("TP53".to_string());
+        let signals = papers_aged(&mut undated, &[None]);
+        let novelty = undated
+            .decayed_literature_novelty(&signals, decay, 0.3)
+            .unwrap();
+        assert!((novelty - normalise::literature_novelty(1.0, 0.0, 0.3)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn source_backed_novelty_weights_recent_papers_more() {
+        let mut candidate = GeneCandidate::new("KRAS".to_string());
+        let signals = papers_aged(&mut candidate, &[Some(0), Some(30)]);
+        // Without citations each paper's novelty is its recency, 1/(1 + age).
+        let (fresh, old) = (1.0, 1.0 / (1.0 + 30.0));
+
+        let (plain, _) = candidate
+            .source_backed_literature_novelty(&signals, EvidenceDecay::NONE)
+            .unwrap();
+        assert!((plain - (fresh + old) / 2.0).abs() < 1e-2);
+
+        let (decayed, _) = candidate
+            .source_backed_literature_novelty(&signals, EvidenceDecay::with_half_life(10.0))
+            .unwrap();
+        assert!((decayed - (fresh + 0.125 * old) / 1.125).abs() < 1e-2);
+    }
+
+    #[test]
+    fn infer_cancer_code_breaks_count_ties_alphabetically() {
+        let mut candidate = GeneCandidate::new("KRAS".to_string());
+        candidate.cancer_codes.insert("PAAD".to_string(), 2);
+        candidate.cancer_codes.insert("LUAD".to_string(), 2);
+        candidate.cancer_codes.insert("COAD".to_string(), 1);
+        assert_eq!(candidate.infer_cancer_code().as_deref(), Some("LUAD"));
+    }
+
+    #[test]
+    fn pathway_redundancy_sums_only_strong_partners() {
+        let partners: Vec<CoDependency> = [0.9, 0.6, 0.5, 0.49]
+            .into_iter()
+            .map(|correlation| CoDependency {
+                gene_symbol: format!("G{correlation}"),
+                correlation,
+                n_cell_lines: 500,
+            })
+            .collect();
+        assert!((pathway_redundancy(&partners) - 2.0).abs() < 1e-9);
+        assert_eq!(pathway_redundancy(&partners[3..]), 0.0);
+    }
+
     #[test]
     fn fields_contain_any_ascii_keyword_scans_across_multiple_fields() {
         let fields = [
@@ -3540,4 +4402,41 @@ mod tests {
             &["healthy", "adjacent"]
         ));
     }
+
+    #[test]
+    fn chembl_summary_round_trips_through_entity_metadata() {
+        let summary = ChemblTargetSummary {
+            target_chembl_id: "CHEMBL2189121".to_string(),
+            uniprot_id: Some("P01116".to_string()),
+            activity_threshold_nm: CHEMBL_ACTIVITY_THRESHOLD_NM,
+            bioactive_compound_count: 42,
+            has_approved_drug: true,
+            top_compounds: vec!["CHEMBL4535757".to_string()],
+        };
+        let meta = with_chembl_summary(
+            Some(r#"{"hgnc_id":"HGNC:6407"}"#),
+            &summary,
+            chrono::Utc::now(),
+        );
+        let parsed: serde_json::Value = serde_json::from_str(&meta).unwrap();
+        assert_eq!(parsed["hgnc_id"], "HGNC:6407");
+        assert_eq!(
+            chembl_summary_from_metadata(Some(&meta), PROVIDER_SIGNAL_TTL_DAYS),
+            Some(summary.clone())
+        );
+
+        let stale = with_chembl_summary(
+            None,
+            &summary,
+            chrono::Utc::now() - chrono::Duration::days(PROVIDER_SIGNAL_TTL_DAYS + 1),
+        );
+        assert_eq!(
+            chembl_summary_from_metadata(Some(&stale), PROVIDER_SIGNAL_TTL_DAYS),
+            None
+        );
+        assert_eq!(
+            chembl_summary_from_metadata(Some("not json"), PROVIDER_SIGNAL_TTL_DAYS),
+            None
+        );
+    }
 }
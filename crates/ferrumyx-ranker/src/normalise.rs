@@ -59,6 +59,19 @@ pub fn normalise_ceres(ceres_score: f64) -> f64 {
     1.0 - norm // invert: more essential (more negative) → higher normalised score
 }
 
+/// Literature novelty proxy for component n9, with review articles counted
+/// separately from primary evidence:
+///
+/// `novelty = 1 / (1 + ln(1 + P + w·R))`
+///
+/// where P is primary evidence, R is review evidence and w the review weight.
+/// A target covered mostly by reviews therefore still reads as underexplored.
+pub fn literature_novelty(primary_evidence: f64, review_evidence: f64, review_weight: f64) -> f64 {
+    let effective =
+        primary_evidence.max(0.0) + review_weight.clamp(0.0, 1.0) * review_evidence.max(0.0);
+    1.0 / (1.0 + effective.ln_1p())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -84,4 +97,15 @@ mod tests {
         // -1.0 (moderate) should → 0.5
         assert!((normalise_ceres(-1.0) - 0.5).abs() < 1e-6);
     }
+
+    #[test]
+    fn test_literature_novelty_counts_reviews_separately() {
+        // 1 / (1 + ln(1 + 2 + 0.3 * 10))
+        let expected = 1.0 / (1.0 + 6.0_f64.ln());
+        assert!((literature_novelty(2.0, 10.0, 0.3) - expected).abs() < 1e-12);
+
+        // Ten reviews weigh less than ten primary papers.
+        assert!(literature_novelty(2.0, 10.0, 0.3) > literature_novelty(12.0, 0.0, 0.3));
+        assert!((literature_novelty(0.0, 0.0, 0.3) - 1.0).abs() < 1e-12);
+    }
 }
@@ -0,0 +1,97 @@
+//! Little-endian primitives for the processed provider caches.
+//!
+//! Strings are a `u32` byte length followed by UTF-8 bytes. Files are
+//! written to a `.part` sibling and renamed, so a reader never sees a
+//! half-written cache.
+
+use std::io::{BufWriter, Read, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+pub(crate) fn write_u32(out: &mut impl Write, value: u32) -> Result<()> {
+    out.write_all(&value.to_le_bytes())?;
+    Ok(())
+}
+
+pub(crate) fn write_u64(out: &mut impl Write, value: u64) -> Result<()> {
+    out.write_all(&value.to_le_bytes())?;
+    Ok(())
+}
+
+pub(crate) fn write_str(out: &mut impl Write, value: &str) -> Result<()> {
+    write_u32(out, value.len() as u32)?;
+    out.write_all(value.as_bytes())?;
+    Ok(())
+}
+
+/// A `u64` count followed by the raw values.
+pub(crate) fn write_f32s(out: &mut impl Write, values: &[f32]) -> Result<()> {
+    write_u64(out, values.len() as u64)?;
+    let mut bytes = Vec::with_capacity(values.len() * 4);
+    for value in values {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    out.write_all(&bytes)?;
+    Ok(())
+}
+
+pub(crate) fn read_u32(input: &mut impl Read) -> Result<u32> {
+    let mut buf = [0u8; 4];
+    input.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+pub(crate) fn read_u64(input: &mut impl Read) -> Result<u64> {
+    let mut buf = [0u8; 8];
+    input.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+pub(crate) fn read_str(input: &mut impl Read) -> Result<String> {
+    let len = read_u32(input)? as usize;
+    let mut buf = vec![0u8; len];
+    input.read_exact(&mut buf)?;
+    String::from_utf8(buf).context("cache file contains invalid UTF-8")
+}
+
+pub(crate) fn read_f32s(input: &mut impl Read) -> Result<Vec<f32>> {
+    let len = read_u64(input)? as usize;
+    let mut bytes = vec![0u8; len * 4];
+    input.read_exact(&mut bytes)?;
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect())
+}
+
+/// Read and check the 8-byte file magic.
+pub(crate) fn expect_magic(input: &mut impl Read, magic: &[u8; 8], path: &Path) -> Result<()> {
+    let mut found = [0u8; 8];
+    input.read_exact(&mut found)?;
+    if &found != magic {
+        anyhow::bail!(
+            "{:?} is not a recognised cache file (or has an old format)",
+            path
+        );
+    }
+    Ok(())
+}
+
+/// Write `path` through `<path>.part` with `body`, renaming once complete.
+pub(crate) fn write_atomically(
+    path: &Path,
+    body: impl FnOnce(&mut BufWriter<std::fs::File>) -> Result<()>,
+) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("part");
+    let file =
+        std::fs::File::create(&tmp).with_context(|| format!("Failed to create {:?}", tmp))?;
+    let mut out = BufWriter::new(file);
+    body(&mut out)?;
+    out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+    std::fs::rename(&tmp, path).with_context(|| format!("Failed to write {:?}", path))?;
+    Ok(())
+}
@@ -3,12 +3,21 @@
 //! Provides access to CRISPR-Cas9 gene dependency scores (CERES) from the
 //! Broad Institute's DepMap portal.
 
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use tracing::info;
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+use super::codec;
 
 /// Default DepMap data URL for bulk downloads
 pub const DEPMAP_DOWNLOAD_URL: &str = "https://depmap.org/portal/download/all/";
@@ -19,17 +28,273 @@ pub const CRISPR_GENE_EFFECT_FILE: &str = "CRISPRGeneEffect.csv";
 /// Model (cell line metadata) filename
 pub const MODEL_FILE: &str = "Model.csv";
 
+/// Somatic mutation calls (long format: one row per mutation); optional
+pub const SOMATIC_MUTATIONS_FILE: &str = "OmicsSomaticMutations.csv";
+
+/// Release manifest filename, written next to the CSVs
+pub const MANIFEST_FILE: &str = "depmap_manifest.json";
+
+/// Parsed gene effects and model metadata, loaded instead of the CSVs while
+/// it is newer than them and matches the manifest
+pub const PROCESSED_CACHE_FILE: &str = "depmap_processed.bin";
+
+/// File magic and format version of [`PROCESSED_CACHE_FILE`].
+const PROCESSED_CACHE_MAGIC: &[u8; 8] = b"FXDEPMP\x01";
+
+/// Release tag for whatever the portal currently serves
+pub const LATEST_RELEASE: &str = "latest";
+
+/// Release tag recorded for caches that predate the manifest
+pub const UNKNOWN_RELEASE: &str = "unknown";
+
+/// Fewest shared cell lines for a co-dependency correlation.
+pub const MIN_CODEPENDENCY_CELL_LINES: usize = 10;
+
+/// Genes per block of the co-dependency sweep: the block's running sums
+/// stay in cache while every cell line of the target streams past.
+const CODEPENDENCY_BLOCK_GENES: usize = 512;
+
+/// Sum of squared deviations below which a gene counts as constant.
+const MIN_CODEPENDENCY_VARIANCE: f64 = 1e-9;
+
 /// A client for accessing DepMap dependency data
 #[derive(Debug, Clone)]
 pub struct DepMapClient {
-    /// Gene effect data: gene_symbol -> cell_line_id -> CERES_score
-    gene_effects: HashMap<String, HashMap<String, f64>>,
+    /// CRISPR gene effect scores, one `f32` per (cell line, gene) pair
+    gene_effects: GeneEffectMatrix,
     /// Cell line metadata: cell_line_id -> cancer_type (OncoTree code)
     cell_line_cancers: HashMap<String, String>,
+    /// Cell line display names: cell_line_id -> CellLineName
+    cell_line_names: HashMap<String, String>,
+    /// Cell line lineages: cell_line_id -> OncotreeLineage (e.g. "Pancreas")
+    cell_line_lineages: HashMap<String, String>,
+    /// Somatic mutation calls, if OmicsSomaticMutations.csv is present
+    mutations: MutationCalls,
+    /// Release manifest of the loaded files
+    release: Option<DepMapRelease>,
     /// Data directory path
     data_dir: PathBuf,
 }
 
+/// Progress of the DepMap file currently downloading.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DownloadProgress {
+    pub file: String,
+    pub downloaded_bytes: u64,
+    /// From Content-Length, or Content-Range on a resumed download; `None`
+    /// when the server sends neither.
+    pub total_bytes: Option<u64>,
+    pub complete: bool,
+}
+
+fn progress_sender() -> &'static watch::Sender<Option<DownloadProgress>> {
+    static PROGRESS: OnceLock<watch::Sender<Option<DownloadProgress>>> = OnceLock::new();
+    PROGRESS.get_or_init(|| watch::channel(None).0)
+}
+
+/// Subscribe to DepMap download progress; `None` until a download starts.
+pub fn download_progress() -> watch::Receiver<Option<DownloadProgress>> {
+    progress_sender().subscribe()
+}
+
+/// Size and checksum of one cached DepMap file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepMapFileInfo {
+    pub name: String,
+    pub size_bytes: u64,
+    pub sha256: String,
+}
+
+/// Which DepMap release the cached CSVs came from.
+///
+/// Persisted as `depmap_manifest.json` and checked against the files on
+/// every load, so a truncated download or a silently replaced file fails
+/// loudly instead of producing different scores.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepMapRelease {
+    /// Release tag, e.g. "24Q4".
+    pub release: String,
+    pub downloaded_at: DateTime<Utc>,
+    pub files: Vec<DepMapFileInfo>,
+}
+
+impl DepMapRelease {
+    /// Describe the CSVs currently in `data_dir` as `release`.
+    pub fn from_files(data_dir: &Path, release: &str) -> Result<Self> {
+        let files = [CRISPR_GENE_EFFECT_FILE, MODEL_FILE]
+            .into_iter()
+            .map(|name| file_info(data_dir, name))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            release: release.to_string(),
+            downloaded_at: Utc::now(),
+            files,
+        })
+    }
+
+    /// Read the manifest from `data_dir`, if one has been written.
+    pub fn read(data_dir: &Path) -> Result<Option<Self>> {
+        let path = data_dir.join(MANIFEST_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read DepMap manifest {:?}", path))?;
+        let manifest = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid DepMap manifest {:?}", path))?;
+        Ok(Some(manifest))
+    }
+
+    pub fn write(&self, data_dir: &Path) -> Result<()> {
+        let path = data_dir.join(MANIFEST_FILE);
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write DepMap manifest {:?}", path))
+    }
+
+    /// SHA-256 of the serialised manifest; a processed cache built for one
+    /// manifest is never loaded under another.
+    pub fn fingerprint(&self) -> Result<String> {
+        let digest = Sha256::digest(serde_json::to_vec(self)?);
+        Ok(hex(&digest))
+    }
+
+    /// Check every listed file against its recorded size and checksum.
+    pub fn verify(&self, data_dir: &Path) -> Result<()> {
+        for expected in &self.files {
+            let actual = file_info(data_dir, &expected.name)?;
+            if actual.size_bytes != expected.size_bytes || actual.sha256 != expected.sha256 {
+                anyhow::bail!(
+                    "DepMap {} file {} is truncated or corrupted: manifest records {} bytes \
+                     (sha256 {}), found {} bytes (sha256 {}); delete {:?} to re-download",
+                    self.release,
+                    expected.name,
+                    expected.size_bytes,
+                    expected.sha256,
+                    actual.size_bytes,
+                    actual.sha256,
+                    data_dir
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Size and SHA-256 of `data_dir/name`, hashed without reading it whole.
+fn file_info(data_dir: &Path, name: &str) -> Result<DepMapFileInfo> {
+    let path = data_dir.join(name);
+    let mut file =
+        std::fs::File::open(&path).with_context(|| format!("Failed to open {:?}", path))?;
+    let mut hasher = Sha256::new();
+    let size_bytes = std::io::copy(&mut file, &mut hasher)?;
+    Ok(DepMapFileInfo {
+        name: name.to_string(),
+        size_bytes,
+        sha256: hex(&hasher.finalize()),
+    })
+}
+
+fn hex(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        let _ = std::fmt::Write::write_fmt(&mut out, format_args!("{:02x}", b));
+    }
+    out
+}
+
+/// Whether `path` exists and was modified after every `sources` file.
+fn is_newer_than_all(path: &Path, sources: &[PathBuf]) -> Result<bool> {
+    if !path.exists() {
+        return Ok(false);
+    }
+    let modified = std::fs::metadata(path)?.modified()?;
+    for source in sources {
+        if std::fs::metadata(source)?.modified()? >= modified {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Parsed CSV contents as stored in [`PROCESSED_CACHE_FILE`].
+#[derive(Debug, Default)]
+struct ProcessedCache {
+    /// [`DepMapRelease::fingerprint`] of the release the CSVs came from.
+    fingerprint: String,
+    gene_effects: GeneEffectMatrix,
+    cell_line_cancers: HashMap<String, String>,
+    cell_line_names: HashMap<String, String>,
+    cell_line_lineages: HashMap<String, String>,
+}
+
+impl ProcessedCache {
+    fn write(&self, path: &Path) -> Result<()> {
+        codec::write_atomically(path, |out| {
+            out.write_all(PROCESSED_CACHE_MAGIC)?;
+            codec::write_str(out, &self.fingerprint)?;
+            for map in [
+                &self.cell_line_cancers,
+                &self.cell_line_names,
+                &self.cell_line_lineages,
+            ] {
+                let mut entries: Vec<_> = map.iter().collect();
+                entries.sort();
+                codec::write_u32(out, entries.len() as u32)?;
+                for (key, value) in entries {
+                    codec::write_str(out, key)?;
+                    codec::write_str(out, value)?;
+                }
+            }
+            let matrix = &self.gene_effects;
+            for names in [&matrix.genes, &matrix.cell_lines] {
+                codec::write_u32(out, names.len() as u32)?;
+                for name in names {
+                    codec::write_str(out, name)?;
+                }
+            }
+            codec::write_f32s(out, &matrix.scores)
+        })
+    }
+
+    fn read(path: &Path) -> Result<Self> {
+        let file =
+            std::fs::File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+        let mut input = std::io::BufReader::new(file);
+        codec::expect_magic(&mut input, PROCESSED_CACHE_MAGIC, path)?;
+
+        let fingerprint = codec::read_str(&mut input)?;
+        let mut maps: [HashMap<String, String>; 3] = Default::default();
+        for map in &mut maps {
+            for _ in 0..codec::read_u32(&mut input)? {
+                let key = codec::read_str(&mut input)?;
+                map.insert(key, codec::read_str(&mut input)?);
+            }
+        }
+        let [cell_line_cancers, cell_line_names, cell_line_lineages] = maps;
+
+        let mut matrix = GeneEffectMatrix::default();
+        for idx in 0..codec::read_u32(&mut input)? as usize {
+            matrix.push_gene(idx, codec::read_str(&mut input)?);
+        }
+        for row in 0..codec::read_u32(&mut input)? as usize {
+            let cell_line = codec::read_str(&mut input)?;
+            matrix.cell_line_index.insert(cell_line.clone(), row);
+            matrix.cell_lines.push(cell_line);
+        }
+        matrix.scores = codec::read_f32s(&mut input)?;
+        if matrix.scores.len() != matrix.genes.len() * matrix.cell_lines.len() {
+            anyhow::bail!("{:?} has a truncated score matrix", path);
+        }
+        Ok(Self {
+            fingerprint,
+            gene_effects: matrix,
+            cell_line_cancers,
+            cell_line_names,
+            cell_line_lineages,
+        })
+    }
+}
+
 /// Gene dependency information for a specific cancer type
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GeneDependency {
@@ -41,6 +306,110 @@ pub struct GeneDependency {
     pub num_cell_lines: usize,
 }
 
+/// How much more essential a gene is in one cancer type than in every
+/// other profiled cell line.
+///
+/// `delta` is `mean_in_type - mean_elsewhere`; negative values mean the gene
+/// is selectively essential in the requested type, values near zero mean it
+/// is equally essential (or not) everywhere.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SelectiveDependency {
+    pub gene_symbol: String,
+    pub cancer_type: String,
+    pub mean_in_type: f64,
+    pub mean_elsewhere: f64,
+    pub delta: f64,
+    /// Welch's t statistic; `None` when either group has fewer than two
+    /// cell lines or both groups have zero variance.
+    pub t_statistic: Option<f64>,
+    /// Cohen's d using the pooled standard deviation; `None` under the same
+    /// conditions as `t_statistic`.
+    pub effect_size: Option<f64>,
+    pub n_in_type: usize,
+    pub n_elsewhere: usize,
+}
+
+/// A gene whose knockout effect tracks another's across cell lines.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoDependency {
+    pub gene_symbol: String,
+    /// Pearson correlation of the two genes' CERES scores.
+    pub correlation: f64,
+    /// Cell lines scored for both genes.
+    pub n_cell_lines: usize,
+}
+
+/// The strongest co-dependencies of one gene, either sign.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CoDependencies {
+    pub gene_symbol: String,
+    /// Cell lines scored for the gene.
+    pub n_cell_lines: usize,
+    /// Genes with enough shared cell lines and variance to correlate.
+    pub genes_compared: usize,
+    /// Positive correlations, highest first.
+    pub correlated: Vec<CoDependency>,
+    /// Negative correlations, most negative first.
+    pub anti_correlated: Vec<CoDependency>,
+}
+
+/// Running sums for the Pearson correlation of one gene pair.
+#[derive(Debug, Clone, Copy, Default)]
+struct PairSums {
+    n: usize,
+    x: f64,
+    y: f64,
+    xx: f64,
+    yy: f64,
+    xy: f64,
+}
+
+impl PairSums {
+    fn add(&mut self, x: f64, y: f64) {
+        self.n += 1;
+        self.x += x;
+        self.y += y;
+        self.xx += x * x;
+        self.yy += y * y;
+        self.xy += x * y;
+    }
+
+    /// `None` when either gene is constant over the shared cell lines.
+    fn correlation(&self) -> Option<f64> {
+        let n = self.n as f64;
+        let var_x = self.xx - self.x * self.x / n;
+        let var_y = self.yy - self.y * self.y / n;
+        if var_x < MIN_CODEPENDENCY_VARIANCE || var_y < MIN_CODEPENDENCY_VARIANCE {
+            return None;
+        }
+        let covariance = self.xy - self.x * self.y / n;
+        Some((covariance / (var_x * var_y).sqrt()).clamp(-1.0, 1.0))
+    }
+}
+
+/// Scores of one gene split by whether the cell line carries a mutation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MutationStratifiedScores {
+    /// Cell lines carrying the mutation, sorted ascending.
+    pub mutant: Vec<f64>,
+    /// Profiled cell lines with no mutation in the gene, sorted ascending.
+    pub wildtype: Vec<f64>,
+}
+
+/// CERES score of one gene in one cell line, with the model metadata it came from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CellLineScore {
+    /// DepMap ModelID (e.g. ACH-000001).
+    pub model_id: String,
+    /// Cell line name from Model.csv, if listed.
+    pub cell_line_name: Option<String>,
+    /// OncoTree code from Model.csv, if listed.
+    pub oncotree_code: Option<String>,
+    /// OncoTree lineage from Model.csv, if listed.
+    pub lineage: Option<String>,
+    pub ceres_score: f64,
+}
+
 impl DepMapClient {
     /// Create a new DepMap client, loading data from the specified directory.
     pub async fn new() -> Result<Self> {
@@ -48,29 +417,72 @@ impl DepMapClient {
     }
 
     /// Create a new DepMap client with a specific data directory.
+    ///
+    /// Uses whichever release is cached there, downloading the latest one
+    /// if the directory is empty.
     pub async fn with_data_dir(data_dir: PathBuf) -> Result<Self> {
+        Self::with_data_dir_and_release(data_dir, LATEST_RELEASE).await
+    }
+
+    /// Create a new DepMap client pinned to `release` (e.g. "24Q4").
+    ///
+    /// A cache holding a different release is replaced. Cached files are
+    /// verified against the manifest before they are parsed.
+    pub async fn with_data_dir_and_release(data_dir: PathBuf, release: &str) -> Result<Self> {
         std::fs::create_dir_all(&data_dir)
             .with_context(|| format!("Failed to create data directory: {:?}", data_dir))?;
 
         let mut client = Self {
-            gene_effects: HashMap::new(),
+            gene_effects: GeneEffectMatrix::default(),
             cell_line_cancers: HashMap::new(),
+            cell_line_names: HashMap::new(),
+            cell_line_lineages: HashMap::new(),
+            mutations: MutationCalls::default(),
+            release: None,
             data_dir,
         };
 
-        if client.data_files_exist() {
+        let manifest = DepMapRelease::read(&client.data_dir)?;
+        let cached_release_matches =
+            release == LATEST_RELEASE || manifest.as_ref().is_some_and(|m| m.release == release);
+        if client.data_files_exist() && cached_release_matches {
             info!("Loading DepMap data from {:?}", client.data_dir);
-            client.load_data().await?;
         } else {
-            info!("DepMap data not found, downloading...");
-            client.download_data().await?;
-            client.load_data().await?;
+            info!("DepMap {} data not found, downloading...", release);
+            client.download_data(release).await?;
         }
 
+        match client.load_processed_cache().await {
+            Some(release) => client.release = Some(release),
+            None => {
+                client.release = Some(client.verify_release().await?);
+                client.load_data().await?;
+                client.write_processed_cache().await?;
+            }
+        }
+        if client.data_dir.join(SOMATIC_MUTATIONS_FILE).exists() {
+            client.load_mutations().await?;
+        }
         Ok(client)
     }
 
-    fn default_data_dir() -> PathBuf {
+    /// The client over the default cache dir, or `None` when no release has
+    /// been downloaded there yet; never starts a download.
+    pub async fn load_cached() -> Result<Option<Self>> {
+        let data_dir = Self::default_data_dir();
+        let cached = |file: &str| data_dir.join(file).exists();
+        if !(cached(CRISPR_GENE_EFFECT_FILE) && cached(MODEL_FILE)) {
+            return Ok(None);
+        }
+        Self::with_data_dir(data_dir).await.map(Some)
+    }
+
+    /// Release, download time and checksums of the loaded files.
+    pub fn release_info(&self) -> Option<&DepMapRelease> {
+        self.release.as_ref()
+    }
+
+    pub(crate) fn default_data_dir() -> PathBuf {
         dirs::cache_dir()
             .unwrap_or_else(|| PathBuf::from(".cache"))
             .join("ferrumyx")
@@ -89,152 +501,586 @@ impl DepMapClient {
         self.data_dir.join(MODEL_FILE)
     }
 
-    async fn download_data(&self) -> Result<()> {
+    fn processed_cache_path(&self) -> PathBuf {
+        self.data_dir.join(PROCESSED_CACHE_FILE)
+    }
+
+    async fn download_data(&self, release: &str) -> Result<()> {
         let client = reqwest::Client::new();
 
         for file in &[CRISPR_GENE_EFFECT_FILE, MODEL_FILE] {
-            let url = format!("{}/{}", DEPMAP_DOWNLOAD_URL, file);
-            let path = self.data_dir.join(file);
-            info!("Downloading {}...", file);
+            let url = release_download_url(release, file);
+            info!("Downloading {} ({})...", file, release);
+            download_file(&client, &url, &self.data_dir.join(file)).await?;
+        }
+
+        let data_dir = self.data_dir.clone();
+        let release = release.to_string();
+        tokio::task::spawn_blocking(move || {
+            DepMapRelease::from_files(&data_dir, &release)?.write(&data_dir)
+        })
+        .await??;
+        Ok(())
+    }
+
+    /// Check the cached files against the manifest, writing one for caches
+    /// that predate it.
+    async fn verify_release(&self) -> Result<DepMapRelease> {
+        let data_dir = self.data_dir.clone();
+        tokio::task::spawn_blocking(move || match DepMapRelease::read(&data_dir)? {
+            Some(manifest) => {
+                manifest.verify(&data_dir)?;
+                Ok(manifest)
+            }
+            None => {
+                warn!(
+                    "DepMap cache {:?} has no manifest; recording it as release '{}'",
+                    data_dir, UNKNOWN_RELEASE
+                );
+                let manifest = DepMapRelease::from_files(&data_dir, UNKNOWN_RELEASE)?;
+                manifest.write(&data_dir)?;
+                Ok(manifest)
+            }
+        })
+        .await?
+    }
 
-            let response = client.get(&url).send().await?;
-            if !response.status().is_success() {
-                anyhow::bail!("Failed to download {}: HTTP {}", file, response.status());
+    async fn load_data(&mut self) -> Result<()> {
+        self.load_model_data().await?;
+        self.load_gene_effects().await
+    }
+
+    /// Load [`PROCESSED_CACHE_FILE`] if it is newer than both CSVs and was
+    /// written for the release in the manifest, returning that manifest.
+    ///
+    /// The CSVs are not read or hashed on this path; any problem with the
+    /// cache is logged and falls back to parsing them.
+    async fn load_processed_cache(&mut self) -> Option<DepMapRelease> {
+        let data_dir = self.data_dir.clone();
+        let cache_path = self.processed_cache_path();
+        let sources = [self.gene_effect_path(), self.model_path()];
+        let loaded = tokio::task::spawn_blocking(move || {
+            let Some(manifest) = DepMapRelease::read(&data_dir)? else {
+                return Ok(None);
+            };
+            // Sizes are a cheap check against files replaced within the
+            // filesystem's timestamp resolution.
+            let sizes_match = manifest.files.iter().all(|f| {
+                std::fs::metadata(data_dir.join(&f.name)).is_ok_and(|m| m.len() == f.size_bytes)
+            });
+            if !sizes_match || !is_newer_than_all(&cache_path, &sources)? {
+                return Ok(None);
+            }
+            let cache = ProcessedCache::read(&cache_path)?;
+            if cache.fingerprint != manifest.fingerprint()? {
+                info!("DepMap manifest changed; reparsing CSVs");
+                return Ok(None);
             }
+            Ok::<_, anyhow::Error>(Some((manifest, cache)))
+        })
+        .await;
 
-            let content = response.bytes().await?;
-            tokio::fs::write(path, content).await?;
+        match loaded {
+            Ok(Ok(Some((manifest, cache)))) => {
+                self.gene_effects = cache.gene_effects;
+                self.cell_line_cancers = cache.cell_line_cancers;
+                self.cell_line_names = cache.cell_line_names;
+                self.cell_line_lineages = cache.cell_line_lineages;
+                info!(
+                    "Loaded processed DepMap cache: {} genes x {} cell lines",
+                    self.gene_effects.genes.len(),
+                    self.gene_effects.cell_lines.len()
+                );
+                Some(manifest)
+            }
+            Ok(Ok(None)) => None,
+            Ok(Err(e)) => {
+                warn!("Ignoring processed DepMap cache: {e:#}");
+                None
+            }
+            Err(e) => {
+                warn!("Failed to read processed DepMap cache: {e}");
+                None
+            }
         }
+    }
 
+    /// Write the parsed CSVs to [`PROCESSED_CACHE_FILE`] for the next start.
+    /// A failed write is logged; the client is usable either way.
+    async fn write_processed_cache(&mut self) -> Result<()> {
+        let Some(fingerprint) = self.release.as_ref().map(DepMapRelease::fingerprint) else {
+            return Ok(());
+        };
+        let fingerprint = fingerprint?;
+        let path = self.processed_cache_path();
+        // Moved to the blocking task and back, so the matrix is not copied.
+        let cache = ProcessedCache {
+            fingerprint,
+            gene_effects: std::mem::take(&mut self.gene_effects),
+            cell_line_cancers: std::mem::take(&mut self.cell_line_cancers),
+            cell_line_names: std::mem::take(&mut self.cell_line_names),
+            cell_line_lineages: std::mem::take(&mut self.cell_line_lineages),
+        };
+        let (cache, written) = tokio::task::spawn_blocking(move || {
+            let written = cache.write(&path);
+            (cache, written)
+        })
+        .await?;
+        self.gene_effects = cache.gene_effects;
+        self.cell_line_cancers = cache.cell_line_cancers;
+        self.cell_line_names = cache.cell_line_names;
+        self.cell_line_lineages = cache.cell_line_lineages;
+        if let Err(e) = written {
+            warn!("Failed to write processed DepMap cache: {e:#}");
+        }
         Ok(())
     }
 
-    async fn load_data(&mut self) -> Result<()> {
-        self.load_model_data().await?;
-        self.load_gene_effects().await?;
+    /// Load OmicsSomaticMutations.csv from the data directory.
+    ///
+    /// The file is not part of the default download; drop it next to the
+    /// other CSVs to enable the mutation-stratified queries.
+    pub async fn load_mutations(&mut self) -> Result<()> {
+        let path = self.data_dir.join(SOMATIC_MUTATIONS_FILE);
+        self.mutations = tokio::task::spawn_blocking(move || {
+            let file =
+                std::fs::File::open(&path).with_context(|| format!("Failed to open {:?}", path))?;
+            MutationCalls::from_reader(file)
+        })
+        .await??;
+        info!(
+            "Loaded DepMap mutation calls for {} cell lines",
+            self.mutations.profiled.len()
+        );
         Ok(())
     }
 
+    pub fn has_mutation_data(&self) -> bool {
+        !self.mutations.profiled.is_empty()
+    }
+
     async fn load_model_data(&mut self) -> Result<()> {
         let path = self.model_path();
         let content = tokio::fs::read_to_string(&path).await?;
+        self.parse_model_csv(&content)
+    }
+
+    fn parse_model_csv(&mut self, content: &str) -> Result<()> {
         let mut reader = csv::Reader::from_reader(content.as_bytes());
+        let headers = reader.headers()?.clone();
+        let column = |name: &str| headers.iter().position(|h| h.eq_ignore_ascii_case(name));
+        let oncotree_col = column("OncotreeCode")
+            .or_else(|| column("OncotreePrimaryDisease"))
+            .or_else(|| column("primary_disease"));
+        let name_col = column("CellLineName")
+            .or_else(|| column("StrippedCellLineName"))
+            .or_else(|| column("stripped_cell_line_name"));
+        let lineage_col = column("OncotreeLineage").or_else(|| column("Lineage"));
+        if oncotree_col.is_none() {
+            warn!("Model.csv has no OncotreeCode column; cell lines will have no cancer type");
+        }
 
         for result in reader.records() {
             let record = result?;
-            let model_id = record.get(0).map(|s| s.to_string());
-            let oncotree_code = record
-                .iter()
-                .find(|&s| s.len() == 4 && s.chars().all(|c| c.is_ascii_uppercase()));
-
-            if let (Some(id), Some(cancer_type)) = (model_id, oncotree_code) {
+            let Some(id) = record.get(0).map(|s| s.to_string()) else {
+                continue;
+            };
+            let oncotree_code = oncotree_col
+                .and_then(|col| record.get(col))
+                .map(str::trim)
+                .filter(|s| !s.is_empty());
+            if let Some(name) = name_col
+                .and_then(|col| record.get(col))
+                .filter(|s| !s.trim().is_empty())
+            {
+                self.cell_line_names.insert(id.clone(), name.to_string());
+            }
+            if let Some(lineage) = lineage_col
+                .and_then(|col| record.get(col))
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+            {
+                self.cell_line_lineages
+                    .insert(id.clone(), lineage.to_string());
+            }
+            if let Some(cancer_type) = oncotree_code {
                 self.cell_line_cancers.insert(id, cancer_type.to_string());
             }
         }
         Ok(())
     }
 
+    /// Stream CRISPRGeneEffect.csv row by row; the file is several hundred
+    /// MB, so it is never read into memory whole.
     async fn load_gene_effects(&mut self) -> Result<()> {
         let path = self.gene_effect_path();
-        let content = tokio::fs::read_to_string(&path).await?;
-        let mut reader = csv::Reader::from_reader(content.as_bytes());
+        self.gene_effects = tokio::task::spawn_blocking(move || {
+            // csv::Reader buffers internally, so the file is read in chunks.
+            let file =
+                std::fs::File::open(&path).with_context(|| format!("Failed to open {:?}", path))?;
+            GeneEffectMatrix::from_reader(file)
+        })
+        .await??;
+        info!(
+            "Loaded DepMap gene effects: {} genes x {} cell lines",
+            self.gene_effects.genes.len(),
+            self.gene_effects.cell_lines.len()
+        );
+        Ok(())
+    }
 
-        let headers = reader
-            .headers()?
-            .iter()
-            .map(|s| s.to_string())
-            .collect::<Vec<_>>();
-        let gene_names: Vec<String> = headers.iter().skip(1).cloned().collect();
+    /// Per-cell-line CERES scores for `gene` in `cancer_type`, ordered by ModelID.
+    ///
+    /// Cell lines missing from Model.csv have no OncoTree code, so they never
+    /// match a cancer type and are skipped.
+    pub fn get_gene_scores_detailed(&self, gene: &str, cancer_type: &str) -> Vec<CellLineScore> {
+        self.scores_in_group(gene, CellLineGroup::Code(cancer_type))
+    }
 
-        for result in reader.records() {
-            let record = result?;
-            let cell_line_id = record.get(0).map(|s| s.to_string());
-            if let Some(cell_line_id) = cell_line_id {
-                for (i, value) in record.iter().skip(1).enumerate() {
-                    if let Ok(ceres_score) = value.parse::<f64>() {
-                        if let Some(gene_name) = gene_names.get(i) {
-                            self.gene_effects
-                                .entry(gene_name.clone())
-                                .or_default()
-                                .insert(cell_line_id.clone(), ceres_score);
-                        }
-                    }
-                }
-            }
-        }
-        Ok(())
+    /// Per-cell-line CERES scores for `gene` across every cell line of
+    /// `lineage` (e.g. "Pancreas"), ordered by ModelID.
+    pub fn get_gene_scores_by_lineage_detailed(
+        &self,
+        gene: &str,
+        lineage: &str,
+    ) -> Vec<CellLineScore> {
+        self.scores_in_group(gene, CellLineGroup::Lineage(lineage))
     }
 
     pub fn get_gene_scores(&self, gene: &str, cancer_type: &str) -> Vec<f64> {
-        let gene_upper = gene.to_uppercase();
-        let cancer_upper = cancer_type.to_uppercase();
-        let mut scores = Vec::new();
-
-        if let Some(cell_lines) = self.gene_effects.get(&gene_upper) {
-            for (cell_line_id, score) in cell_lines {
-                if let Some(cell_cancer) = self.cell_line_cancers.get(cell_line_id) {
-                    if cell_cancer.to_uppercase() == cancer_upper {
-                        scores.push(*score);
+        sorted_scores(self.get_gene_scores_detailed(gene, cancer_type))
+    }
+
+    pub fn get_gene_scores_by_lineage(&self, gene: &str, lineage: &str) -> Vec<f64> {
+        sorted_scores(self.get_gene_scores_by_lineage_detailed(gene, lineage))
+    }
+
+    pub fn get_mean_ceres(&self, gene: &str, cancer_type: &str) -> Option<f64> {
+        mean(&self.get_gene_scores(gene, cancer_type))
+    }
+
+    pub fn get_mean_ceres_by_lineage(&self, gene: &str, lineage: &str) -> Option<f64> {
+        mean(&self.get_gene_scores_by_lineage(gene, lineage))
+    }
+
+    pub fn get_median_ceres(&self, gene: &str, cancer_type: &str) -> Option<f64> {
+        median(&self.get_gene_scores(gene, cancer_type))
+    }
+
+    pub fn get_median_ceres_by_lineage(&self, gene: &str, lineage: &str) -> Option<f64> {
+        median(&self.get_gene_scores_by_lineage(gene, lineage))
+    }
+
+    pub fn get_top_dependencies(&self, cancer_type: &str, n: usize) -> Vec<(String, f64)> {
+        self.top_dependencies_in_group(CellLineGroup::Code(cancer_type), n)
+    }
+
+    /// Top `n` dependencies pooled over every cell line of `lineage`.
+    pub fn get_top_dependencies_by_lineage(&self, lineage: &str, n: usize) -> Vec<(String, f64)> {
+        self.top_dependencies_in_group(CellLineGroup::Lineage(lineage), n)
+    }
+
+    /// Scores of `gene` in cell lines with and without `mutation`,
+    /// optionally restricted to one OncoTree code.
+    ///
+    /// `mutation` is a gene ("KRAS") or a gene and protein change
+    /// ("KRAS_G12D", "KRAS p.G12D"). Mutant lines carry that change (any
+    /// change when only a gene is given); wild-type lines were profiled and
+    /// have no mutation in the gene at all, so lines with a different allele
+    /// fall in neither group.
+    pub fn get_gene_scores_by_mutation(
+        &self,
+        gene: &str,
+        mutation: &str,
+        cancer_type: Option<&str>,
+    ) -> MutationStratifiedScores {
+        let mut scores = MutationStratifiedScores::default();
+        let Some(gene_idx) = self.gene_effects.gene(gene) else {
+            return scores;
+        };
+        let (mutated_gene, allele) = parse_mutation(mutation);
+
+        for (row, cell_line_id) in self.gene_effects.cell_lines.iter().enumerate() {
+            if !self.mutations.profiled.contains(cell_line_id) {
+                continue;
+            }
+            if let Some(code) = cancer_type {
+                if !self.in_group(cell_line_id, CellLineGroup::Code(code)) {
+                    continue;
+                }
+            }
+            let Some(score) = self.gene_effects.score(row, gene_idx) else {
+                continue;
+            };
+            match self.mutations.changes(&mutated_gene, cell_line_id) {
+                None => scores.wildtype.push(score),
+                Some(changes) => {
+                    let carries = allele
+                        .as_ref()
+                        .is_none_or(|allele| changes.iter().any(|c| c == allele));
+                    if carries {
+                        scores.mutant.push(score);
                     }
                 }
             }
         }
+        scores.mutant.sort_by(f64::total_cmp);
+        scores.wildtype.sort_by(f64::total_cmp);
         scores
     }
 
-    pub fn get_mean_ceres(&self, gene: &str, cancer_type: &str) -> Option<f64> {
-        let scores = self.get_gene_scores(gene, cancer_type);
-        if scores.is_empty() {
-            None
-        } else {
-            Some(scores.iter().sum::<f64>() / scores.len() as f64)
+    /// Mean CERES of `gene` in `mutation`-carrying lines minus the mean in
+    /// wild-type lines. Negative values mean the mutation creates the
+    /// dependency. `None` when either group is empty.
+    pub fn get_mutant_vs_wt_delta(
+        &self,
+        gene: &str,
+        mutation: &str,
+        cancer_type: Option<&str>,
+    ) -> Option<f64> {
+        let scores = self.get_gene_scores_by_mutation(gene, mutation, cancer_type);
+        Some(mean(&scores.mutant)? - mean(&scores.wildtype)?)
+    }
+
+    /// Compare `gene` in `cancer_type` against all other cell lines with a
+    /// known OncoTree code.
+    ///
+    /// Cell lines without Model.csv metadata are left out of both groups.
+    /// Returns `None` when either group is empty.
+    pub fn get_selective_dependency(
+        &self,
+        gene: &str,
+        cancer_type: &str,
+    ) -> Option<SelectiveDependency> {
+        let gene_idx = self.gene_effects.gene(gene)?;
+        self.selective_dependency(gene_idx, cancer_type)
+    }
+
+    /// Top `n` genes by selective dependency in `cancer_type`: most negative
+    /// `delta` first, ties broken on gene symbol.
+    pub fn get_top_selective_dependencies(
+        &self,
+        cancer_type: &str,
+        n: usize,
+    ) -> Vec<SelectiveDependency> {
+        let mut selective: Vec<SelectiveDependency> = (0..self.gene_effects.genes.len())
+            .filter_map(|gene_idx| self.selective_dependency(gene_idx, cancer_type))
+            .collect();
+        selective.sort_by(|a, b| {
+            a.delta
+                .total_cmp(&b.delta)
+                .then_with(|| a.gene_symbol.cmp(&b.gene_symbol))
+        });
+        selective.truncate(n);
+        selective
+    }
+
+    /// The `top_n` genes whose CERES scores correlate most strongly with
+    /// `gene`'s, and the `top_n` most anti-correlated, each pair over the
+    /// cell lines scored for both. `None` when `gene` is not in the matrix.
+    ///
+    /// Pairs sharing fewer than `min_cell_lines` lines (at least 3), or with
+    /// no variance in either gene, are left out. Every cell line of `gene`
+    /// streams through blocks of the other genes' running sums, read
+    /// row-contiguously and spread over the rayon pool, so a query against
+    /// the full release is one pass over the matrix.
+    pub fn get_codependencies(
+        &self,
+        gene: &str,
+        top_n: usize,
+        min_cell_lines: usize,
+    ) -> Option<CoDependencies> {
+        let matrix = &self.gene_effects;
+        let gene_idx = matrix.gene(gene)?;
+        let width = matrix.genes.len();
+        let mut target: Vec<(usize, f64)> = (0..matrix.cell_lines.len())
+            .filter_map(|row| Some((row, matrix.score(row, gene_idx)?)))
+            .collect();
+        // Centring the target keeps the running sums well conditioned.
+        let target_mean = mean(&target.iter().map(|&(_, x)| x).collect::<Vec<_>>());
+        for (_, x) in &mut target {
+            *x -= target_mean.unwrap_or(0.0);
         }
+        let min_cell_lines = min_cell_lines.max(3);
+
+        let partners: Vec<CoDependency> = (0..width.div_ceil(CODEPENDENCY_BLOCK_GENES))
+            .into_par_iter()
+            .flat_map_iter(|block| {
+                let start = block * CODEPENDENCY_BLOCK_GENES;
+                let end = (start + CODEPENDENCY_BLOCK_GENES).min(width);
+                let mut sums = vec![PairSums::default(); end - start];
+                for &(row, x) in &target {
+                    let scores = &matrix.scores[row * width + start..row * width + end];
+                    for (pair, &y) in sums.iter_mut().zip(scores) {
+                        if !y.is_nan() {
+                            pair.add(x, f64::from(y));
+                        }
+                    }
+                }
+                (start..end)
+                    .zip(sums)
+                    .filter(move |&(other, pair)| other != gene_idx && pair.n >= min_cell_lines)
+                    .filter_map(|(other, pair)| {
+                        Some(CoDependency {
+                            gene_symbol: matrix.genes[other].clone(),
+                            correlation: pair.correlation()?,
+                            n_cell_lines: pair.n,
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        let genes_compared = partners.len();
+        let (mut correlated, mut anti_correlated): (Vec<_>, Vec<_>) =
+            partners.into_iter().partition(|c| c.correlation > 0.0);
+        anti_correlated.retain(|c| c.correlation < 0.0);
+        correlated.sort_by(|a, b| {
+            b.correlation
+                .total_cmp(&a.correlation)
+                .then_with(|| a.gene_symbol.cmp(&b.gene_symbol))
+        });
+        anti_correlated.sort_by(|a, b| {
+            a.correlation
+                .total_cmp(&b.correlation)
+                .then_with(|| a.gene_symbol.cmp(&b.gene_symbol))
+        });
+        correlated.truncate(top_n);
+        anti_correlated.truncate(top_n);
+        Some(CoDependencies {
+            gene_symbol: matrix.genes[gene_idx].clone(),
+            n_cell_lines: target.len(),
+            genes_compared,
+            correlated,
+            anti_correlated,
+        })
     }
 
-    pub fn get_median_ceres(&self, gene: &str, cancer_type: &str) -> Option<f64> {
-        let mut scores = self.get_gene_scores(gene, cancer_type);
-        if scores.is_empty() {
-            None
-        } else {
-            scores.sort_by(|a, b| a.partial_cmp(b).unwrap());
-            let mid = scores.len() / 2;
-            if scores.len() % 2 == 0 {
-                Some((scores[mid - 1] + scores[mid]) / 2.0)
-            } else {
-                Some(scores[mid])
+    fn selective_dependency(
+        &self,
+        gene_idx: usize,
+        cancer_type: &str,
+    ) -> Option<SelectiveDependency> {
+        let mut in_type = Vec::new();
+        let mut elsewhere = Vec::new();
+        for (row, cell_line_id) in self.gene_effects.cell_lines.iter().enumerate() {
+            let Some(score) = self.gene_effects.score(row, gene_idx) else {
+                continue;
+            };
+            match self.cell_line_cancers.get(cell_line_id) {
+                Some(code) if code.eq_ignore_ascii_case(cancer_type) => in_type.push(score),
+                Some(_) => elsewhere.push(score),
+                None => {}
             }
         }
+        in_type.sort_by(f64::total_cmp);
+        elsewhere.sort_by(f64::total_cmp);
+
+        let mean_in_type = mean(&in_type)?;
+        let mean_elsewhere = mean(&elsewhere)?;
+        let delta = mean_in_type - mean_elsewhere;
+        let (t_statistic, effect_size) = match (
+            sample_variance(&in_type, mean_in_type),
+            sample_variance(&elsewhere, mean_elsewhere),
+        ) {
+            (Some(v1), Some(v2)) => {
+                let (n1, n2) = (in_type.len() as f64, elsewhere.len() as f64);
+                let standard_error = (v1 / n1 + v2 / n2).sqrt();
+                let pooled_sd = (((n1 - 1.0) * v1 + (n2 - 1.0) * v2) / (n1 + n2 - 2.0)).sqrt();
+                (
+                    (standard_error > 0.0).then_some(delta / standard_error),
+                    (pooled_sd > 0.0).then_some(delta / pooled_sd),
+                )
+            }
+            _ => (None, None),
+        };
+
+        Some(SelectiveDependency {
+            gene_symbol: self.gene_effects.genes[gene_idx].clone(),
+            cancer_type: cancer_type.to_string(),
+            mean_in_type,
+            mean_elsewhere,
+            delta,
+            t_statistic,
+            effect_size,
+            n_in_type: in_type.len(),
+            n_elsewhere: elsewhere.len(),
+        })
     }
 
-    pub fn get_top_dependencies(&self, cancer_type: &str, n: usize) -> Vec<(String, f64)> {
-        let cancer_upper = cancer_type.to_uppercase();
+    fn in_group(&self, cell_line_id: &str, group: CellLineGroup<'_>) -> bool {
+        let (metadata, wanted) = match group {
+            CellLineGroup::Code(code) => (&self.cell_line_cancers, code),
+            CellLineGroup::Lineage(lineage) => (&self.cell_line_lineages, lineage),
+        };
+        metadata
+            .get(cell_line_id)
+            .is_some_and(|value| value.eq_ignore_ascii_case(wanted))
+    }
+
+    /// Matrix rows of the cell lines in `group`.
+    fn rows_in_group(&self, group: CellLineGroup<'_>) -> Vec<usize> {
+        self.gene_effects
+            .cell_lines
+            .iter()
+            .enumerate()
+            .filter(|(_, cell_line_id)| self.in_group(cell_line_id, group))
+            .map(|(row, _)| row)
+            .collect()
+    }
+
+    fn scores_in_group(&self, gene: &str, group: CellLineGroup<'_>) -> Vec<CellLineScore> {
+        let Some(gene_idx) = self.gene_effects.gene(gene) else {
+            return Vec::new();
+        };
+        let mut scores: Vec<CellLineScore> = self
+            .rows_in_group(group)
+            .into_iter()
+            .filter_map(|row| {
+                let score = self.gene_effects.score(row, gene_idx)?;
+                let cell_line_id = &self.gene_effects.cell_lines[row];
+                Some(CellLineScore {
+                    model_id: cell_line_id.clone(),
+                    cell_line_name: self.cell_line_names.get(cell_line_id).cloned(),
+                    oncotree_code: self.cell_line_cancers.get(cell_line_id).cloned(),
+                    lineage: self.cell_line_lineages.get(cell_line_id).cloned(),
+                    ceres_score: score,
+                })
+            })
+            .collect();
+        scores.sort_by(|a, b| a.model_id.cmp(&b.model_id));
+        scores
+    }
+
+    fn top_dependencies_in_group(&self, group: CellLineGroup<'_>, n: usize) -> Vec<(String, f64)> {
+        let rows = self.rows_in_group(group);
         let mut gene_means = Vec::new();
 
-        for (gene, cell_lines) in &self.gene_effects {
-            let scores: Vec<f64> = cell_lines
+        for (gene_idx, gene) in self.gene_effects.genes.iter().enumerate() {
+            let mut scores: Vec<f64> = rows
                 .iter()
-                .filter_map(|(id, score)| {
-                    self.cell_line_cancers
-                        .get(id)
-                        .filter(|ct| ct.to_uppercase() == cancer_upper)
-                        .map(|_| *score)
-                })
+                .filter_map(|&row| self.gene_effects.score(row, gene_idx))
                 .collect();
-
-            if !scores.is_empty() {
-                gene_means.push((
-                    gene.clone(),
-                    scores.iter().sum::<f64>() / scores.len() as f64,
-                ));
+            scores.sort_by(f64::total_cmp);
+            if let Some(mean) = mean(&scores) {
+                gene_means.push((gene.clone(), mean));
             }
         }
-        gene_means.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
-        gene_means.truncate(n);
-        gene_means
+        rank_top_dependencies(gene_means, n)
+    }
+
+    /// Genes with a column in the gene effect matrix.
+    pub fn gene_count(&self) -> usize {
+        self.gene_effects.genes.len()
+    }
+
+    /// Cell lines with a row in the gene effect matrix.
+    pub fn cell_line_count(&self) -> usize {
+        self.gene_effects.cell_lines.len()
     }
 
     pub fn has_gene(&self, gene: &str) -> bool {
-        self.gene_effects.contains_key(&gene.to_uppercase())
+        self.gene_effects.gene(gene).is_some()
     }
 
     pub fn cancer_types(&self) -> Vec<String> {
@@ -243,4 +1089,1157 @@ impl DepMapClient {
         types.dedup();
         types
     }
+
+    /// Distinct OncoTree lineages with at least one cell line, sorted.
+    pub fn lineages(&self) -> Vec<String> {
+        let mut lineages: Vec<String> = self.cell_line_lineages.values().cloned().collect();
+        lineages.sort();
+        lineages.dedup();
+        lineages
+    }
+
+    /// Whether `cancer_type` is a known OncoTree code (case-insensitive).
+    pub fn has_cancer_type(&self, cancer_type: &str) -> bool {
+        self.cell_line_cancers
+            .values()
+            .any(|code| code.eq_ignore_ascii_case(cancer_type))
+    }
+
+    /// Whether `lineage` is a known OncoTree lineage (case-insensitive).
+    pub fn has_lineage(&self, lineage: &str) -> bool {
+        self.cell_line_lineages
+            .values()
+            .any(|l| l.eq_ignore_ascii_case(lineage))
+    }
+}
+
+/// CRISPRGeneEffect.csv as a dense row-major matrix: one row per cell
+/// line, one column per gene, missing values stored as NaN.
+///
+/// Interning gene and cell line names once keeps the full DepMap release
+/// (~1,100 lines x ~18,000 genes) at under 100 MB, where nested
+/// per-gene maps keyed by String took several GB.
+#[derive(Debug, Clone, Default)]
+struct GeneEffectMatrix {
+    /// Column headers, in file order.
+    genes: Vec<String>,
+    gene_index: HashMap<String, usize>,
+    /// ModelIDs, in file order.
+    cell_lines: Vec<String>,
+    cell_line_index: HashMap<String, usize>,
+    /// `scores[row * genes.len() + gene_idx]`
+    scores: Vec<f32>,
+}
+
+impl GeneEffectMatrix {
+    /// Build the matrix from CSV, reading one record at a time.
+    fn from_reader<R: std::io::Read>(reader: R) -> Result<Self> {
+        let mut reader = csv::Reader::from_reader(reader);
+        let mut matrix = Self::default();
+        for (idx, gene) in reader.headers()?.iter().skip(1).enumerate() {
+            matrix.push_gene(idx, gene.to_string());
+        }
+        let width = matrix.genes.len();
+
+        let mut record = csv::StringRecord::new();
+        while reader.read_record(&mut record)? {
+            let Some(cell_line_id) = record.get(0) else {
+                continue;
+            };
+            let row = match matrix.cell_line_index.get(cell_line_id) {
+                Some(&row) => row,
+                None => {
+                    let row = matrix.cell_lines.len();
+                    matrix.cell_lines.push(cell_line_id.to_string());
+                    matrix.cell_line_index.insert(cell_line_id.to_string(), row);
+                    matrix.scores.resize((row + 1) * width, f32::NAN);
+                    row
+                }
+            };
+            let start = row * width;
+            for (gene_idx, value) in record.iter().skip(1).take(width).enumerate() {
+                if let Ok(score) = value.trim().parse::<f32>() {
+                    matrix.scores[start + gene_idx] = score;
+                }
+            }
+        }
+        // Rows were appended one at a time; drop the growth slack.
+        matrix.scores.shrink_to_fit();
+        Ok(matrix)
+    }
+
+    /// Append a column header. DepMap headers read `SYMBOL (ENTREZ)`, so
+    /// the bare symbol is indexed too unless another column already has it.
+    fn push_gene(&mut self, idx: usize, gene: String) {
+        if let Some((symbol, _)) = gene.split_once(" (") {
+            self.gene_index
+                .entry(symbol.trim().to_uppercase())
+                .or_insert(idx);
+        }
+        self.gene_index.entry(gene.clone()).or_insert(idx);
+        self.genes.push(gene);
+    }
+
+    /// Column of `gene`, which is upper-cased before lookup.
+    fn gene(&self, gene: &str) -> Option<usize> {
+        self.gene_index.get(&gene.to_uppercase()).copied()
+    }
+
+    fn score(&self, row: usize, gene_idx: usize) -> Option<f64> {
+        let score = self.scores[row * self.genes.len() + gene_idx];
+        (!score.is_nan()).then_some(f64::from(score))
+    }
+
+    #[cfg(test)]
+    fn heap_bytes(&self) -> usize {
+        let names: usize = self
+            .genes
+            .iter()
+            .chain(&self.cell_lines)
+            .map(|name| 2 * name.capacity() + std::mem::size_of::<(String, usize)>())
+            .sum();
+        names + self.scores.capacity() * std::mem::size_of::<f32>()
+    }
+}
+
+/// Stream `url` into `path`.
+///
+/// The body goes to `<path>.part` chunk by chunk, with the response's ETag
+/// or Last-Modified saved beside it. If a `.part` file is left over from an
+/// interrupted run, the download resumes from its length with an HTTP Range
+/// request guarded by `If-Range`, so a file that changed on the server comes
+/// back whole instead of being spliced onto the old bytes. The final name
+/// only appears once the total size is known and that many bytes have
+/// arrived. Progress is published on [`download_progress`].
+async fn download_file(client: &reqwest::Client, url: &str, path: &Path) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let partial = path.with_extension("csv.part");
+    let validator_path = path.with_extension("csv.part.validator");
+    let validator = tokio::fs::read_to_string(&validator_path)
+        .await
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+    // Without a validator there is no way to tell whether the partial
+    // bytes still belong to the server's copy.
+    let mut offset = match validator {
+        Some(_) => tokio::fs::metadata(&partial)
+            .await
+            .map(|m| m.len())
+            .unwrap_or(0),
+        None => 0,
+    };
+
+    let mut request = client.get(url);
+    if let (true, Some(validator)) = (offset > 0, &validator) {
+        info!("Resuming {} from byte {}", file_name, offset);
+        request = request
+            .header(reqwest::header::RANGE, format!("bytes={}-", offset))
+            .header(reqwest::header::IF_RANGE, validator);
+    }
+    let mut response = request.send().await?;
+    if response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+        // The partial file does not match the server's copy; start over.
+        offset = 0;
+        response = client.get(url).send().await?;
+    }
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "Failed to download {}: HTTP {}",
+            file_name,
+            response.status()
+        );
+    }
+    let total_bytes = if response.status() == reqwest::StatusCode::PARTIAL_CONTENT {
+        let (start, total) = parse_content_range(response.headers()).with_context(|| {
+            format!("Resumed download of {file_name} has no usable Content-Range")
+        })?;
+        if start != offset {
+            anyhow::bail!(
+                "Resumed download of {} starts at byte {}, expected {}",
+                file_name,
+                start,
+                offset
+            );
+        }
+        total
+    } else {
+        // A server that ignores Range, or whose file changed since the
+        // partial download, sends the whole file with 200.
+        offset = 0;
+        response.content_length()
+    };
+
+    let mut out = tokio::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(offset > 0)
+        .truncate(offset == 0)
+        .open(&partial)
+        .await
+        .with_context(|| format!("Failed to open {:?}", partial))?;
+    // Written after the truncate, so a stale partial never pairs with the
+    // new file's validator.
+    if offset == 0 {
+        match response_validator(response.headers()) {
+            Some(validator) => tokio::fs::write(&validator_path, validator).await?,
+            None => {
+                let _ = tokio::fs::remove_file(&validator_path).await;
+            }
+        }
+    }
+    let mut progress = DownloadProgress {
+        file: file_name.clone(),
+        downloaded_bytes: offset,
+        total_bytes,
+        complete: false,
+    };
+    progress_sender().send_replace(Some(progress.clone()));
+
+    while let Some(chunk) = response.chunk().await? {
+        out.write_all(&chunk).await?;
+        progress.downloaded_bytes += chunk.len() as u64;
+        progress_sender().send_replace(Some(progress.clone()));
+    }
+    out.flush().await?;
+    drop(out);
+
+    let Some(total) = total_bytes else {
+        anyhow::bail!(
+            "Download of {} has no known size; refusing to install it unverified",
+            file_name
+        );
+    };
+    if progress.downloaded_bytes != total {
+        anyhow::bail!(
+            "Download of {} stopped at {} of {} bytes; retry to resume",
+            file_name,
+            progress.downloaded_bytes,
+            total
+        );
+    }
+    tokio::fs::rename(&partial, path).await?;
+    let _ = tokio::fs::remove_file(&validator_path).await;
+    progress.complete = true;
+    progress_sender().send_replace(Some(progress));
+    Ok(())
+}
+
+/// First byte and total size from `Content-Range: bytes a-b/TOTAL`. The
+/// total is `None` when the server reports it as `*`.
+fn parse_content_range(headers: &reqwest::header::HeaderMap) -> Option<(u64, Option<u64>)> {
+    let value = headers.get(reqwest::header::CONTENT_RANGE)?.to_str().ok()?;
+    let (range, total) = value.trim().strip_prefix("bytes ")?.split_once('/')?;
+    let start = range.split_once('-')?.0.trim().parse().ok()?;
+    let total = match total.trim() {
+        "*" => None,
+        total => Some(total.parse().ok()?),
+    };
+    Some((start, total))
+}
+
+/// Validator for a later `If-Range`: a strong ETag, else Last-Modified.
+/// Weak ETags are not allowed in `If-Range`.
+fn response_validator(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    let header = |name: reqwest::header::HeaderName| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    header(reqwest::header::ETAG)
+        .filter(|etag| !etag.starts_with("W/"))
+        .or_else(|| header(reqwest::header::LAST_MODIFIED))
+}
+
+/// Somatic mutation calls: gene -> ModelID -> protein changes.
+#[derive(Debug, Clone, Default)]
+struct MutationCalls {
+    /// Every ModelID with at least one call, i.e. profiled for mutations.
+    profiled: HashSet<String>,
+    /// Keyed by upper-cased HUGO symbol; changes are upper-cased without
+    /// the "p." prefix (e.g. "G12D").
+    by_gene: HashMap<String, HashMap<String, Vec<String>>>,
+}
+
+impl MutationCalls {
+    fn from_reader<R: std::io::Read>(reader: R) -> Result<Self> {
+        let mut reader = csv::Reader::from_reader(reader);
+        let headers = reader.headers()?.clone();
+        let column = |names: &[&str]| {
+            headers
+                .iter()
+                .position(|h| names.iter().any(|n| h.eq_ignore_ascii_case(n)))
+        };
+        let model_col = column(&["ModelID", "DepMap_ID"])
+            .context("OmicsSomaticMutations.csv has no ModelID column")?;
+        let gene_col = column(&["HugoSymbol", "Hugo_Symbol"])
+            .context("OmicsSomaticMutations.csv has no HugoSymbol column")?;
+        let change_col = column(&["ProteinChange", "Protein_Change"]);
+
+        let mut calls = Self::default();
+        let mut record = csv::StringRecord::new();
+        while reader.read_record(&mut record)? {
+            let (Some(model_id), Some(gene)) = (record.get(model_col), record.get(gene_col)) else {
+                continue;
+            };
+            let (model_id, gene) = (model_id.trim(), gene.trim());
+            if model_id.is_empty() || gene.is_empty() {
+                continue;
+            }
+            calls.profiled.insert(model_id.to_string());
+            let changes = calls
+                .by_gene
+                .entry(gene.to_uppercase())
+                .or_default()
+                .entry(model_id.to_string())
+                .or_default();
+            if let Some(change) = change_col
+                .and_then(|col| record.get(col))
+                .map(normalise_protein_change)
+                .filter(|c| !c.is_empty())
+            {
+                changes.push(change);
+            }
+        }
+        Ok(calls)
+    }
+
+    fn changes(&self, gene: &str, model_id: &str) -> Option<&Vec<String>> {
+        self.by_gene.get(gene)?.get(model_id)
+    }
+}
+
+pub(crate) fn normalise_protein_change(change: &str) -> String {
+    let change = change.trim();
+    change.strip_prefix("p.").unwrap_or(change).to_uppercase()
+}
+
+/// Split "KRAS_G12D" / "KRAS G12D" / "KRAS p.G12D" into the upper-cased
+/// gene and an optional normalised protein change.
+pub(crate) fn parse_mutation(mutation: &str) -> (String, Option<String>) {
+    let mutation = mutation.trim();
+    match mutation.split_once(|c: char| c == '_' || c.is_whitespace()) {
+        Some((gene, change)) if !change.trim().is_empty() => {
+            (gene.to_uppercase(), Some(normalise_protein_change(change)))
+        }
+        _ => (mutation.to_uppercase(), None),
+    }
+}
+
+/// Download URL of `file` for `release`; the latest release is served
+/// without a release selector.
+fn release_download_url(release: &str, file: &str) -> String {
+    if release == LATEST_RELEASE {
+        format!("{}/{}", DEPMAP_DOWNLOAD_URL, file)
+    } else {
+        format!(
+            "{}?release=DepMap+Public+{}&file={}",
+            DEPMAP_DOWNLOAD_URL, release, file
+        )
+    }
+}
+
+/// Which Model.csv column a query groups cell lines by.
+#[derive(Debug, Clone, Copy)]
+enum CellLineGroup<'a> {
+    Code(&'a str),
+    Lineage(&'a str),
+}
+
+/// Scores sorted ascending so float sums do not depend on cell line order.
+fn sorted_scores(scores: Vec<CellLineScore>) -> Vec<f64> {
+    let mut scores: Vec<f64> = scores.into_iter().map(|s| s.ceres_score).collect();
+    scores.sort_by(f64::total_cmp);
+    scores
+}
+
+fn mean(scores: &[f64]) -> Option<f64> {
+    if scores.is_empty() {
+        None
+    } else {
+        Some(scores.iter().sum::<f64>() / scores.len() as f64)
+    }
+}
+
+/// Unbiased sample variance; `None` for fewer than two scores.
+fn sample_variance(scores: &[f64], mean: f64) -> Option<f64> {
+    if scores.len() < 2 {
+        return None;
+    }
+    let sum_sq: f64 = scores.iter().map(|s| (s - mean).powi(2)).sum();
+    Some(sum_sq / (scores.len() - 1) as f64)
+}
+
+/// Median of scores already sorted ascending.
+fn median(scores: &[f64]) -> Option<f64> {
+    if scores.is_empty() {
+        return None;
+    }
+    let mid = scores.len() / 2;
+    if scores.len().is_multiple_of(2) {
+        Some((scores[mid - 1] + scores[mid]) / 2.0)
+    } else {
+        Some(scores[mid])
+    }
+}
+
+/// Most negative mean effect first; ties break on gene symbol so the
+/// cut-off at `n` does not depend on HashMap iteration order.
+fn rank_top_dependencies(mut gene_means: Vec<(String, f64)>, n: usize) -> Vec<(String, f64)> {
+    gene_means.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+    gene_means.truncate(n);
+    gene_means
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODEL_CSV: &str =
+        "ModelID,PatientID,CellLineName,StrippedCellLineName,OncotreeLineage,OncotreeCode\n\
+ACH-000002,PT-2,PANC-1,PANC1,Pancreas,PAAD\n\
+ACH-000001,PT-1,MIA PaCa-2,MIAPACA2,Pancreas,PAAD\n\
+ACH-000003,PT-3,A549,A549,Lung,LUAD\n\
+ACH-000004,PT-4,Capan-1,CAPAN1,Pancreas,PAAC\n";
+
+    const GENE_EFFECT_CSV: &str = ",KRAS (3845),TP53 (7157)\n\
+ACH-000001,-1.2,-0.1\n\
+ACH-000002,-0.8,-0.3\n\
+ACH-000003,-0.5,-0.2\n\
+ACH-000004,-1.0,-0.6\n\
+ACH-999999,-2.0,-0.9\n";
+
+    fn empty_client() -> DepMapClient {
+        DepMapClient {
+            gene_effects: GeneEffectMatrix::default(),
+            cell_line_cancers: HashMap::new(),
+            cell_line_names: HashMap::new(),
+            cell_line_lineages: HashMap::new(),
+            mutations: MutationCalls::default(),
+            release: None,
+            data_dir: PathBuf::new(),
+        }
+    }
+
+    fn client_from_fixture() -> DepMapClient {
+        let mut client = empty_client();
+        client.parse_model_csv(MODEL_CSV).unwrap();
+        client.gene_effects = GeneEffectMatrix::from_reader(GENE_EFFECT_CSV.as_bytes()).unwrap();
+        client
+    }
+
+    #[test]
+    fn detailed_scores_carry_model_metadata() {
+        let client = client_from_fixture();
+        let scores = client.get_gene_scores_detailed("kras (3845)", "paad");
+        assert_eq!(
+            scores,
+            vec![
+                CellLineScore {
+                    model_id: "ACH-000001".to_string(),
+                    cell_line_name: Some("MIA PaCa-2".to_string()),
+                    oncotree_code: Some("PAAD".to_string()),
+                    lineage: Some("Pancreas".to_string()),
+                    ceres_score: f64::from(-1.2f32),
+                },
+                CellLineScore {
+                    model_id: "ACH-000002".to_string(),
+                    cell_line_name: Some("PANC-1".to_string()),
+                    oncotree_code: Some("PAAD".to_string()),
+                    lineage: Some("Pancreas".to_string()),
+                    ceres_score: f64::from(-0.8f32),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn cell_lines_missing_from_model_csv_are_skipped() {
+        let client = client_from_fixture();
+        let scores = client.get_gene_scores_detailed("KRAS (3845)", "PAAD");
+        assert!(scores.iter().all(|s| s.model_id != "ACH-999999"));
+        let mean = client.get_mean_ceres("KRAS (3845)", "PAAD").unwrap();
+        assert!((mean + 1.0).abs() < 1e-6);
+        assert_eq!(client.get_median_ceres("KRAS (3845)", "LUAD"), Some(-0.5));
+    }
+
+    #[test]
+    fn oncotree_codes_are_read_by_column_not_length() {
+        let model_csv = "ModelID,CellLineName,OncotreeLineage,OncotreeCode\n\
+ACH-1,U-87 MG,CNS,GB\n\
+ACH-2,HL-60,MYELOID,AML\n\
+ACH-3,PANC-1,PANC,PAAD\n\
+ACH-4,HCT 116,BOWEL,COADREAD\n\
+ACH-5,Unknown,LUNG,\n";
+        let mut client = empty_client();
+        client.parse_model_csv(model_csv).unwrap();
+
+        assert_eq!(client.cancer_types(), vec!["AML", "COADREAD", "GB", "PAAD"]);
+        // "PANC" and "LUNG" are lineage columns, not codes.
+        assert!(!client.cell_line_cancers.contains_key("ACH-5"));
+        assert_eq!(client.cell_line_cancers["ACH-3"], "PAAD");
+    }
+
+    #[test]
+    fn older_model_schemas_fall_back_to_primary_disease() {
+        let model_csv = "DepMap_ID,stripped_cell_line_name,primary_disease\n\
+ACH-1,A549,Lung Cancer\n";
+        let mut client = empty_client();
+        client.parse_model_csv(model_csv).unwrap();
+        assert_eq!(client.cancer_types(), vec!["Lung Cancer"]);
+    }
+
+    #[test]
+    fn lineage_queries_pool_every_code_in_the_lineage() {
+        let client = client_from_fixture();
+        assert_eq!(client.lineages(), vec!["Lung", "Pancreas"]);
+        assert!(client.has_lineage("pancreas"));
+        assert!(!client.has_lineage("PAAD"));
+        assert!(client.has_cancer_type("paad"));
+
+        let models: Vec<String> = client
+            .get_gene_scores_by_lineage_detailed("KRAS (3845)", "PANCREAS")
+            .into_iter()
+            .map(|s| s.model_id)
+            .collect();
+        assert_eq!(models, vec!["ACH-000001", "ACH-000002", "ACH-000004"]);
+        assert_eq!(
+            client.get_gene_scores_by_lineage("KRAS (3845)", "Pancreas"),
+            vec![f64::from(-1.2f32), -1.0, f64::from(-0.8f32)]
+        );
+        let mean = client
+            .get_mean_ceres_by_lineage("KRAS (3845)", "Pancreas")
+            .unwrap();
+        assert!((mean + 1.0).abs() < 1e-6);
+        assert_eq!(
+            client.get_median_ceres_by_lineage("TP53 (7157)", "Pancreas"),
+            Some(f64::from(-0.3f32))
+        );
+        assert_eq!(
+            client.get_mean_ceres_by_lineage("KRAS (3845)", "Skin"),
+            None
+        );
+
+        let top = client.get_top_dependencies_by_lineage("Pancreas", 1);
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].0, "KRAS (3845)");
+        // PAAC lines are not part of the PAAD code query.
+        assert_eq!(
+            client.get_gene_scores("KRAS (3845)", "PAAD"),
+            vec![f64::from(-1.2f32), f64::from(-0.8f32)]
+        );
+    }
+
+    #[test]
+    fn codependencies_rank_genes_by_correlation_across_cell_lines() {
+        let mut csv = ",KRAS,RAF1,DUSP6,GAPDH,NF1\n".to_string();
+        for line in 0..MIN_CODEPENDENCY_CELL_LINES {
+            let kras = -0.1 * line as f64;
+            let dusp6 = if line % 2 == 0 { kras } else { 0.0 };
+            csv.push_str(&format!(
+                "ACH-{line:06},{kras},{},{dusp6},-1.0,{}\n",
+                2.0 * kras - 0.3,
+                -kras
+            ));
+        }
+        let mut client = empty_client();
+        client.gene_effects = GeneEffectMatrix::from_reader(csv.as_bytes()).unwrap();
+
+        let codependencies = client
+            .get_codependencies("kras", 5, MIN_CODEPENDENCY_CELL_LINES)
+            .unwrap();
+        assert_eq!(codependencies.gene_symbol, "KRAS");
+        assert_eq!(codependencies.n_cell_lines, MIN_CODEPENDENCY_CELL_LINES);
+        // GAPDH never varies, so it has no correlation to report.
+        assert_eq!(codependencies.genes_compared, 3);
+        let genes: Vec<&str> = codependencies
+            .correlated
+            .iter()
+            .map(|c| c.gene_symbol.as_str())
+            .collect();
+        assert_eq!(genes, ["RAF1", "DUSP6"]);
+        let correlated = &codependencies.correlated;
+        assert!((correlated[0].correlation - 1.0).abs() < 1e-6);
+        assert!(correlated[1].correlation < 1.0);
+        assert_eq!(correlated[0].n_cell_lines, MIN_CODEPENDENCY_CELL_LINES);
+        let anti = &codependencies.anti_correlated;
+        assert_eq!(anti.len(), 1);
+        assert_eq!(anti[0].gene_symbol, "NF1");
+        assert!((anti[0].correlation + 1.0).abs() < 1e-6);
+
+        let top = client.get_codependencies("KRAS", 1, MIN_CODEPENDENCY_CELL_LINES);
+        assert_eq!(top.unwrap().correlated.len(), 1);
+        let strict = client
+            .get_codependencies("KRAS", 5, MIN_CODEPENDENCY_CELL_LINES + 1)
+            .unwrap();
+        assert!(strict.correlated.is_empty() && strict.anti_correlated.is_empty());
+        assert!(client.get_codependencies("EGFR", 5, 3).is_none());
+
+        // Real headers carry the Entrez ID; the bare symbol still resolves.
+        client.gene_effects = GeneEffectMatrix::from_reader(GENE_EFFECT_CSV.as_bytes()).unwrap();
+        let kras = client
+            .get_codependencies("KRAS", 5, MIN_CODEPENDENCY_CELL_LINES)
+            .unwrap();
+        assert_eq!(kras.gene_symbol, "KRAS (3845)");
+        assert!(kras.correlated.is_empty() && kras.anti_correlated.is_empty());
+    }
+
+    /// `cell_lines` x `genes` effects where G1 tracks G0 exactly, G2 mirrors
+    /// it and G3 misses all but five lines.
+    fn codependency_matrix(cell_lines: usize, genes: usize) -> GeneEffectMatrix {
+        let mut matrix = GeneEffectMatrix::default();
+        for g in 0..genes {
+            matrix.push_gene(g, format!("G{g} ({g})"));
+        }
+        matrix.cell_lines = (0..cell_lines).map(|c| format!("ACH-{c:06}")).collect();
+        matrix.scores = Vec::with_capacity(cell_lines * genes);
+        for c in 0..cell_lines {
+            let target = ((c * 37) % 101) as f32 / 100.0;
+            for g in 0..genes {
+                let score = match g {
+                    0 => -target,
+                    1 => -2.0 * target + 0.1,
+                    2 => target,
+                    3 if c >= 5 => f32::NAN,
+                    _ => -(((g * 7 + c * 13) % 97) as f32) / 97.0,
+                };
+                matrix.scores.push(score);
+            }
+        }
+        matrix
+    }
+
+    #[test]
+    fn codependency_scan_over_thousands_of_genes() {
+        const CELL_LINES: usize = 1000;
+        const GENES: usize = 2000;
+        let mut client = empty_client();
+        client.gene_effects = codependency_matrix(CELL_LINES, GENES);
+
+        let result = client
+            .get_codependencies("G0", 10, MIN_CODEPENDENCY_CELL_LINES)
+            .unwrap();
+
+        assert_eq!(result.n_cell_lines, CELL_LINES);
+        assert_eq!(result.genes_compared, GENES - 2);
+        assert_eq!(result.correlated[0].gene_symbol, "G1 (1)");
+        assert!((result.correlated[0].correlation - 1.0).abs() < 1e-6);
+        assert_eq!(result.anti_correlated[0].gene_symbol, "G2 (2)");
+        assert!((result.anti_correlated[0].correlation + 1.0).abs() < 1e-6);
+        assert!(result.correlated.len() <= 10 && result.anti_correlated.len() <= 10);
+    }
+
+    /// `cargo test -p ferrumyx-ranker --release -- --ignored codependency_scan_benchmark --nocapture`
+    #[test]
+    #[ignore = "benchmark"]
+    fn codependency_scan_benchmark() {
+        const CELL_LINES: usize = 1000;
+        const GENES: usize = 20_000;
+        let mut client = empty_client();
+        client.gene_effects = codependency_matrix(CELL_LINES, GENES);
+
+        let started = std::time::Instant::now();
+        let result = client
+            .get_codependencies("G0", 10, MIN_CODEPENDENCY_CELL_LINES)
+            .unwrap();
+        println!(
+            "co-dependency scan of {} genes x {CELL_LINES} cell lines: {:?}",
+            result.genes_compared,
+            started.elapsed()
+        );
+    }
+
+    #[test]
+    fn selective_dependency_compares_against_other_cell_lines() {
+        let client = client_from_fixture();
+        let kras = client
+            .get_selective_dependency("KRAS (3845)", "PAAD")
+            .unwrap();
+        assert_eq!((kras.n_in_type, kras.n_elsewhere), (2, 2));
+        assert!((kras.mean_in_type + 1.0).abs() < 1e-6);
+        assert!((kras.mean_elsewhere + 0.75).abs() < 1e-6);
+        assert!((kras.delta + 0.25).abs() < 1e-6);
+        // in: [-1.2, -0.8] var 0.08; elsewhere: [-1.0, -0.5] var 0.125.
+        let t = kras.t_statistic.unwrap();
+        assert!((t + 0.25 / (0.04f64 + 0.0625).sqrt()).abs() < 1e-5);
+        let d = kras.effect_size.unwrap();
+        assert!((d + 0.25 / (0.1025f64).sqrt()).abs() < 1e-5);
+
+        // A single LUAD line: means are reported, statistics are not.
+        let luad = client
+            .get_selective_dependency("KRAS (3845)", "LUAD")
+            .unwrap();
+        assert_eq!((luad.n_in_type, luad.n_elsewhere), (1, 3));
+        assert!(luad.t_statistic.is_none());
+        assert!(client
+            .get_selective_dependency("KRAS (3845)", "SKCM")
+            .is_none());
+        assert!(client.get_selective_dependency("MYC", "PAAD").is_none());
+    }
+
+    #[test]
+    fn top_selective_dependencies_rank_by_delta_not_raw_mean() {
+        // COMMON is the most essential gene in PAAD but equally so everywhere.
+        let model_csv = "ModelID,OncotreeCode\nACH-1,PAAD\nACH-2,PAAD\nACH-3,LUAD\nACH-4,LUAD\n";
+        let gene_effect_csv = ",COMMON,SELECTIVE\n\
+ACH-1,-2.0,-1.0\n\
+ACH-2,-2.0,-1.2\n\
+ACH-3,-2.0,-0.1\n\
+ACH-4,-2.0,0.1\n";
+        let mut client = empty_client();
+        client.parse_model_csv(model_csv).unwrap();
+        client.gene_effects = GeneEffectMatrix::from_reader(gene_effect_csv.as_bytes()).unwrap();
+
+        assert_eq!(client.get_top_dependencies("PAAD", 1)[0].0, "COMMON");
+        let top = client.get_top_selective_dependencies("PAAD", 2);
+        let genes: Vec<&str> = top.iter().map(|s| s.gene_symbol.as_str()).collect();
+        assert_eq!(genes, vec!["SELECTIVE", "COMMON"]);
+        assert_eq!(top[1].delta, 0.0);
+        assert!(top[1].t_statistic.is_none());
+    }
+
+    #[tokio::test]
+    async fn streams_large_gene_effect_file_into_compact_matrix() {
+        const CELL_LINES: usize = 1000;
+        const GENES: usize = 2000;
+        let dir = std::env::temp_dir().join(format!("ferrumyx-depmap-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut model = String::from("ModelID,OncotreeLineage,OncotreeCode\n");
+        let mut effects = String::from("ModelID");
+        for g in 0..GENES {
+            effects.push_str(&format!(",G{g} ({g})"));
+        }
+        effects.push('\n');
+        for c in 0..CELL_LINES {
+            let code = if c % 2 == 0 { "PAAD" } else { "LUAD" };
+            model.push_str(&format!("ACH-{c:06},Lineage{},{code}\n", c % 2));
+            effects.push_str(&format!("ACH-{c:06}"));
+            for g in 0..GENES {
+                // Gene 0 is the strongest PAAD dependency; one value is missing.
+                let score = match (g, c % 2) {
+                    (0, 0) => -2.0,
+                    _ if g == 1 && c == 1 => f64::NAN,
+                    _ => -(((g * 7 + c) % 100) as f64) / 100.0,
+                };
+                if score.is_nan() {
+                    effects.push(',');
+                } else {
+                    effects.push_str(&format!(",{score}"));
+                }
+            }
+            effects.push('\n');
+        }
+        std::fs::write(dir.join(MODEL_FILE), model).unwrap();
+        std::fs::write(dir.join(CRISPR_GENE_EFFECT_FILE), effects).unwrap();
+
+        let started = std::time::Instant::now();
+        let client = DepMapClient::with_data_dir(dir.clone()).await.unwrap();
+        let elapsed = started.elapsed();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let matrix = &client.gene_effects;
+        assert_eq!(matrix.scores.len(), CELL_LINES * GENES);
+        // 2M f32 scores are 8 MB; interned names add well under 1 MB.
+        assert!(
+            matrix.heap_bytes() < 10 * 1024 * 1024,
+            "gene effect matrix uses {} bytes",
+            matrix.heap_bytes()
+        );
+        // Generous bound for unoptimised test builds on slow machines.
+        assert!(elapsed.as_secs() < 120, "load took {elapsed:?}");
+
+        assert!(client.has_gene("G1999 (1999)"));
+        assert_eq!(
+            client.get_gene_scores("G0 (0)", "PAAD").len(),
+            CELL_LINES / 2
+        );
+        assert_eq!(
+            client.get_gene_scores("G1 (1)", "LUAD").len(),
+            CELL_LINES / 2 - 1
+        );
+        assert_eq!(client.get_mean_ceres("G0 (0)", "PAAD"), Some(-2.0));
+        assert_eq!(client.get_top_dependencies("PAAD", 1)[0].0, "G0 (0)");
+    }
+
+    fn fixture_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-depmap-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(MODEL_FILE), MODEL_CSV).unwrap();
+        std::fs::write(dir.join(CRISPR_GENE_EFFECT_FILE), GENE_EFFECT_CSV).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn pinned_release_is_verified_and_reported() {
+        let dir = fixture_dir();
+        DepMapRelease::from_files(&dir, "24Q4")
+            .unwrap()
+            .write(&dir)
+            .unwrap();
+
+        let client = DepMapClient::with_data_dir_and_release(dir.clone(), "24Q4")
+            .await
+            .unwrap();
+        let release = client.release_info().unwrap();
+        assert_eq!(release.release, "24Q4");
+        assert_eq!(release.files.len(), 2);
+        assert!(release.files.iter().all(|f| f.sha256.len() == 64));
+        assert!(client.has_gene("KRAS (3845)"));
+
+        // Truncate the gene effect file: loading must fail, not return fewer scores.
+        let truncated = &GENE_EFFECT_CSV[..GENE_EFFECT_CSV.len() / 2];
+        std::fs::write(dir.join(CRISPR_GENE_EFFECT_FILE), truncated).unwrap();
+        let err = DepMapClient::with_data_dir(dir.clone()).await.unwrap_err();
+        let _ = std::fs::remove_dir_all(&dir);
+        let message = err.to_string();
+        assert!(message.contains("truncated or corrupted"), "{message}");
+        assert!(message.contains(CRISPR_GENE_EFFECT_FILE), "{message}");
+    }
+
+    #[tokio::test]
+    async fn caches_without_manifest_are_recorded_as_unknown_release() {
+        let dir = fixture_dir();
+        let client = DepMapClient::with_data_dir(dir.clone()).await.unwrap();
+        let written = DepMapRelease::read(&dir).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(client.release_info().unwrap().release, UNKNOWN_RELEASE);
+        assert_eq!(written.as_ref(), client.release_info());
+    }
+
+    /// A release directory with `cell_lines` PAAD lines x `genes` genes.
+    fn synthetic_release_dir(cell_lines: usize, genes: usize) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-depmap-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut model = String::from("ModelID,OncotreeLineage,OncotreeCode\n");
+        let mut effects = String::from("ModelID");
+        for g in 0..genes {
+            effects.push_str(&format!(",G{g} ({g})"));
+        }
+        effects.push('\n');
+        for c in 0..cell_lines {
+            model.push_str(&format!("ACH-{c:06},Pancreas,PAAD\n"));
+            effects.push_str(&format!("ACH-{c:06}"));
+            for g in 0..genes {
+                // Full-precision values, as in the DepMap release files.
+                effects.push_str(&format!(",{}", -(((g * 13 + c) % 997) as f64) / 997.0));
+            }
+            effects.push('\n');
+        }
+        std::fs::write(dir.join(MODEL_FILE), model).unwrap();
+        std::fs::write(dir.join(CRISPR_GENE_EFFECT_FILE), effects).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn warm_start_reads_the_processed_cache_not_the_csvs() {
+        let dir = synthetic_release_dir(20, 50);
+        let cold = DepMapClient::with_data_dir(dir.clone()).await.unwrap();
+        let cache_path = dir.join(PROCESSED_CACHE_FILE);
+        assert!(cache_path.exists());
+
+        // Same sizes, unparseable contents and checksums that no longer
+        // match the manifest: a warm start that touched the CSVs would fail.
+        for file in [MODEL_FILE, CRISPR_GENE_EFFECT_FILE] {
+            let size = std::fs::metadata(dir.join(file)).unwrap().len() as usize;
+            std::fs::write(dir.join(file), "#".repeat(size)).unwrap();
+        }
+        std::fs::File::options()
+            .write(true)
+            .open(&cache_path)
+            .unwrap()
+            .set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(60))
+            .unwrap();
+
+        let warm = DepMapClient::with_data_dir(dir.clone()).await;
+        let _ = std::fs::remove_dir_all(&dir);
+        let warm = warm.unwrap();
+
+        assert_eq!(warm.gene_effects.scores.len(), 20 * 50);
+        assert_eq!(warm.release_info(), cold.release_info());
+        assert_eq!(
+            warm.get_gene_scores("G7 (7)", "PAAD"),
+            cold.get_gene_scores("G7 (7)", "PAAD")
+        );
+        assert_eq!(warm.lineages(), cold.lineages());
+    }
+
+    /// `cargo test -p ferrumyx-ranker --release -- --ignored processed_cache_benchmark --nocapture`
+    #[tokio::test]
+    #[ignore = "benchmark"]
+    async fn processed_cache_benchmark() {
+        let dir = synthetic_release_dir(500, 2000);
+
+        let started = std::time::Instant::now();
+        DepMapClient::with_data_dir(dir.clone()).await.unwrap();
+        let cold = started.elapsed();
+
+        let started = std::time::Instant::now();
+        DepMapClient::with_data_dir(dir.clone()).await.unwrap();
+        let warm = started.elapsed();
+        let _ = std::fs::remove_dir_all(&dir);
+        println!(
+            "500 x 2000 matrix: CSV parse {cold:?}, processed cache {warm:?} ({:.1}x)",
+            cold.as_secs_f64() / warm.as_secs_f64()
+        );
+    }
+
+    #[tokio::test]
+    async fn processed_cache_is_rebuilt_when_the_manifest_changes() {
+        let dir = fixture_dir();
+        DepMapClient::with_data_dir(dir.clone()).await.unwrap();
+        let cache_path = dir.join(PROCESSED_CACHE_FILE);
+        let first = ProcessedCache::read(&cache_path).unwrap();
+
+        // New release with a different KRAS score; the old cache must not be served.
+        let updated = GENE_EFFECT_CSV.replace("ACH-000001,-1.2", "ACH-000001,-1.6");
+        std::fs::write(dir.join(CRISPR_GENE_EFFECT_FILE), updated).unwrap();
+        DepMapRelease::from_files(&dir, "24Q4")
+            .unwrap()
+            .write(&dir)
+            .unwrap();
+        let client = DepMapClient::with_data_dir(dir.clone()).await.unwrap();
+        let second = ProcessedCache::read(&cache_path).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(client.release_info().unwrap().release, "24Q4");
+        let mean = client.get_mean_ceres("KRAS (3845)", "PAAD").unwrap();
+        assert!((mean + 1.2).abs() < 1e-6, "mean {mean}");
+        assert_ne!(first.fingerprint, second.fingerprint);
+        assert_eq!(
+            second.fingerprint,
+            client.release_info().unwrap().fingerprint().unwrap()
+        );
+    }
+
+    /// Headers of the request a [`serve_once`] server received.
+    #[derive(Debug, Default)]
+    struct SeenRequest {
+        range: Option<String>,
+        if_range: Option<String>,
+    }
+
+    /// Serve `body` once over plain HTTP with ETag `etag`, honouring a
+    /// `Range: bytes=N-` header unless an `If-Range` names another ETag.
+    /// Without `send_length` the body is delimited by closing the
+    /// connection. Returns the URL and the headers the client sent.
+    async fn serve_once(
+        body: &'static [u8],
+        etag: &'static str,
+        send_length: bool,
+    ) -> (String, tokio::task::JoinHandle<SeenRequest>) {
+        use tokio::io::AsyncReadExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!(
+            "http://{}/CRISPRGeneEffect.csv",
+            listener.local_addr().unwrap()
+        );
+        let handle = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                let n = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            let request = String::from_utf8_lossy(&request).to_string();
+            let header = |name: &str| {
+                request.lines().find_map(|l| {
+                    let (key, value) = l.split_once(':')?;
+                    key.eq_ignore_ascii_case(name)
+                        .then(|| value.trim().to_string())
+                })
+            };
+            let seen = SeenRequest {
+                range: header("range"),
+                if_range: header("if-range"),
+            };
+            let start = seen
+                .range
+                .as_deref()
+                .filter(|_| seen.if_range.as_deref().is_none_or(|v| v == etag))
+                .and_then(|r| r.strip_prefix("bytes="))
+                .and_then(|r| r.trim_end_matches('-').parse::<usize>().ok());
+            let mut head = match start {
+                Some(start) => format!(
+                    "HTTP/1.1 206 Partial Content\r\nContent-Range: bytes {start}-{}/{}\r\n",
+                    body.len() - 1,
+                    body.len()
+                ),
+                None => "HTTP/1.1 200 OK\r\n".to_string(),
+            };
+            let slice = &body[start.unwrap_or(0)..];
+            if send_length {
+                head.push_str(&format!("Content-Length: {}\r\n", slice.len()));
+            }
+            head.push_str(&format!("ETag: {etag}\r\nConnection: close\r\n\r\n"));
+            socket.write_all(head.as_bytes()).await.unwrap();
+            socket.write_all(slice).await.unwrap();
+            socket.shutdown().await.unwrap();
+            seen
+        });
+        (url, handle)
+    }
+
+    /// A gene effect download target in a fresh directory, with a `.part`
+    /// file holding `partial` and, if given, its saved validator.
+    fn partial_download(partial: &[u8], validator: Option<&str>) -> (PathBuf, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-depmap-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(CRISPR_GENE_EFFECT_FILE);
+        std::fs::write(path.with_extension("csv.part"), partial).unwrap();
+        if let Some(validator) = validator {
+            std::fs::write(path.with_extension("csv.part.validator"), validator).unwrap();
+        }
+        (dir, path)
+    }
+
+    #[tokio::test]
+    async fn interrupted_downloads_resume_from_the_partial_file() {
+        let body: &'static [u8] = GENE_EFFECT_CSV.as_bytes();
+        let (dir, path) = partial_download(&body[..10], Some("\"v1\""));
+
+        let mut progress = download_progress();
+        let (url, server) = serve_once(body, "\"v1\"", true).await;
+        download_file(&reqwest::Client::new(), &url, &path)
+            .await
+            .unwrap();
+        let seen = server.await.unwrap();
+        let written = std::fs::read(&path).unwrap();
+        let part_left = path.with_extension("csv.part").exists();
+        let validator_left = path.with_extension("csv.part.validator").exists();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(seen.range.as_deref(), Some("bytes=10-"));
+        assert_eq!(seen.if_range.as_deref(), Some("\"v1\""));
+        assert_eq!(written, body);
+        assert!(!part_left && !validator_left);
+        let last = progress.borrow_and_update().clone().unwrap();
+        assert!(last.complete);
+        assert_eq!(last.downloaded_bytes, body.len() as u64);
+        assert_eq!(last.total_bytes, Some(body.len() as u64));
+    }
+
+    #[tokio::test]
+    async fn a_changed_file_is_downloaded_whole_instead_of_spliced() {
+        let body: &'static [u8] = GENE_EFFECT_CSV.as_bytes();
+        // Bytes of an older release, saved with its ETag.
+        let (dir, path) = partial_download(b"ModelID,OLD (1)\n", Some("\"v0\""));
+
+        let (url, server) = serve_once(body, "\"v1\"", true).await;
+        download_file(&reqwest::Client::new(), &url, &path)
+            .await
+            .unwrap();
+        let seen = server.await.unwrap();
+        let written = std::fs::read(&path).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(seen.if_range.as_deref(), Some("\"v0\""));
+        assert_eq!(written, body);
+    }
+
+    #[tokio::test]
+    async fn partial_files_without_a_validator_are_not_resumed() {
+        let body: &'static [u8] = GENE_EFFECT_CSV.as_bytes();
+        let (dir, path) = partial_download(&body[..10], None);
+
+        let (url, server) = serve_once(body, "\"v1\"", true).await;
+        download_file(&reqwest::Client::new(), &url, &path)
+            .await
+            .unwrap();
+        let seen = server.await.unwrap();
+        let written = std::fs::read(&path).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(seen.range, None);
+        assert_eq!(written, body);
+    }
+
+    #[tokio::test]
+    async fn downloads_of_unknown_size_are_not_installed() {
+        let body: &'static [u8] = GENE_EFFECT_CSV.as_bytes();
+        let dir = std::env::temp_dir().join(format!("ferrumyx-depmap-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(CRISPR_GENE_EFFECT_FILE);
+
+        let (url, server) = serve_once(body, "\"v1\"", false).await;
+        let err = download_file(&reqwest::Client::new(), &url, &path)
+            .await
+            .unwrap_err();
+        server.await.unwrap();
+        let installed = path.exists();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(err.to_string().contains("no known size"), "{err}");
+        assert!(!installed);
+    }
+
+    #[test]
+    fn scores_split_by_mutation_status() {
+        let mutations_csv = "ModelID,HugoSymbol,ProteinChange\n\
+ACH-000001,KRAS,p.G12D\n\
+ACH-000002,KRAS,p.G12V\n\
+ACH-000003,TP53,p.R175H\n\
+ACH-000004,TP53,p.R273H\n";
+        let mut client = client_from_fixture();
+        client.mutations = MutationCalls::from_reader(mutations_csv.as_bytes()).unwrap();
+        assert!(client.has_mutation_data());
+
+        let any_kras = client.get_gene_scores_by_mutation("KRAS (3845)", "KRAS", None);
+        assert_eq!(
+            any_kras.mutant,
+            vec![f64::from(-1.2f32), f64::from(-0.8f32)]
+        );
+        assert_eq!(any_kras.wildtype, vec![-1.0, -0.5]);
+
+        // The G12V line is neither G12D-mutant nor KRAS wild-type.
+        let g12d = client.get_gene_scores_by_mutation("KRAS (3845)", "kras_g12d", Some("PAAD"));
+        assert_eq!(g12d.mutant, vec![f64::from(-1.2f32)]);
+        assert!(g12d.wildtype.is_empty());
+        assert_eq!(
+            client.get_gene_scores_by_mutation("KRAS (3845)", "KRAS p.G12D", None),
+            client.get_gene_scores_by_mutation("KRAS (3845)", "KRAS_G12D", None)
+        );
+
+        let delta = client
+            .get_mutant_vs_wt_delta("KRAS (3845)", "KRAS", None)
+            .unwrap();
+        assert!((delta + 0.25).abs() < 1e-6);
+        assert_eq!(
+            client.get_mutant_vs_wt_delta("KRAS (3845)", "KRAS_G12D", Some("PAAD")),
+            None
+        );
+    }
+
+    #[test]
+    fn release_urls_select_the_requested_release() {
+        assert!(release_download_url(LATEST_RELEASE, MODEL_FILE).ends_with("/Model.csv"));
+        assert!(release_download_url("24Q4", MODEL_FILE).contains("DepMap+Public+24Q4"));
+    }
+
+    #[test]
+    fn top_dependencies_break_ties_on_gene_symbol() {
+        let means = vec![
+            ("TP53".to_string(), -0.5),
+            ("KRAS".to_string(), -1.0),
+            ("BRAF".to_string(), -0.5),
+            ("MYC".to_string(), -0.5),
+        ];
+        let top = rank_top_dependencies(means.clone(), 3);
+        assert_eq!(
+            top.iter().map(|(g, _)| g.as_str()).collect::<Vec<_>>(),
+            vec!["KRAS", "BRAF", "MYC"]
+        );
+
+        let mut reversed = means;
+        reversed.reverse();
+        assert_eq!(rank_top_dependencies(reversed, 3), top);
+    }
 }
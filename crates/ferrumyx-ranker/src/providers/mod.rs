@@ -1 +1,3 @@
+mod codec;
 pub mod depmap;
+pub mod tcga;
@@ -0,0 +1,709 @@
+//! TCGA somatic mutation frequencies per gene and cohort.
+//!
+//! The index is built from a local MAF/TSV snapshot (the MC3 public MAF or
+//! a GDC project MAF) dropped into the data directory, or filled gene by
+//! gene from GDC mutation summaries. Once parsed it is written next to the
+//! snapshot as a compact binary file and reused until the snapshot changes,
+//! so a multi-GB MAF is only parsed once.
+//!
+//! Frequencies count patients, not samples: a patient with several tumour
+//! samples or several calls in the same gene is counted once.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+use tracing::{info, warn};
+
+use super::codec::{
+    expect_magic, read_str, read_u32, read_u64, write_atomically, write_str, write_u32, write_u64,
+};
+use super::depmap::{normalise_protein_change, parse_mutation};
+
+/// GDC REST API base URL
+pub const GDC_API_URL: &str = "https://api.gdc.cancer.gov";
+
+/// Mutation snapshot filename (tab-separated MAF, optionally with `#` headers)
+pub const MAF_SNAPSHOT_FILE: &str = "tcga_mutations.maf";
+
+/// Optional `Tumor_Sample_Barcode<TAB>cohort` mapping for MAFs without a
+/// cohort column, such as the MC3 public MAF
+pub const SAMPLE_COHORTS_FILE: &str = "tcga_sample_cohorts.tsv";
+
+/// Processed index filename, written next to the snapshot
+pub const INDEX_FILE: &str = "tcga_mutation_index.bin";
+
+/// File magic and format version of [`INDEX_FILE`].
+const INDEX_MAGIC: &[u8; 8] = b"FXTCGA\x00\x01";
+
+/// MAF `Variant_Classification` values that change the protein.
+const NON_SILENT_CLASSIFICATIONS: [&str; 9] = [
+    "Missense_Mutation",
+    "Nonsense_Mutation",
+    "Frame_Shift_Del",
+    "Frame_Shift_Ins",
+    "In_Frame_Del",
+    "In_Frame_Ins",
+    "Splice_Site",
+    "Nonstop_Mutation",
+    "Translation_Start_Site",
+];
+
+/// GDC `consequence_type` values that change the protein.
+const NON_SILENT_CONSEQUENCES: [&str; 10] = [
+    "missense_variant",
+    "stop_gained",
+    "stop_lost",
+    "start_lost",
+    "frameshift_variant",
+    "inframe_deletion",
+    "inframe_insertion",
+    "splice_acceptor_variant",
+    "splice_donor_variant",
+    "protein_altering_variant",
+];
+
+/// Fraction of patients in a cohort with a non-silent mutation in a gene.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MutationFrequency {
+    pub frequency: f64,
+    pub mutated: u32,
+    pub total: u32,
+    /// Mutated patients per protein change (e.g. "G12D").
+    pub hotspots: BTreeMap<String, u32>,
+}
+
+impl MutationFrequency {
+    fn new(mutated: u32, total: u32, hotspots: BTreeMap<String, u32>) -> Self {
+        Self {
+            frequency: if total == 0 {
+                0.0
+            } else {
+                mutated as f64 / total as f64
+            },
+            mutated,
+            total,
+            hotspots,
+        }
+    }
+
+    /// Frequency of one protein change ("G12D" or "p.G12D") in the same cohort.
+    pub fn hotspot(&self, change: &str) -> MutationFrequency {
+        let change = normalise_protein_change(change);
+        let mutated = self.hotspots.get(&change).copied().unwrap_or(0);
+        let hotspots = BTreeMap::from([(change, mutated)]);
+        Self::new(mutated, self.total, hotspots)
+    }
+
+    /// Narrow to `focus_mutation` (`"KRAS_G12D"`) when it names `gene` and
+    /// a protein change; unchanged otherwise.
+    pub fn for_focus_mutation(self, gene: &str, focus_mutation: &str) -> MutationFrequency {
+        match parse_mutation(focus_mutation) {
+            (focus_gene, Some(change)) if focus_gene.eq_ignore_ascii_case(gene.trim()) => {
+                self.hotspot(&change)
+            }
+            _ => self,
+        }
+    }
+}
+
+/// Stored counts for one gene in one cohort.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct CohortCounts {
+    mutated: u32,
+    hotspots: BTreeMap<String, u32>,
+}
+
+/// Size and modification time of the snapshot an index was built from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct SnapshotStamp {
+    len: u64,
+    modified_secs: u64,
+}
+
+impl SnapshotStamp {
+    fn of(path: &Path) -> Result<Self> {
+        let meta = std::fs::metadata(path).with_context(|| format!("Failed to stat {:?}", path))?;
+        let modified_secs = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_secs());
+        Ok(Self {
+            len: meta.len(),
+            modified_secs,
+        })
+    }
+}
+
+/// Gene → cohort → mutated patient counts, with the patients profiled per
+/// cohort as denominator.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TcgaMutationIndex {
+    /// Cohort code (e.g. "PAAD") -> patients profiled
+    totals: BTreeMap<String, u32>,
+    /// Upper-cased HUGO symbol -> cohort code -> counts
+    genes: HashMap<String, BTreeMap<String, CohortCounts>>,
+}
+
+impl TcgaMutationIndex {
+    /// `~/.cache/ferrumyx/tcga`, next to the DepMap cache.
+    pub fn default_data_dir() -> PathBuf {
+        dirs::cache_dir()
+            .unwrap_or_else(|| PathBuf::from(".cache"))
+            .join("ferrumyx")
+            .join("tcga")
+    }
+
+    /// Load the index for `data_dir`.
+    ///
+    /// Reuses [`INDEX_FILE`] when it was built from the current snapshot and
+    /// rebuilds it otherwise. Without a snapshot, an index filled from GDC
+    /// is loaded as is; `None` when there is neither.
+    pub fn load_or_build(data_dir: &Path) -> Result<Option<Self>> {
+        let snapshot = data_dir.join(MAF_SNAPSHOT_FILE);
+        let index_path = data_dir.join(INDEX_FILE);
+
+        if !snapshot.exists() {
+            if !index_path.exists() {
+                return Ok(None);
+            }
+            let (index, _) = Self::read_index(&index_path)?;
+            return Ok(Some(index));
+        }
+
+        let stamp = SnapshotStamp::of(&snapshot)?;
+        if index_path.exists() {
+            match Self::read_index(&index_path) {
+                Ok((index, cached)) if cached == stamp => return Ok(Some(index)),
+                Ok(_) => info!("TCGA snapshot {:?} changed; rebuilding index", snapshot),
+                Err(e) => warn!("Ignoring unreadable TCGA index {:?}: {e:#}", index_path),
+            }
+        }
+
+        let cohorts_path = data_dir.join(SAMPLE_COHORTS_FILE);
+        let sample_cohorts = if cohorts_path.exists() {
+            load_sample_cohorts(&cohorts_path)?
+        } else {
+            HashMap::new()
+        };
+        info!("Parsing TCGA mutation snapshot {:?}", snapshot);
+        let file = std::fs::File::open(&snapshot)
+            .with_context(|| format!("Failed to open {:?}", snapshot))?;
+        let index = Self::from_maf_reader(BufReader::new(file), &sample_cohorts)?;
+        info!(
+            genes = index.genes.len(),
+            cohorts = index.totals.len(),
+            "Built TCGA mutation index"
+        );
+        index.write_index(&index_path, stamp)?;
+        Ok(Some(index))
+    }
+
+    /// Build the index from a tab-separated MAF.
+    ///
+    /// The cohort of each row comes from a `project_id`/`cohort` column when
+    /// the MAF has one, else from `sample_cohorts` keyed by tumour barcode
+    /// or patient barcode (its first 12 characters). Rows with no cohort are
+    /// skipped. Every patient with a row counts towards the cohort total.
+    pub fn from_maf_reader<R: Read>(
+        reader: R,
+        sample_cohorts: &HashMap<String, String>,
+    ) -> Result<Self> {
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(b'\t')
+            .comment(Some(b'#'))
+            .flexible(true)
+            .from_reader(reader);
+        let headers = reader.headers()?.clone();
+        let column = |names: &[&str]| {
+            headers
+                .iter()
+                .position(|h| names.iter().any(|n| h.eq_ignore_ascii_case(n)))
+        };
+        let gene_col = column(&["Hugo_Symbol"]).context("MAF has no Hugo_Symbol column")?;
+        let class_col = column(&["Variant_Classification"])
+            .context("MAF has no Variant_Classification column")?;
+        let sample_col =
+            column(&["Tumor_Sample_Barcode"]).context("MAF has no Tumor_Sample_Barcode column")?;
+        let change_col = column(&["HGVSp_Short", "Protein_Change", "HGVSp"]);
+        let cohort_col = column(&["project_id", "Project_Code", "cohort", "cancer_type"]);
+
+        let mut patients: HashMap<String, HashSet<String>> = HashMap::new();
+        let mut mutated: HashMap<(String, String), HashSet<String>> = HashMap::new();
+        let mut hotspots: HashMap<(String, String, String), HashSet<String>> = HashMap::new();
+
+        let mut record = csv::StringRecord::new();
+        while reader.read_record(&mut record)? {
+            let (Some(gene), Some(class), Some(sample)) = (
+                record.get(gene_col),
+                record.get(class_col),
+                record.get(sample_col),
+            ) else {
+                continue;
+            };
+            let sample = sample.trim();
+            let patient = patient_barcode(sample);
+            let cohort = cohort_col
+                .and_then(|col| record.get(col))
+                .map(str::to_string)
+                .or_else(|| {
+                    sample_cohorts
+                        .get(sample)
+                        .or_else(|| sample_cohorts.get(patient))
+                        .cloned()
+                })
+                .and_then(|c| normalise_cohort(&c));
+            let Some(cohort) = cohort else {
+                continue;
+            };
+
+            patients
+                .entry(cohort.clone())
+                .or_default()
+                .insert(patient.to_string());
+
+            let gene = gene.trim().to_uppercase();
+            if gene.is_empty() || gene == "UNKNOWN" {
+                continue;
+            }
+            if !NON_SILENT_CLASSIFICATIONS
+                .iter()
+                .any(|c| c.eq_ignore_ascii_case(class.trim()))
+            {
+                continue;
+            }
+            mutated
+                .entry((gene.clone(), cohort.clone()))
+                .or_default()
+                .insert(patient.to_string());
+            if let Some(change) = change_col
+                .and_then(|col| record.get(col))
+                .map(normalise_protein_change)
+                .filter(|c| !c.is_empty())
+            {
+                hotspots
+                    .entry((gene, cohort, change))
+                    .or_default()
+                    .insert(patient.to_string());
+            }
+        }
+
+        let mut index = Self {
+            totals: patients
+                .into_iter()
+                .map(|(cohort, set)| (cohort, set.len() as u32))
+                .collect(),
+            genes: HashMap::new(),
+        };
+        for ((gene, cohort), set) in mutated {
+            index
+                .genes
+                .entry(gene)
+                .or_default()
+                .entry(cohort)
+                .or_default()
+                .mutated = set.len() as u32;
+        }
+        for ((gene, cohort, change), set) in hotspots {
+            if let Some(counts) = index.genes.get_mut(&gene).and_then(|c| c.get_mut(&cohort)) {
+                counts.hotspots.insert(change, set.len() as u32);
+            }
+        }
+        Ok(index)
+    }
+
+    /// Frequency of non-silent mutations in `gene` across `cohort` ("PAAD"
+    /// or "TCGA-PAAD").
+    ///
+    /// A gene without calls in a profiled cohort has frequency 0.0; `None`
+    /// means the cohort is not in the index.
+    pub fn mutation_frequency(&self, gene: &str, cohort: &str) -> Option<MutationFrequency> {
+        let cohort = normalise_cohort(cohort)?;
+        let total = *self.totals.get(&cohort)?;
+        if total == 0 {
+            return None;
+        }
+        let counts = self
+            .genes
+            .get(&gene.trim().to_uppercase())
+            .and_then(|c| c.get(&cohort))
+            .cloned()
+            .unwrap_or_default();
+        Some(MutationFrequency::new(
+            counts.mutated,
+            total,
+            counts.hotspots,
+        ))
+    }
+
+    /// [`mutation_frequency`](Self::mutation_frequency) narrowed to the
+    /// focus mutation when it names this gene and a protein change
+    /// (`"KRAS_G12D"`); the gene-level frequency otherwise.
+    pub fn focus_mutation_frequency(
+        &self,
+        gene: &str,
+        cohort: &str,
+        focus_mutation: &str,
+    ) -> Option<MutationFrequency> {
+        Some(
+            self.mutation_frequency(gene, cohort)?
+                .for_focus_mutation(gene, focus_mutation),
+        )
+    }
+
+    /// Record a frequency fetched from GDC, replacing any stored counts.
+    pub fn insert(&mut self, gene: &str, cohort: &str, freq: &MutationFrequency) {
+        let Some(cohort) = normalise_cohort(cohort) else {
+            return;
+        };
+        self.totals.insert(cohort.clone(), freq.total);
+        self.genes
+            .entry(gene.trim().to_uppercase())
+            .or_default()
+            .insert(
+                cohort,
+                CohortCounts {
+                    mutated: freq.mutated,
+                    hotspots: freq.hotspots.clone(),
+                },
+            );
+    }
+
+    /// Cohort codes in the index, sorted.
+    pub fn cohorts(&self) -> Vec<String> {
+        self.totals.keys().cloned().collect()
+    }
+
+    /// Number of genes with at least one non-silent call.
+    pub fn gene_count(&self) -> usize {
+        self.genes.len()
+    }
+
+    /// Write the index to `path` for the next start-up. Indexes filled from
+    /// GDC have no snapshot and are saved with an empty stamp.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        self.write_index(path, SnapshotStamp::default())
+    }
+
+    fn write_index(&self, path: &Path, stamp: SnapshotStamp) -> Result<()> {
+        write_atomically(path, |out| {
+            out.write_all(INDEX_MAGIC)?;
+            write_u64(out, stamp.len)?;
+            write_u64(out, stamp.modified_secs)?;
+
+            write_u32(out, self.totals.len() as u32)?;
+            for (cohort, total) in &self.totals {
+                write_str(out, cohort)?;
+                write_u32(out, *total)?;
+            }
+
+            // Sorted so the same index always produces the same bytes.
+            let mut genes: Vec<_> = self.genes.iter().collect();
+            genes.sort_by(|a, b| a.0.cmp(b.0));
+            write_u32(out, genes.len() as u32)?;
+            for (gene, cohorts) in genes {
+                write_str(out, gene)?;
+                write_u32(out, cohorts.len() as u32)?;
+                for (cohort, counts) in cohorts {
+                    write_str(out, cohort)?;
+                    write_u32(out, counts.mutated)?;
+                    write_u32(out, counts.hotspots.len() as u32)?;
+                    for (change, n) in &counts.hotspots {
+                        write_str(out, change)?;
+                        write_u32(out, *n)?;
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn read_index(path: &Path) -> Result<(Self, SnapshotStamp)> {
+        let mut input = BufReader::new(
+            std::fs::File::open(path).with_context(|| format!("Failed to open {:?}", path))?,
+        );
+        expect_magic(&mut input, INDEX_MAGIC, path)?;
+        let stamp = SnapshotStamp {
+            len: read_u64(&mut input)?,
+            modified_secs: read_u64(&mut input)?,
+        };
+
+        let mut index = Self::default();
+        for _ in 0..read_u32(&mut input)? {
+            let cohort = read_str(&mut input)?;
+            index.totals.insert(cohort, read_u32(&mut input)?);
+        }
+        for _ in 0..read_u32(&mut input)? {
+            let gene = read_str(&mut input)?;
+            let mut cohorts = BTreeMap::new();
+            for _ in 0..read_u32(&mut input)? {
+                let cohort = read_str(&mut input)?;
+                let mut counts = CohortCounts {
+                    mutated: read_u32(&mut input)?,
+                    ..Default::default()
+                };
+                for _ in 0..read_u32(&mut input)? {
+                    let change = read_str(&mut input)?;
+                    counts.hotspots.insert(change, read_u32(&mut input)?);
+                }
+                cohorts.insert(cohort, counts);
+            }
+            index.genes.insert(gene, cohorts);
+        }
+        Ok((index, stamp))
+    }
+}
+
+/// Fetch the mutation frequency of `gene` in `cohort` from the GDC API.
+///
+/// The denominator is the number of cohort cases with simple somatic
+/// mutation data; the numerator counts cases with a non-silent call on the
+/// canonical transcript. `None` when GDC has no such cases.
+pub async fn fetch_gdc_mutation_frequency(
+    client: &reqwest::Client,
+    gene: &str,
+    cohort: &str,
+) -> Result<Option<MutationFrequency>> {
+    let Some(cohort) = normalise_cohort(cohort) else {
+        return Ok(None);
+    };
+    let project = format!("TCGA-{cohort}");
+    let gene = gene.trim().to_uppercase();
+
+    let case_filters = serde_json::json!({
+        "op": "and",
+        "content": [
+            {"op": "in", "content": {"field": "project.project_id", "value": [project]}},
+            {"op": "in", "content": {"field": "available_variation_data", "value": ["ssm"]}},
+        ]
+    });
+    let cases: Value = client
+        .get(format!("{GDC_API_URL}/cases"))
+        .query(&[
+            ("filters", case_filters.to_string()),
+            ("size", "0".to_string()),
+        ])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    let total = cases["data"]["pagination"]["total"].as_u64().unwrap_or(0) as u32;
+    if total == 0 {
+        return Ok(None);
+    }
+
+    let ssm_filters = serde_json::json!({
+        "op": "and",
+        "content": [
+            {"op": "in", "content": {"field": "case.project.project_id", "value": [project]}},
+            {"op": "in", "content": {"field": "ssm.consequence.transcript.gene.symbol", "value": [gene]}},
+        ]
+    });
+    let occurrences: Value = client
+        .get(format!("{GDC_API_URL}/ssm_occurrences"))
+        .query(&[
+            ("filters", ssm_filters.to_string()),
+            (
+                "fields",
+                "case.submitter_id,ssm.consequence.transcript.aa_change,\
+                 ssm.consequence.transcript.consequence_type,\
+                 ssm.consequence.transcript.is_canonical"
+                    .to_string(),
+            ),
+            ("size", "10000".to_string()),
+        ])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    let hits = occurrences["data"]["hits"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let (mutated, hotspots) = count_gdc_occurrences(hits);
+    Ok(Some(MutationFrequency::new(mutated, total, hotspots)))
+}
+
+/// Mutated patients and per-change patient counts from GDC `ssm_occurrences`
+/// hits, using the canonical transcript of each occurrence.
+fn count_gdc_occurrences(hits: &[Value]) -> (u32, BTreeMap<String, u32>) {
+    let mut patients = HashSet::new();
+    let mut by_change: BTreeMap<String, HashSet<String>> = BTreeMap::new();
+    for hit in hits {
+        let Some(patient) = hit["case"]["submitter_id"].as_str() else {
+            continue;
+        };
+        let consequences = hit["ssm"]["consequence"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let Some(transcript) = consequences
+            .iter()
+            .map(|c| &c["transcript"])
+            .find(|t| t["is_canonical"].as_bool().unwrap_or(false))
+        else {
+            continue;
+        };
+        let consequence = transcript["consequence_type"].as_str().unwrap_or("");
+        if !NON_SILENT_CONSEQUENCES.contains(&consequence) {
+            continue;
+        }
+        let patient = patient_barcode(patient).to_string();
+        patients.insert(patient.clone());
+        if let Some(change) = transcript["aa_change"]
+            .as_str()
+            .map(normalise_protein_change)
+            .filter(|c| !c.is_empty())
+        {
+            by_change.entry(change).or_default().insert(patient);
+        }
+    }
+    let hotspots = by_change
+        .into_iter()
+        .map(|(change, set)| (change, set.len() as u32))
+        .collect();
+    (patients.len() as u32, hotspots)
+}
+
+/// Read a `barcode<TAB>cohort` mapping; a header row is skipped if present.
+pub fn load_sample_cohorts(path: &Path) -> Result<HashMap<String, String>> {
+    let content =
+        std::fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+    Ok(content
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .filter(|(barcode, _)| !barcode.eq_ignore_ascii_case("Tumor_Sample_Barcode"))
+        .map(|(barcode, cohort)| (barcode.trim().to_string(), cohort.trim().to_string()))
+        .collect())
+}
+
+/// "TCGA-PAAD", "paad" and "PAAD" all map to "PAAD".
+fn normalise_cohort(cohort: &str) -> Option<String> {
+    let cohort = cohort.trim().to_uppercase();
+    let cohort = cohort.strip_prefix("TCGA-").unwrap_or(&cohort);
+    (!cohort.is_empty()).then(|| cohort.to_string())
+}
+
+/// Patient part of a TCGA barcode: "TCGA-2J-AAB1-01A-11D-A40Y-08" becomes
+/// "TCGA-2J-AAB1". Other identifiers are returned unchanged.
+fn patient_barcode(sample: &str) -> &str {
+    if sample.starts_with("TCGA-") && sample.len() >= 12 {
+        &sample[..12]
+    } else {
+        sample
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAF: &str = "#version 2.4\n\
+Hugo_Symbol\tVariant_Classification\tTumor_Sample_Barcode\tHGVSp_Short\tproject_id\n\
+KRAS\tMissense_Mutation\tTCGA-AA-0001-01A\tp.G12D\tTCGA-PAAD\n\
+KRAS\tMissense_Mutation\tTCGA-AA-0001-02A\tp.G12D\tTCGA-PAAD\n\
+KRAS\tMissense_Mutation\tTCGA-AA-0002-01A\tp.G12V\tTCGA-PAAD\n\
+KRAS\tMissense_Mutation\tTCGA-AA-0003-01A\tp.G12D\tTCGA-PAAD\n\
+TP53\tSilent\tTCGA-AA-0004-01A\tp.P72P\tTCGA-PAAD\n\
+TP53\tNonsense_Mutation\tTCGA-BB-0001-01A\tp.R196*\tTCGA-LUAD\n\
+KRAS\tMissense_Mutation\tTCGA-BB-0002-01A\tp.G12C\tTCGA-LUAD\n";
+
+    fn index() -> TcgaMutationIndex {
+        TcgaMutationIndex::from_maf_reader(MAF.as_bytes(), &HashMap::new()).unwrap()
+    }
+
+    #[test]
+    fn frequency_counts_patients_with_non_silent_calls() {
+        let index = index();
+        let kras = index.mutation_frequency("kras", "TCGA-PAAD").unwrap();
+        // Four PAAD patients; patient 0001 has two samples but counts once.
+        assert_eq!((kras.mutated, kras.total), (3, 4));
+        assert!((kras.frequency - 0.75).abs() < 1e-12);
+        assert_eq!(kras.hotspots.get("G12D"), Some(&2));
+        assert_eq!(kras.hotspots.get("G12V"), Some(&1));
+
+        // Silent calls add the patient to the total but not the numerator.
+        let tp53 = index.mutation_frequency("TP53", "PAAD").unwrap();
+        assert_eq!((tp53.mutated, tp53.total), (0, 4));
+        assert!(index.mutation_frequency("KRAS", "BRCA").is_none());
+    }
+
+    #[test]
+    fn focus_mutation_selects_the_hotspot() {
+        let index = index();
+        let g12d = index
+            .focus_mutation_frequency("KRAS", "PAAD", "KRAS_G12D")
+            .unwrap();
+        assert_eq!((g12d.mutated, g12d.total), (2, 4));
+        let g12c = index
+            .focus_mutation_frequency("KRAS", "PAAD", "KRAS_G12C")
+            .unwrap();
+        assert_eq!(g12c.mutated, 0);
+        // A focus mutation on another gene leaves the gene-level frequency.
+        let tp53 = index
+            .focus_mutation_frequency("TP53", "LUAD", "KRAS_G12D")
+            .unwrap();
+        assert_eq!((tp53.mutated, tp53.total), (1, 2));
+    }
+
+    #[test]
+    fn cohorts_come_from_sample_mapping_without_a_cohort_column() {
+        let maf = "Hugo_Symbol\tVariant_Classification\tTumor_Sample_Barcode\n\
+KRAS\tMissense_Mutation\tTCGA-AA-0001-01A\n\
+KRAS\tMissense_Mutation\tTCGA-CC-0009-01A\n";
+        let cohorts = HashMap::from([("TCGA-AA-0001".to_string(), "PAAD".to_string())]);
+        let index = TcgaMutationIndex::from_maf_reader(maf.as_bytes(), &cohorts).unwrap();
+        assert_eq!(index.cohorts(), vec!["PAAD".to_string()]);
+        assert_eq!(index.mutation_frequency("KRAS", "PAAD").unwrap().mutated, 1);
+    }
+
+    #[test]
+    fn index_file_is_reused_until_the_snapshot_changes() {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-tcga-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        assert!(TcgaMutationIndex::load_or_build(&dir).unwrap().is_none());
+
+        std::fs::write(dir.join(MAF_SNAPSHOT_FILE), MAF).unwrap();
+        let built = TcgaMutationIndex::load_or_build(&dir).unwrap().unwrap();
+        assert!(dir.join(INDEX_FILE).exists());
+        let (cached, _) = TcgaMutationIndex::read_index(&dir.join(INDEX_FILE)).unwrap();
+        assert_eq!(cached, built);
+
+        let mut grown = MAF.to_string();
+        grown.push_str("KRAS\tMissense_Mutation\tTCGA-AA-0004-01A\tp.G12R\tTCGA-PAAD\n");
+        std::fs::write(dir.join(MAF_SNAPSHOT_FILE), grown).unwrap();
+        let rebuilt = TcgaMutationIndex::load_or_build(&dir).unwrap().unwrap();
+        assert_eq!(
+            rebuilt.mutation_frequency("KRAS", "PAAD").unwrap().mutated,
+            4
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn gdc_occurrences_use_the_canonical_transcript() {
+        let hits: Vec<Value> = serde_json::from_str(
+            r#"[
+                {"case": {"submitter_id": "TCGA-AA-0001"}, "ssm": {"consequence": [
+                    {"transcript": {"is_canonical": false, "consequence_type": "synonymous_variant"}},
+                    {"transcript": {"is_canonical": true, "consequence_type": "missense_variant", "aa_change": "G12D"}}
+                ]}},
+                {"case": {"submitter_id": "TCGA-AA-0002"}, "ssm": {"consequence": [
+                    {"transcript": {"is_canonical": true, "consequence_type": "synonymous_variant", "aa_change": "G12G"}}
+                ]}}
+            ]"#,
+        )
+        .unwrap();
+        let (mutated, hotspots) = count_gdc_occurrences(&hits);
+        assert_eq!(mutated, 1);
+        assert_eq!(hotspots, BTreeMap::from([("G12D".to_string(), 1)]));
+    }
+}
@@ -0,0 +1,511 @@
+//! Hand-off formats for persisted target scores.
+//!
+//! [`load_export_rows`] flattens the current `target_scores` rows into one
+//! record per gene, sorted by confidence-adjusted score, which
+//! [`write_delimited`] writes as CSV or TSV for spreadsheets.
+//! [`generate_markdown_report`] summarises the same rows — the top
+//! [`REPORT_TOP_N`], the weight vector and the data releases behind them —
+//! for the agent to attach to its outputs.
+
+use crate::absence::COMPONENT_KEYS;
+use crate::providers::depmap::{DepMapClient, DepMapRelease};
+use crate::weights::WeightVector;
+use chrono::{DateTime, Utc};
+use ferrumyx_db::entities::EntityRepository;
+use ferrumyx_db::kg_facts::{FactCounts, KgFactRepository};
+use ferrumyx_db::schema::TargetScore;
+use ferrumyx_db::target_scores::TargetScoreRepository;
+use ferrumyx_db::{Database, Phase4SignalRepository};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Rows in the Markdown report's target table.
+pub const REPORT_TOP_N: usize = 20;
+
+/// Scores read per `target_scores` page.
+const SCORE_PAGE: usize = 1_000;
+
+/// Providers whose latest refresh run dates the report's data releases.
+const REFRESHED_PROVIDERS: [&str; 6] =
+    ["cbioportal", "cosmic", "gtex", "tcga", "chembl", "reactome"];
+
+const SOURCE_PERSISTED: &str = "persisted_score";
+const SOURCE_NO_DATA: &str = "no_data";
+
+/// One persisted score, flattened for export.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScoreExportRow {
+    pub gene: String,
+    pub cancer_type: String,
+    pub tier: String,
+    pub composite_score: f64,
+    pub confidence_adjusted_score: f64,
+    /// Normalised components keyed by [`WeightVector::NAMES`]; `None` means
+    /// no data, not a zero score.
+    pub components: BTreeMap<String, Option<f64>>,
+    pub penalty: f64,
+    /// Distinct KG triples about the gene.
+    pub kg_fact_count: u32,
+    /// Papers backing those triples, counted once per triple.
+    pub kg_support_count: u32,
+    pub chembl_inhibitor_count: Option<u64>,
+    /// Provider behind each component, `persisted_score` when the scorer
+    /// did not record one, or `no_data`.
+    pub data_sources: BTreeMap<String, String>,
+    pub scored_at: DateTime<Utc>,
+}
+
+/// Flattens `scores`, keeping those for `cancer_type` when given, highest
+/// adjusted score first. Gene and cancer names missing from a score's
+/// components come from `names`.
+pub fn export_rows(
+    scores: Vec<TargetScore>,
+    names: &HashMap<Uuid, String>,
+    fact_counts: &HashMap<Uuid, FactCounts>,
+    cancer_type: Option<&str>,
+) -> Vec<ScoreExportRow> {
+    let mut rows: Vec<ScoreExportRow> = scores
+        .into_iter()
+        .filter_map(|score| {
+            let raw: serde_json::Value =
+                serde_json::from_str(&score.components_raw).unwrap_or_default();
+            let normed: serde_json::Value =
+                serde_json::from_str(&score.components_normed).unwrap_or_default();
+            let text = |key: &str| raw.get(key).and_then(|v| v.as_str()).map(str::to_string);
+            let gene = text("gene")
+                .or_else(|| names.get(&score.gene_id).cloned())
+                .unwrap_or_else(|| score.gene_id.to_string());
+            let cancer = text("cancer_code")
+                .or_else(|| names.get(&score.cancer_id).cloned())
+                .unwrap_or_else(|| "UNSPECIFIED".to_string());
+            if cancer_type.is_some_and(|c| !cancer.eq_ignore_ascii_case(c.trim())) {
+                return None;
+            }
+
+            let mut components = BTreeMap::new();
+            let mut data_sources = BTreeMap::new();
+            for &(component, keys) in COMPONENT_KEYS {
+                let value = keys
+                    .iter()
+                    .find_map(|k| normed.get(*k).and_then(|v| v.as_f64()));
+                let source = match value {
+                    None => SOURCE_NO_DATA.to_string(),
+                    Some(_) => raw
+                        .get("sources")
+                        .and_then(|s| s.get(component))
+                        .and_then(|s| s.as_str())
+                        .unwrap_or(SOURCE_PERSISTED)
+                        .to_string(),
+                };
+                components.insert(component.to_string(), value);
+                data_sources.insert(component.to_string(), source);
+            }
+            let counts = fact_counts.get(&score.gene_id).copied().unwrap_or_default();
+            Some(ScoreExportRow {
+                gene,
+                cancer_type: cancer,
+                tier: score.shortlist_tier,
+                composite_score: score.composite_score,
+                confidence_adjusted_score: score.confidence_adjusted_score,
+                components,
+                penalty: score.penalty_score,
+                kg_fact_count: counts.triples,
+                kg_support_count: counts.support,
+                chembl_inhibitor_count: raw.get("chembl_inhibitor_count").and_then(|v| v.as_u64()),
+                data_sources,
+                scored_at: score.created_at,
+            })
+        })
+        .collect();
+    rows.sort_by(|a, b| {
+        b.confidence_adjusted_score
+            .total_cmp(&a.confidence_adjusted_score)
+            .then_with(|| a.gene.cmp(&b.gene))
+    });
+    rows
+}
+
+/// Every current persisted score, flattened by [`export_rows`].
+pub async fn load_export_rows(
+    db: Arc<Database>,
+    cancer_type: Option<&str>,
+) -> anyhow::Result<Vec<ScoreExportRow>> {
+    let repo = TargetScoreRepository::new(db.clone());
+    let mut scores = Vec::new();
+    loop {
+        let page = repo.list(scores.len(), SCORE_PAGE).await?;
+        let done = page.len() < SCORE_PAGE;
+        scores.extend(page);
+        if done {
+            break;
+        }
+    }
+
+    let entity_ids: Vec<Uuid> = scores
+        .iter()
+        .flat_map(|s| [s.gene_id, s.cancer_id])
+        .filter(|id| !id.is_nil())
+        .collect();
+    let names = EntityRepository::new(db.clone())
+        .find_names_by_ids(&entity_ids)
+        .await?;
+    let gene_ids: Vec<Uuid> = scores.iter().map(|s| s.gene_id).collect();
+    let fact_counts = KgFactRepository::new(db)
+        .fact_counts_by_subject_ids(&gene_ids, 32)
+        .await?;
+    Ok(export_rows(scores, &names, &fact_counts, cancer_type))
+}
+
+/// Column names of [`write_delimited`], in order.
+pub fn export_header() -> Vec<String> {
+    let mut header: Vec<String> = [
+        "gene",
+        "cancer_type",
+        "tier",
+        "composite_score",
+        "confidence_adjusted_score",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+    header.extend(WeightVector::NAMES.iter().map(|n| n.to_string()));
+    header.extend(
+        [
+            "penalty",
+            "kg_fact_count",
+            "kg_support_count",
+            "chembl_inhibitor_count",
+        ]
+        .iter()
+        .map(|s| s.to_string()),
+    );
+    header.extend(WeightVector::NAMES.iter().map(|n| format!("{n}_source")));
+    header.push("scored_at".to_string());
+    header
+}
+
+/// Writes `rows` separated by `delimiter` (`b','` or `b'\t'`), quoting
+/// fields that contain it, quotes or line breaks. Missing components are
+/// empty fields.
+pub fn write_delimited<W: std::io::Write>(
+    rows: &[ScoreExportRow],
+    delimiter: u8,
+    include_header: bool,
+    out: W,
+) -> csv::Result<()> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .from_writer(out);
+    if include_header {
+        writer.write_record(export_header())?;
+    }
+    for row in rows {
+        let mut record = vec![
+            row.gene.clone(),
+            row.cancer_type.clone(),
+            row.tier.clone(),
+            row.composite_score.to_string(),
+            row.confidence_adjusted_score.to_string(),
+        ];
+        record.extend(WeightVector::NAMES.iter().map(|n| {
+            row.components
+                .get(*n)
+                .copied()
+                .flatten()
+                .map(|v| v.to_string())
+                .unwrap_or_default()
+        }));
+        record.extend([
+            row.penalty.to_string(),
+            row.kg_fact_count.to_string(),
+            row.kg_support_count.to_string(),
+            row.chembl_inhibitor_count
+                .map(|c| c.to_string())
+                .unwrap_or_default(),
+        ]);
+        record.extend(WeightVector::NAMES.iter().map(|n| {
+            row.data_sources
+                .get(*n)
+                .cloned()
+                .unwrap_or_else(|| SOURCE_NO_DATA.to_string())
+        }));
+        record.push(row.scored_at.to_rfc3339());
+        writer.write_record(&record)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// A data source and the release or refresh the scores were built from.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DataRelease {
+    pub source: String,
+    pub release: String,
+    pub as_of: Option<DateTime<Utc>>,
+}
+
+/// The cached DepMap release and the latest refresh of each live provider.
+pub async fn data_releases(db: Arc<Database>) -> Vec<DataRelease> {
+    let mut releases = Vec::new();
+    match DepMapRelease::read(&DepMapClient::default_data_dir()) {
+        Ok(Some(depmap)) => releases.push(DataRelease {
+            source: "depmap".to_string(),
+            release: depmap.release,
+            as_of: Some(depmap.downloaded_at),
+        }),
+        Ok(None) => {}
+        Err(e) => tracing::warn!("DepMap release manifest unreadable: {e}"),
+    }
+    let signals = Phase4SignalRepository::new(db);
+    for provider in REFRESHED_PROVIDERS {
+        match signals.latest_provider_refresh_run(provider).await {
+            Ok(Some(run)) => releases.push(DataRelease {
+                source: provider.to_string(),
+                release: format!("live refresh ({})", run.trigger_reason),
+                as_of: Some(run.finished_at),
+            }),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Refresh history unavailable for {provider}: {e}"),
+        }
+    }
+    releases
+}
+
+/// Markdown summary of the persisted scores for `cancer_type`.
+pub async fn generate_markdown_report(
+    db: Arc<Database>,
+    cancer_type: &str,
+    weights: &WeightVector,
+) -> anyhow::Result<String> {
+    let rows = load_export_rows(db.clone(), Some(cancer_type)).await?;
+    let releases = data_releases(db).await;
+    Ok(render_markdown_report(
+        cancer_type,
+        &rows,
+        weights,
+        &releases,
+        Utc::now(),
+    ))
+}
+
+/// The report of [`generate_markdown_report`] over already loaded rows.
+pub fn render_markdown_report(
+    cancer_type: &str,
+    rows: &[ScoreExportRow],
+    weights: &WeightVector,
+    releases: &[DataRelease],
+    generated_at: DateTime<Utc>,
+) -> String {
+    let cancer_type = cancer_type.trim().to_uppercase();
+    let mut out = String::new();
+    let _ = writeln!(out, "# Target report: {}\n", cell(&cancer_type));
+    let tier_count = |tier: &str| rows.iter().filter(|r| r.tier == tier).count();
+    let _ = writeln!(
+        out,
+        "Generated {} from {} persisted scores ({} primary, {} secondary, {} excluded).\n",
+        generated_at.format("%Y-%m-%d %H:%M UTC"),
+        rows.len(),
+        tier_count("primary"),
+        tier_count("secondary"),
+        rows.len() - tier_count("primary") - tier_count("secondary"),
+    );
+
+    let _ = writeln!(out, "## Top {REPORT_TOP_N} targets\n");
+    if rows.is_empty() {
+        let _ = writeln!(
+            out,
+            "No persisted scores for {}; run the ranker first.\n",
+            cell(&cancer_type)
+        );
+    } else {
+        out.push_str("| Rank | Gene | Tier | Adjusted | Composite | Penalty | KG facts | Papers | Components with data |\n");
+        out.push_str("|---:|---|---|---:|---:|---:|---:|---:|---:|\n");
+        for (rank, row) in rows.iter().take(REPORT_TOP_N).enumerate() {
+            let covered = row.components.values().filter(|v| v.is_some()).count();
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {:.3} | {:.3} | {:.3} | {} | {} | {covered}/{} |",
+                rank + 1,
+                cell(&row.gene),
+                cell(&row.tier),
+                row.confidence_adjusted_score,
+                row.composite_score,
+                row.penalty,
+                row.kg_fact_count,
+                row.kg_support_count,
+                WeightVector::NAMES.len(),
+            );
+        }
+        out.push('\n');
+    }
+
+    out.push_str("## Weights\n\n| Component | Weight |\n|---|---:|\n");
+    for (name, weight) in WeightVector::NAMES.iter().zip(weights.as_array()) {
+        let _ = writeln!(out, "| {name} | {weight:.3} |");
+    }
+    out.push('\n');
+
+    out.push_str("## Data releases\n\n");
+    if releases.is_empty() {
+        out.push_str("No provider release information recorded.\n");
+    } else {
+        out.push_str("| Source | Release | As of |\n|---|---|---|\n");
+        for release in releases {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} |",
+                cell(&release.source),
+                cell(&release.release),
+                release
+                    .as_of
+                    .map(|t| t.format("%Y-%m-%d").to_string())
+                    .unwrap_or_else(|| "unknown".to_string()),
+            );
+        }
+    }
+    out
+}
+
+/// `text` safe inside a Markdown table cell.
+pub(crate) fn cell(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace(['\r', '\n'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(gene: &str, adjusted: f64, sources: serde_json::Value) -> TargetScore {
+        let mut score = TargetScore::new(
+            Uuid::new_v4(),
+            Uuid::nil(),
+            adjusted + 0.05,
+            adjusted,
+            0.05,
+            "primary".to_string(),
+        );
+        score.components_raw = serde_json::json!({
+            "gene": gene,
+            "cancer_code": "PAAD",
+            "chembl_inhibitor_count": 3,
+            "sources": sources,
+        })
+        .to_string();
+        score.components_normed =
+            serde_json::json!({"mutation_freq": 0.9, "crispr_dependency": 0.5}).to_string();
+        score
+    }
+
+    fn fixture() -> Vec<ScoreExportRow> {
+        let mut other = score("TP53", 0.9, serde_json::json!({}));
+        other.components_raw = other.components_raw.replace("PAAD", "LUAD");
+        let scores = vec![
+            score(
+                "KRAS",
+                0.4,
+                serde_json::json!({"mutation_freq": "cbioportal, \"TCGA PanCan\""}),
+            ),
+            score("CDKN2A, \"p16\"", 0.7, serde_json::json!({})),
+            other,
+        ];
+        let mut counts = HashMap::new();
+        counts.insert(
+            scores[0].gene_id,
+            FactCounts {
+                triples: 4,
+                support: 9,
+            },
+        );
+        export_rows(scores, &HashMap::new(), &counts, Some("paad"))
+    }
+
+    #[test]
+    fn export_rows_filter_by_cancer_and_sort_by_adjusted_score() {
+        let rows = fixture();
+        let genes: Vec<&str> = rows.iter().map(|r| r.gene.as_str()).collect();
+        assert_eq!(genes, ["CDKN2A, \"p16\"", "KRAS"]);
+        let kras = &rows[1];
+        assert_eq!(kras.kg_fact_count, 4);
+        assert_eq!(kras.kg_support_count, 9);
+        assert_eq!(kras.chembl_inhibitor_count, Some(3));
+        assert_eq!(kras.components["mutation_freq"], Some(0.9));
+        assert_eq!(kras.components["survival_correlation"], None);
+        assert_eq!(
+            kras.data_sources["mutation_freq"],
+            "cbioportal, \"TCGA PanCan\""
+        );
+        assert_eq!(kras.data_sources["crispr_dependency"], SOURCE_PERSISTED);
+        assert_eq!(kras.data_sources["novelty_score"], SOURCE_NO_DATA);
+    }
+
+    #[test]
+    fn delimited_export_escapes_and_round_trips() {
+        let rows = fixture();
+        for delimiter in [b',', b'\t'] {
+            let mut buf = Vec::new();
+            write_delimited(&rows, delimiter, true, &mut buf).unwrap();
+            let mut reader = csv::ReaderBuilder::new()
+                .delimiter(delimiter)
+                .from_reader(buf.as_slice());
+            let header: Vec<String> = reader
+                .headers()
+                .unwrap()
+                .iter()
+                .map(str::to_string)
+                .collect();
+            assert_eq!(header, export_header());
+            let records: Vec<csv::StringRecord> =
+                reader.records().collect::<Result<_, _>>().unwrap();
+            assert_eq!(records.len(), 2);
+            let column = |name: &str| header.iter().position(|h| h == name).unwrap();
+            assert_eq!(&records[0][column("gene")], "CDKN2A, \"p16\"");
+            assert_eq!(
+                &records[1][column("mutation_freq_source")],
+                "cbioportal, \"TCGA PanCan\""
+            );
+            assert_eq!(&records[1][column("mutation_freq")], "0.9");
+            assert_eq!(&records[1][column("survival_correlation")], "");
+            assert_eq!(&records[1][column("kg_support_count")], "9");
+        }
+
+        let mut csv = Vec::new();
+        write_delimited(&rows[..1], b',', false, &mut csv).unwrap();
+        let line = String::from_utf8(csv).unwrap();
+        assert!(line.starts_with("\"CDKN2A, \"\"p16\"\"\",PAAD,primary,"));
+    }
+
+    #[test]
+    fn markdown_report_lists_top_targets_weights_and_releases() {
+        let rows = fixture();
+        let releases = [DataRelease {
+            source: "depmap".to_string(),
+            release: "24Q4".to_string(),
+            as_of: None,
+        }];
+        let report = render_markdown_report(
+            "paad",
+            &rows,
+            &WeightVector::default(),
+            &releases,
+            Utc::now(),
+        );
+        assert!(report.starts_with("# Target report: PAAD\n"));
+        assert!(report.contains("from 2 persisted scores (2 primary, 0 secondary, 0 excluded)"));
+        assert!(report
+            .contains("| 1 | CDKN2A, \"p16\" | primary | 0.700 | 0.750 | 0.050 | 0 | 0 | 2/9 |"));
+        assert!(report.contains("| 2 | KRAS |"));
+        assert!(report.contains("| mutation_freq | 0.200 |"));
+        assert!(report.contains("| depmap | 24Q4 | unknown |"));
+
+        let empty = render_markdown_report("LUAD", &[], &WeightVector::default(), &[], Utc::now());
+        assert!(empty.contains("No persisted scores for LUAD"));
+        assert!(empty.contains("No provider release information recorded."));
+        assert_eq!(cell("a|b\nc"), "a\\|b c");
+    }
+}
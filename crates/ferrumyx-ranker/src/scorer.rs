@@ -4,10 +4,27 @@
 use crate::depmap_provider::DepMapProvider;
 use crate::gtex_provider::GtexProvider;
 use crate::normalise::normalise_ceres;
+use crate::providers::depmap::parse_mutation;
+use crate::stats::{
+    bootstrap_dependency_ci, shrink_dependency, DependencyCi, DEFAULT_BOOTSTRAP_RESAMPLES,
+    DEFAULT_BOOTSTRAP_SEED,
+};
 use crate::tcga_provider::TcgaProvider;
 use crate::weights::WeightVector;
+use crate::{lookup_provider_components, ProviderComponents};
 use ferrumyx_common::query::{TargetMetrics, TargetScoreResult};
+use ferrumyx_common::CancerType;
+use ferrumyx_db::entities::EntityRepository;
+use ferrumyx_db::kg_conflicts::KgConflictRepository;
+use ferrumyx_db::kg_facts::KgFactRepository;
+use ferrumyx_db::schema::{EntityType, TargetScore as DbTargetScore};
+use ferrumyx_db::target_scores::TargetScoreRepository;
+use ferrumyx_db::Database;
+use ferrumyx_ingestion::sources::CosmicCensus;
+use ferrumyx_kg::conflict::scoring_confidence;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// Raw component scores for a (gene, cancer) pair.
@@ -62,35 +79,108 @@ pub struct PenaltyInputs {
     pub expression_ratio: f64,
     pub has_pdb: bool,
     pub alphafold_plddt: Option<f64>,
+    /// An approved (phase 4) drug already acts on the target.
+    pub has_approved_drug: bool,
+    /// Interventional trials of the target at phase 3 or later.
+    pub phase3_trial_count: u32,
 }
 
-/// Compute penalty term P(g, c).
+/// One penalty term that fired, with the rule behind it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PenaltyReason {
+    /// Stable rule identifier, e.g. `inhibitor_saturation`.
+    pub rule: &'static str,
+    /// Human-readable condition, e.g. `chembl_inhibitor_count 64 > 50`.
+    pub detail: String,
+    /// Amount subtracted from the weighted sum.
+    pub amount: f64,
+}
+
+/// Penalty terms of P(g, c) that apply to `inputs`.
 /// See ARCHITECTURE.md §4.1
-pub fn compute_penalty(inputs: &PenaltyInputs) -> f64 {
-    let mut penalty = 0.0;
+pub fn penalty_reasons(inputs: &PenaltyInputs) -> Vec<PenaltyReason> {
+    let mut reasons = Vec::new();
 
     // Inhibitor saturation penalty
     if inputs.chembl_inhibitor_count > 50 {
-        penalty += 0.15;
+        reasons.push(PenaltyReason {
+            rule: "inhibitor_saturation",
+            detail: format!(
+                "chembl_inhibitor_count {} > 50",
+                inputs.chembl_inhibitor_count
+            ),
+            amount: 0.15,
+        });
     }
 
     // Low expression specificity penalty
     if inputs.expression_ratio < 1.5 {
-        penalty += 0.10;
+        reasons.push(PenaltyReason {
+            rule: "low_expression_specificity",
+            detail: format!("expression_ratio {:.2} < 1.5", inputs.expression_ratio),
+            amount: 0.10,
+        });
+    }
+
+    // Approved drug penalty: the target is already drugged
+    if inputs.has_approved_drug {
+        reasons.push(PenaltyReason {
+            rule: "approved_drug",
+            detail: "approved ChEMBL drug acts on the target".to_string(),
+            amount: 0.10,
+        });
+    }
+
+    // Late-stage clinical penalty: the target is already being tested
+    if inputs.phase3_trial_count > 0 {
+        reasons.push(PenaltyReason {
+            rule: "late_stage_trials",
+            detail: format!(
+                "{} phase 3+ interventional trials",
+                inputs.phase3_trial_count
+            ),
+            amount: 0.10,
+        });
     }
 
     // Structural void penalty
     if !inputs.has_pdb {
-        if let Some(plddt) = inputs.alphafold_plddt {
-            if plddt < 50.0 {
-                penalty += 0.08;
-            }
-        } else {
-            penalty += 0.08; // No structure at all
+        match inputs.alphafold_plddt {
+            Some(plddt) if plddt < 50.0 => reasons.push(PenaltyReason {
+                rule: "structural_void",
+                detail: format!("no PDB structure and AlphaFold pLDDT {plddt:.1} < 50"),
+                amount: 0.08,
+            }),
+            Some(_) => {}
+            None => reasons.push(PenaltyReason {
+                rule: "structural_void",
+                detail: "no PDB or AlphaFold structure".to_string(),
+                amount: 0.08,
+            }),
         }
     }
 
-    penalty
+    reasons
+}
+
+/// Normalised structural tractability (n5).
+///
+/// Experimental coverage saturates at five structures; without one the
+/// AlphaFold pLDDT (0–100) stands in at a lower weight. The best fpocket
+/// score adds up to 0.25 either way.
+pub fn structural_tractability_score(pdb_count: u32, af_plddt_mean: f64, fpocket_best: f64) -> f64 {
+    let pocket = 0.25 * fpocket_best.clamp(0.0, 1.0);
+    if pdb_count > 0 {
+        0.40 * (pdb_count as f64 / 5.0).min(1.0) + pocket
+    } else {
+        0.35 * (af_plddt_mean / 100.0) + pocket
+    }
+}
+
+/// Compute penalty term P(g, c).
+/// See ARCHITECTURE.md §4.1
+pub fn compute_penalty(inputs: &PenaltyInputs) -> f64 {
+    penalty_reasons(inputs).iter().map(|r| r.amount).sum()
 }
 
 /// Final scored target result.
@@ -138,6 +228,125 @@ pub fn compute_composite_score(
     (composite, adjusted)
 }
 
+/// One component's share of the composite.
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentContribution {
+    pub component: &'static str,
+    /// Value in natural units, when known.
+    pub raw: Option<f64>,
+    pub normalized: f64,
+    pub weight: f64,
+    /// `weight × normalized`.
+    pub contribution: f64,
+    /// Share of the weighted sum, 0–100.
+    pub percent_of_total: f64,
+}
+
+/// Composite score with the terms that produced it.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoreExplanation {
+    pub components: Vec<ComponentContribution>,
+    /// Σ(w_i × n_i) before penalties.
+    pub weighted_sum: f64,
+    pub penalties: Vec<PenaltyReason>,
+    pub penalty_total: f64,
+    pub composite_score: f64,
+    pub confidence_adjusted_score: f64,
+}
+
+impl ScoreExplanation {
+    /// Short rationale naming the leading components and any penalties.
+    pub fn rationale(&self) -> String {
+        let mut leading: Vec<&ComponentContribution> = self
+            .components
+            .iter()
+            .filter(|c| c.contribution > 0.0)
+            .collect();
+        leading.sort_by(|a, b| b.contribution.total_cmp(&a.contribution));
+        let drivers = leading
+            .iter()
+            .take(3)
+            .map(|c| format!("{} ({:.0}%)", c.component, c.percent_of_total))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut out = if drivers.is_empty() {
+            "No component contributes to the score".to_string()
+        } else {
+            format!("Driven by {drivers}")
+        };
+        if !self.penalties.is_empty() {
+            let penalties = self
+                .penalties
+                .iter()
+                .map(|p| format!("{}: -{:.2}", p.detail, p.amount))
+                .collect::<Vec<_>>()
+                .join("; ");
+            out.push_str(&format!("; penalised for {penalties}"));
+        }
+        out
+    }
+}
+
+/// [`compute_composite_score`] with a per-component and per-penalty
+/// breakdown. `raw` supplies the natural-unit values shown alongside the
+/// normalised ones.
+pub fn compute_composite_score_explained(
+    raw: Option<&ComponentScoresRaw>,
+    normed: &ComponentScoresNormed,
+    weights: &WeightVector,
+    penalties: Vec<PenaltyReason>,
+    mean_confidence: f64,
+) -> ScoreExplanation {
+    let raw_values = raw.map(|r| {
+        [
+            r.mutation_freq,
+            r.crispr_dependency,
+            r.survival_correlation,
+            r.expression_specificity,
+            r.structural_tractability,
+            r.pocket_detectability,
+            r.novelty_score,
+            r.pathway_independence,
+            r.literature_novelty,
+        ]
+    });
+    let normalized = normed.as_array();
+    let weight_arr = weights.as_array();
+    let weighted_sum: f64 = normalized.iter().zip(&weight_arr).map(|(n, w)| n * w).sum();
+
+    let components = (0..9)
+        .map(|i| {
+            let contribution = normalized[i] * weight_arr[i];
+            ComponentContribution {
+                component: WeightVector::NAMES[i],
+                raw: raw_values.and_then(|r| r[i]),
+                normalized: normalized[i],
+                weight: weight_arr[i],
+                contribution,
+                percent_of_total: if weighted_sum > 0.0 {
+                    100.0 * contribution / weighted_sum
+                } else {
+                    0.0
+                },
+            }
+        })
+        .collect();
+
+    let penalty_total: f64 = penalties.iter().map(|p| p.amount).sum();
+    let (composite_score, confidence_adjusted_score) =
+        compute_composite_score(normed, weights, penalty_total, mean_confidence);
+
+    ScoreExplanation {
+        components,
+        weighted_sum,
+        penalties,
+        penalty_total,
+        composite_score,
+        confidence_adjusted_score,
+    }
+}
+
 /// Shortlisting threshold check.
 /// See ARCHITECTURE.md §4.5
 #[derive(Debug, Clone, PartialEq)]
@@ -147,6 +356,17 @@ pub enum ShortlistTier {
     Excluded,
 }
 
+impl ShortlistTier {
+    /// Name stored in `target_scores.shortlist_tier`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ShortlistTier::Primary => "primary",
+            ShortlistTier::Secondary => "secondary",
+            ShortlistTier::Excluded => "excluded",
+        }
+    }
+}
+
 pub struct PrioritizationEngine;
 
 impl PrioritizationEngine {
@@ -174,17 +394,18 @@ impl PrioritizationEngine {
             let ceres_normalized = (metrics.crispr_dependency + 2.0) / 2.0;
             let n2 = 1.0 - ceres_normalized.clamp(0.0, 1.0);
 
-            let pdb_cov = (metrics.pdb_structure_count as f64 / 5.0).min(1.0);
-            let n5 = if metrics.pdb_structure_count > 0 {
-                0.40 * pdb_cov + 0.25 * metrics.fpocket_best_score.clamp(0.0, 1.0)
-            } else {
-                0.35 * (metrics.af_plddt_mean / 100.0)
-                    + 0.25 * metrics.fpocket_best_score.clamp(0.0, 1.0)
-            };
+            let n5 = structural_tractability_score(
+                metrics.pdb_structure_count,
+                metrics.af_plddt_mean,
+                metrics.fpocket_best_score,
+            );
 
             let n6 = metrics.fpocket_best_score.clamp(0.0, 1.0);
             let n7 = 1.0 / (1.0 + metrics.chembl_inhibitor_count as f64);
-            let n8 = 1.0 / (1.0 + metrics.reactome_escape_pathway_count as f64);
+            let n8 = 1.0
+                / (1.0
+                    + metrics.reactome_escape_pathway_count as f64
+                    + metrics.pathway_redundancy.max(0.0));
 
             let mut penalty = 0.0;
             if metrics.chembl_inhibitor_count > 50 {
@@ -256,6 +477,16 @@ impl PrioritizationEngine {
     }
 }
 
+/// Name of the hard-exclusion constraint that removes a target from every
+/// shortlist regardless of score, if one applies.
+pub fn hard_exclusion_constraint(
+    penalty_inputs: &PenaltyInputs,
+    novelty_score: f64,
+) -> Option<&'static str> {
+    (penalty_inputs.chembl_inhibitor_count > 50 && novelty_score < 0.20)
+        .then_some("saturated_chemical_matter")
+}
+
 pub fn determine_shortlist_tier(
     score_adjusted: f64,
     mutation_freq_raw: Option<f64>,
@@ -264,7 +495,7 @@ pub fn determine_shortlist_tier(
     novelty_score: f64,
 ) -> ShortlistTier {
     // Hard exclusion: saturated + low novelty
-    if penalty_inputs.chembl_inhibitor_count > 50 && novelty_score < 0.20 {
+    if hard_exclusion_constraint(penalty_inputs, novelty_score).is_some() {
         return ShortlistTier::Excluded;
     }
 
@@ -287,30 +518,147 @@ pub fn determine_shortlist_tier(
 /// Compute CRISPR dependency component score from DepMap data.
 ///
 /// This function:
-/// 1. Queries DepMap for CERES scores
+/// 1. Queries DepMap for the selective CERES delta, falling back to mean CERES
 /// 2. Normalizes the score using `normalise_ceres()`
 /// 3. Returns the normalized component score (0.0–1.0)
 ///
 /// Returns None if no DepMap data is available for this gene-cancer pair.
 pub fn compute_crispr_component(
     gene: &str,
-    cancer_type: &str,
+    cancer: CancerType,
     depmap: &dyn DepMapProvider,
 ) -> Option<f64> {
-    // Try mean CERES first
-    let ceres = depmap.get_mean_ceres(gene, cancer_type)?;
+    let ceres = crispr_dependency_ceres(gene, cancer, depmap)?;
 
     // Normalize: more essential (more negative) → higher score
     Some(normalise_ceres(ceres))
 }
 
+/// CRISPR component together with its cell-line-level uncertainty.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct CrisprComponent {
+    /// Score from [`compute_crispr_component`].
+    pub score: f64,
+    /// `score` shrunk towards the prior by the number of cell lines, or
+    /// `score` itself when the provider has no per-cell-line data.
+    pub adjusted_score: f64,
+    pub ci: Option<DependencyCi>,
+}
+
+/// [`compute_crispr_component`] plus a bootstrap interval over the
+/// provider's per-cell-line scores.
+pub fn compute_crispr_component_with_ci(
+    gene: &str,
+    cancer: CancerType,
+    depmap: &dyn DepMapProvider,
+) -> Option<CrisprComponent> {
+    let score = compute_crispr_component(gene, cancer, depmap)?;
+    let ci = bootstrap_dependency_ci(
+        &depmap.get_cell_line_ceres(gene, cancer),
+        DEFAULT_BOOTSTRAP_RESAMPLES,
+        DEFAULT_BOOTSTRAP_SEED,
+    );
+    let adjusted_score = ci.map_or(score, |ci| shrink_dependency(score, ci.num_cell_lines));
+    Some(CrisprComponent {
+        score,
+        adjusted_score,
+        ci,
+    })
+}
+
+/// CRISPR component for the focus mutation: dependency in mutant cell lines
+/// of the cancer type relative to wild-type lines, normalised like CERES.
+///
+/// For `focus_mutation = "KRAS_G12D"` this scores how much more essential
+/// `gene` is in KRAS G12D lines than in KRAS wild-type lines. Returns None
+/// when the provider has no mutation calls or either group is empty.
+pub fn compute_mutation_crispr_component(
+    gene: &str,
+    cancer: CancerType,
+    focus_mutation: &str,
+    depmap: &dyn DepMapProvider,
+) -> Option<f64> {
+    let delta = depmap.get_mutant_vs_wt_delta(gene, focus_mutation, cancer)?;
+    Some(normalise_ceres(delta))
+}
+
+/// CERES-scale value behind the CRISPR component.
+///
+/// Prefers the selective delta so a gene that is equally essential in every
+/// lineage (e.g. ribosomal genes) does not outrank one that is essential only
+/// in the requested cancer type.
+fn crispr_dependency_ceres(
+    gene: &str,
+    cancer: CancerType,
+    depmap: &dyn DepMapProvider,
+) -> Option<f64> {
+    depmap
+        .get_selective_delta(gene, cancer)
+        .or_else(|| depmap.get_mean_ceres(gene, cancer))
+}
+
+/// Mutation frequency component from TCGA: the fraction of cohort patients
+/// carrying `focus_mutation` when it names this gene and a protein change,
+/// otherwise any non-silent mutation in the gene.
+pub fn compute_mutation_component(
+    gene: &str,
+    cancer: CancerType,
+    focus_mutation: Option<&str>,
+    tcga: &dyn TcgaProvider,
+) -> Option<f64> {
+    let mut freq = tcga.get_mutation_frequency(gene, cancer)?;
+    if let Some(focus) = focus_mutation {
+        freq = freq.for_focus_mutation(gene, focus);
+    }
+    Some(freq.frequency.clamp(0.0, 1.0))
+}
+
+/// Factor applied to the mutation component when the focus mutation is a
+/// known driver in the COSMIC Cancer Mutation Census.
+pub const KNOWN_DRIVER_MUTATION_BOOST: f64 = 1.5;
+
+/// Boost `mutation_freq` by [`KNOWN_DRIVER_MUTATION_BOOST`] (capped at 1.0)
+/// when `focus_mutation` names a known driver hotspot of `gene`, so a rare
+/// but established driver is not outscored by passenger-heavy genes.
+pub fn apply_known_driver_boost(
+    mutation_freq: f64,
+    gene: &str,
+    focus_mutation: &str,
+    census: &CosmicCensus,
+) -> f64 {
+    match parse_mutation(focus_mutation) {
+        (focus_gene, Some(change))
+            if focus_gene.eq_ignore_ascii_case(gene.trim())
+                && census.is_known_driver(gene, &change) =>
+        {
+            (mutation_freq * KNOWN_DRIVER_MUTATION_BOOST).clamp(0.0, 1.0)
+        }
+        _ => mutation_freq,
+    }
+}
+
+/// [`compute_mutation_component`] with the known-driver boost from `census`.
+pub fn compute_mutation_component_with_census(
+    gene: &str,
+    cancer: CancerType,
+    focus_mutation: Option<&str>,
+    tcga: &dyn TcgaProvider,
+    census: Option<&CosmicCensus>,
+) -> Option<f64> {
+    let freq = compute_mutation_component(gene, cancer, focus_mutation, tcga)?;
+    Some(match (focus_mutation, census) {
+        (Some(focus), Some(census)) => apply_known_driver_boost(freq, gene, focus, census),
+        _ => freq,
+    })
+}
+
 /// Compute TCGA survival correlation component score.
 pub fn compute_survival_component(
     gene: &str,
-    cancer_type: &str,
+    cancer: CancerType,
     tcga: &dyn TcgaProvider,
 ) -> Option<f64> {
-    let correlation = tcga.get_survival_correlation(gene, cancer_type)?;
+    let correlation = tcga.get_survival_correlation(gene, cancer)?;
     // the simulation returns correlation -1.0 to 1.0, map to 0.0-1.0
     Some((correlation + 1.0) / 2.0)
 }
@@ -348,7 +696,7 @@ pub fn compute_expression_component(
 /// for the automated components.
 pub fn compute_component_scores_with_providers(
     gene: &str,
-    cancer_type: &str,
+    cancer: CancerType,
     tumour_tpm: Option<f64>,
     depmap: &dyn DepMapProvider,
     tcga: &dyn TcgaProvider,
@@ -360,8 +708,12 @@ pub fn compute_component_scores_with_providers(
     pathway_independence: Option<f64>,
     literature_novelty: Option<f64>,
 ) -> ComponentScoresRaw {
-    let crispr_dependency = depmap.get_mean_ceres(gene, cancer_type);
-    let survival_correlation = tcga.get_survival_correlation(gene, cancer_type);
+    let mutation_freq = mutation_freq.or_else(|| {
+        tcga.get_mutation_frequency(gene, cancer)
+            .map(|f| f.frequency)
+    });
+    let crispr_dependency = crispr_dependency_ceres(gene, cancer, depmap);
+    let survival_correlation = tcga.get_survival_correlation(gene, cancer);
 
     let expression_specificity = match tumour_tpm {
         Some(tpm) => compute_expression_component(gene, Some(tpm), gtex),
@@ -381,13 +733,267 @@ pub fn compute_component_scores_with_providers(
     }
 }
 
+// ── Batch ranking ───────────────────────────────────────────────────────────
+
+/// Rows written per `upsert_batch` call during a batch run.
+const RANK_WRITE_BATCH: usize = 200;
+
+/// Progress of a [`rank_all_targets`] run, reported after each gene.
+#[derive(Debug, Clone, Serialize)]
+pub struct RankProgress {
+    pub gene: String,
+    pub scored: usize,
+    pub total: usize,
+    pub confidence_adjusted_score: f64,
+    pub tier: &'static str,
+}
+
+/// Outcome of a [`rank_all_targets`] run.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RankRunSummary {
+    pub cancer_type: String,
+    pub genes_scored: usize,
+    pub primary_count: usize,
+    pub secondary_count: usize,
+    pub excluded_count: usize,
+}
+
+/// Score every gene entity in the KG for `cancer_type` and persist the
+/// results to `target_scores`. `cancer_type` is resolved through
+/// [`CancerType::parse`] and stored as its OncoTree code.
+///
+/// Components come from cached provider signals only, so a run never waits
+/// on live APIs; components without a cached signal score 0.0 and are left
+/// out of the persisted `components_normed` JSON. The confidence term is the
+/// mean confidence of the gene's KG facts, with contradicted facts
+/// down-weighted or dropped as in [`scoring_confidence`]. `on_progress` is
+/// called after each gene is scored.
+pub async fn rank_all_targets(
+    db: Arc<Database>,
+    cancer_type: &str,
+    weights: &WeightVector,
+    mut on_progress: impl FnMut(&RankProgress),
+) -> anyhow::Result<RankRunSummary> {
+    anyhow::ensure!(!cancer_type.trim().is_empty(), "cancer_type is required");
+    let cancer_type = cancer_type.parse::<CancerType>()?.code().to_string();
+
+    let entity_repo = EntityRepository::new(db.clone());
+    let mut genes: Vec<(String, Uuid)> = entity_repo
+        .find_by_type(EntityType::Gene)
+        .await?
+        .into_iter()
+        .map(|e| (e.name.trim().to_uppercase(), e.id))
+        .filter(|(name, _)| !name.is_empty())
+        .collect();
+    genes.sort();
+    genes.dedup_by(|a, b| a.0 == b.0);
+
+    let cancer_id = entity_repo
+        .find_by_name(&cancer_type)
+        .await?
+        .into_iter()
+        .filter(|e| e.entity_type == EntityType::CancerType.to_string())
+        .map(|e| e.id)
+        .min()
+        .unwrap_or_else(Uuid::nil);
+
+    let gene_ids: Vec<Uuid> = genes.iter().map(|(_, id)| *id).collect();
+    let facts = KgFactRepository::new(db.clone())
+        .find_by_subject_ids(&gene_ids, 32)
+        .await?;
+    let fact_ids: Vec<Uuid> = facts.iter().map(|f| f.id).collect();
+    let mut conflict_nets: HashMap<Uuid, Vec<f64>> = HashMap::new();
+    for conflict in KgConflictRepository::new(db.clone())
+        .find_by_fact_ids(&fact_ids)
+        .await?
+    {
+        let net = conflict.net_confidence as f64;
+        for id in [conflict.fact_a_id, conflict.fact_b_id] {
+            conflict_nets.entry(id).or_default().push(net);
+        }
+    }
+    let mut confidence: HashMap<Uuid, (f64, u32)> = HashMap::new();
+    for fact in &facts {
+        let nets = conflict_nets.get(&fact.id).into_iter().flatten().copied();
+        let Some(adjusted) = scoring_confidence(fact.confidence as f64, nets) else {
+            continue;
+        };
+        let entry = confidence.entry(fact.subject_id).or_default();
+        entry.0 += adjusted;
+        entry.1 += 1;
+    }
+
+    let score_repo = TargetScoreRepository::new(db.clone());
+    let mut summary = RankRunSummary {
+        cancer_type: cancer_type.clone(),
+        ..Default::default()
+    };
+    let mut pending = Vec::with_capacity(RANK_WRITE_BATCH);
+    let total = genes.len();
+
+    for (gene, gene_id) in &genes {
+        let providers =
+            lookup_provider_components(db.clone(), gene, Some(&cancer_type), false).await;
+        let mean_confidence = confidence
+            .get(gene_id)
+            .map(|(sum, n)| sum / *n as f64)
+            .unwrap_or(0.0);
+        let scored = score_from_provider_components(&providers, weights, mean_confidence);
+
+        let mut row = DbTargetScore::new(
+            *gene_id,
+            cancer_id,
+            scored.composite,
+            scored.adjusted,
+            scored.penalty,
+            scored.tier.as_str().to_string(),
+        );
+        row.components_raw = serde_json::json!({
+            "gene": gene,
+            "cancer_code": cancer_type,
+            "mutation_freq": providers.mutation_freq,
+            "crispr_ceres": providers.crispr_ceres,
+            "crispr_ci": providers.crispr_ci,
+            "survival_score": providers.survival_score,
+            "expression_score": providers.expression_score,
+            "chembl_inhibitor_count": providers.chembl_inhibitor_count,
+            "mean_confidence": mean_confidence,
+            "sources": providers.sources,
+        })
+        .to_string();
+        row.components_normed = serde_json::to_string(&scored.available)?;
+        pending.push(row);
+
+        match scored.tier {
+            ShortlistTier::Primary => summary.primary_count += 1,
+            ShortlistTier::Secondary => summary.secondary_count += 1,
+            ShortlistTier::Excluded => summary.excluded_count += 1,
+        }
+        summary.genes_scored += 1;
+        on_progress(&RankProgress {
+            gene: gene.clone(),
+            scored: summary.genes_scored,
+            total,
+            confidence_adjusted_score: scored.adjusted,
+            tier: scored.tier.as_str(),
+        });
+
+        if pending.len() >= RANK_WRITE_BATCH {
+            score_repo.upsert_batch(&pending).await?;
+            pending.clear();
+        }
+    }
+    score_repo.upsert_batch(&pending).await?;
+
+    Ok(summary)
+}
+
+/// Composite, penalty and tier for one gene in a batch run.
+struct BatchScore {
+    composite: f64,
+    adjusted: f64,
+    penalty: f64,
+    tier: ShortlistTier,
+    /// Normalised components that had data, keyed by component name.
+    available: BTreeMap<&'static str, f64>,
+}
+
+/// Penalty inputs known from provider signals alone.
+///
+/// Without a structural assessment the structural-void penalty is not
+/// applied; a missing expression signal is not penalised either. The GTEx
+/// score is the tumour/normal ratio capped at 10x.
+pub fn penalty_inputs_from_providers(providers: &ProviderComponents) -> PenaltyInputs {
+    PenaltyInputs {
+        chembl_inhibitor_count: providers.chembl_inhibitor_count.unwrap_or(0),
+        expression_ratio: providers.expression_score.map_or(1.5, |s| s * 10.0),
+        has_pdb: providers.structural.as_ref().is_none_or(|s| s.has_pdb),
+        alphafold_plddt: providers
+            .structural
+            .as_ref()
+            .and_then(|s| s.alphafold_plddt),
+        has_approved_drug: providers
+            .chembl
+            .as_ref()
+            .is_some_and(|c| c.has_approved_drug),
+        phase3_trial_count: providers.trials.as_ref().map_or(0, |t| t.phase3_or_later()),
+    }
+}
+
+fn score_from_provider_components(
+    providers: &ProviderComponents,
+    weights: &WeightVector,
+    mean_confidence: f64,
+) -> BatchScore {
+    let novelty = providers
+        .chembl_inhibitor_count
+        .map(|count| 1.0 / (1.0 + count as f64));
+    let mut available = BTreeMap::new();
+    for (name, value) in [
+        (
+            "mutation_freq",
+            providers.mutation_freq.map(|f| f.clamp(0.0, 1.0)),
+        ),
+        ("crispr_dependency", providers.crispr_dependency()),
+        ("survival_correlation", providers.survival_score),
+        ("expression_specificity", providers.expression_score),
+        (
+            "structural_tractability",
+            providers.structural_tractability(),
+        ),
+        ("pocket_detectability", providers.pocket_detectability()),
+        ("novelty_score", novelty),
+    ] {
+        if let Some(value) = value {
+            available.insert(name, value);
+        }
+    }
+    let get = |name: &str| available.get(name).copied().unwrap_or(0.0);
+
+    let normed = ComponentScoresNormed {
+        mutation_freq: get("mutation_freq"),
+        crispr_dependency: get("crispr_dependency"),
+        survival_correlation: get("survival_correlation"),
+        expression_specificity: get("expression_specificity"),
+        structural_tractability: get("structural_tractability"),
+        pocket_detectability: get("pocket_detectability"),
+        novelty_score: get("novelty_score"),
+        pathway_independence: 0.0,
+        literature_novelty: 0.0,
+    };
+
+    let penalty_inputs = penalty_inputs_from_providers(providers);
+    let penalty = compute_penalty(&penalty_inputs);
+    let (composite, adjusted) = compute_composite_score(&normed, weights, penalty, mean_confidence);
+    let tier = determine_shortlist_tier(
+        adjusted,
+        providers.mutation_freq,
+        normed.structural_tractability,
+        &penalty_inputs,
+        normed.novelty_score,
+    );
+
+    BatchScore {
+        composite,
+        adjusted,
+        penalty,
+        tier,
+        available,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::depmap_provider::MockDepMapProvider;
     use crate::gtex_provider::MockGtexProvider;
+    use crate::providers::tcga::MutationFrequency;
     use crate::tcga_provider::MockTcgaProvider;
 
+    fn cancer(input: &str) -> CancerType {
+        CancerType::parse(input).unwrap()
+    }
+
     #[test]
     fn test_composite_score_range() {
         let normed = ComponentScoresNormed {
@@ -434,7 +1040,7 @@ mod tests {
     fn test_crispr_component_normalized() {
         let provider = MockDepMapProvider::new().with("KRAS", "PAAD", -1.2); // Strongly essential
 
-        let score = compute_crispr_component("KRAS", "PAAD", &provider);
+        let score = compute_crispr_component("KRAS", cancer("PAAD"), &provider);
 
         // -1.2 should normalize to ~0.6 (moderate-high)
         assert!(score.is_some());
@@ -442,11 +1048,40 @@ mod tests {
         assert!(s > 0.5 && s < 0.7, "Expected ~0.6, got {}", s);
     }
 
+    #[test]
+    fn test_crispr_component_prefers_selective_delta() {
+        let provider = MockDepMapProvider::new()
+            .with("RPL11", "PAAD", -1.5)
+            .with_selective("RPL11", "PAAD", 0.0)
+            .with("KRAS", "PAAD", -1.2)
+            .with_selective("KRAS", "PAAD", -0.9);
+
+        let pan_essential = compute_crispr_component("RPL11", cancer("PAAD"), &provider).unwrap();
+        let selective = compute_crispr_component("KRAS", cancer("PAAD"), &provider).unwrap();
+        assert!(selective > pan_essential);
+    }
+
+    #[test]
+    fn test_mutation_crispr_component_uses_mutant_delta() {
+        let provider = MockDepMapProvider::new()
+            .with("KRAS", "PAAD", -1.0)
+            .with_mutant_delta("KRAS", "KRAS_G12D", "PAAD", -0.8);
+
+        let score =
+            compute_mutation_crispr_component("KRAS", cancer("PAAD"), "KRAS_G12D", &provider)
+                .unwrap();
+        assert!((score - 0.4).abs() < 1e-9);
+        assert!(
+            compute_mutation_crispr_component("KRAS", cancer("LUAD"), "KRAS_G12D", &provider)
+                .is_none()
+        );
+    }
+
     #[test]
     fn test_crispr_component_missing_gene() {
         let provider = MockDepMapProvider::new().with("KRAS", "PAAD", -1.0);
 
-        let score = compute_crispr_component("TP53", "PAAD", &provider);
+        let score = compute_crispr_component("TP53", cancer("PAAD"), &provider);
         assert!(score.is_none());
     }
 
@@ -454,7 +1089,7 @@ mod tests {
     fn test_crispr_component_missing_cancer() {
         let provider = MockDepMapProvider::new().with("KRAS", "PAAD", -1.0);
 
-        let score = compute_crispr_component("KRAS", "LUAD", &provider);
+        let score = compute_crispr_component("KRAS", cancer("LUAD"), &provider);
         assert!(score.is_none());
     }
 
@@ -462,10 +1097,71 @@ mod tests {
     fn test_compute_survival_component() {
         let tcga = MockTcgaProvider::new().with("TP53", "BRCA", -0.5); // Better survival -> negative correlation
 
-        let score = compute_survival_component("TP53", "BRCA", &tcga);
+        let score = compute_survival_component("TP53", cancer("BRCA"), &tcga);
         assert!(score.is_some());
         // -0.5 mapped to 0.0-1.0 is (-0.5 + 1.0) / 2.0 = 0.25
         assert_eq!(score.unwrap(), 0.25);
+        // Subtypes share their TCGA cohort; tissues have none.
+        assert_eq!(
+            compute_survival_component("TP53", cancer("breast invasive ductal carcinoma"), &tcga),
+            Some(0.25)
+        );
+        assert!(compute_survival_component("TP53", cancer("BREAST"), &tcga).is_none());
+    }
+
+    #[test]
+    fn test_compute_mutation_component_matches_focus_hotspot() {
+        let freq = MutationFrequency {
+            frequency: 0.9,
+            mutated: 90,
+            total: 100,
+            hotspots: BTreeMap::from([("G12D".to_string(), 40), ("G12V".to_string(), 30)]),
+        };
+        let tcga = MockTcgaProvider::new().with_mutation_frequency("KRAS", "PAAD", &freq);
+
+        let any = compute_mutation_component("KRAS", cancer("PAAD"), None, &tcga).unwrap();
+        assert!((any - 0.9).abs() < 1e-12);
+        let g12d =
+            compute_mutation_component("KRAS", cancer("PAAD"), Some("KRAS_G12D"), &tcga).unwrap();
+        assert!((g12d - 0.4).abs() < 1e-12);
+        // A focus mutation on another gene falls back to any mutation.
+        let other =
+            compute_mutation_component("KRAS", cancer("PAAD"), Some("TP53_R175H"), &tcga).unwrap();
+        assert!((other - 0.9).abs() < 1e-12);
+        assert!(compute_mutation_component("KRAS", cancer("LUAD"), None, &tcga).is_none());
+    }
+
+    #[test]
+    fn test_known_driver_boosts_focus_mutation() {
+        let census = CosmicCensus::parse(
+            "GENE_NAME\tMutation AA\tCOSMIC_SAMPLE_MUTATED\tCGC_TIER\tMUTATION_SIGNIFICANCE_TIER\n\
+             KRAS\tp.G12D\t16523\t1\t1\n\
+             KRAS\tp.G12V\t40\t1\tOther\n",
+        )
+        .unwrap();
+        let freq = MutationFrequency {
+            frequency: 0.9,
+            mutated: 90,
+            total: 100,
+            hotspots: BTreeMap::from([("G12D".to_string(), 40), ("G12V".to_string(), 30)]),
+        };
+        let tcga = MockTcgaProvider::new().with_mutation_frequency("KRAS", "PAAD", &freq);
+        let component = |focus: Option<&str>, census: Option<&CosmicCensus>| {
+            compute_mutation_component_with_census("KRAS", cancer("PAAD"), focus, &tcga, census)
+                .unwrap()
+        };
+
+        assert!((component(Some("KRAS_G12D"), Some(&census)) - 0.6).abs() < 1e-12);
+        assert!((component(Some("KRAS_G12D"), None) - 0.4).abs() < 1e-12);
+        // Not a driver, another gene's driver, or no focus: unchanged.
+        assert!((component(Some("KRAS_G12V"), Some(&census)) - 0.3).abs() < 1e-12);
+        assert!((component(Some("TP53_R175H"), Some(&census)) - 0.9).abs() < 1e-12);
+        assert!((component(None, Some(&census)) - 0.9).abs() < 1e-12);
+        // Capped at 1.0.
+        assert_eq!(
+            apply_known_driver_boost(0.8, "KRAS", "KRAS G12D", &census),
+            1.0
+        );
     }
 
     #[test]
@@ -483,4 +1179,201 @@ mod tests {
         let score_mid = compute_expression_component("HER2", Some(17.5), &gtex);
         assert_eq!(score_mid.unwrap(), 0.5); // 5x ratio -> 0.5
     }
+
+    #[test]
+    fn test_batch_score_keeps_only_available_components() {
+        let providers = ProviderComponents {
+            crispr_ceres: Some(-1.2),
+            chembl_inhibitor_count: Some(60),
+            ..Default::default()
+        };
+        let scored = score_from_provider_components(&providers, &WeightVector::default(), 0.8);
+
+        assert_eq!(
+            scored.available.keys().copied().collect::<Vec<_>>(),
+            vec!["crispr_dependency", "novelty_score"]
+        );
+        // Saturated chemistry: inhibitor penalty applies and the target is excluded.
+        assert!((scored.penalty - 0.15).abs() < 1e-9);
+        assert_eq!(scored.tier, ShortlistTier::Excluded);
+        assert!(scored.adjusted <= scored.composite);
+    }
+
+    #[test]
+    fn test_phase3_trials_add_late_stage_penalty() {
+        let mut trials = ferrumyx_ingestion::sources::clinicaltrials::TrialSummary::default();
+        trials.by_phase.insert("PHASE2".to_string(), 4);
+        let mut providers = ProviderComponents {
+            trials: Some(trials.clone()),
+            ..Default::default()
+        };
+        assert!(penalty_reasons(&penalty_inputs_from_providers(&providers)).is_empty());
+
+        trials.by_phase.insert("PHASE3".to_string(), 2);
+        providers.trials = Some(trials);
+        let reasons = penalty_reasons(&penalty_inputs_from_providers(&providers));
+        assert_eq!(reasons.len(), 1);
+        assert_eq!(reasons[0].rule, "late_stage_trials");
+        assert!((reasons[0].amount - 0.10).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_chembl_target_summary_adds_approved_drug_penalty() {
+        let providers = ProviderComponents {
+            chembl_inhibitor_count: Some(12),
+            chembl: Some(ferrumyx_ingestion::sources::ChemblTargetSummary {
+                target_chembl_id: "CHEMBL203".to_string(),
+                uniprot_id: Some("P00533".to_string()),
+                activity_threshold_nm: 1000.0,
+                bioactive_compound_count: 12,
+                has_approved_drug: true,
+                top_compounds: vec!["CHEMBL939".to_string()],
+            }),
+            ..Default::default()
+        };
+        let rules: Vec<&str> = penalty_reasons(&penalty_inputs_from_providers(&providers))
+            .iter()
+            .map(|r| r.rule)
+            .collect();
+        assert_eq!(rules, vec!["approved_drug"]);
+    }
+
+    #[test]
+    fn test_structural_assessment_feeds_components_and_penalty() {
+        let mut assessment = ferrumyx_molecules::tractability::StructuralAssessment {
+            uniprot_id: "P01116".to_string(),
+            has_pdb: true,
+            pdb_count: 4,
+            best_pdb_id: Some("6OIM".to_string()),
+            best_resolution: Some(1.9),
+            alphafold_plddt: None,
+            best_pocket_score: Some(0.8),
+            pocket_count: Some(3),
+        };
+        let mut providers = ProviderComponents {
+            structural: Some(assessment.clone()),
+            ..Default::default()
+        };
+        let scored = score_from_provider_components(&providers, &WeightVector::default(), 0.8);
+        assert!((scored.available["structural_tractability"] - (0.32 + 0.20)).abs() < 1e-9);
+        assert!((scored.available["pocket_detectability"] - 0.8).abs() < 1e-9);
+        assert!(penalty_reasons(&penalty_inputs_from_providers(&providers)).is_empty());
+
+        assessment.has_pdb = false;
+        assessment.pdb_count = 0;
+        assessment.alphafold_plddt = Some(40.0);
+        providers.structural = Some(assessment);
+        let rules: Vec<&str> = penalty_reasons(&penalty_inputs_from_providers(&providers))
+            .iter()
+            .map(|r| r.rule)
+            .collect();
+        assert_eq!(rules, vec!["structural_void"]);
+    }
+
+    #[test]
+    fn test_penalty_reasons_match_penalty_total() {
+        let inputs = PenaltyInputs {
+            chembl_inhibitor_count: 64,
+            expression_ratio: 1.2,
+            has_pdb: false,
+            alphafold_plddt: Some(42.0),
+            has_approved_drug: false,
+            phase3_trial_count: 0,
+        };
+        let reasons = penalty_reasons(&inputs);
+        let rules: Vec<&str> = reasons.iter().map(|r| r.rule).collect();
+        assert_eq!(
+            rules,
+            vec![
+                "inhibitor_saturation",
+                "low_expression_specificity",
+                "structural_void"
+            ]
+        );
+        assert!((compute_penalty(&inputs) - 0.33).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_explained_score_matches_composite() {
+        let normed = ComponentScoresNormed {
+            mutation_freq: 0.9,
+            crispr_dependency: 0.6,
+            survival_correlation: 0.0,
+            expression_specificity: 0.4,
+            structural_tractability: 0.5,
+            pocket_detectability: 0.3,
+            novelty_score: 0.2,
+            pathway_independence: 0.5,
+            literature_novelty: 0.1,
+        };
+        let weights = WeightVector::default();
+        let penalties = penalty_reasons(&PenaltyInputs {
+            chembl_inhibitor_count: 80,
+            expression_ratio: 4.0,
+            has_pdb: true,
+            alphafold_plddt: None,
+            has_approved_drug: false,
+            phase3_trial_count: 0,
+        });
+        let explained = compute_composite_score_explained(None, &normed, &weights, penalties, 0.7);
+        let (composite, adjusted) = compute_composite_score(&normed, &weights, 0.15, 0.7);
+
+        assert!((explained.composite_score - composite).abs() < 1e-12);
+        assert!((explained.confidence_adjusted_score - adjusted).abs() < 1e-12);
+        let total: f64 = explained
+            .components
+            .iter()
+            .map(|c| c.percent_of_total)
+            .sum();
+        assert!((total - 100.0).abs() < 1e-9);
+        assert_eq!(explained.components[0].component, "mutation_freq");
+        let rationale = explained.rationale();
+        assert!(
+            rationale.starts_with("Driven by mutation_freq"),
+            "{rationale}"
+        );
+        assert!(rationale.contains("chembl_inhibitor_count 80 > 50: -0.15"));
+    }
+
+    #[test]
+    fn test_crispr_component_ci_shrinks_few_cell_lines() {
+        let many: Vec<f64> = (0..50).map(|i| -1.3 + 0.6 * i as f64 / 49.0).collect();
+        let provider = MockDepMapProvider::new()
+            .with_cell_lines("KRAS", "PAAD", &[-1.2, -0.8])
+            .with_cell_lines("KRAS", "LUAD", &many)
+            .with("MYC", "PAAD", -1.0);
+
+        let few = compute_crispr_component_with_ci("KRAS", cancer("PAAD"), &provider).unwrap();
+        let lots = compute_crispr_component_with_ci("KRAS", cancer("LUAD"), &provider).unwrap();
+        assert!((few.score - lots.score).abs() < 1e-9);
+        assert!(few.adjusted_score < lots.adjusted_score);
+        assert!(few.ci.unwrap().width() > lots.ci.unwrap().width());
+
+        // Aggregate-only data keeps the unshrunk score.
+        let aggregate = compute_crispr_component_with_ci("MYC", cancer("PAAD"), &provider).unwrap();
+        assert!(aggregate.ci.is_none());
+        assert_eq!(aggregate.adjusted_score, aggregate.score);
+    }
+
+    #[test]
+    fn test_pathway_redundancy_lowers_pathway_independence() {
+        let (alone, redundant) = (Uuid::new_v4(), Uuid::new_v4());
+        let metrics = TargetMetrics {
+            reactome_escape_pathway_count: 1,
+            ..Default::default()
+        };
+        let cohort = [
+            (alone, metrics.clone()),
+            (
+                redundant,
+                TargetMetrics {
+                    pathway_redundancy: 2.0,
+                    ..metrics
+                },
+            ),
+        ];
+        let scores = PrioritizationEngine::calculate_scores(&cohort);
+        assert!((scores[&alone].n8_pathway_independence - 0.5).abs() < 1e-9);
+        assert!((scores[&redundant].n8_pathway_independence - 0.25).abs() < 1e-9);
+    }
 }
@@ -0,0 +1,242 @@
+//! Weight sensitivity of a composite score.
+//!
+//! Each weight is nudged up and down by a fixed amount while the other
+//! weights are rescaled to keep the vector summing to 1.0, and the composite
+//! is recomputed. A target whose composite barely moves is robust to the
+//! choice of weights; one that swings widely only looks good under one
+//! weighting.
+//!
+//! Missing components are not zero evidence: their weight is redistributed
+//! over the components that have data before anything is perturbed, and they
+//! are never perturbed themselves.
+
+use crate::scorer::{compute_composite_score, ComponentScoresNormed};
+use crate::weights::WeightVector;
+use serde::Serialize;
+
+/// Composite range when one component's weight is perturbed.
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentSensitivity {
+    pub component: &'static str,
+    /// Weight after redistributing the weight of missing components.
+    pub effective_weight: f64,
+    pub min_composite: f64,
+    pub max_composite: f64,
+}
+
+/// Result of [`sensitivity_analysis`].
+#[derive(Debug, Clone, Serialize)]
+pub struct SensitivityReport {
+    pub perturbation: f64,
+    /// Composite under the (redistributed) base weights.
+    pub base_composite: f64,
+    pub min_composite: f64,
+    pub max_composite: f64,
+    /// `1 − (max − min) / base`, clamped to [0, 1]; 1.0 means no
+    /// perturbation moved the composite.
+    pub stability_index: f64,
+    pub components: Vec<ComponentSensitivity>,
+    /// Components without data, whose weight was redistributed.
+    pub missing_components: Vec<&'static str>,
+}
+
+/// Sensitivity of a fully populated score vector to ±`perturbation` on each
+/// weight. See [`sensitivity_analysis_with_missing`] when some components
+/// have no data.
+pub fn sensitivity_analysis(
+    scores: &ComponentScoresNormed,
+    base_weights: &WeightVector,
+    perturbation: f64,
+) -> SensitivityReport {
+    sensitivity_analysis_with_missing(&scores.as_array().map(Some), base_weights, perturbation)
+}
+
+/// Sensitivity of `scores` (in [`WeightVector::NAMES`] order, `None` for
+/// components without data) to ±`perturbation` on each available weight.
+///
+/// `perturbation` is an absolute change in weight (0.05 moves a 0.20 weight
+/// to 0.15 and 0.25); perturbed weights are clamped to [0, 1]. Penalty and
+/// KG confidence are left out so only the weighting moves the composite.
+pub fn sensitivity_analysis_with_missing(
+    scores: &[Option<f64>; 9],
+    base_weights: &WeightVector,
+    perturbation: f64,
+) -> SensitivityReport {
+    let perturbation = perturbation.abs();
+    let values = scores.map(|s| s.unwrap_or(0.0));
+    let available = scores.map(|s| s.is_some());
+    let missing_components = WeightVector::NAMES
+        .iter()
+        .zip(available)
+        .filter(|(_, has)| !has)
+        .map(|(name, _)| *name)
+        .collect();
+
+    let mut weights = base_weights.as_array();
+    for (w, has) in weights.iter_mut().zip(available) {
+        if !has {
+            *w = 0.0;
+        }
+    }
+    let available_sum: f64 = weights.iter().sum();
+    if available_sum > 0.0 {
+        weights.iter_mut().for_each(|w| *w /= available_sum);
+    }
+
+    let base_composite = composite(&values, &weights);
+    let mut components = Vec::new();
+    let mut min_composite = base_composite;
+    let mut max_composite = base_composite;
+
+    if available_sum > 0.0 {
+        for i in (0..9).filter(|i| available[*i]) {
+            let mut lo = base_composite;
+            let mut hi = base_composite;
+            for delta in [-perturbation, perturbation] {
+                let Some(perturbed) = perturb(&weights, &available, i, delta) else {
+                    continue;
+                };
+                let c = composite(&values, &perturbed);
+                lo = lo.min(c);
+                hi = hi.max(c);
+            }
+            min_composite = min_composite.min(lo);
+            max_composite = max_composite.max(hi);
+            components.push(ComponentSensitivity {
+                component: WeightVector::NAMES[i],
+                effective_weight: weights[i],
+                min_composite: lo,
+                max_composite: hi,
+            });
+        }
+    }
+
+    let spread = max_composite - min_composite;
+    let stability_index = if base_composite > 0.0 {
+        (1.0 - spread / base_composite).clamp(0.0, 1.0)
+    } else if spread == 0.0 {
+        1.0
+    } else {
+        0.0
+    };
+
+    SensitivityReport {
+        perturbation,
+        base_composite,
+        min_composite,
+        max_composite,
+        stability_index,
+        components,
+        missing_components,
+    }
+}
+
+/// Move weight `i` by `delta` and rescale the other available weights so
+/// the vector still sums to 1.0. `None` when there is nothing to rescale.
+fn perturb(weights: &[f64; 9], available: &[bool; 9], i: usize, delta: f64) -> Option<[f64; 9]> {
+    let target = (weights[i] + delta).clamp(0.0, 1.0);
+    let rest: f64 = (0..9)
+        .filter(|j| *j != i && available[*j])
+        .map(|j| weights[j])
+        .sum();
+    if rest <= 0.0 {
+        return None;
+    }
+    let scale = (1.0 - target) / rest;
+    let mut out = *weights;
+    for (j, w) in out.iter_mut().enumerate() {
+        *w = if j == i {
+            target
+        } else if available[j] {
+            *w * scale
+        } else {
+            0.0
+        };
+    }
+    Some(out)
+}
+
+fn composite(values: &[f64; 9], weights: &[f64; 9]) -> f64 {
+    let normed = ComponentScoresNormed {
+        mutation_freq: values[0],
+        crispr_dependency: values[1],
+        survival_correlation: values[2],
+        expression_specificity: values[3],
+        structural_tractability: values[4],
+        pocket_detectability: values[5],
+        novelty_score: values[6],
+        pathway_independence: values[7],
+        literature_novelty: values[8],
+    };
+    let weights = WeightVector {
+        mutation_freq: weights[0],
+        crispr_dependency: weights[1],
+        survival_correlation: weights[2],
+        expression_specificity: weights[3],
+        structural_tractability: weights[4],
+        pocket_detectability: weights[5],
+        novelty_score: weights[6],
+        pathway_independence: weights[7],
+        literature_novelty: weights[8],
+    };
+    compute_composite_score(&normed, &weights, 0.0, 1.0).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uniform(value: f64) -> ComponentScoresNormed {
+        ComponentScoresNormed {
+            mutation_freq: value,
+            crispr_dependency: value,
+            survival_correlation: value,
+            expression_specificity: value,
+            structural_tractability: value,
+            pocket_detectability: value,
+            novelty_score: value,
+            pathway_independence: value,
+            literature_novelty: value,
+        }
+    }
+
+    #[test]
+    fn uniform_scores_are_fully_stable() {
+        let report = sensitivity_analysis(&uniform(0.6), &WeightVector::default(), 0.05);
+        assert_eq!(report.components.len(), 9);
+        assert!((report.max_composite - report.min_composite).abs() < 1e-12);
+        assert!((report.stability_index - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn lopsided_scores_move_with_their_weight() {
+        let mut scores = uniform(0.1);
+        scores.mutation_freq = 0.95;
+        let report = sensitivity_analysis(&scores, &WeightVector::default(), 0.10);
+        let mutation = &report.components[0];
+        assert_eq!(mutation.component, "mutation_freq");
+        assert!(mutation.max_composite > report.base_composite);
+        assert!(mutation.min_composite < report.base_composite);
+        assert!(report.stability_index < 1.0);
+    }
+
+    #[test]
+    fn missing_components_redistribute_weight() {
+        let mut scores = [Some(0.8); 9];
+        scores[1] = None;
+        scores[4] = None;
+        let report = sensitivity_analysis_with_missing(&scores, &WeightVector::default(), 0.05);
+
+        assert_eq!(
+            report.missing_components,
+            vec!["crispr_dependency", "structural_tractability"]
+        );
+        assert_eq!(report.components.len(), 7);
+        let total: f64 = report.components.iter().map(|c| c.effective_weight).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        // The remaining components share the weight, so the composite matches
+        // the fully populated vector instead of dropping by the missing share.
+        let full = sensitivity_analysis(&uniform(0.8), &WeightVector::default(), 0.05);
+        assert!((report.base_composite - full.base_composite).abs() < 1e-9);
+    }
+}
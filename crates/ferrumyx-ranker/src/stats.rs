@@ -0,0 +1,164 @@
+//! Uncertainty of the CRISPR dependency component.
+//!
+//! A mean CERES over 4 cell lines is far less certain than one over 60.
+//! [`bootstrap_dependency_ci`] resamples the per-cell-line scores to put a
+//! 95% interval on the normalised dependency, and [`shrink_dependency`]
+//! pulls a component backed by few cell lines towards the non-essential
+//! prior before it enters the composite.
+
+use crate::normalise::normalise_ceres;
+use serde::Serialize;
+
+/// Bootstrap resamples used by callers without their own budget.
+pub const DEFAULT_BOOTSTRAP_RESAMPLES: usize = 1_000;
+
+/// Fixed seed so an interval is reproducible for the same scores.
+pub const DEFAULT_BOOTSTRAP_SEED: u64 = 0x5eed_cafe;
+
+/// Pseudo cell lines of prior weight in [`shrink_dependency`].
+const PRIOR_CELL_LINES: f64 = 3.0;
+
+/// Normalised dependency of a typical, non-essential gene (CERES 0.0).
+const PRIOR_DEPENDENCY: f64 = 0.0;
+
+/// Spread of normalised dependencies between cell lines assumed when there
+/// are too few lines for the bootstrap to see it. Sets the minimum
+/// half-width `1.96 × PRIOR_SD / √n`.
+const PRIOR_SD: f64 = 0.25;
+
+/// 95% interval for the normalised CRISPR dependency of one gene.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct DependencyCi {
+    /// Normalised mean CERES over all cell lines.
+    pub estimate: f64,
+    pub lo: f64,
+    pub hi: f64,
+    pub num_cell_lines: usize,
+}
+
+impl DependencyCi {
+    pub fn width(&self) -> f64 {
+        self.hi - self.lo
+    }
+
+    /// [`shrink_dependency`] applied to the point estimate.
+    pub fn shrunk_estimate(&self) -> f64 {
+        shrink_dependency(self.estimate, self.num_cell_lines)
+    }
+}
+
+/// Percentile-bootstrap 95% interval on `normalise_ceres(mean(ceres))`.
+///
+/// The percentile interval collapses for one or two cell lines, so it is
+/// widened to at least the prior half-width for `n` lines and clamped to
+/// [0, 1]. Returns None for an empty slice.
+pub fn bootstrap_dependency_ci(ceres: &[f64], resamples: usize, seed: u64) -> Option<DependencyCi> {
+    let n = ceres.len();
+    if n == 0 {
+        return None;
+    }
+    let estimate = normalise_ceres(ceres.iter().sum::<f64>() / n as f64);
+
+    let mut rng = SplitMix64(seed);
+    let mut means: Vec<f64> = (0..resamples.max(1))
+        .map(|_| {
+            let sum: f64 = (0..n).map(|_| ceres[rng.below(n)]).sum();
+            normalise_ceres(sum / n as f64)
+        })
+        .collect();
+    means.sort_by(f64::total_cmp);
+    let percentile = |q: f64| means[((means.len() - 1) as f64 * q).round() as usize];
+
+    let half_width = 1.96 * PRIOR_SD / (n as f64).sqrt();
+    Some(DependencyCi {
+        estimate,
+        lo: percentile(0.025).min(estimate - half_width).max(0.0),
+        hi: percentile(0.975).max(estimate + half_width).min(1.0),
+        num_cell_lines: n,
+    })
+}
+
+/// Pull a normalised dependency backed by `num_cell_lines` lines towards
+/// the non-essential prior: `(n·x + k·prior) / (n + k)` with k = 3.
+pub fn shrink_dependency(normalised: f64, num_cell_lines: usize) -> f64 {
+    let n = num_cell_lines as f64;
+    (n * normalised + PRIOR_CELL_LINES * PRIOR_DEPENDENCY) / (n + PRIOR_CELL_LINES)
+}
+
+/// Small deterministic generator; resampling does not need more.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `n` CERES scores spread evenly over -1.0 ± 0.3.
+    fn scores(n: usize) -> Vec<f64> {
+        (0..n)
+            .map(|i| {
+                let t = if n == 1 {
+                    0.5
+                } else {
+                    i as f64 / (n - 1) as f64
+                };
+                -1.3 + 0.6 * t
+            })
+            .collect()
+    }
+
+    fn ci(n: usize) -> DependencyCi {
+        bootstrap_dependency_ci(
+            &scores(n),
+            DEFAULT_BOOTSTRAP_RESAMPLES,
+            DEFAULT_BOOTSTRAP_SEED,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn single_cell_line_gets_a_wide_interval() {
+        let one = ci(1);
+        assert_eq!(one.num_cell_lines, 1);
+        assert!((one.estimate - 0.5).abs() < 1e-9);
+        assert!(one.lo <= one.estimate && one.estimate <= one.hi);
+        assert!(one.width() > 0.9, "width {}", one.width());
+    }
+
+    #[test]
+    fn interval_narrows_as_cell_lines_increase() {
+        let (one, two, fifty) = (ci(1), ci(2), ci(50));
+        assert!(two.lo <= two.estimate && two.estimate <= two.hi);
+        assert!(fifty.lo <= fifty.estimate && fifty.estimate <= fifty.hi);
+        assert!(one.width() > two.width());
+        assert!(two.width() > fifty.width());
+        assert!(fifty.width() < 0.2, "width {}", fifty.width());
+    }
+
+    #[test]
+    fn bootstrap_is_reproducible_and_empty_input_is_none() {
+        assert_eq!(ci(7), ci(7));
+        assert!(bootstrap_dependency_ci(&[], 100, 1).is_none());
+    }
+
+    #[test]
+    fn shrinkage_fades_with_more_cell_lines() {
+        let few = shrink_dependency(0.8, 4);
+        let many = shrink_dependency(0.8, 60);
+        assert!(few < many && many < 0.8);
+        assert!((shrink_dependency(0.8, 0) - PRIOR_DEPENDENCY).abs() < 1e-12);
+    }
+}
@@ -1,21 +1,41 @@
-//! Trait for TCGA survival correlation data access.
+//! Trait for TCGA survival correlation and mutation frequency data access.
+//!
+//! Cancer types without a TCGA cohort have no data.
+
+use std::sync::Arc;
+
+use ferrumyx_common::CancerType;
+
+use crate::providers::tcga::{MutationFrequency, TcgaMutationIndex};
 
 /// Trait for accessing TCGA survival correlations.
 pub trait TcgaProvider: Send + Sync {
     /// Get survival correlation score for gene in a cancer type.
-    fn get_survival_correlation(&self, gene_symbol: &str, cancer_type: &str) -> Option<f64>;
+    fn get_survival_correlation(&self, gene_symbol: &str, cancer: CancerType) -> Option<f64>;
+
+    /// Fraction of cohort patients with a non-silent mutation in the gene.
+    fn get_mutation_frequency(
+        &self,
+        _gene_symbol: &str,
+        _cancer: CancerType,
+    ) -> Option<MutationFrequency> {
+        None
+    }
 }
 
 // ── Mock Implementation for Testing ────────────────────────────────────────
 
+/// Mock provider keyed by TCGA cohort (e.g. "PAAD").
 pub struct MockTcgaProvider {
     data: std::collections::HashMap<(String, String), f64>,
+    mutations: TcgaMutationIndex,
 }
 
 impl MockTcgaProvider {
     pub fn new() -> Self {
         Self {
             data: std::collections::HashMap::new(),
+            mutations: TcgaMutationIndex::default(),
         }
     }
 
@@ -24,6 +44,17 @@ impl MockTcgaProvider {
             .insert((gene.to_string(), cancer_type.to_string()), correlation);
         self
     }
+
+    /// Add a mutation frequency for a gene-cohort pair.
+    pub fn with_mutation_frequency(
+        mut self,
+        gene: &str,
+        cancer_type: &str,
+        freq: &MutationFrequency,
+    ) -> Self {
+        self.mutations.insert(gene, cancer_type, freq);
+        self
+    }
 }
 
 impl Default for MockTcgaProvider {
@@ -33,35 +64,65 @@ impl Default for MockTcgaProvider {
 }
 
 impl TcgaProvider for MockTcgaProvider {
-    fn get_survival_correlation(&self, gene_symbol: &str, cancer_type: &str) -> Option<f64> {
+    fn get_survival_correlation(&self, gene_symbol: &str, cancer: CancerType) -> Option<f64> {
         self.data
-            .get(&(gene_symbol.to_string(), cancer_type.to_string()))
+            .get(&(gene_symbol.to_string(), cancer.tcga_cohort()?.to_string()))
             .copied()
     }
+
+    fn get_mutation_frequency(
+        &self,
+        gene_symbol: &str,
+        cancer: CancerType,
+    ) -> Option<MutationFrequency> {
+        self.mutations
+            .mutation_frequency(gene_symbol, cancer.tcga_cohort()?)
+    }
 }
 
 // ── Adapter for TcgaClient ─────────────────────────────────────────────────
 
 pub struct TcgaClientAdapter {
     client: ferrumyx_ingestion::sources::TcgaClient,
+    mutations: Option<Arc<TcgaMutationIndex>>,
 }
 
 impl TcgaClientAdapter {
     pub fn new(client: ferrumyx_ingestion::sources::TcgaClient) -> Self {
-        Self { client }
+        Self {
+            client,
+            mutations: None,
+        }
+    }
+
+    /// Serve mutation frequencies from a loaded MAF index.
+    pub fn with_mutation_index(mut self, index: Arc<TcgaMutationIndex>) -> Self {
+        self.mutations = Some(index);
+        self
     }
 }
 
 impl TcgaProvider for TcgaClientAdapter {
-    fn get_survival_correlation(&self, gene_symbol: &str, cancer_type: &str) -> Option<f64> {
+    fn get_survival_correlation(&self, gene_symbol: &str, cancer: CancerType) -> Option<f64> {
+        let project = format!("TCGA-{}", cancer.tcga_cohort()?);
         tokio::task::block_in_place(|| {
             tokio::runtime::Handle::current().block_on(async {
                 let res: anyhow::Result<Option<f64>> = self
                     .client
-                    .get_survival_correlation(gene_symbol, cancer_type)
+                    .get_survival_correlation(gene_symbol, &project)
                     .await;
                 res.unwrap_or(None)
             })
         })
     }
+
+    fn get_mutation_frequency(
+        &self,
+        gene_symbol: &str,
+        cancer: CancerType,
+    ) -> Option<MutationFrequency> {
+        self.mutations
+            .as_ref()?
+            .mutation_frequency(gene_symbol, cancer.tcga_cohort()?)
+    }
 }
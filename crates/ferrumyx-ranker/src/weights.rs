@@ -84,6 +84,40 @@ impl WeightVector {
         }
     }
 
+    /// Check a user-supplied vector and bring it to a usable form.
+    ///
+    /// Rejects negative or non-finite weights and an all-zero vector. A
+    /// vector that does not sum to 1.0 is renormalised with a warning.
+    pub fn validated(mut self) -> anyhow::Result<Self> {
+        for (name, value) in Self::NAMES.iter().zip(self.as_array()) {
+            if !value.is_finite() || value < 0.0 {
+                anyhow::bail!("scoring weight {name} must be a non-negative number, got {value}");
+            }
+        }
+        let sum: f64 = self.as_array().iter().sum();
+        if sum <= 0.0 {
+            anyhow::bail!("scoring weights must not all be zero");
+        }
+        if !self.validate() {
+            tracing::warn!("Scoring weights sum to {sum:.4}, not 1.0; renormalising");
+            self.normalise();
+        }
+        Ok(self)
+    }
+
+    /// Component names, in [`as_array`](Self::as_array) order.
+    pub const NAMES: [&'static str; 9] = [
+        "mutation_freq",
+        "crispr_dependency",
+        "survival_correlation",
+        "expression_specificity",
+        "structural_tractability",
+        "pocket_detectability",
+        "novelty_score",
+        "pathway_independence",
+        "literature_novelty",
+    ];
+
     /// Convert to array for iteration.
     pub fn as_array(&self) -> [f64; 9] {
         [
@@ -118,4 +152,31 @@ mod tests {
         w.normalise();
         assert!(w.validate());
     }
+
+    #[test]
+    fn test_validated_renormalises_and_rejects_negatives() {
+        let w = WeightVector {
+            mutation_freq: 0.40,
+            ..Default::default()
+        }
+        .validated()
+        .unwrap();
+        assert!(w.validate());
+
+        let negative = WeightVector {
+            novelty_score: -0.1,
+            ..Default::default()
+        };
+        let err = negative.validated().unwrap_err().to_string();
+        assert!(err.contains("novelty_score"), "{err}");
+
+        let zero: WeightVector = serde_json::from_value(serde_json::json!({
+            "mutation_freq": 0.0, "crispr_dependency": 0.0, "survival_correlation": 0.0,
+            "expression_specificity": 0.0, "structural_tractability": 0.0,
+            "pocket_detectability": 0.0, "novelty_score": 0.0,
+            "pathway_independence": 0.0, "literature_novelty": 0.0
+        }))
+        .unwrap();
+        assert!(zero.validated().is_err());
+    }
 }
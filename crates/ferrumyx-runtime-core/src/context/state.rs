@@ -1,6 +1,8 @@
 //! Job state machine.
 
+use std::any::{Any, TypeId};
 use std::collections::HashMap;
+use std::fmt;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -94,6 +96,36 @@ pub struct StateTransition {
     pub reason: Option<String>,
 }
 
+/// Values the host application attaches to a job for its tools, keyed by
+/// type, such as the sandbox limiting where tools may write.
+///
+/// Cloning shares the values, so it is as cheap as the other `Arc` fields.
+#[derive(Clone, Default)]
+pub struct JobExtensions {
+    values: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+}
+
+impl JobExtensions {
+    /// Attach `value`, replacing any earlier value of the same type.
+    pub fn insert<T: Any + Send + Sync>(&mut self, value: T) {
+        self.values.insert(TypeId::of::<T>(), Arc::new(value));
+    }
+
+    pub fn get<T: Any + Send + Sync>(&self) -> Option<&T> {
+        self.values
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.as_ref().downcast_ref())
+    }
+}
+
+impl fmt::Debug for JobExtensions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JobExtensions")
+            .field("len", &self.values.len())
+            .finish()
+    }
+}
+
 /// Context for a running job.
 #[derive(Debug, Clone, Serialize)]
 pub struct JobContext {
@@ -164,6 +196,9 @@ pub struct JobContext {
     /// previous results by ID via `$tool_call_id` parameter syntax.
     #[serde(skip)]
     pub tool_output_stash: Arc<tokio::sync::RwLock<HashMap<String, String>>>,
+    /// Values attached by the host application for its tools.
+    #[serde(skip)]
+    pub extensions: JobExtensions,
     /// User's preferred timezone (IANA name, e.g. "America/New_York"). Defaults to "UTC".
     pub user_timezone: String,
 }
@@ -205,6 +240,7 @@ impl JobContext {
             http_interceptor: None,
             metadata: serde_json::Value::Null,
             tool_output_stash: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            extensions: JobExtensions::default(),
             user_timezone: "UTC".to_string(),
         }
     }
@@ -414,4 +450,23 @@ mod tests {
         assert_eq!(ctx.state, JobState::InProgress);
         assert_eq!(ctx.repair_attempts, 1);
     }
+
+    #[test]
+    fn test_extensions_are_typed_and_shared_by_clones() {
+        let mut ctx = JobContext::new("Test", "Extensions");
+        assert!(ctx.extensions.get::<Arc<String>>().is_none());
+        ctx.extensions.insert(Arc::new("workspace".to_string()));
+        ctx.extensions.insert(7u32);
+
+        let clone = ctx.clone();
+        assert_eq!(
+            clone.extensions.get::<Arc<String>>().map(|s| s.as_str()),
+            Some("workspace")
+        );
+        assert_eq!(clone.extensions.get::<u32>(), Some(&7));
+        assert!(clone.extensions.get::<u64>().is_none());
+        assert!(!serde_json::to_string(&clone)
+            .unwrap()
+            .contains("extensions"));
+    }
 }
@@ -168,6 +168,14 @@ pub enum LlmError {
     #[error("Session renewal failed for provider {provider}: {reason}")]
     SessionRenewalFailed { provider: String, reason: String },
 
+    /// The request carries data its classification keeps off this provider.
+    /// Not retryable on the same provider; callers retry on a local one.
+    #[error("{data_class} content may not be sent to non-local provider {provider}")]
+    PolicyViolation {
+        provider: String,
+        data_class: String,
+    },
+
     #[error("HTTP error: {0}")]
     Http(#[from] reqwest::Error),
 
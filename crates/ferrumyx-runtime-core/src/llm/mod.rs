@@ -44,7 +44,7 @@ pub use recording::RecordingLlm;
 pub use registry::{ProviderDefinition, ProviderProtocol, ProviderRegistry};
 pub use response_cache::{CachedProvider, ResponseCacheConfig};
 pub use retry::{RetryConfig, RetryProvider};
-pub use rig_adapter::RigAdapter;
+pub use rig_adapter::{JSON_OBJECT_FORMAT, RESPONSE_FORMAT_KEY, RigAdapter};
 pub use session::{SessionConfig, SessionManager, create_session_manager};
 pub use smart_routing::{SmartRoutingConfig, SmartRoutingProvider, TaskComplexity};
 
@@ -314,6 +314,10 @@ mod tests {
         assert!(!is_retryable(&LlmError::SessionExpired {
             provider: "p".into(),
         }));
+        assert!(!is_retryable(&LlmError::PolicyViolation {
+            provider: "p".into(),
+            data_class: "CONFIDENTIAL".into(),
+        }));
         assert!(!is_retryable(&LlmError::ContextLengthExceeded {
             used: 100_000,
             limit: 50_000,
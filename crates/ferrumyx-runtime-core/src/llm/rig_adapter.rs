@@ -509,6 +509,25 @@ fn build_rig_request(
     })
 }
 
+/// Request metadata key asking for OpenAI-style JSON mode; the only
+/// supported value is [`JSON_OBJECT_FORMAT`].
+pub const RESPONSE_FORMAT_KEY: &str = "response_format";
+pub const JSON_OBJECT_FORMAT: &str = "json_object";
+
+/// Add `response_format: {"type": "json_object"}` next to any other
+/// `additional_params`. Only OpenAI-compatible APIs understand it, so it is
+/// set by callers that know their backend, not here.
+fn with_json_response_format(req: &mut RigRequest) {
+    let format = serde_json::json!({ "type": JSON_OBJECT_FORMAT });
+    req.additional_params = Some(match req.additional_params.take() {
+        Some(serde_json::Value::Object(mut params)) => {
+            params.insert(RESPONSE_FORMAT_KEY.to_string(), format);
+            serde_json::Value::Object(params)
+        }
+        _ => serde_json::json!({ RESPONSE_FORMAT_KEY: format }),
+    });
+}
+
 #[async_trait]
 impl<M> LlmProvider for RigAdapter<M>
 where
@@ -554,7 +573,7 @@ where
         crate::llm::provider::sanitize_tool_messages(&mut messages);
         let (preamble, history) = convert_messages(&messages);
 
-        let rig_req = build_rig_request(
+        let mut rig_req = build_rig_request(
             preamble,
             history,
             Vec::new(),
@@ -563,6 +582,13 @@ where
             request.max_tokens,
             self.cache_retention,
         )?;
+        if request
+            .metadata
+            .get(RESPONSE_FORMAT_KEY)
+            .is_some_and(|f| f == JSON_OBJECT_FORMAT)
+        {
+            with_json_response_format(&mut rig_req);
+        }
 
         let response =
             self.model
@@ -1098,6 +1124,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_json_response_format_keeps_cache_control() {
+        let mut req = build_rig_request(
+            None,
+            vec![RigMessage::user("Reply in JSON")],
+            Vec::new(),
+            None,
+            None,
+            None,
+            CacheRetention::Short,
+        )
+        .unwrap();
+        with_json_response_format(&mut req);
+
+        let params = req.additional_params.expect("should have additional_params");
+        assert_eq!(params["response_format"]["type"], "json_object");
+        assert_eq!(params["cache_control"]["type"], "ephemeral");
+    }
+
     /// Verify that the multiplier match arms in `RigAdapter::cache_write_multiplier`
     /// produce the expected values. We use a standalone helper because constructing
     /// a real `RigAdapter` requires a rig `Model` (which needs network/provider setup).
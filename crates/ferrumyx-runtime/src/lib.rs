@@ -7,6 +7,7 @@
 #![forbid(unsafe_code)]
 
 pub mod llm;
+pub mod tool_params;
 pub mod tools;
 
 pub use ferrumyx_runtime_core::{agent, channels, config, context, hooks, safety, skills};
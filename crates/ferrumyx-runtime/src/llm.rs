@@ -11,11 +11,12 @@ use rig::completion::CompletionModel;
 use rust_decimal::Decimal;
 use ferrumyx_runtime_core::llm::LlmProvider as CoreLlmProvider;
 
+pub use ferrumyx_common::data_class::{DataClass, DATA_CLASS_KEY};
 pub use ferrumyx_runtime_core::error::LlmError;
 pub use ferrumyx_runtime_core::llm::{
     ChatMessage, CompletionRequest, CompletionResponse, ContentPart, CooldownConfig, FinishReason,
     ImageUrl, ModelMetadata, Role, ToolCall, ToolCompletionRequest, ToolCompletionResponse,
-    ToolDefinition, ToolResult,
+    ToolDefinition, ToolResult, JSON_OBJECT_FORMAT, RESPONSE_FORMAT_KEY,
 };
 
 /// Ferrumyx-owned LLM provider contract.
@@ -97,14 +98,19 @@ where
         self.inner.cost_per_token()
     }
 
-    async fn complete(&self, request: CompletionRequest) -> Result<CompletionResponse, LlmError> {
+    async fn complete(
+        &self,
+        mut request: CompletionRequest,
+    ) -> Result<CompletionResponse, LlmError> {
+        pin_for_repro(&mut request.temperature, &mut request.metadata);
         self.inner.complete(request).await
     }
 
     async fn complete_with_tools(
         &self,
-        request: ToolCompletionRequest,
+        mut request: ToolCompletionRequest,
     ) -> Result<ToolCompletionResponse, LlmError> {
+        pin_for_repro(&mut request.temperature, &mut request.metadata);
         self.inner.complete_with_tools(request).await
     }
 
@@ -141,6 +147,21 @@ where
     }
 }
 
+/// Metadata key carrying the reproducibility seed on LLM requests.
+pub const REPRO_SEED_METADATA_KEY: &str = "repro_seed";
+
+/// In reproducibility mode, force greedy decoding and record the seed on the
+/// request so provider logs can be matched to the run.
+fn pin_for_repro(
+    temperature: &mut Option<f32>,
+    metadata: &mut std::collections::HashMap<String, String>,
+) {
+    if let Some(seed) = ferrumyx_common::repro::seed() {
+        *temperature = Some(0.0);
+        metadata.insert(REPRO_SEED_METADATA_KEY.to_string(), seed.to_string());
+    }
+}
+
 /// Ensure tool result messages reference known assistant tool calls.
 ///
 /// Rewrites orphaned `Role::Tool` messages as `Role::User` messages to avoid
@@ -253,12 +274,63 @@ impl LlmProvider for FailoverProvider {
     }
 }
 
-struct FerrumyxToCore {
+/// Class of everything `request` would show the model: the
+/// [`DATA_CLASS_KEY`] metadata entry and any tool results tagged in its
+/// messages. An unreadable metadata entry counts as `CONFIDENTIAL`.
+pub fn request_data_class(
+    metadata: &std::collections::HashMap<String, String>,
+    messages: &[ChatMessage],
+) -> DataClass {
+    let declared = metadata
+        .get(DATA_CLASS_KEY)
+        .map(|class| class.parse().unwrap_or(DataClass::Confidential));
+    messages
+        .iter()
+        .filter_map(|m| DataClass::tagged_in(&m.content))
+        .chain(declared)
+        .max()
+        .unwrap_or_default()
+}
+
+/// Refuses, with [`LlmError::PolicyViolation`], requests whose data class
+/// must stay on a local backend when the wrapped provider is remote.
+pub struct DataClassGuard {
     inner: Arc<dyn LlmProvider>,
+    backend: String,
+    local: bool,
+    allow_internal_remote: bool,
+}
+
+impl DataClassGuard {
+    pub fn new(inner: Arc<dyn LlmProvider>, backend: impl Into<String>, local: bool) -> Self {
+        Self {
+            inner,
+            backend: backend.into(),
+            local,
+            allow_internal_remote: false,
+        }
+    }
+
+    /// Let `INTERNAL` content through to a remote provider (`[llm]
+    /// allow_internal_remote`). `CONFIDENTIAL` content never is.
+    pub fn with_allow_internal_remote(mut self, allow: bool) -> Self {
+        self.allow_internal_remote = allow;
+        self
+    }
+
+    fn check(&self, class: DataClass) -> Result<(), LlmError> {
+        if self.local || !class.requires_local(self.allow_internal_remote) {
+            return Ok(());
+        }
+        Err(LlmError::PolicyViolation {
+            provider: self.backend.clone(),
+            data_class: class.as_str().to_string(),
+        })
+    }
 }
 
 #[async_trait]
-impl CoreLlmProvider for FerrumyxToCore {
+impl LlmProvider for DataClassGuard {
     fn model_name(&self) -> &str {
         self.inner.model_name()
     }
@@ -268,6 +340,7 @@ impl CoreLlmProvider for FerrumyxToCore {
     }
 
     async fn complete(&self, request: CompletionRequest) -> Result<CompletionResponse, LlmError> {
+        self.check(request_data_class(&request.metadata, &request.messages))?;
         self.inner.complete(request).await
     }
 
@@ -275,6 +348,148 @@ impl CoreLlmProvider for FerrumyxToCore {
         &self,
         request: ToolCompletionRequest,
     ) -> Result<ToolCompletionResponse, LlmError> {
+        self.check(request_data_class(&request.metadata, &request.messages))?;
+        self.inner.complete_with_tools(request).await
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, LlmError> {
+        self.inner.list_models().await
+    }
+
+    async fn model_metadata(&self) -> Result<ModelMetadata, LlmError> {
+        self.inner.model_metadata().await
+    }
+
+    fn effective_model_name(&self, requested_model: Option<&str>) -> String {
+        self.inner.effective_model_name(requested_model)
+    }
+
+    fn active_model_name(&self) -> String {
+        self.inner.active_model_name()
+    }
+
+    fn set_model(&self, model: &str) -> Result<(), LlmError> {
+        self.inner.set_model(model)
+    }
+
+    fn calculate_cost(&self, input_tokens: u32, output_tokens: u32) -> Decimal {
+        self.inner.calculate_cost(input_tokens, output_tokens)
+    }
+
+    fn cache_write_multiplier(&self) -> Decimal {
+        self.inner.cache_write_multiplier()
+    }
+
+    fn cache_read_discount(&self) -> Decimal {
+        self.inner.cache_read_discount()
+    }
+}
+
+/// Sends requests to `primary` and, when a remote backend there refuses one
+/// on data-class policy, retries it on the local backend. Without a local
+/// backend the [`LlmError::PolicyViolation`] is returned as is.
+pub struct LocalRetryProvider {
+    primary: Arc<dyn LlmProvider>,
+    local: Option<Arc<dyn LlmProvider>>,
+}
+
+impl LocalRetryProvider {
+    pub fn new(primary: Arc<dyn LlmProvider>, local: Option<Arc<dyn LlmProvider>>) -> Self {
+        Self { primary, local }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for LocalRetryProvider {
+    fn model_name(&self) -> &str {
+        self.primary.model_name()
+    }
+
+    fn cost_per_token(&self) -> (Decimal, Decimal) {
+        self.primary.cost_per_token()
+    }
+
+    async fn complete(&self, request: CompletionRequest) -> Result<CompletionResponse, LlmError> {
+        let result = self.primary.complete(request.clone()).await;
+        match (&self.local, result) {
+            (Some(local), Err(LlmError::PolicyViolation { .. })) => local.complete(request).await,
+            (_, result) => result,
+        }
+    }
+
+    async fn complete_with_tools(
+        &self,
+        request: ToolCompletionRequest,
+    ) -> Result<ToolCompletionResponse, LlmError> {
+        let result = self.primary.complete_with_tools(request.clone()).await;
+        match (&self.local, result) {
+            (Some(local), Err(LlmError::PolicyViolation { .. })) => {
+                local.complete_with_tools(request).await
+            }
+            (_, result) => result,
+        }
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, LlmError> {
+        self.primary.list_models().await
+    }
+
+    async fn model_metadata(&self) -> Result<ModelMetadata, LlmError> {
+        self.primary.model_metadata().await
+    }
+
+    fn effective_model_name(&self, requested_model: Option<&str>) -> String {
+        self.primary.effective_model_name(requested_model)
+    }
+
+    fn active_model_name(&self) -> String {
+        self.primary.active_model_name()
+    }
+
+    fn set_model(&self, model: &str) -> Result<(), LlmError> {
+        self.primary.set_model(model)
+    }
+
+    fn calculate_cost(&self, input_tokens: u32, output_tokens: u32) -> Decimal {
+        self.primary.calculate_cost(input_tokens, output_tokens)
+    }
+
+    fn cache_write_multiplier(&self) -> Decimal {
+        self.primary.cache_write_multiplier()
+    }
+
+    fn cache_read_discount(&self) -> Decimal {
+        self.primary.cache_read_discount()
+    }
+}
+
+struct FerrumyxToCore {
+    inner: Arc<dyn LlmProvider>,
+}
+
+#[async_trait]
+impl CoreLlmProvider for FerrumyxToCore {
+    fn model_name(&self) -> &str {
+        self.inner.model_name()
+    }
+
+    fn cost_per_token(&self) -> (Decimal, Decimal) {
+        self.inner.cost_per_token()
+    }
+
+    async fn complete(
+        &self,
+        mut request: CompletionRequest,
+    ) -> Result<CompletionResponse, LlmError> {
+        pin_for_repro(&mut request.temperature, &mut request.metadata);
+        self.inner.complete(request).await
+    }
+
+    async fn complete_with_tools(
+        &self,
+        mut request: ToolCompletionRequest,
+    ) -> Result<ToolCompletionResponse, LlmError> {
+        pin_for_repro(&mut request.temperature, &mut request.metadata);
         self.inner.complete_with_tools(request).await
     }
 
@@ -379,3 +594,136 @@ pub fn from_core_provider(provider: Arc<dyn CoreLlmProvider>) -> Arc<dyn LlmProv
     Arc::new(CoreToFerrumyx { inner: provider })
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Answers with its own name and counts the calls it served.
+    struct Named {
+        name: &'static str,
+        calls: AtomicUsize,
+    }
+
+    impl Named {
+        fn new(name: &'static str) -> Arc<Self> {
+            Arc::new(Self {
+                name,
+                calls: AtomicUsize::new(0),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl LlmProvider for Named {
+        fn model_name(&self) -> &str {
+            self.name
+        }
+
+        fn cost_per_token(&self) -> (Decimal, Decimal) {
+            (Decimal::ZERO, Decimal::ZERO)
+        }
+
+        async fn complete(
+            &self,
+            _request: CompletionRequest,
+        ) -> Result<CompletionResponse, LlmError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(CompletionResponse {
+                content: self.name.to_string(),
+                input_tokens: 0,
+                output_tokens: 0,
+                finish_reason: FinishReason::Stop,
+                cache_read_input_tokens: 0,
+                cache_creation_input_tokens: 0,
+            })
+        }
+
+        async fn complete_with_tools(
+            &self,
+            _request: ToolCompletionRequest,
+        ) -> Result<ToolCompletionResponse, LlmError> {
+            unimplemented!("not used by these tests")
+        }
+    }
+
+    fn with_tool_result(class: DataClass) -> CompletionRequest {
+        let result = crate::tools::tag_data_class(serde_json::json!({ "rows": 3 }), class);
+        CompletionRequest::new(vec![
+            ChatMessage::user("Summarise the assay results."),
+            ChatMessage::tool_result(
+                "call_1",
+                "lab_results",
+                serde_json::to_string_pretty(&result).unwrap(),
+            ),
+        ])
+    }
+
+    fn router(
+        remote: &Arc<Named>,
+        local: Option<&Arc<Named>>,
+        allow_internal_remote: bool,
+    ) -> LocalRetryProvider {
+        let guard = |p: Arc<Named>, is_local: bool| -> Arc<dyn LlmProvider> {
+            Arc::new(
+                DataClassGuard::new(p.clone(), p.name, is_local)
+                    .with_allow_internal_remote(allow_internal_remote),
+            )
+        };
+        LocalRetryProvider::new(
+            guard(remote.clone(), false),
+            local.map(|l| guard(l.clone(), true)),
+        )
+    }
+
+    async fn served(router: &LocalRetryProvider, class: DataClass) -> String {
+        router
+            .complete(with_tool_result(class))
+            .await
+            .unwrap()
+            .content
+    }
+
+    #[tokio::test]
+    async fn confidential_prompt_without_a_local_backend_fails_without_leaking() {
+        let openai = Named::new("openai");
+        let err = router(&openai, None, true)
+            .complete(with_tool_result(DataClass::Confidential))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            &err,
+            LlmError::PolicyViolation { provider, data_class }
+                if provider == "openai" && data_class == "CONFIDENTIAL"
+        ));
+        assert_eq!(openai.calls.load(Ordering::SeqCst), 0);
+
+        let mut declared = CompletionRequest::new(vec![ChatMessage::user("hi")]);
+        declared
+            .metadata
+            .insert(DATA_CLASS_KEY.to_string(), "classified".to_string());
+        let refused = router(&openai, None, true).complete(declared).await;
+        assert!(matches!(refused, Err(LlmError::PolicyViolation { .. })));
+        assert_eq!(openai.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn refused_requests_are_retried_on_the_local_backend() {
+        let openai = Named::new("openai");
+        let ollama = Named::new("ollama");
+        let strict = router(&openai, Some(&ollama), false);
+        assert_eq!(served(&strict, DataClass::Public).await, "openai");
+        assert_eq!(served(&strict, DataClass::Internal).await, "ollama");
+        assert_eq!(served(&strict, DataClass::Confidential).await, "ollama");
+
+        let relaxed = router(&openai, Some(&ollama), true);
+        assert_eq!(served(&relaxed, DataClass::Internal).await, "openai");
+        assert_eq!(
+            (
+                openai.calls.load(Ordering::SeqCst),
+                ollama.calls.load(Ordering::SeqCst)
+            ),
+            (2, 2)
+        );
+    }
+}
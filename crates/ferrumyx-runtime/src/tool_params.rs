@@ -0,0 +1,180 @@
+//! Tool parameter checking against each tool's declared JSON Schema.
+//!
+//! LLM-generated arguments are prepared before a tool runs: defaults the
+//! schema declares are filled in, unknown fields are optionally rejected, and
+//! the result is validated with the `jsonschema` crate. Every violation is
+//! reported at once so the model can fix all of them in one retry.
+
+use serde_json::{Map, Value};
+
+/// How strictly [`prepare_params`] treats arguments.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParamPolicy {
+    /// Reject fields the schema does not declare, for object schemas that
+    /// do not set `additionalProperties` themselves.
+    pub reject_unknown_fields: bool,
+}
+
+/// `params` with the schema's defaults filled in, or every way it fails
+/// `schema`. A `null` is read as an empty object.
+pub fn prepare_params(
+    schema: &Value,
+    params: Value,
+    policy: ParamPolicy,
+) -> Result<Value, Vec<String>> {
+    let mut params = match params {
+        Value::Null => Value::Object(Map::new()),
+        other => other,
+    };
+    fill_defaults(schema, &mut params);
+
+    let mut violations = Vec::new();
+    if policy.reject_unknown_fields {
+        unknown_fields(schema, &params, "", &mut violations);
+    }
+    match jsonschema::validator_for(schema) {
+        Ok(validator) => {
+            for error in validator.iter_errors(&params) {
+                let path = error.instance_path.to_string();
+                violations.push(if path.is_empty() {
+                    error.to_string()
+                } else {
+                    format!("{path}: {error}")
+                });
+            }
+        }
+        Err(e) => violations.push(format!("the tool declares an invalid schema: {e}")),
+    }
+    if violations.is_empty() {
+        Ok(params)
+    } else {
+        Err(violations)
+    }
+}
+
+/// Insert each declared `default` the object is missing, recursing into
+/// nested objects that are present.
+fn fill_defaults(schema: &Value, value: &mut Value) {
+    let (Some(properties), Value::Object(fields)) =
+        (schema.get("properties").and_then(Value::as_object), value)
+    else {
+        return;
+    };
+    for (name, property) in properties {
+        match fields.get_mut(name) {
+            Some(field) => fill_defaults(property, field),
+            None => {
+                if let Some(default) = property.get("default") {
+                    fields.insert(name.clone(), default.clone());
+                }
+            }
+        }
+    }
+}
+
+fn unknown_fields(schema: &Value, value: &Value, path: &str, violations: &mut Vec<String>) {
+    let (Some(properties), Value::Object(fields)) =
+        (schema.get("properties").and_then(Value::as_object), value)
+    else {
+        return;
+    };
+    for (name, field) in fields {
+        let field_path = format!("{path}/{name}");
+        match properties.get(name) {
+            Some(property) => unknown_fields(property, field, &field_path, violations),
+            None if schema.get("additionalProperties").is_none() => {
+                violations.push(format!("{field_path}: unknown field, not in the schema"));
+            }
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query_text": { "type": "string" },
+                "max_results": { "type": "integer", "default": 20 },
+                "filters": {
+                    "type": "object",
+                    "properties": {
+                        "min_score": { "type": "number", "default": 0.5 }
+                    }
+                }
+            },
+            "required": ["query_text"]
+        })
+    }
+
+    #[test]
+    fn missing_required_fields_and_wrong_types_are_all_reported() {
+        let err = prepare_params(
+            &schema(),
+            json!({ "max_results": "ten" }),
+            ParamPolicy::default(),
+        )
+        .unwrap_err();
+        assert_eq!(err.len(), 2, "{err:?}");
+        assert!(err
+            .iter()
+            .any(|v| v.contains("\"query_text\"") && v.contains("required")));
+        assert!(err
+            .iter()
+            .any(|v| v.starts_with("/max_results:") && v.contains("integer")));
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected_only_when_strict() {
+        let params = json!({ "query_text": "KRAS", "cancer": "PAAD", "filters": { "x": 1 } });
+        assert!(prepare_params(&schema(), params.clone(), ParamPolicy::default()).is_ok());
+
+        let strict = ParamPolicy {
+            reject_unknown_fields: true,
+        };
+        let err = prepare_params(&schema(), params, strict).unwrap_err();
+        assert_eq!(
+            err,
+            vec![
+                "/cancer: unknown field, not in the schema".to_string(),
+                "/filters/x: unknown field, not in the schema".to_string(),
+            ]
+        );
+
+        let open = json!({ "type": "object", "properties": {}, "additionalProperties": true });
+        assert!(prepare_params(&open, json!({ "anything": 1 }), strict).is_ok());
+    }
+
+    #[test]
+    fn declared_defaults_are_filled_in() {
+        let params = prepare_params(
+            &schema(),
+            json!({ "query_text": "KRAS", "filters": {} }),
+            ParamPolicy::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            params,
+            json!({ "query_text": "KRAS", "max_results": 20, "filters": { "min_score": 0.5 } })
+        );
+        // Given values win over defaults.
+        let params = prepare_params(
+            &schema(),
+            json!({ "query_text": "KRAS", "max_results": 5 }),
+            ParamPolicy::default(),
+        )
+        .unwrap();
+        assert_eq!(params["max_results"], 5);
+
+        let no_args = json!({ "type": "object", "properties": { "dry_run": { "default": true } } });
+        assert_eq!(
+            prepare_params(&no_args, Value::Null, ParamPolicy::default()).unwrap(),
+            json!({ "dry_run": true })
+        );
+    }
+}
@@ -4,14 +4,20 @@
 //! At runtime, adapters bridge these tools into the runtime core.
 
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
-use std::time::Duration;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
+use ferrumyx_common::data_class::{DataClass, DATA_CLASS_KEY};
+use ferrumyx_common::sandbox::{Sandbox, SandboxError};
+use ferrumyx_common::tool_stats::{ToolOutcome, ToolStats};
 use rust_decimal::Decimal;
+use tokio::sync::Semaphore;
+pub use tokio_util::sync::CancellationToken;
 
 use crate::context::JobContext;
 use crate::llm::ToolDefinition;
+use crate::tool_params::{prepare_params, ParamPolicy};
 
 /// How much approval a specific tool invocation requires.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -53,6 +59,9 @@ pub enum ToolError {
     ExecutionFailed(String),
     #[error("Timeout after {0:?}")]
     Timeout(Duration),
+    /// Cancelled by the registry for running past its timeout.
+    #[error("{tool} timed out after {timeout:?} and was cancelled")]
+    TimedOut { tool: String, timeout: Duration },
     #[error("Not authorized: {0}")]
     NotAuthorized(String),
     #[error("Rate limited, retry after {0:?}")]
@@ -61,6 +70,31 @@ pub enum ToolError {
     ExternalService(String),
     #[error("Sandbox error: {0}")]
     Sandbox(String),
+    /// Parameters that fail the tool's declared schema, one entry per
+    /// violation.
+    #[error(
+        "Invalid parameters for {tool}: {}. Check them against the tool's parameter schema and call it again.",
+        .violations.join("; ")
+    )]
+    InvalidParams {
+        tool: String,
+        violations: Vec<String>,
+    },
+}
+
+impl From<SandboxError> for ToolError {
+    fn from(value: SandboxError) -> Self {
+        Self::Sandbox(value.to_string())
+    }
+}
+
+/// The workspace sandbox of the registry running this call. Tools resolve
+/// the paths they read or write through it and charge what they write to
+/// the job's quota.
+pub fn sandbox(ctx: &JobContext) -> Result<&Arc<Sandbox>, ToolError> {
+    ctx.extensions.get::<Arc<Sandbox>>().ok_or_else(|| {
+        ToolError::Sandbox("no workspace sandbox is configured for this call".to_string())
+    })
 }
 
 /// Output from a tool execution.
@@ -108,10 +142,13 @@ pub trait Tool: Send + Sync {
     fn description(&self) -> &str;
     fn parameters_schema(&self) -> serde_json::Value;
 
+    /// Run the tool. `cancel` fires when the call times out or once it has
+    /// returned, so work the tool spawned can stop with it.
     async fn execute(
         &self,
         params: serde_json::Value,
         ctx: &JobContext,
+        cancel: &CancellationToken,
     ) -> Result<ToolOutput, ToolError>;
 
     fn estimated_cost(&self, _params: &serde_json::Value) -> Option<Decimal> {
@@ -130,8 +167,16 @@ pub trait Tool: Send + Sync {
         ApprovalRequirement::Never
     }
 
-    fn execution_timeout(&self) -> Duration {
-        Duration::from_secs(60)
+    /// How long a call may run before it is cancelled; the registry's
+    /// [`ToolLimits::default_timeout`] when `None`.
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Calls of this tool allowed to run at once; the registry's
+    /// [`ToolLimits::max_concurrent_per_tool`] when `None`.
+    fn max_concurrency(&self) -> Option<usize> {
+        None
     }
 
     fn domain(&self) -> ToolDomain {
@@ -145,6 +190,26 @@ pub trait Tool: Send + Sync {
     fn rate_limit_config(&self) -> Option<ToolRateLimitConfig> {
         None
     }
+
+    /// Sensitivity of what this tool returns. Results are tagged with it so
+    /// LLM routing keeps `INTERNAL` and `CONFIDENTIAL` results local.
+    fn output_data_class(&self) -> DataClass {
+        DataClass::Public
+    }
+}
+
+/// Tag `result` with `class` under [`DATA_CLASS_KEY`]: added to an object,
+/// or wrapping any other value as `{"data_class": ..., "result": ...}`.
+pub fn tag_data_class(result: serde_json::Value, class: DataClass) -> serde_json::Value {
+    let mut map = match result {
+        serde_json::Value::Object(map) => map,
+        other => serde_json::Map::from_iter([("result".to_string(), other)]),
+    };
+    map.insert(
+        DATA_CLASS_KEY.to_string(),
+        serde_json::Value::String(class.as_str().to_string()),
+    );
+    serde_json::Value::Object(map)
 }
 
 impl From<ToolOutput> for ferrumyx_runtime_core::tools::ToolOutput {
@@ -231,10 +296,14 @@ impl From<ToolError> for ferrumyx_runtime_core::tools::ToolError {
             ToolError::InvalidParameters(v) => Self::InvalidParameters(v),
             ToolError::ExecutionFailed(v) => Self::ExecutionFailed(v),
             ToolError::Timeout(v) => Self::Timeout(v),
+            ToolError::TimedOut { timeout, .. } => Self::Timeout(timeout),
             ToolError::NotAuthorized(v) => Self::NotAuthorized(v),
             ToolError::RateLimited(v) => Self::RateLimited(v),
             ToolError::ExternalService(v) => Self::ExternalService(v),
             ToolError::Sandbox(v) => Self::Sandbox(v),
+            invalid @ ToolError::InvalidParams { .. } => {
+                Self::InvalidParameters(invalid.to_string())
+            }
         }
     }
 }
@@ -255,6 +324,7 @@ impl From<ferrumyx_runtime_core::tools::ToolError> for ToolError {
 
 struct FerrumyxToCoreTool {
     inner: Arc<dyn Tool>,
+    invoker: Arc<Invoker>,
 }
 
 #[async_trait]
@@ -277,10 +347,9 @@ impl ferrumyx_runtime_core::tools::Tool for FerrumyxToCoreTool {
         ctx: &crate::context::JobContext,
     ) -> Result<ferrumyx_runtime_core::tools::ToolOutput, ferrumyx_runtime_core::tools::ToolError> {
         let out = self
-            .inner
-            .execute(params, ctx)
-            .await
-            .map_err(ferrumyx_runtime_core::tools::ToolError::from)?;
+            .invoker
+            .invoke(&self.inner, params, ctx, &CancellationToken::new())
+            .await?;
         Ok(out.into())
     }
 
@@ -304,7 +373,8 @@ impl ferrumyx_runtime_core::tools::Tool for FerrumyxToCoreTool {
     }
 
     fn execution_timeout(&self) -> Duration {
-        self.inner.execution_timeout()
+        // Leave the invoker time to cancel the call and report it first.
+        self.invoker.timeout_for(self.inner.as_ref()) + BRIDGE_TIMEOUT_GRACE
     }
 
     fn domain(&self) -> ferrumyx_runtime_core::tools::ToolDomain {
@@ -342,6 +412,7 @@ impl Tool for CoreToFerrumyxTool {
         &self,
         params: serde_json::Value,
         ctx: &JobContext,
+        _cancel: &CancellationToken,
     ) -> Result<ToolOutput, ToolError> {
         let out = self
             .inner
@@ -367,8 +438,8 @@ impl Tool for CoreToFerrumyxTool {
         self.inner.requires_approval(params).into()
     }
 
-    fn execution_timeout(&self) -> Duration {
-        self.inner.execution_timeout()
+    fn timeout(&self) -> Option<Duration> {
+        Some(self.inner.execution_timeout())
     }
 
     fn domain(&self) -> ToolDomain {
@@ -384,9 +455,32 @@ impl Tool for CoreToFerrumyxTool {
     }
 }
 
-/// Bridge a Ferrumyx tool into the runtime-core tool trait.
+/// Bridge a Ferrumyx tool into the runtime-core tool trait, with default
+/// [`ToolLimits`] of its own.
 pub fn to_core_tool(tool: Arc<dyn Tool>) -> Arc<dyn ferrumyx_runtime_core::tools::Tool> {
-    Arc::new(FerrumyxToCoreTool { inner: tool })
+    Arc::new(FerrumyxToCoreTool {
+        inner: tool,
+        invoker: Arc::new(Invoker::new(
+            ToolLimits::default(),
+            ParamPolicy::default(),
+            None,
+        )),
+    })
+}
+
+/// Check `params` against `tool`'s schema, returning them with the schema's
+/// defaults filled in.
+fn check_params(
+    tool: &dyn Tool,
+    params: serde_json::Value,
+    policy: ParamPolicy,
+) -> Result<serde_json::Value, ToolError> {
+    prepare_params(&tool.parameters_schema(), params, policy).map_err(|violations| {
+        ToolError::InvalidParams {
+            tool: tool.name().to_string(),
+            violations,
+        }
+    })
 }
 
 /// Bridge a runtime-core tool into the Ferrumyx tool trait.
@@ -394,18 +488,212 @@ pub fn from_core_tool(tool: Arc<dyn ferrumyx_runtime_core::tools::Tool>) -> Arc<
     Arc::new(CoreToFerrumyxTool { inner: tool })
 }
 
+/// Extra time the runtime core allows a bridged tool beyond its timeout.
+const BRIDGE_TIMEOUT_GRACE: Duration = Duration::from_secs(5);
+
+/// Timeouts and concurrency limits the registry applies to every call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToolLimits {
+    /// Timeout of tools without their own [`Tool::timeout`].
+    pub default_timeout: Duration,
+    /// Calls running at once across all tools.
+    pub max_concurrent: usize,
+    /// Calls of one tool running at once, unless it sets
+    /// [`Tool::max_concurrency`].
+    pub max_concurrent_per_tool: usize,
+}
+
+impl Default for ToolLimits {
+    fn default() -> Self {
+        Self {
+            default_timeout: Duration::from_secs(60),
+            max_concurrent: 8,
+            max_concurrent_per_tool: 2,
+        }
+    }
+}
+
+/// Runs calls for a registry and the runtime-core tools bridged from it:
+/// checks parameters, waits for a global and a per-tool permit, enforces
+/// the timeout, attaches the sandbox and records [`ToolStats`].
+struct Invoker {
+    limits: ToolLimits,
+    param_policy: ParamPolicy,
+    sandbox: Option<Arc<Sandbox>>,
+    global: Semaphore,
+    per_tool: Mutex<HashMap<String, Arc<Semaphore>>>,
+    stats: Mutex<HashMap<String, ToolStats>>,
+}
+
+impl Invoker {
+    fn new(limits: ToolLimits, param_policy: ParamPolicy, sandbox: Option<Arc<Sandbox>>) -> Self {
+        Self {
+            limits,
+            param_policy,
+            sandbox,
+            global: Semaphore::new(limits.max_concurrent.max(1)),
+            per_tool: Mutex::default(),
+            stats: Mutex::default(),
+        }
+    }
+
+    fn timeout_for(&self, tool: &dyn Tool) -> Duration {
+        tool.timeout().unwrap_or(self.limits.default_timeout)
+    }
+
+    fn semaphore_for(&self, tool: &dyn Tool) -> Arc<Semaphore> {
+        let mut per_tool = self.per_tool.lock().unwrap_or_else(|e| e.into_inner());
+        let permits = tool
+            .max_concurrency()
+            .unwrap_or(self.limits.max_concurrent_per_tool)
+            .max(1);
+        Arc::clone(
+            per_tool
+                .entry(tool.name().to_string())
+                .or_insert_with(|| Arc::new(Semaphore::new(permits))),
+        )
+    }
+
+    async fn invoke(
+        &self,
+        tool: &Arc<dyn Tool>,
+        params: serde_json::Value,
+        ctx: &JobContext,
+        cancel: &CancellationToken,
+    ) -> Result<ToolOutput, ToolError> {
+        let started = Instant::now();
+        let result = self.run(tool.as_ref(), params, ctx, cancel).await;
+        let outcome = match &result {
+            Ok(_) => ToolOutcome::Success,
+            Err(ToolError::TimedOut { .. }) => ToolOutcome::TimedOut,
+            Err(_) => ToolOutcome::Error,
+        };
+        self.stats
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(tool.name().to_string())
+            .or_insert_with(|| ToolStats::new(tool.name()))
+            .record(started.elapsed(), outcome);
+        let mut out = result?;
+        out.result = tag_data_class(out.result, tool.output_data_class());
+        Ok(out)
+    }
+
+    async fn run(
+        &self,
+        tool: &dyn Tool,
+        params: serde_json::Value,
+        ctx: &JobContext,
+        cancel: &CancellationToken,
+    ) -> Result<ToolOutput, ToolError> {
+        let params = check_params(tool, params, self.param_policy)?;
+        let closed = |_| ToolError::ExecutionFailed("tool registry is shutting down".to_string());
+        let _global = self.global.acquire().await.map_err(closed)?;
+        let _own = self
+            .semaphore_for(tool)
+            .acquire_owned()
+            .await
+            .map_err(closed)?;
+
+        let scoped;
+        let ctx = match &self.sandbox {
+            // A sandbox the caller attached already stays.
+            Some(sandbox) if ctx.extensions.get::<Arc<Sandbox>>().is_none() => {
+                let mut with_sandbox = ctx.clone();
+                with_sandbox.extensions.insert(Arc::clone(sandbox));
+                scoped = with_sandbox;
+                &scoped
+            }
+            _ => ctx,
+        };
+
+        let call = cancel.child_token();
+        let _cancel_on_return = call.clone().drop_guard();
+        let timeout = self.timeout_for(tool);
+        match tokio::time::timeout(timeout, tool.execute(params, ctx, &call)).await {
+            Ok(result) => result,
+            Err(_) => {
+                call.cancel();
+                Err(ToolError::TimedOut {
+                    tool: tool.name().to_string(),
+                    timeout,
+                })
+            }
+        }
+    }
+
+    fn stats(&self) -> Vec<ToolStats> {
+        let mut stats: Vec<ToolStats> = self
+            .stats
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect();
+        stats.sort_unstable_by(|a, b| a.tool.cmp(&b.tool));
+        stats
+    }
+}
+
 /// Ferrumyx-owned tool registry.
 pub struct ToolRegistry {
     tools: RwLock<HashMap<String, Arc<dyn Tool>>>,
+    invoker: Arc<Invoker>,
 }
 
 impl ToolRegistry {
     pub fn new() -> Self {
         Self {
             tools: RwLock::new(HashMap::new()),
+            invoker: Arc::new(Invoker::new(
+                ToolLimits::default(),
+                ParamPolicy::default(),
+                None,
+            )),
         }
     }
 
+    /// Reject parameters the tool schemas do not declare instead of
+    /// ignoring them.
+    pub fn with_strict_params(mut self, strict: bool) -> Self {
+        let policy = ParamPolicy {
+            reject_unknown_fields: strict,
+        };
+        self.invoker = Arc::new(Invoker::new(
+            self.invoker.limits,
+            policy,
+            self.invoker.sandbox.clone(),
+        ));
+        self
+    }
+
+    /// Apply `limits` to every call made through this registry or the
+    /// runtime-core registry built from it.
+    pub fn with_limits(mut self, limits: ToolLimits) -> Self {
+        self.invoker = Arc::new(Invoker::new(
+            limits,
+            self.invoker.param_policy,
+            self.invoker.sandbox.clone(),
+        ));
+        self
+    }
+
+    /// Hand `sandbox` to every call made through this registry or the
+    /// runtime-core registry built from it; tools get it from [`sandbox`].
+    pub fn with_sandbox(mut self, sandbox: Arc<Sandbox>) -> Self {
+        self.invoker = Arc::new(Invoker::new(
+            self.invoker.limits,
+            self.invoker.param_policy,
+            Some(sandbox),
+        ));
+        self
+    }
+
+    /// Calls per tool since start-up, by tool name.
+    pub fn stats(&self) -> Vec<ToolStats> {
+        self.invoker.stats()
+    }
+
     pub async fn register(&self, tool: Arc<dyn Tool>) {
         self.register_sync(tool);
     }
@@ -442,6 +730,39 @@ impl ToolRegistry {
         self.tools.read().map(|m| m.len()).unwrap_or(0)
     }
 
+    /// Check `params` against the schema of the tool registered as `name`
+    /// without running it, returning them with the schema's defaults filled
+    /// in. `None` if no such tool is registered.
+    pub fn validate_params(
+        &self,
+        name: &str,
+        params: serde_json::Value,
+    ) -> Option<Result<serde_json::Value, ToolError>> {
+        let tool = self.get_sync(name)?;
+        Some(check_params(
+            tool.as_ref(),
+            params,
+            self.invoker.param_policy,
+        ))
+    }
+
+    /// Run the tool registered as `name` once `params` pass its schema and
+    /// the [`ToolLimits`] allow, tagging its result with the tool's
+    /// [`Tool::output_data_class`]. Cancelling `cancel` cancels the call's
+    /// own token too.
+    pub async fn invoke(
+        &self,
+        name: &str,
+        params: serde_json::Value,
+        ctx: &JobContext,
+        cancel: &CancellationToken,
+    ) -> Result<ToolOutput, ToolError> {
+        let tool = self
+            .get_sync(name)
+            .ok_or_else(|| ToolError::InvalidParameters(format!("unknown tool {name:?}")))?;
+        self.invoker.invoke(&tool, params, ctx, cancel).await
+    }
+
     pub async fn tool_definitions(&self) -> Vec<ToolDefinition> {
         self.tool_definitions_sync()
     }
@@ -471,7 +792,10 @@ impl ToolRegistry {
         let out = Arc::new(ferrumyx_runtime_core::tools::ToolRegistry::new());
         if let Ok(map) = self.tools.read() {
             for tool in map.values() {
-                out.register_sync(to_core_tool(Arc::clone(tool)));
+                out.register_sync(Arc::new(FerrumyxToCoreTool {
+                    inner: Arc::clone(tool),
+                    invoker: Arc::clone(&self.invoker),
+                }));
             }
         }
         out
@@ -484,3 +808,289 @@ impl Default for ToolRegistry {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    struct Echo;
+
+    #[async_trait]
+    impl Tool for Echo {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn description(&self) -> &str {
+            "Returns its parameters."
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            json!({
+                "type": "object",
+                "properties": {
+                    "gene": { "type": "string" },
+                    "limit": { "type": "integer", "default": 10 }
+                },
+                "required": ["gene"]
+            })
+        }
+
+        async fn execute(
+            &self,
+            params: serde_json::Value,
+            _ctx: &JobContext,
+            _cancel: &CancellationToken,
+        ) -> Result<ToolOutput, ToolError> {
+            Ok(ToolOutput::success(params, Duration::ZERO))
+        }
+    }
+
+    /// Writes `bytes` to `path` in the workspace sandbox.
+    struct Writer;
+
+    #[async_trait]
+    impl Tool for Writer {
+        fn name(&self) -> &str {
+            "writer"
+        }
+
+        fn description(&self) -> &str {
+            "Writes a file."
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            json!({
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string" },
+                    "bytes": { "type": "integer" }
+                },
+                "required": ["path", "bytes"]
+            })
+        }
+
+        async fn execute(
+            &self,
+            params: serde_json::Value,
+            ctx: &JobContext,
+            _cancel: &CancellationToken,
+        ) -> Result<ToolOutput, ToolError> {
+            let sandbox = sandbox(ctx)?;
+            let path = sandbox.resolve(params["path"].as_str().unwrap_or_default())?;
+            let bytes = params["bytes"].as_u64().unwrap_or(0);
+            sandbox.charge(&ctx.job_id.to_string(), bytes)?;
+            std::fs::write(&path, vec![0u8; bytes as usize])
+                .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?;
+            Ok(ToolOutput::success(json!({ "path": path }), Duration::ZERO))
+        }
+    }
+
+    /// Sleeps for `ms` unless cancelled first, counting the calls running
+    /// at once.
+    struct Sleepy {
+        running: std::sync::atomic::AtomicUsize,
+        peak: std::sync::atomic::AtomicUsize,
+        cancelled: Arc<std::sync::atomic::AtomicBool>,
+    }
+
+    #[async_trait]
+    impl Tool for Sleepy {
+        fn name(&self) -> &str {
+            "sleepy"
+        }
+
+        fn description(&self) -> &str {
+            "Sleeps."
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            json!({ "type": "object", "properties": { "ms": { "type": "integer" } } })
+        }
+
+        fn timeout(&self) -> Option<Duration> {
+            Some(Duration::from_millis(100))
+        }
+
+        async fn execute(
+            &self,
+            params: serde_json::Value,
+            _ctx: &JobContext,
+            cancel: &CancellationToken,
+        ) -> Result<ToolOutput, ToolError> {
+            use std::sync::atomic::Ordering;
+
+            let now = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            let ms = params["ms"].as_u64().unwrap_or(0);
+            // Work handed to another task keeps running past a dropped
+            // future; the token is what stops it.
+            let cancel = cancel.clone();
+            let cancelled = Arc::clone(&self.cancelled);
+            let work = tokio::spawn(async move {
+                tokio::select! {
+                    _ = tokio::time::sleep(Duration::from_millis(ms)) => {}
+                    _ = cancel.cancelled() => cancelled.store(true, Ordering::SeqCst),
+                }
+            });
+            work.await.unwrap();
+            self.running.fetch_sub(1, Ordering::SeqCst);
+            Ok(ToolOutput::success(json!({}), Duration::ZERO))
+        }
+    }
+
+    #[tokio::test]
+    async fn invoke_checks_params_before_running_the_tool() {
+        let registry = ToolRegistry::new().with_strict_params(true);
+        registry.register_sync(Arc::new(Echo));
+        let ctx = JobContext::default();
+
+        let cancel = CancellationToken::new();
+
+        let out = registry
+            .invoke("echo", json!({ "gene": "KRAS" }), &ctx, &cancel)
+            .await
+            .unwrap();
+        assert_eq!(out.result["limit"], 10);
+
+        let err = registry
+            .invoke(
+                "echo",
+                json!({ "limit": "all", "cancer": "PAAD" }),
+                &ctx,
+                &cancel,
+            )
+            .await
+            .unwrap_err();
+        let ToolError::InvalidParams { tool, violations } = &err else {
+            panic!("expected InvalidParams, got {err:?}");
+        };
+        assert_eq!(tool, "echo");
+        assert_eq!(violations.len(), 3, "{violations:?}");
+        assert!(err.to_string().contains("parameter schema"));
+
+        assert!(registry.validate_params("missing", json!({})).is_none());
+        assert!(matches!(
+            registry.validate_params("echo", json!({ "gene": "TP53" })),
+            Some(Ok(params)) if params["limit"] == 10
+        ));
+    }
+
+    #[tokio::test]
+    async fn calls_past_their_timeout_are_cancelled_and_counted() {
+        let sleepy = Arc::new(Sleepy {
+            running: Default::default(),
+            peak: Default::default(),
+            cancelled: Default::default(),
+        });
+        let registry = ToolRegistry::new();
+        registry.register_sync(sleepy.clone());
+        let ctx = JobContext::default();
+        let cancel = CancellationToken::new();
+
+        registry
+            .invoke("sleepy", json!({ "ms": 1 }), &ctx, &cancel)
+            .await
+            .unwrap();
+        let err = registry
+            .invoke("sleepy", json!({ "ms": 10_000 }), &ctx, &cancel)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, ToolError::TimedOut { tool, timeout } if tool == "sleepy" && *timeout == Duration::from_millis(100)),
+            "{err:?}"
+        );
+        // The spawned work saw the cancellation rather than sleeping on;
+        // the caller's token is left alone.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(sleepy.cancelled.load(std::sync::atomic::Ordering::SeqCst));
+        assert!(!cancel.is_cancelled());
+
+        let stats = registry.stats();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(
+            (stats[0].invocations, stats[0].successes, stats[0].timeouts),
+            (2, 1, 1)
+        );
+        assert_eq!(stats[0].errors, 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn calls_of_one_tool_wait_for_a_permit() {
+        use std::sync::atomic::Ordering;
+
+        let sleepy = Arc::new(Sleepy {
+            running: Default::default(),
+            peak: Default::default(),
+            cancelled: Default::default(),
+        });
+        let registry = Arc::new(ToolRegistry::new().with_limits(ToolLimits {
+            max_concurrent_per_tool: 2,
+            ..ToolLimits::default()
+        }));
+        registry.register_sync(sleepy.clone());
+
+        let calls: Vec<_> = (0..5)
+            .map(|_| {
+                let registry = Arc::clone(&registry);
+                tokio::spawn(async move {
+                    registry
+                        .invoke(
+                            "sleepy",
+                            json!({ "ms": 20 }),
+                            &JobContext::default(),
+                            &CancellationToken::new(),
+                        )
+                        .await
+                })
+            })
+            .collect();
+        for call in calls {
+            call.await.unwrap().unwrap();
+        }
+        assert_eq!(sleepy.peak.load(Ordering::SeqCst), 2);
+        assert!(!sleepy.cancelled.load(Ordering::SeqCst));
+        assert_eq!(registry.stats()[0].successes, 5);
+    }
+
+    #[tokio::test]
+    async fn calls_get_the_registry_sandbox() {
+        let workspace = tempfile::tempdir().unwrap();
+        let sandbox = Arc::new(
+            Sandbox::new(workspace.path())
+                .unwrap()
+                .with_max_job_bytes(10),
+        );
+        let ctx = JobContext::default();
+        let cancel = CancellationToken::new();
+        let write = |path: &str, bytes: u64| json!({ "path": path, "bytes": bytes });
+
+        let unsandboxed = ToolRegistry::new();
+        unsandboxed.register_sync(Arc::new(Writer));
+        let err = unsandboxed
+            .invoke("writer", write("a.txt", 1), &ctx, &cancel)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::Sandbox(_)), "{err:?}");
+
+        let registry = ToolRegistry::new()
+            .with_limits(ToolLimits::default())
+            .with_sandbox(Arc::clone(&sandbox))
+            .with_strict_params(true);
+        registry.register_sync(Arc::new(Writer));
+        let out = registry
+            .invoke("writer", write("a.txt", 6), &ctx, &cancel)
+            .await
+            .unwrap();
+        assert_eq!(out.result["path"], json!(sandbox.root().join("a.txt")));
+
+        for (path, bytes) in [("../escape.txt", 1), ("/tmp/escape.txt", 1), ("b.txt", 5)] {
+            let err = registry
+                .invoke("writer", write(path, bytes), &ctx, &cancel)
+                .await
+                .unwrap_err();
+            assert!(matches!(err, ToolError::Sandbox(_)), "{path}: {err:?}");
+        }
+        assert_eq!(sandbox.job_usage(&ctx.job_id.to_string()), 6);
+    }
+}
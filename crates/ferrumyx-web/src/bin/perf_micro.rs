@@ -1,7 +1,7 @@
 use ferrumyx_db::chunks::ChunkRepository;
 use ferrumyx_db::entities::EntityRepository;
 use ferrumyx_db::schema::{Chunk, Entity, EntityType};
-use ferrumyx_db::Database;
+use ferrumyx_db::{Database, VectorIndexParams, TABLE_CHUNKS};
 use ferrumyx_ingestion::repository::IngestionRepository;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -21,7 +21,9 @@ async fn main() -> anyhow::Result<()> {
 
     let db = Arc::new(Database::open(&db_path).await?);
     db.initialize().await?;
-    let _ = db.create_vector_index().await;
+    let _ = db
+        .create_vector_index(TABLE_CHUNKS, &VectorIndexParams::default())
+        .await;
 
     let entity_repo = EntityRepository::new(db.clone());
     let chunk_repo = ChunkRepository::new(db.clone());
@@ -92,6 +94,8 @@ async fn main() -> anyhow::Result<()> {
             created_at: chrono::Utc::now(),
             embedding,
             embedding_large: None,
+            char_start: None,
+            char_end: None,
         });
     }
     chunk_repo.insert_batch(&chunks).await?;
@@ -145,6 +149,8 @@ async fn main() -> anyhow::Result<()> {
                 created_at: chrono::Utc::now(),
                 embedding: None,
                 embedding_large: None,
+                char_start: None,
+                char_end: None,
             });
         }
         chunk_repo.insert_batch(&row_chunks).await?;
@@ -179,6 +185,8 @@ async fn main() -> anyhow::Result<()> {
                 created_at: chrono::Utc::now(),
                 embedding: None,
                 embedding_large: None,
+                char_start: None,
+                char_end: None,
             });
         }
         chunk_repo.insert_batch(&bulk_chunks).await?;
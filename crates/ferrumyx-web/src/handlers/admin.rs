@@ -0,0 +1,239 @@
+//! Database maintenance and snapshot endpoints.
+//!
+//! Compaction, index builds, orphan cleanup and snapshot imports rewrite
+//! tables, so `POST /api/admin/maintenance` and `/api/admin/snapshot/*` only
+//! run when the request carries the token set in `FERRUMYX_ADMIN_TOKEN`;
+//! without one configured the endpoints are disabled.
+
+use axum::{extract::State, Json};
+use serde::{Deserialize, Serialize};
+
+use crate::state::{AppEvent, SharedState};
+use ferrumyx_common::error::ApiError;
+use ferrumyx_db::{
+    CompactionReport, DbError, IndexStatus, OrphanCleanup, SnapshotImport, SnapshotImportMode,
+    SnapshotManifest, SnapshotProgress, VectorIndexParams, TABLE_CHUNKS,
+};
+
+pub const ADMIN_TOKEN_ENV: &str = "FERRUMYX_ADMIN_TOKEN";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaintenanceAction {
+    Compact,
+    VectorIndex,
+    CleanupOrphans,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MaintenanceRequest {
+    /// Must equal `FERRUMYX_ADMIN_TOKEN`.
+    pub confirm: String,
+    /// Run in the order given; list `cleanup_orphans` before `compact` so
+    /// compaction folds in the deletions.
+    pub actions: Vec<MaintenanceAction>,
+    /// Index parameters for `vector_index`; IVF-HNSW-SQ on
+    /// `chunks.embedding` when omitted.
+    #[serde(default)]
+    pub vector_index: Option<VectorIndexParams>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct MaintenanceResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub orphans: Option<OrphanCleanup>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compaction: Option<Vec<CompactionReport>>,
+    /// Every index after the run.
+    pub indices: Vec<IndexStatus>,
+}
+
+pub(crate) fn check_confirmation(provided: &str, expected: Option<&str>) -> Result<(), ApiError> {
+    let expected = expected
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .ok_or_else(|| {
+            ApiError::ServiceUnavailable(format!(
+                "maintenance is disabled; set {ADMIN_TOKEN_ENV} to enable it"
+            ))
+        })?;
+    if provided.trim() != expected {
+        return Err(ApiError::Forbidden(
+            "confirmation token does not match".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// POST /api/admin/maintenance — run the requested maintenance actions.
+pub async fn api_admin_maintenance(
+    State(state): State<SharedState>,
+    Json(req): Json<MaintenanceRequest>,
+) -> Result<Json<MaintenanceResponse>, ApiError> {
+    check_confirmation(&req.confirm, std::env::var(ADMIN_TOKEN_ENV).ok().as_deref())?;
+    if req.actions.is_empty() {
+        return Err(ApiError::BadRequest(
+            "actions must name at least one of compact, vector_index, cleanup_orphans".to_string(),
+        ));
+    }
+
+    let internal = |e: ferrumyx_db::DbError| ApiError::Internal(e.to_string());
+    let mut response = MaintenanceResponse::default();
+    for action in req.actions {
+        match action {
+            MaintenanceAction::CleanupOrphans => {
+                response.orphans = Some(state.db.cleanup_orphans().await.map_err(internal)?);
+            }
+            MaintenanceAction::Compact => {
+                response.compaction = Some(state.db.compact_all().await.map_err(internal)?);
+            }
+            MaintenanceAction::VectorIndex => {
+                let params = req.vector_index.clone().unwrap_or_default();
+                state
+                    .db
+                    .create_vector_index(TABLE_CHUNKS, &params)
+                    .await
+                    .map_err(internal)?;
+            }
+        }
+    }
+    response.indices = state.db.index_status().await.map_err(internal)?;
+    Ok(Json(response))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SnapshotExportRequest {
+    /// Must equal `FERRUMYX_ADMIN_TOKEN`.
+    pub confirm: String,
+    /// Archive to write on the server, conventionally `*.tar.zst`.
+    pub path: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SnapshotImportRequest {
+    /// Must equal `FERRUMYX_ADMIN_TOKEN`.
+    pub confirm: String,
+    /// Archive on the server written by a snapshot export.
+    pub path: String,
+    /// `merge` (default) or `replace`.
+    #[serde(default)]
+    pub mode: SnapshotImportMode,
+}
+
+fn snapshot_path(path: &str) -> Result<&str, ApiError> {
+    let path = path.trim();
+    if path.is_empty() {
+        return Err(ApiError::BadRequest("path must not be empty".to_string()));
+    }
+    Ok(path)
+}
+
+fn snapshot_error(e: DbError) -> ApiError {
+    match e {
+        DbError::InvalidQuery(_) | DbError::DimensionMismatch { .. } | DbError::Io(_) => {
+            ApiError::BadRequest(e.to_string())
+        }
+        e => ApiError::Internal(e.to_string()),
+    }
+}
+
+/// Forwards snapshot progress to the SSE stream.
+fn progress_events(state: &SharedState) -> impl Fn(SnapshotProgress) + Send + Sync {
+    let tx = state.event_tx.clone();
+    move |p| {
+        let _ = tx.send(AppEvent::SnapshotProgress {
+            operation: p.operation.to_string(),
+            table: p.table,
+            rows: p.rows,
+            total: p.total,
+        });
+    }
+}
+
+/// POST /api/admin/snapshot/export — write the knowledge base to an
+/// archive, streaming `snapshot_progress` events.
+pub async fn api_admin_snapshot_export(
+    State(state): State<SharedState>,
+    Json(req): Json<SnapshotExportRequest>,
+) -> Result<Json<SnapshotManifest>, ApiError> {
+    check_confirmation(&req.confirm, std::env::var(ADMIN_TOKEN_ENV).ok().as_deref())?;
+    let path = snapshot_path(&req.path)?;
+    let manifest = ferrumyx_db::export_snapshot_with_progress(
+        state.db.clone(),
+        path,
+        &progress_events(&state),
+    )
+    .await
+    .map_err(snapshot_error)?;
+    tracing::info!(path, tables = manifest.tables.len(), "Snapshot exported");
+    Ok(Json(manifest))
+}
+
+/// POST /api/admin/snapshot/import — load an archive, replacing or merging
+/// into the stored knowledge base, streaming `snapshot_progress` events.
+pub async fn api_admin_snapshot_import(
+    State(state): State<SharedState>,
+    Json(req): Json<SnapshotImportRequest>,
+) -> Result<Json<SnapshotImport>, ApiError> {
+    check_confirmation(&req.confirm, std::env::var(ADMIN_TOKEN_ENV).ok().as_deref())?;
+    let path = snapshot_path(&req.path)?;
+    let report = ferrumyx_db::import_snapshot_with_progress(
+        state.db.clone(),
+        path,
+        req.mode,
+        &progress_events(&state),
+    )
+    .await
+    .map_err(snapshot_error)?;
+    Ok(Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maintenance_requires_the_configured_token() {
+        assert!(matches!(
+            check_confirmation("anything", None),
+            Err(ApiError::ServiceUnavailable(_))
+        ));
+        assert!(matches!(
+            check_confirmation("anything", Some("  ")),
+            Err(ApiError::ServiceUnavailable(_))
+        ));
+        assert!(matches!(
+            check_confirmation("wrong", Some("s3cret")),
+            Err(ApiError::Forbidden(_))
+        ));
+        assert!(check_confirmation("s3cret", Some("s3cret\n")).is_ok());
+
+        let req: MaintenanceRequest = serde_json::from_str(
+            r#"{"confirm": "s3cret", "actions": ["cleanup_orphans", "vector_index"],
+                "vector_index": {"kind": "ivf_pq", "num_partitions": 64}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            req.actions,
+            [
+                MaintenanceAction::CleanupOrphans,
+                MaintenanceAction::VectorIndex
+            ]
+        );
+        let params = req.vector_index.unwrap();
+        assert_eq!(params.num_partitions, Some(64));
+        assert_eq!(params.column, "embedding");
+    }
+
+    #[test]
+    fn snapshot_import_defaults_to_merge() {
+        let req: SnapshotImportRequest =
+            serde_json::from_str(r#"{"confirm": "t", "path": "kb.tar.zst"}"#).unwrap();
+        assert_eq!(req.mode, SnapshotImportMode::Merge);
+        let req: SnapshotImportRequest =
+            serde_json::from_str(r#"{"confirm": "t", "path": "kb.tar.zst", "mode": "replace"}"#)
+                .unwrap();
+        assert_eq!(req.mode, SnapshotImportMode::Replace);
+        assert!(matches!(snapshot_path("  "), Err(ApiError::BadRequest(_))));
+    }
+}
@@ -0,0 +1,403 @@
+//! Grounded question answering over the corpus.
+//! Retrieves chunks with hybrid search, packs them into a numbered prompt
+//! within a token budget, answers with the backend the data class allows,
+//! and returns the answer with citations back to the papers.
+
+use std::collections::BTreeSet;
+
+use axum::{extract::State, response::IntoResponse, Json};
+use serde::{Deserialize, Serialize};
+use tokio_stream::StreamExt;
+
+use crate::handlers::search::hybrid_chunk_hits;
+use crate::llm::{CallOptions, DataClass, RoutingError, StreamEvent};
+use crate::state::{AppEvent, SharedState};
+use ferrumyx_common::error::ApiError;
+use ferrumyx_db::papers::PaperRepository;
+
+const DEFAULT_TOP_K: usize = 8;
+const MAX_TOP_K: usize = 50;
+const DEFAULT_MAX_PROMPT_TOKENS: usize = 3000;
+const MAX_PROMPT_TOKENS_ENV: &str = "FERRUMYX_ANSWER_MAX_PROMPT_TOKENS";
+const MAX_ANSWER_TOKENS: u32 = 800;
+/// A chunk cut to fewer tokens than this is dropped instead.
+const MIN_CHUNK_TOKENS: usize = 48;
+
+const SYSTEM_PROMPT: &str = "You are a biomedical research assistant. Answer the question using \
+only the numbered sources. Cite every claim inline with the source number, e.g. [2]. If the \
+sources do not answer the question, say so.";
+
+#[derive(Debug, Deserialize)]
+pub struct AnswerRequest {
+    pub question: String,
+    /// Chunks to retrieve before packing; 8 when absent.
+    pub top_k: Option<usize>,
+    #[serde(default)]
+    pub data_class: DataClass,
+    /// Prompt budget; `FERRUMYX_ANSWER_MAX_PROMPT_TOKENS` when absent.
+    pub max_prompt_tokens: Option<usize>,
+    /// Id to tag streamed `answer_token` events with, so a page can
+    /// subscribe before posting; generated when absent.
+    pub answer_id: Option<String>,
+    /// Ask the backend again even if the response cache has this prompt.
+    #[serde(default)]
+    pub bypass_cache: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Citation {
+    /// Source number used in the answer text.
+    pub index: usize,
+    pub chunk_id: String,
+    pub paper_id: String,
+    pub title: Option<String>,
+    pub doi: Option<String>,
+    pub pmid: Option<String>,
+    pub similarity: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AnswerResponse {
+    pub answer_id: String,
+    pub question: String,
+    pub answer: String,
+    /// Model and backend that served the answer; empty when no source
+    /// matched and no backend was called.
+    pub model: String,
+    pub backend: String,
+    /// `backend: reason` for each backend skipped or failed before it.
+    pub failovers: Vec<String>,
+    pub data_class: DataClass,
+    /// Sources the answer cites.
+    pub citations: Vec<Citation>,
+    /// Chunks packed into the prompt.
+    pub context_chunks: usize,
+    /// Retrieved chunks left out to stay within the prompt budget.
+    pub dropped_chunks: usize,
+    /// Estimated prompt size.
+    pub prompt_tokens: usize,
+    /// Answer replayed from the response cache.
+    pub cached: bool,
+}
+
+/// A retrieved chunk with the paper it came from.
+#[derive(Debug, Clone)]
+pub(crate) struct ContextChunk {
+    pub chunk_id: uuid::Uuid,
+    pub paper_id: uuid::Uuid,
+    pub title: Option<String>,
+    pub doi: Option<String>,
+    pub pmid: Option<String>,
+    pub content: String,
+    pub similarity: f64,
+}
+
+/// POST /api/query/answer - Answer a question from the indexed literature
+///
+/// `INTERNAL` and `CONFIDENTIAL` questions are only sent to a local backend.
+/// Answer text is pushed to `/api/events` as `answer_token` events while it
+/// is generated, and the router writes every call to the `llm_audit` table.
+pub async fn api_query_answer(
+    State(state): State<SharedState>,
+    Json(req): Json<AnswerRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let question = req.question.trim().to_string();
+    if question.is_empty() {
+        return Err(ApiError::BadRequest(
+            "question must not be empty".to_string(),
+        ));
+    }
+    state.llm.check(req.data_class).map_err(|e| {
+        tracing::warn!("Blocked answer request: {}", e);
+        let _ = state.event_tx.send(AppEvent::Notification {
+            level: "warning".to_string(),
+            message: format!("Blocked LLM call: {e}"),
+        });
+        ApiError::Conflict(e.to_string())
+    })?;
+    let answer_id = req
+        .answer_id
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let top_k = req.top_k.unwrap_or(DEFAULT_TOP_K).clamp(1, MAX_TOP_K);
+    let budget = req.max_prompt_tokens.unwrap_or_else(max_prompt_tokens);
+
+    let hits = hybrid_chunk_hits(&state, &question, top_k).await;
+    let paper_ids: Vec<uuid::Uuid> = hits.iter().map(|h| h.paper_id).collect();
+    let references = PaperRepository::new(state.db.clone())
+        .find_references_by_ids(&paper_ids)
+        .await
+        .unwrap_or_default();
+    let retrieved: Vec<ContextChunk> = hits
+        .into_iter()
+        .map(|h| {
+            let reference = references.get(&h.paper_id);
+            ContextChunk {
+                chunk_id: h.chunk_id,
+                paper_id: h.paper_id,
+                title: reference.map(|r| r.title.clone()),
+                doi: reference.and_then(|r| r.doi.clone()),
+                pmid: reference.and_then(|r| r.pmid.clone()),
+                content: h.content,
+                similarity: h.score as f64,
+            }
+        })
+        .collect();
+    let retrieved_count = retrieved.len();
+    let context = pack_context(&question, retrieved, budget);
+    let dropped_chunks = retrieved_count - context.len();
+    let prompt = build_prompt(&question, &context);
+    let prompt_tokens = estimate_tokens(SYSTEM_PROMPT) + estimate_tokens(&prompt);
+
+    if context.is_empty() {
+        return Ok(Json(AnswerResponse {
+            answer_id,
+            question,
+            answer: "No indexed literature matches this question.".to_string(),
+            model: String::new(),
+            backend: String::new(),
+            failovers: Vec::new(),
+            data_class: req.data_class,
+            citations: Vec::new(),
+            context_chunks: 0,
+            dropped_chunks,
+            prompt_tokens: 0,
+            cached: false,
+        }));
+    }
+
+    let mut stream = state.llm.complete_stream(
+        req.data_class,
+        SYSTEM_PROMPT.to_string(),
+        prompt.clone(),
+        MAX_ANSWER_TOKENS,
+        CallOptions {
+            bypass_cache: req.bypass_cache,
+            caller: Some("answer".to_string()),
+            session_id: Some(answer_id.clone()),
+            chunk_ids: context.iter().map(|c| c.chunk_id).collect(),
+        },
+    );
+    let mut routed = None;
+    while let Some(event) = stream.next().await {
+        match event {
+            StreamEvent::Token(token) => {
+                let _ = state.event_tx.send(AppEvent::AnswerToken {
+                    answer_id: answer_id.clone(),
+                    token,
+                });
+            }
+            StreamEvent::Done(result) => routed = Some(*result),
+        }
+    }
+    let routed = routed
+        .ok_or_else(|| ApiError::Internal("LLM stream ended without a result".to_string()))?;
+    let routed = routed.map_err(|e| match e {
+        RoutingError::BudgetExceeded { .. } | RoutingError::RateLimited { .. } => {
+            let _ = state.event_tx.send(AppEvent::Notification {
+                level: "warning".to_string(),
+                message: format!("Blocked LLM call: {e}"),
+            });
+            ApiError::TooManyRequests(e.to_string())
+        }
+        e => ApiError::Internal(e.to_string()),
+    })?;
+    if let Some(alert) = routed.budget_alert {
+        tracing::warn!(
+            "LLM spend today is ${:.2}, over the ${:.2} alert threshold",
+            alert.spent_usd,
+            alert.threshold_usd
+        );
+        let _ = state.event_tx.send(AppEvent::LlmBudgetAlert {
+            spent_usd: alert.spent_usd,
+            threshold_usd: alert.threshold_usd,
+            limit_usd: alert.limit_usd,
+        });
+    }
+    let generation = routed.generation;
+
+    let citations = cited_indices(&generation.text, context.len())
+        .into_iter()
+        .map(|index| {
+            let chunk = &context[index - 1];
+            Citation {
+                index,
+                chunk_id: chunk.chunk_id.to_string(),
+                paper_id: chunk.paper_id.to_string(),
+                title: chunk.title.clone(),
+                doi: chunk.doi.clone(),
+                pmid: chunk.pmid.clone(),
+                similarity: chunk.similarity,
+            }
+        })
+        .collect();
+    let _ = state.event_tx.send(AppEvent::AnswerComplete {
+        answer_id: answer_id.clone(),
+        backend: routed.backend.clone(),
+        model: routed.model.clone(),
+        prompt_tokens: generation.prompt_tokens,
+        completion_tokens: generation.completion_tokens,
+    });
+
+    Ok(Json(AnswerResponse {
+        answer_id,
+        question,
+        answer: generation.text,
+        model: routed.model,
+        backend: routed.backend,
+        failovers: routed.failovers,
+        data_class: req.data_class,
+        citations,
+        context_chunks: context.len(),
+        dropped_chunks,
+        prompt_tokens,
+        cached: routed.cached,
+    }))
+}
+
+fn max_prompt_tokens() -> usize {
+    std::env::var(MAX_PROMPT_TOKENS_ENV)
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(DEFAULT_MAX_PROMPT_TOKENS)
+}
+
+/// Rough token count, about four characters per token for English text.
+pub(crate) fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Keep the most relevant `chunks` (already in relevance order) whose
+/// sources fit in `budget` prompt tokens alongside the question. The first
+/// chunk that does not fit is cut to the remaining space, and everything
+/// less relevant is dropped.
+pub(crate) fn pack_context(
+    question: &str,
+    chunks: Vec<ContextChunk>,
+    budget: usize,
+) -> Vec<ContextChunk> {
+    let mut remaining = budget
+        .saturating_sub(estimate_tokens(SYSTEM_PROMPT))
+        .saturating_sub(estimate_tokens(&build_prompt(question, &[])));
+    let mut packed = Vec::new();
+    for mut chunk in chunks {
+        let header = estimate_tokens(&source_header(packed.len() + 1, &chunk));
+        // One more token for the blank line after the source.
+        let cost = header + estimate_tokens(&chunk.content) + 1;
+        if cost <= remaining {
+            remaining -= cost;
+            packed.push(chunk);
+            continue;
+        }
+        let room = remaining.saturating_sub(header + 1);
+        if room >= MIN_CHUNK_TOKENS {
+            chunk.content = chunk.content.chars().take(room * 4).collect();
+            packed.push(chunk);
+        }
+        break;
+    }
+    packed
+}
+
+fn source_header(index: usize, chunk: &ContextChunk) -> String {
+    let mut header = format!("[{index}] {}", chunk.title.as_deref().unwrap_or("Untitled"));
+    if let Some(doi) = &chunk.doi {
+        header.push_str(&format!(" (doi:{doi})"));
+    } else if let Some(pmid) = &chunk.pmid {
+        header.push_str(&format!(" (PMID:{pmid})"));
+    }
+    header.push('\n');
+    header
+}
+
+fn build_prompt(question: &str, context: &[ContextChunk]) -> String {
+    let mut prompt = String::from("Sources:\n\n");
+    for (i, chunk) in context.iter().enumerate() {
+        prompt.push_str(&source_header(i + 1, chunk));
+        prompt.push_str(&chunk.content);
+        prompt.push_str("\n\n");
+    }
+    prompt.push_str("Question: ");
+    prompt.push_str(question);
+    prompt.push_str("\nAnswer with inline citations:");
+    prompt
+}
+
+/// Source numbers `[n]` (or `[n, m]`) cited in `answer`, in `1..=sources`.
+fn cited_indices(answer: &str, sources: usize) -> BTreeSet<usize> {
+    let mut cited = BTreeSet::new();
+    for group in answer.split('[').skip(1) {
+        let Some((inner, _)) = group.split_once(']') else {
+            continue;
+        };
+        for n in inner
+            .split([',', ';'])
+            .filter_map(|n| n.trim().parse::<usize>().ok())
+        {
+            if (1..=sources).contains(&n) {
+                cited.insert(n);
+            }
+        }
+    }
+    cited
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(title: &str, words: usize) -> ContextChunk {
+        ContextChunk {
+            chunk_id: uuid::Uuid::new_v4(),
+            paper_id: uuid::Uuid::new_v4(),
+            title: Some(title.to_string()),
+            doi: Some(format!("10.1000/{title}")),
+            pmid: None,
+            content: vec!["KRAS"; words].join(" "),
+            similarity: 1.0,
+        }
+    }
+
+    #[test]
+    fn packing_drops_the_least_relevant_chunks_first() {
+        let question = "Which KRAS alleles respond to sotorasib?";
+        let chunks = vec![chunk("a", 200), chunk("b", 200), chunk("c", 200)];
+        let overhead =
+            estimate_tokens(SYSTEM_PROMPT) + estimate_tokens(&build_prompt(question, &[]));
+
+        let all = pack_context(question, chunks.clone(), 10_000);
+        assert_eq!(all.len(), 3);
+
+        // Room for the first chunk and part of the second.
+        let first = estimate_tokens(&source_header(1, &chunks[0]))
+            + estimate_tokens(&chunks[0].content)
+            + 1;
+        let packed = pack_context(question, chunks.clone(), overhead + first + 150);
+        assert_eq!(packed.len(), 2);
+        assert_eq!(packed[0].content, chunks[0].content);
+        assert_eq!(packed[1].chunk_id, chunks[1].chunk_id);
+        assert!(packed[1].content.len() < chunks[1].content.len());
+        let prompt = build_prompt(question, &packed);
+        assert!(
+            estimate_tokens(SYSTEM_PROMPT) + estimate_tokens(&prompt) <= overhead + first + 150
+        );
+
+        // Too little room left to be worth cutting the second chunk down.
+        let packed = pack_context(question, chunks.clone(), overhead + first + 20);
+        assert_eq!(packed.len(), 1);
+        assert!(pack_context(question, chunks, overhead).is_empty());
+    }
+
+    #[test]
+    fn cited_indices_reads_inline_markers_within_range() {
+        let answer =
+            "Sotorasib targets G12C [1]. G12D does not respond [2, 3]; see also [9] and [x].";
+        assert_eq!(
+            cited_indices(answer, 3).into_iter().collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert!(cited_indices("No sources apply.", 3).is_empty());
+    }
+}
@@ -2,55 +2,21 @@
 
 use crate::state::SharedState;
 use axum::{extract::State, response::Html};
-use ferrumyx_db::chunks::ChunkRepository;
 use ferrumyx_db::entities::EntityRepository;
-use ferrumyx_db::kg_facts::KgFactRepository;
-use ferrumyx_db::papers::PaperRepository;
 use ferrumyx_db::target_scores::TargetScoreRepository;
 
 /// Navigation HTML template shared across all pages
 pub const NAV_HTML: &str = include_str!("../../templates/nav.html");
 
 pub async fn dashboard(State(state): State<SharedState>) -> Html<String> {
-    // Query DB for summary stats using repositories
-    let paper_count: u64 = PaperRepository::new(state.db.clone())
-        .count()
-        .await
-        .unwrap_or(0);
-
-    let chunk_count: u64 = ChunkRepository::new(state.db.clone())
-        .count()
-        .await
-        .unwrap_or(0);
-
-    let entity_count: u64 = EntityRepository::new(state.db.clone())
-        .count()
-        .await
-        .unwrap_or(0);
-
-    let kg_fact_count: u64 = KgFactRepository::new(state.db.clone())
-        .count()
-        .await
-        .unwrap_or(0);
-
     let top_targets: Vec<(String, String, f64)> = load_top_targets(&state).await;
 
-    Html(render_dashboard(
-        paper_count,
-        chunk_count,
-        entity_count,
-        kg_fact_count,
-        top_targets,
-    ))
+    Html(render_dashboard(top_targets))
 }
 
-fn render_dashboard(
-    papers: u64,
-    chunks: u64,
-    entities: u64,
-    facts: u64,
-    top_targets: Vec<(String, String, f64)>,
-) -> String {
+/// Renders the page; the stat tiles are filled in from
+/// `/api/metrics/summary` by the page script.
+fn render_dashboard(top_targets: Vec<(String, String, f64)>) -> String {
     let targets_html = if top_targets.is_empty() {
         r#"<tr><td colspan="5" class="text-center text-muted">No targets scored yet. Run ingestion to populate the knowledge graph.</td></tr>"#.to_string()
     } else {
@@ -113,28 +79,28 @@ fn render_dashboard(
             <div class="stat-icon">
                 <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24"><path d="M14 2H6c-1.1 0-1.99.9-1.99 2L4 20c0 1.1.89 2 1.99 2H18c1.1 0 2-.9 2-2V8l-6-6zm2 16H8v-2h8v2zm0-4H8v-2h8v2zm-3-5V3.5L18.5 9H13z"/></svg>
             </div>
-            <div class="stat-value">{}</div>
+            <div class="stat-value" data-metric="papers">—</div>
             <div class="stat-label">Scientific Papers</div>
         </div>
         <div class="stat-card">
             <div class="stat-icon">
                 <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24"><path d="M4 6H2v14c0 1.1.9 2 2 2h14v-2H4V6zm16-4H8c-1.1 0-2 .9-2 2v12c0 1.1.9 2 2 2h12c1.1 0 2-.9 2-2V4c0-1.1-.9-2-2-2zm-1 9H9V9h10v2zm-4 4H9v-2h6v2zm4-8H9V5h10v2z"/></svg>
             </div>
-            <div class="stat-value">{}</div>
+            <div class="stat-value" data-metric="chunks">—</div>
             <div class="stat-label">Vector Chunks</div>
         </div>
         <div class="stat-card">
             <div class="stat-icon">
                 <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24"><path d="M12 2C6.48 2 2 6.48 2 12s4.48 10 10 10 10-4.48 10-10S17.52 2 12 2zm1 15h-2v-2h2v2zm0-4h-2V7h2v6z"/></svg>
             </div>
-            <div class="stat-value">{}</div>
+            <div class="stat-value" data-metric="entities">—</div>
             <div class="stat-label">Bio-Entities Extracted</div>
         </div>
         <div class="stat-card">
             <div class="stat-icon">
                 <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24"><path d="M16 11c1.66 0 2.99-1.34 2.99-3S17.66 5 16 5c-1.66 0-3 1.34-3 3v.15l-3.32 1.62A2.97 2.97 0 0 0 8 9c-1.66 0-3 1.34-3 3s1.34 3 3 3c.6 0 1.15-.18 1.61-.48l3.36 1.64c-.01.12-.04.24-.04.37 0 1.66 1.34 3 3 3s3-1.34 3-3-1.34-3-3-3c-.62 0-1.18.19-1.64.5l-3.32-1.62C10.96 12.15 11 12.04 11 11.91V11.9z"/></svg>
             </div>
-            <div class="stat-value">{}</div>
+            <div class="stat-value" data-metric="kg_facts">—</div>
             <div class="stat-label">KG Relations</div>
         </div>
     </div>
@@ -192,9 +158,25 @@ fn render_dashboard(
         </section>
     </div>
 </main>
+<script>
+async function refreshStats() {{
+  try {{
+    const res = await fetch('/api/metrics/summary');
+    const data = await res.json();
+    for (const m of data.metrics || []) {{
+      const el = document.querySelector(`[data-metric="${{m.metric}}"]`);
+      if (el) el.textContent = Number(m.value || 0).toLocaleString();
+    }}
+  }} catch (_) {{}}
+}}
+document.addEventListener('DOMContentLoaded', () => {{
+  refreshStats();
+  setInterval(refreshStats, 60000);
+}});
+</script>
 </body>
 </html>"#,
-        NAV_HTML, papers, chunks, entities, facts, targets_html
+        NAV_HTML, targets_html
     )
 }
 
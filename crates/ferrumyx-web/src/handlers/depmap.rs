@@ -6,8 +6,15 @@ use axum::{
     extract::{Query, State},
     response::{Html, IntoResponse, Json},
 };
-use ferrumyx_ranker::depmap_provider::DepMapClientAdapter;
+use ferrumyx_common::error::ApiError;
+use ferrumyx_ranker::providers::depmap::{
+    download_progress, CoDependencies, DepMapRelease, MIN_CODEPENDENCY_CELL_LINES,
+};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+const DEFAULT_CODEPENDENCY_TOP_N: usize = 25;
+const MAX_CODEPENDENCY_TOP_N: usize = 200;
 
 #[derive(Deserialize)]
 pub struct DepMapFilter {
@@ -15,6 +22,15 @@ pub struct DepMapFilter {
     pub cancer_type: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub struct CoDependencyQuery {
+    pub gene: Option<String>,
+    /// Genes to return per sign.
+    pub top_n: Option<usize>,
+    /// Fewest cell lines a pair must share.
+    pub min_cell_lines: Option<usize>,
+}
+
 #[derive(Serialize)]
 pub struct DepMapGeneStats {
     pub gene: String,
@@ -26,6 +42,20 @@ pub struct DepMapGeneStats {
     pub essential_count: i64,
     pub selective_count: i64,
     pub non_essential_count: i64,
+    /// DepMap release the scores came from.
+    pub release: Option<DepMapRelease>,
+}
+
+/// Load state of the shared DepMap client.
+#[derive(Debug, Serialize)]
+pub struct DepMapStatus {
+    pub loaded: bool,
+    pub genes: usize,
+    pub cell_lines: usize,
+    /// Release tag from the manifest, e.g. "24Q4".
+    pub release: Option<String>,
+    /// Loads started since the server came up, including failed ones.
+    pub load_attempts: usize,
 }
 
 #[derive(Serialize)]
@@ -44,7 +74,7 @@ pub async fn depmap_page(State(_state): State<SharedState>) -> Html<String> {
 
 /// GET /api/depmap/gene/{gene} — Get DepMap stats for a gene
 pub async fn api_depmap_gene(
-    State(_state): State<SharedState>,
+    State(state): State<SharedState>,
     Query(filter): Query<DepMapFilter>,
 ) -> impl IntoResponse {
     let gene = filter.gene.as_deref().unwrap_or("").trim();
@@ -60,10 +90,12 @@ pub async fn api_depmap_gene(
         essential_count: 0,
         selective_count: 0,
         non_essential_count: 0,
+        release: None,
     };
 
     if !gene.is_empty() && !cancer_type.is_empty() {
-        if let Ok(depmap) = DepMapClientAdapter::init().await {
+        if let Ok(depmap) = state.depmap.get().await {
+            stats.release = depmap.client().release_info().cloned();
             let scores = depmap.client().get_gene_scores(gene, cancer_type);
             if !scores.is_empty() {
                 let mut sorted = scores.clone();
@@ -106,6 +138,72 @@ pub async fn api_depmap_gene(
     Json(stats)
 }
 
+/// GET /api/depmap/release — Release tag, download time and checksums of the cached data
+pub async fn api_depmap_release(State(state): State<SharedState>) -> impl IntoResponse {
+    let release = match state.depmap.get().await {
+        Ok(depmap) => depmap.client().release_info().cloned(),
+        Err(_) => None,
+    };
+    Json(release)
+}
+
+/// GET /api/depmap/status — Whether the shared DepMap client is loaded, without loading it
+pub async fn api_depmap_status(State(state): State<SharedState>) -> impl IntoResponse {
+    let client = state.depmap.loaded().map(|d| d.client());
+    Json(DepMapStatus {
+        loaded: client.is_some(),
+        genes: client.map_or(0, |c| c.gene_count()),
+        cell_lines: client.map_or(0, |c| c.cell_line_count()),
+        release: client
+            .and_then(|c| c.release_info())
+            .map(|r| r.release.clone()),
+        load_attempts: state.depmap.load_attempts(),
+    })
+}
+
+/// GET /api/depmap/download — Progress of the current DepMap download, for the ingestion monitor
+pub async fn api_depmap_download(State(_state): State<SharedState>) -> impl IntoResponse {
+    let progress = download_progress().borrow().clone();
+    Json(progress)
+}
+
+/// GET /api/depmap/codependency — Genes whose knockout effect tracks or mirrors a gene's across cell lines
+pub async fn api_depmap_codependency(
+    State(state): State<SharedState>,
+    Query(query): Query<CoDependencyQuery>,
+) -> Result<Json<CoDependencies>, ApiError> {
+    let gene = query
+        .gene
+        .as_deref()
+        .map(str::trim)
+        .filter(|g| !g.is_empty())
+        .ok_or_else(|| ApiError::BadRequest("gene is required".to_string()))?
+        .to_string();
+    let top_n = query
+        .top_n
+        .unwrap_or(DEFAULT_CODEPENDENCY_TOP_N)
+        .clamp(1, MAX_CODEPENDENCY_TOP_N);
+    let min_cell_lines = query.min_cell_lines.unwrap_or(MIN_CODEPENDENCY_CELL_LINES);
+    state
+        .depmap
+        .get()
+        .await
+        .map_err(|e| ApiError::ServiceUnavailable(format!("DepMap data unavailable: {e:#}")))?;
+
+    // One sweep of the whole gene effect matrix; keep it off the runtime.
+    let depmap = Arc::clone(&state.depmap);
+    let codependencies = tokio::task::spawn_blocking(move || {
+        depmap
+            .loaded()
+            .and_then(|d| d.client().get_codependencies(&gene, top_n, min_cell_lines))
+            .ok_or(gene)
+    })
+    .await
+    .map_err(|e| ApiError::Internal(format!("co-dependency scan panicked: {e}")))?
+    .map_err(|gene| ApiError::NotFound(format!("Gene {gene} is not in the DepMap release")))?;
+    Ok(Json(codependencies))
+}
+
 /// GET /api/depmap/celllines — Get cell line data
 pub async fn api_depmap_celllines(
     State(_state): State<SharedState>,
@@ -336,3 +434,64 @@ fn render_depmap_page(_stats: Option<DepMapGeneStats>, _error: Option<String>) -
         NAV_HTML
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{AppState, SharedDepMap};
+    use std::sync::Arc;
+
+    const MODEL_CSV: &str = "ModelID,CellLineName,OncotreeLineage,OncotreeCode\n\
+ACH-000001,MIA PaCa-2,Pancreas,PAAD\n\
+ACH-000002,PANC-1,Pancreas,PAAD\n\
+ACH-000003,A549,Lung,LUAD\n";
+
+    const GENE_EFFECT_CSV: &str = ",KRAS (3845),TP53 (7157)\n\
+ACH-000001,-1.2,-0.1\n\
+ACH-000002,-0.8,-0.3\n\
+ACH-000003,-0.5,-0.2\n";
+
+    async fn fixture_state() -> (SharedState, std::path::PathBuf) {
+        let dir =
+            std::env::temp_dir().join(format!("ferrumyx-web-depmap-{}", uuid::Uuid::new_v4()));
+        let depmap_dir = dir.join("depmap");
+        std::fs::create_dir_all(&depmap_dir).unwrap();
+        std::fs::write(depmap_dir.join("Model.csv"), MODEL_CSV).unwrap();
+        std::fs::write(depmap_dir.join("CRISPRGeneEffect.csv"), GENE_EFFECT_CSV).unwrap();
+
+        let db = ferrumyx_db::Database::open(&dir.join("db")).await.unwrap();
+        let state = AppState::new(Arc::new(db))
+            .with_depmap(Arc::new(SharedDepMap::with_data_dir(depmap_dir)));
+        (Arc::new(state), dir)
+    }
+
+    async fn status(state: &SharedState) -> serde_json::Value {
+        let response = api_depmap_status(State(state.clone()))
+            .await
+            .into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn concurrent_first_requests_share_one_load() {
+        let (state, dir) = fixture_state().await;
+        assert_eq!(status(&state).await["loaded"], false);
+
+        let first = tokio::spawn(api_depmap_release(State(state.clone())));
+        let second = tokio::spawn(api_depmap_release(State(state.clone())));
+        let (first, second) = (first.await.unwrap(), second.await.unwrap());
+        assert!(first.into_response().status().is_success());
+        assert!(second.into_response().status().is_success());
+
+        let status = status(&state).await;
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(status["loaded"], true);
+        assert_eq!(status["load_attempts"], 1);
+        assert_eq!(status["genes"], 2);
+        assert_eq!(status["cell_lines"], 3);
+        assert_eq!(status["release"], "unknown");
+    }
+}
@@ -1,17 +1,29 @@
 //! Ingestion pipeline monitor and trigger — wired to real pipeline.
 
-use axum::{extract::State, response::Html, Form};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{Html, IntoResponse},
+    Form, Json,
+};
 use serde::Deserialize;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
+use ferrumyx_common::error::ApiError;
+use ferrumyx_db::failed_items::FailedItemRepository;
+use ferrumyx_db::papers::PaperRepository;
+use ferrumyx_db::FailedItem;
 use ferrumyx_ingestion::embedding::{
-    EmbeddingBackend as IngestionEmbeddingBackend, EmbeddingConfig as IngestionEmbeddingConfig,
+    embed_missing_chunks, EmbeddingBackend as IngestionEmbeddingBackend,
+    EmbeddingConfig as IngestionEmbeddingConfig,
 };
-use ferrumyx_ingestion::pipeline::{run_ingestion, IngestionJob, IngestionSourceSpec};
+use ferrumyx_ingestion::pipeline::{retry_failed_items, IngestionJob, IngestionSourceSpec};
 use ferrumyx_ingestion::repository::IngestionRepository;
+use ferrumyx_ingestion::scheduler::{RunStart, RunTrigger, ScheduleStatus};
 
 use crate::handlers::dashboard::NAV_HTML;
+use crate::jobs::CancelOutcome;
 use crate::state::{AppEvent, SharedState};
 
 // ── Form input ────────────────────────────────────────────────────────────────
@@ -36,6 +48,8 @@ pub struct IngestionForm {
     pub embed_api_key: Option<String>,
     pub embed_model: Option<String>,
     pub enable_scihub: Option<String>,
+    /// Only search for papers added since the last run of the same query.
+    pub only_new: Option<String>,
 }
 
 // ── Handlers ──────────────────────────────────────────────────────────────────
@@ -98,54 +112,23 @@ pub async fn ingestion_run(
         full_text_prefetch_workers: None,
         source_cache_enabled: true,
         source_cache_ttl_secs: Some(30 * 60),
+        memory_budget: Default::default(),
+        repro_seed: None,
+        since: None,
+        incremental: form.only_new.as_deref() == Some("on"),
     };
 
-    // Emit SSE start event immediately
+    let record = state.jobs.start(job, state.event_tx.clone());
     let _ = state.event_tx.send(AppEvent::PipelineStatus {
         stage: "search".to_string(),
         message: format!(
             "Starting ingestion: {} {} in {}",
-            job.gene,
-            job.mutation.as_deref().unwrap_or(""),
-            job.cancer_type
+            record.gene,
+            record.mutation.as_deref().unwrap_or(""),
+            record.cancer_type
         ),
         count: 0,
-    });
-
-    // Spawn ingestion in background task so we can return immediately
-    let event_tx = state.event_tx.clone();
-    let db = state.db.clone();
-
-    tokio::spawn(async move {
-        let repo = Arc::new(IngestionRepository::new(db));
-
-        // Update progress before starting
-        let _ = event_tx.send(AppEvent::PipelineStatus {
-            stage: "searching".to_string(),
-            message: "Searching PubMed and Europe PMC...".to_string(),
-            count: 0,
-        });
-
-        let result = run_ingestion(job, repo, None).await;
-
-        // Emit SSE completion events
-        let _ = event_tx.send(AppEvent::PipelineStatus {
-            stage: "complete".to_string(),
-            message: format!(
-                "Ingestion complete — {} papers found, {} inserted, {} chunks",
-                result.papers_found, result.papers_inserted, result.chunks_inserted
-            ),
-            count: result.papers_inserted as u64,
-        });
-
-        // Also emit individual PaperIngested events
-        for i in 0..result.papers_inserted.min(10) {
-            let _ = event_tx.send(AppEvent::PaperIngested {
-                paper_id: format!("{}-{}", result.job_id, i),
-                title: format!("Paper #{} ingested from {}", i + 1, result.query),
-                source: "ingestion".to_string(),
-            });
-        }
+        job_id: Some(record.id.to_string()),
     });
 
     // Return immediately with status that job is running
@@ -161,9 +144,254 @@ pub async fn ingestion_run(
         stats,
         &summary,
         form.max_results.unwrap_or(100) as i64,
+        Some(record.id),
+    ))
+}
+
+/// GET /api/ingestion/jobs — running jobs, then finished ones, newest first.
+pub async fn api_ingestion_jobs(
+    State(state): State<SharedState>,
+) -> Result<impl IntoResponse, ApiError> {
+    let jobs = state
+        .jobs
+        .list()
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    Ok(Json(jobs))
+}
+
+/// GET /api/ingestion/jobs/{id} — one job's parameters, stage and counts.
+pub async fn api_ingestion_job(
+    State(state): State<SharedState>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let job_id = parse_job_id(&id)?;
+    match state
+        .jobs
+        .get(job_id)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?
+    {
+        Some(job) => Ok(Json(job)),
+        None => Err(ApiError::NotFound(format!("Ingestion job {id} not found"))),
+    }
+}
+
+/// POST /api/ingestion/jobs/{id}/cancel — stop a running job once the papers
+/// in flight finish; 409 if it has already finished.
+pub async fn api_ingestion_job_cancel(
+    State(state): State<SharedState>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let job_id = parse_job_id(&id)?;
+    let outcome = state
+        .jobs
+        .cancel(job_id)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    match outcome {
+        CancelOutcome::Cancelled => {
+            let _ = state.event_tx.send(AppEvent::PipelineStatus {
+                stage: "cancelling".to_string(),
+                message: "Cancelling — waiting for papers in flight".to_string(),
+                count: 0,
+                job_id: Some(id.clone()),
+            });
+            Ok((
+                StatusCode::ACCEPTED,
+                Json(serde_json::json!({ "status": "cancelling", "job_id": id })),
+            ))
+        }
+        CancelOutcome::NotRunning => Err(ApiError::Conflict(format!(
+            "Ingestion job {id} has already finished"
+        ))),
+        CancelOutcome::NotFound => Err(ApiError::NotFound(format!("Ingestion job {id} not found"))),
+    }
+}
+
+/// Set while an embedding backfill started from this page is running.
+static EMBED_BACKFILL_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// POST /api/ingestion/embed-backfill — embed stored chunks that have no
+/// embedding, reporting progress over SSE; 409 while a backfill is running.
+pub async fn api_ingestion_embed_backfill(
+    State(state): State<SharedState>,
+) -> Result<impl IntoResponse, ApiError> {
+    let cfg = resolve_embedding_cfg(None, None, None, true)
+        .ok_or_else(|| ApiError::BadRequest("embedding is not configured".to_string()))?;
+    if EMBED_BACKFILL_RUNNING.swap(true, Ordering::SeqCst) {
+        return Err(ApiError::Conflict(
+            "An embedding backfill is already running".to_string(),
+        ));
+    }
+
+    let batch_size = cfg.batch_size;
+    let event_tx = state.event_tx.clone();
+    let db = state.db.clone();
+    tokio::spawn(async move {
+        let repo = IngestionRepository::new(db);
+        let progress_tx = event_tx.clone();
+        let result = embed_missing_chunks(&repo, cfg, batch_size, move |p| {
+            let _ = progress_tx.send(AppEvent::PipelineStatus {
+                stage: "embed_backfill".to_string(),
+                message: format!("Embedded {} of {} chunks", p.embedded, p.pending),
+                count: p.embedded as u64,
+                job_id: None,
+            });
+        })
+        .await;
+
+        let status = match result {
+            Ok(report) if report.error.is_none() => AppEvent::PipelineStatus {
+                stage: "embed_backfill_complete".to_string(),
+                message: format!("Embedding backfill complete — {} chunks", report.embedded),
+                count: report.embedded as u64,
+                job_id: None,
+            },
+            Ok(report) => AppEvent::PipelineStatus {
+                stage: "embed_backfill_failed".to_string(),
+                message: format!(
+                    "Embedding backfill stopped after {} chunks: {}",
+                    report.embedded,
+                    report.error.unwrap_or_default()
+                ),
+                count: report.embedded as u64,
+                job_id: None,
+            },
+            Err(e) => AppEvent::PipelineStatus {
+                stage: "embed_backfill_failed".to_string(),
+                message: format!("Embedding backfill failed: {e}"),
+                count: 0,
+                job_id: None,
+            },
+        };
+        let _ = event_tx.send(status);
+        EMBED_BACKFILL_RUNNING.store(false, Ordering::SeqCst);
+    });
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({ "status": "started", "batch_size": batch_size })),
+    ))
+}
+
+/// Papers taken from `failed_items` per retry request.
+const RETRY_FAILED_LIMIT: usize = 200;
+
+/// Set while a retry started from this page is running.
+static RETRY_FAILED_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// POST /api/ingestion/retry-failed — re-queue papers from the
+/// `failed_items` dead-letter table and process them again in the
+/// background, reporting over SSE; 409 while a retry is running.
+pub async fn api_ingestion_retry_failed(
+    State(state): State<SharedState>,
+) -> Result<impl IntoResponse, ApiError> {
+    let pending = FailedItemRepository::new(state.db.clone())
+        .count()
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    if pending == 0 {
+        return Err(ApiError::BadRequest(
+            "There are no failed items to retry".to_string(),
+        ));
+    }
+    if RETRY_FAILED_RUNNING.swap(true, Ordering::SeqCst) {
+        return Err(ApiError::Conflict(
+            "A retry of failed items is already running".to_string(),
+        ));
+    }
+
+    let job = IngestionJob {
+        unpaywall_email: resolve_unpaywall_email(),
+        embedding_cfg: resolve_embedding_cfg(None, None, None, false),
+        enable_scihub_fallback: resolve_scihub_enabled(),
+        full_text_enabled: true,
+        full_text_step_timeout_secs: Some(15),
+        ..IngestionJob::default()
+    };
+    let queued = (pending as usize).min(RETRY_FAILED_LIMIT);
+    let event_tx = state.event_tx.clone();
+    let db = state.db.clone();
+    let _ = event_tx.send(AppEvent::PipelineStatus {
+        stage: "retry_failed".to_string(),
+        message: format!("Retrying {queued} failed papers"),
+        count: 0,
+        job_id: None,
+    });
+    tokio::spawn(async move {
+        let repo = std::sync::Arc::new(IngestionRepository::new(db));
+        let result = retry_failed_items(job, repo, RETRY_FAILED_LIMIT).await;
+        let message = match result.error_summary() {
+            None => format!(
+                "Retry complete — {} papers ingested",
+                result.papers_succeeded
+            ),
+            Some(errors) => format!(
+                "Retry complete — {} papers ingested, {} failed again: {}",
+                result.papers_succeeded, result.papers_failed, errors
+            ),
+        };
+        let _ = event_tx.send(AppEvent::PipelineStatus {
+            stage: "retry_failed_complete".to_string(),
+            message,
+            count: result.papers_succeeded as u64,
+            job_id: None,
+        });
+        RETRY_FAILED_RUNNING.store(false, Ordering::SeqCst);
+    });
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({ "status": "started", "queued": queued })),
     ))
 }
 
+fn parse_job_id(id: &str) -> Result<uuid::Uuid, ApiError> {
+    uuid::Uuid::parse_str(id).map_err(|_| ApiError::BadRequest(format!("Invalid job id: {id}")))
+}
+
+/// GET /api/ingestion/schedules — configured schedules with their last run
+/// and next due time.
+pub async fn api_ingestion_schedules(
+    State(state): State<SharedState>,
+) -> Result<impl IntoResponse, ApiError> {
+    let statuses = state
+        .ingestion_scheduler
+        .statuses()
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    Ok(Json(statuses))
+}
+
+/// POST /api/ingestion/schedules/{id}/run-now — start a run of one schedule
+/// outside its timing rule; 409 while a run of it is still in flight.
+pub async fn api_ingestion_schedule_run_now(
+    State(state): State<SharedState>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let started = state
+        .ingestion_scheduler
+        .run_now(&id, RunTrigger::Manual)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    match started {
+        RunStart::Started { run, .. } => {
+            let _ = state.event_tx.send(AppEvent::PipelineStatus {
+                stage: "search".to_string(),
+                message: format!("Starting scheduled ingestion '{}'", run.schedule_id),
+                count: 0,
+                job_id: None,
+            });
+            Ok((StatusCode::ACCEPTED, Json(run)))
+        }
+        RunStart::AlreadyRunning => Err(ApiError::Conflict(format!(
+            "Schedule {id} is already running"
+        ))),
+        RunStart::UnknownSchedule => Err(ApiError::NotFound(format!("Schedule {id} not found"))),
+    }
+}
+
 // ── Stats loader ──────────────────────────────────────────────────────────────
 
 struct PageStats {
@@ -171,7 +399,14 @@ struct PageStats {
     parsed: i64,
     pending: i64,
     failed: i64,
-    recent_audit: Vec<(String, String, String, String)>,
+    chunks_missing_embeddings: u64,
+    /// Rows in the `failed_items` dead-letter table.
+    failed_items: u64,
+    /// The most recent of those.
+    recent_failures: Vec<FailedItem>,
+    /// (DOI, PMID, parse status, source, PDF text-quality score)
+    recent_audit: Vec<(String, String, String, String, Option<f64>)>,
+    schedules: Vec<ScheduleStatus>,
 }
 
 async fn load_stats(state: &SharedState) -> PageStats {
@@ -185,14 +420,51 @@ async fn load_stats(state: &SharedState) -> PageStats {
             .unwrap_or(0);
     let pending = repo.paper_count_by_status("pending").await.unwrap_or(0)
         + repo.paper_count_by_status("processing").await.unwrap_or(0);
-    let failed = repo.paper_count_by_status("failed").await.unwrap_or(0);
+    let failed = repo.paper_count_by_status("failed").await.unwrap_or(0)
+        + repo.paper_count_by_status("low_quality").await.unwrap_or(0);
+
+    let chunks_missing_embeddings = repo
+        .count_chunks_missing_embeddings(None)
+        .await
+        .unwrap_or(0);
+
+    let failed_item_repo = FailedItemRepository::new(state.db.clone());
+    let failed_items = failed_item_repo.count().await.unwrap_or(0);
+    let recent_failures = failed_item_repo.list(5).await.unwrap_or_default();
+
+    let mut recent_papers = PaperRepository::new(state.db.clone())
+        .list(0, 40)
+        .await
+        .unwrap_or_default();
+    recent_papers.sort_by(|a, b| b.ingested_at.cmp(&a.ingested_at));
+    recent_papers.truncate(12);
+    let recent_audit = recent_papers
+        .into_iter()
+        .map(|p| {
+            (
+                html_escape(p.doi.as_deref().unwrap_or("-")),
+                html_escape(p.pmid.as_deref().unwrap_or("-")),
+                p.parse_status,
+                html_escape(&p.source),
+                p.text_quality,
+            )
+        })
+        .collect();
 
     PageStats {
         total,
         parsed,
         pending,
         failed,
-        recent_audit: vec![],
+        chunks_missing_embeddings,
+        failed_items,
+        recent_failures,
+        recent_audit,
+        schedules: state
+            .ingestion_scheduler
+            .statuses()
+            .await
+            .unwrap_or_default(),
     }
 }
 
@@ -207,7 +479,8 @@ fn resolve_pubmed_api_key() -> Option<String> {
         .map(PathBuf::from)
         .unwrap_or_else(|_| PathBuf::from("ferrumyx.toml"));
     let content = std::fs::read_to_string(path).ok()?;
-    let root = toml::from_str::<toml::Value>(&content).ok()?;
+    let mut root = toml::from_str::<toml::Value>(&content).ok()?;
+    ferrumyx_common::secrets::open_sealed_values(&mut root);
     root.get("ingestion")
         .and_then(|v| v.get("pubmed"))
         .and_then(|v| v.get("api_key").or_else(|| v.get("api_key_secret")))
@@ -234,7 +507,8 @@ fn resolve_semantic_scholar_api_key() -> Option<String> {
         .map(PathBuf::from)
         .unwrap_or_else(|_| PathBuf::from("ferrumyx.toml"));
     let content = std::fs::read_to_string(path).ok()?;
-    let root = toml::from_str::<toml::Value>(&content).ok()?;
+    let mut root = toml::from_str::<toml::Value>(&content).ok()?;
+    ferrumyx_common::secrets::open_sealed_values(&mut root);
     root.get("ingestion")
         .and_then(|v| v.get("semanticscholar"))
         .and_then(|v| v.get("api_key").or_else(|| v.get("api_key_secret")))
@@ -265,6 +539,21 @@ fn resolve_unpaywall_email() -> Option<String> {
         .map(ToString::to_string)
 }
 
+/// Whether the Sci-Hub fallback starts checked: `FERRUMYX_SCIHUB_ENABLED`,
+/// else `ingestion.scihub.enabled`.
+fn resolve_scihub_enabled() -> bool {
+    if let Ok(v) = std::env::var("FERRUMYX_SCIHUB_ENABLED") {
+        return v == "1" || v.eq_ignore_ascii_case("true");
+    }
+    let path = std::env::var("FERRUMYX_CONFIG")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("ferrumyx.toml"));
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| toml::from_str::<toml::Value>(&content).ok())
+        .is_some_and(|root| toml_bool(&root, &["ingestion", "scihub", "enabled"], false))
+}
+
 fn toml_string(root: &toml::Value, path: &[&str]) -> Option<String> {
     let mut cur = root;
     for p in path {
@@ -311,25 +600,42 @@ fn parse_embedding_backend(s: &str) -> IngestionEmbeddingBackend {
 }
 
 fn resolve_embedding_cfg_for_form(form: &IngestionForm) -> Option<IngestionEmbeddingConfig> {
+    resolve_embedding_cfg(
+        form.embed_backend.as_deref(),
+        form.embed_model.as_deref(),
+        form.embed_api_key.as_deref(),
+        false,
+    )
+}
+
+/// Embedding config from explicit choices, falling back to `ferrumyx.toml`.
+/// Unless `force` is set, `None` when no backend is chosen and
+/// `ingestion.enable_embeddings` is off.
+fn resolve_embedding_cfg(
+    backend: Option<&str>,
+    model: Option<&str>,
+    api_key: Option<&str>,
+    force: bool,
+) -> Option<IngestionEmbeddingConfig> {
     let path = std::env::var("FERRUMYX_CONFIG")
         .map(PathBuf::from)
         .unwrap_or_else(|_| PathBuf::from("ferrumyx.toml"));
     let root = std::fs::read_to_string(path)
         .ok()
-        .and_then(|content| toml::from_str::<toml::Value>(&content).ok());
+        .and_then(|content| toml::from_str::<toml::Value>(&content).ok())
+        .map(|mut root| {
+            ferrumyx_common::secrets::open_sealed_values(&mut root);
+            root
+        });
 
     let default_enabled = root
         .as_ref()
         .map(|r| toml_bool(r, &["ingestion", "enable_embeddings"], false))
         .unwrap_or(false);
 
-    let explicit_backend = form
-        .embed_backend
-        .as_deref()
-        .map(str::trim)
-        .filter(|s| !s.is_empty());
+    let explicit_backend = backend.map(str::trim).filter(|s| !s.is_empty());
 
-    if explicit_backend.is_none() && !default_enabled {
+    if explicit_backend.is_none() && !default_enabled && !force {
         return None;
     }
 
@@ -342,9 +648,7 @@ fn resolve_embedding_cfg_for_form(form: &IngestionForm) -> Option<IngestionEmbed
         .unwrap_or_else(|| "rust_native".to_string());
     let backend = parse_embedding_backend(&backend_str);
 
-    let model = form
-        .embed_model
-        .as_deref()
+    let model = model
         .map(str::trim)
         .filter(|s| !s.is_empty())
         .map(ToString::to_string)
@@ -392,9 +696,7 @@ fn resolve_embedding_cfg_for_form(form: &IngestionForm) -> Option<IngestionEmbed
         )
         .clamp(64, 8192) as usize;
 
-    let api_key = form
-        .embed_api_key
-        .as_deref()
+    let api_key = api_key
         .map(str::trim)
         .filter(|s| !s.is_empty())
         .map(ToString::to_string)
@@ -429,10 +731,17 @@ fn resolve_embedding_cfg_for_form(form: &IngestionForm) -> Option<IngestionEmbed
 // ── Renderer ──────────────────────────────────────────────────────────────────
 
 fn render_page(stats: PageStats, result_banner: Option<(&str, &Vec<String>)>) -> String {
-    render_page_with_progress(stats, result_banner.map(|(s, _)| s).unwrap_or(""), 0)
+    render_page_with_progress(stats, result_banner.map(|(s, _)| s).unwrap_or(""), 0, None)
 }
 
-fn render_page_with_progress(stats: PageStats, summary: &str, total_expected: i64) -> String {
+/// Page with the progress card shown for `job_id`, whose SSE updates it
+/// follows; updates tagged with other jobs are ignored.
+fn render_page_with_progress(
+    stats: PageStats,
+    summary: &str,
+    total_expected: i64,
+    job_id: Option<uuid::Uuid>,
+) -> String {
     let banner = if summary.is_empty() {
         String::new()
     } else {
@@ -453,20 +762,31 @@ fn render_page_with_progress(stats: PageStats, summary: &str, total_expected: i6
     };
 
     let progress_display = if total_expected > 0 { "block" } else { "none" };
+    let schedules_card = render_schedules_card(&stats.schedules);
+    let embed_backfill_card = render_embed_backfill_card(stats.chunks_missing_embeddings);
+    let failed_items_card = render_failed_items_card(stats.failed_items, &stats.recent_failures);
+    let job_id_js = job_id.map_or_else(|| "null".to_string(), |id| format!("'{id}'"));
+    let scihub_checked = if resolve_scihub_enabled() {
+        " checked"
+    } else {
+        ""
+    };
 
     let audit_rows: String = if stats.recent_audit.is_empty() {
-        r#"<tr><td colspan="4" class="text-center text-muted py-3">No ingestion events yet.</td></tr>"#.to_string()
+        r#"<tr><td colspan="5" class="text-center text-muted py-3">No ingestion events yet.</td></tr>"#.to_string()
     } else {
-        stats.recent_audit.iter().map(|(doi, pmid, action, source)| {
+        stats.recent_audit.iter().map(|(doi, pmid, action, source, text_quality)| {
             let badge = match action.as_str() {
-                "parsed"       => r#"<span class="badge bg-success">parsed</span>"#,
-                "discovered"   => r#"<span class="badge bg-info text-dark">discovered</span>"#,
+                "parsed" | "parsed_fast" | "parsed_light" => r#"<span class="badge bg-success">parsed</span>"#,
+                "pending" | "processing" => r#"<span class="badge bg-info text-dark">discovered</span>"#,
                 "failed"       => r#"<span class="badge bg-danger">failed</span>"#,
+                "low_quality"  => r#"<span class="badge bg-warning text-dark">low quality</span>"#,
                 "deduplicated" => r#"<span class="badge bg-secondary">dup</span>"#,
                 _              => r#"<span class="badge bg-secondary">other</span>"#,
             };
-            format!(r#"<tr><td class="font-monospace small">{}</td><td class="font-monospace small">{}</td><td>{}</td><td>{}</td></tr>"#,
-                doi, pmid, badge, source)
+            let quality = text_quality.map_or_else(|| "-".to_string(), |q| format!("{q:.2}"));
+            format!(r#"<tr><td class="font-monospace small">{}</td><td class="font-monospace small">{}</td><td>{}</td><td>{}</td><td class="font-monospace small">{}</td></tr>"#,
+                doi, pmid, badge, source, quality)
         }).collect()
     };
 
@@ -529,6 +849,9 @@ fn render_page_with_progress(stats: PageStats, summary: &str, total_expected: i6
         <div class="stat-card card-hover" style="border-bottom: 2px solid var(--danger);">
             <div class="stat-icon"><svg stroke="var(--danger)" xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24"><path d="M19 6.41L17.59 5 12 10.59 6.41 5 5 6.41 10.59 12 5 17.59 6.41 19 12 13.41 17.59 19 19 17.59 13.41 12z"/></svg></div>
             <div class="stat-value" style="color:var(--danger)">{}</div><div class="stat-label">Parsing Failures</div></div>
+        <div class="stat-card card-hover" style="border-bottom: 2px solid var(--danger);">
+            <div class="stat-icon"><svg stroke="var(--danger)" xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24"><path d="M1 21h22L12 2 1 21zm12-3h-2v-2h2v2zm0-4h-2v-4h2v4z"/></svg></div>
+            <div class="stat-value" style="color:var(--danger)" id="failed-items-count">{}</div><div class="stat-label">Dead-Letter Items</div></div>
     </div>
 
     <!-- Pipeline Progress Indicator -->
@@ -538,6 +861,7 @@ fn render_page_with_progress(stats: PageStats, summary: &str, total_expected: i6
             <div class="d-flex align-center gap-2">
                 <span id="sse-status" class="badge badge-outline text-muted sse-live"><div class="sse-dot"></div> Live</span>
                 <span id="pipeline-stage" class="badge badge-primary">Running Operations</span>
+                <button type="button" id="pipeline-cancel" class="btn btn-outline btn-sm" style="display:none;">Cancel</button>
             </div>
         </div>
         <div class="pipeline-card-body">
@@ -568,13 +892,29 @@ fn render_page_with_progress(stats: PageStats, summary: &str, total_expected: i6
             let papersFound = 0;
             let papersInserted = 0;
             const totalExpected = {};
+            const jobId = {};
+            const cancelBtn = document.getElementById('pipeline-cancel');
+            const finishJob = function(badgeClass, label) {{
+                document.getElementById('pipeline-stage').className = 'badge ' + badgeClass;
+                document.getElementById('pipeline-stage').textContent = label;
+                cancelBtn.style.display = 'none';
+                setTimeout(() => evtSource.close(), 2000);
+            }};
+            if (jobId) {{
+                cancelBtn.style.display = 'inline-block';
+                cancelBtn.onclick = function() {{
+                    cancelBtn.disabled = true;
+                    fetch('/api/ingestion/jobs/' + jobId + '/cancel', {{ method: 'POST' }});
+                }};
+            }}
             
             // Connect to SSE
-            const evtSource = new EventSource('/api/events');
+            const evtSource = new EventSource('/api/events?topics=ingestion');
             evtSource.onmessage = function(e) {{
                 const data = JSON.parse(e.data);
                 
                 if (data.type === 'pipeline_status') {{
+                    if (jobId && data.job_id !== jobId) return;
                     document.getElementById('pipeline-status-text').textContent = '> ' + data.message;
                     document.getElementById('pipeline-stage').textContent = data.stage;
                     
@@ -585,9 +925,11 @@ fn render_page_with_progress(stats: PageStats, summary: &str, total_expected: i6
                         document.getElementById('pipeline-progress').style.width = '100%';
                         document.getElementById('pipeline-progress').classList.add('success');
                         document.getElementById('progress-text').textContent = '100%';
-                        document.getElementById('pipeline-stage').className = 'badge badge-success';
-                        document.getElementById('pipeline-stage').textContent = 'Job Completed';
-                        setTimeout(() => evtSource.close(), 2000);
+                        finishJob('badge-success', 'Job Completed');
+                    }} else if (data.stage === 'cancelled') {{
+                        finishJob('badge-outline', 'Job Cancelled');
+                    }} else if (data.stage === 'failed') {{
+                        finishJob('badge-danger', 'Job Failed');
                     }}
                 }}
                 
@@ -659,11 +1001,17 @@ fn render_page_with_progress(stats: PageStats, summary: &str, total_expected: i6
                         </label>
                     </div>
                 </div>
+                <div class="mt-2">
+                    <label style="display:flex; align-items:center; gap:0.5rem; cursor:pointer;">
+                        <input type="checkbox" name="only_new" id="only_new"> <span style="font-weight:500">Only new since last run</span>
+                    </label>
+                    <div class="text-muted small">PubMed and Europe PMC are searched from the start of the previous run of the same query.</div>
+                </div>
                 <details class="advanced-block">
                     <summary>Advanced Options</summary>
                     <div class="mt-2">
                         <label style="display:flex; align-items:center; gap:0.5rem; cursor:pointer;">
-                            <input type="checkbox" name="enable_scihub" id="enable_scihub"> <span style="font-weight:500; color: var(--brand-purple);">Enable Sci-Hub fallback for full-text retrieval</span>
+                            <input type="checkbox" name="enable_scihub" id="enable_scihub"{scihub_checked}> <span style="font-weight:500; color: var(--brand-purple);">Enable Sci-Hub fallback for full-text retrieval</span>
                         </label>
                     </div>
                 </details>
@@ -677,6 +1025,12 @@ fn render_page_with_progress(stats: PageStats, summary: &str, total_expected: i6
             </form>
         </div>
 
+        {}
+
+        {}
+
+        {}
+
         <div class="card">
             <div class="card-header d-flex justify-between">
                 <div>Recent Jobs</div>
@@ -684,7 +1038,7 @@ fn render_page_with_progress(stats: PageStats, summary: &str, total_expected: i6
             </div>
             <div class="table-container p-0">
                 <table class="table mb-0">
-                    <thead><tr><th>Document Auth DOI</th><th>PMID</th><th>State</th><th>Node</th></tr></thead>
+                    <thead><tr><th>Document Auth DOI</th><th>PMID</th><th>State</th><th>Node</th><th>Text Quality</th></tr></thead>
                     <tbody>{}</tbody>
                 </table>
             </div>
@@ -700,9 +1054,193 @@ fn render_page_with_progress(stats: PageStats, summary: &str, total_expected: i6
         stats.parsed,
         stats.pending,
         stats.failed,
+        stats.failed_items,
         progress_display,
         total_expected,
         total_expected,
+        job_id_js,
+        schedules_card,
+        embed_backfill_card,
+        failed_items_card,
         audit_rows
     )
 }
+
+/// Card listing `[[ingestion.schedules]]` with last and next run times;
+/// empty when none are configured.
+fn render_schedules_card(schedules: &[ScheduleStatus]) -> String {
+    if schedules.is_empty() {
+        return String::new();
+    }
+    let fmt_time = |t: Option<chrono::DateTime<chrono::Utc>>| {
+        t.map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+            .unwrap_or_else(|| "—".to_string())
+    };
+    let rows: String = schedules
+        .iter()
+        .map(|s| {
+            let timing = match (&s.schedule.cron, s.schedule.interval_secs) {
+                (Some(cron), _) => format!("cron <code>{}</code>", html_escape(cron)),
+                (None, Some(secs)) => format!("every {}", format_interval(secs)),
+                (None, None) => "—".to_string(),
+            };
+            let last = match &s.last_run {
+                Some(run) => format!(
+                    "{} <span class=\"badge bg-secondary\">{}</span>",
+                    fmt_time(Some(run.started_at)),
+                    html_escape(&run.status)
+                ),
+                None => "never".to_string(),
+            };
+            let next = if s.running {
+                r#"<span class="badge bg-info text-dark">running</span>"#.to_string()
+            } else {
+                fmt_time(s.next_run_at)
+            };
+            format!(
+                r#"<tr><td class="font-monospace small">{id}</td><td>{gene} {mutation} in {cancer}</td><td>{timing}</td><td>{last}</td><td>{next}</td><td><button type="button" class="btn btn-sm btn-outline" onclick="fetch('/api/ingestion/schedules/{id}/run-now', {{method: 'POST'}}).then(() => location.reload())">Run now</button></td></tr>"#,
+                id = html_escape(&s.schedule.id),
+                gene = html_escape(&s.schedule.gene),
+                mutation = html_escape(s.schedule.mutation.as_deref().unwrap_or("")),
+                cancer = html_escape(&s.schedule.cancer_type),
+            )
+        })
+        .collect();
+    format!(
+        r#"<div class="card mb-4">
+            <div class="card-header">Scheduled Jobs</div>
+            <div class="table-container p-0">
+                <table class="table mb-0">
+                    <thead><tr><th>Schedule</th><th>Query</th><th>Timing</th><th>Last Run</th><th>Next Run</th><th></th></tr></thead>
+                    <tbody>{}</tbody>
+                </table>
+            </div>
+        </div>"#,
+        rows
+    )
+}
+
+/// Card with the number of chunks lacking an embedding and a button that
+/// starts a backfill; progress arrives as `embed_backfill*` SSE events.
+fn render_embed_backfill_card(missing: u64) -> String {
+    format!(
+        r#"<div class="card mb-4">
+            <div class="card-header d-flex justify-between align-center">
+                <div>Embeddings</div>
+                <button type="button" id="embed-backfill-btn" class="btn btn-sm btn-outline"{disabled}>Embed missing chunks</button>
+            </div>
+            <div class="pipeline-card-body">
+                <div><span id="chunks-missing-embeddings" class="font-monospace">{missing}</span> chunks have no embedding yet.</div>
+                <p id="embed-backfill-status" class="status-line"></p>
+            </div>
+            <script>
+                (function() {{
+                    const btn = document.getElementById('embed-backfill-btn');
+                    const status = document.getElementById('embed-backfill-status');
+                    btn.onclick = function() {{
+                        btn.disabled = true;
+                        fetch('/api/ingestion/embed-backfill', {{ method: 'POST' }}).then(function(r) {{
+                            if (!r.ok) {{
+                                r.text().then(function(t) {{ status.textContent = '> ' + t; }});
+                                btn.disabled = false;
+                                return;
+                            }}
+                            const events = new EventSource('/api/events?topics=ingestion');
+                            events.onmessage = function(e) {{
+                                const data = JSON.parse(e.data);
+                                if (data.type !== 'pipeline_status' || !data.stage.startsWith('embed_backfill')) return;
+                                status.textContent = '> ' + data.message;
+                                if (data.stage !== 'embed_backfill') {{
+                                    events.close();
+                                    setTimeout(() => location.reload(), 1500);
+                                }}
+                            }};
+                        }});
+                    }};
+                }})();
+            </script>
+        </div>"#,
+        disabled = if missing == 0 { " disabled" } else { "" },
+    )
+}
+
+/// Card with the dead-letter count, the latest failures and a button that
+/// retries them; progress arrives as `retry_failed*` SSE events. Empty
+/// when nothing has failed.
+fn render_failed_items_card(count: u64, recent: &[FailedItem]) -> String {
+    if count == 0 {
+        return String::new();
+    }
+    let rows: String = recent
+        .iter()
+        .map(|item| {
+            format!(
+                r#"<tr><td>{title}</td><td><span class="badge bg-danger">{stage}</span></td><td class="small">{error}</td><td class="font-monospace small">{retries}</td></tr>"#,
+                title = html_escape(&item.title),
+                stage = html_escape(&item.stage),
+                error = html_escape(&item.error),
+                retries = item.retry_count,
+            )
+        })
+        .collect();
+    format!(
+        r#"<div class="card mb-4">
+            <div class="card-header d-flex justify-between align-center">
+                <div>Failed Items</div>
+                <button type="button" id="retry-failed-btn" class="btn btn-sm btn-outline">Retry failed</button>
+            </div>
+            <div class="pipeline-card-body">
+                <div><span class="font-monospace">{count}</span> papers failed during ingestion and are waiting for a retry.</div>
+                <p id="retry-failed-status" class="status-line"></p>
+            </div>
+            <div class="table-container p-0">
+                <table class="table mb-0">
+                    <thead><tr><th>Paper</th><th>Stage</th><th>Error</th><th>Retries</th></tr></thead>
+                    <tbody>{rows}</tbody>
+                </table>
+            </div>
+            <script>
+                (function() {{
+                    const btn = document.getElementById('retry-failed-btn');
+                    const status = document.getElementById('retry-failed-status');
+                    btn.onclick = function() {{
+                        btn.disabled = true;
+                        fetch('/api/ingestion/retry-failed', {{ method: 'POST' }}).then(function(r) {{
+                            if (!r.ok) {{
+                                r.text().then(function(t) {{ status.textContent = '> ' + t; }});
+                                btn.disabled = false;
+                                return;
+                            }}
+                            const events = new EventSource('/api/events?topics=ingestion');
+                            events.onmessage = function(e) {{
+                                const data = JSON.parse(e.data);
+                                if (data.type !== 'pipeline_status' || !data.stage.startsWith('retry_failed')) return;
+                                status.textContent = '> ' + data.message;
+                                if (data.stage !== 'retry_failed') {{
+                                    events.close();
+                                    setTimeout(() => location.reload(), 1500);
+                                }}
+                            }};
+                        }});
+                    }};
+                }})();
+            </script>
+        </div>"#
+    )
+}
+
+fn format_interval(secs: u64) -> String {
+    match secs {
+        s if s % 86_400 == 0 => format!("{}d", s / 86_400),
+        s if s % 3_600 == 0 => format!("{}h", s / 3_600),
+        s if s % 60 == 0 => format!("{}m", s / 60),
+        s => format!("{s}s"),
+    }
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
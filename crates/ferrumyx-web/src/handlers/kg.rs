@@ -1,22 +1,32 @@
 //! Knowledge graph explorer.
 
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::sync::{Mutex, OnceLock};
 use std::time::{Duration, Instant};
 
 use axum::{
-    extract::{Query, State},
-    response::{Html, IntoResponse},
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{Html, IntoResponse, Response},
     Json,
 };
 use serde::{Deserialize, Serialize};
 
 use crate::handlers::dashboard::NAV_HTML;
 use crate::state::SharedState;
+use ferrumyx_common::confidence::{
+    is_review_study_type, review_evidence_weight, AggregatedSupport,
+};
 use ferrumyx_common::error::ApiError;
+use ferrumyx_db::chunks::ChunkRepository;
 use ferrumyx_db::entities::EntityRepository;
-use ferrumyx_db::kg_facts::KgFactRepository;
+use ferrumyx_db::entity_mentions::EntityMentionRepository;
+use ferrumyx_db::kg_conflicts::KgConflictRepository;
+use ferrumyx_db::kg_facts::{fact_supports, merge_facts, KgFactRepository};
 use ferrumyx_db::papers::{PaperReference, PaperRepository};
+use ferrumyx_db::schema::{Entity, KgFact};
+use ferrumyx_db::{PaperCitation, PaperCitationRepository};
+use ferrumyx_kg::graph::{GraphEdge, GraphNode, KgGraph, KgPath};
 
 struct CachedHtml {
     html: String,
@@ -52,6 +62,8 @@ struct ScoredEdge {
     confidence_tier: String,
     provenance: String,
     score: f64,
+    paper_count: usize,
+    review_count: usize,
 }
 
 #[derive(Deserialize, Default)]
@@ -60,6 +72,46 @@ pub struct EntitySuggestQuery {
     pub limit: Option<usize>,
 }
 
+#[derive(Deserialize, Default)]
+pub struct KgPathQuery {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub max_depth: Option<usize>,
+}
+
+#[derive(Deserialize, Default)]
+pub struct KgNeighborhoodQuery {
+    pub entity: Option<String>,
+    pub depth: Option<usize>,
+    pub predicate: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Deserialize, Default)]
+pub struct KgCitationQuery {
+    /// Comma-separated paper ids.
+    pub paper_ids: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+pub struct ConflictQuery {
+    pub severity: Option<String>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+#[derive(Deserialize, Default)]
+pub struct KgEntityQuery {
+    /// List only this predicate's facts, e.g. to page through one group.
+    pub predicate: Option<String>,
+    /// Facts per predicate group.
+    pub limit: Option<usize>,
+    /// Facts skipped in each predicate group.
+    pub offset: Option<usize>,
+    /// Related entities listed.
+    pub related: Option<usize>,
+}
+
 // === API Types ===
 
 #[derive(Debug, Serialize)]
@@ -87,6 +139,168 @@ pub struct ApiEntitySuggest {
     pub value: String,
 }
 
+#[derive(Debug, Serialize)]
+pub struct ApiFactEvidence {
+    pub fact_id: uuid::Uuid,
+    pub subject: String,
+    pub predicate: String,
+    pub object: String,
+    pub evidence: Option<String>,
+    pub chunk_id: Option<uuid::Uuid>,
+    pub evidence_start: Option<i64>,
+    pub evidence_end: Option<i64>,
+    /// Escaped chunk content with the evidence span in `<mark>`.
+    pub highlighted_html: Option<String>,
+    pub paper: Option<ApiPaperCitation>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiKgConflict {
+    pub id: uuid::Uuid,
+    pub subject: String,
+    pub object: String,
+    pub fact_a_id: uuid::Uuid,
+    pub predicate_a: String,
+    pub confidence_a: Option<f32>,
+    pub fact_b_id: uuid::Uuid,
+    pub predicate_b: String,
+    pub confidence_b: Option<f32>,
+    pub severity: Option<String>,
+    pub net_confidence: f32,
+    pub resolution: String,
+    pub paper_ids: Vec<uuid::Uuid>,
+    pub detected_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiKgPath {
+    pub from: uuid::Uuid,
+    pub to: uuid::Uuid,
+    pub max_depth: usize,
+    /// None when the entities are not connected within `max_depth` hops.
+    pub path: Option<KgPath>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiKgNeighborhood {
+    pub center: uuid::Uuid,
+    pub depth: usize,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    /// Whether nodes past `limit` were dropped.
+    pub truncated: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiPaperCitation {
+    pub id: uuid::Uuid,
+    pub title: String,
+    pub doi: Option<String>,
+    pub pmid: Option<String>,
+}
+
+/// A citation between two stored papers.
+#[derive(Debug, Serialize)]
+pub struct ApiCitationEdge {
+    /// Citing paper.
+    pub source: uuid::Uuid,
+    /// Cited paper.
+    pub target: uuid::Uuid,
+    pub cited_doi: String,
+    pub context: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiKgCitations {
+    pub papers: Vec<ApiPaperCitation>,
+    pub edges: Vec<ApiCitationEdge>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiPaperCitations {
+    pub paper_id: uuid::Uuid,
+    /// References made by the paper, stored or not.
+    pub cites: Vec<PaperCitation>,
+    /// Stored papers citing it.
+    pub cited_by: Vec<PaperCitation>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiKgEntity {
+    pub entity: Entity,
+    /// Distinct triples per predicate, over every predicate.
+    pub fact_counts: BTreeMap<String, usize>,
+    /// One page of facts per predicate, largest group first.
+    pub facts: Vec<ApiPredicateFacts>,
+    pub mention_count: usize,
+    /// Most recent first.
+    pub recent_mentions: Vec<ApiEntityMention>,
+    /// Entities sharing facts with this one, most connected first.
+    pub related: Vec<ApiRelatedEntity>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiPredicateFacts {
+    pub predicate: String,
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+    /// Whether facts past this page remain.
+    pub has_more: bool,
+    /// Highest aggregate confidence first, then most supporting papers.
+    pub facts: Vec<ApiEntityFact>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiEntityFact {
+    pub id: uuid::Uuid,
+    pub subject_id: uuid::Uuid,
+    pub subject: String,
+    pub predicate: String,
+    pub object_id: uuid::Uuid,
+    pub object: String,
+    /// "outgoing" when the entity is the subject, else "incoming".
+    pub direction: &'static str,
+    pub confidence: f32,
+    pub confidence_tier: String,
+    pub provenance: String,
+    pub support_count: i64,
+    /// The papers backing the fact.
+    pub papers: Vec<ApiPaperCitation>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiEntityMention {
+    pub id: uuid::Uuid,
+    pub paper: Option<ApiPaperCitation>,
+    pub chunk_id: uuid::Uuid,
+    pub text: String,
+    /// The sentence the mention was read from, else the mention itself.
+    pub snippet: String,
+    pub confidence: Option<f32>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiRelatedEntity {
+    pub id: uuid::Uuid,
+    pub name: String,
+    pub fact_count: usize,
+    pub predicates: Vec<String>,
+    pub max_confidence: f32,
+    pub summary_url: String,
+    pub neighborhood_url: String,
+}
+
+/// An entity listed when a lookup finds nothing.
+#[derive(Debug, Serialize)]
+pub struct ApiEntityMatch {
+    pub id: uuid::Uuid,
+    pub name: String,
+    pub entity_type: String,
+    pub external_id: String,
+}
+
 /// GET /api/kg - List KG facts
 pub async fn api_kg_facts(
     State(state): State<SharedState>,
@@ -114,6 +328,648 @@ pub async fn api_kg_facts(
     Ok(Json(api_facts))
 }
 
+/// GET /api/kg/fact/{id}/evidence - Source chunk and citation of one fact
+pub async fn api_kg_fact_evidence(
+    State(state): State<SharedState>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let fact_id = uuid::Uuid::parse_str(id.trim())
+        .map_err(|_| ApiError::BadRequest(format!("Invalid fact id {id}")))?;
+    let fact = KgFactRepository::new(state.db.clone())
+        .find_by_id(fact_id)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?
+        .ok_or_else(|| ApiError::NotFound(format!("Fact {fact_id} not found")))?;
+
+    let chunk = match fact.chunk_id {
+        Some(chunk_id) => ChunkRepository::new(state.db.clone())
+            .find_by_id(chunk_id)
+            .await
+            .map_err(|e| ApiError::Internal(e.to_string()))?,
+        None => None,
+    };
+    let paper = PaperRepository::new(state.db.clone())
+        .find_by_id(fact.paper_id)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?
+        .map(|p| ApiPaperCitation {
+            id: p.id,
+            title: p.title,
+            doi: p.doi,
+            pmid: p.pmid,
+        });
+
+    Ok(Json(ApiFactEvidence {
+        fact_id: fact.id,
+        highlighted_html: chunk
+            .map(|c| mark_evidence(&c.content, fact.evidence_start, fact.evidence_end)),
+        subject: fact.subject_name,
+        predicate: fact.predicate,
+        object: fact.object_name,
+        evidence: fact.evidence,
+        chunk_id: fact.chunk_id,
+        evidence_start: fact.evidence_start,
+        evidence_end: fact.evidence_end,
+        paper,
+    }))
+}
+
+/// GET /api/kg/path?from=...&to=...&max_depth=... - Shortest connection between two entities
+pub async fn api_kg_path(
+    State(state): State<SharedState>,
+    Query(query): Query<KgPathQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let max_depth = query.max_depth.unwrap_or(3).clamp(1, 6);
+    let graph = state
+        .kg_graph
+        .graph()
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    let from = resolve_graph_entity(&graph, "from", query.from.as_deref())?;
+    let to = resolve_graph_entity(&graph, "to", query.to.as_deref())?;
+
+    Ok(Json(ApiKgPath {
+        from,
+        to,
+        max_depth,
+        path: graph.shortest_path(from, to, max_depth),
+    }))
+}
+
+/// GET /api/kg/neighborhood?entity=...&depth=...&predicate=...&limit=... - Subgraph around one entity
+pub async fn api_kg_neighborhood(
+    State(state): State<SharedState>,
+    Query(query): Query<KgNeighborhoodQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let depth = query.depth.unwrap_or(1).clamp(1, 3);
+    let limit = query.limit.unwrap_or(200).clamp(1, 2_000);
+    let predicate = query
+        .predicate
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty() && !p.eq_ignore_ascii_case("all"));
+    let graph = state
+        .kg_graph
+        .graph()
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    let center = resolve_graph_entity(&graph, "entity", query.entity.as_deref())?;
+    let mut neighborhood = graph
+        .neighbors(center, depth, predicate)
+        .ok_or_else(|| ApiError::NotFound(format!("Entity {center} has no KG facts")))?;
+
+    // Nodes are ordered by depth, so truncation drops the outermost ring.
+    let truncated = neighborhood.nodes.len() > limit;
+    if truncated {
+        neighborhood.nodes.truncate(limit);
+        let kept: HashSet<uuid::Uuid> = neighborhood.nodes.iter().map(|n| n.id).collect();
+        neighborhood
+            .edges
+            .retain(|e| kept.contains(&e.subject_id) && kept.contains(&e.object_id));
+    }
+
+    Ok(Json(ApiKgNeighborhood {
+        center,
+        depth,
+        nodes: neighborhood.nodes,
+        edges: neighborhood.edges,
+        truncated,
+    }))
+}
+
+/// Entity id for a query parameter holding an id or an entity name.
+fn resolve_graph_entity(
+    graph: &KgGraph,
+    param: &str,
+    raw: Option<&str>,
+) -> Result<uuid::Uuid, ApiError> {
+    let raw = raw
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| ApiError::BadRequest(format!("Missing {param}")))?;
+    if let Ok(id) = uuid::Uuid::parse_str(raw) {
+        return Ok(id);
+    }
+    graph
+        .find_entity(raw)
+        .ok_or_else(|| ApiError::NotFound(format!("No KG entity named {raw}")))
+}
+
+/// Mentions listed in an entity summary.
+const RECENT_MENTIONS: usize = 5;
+
+/// Entities read per search when looking for near matches.
+const SUGGESTION_POOL: usize = 200;
+
+/// GET /api/kg/entity/{id_or_symbol}?predicate=&limit=&offset=&related= - Facts, mentions and neighbours of one entity
+pub async fn api_kg_entity(
+    State(state): State<SharedState>,
+    Path(key): Path<String>,
+    Query(query): Query<KgEntityQuery>,
+) -> Result<Response, ApiError> {
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(ApiError::BadRequest("Missing entity".to_string()));
+    }
+    let limit = query.limit.unwrap_or(5).clamp(1, 100);
+    let offset = query.offset.unwrap_or(0);
+    let related_limit = query.related.unwrap_or(20).clamp(1, 100);
+    let predicate = query
+        .predicate
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty() && !p.eq_ignore_ascii_case("all"));
+
+    let entity_repo = EntityRepository::new(state.db.clone());
+    let Some(entity) = find_kg_entity(&entity_repo, key).await? else {
+        let suggestions = entity_suggestions(&entity_repo, key, 5).await?;
+        return Ok((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": format!("No entity matches {key}"),
+                "suggestions": suggestions,
+            })),
+        )
+            .into_response());
+    };
+
+    let facts = KgFactRepository::new(state.db.clone())
+        .find_by_entity(entity.id)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    let facts = merge_entity_facts(facts);
+    let groups = group_by_predicate(&facts);
+    let fact_counts = groups
+        .iter()
+        .map(|(p, facts)| (p.to_string(), facts.len()))
+        .collect();
+    let pages: Vec<(&str, usize, Vec<&KgFact>)> = groups
+        .into_iter()
+        .filter(|(p, _)| predicate.is_none_or(|wanted| p.eq_ignore_ascii_case(wanted)))
+        .map(|(p, facts)| {
+            let total = facts.len();
+            let page = facts.into_iter().skip(offset).take(limit).collect();
+            (p, total, page)
+        })
+        .collect();
+
+    let mut mentions = EntityMentionRepository::new(state.db.clone())
+        .find_by_entity_id(entity.id)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    let mention_count = mentions.len();
+    mentions.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(a.id.cmp(&b.id)));
+    mentions.truncate(RECENT_MENTIONS);
+
+    let paper_ids: Vec<uuid::Uuid> = pages
+        .iter()
+        .flat_map(|(_, _, page)| page.iter())
+        .flat_map(|f| fact_supports(f))
+        .map(|s| s.paper_id)
+        .chain(mentions.iter().map(|m| m.paper_id))
+        .collect();
+    let refs = PaperRepository::new(state.db.clone())
+        .find_references_by_ids(&paper_ids)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    let citation = |id: uuid::Uuid| {
+        refs.get(&id).map(|r| ApiPaperCitation {
+            id,
+            title: r.title.clone(),
+            doi: r.doi.clone(),
+            pmid: r.pmid.clone(),
+        })
+    };
+
+    Ok(Json(ApiKgEntity {
+        fact_counts,
+        facts: pages
+            .into_iter()
+            .map(|(predicate, total, page)| ApiPredicateFacts {
+                predicate: predicate.to_string(),
+                total,
+                offset,
+                limit,
+                has_more: offset.saturating_add(page.len()) < total,
+                facts: page
+                    .into_iter()
+                    .map(|f| ApiEntityFact {
+                        id: f.id,
+                        subject_id: f.subject_id,
+                        subject: f.subject_name.clone(),
+                        predicate: f.predicate.clone(),
+                        object_id: f.object_id,
+                        object: f.object_name.clone(),
+                        direction: if f.subject_id == entity.id {
+                            "outgoing"
+                        } else {
+                            "incoming"
+                        },
+                        confidence: f.confidence,
+                        confidence_tier: classify_confidence_tier(f).to_string(),
+                        provenance: classify_fact_provenance(f).to_string(),
+                        support_count: f.support_count,
+                        papers: fact_supports(f)
+                            .into_iter()
+                            .filter_map(|s| citation(s.paper_id))
+                            .collect(),
+                    })
+                    .collect(),
+            })
+            .collect(),
+        mention_count,
+        recent_mentions: mentions
+            .into_iter()
+            .map(|m| ApiEntityMention {
+                id: m.id,
+                paper: citation(m.paper_id),
+                chunk_id: m.chunk_id,
+                snippet: truncate(m.context.as_deref().unwrap_or(&m.text), 400),
+                text: m.text,
+                confidence: m.confidence,
+                created_at: m.created_at.to_rfc3339(),
+            })
+            .collect(),
+        related: related_entities(entity.id, &facts, related_limit),
+        entity,
+    })
+    .into_response())
+}
+
+/// The entity `key` names: an internal id, an external id such as
+/// "HGNC:6407" or a MeSH id with or without its "MESH:" prefix, else a name
+/// or synonym. Symbols are also tried upper-cased, and genes win ties.
+async fn find_kg_entity(repo: &EntityRepository, key: &str) -> Result<Option<Entity>, ApiError> {
+    let internal = |e: ferrumyx_db::DbError| ApiError::Internal(e.to_string());
+    if let Ok(id) = uuid::Uuid::parse_str(key) {
+        return repo.find_by_id(id).await.map_err(internal);
+    }
+
+    let upper = key.to_uppercase();
+    let mut spellings = vec![key.to_string(), upper.clone()];
+    spellings.dedup();
+    let mut external_ids = spellings.clone();
+    match upper.strip_prefix("MESH:") {
+        Some(bare) => external_ids.push(bare.to_string()),
+        None if is_mesh_id(&upper) => external_ids.push(format!("MESH:{upper}")),
+        None => {}
+    }
+
+    for id in &external_ids {
+        let found = repo.find_by_external_id(id).await.map_err(internal)?;
+        if let Some(entity) = prefer_gene(found) {
+            return Ok(Some(entity));
+        }
+    }
+    for name in &spellings {
+        let found = repo.find_by_name(name).await.map_err(internal)?;
+        if let Some(entity) = prefer_gene(found) {
+            return Ok(Some(entity));
+        }
+    }
+    for name in &spellings {
+        let found = repo.find_by_synonym(name).await.map_err(internal)?;
+        if let Some(entity) = prefer_gene(found) {
+            return Ok(Some(entity));
+        }
+    }
+    Ok(None)
+}
+
+/// A MeSH descriptor ("D010190") or supplementary concept ("C000657245") id.
+fn is_mesh_id(id: &str) -> bool {
+    id.len() >= 7 && id.starts_with(['C', 'D']) && id[1..].bytes().all(|b| b.is_ascii_digit())
+}
+
+fn prefer_gene(mut found: Vec<Entity>) -> Option<Entity> {
+    found.sort_by_key(|e| e.entity_type != "gene");
+    found.into_iter().next()
+}
+
+/// Up to `limit` entities whose name or synonyms look most like `key`, best
+/// first.
+async fn entity_suggestions(
+    repo: &EntityRepository,
+    key: &str,
+    limit: usize,
+) -> Result<Vec<ApiEntityMatch>, ApiError> {
+    // The store only matches substrings, so a misspelt symbol is found
+    // through its first letters.
+    let upper = key.to_uppercase();
+    let prefix: String = upper.chars().take(2).collect();
+    let mut candidates = Vec::new();
+    for q in [key, upper.as_str(), prefix.as_str()] {
+        if q.chars().count() >= 2 {
+            candidates.extend(
+                repo.search(q, SUGGESTION_POOL)
+                    .await
+                    .map_err(|e| ApiError::Internal(e.to_string()))?,
+            );
+        }
+    }
+    Ok(rank_suggestions(&upper, candidates, limit))
+}
+
+fn rank_suggestions(upper: &str, candidates: Vec<Entity>, limit: usize) -> Vec<ApiEntityMatch> {
+    let mut seen = HashSet::new();
+    let mut scored: Vec<(f64, Entity)> = candidates
+        .into_iter()
+        .filter(|e| seen.insert(e.id))
+        .filter_map(|e| {
+            let score = entity_spellings(&e)
+                .map(|s| {
+                    let s = s.to_uppercase();
+                    let similarity = strsim::normalized_damerau_levenshtein(upper, &s);
+                    if upper.len() >= 3 && s.contains(upper) {
+                        similarity.max(0.8)
+                    } else {
+                        similarity
+                    }
+                })
+                .fold(0.0, f64::max);
+            (score >= 0.5).then_some((score, e))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.name.cmp(&b.1.name)));
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(_, e)| ApiEntityMatch {
+            id: e.id,
+            name: e.name,
+            entity_type: e.entity_type,
+            external_id: e.external_id,
+        })
+        .collect()
+}
+
+/// Name, canonical name and synonyms; synonyms are stored as a JSON list.
+fn entity_spellings(entity: &Entity) -> impl Iterator<Item = String> + '_ {
+    let synonyms: Vec<String> = entity
+        .synonyms
+        .as_deref()
+        .and_then(|s| serde_json::from_str(s).ok())
+        .unwrap_or_default();
+    std::iter::once(entity.name.clone())
+        .chain(entity.canonical_name.clone())
+        .chain(synonyms)
+}
+
+/// One row per triple, with rows of the same triple merged as
+/// [`KgFactRepository::upsert_batch`] would. Superseded, mention and
+/// self-referencing facts are left out, as in the graph index.
+fn merge_entity_facts(facts: Vec<KgFact>) -> Vec<KgFact> {
+    let mut by_triple: HashMap<(uuid::Uuid, String, uuid::Uuid), Vec<KgFact>> = HashMap::new();
+    for fact in facts {
+        if fact.valid_until.is_some()
+            || fact.evidence_type == "mention"
+            || fact.subject_id == fact.object_id
+        {
+            continue;
+        }
+        by_triple
+            .entry((fact.subject_id, fact.predicate.clone(), fact.object_id))
+            .or_default()
+            .push(fact);
+    }
+    by_triple.into_values().filter_map(merge_facts).collect()
+}
+
+/// Facts per predicate, largest group first and each group sorted by
+/// aggregate confidence, then support count.
+fn group_by_predicate(facts: &[KgFact]) -> Vec<(&str, Vec<&KgFact>)> {
+    let mut groups: BTreeMap<&str, Vec<&KgFact>> = BTreeMap::new();
+    for fact in facts {
+        groups.entry(&fact.predicate).or_default().push(fact);
+    }
+    let mut groups: Vec<(&str, Vec<&KgFact>)> = groups.into_iter().collect();
+    for (_, facts) in &mut groups {
+        facts.sort_by(|a, b| {
+            b.confidence
+                .total_cmp(&a.confidence)
+                .then(b.support_count.cmp(&a.support_count))
+                .then_with(|| a.subject_name.cmp(&b.subject_name))
+                .then_with(|| a.object_name.cmp(&b.object_name))
+                .then(a.id.cmp(&b.id))
+        });
+    }
+    groups.sort_by_key(|(_, facts)| std::cmp::Reverse(facts.len()));
+    groups
+}
+
+/// The other ends of `facts`, most facts shared first.
+fn related_entities(
+    entity_id: uuid::Uuid,
+    facts: &[KgFact],
+    limit: usize,
+) -> Vec<ApiRelatedEntity> {
+    let mut by_id: HashMap<uuid::Uuid, ApiRelatedEntity> = HashMap::new();
+    for fact in facts {
+        let (id, name) = if fact.subject_id == entity_id {
+            (fact.object_id, &fact.object_name)
+        } else {
+            (fact.subject_id, &fact.subject_name)
+        };
+        if id.is_nil() {
+            continue;
+        }
+        let related = by_id.entry(id).or_insert_with(|| ApiRelatedEntity {
+            id,
+            name: name.clone(),
+            fact_count: 0,
+            predicates: Vec::new(),
+            max_confidence: 0.0,
+            summary_url: format!("/api/kg/entity/{id}"),
+            neighborhood_url: format!("/api/kg/neighborhood?entity={id}"),
+        });
+        related.fact_count += 1;
+        related.max_confidence = related.max_confidence.max(fact.confidence);
+        if !related.predicates.contains(&fact.predicate) {
+            related.predicates.push(fact.predicate.clone());
+        }
+    }
+    let mut related: Vec<ApiRelatedEntity> = by_id.into_values().collect();
+    for r in &mut related {
+        r.predicates.sort();
+    }
+    related.sort_by(|a, b| {
+        b.fact_count
+            .cmp(&a.fact_count)
+            .then(b.max_confidence.total_cmp(&a.max_confidence))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    related.truncate(limit);
+    related
+}
+
+/// GET /api/kg/citations?paper_ids=... - Citation edges among papers, for the explorer overlay
+pub async fn api_kg_citations(
+    State(state): State<SharedState>,
+    Query(query): Query<KgCitationQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let mut paper_ids = Vec::new();
+    for raw in query
+        .paper_ids
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        let id = uuid::Uuid::parse_str(raw)
+            .map_err(|_| ApiError::BadRequest(format!("Invalid paper id {raw}")))?;
+        if !paper_ids.contains(&id) {
+            paper_ids.push(id);
+        }
+    }
+    if paper_ids.len() > 500 {
+        return Err(ApiError::BadRequest(
+            "At most 500 paper ids per request".to_string(),
+        ));
+    }
+
+    let edges = PaperCitationRepository::new(state.db.clone())
+        .edges_among(&paper_ids)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    let refs = PaperRepository::new(state.db.clone())
+        .find_references_by_ids(&paper_ids)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(ApiKgCitations {
+        papers: paper_ids
+            .iter()
+            .filter_map(|id| {
+                refs.get(id).map(|r| ApiPaperCitation {
+                    id: *id,
+                    title: r.title.clone(),
+                    doi: r.doi.clone(),
+                    pmid: r.pmid.clone(),
+                })
+            })
+            .collect(),
+        edges: edges
+            .into_iter()
+            .filter_map(|c| {
+                Some(ApiCitationEdge {
+                    source: c.citing_paper_id,
+                    target: c.cited_paper_id?,
+                    cited_doi: c.cited_doi,
+                    context: c.context,
+                })
+            })
+            .collect(),
+    }))
+}
+
+/// GET /api/kg/paper/{id}/citations - References of one paper and the stored papers citing it
+pub async fn api_kg_paper_citations(
+    State(state): State<SharedState>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let paper_id = uuid::Uuid::parse_str(id.trim())
+        .map_err(|_| ApiError::BadRequest(format!("Invalid paper id {id}")))?;
+    let repo = PaperCitationRepository::new(state.db.clone());
+    let cites = repo
+        .citations_of(paper_id)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    let cited_by = repo
+        .cited_by(paper_id)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(ApiPaperCitations {
+        paper_id,
+        cites,
+        cited_by,
+    }))
+}
+
+/// GET /api/kg/conflicts?severity=...&limit=...&offset=... - Contradictory fact pairs
+pub async fn api_kg_conflicts(
+    State(state): State<SharedState>,
+    Query(query): Query<ConflictQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let severity = query
+        .severity
+        .as_deref()
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty());
+    if let Some(s) = severity.as_deref() {
+        if !matches!(s, "low" | "medium" | "high") {
+            return Err(ApiError::BadRequest(format!("Unknown severity {s}")));
+        }
+    }
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    let offset = query.offset.unwrap_or(0);
+
+    let conflicts = KgConflictRepository::new(state.db.clone())
+        .list(severity.as_deref(), offset, limit)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let fact_repo = KgFactRepository::new(state.db.clone());
+    let mut facts = HashMap::new();
+    for id in conflicts.iter().flat_map(|c| [c.fact_a_id, c.fact_b_id]) {
+        if facts.contains_key(&id) {
+            continue;
+        }
+        if let Some(fact) = fact_repo
+            .find_by_id(id)
+            .await
+            .map_err(|e| ApiError::Internal(e.to_string()))?
+        {
+            facts.insert(id, fact);
+        }
+    }
+
+    let out: Vec<ApiKgConflict> = conflicts
+        .into_iter()
+        .map(|c| {
+            let a = facts.get(&c.fact_a_id);
+            let b = facts.get(&c.fact_b_id);
+            ApiKgConflict {
+                id: c.id,
+                subject: a.or(b).map(|f| f.subject_name.clone()).unwrap_or_default(),
+                object: a.or(b).map(|f| f.object_name.clone()).unwrap_or_default(),
+                fact_a_id: c.fact_a_id,
+                predicate_a: a.map(|f| f.predicate.clone()).unwrap_or_default(),
+                confidence_a: a.map(|f| f.confidence),
+                fact_b_id: c.fact_b_id,
+                predicate_b: b.map(|f| f.predicate.clone()).unwrap_or_default(),
+                confidence_b: b.map(|f| f.confidence),
+                severity: c.severity,
+                net_confidence: c.net_confidence,
+                resolution: c.resolution,
+                paper_ids: c.paper_ids,
+                detected_at: c.detected_at.to_rfc3339(),
+            }
+        })
+        .collect();
+
+    Ok(Json(out))
+}
+
+/// Escape `content` and wrap the byte range `start..end` in `<mark>`; an
+/// unknown or out-of-range span leaves the content unmarked.
+fn mark_evidence(content: &str, start: Option<i64>, end: Option<i64>) -> String {
+    let span = start
+        .zip(end)
+        .and_then(|(s, e)| Some((usize::try_from(s).ok()?, usize::try_from(e).ok()?)))
+        .filter(|(s, e)| s < e && content.get(*s..*e).is_some());
+    match span {
+        Some((s, e)) => format!(
+            "{}<mark>{}</mark>{}",
+            html_escape(&content[..s]),
+            html_escape(&content[s..e]),
+            html_escape(&content[e..])
+        ),
+        None => html_escape(content),
+    }
+}
+
 /// GET /api/kg/stats - KG statistics
 pub async fn api_kg_stats(State(state): State<SharedState>) -> Result<impl IntoResponse, ApiError> {
     let entity_repo = EntityRepository::new(state.db.clone());
@@ -308,6 +1164,10 @@ pub async fn kg_page(
     let fact_repo = KgFactRepository::new(state.db.clone());
     let paper_repo = PaperRepository::new(state.db.clone());
     let total_papers = paper_repo.count().await.unwrap_or(0);
+    let conflict_count = KgConflictRepository::new(state.db.clone())
+        .count()
+        .await
+        .unwrap_or(0);
 
     let scanned = fact_repo
         .list_filtered(
@@ -384,6 +1244,9 @@ pub async fn kg_page(
     struct EdgeAccumulator {
         evidence_count: usize,
         confidence_sum: f64,
+        confidence_weight: f64,
+        paper_ids: HashSet<uuid::Uuid>,
+        review_paper_ids: HashSet<uuid::Uuid>,
         high_count: usize,
         medium_count: usize,
         low_count: usize,
@@ -404,6 +1267,7 @@ pub async fn kg_page(
     };
     let mut edge_acc: HashMap<(String, String, String), EdgeAccumulator> = HashMap::new();
     let mut raw_degree: HashMap<String, usize> = HashMap::new();
+    let review_weight = review_evidence_weight();
 
     for f in &filtered_facts {
         let source = f.subject_name.trim();
@@ -417,7 +1281,16 @@ pub async fn kg_page(
             .entry((source.to_string(), target.to_string(), f.predicate.clone()))
             .or_default();
         entry.evidence_count += 1;
-        entry.confidence_sum += (f.confidence as f64).clamp(0.01, 1.0);
+        // Reviews restate primary findings: down-weight, and count them apart.
+        let fact_weight = if is_review_study_type(f.study_type.as_deref()) {
+            entry.review_paper_ids.insert(f.paper_id);
+            review_weight
+        } else {
+            entry.paper_ids.insert(f.paper_id);
+            1.0
+        };
+        entry.confidence_sum += (f.confidence as f64).clamp(0.01, 1.0) * fact_weight;
+        entry.confidence_weight += fact_weight;
         match classify_confidence_tier(f) {
             "high" => entry.high_count += 1,
             "medium" => entry.medium_count += 1,
@@ -438,11 +1311,12 @@ pub async fn kg_page(
         .map(|((source, target, predicate), acc)| {
             let src_deg = raw_degree.get(&source).copied().unwrap_or(1);
             let dst_deg = raw_degree.get(&target).copied().unwrap_or(1);
-            let avg_confidence = if acc.evidence_count > 0 {
-                acc.confidence_sum / acc.evidence_count as f64
+            let avg_confidence = if acc.confidence_weight > 0.0 {
+                acc.confidence_sum / acc.confidence_weight
             } else {
                 0.5
             };
+            let review_count = acc.review_paper_ids.difference(&acc.paper_ids).count();
             let confidence_tier = dominant_label(
                 &[
                     ("high", acc.high_count),
@@ -485,6 +1359,8 @@ pub async fn kg_page(
                 confidence_tier,
                 provenance,
                 score,
+                paper_count: acc.paper_ids.len(),
+                review_count,
             }
         })
         .collect();
@@ -849,6 +1725,15 @@ pub async fn kg_page(
                 "specificity": (edge.specificity * 1000.0).round() / 1000.0,
                 "confidence_tier": edge.confidence_tier,
                 "provenance": edge.provenance,
+                "paper_count": edge.paper_count,
+                "review_count": edge.review_count,
+                "support": AggregatedSupport {
+                    confidence: edge.avg_confidence,
+                    paper_count: edge.paper_count,
+                    review_count: edge.review_count,
+                }
+                .label(),
+                "review_only": edge.paper_count == 0 && edge.review_count > 0,
             }));
         }
     }
@@ -976,6 +1861,7 @@ pub async fn kg_page(
         title: String,
         url: Option<String>,
         duplicate_count: usize,
+        is_review: bool,
         rows: Vec<(String, String, String, Option<String>)>,
     }
 
@@ -997,6 +1883,7 @@ pub async fn kg_page(
             title: title.clone(),
             url: None,
             duplicate_count: 0usize,
+            is_review: false,
             rows: Vec::new(),
         });
         if entry.title.is_empty() {
@@ -1006,18 +1893,28 @@ pub async fn kg_page(
             entry.url = url;
         }
         entry.duplicate_count += 1;
+        entry.is_review |= paper_ref.is_some_and(|r| r.is_review);
         entry.rows.extend(rows);
     }
     let mut grouped_entries: Vec<(
         String,
         Option<String>,
         usize,
+        bool,
         Vec<(String, String, String, Option<String>)>,
     )> = title_grouped
         .into_values()
-        .map(|group| (group.title, group.url, group.duplicate_count, group.rows))
+        .map(|group| {
+            (
+                group.title,
+                group.url,
+                group.duplicate_count,
+                group.is_review,
+                group.rows,
+            )
+        })
         .collect();
-    grouped_entries.sort_by(|a, b| b.3.len().cmp(&a.3.len()));
+    grouped_entries.sort_by(|a, b| b.4.len().cmp(&a.4.len()));
     let matched_paper_count = grouped_entries.len();
 
     let paper_html = if grouped_entries.is_empty() {
@@ -1029,7 +1926,7 @@ pub async fn kg_page(
         grouped_entries
             .into_iter()
             .take(max_papers)
-            .map(|(title, paper_url, duplicate_count, rows)| {
+            .map(|(title, paper_url, duplicate_count, is_review, rows)| {
                 let expanded_key = canonical_text_bucket(&title);
                 let open_attr = if !expanded_paper.is_empty() && expanded_paper == expanded_key {
                     "open"
@@ -1094,7 +1991,7 @@ pub async fn kg_page(
                                 <span class="info-tip">i
                                     <span class="tooltip-card">{}</span>
                                 </span>
-                                {}
+                                {}{}
                             </div>
                             <span class="badge badge-primary">{} relations</span>
                         </summary>
@@ -1122,6 +2019,11 @@ pub async fn kg_page(
                     } else {
                         String::new()
                     },
+                    if is_review {
+                        r#"<span class="badge badge-warning" title="Review article: facts down-weighted and not counted as independent papers">Review</span>"#
+                    } else {
+                        ""
+                    },
                     rows.len(),
                     rows_html
                 )
@@ -1396,6 +2298,7 @@ pub async fn kg_page(
                 <span class="badge badge-outline">confidence: {}</span>
                 <span class="badge badge-outline">{} nodes</span>
                 <span class="badge badge-outline">{} links</span>
+                <a class="badge {}" href="/api/kg/conflicts" title="Fact pairs with opposing predicates for the same subject and object">{} conflicts</a>
             </div>
         </div>
         {}
@@ -1437,6 +2340,57 @@ pub async fn kg_page(
         return unit * span;
     }}
 
+    // Shift-click on a 2D node merges its one-hop neighbourhood from the
+    // graph index into the rendered graph.
+    async function expandNeighborhood(graph, node) {{
+        if (!node || node.expanded) return;
+        node.expanded = true;
+        try {{
+            const resp = await fetch(`/api/kg/neighborhood?entity=${{encodeURIComponent(node.id)}}&depth=1&limit=40`, {{
+                headers: {{ 'Accept': 'application/json' }},
+            }});
+            if (!resp.ok) return;
+            const sub = await resp.json();
+            const data = graph.graphData();
+            const endId = (end) => (end && typeof end === 'object') ? end.id : end;
+            const ids = new Set(data.nodes.map((n) => n.id));
+            const nodes = (sub.nodes || [])
+                .filter((n) => n.name && !ids.has(n.name))
+                .map((n) => ({{
+                    id: n.name,
+                    name: n.name,
+                    short: n.name.length > 42 ? `${{n.name.slice(0, 41)}}...` : n.name,
+                    group: 2,
+                    degree: 1,
+                    size: 3.2,
+                    x: node.x + deterministicOffset(n.name, 'xexp', 60),
+                    y: node.y + deterministicOffset(n.name, 'yexp', 60),
+                }}));
+            nodes.forEach((n) => ids.add(n.id));
+            const keys = new Set(data.links.map((l) => `${{endId(l.source)}}|${{l.label}}|${{endId(l.target)}}`));
+            const links = [];
+            for (const e of (sub.edges || [])) {{
+                const key = `${{e.subject_name}}|${{e.predicate}}|${{e.object_name}}`;
+                if (keys.has(key) || !ids.has(e.subject_name) || !ids.has(e.object_name)) continue;
+                keys.add(key);
+                links.push({{
+                    source: e.subject_name,
+                    target: e.object_name,
+                    label: e.predicate,
+                    weight: 1,
+                    support: e.support_count,
+                    score: e.confidence,
+                }});
+            }}
+            if (nodes.length || links.length) {{
+                graph.graphData({{ nodes: data.nodes.concat(nodes), links: data.links.concat(links) }});
+                graph.resumeAnimation();
+            }}
+        }} catch (_err) {{
+            node.expanded = false;
+        }}
+    }}
+
     function setEntityOptions(items) {{
         entityOptions.innerHTML = '';
         (items || []).forEach(item => {{
@@ -1568,7 +2522,7 @@ pub async fn kg_page(
         const Graph = ForceGraph()(elem)
             .graphData(graphData)
             .backgroundColor('transparent')
-            .nodeLabel(node => `${{node.name}} (${{node.degree}} links)`)
+            .nodeLabel(node => `${{node.name}} (${{node.degree}} links) · shift-click to expand`)
             .nodeVal(node => densePreset ? Math.max(1.2, (node.size || 3) * 0.55) : (node.size || 3))
             .linkColor(link => {{
                 const l = (link.label || '').toLowerCase();
@@ -1586,10 +2540,16 @@ pub async fn kg_page(
                 const scale = densePreset ? 0.52 : 0.78;
                 return Math.min(densePreset ? 1.45 : 2.8, base + Math.log2((link.weight || 1) + 1) * scale + score * scoreGain);
             }})
+            .linkLineDash(link => link.review_only ? [2, 2] : null)
+            .linkLabel(link => `${{String(link.label || '').replaceAll('_', ' ')}} · papers: ${{link.support || link.weight || 0}}`)
             .cooldownTime(densePreset ? 1700 : 1300)
             .d3AlphaDecay(densePreset ? 0.14 : 0.12)
             .d3VelocityDecay(densePreset ? 0.54 : 0.5)
-            .onNodeClick(node => {{
+            .onNodeClick((node, event) => {{
+                if (event && event.shiftKey) {{
+                    expandNeighborhood(Graph, node);
+                    return;
+                }}
                 Graph.centerAt(node.x, node.y, 800);
                 Graph.zoom(4.2, 600);
             }})
@@ -1973,6 +2933,12 @@ pub async fn kg_page(
         confidence_badge,
         final_node_degree.len(),
         graph_link_count,
+        if conflict_count > 0 {
+            "badge-warning"
+        } else {
+            "badge-outline"
+        },
+        conflict_count,
         lens_status_html,
         max_papers,
         paper_html,
@@ -2747,3 +3713,312 @@ fn derive_entity_stats_from_facts(facts: &[ferrumyx_db::schema::KgFact]) -> (u64
         cancers.len() as u64,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::AppState;
+    use ferrumyx_db::kg_facts::aggregate_confidence;
+    use ferrumyx_db::schema::{EntityMention, EntityType, FactSupport, Paper};
+    use std::sync::Arc;
+
+    struct Fixture {
+        state: SharedState,
+        dir: std::path::PathBuf,
+        kras: Entity,
+        tp53: Entity,
+    }
+
+    fn entity(entity_type: EntityType, name: &str, external_id: &str) -> Entity {
+        Entity::new(
+            entity_type,
+            name.to_string(),
+            external_id.to_string(),
+            "test".to_string(),
+        )
+    }
+
+    fn fact(paper: &Paper, s: &Entity, predicate: &str, o: &Entity, confidence: f32) -> KgFact {
+        let mut fact = KgFact::new(
+            paper.id,
+            s.id,
+            s.name.clone(),
+            predicate.to_string(),
+            o.id,
+            o.name.clone(),
+        );
+        fact.confidence = confidence;
+        fact
+    }
+
+    /// KRAS with five live triples over three predicates, one of them
+    /// stored as two legacy rows, plus a superseded and a mention fact and
+    /// seven mentions.
+    async fn fixture() -> Fixture {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-web-kg-{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(ferrumyx_db::Database::open(&dir.join("db")).await.unwrap());
+
+        let mut kras = entity(EntityType::Gene, "KRAS", "HGNC:6407");
+        kras.synonyms = Some(r#"["KRAS2","RASK2"]"#.to_string());
+        let tp53 = entity(EntityType::Gene, "TP53", "HGNC:11998");
+        let smad4 = entity(EntityType::Gene, "SMAD4", "HGNC:6770");
+        let pdac = entity(EntityType::Disease, "Pancreatic Neoplasms", "MESH:D010190");
+        let sotorasib = entity(EntityType::Chemical, "sotorasib", "CHEMBL4535757");
+        let stale = entity(EntityType::Gene, "NRAS", "HGNC:7989");
+        EntityRepository::new(db.clone())
+            .insert_batch(&[
+                kras.clone(),
+                tp53.clone(),
+                smad4.clone(),
+                pdac.clone(),
+                sotorasib.clone(),
+                stale.clone(),
+            ])
+            .await
+            .unwrap();
+
+        let papers: Vec<Paper> = (1..=3)
+            .map(|i| {
+                let mut paper = Paper::new(format!("KRAS paper {i}"), "test".to_string());
+                paper.pmid = Some(format!("{i}000"));
+                paper
+            })
+            .collect();
+        PaperRepository::new(db.clone())
+            .insert_batch(&papers)
+            .await
+            .unwrap();
+
+        let mut tp53_to_kras = fact(&papers[0], &tp53, "associated_with", &kras, 0.7);
+        tp53_to_kras.supporting_evidence = [(0, 0.5), (2, 0.4)]
+            .iter()
+            .map(|&(i, confidence)| FactSupport {
+                paper_id: papers[i].id,
+                confidence,
+                evidence: None,
+                chunk_id: None,
+                published_at: None,
+            })
+            .collect();
+        tp53_to_kras.support_count = 2;
+        // As confident as TP53 -> KRAS, from one paper instead of two.
+        let kras_to_tp53 = aggregate_confidence([0.5, 0.4]);
+        let mut superseded = fact(&papers[0], &kras, "associated_with", &stale, 0.99);
+        superseded.valid_until = Some(chrono::Utc::now());
+        let mut mention = fact(&papers[1], &kras, "mentions", &pdac, 1.0);
+        mention.evidence_type = "mention".to_string();
+        KgFactRepository::new(db.clone())
+            .insert_batch(&[
+                fact(&papers[0], &kras, "drives", &pdac, 0.6),
+                fact(&papers[1], &kras, "drives", &pdac, 0.5),
+                fact(&papers[1], &kras, "associated_with", &smad4, 0.9),
+                tp53_to_kras,
+                fact(&papers[2], &kras, "associated_with", &tp53, kras_to_tp53),
+                fact(&papers[2], &kras, "inhibited_by", &sotorasib, 0.95),
+                superseded,
+                mention,
+            ])
+            .await
+            .unwrap();
+
+        let start = chrono::Utc::now() - chrono::Duration::hours(1);
+        let mentions: Vec<EntityMention> = (0..7)
+            .map(|i| {
+                let mut m = EntityMention::new(
+                    kras.id,
+                    uuid::Uuid::new_v4(),
+                    papers[i % 3].id,
+                    "KRAS".to_string(),
+                    0,
+                    4,
+                );
+                m.context = Some(format!("KRAS sentence {i}."));
+                m.created_at = start + chrono::Duration::minutes(i as i64);
+                m
+            })
+            .collect();
+        EntityMentionRepository::new(db.clone())
+            .insert_batch(&mentions)
+            .await
+            .unwrap();
+
+        Fixture {
+            state: Arc::new(AppState::new(db)),
+            dir,
+            kras,
+            tp53,
+        }
+    }
+
+    async fn summary(
+        state: &SharedState,
+        key: &str,
+        query: serde_json::Value,
+    ) -> (StatusCode, serde_json::Value) {
+        let query = serde_json::from_value(query).unwrap();
+        let response = api_kg_entity(State(state.clone()), Path(key.to_string()), Query(query))
+            .await
+            .into_response();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    fn objects(group: &serde_json::Value) -> Vec<(&str, &str)> {
+        group["facts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| {
+                (
+                    f["subject"].as_str().unwrap(),
+                    f["object"].as_str().unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn facts_are_grouped_by_predicate_and_merged_per_triple() {
+        let f = fixture().await;
+        let (status, body) = summary(&f.state, "KRAS", serde_json::json!({})).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["entity"]["id"], f.kras.id.to_string());
+        assert_eq!(
+            body["fact_counts"],
+            serde_json::json!({ "associated_with": 3, "drives": 1, "inhibited_by": 1 })
+        );
+
+        let groups = body["facts"].as_array().unwrap();
+        let predicates: Vec<&str> = groups
+            .iter()
+            .map(|g| g["predicate"].as_str().unwrap())
+            .collect();
+        assert_eq!(predicates, ["associated_with", "drives", "inhibited_by"]);
+        // Ties on confidence go to the fact with more papers behind it.
+        assert_eq!(
+            objects(&groups[0]),
+            [("KRAS", "SMAD4"), ("TP53", "KRAS"), ("KRAS", "TP53")]
+        );
+        assert_eq!(groups[0]["facts"][1]["direction"], "incoming");
+        assert_eq!(groups[0]["facts"][1]["papers"].as_array().unwrap().len(), 2);
+
+        // The two rows of KRAS drives PDAC count once, backed by both papers.
+        let drives = &groups[1]["facts"][0];
+        assert_eq!(drives["support_count"], 2);
+        assert!((drives["confidence"].as_f64().unwrap() - 0.8).abs() < 1e-6);
+        let pmids: HashSet<&str> = drives["papers"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p["pmid"].as_str().unwrap())
+            .collect();
+        assert_eq!(pmids, HashSet::from(["1000", "2000"]));
+
+        let related: Vec<&str> = body["related"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            related,
+            ["TP53", "sotorasib", "SMAD4", "Pancreatic Neoplasms"]
+        );
+        assert_eq!(body["related"][0]["fact_count"], 2);
+        assert_eq!(
+            body["related"][0]["summary_url"],
+            format!("/api/kg/entity/{}", f.tp53.id)
+        );
+
+        assert_eq!(body["mention_count"], 7);
+        let snippets: Vec<&str> = body["recent_mentions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["snippet"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            snippets,
+            [
+                "KRAS sentence 6.",
+                "KRAS sentence 5.",
+                "KRAS sentence 4.",
+                "KRAS sentence 3.",
+                "KRAS sentence 2."
+            ]
+        );
+        let _ = std::fs::remove_dir_all(&f.dir);
+    }
+
+    #[tokio::test]
+    async fn fact_lists_page_within_each_predicate() {
+        let f = fixture().await;
+        let page = |offset: usize| serde_json::json!({ "limit": 2, "offset": offset });
+
+        let (_, first) = summary(&f.state, "KRAS", page(0)).await;
+        let group = &first["facts"][0];
+        assert_eq!(group["total"], 3);
+        assert_eq!(group["offset"], 0);
+        assert_eq!(group["limit"], 2);
+        assert_eq!(group["has_more"], true);
+        assert_eq!(objects(group), [("KRAS", "SMAD4"), ("TP53", "KRAS")]);
+        assert_eq!(first["facts"][1]["has_more"], false);
+
+        let (_, second) = summary(&f.state, "KRAS", page(2)).await;
+        assert_eq!(objects(&second["facts"][0]), [("KRAS", "TP53")]);
+        assert_eq!(second["facts"][0]["has_more"], false);
+        assert!(second["facts"][1]["facts"].as_array().unwrap().is_empty());
+
+        let (_, past_end) = summary(&f.state, "KRAS", page(5)).await;
+        assert!(past_end["facts"][0]["facts"].as_array().unwrap().is_empty());
+        assert_eq!(past_end["facts"][0]["total"], 3);
+        assert_eq!(past_end["facts"][0]["has_more"], false);
+
+        // One predicate is listed alone, but every predicate is still counted.
+        let (_, one) = summary(
+            &f.state,
+            "KRAS",
+            serde_json::json!({ "predicate": "Drives", "limit": 1 }),
+        )
+        .await;
+        assert_eq!(one["facts"].as_array().unwrap().len(), 1);
+        assert_eq!(one["facts"][0]["predicate"], "drives");
+        assert_eq!(one["fact_counts"].as_object().unwrap().len(), 3);
+        let _ = std::fs::remove_dir_all(&f.dir);
+    }
+
+    #[tokio::test]
+    async fn entities_are_found_by_id_symbol_synonym_or_mesh_id() {
+        let f = fixture().await;
+        let id = f.kras.id.to_string();
+        for key in [id.as_str(), "kras", "HGNC:6407", "KRAS2"] {
+            let (status, body) = summary(&f.state, key, serde_json::json!({})).await;
+            assert_eq!(status, StatusCode::OK, "{key}");
+            assert_eq!(body["entity"]["name"], "KRAS", "{key}");
+        }
+        for key in ["D010190", "mesh:D010190"] {
+            let (status, body) = summary(&f.state, key, serde_json::json!({})).await;
+            assert_eq!(status, StatusCode::OK, "{key}");
+            assert_eq!(body["entity"]["name"], "Pancreatic Neoplasms", "{key}");
+        }
+        let _ = std::fs::remove_dir_all(&f.dir);
+    }
+
+    #[tokio::test]
+    async fn unknown_symbols_get_near_matches() {
+        let f = fixture().await;
+        let (status, body) = summary(&f.state, "KRSA", serde_json::json!({})).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(body["error"].as_str().unwrap().contains("KRSA"));
+        assert_eq!(body["suggestions"][0]["name"], "KRAS");
+        assert_eq!(body["suggestions"][0]["id"], f.kras.id.to_string());
+
+        let (status, body) = summary(&f.state, "ZZZ9", serde_json::json!({})).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(body["suggestions"].as_array().unwrap().is_empty());
+        let _ = std::fs::remove_dir_all(&f.dir);
+    }
+}
@@ -0,0 +1,118 @@
+//! LLM router status: today's token and cost usage per backend, the
+//! response cache counters and the audit log of past calls.
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::llm_usage::UsageSummary;
+use crate::state::SharedState;
+use ferrumyx_common::{error::ApiError, DataClass};
+use ferrumyx_db::{LlmAuditFilter, LlmAuditLog, LlmAuditRepository};
+
+const DEFAULT_AUDIT_LIMIT: usize = 50;
+const MAX_AUDIT_LIMIT: usize = 500;
+
+/// GET /api/llm/usage — today's (UTC) counters against `[llm.limits]`.
+pub async fn api_llm_usage(State(state): State<SharedState>) -> Json<UsageSummary> {
+    let mut summary = state.llm.usage().summary();
+    summary.cache = state.llm.cache_stats();
+    Json(summary)
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct LlmAuditQuery {
+    /// RFC 3339 timestamp or `YYYY-MM-DD` (UTC midnight).
+    pub since: Option<String>,
+    pub backend: Option<String>,
+    /// "PUBLIC", "INTERNAL" or "CONFIDENTIAL", any case.
+    pub class: Option<String>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LlmAuditPage {
+    /// Calls matching the filter, across all pages.
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+    /// Newest first.
+    pub entries: Vec<LlmAuditLog>,
+}
+
+/// GET /api/audit/llm — logged LLM calls, newest first, filtered by
+/// `since`, `backend` and `class` and paged with `limit` / `offset`.
+pub async fn api_audit_llm(
+    State(state): State<SharedState>,
+    Query(q): Query<LlmAuditQuery>,
+) -> Result<Json<LlmAuditPage>, ApiError> {
+    let since = q
+        .since
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(parse_since)
+        .transpose()?;
+    let filter = LlmAuditFilter {
+        since,
+        backend: non_empty(q.backend),
+        data_class: non_empty(q.class)
+            .map(|c| c.parse::<DataClass>())
+            .transpose()
+            .map_err(ApiError::BadRequest)?,
+    };
+    let limit = q
+        .limit
+        .unwrap_or(DEFAULT_AUDIT_LIMIT)
+        .clamp(1, MAX_AUDIT_LIMIT);
+    let offset = q.offset.unwrap_or(0);
+    let (total, entries) = LlmAuditRepository::new(state.db.clone())
+        .search(&filter, offset, limit)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    Ok(Json(LlmAuditPage {
+        total,
+        offset,
+        limit,
+        entries,
+    }))
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+fn parse_since(value: &str) -> Result<chrono::DateTime<chrono::Utc>, ApiError> {
+    if let Ok(ts) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(ts.with_timezone(&chrono::Utc));
+    }
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(|day| day.and_time(chrono::NaiveTime::MIN).and_utc())
+        .map_err(|_| {
+            ApiError::BadRequest(format!(
+                "since must be an RFC 3339 timestamp or YYYY-MM-DD, got {value:?}"
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn since_accepts_timestamps_and_dates() {
+        let day = parse_since("2026-03-01").unwrap();
+        assert_eq!(day.to_rfc3339(), "2026-03-01T00:00:00+00:00");
+        let ts = parse_since("2026-03-01T12:30:00+02:00").unwrap();
+        assert_eq!(ts.to_rfc3339(), "2026-03-01T10:30:00+00:00");
+        assert!(matches!(
+            parse_since("last week"),
+            Err(ApiError::BadRequest(_))
+        ));
+    }
+}
@@ -1,15 +1,23 @@
 //! Self-improvement metrics dashboard.
 
-use axum::{extract::State, response::Html, Json};
-use serde::Serialize;
-use std::collections::{HashMap, HashSet};
+use axum::{
+    extract::{Query, State},
+    response::Html,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use crate::handlers::dashboard::NAV_HTML;
-use crate::state::SharedState;
+use crate::llm_rate_limit::RateLimitStatus;
+use crate::state::{SharedState, COLLECTED_METRICS};
+use ferrumyx_common::error::ApiError;
 use ferrumyx_db::{
     entities::EntityRepository, kg_facts::KgFactRepository, papers::PaperRepository,
-    schema::EntityType, target_scores::TargetScoreRepository,
+    schema::EntityType, target_scores::TargetScoreRepository, MetricRollupRepository,
 };
+use ferrumyx_ingestion::backpressure::{pipeline_memory_gauge, PipelineMemoryGauge};
 use ferrumyx_ingestion::pipeline::load_recent_perf_snapshots;
 
 #[derive(Debug, Serialize)]
@@ -44,6 +52,9 @@ pub struct PerfSnapshotView {
 pub struct PerfResponse {
     summary: PerfSummaryView,
     recent: Vec<PerfSnapshotView>,
+    pipeline_memory: PipelineMemoryGauge,
+    /// Calls waiting on each rate-limited LLM backend.
+    llm_rate_limits: Vec<RateLimitStatus>,
 }
 
 pub async fn metrics_page(State(state): State<SharedState>) -> Html<String> {
@@ -310,6 +321,28 @@ pub async fn metrics_page(State(state): State<SharedState>) -> Html<String> {
         </div>
     </div>
 
+    <div class="card mb-4">
+        <div class="card-header d-flex align-center justify-between">
+            <span>Knowledge Base</span>
+            <span id="kb_sampled_at" class="text-muted small">Sampled every minute</span>
+        </div>
+        <div class="card-body">
+            <div id="kb_summary" class="grid-2 mb-3"><div class="text-muted">No samples yet.</div></div>
+            <div class="d-flex align-center gap-2 mb-2">
+                <select id="kb_metric" class="form-control" style="max-width:260px"></select>
+                <select id="kb_window" class="form-control" style="max-width:120px">
+                    <option value="1h">1 hour</option>
+                    <option value="24h" selected>24 hours</option>
+                    <option value="7d">7 days</option>
+                    <option value="30d">30 days</option>
+                    <option value="90d">90 days</option>
+                </select>
+                <span id="kb_series_note" class="text-muted small"></span>
+            </div>
+            <svg id="kb_series" viewBox="0 0 600 120" preserveAspectRatio="none" style="width:100%;height:120px"></svg>
+        </div>
+    </div>
+
     <h5 class="mb-3 mt-4" style="font-family:'Outfit'">Current Metric Values</h5>
     <div class="grid-2 mb-4">{}</div>
 
@@ -364,9 +397,62 @@ async function refreshPerfPanel() {{
     byId('perf_recent_rows').innerHTML = rows || '<tr><td colspan="6" class="text-muted text-center py-3">No ingestion telemetry yet.</td></tr>';
   }} catch (_) {{}}
 }}
+const KB_LABELS = {{
+  papers: 'Papers',
+  chunks: 'Chunks',
+  chunks_missing_embeddings: 'Chunks Without Embeddings',
+  entities: 'Entities',
+  kg_facts: 'KG Facts',
+  tool_invocations: 'Tool Invocations',
+  llm_tokens_today: 'LLM Tokens Today',
+}};
+async function refreshKbSummary() {{
+  try {{
+    const res = await fetch('/api/metrics/summary');
+    const data = await res.json();
+    const metrics = data.metrics || [];
+    if (!metrics.length) return;
+    document.getElementById('kb_summary').innerHTML = metrics.map((m) => {{
+      const change = Number(m.change_24h || 0);
+      const sign = change > 0 ? '+' : '';
+      return `<div class="metric-card"><div class="metric-label">${{KB_LABELS[m.metric] || m.metric}}</div><div class="metric-value">${{Number(m.value || 0).toLocaleString()}}</div><small class="text-muted">${{sign}}${{change.toLocaleString()}} in 24h</small></div>`;
+    }}).join('');
+    if (data.sampled_at) {{
+      document.getElementById('kb_sampled_at').textContent = `Sampled ${{new Date(data.sampled_at).toLocaleTimeString()}}`;
+    }}
+    const select = document.getElementById('kb_metric');
+    if (!select.options.length) {{
+      select.innerHTML = metrics.map((m) => `<option value="${{m.metric}}">${{KB_LABELS[m.metric] || m.metric}}</option>`).join('');
+      refreshKbSeries();
+    }}
+  }} catch (_) {{}}
+}}
+async function refreshKbSeries() {{
+  const metric = document.getElementById('kb_metric').value;
+  const windowParam = document.getElementById('kb_window').value;
+  if (!metric) return;
+  try {{
+    const res = await fetch(`/api/metrics/timeseries?metric=${{encodeURIComponent(metric)}}&window=${{windowParam}}`);
+    const data = await res.json();
+    const points = data.points || [];
+    const svg = document.getElementById('kb_series');
+    document.getElementById('kb_series_note').textContent = `${{points.length}} ${{data.resolution || ''}} points`;
+    if (points.length < 2) {{ svg.innerHTML = ''; return; }}
+    const values = points.map((p) => Number(p.value || 0));
+    const lo = Math.min(...values), hi = Math.max(...values);
+    const span = hi - lo || 1;
+    const path = values.map((v, i) => `${{(i / (values.length - 1)) * 600}},${{110 - ((v - lo) / span) * 100}}`).join(' ');
+    svg.innerHTML = `<polyline fill="none" stroke="var(--accent-blue)" stroke-width="2" points="${{path}}"/>`;
+  }} catch (_) {{}}
+}}
 document.addEventListener('DOMContentLoaded', () => {{
   refreshPerfPanel();
   setInterval(refreshPerfPanel, 5000);
+  refreshKbSummary();
+  setInterval(refreshKbSummary, 60000);
+  document.getElementById('kb_metric').addEventListener('change', refreshKbSeries);
+  document.getElementById('kb_window').addEventListener('change', refreshKbSeries);
+  setInterval(refreshKbSeries, 60000);
 }});
 </script>
 </body>
@@ -434,7 +520,183 @@ pub async fn metrics_perf_api(State(state): State<SharedState>) -> Json<PerfResp
         predicate_coverage_flagged_runs: coverage_flagged,
     };
 
-    Json(PerfResponse { summary, recent })
+    Json(PerfResponse {
+        summary,
+        recent,
+        pipeline_memory: pipeline_memory_gauge(),
+        llm_rate_limits: state.llm.rate_limits(),
+    })
+}
+
+/// Longest `window` accepted by `/api/metrics/timeseries`.
+const MAX_TIMESERIES_DAYS: i64 = 365;
+
+/// Windows up to this long are served per minute from the sample buffer;
+/// longer ones per day from the rollups.
+const MINUTE_RESOLUTION_HOURS: i64 = 24;
+
+#[derive(Debug, Serialize)]
+pub struct MetricSummaryView {
+    metric: &'static str,
+    value: f64,
+    /// Change against the oldest buffered sample from the last 24 hours.
+    change_24h: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MetricsSummaryResponse {
+    sampled_at: Option<DateTime<Utc>>,
+    metrics: Vec<MetricSummaryView>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TimeseriesQuery {
+    metric: String,
+    #[serde(default = "default_window")]
+    window: String,
+}
+
+fn default_window() -> String {
+    "24h".to_string()
+}
+
+#[derive(Debug, Serialize)]
+pub struct TimeseriesPoint {
+    at: DateTime<Utc>,
+    /// The sample, or the day's last sample.
+    value: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mean: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TimeseriesResponse {
+    metric: String,
+    window: String,
+    /// `minute` or `day`.
+    resolution: &'static str,
+    points: Vec<TimeseriesPoint>,
+}
+
+/// GET /api/metrics/summary — latest value of every collected metric.
+pub async fn api_metrics_summary(State(state): State<SharedState>) -> Json<MetricsSummaryResponse> {
+    let mut latest = state.metrics.latest();
+    if latest.is_empty() {
+        state.sample_metrics().await;
+        latest = state.metrics.latest();
+    }
+    let day_ago = Utc::now() - chrono::Duration::hours(24);
+    let metrics = COLLECTED_METRICS
+        .iter()
+        .filter_map(|&metric| {
+            let sample = latest.get(metric)?;
+            let baseline = state
+                .metrics
+                .samples_since(metric, day_ago)
+                .first()
+                .map_or(sample.value, |s| s.value);
+            Some(MetricSummaryView {
+                metric,
+                value: sample.value,
+                change_24h: sample.value - baseline,
+            })
+        })
+        .collect();
+    Json(MetricsSummaryResponse {
+        sampled_at: latest.values().map(|s| s.at).max(),
+        metrics,
+    })
+}
+
+/// GET /api/metrics/timeseries?metric=papers&window=7d
+pub async fn api_metrics_timeseries(
+    State(state): State<SharedState>,
+    Query(query): Query<TimeseriesQuery>,
+) -> Result<Json<TimeseriesResponse>, ApiError> {
+    let metric = COLLECTED_METRICS
+        .iter()
+        .find(|m| **m == query.metric)
+        .ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "unknown metric '{}'; expected one of {}",
+                query.metric,
+                COLLECTED_METRICS.join(", ")
+            ))
+        })?;
+    let window = parse_window(&query.window).ok_or_else(|| {
+        ApiError::BadRequest(format!(
+            "invalid window '{}'; use e.g. 90m, 24h or 7d (at most {MAX_TIMESERIES_DAYS}d)",
+            query.window
+        ))
+    })?;
+    let since = Utc::now() - window;
+
+    let (resolution, points) = if window <= chrono::Duration::hours(MINUTE_RESOLUTION_HOURS) {
+        let points = state
+            .metrics
+            .samples_since(metric, since)
+            .into_iter()
+            .map(|s| TimeseriesPoint {
+                at: s.at,
+                value: s.value,
+                min: None,
+                max: None,
+                mean: None,
+            })
+            .collect();
+        ("minute", points)
+    } else {
+        let persisted = MetricRollupRepository::new(state.db.clone())
+            .list_since(metric, since.date_naive())
+            .await
+            .map_err(|e| ApiError::Internal(e.to_string()))?;
+        // Rollups not yet written are newer than their persisted rows.
+        let mut by_day: BTreeMap<_, _> = persisted.into_iter().map(|r| (r.day, r)).collect();
+        for rollup in state.metrics.rollups(metric) {
+            if rollup.day >= since.date_naive() {
+                by_day.insert(rollup.day, rollup);
+            }
+        }
+        let points = by_day
+            .into_values()
+            .map(|r| TimeseriesPoint {
+                at: r.day.and_time(chrono::NaiveTime::MIN).and_utc(),
+                value: r.last,
+                min: Some(r.min),
+                max: Some(r.max),
+                mean: Some(r.mean()),
+            })
+            .collect();
+        ("day", points)
+    };
+
+    Ok(Json(TimeseriesResponse {
+        metric: metric.to_string(),
+        window: query.window,
+        resolution,
+        points,
+    }))
+}
+
+/// Parse a window such as `90m`, `24h` or `7d`.
+fn parse_window(window: &str) -> Option<chrono::Duration> {
+    let window = window.trim();
+    let unit = window.chars().last()?;
+    let amount: i64 = window[..window.len() - unit.len_utf8()].parse().ok()?;
+    if amount <= 0 {
+        return None;
+    }
+    let duration = match unit {
+        'm' => chrono::Duration::try_minutes(amount)?,
+        'h' => chrono::Duration::try_hours(amount)?,
+        'd' => chrono::Duration::try_days(amount)?,
+        _ => return None,
+    };
+    (duration <= chrono::Duration::days(MAX_TIMESERIES_DAYS)).then_some(duration)
 }
 
 fn metric_meta(name: &str) -> (&'static str, &'static str, bool) {
@@ -512,3 +774,19 @@ async fn estimate_conflict_rate_from_facts(fact_repo: &KgFactRepository) -> f64
         (conflicting_pairs as f64 / predicates_by_pair.len() as f64).clamp(0.0, 1.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windows_parse_in_minutes_hours_and_days() {
+        assert_eq!(parse_window("90m"), Some(chrono::Duration::minutes(90)));
+        assert_eq!(parse_window("24h"), Some(chrono::Duration::hours(24)));
+        assert_eq!(parse_window(" 7d "), Some(chrono::Duration::days(7)));
+        assert_eq!(parse_window("365d"), Some(chrono::Duration::days(365)));
+        for bad in ["", "d", "7", "0d", "-1h", "1.5h", "2w", "366d"] {
+            assert_eq!(parse_window(bad), None, "{bad}");
+        }
+    }
+}
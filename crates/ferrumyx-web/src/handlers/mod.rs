@@ -1,17 +1,22 @@
 //! HTTP handlers for all web routes.
 
+pub mod admin;
+pub mod answer;
 pub mod chat;
 pub mod dashboard;
 pub mod depmap;
 pub mod federation;
 pub mod ingestion;
 pub mod kg;
+pub mod llm;
 pub mod metrics;
 pub mod molecules;
 pub mod ner;
+pub mod papers;
 pub mod query;
 pub mod ranker;
 pub mod search;
 pub mod settings;
 pub mod system;
 pub mod targets;
+pub mod tools;
@@ -10,7 +10,12 @@ use std::collections::{HashMap, HashSet};
 
 use crate::handlers::dashboard::NAV_HTML;
 use crate::state::SharedState;
-use ferrumyx_db::{entities::EntityRepository, kg_facts::KgFactRepository, schema::EntityType};
+use ferrumyx_db::schema::{DockingResultRecord, EntityType, MoleculeCandidateRecord};
+use ferrumyx_db::{
+    entities::EntityRepository, kg_facts::KgFactRepository, DockingResultRepository,
+    MoleculeCandidateRepository,
+};
+use ferrumyx_molecules::admet::score_admet;
 use ferrumyx_molecules::pipeline::MoleculesPipeline;
 
 #[derive(Deserialize)]
@@ -73,6 +78,27 @@ pub async fn molecules_page(
 
     let total_docking = docking_facts.len() as u64;
 
+    let mut docked = DockingResultRepository::new(state.db.clone())
+        .list()
+        .await
+        .unwrap_or_default();
+    if !gene.is_empty() {
+        docked.retain(|r| r.target.eq_ignore_ascii_case(&gene));
+    }
+    let total_docked = docked.len() as u64;
+    docked.truncate(250);
+    let docked_rows = docking_result_rows(&docked);
+
+    let mut ranked = MoleculeCandidateRepository::new(state.db.clone())
+        .list()
+        .await
+        .unwrap_or_default();
+    if !gene.is_empty() {
+        ranked.retain(|c| c.gene.eq_ignore_ascii_case(&gene));
+    }
+    ranked.truncate(250);
+    let ranked_rows = candidate_rows(&ranked);
+
     #[derive(Default)]
     struct DockAggregate {
         raw_conf_sum: f64,
@@ -197,15 +223,19 @@ pub async fn molecules_page(
                 <svg xmlns="http://www.w3.org/2000/svg" width="28" height="28" viewBox="0 0 24 24"><path d="M11 2v4.07C7.38 6.55 4.55 9.38 4.07 13H2v-2c0-3.86 3.14-7 7-7zm.3 6V2.3A9.975 9.975 0 0 1 20.3 11H16.3c-.45-1.92-2-3.47-3.92-3.92zM4.07 15C4.55 18.62 7.38 21.45 11 21.93V17.9c-1.92-.45-3.47-2-3.92-3.92H4.07zM15 11v2h5.7c-.42 3.86-3.42 6.86-7.28 7.28V15h-2v5.7C5.56 20.28 2 16.56 2 12V6.3c.42-3.86 3.42-6.86 7.28-7.28v2h2v-2C16.44 2.72 20 6.44 20 11h-5z"/></svg>
                 Molecular Docking Engine
             </h1>
-            <p class="text-muted">Docking and compound-readiness signals from persisted KG and entity outputs</p>
+            <p class="text-muted">Stored Vina docking results with rule-based ADMET and PAINS alerts, plus compound-readiness signals from persisted KG and entity outputs</p>
         </div>
     </div>
 
-    <div class="grid-2 mb-4">
+    <div class="grid-3 mb-4">
         <div class="stat-card card-hover">
             <div class="stat-value text-gradient">{}</div>
             <div class="stat-label">Chemical Entities</div>
         </div>
+        <div class="stat-card card-hover">
+            <div class="stat-value text-gradient">{}</div>
+            <div class="stat-label">Docked Ligands</div>
+        </div>
         <div class="stat-card card-hover">
             <div class="stat-value text-gradient">{}</div>
             <div class="stat-label">Docking-Related KG Facts</div>
@@ -218,6 +248,54 @@ pub async fn molecules_page(
         <button type="submit" class="btn btn-primary">Filter</button>
     </form>
 
+    <div class="card mb-4">
+        <div class="card-header">
+            <div>Ranked Candidates</div>
+            <div id="designProgress" class="text-muted small"></div>
+        </div>
+        <div class="table-container">
+            <table class="table">
+                <thead>
+                    <tr>
+                        <th>Run</th>
+                        <th>Rank</th>
+                        <th>Ligand</th>
+                        <th>Pocket</th>
+                        <th>Best Affinity (kcal/mol)</th>
+                        <th>ADMET</th>
+                        <th>SA</th>
+                        <th>Composite</th>
+                        <th>Ranked At</th>
+                    </tr>
+                </thead>
+                <tbody>{}</tbody>
+            </table>
+        </div>
+    </div>
+
+    <div class="card mb-4">
+        <div class="card-header">
+            <div>Docking Results</div>
+        </div>
+        <div class="table-container">
+            <table class="table">
+                <thead>
+                    <tr>
+                        <th>Target</th>
+                        <th>Pocket</th>
+                        <th>Ligand</th>
+                        <th>Best Affinity (kcal/mol)</th>
+                        <th>ADMET</th>
+                        <th>Poses</th>
+                        <th>Receptor</th>
+                        <th>Docked At</th>
+                    </tr>
+                </thead>
+                <tbody>{}</tbody>
+            </table>
+        </div>
+    </div>
+
     <div class="card">
         <div class="card-header">
             <div>Molecular Evidence Snapshot</div>
@@ -244,16 +322,183 @@ pub async fn molecules_page(
     </div>
 </main>
 <script src="/static/js/main.js"></script>
+<script>
+    // Show design_molecules runs as they go and reload once one is ranked.
+    const designEvents = new EventSource('/api/events?topics=molecules');
+    designEvents.onmessage = function(e) {{
+        let data;
+        try {{
+            data = JSON.parse(e.data);
+        }} catch (_err) {{
+            return;
+        }}
+        if (data.type !== 'molecule_progress') return;
+        const label = `${{data.gene}} / ${{data.cancer}}: ${{data.stage}} ${{data.done}}/${{data.total}}`;
+        document.getElementById('designProgress').textContent = label + (data.resumed ? ' (checkpoint)' : '');
+        if (data.stage === 'ranking' && data.done === data.total && !data.resumed) {{
+            setTimeout(() => window.location.reload(), 1000);
+        }}
+    }};
+</script>
 </body>
 </html>"#,
         NAV_HTML,
         total_mols,
+        total_docked,
         total_docking,
         html_escape(&gene),
+        ranked_rows,
+        docked_rows,
         result_rows
     ))
 }
 
+/// Table rows for stored docking results, best affinity first as the
+/// repository returns them; failed ligands show their error instead.
+fn docking_result_rows(results: &[DockingResultRecord]) -> String {
+    if results.is_empty() {
+        return r#"<tr><td colspan="8" class="text-center text-muted py-4">
+            No docking results yet. Run dock_ligand on a target pocket to populate this table.
+        </td></tr>"#
+            .to_string();
+    }
+    results
+        .iter()
+        .map(|r| {
+            let affinity = match (r.best_affinity_kcal, &r.error) {
+                (Some(kcal), _) => {
+                    // Vina scores below -7 are the usual bar for a hit.
+                    let class = if kcal <= -7.0 { "success" } else { "warning" };
+                    format!(r#"<span style="color:var(--{class});">{kcal:.2}</span>"#)
+                }
+                (None, error) => format!(
+                    r#"<span style="color:var(--danger);">failed</span> <span class="text-muted small">{}</span>"#,
+                    html_escape(error.as_deref().unwrap_or_default())
+                ),
+            };
+            format!(
+                r#"<tr>
+                <td style="font-weight:700; color:var(--text-main);">{}</td>
+                <td>{}</td>
+                <td style="font-family: monospace; font-size: 0.9rem;" title="{}">{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td class="text-muted">{}</td>
+                <td class="text-muted small">{}</td>
+                <td class="text-muted small">{}</td>
+            </tr>"#,
+                html_escape(&r.target),
+                r.pocket_rank,
+                html_escape(&r.smiles),
+                html_escape(&r.ligand_id),
+                affinity,
+                admet_cell(&r.smiles),
+                r.poses.len(),
+                html_escape(&r.structure),
+                r.docked_at.to_rfc3339()
+            )
+        })
+        .collect()
+}
+
+/// Table rows for the stored rankings of design_molecules runs.
+fn candidate_rows(candidates: &[MoleculeCandidateRecord]) -> String {
+    if candidates.is_empty() {
+        return r#"<tr><td colspan="9" class="text-center text-muted py-4">
+            No ranked candidates yet. Run design_molecules on a target to populate this table.
+        </td></tr>"#
+            .to_string();
+    }
+    candidates
+        .iter()
+        .map(|c| {
+            let novel = if c.novelty {
+                r#" <span class="badge badge-outline">analog</span>"#
+            } else {
+                ""
+            };
+            let alerts: String = c
+                .pains_alerts
+                .iter()
+                .map(|a| {
+                    format!(
+                        r#" <span class="badge bg-danger">{}</span>"#,
+                        html_escape(a)
+                    )
+                })
+                .collect();
+            format!(
+                r#"<tr>
+                <td style="font-weight:700; color:var(--text-main);">{} <span class="text-muted small">{}</span></td>
+                <td>{}</td>
+                <td style="font-family: monospace; font-size: 0.9rem;" title="from {}">{}{}</td>
+                <td>{}</td>
+                <td>{:.2}</td>
+                <td>{:.2}{}</td>
+                <td>{:.1}</td>
+                <td style="font-weight:700;">{:.3}</td>
+                <td class="text-muted small">{}</td>
+            </tr>"#,
+                html_escape(&c.gene),
+                html_escape(&c.cancer_type),
+                c.rank,
+                html_escape(&c.parent_chembl_id),
+                html_escape(&c.smiles),
+                novel,
+                c.pocket_rank,
+                c.best_affinity_kcal,
+                c.admet_score,
+                alerts,
+                c.sa_score,
+                c.composite_score,
+                c.ranked_at.to_rfc3339()
+            )
+        })
+        .collect()
+}
+
+/// ADMET score of `smiles` with a badge per PAINS alert that fired; the
+/// broken Lipinski and Veber rules are in the tooltip.
+fn admet_cell(smiles: &str) -> String {
+    let Ok(profile) = score_admet(smiles) else {
+        return r#"<span class="text-muted">n/a</span>"#.to_string();
+    };
+    let class = if profile.passes() {
+        "success"
+    } else if profile.pains_alerts.is_empty() {
+        "warning"
+    } else {
+        "danger"
+    };
+    let violations: Vec<&str> = profile
+        .lipinski
+        .violations
+        .iter()
+        .chain(&profile.veber.violations)
+        .map(String::as_str)
+        .collect();
+    let title = if violations.is_empty() {
+        "Lipinski and Veber pass".to_string()
+    } else {
+        violations.join("; ")
+    };
+    let alerts: String = profile
+        .pains_alerts
+        .iter()
+        .map(|a| {
+            format!(
+                r#" <span class="badge bg-danger">{}</span>"#,
+                html_escape(a)
+            )
+        })
+        .collect();
+    format!(
+        r#"<span style="color:var(--{class});" title="{}">{:.2}</span>{alerts}"#,
+        html_escape(&title),
+        profile.score
+    )
+}
+
 fn is_dockingish_predicate(predicate: &str) -> bool {
     let p = predicate.to_ascii_lowercase();
     p.contains("bind")
@@ -1,21 +1,40 @@
 //! NER entity extraction page — real-time entity recognition demo.
 
 use crate::handlers::dashboard::NAV_HTML;
-use crate::state::SharedState;
+use crate::state::{AppState, SharedState};
 use axum::http::StatusCode;
 use axum::{
     extract::State,
     response::{Html, IntoResponse, Json},
     Form,
 };
-use ferrumyx_kg::ner::TrieNer;
+use ferrumyx_common::error::ApiError;
+use ferrumyx_kg::ner::{HybridEntity, HybridNer, NerFilter, Provenance, SpanTagger};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use tokio::sync::OnceCell;
 
 #[derive(Deserialize)]
 pub struct NerForm {
     pub text: String,
+    /// Minimum confidence to keep; overrides `FERRUMYX_NER_MIN_SCORE`.
+    #[serde(default)]
+    pub min_score: Option<f32>,
+    /// Comma-separated labels to keep, e.g. `GENE,DISEASE`.
+    #[serde(default)]
+    pub allowed_types: Option<String>,
+}
+
+impl NerForm {
+    /// Process-wide filter with this request's overrides applied.
+    fn filter(&self) -> NerFilter {
+        let mut filter = NerFilter::from_env();
+        if let Some(min_score) = self.min_score {
+            filter.min_score = min_score.clamp(0.0, 1.0);
+        }
+        if let Some(types) = self.allowed_types.as_deref() {
+            filter.allowed_types = NerFilter::parse_types(types);
+        }
+        filter
+    }
 }
 
 #[derive(Serialize)]
@@ -31,6 +50,8 @@ pub struct EntityResult {
     pub start: usize,
     pub end: usize,
     pub confidence: f32,
+    pub canonical_id: Option<String>,
+    pub canonical_name: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -41,40 +62,27 @@ pub struct NerStats {
     pub total_patterns: usize,
 }
 
-static NER_CACHE: OnceCell<Arc<TrieNer>> = OnceCell::const_new();
-
-async fn get_ner() -> Result<Arc<TrieNer>, String> {
-    let ner = NER_CACHE
-        .get_or_try_init(|| async {
-            TrieNer::with_complete_databases_async()
-                .await
-                .map(Arc::new)
-                .map_err(|e| e.to_string())
-        })
-        .await?;
-    Ok(Arc::clone(ner))
-}
-
 /// GET /ner — Show NER demo page
-pub async fn ner_page(State(_state): State<SharedState>) -> Html<String> {
-    Html(render_ner_page(None, None))
+pub async fn ner_page(State(state): State<SharedState>) -> Html<String> {
+    Html(render_ner_page(&state, None, None))
 }
 
 /// POST /ner/extract — Extract entities from text
 pub async fn ner_extract(
-    State(_state): State<SharedState>,
+    State(state): State<SharedState>,
     Form(form): Form<NerForm>,
 ) -> Html<String> {
-    let ner = match get_ner().await {
+    let ner = match state.ner.trie().await {
         Ok(ner) => ner,
         Err(e) => {
             return Html(render_ner_page(
+                &state,
                 None,
                 Some(format!("NER resources unavailable: {}", e)),
             ));
         }
     };
-    let extracted = ner.extract(&form.text);
+    let extracted = ner.extract_filtered(&form.text, &form.filter());
 
     let entities: Vec<EntityResult> = extracted
         .into_iter()
@@ -84,6 +92,8 @@ pub async fn ner_extract(
             start: e.start,
             end: e.end,
             confidence: e.confidence,
+            canonical_id: e.canonical_id,
+            canonical_name: e.canonical_name,
         })
         .collect();
 
@@ -92,12 +102,12 @@ pub async fn ner_extract(
         entities,
     };
 
-    Html(render_ner_page(Some(result), None))
+    Html(render_ner_page(&state, Some(result), None))
 }
 
 /// GET /api/ner/stats — Get NER database stats
-pub async fn api_ner_stats() -> impl IntoResponse {
-    let ner = match get_ner().await {
+pub async fn api_ner_stats(State(state): State<SharedState>) -> impl IntoResponse {
+    let ner = match state.ner.trie().await {
         Ok(ner) => ner,
         Err(e) => {
             return (
@@ -118,40 +128,213 @@ pub async fn api_ner_stats() -> impl IntoResponse {
     .into_response()
 }
 
-/// POST /api/ner/extract — API endpoint for entity extraction
-pub async fn api_ner_extract(Json(payload): Json<NerForm>) -> impl IntoResponse {
-    let ner = match get_ner().await {
-        Ok(ner) => ner,
-        Err(e) => {
-            return (
-                StatusCode::SERVICE_UNAVAILABLE,
-                format!("NER resources unavailable: {}", e),
-            )
-                .into_response();
+/// Most texts one `POST /api/ner/extract` call may carry.
+const MAX_EXTRACT_TEXTS: usize = 64;
+/// Most bytes of text, summed over all texts, one call may carry.
+const MAX_EXTRACT_BYTES: usize = 512 * 1024;
+
+/// Extractor run by `POST /api/ner/extract`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NerModel {
+    /// Statistical disease tagger.
+    Diseases,
+    /// Statistical gene, mutation and cancer-type tagger.
+    Oncology,
+    /// HGNC and OncoTree dictionary matches; needs no model weights.
+    #[default]
+    Trie,
+    /// The oncology tagger merged with dictionary matches.
+    Hybrid,
+}
+
+impl NerModel {
+    /// Name of the [`SpanTagger`] this model runs, if any.
+    fn tagger(self) -> Option<&'static str> {
+        match self {
+            NerModel::Diseases => Some("diseases"),
+            NerModel::Oncology | NerModel::Hybrid => Some("oncology"),
+            NerModel::Trie => None,
         }
-    };
-    let extracted = ner.extract(&payload.text);
+    }
+}
 
-    let entities: Vec<EntityResult> = extracted
-        .into_iter()
-        .map(|e| EntityResult {
-            text: e.text,
-            entity_type: format!("{:?}", e.label),
-            start: e.start,
-            end: e.end,
-            confidence: e.confidence,
-        })
-        .collect();
+#[derive(Debug, Deserialize)]
+pub struct NerExtractRequest {
+    #[serde(default)]
+    pub texts: Vec<String>,
+    /// One more text, extracted before `texts`; the endpoint's original
+    /// single-text form.
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub model: NerModel,
+    /// Minimum confidence to keep; overrides `FERRUMYX_NER_MIN_SCORE`.
+    #[serde(default)]
+    pub min_score: Option<f32>,
+    /// Labels to keep, as a list or comma-separated, e.g. `["GENE"]`.
+    #[serde(default)]
+    pub allowed_types: Option<TypeList>,
+}
 
-    Json(NerResult {
-        text: payload.text,
-        entities,
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum TypeList {
+    Csv(String),
+    List(Vec<String>),
+}
+
+impl NerExtractRequest {
+    /// Process-wide filter with this request's overrides applied.
+    fn filter(&self) -> NerFilter {
+        let mut filter = NerFilter::from_env();
+        if let Some(min_score) = self.min_score {
+            filter.min_score = min_score.clamp(0.0, 1.0);
+        }
+        match &self.allowed_types {
+            Some(TypeList::Csv(types)) => filter.allowed_types = NerFilter::parse_types(types),
+            Some(TypeList::List(types)) => {
+                filter.allowed_types = NerFilter::parse_types(&types.join(","))
+            }
+            None => {}
+        }
+        filter
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct NerExtractResponse {
+    pub model: NerModel,
+    /// Entities of each text, in request order.
+    pub results: Vec<Vec<NerEntity>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NerEntity {
+    pub text: String,
+    pub entity_type: String,
+    pub start: usize,
+    pub end: usize,
+    pub confidence: f32,
+    /// HGNC id for genes, OncoTree code for cancer types.
+    pub normalized_id: Option<String>,
+    pub canonical_name: Option<String>,
+    pub provenance: Provenance,
+}
+
+impl From<HybridEntity> for NerEntity {
+    fn from(e: HybridEntity) -> Self {
+        Self {
+            text: e.entity.text,
+            entity_type: format!("{:?}", e.entity.label),
+            start: e.entity.start,
+            end: e.entity.end,
+            confidence: e.entity.confidence,
+            normalized_id: e.normalized_id,
+            canonical_name: e.entity.canonical_name,
+            provenance: e.provenance,
+        }
+    }
+}
+
+/// POST /api/ner/extract — entities in a batch of texts.
+///
+/// `model` picks the extractor: `trie` (the default) matches the HGNC and
+/// OncoTree dictionaries, `diseases` and `oncology` run the statistical
+/// tagger registered under that name in [`crate::state::NerModels`], and
+/// `hybrid` merges the oncology tagger with dictionary matches. Model spans
+/// get dictionary ids wherever the dictionary resolves them; texts longer
+/// than a tagger's input are tagged through its sliding window. Offsets are
+/// byte positions into each text.
+///
+/// ```text
+/// curl -s http://localhost:3000/api/ner/extract \
+///   -H 'Content-Type: application/json' \
+///   -d '{"texts": ["KRAS G12D drives PAAD", "TP53 loss in NSCLC"],
+///        "model": "trie", "min_score": 0.8, "allowed_types": ["GENE"]}'
+/// ```
+pub async fn api_ner_extract(
+    State(state): State<SharedState>,
+    Json(request): Json<NerExtractRequest>,
+) -> Result<Json<NerExtractResponse>, ApiError> {
+    let filter = request.filter();
+    let model = request.model;
+    let mut texts = request.texts;
+    if let Some(text) = request.text {
+        texts.insert(0, text);
+    }
+    if texts.len() > MAX_EXTRACT_TEXTS {
+        return Err(ApiError::PayloadTooLarge(format!(
+            "{} texts; at most {MAX_EXTRACT_TEXTS} per request",
+            texts.len()
+        )));
+    }
+    let bytes: usize = texts.iter().map(String::len).sum();
+    if bytes > MAX_EXTRACT_BYTES {
+        return Err(ApiError::PayloadTooLarge(format!(
+            "{bytes} bytes of text; at most {MAX_EXTRACT_BYTES} per request"
+        )));
+    }
+
+    let trie = state
+        .ner
+        .trie()
+        .await
+        .map_err(|e| ApiError::ServiceUnavailable(format!("NER resources unavailable: {e}")))?;
+    let tagger = match model.tagger() {
+        None => None,
+        Some(name) => Some(
+            state
+                .ner
+                .tagger(name)
+                .await
+                .ok_or_else(|| {
+                    ApiError::BadRequest(format!(
+                        "NER model {name} is not available on this server"
+                    ))
+                })?
+                .map_err(|e| {
+                    ApiError::ServiceUnavailable(format!("NER model {name} failed to load: {e}"))
+                })?,
+        ),
+    };
+
+    let results = tokio::task::spawn_blocking(move || {
+        let hybrid = HybridNer::new(trie);
+        texts
+            .iter()
+            .map(|text| extract(model, &hybrid, tagger.as_deref(), text, &filter))
+            .collect::<anyhow::Result<Vec<_>>>()
     })
-    .into_response()
+    .await
+    .map_err(|e| ApiError::Internal(format!("NER extraction panicked: {e}")))?
+    .map_err(|e| ApiError::Internal(format!("NER extraction failed: {e}")))?;
+
+    Ok(Json(NerExtractResponse { model, results }))
 }
 
-fn render_ner_page(result: Option<NerResult>, error: Option<String>) -> String {
-    let cached_stats = NER_CACHE.get().map(|ner| ner.stats());
+/// Entities `filter` keeps in one text.
+fn extract(
+    model: NerModel,
+    hybrid: &HybridNer,
+    tagger: Option<&dyn SpanTagger>,
+    text: &str,
+    filter: &NerFilter,
+) -> anyhow::Result<Vec<NerEntity>> {
+    let entities = match (model, tagger) {
+        (NerModel::Hybrid, Some(tagger)) => hybrid.merge(text, tagger.tag_windowed(text)?),
+        (_, Some(tagger)) => hybrid.normalise(tagger.tag_windowed(text)?),
+        (_, None) => hybrid.merge(text, Vec::new()),
+    };
+    Ok(entities
+        .into_iter()
+        .filter(|e| filter.allows(&e.entity))
+        .map(NerEntity::from)
+        .collect())
+}
+
+fn render_ner_page(state: &AppState, result: Option<NerResult>, error: Option<String>) -> String {
+    let cached_stats = state.ner.loaded_trie().map(|ner| ner.stats());
     let stats_gene_count = cached_stats.as_ref().map(|s| s.gene_count).unwrap_or(0);
     let stats_disease_count = cached_stats.as_ref().map(|s| s.disease_count).unwrap_or(0);
     let stats_chemical_count = cached_stats.as_ref().map(|s| s.chemical_count).unwrap_or(0);
@@ -328,3 +511,198 @@ fn render_ner_page(result: Option<NerResult>, error: Option<String>) -> String {
             .unwrap_or_default()
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::NerModels;
+    use ferrumyx_kg::ner::{
+        CancerNormaliser, EntityType, ExtractedEntity, HgncNormaliser, TrieNer,
+    };
+    use std::sync::Arc;
+
+    fn hgnc_row(id: &str, symbol: &str, aliases: &str) -> String {
+        let mut fields = vec![""; 26];
+        fields[0] = id;
+        fields[1] = symbol;
+        fields[2] = symbol;
+        fields[5] = "Approved";
+        fields[8] = aliases;
+        fields.join("\t")
+    }
+
+    /// Offline dictionary with a handful of genes and cancer types.
+    fn fixture_trie() -> Arc<TrieNer> {
+        let tsv = [
+            "header".to_string(),
+            hgnc_row("HGNC:6407", "KRAS", ""),
+            hgnc_row("HGNC:6770", "SMAD4", ""),
+            hgnc_row("HGNC:11998", "TP53", "p53"),
+        ]
+        .join("\n");
+        let hgnc = HgncNormaliser::from_tsv(&tsv).unwrap();
+        let cancers = CancerNormaliser::from_json(&serde_json::json!([
+            { "code": "PAAD", "name": "Pancreatic Adenocarcinoma" },
+            { "code": "NSCLC", "name": "Non-Small Cell Lung Cancer" }
+        ]))
+        .unwrap();
+        Arc::new(TrieNer::from_normalisers(hgnc, cancers).unwrap())
+    }
+
+    /// Tags "p53" and "ductal carcinoma" in inputs of at most 24 bytes.
+    struct PhraseTagger;
+
+    impl SpanTagger for PhraseTagger {
+        fn max_len(&self) -> usize {
+            24
+        }
+
+        fn tag(&self, text: &str) -> anyhow::Result<Vec<ExtractedEntity>> {
+            anyhow::ensure!(text.len() <= self.max_len(), "input over the model window");
+            let phrases = [
+                ("p53", EntityType::Gene),
+                ("ductal carcinoma", EntityType::Disease),
+            ];
+            Ok(phrases
+                .iter()
+                .flat_map(|&(phrase, label)| {
+                    text.match_indices(phrase)
+                        .map(move |(start, _)| ExtractedEntity {
+                            text: phrase.to_string(),
+                            label,
+                            start,
+                            end: start + phrase.len(),
+                            confidence: 0.7,
+                            canonical_id: None,
+                            canonical_name: None,
+                        })
+                })
+                .collect())
+        }
+    }
+
+    async fn fixture_state(ner: NerModels) -> (SharedState, std::path::PathBuf) {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-web-ner-{}", uuid::Uuid::new_v4()));
+        let db = ferrumyx_db::Database::open(&dir.join("db")).await.unwrap();
+        let state = AppState::new(Arc::new(db)).with_ner_models(Arc::new(ner));
+        (Arc::new(state), dir)
+    }
+
+    async fn extract(
+        state: &SharedState,
+        body: serde_json::Value,
+    ) -> Result<NerExtractResponse, ApiError> {
+        let request = serde_json::from_value(body).unwrap();
+        api_ner_extract(State(state.clone()), Json(request))
+            .await
+            .map(|Json(response)| response)
+    }
+
+    #[tokio::test]
+    async fn trie_extracts_each_text_with_normalized_ids() {
+        let (state, dir) = fixture_state(NerModels::with_trie(fixture_trie())).await;
+        let response = extract(
+            &state,
+            serde_json::json!({
+                "texts": ["KRAS mutant PAAD with SMAD4 loss", "No entities here"],
+                "allowed_types": ["GENE"]
+            }),
+        )
+        .await
+        .unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(response.model, NerModel::Trie);
+        assert_eq!(response.results.len(), 2);
+        let genes: Vec<(&str, Option<&str>, Provenance)> = response.results[0]
+            .iter()
+            .map(|e| (e.text.as_str(), e.normalized_id.as_deref(), e.provenance))
+            .collect();
+        assert_eq!(
+            genes,
+            vec![
+                ("KRAS", Some("HGNC:6407"), Provenance::Trie),
+                ("SMAD4", Some("HGNC:6770"), Provenance::Trie),
+            ]
+        );
+        assert!(response.results[1].is_empty());
+    }
+
+    #[tokio::test]
+    async fn hybrid_tags_long_texts_through_the_window() {
+        let ner = NerModels::with_trie(fixture_trie()).with_tagger(
+            "oncology",
+            Arc::new(|| -> anyhow::Result<Arc<dyn SpanTagger>> { Ok(Arc::new(PhraseTagger)) }),
+        );
+        let (state, dir) = fixture_state(ner).await;
+        let text = "Loss of p53 and KRAS activation are hallmarks of pancreatic ductal carcinoma";
+        let hybrid = extract(
+            &state,
+            serde_json::json!({ "text": text, "model": "hybrid" }),
+        )
+        .await
+        .unwrap();
+        let oncology = extract(
+            &state,
+            serde_json::json!({ "texts": [text], "model": "oncology" }),
+        )
+        .await
+        .unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let found: Vec<(&str, Option<&str>, Provenance)> = hybrid.results[0]
+            .iter()
+            .map(|e| (e.text.as_str(), e.normalized_id.as_deref(), e.provenance))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("p53", Some("HGNC:11998"), Provenance::Model),
+                ("KRAS", Some("HGNC:6407"), Provenance::Trie),
+                ("ductal carcinoma", None, Provenance::Model),
+            ]
+        );
+        let start = text.find("ductal").unwrap();
+        assert_eq!(
+            (hybrid.results[0][2].start, hybrid.results[0][2].end),
+            (start, start + 16)
+        );
+
+        let model_only: Vec<&str> = oncology.results[0]
+            .iter()
+            .map(|e| e.text.as_str())
+            .collect();
+        assert_eq!(model_only, vec!["p53", "ductal carcinoma"]);
+        assert_eq!(
+            oncology.results[0][0].normalized_id.as_deref(),
+            Some("HGNC:11998")
+        );
+    }
+
+    #[tokio::test]
+    async fn oversized_batches_and_unknown_models_are_rejected() {
+        let (state, dir) = fixture_state(NerModels::with_trie(fixture_trie())).await;
+        let too_many = vec!["KRAS"; MAX_EXTRACT_TEXTS + 1];
+        let too_long = "KRAS ".repeat(MAX_EXTRACT_BYTES / 5 + 1);
+        let statuses = [
+            extract(&state, serde_json::json!({ "texts": too_many })).await,
+            extract(&state, serde_json::json!({ "text": too_long })).await,
+            extract(
+                &state,
+                serde_json::json!({ "texts": ["KRAS"], "model": "diseases" }),
+            )
+            .await,
+        ]
+        .map(|result| result.unwrap_err().into_response().status());
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(
+            statuses,
+            [
+                StatusCode::PAYLOAD_TOO_LARGE,
+                StatusCode::PAYLOAD_TOO_LARGE,
+                StatusCode::BAD_REQUEST,
+            ]
+        );
+    }
+}
@@ -0,0 +1,107 @@
+//! Paper deletion endpoint.
+//!
+//! `POST /api/papers/delete` removes a bad ingest by source and query, by
+//! DOI or by ingestion job. A dry run reports what would go; the real
+//! deletion needs the `FERRUMYX_ADMIN_TOKEN` confirmation like the other
+//! destructive admin actions, and queues the targets whose facts lost
+//! support for re-scoring.
+
+use axum::{extract::State, Json};
+use serde::Deserialize;
+
+use crate::handlers::admin::{check_confirmation, ADMIN_TOKEN_ENV};
+use crate::state::SharedState;
+use ferrumyx_common::error::ApiError;
+use ferrumyx_db::{PaperDeletion, PaperFilter, PaperRepository};
+use ferrumyx_kg::update::KgUpdateTrigger;
+
+#[derive(Debug, Deserialize)]
+pub struct PaperDeleteRequest {
+    /// A `PaperFilter`: `{"source_query": {"source", "query"}}`,
+    /// `{"dois": [...]}` or `{"job_id": "..."}`.
+    pub filter: PaperFilter,
+    /// Report what would be removed without removing it.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Stored on each tombstone.
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// Must equal `FERRUMYX_ADMIN_TOKEN` unless `dry_run` is set.
+    #[serde(default)]
+    pub confirm: Option<String>,
+}
+
+/// POST /api/papers/delete — delete papers with their chunks, mentions and
+/// KG fact support, leaving tombstones.
+pub async fn api_papers_delete(
+    State(state): State<SharedState>,
+    Json(req): Json<PaperDeleteRequest>,
+) -> Result<Json<PaperDeletion>, ApiError> {
+    if !req.dry_run {
+        check_confirmation(
+            req.confirm.as_deref().unwrap_or_default(),
+            std::env::var(ADMIN_TOKEN_ENV).ok().as_deref(),
+        )?;
+    }
+    let reason = req
+        .reason
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .unwrap_or("deleted through /api/papers/delete");
+
+    let report = PaperRepository::new(state.db.clone())
+        .delete_papers(&req.filter, reason, req.dry_run)
+        .await
+        .map_err(|e| match e {
+            ferrumyx_db::DbError::InvalidQuery(msg) => ApiError::BadRequest(msg),
+            e => ApiError::Internal(e.to_string()),
+        })?;
+
+    let entity_ids = &report.kg_facts.subject_ids;
+    if let (false, Some(tx), false) = (report.dry_run, &state.kg_events, entity_ids.is_empty()) {
+        let _ = tx.send(KgUpdateTrigger::FactsRetracted {
+            entity_ids: entity_ids.clone(),
+        });
+    }
+    tracing::info!(
+        dry_run = report.dry_run,
+        papers = report.papers.len(),
+        chunks = report.chunks,
+        facts_deleted = report.kg_facts.deleted,
+        facts_updated = report.kg_facts.updated,
+        "Paper deletion"
+    );
+    Ok(Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delete_request_accepts_each_filter() {
+        let req: PaperDeleteRequest = serde_json::from_str(
+            r#"{"filter": {"source_query": {"source": "pubmed", "query": "kras"}}, "dry_run": true}"#,
+        )
+        .unwrap();
+        assert!(req.dry_run);
+        assert_eq!(
+            req.filter,
+            PaperFilter::SourceQuery {
+                source: "pubmed".to_string(),
+                query: "kras".to_string(),
+            }
+        );
+
+        let req: PaperDeleteRequest =
+            serde_json::from_str(r#"{"filter": {"dois": ["10.1000/a"]}, "confirm": "t"}"#).unwrap();
+        assert!(!req.dry_run);
+        assert_eq!(req.filter, PaperFilter::Dois(vec!["10.1000/a".to_string()]));
+
+        let job = uuid::Uuid::from_u128(7);
+        let req: PaperDeleteRequest =
+            serde_json::from_str(&format!(r#"{{"filter": {{"job_id": "{job}"}}}}"#)).unwrap();
+        assert_eq!(req.filter, PaperFilter::JobId(job));
+    }
+}
@@ -1,13 +1,18 @@
 //! Scientific query interface — NL query → ranked target output.
 
 use crate::handlers::dashboard::NAV_HTML;
+use crate::handlers::search::hybrid_paper_hits;
 use crate::state::SharedState;
 use axum::{extract::State, response::Html, Form};
 use ferrumyx_common::query::{QueryRequest, QueryResult};
+use ferrumyx_ingestion::paper_search::PaperSearchResult;
 use ferrumyx_ranker::TargetQueryEngine;
 
+/// Papers shown under the target table.
+const LITERATURE_LIMIT: usize = 10;
+
 pub async fn query_page(State(_state): State<SharedState>) -> Html<String> {
-    Html(render_query_page(None))
+    Html(render_query_page(None, &[]))
 }
 
 pub async fn query_submit(
@@ -20,11 +25,180 @@ pub async fn query_submit(
     req.max_results = req.max_results.clamp(1, 200);
     let query_text = req.query_text.clone();
     let results = engine.execute_query(req).await.unwrap_or_default();
+    let papers = hybrid_paper_hits(&state, &query_text, LITERATURE_LIMIT).await;
+
+    Html(render_query_page(Some((&query_text, results)), &papers))
+}
+
+/// Paper-level literature hits, each expandable to its matching chunks.
+fn render_paper_hits(papers: &[PaperSearchResult]) -> String {
+    if papers.is_empty() {
+        return String::new();
+    }
+    let rows: String = papers
+        .iter()
+        .map(|p| {
+            let title = html_escape(p.title.as_deref().unwrap_or("Untitled paper"));
+            let title_html = match &p.url {
+                Some(url) => format!(
+                    r#"<a href="{}" target="_blank" rel="noopener" style="font-weight:700;">{}</a>"#,
+                    html_escape(url),
+                    title
+                ),
+                None => format!(r#"<span style="font-weight:700;">{}</span>"#, title),
+            };
+            let venue = [
+                p.journal.as_deref().map(html_escape),
+                p.year.map(|y| y.to_string()),
+                p.pmid.as_deref().map(|v| format!("PMID {}", html_escape(v))),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" · ");
+            let chunks_html: String = p
+                .chunks
+                .iter()
+                .map(|c| {
+                    format!(
+                        r#"<div class="small mb-2"><span class="badge badge-outline">{:.3}</span> {}</div>"#,
+                        c.score,
+                        html_escape(&c.content)
+                    )
+                })
+                .collect();
+            format!(
+                r#"<div class="mb-4">
+                    <div>{}</div>
+                    <div class="small text-muted">{}</div>
+                    <div class="small mt-2">{}</div>
+                    <details class="row-disclosure mt-2">
+                        <summary>{} matching chunks · score {:.3}</summary>
+                        <div class="row-disclosure-content">{}</div>
+                    </details>
+                </div>"#,
+                title_html, venue, p.snippet, p.matching_chunks, p.score, chunks_html
+            )
+        })
+        .collect();
 
-    Html(render_query_page(Some((&query_text, results))))
+    format!(
+        r#"
+            <div class="card mt-4">
+                <div class="card-header">
+                    <div>Supporting literature</div>
+                    <span class="badge badge-outline">{} papers</span>
+                </div>
+                <div class="p-4">{}</div>
+            </div>"#,
+        papers.len(),
+        rows
+    )
 }
 
-fn render_query_page(results: Option<(&str, Vec<QueryResult>)>) -> String {
+/// Question box that streams a cited answer from `/api/query/answer`.
+fn render_answer_card(question: &str) -> String {
+    format!(
+        r#"
+    <div class="card mt-4">
+        <div class="card-header">
+            <div>Ask the literature</div>
+            <span class="badge badge-outline">Cited answer</span>
+        </div>
+        <form id="answer-form" class="d-flex flex-column gap-4 p-4">
+            <textarea id="answer-question" class="form-control" rows="2" required
+                placeholder="e.g. Which KRAS alleles respond to sotorasib?">{question}</textarea>
+            <div class="d-flex gap-4" style="justify-content:flex-end; align-items:center;">
+                <select id="answer-data-class" class="form-control" style="max-width:14rem;">
+                    <option value="PUBLIC" selected>Public data</option>
+                    <option value="INTERNAL">Internal (local LLM only)</option>
+                    <option value="CONFIDENTIAL">Confidential (local LLM only)</option>
+                </select>
+                <button type="submit" id="answer-btn" class="btn btn-primary">Answer</button>
+            </div>
+            <div id="answer-text" style="white-space:pre-wrap;"></div>
+            <div id="answer-meta" class="small text-muted"></div>
+            <ol id="answer-citations" class="small"></ol>
+        </form>
+        <script>
+            (function() {{
+                const form = document.getElementById('answer-form');
+                const btn = document.getElementById('answer-btn');
+                const text = document.getElementById('answer-text');
+                const meta = document.getElementById('answer-meta');
+                const list = document.getElementById('answer-citations');
+                form.onsubmit = function(ev) {{
+                    ev.preventDefault();
+                    const answerId = crypto.randomUUID();
+                    btn.disabled = true;
+                    text.textContent = '';
+                    meta.textContent = 'Retrieving sources…';
+                    list.innerHTML = '';
+                    const events = new EventSource('/api/events?topics=answer');
+                    events.onmessage = function(e) {{
+                        const data = JSON.parse(e.data);
+                        if (data.type === 'answer_token' && data.answer_id === answerId) {{
+                            text.textContent += data.token;
+                        }}
+                    }};
+                    fetch('/api/query/answer', {{
+                        method: 'POST',
+                        headers: {{ 'Content-Type': 'application/json' }},
+                        body: JSON.stringify({{
+                            question: document.getElementById('answer-question').value,
+                            data_class: document.getElementById('answer-data-class').value,
+                            answer_id: answerId
+                        }})
+                    }}).then(function(r) {{
+                        return r.json().then(function(body) {{
+                            if (!r.ok) throw new Error(body.error || r.statusText);
+                            return body;
+                        }});
+                    }}).then(function(body) {{
+                        text.textContent = body.answer;
+                        meta.textContent = body.model + ' (' + body.backend + ') · ' + body.context_chunks
+                            + ' sources in prompt, ' + body.dropped_chunks + ' dropped';
+                        body.citations.forEach(function(c) {{
+                            const li = document.createElement('li');
+                            li.value = c.index;
+                            const link = document.createElement(c.doi ? 'a' : 'span');
+                            link.textContent = c.title || 'Untitled paper';
+                            if (c.doi) {{
+                                link.href = 'https://doi.org/' + c.doi;
+                                link.target = '_blank';
+                                link.rel = 'noopener';
+                            }}
+                            li.appendChild(link);
+                            li.appendChild(document.createTextNode(' · similarity ' + c.similarity.toFixed(3)));
+                            list.appendChild(li);
+                        }});
+                    }}).catch(function(err) {{
+                        meta.textContent = '> ' + err.message;
+                    }}).finally(function() {{
+                        events.close();
+                        btn.disabled = false;
+                    }});
+                }};
+            }})();
+        </script>
+    </div>"#,
+        question = html_escape(question)
+    )
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_query_page(
+    results: Option<(&str, Vec<QueryResult>)>,
+    papers: &[PaperSearchResult],
+) -> String {
+    let question = results.as_ref().map(|(q, _)| *q).unwrap_or_default();
+    let answer_html = render_answer_card(question);
     let results_html = match results {
         None => String::new(),
         Some((query, ref targets)) if targets.is_empty() => format!(
@@ -313,10 +487,15 @@ fn render_query_page(results: Option<(&str, Vec<QueryResult>)>) -> String {
     </div>
 
     {}
+    {}
+    {}
 </main>
 <script src="/static/js/main.js"></script>
 </body>
 </html>"#,
-        NAV_HTML, results_html
+        NAV_HTML,
+        answer_html,
+        results_html,
+        render_paper_hits(papers)
     )
 }
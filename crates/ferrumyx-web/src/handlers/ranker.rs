@@ -1,44 +1,208 @@
 //! Target ranking API — computes composite scores using the ranker engine.
 
 use crate::handlers::dashboard::NAV_HTML;
-use crate::state::SharedState;
+use crate::state::{AppEvent, SharedState};
 use axum::{
+    body::Body,
     extract::{Query, State},
-    response::{Html, IntoResponse},
+    http::header,
+    response::{Html, IntoResponse, Response},
     Json,
 };
-use ferrumyx_common::error::ApiError;
-use ferrumyx_db::{entities::EntityRepository, target_scores::TargetScoreRepository};
-use ferrumyx_ranker::{scorer::ComponentScoresNormed, weights::WeightVector};
+use ferrumyx_common::{confidence::evidence_decay, error::ApiError, CancerType};
+use ferrumyx_db::{
+    entities::EntityRepository,
+    kg_facts::{fact_supports, KgFactRepository},
+    papers::{PaperReference, PaperRepository},
+    schema::TargetScore,
+    target_scores::TargetScoreRepository,
+};
+use ferrumyx_ingestion::sources::{clinicaltrials::TrialSummary, ChemblTargetSummary};
+use ferrumyx_kg::ner::HgncNormaliser;
+use ferrumyx_molecules::tractability::StructuralAssessment;
+use ferrumyx_ranker::{
+    absence::{explain_absence, AbsencePolicy, StoredGeneScore, SymbolIndex, COMPONENT_KEYS},
+    enrichment::{bundled_gene_sets, run_enrichment, EnrichedSet, EnrichmentConfig},
+    lookup_provider_components,
+    report::{load_export_rows, write_delimited},
+    scorer::{
+        compute_composite_score_explained, penalty_inputs_from_providers, penalty_reasons,
+        rank_all_targets, ComponentScoresNormed, ComponentScoresRaw, ScoreExplanation,
+    },
+    sensitivity::{sensitivity_analysis_with_missing, SensitivityReport},
+    weights::WeightVector,
+    ProviderComponents,
+};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::OnceCell;
 
 #[derive(Deserialize)]
 pub struct RankerFilter {
     pub gene: Option<String>,
     pub cancer_type: Option<String>,
     pub limit: Option<usize>,
+    /// Add a weight `sensitivity` block to `/api/ranker/score`.
+    #[serde(default)]
+    pub sensitivity: bool,
+    /// Absolute weight perturbation for the sensitivity block.
+    pub perturbation: Option<f64>,
+}
+
+/// Default ± weight change for `?sensitivity=true`.
+const DEFAULT_SENSITIVITY_PERTURBATION: f64 = 0.05;
+
+#[derive(Deserialize)]
+pub struct RankerRunRequest {
+    pub cancer_type: String,
+    /// Defaults to the configured weight vector.
+    pub weights: Option<WeightVector>,
+}
+
+#[derive(Deserialize)]
+pub struct EnrichmentFilter {
+    pub cancer_type: Option<String>,
+    /// "primary", "secondary", or "all" (primary + secondary, default).
+    pub tier: Option<String>,
+    pub limit: Option<usize>,
+    pub min_set_size: Option<usize>,
+    pub max_set_size: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub struct ExplainAbsenceFilter {
+    pub gene: String,
+    pub cancer_type: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EnrichmentResponse {
+    pub cancer_type: Option<String>,
+    pub tier: String,
+    pub universe_size: usize,
+    pub shortlist_size: usize,
+    pub config: EnrichmentConfig,
+    pub sets: Vec<EnrichedSet>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct RankedTarget {
+    #[serde(skip)]
+    pub gene_id: uuid::Uuid,
     pub gene: String,
     pub cancer_type: String,
     pub composite_score: f64,
     pub confidence_adjusted_score: f64,
     pub tier: String,
-    pub component_scores: ComponentScoresNormed,
+    pub component_scores: ComponentScores,
+    /// Where each component came from: `persisted_score`, a provider name,
+    /// or `no_data`.
+    pub data_sources: BTreeMap<String, String>,
+    /// 95% bootstrap interval `[lo, hi]` on the normalised CRISPR dependency.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crispr_dependency_ci: Option<[f64; 2]>,
+    /// DepMap cell lines behind the CRISPR dependency.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_cell_lines: Option<usize>,
+    /// Composite recomputed with the configured weight vector; set by
+    /// `/api/ranker/score`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weighted_score: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sensitivity: Option<SensitivityReport>,
+    /// Weighted contributions and penalty terms behind `weighted_score`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<ScoreExplanation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rationale: Option<String>,
     pub penalty: f64,
     pub evidence: EvidenceSummary,
 }
 
-#[derive(Debug, Serialize)]
+/// Normalised component scores; `None` means no data, not a zero score.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ComponentScores {
+    pub mutation_freq: Option<f64>,
+    pub crispr_dependency: Option<f64>,
+    pub survival_correlation: Option<f64>,
+    pub expression_specificity: Option<f64>,
+    pub structural_tractability: Option<f64>,
+    pub pocket_detectability: Option<f64>,
+    pub novelty_score: Option<f64>,
+    pub pathway_independence: Option<f64>,
+    pub literature_novelty: Option<f64>,
+}
+
+impl ComponentScores {
+    /// Components in `WeightVector::NAMES` order.
+    fn as_array(&self) -> [Option<f64>; 9] {
+        [
+            self.mutation_freq,
+            self.crispr_dependency,
+            self.survival_correlation,
+            self.expression_specificity,
+            self.structural_tractability,
+            self.pocket_detectability,
+            self.novelty_score,
+            self.pathway_independence,
+            self.literature_novelty,
+        ]
+    }
+
+    fn slot(&mut self, component: &str) -> Option<&mut Option<f64>> {
+        Some(match component {
+            "mutation_freq" => &mut self.mutation_freq,
+            "crispr_dependency" => &mut self.crispr_dependency,
+            "survival_correlation" => &mut self.survival_correlation,
+            "expression_specificity" => &mut self.expression_specificity,
+            "structural_tractability" => &mut self.structural_tractability,
+            "pocket_detectability" => &mut self.pocket_detectability,
+            "novelty_score" => &mut self.novelty_score,
+            "pathway_independence" => &mut self.pathway_independence,
+            "literature_novelty" => &mut self.literature_novelty,
+            _ => return None,
+        })
+    }
+}
+
+/// Evidence counts from the database; `None` when not looked up.
+#[derive(Debug, Default, Serialize)]
 pub struct EvidenceSummary {
-    pub literature_count: u32,
-    pub kg_fact_count: u32,
-    pub clinical_trials: u32,
+    pub literature_count: Option<u32>,
+    /// `literature_count` with each paper weighted by its evidence decay;
+    /// papers without a publication date count in full.
+    pub recency_weighted_literature_count: Option<f64>,
+    /// Distinct (subject, predicate, object) triples about the gene.
+    pub kg_fact_count: Option<u32>,
+    /// Papers backing those triples, counted once per triple.
+    pub kg_support_count: Option<u32>,
+    /// `kg_support_count` with each paper weighted by its evidence decay.
+    pub recency_weighted_kg_support: Option<f64>,
+    pub clinical_trials: Option<u32>,
+    pub chembl_inhibitor_count: Option<u32>,
+    /// ClinicalTrials.gov breakdown behind `clinical_trials`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trials: Option<TrialSummary>,
+    /// ChEMBL target and most potent compounds behind
+    /// `chembl_inhibitor_count`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chembl: Option<ChemblTargetSummary>,
+    /// Experimental structures, AlphaFold pLDDT and fpocket pockets.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub structure: Option<StructuralAssessment>,
+    /// One-line summary of `structure`, e.g. "experimental structure: 4
+    /// (best 1.9Å)".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub structure_summary: Option<String>,
 }
 
+const SOURCE_PERSISTED: &str = "persisted_score";
+const SOURCE_NO_DATA: &str = "no_data";
+
+/// Paper source tag of ingested ClinicalTrials.gov records.
+const CLINICAL_TRIALS_SOURCE: &str = "clinicaltrials";
+
 #[derive(Debug, Serialize)]
 pub struct RankerStats {
     pub weights: WeightVector,
@@ -64,22 +228,64 @@ pub async fn api_ranker_score(
         .map(str::trim)
         .filter(|v| !v.is_empty())
         .ok_or_else(|| ApiError::BadRequest("gene is required".to_string()))?;
-    let cancer_filter = filter
-        .cancer_type
-        .as_deref()
-        .map(str::trim)
-        .filter(|v| !v.is_empty());
+    let cancer_filter = resolve_cancer_filter(filter.cancer_type.as_deref())?;
+
+    let mut row = score_target(&state, gene, cancer_filter, true).await?;
+    if filter.sensitivity {
+        let perturbation = filter
+            .perturbation
+            .filter(|p| p.is_finite())
+            .unwrap_or(DEFAULT_SENSITIVITY_PERTURBATION)
+            .clamp(0.0, 0.5);
+        row.sensitivity = Some(sensitivity_analysis_with_missing(
+            &row.component_scores.as_array(),
+            &state.ranker_weights(),
+            perturbation,
+        ));
+    }
+
+    Ok(Json(row))
+}
 
-    let all = load_ranked_targets(&state, cancer_filter, 3_000).await?;
-    let row = all
+/// The persisted score of `gene`, with missing components filled from
+/// provider signals and the KG evidence attached. Without
+/// `allow_live_fetch` only cached provider signals are read.
+async fn score_target(
+    state: &SharedState,
+    gene: &str,
+    cancer_filter: Option<CancerType>,
+    allow_live_fetch: bool,
+) -> Result<RankedTarget, ApiError> {
+    let all = load_ranked_targets(state, cancer_filter, usize::MAX).await?;
+    let mut row = all
         .into_iter()
         .find(|r| r.gene.eq_ignore_ascii_case(gene))
         .ok_or_else(|| {
-            let scope = cancer_filter.unwrap_or("all indications");
+            let scope = cancer_filter.map_or("all indications", CancerType::code);
             ApiError::NotFound(format!("No persisted score found for {gene} in {scope}"))
         })?;
 
-    Ok(Json(row))
+    let cancer = (row.cancer_type != "UNSPECIFIED").then(|| row.cancer_type.clone());
+    let providers = lookup_provider_components(
+        state.db.clone(),
+        &row.gene,
+        cancer.as_deref(),
+        allow_live_fetch,
+    )
+    .await;
+    apply_provider_components(&mut row, &providers);
+    attach_evidence(state, &mut row).await;
+    let explanation = compute_composite_score_explained(
+        Some(&raw_components(&providers)),
+        &normed_or_zero(&row.component_scores),
+        &state.ranker_weights(),
+        penalty_reasons(&penalty_inputs_from_providers(&providers)),
+        1.0,
+    );
+    row.weighted_score = Some(explanation.composite_score);
+    row.rationale = Some(explanation.rationale());
+    row.explanation = Some(explanation);
+    Ok(row)
 }
 
 /// GET /api/ranker/top — Get top ranked targets for a cancer type
@@ -87,15 +293,12 @@ pub async fn api_ranker_top(
     State(state): State<SharedState>,
     Query(filter): Query<RankerFilter>,
 ) -> Result<impl IntoResponse, ApiError> {
-    let cancer_filter = filter
-        .cancer_type
-        .as_deref()
-        .map(str::trim)
-        .filter(|v| !v.is_empty());
+    let cancer_filter = resolve_cancer_filter(filter.cancer_type.as_deref())?;
     let limit = filter.limit.unwrap_or(10).clamp(1, 100);
     let scan_limit = (limit.saturating_mul(80)).clamp(500, 3_000);
     let mut top_targets = load_ranked_targets(&state, cancer_filter, scan_limit).await?;
     top_targets.truncate(limit);
+    attach_fact_counts(&state, &mut top_targets).await;
     Ok(Json(top_targets))
 }
 
@@ -117,7 +320,7 @@ pub async fn api_ranker_stats(
     }
 
     let stats = RankerStats {
-        weights: WeightVector::default(),
+        weights: state.ranker_weights(),
         total_targets_scored: rows.len() as u32,
         primary_count,
         secondary_count,
@@ -127,18 +330,428 @@ pub async fn api_ranker_stats(
     Ok(Json(stats))
 }
 
+/// POST /api/ranker/run — Score every KG gene for a cancer type in the background
+///
+/// Progress is streamed over SSE as `target_scored` and `pipeline_status`
+/// events; results land in `target_scores`, which `/api/ranker/top` reads.
+pub async fn api_ranker_run(
+    State(state): State<SharedState>,
+    Json(req): Json<RankerRunRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let cancer_type = resolve_cancer_filter(Some(&req.cancer_type))?
+        .ok_or_else(|| ApiError::BadRequest("cancer_type is required".to_string()))?
+        .code();
+    let weights = match req.weights {
+        Some(w) => w
+            .validated()
+            .map_err(|e| ApiError::BadRequest(e.to_string()))?,
+        None => state.ranker_weights(),
+    };
+
+    let _ = state.event_tx.send(AppEvent::PipelineStatus {
+        stage: "ranking".to_string(),
+        message: format!("Scoring all KG genes for {cancer_type}"),
+        count: 0,
+        job_id: None,
+    });
+
+    let event_tx = state.event_tx.clone();
+    let db = state.db.clone();
+    let job_cancer = cancer_type.to_string();
+    tokio::spawn(async move {
+        let progress_tx = event_tx.clone();
+        let cancer = job_cancer.clone();
+        let result = rank_all_targets(db, &job_cancer, &weights, move |p| {
+            let _ = progress_tx.send(AppEvent::TargetScored {
+                gene: p.gene.clone(),
+                cancer: cancer.clone(),
+                score: p.confidence_adjusted_score,
+            });
+        })
+        .await;
+
+        let status = match result {
+            Ok(summary) => AppEvent::PipelineStatus {
+                stage: "complete".to_string(),
+                message: format!(
+                    "Ranking complete for {} — {} genes ({} primary, {} secondary, {} excluded)",
+                    summary.cancer_type,
+                    summary.genes_scored,
+                    summary.primary_count,
+                    summary.secondary_count,
+                    summary.excluded_count
+                ),
+                count: summary.genes_scored as u64,
+                job_id: None,
+            },
+            Err(e) => AppEvent::PipelineStatus {
+                stage: "failed".to_string(),
+                message: format!("Ranking failed for {job_cancer}: {e}"),
+                count: 0,
+                job_id: None,
+            },
+        };
+        let _ = event_tx.send(status);
+    });
+
+    Ok((
+        axum::http::StatusCode::ACCEPTED,
+        Json(serde_json::json!({ "status": "started", "cancer_type": cancer_type })),
+    ))
+}
+
+/// GET /api/ranker/weights — Weight vector used for scoring
+pub async fn api_ranker_weights_get(State(state): State<SharedState>) -> impl IntoResponse {
+    Json(state.ranker_weights())
+}
+
+/// PUT /api/ranker/weights — Replace the weight vector until the next restart
+///
+/// Weights are validated and renormalised like `[scoring.weights]`; the
+/// stored vector is returned.
+pub async fn api_ranker_weights_put(
+    State(state): State<SharedState>,
+    Json(weights): Json<WeightVector>,
+) -> Result<impl IntoResponse, ApiError> {
+    let weights = weights
+        .validated()
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    *state
+        .ranker_weights
+        .write()
+        .unwrap_or_else(|e| e.into_inner()) = weights.clone();
+    tracing::info!("Ranker weights updated: {weights:?}");
+    Ok(Json(weights))
+}
+
+/// GET /api/ranker/enrichment — Gene set over-representation of the shortlist
+pub async fn api_ranker_enrichment(
+    State(state): State<SharedState>,
+    Query(filter): Query<EnrichmentFilter>,
+) -> Result<impl IntoResponse, ApiError> {
+    let cancer_filter = resolve_cancer_filter(filter.cancer_type.as_deref())?;
+    let tier = filter
+        .tier
+        .as_deref()
+        .map(|v| v.trim().to_ascii_lowercase())
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| "all".to_string());
+    let tiers: &[&str] = match tier.as_str() {
+        "primary" => &["primary"],
+        "secondary" => &["secondary"],
+        "all" => &["primary", "secondary"],
+        other => {
+            return Err(ApiError::BadRequest(format!(
+                "unknown tier '{other}' (expected primary, secondary or all)"
+            )))
+        }
+    };
+
+    let mut config = EnrichmentConfig::from_env();
+    if let Some(v) = filter.min_set_size {
+        config.min_set_size = v.max(1);
+    }
+    if let Some(v) = filter.max_set_size {
+        config.max_set_size = v;
+    }
+    config.max_set_size = config.max_set_size.max(config.min_set_size);
+
+    let rows = load_ranked_targets(&state, cancer_filter, 100_000).await?;
+    let universe: Vec<String> = rows.iter().map(|r| r.gene.clone()).collect();
+    let shortlist: Vec<String> = rows
+        .iter()
+        .filter(|r| tiers.iter().any(|t| r.tier.eq_ignore_ascii_case(t)))
+        .map(|r| r.gene.clone())
+        .collect();
+
+    let mut sets = run_enrichment(&shortlist, &universe, &bundled_gene_sets(), &config);
+    sets.truncate(filter.limit.unwrap_or(25).clamp(1, 200));
+
+    Ok(Json(EnrichmentResponse {
+        cancer_type: cancer_filter.map(|c| c.code().to_string()),
+        tier,
+        universe_size: universe.len(),
+        shortlist_size: shortlist.len(),
+        config,
+        sets,
+    }))
+}
+
+/// GET /api/ranker/explain_absence — Why a gene is missing from ranking output
+pub async fn api_ranker_explain_absence(
+    State(state): State<SharedState>,
+    Query(filter): Query<ExplainAbsenceFilter>,
+) -> Result<impl IntoResponse, ApiError> {
+    let query = filter.gene.trim();
+    if query.is_empty() {
+        return Err(ApiError::BadRequest("gene is required".to_string()));
+    }
+    let cancer_filter = resolve_cancer_filter(filter.cancer_type.as_deref())?;
+
+    let scores = load_stored_scores(&state).await?;
+    let symbols = match get_hgnc_index().await {
+        Ok(index) => index,
+        Err(e) => {
+            // Offline fallback: resolve against genes that were actually scored.
+            tracing::warn!("HGNC unavailable for explain_absence, using scored genes: {e}");
+            Arc::new(SymbolIndex::from_pairs(
+                scores.iter().map(|s| (s.gene.clone(), s.gene.clone())),
+            ))
+        }
+    };
+    let constraints = load_campaign_constraints();
+
+    Ok(Json(explain_absence(
+        query,
+        cancer_filter.map(CancerType::code),
+        &symbols,
+        &scores,
+        constraints.as_ref(),
+        &AbsencePolicy::default(),
+    )))
+}
+
+/// Rows per streamed chunk of `/api/ranker/export`.
+const EXPORT_CHUNK_ROWS: usize = 500;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Tsv,
+    Json,
+}
+
+impl ExportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Tsv => "tsv",
+            Self::Json => "json",
+        }
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Tsv => "text/tab-separated-values; charset=utf-8",
+            Self::Json => "application/json",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct RankerExportFilter {
+    pub cancer_type: Option<String>,
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+/// Download name for an export, e.g. `ferrumyx-targets-PAAD-2026-10-14.csv`.
+fn export_filename(
+    cancer_type: Option<&str>,
+    format: ExportFormat,
+    date: chrono::NaiveDate,
+) -> String {
+    let scope: String = cancer_type
+        .map(|c| {
+            c.chars()
+                .filter(|ch| ch.is_ascii_alphanumeric() || *ch == '-' || *ch == '_')
+                .collect::<String>()
+                .to_ascii_uppercase()
+        })
+        .filter(|c| !c.is_empty())
+        .unwrap_or_else(|| "all".to_string());
+    format!(
+        "ferrumyx-targets-{scope}-{}.{}",
+        date.format("%Y-%m-%d"),
+        format.extension()
+    )
+}
+
+/// GET /api/ranker/export — Persisted target scores as CSV, TSV or JSON
+///
+/// One row per scored gene, highest confidence-adjusted score first; see
+/// `ferrumyx_ranker::report::export_header` for the columns.
+pub async fn api_ranker_export(
+    State(state): State<SharedState>,
+    Query(filter): Query<RankerExportFilter>,
+) -> Result<Response, ApiError> {
+    let cancer_filter = resolve_cancer_filter(filter.cancer_type.as_deref())?;
+    let rows = load_export_rows(state.db.clone(), cancer_filter.map(CancerType::code))
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let body = match filter.format {
+        ExportFormat::Json => {
+            Body::from(serde_json::to_vec(&rows).map_err(|e| ApiError::Internal(e.to_string()))?)
+        }
+        format => {
+            let delimiter = if format == ExportFormat::Tsv {
+                b'\t'
+            } else {
+                b','
+            };
+            let mut chunks = Vec::new();
+            let mut head = Vec::new();
+            write_delimited(&[], delimiter, true, &mut head)
+                .map_err(|e| ApiError::Internal(e.to_string()))?;
+            chunks.push(head);
+            for batch in rows.chunks(EXPORT_CHUNK_ROWS) {
+                let mut chunk = Vec::new();
+                write_delimited(batch, delimiter, false, &mut chunk)
+                    .map_err(|e| ApiError::Internal(e.to_string()))?;
+                chunks.push(chunk);
+            }
+            Body::from_stream(tokio_stream::iter(
+                chunks.into_iter().map(Ok::<_, std::io::Error>),
+            ))
+        }
+    };
+
+    let filename = export_filename(
+        cancer_filter.map(CancerType::code),
+        filter.format,
+        chrono::Utc::now().date_naive(),
+    );
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                filter.format.content_type().to_string(),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{filename}\""),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+static HGNC_INDEX: OnceCell<Arc<SymbolIndex>> = OnceCell::const_new();
+
+async fn get_hgnc_index() -> Result<Arc<SymbolIndex>, String> {
+    let index = HGNC_INDEX
+        .get_or_try_init(|| async {
+            let hgnc = HgncNormaliser::from_download()
+                .await
+                .map_err(|e| e.to_string())?;
+            let pairs = hgnc
+                .all_patterns()
+                .into_iter()
+                .filter_map(|p| hgnc.normalise_symbol(&p).map(|s| (p, s)));
+            Ok::<_, String>(Arc::new(SymbolIndex::from_pairs(pairs)))
+        })
+        .await?;
+    Ok(Arc::clone(index))
+}
+
+/// Campaign constraints from `FERRUMYX_TARGET_CONFIG` (YAML or JSON), if set.
+fn load_campaign_constraints() -> Option<ferrumyx_common::Constraints> {
+    let path = std::env::var("FERRUMYX_TARGET_CONFIG").ok()?;
+    let loaded = if path.ends_with(".json") {
+        ferrumyx_common::TargetConfig::from_json(&path)
+    } else {
+        ferrumyx_common::TargetConfig::from_yaml(&path)
+    };
+    match loaded {
+        Ok(cfg) => Some(cfg.constraints),
+        Err(e) => {
+            tracing::warn!("Ignoring FERRUMYX_TARGET_CONFIG {path}: {e}");
+            None
+        }
+    }
+}
+
+/// Rows read per `TargetScoreRepository::list` call.
+const SCORE_PAGE: usize = 1_000;
+
+/// Up to `limit` current scores, read a page at a time.
+async fn list_scores(state: &SharedState, limit: usize) -> Result<Vec<TargetScore>, ApiError> {
+    let repo = TargetScoreRepository::new(state.db.clone());
+    let mut scores = Vec::new();
+    while scores.len() < limit {
+        let page_size = SCORE_PAGE.min(limit - scores.len());
+        let page = repo
+            .list(scores.len(), page_size)
+            .await
+            .map_err(|e| ApiError::Internal(e.to_string()))?;
+        let done = page.len() < page_size;
+        scores.extend(page);
+        if done {
+            break;
+        }
+    }
+    Ok(scores)
+}
+
+async fn load_stored_scores(state: &SharedState) -> Result<Vec<StoredGeneScore>, ApiError> {
+    let entity_repo = EntityRepository::new(state.db.clone());
+
+    let rows = list_scores(state, usize::MAX).await?;
+    let entity_ids: Vec<uuid::Uuid> = rows
+        .iter()
+        .flat_map(|score| [score.gene_id, score.cancer_id])
+        .filter(|id| !id.is_nil())
+        .collect();
+    let name_cache = entity_repo
+        .find_names_by_ids(&entity_ids)
+        .await
+        .unwrap_or_default();
+
+    Ok(rows
+        .into_iter()
+        .map(|s| {
+            let components_raw: serde_json::Value =
+                serde_json::from_str(&s.components_raw).unwrap_or_default();
+            let components_normed: serde_json::Value =
+                serde_json::from_str(&s.components_normed).unwrap_or_default();
+            let gene = components_raw
+                .get("gene")
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+                .or_else(|| name_cache.get(&s.gene_id).cloned())
+                .unwrap_or_else(|| s.gene_id.to_string());
+            let cancer_type = components_raw
+                .get("cancer_code")
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+                .or_else(|| name_cache.get(&s.cancer_id).cloned())
+                .unwrap_or_else(|| "UNSPECIFIED".to_string());
+            StoredGeneScore {
+                gene,
+                cancer_type,
+                tier: s.shortlist_tier,
+                confidence_adjusted_score: s.confidence_adjusted_score,
+                components_raw,
+                components_normed,
+            }
+        })
+        .collect())
+}
+
+/// Resolve a `cancer_type` parameter through the OncoTree taxonomy. Blank
+/// means no filter; unknown values are a 400 naming the closest types.
+fn resolve_cancer_filter(raw: Option<&str>) -> Result<Option<CancerType>, ApiError> {
+    let Some(raw) = raw.map(str::trim).filter(|v| !v.is_empty()) else {
+        return Ok(None);
+    };
+    raw.parse::<CancerType>()
+        .map(Some)
+        .map_err(|e| ApiError::BadRequest(e.to_string()))
+}
+
 async fn load_ranked_targets(
     state: &SharedState,
-    cancer_filter: Option<&str>,
+    cancer_filter: Option<CancerType>,
     limit: usize,
 ) -> Result<Vec<RankedTarget>, ApiError> {
-    let score_repo = TargetScoreRepository::new(state.db.clone());
     let entity_repo = EntityRepository::new(state.db.clone());
 
-    let mut rows = score_repo
-        .list(0, limit.min(3_000))
-        .await
-        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    let mut rows = list_scores(state, limit).await?;
 
     rows.sort_by(|a, b| {
         b.confidence_adjusted_score
@@ -146,9 +759,8 @@ async fn load_ranked_targets(
             .unwrap_or(std::cmp::Ordering::Equal)
     });
 
-    // Avoid heavy KG fan-out on this hot path; keep ranker page responsive and crash-safe.
-    let fact_count_by_gene: HashMap<uuid::Uuid, u32> = HashMap::new();
-
+    // Evidence counts are attached per endpoint, after truncation, so the
+    // KG is only queried for rows that are returned.
     let entity_ids: Vec<uuid::Uuid> = rows
         .iter()
         .flat_map(|score| {
@@ -160,6 +772,9 @@ async fn load_ranked_targets(
         .find_names_by_ids(&entity_ids)
         .await
         .unwrap_or_default();
+    // Scores written before cancer types were resolved may carry a TCGA
+    // cohort or a name rather than the code.
+    let mut resolved: HashMap<String, Option<CancerType>> = HashMap::new();
     let mut out = Vec::with_capacity(rows.len());
     for s in rows {
         let raw_json: serde_json::Value =
@@ -184,66 +799,34 @@ async fn load_ranked_targets(
 
         let gene = gene.unwrap_or_else(|| s.gene_id.to_string());
         let cancer_type = cancer_type.unwrap_or_else(|| "UNSPECIFIED".to_string());
-        if let Some(filter_code) = cancer_filter {
-            if !cancer_type.eq_ignore_ascii_case(filter_code) {
+        if let Some(filter) = cancer_filter {
+            let stored = *resolved
+                .entry(cancer_type.clone())
+                .or_insert_with(|| CancerType::parse(&cancer_type));
+            if stored != Some(filter) {
                 continue;
             }
         }
 
-        let read_normed = |keys: &[&str]| -> f64 {
-            keys.iter()
-                .find_map(|k| norm_json.get(*k).and_then(|v| v.as_f64()))
-                .unwrap_or(0.0)
-        };
-        let component_scores = ComponentScoresNormed {
-            mutation_freq: read_normed(&["mutation_freq", "mutation_score", "n1_mutation_freq"]),
-            crispr_dependency: read_normed(&[
-                "crispr_dependency",
-                "crispr_score",
-                "n2_crispr_dependency",
-            ]),
-            survival_correlation: read_normed(&[
-                "survival_correlation",
-                "survival_score",
-                "n3_survival_correlation",
-            ]),
-            expression_specificity: read_normed(&[
-                "expression_specificity",
-                "expression_score",
-                "n4_expression_specificity",
-            ]),
-            structural_tractability: read_normed(&[
-                "structural_tractability",
-                "tractability_score",
-                "n5_structural_tractability",
-            ]),
-            pocket_detectability: read_normed(&[
-                "pocket_detectability",
-                "pocket_score",
-                "n6_pocket_detectability",
-            ]),
-            novelty_score: read_normed(&["novelty_score", "n7_novelty_score"]),
-            pathway_independence: read_normed(&["pathway_independence", "n8_pathway_independence"]),
-            literature_novelty: read_normed(&[
-                "literature_novelty",
-                "literature_score",
-                "n9_literature_novelty",
-            ]),
-        };
+        let (component_scores, data_sources) = persisted_components(&norm_json);
 
         out.push(RankedTarget {
+            gene_id: s.gene_id,
             gene: gene.clone(),
             cancer_type: cancer_type.clone(),
             composite_score: s.composite_score,
             confidence_adjusted_score: s.confidence_adjusted_score,
             tier: s.shortlist_tier.clone(),
             component_scores,
+            data_sources,
+            weighted_score: None,
+            sensitivity: None,
+            explanation: None,
+            rationale: None,
+            crispr_dependency_ci: None,
+            num_cell_lines: None,
             penalty: s.penalty_score,
-            evidence: EvidenceSummary {
-                literature_count: fact_count_by_gene.get(&s.gene_id).copied().unwrap_or(0),
-                kg_fact_count: fact_count_by_gene.get(&s.gene_id).copied().unwrap_or(0),
-                clinical_trials: 0,
-            },
+            evidence: EvidenceSummary::default(),
         });
     }
 
@@ -255,6 +838,197 @@ async fn load_ranked_targets(
     Ok(out)
 }
 
+/// Components stored with the score, and which ones are missing.
+fn persisted_components(
+    norm_json: &serde_json::Value,
+) -> (ComponentScores, BTreeMap<String, String>) {
+    let mut scores = ComponentScores::default();
+    let mut sources = BTreeMap::new();
+    for &(component, keys) in COMPONENT_KEYS {
+        let value = keys
+            .iter()
+            .find_map(|k| norm_json.get(*k).and_then(|v| v.as_f64()));
+        if let Some(slot) = scores.slot(component) {
+            *slot = value;
+        }
+        let source = if value.is_some() {
+            SOURCE_PERSISTED
+        } else {
+            SOURCE_NO_DATA
+        };
+        sources.insert(component.to_string(), source.to_string());
+    }
+    (scores, sources)
+}
+
+/// Fill components the persisted score lacks from provider signals.
+/// Persisted values are never overwritten.
+fn apply_provider_components(row: &mut RankedTarget, providers: &ProviderComponents) {
+    let derived = [
+        (
+            "mutation_freq",
+            providers.mutation_freq.map(|f| f.clamp(0.0, 1.0)),
+        ),
+        ("crispr_dependency", providers.crispr_dependency()),
+        ("survival_correlation", providers.survival_score),
+        ("expression_specificity", providers.expression_score),
+        (
+            "structural_tractability",
+            providers.structural_tractability(),
+        ),
+        ("pocket_detectability", providers.pocket_detectability()),
+        (
+            "novelty_score",
+            providers
+                .chembl_inhibitor_count
+                .map(|count| 1.0 / (1.0 + count as f64)),
+        ),
+    ];
+    for (component, value) in derived {
+        let (Some(value), Some(source)) = (value, providers.sources.get(component)) else {
+            continue;
+        };
+        if let Some(slot) = row.component_scores.slot(component) {
+            if slot.is_none() {
+                *slot = Some(value);
+                row.data_sources
+                    .insert(component.to_string(), source.clone());
+            }
+        }
+    }
+    row.evidence.chembl_inhibitor_count = providers.chembl_inhibitor_count;
+    row.evidence.chembl = providers.chembl.clone();
+    if let Some(structure) = &providers.structural {
+        row.evidence.structure_summary = Some(structure.evidence_line());
+        row.evidence.structure = Some(structure.clone());
+    }
+    if let Some(trials) = &providers.trials {
+        row.evidence.clinical_trials = Some(trials.total);
+        row.evidence.trials = Some(trials.clone());
+    }
+    if let Some(ci) = providers.crispr_ci {
+        row.crispr_dependency_ci = Some([ci.lo, ci.hi]);
+        row.num_cell_lines = Some(ci.num_cell_lines);
+    }
+}
+
+/// Components with missing values scored as 0.0, for the composite.
+fn normed_or_zero(scores: &ComponentScores) -> ComponentScoresNormed {
+    let [n1, n2, n3, n4, n5, n6, n7, n8, n9] = scores.as_array().map(|v| v.unwrap_or(0.0));
+    ComponentScoresNormed {
+        mutation_freq: n1,
+        crispr_dependency: n2,
+        survival_correlation: n3,
+        expression_specificity: n4,
+        structural_tractability: n5,
+        pocket_detectability: n6,
+        novelty_score: n7,
+        pathway_independence: n8,
+        literature_novelty: n9,
+    }
+}
+
+/// Provider values in natural units, for the explanation.
+fn raw_components(providers: &ProviderComponents) -> ComponentScoresRaw {
+    ComponentScoresRaw {
+        mutation_freq: providers.mutation_freq,
+        crispr_dependency: providers.crispr_ceres,
+        survival_correlation: providers.survival_score,
+        expression_specificity: providers.expression_score,
+        structural_tractability: providers.structural.as_ref().map(|s| s.pdb_count as f64),
+        pocket_detectability: providers
+            .structural
+            .as_ref()
+            .and_then(|s| s.best_pocket_score),
+        novelty_score: providers.chembl_inhibitor_count.map(|n| n as f64),
+        pathway_independence: None,
+        literature_novelty: None,
+    }
+}
+
+/// KG fact, paper and trial counts for a single row. A trial count from
+/// the ClinicalTrials.gov provider is kept.
+async fn attach_evidence(state: &SharedState, row: &mut RankedTarget) {
+    let facts = match KgFactRepository::new(state.db.clone())
+        .find_by_subject(row.gene_id)
+        .await
+    {
+        Ok(facts) => facts,
+        Err(e) => {
+            tracing::warn!("KG evidence lookup failed for {}: {e}", row.gene);
+            return;
+        }
+    };
+    let mut triples: HashMap<(&str, uuid::Uuid), HashSet<uuid::Uuid>> = HashMap::new();
+    for fact in &facts {
+        triples
+            .entry((fact.predicate.as_str(), fact.object_id))
+            .or_default()
+            .extend(fact_supports(fact).into_iter().map(|s| s.paper_id));
+    }
+    let paper_ids: Vec<uuid::Uuid> = triples
+        .values()
+        .flatten()
+        .copied()
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    row.evidence.kg_fact_count = Some(triples.len() as u32);
+    row.evidence.kg_support_count = Some(triples.values().map(|p| p.len() as u32).sum());
+
+    match PaperRepository::new(state.db.clone())
+        .find_references_by_ids(&paper_ids)
+        .await
+    {
+        Ok(refs) => {
+            let is_trial = |r: &&PaperReference| {
+                r.source
+                    .as_deref()
+                    .is_some_and(|s| s.eq_ignore_ascii_case(CLINICAL_TRIALS_SOURCE))
+            };
+            let trials = refs.values().filter(is_trial).count() as u32;
+            // Prefer the provider count, from the trials table or
+            // ClinicalTrials.gov; trials stored as papers by earlier runs
+            // only cover what those runs happened to pull in.
+            row.evidence.clinical_trials.get_or_insert(trials);
+            row.evidence.literature_count = Some(refs.len() as u32 - trials);
+
+            let decay = evidence_decay();
+            let weight = |paper_id: &uuid::Uuid| {
+                decay.weight(refs.get(paper_id).and_then(|r| r.published_at))
+            };
+            let round = |count: f64| (count * 100.0).round() / 100.0;
+            row.evidence.recency_weighted_literature_count = Some(round(
+                refs.values()
+                    .filter(|r| !is_trial(r))
+                    .map(|r| decay.weight(r.published_at))
+                    .sum(),
+            ));
+            row.evidence.recency_weighted_kg_support =
+                Some(round(triples.values().flatten().map(weight).sum()));
+        }
+        Err(e) => tracing::warn!("Paper lookup failed for {}: {e}", row.gene),
+    }
+}
+
+/// KG fact counts for a page of rows, in one bounded query.
+async fn attach_fact_counts(state: &SharedState, rows: &mut [RankedTarget]) {
+    let gene_ids: Vec<uuid::Uuid> = rows.iter().map(|r| r.gene_id).collect();
+    match KgFactRepository::new(state.db.clone())
+        .fact_counts_by_subject_ids(&gene_ids, 32)
+        .await
+    {
+        Ok(counts) => {
+            for row in rows {
+                let counts = counts.get(&row.gene_id).copied().unwrap_or_default();
+                row.evidence.kg_fact_count = Some(counts.triples);
+                row.evidence.kg_support_count = Some(counts.support);
+            }
+        }
+        Err(e) => tracing::warn!("KG fact counts unavailable for ranker rows: {e}"),
+    }
+}
+
 fn render_ranker_page(_result: Option<RankedTarget>) -> String {
     format!(
         r##"<!DOCTYPE html>
@@ -386,7 +1160,7 @@ fn render_ranker_page(_result: Option<RankedTarget>) -> String {
                         <td class="font-outfit" style="color:var(--text-main); font-weight:500">${{t.gene}}</td>
                         <td class="${{tierClass}}">${{(t.confidence_adjusted_score * 100).toFixed(1)}}%</td>
                         <td><span class="badge ${{tierBadge}}">${{t.tier.toUpperCase()}}</span></td>
-                        <td class="text-muted">${{(t.component_scores.crispr_dependency * 100).toFixed(0)}}%</td>
+                        <td class="text-muted">${{t.component_scores.crispr_dependency == null ? 'no data' : (t.component_scores.crispr_dependency * 100).toFixed(0) + '%'}}</td>
                     </tr>`;
                 }}
                 html += '</tbody></table>';
@@ -396,6 +1170,77 @@ fn render_ranker_page(_result: Option<RankedTarget>) -> String {
             }}
         }}
         
+        function renderWaterfall(ex, rationale) {{
+            const scale = Math.max(ex.weighted_sum, 1e-9);
+            const bar = (label, start, delta, color, note) => {{
+                const left = (Math.min(start, start + delta) / scale * 100).toFixed(2);
+                const width = (Math.abs(delta) / scale * 100).toFixed(2);
+                return `<div class="d-flex align-center gap-2 mb-1" title="${{note}}">
+                    <span class="text-muted small" style="width:11rem; flex-shrink:0">${{label}}</span>
+                    <div style="flex:1; position:relative; height:0.7rem; background:rgba(255,255,255,0.04); border-radius:4px;">
+                        <div style="position:absolute; left:${{left}}%; width:${{width}}%; height:100%; background:${{color}}; border-radius:4px;"></div>
+                    </div>
+                    <strong style="color:var(--text-main); font-size:0.8rem; width:4rem; text-align:right">${{delta >= 0 ? '+' : ''}}${{(delta * 100).toFixed(1)}}</strong>
+                </div>`;
+            }};
+            let running = 0;
+            let rows = '';
+            for (const c of [...ex.components].filter(c => c.contribution > 0).sort((a, b) => b.contribution - a.contribution)) {{
+                const note = `weight ${{c.weight.toFixed(3)}} × normalised ${{c.normalized.toFixed(3)}}${{c.raw == null ? '' : ` (raw ${{c.raw.toFixed(3)}})`}} = ${{c.percent_of_total.toFixed(1)}}% of total`;
+                rows += bar(c.component, running, c.contribution, 'var(--brand-blue)', note);
+                running += c.contribution;
+            }}
+            for (const p of ex.penalties) {{
+                rows += bar(p.rule, running, -p.amount, 'var(--danger)', p.detail);
+                running -= p.amount;
+            }}
+            return `
+                <details class="section-disclosure" open>
+                    <summary>Contribution Waterfall</summary>
+                    <div class="section-disclosure-content">
+                        <div class="text-muted small mb-3">${{rationale || ''}}</div>
+                        ${{rows || '<div class="text-muted small">No component contributes to this score.</div>'}}
+                        <div class="text-muted small mt-2">Weighted sum ${{(ex.weighted_sum * 100).toFixed(1)}} − penalties ${{(ex.penalty_total * 100).toFixed(1)}} → composite ${{(ex.composite_score * 100).toFixed(1)}}%</div>
+                    </div>
+                </details>
+            `;
+        }}
+
+        function renderTrials(trials) {{
+            const esc = (v) => String(v ?? '').replace(/[&<>"']/g, ch => ({{'&': '&amp;', '<': '&lt;', '>': '&gt;', '"': '&quot;', "'": '&#39;'}})[ch]);
+            const counts = (m) => Object.entries(m || {{}}).map(([k, n]) => `${{esc(k)}} ${{n}}`).join(' • ') || 'n/a';
+            const rows = (trials.top_trials || []).map(t => `<div class="d-flex justify-between gap-2 mb-1 small">
+                    <a href="https://clinicaltrials.gov/study/${{encodeURIComponent(t.nct_id)}}" target="_blank" rel="noopener">${{esc(t.nct_id)}}</a>
+                    <span class="text-muted" style="flex:1">${{esc(t.title)}}</span>
+                    <span style="color:var(--text-main); white-space:nowrap">${{esc(t.phase)}} · ${{esc(t.status)}}</span>
+                </div>`).join('');
+            return `
+                <details class="section-disclosure">
+                    <summary>Clinical Trials (${{trials.total}} in ${{esc(trials.condition)}})</summary>
+                    <div class="section-disclosure-content">
+                        <div class="text-muted small mb-1">By phase: <span style="color:var(--text-main)">${{counts(trials.by_phase)}}</span></div>
+                        <div class="text-muted small mb-3">By status: <span style="color:var(--text-main)">${{counts(trials.by_status)}}</span></div>
+                        ${{rows || '<div class="text-muted small">No interventional trials found.</div>'}}
+                    </div>
+                </details>
+            `;
+        }}
+
+        function renderChembl(chembl) {{
+            const link = (id) => `<a href="https://www.ebi.ac.uk/chembl/explore/compound/${{encodeURIComponent(id)}}" target="_blank" rel="noopener">${{id}}</a>`;
+            const compounds = (chembl.top_compounds || []).map(link).join(' • ');
+            return `
+                <details class="section-disclosure">
+                    <summary>ChEMBL Chemistry (${{chembl.bioactive_compound_count}} compounds ≤ ${{chembl.activity_threshold_nm}} nM)</summary>
+                    <div class="section-disclosure-content">
+                        <div class="text-muted small mb-1">Target: <a href="https://www.ebi.ac.uk/chembl/explore/target/${{encodeURIComponent(chembl.target_chembl_id)}}" target="_blank" rel="noopener">${{chembl.target_chembl_id}}</a>${{chembl.uniprot_id ? ` (UniProt ${{chembl.uniprot_id}})` : ''}}</div>
+                        <div class="text-muted small mb-1">Approved drug: <span style="color:var(--text-main)">${{chembl.has_approved_drug ? 'yes' : 'no'}}</span></div>
+                        <div class="text-muted small">Most potent: ${{compounds || 'none below threshold'}}</div>
+                    </div>
+                </details>
+            `;
+        }}
+
         async function scoreTarget(gene, cancerType) {{
             try {{
                 const qs = new URLSearchParams();
@@ -409,6 +1254,8 @@ fn render_ranker_page(_result: Option<RankedTarget>) -> String {
                     throw new Error(text || 'Unable to score target for this scope');
                 }}
                 const result = await resp.json();
+                const count = (v, unit) => v == null ? 'no data' : `${{v}} ${{unit}}`;
+                const recent = (v) => v == null ? '' : ` (${{v.toFixed(1)}} recency-weighted)`;
                 
                 const tierClass = result.tier === 'primary' ? 'score-primary' : result.tier === 'secondary' ? 'score-secondary' : 'score-excluded';
                 const tierBadge = result.tier === 'primary' ? 'badge-success' : result.tier === 'secondary' ? 'badge-warning' : 'badge-outline';
@@ -428,27 +1275,28 @@ fn render_ranker_page(_result: Option<RankedTarget>) -> String {
                         <div class="d-flex flex-column justify-center" style="border-left: 1px solid var(--border-glass); padding-left:1.5rem;">
                             <div class="text-muted text-uppercase mb-2" style="font-size:0.8rem; letter-spacing:1px">Evidence Support Topology</div>
                             <div class="d-flex flex-column gap-2 text-muted small">
-                                <div class="d-flex justify-between"><span>Literature Base</span> <strong style="color:var(--text-main)">${{result.evidence.literature_count}} corpus artifacts</strong></div>
-                                <div class="d-flex justify-between"><span>Knowledge Graph</span> <strong style="color:var(--text-main)">${{result.evidence.kg_fact_count}} edges</strong></div>
-                                <div class="d-flex justify-between"><span>Clinical Network</span> <strong style="color:var(--text-main)">${{result.evidence.clinical_trials}} trials</strong></div>
+                                <div class="d-flex justify-between"><span>Literature Base</span> <strong style="color:var(--text-main)">${{count(result.evidence.literature_count, 'corpus artifacts')}}${{recent(result.evidence.recency_weighted_literature_count)}}</strong></div>
+                                <div class="d-flex justify-between"><span>Knowledge Graph</span> <strong style="color:var(--text-main)">${{count(result.evidence.kg_fact_count, 'edges')}} · ${{count(result.evidence.kg_support_count, 'supporting papers')}}${{recent(result.evidence.recency_weighted_kg_support)}}</strong></div>
+                                <div class="d-flex justify-between"><span>Clinical Network</span> <strong style="color:var(--text-main)">${{count(result.evidence.clinical_trials, 'trials')}}</strong></div>
                             </div>
                         </div>
                     </div>
                 `;
                 
                 const components = [
-                    ['Mutation Freq', result.component_scores.mutation_freq],
-                    ['CRISPR Dep.', result.component_scores.crispr_dependency],
-                    ['Survival', result.component_scores.survival_correlation],
-                    ['Expression', result.component_scores.expression_specificity],
-                    ['Structure', result.component_scores.structural_tractability],
-                    ['Pocket', result.component_scores.pocket_detectability],
-                    ['Novelty', result.component_scores.novelty_score],
-                    ['Pathway Ortho', result.component_scores.pathway_independence],
-                    ['Lit. Deficit', result.component_scores.literature_novelty],
-                ];
-
-                const topSignals = [...components]
+                    ['Mutation Freq', 'mutation_freq'],
+                    ['CRISPR Dep.', 'crispr_dependency'],
+                    ['Survival', 'survival_correlation'],
+                    ['Expression', 'expression_specificity'],
+                    ['Structure', 'structural_tractability'],
+                    ['Pocket', 'pocket_detectability'],
+                    ['Novelty', 'novelty_score'],
+                    ['Pathway Ortho', 'pathway_independence'],
+                    ['Lit. Deficit', 'literature_novelty'],
+                ].map(([name, key]) => [name, result.component_scores[key], (result.data_sources || {{}})[key]]);
+
+                const topSignals = components
+                    .filter(([, value]) => value != null)
                     .sort((a, b) => Number(b[1] || 0) - Number(a[1] || 0))
                     .slice(0, 3)
                     .map(([name, value]) => `${{name}} ${{(Number(value || 0) * 100).toFixed(0)}}%`)
@@ -461,13 +1309,14 @@ fn render_ranker_page(_result: Option<RankedTarget>) -> String {
                             <div class="text-muted small mb-3">Top component signals: <span style="color:var(--text-main)">${{topSignals || 'n/a'}}</span></div>
                             <div class="component-grid">
                 `;
-                for (const [name, value] of components) {{
+                for (const [name, value, source] of components) {{
                     const pct = (Number(value || 0) * 100).toFixed(0);
                     const color = value > 0.7 ? 'var(--success)' : value > 0.4 ? 'var(--warning)' : 'var(--danger)';
-                    html += `<div>
+                    const label = value == null ? 'no data' : `${{pct}}%`;
+                    html += `<div title="source: ${{source || 'no_data'}}">
                         <div class="d-flex justify-between align-center mb-1">
                             <span class="text-muted small">${{name}}</span>
-                            <strong style="color:var(--text-main); font-size:0.85rem">${{pct}}%</strong>
+                            <strong style="color:var(--text-main); font-size:0.85rem">${{label}}</strong>
                         </div>
                         <div class="component-bar"><div class="component-fill" style="width: ${{pct}}%; background: ${{color}};"></div></div>
                     </div>`;
@@ -476,6 +1325,20 @@ fn render_ranker_page(_result: Option<RankedTarget>) -> String {
                         </div>
                     </details>
                 `;
+                if (result.explanation) {{
+                    html += renderWaterfall(result.explanation, result.rationale);
+                }}
+                if (result.evidence.trials) {{
+                    html += renderTrials(result.evidence.trials);
+                }}
+                if (result.evidence.chembl) {{
+                    html += renderChembl(result.evidence.chembl);
+                }}
+                if (result.evidence.structure_summary) {{
+                    const pdb = result.evidence.structure.best_pdb_id;
+                    const link = pdb ? ` — <a href="https://www.rcsb.org/structure/${{encodeURIComponent(pdb)}}" target="_blank" rel="noopener">${{pdb}}</a>` : '';
+                    html += `<div class="text-muted small mt-2">Structure: <span style="color:var(--text-main)">${{result.evidence.structure_summary}}</span>${{link}}</div>`;
+                }}
                 document.getElementById('scoreResult').innerHTML = html;
             }} catch (e) {{
                 document.getElementById('scoreResult').innerHTML = '<div class="p-4 text-center text-danger">Network synthesis interference detected</div>';
@@ -503,6 +1366,24 @@ fn render_ranker_page(_result: Option<RankedTarget>) -> String {
         const initialCancer = (document.getElementById('cancerInput').value || '').trim();
         document.getElementById('cancerLabel').textContent = initialCancer || 'All indications';
         loadTopTargets(initialCancer);
+
+        // Reload the shortlist when the KG event queue rescores a target in
+        // scope; a burst of updates triggers one reload.
+        let scoreRefreshTimer = null;
+        const scoreEvents = new EventSource('/api/events?topics=ranker');
+        scoreEvents.onmessage = function(e) {{
+            let data;
+            try {{
+                data = JSON.parse(e.data);
+            }} catch (_err) {{
+                return;
+            }}
+            if (data.type !== 'score_updated') return;
+            const cancer = (document.getElementById('cancerInput').value || '').trim();
+            if (cancer && data.cancer && data.cancer.toUpperCase() !== cancer.toUpperCase()) return;
+            clearTimeout(scoreRefreshTimer);
+            scoreRefreshTimer = setTimeout(() => loadTopTargets(cancer), 1000);
+        }};
     </script>
     <style>@keyframes spin {{ 100% {{ transform: rotate(360deg); }} }}</style>
 </body>
@@ -510,3 +1391,189 @@ fn render_ranker_page(_result: Option<RankedTarget>) -> String {
         NAV_HTML
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::AppState;
+    use ferrumyx_db::phase4_signals::Phase4SignalRepository;
+    use ferrumyx_db::schema::{EntGtexExpression, Entity, EntityType, KgFact, Paper};
+
+    /// A database with a persisted score for `gene` that carries no
+    /// components, two KG facts about it from two papers, and no provider
+    /// signals.
+    async fn scored_state(gene: &str) -> (SharedState, std::path::PathBuf) {
+        let dir =
+            std::env::temp_dir().join(format!("ferrumyx-web-ranker-{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(ferrumyx_db::Database::open(&dir.join("db")).await.unwrap());
+        db.initialize().await.unwrap();
+
+        let entity = |entity_type, name: &str, external_id: &str| {
+            Entity::new(
+                entity_type,
+                name.to_string(),
+                external_id.to_string(),
+                "test".to_string(),
+            )
+        };
+        let target = entity(EntityType::Gene, gene, "HGNC:0");
+        let disease = entity(EntityType::Disease, "Pancreatic Neoplasms", "MESH:D010190");
+        let partner = entity(EntityType::Gene, "TP53", "HGNC:11998");
+        EntityRepository::new(db.clone())
+            .insert_batch(&[target.clone(), disease.clone(), partner.clone()])
+            .await
+            .unwrap();
+
+        let papers: Vec<Paper> = (1..=2)
+            .map(|i| Paper::new(format!("{gene} paper {i}"), "test".to_string()))
+            .collect();
+        PaperRepository::new(db.clone())
+            .insert_batch(&papers)
+            .await
+            .unwrap();
+        let fact = |paper: &Paper, predicate: &str, object: &Entity| {
+            KgFact::new(
+                paper.id,
+                target.id,
+                target.name.clone(),
+                predicate.to_string(),
+                object.id,
+                object.name.clone(),
+            )
+        };
+        KgFactRepository::new(db.clone())
+            .insert_batch(&[
+                fact(&papers[0], "drives", &disease),
+                fact(&papers[1], "associated_with", &partner),
+            ])
+            .await
+            .unwrap();
+
+        let mut score = TargetScore::new(
+            target.id,
+            uuid::Uuid::nil(),
+            0.4,
+            0.4,
+            0.0,
+            "secondary".to_string(),
+        );
+        score.components_raw = serde_json::json!({ "gene": gene }).to_string();
+        TargetScoreRepository::new(db.clone())
+            .insert(&score)
+            .await
+            .unwrap();
+
+        (Arc::new(AppState::new(db)), dir)
+    }
+
+    #[tokio::test]
+    async fn scores_are_listed_past_the_first_page() {
+        let (state, dir) = scored_state("RNKPAGED").await;
+        let extra: Vec<TargetScore> = (0..2 * SCORE_PAGE)
+            .map(|_| {
+                TargetScore::new(
+                    uuid::Uuid::new_v4(),
+                    uuid::Uuid::nil(),
+                    0.1,
+                    0.1,
+                    0.0,
+                    "excluded".to_string(),
+                )
+            })
+            .collect();
+        TargetScoreRepository::new(state.db.clone())
+            .upsert_batch(&extra)
+            .await
+            .unwrap();
+
+        let all = list_scores(&state, usize::MAX).await.unwrap();
+        assert_eq!(all.len(), 2 * SCORE_PAGE + 1);
+        assert_eq!(list_scores(&state, 5).await.unwrap().len(), 5);
+        let stored = load_stored_scores(&state).await.unwrap();
+        assert_eq!(stored.len(), all.len());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn a_score_without_signals_reports_no_data_and_the_kg_evidence() {
+        let (state, dir) = scored_state("RNKNODATA").await;
+        let row = score_target(&state, "rnknodata", None, false)
+            .await
+            .unwrap();
+
+        assert_eq!(row.component_scores.as_array(), [None; 9]);
+        assert_eq!(row.data_sources.len(), COMPONENT_KEYS.len());
+        assert!(
+            row.data_sources.values().all(|s| s == SOURCE_NO_DATA),
+            "{:?}",
+            row.data_sources
+        );
+        assert_eq!(row.evidence.kg_fact_count, Some(2));
+        assert_eq!(row.evidence.kg_support_count, Some(2));
+        assert_eq!(row.evidence.literature_count, Some(2));
+        assert_eq!(row.evidence.clinical_trials, Some(0));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn a_cached_signal_fills_its_component_and_names_the_provider() {
+        let (state, dir) = scored_state("RNKGTEX").await;
+        Phase4SignalRepository::new(state.db.clone())
+            .upsert_gtex_expression(&EntGtexExpression {
+                id: uuid::Uuid::new_v4(),
+                gene_symbol: "RNKGTEX".to_string(),
+                expression_score: 0.8,
+                source: "gtex".to_string(),
+                fetched_at: chrono::Utc::now(),
+            })
+            .await
+            .unwrap();
+
+        let row = score_target(&state, "RNKGTEX", None, false).await.unwrap();
+        assert_eq!(row.component_scores.expression_specificity, Some(0.8));
+        assert_eq!(row.data_sources["expression_specificity"], "gtex");
+        for (component, source) in &row.data_sources {
+            if component != "expression_specificity" {
+                assert_eq!(source, SOURCE_NO_DATA, "{component}");
+            }
+        }
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn cancer_filter_resolves_through_oncotree() {
+        assert_eq!(resolve_cancer_filter(None).unwrap(), None);
+        assert_eq!(resolve_cancer_filter(Some("  ")).unwrap(), None);
+        assert_eq!(
+            resolve_cancer_filter(Some("TCGA-LIHC")).unwrap(),
+            CancerType::parse("HCC")
+        );
+
+        let err = resolve_cancer_filter(Some("xyzzy")).unwrap_err();
+        let response = axum::response::IntoResponse::into_response(err);
+        assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+        match resolve_cancer_filter(Some("PADD")) {
+            Err(ApiError::BadRequest(msg)) => {
+                assert!(msg.contains("did you mean PAAD"), "{msg}")
+            }
+            other => panic!("expected a 400, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn export_filename_names_cancer_type_and_date() {
+        let date = chrono::NaiveDate::from_ymd_opt(2026, 3, 9).unwrap();
+        assert_eq!(
+            export_filename(Some(" paad "), ExportFormat::Csv, date),
+            "ferrumyx-targets-PAAD-2026-03-09.csv"
+        );
+        assert_eq!(
+            export_filename(Some("\"; rm"), ExportFormat::Tsv, date),
+            "ferrumyx-targets-RM-2026-03-09.tsv"
+        );
+        assert_eq!(
+            export_filename(None, ExportFormat::Json, date),
+            "ferrumyx-targets-all-2026-03-09.json"
+        );
+    }
+}
@@ -1,5 +1,6 @@
 //! Hybrid search endpoints.
-//! Combines LanceDB FTS + vector retrieval with RRF fusion + KG evidence aggregation.
+//! Combines LanceDB FTS (BM25) + vector retrieval with RRF fusion (or an alpha
+//! blend) + optional re-ranking + KG evidence aggregation.
 
 use std::collections::HashMap;
 
@@ -15,8 +16,13 @@ use ferrumyx_common::error::ApiError;
 use ferrumyx_db::{kg_facts::KgFactRepository, papers::PaperRepository};
 use ferrumyx_ingestion::embedding::{
     hybrid_search as ingestion_hybrid_search, EmbeddingClient, EmbeddingConfig, HybridSearchConfig,
+    SearchResult as ChunkHit,
+};
+use ferrumyx_ingestion::paper_search::{
+    search_papers as ingestion_search_papers, PaperSearchResult,
 };
 use ferrumyx_ingestion::repository::IngestionRepository;
+use ferrumyx_ingestion::rerank::{rerank_results, RerankConfig, RerankReport, RerankerKind};
 
 #[derive(Debug, Deserialize)]
 pub struct SearchQuery {
@@ -24,6 +30,10 @@ pub struct SearchQuery {
     #[serde(default)]
     pub limit: i32,
     pub cancer_type: Option<String>,
+    /// `vector` | `keyword` | `hybrid` (default).
+    pub mode: Option<String>,
+    /// Vector weight for a linear score blend; RRF when absent.
+    pub alpha: Option<f32>,
 }
 
 impl Default for SearchQuery {
@@ -32,6 +42,37 @@ impl Default for SearchQuery {
             q: String::new(),
             limit: 20,
             cancer_type: None,
+            mode: None,
+            alpha: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SearchMode {
+    Vector,
+    Keyword,
+    Hybrid,
+}
+
+impl SearchMode {
+    fn parse(raw: Option<&str>) -> Result<Self, ApiError> {
+        match raw.map(str::trim).filter(|v| !v.is_empty()) {
+            None => Ok(Self::Hybrid),
+            Some(v) if v.eq_ignore_ascii_case("hybrid") => Ok(Self::Hybrid),
+            Some(v) if v.eq_ignore_ascii_case("vector") => Ok(Self::Vector),
+            Some(v) if v.eq_ignore_ascii_case("keyword") => Ok(Self::Keyword),
+            Some(other) => Err(ApiError::BadRequest(format!(
+                "unknown search mode {other:?}; expected vector, keyword or hybrid"
+            ))),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Vector => "vector",
+            Self::Keyword => "keyword",
+            Self::Hybrid => "hybrid",
         }
     }
 }
@@ -44,14 +85,24 @@ pub struct SearchResult {
     pub similarity: f64,
     pub section_type: Option<String>,
     pub source: String,
+    /// BM25 score from the keyword index.
+    pub keyword_score: Option<f32>,
+    pub keyword_rank: Option<u32>,
+    /// Cosine similarity to the query embedding.
+    pub vector_score: Option<f32>,
+    pub vector_rank: Option<u32>,
+    /// Relevance from the configured reranker, when it applied.
+    pub rerank_score: Option<f32>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct HybridSearchResponse {
     pub query: String,
+    pub mode: String,
     pub results: Vec<SearchResult>,
     pub kg_facts: Vec<KgFactBrief>,
     pub total: u64,
+    pub rerank: RerankReport,
 }
 
 #[derive(Debug, Serialize)]
@@ -63,21 +114,35 @@ pub struct KgFactBrief {
 }
 
 /// GET /api/search - Hybrid search (LanceDB FTS + vector + KG)
+///
+/// `mode=vector|keyword|hybrid` picks the retrieval legs; `alpha` switches
+/// fusion from RRF to a blend of normalised scores. With a reranker configured
+/// (`FERRUMYX_SEARCH_RERANKER`), the top candidates are re-scored before
+/// truncating to `limit`.
 pub async fn hybrid_search(
     State(state): State<SharedState>,
     Query(query): Query<SearchQuery>,
 ) -> Result<impl IntoResponse, ApiError> {
+    let mode = SearchMode::parse(query.mode.as_deref())?;
+    let rerank_cfg = RerankConfig::from_env();
     let q = query.q.trim();
     if q.is_empty() {
         return Ok(Json(HybridSearchResponse {
             query: String::new(),
+            mode: mode.as_str().to_string(),
             results: Vec::new(),
             kg_facts: Vec::new(),
             total: 0,
+            rerank: RerankReport::skipped(rerank_cfg.kind),
         }));
     }
 
     let limit = query.limit.max(1).min(100) as usize;
+    let retrieve_limit = if rerank_cfg.kind == RerankerKind::None {
+        limit
+    } else {
+        limit.max(rerank_cfg.candidates)
+    };
     let scan_limit = (limit * 20).clamp(100, 3000);
     let ingestion_repo = IngestionRepository::new(state.db.clone());
     let kg_repo = KgFactRepository::new(state.db.clone());
@@ -91,39 +156,20 @@ pub async fn hybrid_search(
         .filter(|v| !v.is_empty())
         .map(|v| v.to_ascii_lowercase());
 
-    let embed_client = EmbeddingClient::new(EmbeddingConfig::default());
-    let query_vec = embed_client
-        .embed_batch(&[q.to_string()])
-        .await
-        .ok()
-        .and_then(|mut v| v.pop());
-
     let mut cfg = HybridSearchConfig {
-        limit,
+        limit: retrieve_limit,
         pre_fusion_limit: scan_limit,
+        use_fts: mode != SearchMode::Vector,
+        use_vector: mode != SearchMode::Keyword,
+        alpha: query.alpha,
         ..HybridSearchConfig::default()
     };
-
-    let mut hybrid_rows = if let Some(v) = query_vec {
-        match ingestion_hybrid_search(&ingestion_repo, q, Some(v), &cfg).await {
-            Ok(rows) => rows,
-            Err(_) => {
-                cfg.use_vector = false;
-                ingestion_hybrid_search(&ingestion_repo, q, None, &cfg)
-                    .await
-                    .unwrap_or_default()
-            }
-        }
-    } else {
-        cfg.use_vector = false;
-        ingestion_hybrid_search(&ingestion_repo, q, None, &cfg)
-            .await
-            .unwrap_or_default()
-    };
+    let mut hybrid_rows = retrieve_chunks(&ingestion_repo, q, mode, &mut cfg).await;
 
     if let Some(cancer) = &cancer_filter {
         hybrid_rows.retain(|r| r.content.to_ascii_lowercase().contains(cancer));
     }
+    let (hybrid_rows, rerank) = rerank_results(q, hybrid_rows, limit, &rerank_cfg).await;
 
     let paper_ids: Vec<uuid::Uuid> = hybrid_rows.iter().map(|r| r.paper_id).collect();
     let titles_by_id = paper_repo
@@ -134,7 +180,9 @@ pub async fn hybrid_search(
     let results: Vec<SearchResult> = hybrid_rows
         .into_iter()
         .map(|r| {
-            let source = if r.is_hybrid() {
+            let source = if r.is_hybrid() && cfg.alpha.is_some() {
+                "hybrid-blend".to_string()
+            } else if r.is_hybrid() {
                 "hybrid-rrf".to_string()
             } else if r.vector_rank.is_some() {
                 "vector".to_string()
@@ -148,6 +196,11 @@ pub async fn hybrid_search(
                 similarity: r.score as f64,
                 section_type: None,
                 source,
+                keyword_score: r.fts_score,
+                keyword_rank: r.fts_rank,
+                vector_score: r.vector_score,
+                vector_rank: r.vector_rank,
+                rerank_score: r.rerank_score,
             }
         })
         .collect();
@@ -179,8 +232,168 @@ pub async fn hybrid_search(
 
     Ok(Json(HybridSearchResponse {
         query: q.to_string(),
+        mode: mode.as_str().to_string(),
         total: results.len() as u64,
         results,
         kg_facts,
+        rerank,
     }))
 }
+
+#[derive(Debug, Serialize)]
+pub struct PaperSearchResponse {
+    pub query: String,
+    pub mode: String,
+    pub papers: Vec<PaperSearchResult>,
+    pub total: u64,
+}
+
+/// GET /api/search/papers - Paper-level search
+///
+/// Same parameters as `/api/search`; chunk hits are grouped by paper with
+/// citation metadata and a highlighted best snippet.
+pub async fn paper_search(
+    State(state): State<SharedState>,
+    Query(query): Query<SearchQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let mode = SearchMode::parse(query.mode.as_deref())?;
+    let q = query.q.trim();
+    let limit = query.limit.max(1).min(100) as usize;
+    let papers = if q.is_empty() {
+        Vec::new()
+    } else {
+        search_paper_hits(
+            &state,
+            q,
+            limit,
+            mode,
+            query.alpha,
+            query.cancer_type.as_deref(),
+        )
+        .await
+    };
+
+    Ok(Json(PaperSearchResponse {
+        query: q.to_string(),
+        mode: mode.as_str().to_string(),
+        total: papers.len() as u64,
+        papers,
+    }))
+}
+
+/// Top `limit` papers for `q` in hybrid mode, for server-rendered pages.
+pub(crate) async fn hybrid_paper_hits(
+    state: &SharedState,
+    q: &str,
+    limit: usize,
+) -> Vec<PaperSearchResult> {
+    search_paper_hits(state, q, limit, SearchMode::Hybrid, None, None).await
+}
+
+async fn search_paper_hits(
+    state: &SharedState,
+    q: &str,
+    limit: usize,
+    mode: SearchMode,
+    alpha: Option<f32>,
+    cancer_type: Option<&str>,
+) -> Vec<PaperSearchResult> {
+    let ingestion_repo = IngestionRepository::new(state.db.clone());
+    let query_vec = query_embedding(q, mode).await;
+    if mode == SearchMode::Vector && query_vec.is_none() {
+        return Vec::new();
+    }
+
+    // Several chunks per paper, so group over a wider chunk window.
+    let mut cfg = HybridSearchConfig {
+        limit: (limit * 5).clamp(50, 500),
+        pre_fusion_limit: (limit * 20).clamp(100, 3000),
+        use_fts: mode != SearchMode::Vector,
+        use_vector: query_vec.is_some(),
+        alpha,
+        ..HybridSearchConfig::default()
+    };
+    let mut papers = match ingestion_search_papers(&ingestion_repo, q, query_vec, limit, &cfg).await
+    {
+        Ok(papers) => papers,
+        Err(_) if mode == SearchMode::Hybrid && cfg.use_vector => {
+            cfg.use_vector = false;
+            ingestion_search_papers(&ingestion_repo, q, None, limit, &cfg)
+                .await
+                .unwrap_or_default()
+        }
+        Err(_) => Vec::new(),
+    };
+
+    if let Some(cancer) = cancer_type
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|v| v.to_ascii_lowercase())
+    {
+        papers.retain(|p| {
+            p.chunks
+                .iter()
+                .any(|c| c.content.to_ascii_lowercase().contains(&cancer))
+        });
+    }
+    papers
+}
+
+/// Top `limit` chunks for `q` in hybrid mode, re-ranked when a reranker
+/// is configured, for handlers that ground an LLM on them.
+pub(crate) async fn hybrid_chunk_hits(state: &SharedState, q: &str, limit: usize) -> Vec<ChunkHit> {
+    let rerank_cfg = RerankConfig::from_env();
+    let retrieve_limit = if rerank_cfg.kind == RerankerKind::None {
+        limit
+    } else {
+        limit.max(rerank_cfg.candidates)
+    };
+    let mut cfg = HybridSearchConfig {
+        limit: retrieve_limit,
+        pre_fusion_limit: (limit * 20).clamp(100, 3000),
+        ..HybridSearchConfig::default()
+    };
+    let ingestion_repo = IngestionRepository::new(state.db.clone());
+    let rows = retrieve_chunks(&ingestion_repo, q, SearchMode::Hybrid, &mut cfg).await;
+    rerank_results(q, rows, limit, &rerank_cfg).await.0
+}
+
+/// Run `cfg` for `q`. Hybrid falls back to keyword-only when the vector
+/// leg is unavailable, leaving `cfg.use_vector` cleared.
+async fn retrieve_chunks(
+    repo: &IngestionRepository,
+    q: &str,
+    mode: SearchMode,
+    cfg: &mut HybridSearchConfig,
+) -> Vec<ChunkHit> {
+    match (query_embedding(q, mode).await, mode) {
+        (Some(v), _) => match ingestion_hybrid_search(repo, q, Some(v), cfg).await {
+            Ok(rows) => rows,
+            Err(_) if mode == SearchMode::Hybrid => {
+                cfg.use_vector = false;
+                ingestion_hybrid_search(repo, q, None, cfg)
+                    .await
+                    .unwrap_or_default()
+            }
+            Err(_) => Vec::new(),
+        },
+        (None, SearchMode::Vector) => Vec::new(),
+        (None, _) => {
+            cfg.use_vector = false;
+            ingestion_hybrid_search(repo, q, None, cfg)
+                .await
+                .unwrap_or_default()
+        }
+    }
+}
+
+async fn query_embedding(q: &str, mode: SearchMode) -> Option<Vec<f32>> {
+    if mode == SearchMode::Keyword {
+        return None;
+    }
+    EmbeddingClient::new(EmbeddingConfig::default())
+        .embed_batch(&[q.to_string()])
+        .await
+        .ok()
+        .and_then(|mut v| v.pop())
+}
@@ -86,11 +86,11 @@ async function loadSettings() {
   byId('ingestion_max_relation_genes_per_chunk').value = data.ingestion_max_relation_genes_per_chunk;
   byId('ingestion_async_post_ingest_scoring').checked = data.ingestion_async_post_ingest_scoring;
   byId('unpaywall_email').value = data.unpaywall_email;
+  byId('scihub_enabled').checked = data.scihub_enabled;
   byId('scihub_domains').value = data.scihub_domains;
   byId('scihub_request_timeout_secs').value = data.scihub_request_timeout_secs;
   byId('scihub_domain_parallelism').value = data.scihub_domain_parallelism;
   byId('scihub_domain_cooldown_secs').value = data.scihub_domain_cooldown_secs;
-  byId('scihub_defer_ms').value = data.scihub_defer_ms;
   byId('scihub_adaptive_enabled').checked = data.scihub_adaptive_enabled;
   byId('scihub_adaptive_fail_streak').value = data.scihub_adaptive_fail_streak;
   byId('scihub_adaptive_backoff_secs').value = data.scihub_adaptive_backoff_secs;
@@ -146,6 +146,7 @@ async function loadSettings() {
   byId('cosmic_base_url').value = data.cosmic_base_url;
   byId('cosmic_timeout_secs').value = data.cosmic_timeout_secs;
   byId('cosmic_mutation_data_path').value = data.cosmic_mutation_data_path;
+  byId('cosmic_census_path').value = data.cosmic_census_path;
   byId('federation_default_remote_base_url').value = data.federation_default_remote_base_url;
   byId('federation_node_public_base_url').value = data.federation_node_public_base_url;
   byId('federation_sync_chunk_bytes').value = data.federation_sync_chunk_bytes;
@@ -240,11 +241,11 @@ async function saveSettings() {
     ingestion_max_relation_genes_per_chunk: Number(byId('ingestion_max_relation_genes_per_chunk').value || 4),
     ingestion_async_post_ingest_scoring: byId('ingestion_async_post_ingest_scoring').checked,
     unpaywall_email: byId('unpaywall_email').value,
+    scihub_enabled: byId('scihub_enabled').checked,
     scihub_domains: byId('scihub_domains').value,
     scihub_request_timeout_secs: Number(byId('scihub_request_timeout_secs').value || 10),
     scihub_domain_parallelism: Number(byId('scihub_domain_parallelism').value || 4),
     scihub_domain_cooldown_secs: Number(byId('scihub_domain_cooldown_secs').value || 300),
-    scihub_defer_ms: Number(byId('scihub_defer_ms').value || 700),
     scihub_adaptive_enabled: byId('scihub_adaptive_enabled').checked,
     scihub_adaptive_fail_streak: Number(byId('scihub_adaptive_fail_streak').value || 8),
     scihub_adaptive_backoff_secs: Number(byId('scihub_adaptive_backoff_secs').value || 300),
@@ -300,6 +301,7 @@ async function saveSettings() {
     cosmic_base_url: byId('cosmic_base_url').value,
     cosmic_timeout_secs: Number(byId('cosmic_timeout_secs').value || 10),
     cosmic_mutation_data_path: byId('cosmic_mutation_data_path').value,
+    cosmic_census_path: byId('cosmic_census_path').value,
     federation_default_remote_base_url: byId('federation_default_remote_base_url').value,
     federation_node_public_base_url: byId('federation_node_public_base_url').value,
     federation_sync_chunk_bytes: Number(byId('federation_sync_chunk_bytes').value || 1048576),
@@ -333,7 +335,7 @@ async function saveSettings() {
 
   try {
     const res = await fetch('/api/settings', {
-      method: 'POST',
+      method: 'PUT',
       headers: { 'Content-Type': 'application/json' },
       body: JSON.stringify(payload),
     });
@@ -342,6 +344,11 @@ async function saveSettings() {
 
     btn.innerHTML = 'Saved';
     btn.style.backgroundColor = 'var(--success)';
+    const notice = byId('settings-save-notice');
+    notice.textContent = (data.restart_required || []).length
+      ? 'Restart Ferrumyx to apply: ' + data.restart_required.join(', ') + '.'
+      : '';
+    notice.style.display = notice.textContent ? '' : 'none';
 
     ['openai_api_key','anthropic_api_key','gemini_api_key','compat_api_key','pubmed_api_key','semanticscholar_api_key','cbioportal_api_token','cosmic_api_key','federation_remote_api_token','federation_read_token','federation_write_token','federation_hf_token','embedding_api_key']
       .forEach((id) => { byId(id).value = ''; });
@@ -359,9 +366,105 @@ async function saveSettings() {
   }
 }
 
+const WEIGHT_FIELDS = ['mutation_freq','crispr_dependency','survival_correlation','expression_specificity','structural_tractability','pocket_detectability','novelty_score','pathway_independence','literature_novelty'];
+
+async function loadWeights() {
+  const res = await fetch('/api/ranker/weights');
+  const data = await res.json();
+  WEIGHT_FIELDS.forEach((k) => { byId('weight_' + k).value = Number(data[k]).toFixed(3); });
+}
+
+async function saveWeights() {
+  const status = byId('weights_status');
+  const payload = {};
+  WEIGHT_FIELDS.forEach((k) => { payload[k] = Number(byId('weight_' + k).value || 0); });
+  try {
+    const res = await fetch('/api/ranker/weights', {
+      method: 'PUT',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify(payload),
+    });
+    const data = await res.json();
+    if (!res.ok) throw new Error(data.error || 'save failed');
+    await loadWeights();
+    status.textContent = 'Applied';
+    status.style.color = 'var(--success)';
+  } catch (e) {
+    status.textContent = e.message;
+    status.style.color = 'var(--danger)';
+  }
+}
+
+async function loadLlmUsage() {
+  const res = await fetch('/api/llm/usage');
+  if (!res.ok) return;
+  const data = await res.json();
+  const money = (v) => (v == null ? 'no cap' : '$' + Number(v).toFixed(2));
+  byId('usage_day').textContent = data.day;
+  byId('usage_total_tokens').textContent = Number(data.total_tokens).toLocaleString();
+  byId('usage_total_cost').textContent = '$' + Number(data.total_cost_usd).toFixed(2) + ' of ' + money(data.max_cost_per_day_usd);
+  byId('usage_alert').textContent = money(data.alert_cost_threshold_usd);
+  const rows = byId('usage_backends');
+  rows.innerHTML = '';
+  data.backends.forEach((b) => {
+    const tokens = b.prompt_tokens + b.completion_tokens;
+    [
+      b.backend,
+      b.calls + ' calls',
+      tokens.toLocaleString() + (b.token_limit == null ? '' : ' / ' + b.token_limit.toLocaleString()) + ' tokens',
+      '$' + Number(b.cost_usd).toFixed(4),
+    ].forEach((text) => {
+      const cell = document.createElement('div');
+      cell.textContent = text;
+      rows.appendChild(cell);
+    });
+  });
+}
+
+const AUDIT_PAGE = 25;
+let auditOffset = 0;
+
+async function loadLlmAudit(offset) {
+  const params = new URLSearchParams({ limit: AUDIT_PAGE, offset: Math.max(0, offset || 0) });
+  if (byId('audit_class').value) params.set('class', byId('audit_class').value);
+  if (byId('audit_backend').value.trim()) params.set('backend', byId('audit_backend').value.trim());
+  const res = await fetch('/api/audit/llm?' + params);
+  if (!res.ok) return;
+  const data = await res.json();
+  if (data.total > 0 && data.offset >= data.total) return;
+  auditOffset = data.offset;
+  const rows = byId('audit_rows');
+  rows.innerHTML = '';
+  if (data.entries.length === 0) {
+    byId('audit_range').textContent = '';
+    rows.textContent = 'No model invocations logged.';
+    return;
+  }
+  byId('audit_range').textContent = (data.offset + 1) + '-' + (data.offset + data.entries.length) + ' of ' + data.total;
+  data.entries.forEach((e) => {
+    const tokens = (e.prompt_tokens ?? '?') + ' / ' + (e.completion_tokens ?? '?');
+    [
+      new Date(e.called_at).toLocaleString(),
+      e.backend || '-',
+      e.model || '-',
+      e.data_class,
+      e.caller || '-',
+      tokens,
+      e.error ? 'error: ' + e.error : (e.cached ? 'cached' : (e.latency_ms ?? 0) + ' ms'),
+    ].forEach((text) => {
+      const cell = document.createElement('div');
+      cell.textContent = text;
+      rows.appendChild(cell);
+    });
+  });
+}
+
 document.addEventListener('DOMContentLoaded', () => {
   tabInit();
   loadSettings();
+  loadWeights();
+  loadLlmUsage();
+  loadLlmAudit(0);
 });
 "#;
 
@@ -404,6 +507,7 @@ __NAV__
     <div>
       <h1 class="page-title">Global Settings</h1>
       <p class="text-muted">Provider configuration, API credentials, and runtime defaults.</p>
+      <p id="settings-save-notice" class="text-muted" style="display:none; color:#fbbf24;"></p>
     </div>
     <button class="btn btn-primary" id="master-save-btn" onclick="saveSettings()">Commit Configuration</button>
   </div>
@@ -416,6 +520,7 @@ __NAV__
       <button class="tab-btn" data-tab="tab-runtime">Runtime</button>
       <button class="tab-btn" data-tab="tab-federation">Federation</button>
       <button class="tab-btn" data-tab="tab-graph">Graph</button>
+      <button class="tab-btn" data-tab="tab-scoring">Scoring</button>
       <button class="tab-btn" data-tab="tab-benchmark">Benchmark</button>
     </nav>
 
@@ -423,8 +528,8 @@ __NAV__
       <section id="tab-llm" class="tab-panel active card p-4">
         <h3 class="settings-section-title">Language Model Providers</h3>
         <div class="security-note" style="margin-bottom:0.9rem; border-color:rgba(251,191,36,0.35); background:rgba(120,53,15,0.18);">
-          <strong style="color:#fbbf24;">Agent Restart Required for LLM Changes</strong>
-          <div style="margin-top:0.35rem;">Changes to backend, base URL, model, and API keys are saved immediately but take effect after restarting the Ferrumyx agent process.</div>
+          <strong style="color:#fbbf24;">LLM Changes Apply to Answers Immediately</strong>
+          <div style="margin-top:0.35rem;">Saving rebuilds the answer backends in place. The agent's own chat model keeps its settings until the Ferrumyx agent process restarts. API keys are write-only and stored encrypted with a key derived from this machine (or <code>FERRUMYX_SECRET_KEY</code>).</div>
         </div>
         <div class="form-grid">
           <div class="form-group"><label for="llm_mode">Mode</label><select id="llm_mode" class="form-control"><option value="local_only">local_only</option><option value="prefer_local">prefer_local</option><option value="any">any</option></select></div>
@@ -455,6 +560,30 @@ __NAV__
             <div class="help-text">Enabled by default. Unsupported models/providers ignore caching hints safely.</div>
           </div>
         </div>
+
+        <div class="security-note" style="margin-top:0.8rem;">
+          <strong style="color:var(--text-main);">Usage Today (<span id="usage_day">n/a</span> UTC)</strong>
+          <div class="help-text" style="margin-top:0.35rem;">Calls routed through the answer endpoint, counted against <code>[llm.limits]</code>. Remote backends over their cap fall back to the local backend.</div>
+          <div style="margin-top:0.55rem; display:grid; grid-template-columns: 230px 1fr; row-gap:0.35rem; column-gap:0.9rem;">
+            <div>Total Tokens</div><div id="usage_total_tokens">0</div>
+            <div>Estimated Cost</div><div id="usage_total_cost">$0.00</div>
+            <div>Cost Alert Threshold</div><div id="usage_alert">n/a</div>
+          </div>
+          <div id="usage_backends" style="margin-top:0.55rem; display:grid; grid-template-columns: 230px 110px 220px 1fr; row-gap:0.35rem; column-gap:0.9rem;"></div>
+        </div>
+
+        <div class="security-note" style="margin-top:0.8rem;">
+          <strong style="color:var(--text-main);">LLM Audit Log</strong>
+          <div class="help-text" style="margin-top:0.35rem;">One row per routed call while <code>[security] audit_llm_calls</code> is on. Prompts are kept as a hash and length only.</div>
+          <div style="margin-top:0.55rem; display:flex; gap:0.6rem; align-items:center;">
+            <select id="audit_class" class="form-control" style="max-width:180px;" onchange="loadLlmAudit(0)"><option value="">All classes</option><option value="PUBLIC">PUBLIC</option><option value="INTERNAL">INTERNAL</option><option value="CONFIDENTIAL">CONFIDENTIAL</option></select>
+            <input id="audit_backend" class="form-control" style="max-width:180px;" placeholder="Backend" onchange="loadLlmAudit(0)" />
+            <button class="btn" onclick="loadLlmAudit(auditOffset - AUDIT_PAGE)">Newer</button>
+            <button class="btn" onclick="loadLlmAudit(auditOffset + AUDIT_PAGE)">Older</button>
+            <span id="audit_range" class="help-text"></span>
+          </div>
+          <div id="audit_rows" style="margin-top:0.55rem; display:grid; grid-template-columns: 190px 120px 150px 110px 120px 80px 1fr; row-gap:0.35rem; column-gap:0.9rem;"></div>
+        </div>
       </section>
 
       <section id="tab-ingestion" class="tab-panel card p-4">
@@ -500,6 +629,11 @@ __NAV__
             <input id="cosmic_mutation_data_path" class="form-control" placeholder="data/providers/cosmic_mutation_frequency.tsv" />
             <div class="help-text">Local TSV/CSV export path for fast offline lookup; used before remote API calls.</div>
           </div>
+          <div class="form-group">
+            <label for="cosmic_census_path">COSMIC Cancer Mutation Census Path (optional)</label>
+            <input id="cosmic_census_path" class="form-control" placeholder="data/providers/CancerMutationCensus_AllData.tsv" />
+            <div class="help-text">Licensed CMC export downloaded from COSMIC. Loaded at start-up into has_hotspot KG facts; known drivers boost the focus-mutation score.</div>
+          </div>
           <div class="form-group">
             <label for="cosmic_api_key">COSMIC API Key <span id="cosmic_state" class="state-pill">Not Set</span></label>
             <input id="cosmic_api_key" type="password" class="form-control" placeholder="Leave blank to keep existing" />
@@ -513,6 +647,11 @@ __NAV__
         </div>
         <h4 class="settings-section-title" style="margin-top:1rem;">Sci-Hub Full-Text Fallback</h4>
         <div class="form-grid">
+          <div class="form-group">
+            <label for="scihub_enabled">Sci-Hub Fallback Enabled</label>
+            <input id="scihub_enabled" type="checkbox" />
+            <div class="help-text">Default for new ingestion runs; each run can still switch it off.</div>
+          </div>
           <div class="form-group">
             <label for="scihub_domains">Sci-Hub Mirror List (comma-separated)</label>
             <textarea id="scihub_domains" class="form-control" rows="3" placeholder="https://sci-hub.al,https://sci-hub.mk,https://sci-hub.ee"></textarea>
@@ -531,12 +670,7 @@ __NAV__
           <div class="form-group">
             <label for="scihub_domain_cooldown_secs">Sci-Hub Mirror Cooldown (seconds)</label>
             <input id="scihub_domain_cooldown_secs" type="number" min="15" max="3600" class="form-control" />
-            <div class="help-text">Temporarily suppresses mirrors after server errors/timeouts to avoid repeated stalls.</div>
-          </div>
-          <div class="form-group">
-            <label for="scihub_defer_ms">Sci-Hub Fallback Defer (ms)</label>
-            <input id="scihub_defer_ms" type="number" min="0" max="10000" class="form-control" />
-            <div class="help-text">Delay before launching Sci-Hub so OA routes get first chance and avoid unnecessary fallback calls.</div>
+            <div class="help-text">Rests a mirror after a server error or timeout; the rest doubles with each further failure in a row.</div>
           </div>
           <div class="form-group">
             <label for="scihub_adaptive_enabled">Adaptive Sci-Hub Backoff Enabled</label>
@@ -974,6 +1108,23 @@ __NAV__
         </div>
       </section>
 
+      <section id="tab-scoring" class="tab-panel card p-4">
+        <h3 class="settings-section-title">Target Score Weights</h3>
+        <div class="help-text" style="margin-bottom:0.9rem;">Applied immediately to ranking and kept until restart; set <code>[scoring.weights]</code> in ferrumyx.toml to persist. Weights that do not sum to 1.0 are renormalised.</div>
+        <div class="form-grid">
+          <div class="form-group"><label for="weight_mutation_freq">Mutation Frequency</label><input id="weight_mutation_freq" type="number" min="0" step="0.01" class="form-control" /></div>
+          <div class="form-group"><label for="weight_crispr_dependency">CRISPR Dependency</label><input id="weight_crispr_dependency" type="number" min="0" step="0.01" class="form-control" /></div>
+          <div class="form-group"><label for="weight_survival_correlation">Survival Correlation</label><input id="weight_survival_correlation" type="number" min="0" step="0.01" class="form-control" /></div>
+          <div class="form-group"><label for="weight_expression_specificity">Expression Specificity</label><input id="weight_expression_specificity" type="number" min="0" step="0.01" class="form-control" /></div>
+          <div class="form-group"><label for="weight_structural_tractability">Structural Tractability</label><input id="weight_structural_tractability" type="number" min="0" step="0.01" class="form-control" /></div>
+          <div class="form-group"><label for="weight_pocket_detectability">Pocket Detectability</label><input id="weight_pocket_detectability" type="number" min="0" step="0.01" class="form-control" /></div>
+          <div class="form-group"><label for="weight_novelty_score">Novelty</label><input id="weight_novelty_score" type="number" min="0" step="0.01" class="form-control" /></div>
+          <div class="form-group"><label for="weight_pathway_independence">Pathway Independence</label><input id="weight_pathway_independence" type="number" min="0" step="0.01" class="form-control" /></div>
+          <div class="form-group"><label for="weight_literature_novelty">Literature Novelty</label><input id="weight_literature_novelty" type="number" min="0" step="0.01" class="form-control" /></div>
+        </div>
+        <div style="margin-top:0.8rem;"><button class="btn btn-primary" onclick="saveWeights()">Apply Weights</button> <span id="weights_status" class="help-text"></span></div>
+      </section>
+
       <section id="tab-benchmark" class="tab-panel card p-4">
         <h3 class="settings-section-title">Benchmark Profiles</h3>
         <div class="form-grid">
@@ -1215,6 +1366,8 @@ pub struct SettingsView {
     #[serde(default = "default_true")]
     ingestion_async_post_ingest_scoring: bool,
     unpaywall_email: String,
+    #[serde(default = "default_false")]
+    scihub_enabled: bool,
     #[serde(default = "default_scihub_domains")]
     scihub_domains: String,
     #[serde(default = "default_scihub_request_timeout_secs")]
@@ -1223,8 +1376,6 @@ pub struct SettingsView {
     scihub_domain_parallelism: u64,
     #[serde(default = "default_scihub_domain_cooldown_secs")]
     scihub_domain_cooldown_secs: u64,
-    #[serde(default = "default_scihub_defer_ms")]
-    scihub_defer_ms: u64,
     #[serde(default = "default_true")]
     scihub_adaptive_enabled: bool,
     #[serde(default = "default_scihub_adaptive_fail_streak")]
@@ -1336,6 +1487,8 @@ pub struct SettingsView {
     #[serde(default)]
     cosmic_mutation_data_path: String,
     #[serde(default)]
+    cosmic_census_path: String,
+    #[serde(default)]
     federation_default_remote_base_url: String,
     #[serde(default)]
     federation_node_public_base_url: String,
@@ -1460,6 +1613,8 @@ pub struct SettingsSaveRequest {
     #[serde(default = "default_true")]
     ingestion_async_post_ingest_scoring: bool,
     unpaywall_email: String,
+    #[serde(default = "default_false")]
+    scihub_enabled: bool,
     #[serde(default = "default_scihub_domains")]
     scihub_domains: String,
     #[serde(default = "default_scihub_request_timeout_secs")]
@@ -1468,8 +1623,6 @@ pub struct SettingsSaveRequest {
     scihub_domain_parallelism: u64,
     #[serde(default = "default_scihub_domain_cooldown_secs")]
     scihub_domain_cooldown_secs: u64,
-    #[serde(default = "default_scihub_defer_ms")]
-    scihub_defer_ms: u64,
     #[serde(default = "default_true")]
     scihub_adaptive_enabled: bool,
     #[serde(default = "default_scihub_adaptive_fail_streak")]
@@ -1581,6 +1734,8 @@ pub struct SettingsSaveRequest {
     #[serde(default)]
     cosmic_mutation_data_path: String,
     #[serde(default)]
+    cosmic_census_path: String,
+    #[serde(default)]
     federation_default_remote_base_url: String,
     #[serde(default)]
     federation_node_public_base_url: String,
@@ -1672,9 +1827,6 @@ fn default_scihub_domain_parallelism() -> u64 {
 fn default_scihub_domain_cooldown_secs() -> u64 {
     300
 }
-fn default_scihub_defer_ms() -> u64 {
-    700
-}
 fn default_scihub_adaptive_fail_streak() -> u64 {
     8
 }
@@ -1827,6 +1979,10 @@ fn default_scihub_domains() -> String {
 pub struct SaveResponse {
     ok: bool,
     message: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    reloaded: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    restart_required: Vec<String>,
 }
 
 pub async fn settings_page(State(_state): State<SharedState>) -> Html<String> {
@@ -1844,13 +2000,24 @@ pub async fn settings_get(
 }
 
 pub async fn settings_save(
-    State(_state): State<SharedState>,
+    State(state): State<SharedState>,
     Json(payload): Json<SettingsSaveRequest>,
 ) -> Result<Json<SaveResponse>, (StatusCode, Json<SaveResponse>)> {
-    save_settings(payload).map_err(internal_err)?;
+    let (old, new) = save_settings(payload).map_err(internal_err)?;
+    let applied = state.apply_settings(&old, &new).await;
+    let message = if applied.restart_required.is_empty() {
+        "Settings saved".to_string()
+    } else {
+        format!(
+            "Settings saved; restart to apply: {}",
+            applied.restart_required.join(", ")
+        )
+    };
     Ok(Json(SaveResponse {
         ok: true,
-        message: "Settings saved".to_string(),
+        message,
+        reloaded: applied.reloaded,
+        restart_required: applied.restart_required,
     }))
 }
 
@@ -1860,6 +2027,8 @@ fn internal_err(e: anyhow::Error) -> (StatusCode, Json<SaveResponse>) {
         Json(SaveResponse {
             ok: false,
             message: format!("settings error: {e}"),
+            reloaded: Vec::new(),
+            restart_required: Vec::new(),
         }),
     )
 }
@@ -1870,21 +2039,65 @@ fn config_path() -> PathBuf {
         .unwrap_or_else(|_| PathBuf::from("ferrumyx.toml"))
 }
 
+/// Keys and tokens sealed with [`ferrumyx_common::secrets`] when saved.
+const SECRET_PATHS: &[&[&str]] = &[
+    &["llm", "openai", "api_key"],
+    &["llm", "anthropic", "api_key"],
+    &["llm", "gemini", "api_key"],
+    &["llm", "openai_compatible", "api_key"],
+    &["ingestion", "pubmed", "api_key"],
+    &["ingestion", "pubmed", "api_key_secret"],
+    &["ingestion", "semanticscholar", "api_key"],
+    &["ingestion", "semanticscholar", "api_key_secret"],
+    &["ranker", "providers", "cbioportal", "api_token"],
+    &["ranker", "providers", "cbioportal", "api_token_secret"],
+    &["ranker", "providers", "cosmic", "api_key"],
+    &["ranker", "providers", "cosmic", "api_key_secret"],
+    &["federation", "sync", "remote_api_token"],
+    &["federation", "security", "read_token"],
+    &["federation", "security", "write_token"],
+    &["federation", "huggingface", "token"],
+    &["embedding", "api_key"],
+];
+
+/// The saved settings with every sealed secret opened.
 fn load_toml() -> anyhow::Result<toml::Value> {
     let path = config_path();
     if !path.exists() {
         return Ok(toml::Value::Table(toml::map::Map::new()));
     }
     let content = fs::read_to_string(&path)?;
-    Ok(toml::from_str::<toml::Value>(&content)?)
+    let mut root = toml::from_str::<toml::Value>(&content)?;
+    let failed = ferrumyx_common::secrets::open_sealed_values(&mut root);
+    if !failed.is_empty() {
+        tracing::warn!(
+            "Saved secrets do not open with this machine's key and read as unset: {}",
+            failed.join(", ")
+        );
+    }
+    Ok(root)
 }
 
+/// Write `v`, sealing the secrets at [`SECRET_PATHS`].
 fn save_toml(v: &toml::Value) -> anyhow::Result<()> {
+    let mut sealed = v.clone();
+    for path in SECRET_PATHS {
+        let Some(toml::Value::String(secret)) = value_at_mut(&mut sealed, path) else {
+            continue;
+        };
+        if !secret.trim().is_empty() && !ferrumyx_common::secrets::is_sealed(secret) {
+            *secret = ferrumyx_common::secrets::seal(secret)?;
+        }
+    }
     let path = config_path();
-    fs::write(&path, toml::to_string_pretty(v)?)?;
+    fs::write(&path, toml::to_string_pretty(&sealed)?)?;
     Ok(())
 }
 
+fn value_at_mut<'a>(root: &'a mut toml::Value, path: &[&str]) -> Option<&'a mut toml::Value> {
+    path.iter().try_fold(root, |v, key| v.get_mut(*key))
+}
+
 fn table_mut<'a>(
     root: &'a mut toml::Value,
     key: &str,
@@ -2218,6 +2431,7 @@ fn load_settings_view() -> anyhow::Result<SettingsView> {
                 toml_value
             }
         },
+        scihub_enabled: bool_at(&root, &["ingestion", "scihub", "enabled"], false),
         scihub_domains: {
             let toml_value = str_at(&root, &["ingestion", "scihub", "domains"], "");
             if !toml_value.trim().is_empty() {
@@ -2265,12 +2479,6 @@ fn load_settings_view() -> anyhow::Result<SettingsView> {
                     .clamp(15, 3600)
             }
         },
-        scihub_defer_ms: int_at(
-            &root,
-            &["ingestion", "scihub", "defer_ms"],
-            default_scihub_defer_ms(),
-        )
-        .clamp(0, 10_000),
         scihub_adaptive_enabled: bool_at(&root, &["ingestion", "scihub", "adaptive_enabled"], true),
         scihub_adaptive_fail_streak: int_at(
             &root,
@@ -2792,6 +3000,14 @@ fn load_settings_view() -> anyhow::Result<SettingsView> {
                 std::env::var("FERRUMYX_COSMIC_MUTATION_DATA_PATH").unwrap_or_default()
             }
         },
+        cosmic_census_path: {
+            let toml_value = str_at(&root, &["ranker", "providers", "cosmic", "census_path"], "");
+            if !toml_value.trim().is_empty() {
+                toml_value
+            } else {
+                std::env::var("FERRUMYX_COSMIC_CENSUS_PATH").unwrap_or_default()
+            }
+        },
         federation_default_remote_base_url: {
             let toml_value = str_at(&root, &["federation", "sync", "default_remote_base_url"], "");
             if !toml_value.trim().is_empty() {
@@ -2977,8 +3193,11 @@ fn load_settings_view() -> anyhow::Result<SettingsView> {
     })
 }
 
-fn save_settings(payload: SettingsSaveRequest) -> anyhow::Result<()> {
-    let mut root = load_toml()?;
+/// Save `payload` over the stored settings, returning the settings before
+/// and after.
+fn save_settings(payload: SettingsSaveRequest) -> anyhow::Result<(toml::Value, toml::Value)> {
+    let old = load_toml()?;
+    let mut root = old.clone();
 
     let llm = table_mut(&mut root, "llm");
     set_str(llm, "mode", payload.llm_mode);
@@ -3170,6 +3389,10 @@ fn save_settings(payload: SettingsSaveRequest) -> anyhow::Result<()> {
         payload.unpaywall_email.trim().to_string(),
     );
     let scihub = nested_table_mut(ingestion, "scihub");
+    scihub.insert(
+        "enabled".to_string(),
+        toml::Value::Boolean(payload.scihub_enabled),
+    );
     let domains = payload
         .scihub_domains
         .split(',')
@@ -3198,10 +3421,6 @@ fn save_settings(payload: SettingsSaveRequest) -> anyhow::Result<()> {
         "domain_cooldown_secs".to_string(),
         toml::Value::Integer(payload.scihub_domain_cooldown_secs.clamp(15, 3600) as i64),
     );
-    scihub.insert(
-        "defer_ms".to_string(),
-        toml::Value::Integer(payload.scihub_defer_ms.clamp(0, 10_000) as i64),
-    );
     scihub.insert(
         "adaptive_enabled".to_string(),
         toml::Value::Boolean(payload.scihub_adaptive_enabled),
@@ -3428,6 +3647,11 @@ fn save_settings(payload: SettingsSaveRequest) -> anyhow::Result<()> {
         "mutation_data_path",
         payload.cosmic_mutation_data_path.trim().to_string(),
     );
+    set_str(
+        cosmic,
+        "census_path",
+        payload.cosmic_census_path.trim().to_string(),
+    );
     maybe_set_secret(cosmic, "api_key", &payload.cosmic_api_key);
     maybe_set_secret(cosmic, "api_key_secret", &payload.cosmic_api_key);
 
@@ -3638,7 +3862,7 @@ fn save_settings(payload: SettingsSaveRequest) -> anyhow::Result<()> {
 
     save_toml(&root)?;
     apply_runtime_env_from_saved_toml(&root);
-    Ok(())
+    Ok((old, root))
 }
 
 fn apply_runtime_env_from_saved_toml(root: &toml::Value) {
@@ -3695,6 +3919,15 @@ fn apply_runtime_env_from_saved_toml(root: &toml::Value) {
         if compat_cached_chat { "1" } else { "0" },
     );
 
+    let reranker = str_at(root, &["search", "reranker"], "");
+    if !reranker.is_empty() {
+        std::env::set_var("FERRUMYX_SEARCH_RERANKER", reranker);
+    }
+    let rerank_candidates = int_at(root, &["search", "rerank_candidates"], 50).clamp(1, 200);
+    std::env::set_var("FERRUMYX_RERANK_CANDIDATES", rerank_candidates.to_string());
+    let rerank_timeout_ms = int_at(root, &["search", "rerank_timeout_ms"], 2000).max(1);
+    std::env::set_var("FERRUMYX_RERANK_TIMEOUT_MS", rerank_timeout_ms.to_string());
+
     let fed_default_remote = str_at(root, &["federation", "sync", "default_remote_base_url"], "");
     std::env::set_var("FERRUMYX_FED_DEFAULT_REMOTE_BASE_URL", fed_default_remote);
     let fed_node_public = str_at(root, &["federation", "sync", "node_public_base_url"], "");
@@ -4742,6 +4975,10 @@ fn apply_runtime_env_from_saved_toml(root: &toml::Value) {
             cosmic_mutation_data_path,
         );
     }
+    let cosmic_census_path = str_at(root, &["ranker", "providers", "cosmic", "census_path"], "");
+    if !cosmic_census_path.trim().is_empty() {
+        std::env::set_var("FERRUMYX_COSMIC_CENSUS_PATH", cosmic_census_path);
+    }
     let cosmic_api_key = {
         let k = str_at(root, &["ranker", "providers", "cosmic", "api_key"], "");
         if !k.is_empty() {
@@ -4794,14 +5031,10 @@ fn apply_runtime_env_from_saved_toml(root: &toml::Value) {
         "FERRUMYX_SCIHUB_DOMAIN_COOLDOWN_SECS",
         scihub_domain_cooldown_secs.clamp(15, 3600).to_string(),
     );
-    let scihub_defer_ms = int_at(
-        root,
-        &["ingestion", "scihub", "defer_ms"],
-        default_scihub_defer_ms(),
-    );
+    let scihub_enabled = bool_at(root, &["ingestion", "scihub", "enabled"], false);
     std::env::set_var(
-        "FERRUMYX_SCIHUB_DEFER_MS",
-        scihub_defer_ms.clamp(0, 10_000).to_string(),
+        "FERRUMYX_SCIHUB_ENABLED",
+        if scihub_enabled { "1" } else { "0" },
     );
     let scihub_adaptive_enabled = bool_at(root, &["ingestion", "scihub", "adaptive_enabled"], true);
     std::env::set_var(
@@ -4,7 +4,7 @@ use axum::{extract::State, response::Html};
 
 use crate::handlers::dashboard::NAV_HTML;
 use crate::state::SharedState;
-use ferrumyx_db::{papers::PaperRepository, target_scores::TargetScoreRepository};
+use ferrumyx_db::{papers::PaperRepository, target_scores::TargetScoreRepository, TABLE_CHUNKS};
 
 pub async fn system_page(State(state): State<SharedState>) -> Html<String> {
     let paper_repo = PaperRepository::new(state.db.clone());
@@ -52,6 +52,51 @@ pub async fn system_page(State(state): State<SharedState>) -> Html<String> {
             .collect()
     };
 
+    let vector_index = stats
+        .indices
+        .iter()
+        .find(|i| i.table == TABLE_CHUNKS && i.columns.iter().any(|c| c == "embedding"))
+        .map(|i| {
+            format!(
+                "{}, {} indexed / {} pending",
+                i.index_type, i.indexed_rows, i.unindexed_rows
+            )
+        })
+        .unwrap_or_else(|| "none".to_string());
+
+    let tool_stats = state
+        .tools
+        .as_ref()
+        .map(|tools| tools.stats())
+        .unwrap_or_default();
+    let tool_rows = if tool_stats.is_empty() {
+        r#"<tr><td colspan="7" class="text-center text-muted py-4">No tool invocations recorded yet.</td></tr>"#.to_string()
+    } else {
+        tool_stats
+            .iter()
+            .map(|t| {
+                format!(
+                    r#"<tr>
+                <td><code>{}</code></td>
+                <td class="text-end">{}</td>
+                <td class="text-end">{}</td>
+                <td class="text-end">{}</td>
+                <td class="text-end">{}</td>
+                <td class="text-end">{}</td>
+                <td class="text-end">{}</td>
+            </tr>"#,
+                    html_escape(&t.tool),
+                    t.invocations,
+                    t.successes,
+                    t.errors,
+                    t.timeouts,
+                    t.mean_latency_ms().unwrap_or(0),
+                    t.latency_quantile_ms(0.95).unwrap_or(0),
+                )
+            })
+            .collect()
+    };
+
     Html(format!(
         r#"<!DOCTYPE html>
 <html lang="en">
@@ -113,12 +158,34 @@ pub async fn system_page(State(state): State<SharedState>) -> Html<String> {
                         <tr><td>Entities</td><td class="text-end">{}</td></tr>
                         <tr><td>Entity Mentions</td><td class="text-end">{}</td></tr>
                         <tr><td>Ingestion Audit Rows</td><td class="text-end">{}</td></tr>
+                        <tr><td>Fragments (small)</td><td class="text-end">{} ({})</td></tr>
+                        <tr><td>Vector Index</td><td class="text-end">{}</td></tr>
                     </tbody>
                 </table>
             </div>
         </div>
     </div>
 
+    <div class="card mb-4">
+        <div class="card-header">Tool Invocations</div>
+        <div class="table-container p-0">
+            <table class="table mb-0">
+                <thead>
+                    <tr>
+                        <th>Tool</th>
+                        <th class="text-end">Calls</th>
+                        <th class="text-end">OK</th>
+                        <th class="text-end">Errors</th>
+                        <th class="text-end">Timeouts</th>
+                        <th class="text-end">Mean ms</th>
+                        <th class="text-end">p95 ms</th>
+                    </tr>
+                </thead>
+                <tbody>{}</tbody>
+            </table>
+        </div>
+    </div>
+
     <div class="card">
         <div class="card-header">Recent Ingested Papers</div>
         <div class="table-container p-0">
@@ -150,6 +217,10 @@ pub async fn system_page(State(state): State<SharedState>) -> Html<String> {
         stats.entities,
         stats.entity_mentions,
         stats.ingestion_audit,
+        stats.fragments,
+        stats.small_fragments,
+        html_escape(&vector_index),
+        tool_rows,
         paper_rows
     ))
 }
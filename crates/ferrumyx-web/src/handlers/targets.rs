@@ -2,7 +2,8 @@
 
 use axum::{
     extract::{Path, Query, State},
-    response::{Html, IntoResponse},
+    http::header,
+    response::{Html, IntoResponse, Response},
     Json,
 };
 use serde::{Deserialize, Serialize};
@@ -18,7 +19,8 @@ use ferrumyx_db::{
     phase4_signals::Phase4SignalRepository, target_scores::TargetScoreRepository,
 };
 use ferrumyx_ranker::{
-    depmap_provider::{DepMapClientAdapter, DepMapProvider},
+    depmap_provider::DepMapProvider,
+    dossier::{build_target_dossier, render_html, render_markdown},
     normalise::normalise_ceres,
     ProviderRefreshRequest, TargetQueryEngine,
 };
@@ -31,6 +33,22 @@ pub struct TargetFilter {
     pub page: Option<i64>,
 }
 
+#[derive(Deserialize)]
+pub struct DossierFilter {
+    pub cancer_type: Option<String>,
+    #[serde(default)]
+    pub format: DossierFormat,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DossierFormat {
+    #[default]
+    Json,
+    Markdown,
+    Html,
+}
+
 #[derive(Debug, Serialize)]
 pub struct ApiTarget {
     pub gene: String,
@@ -166,6 +184,46 @@ pub async fn api_target_detail(
     Ok(Json(detail))
 }
 
+/// GET /api/targets/{gene}/dossier — every evidence section for a gene in
+/// one cancer type, as JSON, Markdown or HTML.
+pub async fn api_target_dossier(
+    State(state): State<SharedState>,
+    Path(gene): Path<String>,
+    Query(filter): Query<DossierFilter>,
+) -> Result<Response, ApiError> {
+    let cancer_type = filter
+        .cancer_type
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .ok_or_else(|| ApiError::BadRequest("cancer_type is required".to_string()))?;
+    let depmap = match state.depmap.get().await {
+        Ok(depmap) => Some(depmap.client()),
+        Err(e) => {
+            tracing::warn!("DepMap unavailable for the {gene} dossier: {e:#}");
+            None
+        }
+    };
+    let dossier = build_target_dossier(
+        state.db.clone(),
+        &gene,
+        cancer_type,
+        &state.ranker_weights(),
+        depmap,
+        true,
+    )
+    .await;
+    Ok(match filter.format {
+        DossierFormat::Json => Json(dossier).into_response(),
+        DossierFormat::Markdown => (
+            [(header::CONTENT_TYPE, "text/markdown; charset=utf-8")],
+            render_markdown(&dossier),
+        )
+            .into_response(),
+        DossierFormat::Html => Html(render_html(&dossier)).into_response(),
+    })
+}
+
 pub async fn targets_page(
     State(state): State<SharedState>,
     Query(filter): Query<TargetFilter>,
@@ -1185,7 +1243,7 @@ async fn load_target_rows(
         .ok()
         .is_some_and(|v| v == "1" || v.eq_ignore_ascii_case("true"))
     {
-        DepMapClientAdapter::init().await.ok()
+        state.depmap.get().await.ok()
     } else {
         None
     };
@@ -1257,7 +1315,9 @@ async fn load_target_rows(
         let mut crispr_score = extract_json_number_field(&s.components_normed, "crispr_score");
         if crispr_score.is_none() {
             if let Some(depmap) = &depmap {
-                if let Some(ceres) = depmap.get_mean_ceres(&gene, &cancer_type) {
+                if let Some(ceres) = ferrumyx_common::CancerType::parse(&cancer_type)
+                    .and_then(|cancer| depmap.get_mean_ceres(&gene, cancer))
+                {
                     crispr_score = Some(normalise_ceres(ceres));
                 }
             }
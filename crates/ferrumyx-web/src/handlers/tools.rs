@@ -0,0 +1,72 @@
+//! Agent tool endpoints: dry-run parameter checks against the tool schemas
+//! and per-tool invocation metrics.
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::state::SharedState;
+use ferrumyx_common::error::ApiError;
+use ferrumyx_common::tool_stats::ToolStats;
+
+/// The agent's tool registry as the web API sees it; implemented by the
+/// agent.
+pub trait AgentTools: Send + Sync {
+    /// Checks `params` against a registered tool's schema without running
+    /// it: `params` with the schema's defaults filled in, or every
+    /// violation. `None` if no tool is registered as `tool`.
+    fn validate_params(&self, tool: &str, params: Value) -> Option<Result<Value, Vec<String>>>;
+
+    /// Invocation counters of every tool called since start-up.
+    fn stats(&self) -> Vec<ToolStats>;
+}
+
+#[derive(Debug, Serialize)]
+pub struct ToolParamsCheck {
+    pub valid: bool,
+    /// The parameters `invoke` would run the tool with, defaults included.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+    pub violations: Vec<String>,
+}
+
+/// POST /api/tools/{name}/validate — check a parameter object against the
+/// tool's schema as `invoke` would, without running the tool.
+pub async fn api_tool_validate(
+    State(state): State<SharedState>,
+    Path(name): Path<String>,
+    Json(params): Json<Value>,
+) -> Result<Json<ToolParamsCheck>, ApiError> {
+    let checked = state
+        .tools
+        .as_ref()
+        .and_then(|tools| tools.validate_params(&name, params))
+        .ok_or_else(|| ApiError::NotFound(format!("Tool {name} not found")))?;
+    Ok(Json(match checked {
+        Ok(params) => ToolParamsCheck {
+            valid: true,
+            params: Some(params),
+            violations: Vec::new(),
+        },
+        Err(violations) => ToolParamsCheck {
+            valid: false,
+            params: None,
+            violations,
+        },
+    }))
+}
+
+/// GET /api/tools/metrics — invocation counts, failures, timeouts and
+/// latencies per tool since the agent started.
+pub async fn api_tool_metrics(State(state): State<SharedState>) -> Json<Vec<ToolStats>> {
+    Json(
+        state
+            .tools
+            .as_ref()
+            .map(|tools| tools.stats())
+            .unwrap_or_default(),
+    )
+}
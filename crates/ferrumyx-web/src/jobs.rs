@@ -0,0 +1,338 @@
+//! Ingestion jobs started from the web UI and API.
+//!
+//! [`JobManager`] keeps a record of every job it starts, along with the
+//! token that cancels it. Running jobs live in memory; finished ones are
+//! written to the `ingestion_jobs` table so the history survives restarts.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use chrono::Utc;
+use ferrumyx_db::{Database, IngestionJobRecord, IngestionJobRecordRepository};
+use ferrumyx_ingestion::pipeline::{
+    run_ingestion_with_cancel, IngestionJob, IngestionProgress, IngestionResult,
+};
+use ferrumyx_ingestion::repository::IngestionRepository;
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use crate::sse::EventBus;
+use crate::state::AppEvent;
+
+/// Finished jobs returned by [`JobManager::list`].
+const JOB_HISTORY_LIMIT: usize = 100;
+
+/// Outcome of [`JobManager::cancel`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancelOutcome {
+    /// The job was running and has been asked to stop.
+    Cancelled,
+    /// The job exists but has already finished.
+    NotRunning,
+    /// No job has the given id.
+    NotFound,
+}
+
+struct TrackedJob {
+    record: IngestionJobRecord,
+    cancel: CancellationToken,
+}
+
+/// Registry of ingestion jobs started through the web server.
+pub struct JobManager {
+    db: Arc<Database>,
+    history: IngestionJobRecordRepository,
+    jobs: RwLock<HashMap<Uuid, TrackedJob>>,
+}
+
+impl JobManager {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self {
+            history: IngestionJobRecordRepository::new(db.clone()),
+            db,
+            jobs: RwLock::default(),
+        }
+    }
+
+    /// Start `job` in the background and return its record. Progress goes
+    /// to `event_tx` as [`AppEvent::PipelineStatus`] tagged with the job id,
+    /// and the start and outcome as [`AppEvent::JobStateChanged`].
+    pub fn start(self: &Arc<Self>, job: IngestionJob, event_tx: EventBus) -> IngestionJobRecord {
+        let (record, cancel) = self.track(&job);
+        let id = record.id;
+        event_tx.send(job_state_event(&record));
+
+        let manager = self.clone();
+        tokio::spawn(async move {
+            let (progress_tx, progress_rx) = broadcast::channel(64);
+            let forwarder = tokio::spawn(manager.clone().forward_progress(
+                id,
+                progress_rx,
+                event_tx.clone(),
+            ));
+
+            let repo = Arc::new(IngestionRepository::new(manager.db.clone()));
+            let result = run_ingestion_with_cancel(job, repo, Some(progress_tx), cancel).await;
+            let _ = forwarder.await;
+
+            let record = manager.finish(id, &result);
+            event_tx.send(job_state_event(&record));
+            let _ = event_tx.send(AppEvent::PipelineStatus {
+                stage: record.stage.clone(),
+                message: summary_message(&result),
+                count: result.papers_inserted as u64,
+                job_id: Some(id.to_string()),
+            });
+            for i in 0..result.papers_inserted.min(10) {
+                let _ = event_tx.send(AppEvent::PaperIngested {
+                    paper_id: format!("{}-{}", result.job_id, i),
+                    title: format!("Paper #{} ingested from {}", i + 1, result.query),
+                    source: "ingestion".to_string(),
+                });
+            }
+
+            match manager.history.upsert(&record).await {
+                Ok(()) => {
+                    manager.jobs_mut().remove(&id);
+                }
+                Err(e) => tracing::warn!(job_id = %id, "Failed to persist ingestion job: {e}"),
+            }
+        });
+
+        record
+    }
+
+    /// Running jobs, then finished ones, newest first.
+    pub async fn list(&self) -> anyhow::Result<Vec<IngestionJobRecord>> {
+        let mut jobs: Vec<IngestionJobRecord> = {
+            let tracked = self.jobs.read().unwrap_or_else(|e| e.into_inner());
+            tracked.values().map(|job| job.record.clone()).collect()
+        };
+        for record in self.history.list(JOB_HISTORY_LIMIT).await? {
+            if !jobs.iter().any(|job| job.id == record.id) {
+                jobs.push(record);
+            }
+        }
+        jobs.sort_by(|a, b| {
+            (b.status == "running")
+                .cmp(&(a.status == "running"))
+                .then(b.started_at.cmp(&a.started_at))
+        });
+        Ok(jobs)
+    }
+
+    pub async fn get(&self, id: Uuid) -> anyhow::Result<Option<IngestionJobRecord>> {
+        let tracked = self
+            .jobs
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&id)
+            .map(|job| job.record.clone());
+        match tracked {
+            Some(record) => Ok(Some(record)),
+            None => Ok(self.history.find_by_id(id).await?),
+        }
+    }
+
+    /// Ask job `id` to stop. It finishes the papers already in flight and
+    /// is then recorded as "cancelled".
+    pub async fn cancel(&self, id: Uuid) -> anyhow::Result<CancelOutcome> {
+        if let Some(job) = self.jobs.read().unwrap_or_else(|e| e.into_inner()).get(&id) {
+            if job.record.status != "running" {
+                return Ok(CancelOutcome::NotRunning);
+            }
+            job.cancel.cancel();
+            return Ok(CancelOutcome::Cancelled);
+        }
+        Ok(match self.history.find_by_id(id).await? {
+            Some(_) => CancelOutcome::NotRunning,
+            None => CancelOutcome::NotFound,
+        })
+    }
+
+    fn jobs_mut(&self) -> std::sync::RwLockWriteGuard<'_, HashMap<Uuid, TrackedJob>> {
+        self.jobs.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Register a running record for `job`.
+    fn track(&self, job: &IngestionJob) -> (IngestionJobRecord, CancellationToken) {
+        let sources: Vec<String> = job
+            .sources
+            .iter()
+            .filter_map(|source| serde_json::to_value(source).ok())
+            .filter_map(|value| value.as_str().map(str::to_string))
+            .collect();
+        let record = IngestionJobRecord {
+            id: Uuid::new_v4(),
+            gene: job.gene.clone(),
+            mutation: job.mutation.clone(),
+            cancer_type: job.cancer_type.clone(),
+            sources: sources.join(","),
+            max_results: job.max_results as i64,
+            status: "running".to_string(),
+            stage: "search".to_string(),
+            papers_found: 0,
+            papers_inserted: 0,
+            papers_duplicate: 0,
+            chunks_inserted: 0,
+            started_at: Utc::now(),
+            finished_at: None,
+            error: None,
+        };
+        let cancel = CancellationToken::new();
+        self.jobs_mut().insert(
+            record.id,
+            TrackedJob {
+                record: record.clone(),
+                cancel: cancel.clone(),
+            },
+        );
+        (record, cancel)
+    }
+
+    /// Copy pipeline progress into the job's record and out to SSE clients.
+    /// The terminal stages are left to [`Self::finish`], which knows the
+    /// final counts.
+    async fn forward_progress(
+        self: Arc<Self>,
+        id: Uuid,
+        mut progress_rx: broadcast::Receiver<IngestionProgress>,
+        event_tx: EventBus,
+    ) {
+        loop {
+            let progress = match progress_rx.recv().await {
+                Ok(progress) => progress,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            if matches!(progress.stage.as_str(), "complete" | "cancelled") {
+                continue;
+            }
+            if let Some(job) = self.jobs_mut().get_mut(&id) {
+                job.record.stage = progress.stage.clone();
+                job.record.papers_found = progress.papers_found as i64;
+                job.record.papers_inserted = progress.papers_inserted as i64;
+                job.record.chunks_inserted = progress.chunks_inserted as i64;
+            }
+            let _ = event_tx.send(AppEvent::PipelineStatus {
+                stage: progress.stage,
+                message: progress.message,
+                count: progress.papers_inserted as u64,
+                job_id: Some(id.to_string()),
+            });
+        }
+    }
+
+    /// Record the outcome of job `id` and return its final record.
+    fn finish(&self, id: Uuid, result: &IngestionResult) -> IngestionJobRecord {
+        let mut jobs = self.jobs_mut();
+        let job = jobs
+            .get_mut(&id)
+            .expect("jobs stay tracked until their record is persisted");
+        let record = &mut job.record;
+        record.status = result.status().to_string();
+        record.stage = match record.status.as_str() {
+            "cancelled" => "cancelled",
+            "failed" => "failed",
+            _ => "complete",
+        }
+        .to_string();
+        record.papers_found = result.papers_found as i64;
+        record.papers_inserted = result.papers_inserted as i64;
+        record.papers_duplicate = result.papers_duplicate as i64;
+        record.chunks_inserted = result.chunks_inserted as i64;
+        record.finished_at = Some(Utc::now());
+        record.error = result.error_summary();
+        record.clone()
+    }
+}
+
+fn job_state_event(record: &IngestionJobRecord) -> AppEvent {
+    AppEvent::JobStateChanged {
+        job_id: record.id.to_string(),
+        status: record.status.clone(),
+        stage: record.stage.clone(),
+        error: record.error.clone(),
+    }
+}
+
+fn summary_message(result: &IngestionResult) -> String {
+    let verb = if result.cancelled {
+        "cancelled"
+    } else {
+        "complete"
+    };
+    format!(
+        "Ingestion {} — {} papers found, {} new, {} already known, {} chunks, {} failed",
+        verb,
+        result.papers_found,
+        result.papers_inserted,
+        result.papers_duplicate,
+        result.chunks_inserted,
+        result.papers_failed
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ferrumyx_ingestion::pipeline::IngestionSourceSpec;
+
+    #[tokio::test]
+    async fn cancel_reports_whether_the_job_was_running() {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-web-jobs-{}", Uuid::new_v4()));
+        let db = Arc::new(Database::open(&dir).await.unwrap());
+        db.initialize().await.unwrap();
+        let manager = JobManager::new(db);
+
+        let job = IngestionJob {
+            sources: vec![IngestionSourceSpec::PubMed, IngestionSourceSpec::EuropePmc],
+            ..IngestionJob::default()
+        };
+        let (record, cancel) = manager.track(&job);
+        assert_eq!(record.sources, "pubmed,europepmc");
+        assert_eq!(
+            manager.cancel(record.id).await.unwrap(),
+            CancelOutcome::Cancelled
+        );
+        assert!(cancel.is_cancelled());
+
+        let result = IngestionResult {
+            job_id: Uuid::new_v4(),
+            query: "KRAS".to_string(),
+            papers_found_raw: 5,
+            papers_found: 5,
+            papers_inserted: 2,
+            papers_duplicate: 3,
+            inserted_paper_ids: Vec::new(),
+            chunks_inserted: 9,
+            chunks_embedded: 0,
+            source_telemetry: Vec::new(),
+            perf_telemetry: Default::default(),
+            errors: Vec::new(),
+            papers_succeeded: 2,
+            papers_failed: 0,
+            failure_samples: Vec::new(),
+            duration_ms: 1_000,
+            cancelled: true,
+        };
+        let finished = manager.finish(record.id, &result);
+        assert_eq!(finished.status, "cancelled");
+        assert!(finished.finished_at.is_some());
+        manager.history.upsert(&finished).await.unwrap();
+        manager.jobs_mut().remove(&record.id);
+
+        assert_eq!(manager.get(record.id).await.unwrap(), Some(finished));
+        assert_eq!(
+            manager.cancel(record.id).await.unwrap(),
+            CancelOutcome::NotRunning
+        );
+        assert_eq!(
+            manager.cancel(Uuid::new_v4()).await.unwrap(),
+            CancelOutcome::NotFound
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
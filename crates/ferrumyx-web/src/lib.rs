@@ -8,6 +8,15 @@
 //!   - System status & audit log
 
 pub mod handlers;
+pub mod jobs;
+pub mod llm;
+pub mod llm_audit;
+pub mod llm_cache;
+pub mod llm_json;
+pub mod llm_rate_limit;
+pub mod llm_stream;
+pub mod llm_usage;
 pub mod router;
+pub mod settings_reload;
 pub mod sse;
 pub mod state;
@@ -0,0 +1,1468 @@
+//! LLM backends for the answer endpoint and the data-classification gate
+//! that picks one.
+//!
+//! The routing policy follows the architecture notes:
+//! - `PUBLIC` goes to the default backend (itself ordered by `[llm] mode`).
+//! - `INTERNAL` stays on a local backend unless classification enforcement
+//!   is switched off.
+//! - `CONFIDENTIAL` never leaves a local backend.
+//!
+//! Within what the policy allows, calls go down the failover order: a
+//! backend that keeps failing cools down, and a background probe puts it
+//! back once it answers again. Remote backends over their daily
+//! `[llm.limits]` budget are skipped the same way (see [`crate::llm_usage`]),
+//! and calls to a backend are spaced to its `[llm.rate_limits]` rate (see
+//! [`crate::llm_rate_limit`]). Repeated calls can be answered from the
+//! response cache (see [`crate::llm_cache`]). Remote backends stream tokens
+//! through [`crate::llm_stream`]. Extraction-style callers that need typed
+//! JSON back use [`LlmRouter::complete_json`] (see [`crate::llm_json`]).
+//! With `[security] audit_llm_calls` on, every call is written to the audit
+//! log (see [`crate::llm_audit`]).
+
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+use crate::handlers::answer::estimate_tokens;
+use crate::llm_audit::{prompt_fingerprint, AuditSink};
+use crate::llm_cache::{CacheStats, ResponseCache};
+use crate::llm_rate_limit::{RateLimitStatus, RateLimiter};
+use crate::llm_usage::{BudgetAlert, UsageTracker};
+pub use ferrumyx_common::DataClass;
+use ferrumyx_db::LlmAuditLog;
+
+/// What a backend produced for one prompt.
+#[derive(Debug, Clone, Default)]
+pub struct Generation {
+    pub text: String,
+    pub prompt_tokens: Option<u32>,
+    pub completion_tokens: Option<u32>,
+}
+
+/// A backend the answer endpoint can send a grounded prompt to.
+#[async_trait]
+pub trait AnswerModel: Send + Sync {
+    fn model_name(&self) -> &str;
+    /// "ollama", "openai", "anthropic", "gemini" or "openai_compatible".
+    fn backend(&self) -> &str;
+    /// Whether prompts stay on this machine.
+    fn is_local(&self) -> bool;
+    /// Base URL the backend calls, for status pages.
+    fn endpoint(&self) -> Option<&str> {
+        None
+    }
+    /// Complete `prompt`, sending text to `tokens` as it is generated.
+    /// Backends without streaming send the whole answer once.
+    async fn generate(
+        &self,
+        system: &str,
+        prompt: &str,
+        max_tokens: u32,
+        tokens: &mpsc::UnboundedSender<String>,
+    ) -> anyhow::Result<Generation>;
+
+    /// Complete `prompt` in the backend's JSON output mode, if it has one.
+    /// Backends without one generate plain text and rely on the prompt
+    /// asking for JSON.
+    async fn generate_json(
+        &self,
+        system: &str,
+        prompt: &str,
+        max_tokens: u32,
+    ) -> anyhow::Result<Generation> {
+        let (tokens, _) = mpsc::unbounded_channel();
+        self.generate(system, prompt, max_tokens, &tokens).await
+    }
+
+    /// USD per prompt and per completion token; free unless the backend
+    /// knows its pricing.
+    fn cost_per_token(&self) -> (f64, f64) {
+        (0.0, 0.0)
+    }
+
+    /// Cheap check that the backend answers again; a one-token completion
+    /// unless the backend has something cheaper.
+    async fn probe(&self) -> anyhow::Result<()> {
+        let (tokens, _) = mpsc::unbounded_channel();
+        self.generate("", "ping", 1, &tokens).await.map(|_| ())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RoutingError {
+    #[error("no LLM backend is configured")]
+    NoBackend,
+    #[error("{0} data requires a local LLM backend and none is configured")]
+    NoLocalBackend(&'static str),
+    #[error("no healthy LLM backend for {class} data ({})", .failures.join("; "))]
+    Unavailable {
+        class: &'static str,
+        /// `backend: reason` for every eligible backend that was skipped or
+        /// failed.
+        failures: Vec<String>,
+    },
+    #[error("daily LLM budget exceeded for {class} data ({})", .denials.join("; "))]
+    BudgetExceeded {
+        class: &'static str,
+        /// Why each eligible backend was refused.
+        denials: Vec<String>,
+    },
+    #[error("LLM backends for {class} data are rate limited ({})", .limited.join("; "))]
+    RateLimited {
+        class: &'static str,
+        /// [`crate::llm_rate_limit::RateLimited`] for each eligible backend.
+        limited: Vec<String>,
+    },
+}
+
+/// When a failing backend is taken out of rotation.
+#[derive(Debug, Clone, Copy)]
+pub struct HealthConfig {
+    /// Consecutive failures before a backend cools down.
+    pub failure_threshold: u32,
+    /// How long a backend stays out of rotation before it is probed again.
+    pub cooldown: Duration,
+    /// How often the background probe checks backends in cooldown.
+    pub probe_interval: Duration,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 2,
+            cooldown: Duration::from_secs(120),
+            probe_interval: Duration::from_secs(30),
+        }
+    }
+}
+
+impl HealthConfig {
+    /// `FERRUMYX_LLM_FAILOVER_FAILURE_THRESHOLD` and
+    /// `FERRUMYX_LLM_FAILOVER_COOLDOWN_SECS` as for the agent's chain, and
+    /// `FERRUMYX_LLM_HEALTH_PROBE_SECS`.
+    pub fn from_env() -> Self {
+        let env_u64 = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse::<u64>().ok())
+        };
+        let mut cfg = Self::default();
+        if let Some(n) = env_u64("FERRUMYX_LLM_FAILOVER_FAILURE_THRESHOLD") {
+            cfg.failure_threshold = n.clamp(1, 10) as u32;
+        }
+        if let Some(secs) = env_u64("FERRUMYX_LLM_FAILOVER_COOLDOWN_SECS") {
+            cfg.cooldown = Duration::from_secs(secs.clamp(15, 3600));
+        }
+        if let Some(secs) = env_u64("FERRUMYX_LLM_HEALTH_PROBE_SECS") {
+            cfg.probe_interval = Duration::from_secs(secs.clamp(5, 3600));
+        }
+        cfg
+    }
+}
+
+#[derive(Debug, Default)]
+struct HealthState {
+    consecutive_failures: u32,
+    cooldown_until: Option<Instant>,
+    last_error: Option<String>,
+}
+
+struct Backend {
+    model: Arc<dyn AnswerModel>,
+    health: Mutex<HealthState>,
+}
+
+impl Backend {
+    fn health(&self) -> std::sync::MutexGuard<'_, HealthState> {
+        self.health.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn cooling_down(&self, now: Instant) -> bool {
+        self.health()
+            .cooldown_until
+            .is_some_and(|until| until > now)
+    }
+
+    fn record_success(&self) {
+        *self.health() = HealthState::default();
+    }
+
+    fn record_failure(&self, error: String, cfg: &HealthConfig) {
+        let mut health = self.health();
+        health.consecutive_failures += 1;
+        health.last_error = Some(error);
+        if health.consecutive_failures >= cfg.failure_threshold {
+            health.cooldown_until = Some(Instant::now() + cfg.cooldown);
+        }
+    }
+}
+
+/// Health of one backend, for status pages.
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendStatus {
+    pub backend: String,
+    pub model: String,
+    pub endpoint: Option<String>,
+    pub local: bool,
+    pub consecutive_failures: u32,
+    pub cooling_down: bool,
+    pub last_error: Option<String>,
+}
+
+/// A completion and the backend that actually served it.
+#[derive(Debug, Clone)]
+pub struct RoutedGeneration {
+    pub generation: Generation,
+    pub backend: String,
+    pub model: String,
+    /// `backend: reason` for each backend tried before it.
+    pub failovers: Vec<String>,
+    /// Set when this call took today's spend over the alert threshold.
+    pub budget_alert: Option<BudgetAlert>,
+    /// Served from the response cache; not counted against the budgets.
+    pub cached: bool,
+}
+
+/// What [`LlmRouter::complete_stream`] yields.
+#[derive(Debug)]
+pub enum StreamEvent {
+    /// Text as the serving backend generates it.
+    Token(String),
+    /// Always last: the complete generation with its usage, or why no
+    /// backend could serve the call.
+    Done(Box<Result<RoutedGeneration, RoutingError>>),
+}
+
+/// Per-call switches for [`LlmRouter::complete_with`].
+#[derive(Debug, Clone, Default)]
+pub struct CallOptions {
+    /// Skip the response cache, for calls that should sample afresh.
+    pub bypass_cache: bool,
+    /// Audit tag for what made the call, e.g. "answer".
+    pub caller: Option<String>,
+    /// Audit id tying the call to a request, e.g. an answer id.
+    pub session_id: Option<String>,
+    /// Chunks included in the prompt, for the audit log.
+    pub chunk_ids: Vec<uuid::Uuid>,
+}
+
+/// How a routed call asks the backend to answer.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum OutputMode<'a> {
+    /// Free text, streamed to the sender.
+    Text(&'a mpsc::UnboundedSender<String>),
+    /// [`AnswerModel::generate_json`].
+    Json,
+}
+
+impl OutputMode<'_> {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Text(_) => "text",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// Picks the backend for a call from its data class and fails over to the
+/// next eligible one when it errors.
+#[derive(Clone)]
+pub struct LlmRouter {
+    /// Failover order; the first is the default backend. Shared by every
+    /// clone so [`Self::replace_backends`] reaches them all.
+    backends: Arc<RwLock<Vec<Arc<Backend>>>>,
+    /// `[llm] mode = "local_only"`: nothing goes to a remote backend.
+    local_only: bool,
+    /// Keep `INTERNAL` data on local backends too (`[security]
+    /// enforce_data_classification`).
+    enforce_internal: bool,
+    /// Write an `llm_audit` row per call (`[security] audit_llm_calls`).
+    audit_calls: bool,
+    /// Where those rows go; nothing is written when absent.
+    audit: Option<Arc<dyn AuditSink>>,
+    health_cfg: HealthConfig,
+    /// Daily usage and budgets, shared by every clone.
+    usage: Arc<UsageTracker>,
+    /// Per-backend request rate, shared by every clone.
+    rate_limiter: Arc<RateLimiter>,
+    /// `[llm.cache]`; off when absent.
+    cache: Option<Arc<ResponseCache>>,
+}
+
+impl Default for LlmRouter {
+    fn default() -> Self {
+        Self {
+            backends: Arc::default(),
+            local_only: false,
+            enforce_internal: true,
+            audit_calls: true,
+            audit: None,
+            health_cfg: HealthConfig::default(),
+            usage: Arc::default(),
+            rate_limiter: Arc::default(),
+            cache: None,
+        }
+    }
+}
+
+impl LlmRouter {
+    pub fn new(default_model: Arc<dyn AnswerModel>) -> Self {
+        Self::default().with_backend(default_model)
+    }
+
+    /// Local Ollama from `OLLAMA_BASE_URL` / `OLLAMA_MODEL`, for the
+    /// standalone web server.
+    pub fn from_env() -> Self {
+        Self::new(Arc::new(OllamaAnswerModel::from_env()))
+            .with_health_config(HealthConfig::from_env())
+    }
+
+    /// Append `model` to the failover order.
+    pub fn with_backend(self, model: Arc<dyn AnswerModel>) -> Self {
+        self.backends_mut().push(Arc::new(Backend {
+            model,
+            health: Mutex::default(),
+        }));
+        self
+    }
+
+    /// Swap the failover order for `models`, e.g. after the settings
+    /// changed. Calls already routed finish on the backend they picked;
+    /// the new backends start out healthy.
+    pub fn replace_backends(&self, models: Vec<Arc<dyn AnswerModel>>) {
+        *self.backends_mut() = models
+            .into_iter()
+            .map(|model| {
+                Arc::new(Backend {
+                    model,
+                    health: Mutex::default(),
+                })
+            })
+            .collect();
+    }
+
+    /// The backends in failover order.
+    pub fn backend_models(&self) -> Vec<Arc<dyn AnswerModel>> {
+        self.backends().iter().map(|b| b.model.clone()).collect()
+    }
+
+    fn backends(&self) -> Vec<Arc<Backend>> {
+        self.backends
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    fn backends_mut(&self) -> std::sync::RwLockWriteGuard<'_, Vec<Arc<Backend>>> {
+        self.backends.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn with_local_only(mut self, local_only: bool) -> Self {
+        self.local_only = local_only;
+        self
+    }
+
+    pub fn with_enforce_internal(mut self, enforce: bool) -> Self {
+        self.enforce_internal = enforce;
+        self
+    }
+
+    pub fn with_audit_calls(mut self, audit: bool) -> Self {
+        self.audit_calls = audit;
+        self
+    }
+
+    /// Record every call to `sink` while auditing is on.
+    pub fn with_audit_sink(mut self, sink: Arc<dyn AuditSink>) -> Self {
+        self.audit = Some(sink);
+        self
+    }
+
+    pub fn with_health_config(mut self, cfg: HealthConfig) -> Self {
+        self.health_cfg = cfg;
+        self
+    }
+
+    /// Count usage against these budgets instead of unlimited in-memory
+    /// counters.
+    pub fn with_usage_tracker(mut self, usage: Arc<UsageTracker>) -> Self {
+        self.usage = usage;
+        self
+    }
+
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    pub fn with_response_cache(mut self, cache: Arc<ResponseCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    pub fn audit_calls(&self) -> bool {
+        self.audit_calls
+    }
+
+    pub fn usage(&self) -> &UsageTracker {
+        &self.usage
+    }
+
+    /// Queue depth of every rate-limited backend.
+    pub fn rate_limits(&self) -> Vec<RateLimitStatus> {
+        self.rate_limiter.status()
+    }
+
+    /// Response cache counters, when the cache is on.
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache.as_ref().map(|cache| cache.stats())
+    }
+
+    fn requires_local(&self, class: DataClass) -> bool {
+        self.local_only || class.requires_local(!self.enforce_internal)
+    }
+
+    /// Backends allowed to see `class` data, in failover order, whatever
+    /// their health.
+    fn eligible(&self, class: DataClass) -> Result<Vec<Arc<Backend>>, RoutingError> {
+        let backends = self.backends();
+        if backends.is_empty() {
+            return Err(RoutingError::NoBackend);
+        }
+        let local_only = self.requires_local(class);
+        let eligible: Vec<Arc<Backend>> = backends
+            .into_iter()
+            .filter(|b| !local_only || b.model.is_local())
+            .collect();
+        if eligible.is_empty() {
+            return Err(RoutingError::NoLocalBackend(class.as_str()));
+        }
+        Ok(eligible)
+    }
+
+    /// Check that some backend may see `class` data, before doing the work
+    /// of building a prompt for it.
+    pub fn check(&self, class: DataClass) -> Result<(), RoutingError> {
+        self.eligible(class).map(|_| ())
+    }
+
+    /// First healthy backend allowed to see `class` data.
+    pub fn select(&self, class: DataClass) -> Result<Arc<dyn AnswerModel>, RoutingError> {
+        let eligible = self.eligible(class)?;
+        let now = Instant::now();
+        eligible
+            .iter()
+            .find(|b| !b.cooling_down(now))
+            .map(|b| b.model.clone())
+            .ok_or_else(|| RoutingError::Unavailable {
+                class: class.as_str(),
+                failures: eligible.iter().map(|b| cooling_down_note(b)).collect(),
+            })
+    }
+
+    /// Complete `prompt` on the first healthy backend allowed to see
+    /// `class` data and within its daily budget, moving down the failover
+    /// order while backends fail or are rate limited past the maximum queue
+    /// delay. Every call updates the health of the backends it tried and is
+    /// counted against the budgets, unless the response cache answers it.
+    pub async fn complete(
+        &self,
+        class: DataClass,
+        system: &str,
+        prompt: &str,
+        max_tokens: u32,
+        tokens: &mpsc::UnboundedSender<String>,
+    ) -> Result<RoutedGeneration, RoutingError> {
+        self.complete_with(
+            class,
+            system,
+            prompt,
+            max_tokens,
+            tokens,
+            CallOptions::default(),
+        )
+        .await
+    }
+
+    /// [`Self::complete`] with per-call options.
+    pub async fn complete_with(
+        &self,
+        class: DataClass,
+        system: &str,
+        prompt: &str,
+        max_tokens: u32,
+        tokens: &mpsc::UnboundedSender<String>,
+        options: CallOptions,
+    ) -> Result<RoutedGeneration, RoutingError> {
+        let mode = OutputMode::Text(tokens);
+        self.route(class, system, prompt, max_tokens, mode, options)
+            .await
+    }
+
+    /// [`Self::complete_with`] as a stream of tokens ending in
+    /// [`StreamEvent::Done`]. Tokens from a backend that fails part-way are
+    /// followed by the next backend's.
+    pub fn complete_stream(
+        &self,
+        class: DataClass,
+        system: String,
+        prompt: String,
+        max_tokens: u32,
+        options: CallOptions,
+    ) -> UnboundedReceiverStream<StreamEvent> {
+        let (events, rx) = mpsc::unbounded_channel();
+        let router = self.clone();
+        tokio::spawn(async move {
+            let (tokens, mut token_rx) = mpsc::unbounded_channel();
+            let forward = {
+                let events = events.clone();
+                tokio::spawn(async move {
+                    while let Some(token) = token_rx.recv().await {
+                        let _ = events.send(StreamEvent::Token(token));
+                    }
+                })
+            };
+            let result = router
+                .complete_with(class, &system, &prompt, max_tokens, &tokens, options)
+                .await;
+            drop(tokens);
+            let _ = forward.await;
+            let _ = events.send(StreamEvent::Done(Box::new(result)));
+        });
+        UnboundedReceiverStream::new(rx)
+    }
+
+    /// Route one call and, while auditing is on, record its outcome.
+    pub(crate) async fn route(
+        &self,
+        class: DataClass,
+        system: &str,
+        prompt: &str,
+        max_tokens: u32,
+        mode: OutputMode<'_>,
+        options: CallOptions,
+    ) -> Result<RoutedGeneration, RoutingError> {
+        let started = Instant::now();
+        let result = self
+            .route_unaudited(class, system, prompt, max_tokens, mode, &options)
+            .await;
+        let Some(sink) = self.audit.as_ref().filter(|_| self.audit_calls) else {
+            return result;
+        };
+        let (model, backend, output) = match &result {
+            Ok(routed) => (
+                routed.model.clone(),
+                routed.backend.clone(),
+                routed.generation.text.as_str(),
+            ),
+            Err(_) => (String::new(), String::new(), ""),
+        };
+        let mut entry = LlmAuditLog::new(model, backend, class, output);
+        let (prompt_hash, prompt_chars) = prompt_fingerprint(system, prompt);
+        entry.prompt_hash = Some(prompt_hash);
+        entry.prompt_chars = Some(prompt_chars);
+        entry.latency_ms = Some(started.elapsed().as_millis() as i64);
+        entry.caller = options.caller;
+        entry.session_id = options.session_id;
+        entry.chunk_ids = options.chunk_ids;
+        match &result {
+            Ok(routed) => {
+                let estimated = estimate_tokens(system) + estimate_tokens(prompt);
+                entry.prompt_tokens = Some(
+                    routed
+                        .generation
+                        .prompt_tokens
+                        .map_or(estimated as i64, i64::from),
+                );
+                entry.completion_tokens = routed.generation.completion_tokens.map(i64::from);
+                entry.cached = routed.cached;
+            }
+            Err(e) => entry.error = Some(e.to_string()),
+        }
+        sink.record(entry).await;
+        result
+    }
+
+    async fn route_unaudited(
+        &self,
+        class: DataClass,
+        system: &str,
+        prompt: &str,
+        max_tokens: u32,
+        mode: OutputMode<'_>,
+        options: &CallOptions,
+    ) -> Result<RoutedGeneration, RoutingError> {
+        let estimated_prompt = (estimate_tokens(system) + estimate_tokens(prompt)) as u64;
+        let mut failovers = Vec::new();
+        let mut denials = Vec::new();
+        let mut limited = Vec::new();
+        for backend in self.eligible(class)? {
+            let cache = self
+                .cache
+                .as_ref()
+                .filter(|_| !options.bypass_cache)
+                .map(|cache| {
+                    let key = ResponseCache::key(
+                        backend.model.backend(),
+                        backend.model.model_name(),
+                        mode.as_str(),
+                        max_tokens,
+                        system,
+                        prompt,
+                    );
+                    (cache, key)
+                });
+            if let Some(generation) = cache.as_ref().and_then(|(cache, key)| cache.get(key)) {
+                if let OutputMode::Text(tokens) = mode {
+                    let _ = tokens.send(generation.text.clone());
+                }
+                return Ok(RoutedGeneration {
+                    generation,
+                    backend: backend.model.backend().to_string(),
+                    model: backend.model.model_name().to_string(),
+                    failovers,
+                    budget_alert: None,
+                    cached: true,
+                });
+            }
+            if backend.cooling_down(Instant::now()) {
+                failovers.push(cooling_down_note(&backend));
+                continue;
+            }
+            if let Err(denial) = self.usage.check(
+                backend.model.backend(),
+                backend.model.is_local(),
+                estimated_prompt + u64::from(max_tokens),
+            ) {
+                tracing::info!("Skipping LLM backend over budget: {}", denial);
+                failovers.push(denial.to_string());
+                denials.push(denial.to_string());
+                continue;
+            }
+            if let Err(e) = self.rate_limiter.acquire(backend.model.backend()).await {
+                tracing::info!("Skipping rate-limited LLM backend: {}", e);
+                failovers.push(e.to_string());
+                limited.push(e.to_string());
+                continue;
+            }
+            let result = match mode {
+                OutputMode::Text(tokens) => {
+                    backend
+                        .model
+                        .generate(system, prompt, max_tokens, tokens)
+                        .await
+                }
+                OutputMode::Json => {
+                    backend
+                        .model
+                        .generate_json(system, prompt, max_tokens)
+                        .await
+                }
+            };
+            match result {
+                Ok(generation) => {
+                    backend.record_success();
+                    if let Some((cache, key)) = &cache {
+                        cache.insert(
+                            key,
+                            backend.model.backend(),
+                            backend.model.model_name(),
+                            &generation,
+                        );
+                    }
+                    let prompt_tokens =
+                        generation.prompt_tokens.map_or(estimated_prompt, u64::from);
+                    let completion_tokens = generation
+                        .completion_tokens
+                        .map_or_else(|| estimate_tokens(&generation.text) as u64, u64::from);
+                    let (prompt_rate, completion_rate) = backend.model.cost_per_token();
+                    let cost_usd = prompt_tokens as f64 * prompt_rate
+                        + completion_tokens as f64 * completion_rate;
+                    let budget_alert = self
+                        .usage
+                        .record(
+                            backend.model.backend(),
+                            backend.model.model_name(),
+                            prompt_tokens,
+                            completion_tokens,
+                            cost_usd,
+                        )
+                        .await;
+                    return Ok(RoutedGeneration {
+                        generation,
+                        backend: backend.model.backend().to_string(),
+                        model: backend.model.model_name().to_string(),
+                        failovers,
+                        budget_alert,
+                        cached: false,
+                    });
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "LLM backend {} failed, trying the next one: {}",
+                        backend.model.backend(),
+                        e
+                    );
+                    failovers.push(format!("{}: {e}", backend.model.backend()));
+                    backend.record_failure(e.to_string(), &self.health_cfg);
+                }
+            }
+        }
+        if !denials.is_empty() && denials.len() == failovers.len() {
+            return Err(RoutingError::BudgetExceeded {
+                class: class.as_str(),
+                denials,
+            });
+        }
+        if !limited.is_empty() && limited.len() == failovers.len() {
+            return Err(RoutingError::RateLimited {
+                class: class.as_str(),
+                limited,
+            });
+        }
+        Err(RoutingError::Unavailable {
+            class: class.as_str(),
+            failures: failovers,
+        })
+    }
+
+    /// Probe every backend that has failed and is out of its cooldown,
+    /// putting it back in rotation when it answers.
+    pub async fn probe_unhealthy(&self) {
+        let now = Instant::now();
+        for backend in &self.backends() {
+            let due = {
+                let health = backend.health();
+                health.consecutive_failures > 0
+                    && health.cooldown_until.is_none_or(|until| until <= now)
+            };
+            if !due {
+                continue;
+            }
+            match backend.model.probe().await {
+                Ok(()) => {
+                    tracing::info!("LLM backend {} is healthy again", backend.model.backend());
+                    backend.record_success();
+                }
+                Err(e) => {
+                    let mut health = backend.health();
+                    health.last_error = Some(e.to_string());
+                    health.cooldown_until = Some(Instant::now() + self.health_cfg.cooldown);
+                }
+            }
+        }
+    }
+
+    /// Run [`Self::probe_unhealthy`] every probe interval.
+    pub fn spawn_health_probe(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let interval = self.health_cfg.probe_interval;
+        let router = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                router.probe_unhealthy().await;
+            }
+        })
+    }
+
+    /// Health of every backend, in failover order.
+    pub fn health(&self) -> Vec<BackendStatus> {
+        let now = Instant::now();
+        self.backends()
+            .iter()
+            .map(|b| {
+                let health = b.health();
+                BackendStatus {
+                    backend: b.model.backend().to_string(),
+                    model: b.model.model_name().to_string(),
+                    endpoint: b.model.endpoint().map(str::to_string),
+                    local: b.model.is_local(),
+                    consecutive_failures: health.consecutive_failures,
+                    cooling_down: health.cooldown_until.is_some_and(|until| until > now),
+                    last_error: health.last_error.clone(),
+                }
+            })
+            .collect()
+    }
+}
+
+fn cooling_down_note(backend: &Backend) -> String {
+    format!(
+        "{}: cooling down after {}",
+        backend.model.backend(),
+        backend
+            .health()
+            .last_error
+            .as_deref()
+            .unwrap_or("repeated failures")
+    )
+}
+
+/// Builds the router's backends from saved settings, so answer routing
+/// can follow `ferrumyx.toml` without a restart (see
+/// [`crate::settings_reload`]).
+#[async_trait]
+pub trait AnswerBackendSource: Send + Sync {
+    /// Backends configured by `root`, with secrets opened, in failover
+    /// order.
+    async fn answer_backends(
+        &self,
+        root: &toml::Value,
+    ) -> anyhow::Result<Vec<Arc<dyn AnswerModel>>>;
+
+    /// Components built from `[llm]` that a rebuild does not reach, which
+    /// pick up changes only after a restart.
+    fn fixed_components(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// The standalone web server's single backend: `[llm.ollama]`, falling
+/// back to `OLLAMA_BASE_URL` / `OLLAMA_MODEL` like
+/// [`OllamaAnswerModel::from_env`].
+pub struct OllamaBackendSource;
+
+#[async_trait]
+impl AnswerBackendSource for OllamaBackendSource {
+    async fn answer_backends(
+        &self,
+        root: &toml::Value,
+    ) -> anyhow::Result<Vec<Arc<dyn AnswerModel>>> {
+        let from_env = OllamaAnswerModel::from_env();
+        let ollama = root.get("llm").and_then(|llm| llm.get("ollama"));
+        let setting = |key: &str| {
+            ollama
+                .and_then(|o| o.get(key))
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        let base_url = setting("base_url").unwrap_or(from_env.base_url);
+        let model = setting("model").unwrap_or(from_env.model);
+        Ok(vec![Arc::new(OllamaAnswerModel::new(&base_url, &model))])
+    }
+}
+
+/// Ollama `/api/generate` with streamed output.
+pub struct OllamaAnswerModel {
+    base_url: String,
+    model: String,
+    client: reqwest::Client,
+}
+
+impl OllamaAnswerModel {
+    pub fn new(base_url: &str, model: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(300))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let env = |name: &str, default: &str| {
+            std::env::var(name)
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .unwrap_or_else(|| default.to_string())
+        };
+        Self::new(
+            &env("OLLAMA_BASE_URL", "http://localhost:11434"),
+            &env("OLLAMA_MODEL", "llama3.1:8b"),
+        )
+    }
+}
+
+#[derive(Deserialize)]
+struct OllamaChunk {
+    #[serde(default)]
+    response: String,
+    #[serde(default)]
+    done: bool,
+    prompt_eval_count: Option<u32>,
+    eval_count: Option<u32>,
+}
+
+#[async_trait]
+impl AnswerModel for OllamaAnswerModel {
+    fn model_name(&self) -> &str {
+        &self.model
+    }
+
+    fn backend(&self) -> &str {
+        "ollama"
+    }
+
+    fn is_local(&self) -> bool {
+        let url = self.base_url.to_lowercase();
+        url.contains("localhost") || url.contains("127.0.0.1")
+    }
+
+    fn endpoint(&self) -> Option<&str> {
+        Some(&self.base_url)
+    }
+
+    async fn generate(
+        &self,
+        system: &str,
+        prompt: &str,
+        max_tokens: u32,
+        tokens: &mpsc::UnboundedSender<String>,
+    ) -> anyhow::Result<Generation> {
+        let body = serde_json::json!({
+            "model": self.model,
+            "system": system,
+            "prompt": prompt,
+            "stream": true,
+            "options": { "temperature": 0.1, "num_predict": max_tokens },
+        });
+        let mut response = self
+            .client
+            .post(format!("{}/api/generate", self.base_url))
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        // Newline-delimited JSON, one object per generated fragment.
+        let mut out = Generation::default();
+        let mut pending = Vec::new();
+        while let Some(bytes) = response.chunk().await? {
+            pending.extend_from_slice(&bytes);
+            while let Some(end) = pending.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = pending.drain(..=end).collect();
+                if line.iter().all(u8::is_ascii_whitespace) {
+                    continue;
+                }
+                let chunk: OllamaChunk = serde_json::from_slice(&line)?;
+                if !chunk.response.is_empty() {
+                    let _ = tokens.send(chunk.response.clone());
+                    out.text.push_str(&chunk.response);
+                }
+                if chunk.done {
+                    out.prompt_tokens = chunk.prompt_eval_count;
+                    out.completion_tokens = chunk.eval_count;
+                    return Ok(out);
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    async fn generate_json(
+        &self,
+        system: &str,
+        prompt: &str,
+        max_tokens: u32,
+    ) -> anyhow::Result<Generation> {
+        let body = serde_json::json!({
+            "model": self.model,
+            "system": system,
+            "prompt": prompt,
+            "format": "json",
+            "stream": false,
+            "options": { "temperature": 0.0, "num_predict": max_tokens },
+        });
+        let chunk: OllamaChunk = self
+            .client
+            .post(format!("{}/api/generate", self.base_url))
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(Generation {
+            text: chunk.response,
+            prompt_tokens: chunk.prompt_eval_count,
+            completion_tokens: chunk.eval_count,
+        })
+    }
+
+    async fn probe(&self) -> anyhow::Result<()> {
+        self.client
+            .get(format!("{}/api/tags", self.base_url))
+            .timeout(Duration::from_secs(5))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    struct Stub {
+        local: bool,
+    }
+
+    #[async_trait]
+    impl AnswerModel for Stub {
+        fn model_name(&self) -> &str {
+            if self.local {
+                "llama3.1:8b"
+            } else {
+                "gpt-4o"
+            }
+        }
+
+        fn backend(&self) -> &str {
+            if self.local {
+                "ollama"
+            } else {
+                "openai"
+            }
+        }
+
+        fn is_local(&self) -> bool {
+            self.local
+        }
+
+        async fn generate(
+            &self,
+            _system: &str,
+            _prompt: &str,
+            _max_tokens: u32,
+            _tokens: &mpsc::UnboundedSender<String>,
+        ) -> anyhow::Result<Generation> {
+            Ok(Generation::default())
+        }
+    }
+
+    #[test]
+    fn confidential_data_never_routes_to_a_remote_backend() {
+        let remote: Arc<dyn AnswerModel> = Arc::new(Stub { local: false });
+        let local: Arc<dyn AnswerModel> = Arc::new(Stub { local: true });
+
+        let remote_only = LlmRouter::new(remote.clone());
+        assert_eq!(
+            remote_only.select(DataClass::Public).unwrap().backend(),
+            "openai"
+        );
+        assert!(matches!(
+            remote_only.select(DataClass::Confidential),
+            Err(RoutingError::NoLocalBackend("CONFIDENTIAL"))
+        ));
+        assert!(remote_only.select(DataClass::Internal).is_err());
+        let relaxed = remote_only.clone().with_enforce_internal(false);
+        assert_eq!(
+            relaxed.select(DataClass::Internal).unwrap().backend(),
+            "openai"
+        );
+        assert!(relaxed.select(DataClass::Confidential).is_err());
+
+        let routed = LlmRouter::new(remote).with_backend(local);
+        assert_eq!(
+            routed.select(DataClass::Public).unwrap().backend(),
+            "openai"
+        );
+        assert_eq!(
+            routed.select(DataClass::Confidential).unwrap().backend(),
+            "ollama"
+        );
+        assert!(matches!(
+            LlmRouter::default().select(DataClass::Public),
+            Err(RoutingError::NoBackend)
+        ));
+    }
+
+    /// Backend that fails while `failing` is set and records its calls.
+    struct Flaky {
+        backend: &'static str,
+        local: bool,
+        failing: AtomicBool,
+        calls: AtomicUsize,
+        called_at: Mutex<Vec<Instant>>,
+    }
+
+    impl Flaky {
+        fn new(backend: &'static str, local: bool, failing: bool) -> Arc<Self> {
+            Arc::new(Self {
+                backend,
+                local,
+                failing: AtomicBool::new(failing),
+                calls: AtomicUsize::new(0),
+                called_at: Mutex::default(),
+            })
+        }
+
+        fn calls(&self) -> usize {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl AnswerModel for Flaky {
+        fn model_name(&self) -> &str {
+            "mock"
+        }
+
+        fn backend(&self) -> &str {
+            self.backend
+        }
+
+        fn is_local(&self) -> bool {
+            self.local
+        }
+
+        async fn generate(
+            &self,
+            _system: &str,
+            _prompt: &str,
+            _max_tokens: u32,
+            tokens: &mpsc::UnboundedSender<String>,
+        ) -> anyhow::Result<Generation> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.called_at.lock().unwrap().push(Instant::now());
+            if self.failing.load(Ordering::SeqCst) {
+                anyhow::bail!("429 Too Many Requests");
+            }
+            let _ = tokens.send("answer from ".to_string());
+            let _ = tokens.send(self.backend.to_string());
+            Ok(Generation {
+                text: format!("answer from {}", self.backend),
+                ..Generation::default()
+            })
+        }
+    }
+
+    async fn ask(router: &LlmRouter, class: DataClass) -> Result<RoutedGeneration, RoutingError> {
+        let (tokens, _rx) = mpsc::unbounded_channel();
+        router
+            .complete(class, "system", "question", 16, &tokens)
+            .await
+    }
+
+    const FAST_COOLDOWN: HealthConfig = HealthConfig {
+        failure_threshold: 2,
+        cooldown: Duration::from_millis(50),
+        probe_interval: Duration::from_millis(10),
+    };
+
+    #[tokio::test]
+    async fn failing_backend_fails_over_cools_down_and_recovers() {
+        let openai = Flaky::new("openai", false, true);
+        let ollama = Flaky::new("ollama", true, false);
+        let router = LlmRouter::new(openai.clone())
+            .with_backend(ollama.clone())
+            .with_health_config(FAST_COOLDOWN);
+
+        let served = ask(&router, DataClass::Public).await.unwrap();
+        assert_eq!(served.backend, "ollama");
+        assert_eq!(served.generation.text, "answer from ollama");
+        assert_eq!(served.failovers, vec!["openai: 429 Too Many Requests"]);
+        assert!(!router.health()[0].cooling_down);
+
+        // The second consecutive failure takes openai out of rotation.
+        ask(&router, DataClass::Public).await.unwrap();
+        assert!(router.health()[0].cooling_down);
+        assert_eq!(router.health()[0].consecutive_failures, 2);
+        let served = ask(&router, DataClass::Public).await.unwrap();
+        assert_eq!(served.backend, "ollama");
+        assert!(served.failovers[0].contains("cooling down"));
+        assert_eq!(openai.calls(), 2);
+
+        // Not probed during the cooldown, restored by the background probe
+        // after it.
+        openai.failing.store(false, Ordering::SeqCst);
+        router.probe_unhealthy().await;
+        assert_eq!(openai.calls(), 2);
+        let probe = Arc::new(router.clone()).spawn_health_probe();
+        tokio::time::sleep(Duration::from_millis(120)).await;
+        probe.abort();
+        assert_eq!(openai.calls(), 3);
+        assert_eq!(router.health()[0].consecutive_failures, 0);
+        let served = ask(&router, DataClass::Public).await.unwrap();
+        assert_eq!(served.backend, "openai");
+        assert!(served.failovers.is_empty());
+    }
+
+    #[tokio::test]
+    async fn confidential_traffic_never_fails_over_to_a_remote_backend() {
+        let ollama = Flaky::new("ollama", true, true);
+        let openai = Flaky::new("openai", false, false);
+        let router = LlmRouter::new(ollama.clone())
+            .with_backend(openai.clone())
+            .with_health_config(FAST_COOLDOWN);
+
+        let err = ask(&router, DataClass::Confidential).await.unwrap_err();
+        assert!(
+            matches!(&err, RoutingError::Unavailable { class: "CONFIDENTIAL", failures } if failures.len() == 1),
+            "{err}"
+        );
+        assert_eq!(openai.calls(), 0);
+        assert_eq!(
+            ask(&router, DataClass::Public).await.unwrap().backend,
+            "openai"
+        );
+
+        // local_only mode keeps even public traffic local.
+        let local_only = router.clone().with_local_only(true);
+        assert!(ask(&local_only, DataClass::Public).await.is_err());
+        assert_eq!(openai.calls(), 1);
+        assert!(matches!(
+            LlmRouter::new(openai.clone())
+                .with_local_only(true)
+                .check(DataClass::Public),
+            Err(RoutingError::NoLocalBackend("PUBLIC"))
+        ));
+    }
+
+    #[tokio::test]
+    async fn complete_errors_when_every_eligible_backend_fails() {
+        let openai = Flaky::new("openai", false, true);
+        let ollama = Flaky::new("ollama", true, true);
+        let router = LlmRouter::new(openai.clone())
+            .with_backend(ollama.clone())
+            .with_health_config(HealthConfig {
+                failure_threshold: 1,
+                ..FAST_COOLDOWN
+            });
+
+        let err = ask(&router, DataClass::Public).await.unwrap_err();
+        let RoutingError::Unavailable { failures, .. } = &err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(failures.len(), 2);
+        assert!(err.to_string().contains("openai: 429"));
+
+        // Both are cooling down now, so nothing is called.
+        assert!(ask(&router, DataClass::Public).await.is_err());
+        assert!(router.select(DataClass::Public).is_err());
+        assert_eq!((openai.calls(), ollama.calls()), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn remote_calls_over_the_daily_budget_reroute_to_a_local_backend() {
+        use crate::llm_usage::BudgetLimits;
+
+        let openai = Flaky::new("openai", false, false);
+        let ollama = Flaky::new("ollama", true, false);
+        // Each call is estimated at 20 tokens (prompt and max_tokens) and
+        // counted as 9 once served.
+        let usage = Arc::new(UsageTracker::new(
+            BudgetLimits::default().with_token_limit("openai", 30),
+        ));
+        let router = LlmRouter::new(openai.clone())
+            .with_backend(ollama.clone())
+            .with_usage_tracker(usage.clone());
+
+        assert_eq!(
+            ask(&router, DataClass::Public).await.unwrap().backend,
+            "openai"
+        );
+        assert_eq!(
+            ask(&router, DataClass::Public).await.unwrap().backend,
+            "openai"
+        );
+        // The third would cross the cap part-way through the day.
+        let served = ask(&router, DataClass::Public).await.unwrap();
+        assert_eq!(served.backend, "ollama");
+        assert!(served.failovers[0].contains("daily token limit"));
+        assert_eq!((openai.calls(), ollama.calls()), (2, 1));
+
+        let remote_only = LlmRouter::new(openai.clone()).with_usage_tracker(usage.clone());
+        let err = ask(&remote_only, DataClass::Public).await.unwrap_err();
+        assert!(
+            matches!(&err, RoutingError::BudgetExceeded { denials, .. } if denials.len() == 1),
+            "{err}"
+        );
+        assert_eq!(openai.calls(), 2);
+
+        let summary = usage.summary();
+        assert_eq!(summary.backends.len(), 2);
+        let openai_usage = &summary.backends[1];
+        assert_eq!(openai_usage.backend, "openai");
+        assert_eq!(
+            openai_usage.prompt_tokens + openai_usage.completion_tokens,
+            18
+        );
+    }
+
+    #[tokio::test]
+    async fn complete_stream_yields_tokens_then_the_routed_generation() {
+        use tokio_stream::StreamExt;
+
+        let openai = Flaky::new("openai", false, true);
+        let ollama = Flaky::new("ollama", true, false);
+        let router = LlmRouter::new(openai).with_backend(ollama);
+
+        let events: Vec<StreamEvent> = router
+            .complete_stream(
+                DataClass::Public,
+                "system".to_string(),
+                "question".to_string(),
+                16,
+                CallOptions::default(),
+            )
+            .collect()
+            .await;
+        let tokens: Vec<&str> = events
+            .iter()
+            .filter_map(|e| match e {
+                StreamEvent::Token(t) => Some(t.as_str()),
+                StreamEvent::Done(_) => None,
+            })
+            .collect();
+        assert_eq!(tokens, vec!["answer from ", "ollama"]);
+        let Some(StreamEvent::Done(done)) = events.last() else {
+            panic!("stream did not end with Done");
+        };
+        let served = done.as_ref().as_ref().unwrap();
+        assert_eq!(served.backend, "ollama");
+        assert_eq!(served.generation.text, "answer from ollama");
+        assert_eq!(served.failovers, vec!["openai: 429 Too Many Requests"]);
+        assert_eq!(router.usage().summary().backends[0].calls, 1);
+    }
+
+    #[tokio::test]
+    async fn cache_hits_skip_the_backend_and_the_budget() {
+        use crate::llm_cache::ResponseCacheConfig;
+
+        let dir =
+            std::env::temp_dir().join(format!("ferrumyx-router-cache-{}", uuid::Uuid::new_v4()));
+        let cache = Arc::new(ResponseCache::open(ResponseCacheConfig::new(&dir)).unwrap());
+        let openai = Flaky::new("openai", false, false);
+        let router = LlmRouter::new(openai.clone()).with_response_cache(cache);
+
+        let first = ask(&router, DataClass::Public).await.unwrap();
+        assert!(!first.cached);
+        let (tokens, mut rx) = mpsc::unbounded_channel();
+        let second = router
+            .complete(DataClass::Public, "system", "question", 16, &tokens)
+            .await
+            .unwrap();
+        assert!(second.cached);
+        assert_eq!(second.generation.text, "answer from openai");
+        assert_eq!(rx.recv().await.unwrap(), "answer from openai");
+        assert_eq!(openai.calls(), 1);
+        assert_eq!(router.usage().summary().backends[0].calls, 1);
+
+        let bypass = CallOptions {
+            bypass_cache: true,
+            ..CallOptions::default()
+        };
+        let fresh = router
+            .complete_with(DataClass::Public, "system", "question", 16, &tokens, bypass)
+            .await
+            .unwrap();
+        assert!(!fresh.cached);
+        assert_eq!(openai.calls(), 2);
+        let stats = router.cache_stats().unwrap();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[derive(Default)]
+    struct MemorySink(Mutex<Vec<LlmAuditLog>>);
+
+    #[async_trait]
+    impl AuditSink for MemorySink {
+        async fn record(&self, entry: LlmAuditLog) {
+            self.0.lock().unwrap().push(entry);
+        }
+    }
+
+    #[tokio::test]
+    async fn every_routed_call_is_audited_without_its_prompt() {
+        let sink = Arc::new(MemorySink::default());
+        let router =
+            LlmRouter::new(Flaky::new("openai", false, false)).with_audit_sink(sink.clone());
+        let (tokens, _rx) = mpsc::unbounded_channel();
+        let options = CallOptions {
+            caller: Some("answer".to_string()),
+            session_id: Some("answer-1".to_string()),
+            ..CallOptions::default()
+        };
+        router
+            .complete_with(
+                DataClass::Public,
+                "system",
+                "question",
+                16,
+                &tokens,
+                options,
+            )
+            .await
+            .unwrap();
+        // Blocked by the data-class policy, and still logged.
+        assert!(ask(&router, DataClass::Confidential).await.is_err());
+
+        let entries = sink.0.lock().unwrap().clone();
+        assert_eq!(entries.len(), 2);
+        let served = &entries[0];
+        assert_eq!(
+            (served.backend.as_str(), served.data_class),
+            ("openai", DataClass::Public)
+        );
+        assert_eq!(served.caller.as_deref(), Some("answer"));
+        assert_eq!(served.session_id.as_deref(), Some("answer-1"));
+        assert_eq!(served.prompt_chars, Some(14));
+        assert_eq!(served.prompt_hash.as_ref().map(String::len), Some(64));
+        assert!(served.error.is_none() && served.latency_ms.is_some());
+        let blocked = &entries[1];
+        assert_eq!(blocked.data_class, DataClass::Confidential);
+        assert!(blocked.error.as_deref().unwrap().contains("CONFIDENTIAL"));
+        assert_eq!(blocked.prompt_hash, served.prompt_hash);
+
+        let quiet = router.with_audit_calls(false);
+        ask(&quiet, DataClass::Public).await.unwrap();
+        assert_eq!(sink.0.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn concurrent_requests_are_spaced_to_the_backend_rate() {
+        use crate::llm_rate_limit::RateLimits;
+
+        let openai = Flaky::new("openai", false, false);
+        let limiter = RateLimiter::new(
+            RateLimits::default()
+                .with_rpm("openai", 60)
+                .with_max_queue_delay(Duration::from_secs(120)),
+        );
+        let router = LlmRouter::new(openai.clone()).with_rate_limiter(Arc::new(limiter));
+
+        let calls: Vec<_> = (0..100)
+            .map(|_| {
+                let router = router.clone();
+                tokio::spawn(async move { ask(&router, DataClass::Public).await })
+            })
+            .collect();
+        tokio::time::sleep(Duration::from_millis(10_500)).await;
+        assert_eq!(openai.calls(), 11);
+        assert_eq!(router.rate_limits()[0].queued, 89);
+        for call in calls {
+            assert_eq!(call.await.unwrap().unwrap().backend, "openai");
+        }
+        assert_eq!(router.rate_limits()[0].queued, 0);
+
+        // One request a second, so never more than 60 in any minute.
+        let mut called_at = openai.called_at.lock().unwrap().clone();
+        called_at.sort();
+        assert_eq!(called_at.len(), 100);
+        for pair in called_at.windows(2) {
+            assert!(pair[1] - pair[0] >= Duration::from_millis(999));
+        }
+        for minute in called_at.windows(61) {
+            assert!(minute[60] - minute[0] >= Duration::from_millis(59_999));
+        }
+        let span = called_at[99] - called_at[0];
+        assert!(span >= Duration::from_millis(98_999) && span < Duration::from_secs(100));
+    }
+}
@@ -0,0 +1,92 @@
+//! Audit trail of [`LlmRouter`](crate::llm::LlmRouter) calls.
+//!
+//! With `[security] audit_llm_calls` on, the router itself hands one
+//! [`LlmAuditLog`] per call to its [`AuditSink`] — served, cached or failed —
+//! so no caller can skip the log. Prompts are only kept as a SHA-256 and a
+//! character count, never verbatim, whatever their data class. Rows older
+//! than `[security] audit_log_retention_days` are purged at start-up and
+//! then daily.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+
+use ferrumyx_db::{Database, LlmAuditLog, LlmAuditRepository};
+
+/// Where the router writes its audit records.
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    /// Persist `entry`. Failures are logged rather than failing the call.
+    async fn record(&self, entry: LlmAuditLog);
+}
+
+/// Writes audit records to the `llm_audit` table.
+#[derive(Clone)]
+pub struct DbAuditSink {
+    repo: LlmAuditRepository,
+}
+
+impl DbAuditSink {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self {
+            repo: LlmAuditRepository::new(db),
+        }
+    }
+
+    /// Delete records older than `retention_days`; 0 keeps them all.
+    pub async fn purge_expired(&self, retention_days: u32) -> anyhow::Result<usize> {
+        if retention_days == 0 {
+            return Ok(0);
+        }
+        let cutoff = chrono::Utc::now() - chrono::Duration::days(i64::from(retention_days));
+        Ok(self.repo.purge_before(cutoff).await?)
+    }
+
+    /// Run [`Self::purge_expired`] now and then once a day.
+    pub fn spawn_retention_purge(&self, retention_days: u32) -> tokio::task::JoinHandle<()> {
+        let sink = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_secs(24 * 3600));
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                match sink.purge_expired(retention_days).await {
+                    Ok(0) => {}
+                    Ok(n) => tracing::info!(
+                        "Purged {} LLM audit records older than {} days",
+                        n,
+                        retention_days
+                    ),
+                    Err(e) => tracing::warn!("LLM audit retention purge failed: {}", e),
+                }
+            }
+        })
+    }
+}
+
+#[async_trait]
+impl AuditSink for DbAuditSink {
+    async fn record(&self, entry: LlmAuditLog) {
+        if let Err(e) = self.repo.insert(&entry).await {
+            tracing::warn!("Failed to write LLM audit entry {}: {}", entry.id, e);
+        }
+    }
+}
+
+/// Hex SHA-256 and character count of the prompts of one call.
+pub(crate) fn prompt_fingerprint(system: &str, prompt: &str) -> (String, i64) {
+    let mut hasher = Sha256::new();
+    hasher.update(system.as_bytes());
+    // Keep the boundary between the two prompts in the hash.
+    hasher.update([0u8]);
+    hasher.update(prompt.as_bytes());
+    let hash = hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    let chars = system.chars().count() + prompt.chars().count();
+    (hash, chars as i64)
+}
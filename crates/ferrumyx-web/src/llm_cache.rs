@@ -0,0 +1,305 @@
+//! Content-addressed cache of [`LlmRouter`](crate::llm::LlmRouter)
+//! completions, persisted under the workspace directory.
+//!
+//! The key hashes the backend, model, output mode (which fixes the sampling
+//! temperature), token cap and both prompts, so re-running ingestion over
+//! overlapping corpora replays identical summarisation and extraction calls
+//! instead of paying for them again. Entries live for the TTL and the least
+//! recently used are evicted past the size cap. Hits are not counted against
+//! the daily `[llm.limits]` budgets.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::llm::Generation;
+
+/// Where and how long completions are kept, from `[llm.cache]`.
+#[derive(Debug, Clone)]
+pub struct ResponseCacheConfig {
+    /// One `<key>.json` file per entry.
+    pub dir: PathBuf,
+    pub ttl: Duration,
+    /// Entries kept before the least recently used is evicted.
+    pub max_entries: usize,
+}
+
+impl ResponseCacheConfig {
+    /// A week's TTL and 10 000 entries.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            ttl: Duration::from_secs(7 * 24 * 3600),
+            max_entries: 10_000,
+        }
+    }
+
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+}
+
+/// Hit and miss counters since start-up, for `GET /api/llm/usage`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+}
+
+/// What is written to disk for one entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedCompletion {
+    backend: String,
+    model: String,
+    text: String,
+    prompt_tokens: Option<u32>,
+    completion_tokens: Option<u32>,
+    created_at: DateTime<Utc>,
+}
+
+struct Entry {
+    completion: CachedCompletion,
+    /// Value of [`ResponseCache::clock`] at the last hit or insert.
+    last_used: u64,
+}
+
+/// Completions by key, shared by every clone of a router.
+pub struct ResponseCache {
+    config: ResponseCacheConfig,
+    entries: Mutex<HashMap<String, Entry>>,
+    clock: AtomicU64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl ResponseCache {
+    /// Load the entries already in `config.dir`, dropping expired and
+    /// unreadable ones.
+    pub fn open(config: ResponseCacheConfig) -> std::io::Result<Self> {
+        std::fs::create_dir_all(&config.dir)?;
+        let mut loaded: Vec<(String, CachedCompletion)> = Vec::new();
+        for file in std::fs::read_dir(&config.dir)? {
+            let path = file?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let Some(key) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let completion = std::fs::read(&path)
+                .ok()
+                .and_then(|bytes| serde_json::from_slice::<CachedCompletion>(&bytes).ok());
+            match completion {
+                Some(c) if !expired(&c, config.ttl) => loaded.push((key.to_string(), c)),
+                _ => {
+                    let _ = std::fs::remove_file(&path);
+                }
+            }
+        }
+        // Oldest first, so recency survives a restart approximately.
+        loaded.sort_by_key(|(_, c)| c.created_at);
+        let cache = Self {
+            config,
+            entries: Mutex::new(HashMap::new()),
+            clock: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        };
+        {
+            let mut entries = cache.entries();
+            for (key, completion) in loaded {
+                let last_used = cache.tick();
+                entries.insert(
+                    key,
+                    Entry {
+                        completion,
+                        last_used,
+                    },
+                );
+            }
+            cache.evict(&mut entries);
+        }
+        Ok(cache)
+    }
+
+    /// Cache key for one call.
+    pub fn key(
+        backend: &str,
+        model: &str,
+        mode: &str,
+        max_tokens: u32,
+        system: &str,
+        prompt: &str,
+    ) -> String {
+        // JSON keeps field boundaries unambiguous.
+        let fields = serde_json::json!([backend, model, mode, max_tokens, system, prompt]);
+        Sha256::digest(fields.to_string().as_bytes())
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
+
+    fn entries(&self) -> MutexGuard<'_, HashMap<String, Entry>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.config.dir.join(format!("{key}.json"))
+    }
+
+    /// The cached completion for `key`, counting a hit or a miss.
+    pub fn get(&self, key: &str) -> Option<Generation> {
+        let mut entries = self.entries();
+        let hit = match entries.get_mut(key) {
+            Some(entry) if expired(&entry.completion, self.config.ttl) => {
+                entries.remove(key);
+                let _ = std::fs::remove_file(self.path(key));
+                None
+            }
+            Some(entry) => {
+                entry.last_used = self.tick();
+                Some(Generation {
+                    text: entry.completion.text.clone(),
+                    prompt_tokens: entry.completion.prompt_tokens,
+                    completion_tokens: entry.completion.completion_tokens,
+                })
+            }
+            None => None,
+        };
+        let counter = if hit.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        hit
+    }
+
+    /// Store what `backend` generated for `key`, evicting the least
+    /// recently used entries past the size cap.
+    pub fn insert(&self, key: &str, backend: &str, model: &str, generation: &Generation) {
+        let completion = CachedCompletion {
+            backend: backend.to_string(),
+            model: model.to_string(),
+            text: generation.text.clone(),
+            prompt_tokens: generation.prompt_tokens,
+            completion_tokens: generation.completion_tokens,
+            created_at: Utc::now(),
+        };
+        let written = serde_json::to_vec(&completion)
+            .map_err(std::io::Error::from)
+            .and_then(|bytes| std::fs::write(self.path(key), bytes));
+        if let Err(e) = written {
+            tracing::warn!("Failed to persist LLM cache entry {}: {}", key, e);
+        }
+        let mut entries = self.entries();
+        let last_used = self.tick();
+        entries.insert(
+            key.to_string(),
+            Entry {
+                completion,
+                last_used,
+            },
+        );
+        self.evict(&mut entries);
+    }
+
+    fn evict(&self, entries: &mut HashMap<String, Entry>) {
+        while entries.len() > self.config.max_entries {
+            let Some(oldest) = entries
+                .iter()
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            entries.remove(&oldest);
+            let _ = std::fs::remove_file(self.path(&oldest));
+        }
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.entries().len(),
+        }
+    }
+}
+
+fn expired(completion: &CachedCompletion, ttl: Duration) -> bool {
+    (Utc::now() - completion.created_at)
+        .to_std()
+        .is_ok_and(|age| age >= ttl)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generation(text: &str) -> Generation {
+        Generation {
+            text: text.to_string(),
+            prompt_tokens: Some(120),
+            completion_tokens: Some(30),
+        }
+    }
+
+    #[test]
+    fn entries_persist_expire_and_evict_least_recently_used() {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-llm-cache-{}", uuid::Uuid::new_v4()));
+        let config = ResponseCacheConfig::new(&dir).with_max_entries(2);
+        let key = |prompt: &str| ResponseCache::key("openai", "gpt-4o", "text", 512, "sys", prompt);
+        assert_ne!(key("a"), key("b"));
+        assert_ne!(
+            key("a"),
+            ResponseCache::key("openai", "gpt-4o", "json", 512, "sys", "a")
+        );
+
+        let cache = ResponseCache::open(config.clone()).unwrap();
+        assert!(cache.get(&key("a")).is_none());
+        cache.insert(&key("a"), "openai", "gpt-4o", &generation("A"));
+        cache.insert(&key("b"), "openai", "gpt-4o", &generation("B"));
+        assert_eq!(cache.get(&key("a")).unwrap().text, "A");
+        // "b" is now the least recently used.
+        cache.insert(&key("c"), "openai", "gpt-4o", &generation("C"));
+        assert!(cache.get(&key("b")).is_none());
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                hits: 1,
+                misses: 2,
+                entries: 2,
+            }
+        );
+
+        let reopened = ResponseCache::open(config.clone()).unwrap();
+        let hit = reopened.get(&key("c")).unwrap();
+        assert_eq!((hit.text.as_str(), hit.completion_tokens), ("C", Some(30)));
+        assert_eq!(reopened.stats().entries, 2);
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+
+        let expired = ResponseCache::open(config.with_ttl(Duration::ZERO)).unwrap();
+        assert_eq!(expired.stats().entries, 0);
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,277 @@
+//! Typed JSON completions for extraction-style [`LlmRouter`] calls.
+//!
+//! The prompt carries the JSON Schema of the caller's type, backends with a
+//! JSON output mode are asked to use it (`format: "json"` on Ollama,
+//! `response_format` on OpenAI-compatible APIs), and the reply is
+//! deserialised into the type. A reply that does not fit is sent back with
+//! the error for a bounded number of repair round-trips; after that the
+//! caller gets [`JsonOutputError::Invalid`] with the raw output.
+
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+
+use crate::llm::{CallOptions, DataClass, LlmRouter, OutputMode, RoutingError};
+
+/// Repair round-trips after the first reply.
+pub const DEFAULT_MAX_REPAIRS: usize = 2;
+
+#[derive(Debug, thiserror::Error)]
+pub enum JsonOutputError {
+    #[error(transparent)]
+    Routing(#[from] RoutingError),
+    #[error("LLM output did not match the schema after {attempts} attempts: {error}")]
+    Invalid {
+        attempts: usize,
+        /// Why the last reply was rejected.
+        error: String,
+        /// The last reply, verbatim.
+        raw: String,
+    },
+}
+
+/// A deserialised reply and where it came from.
+#[derive(Debug, Clone)]
+pub struct JsonCompletion<T> {
+    pub value: T,
+    pub backend: String,
+    pub model: String,
+    /// Repair round-trips it took; 0 when the first reply fit.
+    pub repairs: usize,
+}
+
+impl LlmRouter {
+    /// Complete `prompt` as a `T`, routed like [`Self::complete`], retrying
+    /// up to `max_repairs` times with the validation error fed back.
+    pub async fn complete_json<T: DeserializeOwned + JsonSchema>(
+        &self,
+        class: DataClass,
+        system: &str,
+        prompt: &str,
+        max_tokens: u32,
+        max_repairs: usize,
+    ) -> Result<JsonCompletion<T>, JsonOutputError> {
+        let schema = serde_json::to_string_pretty(&schemars::schema_for!(T)).map_err(|e| {
+            JsonOutputError::Invalid {
+                attempts: 0,
+                error: format!("schema: {e}"),
+                raw: String::new(),
+            }
+        })?;
+        let system = format!(
+            "{system}\n\nReply with a single JSON value matching this JSON Schema and nothing \
+             else:\n{schema}"
+        );
+        let mut request = prompt.to_string();
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let routed = self
+                .route(
+                    class,
+                    &system,
+                    &request,
+                    max_tokens,
+                    OutputMode::Json,
+                    CallOptions::default(),
+                )
+                .await?;
+            let raw = routed.generation.text;
+            let error = match parse_reply::<T>(&raw) {
+                Ok(value) => {
+                    return Ok(JsonCompletion {
+                        value,
+                        backend: routed.backend,
+                        model: routed.model,
+                        repairs: attempts - 1,
+                    })
+                }
+                Err(error) => error,
+            };
+            if attempts > max_repairs {
+                return Err(JsonOutputError::Invalid {
+                    attempts,
+                    error,
+                    raw,
+                });
+            }
+            tracing::debug!(
+                "LLM JSON reply from {} rejected ({}), asking for a repair",
+                routed.backend,
+                error
+            );
+            request = format!(
+                "{prompt}\n\nYour previous reply was:\n{raw}\n\nIt was rejected: {error}\n\
+                 Reply with only the corrected JSON."
+            );
+        }
+    }
+}
+
+fn parse_reply<T: DeserializeOwned>(text: &str) -> Result<T, String> {
+    let json = extract_json(text).ok_or("no JSON object or array in the reply")?;
+    serde_json::from_str(json).map_err(|e| e.to_string())
+}
+
+/// The outermost JSON object or array in `text`, dropping any prose or code
+/// fence around it.
+fn extract_json(text: &str) -> Option<&str> {
+    let start = text.find(['{', '['])?;
+    let close = if text[start..].starts_with('{') {
+        '}'
+    } else {
+        ']'
+    };
+    let end = text.rfind(close)?;
+    (end > start).then(|| &text[start..=end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::{AnswerModel, Generation};
+    use async_trait::async_trait;
+    use serde::Deserialize;
+    use std::sync::Mutex;
+    use tokio::sync::mpsc;
+
+    #[derive(Debug, PartialEq, Deserialize, JsonSchema)]
+    struct Relation {
+        subject: String,
+        predicate: String,
+        object: String,
+        confidence: f64,
+    }
+
+    /// Local backend that replies from a script and records its prompts.
+    struct Scripted {
+        replies: Mutex<Vec<&'static str>>,
+        prompts: Mutex<Vec<(String, String)>>,
+    }
+
+    impl Scripted {
+        fn new(replies: &[&'static str]) -> std::sync::Arc<Self> {
+            std::sync::Arc::new(Self {
+                replies: Mutex::new(replies.iter().rev().copied().collect()),
+                prompts: Mutex::default(),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl AnswerModel for Scripted {
+        fn model_name(&self) -> &str {
+            "mock"
+        }
+
+        fn backend(&self) -> &str {
+            "ollama"
+        }
+
+        fn is_local(&self) -> bool {
+            true
+        }
+
+        async fn generate(
+            &self,
+            _system: &str,
+            _prompt: &str,
+            _max_tokens: u32,
+            _tokens: &mpsc::UnboundedSender<String>,
+        ) -> anyhow::Result<Generation> {
+            anyhow::bail!("extraction calls must use JSON mode")
+        }
+
+        async fn generate_json(
+            &self,
+            system: &str,
+            prompt: &str,
+            _max_tokens: u32,
+        ) -> anyhow::Result<Generation> {
+            self.prompts
+                .lock()
+                .unwrap()
+                .push((system.to_string(), prompt.to_string()));
+            let reply = self.replies.lock().unwrap().pop().unwrap_or("");
+            Ok(Generation {
+                text: reply.to_string(),
+                ..Generation::default()
+            })
+        }
+    }
+
+    const VALID: &str =
+        r#"{"subject":"KRAS","predicate":"inhibited_by","object":"sotorasib","confidence":0.9}"#;
+
+    async fn extract(
+        model: &std::sync::Arc<Scripted>,
+    ) -> Result<JsonCompletion<Relation>, JsonOutputError> {
+        LlmRouter::new(model.clone())
+            .complete_json::<Relation>(
+                DataClass::Confidential,
+                "Extract one relation.",
+                "Sotorasib inhibits KRAS G12C.",
+                256,
+                DEFAULT_MAX_REPAIRS,
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn valid_output_is_parsed_on_the_first_attempt() {
+        let model = Scripted::new(&[
+            "Here you go:\n```json\n{\"subject\":\"KRAS\",\"predicate\":\"inhibited_by\",\
+             \"object\":\"sotorasib\",\"confidence\":0.9}\n```",
+        ]);
+        let out = extract(&model).await.unwrap();
+        assert_eq!(out.value, serde_json::from_str::<Relation>(VALID).unwrap());
+        assert_eq!((out.backend.as_str(), out.repairs), ("ollama", 0));
+
+        let prompts = model.prompts.lock().unwrap();
+        assert_eq!(prompts.len(), 1);
+        assert!(prompts[0].0.contains("\"confidence\""));
+        assert_eq!(prompts[0].1, "Sotorasib inhibits KRAS G12C.");
+    }
+
+    #[tokio::test]
+    async fn invalid_output_is_repaired_with_the_error_fed_back() {
+        let bad = r#"{"subject":"KRAS","predicate":"inhibited_by","object":"sotorasib"}"#;
+        let model = Scripted::new(&[bad, VALID]);
+        let out = extract(&model).await.unwrap();
+        assert_eq!(out.value.object, "sotorasib");
+        assert_eq!(out.repairs, 1);
+
+        let prompts = model.prompts.lock().unwrap();
+        assert_eq!(prompts.len(), 2);
+        assert!(prompts[1].1.contains(bad));
+        assert!(prompts[1].1.contains("missing field `confidence`"));
+    }
+
+    #[tokio::test]
+    async fn unrepairable_output_errors_with_the_raw_reply() {
+        let model = Scripted::new(&["not json", "still not json", "I cannot comply."]);
+        let err = extract(&model).await.unwrap_err();
+        let JsonOutputError::Invalid {
+            attempts,
+            error,
+            raw,
+        } = &err
+        else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(*attempts, DEFAULT_MAX_REPAIRS + 1);
+        assert_eq!(error, "no JSON object or array in the reply");
+        assert_eq!(raw, "I cannot comply.");
+        assert_eq!(model.prompts.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn json_is_extracted_from_prose_and_fences() {
+        assert_eq!(extract_json("```json\n[1, 2]\n```"), Some("[1, 2]"));
+        assert_eq!(
+            extract_json("Sure! {\"a\": {\"b\": 1}} Hope that helps."),
+            Some("{\"a\": {\"b\": 1}}")
+        );
+        assert_eq!(extract_json("no braces"), None);
+        assert_eq!(extract_json("} backwards {"), None);
+    }
+}
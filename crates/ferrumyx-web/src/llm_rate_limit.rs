@@ -0,0 +1,213 @@
+//! Per-backend request rate limits for [`LlmRouter`](crate::llm::LlmRouter)
+//! calls, from `[llm.rate_limits]`.
+//!
+//! Each backend has a token bucket refilled at its requests-per-minute rate
+//! with room for one request, so calls are spaced evenly instead of
+//! bursting into a provider's 429s. A call waits for its slot unless the
+//! wait would exceed the configured maximum queue delay, in which case it
+//! fails straight away with [`RateLimited`].
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::time::Instant;
+
+/// Requests-per-minute caps, from `[llm.rate_limits]`.
+#[derive(Debug, Clone)]
+pub struct RateLimits {
+    /// Cap per backend name; backends not listed are unlimited.
+    pub rpm: HashMap<String, u32>,
+    /// Longest a call may wait for its slot before failing.
+    pub max_queue_delay: Duration,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self {
+            rpm: HashMap::new(),
+            max_queue_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RateLimits {
+    pub fn with_rpm(mut self, backend: &str, rpm: u32) -> Self {
+        self.rpm.insert(backend.to_string(), rpm);
+        self
+    }
+
+    pub fn with_max_queue_delay(mut self, delay: Duration) -> Self {
+        self.max_queue_delay = delay;
+        self
+    }
+}
+
+/// The next free slot is further away than the maximum queue delay.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("{backend}: rate limited at {rpm} requests/min (next slot in {}ms, max queue delay {}ms)", .wait.as_millis(), .max_queue_delay.as_millis())]
+pub struct RateLimited {
+    pub backend: String,
+    pub rpm: u32,
+    pub wait: Duration,
+    pub max_queue_delay: Duration,
+}
+
+/// One backend's limiter, for the metrics endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RateLimitStatus {
+    pub backend: String,
+    pub rpm: u32,
+    /// Calls waiting for a slot right now.
+    pub queued: usize,
+}
+
+struct Bucket {
+    rpm: u32,
+    interval: Duration,
+    /// When the next request may start.
+    next_slot: Mutex<Option<Instant>>,
+    queued: AtomicUsize,
+}
+
+impl Bucket {
+    fn new(rpm: u32) -> Self {
+        Self {
+            rpm,
+            interval: Duration::from_secs(60) / rpm,
+            next_slot: Mutex::new(None),
+            queued: AtomicUsize::new(0),
+        }
+    }
+}
+
+/// Keeps [`RateLimiter::acquire`]'s queue depth right when a waiting call
+/// is cancelled.
+struct Queued<'a>(&'a AtomicUsize);
+
+impl Drop for Queued<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Token buckets for every rate-limited backend, shared by every clone of a
+/// router.
+pub struct RateLimiter {
+    buckets: HashMap<String, Bucket>,
+    max_queue_delay: Duration,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(RateLimits::default())
+    }
+}
+
+impl RateLimiter {
+    /// A zero rpm leaves the backend unlimited.
+    pub fn new(limits: RateLimits) -> Self {
+        Self {
+            buckets: limits
+                .rpm
+                .into_iter()
+                .filter(|(_, rpm)| *rpm > 0)
+                .map(|(backend, rpm)| (backend, Bucket::new(rpm)))
+                .collect(),
+            max_queue_delay: limits.max_queue_delay,
+        }
+    }
+
+    /// Wait for `backend`'s next slot, or fail if it is too far away.
+    pub async fn acquire(&self, backend: &str) -> Result<(), RateLimited> {
+        let Some(bucket) = self.buckets.get(backend) else {
+            return Ok(());
+        };
+        let now = Instant::now();
+        let slot = {
+            let mut next_slot = bucket.next_slot.lock().unwrap_or_else(|e| e.into_inner());
+            let slot = next_slot.map_or(now, |next| next.max(now));
+            let wait = slot - now;
+            if wait > self.max_queue_delay {
+                return Err(RateLimited {
+                    backend: backend.to_string(),
+                    rpm: bucket.rpm,
+                    wait,
+                    max_queue_delay: self.max_queue_delay,
+                });
+            }
+            *next_slot = Some(slot + bucket.interval);
+            slot
+        };
+        if slot > now {
+            bucket.queued.fetch_add(1, Ordering::SeqCst);
+            let _queued = Queued(&bucket.queued);
+            tokio::time::sleep_until(slot).await;
+        }
+        Ok(())
+    }
+
+    /// Every rate-limited backend, by name.
+    pub fn status(&self) -> Vec<RateLimitStatus> {
+        let mut status: Vec<RateLimitStatus> = self
+            .buckets
+            .iter()
+            .map(|(backend, bucket)| RateLimitStatus {
+                backend: backend.clone(),
+                rpm: bucket.rpm,
+                queued: bucket.queued.load(Ordering::SeqCst),
+            })
+            .collect();
+        status.sort_by(|a, b| a.backend.cmp(&b.backend));
+        status
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test(start_paused = true)]
+    async fn calls_past_the_max_queue_delay_fail_fast() {
+        let limiter = Arc::new(RateLimiter::new(
+            RateLimits::default()
+                .with_rpm("openai", 60)
+                .with_rpm("ollama", 0)
+                .with_max_queue_delay(Duration::from_secs(2)),
+        ));
+
+        // Slots at 0s, 1s and 2s fit in the queue; the fourth would wait 3s.
+        let start = Instant::now();
+        let waiting: Vec<_> = (0..3)
+            .map(|_| {
+                let limiter = limiter.clone();
+                tokio::spawn(async move { limiter.acquire("openai").await })
+            })
+            .collect();
+        tokio::task::yield_now().await;
+        assert_eq!(limiter.status()[0].queued, 2);
+        let err = limiter.acquire("openai").await.unwrap_err();
+        assert_eq!(err.wait, Duration::from_secs(3));
+        assert!(err.to_string().contains("rate limited at 60 requests/min"));
+        assert!(limiter.acquire("ollama").await.is_ok());
+        assert!(limiter.acquire("gemini").await.is_ok());
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        for call in waiting {
+            call.await.unwrap().unwrap();
+        }
+        assert!(start.elapsed() >= Duration::from_secs(2));
+        assert!(start.elapsed() < Duration::from_millis(2100));
+        assert_eq!(
+            limiter.status(),
+            vec![RateLimitStatus {
+                backend: "openai".to_string(),
+                rpm: 60,
+                queued: 0,
+            }]
+        );
+    }
+}
@@ -0,0 +1,631 @@
+//! Token streaming from remote and local chat APIs for the answer router.
+//!
+//! [`StreamingAnswerModel`] speaks each provider's streaming wire format
+//! directly, so answers reach the query page token by token whatever the
+//! backend:
+//!
+//! - OpenAI and OpenAI-compatible: `/chat/completions` server-sent events,
+//!   ending with `[DONE]`; usage arrives in a final chunk.
+//! - Anthropic: `/v1/messages` events (`message_start`,
+//!   `content_block_delta`, `message_delta`, `message_stop`).
+//! - Gemini: `:streamGenerateContent?alt=sse`, usage repeated per chunk.
+//! - Ollama: `/api/chat` newline-delimited JSON.
+//!
+//! Network reads split events anywhere, including mid-JSON, so bytes are
+//! buffered by [`StreamDecoder`] until a whole event or line is in.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde_json::Value;
+use tokio::sync::mpsc;
+
+use crate::llm::{AnswerModel, Generation};
+
+/// Which streaming protocol a backend speaks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WireFormat {
+    OpenAi,
+    Anthropic,
+    Gemini,
+    OllamaChat,
+}
+
+impl WireFormat {
+    pub fn default_base_url(self) -> &'static str {
+        match self {
+            Self::OpenAi => "https://api.openai.com/v1",
+            Self::Anthropic => "https://api.anthropic.com",
+            Self::Gemini => "https://generativelanguage.googleapis.com",
+            Self::OllamaChat => "http://localhost:11434",
+        }
+    }
+}
+
+/// One decoded piece of a stream.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TokenChunk {
+    pub text: String,
+    /// Set by the chunk that reports it; later reports replace earlier ones.
+    pub prompt_tokens: Option<u32>,
+    pub completion_tokens: Option<u32>,
+    /// The provider signalled the end of the stream.
+    pub done: bool,
+}
+
+/// Splits a byte stream into the `data` payloads of its server-sent events.
+#[derive(Debug, Default)]
+struct SseDecoder {
+    pending: Vec<u8>,
+    data: Vec<String>,
+}
+
+impl SseDecoder {
+    fn push(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.pending.extend_from_slice(bytes);
+        let mut events = Vec::new();
+        while let Some(end) = self.pending.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\n', '\r']);
+            if line.is_empty() {
+                if !self.data.is_empty() {
+                    events.push(std::mem::take(&mut self.data).join("\n"));
+                }
+            } else if let Some(value) = line.strip_prefix("data:") {
+                self.data
+                    .push(value.strip_prefix(' ').unwrap_or(value).to_string());
+            }
+            // `event:`, `id:`, `retry:` and `:` comments carry nothing the
+            // payloads do not.
+        }
+        events
+    }
+
+    /// Events left open when the connection closed.
+    fn finish(&mut self) -> Vec<String> {
+        let mut events = Vec::new();
+        if !self.pending.is_empty() {
+            let mut rest = std::mem::take(&mut self.pending);
+            rest.push(b'\n');
+            events = self.push(&rest);
+        }
+        if !self.data.is_empty() {
+            events.push(std::mem::take(&mut self.data).join("\n"));
+        }
+        events
+    }
+}
+
+/// Turns raw response bytes into [`TokenChunk`]s for one wire format.
+#[derive(Debug)]
+pub struct StreamDecoder {
+    format: WireFormat,
+    sse: SseDecoder,
+    /// Unfinished NDJSON line, for Ollama.
+    pending: Vec<u8>,
+}
+
+impl StreamDecoder {
+    pub fn new(format: WireFormat) -> Self {
+        Self {
+            format,
+            sse: SseDecoder::default(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Feed the next read; returns every chunk it completed.
+    pub fn push(&mut self, bytes: &[u8]) -> anyhow::Result<Vec<TokenChunk>> {
+        if self.format == WireFormat::OllamaChat {
+            self.pending.extend_from_slice(bytes);
+            let mut chunks = Vec::new();
+            while let Some(end) = self.pending.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = self.pending.drain(..=end).collect();
+                if !line.iter().all(u8::is_ascii_whitespace) {
+                    chunks.push(parse_ollama_line(&line)?);
+                }
+            }
+            return Ok(chunks);
+        }
+        self.sse
+            .push(bytes)
+            .iter()
+            .filter_map(|data| self.parse_event(data).transpose())
+            .collect()
+    }
+
+    /// Flush whatever the connection left unterminated.
+    pub fn finish(&mut self) -> anyhow::Result<Vec<TokenChunk>> {
+        if self.format == WireFormat::OllamaChat {
+            let line = std::mem::take(&mut self.pending);
+            if line.iter().all(u8::is_ascii_whitespace) {
+                return Ok(Vec::new());
+            }
+            return Ok(vec![parse_ollama_line(&line)?]);
+        }
+        self.sse
+            .finish()
+            .iter()
+            .filter_map(|data| self.parse_event(data).transpose())
+            .collect()
+    }
+
+    fn parse_event(&self, data: &str) -> anyhow::Result<Option<TokenChunk>> {
+        if data.trim() == "[DONE]" {
+            return Ok(Some(TokenChunk {
+                done: true,
+                ..TokenChunk::default()
+            }));
+        }
+        let event: Value = serde_json::from_str(data)
+            .map_err(|e| anyhow::anyhow!("malformed stream event ({e}): {data}"))?;
+        if event.get("error").is_some_and(|e| !e.is_null()) {
+            anyhow::bail!("stream error: {}", error_message(&event["error"]));
+        }
+        Ok(match self.format {
+            WireFormat::OpenAi => Some(parse_openai_event(&event)),
+            WireFormat::Anthropic => parse_anthropic_event(&event),
+            WireFormat::Gemini => Some(parse_gemini_event(&event)),
+            WireFormat::OllamaChat => None,
+        })
+    }
+}
+
+fn error_message(error: &Value) -> String {
+    error
+        .get("message")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| error.to_string())
+}
+
+fn count(value: &Value) -> Option<u32> {
+    value.as_u64().map(|n| n.min(u32::MAX as u64) as u32)
+}
+
+fn parse_openai_event(event: &Value) -> TokenChunk {
+    let choice = &event["choices"][0];
+    TokenChunk {
+        text: choice["delta"]["content"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        prompt_tokens: count(&event["usage"]["prompt_tokens"]),
+        completion_tokens: count(&event["usage"]["completion_tokens"]),
+        done: false,
+    }
+}
+
+fn parse_anthropic_event(event: &Value) -> Option<TokenChunk> {
+    match event["type"].as_str()? {
+        "message_start" => Some(TokenChunk {
+            prompt_tokens: count(&event["message"]["usage"]["input_tokens"]),
+            ..TokenChunk::default()
+        }),
+        "content_block_delta" => Some(TokenChunk {
+            text: event["delta"]["text"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            ..TokenChunk::default()
+        }),
+        "message_delta" => Some(TokenChunk {
+            completion_tokens: count(&event["usage"]["output_tokens"]),
+            ..TokenChunk::default()
+        }),
+        "message_stop" => Some(TokenChunk {
+            done: true,
+            ..TokenChunk::default()
+        }),
+        // ping, content_block_start, content_block_stop
+        _ => None,
+    }
+}
+
+fn parse_gemini_event(event: &Value) -> TokenChunk {
+    let text = event["candidates"][0]["content"]["parts"]
+        .as_array()
+        .map(|parts| {
+            parts
+                .iter()
+                .filter_map(|p| p["text"].as_str())
+                .collect::<String>()
+        })
+        .unwrap_or_default();
+    let usage = &event["usageMetadata"];
+    TokenChunk {
+        text,
+        prompt_tokens: count(&usage["promptTokenCount"]),
+        completion_tokens: count(&usage["candidatesTokenCount"]),
+        done: false,
+    }
+}
+
+fn parse_ollama_line(line: &[u8]) -> anyhow::Result<TokenChunk> {
+    let event: Value = serde_json::from_slice(line).map_err(|e| {
+        anyhow::anyhow!(
+            "malformed stream line ({e}): {}",
+            String::from_utf8_lossy(line)
+        )
+    })?;
+    if let Some(error) = event["error"].as_str() {
+        anyhow::bail!("stream error: {error}");
+    }
+    let done = event["done"].as_bool().unwrap_or(false);
+    Ok(TokenChunk {
+        text: event["message"]["content"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        prompt_tokens: done.then(|| count(&event["prompt_eval_count"])).flatten(),
+        completion_tokens: done.then(|| count(&event["eval_count"])).flatten(),
+        done,
+    })
+}
+
+/// Read a streaming response to the end, sending text to `tokens` as it
+/// arrives.
+pub async fn read_stream(
+    mut response: reqwest::Response,
+    format: WireFormat,
+    tokens: &mpsc::UnboundedSender<String>,
+) -> anyhow::Result<Generation> {
+    let mut decoder = StreamDecoder::new(format);
+    let mut out = Generation::default();
+    let mut apply = |chunks: Vec<TokenChunk>| {
+        for chunk in chunks {
+            if !chunk.text.is_empty() {
+                let _ = tokens.send(chunk.text.clone());
+                out.text.push_str(&chunk.text);
+            }
+            out.prompt_tokens = chunk.prompt_tokens.or(out.prompt_tokens);
+            out.completion_tokens = chunk.completion_tokens.or(out.completion_tokens);
+        }
+    };
+    while let Some(bytes) = response.chunk().await? {
+        apply(decoder.push(&bytes)?);
+    }
+    apply(decoder.finish()?);
+    Ok(out)
+}
+
+/// Answer backend over a provider's streaming HTTP API.
+pub struct StreamingAnswerModel {
+    backend: String,
+    format: WireFormat,
+    base_url: String,
+    api_key: String,
+    model: String,
+    local: bool,
+    cost_per_token: (f64, f64),
+    client: reqwest::Client,
+}
+
+impl StreamingAnswerModel {
+    /// `backend` is the router name ("openai", "anthropic", "gemini",
+    /// "openai_compatible" or "ollama"); an empty `base_url` uses the
+    /// format's public endpoint.
+    pub fn new(
+        backend: &str,
+        format: WireFormat,
+        base_url: &str,
+        api_key: &str,
+        model: &str,
+    ) -> Self {
+        let base_url = if base_url.trim().is_empty() {
+            format.default_base_url()
+        } else {
+            base_url.trim()
+        };
+        Self {
+            backend: backend.to_string(),
+            format,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+            model: model.to_string(),
+            local: false,
+            cost_per_token: (0.0, 0.0),
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(300))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn with_local(mut self, local: bool) -> Self {
+        self.local = local;
+        self
+    }
+
+    pub fn with_cost_per_token(mut self, prompt_usd: f64, completion_usd: f64) -> Self {
+        self.cost_per_token = (prompt_usd, completion_usd);
+        self
+    }
+
+    fn request(
+        &self,
+        system: &str,
+        prompt: &str,
+        max_tokens: u32,
+        json: bool,
+    ) -> reqwest::RequestBuilder {
+        let temperature = if json { 0.0 } else { 0.1 };
+        let mut messages = Vec::new();
+        if !system.is_empty() {
+            messages.push(serde_json::json!({ "role": "system", "content": system }));
+        }
+        messages.push(serde_json::json!({ "role": "user", "content": prompt }));
+        match self.format {
+            WireFormat::OpenAi => {
+                let mut body = serde_json::json!({
+                    "model": self.model,
+                    "messages": messages,
+                    "max_tokens": max_tokens,
+                    "temperature": temperature,
+                    "stream": true,
+                    "stream_options": { "include_usage": true },
+                });
+                if json {
+                    body["response_format"] = serde_json::json!({ "type": "json_object" });
+                }
+                self.client
+                    .post(format!("{}/chat/completions", self.base_url))
+                    .bearer_auth(&self.api_key)
+                    .json(&body)
+            }
+            WireFormat::Anthropic => {
+                let mut body = serde_json::json!({
+                    "model": self.model,
+                    "messages": [{ "role": "user", "content": prompt }],
+                    "max_tokens": max_tokens,
+                    "temperature": temperature,
+                    "stream": true,
+                });
+                if !system.is_empty() {
+                    body["system"] = Value::from(system);
+                }
+                self.client
+                    .post(format!("{}/v1/messages", self.base_url))
+                    .header("x-api-key", &self.api_key)
+                    .header("anthropic-version", "2023-06-01")
+                    .json(&body)
+            }
+            WireFormat::Gemini => {
+                let mut body = serde_json::json!({
+                    "contents": [{ "role": "user", "parts": [{ "text": prompt }] }],
+                    "generationConfig": {
+                        "maxOutputTokens": max_tokens,
+                        "temperature": temperature,
+                    },
+                });
+                if !system.is_empty() {
+                    body["systemInstruction"] =
+                        serde_json::json!({ "parts": [{ "text": system }] });
+                }
+                if json {
+                    body["generationConfig"]["responseMimeType"] = Value::from("application/json");
+                }
+                self.client
+                    .post(format!(
+                        "{}/v1beta/models/{}:streamGenerateContent?alt=sse",
+                        self.base_url, self.model
+                    ))
+                    .header("x-goog-api-key", &self.api_key)
+                    .json(&body)
+            }
+            WireFormat::OllamaChat => {
+                let mut body = serde_json::json!({
+                    "model": self.model,
+                    "messages": messages,
+                    "stream": true,
+                    "options": { "temperature": temperature, "num_predict": max_tokens },
+                });
+                if json {
+                    body["format"] = Value::from("json");
+                }
+                self.client
+                    .post(format!("{}/api/chat", self.base_url))
+                    .json(&body)
+            }
+        }
+    }
+
+    async fn stream(
+        &self,
+        request: reqwest::RequestBuilder,
+        tokens: &mpsc::UnboundedSender<String>,
+    ) -> anyhow::Result<Generation> {
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("{status}: {}", body.chars().take(300).collect::<String>());
+        }
+        read_stream(response, self.format, tokens).await
+    }
+}
+
+#[async_trait]
+impl AnswerModel for StreamingAnswerModel {
+    fn model_name(&self) -> &str {
+        &self.model
+    }
+
+    fn backend(&self) -> &str {
+        &self.backend
+    }
+
+    fn is_local(&self) -> bool {
+        self.local
+    }
+
+    fn endpoint(&self) -> Option<&str> {
+        Some(&self.base_url)
+    }
+
+    fn cost_per_token(&self) -> (f64, f64) {
+        self.cost_per_token
+    }
+
+    async fn generate(
+        &self,
+        system: &str,
+        prompt: &str,
+        max_tokens: u32,
+        tokens: &mpsc::UnboundedSender<String>,
+    ) -> anyhow::Result<Generation> {
+        self.stream(self.request(system, prompt, max_tokens, false), tokens)
+            .await
+    }
+
+    /// JSON mode where the API has one: `response_format` on OpenAI,
+    /// `responseMimeType` on Gemini, `format` on Ollama. Anthropic relies on
+    /// the prompt.
+    async fn generate_json(
+        &self,
+        system: &str,
+        prompt: &str,
+        max_tokens: u32,
+    ) -> anyhow::Result<Generation> {
+        let (tokens, _) = mpsc::unbounded_channel();
+        self.stream(self.request(system, prompt, max_tokens, true), &tokens)
+            .await
+    }
+
+    async fn probe(&self) -> anyhow::Result<()> {
+        if self.format == WireFormat::OllamaChat {
+            self.client
+                .get(format!("{}/api/tags", self.base_url))
+                .timeout(Duration::from_secs(5))
+                .send()
+                .await?
+                .error_for_status()?;
+            return Ok(());
+        }
+        let (tokens, _) = mpsc::unbounded_channel();
+        self.generate("", "ping", 1, &tokens).await.map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decode `fixture` fed in reads of `split` bytes, so events and JSON
+    /// objects straddle read boundaries.
+    fn decode(format: WireFormat, fixture: &str, split: usize) -> Generation {
+        let mut decoder = StreamDecoder::new(format);
+        let mut chunks = Vec::new();
+        for piece in fixture.as_bytes().chunks(split) {
+            chunks.extend(decoder.push(piece).unwrap());
+        }
+        chunks.extend(decoder.finish().unwrap());
+        let mut out = Generation::default();
+        for chunk in chunks {
+            out.text.push_str(&chunk.text);
+            out.prompt_tokens = chunk.prompt_tokens.or(out.prompt_tokens);
+            out.completion_tokens = chunk.completion_tokens.or(out.completion_tokens);
+        }
+        out
+    }
+
+    fn assert_decodes(format: WireFormat, fixture: &str, text: &str, usage: (u32, u32)) {
+        for split in [1, 3, 7, 64, fixture.len()] {
+            let out = decode(format, fixture, split);
+            assert_eq!(out.text, text, "{format:?} split {split}");
+            assert_eq!(
+                (out.prompt_tokens, out.completion_tokens),
+                (Some(usage.0), Some(usage.1)),
+                "{format:?} split {split}"
+            );
+        }
+    }
+
+    const OPENAI: &str = concat!(
+        "data: {\"id\":\"c1\",\"choices\":[{\"index\":0,\"delta\":{\"role\":\"assistant\",\"content\":\"\"}}]}\n\n",
+        "data: {\"id\":\"c1\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\"KRAS \"}}]}\n\n",
+        ": keep-alive\n\n",
+        "data: {\"id\":\"c1\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\"G12C [1]\"},\"finish_reason\":\"stop\"}]}\n\n",
+        "data: {\"id\":\"c1\",\"choices\":[],\"usage\":{\"prompt_tokens\":812,\"completion_tokens\":5}}\n\n",
+        "data: [DONE]\n\n",
+    );
+
+    const ANTHROPIC: &str = concat!(
+        "event: message_start\r\n",
+        "data: {\"type\":\"message_start\",\"message\":{\"id\":\"m1\",\"usage\":{\"input_tokens\":640,\"output_tokens\":1}}}\r\n\r\n",
+        "event: content_block_start\r\n",
+        "data: {\"type\":\"content_block_start\",\"index\":0,\"content_block\":{\"type\":\"text\",\"text\":\"\"}}\r\n\r\n",
+        "event: ping\r\n",
+        "data: {\"type\":\"ping\"}\r\n\r\n",
+        "event: content_block_delta\r\n",
+        "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"Sotorasib \\u00e9\"}}\r\n\r\n",
+        "event: content_block_delta\r\n",
+        "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"tude [2]\"}}\r\n\r\n",
+        "event: message_delta\r\n",
+        "data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"end_turn\"},\"usage\":{\"output_tokens\":9}}\r\n\r\n",
+        "event: message_stop\r\n",
+        "data: {\"type\":\"message_stop\"}\r\n\r\n",
+    );
+
+    const GEMINI: &str = concat!(
+        "data: {\"candidates\":[{\"content\":{\"parts\":[{\"text\":\"Adagrasib\"}],\"role\":\"model\"}}],\"usageMetadata\":{\"promptTokenCount\":700,\"candidatesTokenCount\":2}}\r\n\r\n",
+        "data: {\"candidates\":[{\"content\":{\"parts\":[{\"text\":\" binds \"},{\"text\":\"G12C\"}],\"role\":\"model\"},\"finishReason\":\"STOP\"}],\"usageMetadata\":{\"promptTokenCount\":700,\"candidatesTokenCount\":6}}\r\n\r\n",
+    );
+
+    // Multi-byte UTF-8 ("µM") so byte-wise splits land inside a character.
+    const OLLAMA: &str = concat!(
+        "{\"model\":\"llama3.1:8b\",\"message\":{\"role\":\"assistant\",\"content\":\"IC50 \"},\"done\":false}\n",
+        "{\"model\":\"llama3.1:8b\",\"message\":{\"role\":\"assistant\",\"content\":\"3 µM\"},\"done\":false}\n",
+        "{\"model\":\"llama3.1:8b\",\"message\":{\"role\":\"assistant\",\"content\":\"\"},\"done\":true,\"prompt_eval_count\":512,\"eval_count\":4}",
+    );
+
+    #[test]
+    fn openai_stream_decodes_across_read_boundaries() {
+        assert_decodes(WireFormat::OpenAi, OPENAI, "KRAS G12C [1]", (812, 5));
+    }
+
+    #[test]
+    fn anthropic_stream_decodes_across_read_boundaries() {
+        assert_decodes(
+            WireFormat::Anthropic,
+            ANTHROPIC,
+            "Sotorasib étude [2]",
+            (640, 9),
+        );
+    }
+
+    #[test]
+    fn gemini_stream_decodes_across_read_boundaries() {
+        assert_decodes(WireFormat::Gemini, GEMINI, "Adagrasib binds G12C", (700, 6));
+    }
+
+    #[test]
+    fn ollama_chat_stream_decodes_across_read_boundaries() {
+        // The last line has no trailing newline and is flushed by finish().
+        assert_decodes(WireFormat::OllamaChat, OLLAMA, "IC50 3 µM", (512, 4));
+    }
+
+    #[test]
+    fn stream_errors_and_malformed_events_fail() {
+        let mut anthropic = StreamDecoder::new(WireFormat::Anthropic);
+        let err = anthropic
+            .push(b"event: error\ndata: {\"type\":\"error\",\"error\":{\"type\":\"overloaded_error\",\"message\":\"Overloaded\"}}\n\n")
+            .unwrap_err();
+        assert!(err.to_string().contains("Overloaded"));
+
+        let mut openai = StreamDecoder::new(WireFormat::OpenAi);
+        assert!(openai
+            .push(b"data: {\"choices\":[{\"delta\":")
+            .unwrap()
+            .is_empty());
+        assert!(openai.finish().is_err());
+
+        let mut ollama = StreamDecoder::new(WireFormat::OllamaChat);
+        assert!(ollama
+            .push(b"{\"error\":\"model 'x' not found\"}\n")
+            .unwrap_err()
+            .to_string()
+            .contains("not found"));
+    }
+}
@@ -0,0 +1,369 @@
+//! Daily token and cost accounting for [`LlmRouter`](crate::llm::LlmRouter)
+//! calls, enforcing the `[llm.limits]` budgets.
+//!
+//! Usage is counted per backend per UTC day. A remote call that would go
+//! over its backend's token cap, or any remote call once the daily cost cap
+//! is spent, is refused so the router can fall back to a local backend.
+//! Local backends are never capped.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+use chrono::NaiveDate;
+use ferrumyx_db::{LlmUsageRecord, LlmUsageRepository};
+use serde::Serialize;
+
+use crate::llm_cache::CacheStats;
+
+/// Daily caps, from `[llm.limits]`.
+#[derive(Debug, Clone, Default)]
+pub struct BudgetLimits {
+    /// Token cap per backend name; backends not listed are uncapped.
+    pub max_tokens_per_day: HashMap<String, u64>,
+    /// Spend cap across all backends, in USD.
+    pub max_cost_per_day_usd: Option<f64>,
+    /// Spend at which a warning event is raised, in USD.
+    pub alert_cost_threshold_usd: Option<f64>,
+}
+
+impl BudgetLimits {
+    pub fn with_token_limit(mut self, backend: &str, tokens: u64) -> Self {
+        self.max_tokens_per_day.insert(backend.to_string(), tokens);
+        self
+    }
+
+    pub fn with_cost_limit(mut self, usd: f64) -> Self {
+        self.max_cost_per_day_usd = Some(usd);
+        self
+    }
+
+    pub fn with_cost_alert(mut self, usd: f64) -> Self {
+        self.alert_cost_threshold_usd = Some(usd);
+        self
+    }
+}
+
+/// Why a call was refused.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum BudgetDenial {
+    #[error("{backend}: call would exceed the daily token limit ({used} of {limit} used)")]
+    Tokens {
+        backend: String,
+        used: u64,
+        limit: u64,
+    },
+    #[error("{backend}: daily cost limit reached (${spent_usd:.2} of ${limit_usd:.2})")]
+    Cost {
+        backend: String,
+        spent_usd: f64,
+        limit_usd: f64,
+    },
+}
+
+/// Today's spend crossed `alert_cost_threshold_usd`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct BudgetAlert {
+    pub spent_usd: f64,
+    pub threshold_usd: f64,
+    pub limit_usd: Option<f64>,
+}
+
+/// One backend's usage today.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct BackendUsage {
+    pub backend: String,
+    /// Last model the backend served.
+    pub model: String,
+    pub calls: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub cost_usd: f64,
+    pub token_limit: Option<u64>,
+}
+
+impl BackendUsage {
+    fn total_tokens(&self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+}
+
+/// Today's totals, for `GET /api/llm/usage`.
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageSummary {
+    pub day: NaiveDate,
+    pub backends: Vec<BackendUsage>,
+    pub total_tokens: u64,
+    pub total_cost_usd: f64,
+    pub max_cost_per_day_usd: Option<f64>,
+    pub alert_cost_threshold_usd: Option<f64>,
+    /// Response cache counters since start-up; absent when the cache is off.
+    /// Cache hits are not in the token and cost totals.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache: Option<CacheStats>,
+}
+
+#[derive(Debug)]
+struct DailyUsage {
+    day: NaiveDate,
+    backends: HashMap<String, BackendUsage>,
+    /// The cost alert already fired today.
+    alerted: bool,
+}
+
+impl DailyUsage {
+    fn new(day: NaiveDate) -> Self {
+        Self {
+            day,
+            backends: HashMap::new(),
+            alerted: false,
+        }
+    }
+
+    fn cost_usd(&self) -> f64 {
+        self.backends.values().map(|u| u.cost_usd).sum()
+    }
+
+    fn add(&mut self, backend: &str, model: &str, prompt: u64, completion: u64, cost_usd: f64) {
+        let usage = self
+            .backends
+            .entry(backend.to_string())
+            .or_insert_with(|| BackendUsage {
+                backend: backend.to_string(),
+                ..BackendUsage::default()
+            });
+        usage.model = model.to_string();
+        usage.calls += 1;
+        usage.prompt_tokens += prompt;
+        usage.completion_tokens += completion;
+        usage.cost_usd += cost_usd;
+    }
+}
+
+/// Per-day usage counters shared by every clone of a router, optionally
+/// persisted to the `llm_usage` table.
+pub struct UsageTracker {
+    limits: BudgetLimits,
+    today: Mutex<DailyUsage>,
+    repo: Option<LlmUsageRepository>,
+}
+
+impl Default for UsageTracker {
+    fn default() -> Self {
+        Self::new(BudgetLimits::default())
+    }
+}
+
+impl UsageTracker {
+    pub fn new(limits: BudgetLimits) -> Self {
+        Self {
+            limits,
+            today: Mutex::new(DailyUsage::new(chrono::Utc::now().date_naive())),
+            repo: None,
+        }
+    }
+
+    /// Persist every call so the budgets survive restarts; see
+    /// [`Self::restore`].
+    pub fn with_repository(mut self, repo: LlmUsageRepository) -> Self {
+        self.repo = Some(repo);
+        self
+    }
+
+    pub fn limits(&self) -> &BudgetLimits {
+        &self.limits
+    }
+
+    /// Today's counters, reset when the UTC day has changed.
+    fn today(&self) -> MutexGuard<'_, DailyUsage> {
+        let mut today = self.today.lock().unwrap_or_else(|e| e.into_inner());
+        let day = chrono::Utc::now().date_naive();
+        if today.day != day {
+            *today = DailyUsage::new(day);
+        }
+        today
+    }
+
+    /// Reload today's counters from the repository.
+    pub async fn restore(&self) -> anyhow::Result<()> {
+        let Some(repo) = &self.repo else {
+            return Ok(());
+        };
+        let day = chrono::Utc::now().date_naive();
+        let records = repo.for_day(day).await?;
+        let mut restored = DailyUsage::new(day);
+        for r in &records {
+            restored.add(
+                &r.backend,
+                &r.model,
+                r.prompt_tokens.max(0) as u64,
+                r.completion_tokens.max(0) as u64,
+                r.cost_usd,
+            );
+        }
+        restored.alerted = self
+            .limits
+            .alert_cost_threshold_usd
+            .is_some_and(|threshold| restored.cost_usd() >= threshold);
+        *self.today() = restored;
+        Ok(())
+    }
+
+    /// Whether a call of about `estimated_tokens` may go to `backend`.
+    pub fn check(
+        &self,
+        backend: &str,
+        local: bool,
+        estimated_tokens: u64,
+    ) -> Result<(), BudgetDenial> {
+        if local {
+            return Ok(());
+        }
+        let today = self.today();
+        if let Some(&limit) = self.limits.max_tokens_per_day.get(backend) {
+            let used = today
+                .backends
+                .get(backend)
+                .map_or(0, BackendUsage::total_tokens);
+            if used + estimated_tokens > limit {
+                return Err(BudgetDenial::Tokens {
+                    backend: backend.to_string(),
+                    used,
+                    limit,
+                });
+            }
+        }
+        if let Some(limit_usd) = self.limits.max_cost_per_day_usd {
+            let spent_usd = today.cost_usd();
+            if spent_usd >= limit_usd {
+                return Err(BudgetDenial::Cost {
+                    backend: backend.to_string(),
+                    spent_usd,
+                    limit_usd,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Count a finished call. Returns the alert the first time today's
+    /// spend reaches the alert threshold.
+    pub async fn record(
+        &self,
+        backend: &str,
+        model: &str,
+        prompt_tokens: u64,
+        completion_tokens: u64,
+        cost_usd: f64,
+    ) -> Option<BudgetAlert> {
+        let alert = {
+            let mut today = self.today();
+            today.add(backend, model, prompt_tokens, completion_tokens, cost_usd);
+            let spent_usd = today.cost_usd();
+            match self.limits.alert_cost_threshold_usd {
+                Some(threshold_usd) if !today.alerted && spent_usd >= threshold_usd => {
+                    today.alerted = true;
+                    Some(BudgetAlert {
+                        spent_usd,
+                        threshold_usd,
+                        limit_usd: self.limits.max_cost_per_day_usd,
+                    })
+                }
+                _ => None,
+            }
+        };
+        if let Some(repo) = &self.repo {
+            let record = LlmUsageRecord::new(
+                backend.to_string(),
+                model.to_string(),
+                prompt_tokens as i64,
+                completion_tokens as i64,
+                cost_usd,
+            );
+            if let Err(e) = repo.insert(&record).await {
+                tracing::warn!("Failed to persist LLM usage for {}: {}", backend, e);
+            }
+        }
+        alert
+    }
+
+    /// Today's usage per backend, including capped backends not used yet.
+    pub fn summary(&self) -> UsageSummary {
+        let today = self.today();
+        let mut backends = today.backends.clone();
+        for (backend, &limit) in &self.limits.max_tokens_per_day {
+            backends
+                .entry(backend.clone())
+                .or_insert_with(|| BackendUsage {
+                    backend: backend.clone(),
+                    ..BackendUsage::default()
+                })
+                .token_limit = Some(limit);
+        }
+        let mut backends: Vec<BackendUsage> = backends.into_values().collect();
+        backends.sort_by(|a, b| a.backend.cmp(&b.backend));
+        UsageSummary {
+            day: today.day,
+            total_tokens: backends.iter().map(BackendUsage::total_tokens).sum(),
+            total_cost_usd: today.cost_usd(),
+            backends,
+            max_cost_per_day_usd: self.limits.max_cost_per_day_usd,
+            alert_cost_threshold_usd: self.limits.alert_cost_threshold_usd,
+            cache: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn caps_apply_to_remote_backends_and_the_alert_fires_once() {
+        let tracker = UsageTracker::new(
+            BudgetLimits::default()
+                .with_token_limit("openai", 1_000)
+                .with_cost_limit(1.0)
+                .with_cost_alert(0.5),
+        );
+
+        assert!(tracker.check("openai", false, 600).is_ok());
+        assert_eq!(
+            tracker.record("openai", "gpt-4o", 500, 100, 0.25).await,
+            None
+        );
+        assert_eq!(
+            tracker.check("openai", false, 600),
+            Err(BudgetDenial::Tokens {
+                backend: "openai".to_string(),
+                used: 600,
+                limit: 1_000,
+            })
+        );
+        assert!(tracker.check("openai", false, 400).is_ok());
+        assert!(tracker.check("ollama", true, 1_000_000).is_ok());
+
+        let alert = tracker.record("anthropic", "claude", 10, 10, 0.5).await;
+        assert_eq!(alert.map(|a| a.threshold_usd), Some(0.5));
+        assert_eq!(
+            tracker.record("anthropic", "claude", 10, 10, 0.25).await,
+            None
+        );
+        assert!(matches!(
+            tracker.check("anthropic", false, 1),
+            Err(BudgetDenial::Cost { .. })
+        ));
+
+        let summary = tracker.summary();
+        assert_eq!(summary.total_tokens, 640);
+        assert_eq!(summary.total_cost_usd, 1.0);
+        let backends: Vec<(&str, u64, Option<u64>)> = summary
+            .backends
+            .iter()
+            .map(|b| (b.backend.as_str(), b.calls, b.token_limit))
+            .collect();
+        assert_eq!(
+            backends,
+            vec![("anthropic", 2, None), ("openai", 1, Some(1_000))]
+        );
+    }
+}
@@ -18,6 +18,8 @@ async fn main() -> anyhow::Result<()> {
 
     // Create app state
     let state = ferrumyx_web::state::AppState::new_without_db().await?;
+    state.llm.spawn_health_probe();
+    state.spawn_metrics_collector();
 
     // Build router
     let app = ferrumyx_web::router::build_router(state);
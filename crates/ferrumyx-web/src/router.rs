@@ -1,12 +1,17 @@
 //! Axum router — maps all URL paths to handlers.
 
 use crate::handlers::{
+    admin::{api_admin_maintenance, api_admin_snapshot_export, api_admin_snapshot_import},
+    answer::api_query_answer,
     chat::{
         chat_events_proxy, chat_history, chat_lab_monitor, chat_page, chat_submit, chat_thread_new,
         chat_threads,
     },
     dashboard::dashboard,
-    depmap::{api_depmap_celllines, api_depmap_gene, depmap_page},
+    depmap::{
+        api_depmap_celllines, api_depmap_codependency, api_depmap_download, api_depmap_gene,
+        api_depmap_release, api_depmap_status, depmap_page,
+    },
     federation::{
         api_federation_canonical_lineage,
         api_federation_merge_decide,
@@ -28,17 +33,32 @@ use crate::handlers::{
         api_federation_package_export, api_federation_package_sign,
         api_federation_package_validate, api_federation_schema,
     },
-    ingestion::{ingestion_page, ingestion_run},
-    kg::{api_entity_suggest, api_kg_facts, api_kg_stats, kg_page},
-    metrics::{metrics_page, metrics_perf_api},
+    ingestion::{
+        api_ingestion_embed_backfill, api_ingestion_job, api_ingestion_job_cancel,
+        api_ingestion_jobs, api_ingestion_retry_failed, api_ingestion_schedule_run_now,
+        api_ingestion_schedules, ingestion_page, ingestion_run,
+    },
+    kg::{
+        api_entity_suggest, api_kg_citations, api_kg_conflicts, api_kg_entity,
+        api_kg_fact_evidence, api_kg_facts, api_kg_neighborhood, api_kg_paper_citations,
+        api_kg_path, api_kg_stats, kg_page,
+    },
+    llm::{api_audit_llm, api_llm_usage},
+    metrics::{api_metrics_summary, api_metrics_timeseries, metrics_page, metrics_perf_api},
     molecules::{api_molecules_run, molecules_page},
     ner::{api_ner_extract, api_ner_stats, ner_extract, ner_page},
+    papers::api_papers_delete,
     query::{query_page, query_submit},
-    ranker::{api_ranker_score, api_ranker_stats, api_ranker_top, ranker_page},
-    search::hybrid_search,
+    ranker::{
+        api_ranker_enrichment, api_ranker_explain_absence, api_ranker_export, api_ranker_run,
+        api_ranker_score, api_ranker_stats, api_ranker_top, api_ranker_weights_get,
+        api_ranker_weights_put, ranker_page,
+    },
+    search::{hybrid_search, paper_search},
     settings::{settings_get, settings_page, settings_save},
     system::system_page,
-    targets::{api_target_detail, api_targets, targets_page},
+    targets::{api_target_detail, api_target_dossier, api_targets, targets_page},
+    tools::{api_tool_metrics, api_tool_validate},
 };
 use crate::sse::sse_handler;
 use crate::state::{AppState, SharedState};
@@ -82,19 +102,79 @@ pub fn build_router(state: AppState) -> Router {
         // API endpoints
         .route("/api/targets", get(api_targets))
         .route("/api/targets/{gene}", get(api_target_detail))
+        .route("/api/targets/{gene}/dossier", get(api_target_dossier))
+        .route("/api/ingestion/jobs", get(api_ingestion_jobs))
+        .route("/api/ingestion/jobs/{id}", get(api_ingestion_job))
+        .route(
+            "/api/ingestion/jobs/{id}/cancel",
+            post(api_ingestion_job_cancel),
+        )
+        .route("/api/ingestion/schedules", get(api_ingestion_schedules))
+        .route(
+            "/api/ingestion/schedules/{id}/run-now",
+            post(api_ingestion_schedule_run_now),
+        )
+        .route(
+            "/api/ingestion/embed-backfill",
+            post(api_ingestion_embed_backfill),
+        )
+        .route(
+            "/api/ingestion/retry-failed",
+            post(api_ingestion_retry_failed),
+        )
         .route("/api/kg", get(api_kg_facts))
         .route("/api/kg/stats", get(api_kg_stats))
+        .route("/api/kg/conflicts", get(api_kg_conflicts))
+        .route("/api/kg/path", get(api_kg_path))
+        .route("/api/kg/neighborhood", get(api_kg_neighborhood))
+        .route("/api/kg/entity/{id}", get(api_kg_entity))
+        .route("/api/kg/fact/{id}/evidence", get(api_kg_fact_evidence))
+        .route("/api/kg/citations", get(api_kg_citations))
+        .route("/api/kg/paper/{id}/citations", get(api_kg_paper_citations))
         .route("/api/entities/suggest", get(api_entity_suggest))
         .route("/api/search", get(hybrid_search))
+        .route("/api/search/papers", get(paper_search))
+        .route("/api/papers/delete", post(api_papers_delete))
+        .route("/api/query/answer", post(api_query_answer))
+        .route("/api/llm/usage", get(api_llm_usage))
+        .route("/api/audit/llm", get(api_audit_llm))
+        .route("/api/tools/metrics", get(api_tool_metrics))
+        .route("/api/tools/{name}/validate", post(api_tool_validate))
         .route("/api/ner/stats", get(api_ner_stats))
         .route("/api/ner/extract", post(api_ner_extract))
         .route("/api/molecules/run", post(api_molecules_run))
         .route("/api/depmap/gene", get(api_depmap_gene))
         .route("/api/depmap/celllines", get(api_depmap_celllines))
+        .route("/api/depmap/codependency", get(api_depmap_codependency))
+        .route("/api/depmap/release", get(api_depmap_release))
+        .route("/api/depmap/status", get(api_depmap_status))
+        .route("/api/depmap/download", get(api_depmap_download))
         .route("/api/ranker/score", get(api_ranker_score))
         .route("/api/ranker/top", get(api_ranker_top))
+        .route("/api/ranker/run", post(api_ranker_run))
+        .route(
+            "/api/ranker/weights",
+            get(api_ranker_weights_get).put(api_ranker_weights_put),
+        )
         .route("/api/ranker/stats", get(api_ranker_stats))
+        .route("/api/ranker/enrichment", get(api_ranker_enrichment))
+        .route("/api/ranker/export", get(api_ranker_export))
+        .route(
+            "/api/ranker/explain_absence",
+            get(api_ranker_explain_absence),
+        )
         .route("/api/metrics/perf", get(metrics_perf_api))
+        .route("/api/metrics/summary", get(api_metrics_summary))
+        .route("/api/metrics/timeseries", get(api_metrics_timeseries))
+        .route("/api/admin/maintenance", post(api_admin_maintenance))
+        .route(
+            "/api/admin/snapshot/export",
+            post(api_admin_snapshot_export),
+        )
+        .route(
+            "/api/admin/snapshot/import",
+            post(api_admin_snapshot_import),
+        )
         .route("/api/federation/schema", get(api_federation_schema))
         .route(
             "/api/federation/manifest/draft",
@@ -162,7 +242,10 @@ pub fn build_router(state: AppState) -> Router {
         .route("/api/chat/thread/new", post(chat_thread_new))
         .route("/api/chat/lab-monitor", get(chat_lab_monitor))
         .route("/api/chat/events", get(chat_events_proxy))
-        .route("/api/settings", get(settings_get).post(settings_save))
+        .route(
+            "/api/settings",
+            get(settings_get).put(settings_save).post(settings_save),
+        )
         // Static files
         .nest_service(
             "/static",
@@ -0,0 +1,219 @@
+//! Applying saved settings to the running process.
+//!
+//! `PUT /api/settings` writes `ferrumyx.toml` and exports the runtime
+//! environment; this module works out which components the change touched.
+//! Answer routing is rebuilt in place from [`AppState::answer_backends`].
+//! Ingestion sources, ranker providers, federation and the graph and
+//! benchmark defaults read the config or environment on every use, so they
+//! are live as soon as the file is written. What is built once at start-up
+//! is reported as needing a restart.
+
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+
+use crate::state::AppState;
+
+/// How a settings section reaches the running process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Reload {
+    /// Rebuild the answer router's backends.
+    Router,
+    /// Read on every use; nothing to do.
+    Live,
+    /// Built at start-up.
+    Restart,
+}
+
+/// Settings sections, most specific first, with the component each one
+/// configures.
+const SECTIONS: &[(&[&str], &str, Reload)] = &[
+    (&["llm"], "answer routing", Reload::Router),
+    (&["embedding"], "embedding backend", Reload::Restart),
+    (
+        &["ranker", "phase4", "background_refresh"],
+        "provider background refresh",
+        Reload::Restart,
+    ),
+    (
+        &["ranker", "providers", "cosmic", "census_path"],
+        "COSMIC hotspot bootstrap",
+        Reload::Restart,
+    ),
+    (&["ranker"], "ranker providers", Reload::Live),
+    (&["ingestion"], "ingestion sources", Reload::Live),
+    (&["federation"], "federation", Reload::Live),
+    (&["graph"], "graph defaults", Reload::Live),
+    (&["benchmark"], "benchmark profiles", Reload::Live),
+];
+
+/// What saving the settings changed in the running process.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct AppliedSettings {
+    /// Components now running with the new settings.
+    pub reloaded: Vec<String>,
+    /// Components that keep the old settings until the process restarts.
+    pub restart_required: Vec<String>,
+}
+
+impl AppState {
+    /// Bring the running components in line with `new`, the settings just
+    /// saved over `old`. Both have their secrets opened.
+    pub async fn apply_settings(&self, old: &toml::Value, new: &toml::Value) -> AppliedSettings {
+        let mut changed: BTreeSet<(usize, &'static str, Reload)> = BTreeSet::new();
+        for path in changed_paths(old, new) {
+            let section = SECTIONS.iter().enumerate().find(|(_, (prefix, _, _))| {
+                path.len() >= prefix.len() && prefix.iter().zip(&path).all(|(a, b)| a == b)
+            });
+            if let Some((i, (_, component, reload))) = section {
+                changed.insert((i, component, *reload));
+            }
+        }
+
+        let mut applied = AppliedSettings::default();
+        for (_, component, reload) in changed {
+            match reload {
+                Reload::Live => applied.reloaded.push(component.to_string()),
+                Reload::Restart => applied.restart_required.push(component.to_string()),
+                Reload::Router => {
+                    match self.answer_backends.answer_backends(new).await {
+                        Ok(models) if !models.is_empty() => {
+                            self.llm.replace_backends(models);
+                            applied.reloaded.push(component.to_string());
+                        }
+                        Ok(_) => {
+                            tracing::warn!("New LLM settings configure no answer backend");
+                            applied.restart_required.push(component.to_string());
+                        }
+                        Err(e) => {
+                            tracing::warn!("Could not rebuild answer backends: {e}");
+                            applied.restart_required.push(component.to_string());
+                        }
+                    }
+                    applied
+                        .restart_required
+                        .extend(self.answer_backends.fixed_components());
+                }
+            }
+        }
+        applied
+    }
+}
+
+/// Key paths of every leaf whose value differs between `old` and `new`,
+/// including leaves present in only one.
+fn changed_paths(old: &toml::Value, new: &toml::Value) -> Vec<Vec<String>> {
+    let mut out = Vec::new();
+    diff_into(Some(old), Some(new), &mut Vec::new(), &mut out);
+    out
+}
+
+fn diff_into(
+    old: Option<&toml::Value>,
+    new: Option<&toml::Value>,
+    path: &mut Vec<String>,
+    out: &mut Vec<Vec<String>>,
+) {
+    match (old, new) {
+        (Some(toml::Value::Table(a)), Some(toml::Value::Table(b))) => {
+            let keys: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+            for key in keys {
+                path.push(key.clone());
+                diff_into(a.get(key), b.get(key), path, out);
+                path.pop();
+            }
+        }
+        (a, b) if a != b => out.push(path.clone()),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::{AnswerBackendSource, AnswerModel, OllamaBackendSource};
+    use ferrumyx_db::Database;
+    use std::sync::Arc;
+
+    fn settings(ollama_url: &str, graph_mode: &str) -> toml::Value {
+        toml::from_str(&format!(
+            "[llm.ollama]\nbase_url = \"{ollama_url}\"\nmodel = \"llama3.1:8b\"\n\n\
+             [graph]\ndefault_mode = \"{graph_mode}\"\n\n\
+             [embedding]\nbackend = \"rust_native\"\n"
+        ))
+        .unwrap()
+    }
+
+    async fn state() -> (AppState, std::path::PathBuf) {
+        let dir =
+            std::env::temp_dir().join(format!("ferrumyx-settings-reload-{}", uuid::Uuid::new_v4()));
+        let db = Database::open(&dir).await.unwrap();
+        db.initialize().await.unwrap();
+        (AppState::new(Arc::new(db)), dir)
+    }
+
+    #[test]
+    fn changed_paths_cover_edits_additions_and_removals() {
+        let old = settings("http://localhost:11434", "2d");
+        let mut new = settings("http://localhost:11434", "3d");
+        new.as_table_mut().unwrap().insert(
+            "federation".into(),
+            toml::from_str("enabled = true").unwrap(),
+        );
+        new["embedding"].as_table_mut().unwrap().remove("backend");
+
+        let paths = changed_paths(&old, &new);
+        assert_eq!(
+            paths,
+            [
+                vec!["embedding", "backend"],
+                vec!["federation"],
+                vec!["graph", "default_mode"],
+            ]
+        );
+        assert!(changed_paths(&old, &old).is_empty());
+    }
+
+    #[tokio::test]
+    async fn ollama_url_change_rebuilds_the_router() {
+        let (state, dir) = state().await;
+        let old = settings("http://localhost:11434", "2d");
+        let models = OllamaBackendSource.answer_backends(&old).await.unwrap();
+        state.llm.replace_backends(models);
+
+        let new = settings("http://127.0.0.1:11500", "2d");
+        let applied = state.apply_settings(&old, &new).await;
+        assert_eq!(applied.reloaded, ["answer routing"]);
+        assert!(applied.restart_required.is_empty());
+
+        let health = state.llm.health();
+        assert_eq!(health.len(), 1);
+        assert_eq!(health[0].backend, "ollama");
+        assert_eq!(
+            health[0].endpoint.as_deref(),
+            Some("http://127.0.0.1:11500")
+        );
+        assert!(health[0].local);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn unchanged_llm_settings_keep_the_router() {
+        let (state, dir) = state().await;
+        let before: Vec<Arc<dyn AnswerModel>> = state.llm.backend_models();
+
+        let old = settings("http://localhost:11434", "2d");
+        let mut new = settings("http://localhost:11434", "3d");
+        new["embedding"]["backend"] = toml::Value::String("ollama".into());
+        let applied = state.apply_settings(&old, &new).await;
+        assert_eq!(applied.reloaded, ["graph defaults"]);
+        assert_eq!(applied.restart_required, ["embedding backend"]);
+
+        let after = state.llm.backend_models();
+        assert_eq!(before.len(), after.len());
+        assert!(before.iter().zip(&after).all(|(a, b)| Arc::ptr_eq(a, b)));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -1,31 +1,412 @@
 //! Server-Sent Events (SSE) streaming for real-time UI updates.
+//!
+//! Every [`AppEvent`] sent through the [`EventBus`] gets the next id and is
+//! kept in a bounded replay buffer, so a client reconnecting with
+//! `Last-Event-ID` first receives what it missed. `?topics=ingestion,ranker`
+//! limits a stream to the [`EventTopic`]s a page renders.
 
-use axum::extract::State;
+use axum::extract::{Query, State};
+use axum::http::HeaderMap;
 use axum::response::sse::{Event, KeepAlive, Sse};
 use futures_core::Stream;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, VecDeque};
 use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
+use tokio::sync::broadcast;
 use tokio_stream::wrappers::BroadcastStream;
 use tokio_stream::StreamExt;
 
-use crate::state::SharedState;
+use crate::state::{AppEvent, SharedState};
+use ferrumyx_common::error::ApiError;
+
+/// Events kept for `Last-Event-ID` replay.
+pub const REPLAY_CAPACITY: usize = 512;
+
+/// Interval of the comment lines that keep proxies from closing idle
+/// streams.
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Events a live receiver may fall behind by before it skips some.
+const LIVE_CAPACITY: usize = 256;
+
+/// Groups of events a page can subscribe to with `?topics=`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventTopic {
+    Ingestion,
+    Ranker,
+    Molecules,
+    Answer,
+    Llm,
+    Admin,
+    System,
+}
+
+impl EventTopic {
+    pub const ALL: [EventTopic; 7] = [
+        Self::Ingestion,
+        Self::Ranker,
+        Self::Molecules,
+        Self::Answer,
+        Self::Llm,
+        Self::Admin,
+        Self::System,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Ingestion => "ingestion",
+            Self::Ranker => "ranker",
+            Self::Molecules => "molecules",
+            Self::Answer => "answer",
+            Self::Llm => "llm",
+            Self::Admin => "admin",
+            Self::System => "system",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        let raw = raw.trim();
+        Self::ALL
+            .into_iter()
+            .find(|t| t.as_str().eq_ignore_ascii_case(raw))
+    }
+}
+
+/// An event with the id it was sent under.
+#[derive(Debug, Clone)]
+pub struct SequencedEvent {
+    pub id: u64,
+    pub event: AppEvent,
+}
+
+/// Buffered events after a client's last id, and a receiver for the ones
+/// sent from then on.
+pub struct Subscription {
+    pub replay: Vec<Arc<SequencedEvent>>,
+    pub live: broadcast::Receiver<Arc<SequencedEvent>>,
+}
+
+/// Broadcasts [`AppEvent`]s to SSE clients, numbering them and keeping the
+/// last [`REPLAY_CAPACITY`] for replay. Clones share one bus.
+#[derive(Clone)]
+pub struct EventBus {
+    inner: Arc<EventBusInner>,
+}
+
+struct EventBusInner {
+    tx: broadcast::Sender<Arc<SequencedEvent>>,
+    log: Mutex<ReplayLog>,
+}
+
+struct ReplayLog {
+    next_id: u64,
+    capacity: usize,
+    events: VecDeque<Arc<SequencedEvent>>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::with_capacity(REPLAY_CAPACITY)
+    }
+}
+
+impl EventBus {
+    /// A bus replaying up to `capacity` events.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(LIVE_CAPACITY);
+        Self {
+            inner: Arc::new(EventBusInner {
+                tx,
+                log: Mutex::new(ReplayLog {
+                    next_id: 1,
+                    capacity: capacity.max(1),
+                    events: VecDeque::new(),
+                }),
+            }),
+        }
+    }
+
+    /// Record `event` and push it to connected clients; returns its id.
+    pub fn send(&self, event: AppEvent) -> u64 {
+        let mut log = self.log();
+        let event = Arc::new(SequencedEvent {
+            id: log.next_id,
+            event,
+        });
+        log.next_id += 1;
+        if log.events.len() == log.capacity {
+            log.events.pop_front();
+        }
+        log.events.push_back(event.clone());
+        // Sent under the lock so receivers see ids in order and a new
+        // subscription neither misses nor repeats an event.
+        let _ = self.inner.tx.send(event.clone());
+        event.id
+    }
+
+    /// Subscribe to events sent after `last_event_id`. Buffered ones are
+    /// returned for replay; an id this bus never issued (e.g. one from
+    /// before a restart) replays nothing.
+    pub fn subscribe(&self, last_event_id: Option<u64>) -> Subscription {
+        let log = self.log();
+        let replay = match last_event_id {
+            Some(last) if last < log.next_id => {
+                log.events.iter().filter(|e| e.id > last).cloned().collect()
+            }
+            _ => Vec::new(),
+        };
+        Subscription {
+            replay,
+            live: self.inner.tx.subscribe(),
+        }
+    }
+
+    /// Id of the most recently sent event, 0 before the first.
+    pub fn last_id(&self) -> u64 {
+        self.log().next_id - 1
+    }
+
+    fn log(&self) -> std::sync::MutexGuard<'_, ReplayLog> {
+        self.inner.log.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SseQuery {
+    /// Comma-separated [`EventTopic`]s; every topic when omitted.
+    pub topics: Option<String>,
+}
+
+/// The topics named in `raw`, or `None` for all of them.
+pub fn parse_topics(raw: Option<&str>) -> Result<Option<BTreeSet<EventTopic>>, ApiError> {
+    let Some(raw) = raw.map(str::trim).filter(|r| !r.is_empty()) else {
+        return Ok(None);
+    };
+    raw.split(',')
+        .filter(|t| !t.trim().is_empty())
+        .map(|t| {
+            EventTopic::parse(t).ok_or_else(|| {
+                let known: Vec<&str> = EventTopic::ALL.iter().map(|t| t.as_str()).collect();
+                ApiError::BadRequest(format!(
+                    "unknown topic '{}' (expected one of {})",
+                    t.trim(),
+                    known.join(", ")
+                ))
+            })
+        })
+        .collect::<Result<BTreeSet<_>, _>>()
+        .map(Some)
+}
+
+fn last_event_id(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get("last-event-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+}
+
+fn to_sse(event: &SequencedEvent) -> Option<Event> {
+    serde_json::to_string(&event.event)
+        .ok()
+        .map(|data| Event::default().id(event.id.to_string()).data(data))
+}
 
 /// SSE endpoint — clients subscribe here for real-time updates.
 pub async fn sse_handler(
     State(state): State<SharedState>,
-) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
-    let rx = state.subscribe();
-    let stream = BroadcastStream::new(rx).filter_map(|result| {
-        result.ok().and_then(|event| {
-            serde_json::to_string(&event)
-                .ok()
-                .map(|data| Ok(Event::default().data(data)))
-        })
+    headers: HeaderMap,
+    Query(query): Query<SseQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let topics = parse_topics(query.topics.as_deref())?;
+    let Subscription { replay, live } = state.event_tx.subscribe(last_event_id(&headers));
+
+    let wanted = move |event: &SequencedEvent| {
+        topics
+            .as_ref()
+            .is_none_or(|t| t.contains(&event.event.topic()))
+    };
+    let live_wanted = wanted.clone();
+    let replayed = tokio_stream::iter(replay).filter(move |e| wanted(e));
+    let live = BroadcastStream::new(live).filter_map(move |result| match result {
+        Ok(event) if live_wanted(&event) => Some(event),
+        Ok(_) => None,
+        Err(e) => {
+            tracing::warn!("SSE client fell behind: {e}");
+            None
+        }
     });
+    let stream = replayed
+        .chain(live)
+        .filter_map(|event| to_sse(&event).map(Ok));
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::new().interval(HEARTBEAT_INTERVAL).text("ping")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::AppState;
+    use axum::body::Body;
+    use axum::http::Request;
+    use axum::routing::get;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    fn notification(message: &str) -> AppEvent {
+        AppEvent::Notification {
+            level: "info".to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    fn messages(replay: &[Arc<SequencedEvent>]) -> Vec<(u64, String)> {
+        replay
+            .iter()
+            .map(|e| match &e.event {
+                AppEvent::Notification { message, .. } => (e.id, message.clone()),
+                other => panic!("unexpected event {other:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn replay_returns_buffered_events_after_the_last_id() {
+        let bus = EventBus::with_capacity(3);
+        assert_eq!(bus.last_id(), 0);
+        for message in ["a", "b", "c", "d"] {
+            bus.send(notification(message));
+        }
+        assert_eq!(bus.last_id(), 4);
+
+        assert!(bus.subscribe(None).replay.is_empty());
+        assert_eq!(
+            messages(&bus.subscribe(Some(2)).replay),
+            [(3, "c".to_string()), (4, "d".to_string())]
+        );
+        // The oldest event fell out of the buffer.
+        assert_eq!(messages(&bus.subscribe(Some(0)).replay).len(), 3);
+        assert!(bus.subscribe(Some(4)).replay.is_empty());
+        // Ids from a previous process replay nothing.
+        assert!(bus.subscribe(Some(99)).replay.is_empty());
+    }
+
+    #[tokio::test]
+    async fn subscription_receives_later_events_exactly_once() {
+        let bus = EventBus::default();
+        bus.send(notification("before"));
+        let mut sub = bus.subscribe(Some(0));
+        bus.send(notification("after"));
+        assert_eq!(messages(&sub.replay), [(1, "before".to_string())]);
+        assert_eq!(sub.live.recv().await.unwrap().id, 2);
+        assert!(sub.live.try_recv().is_err());
+    }
+
+    #[test]
+    fn topics_parse_and_reject_unknown_names() {
+        assert_eq!(parse_topics(None).unwrap(), None);
+        assert_eq!(parse_topics(Some(" ")).unwrap(), None);
+        assert_eq!(
+            parse_topics(Some("ingestion, Ranker,")).unwrap(),
+            Some(BTreeSet::from([EventTopic::Ingestion, EventTopic::Ranker]))
+        );
+        assert!(matches!(
+            parse_topics(Some("ingestion,docking")),
+            Err(ApiError::BadRequest(_))
+        ));
+    }
+
+    async fn fixture_state() -> (SharedState, std::path::PathBuf) {
+        let dir = std::env::temp_dir().join(format!("ferrumyx-web-sse-{}", uuid::Uuid::new_v4()));
+        let db = ferrumyx_db::Database::open(&dir).await.unwrap();
+        (Arc::new(AppState::new(Arc::new(db))), dir)
+    }
+
+    /// The `(id, type)` of the first `count` events on an SSE response.
+    async fn read_events(body: Body, count: usize) -> Vec<(u64, String)> {
+        let mut stream = body.into_data_stream();
+        let mut text = String::new();
+        let mut events = Vec::new();
+        while events.len() < count {
+            let chunk = tokio::time::timeout(Duration::from_secs(5), stream.next())
+                .await
+                .expect("SSE event within 5s")
+                .expect("stream open")
+                .unwrap();
+            text.push_str(std::str::from_utf8(&chunk).unwrap());
+            while let Some(end) = text.find("\n\n") {
+                let frame: String = text.drain(..end + 2).collect();
+                let id = frame
+                    .lines()
+                    .find_map(|l| l.strip_prefix("id: "))
+                    .and_then(|id| id.parse().ok());
+                let data = frame.lines().find_map(|l| l.strip_prefix("data: "));
+                if let (Some(id), Some(data)) = (id, data) {
+                    let value: serde_json::Value = serde_json::from_str(data).unwrap();
+                    events.push((id, value["type"].as_str().unwrap().to_string()));
+                }
+            }
+        }
+        events
+    }
+
+    #[tokio::test]
+    async fn endpoint_replays_after_last_event_id_and_filters_topics() {
+        let (state, dir) = fixture_state().await;
+        let app = Router::new()
+            .route("/api/events", get(sse_handler))
+            .with_state(state.clone());
+
+        state.event_tx.send(notification("missed"));
+        state.event_tx.send(AppEvent::TargetScored {
+            gene: "KRAS".to_string(),
+            cancer: "PAAD".to_string(),
+            score: 0.8,
+        });
+        state.event_tx.send(AppEvent::PipelineStatus {
+            stage: "search".to_string(),
+            message: "Searching".to_string(),
+            count: 0,
+            job_id: None,
+        });
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::get("/api/events?topics=ingestion,ranker")
+                    .header("Last-Event-ID", "1")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+        state.event_tx.send(notification("filtered out"));
+        state.event_tx.send(AppEvent::PaperIngested {
+            paper_id: "p1".to_string(),
+            title: "KRAS G12D".to_string(),
+            source: "pubmed".to_string(),
+        });
+        assert_eq!(
+            read_events(response.into_body(), 3).await,
+            [
+                (2, "target_scored".to_string()),
+                (3, "pipeline_status".to_string()),
+                (5, "paper_ingested".to_string()),
+            ]
+        );
 
-    Sse::new(stream).keep_alive(
-        KeepAlive::new()
-            .interval(Duration::from_secs(15))
-            .text("ping"),
-    )
+        let rejected = app
+            .oneshot(
+                Request::get("/api/events?topics=nope")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(rejected.status(), axum::http::StatusCode::BAD_REQUEST);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
@@ -1,11 +1,37 @@
 //! Shared application state for the web server.
 
-use ferrumyx_db::Database;
+use chrono::{DateTime, NaiveDate, Utc};
+use ferrumyx_db::chunks::ChunkRepository;
+use ferrumyx_db::entities::EntityRepository;
+use ferrumyx_db::kg_facts::KgFactRepository;
+use ferrumyx_db::papers::PaperRepository;
+use ferrumyx_db::{Database, MetricRollup, MetricRollupRepository};
+use ferrumyx_ingestion::pipeline::IngestionJob;
+use ferrumyx_ingestion::repository::IngestionRepository;
+use ferrumyx_ingestion::scheduler::IngestionScheduler;
+use ferrumyx_kg::ner::{SpanTagger, TrieNer};
+use ferrumyx_kg::update::{KgUpdateTrigger, ScoreUpdate};
+use ferrumyx_kg::KgGraphIndex;
+use ferrumyx_molecules::pipeline::PipelineProgress;
+use ferrumyx_ranker::depmap_provider::DepMapClientAdapter;
+use ferrumyx_ranker::providers::depmap::DepMapClient;
+use ferrumyx_ranker::weights::WeightVector;
+
+use crate::handlers::tools::AgentTools;
+use crate::jobs::JobManager;
+use crate::llm::{AnswerBackendSource, LlmRouter, OllamaBackendSource};
+use crate::llm_audit::DbAuditSink;
+use crate::sse::{EventBus, EventTopic};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use tokio::sync::broadcast;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, OnceCell};
 
-/// Events pushed to connected clients via SSE.
+/// Events pushed to connected clients via SSE; `type` carries the
+/// variant name so pages can switch on it.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum AppEvent {
@@ -21,17 +47,70 @@ pub enum AppEvent {
         cancer: String,
         score: f64,
     },
+    /// The KG event queue rewrote a target score
+    ScoreUpdated {
+        gene_id: String,
+        gene: String,
+        cancer: String,
+        score: f64,
+        tier: String,
+    },
     /// A docking job completed
     DockingComplete {
         molecule_id: String,
         gene: String,
         vina_score: f64,
     },
+    /// A molecule design run finished `done` of `total` items of a stage
+    MoleculeProgress {
+        gene: String,
+        cancer: String,
+        stage: String,
+        done: u64,
+        total: u64,
+        resumed: bool,
+    },
     /// Ingestion pipeline status update
     PipelineStatus {
         stage: String,
         message: String,
         count: u64,
+        /// Job the update belongs to, when it comes from a tracked job.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        job_id: Option<String>,
+    },
+    /// A tracked ingestion job started or finished
+    JobStateChanged {
+        job_id: String,
+        /// "running", then "succeeded", "partial", "failed" or "cancelled".
+        status: String,
+        stage: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    /// Text generated so far for a `POST /api/query/answer` request
+    AnswerToken { answer_id: String, token: String },
+    /// An answer finished generating
+    AnswerComplete {
+        answer_id: String,
+        backend: String,
+        model: String,
+        /// Usage as the backend reported it, when it did.
+        prompt_tokens: Option<u32>,
+        completion_tokens: Option<u32>,
+    },
+    /// Today's LLM spend crossed `[llm.limits] alert_cost_threshold_usd`
+    LlmBudgetAlert {
+        spent_usd: f64,
+        threshold_usd: f64,
+        limit_usd: Option<f64>,
+    },
+    /// Rows copied so far by a snapshot export or import
+    SnapshotProgress {
+        operation: String,
+        table: String,
+        rows: u64,
+        total: u64,
     },
     /// Feedback metric computed
     FeedbackMetric { metric: String, value: f64 },
@@ -39,18 +118,413 @@ pub enum AppEvent {
     Notification { level: String, message: String },
 }
 
+impl AppEvent {
+    /// Topic clients filter on with `?topics=`.
+    pub fn topic(&self) -> EventTopic {
+        match self {
+            Self::PaperIngested { .. }
+            | Self::PipelineStatus { .. }
+            | Self::JobStateChanged { .. } => EventTopic::Ingestion,
+            Self::TargetScored { .. } | Self::ScoreUpdated { .. } => EventTopic::Ranker,
+            Self::DockingComplete { .. } | Self::MoleculeProgress { .. } => EventTopic::Molecules,
+            Self::AnswerToken { .. } | Self::AnswerComplete { .. } => EventTopic::Answer,
+            Self::LlmBudgetAlert { .. } => EventTopic::Llm,
+            Self::SnapshotProgress { .. } => EventTopic::Admin,
+            Self::FeedbackMetric { .. } | Self::Notification { .. } => EventTopic::System,
+        }
+    }
+}
+
 /// Shared state injected into every Axum handler.
 #[derive(Clone)]
 pub struct AppState {
     pub db: Arc<Database>,
-    /// Broadcast channel for SSE push events
-    pub event_tx: broadcast::Sender<AppEvent>,
+    /// SSE push events, numbered and buffered for `Last-Event-ID` replay
+    pub event_tx: EventBus,
+    /// Ranker weight vector; seeded from `[scoring.weights]` and updated
+    /// through `PUT /api/ranker/weights`.
+    pub ranker_weights: Arc<RwLock<WeightVector>>,
+    /// DepMap client shared by every handler, loaded on first use.
+    pub depmap: Arc<SharedDepMap>,
+    /// Graph index behind the path and neighbourhood endpoints, built on
+    /// first use.
+    pub kg_graph: Arc<KgGraphIndex>,
+    /// Scheduler behind `/api/ingestion/schedules`; has no schedules
+    /// unless one loaded from `[[ingestion.schedules]]` is shared in.
+    pub ingestion_scheduler: Arc<IngestionScheduler>,
+    /// Ingestion jobs started from the UI and API, behind
+    /// `/api/ingestion/jobs`.
+    pub jobs: Arc<JobManager>,
+    /// LLM backends behind `/api/query/answer`; local Ollama unless the
+    /// agent shares its configured backends.
+    pub llm: Arc<LlmRouter>,
+    /// Rebuilds `llm`'s backends when the settings change; the standalone
+    /// server's Ollama backend unless the agent shares its builder.
+    pub answer_backends: Arc<dyn AnswerBackendSource>,
+    /// Extractors behind `/ner` and `/api/ner`, loaded on first use.
+    pub ner: Arc<NerModels>,
+    /// Agent tool registry behind `/api/tools`; no tools are known unless
+    /// the agent shares its registry.
+    pub tools: Option<Arc<dyn AgentTools>>,
+    /// KG event queue that re-scores targets after handlers change facts;
+    /// scores are left stale unless the agent shares its queue.
+    pub kg_events: Option<mpsc::UnboundedSender<KgUpdateTrigger>>,
+    /// Samples behind `/api/metrics/*`; empty until
+    /// [`AppState::spawn_metrics_collector`] runs or a summary is requested.
+    pub metrics: Arc<MetricsCollector>,
+}
+
+/// Lazily loaded DepMap client.
+///
+/// Concurrent first callers wait on the same load instead of each parsing
+/// the dataset; a failed load is retried by the next caller.
+#[derive(Default)]
+pub struct SharedDepMap {
+    client: OnceCell<DepMapClientAdapter>,
+    /// Data directory override; the client's default cache dir otherwise.
+    data_dir: Option<PathBuf>,
+    load_attempts: AtomicUsize,
+}
+
+impl SharedDepMap {
+    pub fn with_data_dir(data_dir: PathBuf) -> Self {
+        Self {
+            data_dir: Some(data_dir),
+            ..Self::default()
+        }
+    }
+
+    /// The shared client, loading it if no caller has yet.
+    pub async fn get(&self) -> anyhow::Result<&DepMapClientAdapter> {
+        self.client
+            .get_or_try_init(|| async {
+                self.load_attempts.fetch_add(1, Ordering::SeqCst);
+                match &self.data_dir {
+                    Some(dir) => Ok(DepMapClientAdapter::new(
+                        DepMapClient::with_data_dir(dir.clone()).await?,
+                    )),
+                    None => DepMapClientAdapter::init().await,
+                }
+            })
+            .await
+    }
+
+    /// The client if it has already been loaded; never starts a load.
+    pub fn loaded(&self) -> Option<&DepMapClientAdapter> {
+        self.client.get()
+    }
+
+    /// Number of loads started, including failed ones.
+    pub fn load_attempts(&self) -> usize {
+        self.load_attempts.load(Ordering::SeqCst)
+    }
+}
+
+/// Metrics sampled by [`MetricsCollector`], in display order.
+pub const COLLECTED_METRICS: [&str; 7] = [
+    "papers",
+    "chunks",
+    "chunks_missing_embeddings",
+    "entities",
+    "kg_facts",
+    "tool_invocations",
+    "llm_tokens_today",
+];
+
+/// Interval between samples of [`AppState::spawn_metrics_collector`].
+pub const METRICS_SAMPLE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Samples kept per metric: one day at one per minute.
+const METRICS_BUFFER_LEN: usize = 24 * 60;
+
+/// Minutes between writes of today's rollups to `metric_rollups`.
+const METRICS_PERSIST_MINUTES: i64 = 15;
+
+/// One metric value at one time.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct MetricSample {
+    pub at: DateTime<Utc>,
+    pub value: f64,
+}
+
+/// Recent samples of [`COLLECTED_METRICS`] plus a daily rollup of each.
+///
+/// Samples are kept in a ring buffer of [`METRICS_BUFFER_LEN`] per metric;
+/// rollups of the days in the buffer are kept until written to the
+/// `metric_rollups` table, which holds the longer history.
+#[derive(Default)]
+pub struct MetricsCollector {
+    inner: std::sync::Mutex<CollectorState>,
+}
+
+#[derive(Default)]
+struct CollectorState {
+    samples: HashMap<String, VecDeque<MetricSample>>,
+    rollups: BTreeMap<(String, NaiveDate), MetricRollup>,
+    persisted_at: Option<DateTime<Utc>>,
+}
+
+impl MetricsCollector {
+    /// Record `values`, all sampled at `at`.
+    pub fn record(&self, at: DateTime<Utc>, values: &[(&str, f64)]) {
+        let mut state = self.state();
+        for &(metric, value) in values {
+            let buffer = state.samples.entry(metric.to_string()).or_default();
+            if buffer.len() == METRICS_BUFFER_LEN {
+                buffer.pop_front();
+            }
+            buffer.push_back(MetricSample { at, value });
+            state
+                .rollups
+                .entry((metric.to_string(), at.date_naive()))
+                .and_modify(|r| r.absorb(value, at))
+                .or_insert_with(|| MetricRollup::new(metric, value, at));
+        }
+    }
+
+    /// Continue today's rollups from their persisted rows, e.g. after a
+    /// restart.
+    pub fn restore(&self, rollups: Vec<MetricRollup>) {
+        let mut state = self.state();
+        for rollup in rollups {
+            state
+                .rollups
+                .entry((rollup.metric.clone(), rollup.day))
+                .and_modify(|r| {
+                    r.samples += rollup.samples;
+                    r.min = r.min.min(rollup.min);
+                    r.max = r.max.max(rollup.max);
+                    r.sum += rollup.sum;
+                    if rollup.updated_at > r.updated_at {
+                        r.last = rollup.last;
+                        r.updated_at = rollup.updated_at;
+                    }
+                })
+                .or_insert(rollup);
+        }
+    }
+
+    /// Most recent sample of every metric sampled so far.
+    pub fn latest(&self) -> BTreeMap<String, MetricSample> {
+        self.state()
+            .samples
+            .iter()
+            .filter_map(|(metric, buffer)| Some((metric.clone(), *buffer.back()?)))
+            .collect()
+    }
+
+    /// Buffered samples of `metric` taken at or after `since`, oldest first.
+    pub fn samples_since(&self, metric: &str, since: DateTime<Utc>) -> Vec<MetricSample> {
+        self.state()
+            .samples
+            .get(metric)
+            .map(|buffer| buffer.iter().filter(|s| s.at >= since).copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// In-memory rollups of `metric`, oldest day first.
+    pub fn rollups(&self, metric: &str) -> Vec<MetricRollup> {
+        self.state()
+            .rollups
+            .values()
+            .filter(|r| r.metric == metric)
+            .cloned()
+            .collect()
+    }
+
+    /// Rollups changed since the last write if one is due at `now`: every
+    /// [`METRICS_PERSIST_MINUTES`] and as soon as a new day starts. Days
+    /// no longer in the sample buffer are dropped once handed out.
+    pub fn rollups_to_persist(&self, now: DateTime<Utc>) -> Vec<MetricRollup> {
+        let mut state = self.state();
+        let due = match state.persisted_at {
+            None => true,
+            Some(at) => {
+                at.date_naive() != now.date_naive()
+                    || now - at >= chrono::Duration::minutes(METRICS_PERSIST_MINUTES)
+            }
+        };
+        if !due {
+            return Vec::new();
+        }
+        let since = state.persisted_at;
+        let changed = state
+            .rollups
+            .values()
+            .filter(|r| since.is_none_or(|at| r.updated_at > at))
+            .cloned()
+            .collect();
+        state.persisted_at = Some(now);
+        let oldest_kept = (now - chrono::Duration::days(1)).date_naive();
+        state.rollups.retain(|(_, day), _| *day >= oldest_kept);
+        changed
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, CollectorState> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Builds a [`SpanTagger`], e.g. by loading model weights; run on the
+/// blocking pool.
+pub type SpanTaggerLoader = Arc<dyn Fn() -> anyhow::Result<Arc<dyn SpanTagger>> + Send + Sync>;
+
+/// NER extractors shared by every request, each loaded on first use.
+///
+/// The dictionary [`TrieNer`] is always available; statistical taggers
+/// exist only under the names a loader is registered for. As with
+/// [`SharedDepMap`], concurrent first callers share one load and a failed
+/// load is retried by the next caller.
+#[derive(Default)]
+pub struct NerModels {
+    trie: OnceCell<Arc<TrieNer>>,
+    taggers: HashMap<String, LazyTagger>,
+}
+
+struct LazyTagger {
+    load: SpanTaggerLoader,
+    tagger: OnceCell<Arc<dyn SpanTagger>>,
+}
+
+impl NerModels {
+    /// Use `trie` instead of building one from HGNC and OncoTree.
+    pub fn with_trie(trie: Arc<TrieNer>) -> Self {
+        Self {
+            trie: OnceCell::new_with(Some(trie)),
+            ..Self::default()
+        }
+    }
+
+    /// Serve the tagger `load` builds as model `name`.
+    pub fn with_tagger(mut self, name: impl Into<String>, load: SpanTaggerLoader) -> Self {
+        self.taggers.insert(
+            name.into(),
+            LazyTagger {
+                load,
+                tagger: OnceCell::new(),
+            },
+        );
+        self
+    }
+
+    /// The dictionary extractor, building it if no caller has yet.
+    pub async fn trie(&self) -> anyhow::Result<Arc<TrieNer>> {
+        let trie = self
+            .trie
+            .get_or_try_init(|| async {
+                TrieNer::with_complete_databases_async().await.map(Arc::new)
+            })
+            .await?;
+        Ok(Arc::clone(trie))
+    }
+
+    /// The dictionary extractor if it has already been built.
+    pub fn loaded_trie(&self) -> Option<Arc<TrieNer>> {
+        self.trie.get().cloned()
+    }
+
+    /// The tagger registered as `name`, loading it if no caller has yet.
+    /// `None` if no loader is registered under that name.
+    pub async fn tagger(&self, name: &str) -> Option<anyhow::Result<Arc<dyn SpanTagger>>> {
+        let lazy = self.taggers.get(name)?;
+        let tagger = lazy
+            .tagger
+            .get_or_try_init(|| async {
+                let load = Arc::clone(&lazy.load);
+                tokio::task::spawn_blocking(move || load()).await?
+            })
+            .await
+            .map(Arc::clone);
+        Some(tagger)
+    }
 }
 
 impl AppState {
     pub fn new(db: Arc<Database>) -> Self {
-        let (event_tx, _) = broadcast::channel(256);
-        Self { db, event_tx }
+        Self {
+            kg_graph: Arc::new(KgGraphIndex::new(db.clone())),
+            ingestion_scheduler: Arc::new(IngestionScheduler::for_pipeline(
+                Vec::new(),
+                IngestionJob::default(),
+                db.clone(),
+            )),
+            jobs: Arc::new(JobManager::new(db.clone())),
+            llm: Arc::new(
+                LlmRouter::from_env().with_audit_sink(Arc::new(DbAuditSink::new(db.clone()))),
+            ),
+            answer_backends: Arc::new(OllamaBackendSource),
+            db,
+            event_tx: EventBus::default(),
+            ranker_weights: Arc::default(),
+            depmap: Arc::default(),
+            ner: Arc::default(),
+            tools: None,
+            kg_events: None,
+            metrics: Arc::default(),
+        }
+    }
+
+    /// Rank with a weight vector shared with other holders (e.g. agent
+    /// tools), so runtime updates reach all of them.
+    pub fn with_ranker_weights(mut self, weights: Arc<RwLock<WeightVector>>) -> Self {
+        self.ranker_weights = weights;
+        self
+    }
+
+    /// Share a DepMap client holder, e.g. one pointed at a fixture directory.
+    pub fn with_depmap(mut self, depmap: Arc<SharedDepMap>) -> Self {
+        self.depmap = depmap;
+        self
+    }
+
+    /// Share NER extractors, e.g. with statistical taggers registered.
+    pub fn with_ner_models(mut self, ner: Arc<NerModels>) -> Self {
+        self.ner = ner;
+        self
+    }
+
+    /// Share a graph index, e.g. one the KG event queue keeps up to date.
+    pub fn with_kg_graph(mut self, kg_graph: Arc<KgGraphIndex>) -> Self {
+        self.kg_graph = kg_graph;
+        self
+    }
+
+    /// Re-score targets through the agent's KG event queue.
+    pub fn with_kg_events(mut self, tx: mpsc::UnboundedSender<KgUpdateTrigger>) -> Self {
+        self.kg_events = Some(tx);
+        self
+    }
+
+    /// Share the scheduler running the configured ingestion schedules.
+    pub fn with_ingestion_scheduler(mut self, scheduler: Arc<IngestionScheduler>) -> Self {
+        self.ingestion_scheduler = scheduler;
+        self
+    }
+
+    /// Answer with backends routed by the agent's `[llm]` configuration.
+    pub fn with_llm_router(mut self, llm: Arc<LlmRouter>) -> Self {
+        self.llm = llm;
+        self
+    }
+
+    /// Rebuild answer backends the way the agent built them.
+    pub fn with_answer_backends(mut self, source: Arc<dyn AnswerBackendSource>) -> Self {
+        self.answer_backends = source;
+        self
+    }
+
+    /// Serve parameter checks and invocation metrics of the agent's tools.
+    pub fn with_agent_tools(mut self, tools: Arc<dyn AgentTools>) -> Self {
+        self.tools = Some(tools);
+        self
+    }
+
+    /// Current ranker weight vector.
+    pub fn ranker_weights(&self) -> WeightVector {
+        self.ranker_weights
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
     }
 
     /// Create state with embedded database (LanceDB)
@@ -70,10 +544,27 @@ impl AppState {
             data_dir
         );
 
-        let (event_tx, _) = broadcast::channel(256);
+        let db = Arc::new(db);
         Ok(Self {
-            db: Arc::new(db),
-            event_tx,
+            kg_graph: Arc::new(KgGraphIndex::new(db.clone())),
+            ingestion_scheduler: Arc::new(IngestionScheduler::for_pipeline(
+                Vec::new(),
+                IngestionJob::default(),
+                db.clone(),
+            )),
+            jobs: Arc::new(JobManager::new(db.clone())),
+            llm: Arc::new(
+                LlmRouter::from_env().with_audit_sink(Arc::new(DbAuditSink::new(db.clone()))),
+            ),
+            answer_backends: Arc::new(OllamaBackendSource),
+            db,
+            event_tx: EventBus::default(),
+            ranker_weights: Arc::default(),
+            depmap: Arc::default(),
+            ner: Arc::default(),
+            tools: None,
+            kg_events: None,
+            metrics: Arc::default(),
         })
     }
 
@@ -82,9 +573,221 @@ impl AppState {
         Self::new_with_db().await
     }
 
-    pub fn subscribe(&self) -> broadcast::Receiver<AppEvent> {
-        self.event_tx.subscribe()
+    /// Sample every metric once and record it; a metric whose source is
+    /// unavailable is skipped rather than recorded as zero.
+    pub async fn sample_metrics(&self) -> Vec<(&'static str, f64)> {
+        let db = &self.db;
+        let counts: [(&'static str, anyhow::Result<u64>); 5] = [
+            (
+                "papers",
+                PaperRepository::new(db.clone())
+                    .count()
+                    .await
+                    .map_err(Into::into),
+            ),
+            (
+                "chunks",
+                ChunkRepository::new(db.clone())
+                    .count()
+                    .await
+                    .map_err(Into::into),
+            ),
+            (
+                "chunks_missing_embeddings",
+                IngestionRepository::new(db.clone())
+                    .count_chunks_missing_embeddings(None)
+                    .await,
+            ),
+            (
+                "entities",
+                EntityRepository::new(db.clone())
+                    .count()
+                    .await
+                    .map_err(Into::into),
+            ),
+            (
+                "kg_facts",
+                KgFactRepository::new(db.clone())
+                    .count()
+                    .await
+                    .map_err(Into::into),
+            ),
+        ];
+        let mut values: Vec<(&'static str, f64)> = Vec::with_capacity(COLLECTED_METRICS.len());
+        for (metric, count) in counts {
+            match count {
+                Ok(count) => values.push((metric, count as f64)),
+                Err(e) => tracing::debug!("Metric {metric} unavailable: {e}"),
+            }
+        }
+        if let Some(tools) = &self.tools {
+            let invocations: u64 = tools.stats().iter().map(|t| t.invocations).sum();
+            values.push(("tool_invocations", invocations as f64));
+        }
+        values.push((
+            "llm_tokens_today",
+            self.llm.usage().summary().total_tokens as f64,
+        ));
+        self.metrics.record(Utc::now(), &values);
+        values
+    }
+
+    /// Sample metrics every [`METRICS_SAMPLE_INTERVAL`], writing the daily
+    /// rollups to `metric_rollups` as they come due.
+    pub fn spawn_metrics_collector(&self) -> tokio::task::JoinHandle<()> {
+        let state = self.clone();
+        tokio::spawn(async move {
+            let repo = MetricRollupRepository::new(state.db.clone());
+            let today = Utc::now().date_naive();
+            for metric in COLLECTED_METRICS {
+                match repo.list_since(metric, today).await {
+                    Ok(rollups) => state.metrics.restore(rollups),
+                    Err(e) => tracing::warn!("Could not restore {metric} rollup: {e}"),
+                }
+            }
+            let mut ticker = tokio::time::interval(METRICS_SAMPLE_INTERVAL);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                state.sample_metrics().await;
+                let rollups = state.metrics.rollups_to_persist(Utc::now());
+                if let Err(e) = repo.upsert_batch(&rollups).await {
+                    tracing::warn!("Failed to persist metric rollups: {e}");
+                }
+            }
+        })
+    }
+
+    /// Push score updates from the KG event queue to SSE clients as
+    /// [`AppEvent::ScoreUpdated`].
+    pub fn forward_score_updates(&self, mut updates: broadcast::Receiver<ScoreUpdate>) {
+        let event_tx = self.event_tx.clone();
+        tokio::spawn(async move {
+            loop {
+                match updates.recv().await {
+                    Ok(update) => {
+                        let _ = event_tx.send(AppEvent::ScoreUpdated {
+                            gene_id: update.gene_id.to_string(),
+                            gene: update.gene,
+                            cancer: update.cancer_code.unwrap_or_default(),
+                            score: update.score,
+                            tier: update.tier,
+                        });
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Dropped {} score updates for SSE clients", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+
+    /// Push molecule pipeline progress to SSE clients as
+    /// [`AppEvent::MoleculeProgress`].
+    pub fn forward_molecule_progress(&self, mut progress: broadcast::Receiver<PipelineProgress>) {
+        let event_tx = self.event_tx.clone();
+        tokio::spawn(async move {
+            loop {
+                match progress.recv().await {
+                    Ok(p) => {
+                        let _ = event_tx.send(AppEvent::MoleculeProgress {
+                            gene: p.gene,
+                            cancer: p.cancer_type,
+                            stage: p.stage.to_string(),
+                            done: p.done as u64,
+                            total: p.total as u64,
+                            resumed: p.resumed,
+                        });
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!(
+                            "Dropped {} molecule progress events for SSE clients",
+                            skipped
+                        );
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
     }
 }
 
 pub type SharedState = Arc<AppState>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn rollups_split_at_utc_midnight() {
+        let collector = MetricsCollector::default();
+        let at = |d, h, m| Utc.with_ymd_and_hms(2026, 3, d, h, m, 0).unwrap();
+        collector.record(at(9, 23, 58), &[("papers", 10.0), ("chunks", 100.0)]);
+        collector.record(at(9, 23, 59), &[("papers", 14.0)]);
+        collector.record(at(10, 0, 0), &[("papers", 12.0)]);
+        collector.record(at(10, 0, 1), &[("papers", 13.0)]);
+
+        let papers = collector.rollups("papers");
+        assert_eq!(papers.len(), 2);
+        let (first, second) = (&papers[0], &papers[1]);
+        assert_eq!(first.day, at(9, 0, 0).date_naive());
+        assert_eq!(
+            (first.samples, first.min, first.max, first.last),
+            (2, 10.0, 14.0, 14.0)
+        );
+        assert_eq!(first.mean(), 12.0);
+        assert_eq!(second.day, at(10, 0, 0).date_naive());
+        assert_eq!(
+            (second.samples, second.min, second.max, second.last),
+            (2, 12.0, 13.0, 13.0)
+        );
+        assert_eq!(second.updated_at, at(10, 0, 1));
+        assert_eq!(collector.rollups("chunks").len(), 1);
+        assert_eq!(collector.latest()["papers"].value, 13.0);
+        assert_eq!(collector.samples_since("papers", at(10, 0, 0)).len(), 2);
+    }
+
+    #[test]
+    fn restored_rollups_continue_the_day() {
+        let collector = MetricsCollector::default();
+        let morning = Utc.with_ymd_and_hms(2026, 3, 10, 8, 0, 0).unwrap();
+        let mut persisted = MetricRollup::new("kg_facts", 50.0, morning);
+        persisted.absorb(70.0, morning + chrono::Duration::hours(1));
+        collector.restore(vec![persisted]);
+        collector.record(morning + chrono::Duration::hours(4), &[("kg_facts", 60.0)]);
+
+        let rollup = &collector.rollups("kg_facts")[0];
+        assert_eq!(rollup.samples, 3);
+        assert_eq!((rollup.min, rollup.max, rollup.last), (50.0, 70.0, 60.0));
+        assert_eq!(rollup.sum, 180.0);
+    }
+
+    #[test]
+    fn rollups_are_persisted_on_schedule_and_at_day_change() {
+        let collector = MetricsCollector::default();
+        let at = |d, h, m| Utc.with_ymd_and_hms(2026, 3, d, h, m, 0).unwrap();
+        collector.record(at(9, 23, 40), &[("papers", 1.0)]);
+        assert_eq!(collector.rollups_to_persist(at(9, 23, 40)).len(), 1);
+
+        collector.record(at(9, 23, 41), &[("papers", 2.0)]);
+        assert!(collector.rollups_to_persist(at(9, 23, 41)).is_empty());
+
+        // Midnight forces a write of both the finished and the new day.
+        collector.record(at(10, 0, 0), &[("papers", 3.0)]);
+        let written = collector.rollups_to_persist(at(10, 0, 0));
+        let days: Vec<NaiveDate> = written.iter().map(|r| r.day).collect();
+        assert_eq!(days, [at(9, 0, 0).date_naive(), at(10, 0, 0).date_naive()]);
+        assert_eq!(written[0].last, 2.0);
+
+        collector.record(at(10, 0, 20), &[("papers", 4.0)]);
+        let written = collector.rollups_to_persist(at(10, 0, 20));
+        assert_eq!(written.len(), 1);
+        assert_eq!(written[0].samples, 2);
+
+        // Days older than the sample buffer are dropped once written.
+        assert!(collector.rollups_to_persist(at(11, 6, 0)).is_empty());
+        assert_eq!(collector.rollups("papers").len(), 1);
+    }
+}